@@ -48,6 +48,19 @@
 //!         add x10, x5, x16    // Perform addition
 //! ```
 //!
+//! ## Zero Operands
+//!
+//! A stored literal 0 comes from the zero register, and adding or
+//! subtracting zero between general registers other than `sp` is a move:
+//!
+//! ```text
+//! Input:  str #0, [x6, #8]
+//! Output: str xzr, [x6, #8]
+//!
+//! Input:  add x10, x5, #0
+//! Output: mov x10, x5
+//! ```
+//!
 //! # Usage
 //!
 //! Wrap any WriterCore implementation with DesugaringWriter to automatically handle
@@ -87,7 +100,7 @@
 use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
 
 use crate::{
-    AArch64Arch, RegisterClass,
+    AArch64Arch, PredicateReg, RegisterClass, SysReg,
     out::{
         WriterCore,
         arg::{ArgKind, MemArg, MemArgKind},
@@ -156,7 +169,9 @@ impl TempRegManager {
     ) -> Result<Reg, W::Error> {
         let candidates = match reg_class {
             RegisterClass::Gpr => [config.temp_reg, config.temp_reg2, config.temp_reg3],
-            RegisterClass::Simd => [Reg(16), Reg(17), Reg(0)], // v16, v17 as SIMD temps, pad to 3 elements
+            // The Z registers extend the V registers, so they share temps.
+            RegisterClass::Simd | RegisterClass::Sve => [Reg(16), Reg(17), Reg(0)], // v16, v17 as SIMD temps, pad to 3 elements
+            RegisterClass::Predicate => [Reg(15), Reg(14), Reg(0)],
         };
 
         // Find first candidate that doesn't conflict
@@ -349,6 +364,63 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
         value < (1 << 16)
     }
 
+    /// Returns the literal in `b` as an ADD/SUB immediate when `a` is a
+    /// register and the value (or its negation) fits the 12-bit field, so the
+    /// operation needs no scratch register.
+    fn arith_imm_operand(a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Option<i64> {
+        match (a.concrete_mem_kind(), b.concrete_mem_kind()) {
+            (MemArgKind::NoMem(ArgKind::Reg { .. }), MemArgKind::NoMem(ArgKind::Lit(v))) => {
+                let v = v as i64;
+                crate::imm::arith_imm(v.unsigned_abs()).map(|_| v)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `true` when `dest = a ± b` is a plain `mov`: `b` is the
+    /// literal 0 and `dest` and `a` are registers other than register 31,
+    /// which only the ADD form can read as `sp`.
+    fn is_zero_move(
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> bool {
+        let reg = |arg: &(dyn MemArg + '_)| match arg.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => reg != Reg(31),
+            _ => false,
+        };
+        matches!(b.concrete_mem_kind(), MemArgKind::NoMem(ArgKind::Lit(0))) && reg(dest) && reg(a)
+    }
+
+    /// Replaces a literal 0 store source with the zero register: `xzr` for a
+    /// 64-bit access of `mem` and `wzr` otherwise.
+    fn store_src(src: &(dyn MemArg + '_), mem: &MemArgKind<ArgKind>) -> MemArgKind<ArgKind> {
+        match src.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Lit(0)) => {
+                let size = match mem {
+                    MemArgKind::Mem {
+                        size: MemorySize::_8 | MemorySize::_16 | MemorySize::_32,
+                        ..
+                    } => MemorySize::_32,
+                    _ => MemorySize::_64,
+                };
+                MemArgKind::NoMem(ArgKind::Reg { reg: Reg(31), size })
+            }
+            src => src,
+        }
+    }
+
+    /// Returns the literal in `b` when `a` is a register and the value is a
+    /// valid bitmask immediate for AND/ORR/EOR.
+    fn logical_imm_operand(a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Option<u64> {
+        match (a.concrete_mem_kind(), b.concrete_mem_kind()) {
+            (MemArgKind::NoMem(ArgKind::Reg { .. }), MemArgKind::NoMem(ArgKind::Lit(v))) => {
+                crate::imm::logical_imm(v).map(|_| v)
+            }
+            _ => None,
+        }
+    }
+
     /// Desugars a memory operand if needed.
     ///
     /// Currently, AArch64 supports most memory addressing modes directly,
@@ -356,12 +428,82 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
     fn desugar_mem_operand(
         &mut self,
         ctx: &mut Context,
-        _arch: AArch64Arch,
+        arch: AArch64Arch,
         mem: &MemArgKind<ArgKind>,
     ) -> Result<MemArgKind<ArgKind>, W::Error> {
-        // For now, AArch64 memory operands are mostly supported directly
-        // This could be extended for complex cases in the future
-        Ok(mem.clone())
+        #[cfg(feature = "tracing")]
+        tracing::trace!(?mem, "desugaring memory operand");
+        match mem {
+            // Only `ldr` has a PC-relative form; everything else addresses the
+            // symbol through a register.
+            MemArgKind::Mem {
+                base: ArgKind::Sym { name, addend },
+                offset,
+                disp,
+                size,
+                reg_class,
+                mode,
+            } => {
+                let temp_reg = self.config.temp_reg;
+                let sym = ArgKind::Sym {
+                    name: *name,
+                    addend: *addend,
+                };
+                self.writer.adr(ctx, arch, &temp_reg, &sym)?;
+                Ok(MemArgKind::Mem {
+                    base: ArgKind::Reg {
+                        reg: temp_reg,
+                        size: MemorySize::_64,
+                    },
+                    offset: *offset,
+                    disp: *disp,
+                    size: *size,
+                    reg_class: *reg_class,
+                    mode: *mode,
+                })
+            }
+            // A literal base (`[index + disp]` without a base register, or an
+            // absolute address) is loaded into a register.
+            MemArgKind::Mem {
+                base: ArgKind::Lit(val),
+                offset,
+                disp,
+                size,
+                reg_class,
+                mode,
+            } => {
+                let temp_reg = self.config.temp_reg;
+                self.writer.mov_imm(ctx, arch, &temp_reg, *val)?;
+                Ok(MemArgKind::Mem {
+                    base: ArgKind::Reg {
+                        reg: temp_reg,
+                        size: MemorySize::_64,
+                    },
+                    offset: *offset,
+                    disp: *disp,
+                    size: *size,
+                    reg_class: *reg_class,
+                    mode: *mode,
+                })
+            }
+            // For now, other AArch64 memory operands are supported directly
+            _ => Ok(mem.clone()),
+        }
+    }
+
+    /// Returns `true` if `mem` is `[sym + disp]` loadable with a PC-relative
+    /// `ldr` literal.
+    fn is_literal_load(mem: &MemArgKind<ArgKind>) -> bool {
+        matches!(
+            mem,
+            MemArgKind::Mem {
+                base: ArgKind::Sym { .. },
+                offset: None,
+                size: MemorySize::_32 | MemorySize::_64,
+                mode: crate::out::arg::AddressingMode::Offset,
+                ..
+            }
+        )
     }
 
     /// Helper to create a simple memory operand from base and displacement.
@@ -461,6 +603,15 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                     size: MemorySize::_64, // Literals are loaded as 64-bit values
                 }))
             }
+            MemArgKind::NoMem(ArgKind::Sym { .. }) => {
+                // Symbol addresses are formed PC-relatively
+                let temp_reg = self.config.temp_reg;
+                self.writer.adr(ctx, arch, &temp_reg, &concrete)?;
+                Ok(MemArgKind::NoMem(ArgKind::Reg {
+                    reg: temp_reg,
+                    size: MemorySize::_64,
+                }))
+            }
             MemArgKind::Mem { size, .. } => {
                 // This is a memory operand - load it into a temp register
                 let mut used = [Reg(0); 2];
@@ -522,6 +673,15 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                     size: MemorySize::_64, // Literals are loaded as 64-bit values
                 }))
             }
+            MemArgKind::NoMem(ArgKind::Sym { .. }) => {
+                // Symbol addresses are formed PC-relatively
+                let temp_reg = self.config.temp_reg;
+                self.writer.adr(ctx, arch, &temp_reg, &concrete)?;
+                Ok(MemArgKind::NoMem(ArgKind::Reg {
+                    reg: temp_reg,
+                    size: MemorySize::_64,
+                }))
+            }
             MemArgKind::Mem { .. } => {
                 // This is a memory operand - return desugared memory form
                 // Caller will handle loading into register if needed
@@ -671,7 +831,7 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                     count = 1;
                 }
             }
-            MemArgKind::NoMem(ArgKind::Lit(_)) => {
+            MemArgKind::NoMem(_) => {
                 // no registers
             }
             MemArgKind::Mem { base, offset, .. } => {
@@ -715,13 +875,12 @@ mod tests {
             // Literal operand in add instruction
             let b_literal = MemArgKind::NoMem(ArgKind::Lit(42));
 
-            // This should desugar to mov + add
+            // A 12-bit literal folds into the immediate form of add
             let _ = desugar.add(ctx, &dest, &a, &b_literal);
         }
 
-        // Check that output contains mov and add
-        assert!(output.contains("mov"));
-        assert!(output.contains("add"));
+        assert!(output.contains("add x10, x5, #42"));
+        assert!(!output.contains("mov"));
     }
 
     #[test]
@@ -794,6 +953,9 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         dest: &(dyn MemArg + '_),
         mem: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if Self::is_literal_load(&mem.concrete_mem_kind()) {
+            return self.writer.ldr(ctx, cfg, dest, mem);
+        }
         let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
         self.writer.ldr(ctx, cfg, dest, &desugared_mem)
     }
@@ -806,7 +968,8 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         mem: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
         let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
-        self.writer.str(ctx, cfg, src, &desugared_mem)
+        let src = Self::store_src(src, &desugared_mem);
+        self.writer.str(ctx, cfg, &src, &desugared_mem)
     }
 
     fn stp(
@@ -818,7 +981,9 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         mem: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
         let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
-        self.writer.stp(ctx, cfg, src1, src2, &desugared_mem)
+        let src1 = Self::store_src(src1, &desugared_mem);
+        let src2 = Self::store_src(src2, &desugared_mem);
+        self.writer.stp(ctx, cfg, &src1, &src2, &desugared_mem)
     }
 
     fn ldp(
@@ -840,6 +1005,27 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.writer.brk(ctx, cfg, imm)
     }
 
+    fn svc(&mut self, ctx: &mut Context, cfg: AArch64Arch, imm: u16) -> Result<(), Self::Error> {
+        self.writer.svc(ctx, cfg, imm)
+    }
+
+    fn bti(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: crate::BtiTarget,
+    ) -> Result<(), Self::Error> {
+        self.writer.bti(ctx, cfg, target)
+    }
+
+    fn paciasp(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.writer.paciasp(ctx, cfg)
+    }
+
+    fn autiasp(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.writer.autiasp(ctx, cfg)
+    }
+
     fn mov(
         &mut self,
         ctx: &mut Context,
@@ -861,12 +1047,20 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
                         // Source is a literal - use mov_imm
                         self.writer.mov_imm(ctx, cfg, dest, *val)
                     }
+                    MemArgKind::NoMem(ArgKind::Sym { .. }) => {
+                        // Source is a symbol address - use adr
+                        self.writer.adr(ctx, cfg, dest, src)
+                    }
                     _ => {
                         // Both registers
                         self.writer.mov(ctx, cfg, dest, src)
                     }
                 }
             }
+            (false, true) if Self::is_literal_load(&src_concrete) => {
+                // src is a global - PC-relative literal load
+                self.writer.ldr(ctx, cfg, dest, src)
+            }
             (false, true) => {
                 // src is memory, dest is register - load from memory
                 let desugared_src = self.desugar_mem_arg(ctx, cfg, src)?;
@@ -876,12 +1070,23 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
                 // dest is memory, src is register/literal - store to memory
                 let desugared_dest = self.desugar_mem_arg(ctx, cfg, dest)?;
                 match &src_concrete {
+                    MemArgKind::NoMem(ArgKind::Lit(0)) => {
+                        // Source is zero - store the zero register
+                        let zero = Self::store_src(src, &desugared_dest);
+                        self.writer.str(ctx, cfg, &zero, &desugared_dest)
+                    }
                     MemArgKind::NoMem(ArgKind::Lit(val)) => {
                         // Source is literal - load to temp then store
                         let temp_reg = self.config.temp_reg;
                         self.writer.mov_imm(ctx, cfg, &temp_reg, *val)?;
                         self.writer.str(ctx, cfg, &temp_reg, &desugared_dest)
                     }
+                    MemArgKind::NoMem(ArgKind::Sym { .. }) => {
+                        // Source is a symbol address - form it then store
+                        let temp_reg = self.config.temp_reg;
+                        self.writer.adr(ctx, cfg, &temp_reg, src)?;
+                        self.writer.str(ctx, cfg, &temp_reg, &desugared_dest)
+                    }
                     _ => {
                         // Source is register
                         self.writer.str(ctx, cfg, src, &desugared_dest)
@@ -934,11 +1139,104 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if Self::is_zero_move(dest, a, b) {
+            return self.writer.mov(ctx, cfg, dest, a);
+        }
+        if let Some(imm) = Self::arith_imm_operand(a, b) {
+            return self.writer.add_imm(ctx, cfg, dest, a, imm);
+        }
         self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
             writer.add(ctx, cfg, dest, a, b)
         })
     }
 
+    fn add_lsl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        shift: u8,
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.add_lsl(ctx, cfg, dest, a, b, shift)
+        })
+    }
+
+    fn adds(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Adding -n carries exactly when subtracting n does not borrow, so
+        // SUBS #n sets the same flags.
+        match Self::arith_imm_operand(a, b) {
+            Some(imm) if imm < 0 => self.writer.subs(ctx, cfg, dest, a, &imm.unsigned_abs()),
+            Some(imm) => self.writer.adds(ctx, cfg, dest, a, &(imm as u64)),
+            None => self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+                writer.adds(ctx, cfg, dest, a, b)
+            }),
+        }
+    }
+
+    fn subs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        match Self::arith_imm_operand(a, b) {
+            Some(imm) if imm < 0 => self.writer.adds(ctx, cfg, dest, a, &imm.unsigned_abs()),
+            Some(imm) => self.writer.subs(ctx, cfg, dest, a, &(imm as u64)),
+            None => self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+                writer.subs(ctx, cfg, dest, a, b)
+            }),
+        }
+    }
+
+    fn adcs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.adcs(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn sbcs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.sbcs(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.add(ctx, cfg, dest, a, &(imm as u64))
+    }
+
     fn sub(
         &mut self,
         ctx: &mut Context,
@@ -947,11 +1245,28 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if Self::is_zero_move(dest, a, b) {
+            return self.writer.mov(ctx, cfg, dest, a);
+        }
+        if let Some(imm) = Self::arith_imm_operand(a, b) {
+            return self.writer.sub_imm(ctx, cfg, dest, a, imm);
+        }
         self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
             writer.sub(ctx, cfg, dest, a, b)
         })
     }
 
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.sub(ctx, cfg, dest, a, &(imm as u64))
+    }
+
     fn mov_imm(
         &mut self,
         ctx: &mut Context,
@@ -975,6 +1290,32 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         })
     }
 
+    fn umulh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.umulh(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn smulh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.smulh(ctx, cfg, dest, a, b)
+        })
+    }
+
     fn udiv(
         &mut self,
         ctx: &mut Context,
@@ -1009,11 +1350,25 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if let Some(imm) = Self::logical_imm_operand(a, b) {
+            return self.writer.and_imm(ctx, cfg, dest, a, imm);
+        }
         self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
             writer.and(ctx, cfg, dest, a, b)
         })
     }
 
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.and(ctx, cfg, dest, a, &imm)
+    }
+
     fn orr(
         &mut self,
         ctx: &mut Context,
@@ -1022,11 +1377,25 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if let Some(imm) = Self::logical_imm_operand(a, b) {
+            return self.writer.orr_imm(ctx, cfg, dest, a, imm);
+        }
         self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
             writer.orr(ctx, cfg, dest, a, b)
         })
     }
 
+    fn orr_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.orr(ctx, cfg, dest, a, &imm)
+    }
+
     fn eor(
         &mut self,
         ctx: &mut Context,
@@ -1035,11 +1404,25 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if let Some(imm) = Self::logical_imm_operand(a, b) {
+            return self.writer.eor_imm(ctx, cfg, dest, a, imm);
+        }
         self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
             writer.eor(ctx, cfg, dest, a, b)
         })
     }
 
+    fn eor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.eor(ctx, cfg, dest, a, &imm)
+    }
+
     fn lsl(
         &mut self,
         ctx: &mut Context,
@@ -1079,6 +1462,19 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         })
     }
 
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.ror(ctx, cfg, dest, a, b)
+        })
+    }
+
     fn sxt(
         &mut self,
         ctx: &mut Context,
@@ -1112,56 +1508,181 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.writer.mvn(ctx, cfg, dest, &desugared_src)
     }
 
-    fn bl(
+    fn neg(
         &mut self,
         ctx: &mut Context,
         cfg: AArch64Arch,
-        target: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let desugared_target = self.load_operand_to_reg(ctx, cfg, target, RegisterClass::Gpr)?;
-        self.writer.bl(ctx, cfg, &desugared_target)
+        let desugared_src = self.load_operand_to_reg(ctx, cfg, src, RegisterClass::Gpr)?;
+        self.writer.neg(ctx, cfg, dest, &desugared_src)
     }
 
-    fn br(
+    fn negs(
         &mut self,
         ctx: &mut Context,
         cfg: AArch64Arch,
-        target: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let desugared_target = self.load_operand_to_reg(ctx, cfg, target, RegisterClass::Gpr)?;
-        self.writer.br(ctx, cfg, &desugared_target)
+        let desugared_src = self.load_operand_to_reg(ctx, cfg, src, RegisterClass::Gpr)?;
+        self.writer.negs(ctx, cfg, dest, &desugared_src)
     }
 
-    fn b(
+    fn cls(
         &mut self,
         ctx: &mut Context,
         cfg: AArch64Arch,
-        target: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let desugared_target = self.load_operand_to_reg(ctx, cfg, target, RegisterClass::Gpr)?;
-        self.writer.b(ctx, cfg, &desugared_target)
+        let desugared_src = self.load_operand_to_reg(ctx, cfg, src, RegisterClass::Gpr)?;
+        self.writer.cls(ctx, cfg, dest, &desugared_src)
     }
 
-    fn cmp(
+    fn clz(
         &mut self,
         ctx: &mut Context,
         cfg: AArch64Arch,
-        a: &(dyn MemArg + '_),
-        b: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let desugared_a = self.load_operand_to_reg(ctx, cfg, a, RegisterClass::Gpr)?;
-        let desugared_b = self.load_operand_to_reg(ctx, cfg, b, RegisterClass::Gpr)?;
-        self.writer.cmp(ctx, cfg, &desugared_a, &desugared_b)
+        let desugared_src = self.load_operand_to_reg(ctx, cfg, src, RegisterClass::Gpr)?;
+        self.writer.clz(ctx, cfg, dest, &desugared_src)
     }
 
-    fn csel(
+    fn rbit(
         &mut self,
         ctx: &mut Context,
         cfg: AArch64Arch,
-        cond: crate::ConditionCode,
         dest: &(dyn MemArg + '_),
-        true_val: &(dyn MemArg + '_),
-        false_val: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.load_operand_to_reg(ctx, cfg, src, RegisterClass::Gpr)?;
+        self.writer.rbit(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn rev(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.load_operand_to_reg(ctx, cfg, src, RegisterClass::Gpr)?;
+        self.writer.rev(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn ubfx(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        lsb: u8,
+        width: u8,
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.load_operand_to_reg(ctx, cfg, src, RegisterClass::Gpr)?;
+        self.writer.ubfx(ctx, cfg, dest, &desugared_src, lsb, width)
+    }
+
+    fn bfi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        lsb: u8,
+        width: u8,
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.load_operand_to_reg(ctx, cfg, src, RegisterClass::Gpr)?;
+        self.writer.bfi(ctx, cfg, dest, &desugared_src, lsb, width)
+    }
+
+    fn bl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Direct branches to a symbol are relocated, not materialized
+        if let MemArgKind::NoMem(ArgKind::Sym { .. }) = target.concrete_mem_kind() {
+            return self.writer.bl(ctx, cfg, target);
+        }
+        let desugared_target = self.load_operand_to_reg(ctx, cfg, target, RegisterClass::Gpr)?;
+        self.writer.bl(ctx, cfg, &desugared_target)
+    }
+
+    fn br(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_target = self.load_operand_to_reg(ctx, cfg, target, RegisterClass::Gpr)?;
+        self.writer.br(ctx, cfg, &desugared_target)
+    }
+
+    fn b(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Direct branches to a symbol are relocated, not materialized
+        if let MemArgKind::NoMem(ArgKind::Sym { .. }) = target.concrete_mem_kind() {
+            return self.writer.b(ctx, cfg, target);
+        }
+        let desugared_target = self.load_operand_to_reg(ctx, cfg, target, RegisterClass::Gpr)?;
+        self.writer.b(ctx, cfg, &desugared_target)
+    }
+
+    fn cmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if let Some(imm) = Self::arith_imm_operand(a, b) {
+            return self.writer.cmp_imm(ctx, cfg, a, imm);
+        }
+        let desugared_a = self.load_operand_to_reg(ctx, cfg, a, RegisterClass::Gpr)?;
+        let desugared_b = self.load_operand_to_reg(ctx, cfg, b, RegisterClass::Gpr)?;
+        self.writer.cmp(ctx, cfg, &desugared_a, &desugared_b)
+    }
+
+    fn cmp_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.cmp(ctx, cfg, a, &(imm as u64))
+    }
+
+    fn tst(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_a = self.load_operand_to_reg(ctx, cfg, a, RegisterClass::Gpr)?;
+        let desugared_b = self.load_operand_to_reg(ctx, cfg, b, RegisterClass::Gpr)?;
+        self.writer.tst(ctx, cfg, &desugared_a, &desugared_b)
+    }
+
+    fn csel(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        dest: &(dyn MemArg + '_),
+        true_val: &(dyn MemArg + '_),
+        false_val: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
         let desugared_true = self.load_operand_to_reg(ctx, cfg, true_val, RegisterClass::Gpr)?;
         let desugared_false = self.load_operand_to_reg(ctx, cfg, false_val, RegisterClass::Gpr)?;
@@ -1169,6 +1690,16 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
             .csel(ctx, cfg, cond, dest, &desugared_true, &desugared_false)
     }
 
+    fn cset(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.cset(ctx, cfg, cond, dest)
+    }
+
     fn bcond(
         &mut self,
         ctx: &mut Context,
@@ -1187,10 +1718,31 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         dest: &(dyn MemArg + '_),
         src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        // `adr x0, sym` and `adr x0, [sym + disp]` both name the symbol directly
+        if let MemArgKind::NoMem(ArgKind::Sym { .. })
+        | MemArgKind::Mem {
+            base: ArgKind::Sym { .. },
+            offset: None,
+            ..
+        } = src.concrete_mem_kind()
+        {
+            return self.writer.adr(ctx, cfg, dest, src);
+        }
         let desugared_src = self.load_operand_to_reg(ctx, cfg, src, RegisterClass::Gpr)?;
         self.writer.adr(ctx, cfg, dest, &desugared_src)
     }
 
+    fn msr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        reg: SysReg,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.load_operand_to_reg(ctx, cfg, src, RegisterClass::Gpr)?;
+        self.writer.msr(ctx, cfg, reg, &desugared_src)
+    }
+
     fn msr_nzcv(
         &mut self,
         ctx: &mut Context,
@@ -1201,10 +1753,30 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.writer.msr_nzcv(ctx, cfg, &desugared_src)
     }
 
+    fn msr_fpcr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.load_operand_to_reg(ctx, cfg, src, RegisterClass::Gpr)?;
+        self.writer.msr_fpcr(ctx, cfg, &desugared_src)
+    }
+
     fn ret(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
         self.writer.ret(ctx, cfg)
     }
 
+    fn mrs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        reg: SysReg,
+    ) -> Result<(), Self::Error> {
+        self.writer.mrs(ctx, cfg, dest, reg)
+    }
+
     fn mrs_nzcv(
         &mut self,
         ctx: &mut Context,
@@ -1214,6 +1786,133 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.writer.mrs_nzcv(ctx, cfg, dest)
     }
 
+    fn mrs_fpcr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.mrs_fpcr(ctx, cfg, dest)
+    }
+
+    fn mrs_tpidr_el0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.mrs_tpidr_el0(ctx, cfg, dest)
+    }
+
+    fn dmb(&mut self, ctx: &mut Context, cfg: AArch64Arch, barrier: crate::Barrier) -> Result<(), Self::Error> {
+        self.writer.dmb(ctx, cfg, barrier)
+    }
+
+    fn dsb(&mut self, ctx: &mut Context, cfg: AArch64Arch, barrier: crate::Barrier) -> Result<(), Self::Error> {
+        self.writer.dsb(ctx, cfg, barrier)
+    }
+
+    fn isb(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.writer.isb(ctx, cfg)
+    }
+
+    fn hint(&mut self, ctx: &mut Context, cfg: AArch64Arch, imm: u8) -> Result<(), Self::Error> {
+        self.writer.hint(ctx, cfg, imm)
+    }
+
+    fn yield_(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.writer.yield_(ctx, cfg)
+    }
+
+    fn wfe(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.writer.wfe(ctx, cfg)
+    }
+
+    fn sev(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.writer.sev(ctx, cfg)
+    }
+
+    fn prfm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        op: crate::PrfOp,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.prfm(ctx, cfg, op, mem)
+    }
+
+    fn dc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        op: crate::DcOp,
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.dc(ctx, cfg, op, addr)
+    }
+
+    fn ic_ivau(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.ic_ivau(ctx, cfg, addr)
+    }
+
+    fn ldar(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.ldar(ctx, cfg, dest, mem)
+    }
+
+    fn stlr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.stlr(ctx, cfg, src, mem)
+    }
+
+    fn swpal(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.swpal(ctx, cfg, src, dest, mem)
+    }
+
+    fn adrp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.adrp(ctx, cfg, dest, src)
+    }
+
+    fn add_lo12(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        sym: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.add_lo12(ctx, cfg, dest, src, sym)
+    }
+
     // Floating-point operations
 
     fn fadd(
@@ -1268,6 +1967,188 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         })
     }
 
+    fn fmadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fmadd(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fmsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fmsub(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fnmadd(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fnmsub(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fcmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fcmp(ctx, cfg, a, b)
+    }
+
+    fn fmin(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.fmin(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn fmax(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.fmax(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn fsqrt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fsqrt(ctx, cfg, dest, src)
+    }
+
+    fn fabs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fabs(ctx, cfg, dest, src)
+    }
+
+    fn fneg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fneg(ctx, cfg, dest, src)
+    }
+
+    fn fcsel(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fcsel(ctx, cfg, cond, dest, a, b)
+    }
+
+    fn scvtf(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.scvtf(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn ucvtf(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.ucvtf(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn fcvtzs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fcvtzs(ctx, cfg, dest, src)
+    }
+
+    fn fcvtzu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fcvtzu(ctx, cfg, dest, src)
+    }
+
+    fn fcvt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fcvt(ctx, cfg, dest, src)
+    }
+
     fn fmov(
         &mut self,
         ctx: &mut Context,
@@ -1278,6 +2159,197 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         let desugared_src = self.desugar_operand(ctx, cfg, src)?;
         self.writer.fmov(ctx, cfg, dest, &desugared_src)
     }
+
+    fn whilelt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.whilelt(ctx, cfg, dest, a, b)
+    }
+
+    fn ptrue(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.ptrue(ctx, cfg, dest)
+    }
+
+    fn sve_inc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        elem: MemorySize,
+    ) -> Result<(), Self::Error> {
+        self.writer.sve_inc(ctx, cfg, dest, elem)
+    }
+
+    fn sve_ld1(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.sve_ld1(ctx, cfg, dest, pg, mem)
+    }
+
+    fn sve_st1(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.sve_st1(ctx, cfg, src, pg, mem)
+    }
+
+    fn sve_ld1d_gather(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        base: &(dyn MemArg + '_),
+        offsets: &(dyn MemArg + '_),
+        scaled: bool,
+    ) -> Result<(), Self::Error> {
+        self.writer
+            .sve_ld1d_gather(ctx, cfg, dest, pg, base, offsets, scaled)
+    }
+
+    fn sve_st1d_scatter(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        base: &(dyn MemArg + '_),
+        offsets: &(dyn MemArg + '_),
+        scaled: bool,
+    ) -> Result<(), Self::Error> {
+        self.writer
+            .sve_st1d_scatter(ctx, cfg, src, pg, base, offsets, scaled)
+    }
+
+    fn sve_add(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.sve_add(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_sub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.sve_sub(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.sve_fadd(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.sve_fsub(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fmul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.sve_fmul(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fmla(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.sve_fmla(ctx, cfg, dest, pg, a, b)
+    }
+
+    fn sve_dup(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.sve_dup(ctx, cfg, dest, src)
+    }
+
+    fn db(&mut self, ctx: &mut Context, cfg: AArch64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.writer.db(ctx, cfg, bytes)
+    }
+
+    fn current_offset(&self) -> Option<usize> {
+        self.writer.current_offset()
+    }
+
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        self.writer.align_to(ctx, cfg, alignment)
+    }
+
+    fn nop(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.writer.nop(ctx, cfg)
+    }
+
+    fn nops(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        self.writer.nops(ctx, cfg, n_bytes)
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: AArch64Arch, pow2: u32) -> Result<(), Self::Error> {
+        self.writer.align(ctx, cfg, pow2)
+    }
 }
 
 // Implement Writer trait for DesugaringWriter
@@ -1332,4 +2404,13 @@ where
     ) -> Result<(), Self::Error> {
         self.writer.bl_label(ctx, cfg, label)
     }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.dd_label(ctx, cfg, label)
+    }
 }
@@ -0,0 +1,93 @@
+//! Immediate-operand encodability.
+//!
+//! AArch64 data-processing instructions have two immediate formats: the 12-bit
+//! field of ADD/SUB/CMP/CMN, optionally shifted left by 12, and the bitmask
+//! field of AND/ORR/EOR/TST. These helpers decide whether a value fits and
+//! return the raw field bits, so the desugaring layer and the binary backend
+//! agree on which immediates need a scratch register.
+
+/// Encodes `imm` as an ADD/SUB immediate.
+///
+/// Returns the 12-bit field and whether it is shifted left by 12, or `None`
+/// if `imm` fits neither form.
+pub fn arith_imm(imm: u64) -> Option<(u32, bool)> {
+    if imm < 0x1000 {
+        Some((imm as u32, false))
+    } else if imm & 0xFFF == 0 && imm >> 12 < 0x1000 {
+        Some(((imm >> 12) as u32, true))
+    } else {
+        None
+    }
+}
+
+/// Encodes `imm` as a 64-bit logical (bitmask) immediate.
+///
+/// Returns the 13-bit `N:immr:imms` field, or `None` if `imm` is not a
+/// replicated, rotated run of ones. All-zeros and all-ones are never
+/// encodable.
+pub fn logical_imm(imm: u64) -> Option<u32> {
+    if imm == 0 || imm == u64::MAX {
+        return None;
+    }
+    // Find the smallest element size the pattern repeats at.
+    let mut size = 64u32;
+    while size > 2 {
+        let half = size / 2;
+        let mask = (1u64 << half) - 1;
+        if imm & mask != (imm >> half) & mask {
+            break;
+        }
+        size = half;
+    }
+    let mask = if size == 64 {
+        u64::MAX
+    } else {
+        (1u64 << size) - 1
+    };
+    let elem = imm & mask;
+    let ones = elem.count_ones();
+    let run = (1u64 << ones) - 1;
+    // The element must be a rotation of a run of ones starting at bit 0.
+    let rot = (0..size).find(|&r| {
+        let rotated = if r == 0 {
+            elem
+        } else {
+            ((elem >> r) | (elem << (size - r))) & mask
+        };
+        rotated == run
+    })?;
+    let immr = (size - rot) % size;
+    let n_imms = (!(size - 1) << 1) | (ones - 1);
+    let n = ((n_imms >> 6) & 1) ^ 1;
+    Some((n << 12) | (immr << 6) | (n_imms & 0x3F))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arith_imm_forms() {
+        assert_eq!(arith_imm(0), Some((0, false)));
+        assert_eq!(arith_imm(0xFFF), Some((0xFFF, false)));
+        assert_eq!(arith_imm(0x1000), Some((1, true)));
+        assert_eq!(arith_imm(0xFFF000), Some((0xFFF, true)));
+        assert_eq!(arith_imm(0x1001), None);
+        assert_eq!(arith_imm(0x100_0000), None);
+    }
+
+    #[test]
+    fn logical_imm_forms() {
+        assert_eq!(logical_imm(0), None);
+        assert_eq!(logical_imm(u64::MAX), None);
+        // 64-bit element, eight ones, no rotation.
+        assert_eq!(logical_imm(0xFF), Some(0x1007));
+        // 64-bit element, four ones rotated left by 4.
+        assert_eq!(logical_imm(0xF0), Some(0x1F03));
+        // 2-bit element `01` replicated.
+        assert_eq!(logical_imm(0x5555_5555_5555_5555), Some(0x03C));
+        // 32-bit element replicated.
+        assert_eq!(logical_imm(0x0000_FFFF_0000_FFFF), Some(0x00F));
+        assert_eq!(logical_imm(0x1234), None);
+    }
+}
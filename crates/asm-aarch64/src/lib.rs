@@ -7,6 +7,8 @@
 //!
 //! - `alloc`: Enables heap allocation support for dynamic collections
 //! - `x64_shim`: Enables x86-64 to AArch64 translation shim
+//! - `serde`: Derives `Serialize`/`Deserialize` for the architecture
+//!   configuration and operand types
 //!
 //! # Example
 //!
@@ -22,6 +24,7 @@ extern crate alloc;
 #[doc(hidden)]
 pub mod __ {
     pub use core;
+    pub use portal_pc_asm_common::types::mem::MemorySize;
 }
 
 use core::fmt::Display;
@@ -54,12 +57,18 @@ static VREG_NAMES: &'static [&'static str; 32] = &[
 /// or SIMD/FP registers for floating-point operations.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RegisterClass {
     /// General-purpose register (x0, x1, etc.).
     #[default]
     Gpr,
     /// SIMD/FP register for floating-point/SIMD operations (v0, v1, etc.).
     Simd,
+    /// SVE scalable vector register, with the element size as a suffix
+    /// (`z0.d`). The Z registers extend the V registers.
+    Sve,
+    /// SVE predicate register, with the element size as a suffix (`p0.d`).
+    Predicate,
 }
 
 /// Display options for formatting assembly operands.
@@ -73,19 +82,31 @@ pub struct DisplayOpts {
     pub arch: AArch64Arch,
     /// The register class for display.
     pub reg_class: RegisterClass,
+    /// What register 31 means in the operand being displayed.
+    pub reg31: Reg31,
 }
 
 impl DisplayOpts {
     /// Creates display options with the given architecture and default register class.
     pub fn new(arch: AArch64Arch) -> Self {
+        Self::with_reg_class(arch, Default::default())
+    }
+    /// Creates display options with the given architecture and register class.
+    pub fn with_reg_class(arch: AArch64Arch, reg_class: RegisterClass) -> Self {
         Self {
             arch,
-            reg_class: Default::default(),
+            reg_class,
+            reg31: Default::default(),
         }
     }
-    /// Creates display options with the given architecture and register class.
-    pub fn with_reg_class(arch: AArch64Arch, reg_class: RegisterClass) -> Self {
-        Self { arch, reg_class }
+    /// Returns these options with register 31 displayed as `reg31`.
+    pub fn with_reg31(self, reg31: Reg31) -> Self {
+        Self { reg31, ..self }
+    }
+    /// Returns the options for formatting a register of `size` under these
+    /// options.
+    pub fn reg_format(self, size: MemorySize) -> RegFormatOpts {
+        RegFormatOpts::with_reg_class(self.arch, size, self.reg_class).with_reg31(self.reg31)
     }
 }
 
@@ -105,11 +126,306 @@ impl From<AArch64Arch> for DisplayOpts {
 ///
 /// This struct holds configuration options for the AArch64 architecture,
 /// such as available extensions.
+///
+/// The extension flags gate the instructions
+/// [`ValidatingWriter`](out::validate::ValidatingWriter) accepts and select
+/// the lowerings in [`strategy`]. All default to off, the baseline ARMv8.0-A.
 #[non_exhaustive]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct AArch64Arch {
-    /// Placeholder for future architecture-specific options.
-    _reserved: (),
+    /// How text output refers to labels.
+    pub label_refs: LabelRefs,
+    /// Whether data is big-endian (`aarch64_be`).
+    ///
+    /// Instructions are always stored little-endian; this only affects data
+    /// emitted through helpers such as [`AArch64Arch::u64_bytes`] and the
+    /// byte order the x86-64 shim assumes for guest memory.
+    pub big_endian: bool,
+    /// Whether to emit position-independent code.
+    ///
+    /// Moving a symbol's address into a register then uses `adrp` + `add
+    /// :lo12:` instead of loading the absolute address from a literal.
+    pub pic: bool,
+    /// Which assembler text output is written for.
+    pub assembler: AssemblerDialect,
+    /// Operating system the code runs on, which decides the platform ABI
+    /// details in [`TargetOs`].
+    pub os: TargetOs,
+    /// Whether branch target identification is enforced.
+    ///
+    /// Function entries and other indirect-branch targets then start with a
+    /// `bti` landing pad (see [`out::cfi`]).
+    pub bti: bool,
+    /// Whether return addresses are signed with pointer authentication.
+    ///
+    /// Function entries then sign `x30` with `paciasp` and returns
+    /// authenticate it with `autiasp` (see [`out::cfi`]).
+    pub pac_ret: bool,
+    /// How text output names SIMD/FP registers.
+    pub simd_view: SimdRegView,
+    /// Whether the Large System Extensions (FEAT_LSE, ARMv8.1) are
+    /// available: single-instruction atomics such as `swpal` in place of
+    /// exclusive load/store loops.
+    pub lse: bool,
+    /// Whether the Scalable Vector Extension (FEAT_SVE) is available.
+    ///
+    /// See [`out::sve`] for the instructions it enables.
+    pub sve: bool,
+    /// The SVE vector length in bits, a multiple of 128 from 128 to 2048,
+    /// or 0 when code must work at any vector length.
+    ///
+    /// Only meaningful when [`sve`](Self::sve) is set.
+    pub sve_vector_bits: u16,
+    /// Whether half-precision scalar arithmetic (FEAT_FP16, ARMv8.2) is
+    /// available, so floating-point instructions accept 16-bit `h`
+    /// registers.
+    pub fp16: bool,
+    /// Whether the dot-product instructions (FEAT_DotProd, ARMv8.2) are
+    /// available.
+    pub dotprod: bool,
+}
+
+impl AArch64Arch {
+    /// Parses the architecture component of a target triple.
+    ///
+    /// Accepts `aarch64`, `arm64` and `arm64e` as little-endian and
+    /// `aarch64_be` as big-endian, with or without the rest of the triple
+    /// (`aarch64_be-unknown-linux-gnu`). Returns `None` for other architectures.
+    ///
+    /// The OS is taken from the rest of the triple: `apple`, `darwin`,
+    /// `macos` and `ios` select [`TargetOs::Apple`], `windows` selects
+    /// [`TargetOs::Windows`], and anything else [`TargetOs::Linux`].
+    pub fn from_triple(triple: &str) -> Option<Self> {
+        let mut parts = triple.split('-');
+        let big_endian = match parts.next()? {
+            "aarch64" | "arm64" | "arm64e" => false,
+            "aarch64_be" => true,
+            _ => return None,
+        };
+        let mut os = TargetOs::Linux;
+        for part in parts {
+            if part == "windows" {
+                os = TargetOs::Windows;
+            } else if part == "apple"
+                || ["darwin", "macos", "ios"].iter().any(|p| part.starts_with(p))
+            {
+                os = TargetOs::Apple;
+            }
+        }
+        Some(Self {
+            big_endian,
+            os,
+            ..Self::default()
+        })
+    }
+
+    /// Returns this configuration with LSE atomics set to `enabled`.
+    pub fn with_lse(self, enabled: bool) -> Self {
+        Self {
+            lse: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with SVE enabled at `vector_bits`, or with
+    /// a length-agnostic vector length when `vector_bits` is 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `vector_bits` is not 0 or a multiple of 128 up to 2048.
+    pub fn with_sve(self, vector_bits: u16) -> Self {
+        assert!(
+            vector_bits % 128 == 0 && vector_bits <= 2048,
+            "SVE vector length must be a multiple of 128 up to 2048, got {vector_bits}"
+        );
+        Self {
+            sve: true,
+            sve_vector_bits: vector_bits,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with half-precision arithmetic set to
+    /// `enabled`.
+    pub fn with_fp16(self, enabled: bool) -> Self {
+        Self {
+            fp16: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with the dot-product instructions set to
+    /// `enabled`.
+    pub fn with_dotprod(self, enabled: bool) -> Self {
+        Self {
+            dotprod: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with branch target identification set to
+    /// `enabled`.
+    pub fn with_bti(self, enabled: bool) -> Self {
+        Self {
+            bti: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with return-address signing set to
+    /// `enabled`.
+    pub fn with_pac_ret(self, enabled: bool) -> Self {
+        Self {
+            pac_ret: enabled,
+            ..self
+        }
+    }
+
+    /// Returns the bytes of a 16-bit data word in the target's byte order.
+    pub fn u16_bytes(self, value: u16) -> [u8; 2] {
+        if self.big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        }
+    }
+
+    /// Returns the bytes of a 32-bit data word in the target's byte order.
+    pub fn u32_bytes(self, value: u32) -> [u8; 4] {
+        if self.big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        }
+    }
+
+    /// Returns the bytes of a 64-bit data word in the target's byte order.
+    pub fn u64_bytes(self, value: u64) -> [u8; 8] {
+        if self.big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        }
+    }
+}
+
+/// How assembly text refers to labels in branches and address loads.
+///
+/// Binary writers resolve labels themselves and always encode PC-relative
+/// offsets, so the policy only changes text output. Conditional branches
+/// have no absolute form and stay label-relative under either policy.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LabelRefs {
+    /// PC-relative references (`b label`, `adr x0, label`), valid at any load address.
+    #[default]
+    Relative,
+    /// Absolute addresses (`ldr x16, =label` then `br x16`), for code linked at a fixed base.
+    Absolute,
+}
+
+/// The assembler that consumes text output.
+///
+/// GNU `as` and LLVM's integrated assembler accept the same directives for
+/// everything the text writers emit (`.balign 16`, `.type f, %function`); the dialect
+/// names the target so directive and comment spellings can follow it.
+/// Binary writers ignore it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AssemblerDialect {
+    /// GNU `as`.
+    #[default]
+    Gnu,
+    /// LLVM's integrated assembler.
+    Llvm,
+}
+
+impl AssemblerDialect {
+    /// Returns the string that starts a comment running to the end of the line.
+    pub fn comment(self) -> &'static str {
+        "//"
+    }
+}
+
+/// The operating system, and with it the platform ABI and object format.
+///
+/// Binary writers encode the same instructions for every OS; the OS changes
+/// which registers are free, how text output spells symbols and
+/// relocations, and whether large stack frames must be probed.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TargetOs {
+    /// Linux and other ELF platforms following the generic AAPCS64.
+    #[default]
+    Linux,
+    /// macOS and iOS: Mach-O, `_`-prefixed symbols and `@PAGE`/`@PAGEOFF`
+    /// relocations.
+    Apple,
+    /// Windows: COFF, with stack probes through `__chkstk`.
+    Windows,
+}
+
+impl TargetOs {
+    /// Returns `true` if the platform reserves `x18` (the platform register),
+    /// so generated code must never write it.
+    pub fn reserves_x18(self) -> bool {
+        self != TargetOs::Linux
+    }
+
+    /// Returns the prefix the object format adds to C symbol names in text
+    /// output.
+    pub fn symbol_prefix(self) -> &'static str {
+        match self {
+            TargetOs::Apple => "_",
+            _ => "",
+        }
+    }
+
+    /// Returns the routine that must probe the stack before `sp` moves down
+    /// by a page or more, if the platform requires one.
+    pub fn stack_probe(self) -> Option<&'static str> {
+        match self {
+            TargetOs::Windows => Some("__chkstk"),
+            _ => None,
+        }
+    }
+}
+
+/// How SIMD/FP registers are named in text output.
+///
+/// The operand size picks the element or scalar width in every view;
+/// 128-bit operands print as `q0` and wider SVE operands as `z0`, except in
+/// [`SimdRegView::Arrangement`], which describes a full 128-bit vector.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SimdRegView {
+    /// A `v` register with an element qualifier: `v0.d`, `v0.s`.
+    #[default]
+    Element,
+    /// The scalar view of the register: `d0`, `s0`, `h0`, `b0`.
+    Scalar,
+    /// A full vector arrangement: `v0.2d`, `v0.4s`, `v0.8h`, `v0.16b`.
+    Arrangement,
+}
+
+/// What register number 31 stands for in an operand.
+///
+/// The encoding reuses 31 for the stack pointer in address bases and
+/// immediate adds, and for the zero register in most other operands.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Reg31 {
+    /// `sp`/`wsp`.
+    #[default]
+    StackPointer,
+    /// `xzr`/`wzr`.
+    Zero,
 }
 
 /// Options for formatting register names.
@@ -125,6 +441,11 @@ pub struct RegFormatOpts {
     pub size: MemorySize,
     /// The register class for display.
     pub reg_class: RegisterClass,
+    /// How SIMD/FP registers are named; taken from
+    /// [`AArch64Arch::simd_view`] by the constructors.
+    pub simd_view: SimdRegView,
+    /// What general-purpose register 31 is displayed as.
+    pub reg31: Reg31,
 }
 
 impl RegFormatOpts {
@@ -134,11 +455,7 @@ impl RegFormatOpts {
     }
     /// Creates formatting options with the given architecture and size.
     pub fn default_with_arch_and_size(arch: AArch64Arch, size: MemorySize) -> Self {
-        Self {
-            arch,
-            size,
-            reg_class: Default::default(),
-        }
+        Self::with_reg_class(arch, size, Default::default())
     }
     /// Creates formatting options with the given architecture, size, and register class.
     pub fn with_reg_class(arch: AArch64Arch, size: MemorySize, reg_class: RegisterClass) -> Self {
@@ -146,8 +463,18 @@ impl RegFormatOpts {
             arch,
             size,
             reg_class,
+            simd_view: arch.simd_view,
+            reg31: Default::default(),
         }
     }
+    /// Returns these options with SIMD/FP registers named as `simd_view`.
+    pub fn with_simd_view(self, simd_view: SimdRegView) -> Self {
+        Self { simd_view, ..self }
+    }
+    /// Returns these options with register 31 displayed as `reg31`.
+    pub fn with_reg31(self, reg31: Reg31) -> Self {
+        Self { reg31, ..self }
+    }
 }
 
 impl Default for RegFormatOpts {
@@ -158,12 +485,16 @@ impl Default for RegFormatOpts {
 
 /// Desugaring wrapper for complex operands.
 pub mod desugar;
+/// Immediate-operand encodability helpers.
+pub mod imm;
 /// Instruction output generation module.
 pub mod out;
 /// Register handling and formatting module.
 pub mod reg;
 /// Stack management and optimization module.
 pub mod stack;
+/// Lowering strategy selection keyed by enabled extensions.
+pub mod strategy;
 
 #[cfg(feature = "x64_shim")]
 pub use portal_solutions_asm_x86_64_shim::aarch64 as shim;
@@ -171,6 +502,9 @@ pub use portal_solutions_asm_x86_64_shim::aarch64 as shim;
 /// Register allocation integration module (gated by `regalloc-integration` feature).
 #[cfg(feature = "regalloc-integration")]
 pub mod regalloc;
+/// Serde adapters for foreign types in operands (gated by `serde` feature).
+#[cfg(feature = "serde")]
+mod serde_remote;
 
 // #[cfg(all(test, feature = "alloc"))]
 #[cfg(false)]
@@ -217,6 +551,12 @@ mod tests {
 
         assert_eq!(v0, "v0.d");
         assert_eq!(v1, "v1.d");
+
+        // Test vector-width SIMD display
+        let q_opts = RegFormatOpts::with_reg_class(cfg, MemorySize::_128, RegisterClass::Simd);
+        let z_opts = RegFormatOpts::with_reg_class(cfg, MemorySize::_256, RegisterClass::Simd);
+        assert_eq!(format!("{}", AArch64Reg::display(&reg0, q_opts)), "q0");
+        assert_eq!(format!("{}", AArch64Reg::display(&reg1, z_opts)), "z1");
     }
 
     #[test]
@@ -347,6 +687,33 @@ pub enum ConditionCode {
     NV = 0b1111,
 }
 
+impl ConditionCode {
+    /// Returns the condition that holds exactly when `self` does not.
+    ///
+    /// `AL` and `NV` invert to each other, but both always hold.
+    pub fn invert(self) -> Self {
+        // Conditions come in pairs that differ only in the low bit.
+        match self {
+            ConditionCode::EQ => ConditionCode::NE,
+            ConditionCode::NE => ConditionCode::EQ,
+            ConditionCode::HS => ConditionCode::LO,
+            ConditionCode::LO => ConditionCode::HS,
+            ConditionCode::MI => ConditionCode::PL,
+            ConditionCode::PL => ConditionCode::MI,
+            ConditionCode::VS => ConditionCode::VC,
+            ConditionCode::VC => ConditionCode::VS,
+            ConditionCode::HI => ConditionCode::LS,
+            ConditionCode::LS => ConditionCode::HI,
+            ConditionCode::GE => ConditionCode::LT,
+            ConditionCode::LT => ConditionCode::GE,
+            ConditionCode::GT => ConditionCode::LE,
+            ConditionCode::LE => ConditionCode::GT,
+            ConditionCode::AL => ConditionCode::NV,
+            ConditionCode::NV => ConditionCode::AL,
+        }
+    }
+}
+
 impl Display for ConditionCode {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -369,3 +736,316 @@ impl Display for ConditionCode {
         }
     }
 }
+
+/// Expected direction of a conditional branch.
+///
+/// Hints never change what a branch does. Layout helpers (see
+/// [`out::branch_hint`]) use them to keep the expected path in fall-through
+/// order. AArch64 has no
+/// hint encoding, so binary and text writers drop them.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum BranchHint {
+    /// No expectation.
+    #[default]
+    None,
+    /// The branch is expected to be taken.
+    Likely,
+    /// The branch is expected to fall through.
+    Unlikely,
+}
+
+/// Which indirect branches a BTI landing pad accepts.
+///
+/// The value is the `op2` field of the `bti` hint.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum BtiTarget {
+    /// Indirect calls (`blr`), as at function entries (`bti c`).
+    C = 0b010,
+    /// Indirect jumps (`br`), as at jump table cases (`bti j`).
+    J = 0b100,
+    /// Both indirect calls and jumps (`bti jc`).
+    JC = 0b110,
+}
+
+impl Display for BtiTarget {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BtiTarget::C => write!(f, "c"),
+            BtiTarget::J => write!(f, "j"),
+            BtiTarget::JC => write!(f, "jc"),
+        }
+    }
+}
+
+/// Shareability domain and access types ordered by a DMB or DSB barrier.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum Barrier {
+    /// Full system, all accesses (`sy`).
+    SY = 0b1111,
+    /// Inner shareable, all accesses (`ish`).
+    ISH = 0b1011,
+    /// Inner shareable, loads before loads and stores (`ishld`).
+    ISHLD = 0b1001,
+    /// Inner shareable, stores before stores (`ishst`).
+    ISHST = 0b1010,
+}
+
+impl Display for Barrier {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Barrier::SY => write!(f, "sy"),
+            Barrier::ISH => write!(f, "ish"),
+            Barrier::ISHLD => write!(f, "ishld"),
+            Barrier::ISHST => write!(f, "ishst"),
+        }
+    }
+}
+
+/// What a PRFM prefetch fetches for.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum PrfKind {
+    /// Data that will be loaded (`pld`).
+    #[default]
+    PLD = 0b00,
+    /// Instructions that will be executed (`pli`).
+    PLI = 0b01,
+    /// Data that will be stored to (`pst`).
+    PST = 0b10,
+}
+
+impl Display for PrfKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PrfKind::PLD => write!(f, "pld"),
+            PrfKind::PLI => write!(f, "pli"),
+            PrfKind::PST => write!(f, "pst"),
+        }
+    }
+}
+
+/// Whether a PRFM prefetch expects the line to be reused.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum PrfPolicy {
+    /// The line will be reused and should be kept (`keep`).
+    #[default]
+    KEEP = 0,
+    /// The line will be used once and may be evicted first (`strm`).
+    STRM = 1,
+}
+
+impl Display for PrfPolicy {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PrfPolicy::KEEP => write!(f, "keep"),
+            PrfPolicy::STRM => write!(f, "strm"),
+        }
+    }
+}
+
+/// The operation of a PRFM prefetch, such as `pldl1keep`.
+///
+/// Displays as the assembler's name for it; [`bits`](Self::bits) is the
+/// `Rt` field that encodes it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct PrfOp {
+    /// What the prefetch fetches for.
+    pub kind: PrfKind,
+    /// The cache level to fetch into, 1 to 3.
+    pub level: u8,
+    /// Whether the line is expected to be reused.
+    pub policy: PrfPolicy,
+}
+
+impl PrfOp {
+    /// Creates the operation fetching for `kind` into cache level `level`
+    /// (1 to 3) under `policy`.
+    pub const fn new(kind: PrfKind, level: u8, policy: PrfPolicy) -> Self {
+        Self {
+            kind,
+            level,
+            policy,
+        }
+    }
+
+    /// Returns the 5-bit `prfop` field: the kind, then the level less one,
+    /// then the policy.
+    pub fn bits(self) -> u32 {
+        ((self.kind as u32) << 3)
+            | ((self.level.wrapping_sub(1) as u32 & 0b11) << 1)
+            | self.policy as u32
+    }
+}
+
+impl Default for PrfOp {
+    /// Returns `pldl1keep`.
+    fn default() -> Self {
+        Self::new(PrfKind::PLD, 1, PrfPolicy::KEEP)
+    }
+}
+
+impl Display for PrfOp {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}l{}{}", self.kind, self.level, self.policy)
+    }
+}
+
+/// A data cache maintenance operation by virtual address, the operation of
+/// a DC instruction that code running at EL0 may use.
+///
+/// The discriminant is the `CRm` field of the encoding.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum DcOp {
+    /// Clean to the point of coherency, where every observer, including
+    /// devices, sees the data (`cvac`).
+    CVAC = 0b1010,
+    /// Clean to the point of unification, where the instruction and data
+    /// caches of a core see the same data (`cvau`).
+    CVAU = 0b1011,
+    /// Clean and invalidate to the point of coherency (`civac`).
+    CIVAC = 0b1110,
+}
+
+impl Display for DcOp {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DcOp::CVAC => write!(f, "cvac"),
+            DcOp::CVAU => write!(f, "cvau"),
+            DcOp::CIVAC => write!(f, "civac"),
+        }
+    }
+}
+
+/// A system register, the operand of the MRS and MSR instructions.
+///
+/// The named registers are the ones code running at EL0 uses; others are
+/// reached through [`Custom`](Self::Custom). Displays as the assembler's name,
+/// or in the generic `s<op0>_<op1>_c<n>_c<m>_<op2>` form for `Custom`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[non_exhaustive]
+pub enum SysReg {
+    /// The condition flags (`nzcv`).
+    Nzcv,
+    /// The floating-point control register (`fpcr`), holding the rounding
+    /// mode.
+    Fpcr,
+    /// The floating-point status register (`fpsr`), holding the cumulative
+    /// exception flags.
+    Fpsr,
+    /// The thread pointer (`tpidr_el0`).
+    TpidrEl0,
+    /// The read-only thread pointer (`tpidrro_el0`), set by the kernel.
+    TpidrroEl0,
+    /// The virtual counter (`cntvct_el0`).
+    CntvctEl0,
+    /// The frequency of the system counter in Hz (`cntfrq_el0`).
+    CntfrqEl0,
+    /// The cache type register (`ctr_el0`), giving the cache line sizes.
+    CtrEl0,
+    /// The block size of `dc zva` (`dczid_el0`).
+    DczidEl0,
+    /// Any other register, by its encoding: `op0` in bits 15:14, `op1` in
+    /// 13:11, `CRn` in 10:7, `CRm` in 6:3 and `op2` in 2:0.
+    Custom(u16),
+}
+
+impl SysReg {
+    /// Returns the 16-bit encoding, `op0:op1:CRn:CRm:op2`.
+    pub fn encoding(self) -> u16 {
+        let (op0, op1, crn, crm, op2) = match self {
+            SysReg::Nzcv => (3, 3, 4, 2, 0),
+            SysReg::Fpcr => (3, 3, 4, 4, 0),
+            SysReg::Fpsr => (3, 3, 4, 4, 1),
+            SysReg::TpidrEl0 => (3, 3, 13, 0, 2),
+            SysReg::TpidrroEl0 => (3, 3, 13, 0, 3),
+            SysReg::CntvctEl0 => (3, 3, 14, 0, 2),
+            SysReg::CntfrqEl0 => (3, 3, 14, 0, 0),
+            SysReg::CtrEl0 => (3, 3, 0, 0, 1),
+            SysReg::DczidEl0 => (3, 3, 0, 0, 7),
+            SysReg::Custom(encoding) => return encoding,
+        };
+        (op0 << 14) | (op1 << 11) | (crn << 7) | (crm << 3) | op2
+    }
+
+    /// Returns whether code running at EL0 can only read the register.
+    ///
+    /// [`Custom`](Self::Custom) registers are not known to be read-only.
+    pub fn is_read_only(self) -> bool {
+        matches!(
+            self,
+            SysReg::TpidrroEl0
+                | SysReg::CntvctEl0
+                | SysReg::CntfrqEl0
+                | SysReg::CtrEl0
+                | SysReg::DczidEl0
+        )
+    }
+}
+
+impl Display for SysReg {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SysReg::Nzcv => write!(f, "nzcv"),
+            SysReg::Fpcr => write!(f, "fpcr"),
+            SysReg::Fpsr => write!(f, "fpsr"),
+            SysReg::TpidrEl0 => write!(f, "tpidr_el0"),
+            SysReg::TpidrroEl0 => write!(f, "tpidrro_el0"),
+            SysReg::CntvctEl0 => write!(f, "cntvct_el0"),
+            SysReg::CntfrqEl0 => write!(f, "cntfrq_el0"),
+            SysReg::CtrEl0 => write!(f, "ctr_el0"),
+            SysReg::DczidEl0 => write!(f, "dczid_el0"),
+            SysReg::Custom(encoding) => write!(
+                f,
+                "s{}_{}_c{}_c{}_{}",
+                encoding >> 14,
+                (encoding >> 11) & 0b111,
+                (encoding >> 7) & 0b1111,
+                (encoding >> 3) & 0b1111,
+                encoding & 0b111
+            ),
+        }
+    }
+}
+
+/// The governing predicate of an SVE instruction, `p0` to `p7`.
+///
+/// Displays as the bare register (`p0`); the instruction adds the zeroing
+/// (`/z`) or merging (`/m`) qualifier. Predicates written by an instruction,
+/// such as the destination of `whilelt`, are ordinary operands in the
+/// [`RegisterClass::Predicate`] class instead.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct PredicateReg(pub u8);
+
+impl Display for PredicateReg {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "p{}", self.0)
+    }
+}
+
+/// Floating-point rounding mode, as held in the FPCR `RMode` field.
+///
+/// See [`out::rounding`] for changing it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum RoundingMode {
+    /// Round to nearest, ties to even (RN).
+    #[default]
+    NearestEven = 0b00,
+    /// Round towards plus infinity (RP).
+    Up = 0b01,
+    /// Round towards minus infinity (RM).
+    Down = 0b10,
+    /// Round towards zero (RZ).
+    TowardZero = 0b11,
+}
@@ -10,6 +10,37 @@
 //!
 //! - [`arg`]: Argument and memory operand types
 //! - [`asm`]: Assembly text output implementations
+//! - `label_scope`: Scoped label allocation with definition checks (requires `alloc`)
+//! - `const_pool`: Pooled 64-bit constants with PC-relative loads (requires `alloc`)
+//! - `got`: Patchable address slots for load-time-resolved symbols (requires `alloc`)
+//! - `veneer`: Deduplicated long-range call stubs (requires `alloc`)
+//! - `record`: Recorded writer calls for replay into other writers (requires `alloc`)
+//! - `blocks`: Functions built from basic blocks, with layout and fall-through chosen on emission (requires `alloc`)
+//! - [`multiversion`]: One function emitted for several arch configurations
+//! - [`session`]: Two-pass emission with label offsets from a sizing pass
+//! - [`dyn_writer`]: Object-safe writer facade with erased labels and context
+//! - [`error`]: Structured errors for rejected instructions and operands
+//! - [`validate`]: Rejects instructions the arch configuration does not support
+//! - [`dialect`]: Mnemonic and operand rewriting for other assemblers
+//! - [`function`]: Function symbol directives and hot/cold section placement
+//! - [`branch_align`]: Alignment of loop headers and other hot branch targets
+//! - [`coalesce`]: Merges adjacent loads and stores into `ldp`/`stp`
+//! - [`save_restore`]: Saving and restoring register sets in prologues and epilogues
+//! - [`patchpoint`]: Fixed-length call sites whose target can be rewritten atomically
+//! - [`data`]: Data directives (integers, strings, zero fill) interleaved with code
+//! - [`jump_table`]: Switch dispatch through a table of self-relative offsets
+//! - [`frame`]: Stack frame allocation with guard-page probing
+//! - [`cfi`]: BTI landing pads and return-address signing
+//! - [`branch_hint`]: Two-way branch layout by expected direction
+//! - [`instrument`]: Profiling counters and hook calls at blocks, entries and call sites
+//! - [`select`]: Branchless conditional selects and booleans from flags
+//! - [`fma`]: Fused multiply-add with a single rounding
+//! - [`tee`]: Emission to two writers at once, e.g. a listing and machine code
+//! - [`sync`]: Spinlocks with `yield` spin hints and Linux futex wait/wake system calls
+//! - [`prefetch`]: Software prefetches by locality and access, lowered to `prfm`
+//! - [`cache`]: Making written code executable with `dc cvau`/`ic ivau` maintenance
+//! - [`timestamp`]: Reads of the `cntvct_el0` system counter
+//! - [`bounds`]: Unsigned index bounds checks that branch or trap, and the checked element operand
 
 use core::error::Error;
 
@@ -26,6 +57,115 @@ pub mod asm;
 #[cfg(feature = "bin-backend")]
 pub mod bin;
 
+/// Scoped label allocation with definition checks.
+#[cfg(feature = "alloc")]
+pub mod label_scope;
+
+/// Runtime-configured layering of writer passes.
+#[cfg(feature = "alloc")]
+pub mod writer_stack;
+
+/// Multi-versioned function emission with a dispatcher stub.
+pub mod multiversion;
+
+/// Two-pass emission with label offsets from a sizing pass.
+pub mod session;
+
+/// Object-safe writer facade for runtime backend selection.
+pub mod dyn_writer;
+
+/// Structured errors for instruction emission.
+pub mod error;
+
+/// Instruction validation against the arch configuration.
+pub mod validate;
+
+/// Assembler dialects for text output.
+pub mod dialect;
+
+/// Function symbols and hot/cold placement for text output.
+pub mod function;
+
+/// Alignment of loop headers and other hot branch targets.
+pub mod branch_align;
+
+/// Coalescing of adjacent loads and stores into pair instructions.
+pub mod coalesce;
+
+/// Register save and restore sequences.
+pub mod save_restore;
+
+/// Patchable call sites for hot-patching and lazy binding.
+pub mod patchpoint;
+
+/// Data directives for jump tables and literals placed next to code.
+pub mod data;
+
+/// Jump tables for lowering dense `switch` statements.
+pub mod jump_table;
+
+/// Probed allocation of large stack frames.
+pub mod frame;
+
+/// Control-flow integrity landing pads and return-address signing.
+pub mod cfi;
+
+/// Conditional branches laid out by expected direction.
+pub mod branch_hint;
+
+/// Profiling probes injected at function entries, blocks and call sites.
+pub mod instrument;
+
+/// Branchless selects and boolean materialization with CSEL and CSET.
+pub mod select;
+
+/// Fused multiply-add through FMADD.
+pub mod fma;
+
+/// Rounding mode changes through FPCR.
+pub mod rounding;
+
+/// Vector-length-agnostic SVE loops.
+pub mod sve;
+
+/// Forwarding of every call to two writers.
+pub mod tee;
+
+/// Spinlocks and futex system calls.
+pub mod sync;
+
+/// Software prefetches.
+pub mod prefetch;
+
+/// Cache maintenance for code written at run time.
+pub mod cache;
+
+/// Reads of the system counter.
+pub mod timestamp;
+
+/// Bounds checks on array indices.
+pub mod bounds;
+
+/// Recording of writer calls for later replay.
+#[cfg(feature = "alloc")]
+pub mod record;
+
+/// Basic blocks laid out into a function.
+#[cfg(feature = "alloc")]
+pub mod blocks;
+
+/// Constant pool for 64-bit immediates and floating-point literals.
+#[cfg(feature = "alloc")]
+pub mod const_pool;
+
+/// Patchable address slots for symbols resolved at load time.
+#[cfg(feature = "alloc")]
+pub mod got;
+
+/// Long-range call veneers keyed by target symbol.
+#[cfg(feature = "alloc")]
+pub mod veneer;
+
 /// Core trait for writing AArch64 instructions.
 ///
 /// Implementors of this trait can emit individual AArch64 instructions.
@@ -45,6 +185,70 @@ pub trait WriterCore<Context> {
         todo!("brk instruction not implemented")
     }
 
+    /// Marks a point control never reaches, trapping if it does.
+    ///
+    /// Emits `brk #1`, the immediate compilers use for traps, so a debugger
+    /// can tell it from a breakpoint.
+    fn unreachable(&mut self, ctx: &mut Context, cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        self.brk(ctx, cfg, 1)
+    }
+
+    /// Stops in an attached debugger.
+    ///
+    /// Emits `brk #0xf000`, which debuggers step over rather than treat as
+    /// their own breakpoint.
+    fn debug_break(&mut self, ctx: &mut Context, cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        self.brk(ctx, cfg, 0xf000)
+    }
+
+    /// Emits an SVC (supervisor call) instruction, entering the kernel.
+    ///
+    /// Linux ignores `imm` (conventionally 0) and takes the system call
+    /// number from X8 and its arguments from X0-X5; the result is returned
+    /// in X0.
+    #[track_caller]
+    fn svc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _imm: u16,
+    ) -> Result<(), Self::Error> {
+        todo!("svc instruction not implemented")
+    }
+
+    /// Emits a BTI (branch target identification) landing pad.
+    ///
+    /// With BTI enforced, an indirect branch faults unless it lands on a
+    /// `bti` that accepts its kind; elsewhere it executes as a NOP.
+    #[track_caller]
+    fn bti(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _target: crate::BtiTarget,
+    ) -> Result<(), Self::Error> {
+        todo!("bti instruction not implemented")
+    }
+
+    /// Emits a PACIASP instruction, signing X30 with key A and SP as the
+    /// modifier.
+    ///
+    /// It also serves as a `bti c` landing pad. Without pointer
+    /// authentication it executes as a NOP.
+    #[track_caller]
+    fn paciasp(&mut self, ctx: &mut Context, _cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        todo!("paciasp instruction not implemented")
+    }
+
+    /// Emits an AUTIASP instruction, authenticating X30 signed by
+    /// [`paciasp`](Self::paciasp) with the same SP.
+    ///
+    /// A failed check corrupts X30 so that returning through it faults.
+    #[track_caller]
+    fn autiasp(&mut self, ctx: &mut Context, _cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        todo!("autiasp instruction not implemented")
+    }
+
     /// Emits a MOV (move) instruction.
     ///
     /// Copies the value from `src` to `dest`.
@@ -89,6 +293,109 @@ pub trait WriterCore<Context> {
         todo!("add instruction not implemented")
     }
 
+    /// Emits an ADDS (add, setting flags) instruction.
+    ///
+    /// Adds `a` and `b`, stores the result in `dest` and sets NZCV. C is the
+    /// unsigned carry out.
+    #[track_caller]
+    fn adds(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("adds instruction not implemented")
+    }
+
+    /// Emits an SUBS (subtract, setting flags) instruction.
+    ///
+    /// Subtracts `b` from `a`, stores the result in `dest` and sets NZCV. C
+    /// is set when the subtraction does *not* borrow.
+    #[track_caller]
+    fn subs(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("subs instruction not implemented")
+    }
+
+    /// Emits an ADCS (add with carry, setting flags) instruction.
+    ///
+    /// Computes `dest = a + b + C` and sets NZCV, continuing a carry chain
+    /// started by [`adds`](Self::adds).
+    #[track_caller]
+    fn adcs(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("adcs instruction not implemented")
+    }
+
+    /// Emits an SBCS (subtract with carry, setting flags) instruction.
+    ///
+    /// Computes `dest = a - b - !C` and sets NZCV, continuing a borrow chain
+    /// started by [`subs`](Self::subs).
+    #[track_caller]
+    fn sbcs(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sbcs instruction not implemented")
+    }
+
+    /// Emits an ADD with an immediate operand: `dest = a + imm`.
+    ///
+    /// Negative values become a SUB. The default forwards to [`add`](Self::add)
+    /// with `imm` as a literal; backends override it to pick the 12-bit or
+    /// `LSL #12` immediate form directly.
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        if imm < 0 {
+            self.sub(ctx, cfg, dest, a, &imm.unsigned_abs())
+        } else {
+            self.add(ctx, cfg, dest, a, &(imm as u64))
+        }
+    }
+
+    /// Emits a SUB with an immediate operand: `dest = a - imm`.
+    ///
+    /// Negative values become an ADD. The default forwards to [`sub`](Self::sub)
+    /// with `imm` as a literal.
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        if imm < 0 {
+            self.add(ctx, cfg, dest, a, &imm.unsigned_abs())
+        } else {
+            self.sub(ctx, cfg, dest, a, &(imm as u64))
+        }
+    }
+
     /// Emits `ADD Xd, Xn, Wm, UXTW` — extended-register ADD used by LFI.
     ///
     /// Zero-extends the 32-bit register `b` (treated as `Wm`) to 64 bits and adds it
@@ -106,6 +413,23 @@ pub trait WriterCore<Context> {
         todo!("add_uxtw instruction not implemented")
     }
 
+    /// Emits `ADD Xd, Xn, Xm, LSL #shift` — shifted-register ADD.
+    ///
+    /// Computes `dest = a + (b << shift)` in one instruction, as when forming
+    /// a scaled-index address. `shift` is 0–63.
+    #[track_caller]
+    fn add_lsl(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _shift: u8,
+    ) -> Result<(), Self::Error> {
+        todo!("add_lsl instruction not implemented")
+    }
+
     /// Emits a SXTB/SXTH/SXTW (sign-extend) instruction.
     #[track_caller]
     fn sxt(
@@ -227,6 +551,34 @@ pub trait WriterCore<Context> {
         todo!("cmp instruction not implemented")
     }
 
+    /// Emits a CMP with an immediate operand, setting flags from `a - imm`.
+    ///
+    /// Backends encode negative values as a CMN. The default forwards to
+    /// [`cmp`](Self::cmp) with `imm` as a literal.
+    fn cmp_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.cmp(ctx, cfg, a, &(imm as u64))
+    }
+
+    /// Emits a TST (test bits) instruction.
+    ///
+    /// Computes `a & b` and sets flags (`ANDS XZR, a, b`).
+    #[track_caller]
+    fn tst(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("tst instruction not implemented")
+    }
+
     /// Emits a CSEL (conditional select) instruction.
     #[track_caller]
     fn csel(
@@ -241,6 +593,19 @@ pub trait WriterCore<Context> {
         todo!("csel instruction not implemented")
     }
 
+    /// Emits a CSET (conditional set) instruction, writing 1 to `dest` if
+    /// `cond` holds and 0 otherwise.
+    #[track_caller]
+    fn cset(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _cond: ConditionCode,
+        _dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("cset instruction not implemented")
+    }
+
     /// Emits a B.cond (conditional branch) instruction.
     #[track_caller]
     fn bcond(
@@ -292,6 +657,54 @@ pub trait WriterCore<Context> {
         todo!("eor instruction not implemented")
     }
 
+    /// Emits an AND (bitwise AND) with an immediate operand.
+    ///
+    /// The default forwards to [`and`](Self::and) with `imm` as a literal;
+    /// backends override it to use the bitmask immediate form when
+    /// [`logical_imm`](crate::imm::logical_imm) accepts `imm`.
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.and(ctx, cfg, dest, a, &imm)
+    }
+
+    /// Emits an ORR (bitwise OR) with an immediate operand.
+    ///
+    /// The default forwards to [`orr`](Self::orr) with `imm` as a literal;
+    /// backends override it to use the bitmask immediate form when
+    /// [`logical_imm`](crate::imm::logical_imm) accepts `imm`.
+    fn orr_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.orr(ctx, cfg, dest, a, &imm)
+    }
+
+    /// Emits an EOR (bitwise exclusive OR) with an immediate operand.
+    ///
+    /// The default forwards to [`eor`](Self::eor) with `imm` as a literal;
+    /// backends override it to use the bitmask immediate form when
+    /// [`logical_imm`](crate::imm::logical_imm) accepts `imm`.
+    fn eor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.eor(ctx, cfg, dest, a, &imm)
+    }
+
     /// Emits a LSL (logical shift left) instruction.
     #[track_caller]
     fn lsl(
@@ -331,159 +744,784 @@ pub trait WriterCore<Context> {
         todo!("asr instruction not implemented")
     }
 
-    /// Emits an MVN (bitwise NOT) instruction.
+    /// Emits an ROR (rotate right) instruction.
     #[track_caller]
-    fn mvn(
+    fn ror(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
         _dest: &(dyn MemArg + '_),
-        _src: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("mvn instruction not implemented")
+        todo!("ror instruction not implemented")
     }
 
-    /// Emits an ADR (address of label) instruction.
+    /// Emits a CLS (count leading sign bits) instruction.
+    ///
+    /// Stores the number of bits below the sign bit of `src` that match it into `dest`.
     #[track_caller]
-    fn adr(
+    fn cls(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
         _dest: &(dyn MemArg + '_),
         _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("adr instruction not implemented")
+        todo!("cls instruction not implemented")
     }
 
-    /// Emits a RET (return) instruction.
-    #[track_caller]
-    fn ret(&mut self, ctx: &mut Context, _cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
-        todo!("ret instruction not implemented")
-    }
-
-    /// Emits an MRS NZCV (move from NZCV flags to register) instruction.
+    /// Emits a CLZ (count leading zeros) instruction.
+    ///
+    /// Stores the number of leading zero bits of `src` into `dest`.
     #[track_caller]
-    fn mrs_nzcv(
+    fn clz(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
         _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("mrs_nzcv instruction not implemented")
+        todo!("clz instruction not implemented")
     }
 
-    /// Emits an MSR NZCV (move to NZCV flags from register) instruction.
+    /// Emits a RBIT (reverse bits) instruction.
+    ///
+    /// Reverses the bit order of `src` into `dest`.
     #[track_caller]
-    fn msr_nzcv(
+    fn rbit(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
         _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("msr_nzcv instruction not implemented")
+        todo!("rbit instruction not implemented")
     }
 
-    /// Emits a MOVZ/MOVK sequence to load a 64-bit immediate.
+    /// Emits a REV (reverse bytes) instruction.
+    ///
+    /// Reverses the byte order of `src` into `dest`.
     #[track_caller]
-    fn mov_imm(
+    fn rev(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
         _dest: &(dyn MemArg + '_),
-        _val: u64,
+        _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("mov_imm instruction not implemented")
+        todo!("rev instruction not implemented")
     }
 
-    /// Emits a MUL (multiply) instruction.
+    /// Emits a UBFX (unsigned bitfield extract) instruction.
+    ///
+    /// Extracts `width` bits of `src` starting at bit `lsb`, zero-extended into `dest`.
     #[track_caller]
-    fn mul(
+    fn ubfx(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
         _dest: &(dyn MemArg + '_),
-        _a: &(dyn MemArg + '_),
-        _b: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+        _lsb: u8,
+        _width: u8,
     ) -> Result<(), Self::Error> {
-        todo!("mul instruction not implemented")
+        todo!("ubfx instruction not implemented")
     }
 
-    /// Emits a UDIV (unsigned divide) instruction.
+    /// Emits a BFI (bitfield insert) instruction.
+    ///
+    /// Copies the low `width` bits of `src` into `dest` at bit `lsb`, leaving the
+    /// other bits of `dest` unchanged.
     #[track_caller]
-    fn udiv(
+    fn bfi(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
         _dest: &(dyn MemArg + '_),
-        _a: &(dyn MemArg + '_),
-        _b: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+        _lsb: u8,
+        _width: u8,
     ) -> Result<(), Self::Error> {
-        todo!("udiv instruction not implemented")
+        todo!("bfi instruction not implemented")
     }
 
-    /// Emits a SDIV (signed divide) instruction.
+    /// Emits an MVN (bitwise NOT) instruction.
     #[track_caller]
-    fn sdiv(
+    fn mvn(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
         _dest: &(dyn MemArg + '_),
-        _a: &(dyn MemArg + '_),
-        _b: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("sdiv instruction not implemented")
+        todo!("mvn instruction not implemented")
     }
 
-    /// Emits a FADD (floating-point add) instruction.
+    /// Emits a NEG (negate) instruction.
     #[track_caller]
-    fn fadd(
+    fn neg(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
         _dest: &(dyn MemArg + '_),
-        _a: &(dyn MemArg + '_),
-        _b: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("fadd instruction not implemented")
+        todo!("neg instruction not implemented")
     }
 
-    /// Emits a FSUB (floating-point subtract) instruction.
+    /// Emits a NEGS (negate, setting flags) instruction.
     #[track_caller]
-    fn fsub(
+    fn negs(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
         _dest: &(dyn MemArg + '_),
-        _a: &(dyn MemArg + '_),
-        _b: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("fsub instruction not implemented")
+        todo!("negs instruction not implemented")
     }
 
-    /// Emits a FMUL (floating-point multiply) instruction.
+    /// Emits an ADR (address of label) instruction.
     #[track_caller]
-    fn fmul(
+    fn adr(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
         _dest: &(dyn MemArg + '_),
-        _a: &(dyn MemArg + '_),
-        _b: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("fmul instruction not implemented")
+        todo!("adr instruction not implemented")
+    }
+
+    /// Emits a RET (return) instruction.
+    #[track_caller]
+    fn ret(&mut self, ctx: &mut Context, _cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        todo!("ret instruction not implemented")
+    }
+
+    /// Emits an MRS (move from system register) instruction, reading `reg`
+    /// into `dest`.
+    #[track_caller]
+    fn mrs(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _reg: crate::SysReg,
+    ) -> Result<(), Self::Error> {
+        todo!("mrs instruction not implemented")
+    }
+
+    /// Emits an MSR (move to system register) instruction, writing `src` to
+    /// `reg`.
+    ///
+    /// Writing a register that is read-only at the current exception level,
+    /// such as [`CntvctEl0`](crate::SysReg::CntvctEl0) at EL0, traps.
+    #[track_caller]
+    fn msr(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _reg: crate::SysReg,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("msr instruction not implemented")
+    }
+
+    /// Emits an MRS NZCV (move from NZCV flags to register) instruction.
+    #[track_caller]
+    fn mrs_nzcv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.mrs(ctx, cfg, dest, crate::SysReg::Nzcv)
+    }
+
+    /// Emits an MSR NZCV (move to NZCV flags from register) instruction.
+    #[track_caller]
+    fn msr_nzcv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.msr(ctx, cfg, crate::SysReg::Nzcv, src)
+    }
+
+    /// Emits an MRS FPCR instruction, reading the floating-point control
+    /// register into `dest`.
+    ///
+    /// The rounding mode is in bits 23:22; see [`rounding`](crate::out::rounding).
+    #[track_caller]
+    fn mrs_fpcr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.mrs(ctx, cfg, dest, crate::SysReg::Fpcr)
+    }
+
+    /// Emits an MSR FPCR instruction, writing `src` to the floating-point
+    /// control register.
+    #[track_caller]
+    fn msr_fpcr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.msr(ctx, cfg, crate::SysReg::Fpcr, src)
+    }
+
+    /// Emits an MRS TPIDR_EL0 instruction, reading the thread pointer into `dest`.
+    ///
+    /// Thread-local variables are then addressed as `[dest, #offset]`.
+    #[track_caller]
+    fn mrs_tpidr_el0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.mrs(ctx, cfg, dest, crate::SysReg::TpidrEl0)
+    }
+
+    /// Emits a DMB (data memory barrier) instruction.
+    #[track_caller]
+    fn dmb(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _barrier: crate::Barrier,
+    ) -> Result<(), Self::Error> {
+        todo!("dmb instruction not implemented")
+    }
+
+    /// Emits a DSB (data synchronization barrier) instruction, which waits
+    /// until the accesses and cache maintenance before it have completed.
+    #[track_caller]
+    fn dsb(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _barrier: crate::Barrier,
+    ) -> Result<(), Self::Error> {
+        todo!("dsb instruction not implemented")
+    }
+
+    /// Emits an ISB (instruction synchronization barrier) instruction,
+    /// discarding instructions the core fetched before it.
+    #[track_caller]
+    fn isb(&mut self, ctx: &mut Context, _cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        todo!("isb instruction not implemented")
+    }
+
+    /// Emits a HINT instruction with hint number `imm` (0 to 127).
+    ///
+    /// Cores execute hint numbers they do not implement as `nop`s.
+    #[track_caller]
+    fn hint(&mut self, ctx: &mut Context, _cfg: crate::AArch64Arch, _imm: u8) -> Result<(), Self::Error> {
+        todo!("hint instruction not implemented")
+    }
+
+    /// Emits a YIELD instruction, a hint that the code is a spin-wait loop
+    /// and other threads could use the core.
+    fn yield_(&mut self, ctx: &mut Context, cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        self.hint(ctx, cfg, 1)
+    }
+
+    /// Emits a WFE instruction, which waits for an event, such as a SEV or
+    /// the clearing of an exclusive monitor, before continuing.
+    fn wfe(&mut self, ctx: &mut Context, cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        self.hint(ctx, cfg, 2)
+    }
+
+    /// Emits a SEV instruction, which sends an event to every core, waking
+    /// those waiting in WFE.
+    fn sev(&mut self, ctx: &mut Context, cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        self.hint(ctx, cfg, 4)
+    }
+
+    /// Emits a PRFM instruction, a hint to fetch the cache line holding
+    /// `mem` as `op` says, such as `pldl1keep`.
+    ///
+    /// Prefetches never fault, so `mem` may be any address.
+    #[track_caller]
+    fn prfm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _op: crate::PrfOp,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("prfm instruction not implemented")
+    }
+
+    /// Emits a DC instruction, performing `op` on the data cache line
+    /// holding the address in the register `addr`.
+    #[track_caller]
+    fn dc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _op: crate::DcOp,
+        _addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("dc instruction not implemented")
+    }
+
+    /// Emits an IC IVAU instruction, invalidating the instruction cache
+    /// line holding the address in the register `addr` to the point of
+    /// unification.
+    ///
+    /// See [`cache`] for the sequence that makes written code executable.
+    #[track_caller]
+    fn ic_ivau(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("ic ivau instruction not implemented")
+    }
+
+    /// Emits an LDAR (load-acquire) instruction.
+    ///
+    /// `mem` must be a bare base register (`[xn]`); the access width follows
+    /// `dest`.
+    #[track_caller]
+    fn ldar(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("ldar instruction not implemented")
+    }
+
+    /// Emits an STLR (store-release) instruction.
+    ///
+    /// `mem` must be a bare base register (`[xn]`); the access width follows
+    /// `src`.
+    #[track_caller]
+    fn stlr(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _src: &(dyn MemArg + '_),
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("stlr instruction not implemented")
+    }
+
+    /// Emits an LSE SWPAL (atomic swap, acquire-release) instruction.
+    ///
+    /// Stores `src` to `mem` and loads the old value into `dest`, which may
+    /// be the same register. `mem` must be a bare base register (`[xn]`);
+    /// the access width follows `src`. Requires [`AArch64Arch::lse`].
+    ///
+    /// [`AArch64Arch::lse`]: crate::AArch64Arch::lse
+    #[track_caller]
+    fn swpal(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _src: &(dyn MemArg + '_),
+        _dest: &(dyn MemArg + '_),
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("swpal instruction not implemented")
+    }
+
+    /// Emits an ADRP instruction: the address of the 4 KiB page holding
+    /// `src`, PC-relative within ±4 GiB.
+    ///
+    /// Pair it with [`add_lo12`](Self::add_lo12) for the full address.
+    #[track_caller]
+    fn adrp(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("adrp instruction not implemented")
+    }
+
+    /// Emits an ADD of the low 12 bits of `sym`'s address (`:lo12:sym`).
+    #[track_caller]
+    fn add_lo12(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+        _sym: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("add_lo12 instruction not implemented")
+    }
+
+    /// Emits a MOVZ/MOVK sequence to load a 64-bit immediate.
+    #[track_caller]
+    fn mov_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _val: u64,
+    ) -> Result<(), Self::Error> {
+        todo!("mov_imm instruction not implemented")
+    }
+
+    /// Emits a MUL (multiply) instruction.
+    #[track_caller]
+    fn mul(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("mul instruction not implemented")
+    }
+
+    /// Emits a UMULH (unsigned multiply, high 64 bits) instruction.
+    #[track_caller]
+    fn umulh(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("umulh instruction not implemented")
+    }
+
+    /// Emits an SMULH (signed multiply, high 64 bits) instruction.
+    #[track_caller]
+    fn smulh(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("smulh instruction not implemented")
+    }
+
+    /// Emits a UDIV (unsigned divide) instruction.
+    #[track_caller]
+    fn udiv(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("udiv instruction not implemented")
+    }
+
+    /// Emits a SDIV (signed divide) instruction.
+    #[track_caller]
+    fn sdiv(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sdiv instruction not implemented")
+    }
+
+    /// Emits a FADD (floating-point add) instruction.
+    ///
+    /// This and the other scalar floating-point instructions use single
+    /// precision when their floating-point operands are 32 bits wide, half
+    /// precision when they are 16 bits wide (which needs
+    /// [`AArch64Arch::fp16`](crate::AArch64Arch::fp16), except for `fcvt`),
+    /// and double precision otherwise.
+    #[track_caller]
+    fn fadd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fadd instruction not implemented")
+    }
+
+    /// Emits a FSUB (floating-point subtract) instruction.
+    #[track_caller]
+    fn fsub(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fsub instruction not implemented")
+    }
+
+    /// Emits a FMUL (floating-point multiply) instruction.
+    #[track_caller]
+    fn fmul(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmul instruction not implemented")
     }
 
     /// Emits a FDIV (floating-point divide) instruction.
     #[track_caller]
-    fn fdiv(
+    fn fdiv(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fdiv instruction not implemented")
+    }
+
+    /// Emits an FMADD (fused multiply-add): `dest = a * b + c`, rounding once.
+    #[track_caller]
+    fn fmadd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmadd instruction not implemented")
+    }
+
+    /// Emits an FMSUB (fused multiply-subtract): `dest = c - a * b`, rounding once.
+    #[track_caller]
+    fn fmsub(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmsub instruction not implemented")
+    }
+
+    /// Emits an FNMADD (negated fused multiply-add): `dest = -(a * b) - c`, rounding once.
+    #[track_caller]
+    fn fnmadd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fnmadd instruction not implemented")
+    }
+
+    /// Emits an FNMSUB (negated fused multiply-subtract): `dest = a * b - c`, rounding once.
+    #[track_caller]
+    fn fnmsub(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fnmsub instruction not implemented")
+    }
+
+    /// Emits an FCMP instruction, setting NZCV from comparing `a` with `b`.
+    ///
+    /// An unordered result (either operand NaN) sets C and V.
+    #[track_caller]
+    fn fcmp(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcmp instruction not implemented")
+    }
+
+    /// Emits an FMIN instruction: `dest = min(a, b)`, or NaN if either operand is NaN.
+    #[track_caller]
+    fn fmin(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmin instruction not implemented")
+    }
+
+    /// Emits an FMAX instruction: `dest = max(a, b)`, or NaN if either operand is NaN.
+    #[track_caller]
+    fn fmax(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmax instruction not implemented")
+    }
+
+    /// Emits an FSQRT (floating-point square root) instruction.
+    #[track_caller]
+    fn fsqrt(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fsqrt instruction not implemented")
+    }
+
+    /// Emits an FABS (floating-point absolute value) instruction.
+    #[track_caller]
+    fn fabs(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fabs instruction not implemented")
+    }
+
+    /// Emits an FNEG (floating-point negate) instruction.
+    #[track_caller]
+    fn fneg(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fneg instruction not implemented")
+    }
+
+    /// Emits an FCSEL instruction: `dest = cond ? a : b` on FP registers.
+    #[track_caller]
+    fn fcsel(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _cond: ConditionCode,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcsel instruction not implemented")
+    }
+
+    /// Emits an SCVTF instruction, converting the signed integer `src` to a
+    /// double in `dest`.
+    ///
+    /// A 32-bit `src` selects the W-register form.
+    #[track_caller]
+    fn scvtf(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("scvtf instruction not implemented")
+    }
+
+    /// Emits a UCVTF instruction, converting the unsigned integer `src` to a
+    /// double in `dest`.
+    ///
+    /// A 32-bit `src` selects the W-register form.
+    #[track_caller]
+    fn ucvtf(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("ucvtf instruction not implemented")
+    }
+
+    /// Emits an FCVTZS instruction, converting the double `src` to a signed
+    /// integer in `dest`, rounding toward zero.
+    ///
+    /// Out-of-range values saturate and NaN converts to 0. A 32-bit `dest`
+    /// selects the W-register form.
+    #[track_caller]
+    fn fcvtzs(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::AArch64Arch,
         _dest: &(dyn MemArg + '_),
-        _a: &(dyn MemArg + '_),
-        _b: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("fdiv instruction not implemented")
+        todo!("fcvtzs instruction not implemented")
+    }
+
+    /// Emits an FCVTZU instruction, converting the double `src` to an unsigned
+    /// integer in `dest`, rounding toward zero.
+    ///
+    /// Out-of-range values saturate and NaN converts to 0. A 32-bit `dest`
+    /// selects the W-register form.
+    #[track_caller]
+    fn fcvtzu(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvtzu instruction not implemented")
+    }
+
+    /// Emits a FCVT (floating-point precision conversion) instruction.
+    ///
+    /// Converts between single and double precision; the sizes of `dest`
+    /// and `src` select the direction and must differ.
+    #[track_caller]
+    fn fcvt(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt instruction not implemented")
     }
 
     /// Emits a FMOV (floating-point move) instruction.
@@ -498,6 +1536,232 @@ pub trait WriterCore<Context> {
         todo!("fmov instruction not implemented")
     }
 
+    /// Emits an SVE WHILELT instruction.
+    ///
+    /// Sets the elements of `dest`, a [`Predicate`](crate::RegisterClass::Predicate)
+    /// register whose size names the element size, that are numbered below
+    /// `b - a`, and sets the flags so `b.first` (`b.mi`) branches if the
+    /// first element is active. `a` and `b` are compared as signed
+    /// integers of their width.
+    #[track_caller]
+    fn whilelt(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("whilelt instruction not implemented")
+    }
+
+    /// Emits an SVE PTRUE instruction, setting every element of `dest`.
+    #[track_caller]
+    fn ptrue(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("ptrue instruction not implemented")
+    }
+
+    /// Emits an SVE INCB, INCH, INCW or INCD instruction, adding the number
+    /// of `elem`-sized elements in a vector to the general-purpose `dest`.
+    #[track_caller]
+    fn sve_inc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _elem: MemorySize,
+    ) -> Result<(), Self::Error> {
+        todo!("sve_inc instruction not implemented")
+    }
+
+    /// Emits an SVE contiguous load (LD1B, LD1H, LD1W or LD1D).
+    ///
+    /// Loads the elements of `dest` that are active in `pg` and zeroes the
+    /// others. The element size follows `dest`, and each element fills one
+    /// element of memory. `mem` is a bare base register (`[xn]`) or a base
+    /// plus an index scaled by the element size (`[xn, xm, lsl #3]`).
+    ///
+    /// This and the other SVE instructions treat vector operands as Z
+    /// registers whose size names the element size.
+    #[track_caller]
+    fn sve_ld1(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _pg: crate::PredicateReg,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sve_ld1 instruction not implemented")
+    }
+
+    /// Emits an SVE contiguous store (ST1B, ST1H, ST1W or ST1D), storing the
+    /// elements of `src` that are active in `pg`.
+    ///
+    /// `mem` takes the same forms as for [`sve_ld1`](Self::sve_ld1).
+    #[track_caller]
+    fn sve_st1(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _src: &(dyn MemArg + '_),
+        _pg: crate::PredicateReg,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sve_st1 instruction not implemented")
+    }
+
+    /// Emits an SVE LD1D gather load.
+    ///
+    /// Loads each active doubleword of `dest` from `base` plus the matching
+    /// doubleword of `offsets`, multiplied by 8 when `scaled` is set, and
+    /// zeroes the inactive ones.
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    fn sve_ld1d_gather(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _pg: crate::PredicateReg,
+        _base: &(dyn MemArg + '_),
+        _offsets: &(dyn MemArg + '_),
+        _scaled: bool,
+    ) -> Result<(), Self::Error> {
+        todo!("sve_ld1d_gather instruction not implemented")
+    }
+
+    /// Emits an SVE ST1D scatter store, the inverse of
+    /// [`sve_ld1d_gather`](Self::sve_ld1d_gather).
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    fn sve_st1d_scatter(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _src: &(dyn MemArg + '_),
+        _pg: crate::PredicateReg,
+        _base: &(dyn MemArg + '_),
+        _offsets: &(dyn MemArg + '_),
+        _scaled: bool,
+    ) -> Result<(), Self::Error> {
+        todo!("sve_st1d_scatter instruction not implemented")
+    }
+
+    /// Emits an unpredicated SVE integer ADD.
+    #[track_caller]
+    fn sve_add(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sve_add instruction not implemented")
+    }
+
+    /// Emits an unpredicated SVE integer SUB.
+    #[track_caller]
+    fn sve_sub(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sve_sub instruction not implemented")
+    }
+
+    /// Emits an unpredicated SVE FADD.
+    ///
+    /// This and the other SVE floating-point instructions take 16-, 32- or
+    /// 64-bit elements.
+    #[track_caller]
+    fn sve_fadd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sve_fadd instruction not implemented")
+    }
+
+    /// Emits an unpredicated SVE FSUB.
+    #[track_caller]
+    fn sve_fsub(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sve_fsub instruction not implemented")
+    }
+
+    /// Emits an unpredicated SVE FMUL.
+    #[track_caller]
+    fn sve_fmul(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sve_fmul instruction not implemented")
+    }
+
+    /// Emits an SVE FMLA, adding `a * b` to the elements of `dest` active in
+    /// `pg` with a single rounding and leaving the others unchanged.
+    #[track_caller]
+    fn sve_fmla(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _pg: crate::PredicateReg,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sve_fmla instruction not implemented")
+    }
+
+    /// Emits an SVE DUP, copying the general-purpose `src` into every
+    /// element of `dest`.
+    #[track_caller]
+    fn sve_dup(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sve_dup instruction not implemented")
+    }
+
+    /// Emits raw bytes as data.
+    ///
+    /// Generates a `.byte` directive (or equivalent) for the given bytes.
+    #[track_caller]
+    fn db(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _bytes: &[u8],
+    ) -> Result<(), Self::Error> {
+        todo!("db directive not implemented")
+    }
+
     /// Returns the current byte offset into the output stream, if tracked.
     ///
     /// Binary writers return `Some(n)`; text writers return `None`.
@@ -517,6 +1781,42 @@ pub trait WriterCore<Context> {
     ) -> Result<(), Self::Error> {
         todo!("align_to not implemented")
     }
+
+    /// Emits a `nop` instruction.
+    #[track_caller]
+    fn nop(&mut self, ctx: &mut Context, _cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        todo!("nop not implemented")
+    }
+
+    /// Emits exactly `n_bytes` bytes of `nop` instructions.
+    ///
+    /// `n_bytes` must be a multiple of 4; otherwise an
+    /// [`EmitError::InvalidOperands`](error::EmitError::InvalidOperands) is
+    /// returned.
+    #[track_caller]
+    fn nops(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        todo!("nops not implemented")
+    }
+
+    /// Pads with `nop`s to the next `1 << pow2`-byte boundary.
+    ///
+    /// Text writers emit a `.p2align` directive; binary writers emit the
+    /// `nop`s directly. Use it before loop headers and other hot branch
+    /// targets.
+    #[track_caller]
+    fn align(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _pow2: u32,
+    ) -> Result<(), Self::Error> {
+        todo!("align not implemented")
+    }
 }
 
 /// Extended writer trait with label support.
@@ -570,6 +1870,22 @@ pub trait Writer<L, Context>: WriterCore<Context> {
         todo!("bcond_label not implemented")
     }
 
+    /// Emits a B.cond to a label, expected to go as `hint` says.
+    ///
+    /// AArch64 has no branch hint encoding; by default this is
+    /// [`bcond_label`](Self::bcond_label). Writers that lay out code
+    /// themselves may use the hint.
+    fn bcond_label_hinted(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        cond: crate::ConditionCode,
+        label: L,
+        _hint: crate::BranchHint,
+    ) -> Result<(), Self::Error> {
+        self.bcond_label(ctx, cfg, cond, label)
+    }
+
     /// Emits a BL (branch with link) instruction to a label.
     #[track_caller]
     fn bl_label(
@@ -580,127 +1896,453 @@ pub trait Writer<L, Context>: WriterCore<Context> {
     ) -> Result<(), Self::Error> {
         todo!("bl_label not implemented")
     }
+
+    /// Emits a 32-bit data word holding the signed offset from the word
+    /// itself to `label` (`.4byte label - .`).
+    ///
+    /// Entries of position-independent jump tables are written this way (see
+    /// [`jump_table`]).
+    #[track_caller]
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::AArch64Arch,
+        _label: L,
+    ) -> Result<(), Self::Error> {
+        todo!("dd_label not implemented")
+    }
+
+    /// Runs `body` inside a [`LabelScope`](label_scope::LabelScope) that allocates
+    /// labels with `alloc`.
+    ///
+    /// Once `body` returns, every label it allocated must have been defined exactly
+    /// once; otherwise a [`LabelScopeError`](label_scope::LabelScopeError) is returned.
+    #[cfg(feature = "alloc")]
+    fn loop_scope<T, F: FnMut() -> L>(
+        &mut self,
+        ctx: &mut Context,
+        alloc: F,
+        body: impl FnOnce(
+            &mut label_scope::LabelScope<'_, Self, L, F>,
+            &mut Context,
+        ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>,
+    ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>
+    where
+        Self: Sized,
+        L: Clone + PartialEq,
+    {
+        let mut scope = label_scope::LabelScope::new(self, alloc);
+        let result = body(&mut scope, ctx)?;
+        scope.finish()?;
+        Ok(result)
+    }
+
+    /// Allocates a single label with `alloc` and passes it to `body`, like
+    /// [`Writer::loop_scope`].
+    #[cfg(feature = "alloc")]
+    fn with_label<T, F: FnMut() -> L>(
+        &mut self,
+        ctx: &mut Context,
+        alloc: F,
+        body: impl FnOnce(
+            &mut label_scope::LabelScope<'_, Self, L, F>,
+            &mut Context,
+            L,
+        ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>,
+    ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>
+    where
+        Self: Sized,
+        L: Clone + PartialEq,
+    {
+        self.loop_scope(ctx, alloc, |scope, ctx| {
+            let label = scope.label();
+            body(scope, ctx, label)
+        })
+    }
 }
 
 #[macro_export]
 macro_rules! writer_dispatch {
+    (@core [ $($t:tt)* ] $ty:ty => $e:ty [$ctx:ty] [$wrapped:ty]) => {
+        impl<$($t)*> $crate::out::WriterCore<$ctx> for $ty{
+            type Error = $e;
+            fn brk(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, imm: u16) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::brk(&mut **self, ctx, cfg, imm)
+            }
+            fn unreachable(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::unreachable(&mut **self, ctx, cfg)
+            }
+            fn debug_break(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::debug_break(&mut **self, ctx, cfg)
+            }
+            fn svc(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, imm: u16) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::svc(&mut **self, ctx, cfg, imm)
+            }
+            fn bti(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, target: $crate::BtiTarget) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::bti(&mut **self, ctx, cfg, target)
+            }
+            fn paciasp(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::paciasp(&mut **self, ctx, cfg)
+            }
+            fn autiasp(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::autiasp(&mut **self, ctx, cfg)
+            }
+            fn mov(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mov(&mut **self, ctx, cfg, dest, src)
+            }
+            fn str(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::str(&mut **self, ctx, cfg, src, mem)
+            }
+            fn ldr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ldr(&mut **self, ctx, cfg, dest, mem)
+            }
+            fn stp(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, src1: &(dyn $crate::out::arg::MemArg + '_), src2: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::stp(&mut **self, ctx, cfg, src1, src2, mem)
+            }
+            fn ldp(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest1: &(dyn $crate::out::arg::MemArg + '_), dest2: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ldp(&mut **self, ctx, cfg, dest1, dest2, mem)
+            }
+            fn bl(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, target: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::bl(&mut **self, ctx, cfg, target)
+            }
+            fn br(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, target: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::br(&mut **self, ctx, cfg, target)
+            }
+            fn b(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, target: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::b(&mut **self, ctx, cfg, target)
+            }
+            fn cmp(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cmp(&mut **self, ctx, cfg, a, b)
+            }
+            fn tst(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::tst(&mut **self, ctx, cfg, a, b)
+            }
+            fn csel(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, dest: &(dyn $crate::out::arg::MemArg + '_), true_val: &(dyn $crate::out::arg::MemArg + '_), false_val: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::csel(&mut **self, ctx, cfg, cond, dest, true_val, false_val)
+            }
+            fn cset(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cset(&mut **self, ctx, cfg, cond, dest)
+            }
+            fn bcond(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, target: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::bcond(&mut **self, ctx, cfg, cond, target)
+            }
+            fn adr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::adr(&mut **self, ctx, cfg, dest, src)
+            }
+            fn ret(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ret(&mut **self, ctx, cfg)
+            }
+            fn mov_imm(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), val: u64) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mov_imm(&mut **self, ctx, cfg, dest, val)
+            }
+            fn mul(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mul(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn umulh(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::umulh(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn smulh(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::smulh(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn udiv(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::udiv(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sdiv(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sdiv(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn and(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::and(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn orr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::orr(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn eor(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::eor(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn lsl(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lsl(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn lsr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lsr(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn asr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::asr(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn ror(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ror(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn cls(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cls(&mut **self, ctx, cfg, dest, src)
+            }
+            fn clz(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::clz(&mut **self, ctx, cfg, dest, src)
+            }
+            fn rbit(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::rbit(&mut **self, ctx, cfg, dest, src)
+            }
+            fn rev(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::rev(&mut **self, ctx, cfg, dest, src)
+            }
+            fn ubfx(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), lsb: u8, width: u8) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ubfx(&mut **self, ctx, cfg, dest, src, lsb, width)
+            }
+            fn bfi(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), lsb: u8, width: u8) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::bfi(&mut **self, ctx, cfg, dest, src, lsb, width)
+            }
+            fn sub(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sub(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn add(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::add(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn adds(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::adds(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn subs(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::subs(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn adcs(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::adcs(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sbcs(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sbcs(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn add_imm(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: i64) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::add_imm(&mut **self, ctx, cfg, dest, a, imm)
+            }
+            fn sub_imm(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: i64) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sub_imm(&mut **self, ctx, cfg, dest, a, imm)
+            }
+            fn and_imm(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: u64) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::and_imm(&mut **self, ctx, cfg, dest, a, imm)
+            }
+            fn orr_imm(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: u64) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::orr_imm(&mut **self, ctx, cfg, dest, a, imm)
+            }
+            fn eor_imm(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: u64) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::eor_imm(&mut **self, ctx, cfg, dest, a, imm)
+            }
+            fn cmp_imm(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i64) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cmp_imm(&mut **self, ctx, cfg, a, imm)
+            }
+            fn add_uxtw(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::add_uxtw(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn add_lsl(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), shift: u8) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::add_lsl(&mut **self, ctx, cfg, dest, a, b, shift)
+            }
+            fn sxt(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sxt(&mut **self, ctx, cfg, dest, src)
+            }
+            fn uxt(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::uxt(&mut **self, ctx, cfg, dest, src)
+            }
+            fn mvn(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mvn(&mut **self, ctx, cfg, dest, src)
+            }
+            fn neg(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::neg(&mut **self, ctx, cfg, dest, src)
+            }
+            fn negs(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::negs(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fadd(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fadd(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fsub(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsub(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fmul(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmul(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fdiv(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fdiv(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fmadd(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmadd(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fmsub(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmsub(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fnmadd(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fnmadd(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fnmsub(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fnmsub(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fcmp(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcmp(&mut **self, ctx, cfg, a, b)
+            }
+            fn fmin(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmin(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fmax(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmax(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fsqrt(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsqrt(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fabs(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fabs(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fneg(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fneg(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcsel(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcsel(&mut **self, ctx, cfg, cond, dest, a, b)
+            }
+            fn scvtf(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::scvtf(&mut **self, ctx, cfg, dest, src)
+            }
+            fn ucvtf(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ucvtf(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvtzs(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvtzs(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvtzu(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvtzu(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fmov(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmov(&mut **self, ctx, cfg, dest, src)
+            }
+            fn whilelt(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::whilelt(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn ptrue(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ptrue(&mut **self, ctx, cfg, dest)
+            }
+            fn sve_inc(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), elem: $crate::__::MemorySize) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_inc(&mut **self, ctx, cfg, dest, elem)
+            }
+            fn sve_ld1(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), pg: $crate::PredicateReg, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_ld1(&mut **self, ctx, cfg, dest, pg, mem)
+            }
+            fn sve_st1(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_), pg: $crate::PredicateReg, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_st1(&mut **self, ctx, cfg, src, pg, mem)
+            }
+            #[allow(clippy::too_many_arguments)]
+            fn sve_ld1d_gather(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), pg: $crate::PredicateReg, base: &(dyn $crate::out::arg::MemArg + '_), offsets: &(dyn $crate::out::arg::MemArg + '_), scaled: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_ld1d_gather(&mut **self, ctx, cfg, dest, pg, base, offsets, scaled)
+            }
+            #[allow(clippy::too_many_arguments)]
+            fn sve_st1d_scatter(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_), pg: $crate::PredicateReg, base: &(dyn $crate::out::arg::MemArg + '_), offsets: &(dyn $crate::out::arg::MemArg + '_), scaled: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_st1d_scatter(&mut **self, ctx, cfg, src, pg, base, offsets, scaled)
+            }
+            fn sve_add(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_add(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sve_sub(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_sub(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sve_fadd(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_fadd(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sve_fsub(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_fsub(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sve_fmul(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_fmul(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sve_fmla(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), pg: $crate::PredicateReg, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_fmla(&mut **self, ctx, cfg, dest, pg, a, b)
+            }
+            fn sve_dup(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sve_dup(&mut **self, ctx, cfg, dest, src)
+            }
+            fn db(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, bytes: &[u8]) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::db(&mut **self, ctx, cfg, bytes)
+            }
+            fn current_offset(&self) -> ::core::option::Option<usize> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::current_offset(&**self)
+            }
+            fn align_to(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, alignment: usize) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::align_to(&mut **self, ctx, cfg, alignment)
+            }
+            fn nop(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::nop(&mut **self, ctx, cfg)
+            }
+            fn nops(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, n_bytes: usize) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::nops(&mut **self, ctx, cfg, n_bytes)
+            }
+            fn align(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, pow2: u32) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::align(&mut **self, ctx, cfg, pow2)
+            }
+            fn mrs(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), reg: $crate::SysReg) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mrs(&mut **self, ctx, cfg, dest, reg)
+            }
+            fn msr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, reg: $crate::SysReg, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::msr(&mut **self, ctx, cfg, reg, src)
+            }
+            fn mrs_nzcv(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mrs_nzcv(&mut **self, ctx, cfg, dest)
+            }
+            fn msr_nzcv(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::msr_nzcv(&mut **self, ctx, cfg, src)
+            }
+            fn mrs_fpcr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mrs_fpcr(&mut **self, ctx, cfg, dest)
+            }
+            fn msr_fpcr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::msr_fpcr(&mut **self, ctx, cfg, src)
+            }
+            fn mrs_tpidr_el0(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mrs_tpidr_el0(&mut **self, ctx, cfg, dest)
+            }
+            fn dmb(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, barrier: $crate::Barrier) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::dmb(&mut **self, ctx, cfg, barrier)
+            }
+            fn dsb(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, barrier: $crate::Barrier) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::dsb(&mut **self, ctx, cfg, barrier)
+            }
+            fn isb(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::isb(&mut **self, ctx, cfg)
+            }
+            fn hint(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, imm: u8) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::hint(&mut **self, ctx, cfg, imm)
+            }
+            fn yield_(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::yield_(&mut **self, ctx, cfg)
+            }
+            fn wfe(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::wfe(&mut **self, ctx, cfg)
+            }
+            fn sev(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sev(&mut **self, ctx, cfg)
+            }
+            fn prfm(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, op: $crate::PrfOp, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::prfm(&mut **self, ctx, cfg, op, mem)
+            }
+            fn dc(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, op: $crate::DcOp, addr: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::dc(&mut **self, ctx, cfg, op, addr)
+            }
+            fn ic_ivau(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, addr: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ic_ivau(&mut **self, ctx, cfg, addr)
+            }
+            fn ldar(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ldar(&mut **self, ctx, cfg, dest, mem)
+            }
+            fn stlr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::stlr(&mut **self, ctx, cfg, src, mem)
+            }
+            fn swpal(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_), dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::swpal(&mut **self, ctx, cfg, src, dest, mem)
+            }
+            fn adrp(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::adrp(&mut **self, ctx, cfg, dest, src)
+            }
+            fn add_lo12(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), sym: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::add_lo12(&mut **self, ctx, cfg, dest, src, sym)
+            }
+        }
+    };
     ($( [ $($t:tt)* ] [$($u:tt)*] $ty:ty => $e:ty [$l:ty] [$ctx:ty] [$wrapped:ty]),*) => {
         const _: () = {
             $(
-                impl<$($t)*> $crate::out::WriterCore<$ctx> for $ty{
-                    type Error = $e;
-                    fn brk(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, imm: u16) -> $crate::__::core::result::Result<(),Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::brk(&mut **self, ctx, cfg, imm)
-                    }
-                    fn mov(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::mov(&mut **self, ctx, cfg, dest, src)
-                    }
-                    fn str(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::str(&mut **self, ctx, cfg, src, mem)
-                    }
-                    fn ldr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::ldr(&mut **self, ctx, cfg, dest, mem)
-                    }
-                    fn stp(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, src1: &(dyn $crate::out::arg::MemArg + '_), src2: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::stp(&mut **self, ctx, cfg, src1, src2, mem)
-                    }
-                    fn ldp(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest1: &(dyn $crate::out::arg::MemArg + '_), dest2: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::ldp(&mut **self, ctx, cfg, dest1, dest2, mem)
-                    }
-                    fn bl(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, target: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::bl(&mut **self, ctx, cfg, target)
-                    }
-                    fn br(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, target: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::br(&mut **self, ctx, cfg, target)
-                    }
-                    fn b(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, target: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::b(&mut **self, ctx, cfg, target)
-                    }
-                    fn cmp(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::cmp(&mut **self, ctx, cfg, a, b)
-                    }
-                    fn csel(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, dest: &(dyn $crate::out::arg::MemArg + '_), true_val: &(dyn $crate::out::arg::MemArg + '_), false_val: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::csel(&mut **self, ctx, cfg, cond, dest, true_val, false_val)
-                    }
-                    fn bcond(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, target: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::bcond(&mut **self, ctx, cfg, cond, target)
-                    }
-                    fn adr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::adr(&mut **self, ctx, cfg, dest, src)
-                    }
-                    fn ret(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::ret(&mut **self, ctx, cfg)
-                    }
-                    fn mov_imm(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), val: u64) -> $crate::__::core::result::Result<(),Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::mov_imm(&mut **self, ctx, cfg, dest, val)
-                    }
-                    fn mul(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::mul(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn udiv(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::udiv(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn sdiv(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::sdiv(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn and(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::and(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn orr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::orr(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn eor(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::eor(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn lsl(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::lsl(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn lsr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::lsr(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn asr(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::asr(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn sub(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::sub(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn add(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::add(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn add_uxtw(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::add_uxtw(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn sxt(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::sxt(&mut **self, ctx, cfg, dest, src)
-                    }
-                    fn uxt(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::uxt(&mut **self, ctx, cfg, dest, src)
-                    }
-                    fn mvn(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::mvn(&mut **self, ctx, cfg, dest, src)
-                    }
-                    fn fadd(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::fadd(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn fsub(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::fsub(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn fmul(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::fmul(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn fdiv(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::fdiv(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn fmov(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::fmov(&mut **self, ctx, cfg, dest, src)
-                    }
-                    fn current_offset(&self) -> ::core::option::Option<usize> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::current_offset(&**self)
-                    }
-                    fn align_to(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, alignment: usize) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::align_to(&mut **self, ctx, cfg, alignment)
-                    }
-                }
+                $crate::writer_dispatch!(@core [ $($t)* ] $ty => $e [$ctx] [$wrapped]);
                 impl<$($u)*>$crate::out::Writer<$l, $ctx> for $ty{
                     fn set_label(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, s: $l) -> $crate::__::core::result::Result<(), Self::Error> {
                         <$wrapped as $crate::out::Writer<$l, $ctx>>::set_label(&mut **self, ctx, cfg, s)
@@ -714,9 +2356,15 @@ macro_rules! writer_dispatch {
                     fn bcond_label(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, label: $l) -> $crate::__::core::result::Result<(), Self::Error> {
                         <$wrapped as $crate::out::Writer<$l, $ctx>>::bcond_label(&mut **self, ctx, cfg, cond, label)
                     }
+                    fn bcond_label_hinted(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, label: $l, hint: $crate::BranchHint) -> $crate::__::core::result::Result<(), Self::Error> {
+                        <$wrapped as $crate::out::Writer<$l, $ctx>>::bcond_label_hinted(&mut **self, ctx, cfg, cond, label, hint)
+                    }
                     fn bl_label(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, label: $l) -> $crate::__::core::result::Result<(), Self::Error> {
                         <$wrapped as $crate::out::Writer<$l, $ctx>>::bl_label(&mut **self, ctx, cfg, label)
                     }
+                    fn dd_label(&mut self, ctx: &mut $ctx, cfg: $crate::AArch64Arch, label: $l) -> $crate::__::core::result::Result<(), Self::Error> {
+                        <$wrapped as $crate::out::Writer<$l, $ctx>>::dd_label(&mut **self, ctx, cfg, label)
+                    }
                 }
             )*
         };
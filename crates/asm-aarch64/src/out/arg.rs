@@ -14,30 +14,54 @@ use core::{
 };
 use typeid;
 
-/// Represents a concrete argument kind (register or literal).
+/// Represents a concrete argument kind (register, literal, or symbol).
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArgKind {
     /// A register with a specific size.
     Reg {
         /// The register.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::reg"))]
         reg: Reg,
         /// The operand size.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::memory_size"))]
         size: MemorySize,
     },
     /// A literal 64-bit value.
     Lit(u64),
+    /// The address of a named global or external function, plus `addend`.
+    ///
+    /// As a branch target or `adr` source this is the symbol itself; as the
+    /// base of a memory operand it is a PC-relative literal load
+    /// (`ldr x0, sym`).
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    Sym {
+        /// The symbol name.
+        name: &'static str,
+        /// Byte offset added to the symbol's address.
+        addend: i64,
+    },
 }
 
 impl ArgKind {
+    /// Creates a reference to the symbol `name`.
+    pub const fn sym(name: &'static str) -> Self {
+        ArgKind::Sym { name, addend: 0 }
+    }
+
     /// Creates a displayable representation of this argument kind.
     pub fn display(&self, opts: crate::DisplayOpts) -> ArgKindDisplay {
         match self {
-            ArgKind::Reg { reg, size } => ArgKindDisplay::Reg(AArch64Reg::display(
-                reg,
-                RegFormatOpts::with_reg_class(opts.arch, *size, opts.reg_class),
-            )),
+            ArgKind::Reg { reg, size } => {
+                ArgKindDisplay::Reg(AArch64Reg::display(reg, opts.reg_format(*size)))
+            }
             ArgKind::Lit(i) => ArgKindDisplay::Lit(*i),
+            ArgKind::Sym { name, addend } => ArgKindDisplay::Sym {
+                prefix: opts.arch.os.symbol_prefix(),
+                name: *name,
+                addend: *addend,
+            },
         }
     }
 }
@@ -49,6 +73,15 @@ pub enum ArgKindDisplay {
     Reg(RegDisplay),
     /// A literal value.
     Lit(u64),
+    /// A symbol reference.
+    Sym {
+        /// Prefix the object format adds to the name (`_` on Mach-O).
+        prefix: &'static str,
+        /// The symbol name.
+        name: &'static str,
+        /// Byte offset added to the symbol's address.
+        addend: i64,
+    },
 }
 
 impl Display for ArgKindDisplay {
@@ -56,6 +89,15 @@ impl Display for ArgKindDisplay {
         match self {
             ArgKindDisplay::Reg(reg_display) => write!(f, "{reg_display}"),
             ArgKindDisplay::Lit(i) => write!(f, "#{i}"), // AArch64 uses # for immediates
+            ArgKindDisplay::Sym {
+                prefix,
+                name,
+                addend,
+            } => match *addend {
+                0 => write!(f, "{prefix}{name}"),
+                a if a > 0 => write!(f, "{prefix}{name}+{a}"),
+                a => write!(f, "{prefix}{name}{a}"),
+            },
         }
     }
 }
@@ -69,6 +111,7 @@ impl Display for ArgKind {
 /// Addressing mode for memory operations.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AddressingMode {
     /// Standard offset addressing: [base, #offset]
     Offset,
@@ -84,6 +127,7 @@ pub enum AddressingMode {
 /// displacement, and size.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MemArgKind<A = ArgKind> {
     /// A direct operand (not a memory reference).
     NoMem(A),
@@ -91,11 +135,14 @@ pub enum MemArgKind<A = ArgKind> {
     Mem {
         /// The base operand.
         base: A,
-        /// Optional scaled index (operand, scale factor).
+        /// Optional shifted index (operand, left shift), addressing
+        /// `base + (index << shift)`; an x86 scale `s` becomes the shift
+        /// `s.trailing_zeros()`.
         offset: Option<(A, u32)>,
         /// Displacement added to the address (signed for AArch64).
         disp: i32,
         /// Size of the memory access.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::memory_size"))]
         size: MemorySize,
         /// Register class for the memory access.
         reg_class: crate::RegisterClass,
@@ -122,8 +169,22 @@ impl<A: Arg> MemArgKind<A> {
             } => {
                 // For memory operands, force base and offset to be GPRs
                 let gpr_opts = crate::DisplayOpts::new(opts.arch);
+                let base = base.display(gpr_opts);
+                // `[sym + disp]` is written as a PC-relative literal operand.
+                if let ArgKindDisplay::Sym {
+                    prefix,
+                    name,
+                    addend,
+                } = base
+                {
+                    return MemArgKind::NoMem(ArgKindDisplay::Sym {
+                        prefix,
+                        name,
+                        addend: addend + *disp as i64,
+                    });
+                }
                 MemArgKind::Mem {
-                    base: base.display(gpr_opts),
+                    base,
                     offset: offset
                         .as_ref()
                         .map(|(a, scale)| (a.display(gpr_opts), *scale)),
@@ -137,6 +198,20 @@ impl<A: Arg> MemArgKind<A> {
     }
 }
 
+impl MemArgKind<ArgKind> {
+    /// Creates a PC-relative operand addressing the global `name`.
+    pub fn sym(name: &'static str, size: MemorySize) -> Self {
+        MemArgKind::Mem {
+            base: ArgKind::sym(name),
+            offset: None,
+            disp: 0,
+            size,
+            reg_class: crate::RegisterClass::Gpr,
+            mode: AddressingMode::Offset,
+        }
+    }
+}
+
 impl<T: Display> Display for MemArgKind<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -151,14 +226,14 @@ impl<T: Display> Display for MemArgKind<T> {
             } => {
                 match mode {
                     AddressingMode::Offset => {
-                        // Standard offset: [base, #disp] or [base, offset, LSL #scale]
+                        // Standard offset: [base, #disp] or [base, offset, LSL #shift]
                         write!(f, "[")?;
                         write!(f, "{base}")?;
 
-                        if let Some((off, scale)) = offset {
+                        if let Some((off, shift)) = offset {
                             write!(f, ", {off}")?;
-                            if *scale > 0 {
-                                write!(f, ", LSL #{scale}")?;
+                            if *shift > 0 {
+                                write!(f, ", LSL #{shift}")?;
                             }
                         }
 
@@ -379,7 +454,7 @@ pub trait Arg: MemArg {
 
         match self.kind() {
             ArgKind::Reg { reg, size: _ } => ::alloc::boxed::Box::new([reg].into_iter()),
-            ArgKind::Lit(_) => ::alloc::boxed::Box::new(empty()),
+            ArgKind::Lit(_) | ArgKind::Sym { .. } => ::alloc::boxed::Box::new(empty()),
         }
     }
 }
@@ -413,15 +488,11 @@ impl Arg for Reg {
     fn display(&self, opts: crate::DisplayOpts) -> ArgKindDisplay {
         ArgKindDisplay::Reg(AArch64Reg::display(
             self,
-            RegFormatOpts::with_reg_class(opts.arch, Default::default(), opts.reg_class),
+            opts.reg_format(Default::default()),
         ))
     }
     fn format(&self, f: &mut Formatter<'_>, opts: crate::DisplayOpts) -> core::fmt::Result {
-        AArch64Reg::format(
-            self,
-            f,
-            &RegFormatOpts::with_reg_class(opts.arch, Default::default(), opts.reg_class),
-        )
+        AArch64Reg::format(self, f, &opts.reg_format(Default::default()))
     }
     #[cfg(feature = "alloc")]
     fn regs<'a>(&'a self) -> ::alloc::boxed::Box<dyn Iterator<Item = Reg> + 'a> {
@@ -468,7 +539,7 @@ impl<T: Arg> Arg for MemorySized<T> {
         if typeid::of::<T>() == typeid::of::<Reg>() {
             ArgKindDisplay::Reg(AArch64Reg::display(
                 unsafe { transmute::<&T, &Reg>(value) },
-                RegFormatOpts::with_reg_class(opts.arch, *size, opts.reg_class),
+                opts.reg_format(*size),
             ))
         } else {
             self.kind().display(opts)
@@ -480,7 +551,7 @@ impl<T: Arg> Arg for MemorySized<T> {
             AArch64Reg::format(
                 unsafe { transmute::<&T, &Reg>(value) },
                 f,
-                &RegFormatOpts::with_reg_class(opts.arch, *size, opts.reg_class),
+                &opts.reg_format(*size),
             )
         } else {
             write!(f, "{}", self.display(opts))
@@ -496,7 +567,7 @@ impl<T: MemArg> MemArg for MemorySized<T> {
                     reg,
                     size: self.size,
                 },
-                ArgKind::Lit(l) => ArgKind::Lit(l),
+                other => other,
             }),
             MemArgKind::Mem {
                 base,
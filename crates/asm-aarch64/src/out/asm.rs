@@ -3,6 +3,11 @@
 //! This module provides macro-based implementations of [`WriterCore`] and [`Writer`]
 //! for types that implement [`core::fmt::Write`], enabling assembly code to be
 //! written as text.
+//!
+//! Register 31 prints as `sp` in address bases and add/sub immediates, and as
+//! `xzr`/`wzr` in store sources and the register operands of logical,
+//! multiply, divide, shift, bitfield and conditional-select instructions,
+//! matching what the binary encoder emits for it.
 
 use super::*;
 use core::fmt::{Display, Formatter, Write};
@@ -21,15 +26,34 @@ macro_rules! writers {
                 fn brk(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, imm: u16) -> $crate::__::core::result::Result<(),Self::Error>{
                     $crate::__::core::write!(self,"brk #{imm}\n")
                 }
+                fn svc(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, imm: u16) -> $crate::__::core::result::Result<(),Self::Error>{
+                    $crate::__::core::write!(self,"svc #{imm}\n")
+                }
+                fn bti(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, target: $crate::BtiTarget) -> $crate::__::core::result::Result<(),Self::Error>{
+                    $crate::__::core::write!(self,"bti {target}\n")
+                }
+                fn paciasp(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(),Self::Error>{
+                    $crate::__::core::write!(self,"paciasp\n")
+                }
+                fn autiasp(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(),Self::Error>{
+                    $crate::__::core::write!(self,"autiasp\n")
+                }
 
                 fn mov(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let is_sym = $crate::__::core::matches!(src.concrete_mem_kind(), $crate::out::arg::MemArgKind::NoMem($crate::out::arg::ArgKind::Sym { .. }));
                     let dest = dest.mem_display(cfg.into());
                     let src = src.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"mov {dest}, {src}\n")
+                    match (is_sym, cfg.pic) {
+                        (true, true) if cfg.os == $crate::TargetOs::Apple => $crate::__::core::write!(self,"adrp {dest}, {src}@PAGE\nadd {dest}, {dest}, {src}@PAGEOFF\n"),
+                        (true, true) => $crate::__::core::write!(self,"adrp {dest}, {src}\nadd {dest}, {dest}, :lo12:{src}\n"),
+                        (true, false) => $crate::__::core::write!(self,"ldr {dest}, ={src}\n"),
+                        _ => $crate::__::core::write!(self,"mov {dest}, {src}\n"),
+                    }
                 }
 
                 fn str(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                    let src = src.mem_display(cfg.into());
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let src = src.mem_display(zr);
                     let mem = mem.mem_display(cfg.into());
                     $crate::__::core::write!(self,"str {src}, {mem}\n")
                 }
@@ -41,8 +65,9 @@ macro_rules! writers {
                 }
 
                 fn stp(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, src1: &(dyn $crate::out::arg::MemArg + '_), src2: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                    let src1 = src1.mem_display(cfg.into());
-                    let src2 = src2.mem_display(cfg.into());
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let src1 = src1.mem_display(zr);
+                    let src2 = src2.mem_display(zr);
                     let mem = mem.mem_display(cfg.into());
                     $crate::__::core::write!(self,"stp {src1}, {src2}, {mem}\n")
                 }
@@ -55,6 +80,10 @@ macro_rules! writers {
                 }
 
                 fn bl(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, target: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    if let $crate::out::arg::MemArgKind::NoMem($crate::out::arg::ArgKind::Sym { .. }) = target.concrete_mem_kind() {
+                        let target = target.mem_display(cfg.into());
+                        return $crate::__::core::write!(self,"bl {target}\n");
+                    }
                     let target = target.mem_display(cfg.into());
                     // `bl` only accepts an immediate label.  When the target
                     // is a register we must emit `blr` (branch-with-link to
@@ -79,13 +108,33 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"cmp {a}, {b}\n")
                 }
 
+                fn cmp_imm(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i64) -> $crate::__::core::result::Result<(),Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    let op = if imm < 0 { "cmn" } else { "cmp" };
+                    $crate::__::core::write!(self,"{op} {a}, #{}\n", imm.unsigned_abs())
+                }
+
+                fn tst(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
+                    $crate::__::core::write!(self,"tst {a}, {b}\n")
+                }
+
                 fn csel(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, dest: &(dyn $crate::out::arg::MemArg + '_), true_val: &(dyn $crate::out::arg::MemArg + '_), false_val: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                    let dest = dest.mem_display(cfg.into());
-                    let true_val = true_val.mem_display(cfg.into());
-                    let false_val = false_val.mem_display(cfg.into());
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let true_val = true_val.mem_display(zr);
+                    let false_val = false_val.mem_display(zr);
                     $crate::__::core::write!(self,"csel {dest}, {true_val}, {false_val}, {cond}\n")
                 }
 
+                fn cset(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    $crate::__::core::write!(self,"cset {dest}, {cond}\n")
+                }
+
                 fn bcond(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, target: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let target = target.mem_display(cfg.into());
                     $crate::__::core::write!(self,"b.{cond} {target}\n")
@@ -101,14 +150,99 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"ret\n")
                 }
 
-                fn mrs_nzcv(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                fn mrs(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), reg: $crate::SysReg) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"mrs {dest}, {reg}\n")
+                }
+
+                fn msr(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, reg: $crate::SysReg, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let src = src.mem_display(zr);
+                    $crate::__::core::write!(self,"msr {reg}, {src}\n")
+                }
+
+                fn dmb(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, barrier: $crate::Barrier) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"dmb {barrier}\n")
+                }
+
+                fn dsb(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, barrier: $crate::Barrier) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"dsb {barrier}\n")
+                }
+
+                fn isb(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"isb\n")
+                }
+
+                fn hint(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, imm: u8) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"hint #{imm}\n")
+                }
+
+                fn yield_(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"yield\n")
+                }
+
+                fn wfe(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"wfe\n")
+                }
+
+                fn sev(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"sev\n")
+                }
+
+                fn prfm(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, op: $crate::PrfOp, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"prfm {op}, {mem}\n")
+                }
+
+                fn dc(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, op: $crate::DcOp, addr: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let addr = addr.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"dc {op}, {addr}\n")
+                }
+
+                fn ic_ivau(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, addr: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let addr = addr.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"ic ivau, {addr}\n")
+                }
+
+                fn ldar(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"ldar {dest}, {mem}\n")
+                }
+
+                fn stlr(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let src = src.mem_display(zr);
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"stlr {src}, {mem}\n")
+                }
+
+                fn swpal(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_), dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let src = src.mem_display(zr);
+                    let dest = dest.mem_display(zr);
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"swpal {src}, {dest}, {mem}\n")
+                }
+
+                fn adrp(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let dest = dest.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"mrs {dest}, nzcv\n")
+                    let src = src.mem_display(cfg.into());
+                    match cfg.os {
+                        $crate::TargetOs::Apple => $crate::__::core::write!(self,"adrp {dest}, {src}@PAGE\n"),
+                        _ => $crate::__::core::write!(self,"adrp {dest}, {src}\n"),
+                    }
                 }
 
-                fn msr_nzcv(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                fn add_lo12(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), sym: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
                     let src = src.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"msr nzcv, {src}\n")
+                    let sym = sym.mem_display(cfg.into());
+                    match cfg.os {
+                        $crate::TargetOs::Apple => $crate::__::core::write!(self,"add {dest}, {src}, {sym}@PAGEOFF\n"),
+                        _ => $crate::__::core::write!(self,"add {dest}, {src}, :lo12:{sym}\n"),
+                    }
                 }
 
                 fn mov_imm(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), val: u64) -> $crate::__::core::result::Result<(),Self::Error>{
@@ -128,60 +262,116 @@ macro_rules! writers {
                 }
 
                 fn mul(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                    let dest = dest.mem_display(cfg.into());
-                    let a = a.mem_display(cfg.into());
-                    let b = b.mem_display(cfg.into());
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
                     $crate::__::core::write!(self,"mul {dest}, {a}, {b}\n")
                 }
 
+                fn umulh(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
+                    $crate::__::core::write!(self,"umulh {dest}, {a}, {b}\n")
+                }
+
+                fn smulh(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
+                    $crate::__::core::write!(self,"smulh {dest}, {a}, {b}\n")
+                }
+
                 fn udiv(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                    let dest = dest.mem_display(cfg.into());
-                    let a = a.mem_display(cfg.into());
-                    let b = b.mem_display(cfg.into());
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
                     $crate::__::core::write!(self,"udiv {dest}, {a}, {b}\n")
                 }
 
                 fn sdiv(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                    let dest = dest.mem_display(cfg.into());
-                    let a = a.mem_display(cfg.into());
-                    let b = b.mem_display(cfg.into());
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
                     $crate::__::core::write!(self,"sdiv {dest}, {a}, {b}\n")
                 }
 
                 fn and(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                    let dest = dest.mem_display(cfg.into());
-                    let a = a.mem_display(cfg.into());
-                    let b = b.mem_display(cfg.into());
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
                     $crate::__::core::write!(self,"and {dest}, {a}, {b}\n")
                 }
 
-                fn orr(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                fn and_imm(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: u64) -> $crate::__::core::result::Result<(), Self::Error>{
                     let dest = dest.mem_display(cfg.into());
                     let a = a.mem_display(cfg.into());
-                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"and {dest}, {a}, #{imm:#x}\n")
+                }
+
+                fn orr(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
                     $crate::__::core::write!(self,"orr {dest}, {a}, {b}\n")
                 }
 
-                fn eor(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                fn orr_imm(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: u64) -> $crate::__::core::result::Result<(), Self::Error>{
                     let dest = dest.mem_display(cfg.into());
                     let a = a.mem_display(cfg.into());
-                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"orr {dest}, {a}, #{imm:#x}\n")
+                }
+
+                fn eor(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
                     $crate::__::core::write!(self,"eor {dest}, {a}, {b}\n")
                 }
 
-                fn lsl(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                fn eor_imm(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: u64) -> $crate::__::core::result::Result<(), Self::Error>{
                     let dest = dest.mem_display(cfg.into());
                     let a = a.mem_display(cfg.into());
-                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"eor {dest}, {a}, #{imm:#x}\n")
+                }
+
+                fn lsl(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
                     $crate::__::core::write!(self,"lsl {dest}, {a}, {b}\n")
                 }
 
                 fn lsr(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                    let dest = dest.mem_display(cfg.into());
-                    let a = a.mem_display(cfg.into());
-                    let b = b.mem_display(cfg.into());
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
                     $crate::__::core::write!(self,"lsr {dest}, {a}, {b}\n")
                 }
+                fn asr(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
+                    $crate::__::core::write!(self,"asr {dest}, {a}, {b}\n")
+                }
+                fn ror(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
+                    $crate::__::core::write!(self,"ror {dest}, {a}, {b}\n")
+                }
 
                 fn sub(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let dest = dest.mem_display(cfg.into());
@@ -197,6 +387,50 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"add {dest}, {a}, {b}\n")
                 }
 
+                fn adds(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"adds {dest}, {a}, {b}\n")
+                }
+
+                fn subs(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"subs {dest}, {a}, {b}\n")
+                }
+
+                fn adcs(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
+                    $crate::__::core::write!(self,"adcs {dest}, {a}, {b}\n")
+                }
+
+                fn sbcs(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let a = a.mem_display(zr);
+                    let b = b.mem_display(zr);
+                    $crate::__::core::write!(self,"sbcs {dest}, {a}, {b}\n")
+                }
+
+                fn add_imm(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: i64) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let a = a.mem_display(cfg.into());
+                    let op = if imm < 0 { "sub" } else { "add" };
+                    $crate::__::core::write!(self,"{op} {dest}, {a}, #{}\n", imm.unsigned_abs())
+                }
+
+                fn sub_imm(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: i64) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let a = a.mem_display(cfg.into());
+                    let op = if imm < 0 { "add" } else { "sub" };
+                    $crate::__::core::write!(self,"{op} {dest}, {a}, #{}\n", imm.unsigned_abs())
+                }
+
                 fn add_uxtw(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let dest = dest.mem_display(cfg.into());
                     let a = a.mem_display(cfg.into());
@@ -205,6 +439,13 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"add {dest}, {a}, {b}, uxtw\n")
                 }
 
+                fn add_lsl(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), shift: u8) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"add {dest}, {a}, {b}, lsl #{shift}\n")
+                }
+
                 fn sxt(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let dest = dest.mem_display(cfg.into());
                     let src = src.mem_display(cfg.into());
@@ -218,11 +459,68 @@ macro_rules! writers {
                 }
 
                 fn mvn(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                    let dest = dest.mem_display(cfg.into());
-                    let src = src.mem_display(cfg.into());
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let src = src.mem_display(zr);
                     $crate::__::core::write!(self,"mvn {dest}, {src}\n")
                 }
 
+                fn neg(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let src = src.mem_display(zr);
+                    $crate::__::core::write!(self,"neg {dest}, {src}\n")
+                }
+
+                fn negs(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let src = src.mem_display(zr);
+                    $crate::__::core::write!(self,"negs {dest}, {src}\n")
+                }
+
+                fn cls(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let src = src.mem_display(zr);
+                    $crate::__::core::write!(self,"cls {dest}, {src}\n")
+                }
+
+                fn clz(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let src = src.mem_display(zr);
+                    $crate::__::core::write!(self,"clz {dest}, {src}\n")
+                }
+
+                fn rbit(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let src = src.mem_display(zr);
+                    $crate::__::core::write!(self,"rbit {dest}, {src}\n")
+                }
+
+                fn rev(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let src = src.mem_display(zr);
+                    $crate::__::core::write!(self,"rev {dest}, {src}\n")
+                }
+
+                fn ubfx(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), lsb: u8, width: u8) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let src = src.mem_display(zr);
+                    $crate::__::core::write!(self,"ubfx {dest}, {src}, #{lsb}, #{width}\n")
+                }
+
+                fn bfi(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), lsb: u8, width: u8) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let zr = $crate::DisplayOpts::from(cfg).with_reg31($crate::Reg31::Zero);
+                    let dest = dest.mem_display(zr);
+                    let src = src.mem_display(zr);
+                    $crate::__::core::write!(self,"bfi {dest}, {src}, #{lsb}, #{width}\n")
+                }
+
                 fn fadd(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
                     let dest = dest.mem_display(opts);
@@ -255,12 +553,277 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"fdiv {dest}, {a}, {b}\n")
                 }
 
+                fn fmadd(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fmadd {dest}, {a}, {b}, {c}\n")
+                }
+
+                fn fmsub(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fmsub {dest}, {a}, {b}, {c}\n")
+                }
+
+                fn fnmadd(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fnmadd {dest}, {a}, {b}, {c}\n")
+                }
+
+                fn fnmsub(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fnmsub {dest}, {a}, {b}, {c}\n")
+                }
+
+                fn fcmp(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fcmp {a}, {b}\n")
+                }
+
+                fn fmin(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fmin {dest}, {a}, {b}\n")
+                }
+
+                fn fmax(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fmax {dest}, {a}, {b}\n")
+                }
+
+                fn fsqrt(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fsqrt {dest}, {src}\n")
+                }
+
+                fn fabs(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fabs {dest}, {src}\n")
+                }
+
+                fn fneg(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fneg {dest}, {src}\n")
+                }
+
+                fn fcsel(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fcsel {dest}, {a}, {b}, {cond}\n")
+                }
+
+                fn scvtf(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"scvtf {dest}, {src}\n")
+                }
+
+                fn ucvtf(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"ucvtf {dest}, {src}\n")
+                }
+
+                fn fcvtzs(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fcvtzs {dest}, {src}\n")
+                }
+
+                fn fcvtzu(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fcvtzu {dest}, {src}\n")
+                }
+
+                fn fcvt(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fcvt {dest}, {src}\n")
+                }
+
                 fn fmov(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Simd);
                     let dest = dest.mem_display(opts);
                     let src = src.mem_display(opts);
                     $crate::__::core::write!(self,"fmov {dest}, {src}\n")
                 }
+
+                fn whilelt(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let p = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Predicate);
+                    let dest = dest.mem_display(p);
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"whilelt {dest}, {a}, {b}\n")
+                }
+
+                fn ptrue(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let p = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Predicate);
+                    let dest = dest.mem_display(p);
+                    $crate::__::core::write!(self,"ptrue {dest}\n")
+                }
+
+                fn sve_inc(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), elem: $crate::__::MemorySize) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let t = $crate::out::sve::element_letter(elem);
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"inc{t} {dest}\n")
+                }
+
+                fn sve_ld1(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), pg: $crate::PredicateReg, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let t = $crate::out::sve::element_letter($crate::out::sve::element_size(dest));
+                    let z = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Sve);
+                    let dest = dest.mem_display(z);
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"ld1{t} {{{dest}}}, {pg}/z, {mem}\n")
+                }
+
+                fn sve_st1(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_), pg: $crate::PredicateReg, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let t = $crate::out::sve::element_letter($crate::out::sve::element_size(src));
+                    let z = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Sve);
+                    let src = src.mem_display(z);
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"st1{t} {{{src}}}, {pg}, {mem}\n")
+                }
+
+                fn sve_ld1d_gather(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), pg: $crate::PredicateReg, base: &(dyn $crate::out::arg::MemArg + '_), offsets: &(dyn $crate::out::arg::MemArg + '_), scaled: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let z = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Sve);
+                    let dest = dest.mem_display(z);
+                    let base = base.mem_display(cfg.into());
+                    let offsets = offsets.mem_display(z);
+                    let scale = if scaled { ", LSL #3" } else { "" };
+                    $crate::__::core::write!(self,"ld1d {{{dest}}}, {pg}/z, [{base}, {offsets}{scale}]\n")
+                }
+
+                fn sve_st1d_scatter(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, src: &(dyn $crate::out::arg::MemArg + '_), pg: $crate::PredicateReg, base: &(dyn $crate::out::arg::MemArg + '_), offsets: &(dyn $crate::out::arg::MemArg + '_), scaled: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let z = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Sve);
+                    let src = src.mem_display(z);
+                    let base = base.mem_display(cfg.into());
+                    let offsets = offsets.mem_display(z);
+                    let scale = if scaled { ", LSL #3" } else { "" };
+                    $crate::__::core::write!(self,"st1d {{{src}}}, {pg}, [{base}, {offsets}{scale}]\n")
+                }
+
+                fn sve_add(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let z = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Sve);
+                    let dest = dest.mem_display(z);
+                    let a = a.mem_display(z);
+                    let b = b.mem_display(z);
+                    $crate::__::core::write!(self,"add {dest}, {a}, {b}\n")
+                }
+
+                fn sve_sub(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let z = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Sve);
+                    let dest = dest.mem_display(z);
+                    let a = a.mem_display(z);
+                    let b = b.mem_display(z);
+                    $crate::__::core::write!(self,"sub {dest}, {a}, {b}\n")
+                }
+
+                fn sve_fadd(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let z = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Sve);
+                    let dest = dest.mem_display(z);
+                    let a = a.mem_display(z);
+                    let b = b.mem_display(z);
+                    $crate::__::core::write!(self,"fadd {dest}, {a}, {b}\n")
+                }
+
+                fn sve_fsub(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let z = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Sve);
+                    let dest = dest.mem_display(z);
+                    let a = a.mem_display(z);
+                    let b = b.mem_display(z);
+                    $crate::__::core::write!(self,"fsub {dest}, {a}, {b}\n")
+                }
+
+                fn sve_fmul(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let z = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Sve);
+                    let dest = dest.mem_display(z);
+                    let a = a.mem_display(z);
+                    let b = b.mem_display(z);
+                    $crate::__::core::write!(self,"fmul {dest}, {a}, {b}\n")
+                }
+
+                fn sve_fmla(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), pg: $crate::PredicateReg, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let z = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Sve);
+                    let dest = dest.mem_display(z);
+                    let a = a.mem_display(z);
+                    let b = b.mem_display(z);
+                    $crate::__::core::write!(self,"fmla {dest}, {pg}/m, {a}, {b}\n")
+                }
+
+                fn sve_dup(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let z = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Sve);
+                    let dest = dest.mem_display(z);
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"dup {dest}, {src}\n")
+                }
+
+                fn db(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, bytes: &[u8]) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self, ".byte ")?;
+                    for (i, b) in bytes.iter().enumerate() {
+                        if i > 0 {
+                            $crate::__::core::write!(self, ", ")?;
+                        }
+                        $crate::__::core::write!(self, "0x{:02x}", b)?;
+                    }
+                    $crate::__::core::write!(self, "\n")
+                }
+
+                fn align_to(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, alignment: usize) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self, ".balign {alignment}\n")
+                }
+
+                fn nop(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self, "nop\n")
+                }
+
+                fn nops(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, n_bytes: usize) -> $crate::__::core::result::Result<(), Self::Error>{
+                    if n_bytes % 4 != 0 {
+                        return Err($crate::out::error::EmitError::InvalidOperands { mnemonic: "nops", reason: "length is not a multiple of 4" }.into());
+                    }
+                    for _ in 0..n_bytes / 4 {
+                        $crate::__::core::write!(self, "nop\n")?;
+                    }
+                    Ok(())
+                }
+
+                fn align(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, pow2: u32) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self, ".p2align {pow2}\n")
+                }
             }
 
             impl<L: Display, Context> $crate::out::Writer<L, Context> for $ty {
@@ -270,20 +833,33 @@ macro_rules! writers {
 
                 fn adr_label(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), label: L) -> $crate::__::core::result::Result<(),Self::Error>{
                     let dest = dest.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"adr {dest}, {label}\n")
+                    match cfg.label_refs {
+                        $crate::LabelRefs::Absolute => $crate::__::core::write!(self,"ldr {dest}, ={label}\n"),
+                        _ => $crate::__::core::write!(self,"adr {dest}, {label}\n"),
+                    }
                 }
 
-                fn b_label(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, label: L) -> $crate::__::core::result::Result<(),Self::Error>{
-                    $crate::__::core::write!(self,"b {label}\n")
+                // Absolute branches go through x16, the first intra-procedure-call scratch register.
+                fn b_label(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, label: L) -> $crate::__::core::result::Result<(),Self::Error>{
+                    match cfg.label_refs {
+                        $crate::LabelRefs::Absolute => $crate::__::core::write!(self,"ldr x16, ={label}\nbr x16\n"),
+                        _ => $crate::__::core::write!(self,"b {label}\n"),
+                    }
                 }
 
-                fn bl_label(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, label: L) -> $crate::__::core::result::Result<(),Self::Error>{
-                    $crate::__::core::write!(self,"bl {label}\n")
+                fn bl_label(&mut self, _ctx: &mut Context, cfg: $crate::AArch64Arch, label: L) -> $crate::__::core::result::Result<(),Self::Error>{
+                    match cfg.label_refs {
+                        $crate::LabelRefs::Absolute => $crate::__::core::write!(self,"ldr x16, ={label}\nblr x16\n"),
+                        _ => $crate::__::core::write!(self,"bl {label}\n"),
+                    }
                 }
 
                 fn bcond_label(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, cond: $crate::ConditionCode, label: L) -> $crate::__::core::result::Result<(),Self::Error>{
                     $crate::__::core::write!(self,"b.{cond} {label}\n")
                 }
+                fn dd_label(&mut self, _ctx: &mut Context, _cfg: $crate::AArch64Arch, label: L) -> $crate::__::core::result::Result<(), Self::Error> {
+                    $crate::__::core::write!(self, ".4byte {label} - .\n")
+                }
             })*
         };
     };
@@ -1,6 +1,6 @@
 extern crate alloc;
 
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::vec::Vec;
 use portal_pc_asm_common::types::mem::MemorySize;
 
@@ -14,30 +14,165 @@ use crate::out::MemArg;
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum NoLabel {}
 
+// ── emission checks ──────────────────────────────────────────────────────────
+
+/// Panics when `$cond` is false and the `debug_checks` feature is enabled.
+///
+/// Without the feature the condition is behind a constant `false` and is
+/// compiled out. Used from `#[track_caller]` helpers, so the panic location
+/// is the `WriterCore` method that received the bad operand.
+macro_rules! debug_check {
+    ($cond:expr, $($msg:tt)*) => {
+        if cfg!(feature = "debug_checks") && !$cond {
+            panic!("debug_checks: {}", format_args!($($msg)*));
+        }
+    };
+}
+
+/// Check that an unsigned 12-bit immediate (ADD/SUB/CMP) fits, and encode it.
+#[track_caller]
+fn imm12(v: u64) -> u32 {
+    debug_check!(v < 0x1000, "immediate {v:#x} does not fit in an unsigned 12-bit field");
+    v as u32 & 0xFFF
+}
+
+/// Check and encode an ADD/SUB immediate, returning the `sh` and `imm12` bits in place.
+#[track_caller]
+fn arith_imm_bits(v: u64) -> u32 {
+    match crate::imm::arith_imm(v) {
+        Some((field, shifted)) => ((shifted as u32) << 22) | (field << 10),
+        None => {
+            debug_check!(false, "immediate {v:#x} does not fit in 12 bits, optionally shifted by 12");
+            0
+        }
+    }
+}
+
+/// Check and encode a 64-bit logical immediate, returning the `N:immr:imms` bits in place.
+#[track_caller]
+fn logical_imm_bits(v: u64) -> u32 {
+    match crate::imm::logical_imm(v) {
+        Some(field) => field << 10,
+        None => {
+            debug_check!(false, "immediate {v:#x} is not a valid bitmask immediate");
+            0
+        }
+    }
+}
+
+/// Check that a load/store displacement is encodable for an access of `size`.
+#[track_caller]
+fn check_disp(disp: i32, mode: AddressingMode, size: MemorySize) {
+    let scale = match size {
+        MemorySize::_64 => 8,
+        MemorySize::_32 => 4,
+        MemorySize::_16 => 2,
+        _ => 1,
+    };
+    let scaled = mode == AddressingMode::Offset && disp >= 0;
+    debug_check!(
+        if scaled { disp % scale == 0 && disp / scale < 0x1000 } else { (-256..=255).contains(&disp) },
+        "displacement {disp} is not encodable for a {size:?} access with {mode:?} addressing"
+    );
+}
+
 // ── register extraction ──────────────────────────────────────────────────────
 
+#[track_caller]
 fn to_reg(arg: &dyn MemArg) -> u32 {
-    match arg.concrete_mem_kind() {
-        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => reg.0 as u32,
-        _ => 0,
-    }
+    to_reg_size(arg).0
 }
 
+#[track_caller]
 fn to_reg_size(arg: &dyn MemArg) -> (u32, MemorySize) {
     match arg.concrete_mem_kind() {
-        MemArgKind::NoMem(ArgKind::Reg { reg, size }) => (reg.0 as u32, size),
-        _ => (0, MemorySize::_64),
+        MemArgKind::NoMem(ArgKind::Reg { reg, size }) => {
+            debug_check!(reg.0 < 32, "register index {} is out of range (0..=31)", reg.0);
+            (reg.0 as u32, size)
+        }
+        other => {
+            debug_check!(false, "expected a register operand, got {other:?}");
+            (0, MemorySize::_64)
+        }
+    }
+}
+
+/// Returns the `type` field (bits 23:22) of a scalar floating-point
+/// instruction: half precision for a 16-bit operand, single precision for a
+/// 32-bit one, double otherwise.
+///
+/// The encodings below are commented with their double-precision forms.
+#[track_caller]
+fn fp_type(arg: &dyn MemArg) -> u32 {
+    match to_reg_size(arg).1 {
+        MemorySize::_16 => 3 << 22,
+        MemorySize::_32 => 0,
+        _ => 1 << 22,
+    }
+}
+
+/// Returns the `size` field (0-3) of a load/store of `size`.
+fn size_bits(size: MemorySize) -> u32 {
+    match size {
+        MemorySize::_64 => 3,
+        MemorySize::_32 => 2,
+        MemorySize::_16 => 1,
+        _ => 0,
     }
 }
 
+#[track_caller]
 fn mem_base_disp(mem: &dyn MemArg) -> (u32, i32, AddressingMode) {
     match mem.concrete_mem_kind() {
-        MemArgKind::Mem { base: ArgKind::Reg { reg, .. }, disp, mode, .. } => (reg.0 as u32, disp, mode),
+        MemArgKind::Mem { base: ArgKind::Reg { reg, .. }, offset, disp, mode, .. } => {
+            debug_check!(reg.0 < 32, "base register index {} is out of range (0..=31)", reg.0);
+            debug_check!(offset.is_none(), "index register {offset:?} must be desugared before encoding");
+            (reg.0 as u32, disp, mode)
+        }
         MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => (reg.0 as u32, 0, AddressingMode::Offset),
-        _ => (0, 0, AddressingMode::Offset),
+        other => {
+            debug_check!(false, "expected a register-based memory operand, got {other:?}");
+            (0, 0, AddressingMode::Offset)
+        }
+    }
+}
+
+/// The base, index and `S` bit of a `[Xn, Xm, LSL #s]` operand for an
+/// access of `size`, whose shift must be 0 or the access size's log2.
+#[track_caller]
+fn mem_index(mem: &dyn MemArg, size: MemorySize) -> Option<(u32, u32, u32)> {
+    match mem.concrete_mem_kind() {
+        MemArgKind::Mem { base: ArgKind::Reg { reg, .. }, offset: Some((ArgKind::Reg { reg: index, .. }, shift)), disp, mode, .. } => {
+            debug_check!(disp == 0 && mode == AddressingMode::Offset, "a register-offset address takes no displacement or writeback");
+            debug_check!(shift == 0 || shift == size_bits(size), "index shift {shift} must be 0 or {}", size_bits(size));
+            Some((reg.0 as u32, index.0 as u32, (shift != 0) as u32))
+        }
+        _ => None,
     }
 }
 
+/// The base and index of an SVE `[Xn, Xm, LSL #s]` operand for elements of
+/// `size`, whose shift must be the element size's log2.
+#[track_caller]
+fn sve_index(mem: &dyn MemArg, size: MemorySize) -> Option<(u32, u32)> {
+    match mem.concrete_mem_kind() {
+        MemArgKind::Mem { base: ArgKind::Reg { reg, .. }, offset: Some((ArgKind::Reg { reg: index, .. }, shift)), disp, mode, .. } => {
+            debug_check!(disp == 0 && mode == AddressingMode::Offset, "a register-offset address takes no displacement or writeback");
+            debug_check!(shift == size_bits(size), "index shift {shift} must be {}", size_bits(size));
+            debug_check!(index.0 != 31, "the index of an SVE address cannot be xzr");
+            Some((reg.0 as u32, index.0 as u32))
+        }
+        _ => None,
+    }
+}
+
+/// The field of an SVE governing predicate, which must be `p0` to `p7`.
+#[track_caller]
+fn governing(pg: crate::PredicateReg) -> u32 {
+    debug_check!(pg.0 < 8, "governing predicate p{} is out of range (p0..=p7)", pg.0);
+    (pg.0 & 7) as u32
+}
+
 fn lit_value(arg: &dyn MemArg) -> Option<u64> {
     match arg.concrete_mem_kind() {
         MemArgKind::NoMem(ArgKind::Lit(v)) => Some(v),
@@ -45,6 +180,15 @@ fn lit_value(arg: &dyn MemArg) -> Option<u64> {
     }
 }
 
+/// The symbol and addend of a `sym` or `[sym + disp]` operand.
+fn sym_value(arg: &dyn MemArg) -> Option<(&'static str, i64)> {
+    match arg.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Sym { name, addend }) => Some((name, addend)),
+        MemArgKind::Mem { base: ArgKind::Sym { name, addend }, offset: None, disp, .. } => Some((name, addend + disp as i64)),
+        _ => None,
+    }
+}
+
 // ── binary assembler ─────────────────────────────────────────────────────────
 
 /// Binary assembler backend for AArch64 using manual 32-bit instruction encoding.
@@ -66,6 +210,8 @@ enum AArch64FixupKind {
     Bl,
     /// B.cond #imm19 — conditional branch; need condition code.
     BCond { cond: crate::ConditionCode },
+    /// `.4byte label - .` — data word in the configured byte order.
+    Data32 { big_endian: bool },
 }
 
 /// A pending fixup: once `set_label(label)` is called the instruction at
@@ -101,20 +247,78 @@ impl<L> AArch64Fixup<L> {
                 let imm19 = ((delta / 4) as u32) & 0x7_FFFF;
                 0x5400_0000 | (imm19 << 5) | (*cond as u32)
             }
+            AArch64FixupKind::Data32 { big_endian } => {
+                // Data, not an instruction: store the byte offset as is.
+                let bytes = if *big_endian { delta.to_be_bytes() } else { delta.to_le_bytes() };
+                buf[self.instr_offset..self.instr_offset + 4].copy_from_slice(&bytes);
+                return;
+            }
         };
         buf[self.instr_offset..self.instr_offset + 4].copy_from_slice(&word.to_le_bytes());
     }
 }
 
+/// Relocation types recorded by [`AArch64Writer`], named after their ELF counterparts.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[non_exhaustive]
+pub enum RelocKind {
+    /// `R_AARCH64_CALL26`: `bl` target.
+    Call26,
+    /// `R_AARCH64_JUMP26`: `b` target.
+    Jump26,
+    /// `R_AARCH64_CONDBR19`: `b.cond` target.
+    CondBr19,
+    /// `R_AARCH64_ADR_PREL_LO21`: `adr` source.
+    AdrPrelLo21,
+    /// `R_AARCH64_LD_PREL_LO19`: `ldr` literal source.
+    LdPrelLo19,
+    /// `R_AARCH64_ADR_PREL_PG_HI21`: `adrp` source.
+    AdrPrelPgHi21,
+    /// `R_AARCH64_ADD_ABS_LO12_NC`: `add :lo12:` immediate.
+    AddAbsLo12Nc,
+    /// `R_AARCH64_ABS64`: 64-bit absolute address in an inline literal.
+    Abs64,
+}
+
+/// A symbol reference left for the linker, recorded by [`AArch64Writer`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Relocation {
+    /// Byte offset of the instruction to patch within the assembled output.
+    pub offset: usize,
+    pub kind: RelocKind,
+    pub symbol: &'static str,
+    pub addend: i64,
+}
+
+/// A label problem found by [`AArch64Writer::label_violations`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum LabelViolation<L> {
+    /// A branch or address reference targets a label that was never set.
+    Undefined(L),
+    /// The label was set more than once; the last definition wins.
+    Duplicate(L),
+}
+
 pub struct AArch64Writer<L = NoLabel> {
     buf: Vec<u8>,
     labels: BTreeMap<L, usize>,
     pending_fixups: Vec<AArch64Fixup<L>>,
+    /// Labels passed to `set_label` after they were already defined.
+    duplicate_labels: Vec<L>,
+    relocations: Vec<Relocation>,
 }
 
 impl<L> AArch64Writer<L> {
     pub fn new() -> Self {
-        Self { buf: Vec::new(), labels: BTreeMap::new(), pending_fixups: Vec::new() }
+        Self { buf: Vec::new(), labels: BTreeMap::new(), pending_fixups: Vec::new(), duplicate_labels: Vec::new(), relocations: Vec::new() }
+    }
+
+    /// Symbol references in the assembled bytes, in emission order.
+    ///
+    /// Symbol operands are encoded with a zero offset; each needs its
+    /// relocation applied before the code can run.
+    pub fn relocations(&self) -> &[Relocation] {
+        &self.relocations
     }
 
     /// Return the assembled bytes, discarding any recorded label offsets.
@@ -134,8 +338,44 @@ impl<L> AArch64Writer<L> {
 
     #[inline(always)]
     fn emit(&mut self, word: u32) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(word, offset = self.buf.len(), "encoding instruction");
         self.buf.extend_from_slice(&word.to_le_bytes());
     }
+
+    /// Emit `word` with a relocation against `sym`.
+    fn emit_reloc(&mut self, word: u32, kind: RelocKind, (symbol, addend): (&'static str, i64)) {
+        self.relocations.push(Relocation { offset: self.buf.len(), kind, symbol, addend });
+        self.emit(word);
+    }
+
+    /// Emit an unpredicated SVE `op Zd.T, Zn.T, Zm.T`, with the element size
+    /// taken from `dest`.
+    fn sve_binary(&mut self, op: u32, dest: &dyn MemArg, a: &dyn MemArg, b: &dyn MemArg) {
+        let (zd, size) = to_reg_size(dest);
+        self.emit(op | (size_bits(size) << 22) | (to_reg(b) << 16) | (to_reg(a) << 5) | zd);
+    }
+}
+
+impl<L: Ord + Clone> AArch64Writer<L> {
+    /// List every label that is referenced but never defined, or defined more
+    /// than once. An empty list means the output is safe to finalize.
+    ///
+    /// Undefined labels are those with fixups still pending; each is reported
+    /// once regardless of how many instructions reference it.
+    pub fn label_violations(&self) -> Vec<LabelViolation<L>> {
+        let undefined: BTreeSet<&L> = self.pending_fixups.iter().map(|fix| &fix.label).collect();
+        undefined.into_iter().cloned().map(LabelViolation::Undefined)
+            .chain(self.duplicate_labels.iter().cloned().map(LabelViolation::Duplicate))
+            .collect()
+    }
+
+    /// Like [`into_parts`](Self::into_parts), but fails with the list of
+    /// [`label_violations`](Self::label_violations) if there are any.
+    pub fn try_into_parts(self) -> Result<(Vec<u8>, BTreeMap<L, usize>), Vec<LabelViolation<L>>> {
+        let violations = self.label_violations();
+        if violations.is_empty() { Ok(self.into_parts()) } else { Err(violations) }
+    }
 }
 
 impl<L> Default for AArch64Writer<L> {
@@ -147,7 +387,7 @@ impl<L> Default for AArch64Writer<L> {
 // ── WriterCore implementation ────────────────────────────────────────────────
 
 impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
-    type Error = core::convert::Infallible;
+    type Error = crate::out::error::EmitError;
 
     fn brk(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, imm: u16) -> Result<(), Self::Error> {
         // BRK #imm16 = 0xD4200000 | (imm16 << 5)
@@ -155,13 +395,50 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         Ok(())
     }
 
+    fn svc(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, imm: u16) -> Result<(), Self::Error> {
+        // SVC #imm16 = 0xD4000001 | (imm16 << 5)
+        self.emit(0xD400_0001 | ((imm as u32) << 5));
+        Ok(())
+    }
+
+    fn bti(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, target: crate::BtiTarget) -> Result<(), Self::Error> {
+        // BTI <targets> = HINT #(32 | op2) = 0xD503241F | (op2 << 5)
+        self.emit(0xD503_241F | ((target as u32) << 5));
+        Ok(())
+    }
+
+    fn paciasp(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        // PACIASP = HINT #25 = 0xD503233F
+        self.emit(0xD503_233F);
+        Ok(())
+    }
+
+    fn autiasp(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        // AUTIASP = HINT #29 = 0xD50323BF
+        self.emit(0xD503_23BF);
+        Ok(())
+    }
+
     fn ret(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
         // RET X30 = 0xD65F03C0
         self.emit(0xD65F_03C0);
         Ok(())
     }
 
-    fn mov(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+    fn mov(&mut self, ctx: &mut Context, cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        if let MemArgKind::NoMem(ArgKind::Sym { name, addend }) = src.concrete_mem_kind() {
+            if cfg.pic {
+                // ADRP Xd, sym ; ADD Xd, Xd, :lo12:sym
+                crate::out::WriterCore::adrp(self, ctx, cfg, dest, src)?;
+                return crate::out::WriterCore::add_lo12(self, ctx, cfg, dest, dest, src);
+            }
+            // LDR Xd, #8 ; B #12 ; .quad sym
+            self.emit(0x5800_0040 | to_reg(dest));
+            self.emit(0x1400_0003);
+            self.relocations.push(Relocation { offset: self.buf.len(), kind: RelocKind::Abs64, symbol: name, addend });
+            self.buf.extend_from_slice(&[0; 8]);
+            return Ok(());
+        }
         let (rd, size) = to_reg_size(dest);
         if size == MemorySize::_64 || size == MemorySize::_32 {
             if let Some(v) = lit_value(src) {
@@ -215,7 +492,7 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         let rn = to_reg(a);
         if let Some(v) = lit_value(b) {
             // ADD Xd, Xn, #imm12
-            self.emit(0x9100_0000 | ((v as u32 & 0xFFF) << 10) | (rn << 5) | rd);
+            self.emit(0x9100_0000 | (imm12(v) << 10) | (rn << 5) | rd);
         } else {
             let rm = to_reg(b);
             // ADD Xd, Xn, Xm
@@ -224,6 +501,44 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         Ok(())
     }
 
+    fn adds(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rn = to_reg(a);
+        if let Some(v) = lit_value(b) {
+            // ADDS Xd, Xn, #imm{, LSL #12}
+            self.emit(0xB100_0000 | arith_imm_bits(v) | (rn << 5) | rd);
+        } else {
+            // ADDS Xd, Xn, Xm
+            self.emit(0xAB00_0000 | (to_reg(b) << 16) | (rn << 5) | rd);
+        }
+        Ok(())
+    }
+
+    fn subs(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rn = to_reg(a);
+        if let Some(v) = lit_value(b) {
+            // SUBS Xd, Xn, #imm{, LSL #12}
+            self.emit(0xF100_0000 | arith_imm_bits(v) | (rn << 5) | rd);
+        } else {
+            // SUBS Xd, Xn, Xm
+            self.emit(0xEB00_0000 | (to_reg(b) << 16) | (rn << 5) | rd);
+        }
+        Ok(())
+    }
+
+    fn adcs(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        // ADCS Xd, Xn, Xm
+        self.emit(0xBA00_0000 | (to_reg(b) << 16) | (to_reg(a) << 5) | to_reg(dest));
+        Ok(())
+    }
+
+    fn sbcs(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        // SBCS Xd, Xn, Xm
+        self.emit(0xFA00_0000 | (to_reg(b) << 16) | (to_reg(a) << 5) | to_reg(dest));
+        Ok(())
+    }
+
     fn add_uxtw(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let rd = to_reg(dest);
         let rn = to_reg(a);
@@ -235,12 +550,19 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         Ok(())
     }
 
+    fn add_lsl(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_), shift: u8) -> Result<(), Self::Error> {
+        debug_check!(shift < 64, "shift amount {shift} is out of range (0..=63)");
+        // ADD Xd, Xn, Xm, LSL #imm6
+        self.emit(0x8B00_0000 | (to_reg(b) << 16) | ((shift as u32 & 0x3F) << 10) | (to_reg(a) << 5) | to_reg(dest));
+        Ok(())
+    }
+
     fn sub(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let rd = to_reg(dest);
         let rn = to_reg(a);
         if let Some(v) = lit_value(b) {
             // SUB Xd, Xn, #imm12
-            self.emit(0xD100_0000 | ((v as u32 & 0xFFF) << 10) | (rn << 5) | rd);
+            self.emit(0xD100_0000 | (imm12(v) << 10) | (rn << 5) | rd);
         } else {
             let rm = to_reg(b);
             // SUB Xd, Xn, Xm
@@ -249,6 +571,20 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         Ok(())
     }
 
+    fn add_imm(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), imm: i64) -> Result<(), Self::Error> {
+        // ADD Xd, Xn, #imm{, LSL #12} (SUB for negative values)
+        let op = if imm < 0 { 0xD100_0000 } else { 0x9100_0000 };
+        self.emit(op | arith_imm_bits(imm.unsigned_abs()) | (to_reg(a) << 5) | to_reg(dest));
+        Ok(())
+    }
+
+    fn sub_imm(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), imm: i64) -> Result<(), Self::Error> {
+        // SUB Xd, Xn, #imm{, LSL #12} (ADD for negative values)
+        let op = if imm < 0 { 0x9100_0000 } else { 0xD100_0000 };
+        self.emit(op | arith_imm_bits(imm.unsigned_abs()) | (to_reg(a) << 5) | to_reg(dest));
+        Ok(())
+    }
+
     fn and(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let rd = to_reg(dest);
         let rn = to_reg(a);
@@ -276,6 +612,24 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         Ok(())
     }
 
+    fn and_imm(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), imm: u64) -> Result<(), Self::Error> {
+        // AND Xd, Xn, #bimm
+        self.emit(0x9200_0000 | logical_imm_bits(imm) | (to_reg(a) << 5) | to_reg(dest));
+        Ok(())
+    }
+
+    fn orr_imm(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), imm: u64) -> Result<(), Self::Error> {
+        // ORR Xd, Xn, #bimm
+        self.emit(0xB200_0000 | logical_imm_bits(imm) | (to_reg(a) << 5) | to_reg(dest));
+        Ok(())
+    }
+
+    fn eor_imm(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), imm: u64) -> Result<(), Self::Error> {
+        // EOR Xd, Xn, #bimm
+        self.emit(0xD200_0000 | logical_imm_bits(imm) | (to_reg(a) << 5) | to_reg(dest));
+        Ok(())
+    }
+
     fn lsl(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let rd = to_reg(dest);
         let rn = to_reg(a);
@@ -323,6 +677,21 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         Ok(())
     }
 
+    fn ror(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rn = to_reg(a);
+        if let Some(v) = lit_value(b) {
+            // ROR Xd, Xn, #shift = EXTR Xd, Xn, Xn, #shift
+            let shift = (v & 0x3F) as u32;
+            self.emit(0x93C0_0000 | (rn << 16) | (shift << 10) | (rn << 5) | rd);
+        } else {
+            let rm = to_reg(b);
+            // RORV Xd, Xn, Xm
+            self.emit(0x9AC0_2C00 | (rm << 16) | (rn << 5) | rd);
+        }
+        Ok(())
+    }
+
     fn mvn(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let rd = to_reg(dest);
         let rm = to_reg(src);
@@ -331,6 +700,76 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         Ok(())
     }
 
+    fn neg(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rm = to_reg(src);
+        // NEG Xd, Xm = SUB Xd, XZR, Xm
+        self.emit(0xCB00_03E0 | (rm << 16) | rd);
+        Ok(())
+    }
+
+    fn negs(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rm = to_reg(src);
+        // NEGS Xd, Xm = SUBS Xd, XZR, Xm
+        self.emit(0xEB00_03E0 | (rm << 16) | rd);
+        Ok(())
+    }
+
+    fn cls(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rn = to_reg(src);
+        // CLS Xd, Xn
+        self.emit(0xDAC0_1400 | (rn << 5) | rd);
+        Ok(())
+    }
+
+    fn clz(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rn = to_reg(src);
+        // CLZ Xd, Xn
+        self.emit(0xDAC0_1000 | (rn << 5) | rd);
+        Ok(())
+    }
+
+    fn rbit(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rn = to_reg(src);
+        // RBIT Xd, Xn
+        self.emit(0xDAC0_0000 | (rn << 5) | rd);
+        Ok(())
+    }
+
+    fn rev(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rn = to_reg(src);
+        // REV Xd, Xn
+        self.emit(0xDAC0_0C00 | (rn << 5) | rd);
+        Ok(())
+    }
+
+    fn ubfx(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_), lsb: u8, width: u8) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rn = to_reg(src);
+        debug_check!(width >= 1 && lsb as u32 + width as u32 <= 64, "bitfield of width {width} at bit {lsb} does not fit in 64 bits");
+        // UBFX Xd, Xn, #lsb, #width = UBFM Xd, Xn, #lsb, #(lsb + width - 1)
+        let immr = (lsb & 0x3F) as u32;
+        let imms = ((lsb as u32 + width as u32).wrapping_sub(1)) & 0x3F;
+        self.emit(0xD340_0000 | (immr << 16) | (imms << 10) | (rn << 5) | rd);
+        Ok(())
+    }
+
+    fn bfi(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_), lsb: u8, width: u8) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rn = to_reg(src);
+        debug_check!(width >= 1 && lsb as u32 + width as u32 <= 64, "bitfield of width {width} at bit {lsb} does not fit in 64 bits");
+        // BFI Xd, Xn, #lsb, #width = BFM Xd, Xn, #(-lsb % 64), #(width - 1)
+        let immr = (64 - (lsb & 0x3F) as u32) & 0x3F;
+        let imms = (width as u32).wrapping_sub(1) & 0x3F;
+        self.emit(0xB340_0000 | (immr << 16) | (imms << 10) | (rn << 5) | rd);
+        Ok(())
+    }
+
     fn mul(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let rd = to_reg(dest);
         let rn = to_reg(a);
@@ -340,6 +779,24 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         Ok(())
     }
 
+    fn umulh(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rn = to_reg(a);
+        let rm = to_reg(b);
+        // UMULH Xd, Xn, Xm (Ra = XZR)
+        self.emit(0x9BC0_7C00 | (rm << 16) | (rn << 5) | rd);
+        Ok(())
+    }
+
+    fn smulh(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        let rn = to_reg(a);
+        let rm = to_reg(b);
+        // SMULH Xd, Xn, Xm (Ra = XZR)
+        self.emit(0x9B40_7C00 | (rm << 16) | (rn << 5) | rd);
+        Ok(())
+    }
+
     fn udiv(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let rd = to_reg(dest);
         let rn = to_reg(a);
@@ -362,7 +819,7 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         let rn = to_reg(a);
         if let Some(v) = lit_value(b) {
             // CMP Xn, #imm12 = SUBS XZR, Xn, #imm
-            self.emit(0xF100_001F | ((v as u32 & 0xFFF) << 10) | (rn << 5));
+            self.emit(0xF100_001F | (imm12(v) << 10) | (rn << 5));
         } else {
             let rm = to_reg(b);
             // CMP Xn, Xm = SUBS XZR, Xn, Xm
@@ -371,6 +828,21 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         Ok(())
     }
 
+    fn cmp_imm(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, a: &(dyn MemArg + '_), imm: i64) -> Result<(), Self::Error> {
+        // CMP Xn, #imm = SUBS XZR, Xn, #imm (CMN = ADDS XZR for negative values)
+        let op = if imm < 0 { 0xB100_001F } else { 0xF100_001F };
+        self.emit(op | arith_imm_bits(imm.unsigned_abs()) | (to_reg(a) << 5));
+        Ok(())
+    }
+
+    fn tst(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rn = to_reg(a);
+        let rm = to_reg(b);
+        // TST Xn, Xm = ANDS XZR, Xn, Xm
+        self.emit(0xEA00_001F | (rm << 16) | (rn << 5));
+        Ok(())
+    }
+
     fn csel(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, cond: crate::ConditionCode, dest: &(dyn MemArg + '_), true_val: &(dyn MemArg + '_), false_val: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let rd = to_reg(dest);
         let rn = to_reg(true_val);
@@ -380,6 +852,13 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         Ok(())
     }
 
+    fn cset(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, cond: crate::ConditionCode, dest: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        // CSET Xd, cond = CSINC Xd, XZR, XZR, invert(cond)
+        self.emit(0x9A9F_07E0 | ((cond.invert() as u32) << 12) | rd);
+        Ok(())
+    }
+
     fn sxt(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let rd = to_reg(dest);
         let (rn, size) = to_reg_size(src);
@@ -406,7 +885,19 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
 
     fn ldr(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let (rt, size) = to_reg_size(dest);
+        if let Some(sym) = sym_value(mem) {
+            // LDR Xt/Wt, label (PC-relative literal)
+            let opc = if size == MemorySize::_64 { 0x5800_0000 } else { 0x1800_0000 };
+            self.emit_reloc(opc | rt, RelocKind::LdPrelLo19, sym);
+            return Ok(());
+        }
+        if let Some((rn, rm, s)) = mem_index(mem, size) {
+            // LDR Xt, [Xn, Xm, LSL #s]
+            self.emit(0x3860_6800 | (size_bits(size) << 30) | (rm << 16) | (s << 12) | (rn << 5) | rt);
+            return Ok(());
+        }
         let (rn, disp, mode) = mem_base_disp(mem);
+        check_disp(disp, mode, size);
         let instr = match mode {
             AddressingMode::Offset => {
                 let simm9 = (disp as u32) & 0x1FF;
@@ -453,7 +944,13 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
 
     fn str(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, src: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let (rt, size) = to_reg_size(src);
+        if let Some((rn, rm, s)) = mem_index(mem, size) {
+            // STR Xt, [Xn, Xm, LSL #s]
+            self.emit(0x3820_6800 | (size_bits(size) << 30) | (rm << 16) | (s << 12) | (rn << 5) | rt);
+            return Ok(());
+        }
         let (rn, disp, mode) = mem_base_disp(mem);
+        check_disp(disp, mode, size);
         let instr = match mode {
             AddressingMode::Offset => {
                 let simm9 = (disp as u32) & 0x1FF;
@@ -526,7 +1023,9 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
     }
 
     fn bl(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, target: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
-        if let Some(v) = lit_value(target) {
+        if let Some(sym) = sym_value(target) {
+            self.emit_reloc(0x9400_0000, RelocKind::Call26, sym);
+        } else if let Some(v) = lit_value(target) {
             // BL #imm26 (PC-relative, in units of 4 bytes)
             self.emit(0x9400_0000 | ((v as u32 >> 2) & 0x3FF_FFFF));
         } else {
@@ -545,7 +1044,9 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
     }
 
     fn b(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, target: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
-        if let Some(v) = lit_value(target) {
+        if let Some(sym) = sym_value(target) {
+            self.emit_reloc(0x1400_0000, RelocKind::Jump26, sym);
+        } else if let Some(v) = lit_value(target) {
             // B #imm26
             self.emit(0x1400_0000 | ((v as u32 >> 2) & 0x3FF_FFFF));
         } else {
@@ -557,6 +1058,10 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
     }
 
     fn bcond(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, cond: crate::ConditionCode, target: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        if let Some(sym) = sym_value(target) {
+            self.emit_reloc(0x5400_0000 | (cond as u32), RelocKind::CondBr19, sym);
+            return Ok(());
+        }
         let imm19 = if let Some(v) = lit_value(target) {
             ((v as i64 >> 2) as u32) & 0x7_FFFF
         } else {
@@ -569,6 +1074,10 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
 
     fn adr(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let rd = to_reg(dest);
+        if let Some(sym) = sym_value(src) {
+            self.emit_reloc(0x1000_0000 | rd, RelocKind::AdrPrelLo21, sym);
+            return Ok(());
+        }
         let imm21 = if let Some(v) = lit_value(src) { v as u32 } else { 0 };
         // ADR Xd, #imm21 (PC-relative)
         let immlo = imm21 & 0x3;
@@ -577,17 +1086,131 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         Ok(())
     }
 
-    fn mrs_nzcv(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+    fn mrs(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), reg: crate::SysReg) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
+        // MRS Xd, <reg> = 0xD5300000 | (op0:op1:CRn:CRm:op2 << 5) | Rd
+        self.emit(0xD530_0000 | ((reg.encoding() as u32) << 5) | rd);
+        Ok(())
+    }
+
+    fn msr(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, reg: crate::SysReg, src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rn = to_reg(src);
+        // MSR <reg>, Xn = 0xD5100000 | (op0:op1:CRn:CRm:op2 << 5) | Rn
+        self.emit(0xD510_0000 | ((reg.encoding() as u32) << 5) | rn);
+        Ok(())
+    }
+
+    fn dmb(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, barrier: crate::Barrier) -> Result<(), Self::Error> {
+        // DMB <option> = 0xD50330BF | (CRm << 8)
+        self.emit(0xD503_30BF | ((barrier as u32) << 8));
+        Ok(())
+    }
+
+    fn dsb(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, barrier: crate::Barrier) -> Result<(), Self::Error> {
+        // DSB <option> = 0xD503309F | (CRm << 8)
+        self.emit(0xD503_309F | ((barrier as u32) << 8));
+        Ok(())
+    }
+
+    fn isb(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        // ISB SY
+        self.emit(0xD503_3FDF);
+        Ok(())
+    }
+
+    fn hint(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, imm: u8) -> Result<(), Self::Error> {
+        debug_check!(imm < 128, "hint number {imm} does not fit in 7 bits");
+        // HINT #imm = 0xD503201F | (CRm:op2 << 5)
+        self.emit(0xD503_201F | ((imm as u32 & 0x7F) << 5));
+        Ok(())
+    }
+
+    fn prfm(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, op: crate::PrfOp, mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        debug_check!((1..=3).contains(&op.level), "prefetch cache level {} is not 1, 2 or 3", op.level);
+        let rt = op.bits();
+        if let Some((rn, rm, s)) = mem_index(mem, MemorySize::_64) {
+            // PRFM op, [Xn, Xm, LSL #s]
+            self.emit(0xF8A0_6800 | (rm << 16) | (s << 12) | (rn << 5) | rt);
+            return Ok(());
+        }
+        let (rn, disp, mode) = mem_base_disp(mem);
+        debug_check!(mode == AddressingMode::Offset, "prfm takes no writeback, got {mode:?}");
+        check_disp(disp, mode, MemorySize::_64);
+        if disp >= 0 {
+            // PRFM op, [Xn, #pimm]
+            self.emit(0xF980_0000 | (((disp / 8) as u32 & 0xFFF) << 10) | (rn << 5) | rt);
+        } else {
+            // PRFUM op, [Xn, #simm9]
+            self.emit(0xF880_0000 | (((disp as u32) & 0x1FF) << 12) | (rn << 5) | rt);
+        }
+        Ok(())
+    }
+
+    fn dc(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, op: crate::DcOp, addr: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (rt, _) = to_reg_size(addr);
+        // DC <op>, Xt = SYS #3, C7, C<CRm>, #1, Xt = 0xD50B7020 | (CRm << 8) | Rt
+        self.emit(0xD50B_7020 | ((op as u32) << 8) | rt);
+        Ok(())
+    }
+
+    fn ic_ivau(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, addr: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (rt, _) = to_reg_size(addr);
+        // IC IVAU, Xt = SYS #3, C7, C5, #1, Xt
+        self.emit(0xD50B_7520 | rt);
+        Ok(())
+    }
+
+    fn ldar(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (rt, size) = to_reg_size(dest);
+        let (rn, disp, mode) = mem_base_disp(mem);
+        debug_check!(disp == 0 && mode == AddressingMode::Offset, "ldar takes a bare base register, got #{disp} with {mode:?}");
+        // LDAR{B,H} Wt / LDAR Xt, [Xn] = 0x08DFFC00 | (size << 30) | (Rn << 5) | Rt
+        self.emit(0x08DF_FC00 | (size_bits(size) << 30) | (rn << 5) | rt);
+        Ok(())
+    }
+
+    fn stlr(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, src: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (rt, size) = to_reg_size(src);
+        let (rn, disp, mode) = mem_base_disp(mem);
+        debug_check!(disp == 0 && mode == AddressingMode::Offset, "stlr takes a bare base register, got #{disp} with {mode:?}");
+        // STLR{B,H} Wt / STLR Xt, [Xn] = 0x089FFC00 | (size << 30) | (Rn << 5) | Rt
+        self.emit(0x089F_FC00 | (size_bits(size) << 30) | (rn << 5) | rt);
+        Ok(())
+    }
+
+    fn swpal(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, src: &(dyn MemArg + '_), dest: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (rs, size) = to_reg_size(src);
+        let rt = to_reg(dest);
+        let (rn, disp, mode) = mem_base_disp(mem);
+        debug_check!(disp == 0 && mode == AddressingMode::Offset, "swpal takes a bare base register, got #{disp} with {mode:?}");
+        // SWPAL{B,H} Ws, Wt / SWPAL Xs, Xt, [Xn] = 0x38E08000 | (size << 30) | (Rs << 16) | (Rn << 5) | Rt
+        self.emit(0x38E0_8000 | (size_bits(size) << 30) | (rs << 16) | (rn << 5) | rt);
+        Ok(())
+    }
+
+    fn adrp(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let rd = to_reg(dest);
-        // MRS Xd, NZCV = 0xD53B4200 | Rd
-        self.emit(0xD53B_4200 | rd);
+        if let Some(sym) = sym_value(src) {
+            self.emit_reloc(0x9000_0000 | rd, RelocKind::AdrPrelPgHi21, sym);
+            return Ok(());
+        }
+        // ADRP Xd, #imm21 pages; a literal is a byte offset from this page
+        let imm21 = lit_value(src).map_or(0, |v| (v >> 12) as u32);
+        let immlo = imm21 & 0x3;
+        let immhi = (imm21 >> 2) & 0x7_FFFF;
+        self.emit(0x9000_0000 | (immlo << 29) | (immhi << 5) | rd);
         Ok(())
     }
 
-    fn msr_nzcv(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+    fn add_lo12(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_), sym: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_reg(dest);
         let rn = to_reg(src);
-        // MSR NZCV, Xn = 0xD51B4200 | Rn
-        self.emit(0xD51B_4200 | rn);
+        // ADD Xd, Xn, #imm12 = 0x91000000 | (imm12 << 10) | (Rn << 5) | Rd
+        let word = 0x9100_0000 | (rn << 5) | rd;
+        match sym_value(sym) {
+            Some(sym) => self.emit_reloc(word, RelocKind::AddAbsLo12Nc, sym),
+            None => self.emit(word | ((lit_value(sym).unwrap_or(0) as u32 & 0xFFF) << 10)),
+        }
         Ok(())
     }
 
@@ -597,8 +1220,9 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         let fd = to_reg(dest);
         let fn_ = to_reg(a);
         let fm = to_reg(b);
+        let ty = fp_type(dest);
         // FADD Dd, Dn, Dm
-        self.emit(0x1E60_2800 | (fm << 16) | (fn_ << 5) | fd);
+        self.emit(0x1E20_2800 | ty | (fm << 16) | (fn_ << 5) | fd);
         Ok(())
     }
 
@@ -606,8 +1230,9 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         let fd = to_reg(dest);
         let fn_ = to_reg(a);
         let fm = to_reg(b);
+        let ty = fp_type(dest);
         // FSUB Dd, Dn, Dm
-        self.emit(0x1E60_3800 | (fm << 16) | (fn_ << 5) | fd);
+        self.emit(0x1E20_3800 | ty | (fm << 16) | (fn_ << 5) | fd);
         Ok(())
     }
 
@@ -615,8 +1240,9 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         let fd = to_reg(dest);
         let fn_ = to_reg(a);
         let fm = to_reg(b);
+        let ty = fp_type(dest);
         // FMUL Dd, Dn, Dm
-        self.emit(0x1E60_0800 | (fm << 16) | (fn_ << 5) | fd);
+        self.emit(0x1E20_0800 | ty | (fm << 16) | (fn_ << 5) | fd);
         Ok(())
     }
 
@@ -624,16 +1250,308 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         let fd = to_reg(dest);
         let fn_ = to_reg(a);
         let fm = to_reg(b);
+        let ty = fp_type(dest);
         // FDIV Dd, Dn, Dm
-        self.emit(0x1E60_1800 | (fm << 16) | (fn_ << 5) | fd);
+        self.emit(0x1E20_1800 | ty | (fm << 16) | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn fmadd(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_), c: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let fn_ = to_reg(a);
+        let fm = to_reg(b);
+        let fa = to_reg(c);
+        let ty = fp_type(dest);
+        // FMADD Dd, Dn, Dm, Da
+        self.emit(0x1F00_0000 | ty | (fm << 16) | (fa << 10) | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn fmsub(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_), c: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let fn_ = to_reg(a);
+        let fm = to_reg(b);
+        let fa = to_reg(c);
+        let ty = fp_type(dest);
+        // FMSUB Dd, Dn, Dm, Da
+        self.emit(0x1F00_8000 | ty | (fm << 16) | (fa << 10) | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn fnmadd(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_), c: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let fn_ = to_reg(a);
+        let fm = to_reg(b);
+        let fa = to_reg(c);
+        let ty = fp_type(dest);
+        // FNMADD Dd, Dn, Dm, Da
+        self.emit(0x1F20_0000 | ty | (fm << 16) | (fa << 10) | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn fnmsub(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_), c: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let fn_ = to_reg(a);
+        let fm = to_reg(b);
+        let fa = to_reg(c);
+        let ty = fp_type(dest);
+        // FNMSUB Dd, Dn, Dm, Da
+        self.emit(0x1F20_8000 | ty | (fm << 16) | (fa << 10) | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn fcmp(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fn_ = to_reg(a);
+        let fm = to_reg(b);
+        let ty = fp_type(a);
+        // FCMP Dn, Dm
+        self.emit(0x1E20_2000 | ty | (fm << 16) | (fn_ << 5));
+        Ok(())
+    }
+
+    fn fmin(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let fn_ = to_reg(a);
+        let fm = to_reg(b);
+        let ty = fp_type(dest);
+        // FMIN Dd, Dn, Dm
+        self.emit(0x1E20_5800 | ty | (fm << 16) | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn fmax(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let fn_ = to_reg(a);
+        let fm = to_reg(b);
+        let ty = fp_type(dest);
+        // FMAX Dd, Dn, Dm
+        self.emit(0x1E20_4800 | ty | (fm << 16) | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn fsqrt(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let fn_ = to_reg(src);
+        let ty = fp_type(dest);
+        // FSQRT Dd, Dn
+        self.emit(0x1E21_C000 | ty | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn fabs(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let fn_ = to_reg(src);
+        let ty = fp_type(dest);
+        // FABS Dd, Dn
+        self.emit(0x1E20_C000 | ty | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn fneg(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let fn_ = to_reg(src);
+        let ty = fp_type(dest);
+        // FNEG Dd, Dn
+        self.emit(0x1E21_4000 | ty | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn fcsel(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, cond: crate::ConditionCode, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let fn_ = to_reg(a);
+        let fm = to_reg(b);
+        let ty = fp_type(dest);
+        // FCSEL Dd, Dn, Dm, cond
+        self.emit(0x1E20_0C00 | ty | (fm << 16) | ((cond as u32) << 12) | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn scvtf(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let (rn, size) = to_reg_size(src);
+        let sf = if size == MemorySize::_32 { 0 } else { 1 << 31 };
+        let ty = fp_type(dest);
+        // SCVTF Dd, Xn (Wn when sf is clear)
+        self.emit(0x1E22_0000 | ty | sf | (rn << 5) | fd);
+        Ok(())
+    }
+
+    fn ucvtf(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let (rn, size) = to_reg_size(src);
+        let sf = if size == MemorySize::_32 { 0 } else { 1 << 31 };
+        let ty = fp_type(dest);
+        // UCVTF Dd, Xn (Wn when sf is clear)
+        self.emit(0x1E23_0000 | ty | sf | (rn << 5) | fd);
+        Ok(())
+    }
+
+    fn fcvtzs(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (rd, size) = to_reg_size(dest);
+        let fn_ = to_reg(src);
+        let sf = if size == MemorySize::_32 { 0 } else { 1 << 31 };
+        let ty = fp_type(src);
+        // FCVTZS Xd, Dn (Wd when sf is clear)
+        self.emit(0x1E38_0000 | ty | sf | (fn_ << 5) | rd);
+        Ok(())
+    }
+
+    fn fcvtzu(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (rd, size) = to_reg_size(dest);
+        let fn_ = to_reg(src);
+        let sf = if size == MemorySize::_32 { 0 } else { 1 << 31 };
+        let ty = fp_type(src);
+        // FCVTZU Xd, Dn (Wd when sf is clear)
+        self.emit(0x1E39_0000 | ty | sf | (fn_ << 5) | rd);
+        Ok(())
+    }
+
+    fn fcvt(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let fd = to_reg(dest);
+        let fn_ = to_reg(src);
+        // FCVT Dd, Sn (Sd, Dn when dest is 32-bit); opc names the destination type
+        let ty = fp_type(src);
+        let opc = fp_type(dest) >> 7;
+        self.emit(0x1E22_4000 | ty | opc | (fn_ << 5) | fd);
         Ok(())
     }
 
     fn fmov(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let fd = to_reg(dest);
         let fn_ = to_reg(src);
+        let ty = fp_type(dest);
         // FMOV Dd, Dn
-        self.emit(0x1E60_4000 | (fn_ << 5) | fd);
+        self.emit(0x1E20_4000 | ty | (fn_ << 5) | fd);
+        Ok(())
+    }
+
+    fn whilelt(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (pd, size) = to_reg_size(dest);
+        let (rn, width) = to_reg_size(a);
+        let rm = to_reg(b);
+        let sf = if width == MemorySize::_32 { 0 } else { 1 << 12 };
+        // WHILELT Pd.T, Xn, Xm (Wn, Wm when sf is clear)
+        self.emit(0x2520_0400 | (size_bits(size) << 22) | (rm << 16) | sf | (rn << 5) | (pd & 0xF));
+        Ok(())
+    }
+
+    fn ptrue(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (pd, size) = to_reg_size(dest);
+        // PTRUE Pd.T, ALL
+        self.emit(0x2518_E3E0 | (size_bits(size) << 22) | (pd & 0xF));
+        Ok(())
+    }
+
+    fn sve_inc(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), elem: MemorySize) -> Result<(), Self::Error> {
+        let rdn = to_reg(dest);
+        // INC{B,H,W,D} Xdn, ALL, MUL #1
+        self.emit(0x0430_E3E0 | (size_bits(elem) << 22) | rdn);
+        Ok(())
+    }
+
+    fn sve_ld1(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), pg: crate::PredicateReg, mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (zt, size) = to_reg_size(dest);
+        let dtype = size_bits(size) * 5;
+        let pg = governing(pg);
+        match sve_index(mem, size) {
+            // LD1D Zt.D, Pg/Z, [Xn, Xm, LSL #3]; dtype names the element and memory sizes
+            Some((rn, rm)) => self.emit(0xA400_4000 | (dtype << 21) | (rm << 16) | (pg << 10) | (rn << 5) | zt),
+            None => {
+                let (rn, disp, mode) = mem_base_disp(mem);
+                debug_check!(disp == 0 && mode == AddressingMode::Offset, "sve_ld1 takes [xn] or [xn, xm, lsl #s], got #{disp} with {mode:?}");
+                // LD1D Zt.D, Pg/Z, [Xn]
+                self.emit(0xA400_A000 | (dtype << 21) | (pg << 10) | (rn << 5) | zt)
+            }
+        }
+        Ok(())
+    }
+
+    fn sve_st1(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, src: &(dyn MemArg + '_), pg: crate::PredicateReg, mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (zt, size) = to_reg_size(src);
+        let msz_size = size_bits(size) * 5;
+        let pg = governing(pg);
+        match sve_index(mem, size) {
+            // ST1D Zt.D, Pg, [Xn, Xm, LSL #3]
+            Some((rn, rm)) => self.emit(0xE400_4000 | (msz_size << 21) | (rm << 16) | (pg << 10) | (rn << 5) | zt),
+            None => {
+                let (rn, disp, mode) = mem_base_disp(mem);
+                debug_check!(disp == 0 && mode == AddressingMode::Offset, "sve_st1 takes [xn] or [xn, xm, lsl #s], got #{disp} with {mode:?}");
+                // ST1D Zt.D, Pg, [Xn]
+                self.emit(0xE400_E000 | (msz_size << 21) | (pg << 10) | (rn << 5) | zt)
+            }
+        }
+        Ok(())
+    }
+
+    fn sve_ld1d_gather(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), pg: crate::PredicateReg, base: &(dyn MemArg + '_), offsets: &(dyn MemArg + '_), scaled: bool) -> Result<(), Self::Error> {
+        let zt = to_reg(dest);
+        let rn = to_reg(base);
+        let zm = to_reg(offsets);
+        // LD1D Zt.D, Pg/Z, [Xn, Zm.D, LSL #3] (0xC5C0C000 unscaled)
+        let op = if scaled { 0xC5E0_C000 } else { 0xC5C0_C000 };
+        self.emit(op | (zm << 16) | (governing(pg) << 10) | (rn << 5) | zt);
+        Ok(())
+    }
+
+    fn sve_st1d_scatter(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, src: &(dyn MemArg + '_), pg: crate::PredicateReg, base: &(dyn MemArg + '_), offsets: &(dyn MemArg + '_), scaled: bool) -> Result<(), Self::Error> {
+        let zt = to_reg(src);
+        let rn = to_reg(base);
+        let zm = to_reg(offsets);
+        // ST1D Zt.D, Pg, [Xn, Zm.D, LSL #3] (0xE580A000 unscaled)
+        let op = if scaled { 0xE5A0_A000 } else { 0xE580_A000 };
+        self.emit(op | (zm << 16) | (governing(pg) << 10) | (rn << 5) | zt);
+        Ok(())
+    }
+
+    fn sve_add(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        // ADD Zd.T, Zn.T, Zm.T
+        self.sve_binary(0x0420_0000, dest, a, b);
+        Ok(())
+    }
+
+    fn sve_sub(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        // SUB Zd.T, Zn.T, Zm.T
+        self.sve_binary(0x0420_0400, dest, a, b);
+        Ok(())
+    }
+
+    fn sve_fadd(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        // FADD Zd.T, Zn.T, Zm.T
+        self.sve_binary(0x6500_0000, dest, a, b);
+        Ok(())
+    }
+
+    fn sve_fsub(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        // FSUB Zd.T, Zn.T, Zm.T
+        self.sve_binary(0x6500_0400, dest, a, b);
+        Ok(())
+    }
+
+    fn sve_fmul(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        // FMUL Zd.T, Zn.T, Zm.T
+        self.sve_binary(0x6500_0800, dest, a, b);
+        Ok(())
+    }
+
+    fn sve_fmla(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), pg: crate::PredicateReg, a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (zda, size) = to_reg_size(dest);
+        let zn = to_reg(a);
+        let zm = to_reg(b);
+        // FMLA Zda.T, Pg/M, Zn.T, Zm.T
+        self.emit(0x6520_0000 | (size_bits(size) << 22) | (zm << 16) | (governing(pg) << 10) | (zn << 5) | zda);
+        Ok(())
+    }
+
+    fn sve_dup(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (zd, size) = to_reg_size(dest);
+        let rn = to_reg(src);
+        // DUP Zd.T, Xn
+        self.emit(0x0520_3800 | (size_bits(size) << 22) | (rn << 5) | zd);
+        Ok(())
+    }
+
+    fn db(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.buf.extend_from_slice(bytes);
         Ok(())
     }
 
@@ -649,6 +1567,25 @@ impl<L, Context> crate::out::WriterCore<Context> for AArch64Writer<L> {
         }
         Ok(())
     }
+
+    fn nop(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch) -> Result<(), Self::Error> {
+        self.emit(0xD503_201F);
+        Ok(())
+    }
+
+    fn nops(&mut self, _ctx: &mut Context, _cfg: crate::AArch64Arch, n_bytes: usize) -> Result<(), Self::Error> {
+        if n_bytes % 4 != 0 {
+            return Err(crate::out::error::EmitError::InvalidOperands { mnemonic: "nops", reason: "length is not a multiple of 4" });
+        }
+        for _ in 0..n_bytes / 4 {
+            self.emit(0xD503_201F);
+        }
+        Ok(())
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: crate::AArch64Arch, pow2: u32) -> Result<(), Self::Error> {
+        crate::out::WriterCore::<Context>::align_to(self, ctx, cfg, 1 << pow2)
+    }
 }
 
 // ── Writer implementation ────────────────────────────────────────────────────
@@ -671,6 +1608,9 @@ impl<L: Ord, Context> crate::out::Writer<L, Context> for AArch64Writer<L> {
                 i += 1;
             }
         }
+        if let Some((prev, _)) = self.labels.remove_entry(&s) {
+            self.duplicate_labels.push(prev);
+        }
         self.labels.insert(s, target);
         Ok(())
     }
@@ -752,6 +1692,24 @@ impl<L: Ord, Context> crate::out::Writer<L, Context> for AArch64Writer<L> {
         }
         Ok(())
     }
+
+    fn dd_label(
+        &mut self,
+        _ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        let instr_offset = self.buf.len();
+        if let Some(&target) = self.labels.get(&label) {
+            let delta = (target as i64 - instr_offset as i64) as i32;
+            self.buf.extend_from_slice(&cfg.u32_bytes(delta as u32));
+        } else {
+            self.buf.extend_from_slice(&[0; 4]); // patched when label is defined
+            let kind = AArch64FixupKind::Data32 { big_endian: cfg.big_endian };
+            self.pending_fixups.push(AArch64Fixup { instr_offset, label, kind });
+        }
+        Ok(())
+    }
 }
 
 // ── Tests ────────────────────────────────────────────────────────────────────
@@ -782,4 +1740,400 @@ mod tests {
         assert_eq!(bytes.len(), 12);
         assert_eq!(labels[&42u32], 8);
     }
+
+    #[test]
+    fn traps_use_distinct_brk_immediates() {
+        let arch = crate::AArch64Arch::default();
+        let mut ctx = ();
+        let mut w: AArch64Writer<u32> = AArch64Writer::new();
+
+        w.unreachable(&mut ctx, arch).unwrap(); // brk #1
+        w.debug_break(&mut ctx, arch).unwrap(); // brk #0xf000
+        let (bytes, _) = w.into_parts();
+        assert_eq!(bytes, [0x20, 0x00, 0x20, 0xD4, 0x00, 0x00, 0x3E, 0xD4]);
+    }
+
+    #[test]
+    fn label_violations_reports_undefined_and_duplicate() {
+        let arch = crate::AArch64Arch::default();
+        let mut ctx = ();
+        let mut w: AArch64Writer<u32> = AArch64Writer::new();
+
+        w.b_label(&mut ctx, arch, 1u32).unwrap();
+        w.b_label(&mut ctx, arch, 1u32).unwrap();
+        w.set_label(&mut ctx, arch, 2u32).unwrap();
+        w.set_label(&mut ctx, arch, 2u32).unwrap();
+
+        assert_eq!(w.label_violations(), [LabelViolation::Undefined(1), LabelViolation::Duplicate(2)]);
+
+        w.set_label(&mut ctx, arch, 1u32).unwrap();
+        assert_eq!(w.try_into_parts().unwrap_err(), [LabelViolation::Duplicate(2)]);
+    }
+
+    #[test]
+    fn mrs_tpidr_el0_encoding() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.mrs_tpidr_el0(&mut (), arch, &Reg(3)).unwrap();
+        // MRS X3, TPIDR_EL0
+        assert_eq!(w.into_bytes(), 0xD53B_D043u32.to_le_bytes());
+    }
+
+    #[test]
+    fn system_register_encodings() {
+        use crate::out::WriterCore as _;
+        use crate::SysReg;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.mrs(&mut (), arch, &Reg(0), SysReg::CntvctEl0).unwrap(); // MRS X0, CNTVCT_EL0
+        w.msr(&mut (), arch, SysReg::Fpsr, &Reg(1)).unwrap(); // MSR FPSR, X1
+        w.mrs(&mut (), arch, &Reg(2), SysReg::CtrEl0).unwrap(); // MRS X2, CTR_EL0
+        w.mrs_nzcv(&mut (), arch, &Reg(3)).unwrap(); // MRS X3, NZCV
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0xD53B_E040, 0xD51B_4421, 0xD53B_0022, 0xD53B_4203]);
+    }
+
+    #[test]
+    fn barrier_and_acquire_release_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.dmb(&mut (), arch, crate::Barrier::ISH).unwrap(); // DMB ISH
+        w.ldar(&mut (), arch, &Reg(0), &Reg(1)).unwrap(); // LDAR X0, [X1]
+        w.stlr(&mut (), arch, &Reg(2), &Reg(3)).unwrap(); // STLR X2, [X3]
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0xD503_3BBF, 0xC8DF_FC20, 0xC89F_FC62]);
+    }
+
+    #[test]
+    fn lse_swap_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default().with_lse(true);
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.swpal(&mut (), arch, &Reg(0), &Reg(1), &Reg(2)).unwrap(); // SWPAL X0, X1, [X2]
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0xF8E0_8041]);
+    }
+
+    #[test]
+    fn hint_encodings() {
+        use crate::out::WriterCore as _;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.yield_(&mut (), arch).unwrap(); // YIELD
+        w.wfe(&mut (), arch).unwrap(); // WFE
+        w.sev(&mut (), arch).unwrap(); // SEV
+        w.hint(&mut (), arch, 34).unwrap(); // HINT #34 (BTI C)
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0xD503_203F, 0xD503_205F, 0xD503_209F, 0xD503_245F]);
+    }
+
+    #[test]
+    fn prfm_encodings() {
+        use crate::out::WriterCore as _;
+        use crate::{PrfKind, PrfOp, PrfPolicy};
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        let x = |r| ArgKind::Reg { reg: Reg(r), size: MemorySize::_64 };
+        let mem = |base, offset, disp| MemArgKind::Mem {
+            base: x(base),
+            offset,
+            disp,
+            size: MemorySize::_64,
+            reg_class: crate::RegisterClass::Gpr,
+            mode: AddressingMode::Offset,
+        };
+
+        w.prfm(&mut (), arch, PrfOp::default(), &mem(0, None, 0)).unwrap(); // PRFM PLDL1KEEP, [X0]
+        w.prfm(&mut (), arch, PrfOp::new(PrfKind::PST, 2, PrfPolicy::STRM), &mem(1, None, 64)).unwrap(); // PRFM PSTL2STRM, [X1, #64]
+        w.prfm(&mut (), arch, PrfOp::new(PrfKind::PLD, 3, PrfPolicy::KEEP), &mem(2, None, -16)).unwrap(); // PRFUM PLDL3KEEP, [X2, #-16]
+        w.prfm(&mut (), arch, PrfOp::new(PrfKind::PLD, 1, PrfPolicy::STRM), &mem(1, Some((x(2), 3)), 0)).unwrap(); // PRFM PLDL1STRM, [X1, X2, LSL #3]
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0xF980_0000, 0xF980_2033, 0xF89F_0044, 0xF8A2_7821]);
+    }
+
+    #[test]
+    fn cache_maintenance_encodings() {
+        use crate::out::WriterCore as _;
+        use crate::{Barrier, DcOp};
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.dc(&mut (), arch, DcOp::CVAU, &Reg(0)).unwrap(); // DC CVAU, X0
+        w.dc(&mut (), arch, DcOp::CIVAC, &Reg(1)).unwrap(); // DC CIVAC, X1
+        w.dsb(&mut (), arch, Barrier::ISH).unwrap(); // DSB ISH
+        w.ic_ivau(&mut (), arch, &Reg(0)).unwrap(); // IC IVAU, X0
+        w.isb(&mut (), arch).unwrap(); // ISB
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0xD50B_7B20, 0xD50B_7E21, 0xD503_3B9F, 0xD50B_7520, 0xD503_3FDF]);
+    }
+
+    #[test]
+    fn sve_encodings() {
+        use crate::out::WriterCore as _;
+        use crate::out::sve::element;
+        use crate::PredicateReg;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default().with_sve(0);
+        let mut w: AArch64Writer = AArch64Writer::new();
+        let p0 = PredicateReg(0);
+        w.whilelt(&mut (), arch, &Reg(0), &Reg(0), &Reg(1)).unwrap(); // WHILELT P0.D, X0, X1
+        w.ptrue(&mut (), arch, &Reg(0)).unwrap(); // PTRUE P0.D
+        w.sve_inc(&mut (), arch, &Reg(0), MemorySize::_64).unwrap(); // INCD X0
+        w.sve_ld1(&mut (), arch, &Reg(0), p0, &element(Reg(0), Reg(1), MemorySize::_64)).unwrap(); // LD1D {Z0.D}, P0/Z, [X0, X1, LSL #3]
+        w.sve_ld1(&mut (), arch, &Reg(0), p0, &Reg(0)).unwrap(); // LD1D {Z0.D}, P0/Z, [X0]
+        w.sve_st1(&mut (), arch, &Reg(0), p0, &element(Reg(0), Reg(1), MemorySize::_64)).unwrap(); // ST1D {Z0.D}, P0, [X0, X1, LSL #3]
+        w.sve_ld1d_gather(&mut (), arch, &Reg(0), p0, &Reg(0), &Reg(1), true).unwrap(); // LD1D {Z0.D}, P0/Z, [X0, Z1.D, LSL #3]
+        w.sve_st1d_scatter(&mut (), arch, &Reg(0), p0, &Reg(0), &Reg(1), true).unwrap(); // ST1D {Z0.D}, P0, [X0, Z1.D, LSL #3]
+        w.sve_fadd(&mut (), arch, &Reg(0), &Reg(1), &Reg(2)).unwrap(); // FADD Z0.D, Z1.D, Z2.D
+        w.sve_fmla(&mut (), arch, &Reg(0), p0, &Reg(1), &Reg(2)).unwrap(); // FMLA Z0.D, P0/M, Z1.D, Z2.D
+        w.sve_dup(&mut (), arch, &Reg(0), &Reg(0)).unwrap(); // DUP Z0.D, X0
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(
+            words,
+            [0x25E1_1400, 0x25D8_E3E0, 0x04F0_E3E0, 0xA5E1_4000, 0xA5E0_A000, 0xE5E1_4000, 0xC5E1_C000, 0xE5A1_A000, 0x65C2_0020, 0x65E2_0020, 0x05E0_3800]
+        );
+    }
+
+    #[test]
+    fn carry_chain_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.adds(&mut (), arch, &Reg(0), &Reg(0), &Reg(2)).unwrap(); // ADDS X0, X0, X2
+        w.adcs(&mut (), arch, &Reg(1), &Reg(1), &Reg(3)).unwrap(); // ADCS X1, X1, X3
+        w.subs(&mut (), arch, &Reg(0), &Reg(0), &1u64).unwrap(); // SUBS X0, X0, #1
+        w.sbcs(&mut (), arch, &Reg(1), &Reg(1), &Reg(3)).unwrap(); // SBCS X1, X1, X3
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0xAB02_0000, 0xBA03_0021, 0xF100_0400, 0xFA03_0021]);
+    }
+
+    #[test]
+    fn neg_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.neg(&mut (), arch, &Reg(0), &Reg(1)).unwrap(); // NEG X0, X1
+        w.negs(&mut (), arch, &Reg(2), &Reg(2)).unwrap(); // NEGS X2, X2
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0xCB01_03E0, 0xEB02_03E2]);
+    }
+
+    #[test]
+    fn fused_multiply_add_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.fmadd(&mut (), arch, &Reg(0), &Reg(1), &Reg(2), &Reg(3)).unwrap(); // FMADD D0, D1, D2, D3
+        w.fnmsub(&mut (), arch, &Reg(4), &Reg(5), &Reg(6), &Reg(7)).unwrap(); // FNMSUB D4, D5, D6, D7
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0x1F42_0C20, 0x1F66_9CA4]);
+    }
+
+    #[test]
+    fn float_compare_select_and_conversion_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        let w0 = ArgKind::Reg { reg: Reg(0), size: MemorySize::_32 };
+        w.fcmp(&mut (), arch, &Reg(0), &Reg(1)).unwrap(); // FCMP D0, D1
+        w.fmin(&mut (), arch, &Reg(0), &Reg(1), &Reg(2)).unwrap(); // FMIN D0, D1, D2
+        w.fsqrt(&mut (), arch, &Reg(0), &Reg(1)).unwrap(); // FSQRT D0, D1
+        w.fcsel(&mut (), arch, crate::ConditionCode::MI, &Reg(0), &Reg(1), &Reg(2)).unwrap(); // FCSEL D0, D1, D2, MI
+        w.scvtf(&mut (), arch, &Reg(0), &Reg(1)).unwrap(); // SCVTF D0, X1
+        w.fcvtzs(&mut (), arch, &w0, &Reg(1)).unwrap(); // FCVTZS W0, D1
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0x1E61_2000, 0x1E62_5820, 0x1E61_C020, 0x1E62_4C20, 0x9E62_0020, 0x1E78_0020]);
+    }
+
+    #[test]
+    fn single_precision_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        let s = |r| ArgKind::Reg { reg: Reg(r), size: MemorySize::_32 };
+        w.fadd(&mut (), arch, &s(0), &s(1), &s(2)).unwrap(); // FADD S0, S1, S2
+        w.fcmp(&mut (), arch, &s(0), &s(1)).unwrap(); // FCMP S0, S1
+        w.scvtf(&mut (), arch, &s(0), &s(1)).unwrap(); // SCVTF S0, W1
+        w.fcvtzs(&mut (), arch, &Reg(0), &s(1)).unwrap(); // FCVTZS X0, S1
+        w.fcvt(&mut (), arch, &Reg(0), &s(1)).unwrap(); // FCVT D0, S1
+        w.fcvt(&mut (), arch, &s(0), &Reg(1)).unwrap(); // FCVT S0, D1
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0x1E22_2820, 0x1E21_2000, 0x1E22_0020, 0x9E38_0020, 0x1E22_C020, 0x1E62_4020]);
+    }
+
+    #[test]
+    fn half_precision_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default().with_fp16(true);
+        let mut w: AArch64Writer = AArch64Writer::new();
+        let h = |r| ArgKind::Reg { reg: Reg(r), size: MemorySize::_16 };
+        let s = |r| ArgKind::Reg { reg: Reg(r), size: MemorySize::_32 };
+        w.fadd(&mut (), arch, &h(0), &h(1), &h(2)).unwrap(); // FADD H0, H1, H2
+        w.fcvt(&mut (), arch, &s(0), &h(1)).unwrap(); // FCVT S0, H1
+        w.fcvt(&mut (), arch, &h(0), &s(1)).unwrap(); // FCVT H0, S1
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(words, [0x1EE2_2820, 0x1EE2_4020, 0x1E23_C020]);
+    }
+
+    #[test]
+    fn fpcr_rounding_mode_encodings() {
+        use crate::out::rounding::Rounding as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.set_rounding_mode(&mut (), arch, crate::RoundingMode::Up, &Reg(16)).unwrap();
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // MRS X16, FPCR; AND X16, X16, #~(3 << 22); ORR X16, X16, #(1 << 22); MSR FPCR, X16
+        assert_eq!(words, [0xD53B_4410, 0x9268_F610, 0xB26A_0210, 0xD51B_4410]);
+    }
+
+    #[test]
+    fn adrp_add_lo12_record_relocations() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        let target = ArgKind::sym("far");
+        w.adrp(&mut (), arch, &Reg(16), &target).unwrap();
+        w.add_lo12(&mut (), arch, &Reg(16), &Reg(16), &target).unwrap();
+
+        assert_eq!(w.relocations(), [
+            Relocation { offset: 0, kind: RelocKind::AdrPrelPgHi21, symbol: "far", addend: 0 },
+            Relocation { offset: 4, kind: RelocKind::AddAbsLo12Nc,  symbol: "far", addend: 0 },
+        ]);
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // ADRP X16, 0; ADD X16, X16, #0
+        assert_eq!(words, [0x9000_0010, 0x9100_0210]);
+    }
+
+    #[test]
+    fn mov_symbol_honors_pic() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let target = ArgKind::Sym { name: "far", addend: 8 };
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.mov(&mut (), crate::AArch64Arch { pic: true, ..Default::default() }, &Reg(0), &target).unwrap();
+        w.mov(&mut (), crate::AArch64Arch::default(), &Reg(1), &target).unwrap();
+
+        assert_eq!(w.relocations(), [
+            Relocation { offset: 0,  kind: RelocKind::AdrPrelPgHi21, symbol: "far", addend: 8 },
+            Relocation { offset: 4,  kind: RelocKind::AddAbsLo12Nc,  symbol: "far", addend: 8 },
+            Relocation { offset: 16, kind: RelocKind::Abs64,         symbol: "far", addend: 8 },
+        ]);
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // ADRP X0, 0; ADD X0, X0, #0; LDR X1, #8; B #12; .quad 0
+        assert_eq!(words, [0x9000_0000, 0x9100_0000, 0x5800_0041, 0x1400_0003, 0, 0]);
+    }
+
+    #[test]
+    fn symbol_operands_record_relocations() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.bl(&mut (), arch, &ArgKind::sym("puts")).unwrap();
+        w.adr(&mut (), arch, &Reg(1), &ArgKind::Sym { name: "table", addend: 8 }).unwrap();
+        w.ldr(&mut (), arch, &Reg(2), &MemArgKind::sym("counter", MemorySize::_64)).unwrap();
+
+        assert_eq!(w.relocations(), [
+            Relocation { offset: 0, kind: RelocKind::Call26,      symbol: "puts",    addend: 0 },
+            Relocation { offset: 4, kind: RelocKind::AdrPrelLo21, symbol: "table",   addend: 8 },
+            Relocation { offset: 8, kind: RelocKind::LdPrelLo19,  symbol: "counter", addend: 0 },
+        ]);
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // BL 0; ADR X1, 0; LDR X2, 0
+        assert_eq!(words, [0x9400_0000, 0x1000_0001, 0x5800_0002]);
+    }
+
+    #[test]
+    fn nops_and_align() {
+        use crate::out::WriterCore as _;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        w.brk(&mut (), arch, 0).unwrap();
+        w.align(&mut (), arch, 4).unwrap();
+        w.nops(&mut (), arch, 8).unwrap();
+        assert!(w.nops(&mut (), arch, 6).is_err());
+
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // BRK #0; 3 x NOP of padding; 2 x NOP
+        assert_eq!(words, [0xD420_0000, 0xD503_201F, 0xD503_201F, 0xD503_201F, 0xD503_201F, 0xD503_201F]);
+    }
+
+    #[test]
+    fn dd_label_is_relative_to_the_word() {
+        use crate::out::WriterCore as _;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer<u32> = AArch64Writer::new();
+        w.set_label(&mut (), arch, 1).unwrap();
+        w.nop(&mut (), arch).unwrap();
+        w.dd_label(&mut (), arch, 1).unwrap();
+        w.dd_label(&mut (), arch, 2).unwrap();
+        w.nop(&mut (), arch).unwrap();
+        w.set_label(&mut (), arch, 2).unwrap();
+
+        let words: Vec<i32> = w.into_bytes().chunks(4).map(|c| i32::from_le_bytes(c.try_into().unwrap())).collect();
+        // NOP; .4byte 1b - .; .4byte 2f - .; NOP
+        assert_eq!(words[1..3], [-4, 8]);
+    }
+
+    #[test]
+    fn scaled_index_addressing() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let arch = crate::AArch64Arch::default();
+        let mut w: AArch64Writer = AArch64Writer::new();
+        let x = |r| ArgKind::Reg { reg: Reg(r), size: MemorySize::_64 };
+        let mem = |shift, size| MemArgKind::Mem {
+            base: x(1),
+            offset: Some((x(2), shift)),
+            disp: 0,
+            size,
+            reg_class: crate::RegisterClass::Gpr,
+            mode: AddressingMode::Offset,
+        };
+        let w0 = ArgKind::Reg { reg: Reg(0), size: MemorySize::_32 };
+
+        w.add_lsl(&mut (), arch, &Reg(0), &Reg(1), &Reg(2), 3).unwrap();
+        w.ldr(&mut (), arch, &Reg(0), &mem(3, MemorySize::_64)).unwrap();
+        w.str(&mut (), arch, &w0, &mem(0, MemorySize::_32)).unwrap();
+
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // add x0, x1, x2, lsl #3; ldr x0, [x1, x2, lsl #3]; str w0, [x1, x2]
+        assert_eq!(words, [0x8B02_0C20, 0xF862_7820, 0xB822_6820]);
+    }
 }
@@ -0,0 +1,232 @@
+//! Bounds checks on array indices.
+//!
+//! [`BoundsCheck::bounds_check`] compares an index against a limit as
+//! unsigned values, so a negative index is out of bounds too, and either
+//! branches to a handler or traps when `index >= limit`:
+//!
+//! ```ignore
+//! writer.bounds_check(ctx, cfg, &Reg(0), &Reg(1), BoundsPolicy::Branch(oob))?;
+//! // cmp x0, x1
+//! // b.hs oob
+//! writer.bounds_check(ctx, cfg, &Reg(0), &Reg(1), BoundsPolicy::Trap(ok))?;
+//! // cmp x0, x1
+//! // b.lo ok
+//! // brk #1
+//! // ok:
+//! ```
+//!
+//! [`checked_element`](BoundsCheck::checked_element) emits the check and
+//! returns the element operand `[base, index, lsl #log2(size)]` for the load
+//! or store that follows, which AArch64 addresses directly.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::Writer;
+use crate::out::arg::{AddressingMode, ArgKind, MemArg, MemArgKind};
+use crate::{AArch64Arch, BranchHint, ConditionCode, RegisterClass};
+
+/// What a failed bounds check does.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BoundsPolicy<L> {
+    /// Branches to the label.
+    Branch(L),
+    /// Traps with `brk #1`. The label is defined after the trap, where
+    /// execution continues when the check passes.
+    Trap(L),
+}
+
+/// Emits bounds checks.
+///
+/// Implemented for every [`Writer`].
+pub trait BoundsCheck<L: Clone, Context>: Writer<L, Context> {
+    /// Checks `index < limit`, unsigned, and applies `policy` when it does
+    /// not hold.
+    ///
+    /// `index` is a register and `limit` a register or an immediate.
+    /// Clobbers the flags.
+    fn bounds_check(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        index: &(dyn MemArg + '_),
+        limit: &(dyn MemArg + '_),
+        policy: BoundsPolicy<L>,
+    ) -> Result<(), Self::Error> {
+        match limit.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Lit(limit)) => {
+                self.cmp_imm(ctx, cfg, index, limit as i64)?
+            }
+            _ => self.cmp(ctx, cfg, index, limit)?,
+        }
+        match policy {
+            BoundsPolicy::Branch(oob) => {
+                self.bcond_label_hinted(ctx, cfg, ConditionCode::HS, oob, BranchHint::Unlikely)
+            }
+            BoundsPolicy::Trap(ok) => {
+                self.bcond_label_hinted(
+                    ctx,
+                    cfg,
+                    ConditionCode::LO,
+                    ok.clone(),
+                    BranchHint::Likely,
+                )?;
+                self.unreachable(ctx, cfg)?;
+                self.set_label(ctx, cfg, ok)
+            }
+        }
+    }
+
+    /// Checks `index < limit` as [`bounds_check`](Self::bounds_check) does
+    /// and returns the `size` element at `index` of the array at `base`.
+    #[allow(clippy::too_many_arguments)]
+    fn checked_element(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        base: Reg,
+        index: Reg,
+        limit: &(dyn MemArg + '_),
+        size: MemorySize,
+        policy: BoundsPolicy<L>,
+    ) -> Result<MemArgKind<ArgKind>, Self::Error> {
+        self.bounds_check(ctx, cfg, &index, limit, policy)?;
+        Ok(element(base, index, size))
+    }
+}
+
+impl<L: Clone, Context, W: Writer<L, Context> + ?Sized> BoundsCheck<L, Context> for W {}
+
+/// The `size` element `[base, index, lsl #log2(size)]`.
+///
+/// # Panics
+///
+/// Panics if `size` is wider than 128 bits, which no load scales by.
+pub fn element(base: Reg, index: Reg, size: MemorySize) -> MemArgKind<ArgKind> {
+    let shift = match size {
+        MemorySize::_8 => 0,
+        MemorySize::_16 => 1,
+        MemorySize::_32 => 2,
+        MemorySize::_64 => 3,
+        MemorySize::_128 => 4,
+        _ => panic!("element size has no AArch64 index shift"),
+    };
+    let reg = |reg| ArgKind::Reg {
+        reg,
+        size: MemorySize::_64,
+    };
+    MemArgKind::Mem {
+        base: reg(base),
+        offset: Some((reg(index), shift)),
+        disp: 0,
+        size,
+        reg_class: RegisterClass::Gpr,
+        mode: AddressingMode::Offset,
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::out::WriterCore;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt::Write;
+
+    /// Runs the compares and branches at the top of `listing` with `regs`
+    /// holding the named registers, and returns the first other
+    /// instruction reached.
+    fn reached<'a>(listing: &'a str, regs: &[(&str, u64)]) -> &'a str {
+        let value = |operand: &str| match regs.iter().find(|(name, _)| *name == operand) {
+            Some(&(_, value)) => value,
+            None => operand.trim_start_matches('#').parse().unwrap(),
+        };
+        let lines: Vec<&str> = listing.lines().collect();
+        let (mut pc, mut lo) = (0, false);
+        loop {
+            let line = lines[pc];
+            pc += 1;
+            let (mnemonic, operands) = line.split_once(' ').unwrap_or((line, ""));
+            let taken = match mnemonic {
+                "cmp" => {
+                    let (a, b) = operands.split_once(", ").unwrap();
+                    lo = value(a) < value(b);
+                    continue;
+                }
+                "b.lo" => lo,
+                "b.hs" => !lo,
+                _ if line.ends_with(':') => continue,
+                _ => return line,
+            };
+            if taken {
+                pc = lines
+                    .iter()
+                    .position(|l| l.strip_suffix(':') == Some(operands))
+                    .unwrap()
+                    + 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_index_takes_the_trap() {
+        let mut output = String::new();
+        let writer: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default();
+        writer
+            .bounds_check(
+                &mut (),
+                cfg,
+                &Reg(0),
+                &ArgKind::Lit(16),
+                BoundsPolicy::Trap(".Lok"),
+            )
+            .unwrap();
+        writer.ret(&mut (), cfg).unwrap();
+        assert_eq!(reached(&output, &[("x0", 15)]), "ret");
+        assert_eq!(reached(&output, &[("x0", 16)]), "brk #1");
+        // A negative index is a huge unsigned one.
+        assert_eq!(reached(&output, &[("x0", -1i64 as u64)]), "brk #1");
+    }
+
+    #[test]
+    fn test_out_of_range_index_takes_the_branch() {
+        let mut output = String::new();
+        let writer: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default();
+        writer
+            .bounds_check(
+                &mut (),
+                cfg,
+                &Reg(0),
+                &Reg(1),
+                BoundsPolicy::Branch(".Loob"),
+            )
+            .unwrap();
+        writer.ret(&mut (), cfg).unwrap();
+        writer.set_label(&mut (), cfg, ".Loob").unwrap();
+        writer.nop(&mut (), cfg).unwrap();
+        assert_eq!(reached(&output, &[("x0", 3), ("x1", 4)]), "ret");
+        assert_eq!(reached(&output, &[("x0", 4), ("x1", 4)]), "nop");
+        assert_eq!(reached(&output, &[("x0", u64::MAX), ("x1", 4)]), "nop");
+    }
+
+    #[test]
+    fn test_checked_element() {
+        let mut output = String::new();
+        let writer: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default();
+        let elem = writer
+            .checked_element(
+                &mut (),
+                cfg,
+                Reg(2),
+                Reg(0),
+                &Reg(1),
+                MemorySize::_64,
+                BoundsPolicy::Branch(".Loob"),
+            )
+            .unwrap();
+        writer.ldr(&mut (), cfg, &Reg(3), &elem).unwrap();
+        assert_eq!(output, "cmp x0, x1\nb.hs .Loob\nldr x3, [x2, x0, LSL #3]\n");
+    }
+}
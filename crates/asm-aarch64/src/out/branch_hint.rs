@@ -0,0 +1,149 @@
+//! Layout of two-way branches by expected direction.
+//!
+//! Static predictors and the instruction fetch both favour the fall-through
+//! path. [`HintedBranch::branch_two_way`] emits the single conditional
+//! branch that leaves the expected successor in fall-through position,
+//! inverting the condition when the branch is [`Likely`](BranchHint::Likely):
+//!
+//! ```ignore
+//! match writer.branch_two_way(ctx, cfg, ConditionCode::EQ, BranchHint::Likely, hot, cold)? {
+//!     // b.ne cold
+//!     Fallthrough::Taken => { /* hot: ... */ }
+//!     Fallthrough::NotTaken => unreachable!(),
+//! }
+//! ```
+//!
+//! The branch itself goes through
+//! [`bcond_label_hinted`](Writer::bcond_label_hinted), so writers that lay
+//! out code themselves still see the hint.
+
+use crate::out::Writer;
+use crate::{AArch64Arch, BranchHint, ConditionCode};
+
+/// Which successor of a two-way branch follows it in memory.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Fallthrough {
+    /// The block run when the condition holds.
+    Taken,
+    /// The block run when the condition does not hold.
+    NotTaken,
+}
+
+impl Fallthrough {
+    /// Returns the successor to place next for a branch hinted `hint`.
+    pub fn for_hint(hint: BranchHint) -> Self {
+        match hint {
+            BranchHint::Likely => Fallthrough::Taken,
+            _ => Fallthrough::NotTaken,
+        }
+    }
+}
+
+/// Emits conditional branches laid out by their expected direction.
+///
+/// Implemented for every [`Writer`].
+pub trait HintedBranch<L, Context>: Writer<L, Context> {
+    /// Branches to `taken` if `cc` holds and to `not_taken` otherwise.
+    ///
+    /// Only one branch is emitted; the returned successor must be placed
+    /// immediately after it.
+    fn branch_two_way(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cc: ConditionCode,
+        hint: BranchHint,
+        taken: L,
+        not_taken: L,
+    ) -> Result<Fallthrough, Self::Error> {
+        let next = Fallthrough::for_hint(hint);
+        match next {
+            Fallthrough::Taken => {
+                self.bcond_label_hinted(ctx, cfg, cc.invert(), not_taken, BranchHint::Unlikely)?
+            }
+            Fallthrough::NotTaken => self.bcond_label_hinted(ctx, cfg, cc, taken, hint)?,
+        }
+        Ok(next)
+    }
+}
+
+impl<L, Context, W: Writer<L, Context> + ?Sized> HintedBranch<L, Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    const CONDITIONS: [ConditionCode; 14] = {
+        use ConditionCode::*;
+        [EQ, NE, HS, LO, MI, PL, VS, VC, HI, LS, GE, LT, GT, LE]
+    };
+
+    /// Whether `cc` holds for the flags `[n, z, c, v]`.
+    fn holds(cc: ConditionCode, [n, z, c, v]: [bool; 4]) -> bool {
+        use ConditionCode::*;
+        match cc {
+            EQ => z,
+            NE => !z,
+            HS => c,
+            LO => !c,
+            MI => n,
+            PL => !n,
+            VS => v,
+            VC => !v,
+            HI => c && !z,
+            LS => !c || z,
+            GE => n == v,
+            LT => n != v,
+            GT => !z && n == v,
+            LE => z || n != v,
+            AL | NV => true,
+        }
+    }
+
+    #[test]
+    fn test_each_successor_is_reached_exactly_when_it_should_be() {
+        for cc in CONDITIONS {
+            for hint in [BranchHint::None, BranchHint::Likely, BranchHint::Unlikely] {
+                let mut output = String::new();
+                let mut writer: &mut dyn Write = &mut output;
+                let next = writer
+                    .branch_two_way(
+                        &mut (),
+                        AArch64Arch::default(),
+                        cc,
+                        hint,
+                        "taken",
+                        "not_taken",
+                    )
+                    .unwrap();
+                assert_eq!(next, Fallthrough::for_hint(hint));
+                let (mnemonic, target) = output.trim_end().split_once(' ').unwrap();
+                let bcond = CONDITIONS
+                    .into_iter()
+                    .find(|c| format!("b.{c}") == mnemonic)
+                    .unwrap();
+                let fallthrough = match next {
+                    Fallthrough::Taken => "taken",
+                    Fallthrough::NotTaken => "not_taken",
+                };
+                for bits in 0..16 {
+                    let flags = core::array::from_fn(|i| (bits >> i) & 1 == 1);
+                    let reached = if holds(bcond, flags) {
+                        target
+                    } else {
+                        fallthrough
+                    };
+                    let expected = if holds(cc, flags) {
+                        "taken"
+                    } else {
+                        "not_taken"
+                    };
+                    assert_eq!(reached, expected, "{cc:?} {hint:?} {flags:?}");
+                }
+            }
+        }
+    }
+}
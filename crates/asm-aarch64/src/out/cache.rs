@@ -0,0 +1,124 @@
+//! Cache maintenance for code written at run time.
+//!
+//! AArch64 instruction fetch is not coherent with data stores, so code a JIT
+//! has just written may not be what the core executes. The data cache lines
+//! have to be cleaned to the point of unification, the instruction cache
+//! lines invalidated, and the fetched instructions discarded. [`IcacheSync`]
+//! emits that sequence for a range of addresses:
+//!
+//! ```ignore
+//! // start in x0, end in x1, x9 as scratch
+//! let sync = IcacheSync::new(Reg(0), Reg(1), Reg(9), 64, clean, invalidate);
+//! sync.emit(w, ctx, cfg)?;
+//! // and x9, x0, #0xffffffffffffffc0
+//! // clean: dc cvau, x9; add x9, x9, #64; cmp x9, x1; b.lo clean
+//! // dsb ish
+//! // and x9, x0, #0xffffffffffffffc0
+//! // invalidate: ic ivau, x9; add x9, x9, #64; cmp x9, x1; b.lo invalidate
+//! // dsb ish; isb
+//! ```
+//!
+//! The maintenance reaches every core in the inner shareable domain, but the
+//! `isb` only discards what the core running it has fetched; other cores
+//! that will run the code need an `isb` of their own. The x86-64 crate needs
+//! no sequence, and the RISC-V crate has `fence.i`.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::Writer;
+use crate::out::arg::ArgKind;
+use crate::{AArch64Arch, Barrier, ConditionCode, DcOp};
+
+fn reg64(reg: Reg) -> ArgKind {
+    ArgKind::Reg {
+        reg,
+        size: MemorySize::_64,
+    }
+}
+
+/// Emits the cache maintenance that makes written code executable.
+pub struct IcacheSync<L> {
+    start: Reg,
+    end: Reg,
+    scratch: Reg,
+    line: u32,
+    clean: L,
+    invalidate: L,
+}
+
+impl<L: Clone> IcacheSync<L> {
+    /// Creates the sequence for the code from the address in `start` up to
+    /// the address in `end`, stepping `scratch` through it a `line` at a time
+    /// and labelling the two loops `clean` and `invalidate`.
+    ///
+    /// `line` must be a power of two no larger than the smallest cache line
+    /// of the cores the code runs on. `CTR_EL0` reports the sizes, and 64
+    /// suits most cores; a smaller step only costs time.
+    pub fn new(start: Reg, end: Reg, scratch: Reg, line: u32, clean: L, invalidate: L) -> Self {
+        Self {
+            start,
+            end,
+            scratch,
+            line,
+            clean,
+            invalidate,
+        }
+    }
+
+    /// Emits the clean loop, a `dsb ish`, the invalidate loop, and a
+    /// `dsb ish` and `isb`.
+    ///
+    /// The sequence clobbers `scratch` and the flags, and defines both
+    /// labels, so it can be emitted once per pair of labels. An empty range
+    /// still maintains the line holding `start`.
+    pub fn emit<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let (start, end, scratch) = (reg64(self.start), reg64(self.end), reg64(self.scratch));
+        let mask = !(self.line as u64 - 1);
+        w.and_imm(ctx, cfg, &scratch, &start, mask)?;
+        w.set_label(ctx, cfg, self.clean.clone())?;
+        w.dc(ctx, cfg, DcOp::CVAU, &scratch)?;
+        w.add_imm(ctx, cfg, &scratch, &scratch, self.line.into())?;
+        w.cmp(ctx, cfg, &scratch, &end)?;
+        w.bcond_label(ctx, cfg, ConditionCode::LO, self.clean.clone())?;
+        w.dsb(ctx, cfg, Barrier::ISH)?;
+        w.and_imm(ctx, cfg, &scratch, &start, mask)?;
+        w.set_label(ctx, cfg, self.invalidate.clone())?;
+        w.ic_ivau(ctx, cfg, &scratch)?;
+        w.add_imm(ctx, cfg, &scratch, &scratch, self.line.into())?;
+        w.cmp(ctx, cfg, &scratch, &end)?;
+        w.bcond_label(ctx, cfg, ConditionCode::LO, self.invalidate.clone())?;
+        w.dsb(ctx, cfg, Barrier::ISH)?;
+        w.isb(ctx, cfg)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_cleans_then_invalidates_each_line() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let sync = IcacheSync::new(Reg(0), Reg(1), Reg(9), 64, ".Lclean", ".Linval");
+        sync.emit(&mut writer, &mut (), AArch64Arch::default())
+            .unwrap();
+        assert_eq!(
+            output,
+            "and x9, x0, #0xffffffffffffffc0\n.Lclean:\ndc cvau, x9\nadd x9, x9, #64\n\
+             cmp x9, x1\nb.lo .Lclean\ndsb ish\nand x9, x0, #0xffffffffffffffc0\n\
+             .Linval:\nic ivau, x9\nadd x9, x9, #64\ncmp x9, x1\nb.lo .Linval\n\
+             dsb ish\nisb\n"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Control-flow integrity landing pads and return-address signing.
+//!
+//! With branch target identification enforced, an indirect branch must
+//! land on a `bti` of the matching kind: `bti c` for calls through `blr`,
+//! `bti j` for jumps through `br`. With return-address signing, a function
+//! signs `x30` on entry and authenticates it before returning, so a
+//! corrupted return address faults instead of being followed. [`Cfi`]
+//! emits both where [`AArch64Arch::bti`] and [`AArch64Arch::pac_ret`] ask
+//! for them and nothing otherwise:
+//!
+//! ```ignore
+//! writer.function_entry(ctx, cfg)?;             // paciasp (or bti c)
+//! // ...
+//! writer.set_branch_target(ctx, cfg, case0)?;   // case0: bti j
+//! // ...
+//! writer.function_return(ctx, cfg)?;            // autiasp; ret
+//! ```
+//!
+//! `paciasp` is itself accepted as a `bti c` landing pad, so a signing
+//! entry needs no separate `bti`. Function entries written with
+//! [`emit_function`](crate::out::function::emit_function) get their pad
+//! from [`Function::with_landing_pad`](crate::out::function::Function::with_landing_pad).
+//! `sp` must have the same value at the return as at the entry.
+
+use crate::out::{Writer, WriterCore};
+use crate::{AArch64Arch, BtiTarget};
+
+/// Emits control-flow integrity landing pads and return-address signing.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Cfi<Context>: WriterCore<Context> {
+    /// Emits what a function entry needs: `paciasp` when return addresses
+    /// are signed, else `bti c` when BTI is enforced.
+    fn function_entry(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        if cfg.pac_ret {
+            self.paciasp(ctx, cfg)
+        } else if cfg.bti {
+            self.bti(ctx, cfg, BtiTarget::C)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns from a function entered with
+    /// [`function_entry`](Self::function_entry), authenticating `x30` first
+    /// when return addresses are signed.
+    fn function_return(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        if cfg.pac_ret {
+            self.autiasp(ctx, cfg)?;
+        }
+        self.ret(ctx, cfg)
+    }
+
+    /// Emits the landing pad an indirect jump target needs, if any.
+    fn branch_target(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        if cfg.bti {
+            self.bti(ctx, cfg, BtiTarget::J)?;
+        }
+        Ok(())
+    }
+
+    /// Defines `label` as the target of an indirect jump.
+    fn set_branch_target<L>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error>
+    where
+        Self: Writer<L, Context>,
+    {
+        self.set_label(ctx, cfg, label)?;
+        self.branch_target(ctx, cfg)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Cfi<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[cfg(feature = "bin-backend")]
+    #[test]
+    fn test_pads_and_signing_in_machine_code() {
+        use crate::out::bin::AArch64Writer;
+
+        let cfg = AArch64Arch {
+            bti: true,
+            pac_ret: true,
+            ..AArch64Arch::default()
+        };
+        let mut binary = AArch64Writer::<u32>::new();
+        binary.function_entry(&mut (), cfg).unwrap();
+        binary.set_branch_target(&mut (), cfg, 1).unwrap();
+        binary.function_return(&mut (), cfg).unwrap();
+        let (code, labels) = binary.into_parts();
+        let word = |at: usize| u32::from_le_bytes(code[at..at + 4].try_into().unwrap());
+        // `paciasp` signs x30 and is itself a valid `bti c` landing pad.
+        assert_eq!(word(0), 0xD503_233F);
+        // An indirect jump to the label reaches `bti j`.
+        assert_eq!(labels[&1], 4);
+        assert_eq!(word(4), 0xD503_249F);
+        // x30 is authenticated before `ret` uses it.
+        assert_eq!(word(8), 0xD503_23BF);
+        assert_eq!(word(12), 0xD65F_03C0);
+    }
+
+    #[test]
+    fn test_bti_only() {
+        let cfg = AArch64Arch {
+            bti: true,
+            ..AArch64Arch::default()
+        };
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        out.function_entry(&mut (), cfg).unwrap();
+        out.function_return(&mut (), cfg).unwrap();
+        assert_eq!(output, "bti c\nret\n");
+    }
+}
@@ -0,0 +1,1647 @@
+//! Coalescing of adjacent loads and stores into pair instructions.
+//!
+//! Translated code often saves and restores registers one at a time: the
+//! x86-64 shim lowers each `push` to `str xN, [sp, #-8]!`, and prologues
+//! spill callee-saved registers to consecutive stack slots.
+//! [`CoalescingWriter`] holds back each 64-bit `str`/`ldr` for one
+//! instruction and, when the next is its partner, emits a single `stp`/`ldp`
+//! instead:
+//!
+//! - `[base, #d]` followed by `[base, #d + 8]` (in either order) becomes
+//!   `[base, #d]`;
+//! - two `[base, #-8]!` accesses become `[base, #-16]!`, the second register
+//!   first;
+//! - two `[base], #8` accesses become `[base], #16`.
+//!
+//! Accesses are only merged when the pair has the same effect as the two
+//! instructions, so loads whose first destination is the base or the second
+//! destination, and writeback accesses of the base register itself, are left
+//! alone. Any other instruction, label or alignment emits the held access
+//! first. Call [`CoalescingWriter::finish`] at the end to emit the last one.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::{AArch64Arch, Barrier, DcOp, PredicateReg, PrfOp, SysReg};
+use crate::out::arg::{AddressingMode, ArgKind, MemArg, MemArgKind};
+use crate::out::{Writer, WriterCore};
+
+/// A 64-bit general-purpose `str` or `ldr` that may start a pair.
+#[derive(Clone, Copy)]
+struct Access {
+    cfg: AArch64Arch,
+    load: bool,
+    data: ArgKind,
+    mem: MemArgKind<ArgKind>,
+    reg: Reg,
+    base: Reg,
+    disp: i32,
+    mode: AddressingMode,
+}
+
+impl Access {
+    /// Returns the access if it is a candidate for pairing.
+    fn new(
+        cfg: AArch64Arch,
+        load: bool,
+        data: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Option<Self> {
+        let data = data.concrete_mem_kind();
+        let mem = mem.concrete_mem_kind();
+        let MemArgKind::NoMem(
+            data @ ArgKind::Reg {
+                reg,
+                size: MemorySize::_64,
+            },
+        ) = data
+        else {
+            return None;
+        };
+        let MemArgKind::Mem {
+            base: ArgKind::Reg { reg: base, .. },
+            offset: None,
+            disp,
+            size: MemorySize::_64,
+            reg_class: crate::RegisterClass::Gpr,
+            mode,
+        } = mem
+        else {
+            return None;
+        };
+        if mode != AddressingMode::Offset && reg == base {
+            return None;
+        }
+        Some(Self {
+            cfg,
+            load,
+            data,
+            mem,
+            reg,
+            base,
+            disp,
+            mode,
+        })
+    }
+
+    /// Returns the registers and memory operand of the pair instruction that
+    /// performs `self` followed by `next`, if there is one.
+    fn pair(&self, next: &Self) -> Option<(ArgKind, ArgKind, MemArgKind<ArgKind>)> {
+        if self.cfg != next.cfg
+            || self.load != next.load
+            || self.base != next.base
+            || self.mode != next.mode
+        {
+            return None;
+        }
+        if self.load && (self.reg == self.base || self.reg == next.reg) {
+            return None;
+        }
+        let (first, second, disp) = match self.mode {
+            AddressingMode::PreIndex if self.disp == -8 && next.disp == -8 => {
+                (next.data, self.data, -16)
+            }
+            AddressingMode::PostIndex if self.disp == 8 && next.disp == 8 => {
+                (self.data, next.data, 16)
+            }
+            AddressingMode::Offset if next.disp == self.disp + 8 => {
+                (self.data, next.data, self.disp)
+            }
+            AddressingMode::Offset if self.disp == next.disp + 8 => {
+                (next.data, self.data, next.disp)
+            }
+            _ => return None,
+        };
+        // LDP/STP take a signed 7-bit immediate scaled by 8.
+        if disp % 8 != 0 || !(-512..=504).contains(&disp) {
+            return None;
+        }
+        let MemArgKind::Mem {
+            base,
+            offset,
+            size,
+            reg_class,
+            mode,
+            ..
+        } = self.mem
+        else {
+            return None;
+        };
+        let mem = MemArgKind::Mem {
+            base,
+            offset,
+            disp,
+            size,
+            reg_class,
+            mode,
+        };
+        Some((first, second, mem))
+    }
+
+    /// Emits the access on its own.
+    fn emit<W: WriterCore<Context> + ?Sized, Context>(
+        &self,
+        writer: &mut W,
+        ctx: &mut Context,
+    ) -> Result<(), W::Error> {
+        if self.load {
+            writer.ldr(ctx, self.cfg, &self.data, &self.mem)
+        } else {
+            writer.str(ctx, self.cfg, &self.data, &self.mem)
+        }
+    }
+}
+
+/// Merges adjacent 64-bit loads and stores into `ldp`/`stp`.
+///
+/// Holds back at most one access; see the [module documentation](self) for
+/// which accesses are merged.
+pub struct CoalescingWriter<W> {
+    writer: W,
+    pending: Option<Access>,
+}
+
+impl<W> CoalescingWriter<W> {
+    /// Wraps `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            pending: None,
+        }
+    }
+
+    /// Emits the held access, if any.
+    pub fn flush<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: WriterCore<Context>,
+    {
+        match self.pending.take() {
+            Some(access) => access.emit(&mut self.writer, ctx),
+            None => Ok(()),
+        }
+    }
+
+    /// Emits the held access and returns the wrapped writer.
+    pub fn finish<Context>(mut self, ctx: &mut Context) -> Result<W, W::Error>
+    where
+        W: WriterCore<Context>,
+    {
+        self.flush(ctx)?;
+        Ok(self.writer)
+    }
+
+    /// Handles a `str` (`load == false`) or `ldr`.
+    fn access<Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        load: bool,
+        data: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: WriterCore<Context>,
+    {
+        let Some(next) = Access::new(cfg, load, data, mem) else {
+            self.flush(ctx)?;
+            return if load {
+                self.writer.ldr(ctx, cfg, data, mem)
+            } else {
+                self.writer.str(ctx, cfg, data, mem)
+            };
+        };
+        let Some(first) = self.pending.take() else {
+            self.pending = Some(next);
+            return Ok(());
+        };
+        match first.pair(&next) {
+            Some((a, b, mem)) if load => self.writer.ldp(ctx, cfg, &a, &b, &mem),
+            Some((a, b, mem)) => self.writer.stp(ctx, cfg, &a, &b, &mem),
+            None => {
+                first.emit(&mut self.writer, ctx)?;
+                self.pending = Some(next);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<W: WriterCore<Context>, Context> WriterCore<Context> for CoalescingWriter<W> {
+    type Error = W::Error;
+
+    fn str(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.access(ctx, cfg, false, src, mem)
+    }
+
+    fn ldr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.access(ctx, cfg, true, dest, mem)
+    }
+
+    /// Counts a held access as already emitted.
+    fn current_offset(&self) -> Option<usize> {
+        let held = usize::from(self.pending.is_some()) * 4;
+        self.writer.current_offset().map(|offset| offset + held)
+    }
+
+    fn brk(&mut self, ctx: &mut Context, cfg: AArch64Arch, imm: u16) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.brk(ctx, cfg, imm)
+    }
+
+    fn svc(&mut self, ctx: &mut Context, cfg: AArch64Arch, imm: u16) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.svc(ctx, cfg, imm)
+    }
+
+    fn bti(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: crate::BtiTarget,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.bti(ctx, cfg, target)
+    }
+
+    fn paciasp(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.paciasp(ctx, cfg)
+    }
+
+    fn autiasp(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.autiasp(ctx, cfg)
+    }
+
+    fn mov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.mov(ctx, cfg, dest, src)
+    }
+
+    fn stp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src1: &(dyn MemArg + '_),
+        src2: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.stp(ctx, cfg, src1, src2, mem)
+    }
+
+    fn ldp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest1: &(dyn MemArg + '_),
+        dest2: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.ldp(ctx, cfg, dest1, dest2, mem)
+    }
+
+    fn bl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.bl(ctx, cfg, target)
+    }
+
+    fn br(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.br(ctx, cfg, target)
+    }
+
+    fn b(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.b(ctx, cfg, target)
+    }
+
+    fn cmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.cmp(ctx, cfg, a, b)
+    }
+
+    fn tst(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.tst(ctx, cfg, a, b)
+    }
+
+    fn csel(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        dest: &(dyn MemArg + '_),
+        true_val: &(dyn MemArg + '_),
+        false_val: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.csel(ctx, cfg, cond, dest, true_val, false_val)
+    }
+
+    fn cset(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.cset(ctx, cfg, cond, dest)
+    }
+
+    fn bcond(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.bcond(ctx, cfg, cond, target)
+    }
+
+    fn adr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.adr(ctx, cfg, dest, src)
+    }
+
+    fn ret(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.ret(ctx, cfg)
+    }
+
+    fn mov_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.mov_imm(ctx, cfg, dest, val)
+    }
+
+    fn mul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.mul(ctx, cfg, dest, a, b)
+    }
+
+    fn umulh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.umulh(ctx, cfg, dest, a, b)
+    }
+
+    fn smulh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.smulh(ctx, cfg, dest, a, b)
+    }
+
+    fn udiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.udiv(ctx, cfg, dest, a, b)
+    }
+
+    fn sdiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sdiv(ctx, cfg, dest, a, b)
+    }
+
+    fn and(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.and(ctx, cfg, dest, a, b)
+    }
+
+    fn orr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.orr(ctx, cfg, dest, a, b)
+    }
+
+    fn eor(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.eor(ctx, cfg, dest, a, b)
+    }
+
+    fn lsl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.lsl(ctx, cfg, dest, a, b)
+    }
+
+    fn lsr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.lsr(ctx, cfg, dest, a, b)
+    }
+
+    fn asr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.asr(ctx, cfg, dest, a, b)
+    }
+
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.ror(ctx, cfg, dest, a, b)
+    }
+
+    fn cls(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.cls(ctx, cfg, dest, src)
+    }
+
+    fn clz(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.clz(ctx, cfg, dest, src)
+    }
+
+    fn rbit(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.rbit(ctx, cfg, dest, src)
+    }
+
+    fn rev(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.rev(ctx, cfg, dest, src)
+    }
+
+    fn ubfx(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        lsb: u8,
+        width: u8,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.ubfx(ctx, cfg, dest, src, lsb, width)
+    }
+
+    fn bfi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        lsb: u8,
+        width: u8,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.bfi(ctx, cfg, dest, src, lsb, width)
+    }
+
+    fn sub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sub(ctx, cfg, dest, a, b)
+    }
+
+    fn add(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.add(ctx, cfg, dest, a, b)
+    }
+
+    fn adds(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.adds(ctx, cfg, dest, a, b)
+    }
+
+    fn subs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.subs(ctx, cfg, dest, a, b)
+    }
+
+    fn adcs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.adcs(ctx, cfg, dest, a, b)
+    }
+
+    fn sbcs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sbcs(ctx, cfg, dest, a, b)
+    }
+
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.add_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sub_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.and_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn orr_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.orr_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn eor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.eor_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn cmp_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.cmp_imm(ctx, cfg, a, imm)
+    }
+
+    fn add_uxtw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.add_uxtw(ctx, cfg, dest, a, b)
+    }
+
+    fn add_lsl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        shift: u8,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.add_lsl(ctx, cfg, dest, a, b, shift)
+    }
+
+    fn sxt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sxt(ctx, cfg, dest, src)
+    }
+
+    fn uxt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.uxt(ctx, cfg, dest, src)
+    }
+
+    fn mvn(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.mvn(ctx, cfg, dest, src)
+    }
+
+    fn neg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.neg(ctx, cfg, dest, src)
+    }
+
+    fn negs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.negs(ctx, cfg, dest, src)
+    }
+
+    fn fadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fadd(ctx, cfg, dest, a, b)
+    }
+
+    fn fsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fsub(ctx, cfg, dest, a, b)
+    }
+
+    fn fmul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fmul(ctx, cfg, dest, a, b)
+    }
+
+    fn fdiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fdiv(ctx, cfg, dest, a, b)
+    }
+
+    fn fmadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fmadd(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fmsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fmsub(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fnmadd(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fnmsub(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fcmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fcmp(ctx, cfg, a, b)
+    }
+
+    fn fmin(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fmin(ctx, cfg, dest, a, b)
+    }
+
+    fn fmax(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fmax(ctx, cfg, dest, a, b)
+    }
+
+    fn fsqrt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fsqrt(ctx, cfg, dest, src)
+    }
+
+    fn fabs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fabs(ctx, cfg, dest, src)
+    }
+
+    fn fneg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fneg(ctx, cfg, dest, src)
+    }
+
+    fn fcsel(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fcsel(ctx, cfg, cond, dest, a, b)
+    }
+
+    fn scvtf(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.scvtf(ctx, cfg, dest, src)
+    }
+
+    fn ucvtf(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.ucvtf(ctx, cfg, dest, src)
+    }
+
+    fn fcvtzs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fcvtzs(ctx, cfg, dest, src)
+    }
+
+    fn fcvtzu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fcvtzu(ctx, cfg, dest, src)
+    }
+
+    fn fcvt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fcvt(ctx, cfg, dest, src)
+    }
+
+    fn fmov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.fmov(ctx, cfg, dest, src)
+    }
+
+    fn whilelt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.whilelt(ctx, cfg, dest, a, b)
+    }
+
+    fn ptrue(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.ptrue(ctx, cfg, dest)
+    }
+
+    fn sve_inc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        elem: MemorySize,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sve_inc(ctx, cfg, dest, elem)
+    }
+
+    fn sve_ld1(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sve_ld1(ctx, cfg, dest, pg, mem)
+    }
+
+    fn sve_st1(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sve_st1(ctx, cfg, src, pg, mem)
+    }
+
+    fn sve_ld1d_gather(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        base: &(dyn MemArg + '_),
+        offsets: &(dyn MemArg + '_),
+        scaled: bool,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer
+            .sve_ld1d_gather(ctx, cfg, dest, pg, base, offsets, scaled)
+    }
+
+    fn sve_st1d_scatter(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        base: &(dyn MemArg + '_),
+        offsets: &(dyn MemArg + '_),
+        scaled: bool,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer
+            .sve_st1d_scatter(ctx, cfg, src, pg, base, offsets, scaled)
+    }
+
+    fn sve_add(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sve_add(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_sub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sve_sub(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sve_fadd(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sve_fsub(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fmul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sve_fmul(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fmla(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sve_fmla(ctx, cfg, dest, pg, a, b)
+    }
+
+    fn sve_dup(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sve_dup(ctx, cfg, dest, src)
+    }
+
+    fn db(&mut self, ctx: &mut Context, cfg: AArch64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.db(ctx, cfg, bytes)
+    }
+
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.align_to(ctx, cfg, alignment)
+    }
+
+    fn nop(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.nop(ctx, cfg)
+    }
+
+    fn nops(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.nops(ctx, cfg, n_bytes)
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: AArch64Arch, pow2: u32) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.align(ctx, cfg, pow2)
+    }
+
+    fn mrs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        reg: SysReg,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.mrs(ctx, cfg, dest, reg)
+    }
+
+    fn msr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        reg: SysReg,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.msr(ctx, cfg, reg, src)
+    }
+
+    fn mrs_nzcv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.mrs_nzcv(ctx, cfg, dest)
+    }
+
+    fn msr_nzcv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.msr_nzcv(ctx, cfg, src)
+    }
+
+    fn mrs_fpcr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.mrs_fpcr(ctx, cfg, dest)
+    }
+
+    fn msr_fpcr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.msr_fpcr(ctx, cfg, src)
+    }
+
+    fn mrs_tpidr_el0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.mrs_tpidr_el0(ctx, cfg, dest)
+    }
+
+    fn dmb(&mut self, ctx: &mut Context, cfg: AArch64Arch, barrier: Barrier) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.dmb(ctx, cfg, barrier)
+    }
+
+    fn dsb(&mut self, ctx: &mut Context, cfg: AArch64Arch, barrier: Barrier) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.dsb(ctx, cfg, barrier)
+    }
+
+    fn isb(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.isb(ctx, cfg)
+    }
+
+    fn hint(&mut self, ctx: &mut Context, cfg: AArch64Arch, imm: u8) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.hint(ctx, cfg, imm)
+    }
+
+    fn yield_(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.yield_(ctx, cfg)
+    }
+
+    fn wfe(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.wfe(ctx, cfg)
+    }
+
+    fn sev(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.sev(ctx, cfg)
+    }
+
+    fn prfm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        op: PrfOp,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.prfm(ctx, cfg, op, mem)
+    }
+
+    fn dc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        op: DcOp,
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.dc(ctx, cfg, op, addr)
+    }
+
+    fn ic_ivau(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.ic_ivau(ctx, cfg, addr)
+    }
+
+    fn ldar(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.ldar(ctx, cfg, dest, mem)
+    }
+
+    fn stlr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.stlr(ctx, cfg, src, mem)
+    }
+
+    fn swpal(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.swpal(ctx, cfg, src, dest, mem)
+    }
+
+    fn adrp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.adrp(ctx, cfg, dest, src)
+    }
+
+    fn add_lo12(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        sym: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.add_lo12(ctx, cfg, dest, src, sym)
+    }
+}
+
+impl<W: Writer<L, Context>, L, Context> Writer<L, Context> for CoalescingWriter<W> {
+    fn set_label(&mut self, ctx: &mut Context, cfg: AArch64Arch, s: L) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.set_label(ctx, cfg, s)
+    }
+
+    fn adr_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.adr_label(ctx, cfg, dest, label)
+    }
+
+    fn b_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.b_label(ctx, cfg, label)
+    }
+
+    fn bcond_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.bcond_label(ctx, cfg, cond, label)
+    }
+
+    fn bl_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.bl_label(ctx, cfg, label)
+    }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.flush(ctx)?;
+        self.writer.dd_label(ctx, cfg, label)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    fn slot(base: Reg, disp: i32, mode: AddressingMode) -> MemArgKind<ArgKind> {
+        MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: base,
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp,
+            size: MemorySize::_64,
+            reg_class: crate::RegisterClass::Gpr,
+            mode,
+        }
+    }
+
+    #[test]
+    fn test_pushes_and_pops_pair_up() {
+        let cfg = AArch64Arch::default();
+        let (x0, x1, x2, sp) = (Reg(0), Reg(1), Reg(2), Reg(31));
+        let push = slot(sp, -8, AddressingMode::PreIndex);
+        let pop = slot(sp, 8, AddressingMode::PostIndex);
+
+        let mut output = String::new();
+        let mut writer = CoalescingWriter::new(&mut output as &mut dyn Write);
+        writer.str(&mut (), cfg, &x0, &push).unwrap();
+        writer.str(&mut (), cfg, &x1, &push).unwrap();
+        writer.str(&mut (), cfg, &x2, &push).unwrap();
+        writer.ldr(&mut (), cfg, &x2, &pop).unwrap();
+        writer.ldr(&mut (), cfg, &x1, &pop).unwrap();
+        writer.ldr(&mut (), cfg, &x0, &pop).unwrap();
+        writer.finish(&mut ()).unwrap();
+
+        let mut expected = String::new();
+        let plain: &mut dyn Write = &mut expected;
+        let pair_push = slot(sp, -16, AddressingMode::PreIndex);
+        let pair_pop = slot(sp, 16, AddressingMode::PostIndex);
+        plain.stp(&mut (), cfg, &x1, &x0, &pair_push).unwrap();
+        plain.str(&mut (), cfg, &x2, &push).unwrap();
+        plain.ldp(&mut (), cfg, &x2, &x1, &pair_pop).unwrap();
+        plain.ldr(&mut (), cfg, &x0, &pop).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_dependent_loads_stay_separate() {
+        let cfg = AArch64Arch::default();
+        let (x0, x1) = (Reg(0), Reg(1));
+
+        let mut output = String::new();
+        let mut writer = CoalescingWriter::new(&mut output as &mut dyn Write);
+        writer
+            .ldr(&mut (), cfg, &x0, &slot(x0, 0, AddressingMode::Offset))
+            .unwrap();
+        writer
+            .ldr(&mut (), cfg, &x1, &slot(x0, 8, AddressingMode::Offset))
+            .unwrap();
+        writer.finish(&mut ()).unwrap();
+        assert!(!output.contains("ldp"));
+        assert_eq!(output.lines().count(), 2);
+    }
+}
@@ -0,0 +1,189 @@
+//! Constant pool for 64-bit immediates and floating-point literals.
+//!
+//! A 64-bit immediate takes up to four `movz`/`movk` instructions, and FP
+//! literals other than the few `fmov` can encode have to be bounced through
+//! a general-purpose register. [`ConstantPool`] records each constant once,
+//! loads it with `adr` + `ldr`, and emits the collected values after the
+//! function:
+//!
+//! ```ignore
+//! let mut next = 0usize;
+//! let mut pool = ConstantPool::new(|| { next += 1; format!(".LCP{next}") });
+//! pool.load_u64(&mut writer, ctx, cfg, Reg(16), 0x1234_5678_9abc_def0)?;
+//! pool.load_f64(&mut writer, ctx, cfg, &d1, Reg(16), 1.5)?;
+//! writer.ret(ctx, cfg)?;
+//! pool.emit(&mut writer, ctx, cfg)?;
+//! ```
+
+use alloc::vec::Vec;
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::AArch64Arch;
+use crate::out::Writer;
+use crate::out::arg::{AddressingMode, ArgKind, MemArg, MemArgKind};
+
+/// Collects 64-bit constants and loads them PC-relatively.
+///
+/// Identical bit patterns share one slot. Slots are labelled with labels from
+/// the `alloc` closure and must be written out with [`ConstantPool::emit`]
+/// within `adr` range (±1 MiB) of the loads, typically right after the
+/// function that uses them.
+pub struct ConstantPool<L, F> {
+    alloc: F,
+    /// Pooled values and the labels of their slots.
+    entries: Vec<(u64, L)>,
+}
+
+impl<L: Clone, F: FnMut() -> L> ConstantPool<L, F> {
+    /// Creates an empty pool that labels its slots with `alloc`.
+    pub fn new(alloc: F) -> Self {
+        Self {
+            alloc,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the number of distinct constants in the pool.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no constants have been pooled.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the label of the slot holding `bits`, adding one if needed.
+    pub fn intern(&mut self, bits: u64) -> L {
+        if let Some((_, label)) = self.entries.iter().find(|(b, _)| *b == bits) {
+            return label.clone();
+        }
+        let label = (self.alloc)();
+        self.entries.push((bits, label.clone()));
+        label
+    }
+
+    /// Loads `value` into the general-purpose register `dest`.
+    pub fn load_u64<W, Context>(
+        &mut self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: Reg,
+        value: u64,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let label = self.intern(value);
+        w.adr_label(ctx, cfg, &dest, label)?;
+        w.ldr(ctx, cfg, &dest, &slot(dest))
+    }
+
+    /// Loads `value` into the FP register `dest`, clobbering `addr`.
+    pub fn load_f64<W, Context>(
+        &mut self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        addr: Reg,
+        value: f64,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let label = self.intern(value.to_bits());
+        w.adr_label(ctx, cfg, &addr, label)?;
+        w.ldr(ctx, cfg, dest, &slot(addr))
+    }
+
+    /// Emits the pooled constants, 8-byte aligned, and empties the pool.
+    ///
+    /// The pool is data: emit it where execution cannot fall into it, such as
+    /// after the function's final `ret` or `b`.
+    pub fn emit<W, Context>(
+        &mut self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        if self.entries.is_empty() {
+            return Ok(());
+        }
+        w.align_to(ctx, cfg, 8)?;
+        for (bits, label) in self.entries.drain(..) {
+            w.set_label(ctx, cfg, label)?;
+            w.db(ctx, cfg, &cfg.u64_bytes(bits))?;
+        }
+        Ok(())
+    }
+}
+
+/// The 64-bit slot addressed by `addr`.
+fn slot(addr: Reg) -> MemArgKind<ArgKind> {
+    MemArgKind::Mem {
+        base: ArgKind::Reg {
+            reg: addr,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp: 0,
+        size: MemorySize::_64,
+        reg_class: crate::RegisterClass::Gpr,
+        mode: AddressingMode::Offset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_constants_are_shared_and_emitted() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default();
+        let mut next = 0;
+        let mut pool = ConstantPool::new(|| {
+            next += 1;
+            format!(".LCP{next}")
+        });
+        pool.load_u64(&mut writer, &mut (), cfg, Reg(16), 0x0123_4567_89ab_cdef)
+            .unwrap();
+        pool.load_u64(&mut writer, &mut (), cfg, Reg(17), 0x0123_4567_89ab_cdef)
+            .unwrap();
+        pool.load_f64(&mut writer, &mut (), cfg, &Reg(2), Reg(16), 1.0)
+            .unwrap();
+        assert_eq!(pool.len(), 2);
+        pool.emit(&mut writer, &mut (), cfg).unwrap();
+        assert!(pool.is_empty());
+
+        assert_eq!(output.matches(", .LCP1\n").count(), 2);
+        assert!(output.contains("adr x16, .LCP2\n"));
+        assert!(output.contains(".balign 8\n.LCP1:\n.byte 0xef, 0xcd"));
+        // 1.0 = 0x3ff0_0000_0000_0000
+        assert!(output.contains(".LCP2:\n.byte 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f\n"));
+    }
+
+    #[test]
+    fn test_big_endian_pool_bytes() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::from_triple("aarch64_be-unknown-linux-gnu").unwrap();
+        assert!(cfg.big_endian);
+        assert_eq!(AArch64Arch::from_triple("x86_64-unknown-linux-gnu"), None);
+        let mut pool = ConstantPool::new(|| String::from(".LCP"));
+        pool.load_u64(&mut writer, &mut (), cfg, Reg(16), 0x0123_4567_89ab_cdef)
+            .unwrap();
+        pool.emit(&mut writer, &mut (), cfg).unwrap();
+        assert!(output.contains(".LCP:\n.byte 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef\n"));
+    }
+}
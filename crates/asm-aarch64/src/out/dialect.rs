@@ -0,0 +1,229 @@
+//! Assembler dialects for text output.
+//!
+//! The text writers produce one instruction per line in the syntax GNU `as`
+//! accepts. Toolchains that want some instructions spelled differently can
+//! put a [`DialectWriter`] between a text writer and its output; it passes
+//! every instruction line through a [`Dialect`], which may rename the
+//! mnemonic or reorder the operands:
+//!
+//! ```ignore
+//! let mut out = DialectWriter::new(&mut output, |out: &mut dyn Write, m: &str, ops: &[&str]| {
+//!     write_insn(out, if m == "mov" { "orr" } else { m }, ops)
+//! });
+//! let writer: &mut dyn Write = &mut out;
+//! ```
+//!
+//! Labels, directives and lines too long to buffer are passed through
+//! unchanged.
+
+use core::fmt::{self, Write};
+
+/// Longest line [`DialectWriter`] rewrites; longer lines pass through as is.
+const LINE: usize = 256;
+
+/// Most operands [`DialectWriter`] splits a line into.
+const OPERANDS: usize = 8;
+
+/// Rewrites instructions for a particular assembler.
+///
+/// Closures with the same signature as [`Dialect::write_insn`] are dialects,
+/// so one-off renames need no type of their own.
+pub trait Dialect {
+    /// Writes one instruction, without the trailing newline.
+    ///
+    /// `operands` are split at top-level commas and trimmed. The default
+    /// writes the instruction unchanged.
+    fn write_insn(
+        &mut self,
+        out: &mut dyn Write,
+        mnemonic: &str,
+        operands: &[&str],
+    ) -> fmt::Result {
+        write_insn(out, mnemonic, operands)
+    }
+}
+
+impl<F> Dialect for F
+where
+    F: FnMut(&mut dyn Write, &str, &[&str]) -> fmt::Result,
+{
+    fn write_insn(
+        &mut self,
+        out: &mut dyn Write,
+        mnemonic: &str,
+        operands: &[&str],
+    ) -> fmt::Result {
+        self(out, mnemonic, operands)
+    }
+}
+
+/// Writes `mnemonic op1, op2, ...`.
+pub fn write_insn(out: &mut dyn Write, mnemonic: &str, operands: &[&str]) -> fmt::Result {
+    out.write_str(mnemonic)?;
+    for (i, op) in operands.iter().enumerate() {
+        out.write_str(if i == 0 { " " } else { ", " })?;
+        out.write_str(op)?;
+    }
+    Ok(())
+}
+
+/// GNU `as`, which accepts the text writers' output unchanged.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct GnuAs;
+
+impl Dialect for GnuAs {}
+
+/// LLVM's integrated assembler.
+///
+/// Accepts the text writers' output unchanged, like [`GnuAs`]; it is a
+/// separate dialect so callers can name the assembler they target.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct LlvmIas;
+
+impl Dialect for LlvmIas {}
+
+/// A text sink that rewrites each instruction line with a [`Dialect`].
+///
+/// Implements [`Write`], so it is used as `&mut dyn Write` with the text
+/// writers.
+pub struct DialectWriter<W, D> {
+    out: W,
+    dialect: D,
+    line: [u8; LINE],
+    len: usize,
+    /// The current line did not fit and is being passed through.
+    overflow: bool,
+}
+
+impl<W: Write, D: Dialect> DialectWriter<W, D> {
+    /// Writes to `out` in `dialect`.
+    pub fn new(out: W, dialect: D) -> Self {
+        Self {
+            out,
+            dialect,
+            line: [0; LINE],
+            len: 0,
+            overflow: false,
+        }
+    }
+
+    /// Returns the output sink.
+    ///
+    /// A final line without a newline is written unchanged.
+    pub fn into_inner(mut self) -> Result<W, fmt::Error> {
+        if !self.overflow {
+            let line = core::str::from_utf8(&self.line[..self.len]).map_err(|_| fmt::Error)?;
+            self.out.write_str(line)?;
+        }
+        Ok(self.out)
+    }
+
+    /// Appends `s`, which contains no newline, to the current line.
+    fn push(&mut self, s: &str) -> fmt::Result {
+        if self.overflow {
+            return self.out.write_str(s);
+        }
+        let end = self.len + s.len();
+        if end > LINE {
+            let held = core::str::from_utf8(&self.line[..self.len]).map_err(|_| fmt::Error)?;
+            self.out.write_str(held)?;
+            self.out.write_str(s)?;
+            self.overflow = true;
+            self.len = 0;
+            return Ok(());
+        }
+        self.line[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+
+    /// Writes the current line through the dialect and starts a new one.
+    fn end_line(&mut self) -> fmt::Result {
+        if !self.overflow {
+            let line = core::str::from_utf8(&self.line[..self.len]).map_err(|_| fmt::Error)?;
+            rewrite(&mut self.out, &mut self.dialect, line)?;
+        }
+        self.len = 0;
+        self.overflow = false;
+        self.out.write_char('\n')
+    }
+}
+
+impl<W: Write, D: Dialect> Write for DialectWriter<W, D> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut lines = s.split('\n');
+        if let Some(first) = lines.next() {
+            self.push(first)?;
+        }
+        for line in lines {
+            self.end_line()?;
+            self.push(line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Passes one line through `dialect` if it holds an instruction.
+fn rewrite(out: &mut dyn Write, dialect: &mut dyn Dialect, line: &str) -> fmt::Result {
+    let text = line.trim();
+    if text.is_empty() || text.ends_with(':') || text.starts_with('.') {
+        return out.write_str(line);
+    }
+    let (mnemonic, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let mut operands = [""; OPERANDS];
+    let mut count = 0;
+    let mut depth = 0i32;
+    let mut start = 0;
+    let rest = rest.trim();
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                if count == OPERANDS {
+                    return out.write_str(line);
+                }
+                operands[count] = rest[start..i].trim();
+                count += 1;
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !rest.is_empty() {
+        if count == OPERANDS {
+            return out.write_str(line);
+        }
+        operands[count] = rest[start..].trim();
+        count += 1;
+    }
+    let indent = &line[..line.len() - line.trim_start().len()];
+    out.write_str(indent)?;
+    dialect.write_insn(out, mnemonic, &operands[..count])
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::AArch64Arch;
+    use crate::out::WriterCore;
+    use alloc::string::String;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_custom_dialect_renames_mnemonics() {
+        let cfg = AArch64Arch::default();
+        let mut output = String::new();
+        {
+            let rename = |out: &mut dyn Write, mnemonic: &str, operands: &[&str]| match mnemonic {
+                "mov" => write_insn(out, "orr", &[operands[0], "xzr", operands[1]]),
+                _ => write_insn(out, mnemonic, operands),
+            };
+            let mut vendor = DialectWriter::new(&mut output, rename);
+            let writer: &mut dyn Write = &mut vendor;
+            writer.mov(&mut (), cfg, &Reg(0), &Reg(2)).unwrap();
+            writer.ret(&mut (), cfg).unwrap();
+        }
+        assert_eq!(output, "orr x0, xzr, x2\nret\n");
+    }
+}
@@ -0,0 +1,65 @@
+//! Fused multiply-add.
+//!
+//! [`Fma`] computes `a * b + c` on double-precision values with a single
+//! rounding:
+//!
+//! ```ignore
+//! writer.fma(ctx, cfg, &d0, &d1, &d2, &d3)?;
+//! // fmadd d0, d1, d2, d3
+//! ```
+//!
+//! FMADD is part of the base AArch64 floating-point instructions, so there
+//! is no fallback. The x86-64 and RISC-V crates provide the same helper,
+//! with x86-64 falling back to a separately rounded multiply and add when
+//! FMA3 is disabled.
+
+use crate::AArch64Arch;
+use crate::out::WriterCore;
+use crate::out::arg::MemArg;
+
+/// Emits fused multiply-adds.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Fma<Context>: WriterCore<Context> {
+    /// Sets `dest` to `a * b + c`, rounded once.
+    ///
+    /// Any of the operands may be the same register.
+    fn fma(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.fmadd(ctx, cfg, dest, a, b, c)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Fma<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_fma_is_fmadd() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        writer
+            .fma(
+                &mut (),
+                AArch64Arch::default(),
+                &Reg(0),
+                &Reg(1),
+                &Reg(2),
+                &Reg(0),
+            )
+            .unwrap();
+        assert_eq!(output, "fmadd v0.d, v1.d, v2.d, v0.d\n");
+    }
+}
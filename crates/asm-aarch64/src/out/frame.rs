@@ -0,0 +1,181 @@
+//! Stack probing for large frames.
+//!
+//! Below the stack sits a guard page that turns an overflow into a fault.
+//! Moving `sp` down by a page or more in one step can jump over it and land
+//! in other memory (a "stack clash"), so every page of a large frame must be
+//! touched in order as it is allocated. [`FrameBuilder`] allocates frames
+//! that way:
+//!
+//! ```ignore
+//! let frame = FrameBuilder::new(probe_label);
+//! frame.probe_large_frame(&mut writer, ctx, cfg, 0x6000)?;
+//! // movz x16, #6
+//! // probe_label: sub sp, sp, #4096; str x16, [sp]
+//! // sub x16, x16, #1; cmp x16, #0; b.ne probe_label
+//! ```
+//!
+//! On [`TargetOs::Windows`](crate::TargetOs::Windows) the platform routine
+//! does the probing instead, through [`alloc_frame`]. Elsewhere a frame of
+//! up to four pages is probed with straight-line code and larger ones with
+//! a loop counting pages in a scratch register.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::Writer;
+use crate::out::arg::{AddressingMode, MemArgKind};
+use crate::stack::alloc_frame;
+use crate::{AArch64Arch, ConditionCode, RegisterClass};
+
+/// Bytes of stack a frame may allocate before it must be probed.
+pub const PROBE_INTERVAL: u32 = 4096;
+
+/// Default scratch register for the probe loop: `x16` (`ip0`), which the
+/// AAPCS64 reserves for linker veneers and never uses for arguments.
+pub const DEFAULT_SCRATCH: Reg = Reg(16);
+
+/// Most pages probed with straight-line code before a loop is used.
+const UNROLL_LIMIT: u32 = 4;
+
+/// The stack pointer, `sp`.
+const SP: Reg = Reg(31);
+
+/// The doubleword at `[sp]`.
+fn stack_top() -> MemArgKind<Reg> {
+    MemArgKind::Mem {
+        base: SP,
+        offset: None,
+        disp: 0,
+        size: MemorySize::_64,
+        reg_class: RegisterClass::Gpr,
+        mode: AddressingMode::Offset,
+    }
+}
+
+/// Allocates stack frames without skipping the guard page.
+pub struct FrameBuilder<L> {
+    probe_loop: L,
+    scratch: Reg,
+}
+
+impl<L: Clone> FrameBuilder<L> {
+    /// Creates a builder that labels its probe loop `probe_loop`.
+    ///
+    /// The label is only defined when a loop is emitted, and at most once
+    /// per call to [`probe_large_frame`](Self::probe_large_frame).
+    pub fn new(probe_loop: L) -> Self {
+        Self {
+            probe_loop,
+            scratch: DEFAULT_SCRATCH,
+        }
+    }
+
+    /// Sets the register the probe loop counts pages in.
+    pub fn with_scratch(self, scratch: Reg) -> Self {
+        Self { scratch, ..self }
+    }
+
+    /// Moves `sp` down by `size` bytes, touching each new page on the way.
+    ///
+    /// Frames smaller than [`PROBE_INTERVAL`] are a plain `sub`. The
+    /// sequence clobbers the flags and, for large frames, the scratch
+    /// register (or `x15` to `x17` on Windows).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not a multiple of 16 or does not fit in 24 bits.
+    pub fn probe_large_frame<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        size: u32,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        if size < PROBE_INTERVAL || cfg.os.stack_probe().is_some() {
+            return alloc_frame(w, ctx, cfg, size);
+        }
+        assert!(
+            size % 16 == 0 && size < 1 << 24,
+            "frame size must be a 16-byte multiple below 16 MiB"
+        );
+        let pages = size / PROBE_INTERVAL;
+        if pages <= UNROLL_LIMIT {
+            for _ in 0..pages {
+                self.probe_page(w, ctx, cfg, PROBE_INTERVAL)?;
+            }
+        } else {
+            w.mov_imm(ctx, cfg, &self.scratch, u64::from(pages))?;
+            w.set_label(ctx, cfg, self.probe_loop.clone())?;
+            self.probe_page(w, ctx, cfg, PROBE_INTERVAL)?;
+            w.sub_imm(ctx, cfg, &self.scratch, &self.scratch, 1)?;
+            w.cmp_imm(ctx, cfg, &self.scratch, 0)?;
+            w.bcond_label(ctx, cfg, ConditionCode::NE, self.probe_loop.clone())?;
+        }
+        match size % PROBE_INTERVAL {
+            0 => Ok(()),
+            rest => self.probe_page(w, ctx, cfg, rest),
+        }
+    }
+
+    /// Moves `sp` down by `bytes` and stores the scratch register at the
+    /// new top of stack.
+    fn probe_page<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        bytes: u32,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        w.sub_imm(ctx, cfg, &SP, &SP, i64::from(bytes))?;
+        w.str(ctx, cfg, &self.scratch, &stack_top())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::TargetOs;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_small_frame_is_not_probed() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        FrameBuilder::new(".Lprobe")
+            .probe_large_frame(&mut writer, &mut (), AArch64Arch::default(), 48)
+            .unwrap();
+        assert_eq!(output, "sub sp, sp, #48\n");
+    }
+
+    #[test]
+    fn test_many_pages_use_a_loop() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        FrameBuilder::new(".Lprobe")
+            .probe_large_frame(&mut writer, &mut (), AArch64Arch::default(), 10 * 4096 + 32)
+            .unwrap();
+        assert!(output.starts_with("movz x16, #10, lsl #0\n.Lprobe:\nsub sp, sp, #4096\n"));
+        assert!(output.contains("sub x16, x16, #1\ncmp x16, #0\nb.ne .Lprobe\nsub sp, sp, #32\n"));
+    }
+
+    #[test]
+    fn test_windows_calls_chkstk() {
+        let cfg = AArch64Arch {
+            os: TargetOs::Windows,
+            ..AArch64Arch::default()
+        };
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        FrameBuilder::new(".Lprobe")
+            .probe_large_frame(&mut writer, &mut (), cfg, 0x10000)
+            .unwrap();
+        assert!(output.contains("bl __chkstk\n"));
+        assert!(!output.contains(".Lprobe"));
+    }
+}
@@ -0,0 +1,243 @@
+//! Function symbols and hot/cold placement for text output.
+//!
+//! Writers emit instructions and labels but no symbol metadata, so a
+//! translated function is just a label to the linker. [`emit_function`]
+//! brackets a body with the directives an ELF toolchain expects: the
+//! section, the symbol's binding and visibility, `.type` and `.size`.
+//! Marking a function [`Temperature::Hot`] or [`Temperature::Cold`] places it
+//! in `.text.hot` or `.text.unlikely`, which linkers group together the same
+//! way they do for PGO-built code. For [`TargetOs::Apple`] the directives
+//! are the Mach-O ones instead: symbols get a leading `_`, hidden and
+//! protected symbols are `.private_extern`, and there is no `.type`, `.size`
+//! or hot/cold section:
+//!
+//! ```ignore
+//! let out: &mut dyn Write = &mut output;
+//! let func = Function::new("handler").with_visibility(Visibility::Global).with_temperature(Temperature::Cold);
+//! emit_function(out, &func, |w| w.ret(ctx, cfg))?;
+//! ```
+
+use core::fmt::{self, Write};
+
+use crate::TargetOs;
+
+/// Operand of `.type` that marks a function symbol.
+const FUNCTION_TYPE: &str = "%function";
+
+/// Where the linker should place a function relative to other code.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Temperature {
+    /// Ordinary code, in `.text`.
+    #[default]
+    Normal,
+    /// Frequently executed code, in `.text.hot`.
+    Hot,
+    /// Rarely executed code, in `.text.unlikely`.
+    Cold,
+}
+
+impl Temperature {
+    /// Returns the name of the section functions of this temperature go in.
+    pub fn section(self) -> &'static str {
+        match self {
+            Temperature::Normal => ".text",
+            Temperature::Hot => ".text.hot",
+            Temperature::Cold => ".text.unlikely",
+        }
+    }
+}
+
+/// Binding and visibility of a function symbol.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Visibility {
+    /// Local to the object file.
+    #[default]
+    Local,
+    /// Global and visible outside the shared object.
+    Global,
+    /// Global, but not exported from the shared object (`.hidden`).
+    Hidden,
+    /// Global and exported, but not preemptible (`.protected`).
+    Protected,
+}
+
+/// Symbol metadata for one function.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Function<'a> {
+    /// Symbol name, also used as the entry label.
+    pub name: &'a str,
+    /// Binding and visibility of the symbol.
+    pub visibility: Visibility,
+    /// Section placement.
+    pub temperature: Temperature,
+    /// Operating system, which picks the object format's directives.
+    pub os: TargetOs,
+    /// Whether the entry starts with a `bti c` landing pad, as branch target
+    /// identification ([`AArch64Arch::bti`](crate::AArch64Arch::bti))
+    /// requires.
+    pub landing_pad: bool,
+}
+
+impl<'a> Function<'a> {
+    /// Describes a local function in `.text`.
+    pub fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            visibility: Visibility::Local,
+            temperature: Temperature::Normal,
+            os: TargetOs::Linux,
+            landing_pad: false,
+        }
+    }
+
+    /// Sets the symbol's binding and visibility.
+    pub fn with_visibility(self, visibility: Visibility) -> Self {
+        Self { visibility, ..self }
+    }
+
+    /// Sets the section placement.
+    pub fn with_temperature(self, temperature: Temperature) -> Self {
+        Self {
+            temperature,
+            ..self
+        }
+    }
+
+    /// Sets the operating system the directives are written for.
+    pub fn with_os(self, os: TargetOs) -> Self {
+        Self { os, ..self }
+    }
+
+    /// Sets whether the entry starts with a `bti c` landing pad.
+    pub fn with_landing_pad(self, landing_pad: bool) -> Self {
+        Self {
+            landing_pad,
+            ..self
+        }
+    }
+
+    /// Writes the section, symbol directives and entry label, followed by
+    /// the landing pad if there is one.
+    pub fn begin(&self, out: &mut (impl Write + ?Sized)) -> fmt::Result {
+        self.begin_symbol(out)?;
+        if self.landing_pad {
+            out.write_str("bti c\n")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the section, symbol directives and entry label.
+    fn begin_symbol(&self, out: &mut (impl Write + ?Sized)) -> fmt::Result {
+        let name = self.name;
+        if self.os == TargetOs::Apple {
+            out.write_str(".text\n")?;
+            match self.visibility {
+                Visibility::Local => {}
+                Visibility::Global => writeln!(out, ".globl _{name}")?,
+                _ => writeln!(out, ".globl _{name}\n.private_extern _{name}")?,
+            }
+            return writeln!(out, "_{name}:");
+        }
+        match self.temperature {
+            Temperature::Normal => out.write_str(".text\n")?,
+            t => writeln!(out, ".section {},\"ax\",%progbits", t.section())?,
+        }
+        match self.visibility {
+            Visibility::Local => {}
+            Visibility::Global => writeln!(out, ".globl {name}")?,
+            Visibility::Hidden => writeln!(out, ".globl {name}\n.hidden {name}")?,
+            Visibility::Protected => writeln!(out, ".globl {name}\n.protected {name}")?,
+        }
+        writeln!(out, ".type {name}, {FUNCTION_TYPE}\n{name}:")
+    }
+
+    /// Writes the `.size` directive closing the function.
+    pub fn end(&self, out: &mut (impl Write + ?Sized)) -> fmt::Result {
+        if self.os == TargetOs::Apple {
+            return Ok(());
+        }
+        writeln!(out, ".size {name}, .-{name}", name = self.name)
+    }
+}
+
+/// Emits `body` as the function `func`.
+///
+/// `body` receives the same sink, so with `W = dyn Write` it can use the
+/// text writers directly.
+pub fn emit_function<W, E>(
+    out: &mut W,
+    func: &Function<'_>,
+    body: impl FnOnce(&mut W) -> Result<(), E>,
+) -> Result<(), E>
+where
+    W: Write + ?Sized,
+    E: From<fmt::Error>,
+{
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("function", name = func.name).entered();
+    func.begin(out)?;
+    body(out)?;
+    func.end(out)?;
+    Ok(())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::AArch64Arch;
+    use crate::out::WriterCore;
+    use alloc::string::String;
+
+    #[test]
+    fn test_cold_global_function() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let func = Function::new("handler")
+            .with_visibility(Visibility::Hidden)
+            .with_temperature(Temperature::Cold);
+        emit_function(out, &func, |w| w.ret(&mut (), AArch64Arch::default())).unwrap();
+        assert_eq!(
+            output,
+            ".section .text.unlikely,\"ax\",%progbits\n.globl handler\n.hidden handler\n\
+             .type handler, %function\nhandler:\nret\n.size handler, .-handler\n"
+        );
+    }
+
+    #[test]
+    fn test_landing_pad_follows_entry_label() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let func = Function::new("handler").with_landing_pad(true);
+        emit_function(out, &func, |w| w.ret(&mut (), AArch64Arch::default())).unwrap();
+        assert!(output.contains("handler:\nbti c\nret\n"));
+    }
+
+    #[test]
+    fn test_apple_function() {
+        use crate::out::arg::ArgKind;
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let cfg = AArch64Arch {
+            pic: true,
+            ..AArch64Arch::from_triple("arm64-apple-macosx14.0").unwrap()
+        };
+        assert_eq!(cfg.os, TargetOs::Apple);
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let func = Function::new("handler")
+            .with_visibility(Visibility::Hidden)
+            .with_os(cfg.os);
+        emit_function(out, &func, |w| {
+            w.mov(&mut (), cfg, &Reg(0), &ArgKind::sym("counter"))?;
+            w.ret(&mut (), cfg)
+        })
+        .unwrap();
+        assert_eq!(
+            output,
+            ".text\n.globl _handler\n.private_extern _handler\n_handler:\n\
+             adrp x0, _counter@PAGE\nadd x0, x0, _counter@PAGEOFF\nret\n"
+        );
+    }
+}
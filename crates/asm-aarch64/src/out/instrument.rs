@@ -0,0 +1,329 @@
+//! Profiling probes at function entries, basic blocks and call sites.
+//!
+//! [`InstrumentingWriter`] wraps a writer and injects a [`Probe`] at three
+//! kinds of event, each configured separately:
+//!
+//! - function entries, defined with [`InstrumentingWriter::function_entry`];
+//! - every other label passed to [`set_label`](Writer::set_label), which
+//!   starts a basic block;
+//! - calls made with [`bl_label`](Writer::bl_label).
+//!
+//! Events are numbered in emission order, shared by all three kinds. A
+//! [`Probe::Count`] probe increments the event's own 64-bit slot in a
+//! counter table, so wrapping the target writer of a translation shim gives
+//! block-level execution counts without touching the emitter:
+//!
+//! ```ignore
+//! let writer = InstrumentingWriter::new(writer).with_blocks(Probe::Count { base: Reg(28), disp: 0 });
+//! let mut shim = X64ToAArch64Shim::new(writer);
+//! // ... translate ...
+//! let slots = shim.inner.event_count(); // the table needs this many u64s
+//! ```
+//!
+//! Probes clobber x16 and preserve NZCV. Calls through
+//! [`bl`](WriterCore::bl) or [`blr`](WriterCore::blr) are not call-site
+//! events. A probe lands after the label, so a label that must start with a
+//! `bti` landing pad should not be instrumented.
+
+use core::ops::{Deref, DerefMut};
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::arg::{AddressingMode, ArgKind, MemArgKind};
+use crate::out::{Writer, WriterCore};
+use crate::{AArch64Arch, RegisterClass};
+
+/// Register probes load counters into and pass event numbers in: `x16`.
+const SCRATCH: Reg = Reg(16);
+
+/// The link register, saved around [`Probe::Call`].
+const LR: Reg = Reg(30);
+
+/// The stack pointer.
+const SP: Reg = Reg(31);
+
+/// Code injected at an instrumented event.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Probe {
+    /// Nothing is injected.
+    #[default]
+    Off,
+    /// Increments the 64-bit counter of event `n` at `[base, #disp + 8 * n]`.
+    ///
+    /// `base` must hold the table address wherever a probe runs, and
+    /// `disp + 8 * n` must be a multiple of 8 below 32768.
+    Count {
+        /// Register holding the counter table address.
+        base: Reg,
+        /// Byte offset of event 0's counter from `base`.
+        disp: i32,
+    },
+    /// Calls the routine `symbol` with the event number in x16.
+    ///
+    /// The routine must preserve every register except x16, and the flags.
+    /// The probe saves LR itself.
+    Call(&'static str),
+}
+
+/// Injects [`Probe`]s at function entries, block labels and call sites.
+pub struct InstrumentingWriter<W> {
+    writer: W,
+    function_entries: Probe,
+    blocks: Probe,
+    call_sites: Probe,
+    events: usize,
+}
+
+impl<W> InstrumentingWriter<W> {
+    /// Wraps `writer` with every probe [`Off`](Probe::Off).
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            function_entries: Probe::Off,
+            blocks: Probe::Off,
+            call_sites: Probe::Off,
+            events: 0,
+        }
+    }
+
+    /// Sets the probe run at function entries.
+    pub fn with_function_entries(self, probe: Probe) -> Self {
+        Self {
+            function_entries: probe,
+            ..self
+        }
+    }
+
+    /// Sets the probe run at basic block labels.
+    pub fn with_blocks(self, probe: Probe) -> Self {
+        Self {
+            blocks: probe,
+            ..self
+        }
+    }
+
+    /// Sets the probe run before call sites.
+    pub fn with_call_sites(self, probe: Probe) -> Self {
+        Self {
+            call_sites: probe,
+            ..self
+        }
+    }
+
+    /// Returns how many events have been numbered, which is the number of
+    /// slots a counter table needs.
+    pub fn event_count(&self) -> usize {
+        self.events
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Emits `probe` for the next event, if it is not off.
+    fn probe<Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        probe: Probe,
+    ) -> Result<(), W::Error>
+    where
+        W: WriterCore<Context>,
+    {
+        let event = self.events;
+        match probe {
+            Probe::Off => return Ok(()),
+            Probe::Count { base, disp } => {
+                let offset = disp + 8 * event as i32;
+                assert!(
+                    offset % 8 == 0 && (0..32768).contains(&offset),
+                    "counter offset {offset} is out of ldr/str range"
+                );
+                let slot = MemArgKind::Mem {
+                    base: ArgKind::Reg {
+                        reg: base,
+                        size: MemorySize::_64,
+                    },
+                    offset: None,
+                    disp: offset,
+                    size: MemorySize::_64,
+                    reg_class: RegisterClass::Gpr,
+                    mode: AddressingMode::Offset,
+                };
+                self.writer.ldr(ctx, cfg, &SCRATCH, &slot)?;
+                self.writer.add_imm(ctx, cfg, &SCRATCH, &SCRATCH, 1)?;
+                self.writer.str(ctx, cfg, &SCRATCH, &slot)?;
+            }
+            Probe::Call(symbol) => {
+                let stack = |disp, mode| MemArgKind::Mem {
+                    base: ArgKind::Reg {
+                        reg: SP,
+                        size: MemorySize::_64,
+                    },
+                    offset: None,
+                    disp,
+                    size: MemorySize::_64,
+                    reg_class: RegisterClass::Gpr,
+                    mode,
+                };
+                self.writer
+                    .str(ctx, cfg, &LR, &stack(-16, AddressingMode::PreIndex))?;
+                self.writer.mov_imm(ctx, cfg, &SCRATCH, event as u64)?;
+                self.writer.bl(ctx, cfg, &ArgKind::sym(symbol))?;
+                self.writer
+                    .ldr(ctx, cfg, &LR, &stack(16, AddressingMode::PostIndex))?;
+            }
+        }
+        self.events += 1;
+        Ok(())
+    }
+
+    /// Defines `label` as a function entry and runs the function-entry
+    /// probe.
+    pub fn function_entry<L, Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context>,
+    {
+        self.writer.set_label(ctx, cfg, label)?;
+        self.probe(ctx, cfg, self.function_entries)
+    }
+}
+
+impl<W> Deref for InstrumentingWriter<W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        &self.writer
+    }
+}
+
+impl<W> DerefMut for InstrumentingWriter<W> {
+    fn deref_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+crate::writer_dispatch!(@core [ W: WriterCore<Context>, Context ] InstrumentingWriter<W> => W::Error [Context] [W]);
+
+impl<W, L, Context> Writer<L, Context> for InstrumentingWriter<W>
+where
+    W: Writer<L, Context>,
+{
+    fn set_label(&mut self, ctx: &mut Context, cfg: AArch64Arch, s: L) -> Result<(), Self::Error> {
+        self.writer.set_label(ctx, cfg, s)?;
+        self.probe(ctx, cfg, self.blocks)
+    }
+
+    fn adr_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn crate::out::arg::MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.adr_label(ctx, cfg, dest, label)
+    }
+
+    fn b_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.b_label(ctx, cfg, label)
+    }
+
+    fn bcond_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.bcond_label(ctx, cfg, cond, label)
+    }
+
+    fn bcond_label_hinted(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        label: L,
+        hint: crate::BranchHint,
+    ) -> Result<(), Self::Error> {
+        self.writer.bcond_label_hinted(ctx, cfg, cond, label, hint)
+    }
+
+    fn bl_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.probe(ctx, cfg, self.call_sites)?;
+        self.writer.bl_label(ctx, cfg, label)
+    }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.dd_label(ctx, cfg, label)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_events_are_numbered_across_kinds() {
+        let cfg = AArch64Arch::default();
+        let mut output = String::new();
+        let count = Probe::Count {
+            base: Reg(28),
+            disp: 0,
+        };
+        let mut writer = InstrumentingWriter::new(&mut output as &mut dyn Write)
+            .with_function_entries(count)
+            .with_call_sites(count);
+        writer.function_entry(&mut (), cfg, "main").unwrap();
+        // Blocks are not instrumented, so the label takes no event number.
+        writer.set_label(&mut (), cfg, "loop").unwrap();
+        writer.bl_label(&mut (), cfg, "f").unwrap();
+        assert_eq!(writer.event_count(), 2);
+        drop(writer);
+        assert_eq!(
+            output,
+            "main:\nldr x16, [x28]\nadd x16, x16, #1\nstr x16, [x28]\nloop:\n\
+             ldr x16, [x28, #8]\nadd x16, x16, #1\nstr x16, [x28, #8]\nbl f\n"
+        );
+    }
+
+    #[test]
+    fn test_call_probe_passes_event_number() {
+        let cfg = AArch64Arch::default();
+        let mut output = String::new();
+        let mut writer = InstrumentingWriter::new(&mut output as &mut dyn Write)
+            .with_function_entries(Probe::Call("__profile_hook"));
+        writer.function_entry(&mut (), cfg, "main").unwrap();
+        writer.set_label(&mut (), cfg, "loop").unwrap();
+        drop(writer);
+        assert_eq!(
+            output,
+            "main:\nstr x30, [sp, #-16]!\nmovz x16, #0, lsl #0\nbl __profile_hook\n\
+             ldr x30, [sp], #16\nloop:\n"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Jump tables for dense `switch` statements.
+//!
+//! A `switch` over a dense range of values is cheapest as an indexed jump:
+//! bounds-check the index once, load the target from a table and branch
+//! there. [`JumpTable`] emits both halves. Entries are 32-bit offsets from
+//! the entry to its case label (see
+//! [`dd_label`](crate::out::Writer::dd_label)), so the table needs no
+//! relocations and works in position-independent code:
+//!
+//! ```ignore
+//! let cases = [case0, case1, case2];
+//! let table = JumpTable::new(&cases, default);
+//! table.dispatch(&mut writer, ctx, cfg, &Reg(0), table_label)?;
+//! // cmp x0, #3; b.hs default
+//! // adr x16, table_label; lsl x17, x0, #2; add x16, x16, x17
+//! // ldr w17, [x16]; sxtw x17, w17; add x16, x16, x17; br x16
+//! // ... case bodies ...
+//! table.emit_table(&mut writer, ctx, cfg, table_label)?;
+//! ```
+//!
+//! The index is an unsigned 64-bit value in a register; values at or above
+//! the number of cases go to the default label. A case count that is not a
+//! `cmp` immediate is loaded into the second scratch register first. The
+//! table must be within `adr` range (±1 MiB) of the dispatch.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::Writer;
+use crate::out::arg::{AddressingMode, ArgKind, MemArgKind};
+use crate::{AArch64Arch, ConditionCode, RegisterClass};
+
+/// Default scratch registers: the intra-procedure-call registers `x16` and
+/// `x17`.
+pub const DEFAULT_SCRATCH: [Reg; 2] = [Reg(16), Reg(17)];
+
+/// A jump table over the labels of consecutive cases.
+pub struct JumpTable<'a, L> {
+    cases: &'a [L],
+    default: L,
+    scratch: [Reg; 2],
+}
+
+impl<'a, L: Clone> JumpTable<'a, L> {
+    /// Creates a table that branches to `cases[index]`, or to `default`
+    /// when the index is out of range.
+    pub fn new(cases: &'a [L], default: L) -> Self {
+        Self {
+            cases,
+            default,
+            scratch: DEFAULT_SCRATCH,
+        }
+    }
+
+    /// Sets the two registers the dispatch sequence clobbers.
+    pub fn with_scratch(self, scratch: [Reg; 2]) -> Self {
+        Self { scratch, ..self }
+    }
+
+    /// Returns the number of cases.
+    pub fn len(&self) -> usize {
+        self.cases.len()
+    }
+
+    /// Returns `true` if the table has no cases.
+    pub fn is_empty(&self) -> bool {
+        self.cases.is_empty()
+    }
+
+    /// Emits the bounds check and the indexed branch through the table at
+    /// `table`.
+    ///
+    /// `index` must not be one of the scratch registers.
+    pub fn dispatch<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        index: &Reg,
+        table: L,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let [base, entry] = self.scratch;
+        let entry_w = ArgKind::Reg {
+            reg: entry,
+            size: MemorySize::_32,
+        };
+        let len = self.cases.len() as u64;
+        if crate::imm::arith_imm(len).is_some() {
+            w.cmp_imm(ctx, cfg, index, len as i64)?;
+        } else {
+            w.mov_imm(ctx, cfg, &entry, len)?;
+            w.cmp(ctx, cfg, index, &entry)?;
+        }
+        w.bcond_label(ctx, cfg, ConditionCode::HS, self.default.clone())?;
+        w.adr_label(ctx, cfg, &base, table)?;
+        w.lsl(ctx, cfg, &entry, index, &ArgKind::Lit(2))?;
+        w.add(ctx, cfg, &base, &base, &entry)?;
+        w.ldr(ctx, cfg, &entry_w, &table_entry(base))?;
+        w.sxt(ctx, cfg, &entry, &entry_w)?;
+        w.add(ctx, cfg, &base, &base, &entry)?;
+        w.br(ctx, cfg, &base)
+    }
+
+    /// Emits the table, 4-byte aligned, labelled `table`.
+    ///
+    /// The table is data: place it where execution cannot fall into it.
+    pub fn emit_table<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        table: L,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        w.align_to(ctx, cfg, 4)?;
+        w.set_label(ctx, cfg, table)?;
+        for case in self.cases {
+            w.dd_label(ctx, cfg, case.clone())?;
+        }
+        Ok(())
+    }
+}
+
+/// The 32-bit entry at `[addr]`.
+fn table_entry(addr: Reg) -> MemArgKind<ArgKind> {
+    MemArgKind::Mem {
+        base: ArgKind::Reg {
+            reg: addr,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp: 0,
+        size: MemorySize::_32,
+        reg_class: RegisterClass::Gpr,
+        mode: AddressingMode::Offset,
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_dispatch_and_table() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default();
+        let cases = [".Lc0", ".Lc1"];
+        let table = JumpTable::new(&cases, ".Ldef");
+        table
+            .dispatch(&mut writer, &mut (), cfg, &Reg(0), ".Ltab")
+            .unwrap();
+        table
+            .emit_table(&mut writer, &mut (), cfg, ".Ltab")
+            .unwrap();
+
+        assert!(output.starts_with(
+            "cmp x0, #2\nb.hs .Ldef\nadr x16, .Ltab\nlsl x17, x0, #2\nadd x16, x16, x17\n"
+        ));
+        assert!(output.contains("ldr w17, [x16]\nsxtw x17, w17\nadd x16, x16, x17\nbr x16\n"));
+        assert!(output.ends_with(".Ltab:\n.4byte .Lc0 - .\n.4byte .Lc1 - .\n"));
+    }
+
+    #[test]
+    fn test_case_count_beyond_cmp_immediates() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cases = [".Lc"; 4097];
+        JumpTable::new(&cases, ".Ldef")
+            .dispatch(
+                &mut writer,
+                &mut (),
+                AArch64Arch::default(),
+                &Reg(0),
+                ".Ltab",
+            )
+            .unwrap();
+        assert!(output.starts_with("movz x17, #4097, lsl #0\ncmp x0, x17\nb.hs .Ldef\n"));
+    }
+}
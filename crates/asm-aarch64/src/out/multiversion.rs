@@ -0,0 +1,156 @@
+//! Multi-versioned function emission.
+//!
+//! Fat binaries often carry several builds of a hot function, each tuned for
+//! a different set of extensions, plus a small dispatcher that jumps to the
+//! one selected at load time. [`emit_multiversioned`] replays the same body
+//! once per [`Version`] under a suffixed label and emits that dispatcher:
+//!
+//! ```ignore
+//! let versions = [
+//!     Version { cfg: tuned_cfg, suffix: "tuned" },
+//!     Version { cfg: AArch64Arch::default(), suffix: "base" },
+//! ];
+//! let dispatch = Dispatcher { cfg: AArch64Arch::default(), scratch: &Reg(16), selector: &slot };
+//! emit_multiversioned(
+//!     &mut writer, ctx, dispatch, &versions,
+//!     |suffix| match suffix { Some(s) => format!("memcpy.{s}"), None => "memcpy".into() },
+//!     |w, ctx, cfg| emit_memcpy(w, ctx, cfg),
+//! )?;
+//! ```
+
+use crate::out::Writer;
+use crate::out::arg::MemArg;
+use crate::{AArch64Arch, ConditionCode};
+
+/// One build of a multi-versioned function.
+#[derive(Clone, Copy, Debug)]
+pub struct Version<'a> {
+    /// Configuration the body is emitted for.
+    pub cfg: AArch64Arch,
+    /// Suffix distinguishing this build's symbol, e.g. `"lse"`.
+    pub suffix: &'a str,
+}
+
+/// How the dispatcher selects a version at run time.
+///
+/// The dispatcher loads the index of the selected version from `selector` (a
+/// slot filled by a loader or CPU-feature probe) into `scratch` and branches
+/// to that version; an out-of-range index selects the last one.
+#[derive(Clone, Copy)]
+pub struct Dispatcher<'a> {
+    /// Configuration the dispatcher is emitted for; it must run on every target.
+    pub cfg: AArch64Arch,
+    /// Register clobbered by the dispatcher, along with the flags.
+    pub scratch: &'a (dyn MemArg + 'a),
+    /// Memory operand holding the selected version's index.
+    pub selector: &'a (dyn MemArg + 'a),
+}
+
+/// Emits a dispatcher followed by `body` once per version.
+///
+/// `label(None)` names the dispatcher and `label(Some(suffix))` each version;
+/// the closure must return the same label for the same argument.
+///
+/// # Panics
+///
+/// Panics if `versions` is empty.
+pub fn emit_multiversioned<W, L, Context>(
+    w: &mut W,
+    ctx: &mut Context,
+    dispatch: Dispatcher<'_>,
+    versions: &[Version<'_>],
+    mut label: impl FnMut(Option<&str>) -> L,
+    mut body: impl FnMut(&mut W, &mut Context, AArch64Arch) -> Result<(), W::Error>,
+) -> Result<(), W::Error>
+where
+    W: Writer<L, Context> + ?Sized,
+{
+    let (last, rest) = versions
+        .split_last()
+        .expect("multi-versioned function needs at least one version");
+
+    let Dispatcher {
+        cfg: base,
+        scratch,
+        selector,
+    } = dispatch;
+    w.set_label(ctx, base, label(None))?;
+    w.ldr(ctx, base, scratch, selector)?;
+    for (i, version) in rest.iter().enumerate() {
+        w.cmp(ctx, base, scratch, &(i as u64))?;
+        w.bcond_label(ctx, base, ConditionCode::EQ, label(Some(version.suffix)))?;
+    }
+    w.b_label(ctx, base, label(Some(last.suffix)))?;
+
+    for version in versions {
+        w.set_label(ctx, version.cfg, label(Some(version.suffix)))?;
+        body(w, ctx, version.cfg)?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::RegisterClass;
+    use crate::out::WriterCore;
+    use crate::out::arg::{AddressingMode, ArgKind, MemArgKind};
+    use alloc::format;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+    #[test]
+    fn test_versions_and_dispatcher() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let base = AArch64Arch::default();
+        let versions = [
+            Version {
+                cfg: base,
+                suffix: "tuned",
+            },
+            Version {
+                cfg: base,
+                suffix: "base",
+            },
+        ];
+        let selector = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(0),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_64,
+            reg_class: RegisterClass::Gpr,
+            mode: AddressingMode::Offset,
+        };
+        let mut bodies = 0;
+        let dispatch = Dispatcher {
+            cfg: base,
+            scratch: &Reg(16),
+            selector: &selector,
+        };
+        emit_multiversioned(
+            &mut writer,
+            &mut (),
+            dispatch,
+            &versions,
+            |suffix| match suffix {
+                Some(s) => format!("f.{s}"),
+                None => String::from("f"),
+            },
+            |w, ctx, cfg| {
+                bodies += 1;
+                w.ret(ctx, cfg)
+            },
+        )
+        .unwrap();
+        assert_eq!(bodies, 2);
+        assert!(output.starts_with("f:\n"));
+        assert!(output.contains("f.tuned:\n"));
+        assert!(output.contains("f.base:\n"));
+        assert_eq!(output.matches("ret").count(), 2);
+    }
+}
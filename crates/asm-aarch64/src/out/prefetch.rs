@@ -0,0 +1,132 @@
+//! Software prefetches.
+//!
+//! [`Prefetch::prefetch`] asks for the cache line holding an address to be
+//! brought in before it is used, picking the `prfm` operation from how much
+//! the data will be reused and whether it will be written:
+//!
+//! ```ignore
+//! writer.prefetch(ctx, cfg, &at_x0, Locality::High, PrefetchAccess::Read)?;
+//! // prfm pldl1keep, [x0]
+//! writer.prefetch(ctx, cfg, &at_x0, Locality::NonTemporal, PrefetchAccess::Write)?;
+//! // prfm pstl1strm, [x0]
+//! ```
+//!
+//! The locality levels are those of GCC's `__builtin_prefetch`. The x86-64
+//! and RISC-V crates provide the same helper with `prefetcht0`-`prefetchnta`
+//! and the Zicbop `prefetch.r` and `prefetch.w`.
+
+use crate::out::WriterCore;
+use crate::out::arg::MemArg;
+use crate::{AArch64Arch, PrfKind, PrfOp, PrfPolicy};
+
+/// How much prefetched data will be reused, from least to most.
+///
+/// The levels match the `locality` argument of GCC's `__builtin_prefetch`,
+/// 0 to 3.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Locality {
+    /// Used once, so it should not displace other data from the caches.
+    NonTemporal,
+    /// Reused a little; only the outermost cache needs to keep it.
+    Low,
+    /// Reused moderately; the second-level cache and above keep it.
+    Moderate,
+    /// Reused heavily; every cache level keeps it.
+    #[default]
+    High,
+}
+
+/// Whether prefetched data will be read or written.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub enum PrefetchAccess {
+    /// The data will be loaded.
+    #[default]
+    Read,
+    /// The data will be stored to, so the line is best fetched for ownership.
+    Write,
+}
+
+impl PrfOp {
+    /// Returns the data prefetch for `access` with reuse `locality`:
+    /// `keep` into level 1, 2 or 3 from high to low locality, and `strm`
+    /// into level 1 for non-temporal data.
+    pub fn for_locality(locality: Locality, access: PrefetchAccess) -> Self {
+        let kind = match access {
+            PrefetchAccess::Read => PrfKind::PLD,
+            PrefetchAccess::Write => PrfKind::PST,
+        };
+        match locality {
+            Locality::NonTemporal => Self::new(kind, 1, PrfPolicy::STRM),
+            Locality::Low => Self::new(kind, 3, PrfPolicy::KEEP),
+            Locality::Moderate => Self::new(kind, 2, PrfPolicy::KEEP),
+            Locality::High => Self::new(kind, 1, PrfPolicy::KEEP),
+        }
+    }
+}
+
+/// Emits software prefetches by locality.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Prefetch<Context>: WriterCore<Context> {
+    /// Hints that the cache line holding `mem` will soon be accessed as
+    /// `access`, with reuse `locality`.
+    ///
+    /// Prefetches never fault, so `mem` may be any address.
+    fn prefetch(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        mem: &(dyn MemArg + '_),
+        locality: Locality,
+        access: PrefetchAccess,
+    ) -> Result<(), Self::Error> {
+        self.prfm(ctx, cfg, PrfOp::for_locality(locality, access), mem)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Prefetch<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::RegisterClass;
+    use crate::out::arg::{AddressingMode, ArgKind, MemArgKind};
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+    #[test]
+    fn test_locality_picks_the_operation() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default();
+        let mem = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(1),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 64,
+            size: MemorySize::_64,
+            reg_class: RegisterClass::Gpr,
+            mode: AddressingMode::Offset,
+        };
+        w.prefetch(&mut (), cfg, &mem, Locality::High, PrefetchAccess::Read)
+            .unwrap();
+        w.prefetch(&mut (), cfg, &mem, Locality::Low, PrefetchAccess::Read)
+            .unwrap();
+        w.prefetch(
+            &mut (),
+            cfg,
+            &mem,
+            Locality::NonTemporal,
+            PrefetchAccess::Write,
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            "prfm pldl1keep, [x1, #64]\nprfm pldl3keep, [x1, #64]\nprfm pstl1strm, [x1, #64]\n"
+        );
+    }
+}
@@ -0,0 +1,2489 @@
+//! Recorded instruction streams for replay.
+//!
+//! [`RecordingWriter`] emits nothing: it captures every call made to it as
+//! an owned [`Insn`], with operands converted to [`MemArgKind`]s. The list
+//! can then be replayed into any other writer, as many times as needed and
+//! after editing or reordering it, which suits multi-pass layout (a sizing
+//! pass, then emission) and caching generated sequences:
+//!
+//! ```ignore
+//! let mut rec = RecordingWriter::new();
+//! generate(&mut rec, &mut (), cfg)?;
+//! rec.replay(&mut sizer, &mut ctx, cfg)?;
+//! rec.replay(&mut binary, &mut ctx, cfg)?;
+//! ```
+//!
+//! The configuration and context passed while recording are dropped, and
+//! each replay supplies its own. Labels are cloned on every replay.
+//! Recording never fails, and the writer reports no offset.
+
+use alloc::vec::Vec;
+
+use portal_pc_asm_common::types::mem::MemorySize;
+
+use crate::out::arg::{MemArg, MemArgKind};
+use crate::out::error::EmitError;
+use crate::out::{Writer, WriterCore};
+use crate::{
+    AArch64Arch, Barrier, BranchHint, BtiTarget, ConditionCode, DcOp, PredicateReg, PrfOp, SysReg,
+};
+
+/// A recorded writer call.
+///
+/// Each variant names the [`WriterCore`] or [`Writer`] method it replays
+/// and holds that method's arguments after the configuration.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Insn<L> {
+    /// [`WriterCore::brk`].
+    Brk { imm: u16 },
+    /// [`WriterCore::unreachable`].
+    Unreachable,
+    /// [`WriterCore::debug_break`].
+    DebugBreak,
+    /// [`WriterCore::svc`].
+    Svc { imm: u16 },
+    /// [`WriterCore::bti`].
+    Bti { target: BtiTarget },
+    /// [`WriterCore::paciasp`].
+    Paciasp,
+    /// [`WriterCore::autiasp`].
+    Autiasp,
+    /// [`WriterCore::mov`].
+    Mov { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::sub`].
+    Sub {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::add`].
+    Add {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::adds`].
+    Adds {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::subs`].
+    Subs {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::adcs`].
+    Adcs {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::sbcs`].
+    Sbcs {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::add_imm`].
+    AddImm {
+        dest: MemArgKind,
+        a: MemArgKind,
+        imm: i64,
+    },
+    /// [`WriterCore::sub_imm`].
+    SubImm {
+        dest: MemArgKind,
+        a: MemArgKind,
+        imm: i64,
+    },
+    /// [`WriterCore::add_uxtw`].
+    AddUxtw {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::add_lsl`].
+    AddLsl {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        shift: u8,
+    },
+    /// [`WriterCore::sxt`].
+    Sxt { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::uxt`].
+    Uxt { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::str`].
+    Str { src: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::ldr`].
+    Ldr { dest: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::stp`].
+    Stp {
+        src1: MemArgKind,
+        src2: MemArgKind,
+        mem: MemArgKind,
+    },
+    /// [`WriterCore::ldp`].
+    Ldp {
+        dest1: MemArgKind,
+        dest2: MemArgKind,
+        mem: MemArgKind,
+    },
+    /// [`WriterCore::bl`].
+    Bl { target: MemArgKind },
+    /// [`WriterCore::br`].
+    Br { target: MemArgKind },
+    /// [`WriterCore::b`].
+    B { target: MemArgKind },
+    /// [`WriterCore::cmp`].
+    Cmp { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::cmp_imm`].
+    CmpImm { a: MemArgKind, imm: i64 },
+    /// [`WriterCore::tst`].
+    Tst { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::csel`].
+    Csel {
+        cond: ConditionCode,
+        dest: MemArgKind,
+        true_val: MemArgKind,
+        false_val: MemArgKind,
+    },
+    /// [`WriterCore::cset`].
+    Cset {
+        cond: ConditionCode,
+        dest: MemArgKind,
+    },
+    /// [`WriterCore::bcond`].
+    Bcond {
+        cond: ConditionCode,
+        target: MemArgKind,
+    },
+    /// [`WriterCore::and`].
+    And {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::orr`].
+    Orr {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::eor`].
+    Eor {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::and_imm`].
+    AndImm {
+        dest: MemArgKind,
+        a: MemArgKind,
+        imm: u64,
+    },
+    /// [`WriterCore::orr_imm`].
+    OrrImm {
+        dest: MemArgKind,
+        a: MemArgKind,
+        imm: u64,
+    },
+    /// [`WriterCore::eor_imm`].
+    EorImm {
+        dest: MemArgKind,
+        a: MemArgKind,
+        imm: u64,
+    },
+    /// [`WriterCore::lsl`].
+    Lsl {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::lsr`].
+    Lsr {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::asr`].
+    Asr {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::ror`].
+    Ror {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::cls`].
+    Cls { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::clz`].
+    Clz { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::rbit`].
+    Rbit { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::rev`].
+    Rev { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::ubfx`].
+    Ubfx {
+        dest: MemArgKind,
+        src: MemArgKind,
+        lsb: u8,
+        width: u8,
+    },
+    /// [`WriterCore::bfi`].
+    Bfi {
+        dest: MemArgKind,
+        src: MemArgKind,
+        lsb: u8,
+        width: u8,
+    },
+    /// [`WriterCore::mvn`].
+    Mvn { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::neg`].
+    Neg { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::negs`].
+    Negs { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::adr`].
+    Adr { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::ret`].
+    Ret,
+    /// [`WriterCore::mrs`].
+    Mrs { dest: MemArgKind, reg: SysReg },
+    /// [`WriterCore::msr`].
+    Msr { reg: SysReg, src: MemArgKind },
+    /// [`WriterCore::mrs_nzcv`].
+    MrsNzcv { dest: MemArgKind },
+    /// [`WriterCore::msr_nzcv`].
+    MsrNzcv { src: MemArgKind },
+    /// [`WriterCore::mrs_fpcr`].
+    MrsFpcr { dest: MemArgKind },
+    /// [`WriterCore::msr_fpcr`].
+    MsrFpcr { src: MemArgKind },
+    /// [`WriterCore::mrs_tpidr_el0`].
+    MrsTpidrEl0 { dest: MemArgKind },
+    /// [`WriterCore::dmb`].
+    Dmb { barrier: Barrier },
+    /// [`WriterCore::dsb`].
+    Dsb { barrier: Barrier },
+    /// [`WriterCore::isb`].
+    Isb,
+    /// [`WriterCore::hint`].
+    Hint { imm: u8 },
+    /// [`WriterCore::yield_`].
+    Yield,
+    /// [`WriterCore::wfe`].
+    Wfe,
+    /// [`WriterCore::sev`].
+    Sev,
+    /// [`WriterCore::prfm`].
+    Prfm { op: PrfOp, mem: MemArgKind },
+    /// [`WriterCore::dc`].
+    Dc { op: DcOp, addr: MemArgKind },
+    /// [`WriterCore::ic_ivau`].
+    IcIvau { addr: MemArgKind },
+    /// [`WriterCore::ldar`].
+    Ldar { dest: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::stlr`].
+    Stlr { src: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::swpal`].
+    Swpal {
+        src: MemArgKind,
+        dest: MemArgKind,
+        mem: MemArgKind,
+    },
+    /// [`WriterCore::adrp`].
+    Adrp { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::add_lo12`].
+    AddLo12 {
+        dest: MemArgKind,
+        src: MemArgKind,
+        sym: MemArgKind,
+    },
+    /// [`WriterCore::mov_imm`].
+    MovImm { dest: MemArgKind, val: u64 },
+    /// [`WriterCore::mul`].
+    Mul {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::umulh`].
+    Umulh {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::smulh`].
+    Smulh {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::udiv`].
+    Udiv {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::sdiv`].
+    Sdiv {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fadd`].
+    Fadd {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fsub`].
+    Fsub {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fmul`].
+    Fmul {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fdiv`].
+    Fdiv {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fmadd`].
+    Fmadd {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fmsub`].
+    Fmsub {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fnmadd`].
+    Fnmadd {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fnmsub`].
+    Fnmsub {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fcmp`].
+    Fcmp { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::fmin`].
+    Fmin {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fmax`].
+    Fmax {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fsqrt`].
+    Fsqrt { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fabs`].
+    Fabs { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fneg`].
+    Fneg { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcsel`].
+    Fcsel {
+        cond: ConditionCode,
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::scvtf`].
+    Scvtf { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::ucvtf`].
+    Ucvtf { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvtzs`].
+    Fcvtzs { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvtzu`].
+    Fcvtzu { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt`].
+    Fcvt { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fmov`].
+    Fmov { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::whilelt`].
+    Whilelt {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::ptrue`].
+    Ptrue { dest: MemArgKind },
+    /// [`WriterCore::sve_inc`].
+    SveInc { dest: MemArgKind, elem: MemorySize },
+    /// [`WriterCore::sve_ld1`].
+    SveLd1 {
+        dest: MemArgKind,
+        pg: PredicateReg,
+        mem: MemArgKind,
+    },
+    /// [`WriterCore::sve_st1`].
+    SveSt1 {
+        src: MemArgKind,
+        pg: PredicateReg,
+        mem: MemArgKind,
+    },
+    /// [`WriterCore::sve_ld1d_gather`].
+    SveLd1dGather {
+        dest: MemArgKind,
+        pg: PredicateReg,
+        base: MemArgKind,
+        offsets: MemArgKind,
+        scaled: bool,
+    },
+    /// [`WriterCore::sve_st1d_scatter`].
+    SveSt1dScatter {
+        src: MemArgKind,
+        pg: PredicateReg,
+        base: MemArgKind,
+        offsets: MemArgKind,
+        scaled: bool,
+    },
+    /// [`WriterCore::sve_add`].
+    SveAdd {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::sve_sub`].
+    SveSub {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::sve_fadd`].
+    SveFadd {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::sve_fsub`].
+    SveFsub {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::sve_fmul`].
+    SveFmul {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::sve_fmla`].
+    SveFmla {
+        dest: MemArgKind,
+        pg: PredicateReg,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::sve_dup`].
+    SveDup { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::db`].
+    Db { bytes: Vec<u8> },
+    /// [`WriterCore::align_to`].
+    AlignTo { alignment: usize },
+    /// [`WriterCore::nop`].
+    Nop,
+    /// [`WriterCore::nops`].
+    Nops { n_bytes: usize },
+    /// [`WriterCore::align`].
+    Align { pow2: u32 },
+    /// [`Writer::set_label`].
+    SetLabel { label: L },
+    /// [`Writer::adr_label`].
+    AdrLabel { dest: MemArgKind, label: L },
+    /// [`Writer::b_label`].
+    BLabel { label: L },
+    /// [`Writer::bcond_label`].
+    BcondLabel { cond: ConditionCode, label: L },
+    /// [`Writer::bcond_label_hinted`].
+    BcondLabelHinted {
+        cond: ConditionCode,
+        label: L,
+        hint: BranchHint,
+    },
+    /// [`Writer::bl_label`].
+    BlLabel { label: L },
+    /// [`Writer::dd_label`].
+    DdLabel { label: L },
+}
+
+impl<L: Clone> Insn<L> {
+    /// Makes the recorded call on `w`.
+    pub fn replay<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        match self {
+            Insn::Brk { imm } => w.brk(ctx, cfg, *imm),
+            Insn::Unreachable => w.unreachable(ctx, cfg),
+            Insn::DebugBreak => w.debug_break(ctx, cfg),
+            Insn::Svc { imm } => w.svc(ctx, cfg, *imm),
+            Insn::Bti { target } => w.bti(ctx, cfg, *target),
+            Insn::Paciasp => w.paciasp(ctx, cfg),
+            Insn::Autiasp => w.autiasp(ctx, cfg),
+            Insn::Mov { dest, src } => w.mov(ctx, cfg, dest, src),
+            Insn::Sub { dest, a, b } => w.sub(ctx, cfg, dest, a, b),
+            Insn::Add { dest, a, b } => w.add(ctx, cfg, dest, a, b),
+            Insn::Adds { dest, a, b } => w.adds(ctx, cfg, dest, a, b),
+            Insn::Subs { dest, a, b } => w.subs(ctx, cfg, dest, a, b),
+            Insn::Adcs { dest, a, b } => w.adcs(ctx, cfg, dest, a, b),
+            Insn::Sbcs { dest, a, b } => w.sbcs(ctx, cfg, dest, a, b),
+            Insn::AddImm { dest, a, imm } => w.add_imm(ctx, cfg, dest, a, *imm),
+            Insn::SubImm { dest, a, imm } => w.sub_imm(ctx, cfg, dest, a, *imm),
+            Insn::AddUxtw { dest, a, b } => w.add_uxtw(ctx, cfg, dest, a, b),
+            Insn::AddLsl { dest, a, b, shift } => w.add_lsl(ctx, cfg, dest, a, b, *shift),
+            Insn::Sxt { dest, src } => w.sxt(ctx, cfg, dest, src),
+            Insn::Uxt { dest, src } => w.uxt(ctx, cfg, dest, src),
+            Insn::Str { src, mem } => w.str(ctx, cfg, src, mem),
+            Insn::Ldr { dest, mem } => w.ldr(ctx, cfg, dest, mem),
+            Insn::Stp { src1, src2, mem } => w.stp(ctx, cfg, src1, src2, mem),
+            Insn::Ldp { dest1, dest2, mem } => w.ldp(ctx, cfg, dest1, dest2, mem),
+            Insn::Bl { target } => w.bl(ctx, cfg, target),
+            Insn::Br { target } => w.br(ctx, cfg, target),
+            Insn::B { target } => w.b(ctx, cfg, target),
+            Insn::Cmp { a, b } => w.cmp(ctx, cfg, a, b),
+            Insn::CmpImm { a, imm } => w.cmp_imm(ctx, cfg, a, *imm),
+            Insn::Tst { a, b } => w.tst(ctx, cfg, a, b),
+            Insn::Csel {
+                cond,
+                dest,
+                true_val,
+                false_val,
+            } => w.csel(ctx, cfg, *cond, dest, true_val, false_val),
+            Insn::Cset { cond, dest } => w.cset(ctx, cfg, *cond, dest),
+            Insn::Bcond { cond, target } => w.bcond(ctx, cfg, *cond, target),
+            Insn::And { dest, a, b } => w.and(ctx, cfg, dest, a, b),
+            Insn::Orr { dest, a, b } => w.orr(ctx, cfg, dest, a, b),
+            Insn::Eor { dest, a, b } => w.eor(ctx, cfg, dest, a, b),
+            Insn::AndImm { dest, a, imm } => w.and_imm(ctx, cfg, dest, a, *imm),
+            Insn::OrrImm { dest, a, imm } => w.orr_imm(ctx, cfg, dest, a, *imm),
+            Insn::EorImm { dest, a, imm } => w.eor_imm(ctx, cfg, dest, a, *imm),
+            Insn::Lsl { dest, a, b } => w.lsl(ctx, cfg, dest, a, b),
+            Insn::Lsr { dest, a, b } => w.lsr(ctx, cfg, dest, a, b),
+            Insn::Asr { dest, a, b } => w.asr(ctx, cfg, dest, a, b),
+            Insn::Ror { dest, a, b } => w.ror(ctx, cfg, dest, a, b),
+            Insn::Cls { dest, src } => w.cls(ctx, cfg, dest, src),
+            Insn::Clz { dest, src } => w.clz(ctx, cfg, dest, src),
+            Insn::Rbit { dest, src } => w.rbit(ctx, cfg, dest, src),
+            Insn::Rev { dest, src } => w.rev(ctx, cfg, dest, src),
+            Insn::Ubfx {
+                dest,
+                src,
+                lsb,
+                width,
+            } => w.ubfx(ctx, cfg, dest, src, *lsb, *width),
+            Insn::Bfi {
+                dest,
+                src,
+                lsb,
+                width,
+            } => w.bfi(ctx, cfg, dest, src, *lsb, *width),
+            Insn::Mvn { dest, src } => w.mvn(ctx, cfg, dest, src),
+            Insn::Neg { dest, src } => w.neg(ctx, cfg, dest, src),
+            Insn::Negs { dest, src } => w.negs(ctx, cfg, dest, src),
+            Insn::Adr { dest, src } => w.adr(ctx, cfg, dest, src),
+            Insn::Ret => w.ret(ctx, cfg),
+            Insn::Mrs { dest, reg } => w.mrs(ctx, cfg, dest, *reg),
+            Insn::Msr { reg, src } => w.msr(ctx, cfg, *reg, src),
+            Insn::MrsNzcv { dest } => w.mrs_nzcv(ctx, cfg, dest),
+            Insn::MsrNzcv { src } => w.msr_nzcv(ctx, cfg, src),
+            Insn::MrsFpcr { dest } => w.mrs_fpcr(ctx, cfg, dest),
+            Insn::MsrFpcr { src } => w.msr_fpcr(ctx, cfg, src),
+            Insn::MrsTpidrEl0 { dest } => w.mrs_tpidr_el0(ctx, cfg, dest),
+            Insn::Dmb { barrier } => w.dmb(ctx, cfg, *barrier),
+            Insn::Dsb { barrier } => w.dsb(ctx, cfg, *barrier),
+            Insn::Isb => w.isb(ctx, cfg),
+            Insn::Hint { imm } => w.hint(ctx, cfg, *imm),
+            Insn::Yield => w.yield_(ctx, cfg),
+            Insn::Wfe => w.wfe(ctx, cfg),
+            Insn::Sev => w.sev(ctx, cfg),
+            Insn::Prfm { op, mem } => w.prfm(ctx, cfg, *op, mem),
+            Insn::Dc { op, addr } => w.dc(ctx, cfg, *op, addr),
+            Insn::IcIvau { addr } => w.ic_ivau(ctx, cfg, addr),
+            Insn::Ldar { dest, mem } => w.ldar(ctx, cfg, dest, mem),
+            Insn::Stlr { src, mem } => w.stlr(ctx, cfg, src, mem),
+            Insn::Swpal { src, dest, mem } => w.swpal(ctx, cfg, src, dest, mem),
+            Insn::Adrp { dest, src } => w.adrp(ctx, cfg, dest, src),
+            Insn::AddLo12 { dest, src, sym } => w.add_lo12(ctx, cfg, dest, src, sym),
+            Insn::MovImm { dest, val } => w.mov_imm(ctx, cfg, dest, *val),
+            Insn::Mul { dest, a, b } => w.mul(ctx, cfg, dest, a, b),
+            Insn::Umulh { dest, a, b } => w.umulh(ctx, cfg, dest, a, b),
+            Insn::Smulh { dest, a, b } => w.smulh(ctx, cfg, dest, a, b),
+            Insn::Udiv { dest, a, b } => w.udiv(ctx, cfg, dest, a, b),
+            Insn::Sdiv { dest, a, b } => w.sdiv(ctx, cfg, dest, a, b),
+            Insn::Fadd { dest, a, b } => w.fadd(ctx, cfg, dest, a, b),
+            Insn::Fsub { dest, a, b } => w.fsub(ctx, cfg, dest, a, b),
+            Insn::Fmul { dest, a, b } => w.fmul(ctx, cfg, dest, a, b),
+            Insn::Fdiv { dest, a, b } => w.fdiv(ctx, cfg, dest, a, b),
+            Insn::Fmadd { dest, a, b, c } => w.fmadd(ctx, cfg, dest, a, b, c),
+            Insn::Fmsub { dest, a, b, c } => w.fmsub(ctx, cfg, dest, a, b, c),
+            Insn::Fnmadd { dest, a, b, c } => w.fnmadd(ctx, cfg, dest, a, b, c),
+            Insn::Fnmsub { dest, a, b, c } => w.fnmsub(ctx, cfg, dest, a, b, c),
+            Insn::Fcmp { a, b } => w.fcmp(ctx, cfg, a, b),
+            Insn::Fmin { dest, a, b } => w.fmin(ctx, cfg, dest, a, b),
+            Insn::Fmax { dest, a, b } => w.fmax(ctx, cfg, dest, a, b),
+            Insn::Fsqrt { dest, src } => w.fsqrt(ctx, cfg, dest, src),
+            Insn::Fabs { dest, src } => w.fabs(ctx, cfg, dest, src),
+            Insn::Fneg { dest, src } => w.fneg(ctx, cfg, dest, src),
+            Insn::Fcsel { cond, dest, a, b } => w.fcsel(ctx, cfg, *cond, dest, a, b),
+            Insn::Scvtf { dest, src } => w.scvtf(ctx, cfg, dest, src),
+            Insn::Ucvtf { dest, src } => w.ucvtf(ctx, cfg, dest, src),
+            Insn::Fcvtzs { dest, src } => w.fcvtzs(ctx, cfg, dest, src),
+            Insn::Fcvtzu { dest, src } => w.fcvtzu(ctx, cfg, dest, src),
+            Insn::Fcvt { dest, src } => w.fcvt(ctx, cfg, dest, src),
+            Insn::Fmov { dest, src } => w.fmov(ctx, cfg, dest, src),
+            Insn::Whilelt { dest, a, b } => w.whilelt(ctx, cfg, dest, a, b),
+            Insn::Ptrue { dest } => w.ptrue(ctx, cfg, dest),
+            Insn::SveInc { dest, elem } => w.sve_inc(ctx, cfg, dest, *elem),
+            Insn::SveLd1 { dest, pg, mem } => w.sve_ld1(ctx, cfg, dest, *pg, mem),
+            Insn::SveSt1 { src, pg, mem } => w.sve_st1(ctx, cfg, src, *pg, mem),
+            Insn::SveLd1dGather {
+                dest,
+                pg,
+                base,
+                offsets,
+                scaled,
+            } => w.sve_ld1d_gather(ctx, cfg, dest, *pg, base, offsets, *scaled),
+            Insn::SveSt1dScatter {
+                src,
+                pg,
+                base,
+                offsets,
+                scaled,
+            } => w.sve_st1d_scatter(ctx, cfg, src, *pg, base, offsets, *scaled),
+            Insn::SveAdd { dest, a, b } => w.sve_add(ctx, cfg, dest, a, b),
+            Insn::SveSub { dest, a, b } => w.sve_sub(ctx, cfg, dest, a, b),
+            Insn::SveFadd { dest, a, b } => w.sve_fadd(ctx, cfg, dest, a, b),
+            Insn::SveFsub { dest, a, b } => w.sve_fsub(ctx, cfg, dest, a, b),
+            Insn::SveFmul { dest, a, b } => w.sve_fmul(ctx, cfg, dest, a, b),
+            Insn::SveFmla { dest, pg, a, b } => w.sve_fmla(ctx, cfg, dest, *pg, a, b),
+            Insn::SveDup { dest, src } => w.sve_dup(ctx, cfg, dest, src),
+            Insn::Db { bytes } => w.db(ctx, cfg, bytes),
+            Insn::AlignTo { alignment } => w.align_to(ctx, cfg, *alignment),
+            Insn::Nop => w.nop(ctx, cfg),
+            Insn::Nops { n_bytes } => w.nops(ctx, cfg, *n_bytes),
+            Insn::Align { pow2 } => w.align(ctx, cfg, *pow2),
+            Insn::SetLabel { label } => w.set_label(ctx, cfg, label.clone()),
+            Insn::AdrLabel { dest, label } => w.adr_label(ctx, cfg, dest, label.clone()),
+            Insn::BLabel { label } => w.b_label(ctx, cfg, label.clone()),
+            Insn::BcondLabel { cond, label } => w.bcond_label(ctx, cfg, *cond, label.clone()),
+            Insn::BcondLabelHinted { cond, label, hint } => {
+                w.bcond_label_hinted(ctx, cfg, *cond, label.clone(), *hint)
+            }
+            Insn::BlLabel { label } => w.bl_label(ctx, cfg, label.clone()),
+            Insn::DdLabel { label } => w.dd_label(ctx, cfg, label.clone()),
+        }
+    }
+}
+
+/// Records calls as a list of [`Insn`]s.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RecordingWriter<L> {
+    insns: Vec<Insn<L>>,
+}
+
+impl<L> RecordingWriter<L> {
+    /// Creates an empty recording.
+    pub fn new() -> Self {
+        Self { insns: Vec::new() }
+    }
+
+    /// Returns the recorded calls in the order they were made.
+    pub fn insns(&self) -> &[Insn<L>] {
+        &self.insns
+    }
+
+    /// Returns the recorded calls for editing or reordering.
+    pub fn insns_mut(&mut self) -> &mut Vec<Insn<L>> {
+        &mut self.insns
+    }
+
+    /// Consumes the recording and returns its calls.
+    pub fn into_insns(self) -> Vec<Insn<L>> {
+        self.insns
+    }
+
+    /// Replays every recorded call into `w`, in order, stopping at the first
+    /// error.
+    pub fn replay<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+        L: Clone,
+    {
+        for insn in &self.insns {
+            insn.replay(w, ctx, cfg)?;
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, insn: Insn<L>) -> Result<(), EmitError> {
+        self.insns.push(insn);
+        Ok(())
+    }
+}
+
+impl<L> Default for RecordingWriter<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L> From<Vec<Insn<L>>> for RecordingWriter<L> {
+    fn from(insns: Vec<Insn<L>>) -> Self {
+        Self { insns }
+    }
+}
+
+impl<L, Context> WriterCore<Context> for RecordingWriter<L> {
+    type Error = EmitError;
+
+    fn brk(&mut self, _ctx: &mut Context, _cfg: AArch64Arch, imm: u16) -> Result<(), Self::Error> {
+        self.record(Insn::Brk { imm })
+    }
+
+    fn unreachable(&mut self, _ctx: &mut Context, _cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Unreachable)
+    }
+
+    fn debug_break(&mut self, _ctx: &mut Context, _cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::DebugBreak)
+    }
+
+    fn svc(&mut self, _ctx: &mut Context, _cfg: AArch64Arch, imm: u16) -> Result<(), Self::Error> {
+        self.record(Insn::Svc { imm })
+    }
+
+    fn bti(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        target: BtiTarget,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Bti { target })
+    }
+
+    fn paciasp(&mut self, _ctx: &mut Context, _cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Paciasp)
+    }
+
+    fn autiasp(&mut self, _ctx: &mut Context, _cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Autiasp)
+    }
+
+    fn mov(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mov {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn sub(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sub {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn add(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Add {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn adds(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Adds {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn subs(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Subs {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn adcs(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Adcs {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sbcs(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sbcs {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn add_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AddImm {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn sub_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SubImm {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn add_uxtw(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AddUxtw {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn add_lsl(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        shift: u8,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AddLsl {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            shift,
+        })
+    }
+
+    fn sxt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sxt {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn uxt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Uxt {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn str(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Str {
+            src: src.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn ldr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ldr {
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn stp(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        src1: &(dyn MemArg + '_),
+        src2: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Stp {
+            src1: src1.concrete_mem_kind(),
+            src2: src2.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn ldp(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest1: &(dyn MemArg + '_),
+        dest2: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ldp {
+            dest1: dest1.concrete_mem_kind(),
+            dest2: dest2.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn bl(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Bl {
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn br(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Br {
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn b(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::B {
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn cmp(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cmp {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn cmp_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::CmpImm {
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn tst(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Tst {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn csel(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        cond: ConditionCode,
+        dest: &(dyn MemArg + '_),
+        true_val: &(dyn MemArg + '_),
+        false_val: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Csel {
+            cond,
+            dest: dest.concrete_mem_kind(),
+            true_val: true_val.concrete_mem_kind(),
+            false_val: false_val.concrete_mem_kind(),
+        })
+    }
+
+    fn cset(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        cond: ConditionCode,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cset {
+            cond,
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn bcond(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        cond: ConditionCode,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Bcond {
+            cond,
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn and(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::And {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn orr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Orr {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn eor(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Eor {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn and_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AndImm {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn orr_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::OrrImm {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn eor_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::EorImm {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn lsl(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lsl {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn lsr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lsr {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn asr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Asr {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn ror(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ror {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn cls(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cls {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn clz(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Clz {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn rbit(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Rbit {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn rev(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Rev {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn ubfx(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        lsb: u8,
+        width: u8,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ubfx {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+            lsb,
+            width,
+        })
+    }
+
+    fn bfi(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        lsb: u8,
+        width: u8,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Bfi {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+            lsb,
+            width,
+        })
+    }
+
+    fn mvn(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mvn {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn neg(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Neg {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn negs(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Negs {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn adr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Adr {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn ret(&mut self, _ctx: &mut Context, _cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Ret)
+    }
+
+    fn mrs(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        reg: SysReg,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mrs {
+            dest: dest.concrete_mem_kind(),
+            reg,
+        })
+    }
+
+    fn msr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        reg: SysReg,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Msr {
+            reg,
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn mrs_nzcv(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::MrsNzcv {
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn msr_nzcv(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::MsrNzcv {
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn mrs_fpcr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::MrsFpcr {
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn msr_fpcr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::MsrFpcr {
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn mrs_tpidr_el0(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::MrsTpidrEl0 {
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn dmb(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        barrier: Barrier,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Dmb { barrier })
+    }
+
+    fn dsb(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        barrier: Barrier,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Dsb { barrier })
+    }
+
+    fn isb(&mut self, _ctx: &mut Context, _cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Isb)
+    }
+
+    fn hint(&mut self, _ctx: &mut Context, _cfg: AArch64Arch, imm: u8) -> Result<(), Self::Error> {
+        self.record(Insn::Hint { imm })
+    }
+
+    fn yield_(&mut self, _ctx: &mut Context, _cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Yield)
+    }
+
+    fn wfe(&mut self, _ctx: &mut Context, _cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Wfe)
+    }
+
+    fn sev(&mut self, _ctx: &mut Context, _cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Sev)
+    }
+
+    fn prfm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        op: PrfOp,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Prfm {
+            op,
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn dc(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        op: DcOp,
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Dc {
+            op,
+            addr: addr.concrete_mem_kind(),
+        })
+    }
+
+    fn ic_ivau(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::IcIvau {
+            addr: addr.concrete_mem_kind(),
+        })
+    }
+
+    fn ldar(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ldar {
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn stlr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Stlr {
+            src: src.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn swpal(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Swpal {
+            src: src.concrete_mem_kind(),
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn adrp(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Adrp {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn add_lo12(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        sym: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AddLo12 {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+            sym: sym.concrete_mem_kind(),
+        })
+    }
+
+    fn mov_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::MovImm {
+            dest: dest.concrete_mem_kind(),
+            val,
+        })
+    }
+
+    fn mul(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mul {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn umulh(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Umulh {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn smulh(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Smulh {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn udiv(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Udiv {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sdiv(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sdiv {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fadd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fadd {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fsub(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fsub {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmul(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fmul {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fdiv(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fdiv {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmadd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fmadd {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fmsub(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fmsub {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fnmadd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fnmadd {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fnmsub(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fnmsub {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fcmp(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fcmp {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmin(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fmin {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmax(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fmax {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fsqrt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fsqrt {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fabs(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fabs {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fneg(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fneg {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcsel(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        cond: ConditionCode,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fcsel {
+            cond,
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn scvtf(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Scvtf {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn ucvtf(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ucvtf {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvtzs(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fcvtzs {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvtzu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fcvtzu {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fcvt {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fmov(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fmov {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn whilelt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Whilelt {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn ptrue(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ptrue {
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn sve_inc(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        elem: MemorySize,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveInc {
+            dest: dest.concrete_mem_kind(),
+            elem,
+        })
+    }
+
+    fn sve_ld1(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveLd1 {
+            dest: dest.concrete_mem_kind(),
+            pg,
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn sve_st1(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveSt1 {
+            src: src.concrete_mem_kind(),
+            pg,
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn sve_ld1d_gather(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        base: &(dyn MemArg + '_),
+        offsets: &(dyn MemArg + '_),
+        scaled: bool,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveLd1dGather {
+            dest: dest.concrete_mem_kind(),
+            pg,
+            base: base.concrete_mem_kind(),
+            offsets: offsets.concrete_mem_kind(),
+            scaled,
+        })
+    }
+
+    fn sve_st1d_scatter(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        base: &(dyn MemArg + '_),
+        offsets: &(dyn MemArg + '_),
+        scaled: bool,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveSt1dScatter {
+            src: src.concrete_mem_kind(),
+            pg,
+            base: base.concrete_mem_kind(),
+            offsets: offsets.concrete_mem_kind(),
+            scaled,
+        })
+    }
+
+    fn sve_add(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveAdd {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sve_sub(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveSub {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sve_fadd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveFadd {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sve_fsub(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveFsub {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sve_fmul(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveFmul {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sve_fmla(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveFmla {
+            dest: dest.concrete_mem_kind(),
+            pg,
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sve_dup(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SveDup {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn db(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        bytes: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Db {
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    fn align_to(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AlignTo { alignment })
+    }
+
+    fn nop(&mut self, _ctx: &mut Context, _cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Nop)
+    }
+
+    fn nops(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Nops { n_bytes })
+    }
+
+    fn align(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        pow2: u32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Align { pow2 })
+    }
+}
+
+impl<L, Context> Writer<L, Context> for RecordingWriter<L> {
+    fn set_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        s: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SetLabel { label: s })
+    }
+
+    fn adr_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AdrLabel {
+            dest: dest.concrete_mem_kind(),
+            label,
+        })
+    }
+
+    fn b_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::BLabel { label })
+    }
+
+    fn bcond_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        cond: ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::BcondLabel { cond, label })
+    }
+
+    fn bcond_label_hinted(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        cond: ConditionCode,
+        label: L,
+        hint: BranchHint,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::BcondLabelHinted { cond, label, hint })
+    }
+
+    fn bl_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::BlLabel { label })
+    }
+
+    fn dd_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::DdLabel { label })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    fn generate<W: Writer<u32, ()> + ?Sized>(w: &mut W, cfg: AArch64Arch) -> Result<(), W::Error> {
+        w.set_label(&mut (), cfg, 1)?;
+        w.mov(&mut (), cfg, &Reg(0), &Reg(1))?;
+        w.brk(&mut (), cfg, 0)?;
+        w.b_label(&mut (), cfg, 1)
+    }
+
+    #[test]
+    fn test_replay_matches_direct_emission() {
+        let cfg = AArch64Arch::default();
+        let mut rec = RecordingWriter::new();
+        generate(&mut rec, cfg).unwrap();
+        assert_eq!(rec.insns().len(), 4);
+        assert_eq!(rec.insns()[0], Insn::SetLabel { label: 1 });
+
+        let mut direct = String::new();
+        let mut w: &mut dyn Write = &mut direct;
+        generate(&mut w, cfg).unwrap();
+
+        let mut replayed = String::new();
+        let mut w: &mut dyn Write = &mut replayed;
+        rec.replay(&mut w, &mut (), cfg).unwrap();
+        rec.replay(&mut w, &mut (), cfg).unwrap();
+        assert_eq!(replayed, direct.repeat(2));
+    }
+
+    /// Records a trap, then a block at label 1 that returns, then a jump
+    /// back to the label.
+    fn record(cfg: AArch64Arch) -> RecordingWriter<u32> {
+        let mut rec = RecordingWriter::new();
+        rec.brk(&mut (), cfg, 0).unwrap();
+        rec.set_label(&mut (), cfg, 1).unwrap();
+        rec.ret(&mut (), cfg).unwrap();
+        rec.b_label(&mut (), cfg, 1).unwrap();
+        rec
+    }
+
+    #[test]
+    fn test_reordered_replay() {
+        let cfg = AArch64Arch::default();
+        let mut rec = record(cfg);
+        // Move the label in front of the trap, so the jump now targets it.
+        let label = rec.insns_mut().remove(1);
+        rec.insns_mut().insert(0, label);
+
+        let mut output = String::new();
+        let mut w: &mut dyn Write = &mut output;
+        rec.replay(&mut w, &mut (), cfg).unwrap();
+        assert_eq!(output, "1:\nbrk #0\nret\nb 1\n");
+    }
+
+    #[cfg(feature = "bin-backend")]
+    #[test]
+    fn test_reordered_replay_resolves_labels_at_their_new_offset() {
+        use crate::out::bin::AArch64Writer;
+
+        let cfg = AArch64Arch::default();
+        let mut rec = record(cfg);
+        let label = rec.insns_mut().remove(1);
+        rec.insns_mut().insert(0, label);
+
+        let mut binary = AArch64Writer::<u32>::new();
+        rec.replay(&mut binary, &mut (), cfg).unwrap();
+        let (code, labels) = binary.into_parts();
+        assert_eq!(labels[&1], 0);
+        assert_eq!(
+            code,
+            [
+                0x00, 0x00, 0x20, 0xD4, 0xC0, 0x03, 0x5F, 0xD6, 0xFE, 0xFF, 0xFF, 0x17
+            ]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Floating-point rounding mode control through FPCR.
+//!
+//! [`Rounding`] reads and changes the `RMode` field of FPCR, which
+//! floating-point arithmetic, square roots and conversions to floating
+//! point round by:
+//!
+//! ```ignore
+//! writer.set_rounding_mode(ctx, cfg, RoundingMode::TowardZero, &x16)?;
+//! // mrs x16, fpcr
+//! // and x16, x16, #0xffffffffff3fffff
+//! // orr x16, x16, #0xc00000
+//! // msr fpcr, x16
+//! ```
+//!
+//! `fcvtzs` and `fcvtzu` always round towards zero. The RISC-V crate
+//! instead names a static rounding mode in each instruction and sets the
+//! dynamic one with `fsrm`; the x86-64 crate loads and stores MXCSR with
+//! `ldmxcsr`/`stmxcsr`.
+
+use crate::out::WriterCore;
+use crate::out::arg::MemArg;
+use crate::{AArch64Arch, RoundingMode};
+
+/// Position of the `RMode` field in FPCR.
+const RMODE_SHIFT: u8 = 22;
+/// Mask of the `RMode` field in FPCR.
+const RMODE_MASK: u64 = 0b11 << RMODE_SHIFT;
+
+/// Emits reads and writes of the FPCR rounding mode.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Rounding<Context>: WriterCore<Context> {
+    /// Sets the rounding mode to `mode`, keeping the rest of FPCR.
+    ///
+    /// `scratch` is clobbered.
+    fn set_rounding_mode(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        mode: RoundingMode,
+        scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.mrs_fpcr(ctx, cfg, scratch)?;
+        self.and_imm(ctx, cfg, scratch, scratch, !RMODE_MASK)?;
+        if mode != RoundingMode::NearestEven {
+            self.orr_imm(ctx, cfg, scratch, scratch, (mode as u64) << RMODE_SHIFT)?;
+        }
+        self.msr_fpcr(ctx, cfg, scratch)
+    }
+
+    /// Reads the `RMode` field into `dest`, as the discriminant of a
+    /// [`RoundingMode`].
+    fn read_rounding_mode(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.mrs_fpcr(ctx, cfg, dest)?;
+        self.ubfx(ctx, cfg, dest, dest, RMODE_SHIFT, 2)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Rounding<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_set_rounding_mode_rewrites_rmode() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default();
+        writer
+            .set_rounding_mode(&mut (), cfg, RoundingMode::TowardZero, &Reg(16))
+            .unwrap();
+        writer
+            .set_rounding_mode(&mut (), cfg, RoundingMode::NearestEven, &Reg(16))
+            .unwrap();
+        writer.read_rounding_mode(&mut (), cfg, &Reg(0)).unwrap();
+        assert_eq!(
+            output,
+            "mrs x16, fpcr\nand x16, x16, #0xffffffffff3fffff\norr x16, x16, #0xc00000\nmsr fpcr, x16\n\
+             mrs x16, fpcr\nand x16, x16, #0xffffffffff3fffff\nmsr fpcr, x16\n\
+             mrs x0, fpcr\nubfx x0, x0, #22, #2\n"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Saving and restoring sets of registers on the stack.
+//!
+//! Prologues and epilogues that preserve callee-saved registers, and
+//! shims that spill around a call, need the same two sequences: store a set
+//! of registers keeping `sp` 16-byte aligned, and reload it later.
+//! [`SaveRestore`] emits them for any [`WriterCore`]:
+//!
+//! ```ignore
+//! let saved = [Reg(19), Reg(20), Reg(21)];
+//! writer.save_regs(ctx, cfg, &saved)?;    // stp x19, x20, [sp, #-32]!; str x21, [sp, #16]
+//! // ...
+//! writer.restore_regs(ctx, cfg, &saved)?; // ldr x21, [sp, #16]; ldp x19, x20, [sp], #32
+//! ```
+//!
+//! Registers are stored in pairs with `stp`/`ldp`. The first pair allocates
+//! the whole area with a pre-indexed store and the last load frees it with
+//! a post-indexed one, so no separate `sub`/`add` of `sp` is emitted.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::WriterCore;
+use crate::out::arg::{AddressingMode, MemArgKind};
+use crate::{AArch64Arch, RegisterClass};
+
+/// The stack pointer, `sp`.
+const SP: Reg = Reg(31);
+
+/// Returns the size, in bytes, of the area [`SaveRestore::save_regs`]
+/// allocates for `count` registers: 8 bytes each, rounded up to 16.
+pub fn save_area_size(count: usize) -> i32 {
+    (count * 8).next_multiple_of(16) as i32
+}
+
+/// `[sp, #disp]` with the given addressing mode.
+fn sp_slot(disp: i32, mode: AddressingMode) -> MemArgKind<Reg> {
+    MemArgKind::Mem {
+        base: SP,
+        offset: None,
+        disp,
+        size: MemorySize::_64,
+        reg_class: RegisterClass::Gpr,
+        mode,
+    }
+}
+
+/// Emits register save and restore sequences.
+///
+/// Implemented for every [`WriterCore`].
+pub trait SaveRestore<Context>: WriterCore<Context> {
+    /// Stores the general-purpose registers `regs` below `sp`, first
+    /// register lowest, and moves `sp` down by
+    /// [`save_area_size`]`(regs.len())`.
+    fn save_regs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        regs: &[Reg],
+    ) -> Result<(), Self::Error> {
+        let size = save_area_size(regs.len());
+        for (i, pair) in regs.chunks(2).enumerate() {
+            let slot = match i {
+                0 => sp_slot(-size, AddressingMode::PreIndex),
+                i => sp_slot(i as i32 * 16, AddressingMode::Offset),
+            };
+            match pair {
+                [a, b] => self.stp(ctx, cfg, a, b, &slot)?,
+                [a] => self.str(ctx, cfg, a, &slot)?,
+                _ => unreachable!(),
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores registers saved by [`save_regs`](Self::save_regs) with the
+    /// same `regs`, moving `sp` back up.
+    fn restore_regs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        regs: &[Reg],
+    ) -> Result<(), Self::Error> {
+        let size = save_area_size(regs.len());
+        for (i, pair) in regs.chunks(2).enumerate().rev() {
+            let slot = match i {
+                0 => sp_slot(size, AddressingMode::PostIndex),
+                i => sp_slot(i as i32 * 16, AddressingMode::Offset),
+            };
+            match pair {
+                [a, b] => self.ldp(ctx, cfg, a, b, &slot)?,
+                [a] => self.ldr(ctx, cfg, a, &slot)?,
+                _ => unreachable!(),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> SaveRestore<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_pairs_with_odd_tail() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let regs = [Reg(19), Reg(20), Reg(21)];
+        out.save_regs(&mut (), AArch64Arch::default(), &regs)
+            .unwrap();
+        out.restore_regs(&mut (), AArch64Arch::default(), &regs)
+            .unwrap();
+        assert_eq!(
+            output,
+            "stp x19, x20, [sp, #-32]!\nstr x21, [sp, #16]\n\
+             ldr x21, [sp, #16]\nldp x19, x20, [sp], #32\n"
+        );
+    }
+
+    #[test]
+    fn test_single_register_keeps_alignment() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let regs = [Reg(30)];
+        out.save_regs(&mut (), AArch64Arch::default(), &regs)
+            .unwrap();
+        out.restore_regs(&mut (), AArch64Arch::default(), &regs)
+            .unwrap();
+        assert_eq!(output, "str x30, [sp, #-16]!\nldr x30, [sp], #16\n");
+    }
+
+    #[test]
+    fn test_even_set_is_all_pairs() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let regs = [Reg(19), Reg(20), Reg(21), Reg(22)];
+        out.save_regs(&mut (), AArch64Arch::default(), &regs)
+            .unwrap();
+        out.restore_regs(&mut (), AArch64Arch::default(), &regs)
+            .unwrap();
+        out.save_regs(&mut (), AArch64Arch::default(), &[]).unwrap();
+        assert_eq!(
+            output,
+            "stp x19, x20, [sp, #-32]!\nstp x21, x22, [sp, #16]\n\
+             ldp x21, x22, [sp, #16]\nldp x19, x20, [sp], #32\n"
+        );
+    }
+}
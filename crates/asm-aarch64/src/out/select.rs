@@ -0,0 +1,102 @@
+//! Branchless conditional selects and booleans from flags.
+//!
+//! [`Select`] turns the flags left by a compare into a value without a
+//! branch, using the AArch64 idioms for it:
+//!
+//! ```ignore
+//! writer.cmp(ctx, cfg, &a, &b)?;
+//! writer.select(ctx, cfg, ConditionCode::LT, &min, &a, &b)?;
+//! // csel min, a, b, lt
+//! writer.set_bool(ctx, cfg, ConditionCode::EQ, &eq)?;
+//! // cset eq, eq
+//! ```
+//!
+//! Neither helper changes the flags, so several can share one compare. The
+//! x86-64 and RISC-V crates provide the same helpers with `cmov`/`setcc` and
+//! `slt`-based sequences.
+
+use crate::out::WriterCore;
+use crate::out::arg::MemArg;
+use crate::{AArch64Arch, ConditionCode};
+
+/// Emits branchless selects and booleans from the current flags.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Select<Context>: WriterCore<Context> {
+    /// Sets `dest` to `if_true` if `cc` holds and to `if_false` otherwise.
+    ///
+    /// Any of the operands may be the same register.
+    fn select(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cc: ConditionCode,
+        dest: &(dyn MemArg + '_),
+        if_true: &(dyn MemArg + '_),
+        if_false: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.csel(ctx, cfg, cc, dest, if_true, if_false)
+    }
+
+    /// Sets `dest` to 1 if `cc` holds and to 0 otherwise.
+    fn set_bool(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cc: ConditionCode,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.cset(ctx, cfg, cc, dest)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Select<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_one_compare_feeds_several_selects() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default();
+        writer.cmp(&mut (), cfg, &Reg(0), &Reg(1)).unwrap();
+        writer
+            .select(&mut (), cfg, ConditionCode::LT, &Reg(2), &Reg(0), &Reg(1))
+            .unwrap();
+        writer
+            .select(&mut (), cfg, ConditionCode::LT, &Reg(0), &Reg(1), &Reg(0))
+            .unwrap();
+        writer
+            .set_bool(&mut (), cfg, ConditionCode::EQ, &Reg(3))
+            .unwrap();
+        assert_eq!(
+            output,
+            "cmp x0, x1\ncsel x2, x0, x1, lt\ncsel x0, x1, x0, lt\ncset x3, eq\n"
+        );
+    }
+
+    #[cfg(feature = "bin-backend")]
+    #[test]
+    fn test_set_bool_encodes_the_inverted_csinc() {
+        use crate::out::bin::AArch64Writer;
+
+        let cfg = AArch64Arch::default();
+        let mut writer = AArch64Writer::<u32>::new();
+        writer
+            .select(&mut (), cfg, ConditionCode::LT, &Reg(0), &Reg(1), &Reg(2))
+            .unwrap();
+        writer
+            .set_bool(&mut (), cfg, ConditionCode::EQ, &Reg(3))
+            .unwrap();
+        // csel x0, x1, x2, lt; csinc x3, xzr, xzr, ne
+        assert_eq!(
+            writer.into_bytes(),
+            [0x20, 0xB0, 0x82, 0x9A, 0xE3, 0x17, 0x9F, 0x9A]
+        );
+    }
+}
@@ -0,0 +1,331 @@
+//! Two-pass emission over a single body.
+//!
+//! Choosing between encodings that depend on where a label lands (a `b.cond`
+//! that may be out of range, a constant pool that must stay within `adr`
+//! range) normally means assembling everything into a buffer and patching it.
+//! [`EmitSession`] instead runs the same body twice: a sizing pass into a
+//! writer that reports [`current_offset`](crate::out::WriterCore::current_offset),
+//! recording where each label is defined, and an emission pass into the real
+//! output with those offsets available through [`Layout`]:
+//!
+//! ```ignore
+//! fn body<E>(w: &mut dyn Writer<usize, (), Error = E>, ctx: &mut (), layout: &Layout<'_, [Option<usize>; 4]>) -> Result<(), E> {
+//!     match layout.label_offset(&EXIT) {
+//!         Some(exit) if exit < 1 << 20 => w.bcond_label(ctx, cfg, ConditionCode::EQ, EXIT)?,
+//!         _ => { /* long form */ }
+//!     }
+//!     // ...
+//! }
+//!
+//! let mut session = EmitSession::new([None; 4]);
+//! session.size(&mut AArch64Writer::new(), &mut (), body)?;
+//! session.emit(&mut out, &mut (), body)?;
+//! ```
+//!
+//! Label offsets are kept in a [`LabelTable`], which a fixed-size array
+//! implements for `usize` labels, so the session itself never allocates.
+
+use core::ops::{Deref, DerefMut};
+
+use crate::out::{Writer, WriterCore};
+
+/// Storage for label offsets recorded during a sizing pass.
+pub trait LabelTable<L> {
+    /// Records that `label` is defined `offset` bytes into the body.
+    fn record(&mut self, label: &L, offset: usize);
+
+    /// Returns the recorded offset of `label`, if it was defined.
+    fn offset(&self, label: &L) -> Option<usize>;
+}
+
+/// Labels are indices into the slice.
+///
+/// # Panics
+///
+/// [`record`](LabelTable::record) panics if the label is out of bounds.
+impl LabelTable<usize> for [Option<usize>] {
+    fn record(&mut self, label: &usize, offset: usize) {
+        self[*label] = Some(offset);
+    }
+
+    fn offset(&self, label: &usize) -> Option<usize> {
+        self.get(*label).copied().flatten()
+    }
+}
+
+impl<const N: usize> LabelTable<usize> for [Option<usize>; N] {
+    fn record(&mut self, label: &usize, offset: usize) {
+        self.as_mut_slice().record(label, offset);
+    }
+
+    fn offset(&self, label: &usize) -> Option<usize> {
+        self.as_slice().offset(label)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L: Ord + Clone> LabelTable<L> for alloc::collections::BTreeMap<L, usize> {
+    fn record(&mut self, label: &L, offset: usize) {
+        self.insert(label.clone(), offset);
+    }
+
+    fn offset(&self, label: &L) -> Option<usize> {
+        self.get(label).copied()
+    }
+}
+
+/// What the body knows about the layout while it runs.
+///
+/// During the sizing pass nothing is known yet, so the body should pick the
+/// largest form of every layout-dependent sequence; during the emission pass
+/// it may pick smaller ones as long as label offsets do not move.
+pub struct Layout<'a, T: ?Sized> {
+    labels: Option<&'a T>,
+    size: Option<usize>,
+}
+
+impl<T: ?Sized> Layout<'_, T> {
+    /// Returns `true` during the sizing pass.
+    pub fn is_sizing(&self) -> bool {
+        self.labels.is_none()
+    }
+
+    /// Byte offset of `label` from the start of the body, once sized.
+    pub fn label_offset<L>(&self, label: &L) -> Option<usize>
+    where
+        T: LabelTable<L>,
+    {
+        self.labels?.offset(label)
+    }
+
+    /// Total size of the body in bytes, once sized.
+    pub fn size(&self) -> Option<usize> {
+        self.size
+    }
+}
+
+/// A sizing pass followed by an emission pass over the same body.
+pub struct EmitSession<T> {
+    labels: T,
+    size: Option<usize>,
+}
+
+impl<T> EmitSession<T> {
+    /// Creates a session that records label offsets into `labels`.
+    pub fn new(labels: T) -> Self {
+        Self { labels, size: None }
+    }
+
+    /// Size of the body measured by the last [`size`](Self::size) call.
+    pub fn body_size(&self) -> Option<usize> {
+        self.size
+    }
+
+    /// Returns the label table, with the offsets from the sizing pass.
+    pub fn into_labels(self) -> T {
+        self.labels
+    }
+
+    /// Runs `body` into `sizer`, recording its size and label offsets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sizer` does not report a
+    /// [`current_offset`](WriterCore::current_offset).
+    pub fn size<L, Context, E>(
+        &mut self,
+        sizer: &mut (dyn Writer<L, Context, Error = E> + '_),
+        ctx: &mut Context,
+        mut body: impl FnMut(
+            &mut (dyn Writer<L, Context, Error = E> + '_),
+            &mut Context,
+            &Layout<'_, T>,
+        ) -> Result<(), E>,
+    ) -> Result<usize, E>
+    where
+        T: LabelTable<L>,
+    {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("emit_session", pass = "size").entered();
+        let start = sizer
+            .current_offset()
+            .expect("sizing writer must track its offset");
+        let mut recorder = Recorder {
+            writer: sizer,
+            labels: &mut self.labels,
+            start,
+        };
+        body(
+            &mut recorder,
+            ctx,
+            &Layout {
+                labels: None,
+                size: None,
+            },
+        )?;
+        let size = recorder.writer.current_offset().unwrap_or(start) - start;
+        self.size = Some(size);
+        Ok(size)
+    }
+
+    /// Runs `body` into `out` with the layout from the sizing pass.
+    pub fn emit<L, Context, E>(
+        &self,
+        out: &mut (dyn Writer<L, Context, Error = E> + '_),
+        ctx: &mut Context,
+        mut body: impl FnMut(
+            &mut (dyn Writer<L, Context, Error = E> + '_),
+            &mut Context,
+            &Layout<'_, T>,
+        ) -> Result<(), E>,
+    ) -> Result<(), E> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("emit_session", pass = "emit").entered();
+        body(
+            out,
+            ctx,
+            &Layout {
+                labels: Some(&self.labels),
+                size: self.size,
+            },
+        )
+    }
+}
+
+/// Forwards to the sizing writer, recording label definitions on the way.
+struct Recorder<'a, W: ?Sized, T: ?Sized> {
+    writer: &'a mut W,
+    labels: &'a mut T,
+    start: usize,
+}
+
+impl<W: ?Sized, T: ?Sized> Deref for Recorder<'_, W, T> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        self.writer
+    }
+}
+
+impl<W: ?Sized, T: ?Sized> DerefMut for Recorder<'_, W, T> {
+    fn deref_mut(&mut self) -> &mut W {
+        self.writer
+    }
+}
+
+crate::writer_dispatch!(@core [ W: WriterCore<Context> + ?Sized, T: ?Sized, Context ] Recorder<'_, W, T> => W::Error [Context] [W]);
+
+impl<W, T, L, Context> Writer<L, Context> for Recorder<'_, W, T>
+where
+    W: Writer<L, Context> + ?Sized,
+    T: LabelTable<L> + ?Sized,
+{
+    fn set_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        s: L,
+    ) -> Result<(), Self::Error> {
+        if let Some(offset) = self.writer.current_offset() {
+            self.labels.record(&s, offset - self.start);
+        }
+        self.writer.set_label(ctx, cfg, s)
+    }
+
+    fn adr_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        dest: &(dyn crate::out::arg::MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.adr_label(ctx, cfg, dest, label)
+    }
+
+    fn b_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.b_label(ctx, cfg, label)
+    }
+
+    fn bcond_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        cond: crate::ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.bcond_label(ctx, cfg, cond, label)
+    }
+
+    fn bl_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.bl_label(ctx, cfg, label)
+    }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.dd_label(ctx, cfg, label)
+    }
+}
+
+#[cfg(all(test, feature = "bin-backend"))]
+mod tests {
+    use super::*;
+    use crate::AArch64Arch;
+    use crate::out::bin::AArch64Writer;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    const LOOP: usize = 0;
+    const EXIT: usize = 1;
+
+    fn body<E>(
+        w: &mut (dyn Writer<usize, (), Error = E> + '_),
+        ctx: &mut (),
+        _layout: &Layout<'_, [Option<usize>; 2]>,
+    ) -> Result<(), E> {
+        let cfg = AArch64Arch::default();
+        w.set_label(ctx, cfg, LOOP)?;
+        w.b_label(ctx, cfg, EXIT)?;
+        w.db(ctx, cfg, &[0; 4])?;
+        w.b_label(ctx, cfg, LOOP)?;
+        w.set_label(ctx, cfg, EXIT)?;
+        w.ret(ctx, cfg)
+    }
+
+    #[test]
+    fn test_sizing_pass_records_labels() {
+        let mut session = EmitSession::new([None; 2]);
+        let mut sizer: AArch64Writer<usize> = AArch64Writer::new();
+        sizer.db(&mut (), AArch64Arch::default(), &[0; 8]).unwrap();
+        let size = session.size(&mut sizer, &mut (), body).unwrap();
+        assert_eq!(size, 16);
+        assert_eq!(session.body_size(), Some(16));
+
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let mut seen = None;
+        session
+            .emit(&mut writer, &mut (), |w, ctx, layout| {
+                seen = layout.label_offset(&EXIT);
+                assert!(!layout.is_sizing());
+                body(w, ctx, layout)
+            })
+            .unwrap();
+        assert_eq!(seen, Some(12));
+        assert_eq!(session.into_labels(), [Some(0), Some(12)]);
+        assert!(output.contains("b 1\n"));
+    }
+}
@@ -0,0 +1,197 @@
+//! Scalable Vector Extension (SVE) loops.
+//!
+//! SVE vectors are between 128 and 2048 bits long, and the length is only
+//! known at run time. A loop written for any length steps its index by the
+//! number of elements in a vector (`incd`) and uses a `whilelt` predicate
+//! to switch off the elements past the end, so it needs no scalar tail.
+//! [`SveLoop`] emits that loop around a body:
+//!
+//! ```ignore
+//! // y[i] += a * x[i] for i in 0..n, with a in z2, x in x0, y in x1, n in x2
+//! let (x, y, i, d) = (Reg(0), Reg(1), Reg(3), MemorySize::_64);
+//! SveLoop::new(head, i, d).emit(w, ctx, cfg, &Reg(2), |w, ctx, pg| {
+//!     w.sve_ld1(ctx, cfg, &Reg(0), pg, &element(x, i, d))?;
+//!     w.sve_ld1(ctx, cfg, &Reg(1), pg, &element(y, i, d))?;
+//!     w.sve_fmla(ctx, cfg, &Reg(1), pg, &Reg(0), &Reg(2))?;
+//!     w.sve_st1(ctx, cfg, &Reg(1), pg, &element(y, i, d))
+//! })?;
+//! // movz x3, #0
+//! // whilelt p0.d, x3, x2
+//! // head: ld1d {z0.d}, p0/z, [x0, x3, LSL #3] ...
+//! // incd x3
+//! // whilelt p0.d, x3, x2
+//! // b.mi head
+//! ```
+//!
+//! The instructions themselves are [`WriterCore`](crate::out::WriterCore)
+//! methods (`whilelt`, `sve_ld1`, `sve_fmla` and so on), which need
+//! [`AArch64Arch::sve`]. Vector operands name Z registers and predicate
+//! destinations name P registers; in both the operand size is the element
+//! size, so a plain [`Reg`] is a doubleword vector.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::Writer;
+use crate::out::arg::{AddressingMode, ArgKind, MemArg, MemArgKind};
+use crate::{AArch64Arch, ConditionCode, PredicateReg, RegisterClass};
+
+/// Returns the letter that names `size` as an SVE element size: `b`, `h`,
+/// `s` or `d`.
+///
+/// Sizes above 64 bits are named `d`.
+pub fn element_letter(size: MemorySize) -> char {
+    match size {
+        MemorySize::_8 => 'b',
+        MemorySize::_16 => 'h',
+        MemorySize::_32 => 's',
+        _ => 'd',
+    }
+}
+
+/// Returns the element size of a vector or predicate operand: the size of
+/// a register operand, and 64 bits for anything else.
+pub fn element_size(arg: &(dyn MemArg + '_)) -> MemorySize {
+    match arg.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Reg { size, .. }) => size,
+        _ => MemorySize::_64,
+    }
+}
+
+/// Returns the address of element `index` of an array at `base` whose
+/// elements are `size` wide (`[base, index, lsl #s]`), the form
+/// [`sve_ld1`](crate::out::WriterCore::sve_ld1) and
+/// [`sve_st1`](crate::out::WriterCore::sve_st1) take.
+pub fn element(base: Reg, index: Reg, size: MemorySize) -> MemArgKind<ArgKind> {
+    let shift = match size {
+        MemorySize::_8 => 0,
+        MemorySize::_16 => 1,
+        MemorySize::_32 => 2,
+        _ => 3,
+    };
+    let reg = |reg| ArgKind::Reg {
+        reg,
+        size: MemorySize::_64,
+    };
+    MemArgKind::Mem {
+        base: reg(base),
+        offset: Some((reg(index), shift)),
+        disp: 0,
+        size,
+        reg_class: RegisterClass::Sve,
+        mode: AddressingMode::Offset,
+    }
+}
+
+/// Emits vector-length-agnostic loops.
+pub struct SveLoop<L> {
+    head: L,
+    index: Reg,
+    elem: MemorySize,
+    predicate: PredicateReg,
+}
+
+impl<L: Clone> SveLoop<L> {
+    /// Creates a loop that labels its body `head`, counts elements of
+    /// `elem` size in the general-purpose `index`, and governs the body with
+    /// `p0`.
+    pub fn new(head: L, index: Reg, elem: MemorySize) -> Self {
+        Self {
+            head,
+            index,
+            elem,
+            predicate: PredicateReg(0),
+        }
+    }
+
+    /// Sets the predicate the loop keeps the active elements in.
+    pub fn with_predicate(self, predicate: PredicateReg) -> Self {
+        Self { predicate, ..self }
+    }
+
+    /// Emits a loop running `body` over elements `0..count`, a vector at a
+    /// time.
+    ///
+    /// `index` starts at 0 and holds the first element of the current
+    /// vector; `body` receives the predicate of the elements below `count`.
+    /// The loop runs `body` at least once, with no element active when
+    /// `count` is 0 or negative, so `body` should only access memory through
+    /// predicated loads and stores. The sequence clobbers the flags.
+    pub fn emit<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        count: &(dyn MemArg + '_),
+        mut body: impl FnMut(&mut W, &mut Context, PredicateReg) -> Result<(), W::Error>,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let pd = ArgKind::Reg {
+            reg: Reg(self.predicate.0),
+            size: self.elem,
+        };
+        w.mov_imm(ctx, cfg, &self.index, 0)?;
+        w.whilelt(ctx, cfg, &pd, &self.index, count)?;
+        w.set_label(ctx, cfg, self.head.clone())?;
+        body(w, ctx, self.predicate)?;
+        w.sve_inc(ctx, cfg, &self.index, self.elem)?;
+        w.whilelt(ctx, cfg, &pd, &self.index, count)?;
+        // b.first: the first element is active.
+        w.bcond_label(ctx, cfg, ConditionCode::MI, self.head.clone())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::out::WriterCore;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_axpy_loop() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default().with_sve(0);
+        let (x, y, i) = (Reg(0), Reg(1), Reg(3));
+        SveLoop::new("head", i, MemorySize::_64)
+            .emit(&mut writer, &mut (), cfg, &Reg(2), |w, ctx, pg| {
+                w.sve_ld1(ctx, cfg, &Reg(0), pg, &element(x, i, MemorySize::_64))?;
+                w.sve_ld1(ctx, cfg, &Reg(1), pg, &element(y, i, MemorySize::_64))?;
+                w.sve_fmla(ctx, cfg, &Reg(1), pg, &Reg(0), &Reg(2))?;
+                w.sve_st1(ctx, cfg, &Reg(1), pg, &element(y, i, MemorySize::_64))
+            })
+            .unwrap();
+        assert_eq!(
+            output,
+            "movz x3, #0, lsl #0\nwhilelt p0.d, x3, x2\nhead:\n\
+             ld1d {z0.d}, p0/z, [x0, x3, LSL #3]\nld1d {z1.d}, p0/z, [x1, x3, LSL #3]\n\
+             fmla z1.d, p0/m, z0.d, z2.d\nst1d {z1.d}, p0, [x1, x3, LSL #3]\n\
+             incd x3\nwhilelt p0.d, x3, x2\nb.mi head\n"
+        );
+    }
+
+    #[test]
+    fn test_gather_and_scatter_text() {
+        let mut output = String::new();
+        let mut w: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default().with_sve(256);
+        let pg = PredicateReg(1);
+        let s = ArgKind::Reg {
+            reg: Reg(4),
+            size: MemorySize::_32,
+        };
+        w.ptrue(&mut (), cfg, &s).unwrap();
+        w.sve_ld1d_gather(&mut (), cfg, &Reg(0), pg, &Reg(1), &Reg(2), true)
+            .unwrap();
+        w.sve_st1d_scatter(&mut (), cfg, &Reg(0), pg, &Reg(1), &Reg(2), false)
+            .unwrap();
+        w.sve_dup(&mut (), cfg, &Reg(4), &Reg(5)).unwrap();
+        assert_eq!(
+            output,
+            "ptrue p4.s\nld1d {z0.d}, p1/z, [x1, z2.d, LSL #3]\nst1d {z0.d}, p1, [x1, z2.d]\n\
+             dup z4.d, x5\n"
+        );
+    }
+}
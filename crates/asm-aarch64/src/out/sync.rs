@@ -0,0 +1,257 @@
+//! Spinlocks and Linux futex system calls.
+//!
+//! [`SpinLock`] emits the acquire and release sequences of a lock held in a
+//! 32-bit word, 0 when free and 1 when held. Acquiring swaps 1 in with
+//! `swpal` and, while the lock is held, spins on plain loads with `yield`
+//! between them, only retrying the swap once the lock reads free:
+//!
+//! ```ignore
+//! // lock address in x0, w9 as scratch
+//! let lock = SpinLock::new(Reg(0), Reg(9), spin, attempt);
+//! lock.acquire(w, ctx, cfg)?;
+//! // b attempt
+//! // spin: yield; ldr w9, [x0]; cmp w9, #0; b.ne spin
+//! // attempt: movz w9, #1; swpal w9, w9, [x0]; cmp w9, #0; b.ne spin
+//! lock.release(w, ctx, cfg)?;
+//! // stlr wzr, [x0]
+//! ```
+//!
+//! `swpal` is an LSE instruction, so acquiring needs
+//! [`AArch64Arch::lse`]. A lock word is also a futex, so a runtime that
+//! would rather sleep than spin can block on it with
+//! [`SpinWait::futex_wait`] and wake waiters with [`SpinWait::futex_wake`].
+//! The x86-64 and RISC-V crates provide the same helpers with `xchg` and
+//! `amoswap.w`.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::arg::{AddressingMode, ArgKind, MemArg, MemArgKind};
+use crate::out::{Writer, WriterCore};
+use crate::{AArch64Arch, ConditionCode, RegisterClass};
+
+/// The Linux `futex` system call number.
+pub const SYS_FUTEX: u64 = 98;
+
+/// The `futex` operation that sleeps while the word holds an expected value,
+/// for a word not shared between processes.
+pub const FUTEX_WAIT_PRIVATE: u64 = 128;
+
+/// The `futex` operation that wakes sleepers on a word, for a word not
+/// shared between processes.
+pub const FUTEX_WAKE_PRIVATE: u64 = 129;
+
+fn reg32(reg: Reg) -> ArgKind {
+    ArgKind::Reg {
+        reg,
+        size: MemorySize::_32,
+    }
+}
+
+/// Returns the 32-bit word at the address in `base`.
+fn word_at(base: Reg) -> MemArgKind {
+    MemArgKind::Mem {
+        base: ArgKind::Reg {
+            reg: base,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp: 0,
+        size: MemorySize::_32,
+        reg_class: RegisterClass::Gpr,
+        mode: AddressingMode::Offset,
+    }
+}
+
+/// Moves `src` into `dest`, resized to `dest`'s width if it is a register,
+/// unless it is already there.
+fn set<Context, W: WriterCore<Context> + ?Sized>(
+    w: &mut W,
+    ctx: &mut Context,
+    cfg: AArch64Arch,
+    dest: Reg,
+    size: MemorySize,
+    src: &(dyn MemArg + '_),
+) -> Result<(), W::Error> {
+    match src.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) if reg == dest => Ok(()),
+        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => w.mov(
+            ctx,
+            cfg,
+            &ArgKind::Reg { reg: dest, size },
+            &ArgKind::Reg { reg, size },
+        ),
+        MemArgKind::NoMem(ArgKind::Lit(val)) => {
+            w.mov_imm(ctx, cfg, &ArgKind::Reg { reg: dest, size }, val)
+        }
+        _ => w.mov(ctx, cfg, &ArgKind::Reg { reg: dest, size }, src),
+    }
+}
+
+/// Emits spin-wait hints and futex system calls.
+///
+/// Implemented for every [`WriterCore`].
+pub trait SpinWait<Context>: WriterCore<Context> {
+    /// Emits `yield`, telling the core it is in a spin-wait loop so a
+    /// simultaneous thread on the same core can run.
+    fn spin_hint(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.yield_(ctx, cfg)
+    }
+
+    /// Emits a `futex` system call that sleeps until woken, as long as the
+    /// 32-bit word at `addr` holds `val`, with no timeout.
+    ///
+    /// `addr` goes in `x0` and `val` in `w2`, in that order, so `val` must
+    /// not be in `x0` unless `addr` is too. The call returns 0 in `x0` once
+    /// woken and `-EAGAIN` if the word did not hold `val`, and clobbers
+    /// `x0` to `x3` and `x8`.
+    fn futex_wait(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        addr: &(dyn MemArg + '_),
+        val: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        set(self, ctx, cfg, Reg(0), MemorySize::_64, addr)?;
+        set(self, ctx, cfg, Reg(2), MemorySize::_32, val)?;
+        self.mov_imm(ctx, cfg, &reg32(Reg(1)), FUTEX_WAIT_PRIVATE)?;
+        self.mov_imm(ctx, cfg, &Reg(3), 0)?;
+        self.mov_imm(ctx, cfg, &Reg(8), SYS_FUTEX)?;
+        self.svc(ctx, cfg, 0)
+    }
+
+    /// Emits a `futex` system call that wakes up to `count` threads sleeping
+    /// on the word at `addr`.
+    ///
+    /// `addr` goes in `x0`. The call returns the number of threads woken in
+    /// `x0`, and clobbers `x0` to `x2` and `x8`.
+    fn futex_wake(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        addr: &(dyn MemArg + '_),
+        count: u32,
+    ) -> Result<(), Self::Error> {
+        set(self, ctx, cfg, Reg(0), MemorySize::_64, addr)?;
+        self.mov_imm(ctx, cfg, &reg32(Reg(2)), count as u64)?;
+        self.mov_imm(ctx, cfg, &reg32(Reg(1)), FUTEX_WAKE_PRIVATE)?;
+        self.mov_imm(ctx, cfg, &Reg(8), SYS_FUTEX)?;
+        self.svc(ctx, cfg, 0)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> SpinWait<Context> for W {}
+
+/// Emits test-and-test-and-set spinlock sequences.
+pub struct SpinLock<L> {
+    lock: Reg,
+    scratch: Reg,
+    spin: L,
+    attempt: L,
+}
+
+impl<L: Clone> SpinLock<L> {
+    /// Creates a lock on the word at the address in `lock`, using `scratch`
+    /// for the swapped value and labelling the spin loop `spin` and the swap
+    /// `attempt`.
+    pub fn new(lock: Reg, scratch: Reg, spin: L, attempt: L) -> Self {
+        Self {
+            lock,
+            scratch,
+            spin,
+            attempt,
+        }
+    }
+
+    /// Emits a loop that returns once it has set the lock word from 0 to 1.
+    ///
+    /// The swap has acquire-release ordering, so accesses after the sequence
+    /// cannot be performed before the lock is taken. The sequence needs LSE,
+    /// clobbers `scratch` and the flags, and defines both labels, so it can
+    /// be emitted once per pair of labels.
+    pub fn acquire<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let (word, scratch) = (word_at(self.lock), reg32(self.scratch));
+        w.b_label(ctx, cfg, self.attempt.clone())?;
+        w.set_label(ctx, cfg, self.spin.clone())?;
+        w.spin_hint(ctx, cfg)?;
+        w.ldr(ctx, cfg, &scratch, &word)?;
+        w.cmp_imm(ctx, cfg, &scratch, 0)?;
+        w.bcond_label(ctx, cfg, ConditionCode::NE, self.spin.clone())?;
+        w.set_label(ctx, cfg, self.attempt.clone())?;
+        w.mov_imm(ctx, cfg, &scratch, 1)?;
+        w.swpal(ctx, cfg, &scratch, &scratch, &word)?;
+        w.cmp_imm(ctx, cfg, &scratch, 0)?;
+        w.bcond_label(ctx, cfg, ConditionCode::NE, self.spin.clone())
+    }
+
+    /// Emits the store-release of 0 that frees the lock.
+    pub fn release<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        w.stlr(ctx, cfg, &reg32(Reg(31)), &word_at(self.lock))
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_acquire_spins_on_loads_with_yield() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default().with_lse(true);
+        let lock = SpinLock::new(Reg(0), Reg(9), ".Lspin", ".Lattempt");
+        lock.acquire(&mut writer, &mut (), cfg).unwrap();
+        lock.release(&mut writer, &mut (), cfg).unwrap();
+        assert_eq!(
+            output,
+            "b .Lattempt\n.Lspin:\nyield\nldr w9, [x0]\ncmp w9, #0\n\
+             b.ne .Lspin\n.Lattempt:\nmovz w9, #1, lsl #0\nswpal w9, w9, [x0]\ncmp w9, #0\n\
+             b.ne .Lspin\nstlr wzr, [x0]\n"
+        );
+    }
+
+    #[test]
+    fn test_futex_arguments_in_place_are_not_moved() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default();
+        w.futex_wait(&mut (), cfg, &Reg(0), &Reg(2)).unwrap();
+        w.futex_wake(&mut (), cfg, &Reg(0), 1).unwrap();
+        assert_eq!(
+            output,
+            "movz w1, #128, lsl #0\nmovz x3, #0, lsl #0\nmovz x8, #98, lsl #0\nsvc #0\n\
+             movz w2, #1, lsl #0\nmovz w1, #129, lsl #0\nmovz x8, #98, lsl #0\nsvc #0\n"
+        );
+    }
+
+    #[test]
+    fn test_futex_wait_loads_an_immediate_value() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default();
+        w.futex_wait(&mut (), cfg, &Reg(19), &ArgKind::Lit(1))
+            .unwrap();
+        assert_eq!(
+            output,
+            "mov x0, x19\nmovz w2, #1, lsl #0\n\
+             movz w1, #128, lsl #0\nmovz x3, #0, lsl #0\nmovz x8, #98, lsl #0\nsvc #0\n"
+        );
+    }
+}
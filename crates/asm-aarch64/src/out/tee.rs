@@ -0,0 +1,1649 @@
+//! Emission to two writers at once.
+//!
+//! [`TeeWriter`] forwards every instruction, directive and label to two
+//! writers in turn, so one run of a code generator can produce an assembly
+//! listing together with the machine code, or feed a text writer and a
+//! counting pass, without generating the code twice:
+//!
+//! ```ignore
+//! let mut listing = String::new();
+//! let mut tee = TeeWriter::new(&mut listing as &mut dyn Write, AArch64Writer::<u32>::new());
+//! generate(&mut tee, &mut ctx, cfg)?;
+//! let (_, binary) = tee.into_inner();
+//! let (code, labels) = binary.into_parts();
+//! ```
+//!
+//! The first writer gets each call before the second, and an error from
+//! either ends the call, so after a failure the second writer may be one
+//! call behind the first. Errors from the second writer are converted into
+//! the first writer's error type.
+
+use portal_pc_asm_common::types::mem::MemorySize;
+
+use crate::out::arg::MemArg;
+use crate::out::{Writer, WriterCore};
+use crate::{
+    AArch64Arch, Barrier, BranchHint, BtiTarget, ConditionCode, DcOp, PredicateReg, PrfOp, SysReg,
+};
+
+/// Forwards every call to two writers.
+pub struct TeeWriter<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> TeeWriter<A, B> {
+    /// Creates a writer that forwards to `first`, then `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Returns the first writer.
+    pub fn first(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Returns the second writer.
+    pub fn second(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Returns both writers.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: WriterCore<Context>, B: WriterCore<Context>, Context> WriterCore<Context>
+    for TeeWriter<A, B>
+where
+    A::Error: From<B::Error>,
+{
+    type Error = A::Error;
+
+    fn brk(&mut self, ctx: &mut Context, cfg: AArch64Arch, imm: u16) -> Result<(), Self::Error> {
+        self.first.brk(ctx, cfg, imm)?;
+        self.second.brk(ctx, cfg, imm)?;
+        Ok(())
+    }
+
+    fn unreachable(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.first.unreachable(ctx, cfg)?;
+        self.second.unreachable(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn debug_break(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.first.debug_break(ctx, cfg)?;
+        self.second.debug_break(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn svc(&mut self, ctx: &mut Context, cfg: AArch64Arch, imm: u16) -> Result<(), Self::Error> {
+        self.first.svc(ctx, cfg, imm)?;
+        self.second.svc(ctx, cfg, imm)?;
+        Ok(())
+    }
+
+    fn bti(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: BtiTarget,
+    ) -> Result<(), Self::Error> {
+        self.first.bti(ctx, cfg, target)?;
+        self.second.bti(ctx, cfg, target)?;
+        Ok(())
+    }
+
+    fn paciasp(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.first.paciasp(ctx, cfg)?;
+        self.second.paciasp(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn autiasp(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.first.autiasp(ctx, cfg)?;
+        self.second.autiasp(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn mov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mov(ctx, cfg, dest, src)?;
+        self.second.mov(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn sub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sub(ctx, cfg, dest, a, b)?;
+        self.second.sub(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn add(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.add(ctx, cfg, dest, a, b)?;
+        self.second.add(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn adds(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.adds(ctx, cfg, dest, a, b)?;
+        self.second.adds(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn subs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.subs(ctx, cfg, dest, a, b)?;
+        self.second.subs(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn adcs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.adcs(ctx, cfg, dest, a, b)?;
+        self.second.adcs(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn sbcs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sbcs(ctx, cfg, dest, a, b)?;
+        self.second.sbcs(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.first.add_imm(ctx, cfg, dest, a, imm)?;
+        self.second.add_imm(ctx, cfg, dest, a, imm)?;
+        Ok(())
+    }
+
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.first.sub_imm(ctx, cfg, dest, a, imm)?;
+        self.second.sub_imm(ctx, cfg, dest, a, imm)?;
+        Ok(())
+    }
+
+    fn add_uxtw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.add_uxtw(ctx, cfg, dest, a, b)?;
+        self.second.add_uxtw(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn add_lsl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        shift: u8,
+    ) -> Result<(), Self::Error> {
+        self.first.add_lsl(ctx, cfg, dest, a, b, shift)?;
+        self.second.add_lsl(ctx, cfg, dest, a, b, shift)?;
+        Ok(())
+    }
+
+    fn sxt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sxt(ctx, cfg, dest, src)?;
+        self.second.sxt(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn uxt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.uxt(ctx, cfg, dest, src)?;
+        self.second.uxt(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn str(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.str(ctx, cfg, src, mem)?;
+        self.second.str(ctx, cfg, src, mem)?;
+        Ok(())
+    }
+
+    fn ldr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ldr(ctx, cfg, dest, mem)?;
+        self.second.ldr(ctx, cfg, dest, mem)?;
+        Ok(())
+    }
+
+    fn stp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src1: &(dyn MemArg + '_),
+        src2: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.stp(ctx, cfg, src1, src2, mem)?;
+        self.second.stp(ctx, cfg, src1, src2, mem)?;
+        Ok(())
+    }
+
+    fn ldp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest1: &(dyn MemArg + '_),
+        dest2: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ldp(ctx, cfg, dest1, dest2, mem)?;
+        self.second.ldp(ctx, cfg, dest1, dest2, mem)?;
+        Ok(())
+    }
+
+    fn bl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.bl(ctx, cfg, target)?;
+        self.second.bl(ctx, cfg, target)?;
+        Ok(())
+    }
+
+    fn br(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.br(ctx, cfg, target)?;
+        self.second.br(ctx, cfg, target)?;
+        Ok(())
+    }
+
+    fn b(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.b(ctx, cfg, target)?;
+        self.second.b(ctx, cfg, target)?;
+        Ok(())
+    }
+
+    fn cmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cmp(ctx, cfg, a, b)?;
+        self.second.cmp(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn cmp_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.first.cmp_imm(ctx, cfg, a, imm)?;
+        self.second.cmp_imm(ctx, cfg, a, imm)?;
+        Ok(())
+    }
+
+    fn tst(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.tst(ctx, cfg, a, b)?;
+        self.second.tst(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn csel(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: ConditionCode,
+        dest: &(dyn MemArg + '_),
+        true_val: &(dyn MemArg + '_),
+        false_val: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.csel(ctx, cfg, cond, dest, true_val, false_val)?;
+        self.second
+            .csel(ctx, cfg, cond, dest, true_val, false_val)?;
+        Ok(())
+    }
+
+    fn cset(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: ConditionCode,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cset(ctx, cfg, cond, dest)?;
+        self.second.cset(ctx, cfg, cond, dest)?;
+        Ok(())
+    }
+
+    fn bcond(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: ConditionCode,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.bcond(ctx, cfg, cond, target)?;
+        self.second.bcond(ctx, cfg, cond, target)?;
+        Ok(())
+    }
+
+    fn and(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.and(ctx, cfg, dest, a, b)?;
+        self.second.and(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn orr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.orr(ctx, cfg, dest, a, b)?;
+        self.second.orr(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn eor(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.eor(ctx, cfg, dest, a, b)?;
+        self.second.eor(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.first.and_imm(ctx, cfg, dest, a, imm)?;
+        self.second.and_imm(ctx, cfg, dest, a, imm)?;
+        Ok(())
+    }
+
+    fn orr_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.first.orr_imm(ctx, cfg, dest, a, imm)?;
+        self.second.orr_imm(ctx, cfg, dest, a, imm)?;
+        Ok(())
+    }
+
+    fn eor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        self.first.eor_imm(ctx, cfg, dest, a, imm)?;
+        self.second.eor_imm(ctx, cfg, dest, a, imm)?;
+        Ok(())
+    }
+
+    fn lsl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.lsl(ctx, cfg, dest, a, b)?;
+        self.second.lsl(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn lsr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.lsr(ctx, cfg, dest, a, b)?;
+        self.second.lsr(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn asr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.asr(ctx, cfg, dest, a, b)?;
+        self.second.asr(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ror(ctx, cfg, dest, a, b)?;
+        self.second.ror(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn cls(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cls(ctx, cfg, dest, src)?;
+        self.second.cls(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn clz(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.clz(ctx, cfg, dest, src)?;
+        self.second.clz(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn rbit(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.rbit(ctx, cfg, dest, src)?;
+        self.second.rbit(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn rev(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.rev(ctx, cfg, dest, src)?;
+        self.second.rev(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn ubfx(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        lsb: u8,
+        width: u8,
+    ) -> Result<(), Self::Error> {
+        self.first.ubfx(ctx, cfg, dest, src, lsb, width)?;
+        self.second.ubfx(ctx, cfg, dest, src, lsb, width)?;
+        Ok(())
+    }
+
+    fn bfi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        lsb: u8,
+        width: u8,
+    ) -> Result<(), Self::Error> {
+        self.first.bfi(ctx, cfg, dest, src, lsb, width)?;
+        self.second.bfi(ctx, cfg, dest, src, lsb, width)?;
+        Ok(())
+    }
+
+    fn mvn(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mvn(ctx, cfg, dest, src)?;
+        self.second.mvn(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn neg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.neg(ctx, cfg, dest, src)?;
+        self.second.neg(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn negs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.negs(ctx, cfg, dest, src)?;
+        self.second.negs(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn adr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.adr(ctx, cfg, dest, src)?;
+        self.second.adr(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn ret(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.first.ret(ctx, cfg)?;
+        self.second.ret(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn mrs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        reg: SysReg,
+    ) -> Result<(), Self::Error> {
+        self.first.mrs(ctx, cfg, dest, reg)?;
+        self.second.mrs(ctx, cfg, dest, reg)?;
+        Ok(())
+    }
+
+    fn msr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        reg: SysReg,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.msr(ctx, cfg, reg, src)?;
+        self.second.msr(ctx, cfg, reg, src)?;
+        Ok(())
+    }
+
+    fn mrs_nzcv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mrs_nzcv(ctx, cfg, dest)?;
+        self.second.mrs_nzcv(ctx, cfg, dest)?;
+        Ok(())
+    }
+
+    fn msr_nzcv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.msr_nzcv(ctx, cfg, src)?;
+        self.second.msr_nzcv(ctx, cfg, src)?;
+        Ok(())
+    }
+
+    fn mrs_fpcr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mrs_fpcr(ctx, cfg, dest)?;
+        self.second.mrs_fpcr(ctx, cfg, dest)?;
+        Ok(())
+    }
+
+    fn msr_fpcr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.msr_fpcr(ctx, cfg, src)?;
+        self.second.msr_fpcr(ctx, cfg, src)?;
+        Ok(())
+    }
+
+    fn mrs_tpidr_el0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mrs_tpidr_el0(ctx, cfg, dest)?;
+        self.second.mrs_tpidr_el0(ctx, cfg, dest)?;
+        Ok(())
+    }
+
+    fn dmb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        barrier: Barrier,
+    ) -> Result<(), Self::Error> {
+        self.first.dmb(ctx, cfg, barrier)?;
+        self.second.dmb(ctx, cfg, barrier)?;
+        Ok(())
+    }
+
+    fn dsb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        barrier: Barrier,
+    ) -> Result<(), Self::Error> {
+        self.first.dsb(ctx, cfg, barrier)?;
+        self.second.dsb(ctx, cfg, barrier)?;
+        Ok(())
+    }
+
+    fn isb(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.first.isb(ctx, cfg)?;
+        self.second.isb(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn hint(&mut self, ctx: &mut Context, cfg: AArch64Arch, imm: u8) -> Result<(), Self::Error> {
+        self.first.hint(ctx, cfg, imm)?;
+        self.second.hint(ctx, cfg, imm)?;
+        Ok(())
+    }
+
+    fn yield_(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.first.yield_(ctx, cfg)?;
+        self.second.yield_(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn wfe(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.first.wfe(ctx, cfg)?;
+        self.second.wfe(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn sev(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.first.sev(ctx, cfg)?;
+        self.second.sev(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn prfm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        op: PrfOp,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.prfm(ctx, cfg, op, mem)?;
+        self.second.prfm(ctx, cfg, op, mem)?;
+        Ok(())
+    }
+
+    fn dc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        op: DcOp,
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.dc(ctx, cfg, op, addr)?;
+        self.second.dc(ctx, cfg, op, addr)?;
+        Ok(())
+    }
+
+    fn ic_ivau(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ic_ivau(ctx, cfg, addr)?;
+        self.second.ic_ivau(ctx, cfg, addr)?;
+        Ok(())
+    }
+
+    fn ldar(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ldar(ctx, cfg, dest, mem)?;
+        self.second.ldar(ctx, cfg, dest, mem)?;
+        Ok(())
+    }
+
+    fn stlr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.stlr(ctx, cfg, src, mem)?;
+        self.second.stlr(ctx, cfg, src, mem)?;
+        Ok(())
+    }
+
+    fn swpal(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.swpal(ctx, cfg, src, dest, mem)?;
+        self.second.swpal(ctx, cfg, src, dest, mem)?;
+        Ok(())
+    }
+
+    fn adrp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.adrp(ctx, cfg, dest, src)?;
+        self.second.adrp(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn add_lo12(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        sym: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.add_lo12(ctx, cfg, dest, src, sym)?;
+        self.second.add_lo12(ctx, cfg, dest, src, sym)?;
+        Ok(())
+    }
+
+    fn mov_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        self.first.mov_imm(ctx, cfg, dest, val)?;
+        self.second.mov_imm(ctx, cfg, dest, val)?;
+        Ok(())
+    }
+
+    fn mul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mul(ctx, cfg, dest, a, b)?;
+        self.second.mul(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn umulh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.umulh(ctx, cfg, dest, a, b)?;
+        self.second.umulh(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn smulh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.smulh(ctx, cfg, dest, a, b)?;
+        self.second.smulh(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn udiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.udiv(ctx, cfg, dest, a, b)?;
+        self.second.udiv(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn sdiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sdiv(ctx, cfg, dest, a, b)?;
+        self.second.sdiv(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fadd(ctx, cfg, dest, a, b)?;
+        self.second.fadd(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fsub(ctx, cfg, dest, a, b)?;
+        self.second.fsub(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fmul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmul(ctx, cfg, dest, a, b)?;
+        self.second.fmul(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fdiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fdiv(ctx, cfg, dest, a, b)?;
+        self.second.fdiv(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fmadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmadd(ctx, cfg, dest, a, b, c)?;
+        self.second.fmadd(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fmsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmsub(ctx, cfg, dest, a, b, c)?;
+        self.second.fmsub(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fnmadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fnmadd(ctx, cfg, dest, a, b, c)?;
+        self.second.fnmadd(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fnmsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fnmsub(ctx, cfg, dest, a, b, c)?;
+        self.second.fnmsub(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fcmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcmp(ctx, cfg, a, b)?;
+        self.second.fcmp(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn fmin(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmin(ctx, cfg, dest, a, b)?;
+        self.second.fmin(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fmax(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmax(ctx, cfg, dest, a, b)?;
+        self.second.fmax(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fsqrt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fsqrt(ctx, cfg, dest, src)?;
+        self.second.fsqrt(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fabs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fabs(ctx, cfg, dest, src)?;
+        self.second.fabs(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fneg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fneg(ctx, cfg, dest, src)?;
+        self.second.fneg(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcsel(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: ConditionCode,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcsel(ctx, cfg, cond, dest, a, b)?;
+        self.second.fcsel(ctx, cfg, cond, dest, a, b)?;
+        Ok(())
+    }
+
+    fn scvtf(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.scvtf(ctx, cfg, dest, src)?;
+        self.second.scvtf(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn ucvtf(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ucvtf(ctx, cfg, dest, src)?;
+        self.second.ucvtf(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvtzs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvtzs(ctx, cfg, dest, src)?;
+        self.second.fcvtzs(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvtzu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvtzu(ctx, cfg, dest, src)?;
+        self.second.fcvtzu(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt(ctx, cfg, dest, src)?;
+        self.second.fcvt(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fmov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmov(ctx, cfg, dest, src)?;
+        self.second.fmov(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn whilelt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.whilelt(ctx, cfg, dest, a, b)?;
+        self.second.whilelt(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn ptrue(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ptrue(ctx, cfg, dest)?;
+        self.second.ptrue(ctx, cfg, dest)?;
+        Ok(())
+    }
+
+    fn sve_inc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        elem: MemorySize,
+    ) -> Result<(), Self::Error> {
+        self.first.sve_inc(ctx, cfg, dest, elem)?;
+        self.second.sve_inc(ctx, cfg, dest, elem)?;
+        Ok(())
+    }
+
+    fn sve_ld1(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sve_ld1(ctx, cfg, dest, pg, mem)?;
+        self.second.sve_ld1(ctx, cfg, dest, pg, mem)?;
+        Ok(())
+    }
+
+    fn sve_st1(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sve_st1(ctx, cfg, src, pg, mem)?;
+        self.second.sve_st1(ctx, cfg, src, pg, mem)?;
+        Ok(())
+    }
+
+    fn sve_ld1d_gather(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        base: &(dyn MemArg + '_),
+        offsets: &(dyn MemArg + '_),
+        scaled: bool,
+    ) -> Result<(), Self::Error> {
+        self.first
+            .sve_ld1d_gather(ctx, cfg, dest, pg, base, offsets, scaled)?;
+        self.second
+            .sve_ld1d_gather(ctx, cfg, dest, pg, base, offsets, scaled)?;
+        Ok(())
+    }
+
+    fn sve_st1d_scatter(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        base: &(dyn MemArg + '_),
+        offsets: &(dyn MemArg + '_),
+        scaled: bool,
+    ) -> Result<(), Self::Error> {
+        self.first
+            .sve_st1d_scatter(ctx, cfg, src, pg, base, offsets, scaled)?;
+        self.second
+            .sve_st1d_scatter(ctx, cfg, src, pg, base, offsets, scaled)?;
+        Ok(())
+    }
+
+    fn sve_add(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sve_add(ctx, cfg, dest, a, b)?;
+        self.second.sve_add(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn sve_sub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sve_sub(ctx, cfg, dest, a, b)?;
+        self.second.sve_sub(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn sve_fadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sve_fadd(ctx, cfg, dest, a, b)?;
+        self.second.sve_fadd(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn sve_fsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sve_fsub(ctx, cfg, dest, a, b)?;
+        self.second.sve_fsub(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn sve_fmul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sve_fmul(ctx, cfg, dest, a, b)?;
+        self.second.sve_fmul(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn sve_fmla(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sve_fmla(ctx, cfg, dest, pg, a, b)?;
+        self.second.sve_fmla(ctx, cfg, dest, pg, a, b)?;
+        Ok(())
+    }
+
+    fn sve_dup(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sve_dup(ctx, cfg, dest, src)?;
+        self.second.sve_dup(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn db(&mut self, ctx: &mut Context, cfg: AArch64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.first.db(ctx, cfg, bytes)?;
+        self.second.db(ctx, cfg, bytes)?;
+        Ok(())
+    }
+
+    fn current_offset(&self) -> Option<usize> {
+        self.first
+            .current_offset()
+            .or_else(|| self.second.current_offset())
+    }
+
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        self.first.align_to(ctx, cfg, alignment)?;
+        self.second.align_to(ctx, cfg, alignment)?;
+        Ok(())
+    }
+
+    fn nop(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        self.first.nop(ctx, cfg)?;
+        self.second.nop(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn nops(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        self.first.nops(ctx, cfg, n_bytes)?;
+        self.second.nops(ctx, cfg, n_bytes)?;
+        Ok(())
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: AArch64Arch, pow2: u32) -> Result<(), Self::Error> {
+        self.first.align(ctx, cfg, pow2)?;
+        self.second.align(ctx, cfg, pow2)?;
+        Ok(())
+    }
+}
+
+/// Labels are cloned for the first writer and moved into the second.
+impl<A: Writer<L, Context>, B: Writer<L, Context>, L: Clone, Context> Writer<L, Context>
+    for TeeWriter<A, B>
+where
+    A::Error: From<B::Error>,
+{
+    fn set_label(&mut self, ctx: &mut Context, cfg: AArch64Arch, s: L) -> Result<(), Self::Error> {
+        self.first.set_label(ctx, cfg, s.clone())?;
+        self.second.set_label(ctx, cfg, s)?;
+        Ok(())
+    }
+
+    fn adr_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.first.adr_label(ctx, cfg, dest, label.clone())?;
+        self.second.adr_label(ctx, cfg, dest, label)?;
+        Ok(())
+    }
+
+    fn b_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.first.b_label(ctx, cfg, label.clone())?;
+        self.second.b_label(ctx, cfg, label)?;
+        Ok(())
+    }
+
+    fn bcond_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.first.bcond_label(ctx, cfg, cond, label.clone())?;
+        self.second.bcond_label(ctx, cfg, cond, label)?;
+        Ok(())
+    }
+
+    fn bcond_label_hinted(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: ConditionCode,
+        label: L,
+        hint: BranchHint,
+    ) -> Result<(), Self::Error> {
+        self.first
+            .bcond_label_hinted(ctx, cfg, cond, label.clone(), hint)?;
+        self.second
+            .bcond_label_hinted(ctx, cfg, cond, label, hint)?;
+        Ok(())
+    }
+
+    fn bl_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.first.bl_label(ctx, cfg, label.clone())?;
+        self.second.bl_label(ctx, cfg, label)?;
+        Ok(())
+    }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.first.dd_label(ctx, cfg, label.clone())?;
+        self.second.dd_label(ctx, cfg, label)?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::{self, Write};
+
+    /// A text sink that rejects every write.
+    struct Full;
+
+    impl Write for Full {
+        fn write_str(&mut self, _: &str) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    #[test]
+    fn test_labelled_call_reaches_both_writers() {
+        let cfg = AArch64Arch::default();
+        let (mut first, mut second) = (String::new(), String::new());
+        let mut tee = TeeWriter::new(&mut first as &mut dyn Write, &mut second as &mut dyn Write);
+        tee.bl_label(&mut (), cfg, 1u32).unwrap();
+        tee.set_label(&mut (), cfg, 1u32).unwrap();
+        assert_eq!(first, "bl 1\n1:\n");
+        assert_eq!(second, first);
+    }
+
+    #[test]
+    fn test_error_from_second_writer_is_returned() {
+        let cfg = AArch64Arch::default();
+        let (mut first, mut full) = (String::new(), Full);
+        let mut tee = TeeWriter::new(&mut first as &mut dyn Write, &mut full as &mut dyn Write);
+        assert_eq!(tee.ret(&mut (), cfg), Err(fmt::Error));
+        assert_eq!(tee.set_label(&mut (), cfg, 1u32), Err(fmt::Error));
+        // The first writer took each call before the second failed it.
+        assert_eq!(first, "ret\n1:\n");
+    }
+
+    #[cfg(feature = "bin-backend")]
+    #[test]
+    fn test_listing_and_machine_code_in_one_pass() {
+        use crate::out::bin::AArch64Writer;
+
+        let cfg = AArch64Arch::default();
+        let mut listing = String::new();
+        let mut tee = TeeWriter::new(&mut listing as &mut dyn Write, AArch64Writer::<u32>::new());
+        tee.ret(&mut (), cfg).unwrap();
+        tee.set_label(&mut (), cfg, 1u32).unwrap();
+        tee.ret(&mut (), cfg).unwrap();
+        tee.bl_label(&mut (), cfg, 1u32).unwrap();
+        assert_eq!(WriterCore::<()>::current_offset(&tee), Some(12));
+        let (_, binary) = tee.into_inner();
+        let (code, labels) = binary.into_parts();
+        assert_eq!(listing, "ret\n1:\nret\nbl 1\n");
+        assert_eq!(
+            code,
+            [
+                0xC0, 0x03, 0x5F, 0xD6, 0xC0, 0x03, 0x5F, 0xD6, 0xFF, 0xFF, 0xFF, 0x97
+            ]
+        );
+        assert_eq!(labels[&1], 4);
+    }
+}
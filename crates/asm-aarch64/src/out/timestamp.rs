@@ -0,0 +1,54 @@
+//! Reads of the system counter.
+//!
+//! [`ReadTimestamp::read_timestamp`] reads a 64-bit counter that increases
+//! at a constant rate into a register, for timers and profiling:
+//!
+//! ```ignore
+//! writer.read_timestamp(ctx, cfg, &Reg(0))?;
+//! // mrs x0, cntvct_el0
+//! ```
+//!
+//! The counter is the virtual count `cntvct_el0`, which Linux lets EL0
+//! read; `cntfrq_el0` holds its frequency in Hz. The read may be performed
+//! early, before the instructions ahead of it; emit an
+//! [`isb`](WriterCore::isb) first to time a region precisely. The x86-64
+//! crate reads the time-stamp counter with `rdtsc` and the RISC-V crate
+//! reads `time`.
+
+use crate::out::WriterCore;
+use crate::out::arg::MemArg;
+use crate::{AArch64Arch, SysReg};
+
+/// Emits reads of the system counter.
+///
+/// Implemented for every [`WriterCore`].
+pub trait ReadTimestamp<Context>: WriterCore<Context> {
+    /// Reads the 64-bit virtual counter into the register `dest`.
+    fn read_timestamp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.mrs(ctx, cfg, dest, SysReg::CntvctEl0)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> ReadTimestamp<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_reads_the_virtual_counter() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        w.read_timestamp(&mut (), AArch64Arch::default(), &Reg(3))
+            .unwrap();
+        assert_eq!(output, "mrs x3, cntvct_el0\n");
+    }
+}
@@ -0,0 +1,1739 @@
+//! Instruction validation against the arch configuration.
+//!
+//! Writers emit whatever they are asked to: a text writer happily prints
+//! `ldr x0, [x1, x2]!`, and the mistake only surfaces when the assembler
+//! rejects it. [`ValidatingWriter`] sits in front of another writer and
+//! checks each instruction against the [`AArch64Arch`] it is emitted for,
+//! returning an [`EmitError`] instead of forwarding it:
+//!
+//! - register operands must name one of the 32 registers;
+//! - pre- and post-indexed memory operands take no index register;
+//! - instructions from an extension need its flag: `swpal` needs
+//!   [`lse`](AArch64Arch::lse), the SVE instructions need
+//!   [`sve`](AArch64Arch::sve), and floating-point arithmetic on 16-bit
+//!   operands needs [`fp16`](AArch64Arch::fp16);
+//! - SVE governing predicates are `p0` to `p7`, and predicate destinations
+//!   `p0` to `p15`;
+//! - `hint` numbers are below 128, and `prfm` cache levels 1 to 3;
+//! - `dc` and `ic ivau` take the address in a register;
+//! - `msr` does not write registers that are read-only at EL0, such as
+//!   `cntvct_el0`, and custom system registers have an `op0` of 2 or 3.
+//!
+//! `bti`, `paciasp`, `autiasp`, `yield`, `wfe` and `sev` are in the hint
+//! space and execute as `nop`s on cores without the feature, so they are
+//! always accepted.
+//!
+//! Place it below [`DesugaringWriter`](crate::desugar::DesugaringWriter) to
+//! check the lowered output, or above it to check what callers request.
+
+use core::ops::{Deref, DerefMut};
+
+use portal_pc_asm_common::types::mem::MemorySize;
+
+use crate::out::arg::{AddressingMode, ArgKind, MemArg, MemArgKind};
+use crate::out::error::EmitError;
+use crate::out::{Writer, WriterCore};
+use crate::{AArch64Arch, Barrier, DcOp, PredicateReg, PrfOp, SysReg};
+
+/// Rejects instructions the arch configuration does not support.
+///
+/// Every instruction is checked against the `cfg` it is emitted with before
+/// being forwarded to the wrapped writer; rejected instructions are not
+/// forwarded.
+pub struct ValidatingWriter<W> {
+    writer: W,
+}
+
+impl<W> ValidatingWriter<W> {
+    /// Wraps `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W> Deref for ValidatingWriter<W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        &self.writer
+    }
+}
+
+impl<W> DerefMut for ValidatingWriter<W> {
+    fn deref_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+/// Returns the extension `mnemonic` needs with these `operands` when `cfg`
+/// does not enable it.
+fn missing_extension(
+    cfg: AArch64Arch,
+    mnemonic: &str,
+    operands: &[&(dyn MemArg + '_)],
+) -> Option<&'static str> {
+    match mnemonic {
+        "swpal" if !cfg.lse => Some("LSE"),
+        "whilelt" | "ptrue" if !cfg.sve => Some("SVE"),
+        m if m.starts_with("sve_") && !cfg.sve => Some("SVE"),
+        // Conversions between half and single or double precision are in the
+        // base ISA; arithmetic on half-precision registers is not.
+        "fadd" | "fsub" | "fmul" | "fdiv" | "fmadd" | "fmsub" | "fnmadd" | "fnmsub" | "fcmp"
+        | "fmin" | "fmax" | "fsqrt" | "fabs" | "fneg" | "fcsel" | "scvtf" | "ucvtf" | "fcvtzs"
+        | "fcvtzu" | "fmov"
+            if !cfg.fp16 && operands.iter().any(|op| is_half(op.concrete_mem_kind())) =>
+        {
+            Some("FP16")
+        }
+        _ => None,
+    }
+}
+
+/// Returns `true` for a 16-bit register operand.
+fn is_half(op: MemArgKind<ArgKind>) -> bool {
+    matches!(
+        op,
+        MemArgKind::NoMem(ArgKind::Reg {
+            size: MemorySize::_16,
+            ..
+        })
+    )
+}
+
+/// Checks that a governing predicate is one of `p0` to `p7`, the only ones
+/// a predicated instruction can encode.
+fn check_governing(mnemonic: &'static str, pg: PredicateReg) -> Result<(), EmitError> {
+    if pg.0 >= 8 {
+        return Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "governing predicate must be p0-p7",
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a predicate destination names one of the 16 predicate
+/// registers.
+fn check_predicate(mnemonic: &'static str, op: &(dyn MemArg + '_)) -> Result<(), EmitError> {
+    match op.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) if reg.0 < 16 => Ok(()),
+        _ => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "operand must be a predicate register p0-p15",
+        }),
+    }
+}
+
+/// Checks that a cache maintenance operand is a register holding the
+/// address, since DC and IC have no addressing modes.
+fn check_address_reg(mnemonic: &'static str, op: &(dyn MemArg + '_)) -> Result<(), EmitError> {
+    match op.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Reg { .. }) => Ok(()),
+        _ => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "operand must be a register holding the address",
+        }),
+    }
+}
+
+/// Checks that `reg` is a system register MRS and MSR can encode, and that
+/// code at EL0 may write it when `write` is set.
+fn check_sysreg(mnemonic: &'static str, reg: SysReg, write: bool) -> Result<(), EmitError> {
+    if reg.encoding() >> 14 < 2 {
+        return Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "system register op0 must be 2 or 3",
+        });
+    }
+    if write && reg.is_read_only() {
+        return Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "system register is read-only at EL0",
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a register operand names one of the 32 registers.
+fn check_arg(mnemonic: &'static str, arg: ArgKind) -> Result<(), EmitError> {
+    match arg {
+        ArgKind::Reg { reg, .. } if reg.0 >= 32 => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "register number out of range",
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Checks one instruction against `cfg`.
+///
+/// `mnemonic` is the name of the [`WriterCore`] or [`Writer`] method.
+pub fn check(
+    cfg: AArch64Arch,
+    mnemonic: &'static str,
+    operands: &[&(dyn MemArg + '_)],
+) -> Result<(), EmitError> {
+    if let Some(extension) = missing_extension(cfg, mnemonic, operands) {
+        return Err(EmitError::ExtensionNotEnabled {
+            mnemonic,
+            extension,
+        });
+    }
+    for op in operands {
+        match op.concrete_mem_kind() {
+            MemArgKind::NoMem(arg) => check_arg(mnemonic, arg)?,
+            MemArgKind::Mem {
+                base, offset, mode, ..
+            } => {
+                check_arg(mnemonic, base)?;
+                if let Some((index, _)) = offset {
+                    if mode != AddressingMode::Offset {
+                        return Err(EmitError::InvalidOperands {
+                            mnemonic,
+                            reason: "writeback addressing takes no index register",
+                        });
+                    }
+                    check_arg(mnemonic, index)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+impl<W: WriterCore<Context>, Context> WriterCore<Context> for ValidatingWriter<W>
+where
+    W::Error: From<EmitError>,
+{
+    type Error = W::Error;
+
+    fn brk(&mut self, ctx: &mut Context, cfg: AArch64Arch, imm: u16) -> Result<(), Self::Error> {
+        check(cfg, "brk", &[])?;
+        self.writer.brk(ctx, cfg, imm)
+    }
+
+    fn svc(&mut self, ctx: &mut Context, cfg: AArch64Arch, imm: u16) -> Result<(), Self::Error> {
+        check(cfg, "svc", &[])?;
+        self.writer.svc(ctx, cfg, imm)
+    }
+
+    fn bti(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: crate::BtiTarget,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bti", &[])?;
+        self.writer.bti(ctx, cfg, target)
+    }
+
+    fn paciasp(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        check(cfg, "paciasp", &[])?;
+        self.writer.paciasp(ctx, cfg)
+    }
+
+    fn autiasp(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        check(cfg, "autiasp", &[])?;
+        self.writer.autiasp(ctx, cfg)
+    }
+
+    fn mov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mov", &[dest, src])?;
+        self.writer.mov(ctx, cfg, dest, src)
+    }
+
+    fn str(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "str", &[src, mem])?;
+        self.writer.str(ctx, cfg, src, mem)
+    }
+
+    fn ldr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ldr", &[dest, mem])?;
+        self.writer.ldr(ctx, cfg, dest, mem)
+    }
+
+    fn stp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src1: &(dyn MemArg + '_),
+        src2: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "stp", &[src1, src2, mem])?;
+        self.writer.stp(ctx, cfg, src1, src2, mem)
+    }
+
+    fn ldp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest1: &(dyn MemArg + '_),
+        dest2: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ldp", &[dest1, dest2, mem])?;
+        self.writer.ldp(ctx, cfg, dest1, dest2, mem)
+    }
+
+    fn bl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bl", &[target])?;
+        self.writer.bl(ctx, cfg, target)
+    }
+
+    fn br(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "br", &[target])?;
+        self.writer.br(ctx, cfg, target)
+    }
+
+    fn b(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "b", &[target])?;
+        self.writer.b(ctx, cfg, target)
+    }
+
+    fn cmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cmp", &[a, b])?;
+        self.writer.cmp(ctx, cfg, a, b)
+    }
+
+    fn tst(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "tst", &[a, b])?;
+        self.writer.tst(ctx, cfg, a, b)
+    }
+
+    fn csel(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        dest: &(dyn MemArg + '_),
+        true_val: &(dyn MemArg + '_),
+        false_val: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "csel", &[dest, true_val, false_val])?;
+        self.writer.csel(ctx, cfg, cond, dest, true_val, false_val)
+    }
+
+    fn cset(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cset", &[dest])?;
+        self.writer.cset(ctx, cfg, cond, dest)
+    }
+
+    fn bcond(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bcond", &[target])?;
+        self.writer.bcond(ctx, cfg, cond, target)
+    }
+
+    fn adr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "adr", &[dest, src])?;
+        self.writer.adr(ctx, cfg, dest, src)
+    }
+
+    fn ret(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        check(cfg, "ret", &[])?;
+        self.writer.ret(ctx, cfg)
+    }
+
+    fn mov_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mov_imm", &[dest])?;
+        self.writer.mov_imm(ctx, cfg, dest, val)
+    }
+
+    fn mul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mul", &[dest, a, b])?;
+        self.writer.mul(ctx, cfg, dest, a, b)
+    }
+
+    fn umulh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "umulh", &[dest, a, b])?;
+        self.writer.umulh(ctx, cfg, dest, a, b)
+    }
+
+    fn smulh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "smulh", &[dest, a, b])?;
+        self.writer.smulh(ctx, cfg, dest, a, b)
+    }
+
+    fn udiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "udiv", &[dest, a, b])?;
+        self.writer.udiv(ctx, cfg, dest, a, b)
+    }
+
+    fn sdiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sdiv", &[dest, a, b])?;
+        self.writer.sdiv(ctx, cfg, dest, a, b)
+    }
+
+    fn and(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "and", &[dest, a, b])?;
+        self.writer.and(ctx, cfg, dest, a, b)
+    }
+
+    fn orr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "orr", &[dest, a, b])?;
+        self.writer.orr(ctx, cfg, dest, a, b)
+    }
+
+    fn eor(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "eor", &[dest, a, b])?;
+        self.writer.eor(ctx, cfg, dest, a, b)
+    }
+
+    fn lsl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lsl", &[dest, a, b])?;
+        self.writer.lsl(ctx, cfg, dest, a, b)
+    }
+
+    fn lsr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lsr", &[dest, a, b])?;
+        self.writer.lsr(ctx, cfg, dest, a, b)
+    }
+
+    fn asr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "asr", &[dest, a, b])?;
+        self.writer.asr(ctx, cfg, dest, a, b)
+    }
+
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ror", &[dest, a, b])?;
+        self.writer.ror(ctx, cfg, dest, a, b)
+    }
+
+    fn cls(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cls", &[dest, src])?;
+        self.writer.cls(ctx, cfg, dest, src)
+    }
+
+    fn clz(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "clz", &[dest, src])?;
+        self.writer.clz(ctx, cfg, dest, src)
+    }
+
+    fn rbit(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "rbit", &[dest, src])?;
+        self.writer.rbit(ctx, cfg, dest, src)
+    }
+
+    fn rev(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "rev", &[dest, src])?;
+        self.writer.rev(ctx, cfg, dest, src)
+    }
+
+    fn ubfx(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        lsb: u8,
+        width: u8,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ubfx", &[dest, src])?;
+        self.writer.ubfx(ctx, cfg, dest, src, lsb, width)
+    }
+
+    fn bfi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        lsb: u8,
+        width: u8,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bfi", &[dest, src])?;
+        self.writer.bfi(ctx, cfg, dest, src, lsb, width)
+    }
+
+    fn sub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sub", &[dest, a, b])?;
+        self.writer.sub(ctx, cfg, dest, a, b)
+    }
+
+    fn add(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "add", &[dest, a, b])?;
+        self.writer.add(ctx, cfg, dest, a, b)
+    }
+
+    fn adds(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "adds", &[dest, a, b])?;
+        self.writer.adds(ctx, cfg, dest, a, b)
+    }
+
+    fn subs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "subs", &[dest, a, b])?;
+        self.writer.subs(ctx, cfg, dest, a, b)
+    }
+
+    fn adcs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "adcs", &[dest, a, b])?;
+        self.writer.adcs(ctx, cfg, dest, a, b)
+    }
+
+    fn sbcs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sbcs", &[dest, a, b])?;
+        self.writer.sbcs(ctx, cfg, dest, a, b)
+    }
+
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "add_imm", &[dest, a])?;
+        self.writer.add_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sub_imm", &[dest, a])?;
+        self.writer.sub_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "and_imm", &[dest, a])?;
+        self.writer.and_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn orr_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "orr_imm", &[dest, a])?;
+        self.writer.orr_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn eor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: u64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "eor_imm", &[dest, a])?;
+        self.writer.eor_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn cmp_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cmp_imm", &[a])?;
+        self.writer.cmp_imm(ctx, cfg, a, imm)
+    }
+
+    fn add_uxtw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "add_uxtw", &[dest, a, b])?;
+        self.writer.add_uxtw(ctx, cfg, dest, a, b)
+    }
+
+    fn add_lsl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        shift: u8,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "add_lsl", &[dest, a, b])?;
+        self.writer.add_lsl(ctx, cfg, dest, a, b, shift)
+    }
+
+    fn sxt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sxt", &[dest, src])?;
+        self.writer.sxt(ctx, cfg, dest, src)
+    }
+
+    fn uxt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "uxt", &[dest, src])?;
+        self.writer.uxt(ctx, cfg, dest, src)
+    }
+
+    fn mvn(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mvn", &[dest, src])?;
+        self.writer.mvn(ctx, cfg, dest, src)
+    }
+
+    fn neg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "neg", &[dest, src])?;
+        self.writer.neg(ctx, cfg, dest, src)
+    }
+
+    fn negs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "negs", &[dest, src])?;
+        self.writer.negs(ctx, cfg, dest, src)
+    }
+
+    fn fadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fadd", &[dest, a, b])?;
+        self.writer.fadd(ctx, cfg, dest, a, b)
+    }
+
+    fn fsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fsub", &[dest, a, b])?;
+        self.writer.fsub(ctx, cfg, dest, a, b)
+    }
+
+    fn fmul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmul", &[dest, a, b])?;
+        self.writer.fmul(ctx, cfg, dest, a, b)
+    }
+
+    fn fdiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fdiv", &[dest, a, b])?;
+        self.writer.fdiv(ctx, cfg, dest, a, b)
+    }
+
+    fn fmadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmadd", &[dest, a, b, c])?;
+        self.writer.fmadd(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fmsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmsub", &[dest, a, b, c])?;
+        self.writer.fmsub(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fnmadd", &[dest, a, b, c])?;
+        self.writer.fnmadd(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fnmsub", &[dest, a, b, c])?;
+        self.writer.fnmsub(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fcmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcmp", &[a, b])?;
+        self.writer.fcmp(ctx, cfg, a, b)
+    }
+
+    fn fmin(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmin", &[dest, a, b])?;
+        self.writer.fmin(ctx, cfg, dest, a, b)
+    }
+
+    fn fmax(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmax", &[dest, a, b])?;
+        self.writer.fmax(ctx, cfg, dest, a, b)
+    }
+
+    fn fsqrt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fsqrt", &[dest, src])?;
+        self.writer.fsqrt(ctx, cfg, dest, src)
+    }
+
+    fn fabs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fabs", &[dest, src])?;
+        self.writer.fabs(ctx, cfg, dest, src)
+    }
+
+    fn fneg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fneg", &[dest, src])?;
+        self.writer.fneg(ctx, cfg, dest, src)
+    }
+
+    fn fcsel(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcsel", &[dest, a, b])?;
+        self.writer.fcsel(ctx, cfg, cond, dest, a, b)
+    }
+
+    fn scvtf(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "scvtf", &[dest, src])?;
+        self.writer.scvtf(ctx, cfg, dest, src)
+    }
+
+    fn ucvtf(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ucvtf", &[dest, src])?;
+        self.writer.ucvtf(ctx, cfg, dest, src)
+    }
+
+    fn fcvtzs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvtzs", &[dest, src])?;
+        self.writer.fcvtzs(ctx, cfg, dest, src)
+    }
+
+    fn fcvtzu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvtzu", &[dest, src])?;
+        self.writer.fcvtzu(ctx, cfg, dest, src)
+    }
+
+    fn fcvt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt", &[dest, src])?;
+        self.writer.fcvt(ctx, cfg, dest, src)
+    }
+
+    fn fmov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmov", &[dest, src])?;
+        self.writer.fmov(ctx, cfg, dest, src)
+    }
+
+    fn whilelt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "whilelt", &[dest, a, b])?;
+        check_predicate("whilelt", dest)?;
+        self.writer.whilelt(ctx, cfg, dest, a, b)
+    }
+
+    fn ptrue(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ptrue", &[dest])?;
+        check_predicate("ptrue", dest)?;
+        self.writer.ptrue(ctx, cfg, dest)
+    }
+
+    fn sve_inc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        elem: MemorySize,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_inc", &[dest])?;
+        self.writer.sve_inc(ctx, cfg, dest, elem)
+    }
+
+    fn sve_ld1(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_ld1", &[dest, mem])?;
+        check_governing("sve_ld1", pg)?;
+        self.writer.sve_ld1(ctx, cfg, dest, pg, mem)
+    }
+
+    fn sve_st1(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_st1", &[src, mem])?;
+        check_governing("sve_st1", pg)?;
+        self.writer.sve_st1(ctx, cfg, src, pg, mem)
+    }
+
+    fn sve_ld1d_gather(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        base: &(dyn MemArg + '_),
+        offsets: &(dyn MemArg + '_),
+        scaled: bool,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_ld1d_gather", &[dest, base, offsets])?;
+        check_governing("sve_ld1d_gather", pg)?;
+        self.writer
+            .sve_ld1d_gather(ctx, cfg, dest, pg, base, offsets, scaled)
+    }
+
+    fn sve_st1d_scatter(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        base: &(dyn MemArg + '_),
+        offsets: &(dyn MemArg + '_),
+        scaled: bool,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_st1d_scatter", &[src, base, offsets])?;
+        check_governing("sve_st1d_scatter", pg)?;
+        self.writer
+            .sve_st1d_scatter(ctx, cfg, src, pg, base, offsets, scaled)
+    }
+
+    fn sve_add(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_add", &[dest, a, b])?;
+        self.writer.sve_add(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_sub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_sub", &[dest, a, b])?;
+        self.writer.sve_sub(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_fadd", &[dest, a, b])?;
+        self.writer.sve_fadd(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_fsub", &[dest, a, b])?;
+        self.writer.sve_fsub(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fmul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_fmul", &[dest, a, b])?;
+        self.writer.sve_fmul(ctx, cfg, dest, a, b)
+    }
+
+    fn sve_fmla(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        pg: PredicateReg,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_fmla", &[dest, a, b])?;
+        check_governing("sve_fmla", pg)?;
+        self.writer.sve_fmla(ctx, cfg, dest, pg, a, b)
+    }
+
+    fn sve_dup(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sve_dup", &[dest, src])?;
+        self.writer.sve_dup(ctx, cfg, dest, src)
+    }
+
+    fn db(&mut self, ctx: &mut Context, cfg: AArch64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        check(cfg, "db", &[])?;
+        self.writer.db(ctx, cfg, bytes)
+    }
+
+    fn current_offset(&self) -> Option<usize> {
+        self.writer.current_offset()
+    }
+
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "align_to", &[])?;
+        self.writer.align_to(ctx, cfg, alignment)
+    }
+
+    fn nop(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        check(cfg, "nop", &[])?;
+        self.writer.nop(ctx, cfg)
+    }
+
+    fn nops(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "nops", &[])?;
+        self.writer.nops(ctx, cfg, n_bytes)
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: AArch64Arch, pow2: u32) -> Result<(), Self::Error> {
+        check(cfg, "align", &[])?;
+        self.writer.align(ctx, cfg, pow2)
+    }
+
+    fn mrs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        reg: SysReg,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mrs", &[dest])?;
+        check_sysreg("mrs", reg, false)?;
+        self.writer.mrs(ctx, cfg, dest, reg)
+    }
+
+    fn msr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        reg: SysReg,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "msr", &[src])?;
+        check_sysreg("msr", reg, true)?;
+        self.writer.msr(ctx, cfg, reg, src)
+    }
+
+    fn mrs_nzcv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mrs_nzcv", &[dest])?;
+        self.writer.mrs_nzcv(ctx, cfg, dest)
+    }
+
+    fn msr_nzcv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "msr_nzcv", &[src])?;
+        self.writer.msr_nzcv(ctx, cfg, src)
+    }
+
+    fn mrs_fpcr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mrs_fpcr", &[dest])?;
+        self.writer.mrs_fpcr(ctx, cfg, dest)
+    }
+
+    fn msr_fpcr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "msr_fpcr", &[src])?;
+        self.writer.msr_fpcr(ctx, cfg, src)
+    }
+
+    fn mrs_tpidr_el0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mrs_tpidr_el0", &[dest])?;
+        self.writer.mrs_tpidr_el0(ctx, cfg, dest)
+    }
+
+    fn dmb(&mut self, ctx: &mut Context, cfg: AArch64Arch, barrier: Barrier) -> Result<(), Self::Error> {
+        check(cfg, "dmb", &[])?;
+        self.writer.dmb(ctx, cfg, barrier)
+    }
+
+    fn dsb(&mut self, ctx: &mut Context, cfg: AArch64Arch, barrier: Barrier) -> Result<(), Self::Error> {
+        check(cfg, "dsb", &[])?;
+        self.writer.dsb(ctx, cfg, barrier)
+    }
+
+    fn isb(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        check(cfg, "isb", &[])?;
+        self.writer.isb(ctx, cfg)
+    }
+
+    fn hint(&mut self, ctx: &mut Context, cfg: AArch64Arch, imm: u8) -> Result<(), Self::Error> {
+        check(cfg, "hint", &[])?;
+        if imm >= 128 {
+            return Err(EmitError::InvalidOperands {
+                mnemonic: "hint",
+                reason: "hint number out of range",
+            });
+        }
+        self.writer.hint(ctx, cfg, imm)
+    }
+
+    fn yield_(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        check(cfg, "yield", &[])?;
+        self.writer.yield_(ctx, cfg)
+    }
+
+    fn wfe(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        check(cfg, "wfe", &[])?;
+        self.writer.wfe(ctx, cfg)
+    }
+
+    fn sev(&mut self, ctx: &mut Context, cfg: AArch64Arch) -> Result<(), Self::Error> {
+        check(cfg, "sev", &[])?;
+        self.writer.sev(ctx, cfg)
+    }
+
+    fn prfm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        op: PrfOp,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "prfm", &[mem])?;
+        if !(1..=3).contains(&op.level) {
+            return Err(EmitError::InvalidOperands {
+                mnemonic: "prfm",
+                reason: "prefetch cache level must be 1-3",
+            });
+        }
+        self.writer.prfm(ctx, cfg, op, mem)
+    }
+
+    fn dc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        op: DcOp,
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "dc", &[addr])?;
+        check_address_reg("dc", addr)?;
+        self.writer.dc(ctx, cfg, op, addr)
+    }
+
+    fn ic_ivau(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ic_ivau", &[addr])?;
+        check_address_reg("ic_ivau", addr)?;
+        self.writer.ic_ivau(ctx, cfg, addr)
+    }
+
+    fn ldar(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ldar", &[dest, mem])?;
+        self.writer.ldar(ctx, cfg, dest, mem)
+    }
+
+    fn stlr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "stlr", &[src, mem])?;
+        self.writer.stlr(ctx, cfg, src, mem)
+    }
+
+    fn swpal(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        src: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "swpal", &[src, dest, mem])?;
+        self.writer.swpal(ctx, cfg, src, dest, mem)
+    }
+
+    fn adrp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "adrp", &[dest, src])?;
+        self.writer.adrp(ctx, cfg, dest, src)
+    }
+
+    fn add_lo12(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        sym: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "add", &[dest, src, sym])?;
+        self.writer.add_lo12(ctx, cfg, dest, src, sym)
+    }
+}
+
+impl<W: Writer<L, Context>, L, Context> Writer<L, Context> for ValidatingWriter<W>
+where
+    W::Error: From<EmitError>,
+{
+    fn set_label(&mut self, ctx: &mut Context, cfg: AArch64Arch, s: L) -> Result<(), Self::Error> {
+        check(cfg, "set_label", &[])?;
+        self.writer.set_label(ctx, cfg, s)
+    }
+
+    fn adr_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "adr_label", &[dest])?;
+        self.writer.adr_label(ctx, cfg, dest, label)
+    }
+
+    fn b_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "b_label", &[])?;
+        self.writer.b_label(ctx, cfg, label)
+    }
+
+    fn bcond_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        cond: crate::ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bcond_label", &[])?;
+        self.writer.bcond_label(ctx, cfg, cond, label)
+    }
+
+    fn bl_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bl_label", &[])?;
+        self.writer.bl_label(ctx, cfg, label)
+    }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: AArch64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "dd_label", &[])?;
+        self.writer.dd_label(ctx, cfg, label)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::RegisterClass;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    fn mem(index: Option<Reg>, mode: AddressingMode) -> MemArgKind<ArgKind> {
+        let reg = |reg| ArgKind::Reg {
+            reg,
+            size: MemorySize::_64,
+        };
+        MemArgKind::Mem {
+            base: reg(Reg(1)),
+            offset: index.map(|index| (reg(index), 0)),
+            disp: 8,
+            size: MemorySize::_64,
+            reg_class: RegisterClass::Gpr,
+            mode,
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_operands() {
+        let cfg = AArch64Arch::default();
+        let x0 = Reg(0);
+        assert!(check(cfg, "ldr", &[&x0, &mem(None, AddressingMode::PreIndex)]).is_ok());
+        assert!(
+            check(
+                cfg,
+                "ldr",
+                &[&x0, &mem(Some(Reg(2)), AddressingMode::Offset)]
+            )
+            .is_ok()
+        );
+        assert_eq!(
+            check(
+                cfg,
+                "ldr",
+                &[&x0, &mem(Some(Reg(2)), AddressingMode::PreIndex)]
+            ),
+            Err(EmitError::InvalidOperands {
+                mnemonic: "ldr",
+                reason: "writeback addressing takes no index register",
+            })
+        );
+        assert!(check(cfg, "mov", &[&x0, &Reg(32)]).is_err());
+
+        let mut output = String::new();
+        let mut writer = ValidatingWriter::new(&mut output as &mut dyn Write);
+        assert!(writer.mov(&mut (), cfg, &x0, &Reg(32)).is_err());
+        writer.mov(&mut (), cfg, &x0, &Reg(2)).unwrap();
+        assert_eq!(output, "mov x0, x2\n");
+    }
+
+    #[test]
+    fn test_msr_rejects_read_only_system_registers() {
+        let cfg = AArch64Arch::default();
+        let mut output = String::new();
+        let mut writer = ValidatingWriter::new(&mut output as &mut dyn Write);
+        assert!(
+            writer
+                .msr(&mut (), cfg, SysReg::CntvctEl0, &Reg(0))
+                .is_err()
+        );
+        assert!(
+            writer
+                .mrs(&mut (), cfg, &Reg(0), SysReg::Custom(0))
+                .is_err()
+        );
+        writer
+            .mrs(&mut (), cfg, &Reg(0), SysReg::CntvctEl0)
+            .unwrap();
+        writer.msr(&mut (), cfg, SysReg::Fpsr, &Reg(1)).unwrap();
+        // MIDR_EL1, readable at EL1
+        writer
+            .mrs(&mut (), cfg, &Reg(2), SysReg::Custom(0xC000))
+            .unwrap();
+        assert_eq!(
+            output,
+            "mrs x0, cntvct_el0\nmsr fpsr, x1\nmrs x2, s3_0_c0_c0_0\n"
+        );
+    }
+
+    #[test]
+    fn test_rejects_disabled_extensions() {
+        let base = AArch64Arch::default();
+        let full = base.with_lse(true).with_fp16(true);
+        let (x0, x1, x2) = (Reg(0), Reg(1), Reg(2));
+        assert_eq!(
+            check(base, "swpal", &[&x0, &x1, &x2]),
+            Err(EmitError::ExtensionNotEnabled {
+                mnemonic: "swpal",
+                extension: "LSE",
+            })
+        );
+        assert!(check(full, "swpal", &[&x0, &x1, &x2]).is_ok());
+
+        let h = |reg| {
+            MemArgKind::NoMem(ArgKind::Reg {
+                reg: Reg(reg),
+                size: MemorySize::_16,
+            })
+        };
+        let s = |reg| {
+            MemArgKind::NoMem(ArgKind::Reg {
+                reg: Reg(reg),
+                size: MemorySize::_32,
+            })
+        };
+        assert!(check(base, "fadd", &[&s(0), &s(1), &s(2)]).is_ok());
+        assert_eq!(
+            check(base, "fadd", &[&h(0), &h(1), &h(2)]),
+            Err(EmitError::ExtensionNotEnabled {
+                mnemonic: "fadd",
+                extension: "FP16",
+            })
+        );
+        assert!(check(full, "fadd", &[&h(0), &h(1), &h(2)]).is_ok());
+        // Widening a half-precision value is in the base ISA.
+        assert!(check(base, "fcvt", &[&s(0), &h(1)]).is_ok());
+
+        let sve = base.with_sve(0);
+        assert_eq!(
+            check(base, "sve_fadd", &[&x0, &x1, &x2]),
+            Err(EmitError::ExtensionNotEnabled {
+                mnemonic: "sve_fadd",
+                extension: "SVE",
+            })
+        );
+        assert!(check(sve, "sve_fadd", &[&x0, &x1, &x2]).is_ok());
+
+        let mut output = String::new();
+        let mut writer = ValidatingWriter::new(&mut output as &mut dyn Write);
+        assert!(writer.whilelt(&mut (), base, &x0, &x1, &x2).is_err());
+        assert!(writer.ptrue(&mut (), sve, &Reg(16)).is_err());
+        assert!(
+            writer
+                .sve_fmla(&mut (), sve, &x0, PredicateReg(8), &x1, &x2)
+                .is_err()
+        );
+        writer
+            .sve_fmla(&mut (), sve, &x0, PredicateReg(7), &x1, &x2)
+            .unwrap();
+        assert_eq!(output, "fmla z0.d, p7/m, z1.d, z2.d\n");
+    }
+}
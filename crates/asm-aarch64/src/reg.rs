@@ -94,16 +94,52 @@ impl AArch64Reg for Reg {
         let idx = (self.0 as usize) % 32;
 
         match opts.reg_class {
-            crate::RegisterClass::Simd => {
-                // For SIMD/FP registers, use v registers with element size qualifiers
-                let suffix = match &opts.size {
-                    MemorySize::_8 => ".b",  // byte element
-                    MemorySize::_16 => ".h", // halfword element
-                    MemorySize::_32 => ".s", // single precision
-                    MemorySize::_64 => ".d", // double precision
-                    _ => ".d",               // default to double
-                };
-                write!(f, "{}{}", VREG_NAMES[idx], suffix)
+            crate::RegisterClass::Simd => match (&opts.size, opts.simd_view) {
+                // A full vector, by element count
+                (MemorySize::_8, SimdRegView::Arrangement) => write!(f, "{}.16b", VREG_NAMES[idx]),
+                (MemorySize::_16, SimdRegView::Arrangement) => write!(f, "{}.8h", VREG_NAMES[idx]),
+                (MemorySize::_32, SimdRegView::Arrangement) => write!(f, "{}.4s", VREG_NAMES[idx]),
+                (MemorySize::_64, SimdRegView::Arrangement) => write!(f, "{}.2d", VREG_NAMES[idx]),
+                // Full-width vectors: q for 128-bit NEON, z for wider SVE vectors
+                (MemorySize::_128, _) => write!(f, "q{idx}"),
+                (MemorySize::_256 | MemorySize::_512, _) => write!(f, "z{idx}"),
+                (size, SimdRegView::Scalar) => {
+                    let prefix = match size {
+                        MemorySize::_8 => "b",
+                        MemorySize::_16 => "h",
+                        MemorySize::_32 => "s",
+                        _ => "d",
+                    };
+                    write!(f, "{prefix}{idx}")
+                }
+                (size, _) => {
+                    // For SIMD/FP registers, use v registers with element size qualifiers
+                    let suffix = match size {
+                        MemorySize::_8 => ".b",  // byte element
+                        MemorySize::_16 => ".h", // halfword element
+                        MemorySize::_32 => ".s", // single precision
+                        MemorySize::_64 => ".d", // double precision
+                        _ => ".d",               // default to double
+                    };
+                    write!(f, "{}{}", VREG_NAMES[idx], suffix)
+                }
+            },
+            crate::RegisterClass::Sve => {
+                write!(f, "z{idx}.{}", crate::out::sve::element_letter(opts.size))
+            }
+            crate::RegisterClass::Predicate => {
+                write!(
+                    f,
+                    "p{}.{}",
+                    idx % 16,
+                    crate::out::sve::element_letter(opts.size)
+                )
+            }
+            crate::RegisterClass::Gpr if idx == 31 && opts.reg31 == Reg31::Zero => {
+                match &opts.size {
+                    MemorySize::_32 => write!(f, "wzr"),
+                    _ => write!(f, "xzr"),
+                }
             }
             crate::RegisterClass::Gpr => {
                 // For general-purpose registers
@@ -144,3 +180,71 @@ impl Display for RegDisplay {
         AArch64Reg::format(&self.reg, f, &self.opts)
     }
 }
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::out::arg::ArgKind;
+    use alloc::format;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_simd_views() {
+        let show = |view, size| {
+            let opts = RegFormatOpts::with_reg_class(Default::default(), size, RegisterClass::Simd)
+                .with_simd_view(view);
+            format!("{}", AArch64Reg::display(&Reg(3), opts))
+        };
+        assert_eq!(show(SimdRegView::Element, MemorySize::_64), "v3.d");
+        assert_eq!(show(SimdRegView::Scalar, MemorySize::_64), "d3");
+        assert_eq!(show(SimdRegView::Scalar, MemorySize::_32), "s3");
+        assert_eq!(show(SimdRegView::Scalar, MemorySize::_8), "b3");
+        assert_eq!(show(SimdRegView::Scalar, MemorySize::_128), "q3");
+        assert_eq!(show(SimdRegView::Arrangement, MemorySize::_8), "v3.16b");
+        assert_eq!(show(SimdRegView::Arrangement, MemorySize::_16), "v3.8h");
+        assert_eq!(show(SimdRegView::Arrangement, MemorySize::_64), "v3.2d");
+    }
+
+    #[test]
+    fn test_sve_registers() {
+        let show = |reg_class, size| {
+            let opts = RegFormatOpts::with_reg_class(Default::default(), size, reg_class);
+            format!("{}", AArch64Reg::display(&Reg(3), opts))
+        };
+        assert_eq!(show(RegisterClass::Sve, MemorySize::_64), "z3.d");
+        assert_eq!(show(RegisterClass::Sve, MemorySize::_8), "z3.b");
+        assert_eq!(show(RegisterClass::Predicate, MemorySize::_32), "p3.s");
+    }
+
+    #[test]
+    fn test_reg31_follows_the_operand() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = AArch64Arch::default();
+        let wzr = ArgKind::Reg {
+            reg: Reg(31),
+            size: MemorySize::_32,
+        };
+        let mem = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(31),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 8,
+            size: MemorySize::_32,
+            reg_class: RegisterClass::Gpr,
+            mode: crate::out::arg::AddressingMode::Offset,
+        };
+        writer.str(&mut (), cfg, &wzr, &mem).unwrap();
+        writer
+            .orr(&mut (), cfg, &Reg(0), &Reg(31), &Reg(1))
+            .unwrap();
+        writer.add_imm(&mut (), cfg, &Reg(0), &Reg(31), 16).unwrap();
+        assert_eq!(
+            output,
+            "str wzr, [sp, #8]\norr x0, xzr, x1\nadd x0, sp, #16\n"
+        );
+    }
+}
@@ -168,12 +168,13 @@ pub fn map_index_to_kind(idx: usize, is_float: bool) -> RegKind {
 /// * `N` - Number of registers per kind (typically 32 for AArch64)
 ///
 /// # Arguments
-/// * `_arch` - The AArch64 architecture configuration (currently unused)
+/// * `arch` - The AArch64 architecture configuration; `x18` is reserved
+///   when [`TargetOs::reserves_x18`](crate::TargetOs::reserves_x18)
 ///
 /// # Returns
 /// A newly initialized RegAlloc instance with appropriate registers reserved
 pub fn init_regalloc<const N: usize>(
-    _arch: AArch64Arch,
+    arch: AArch64Arch,
 ) -> RegAlloc<RegKind, N, [[RegAllocFrame<RegKind>; N]; 2]> {
     // Initialize integer register frame
     let mut int_frame: [RegAllocFrame<RegKind>; N] = core::array::from_fn(|_| RegAllocFrame::Empty);
@@ -189,6 +190,11 @@ pub fn init_regalloc<const N: usize>(
         int_frame[31] = RegAllocFrame::Reserved;
     }
 
+    // Reserve x18 where the platform owns it
+    if N > 18 && arch.os.reserves_x18() {
+        int_frame[18] = RegAllocFrame::Reserved;
+    }
+
     // Initialize float register frame
     let float_frame: [RegAllocFrame<RegKind>; N] = core::array::from_fn(|_| RegAllocFrame::Empty);
 
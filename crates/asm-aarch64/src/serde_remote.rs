@@ -0,0 +1,61 @@
+//! Serde adapters for the `portal-pc-asm-common` types that appear in
+//! operands, which do not implement serde themselves.
+//!
+//! A register is written as its number and a memory size as its width in
+//! bits, so `MemorySize::_64` is `64`.
+
+/// `serde(with)` adapter for [`Reg`](portal_pc_asm_common::types::reg::Reg).
+pub(crate) mod reg {
+    use portal_pc_asm_common::types::reg::Reg;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(reg: &Reg, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(reg.0)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Reg, D::Error> {
+        u8::deserialize(deserializer).map(Reg)
+    }
+}
+
+/// `serde(with)` adapter for
+/// [`MemorySize`](portal_pc_asm_common::types::mem::MemorySize).
+pub(crate) mod memory_size {
+    use portal_pc_asm_common::types::mem::MemorySize;
+    use serde::{Deserialize, Deserializer, Serializer, de, ser};
+
+    pub(crate) fn serialize<S: Serializer>(
+        size: &MemorySize,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        #[allow(unreachable_patterns)]
+        let bits = match size {
+            MemorySize::_8 => 8,
+            MemorySize::_16 => 16,
+            MemorySize::_32 => 32,
+            MemorySize::_64 => 64,
+            MemorySize::_128 => 128,
+            MemorySize::_256 => 256,
+            MemorySize::_512 => 512,
+            _ => return Err(ser::Error::custom("unsupported memory size")),
+        };
+        serializer.serialize_u16(bits)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<MemorySize, D::Error> {
+        match u16::deserialize(deserializer)? {
+            8 => Ok(MemorySize::_8),
+            16 => Ok(MemorySize::_16),
+            32 => Ok(MemorySize::_32),
+            64 => Ok(MemorySize::_64),
+            128 => Ok(MemorySize::_128),
+            256 => Ok(MemorySize::_256),
+            512 => Ok(MemorySize::_512),
+            bits => Err(de::Error::custom(format_args!(
+                "invalid memory size: {bits} bits"
+            ))),
+        }
+    }
+}
@@ -418,3 +418,44 @@ impl Default for StackManager {
         Self::new()
     }
 }
+
+/// Bytes of stack a frame may allocate before it must be probed.
+const PROBE_INTERVAL: u32 = 4096;
+
+/// Moves `sp` down by `size` bytes, probing the new pages first on
+/// platforms that require it.
+///
+/// On [`TargetOs::Windows`](crate::TargetOs::Windows), frames of a page or
+/// more call [`stack_probe`](crate::TargetOs::stack_probe) with the size in
+/// 16-byte units in `x15`; the probe clobbers `x16` and `x17`. Other
+/// platforms only move `sp`.
+///
+/// # Panics
+///
+/// Panics if `size` is not a multiple of 16 or does not fit in 24 bits.
+pub fn alloc_frame<W: WriterCore<Context> + ?Sized, Context>(
+    writer: &mut W,
+    ctx: &mut Context,
+    arch: AArch64Arch,
+    size: u32,
+) -> Result<(), W::Error> {
+    assert!(
+        size % 16 == 0 && size < 1 << 24,
+        "frame size must be a 16-byte multiple below 16 MiB"
+    );
+    let sp = Reg(31);
+    if let Some(probe) = arch.os.stack_probe().filter(|_| size >= PROBE_INTERVAL) {
+        writer.mov_imm(ctx, arch, &Reg(15), u64::from(size / 16))?;
+        writer.bl(ctx, arch, &ArgKind::sym(probe))?;
+    }
+    // `sub` takes a 12-bit immediate, optionally shifted left by 12.
+    let high = size & !0xfff;
+    let low = size & 0xfff;
+    if high != 0 {
+        writer.sub_imm(ctx, arch, &sp, &sp, i64::from(high))?;
+    }
+    if low != 0 {
+        writer.sub_imm(ctx, arch, &sp, &sp, i64::from(low))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Lowering strategy selection.
+//!
+//! Some operations lower differently depending on which extensions
+//! [`AArch64Arch`] enables. [`Strategies::for_arch`] makes each of those
+//! choices in one place. The x86-64 shim consults it instead of testing
+//! extension flags directly, and callers can inspect it to see which
+//! sequences a configuration will produce.
+
+use crate::AArch64Arch;
+
+/// How an exchange between a register and memory is lowered.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum Exchange {
+    /// LSE `swpal`, a single atomic swap with acquire-release ordering.
+    Native,
+    /// A load and a store through a scratch register, which is not atomic.
+    Moves,
+}
+
+/// The lowering chosen for each operation with more than one.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub struct Strategies {
+    /// Register/memory exchange lowering.
+    pub exchange: Exchange,
+}
+
+impl Strategies {
+    /// Selects the lowering for each operation from the enabled extensions.
+    pub fn for_arch(cfg: AArch64Arch) -> Self {
+        Self {
+            exchange: if cfg.lse {
+                Exchange::Native
+            } else {
+                Exchange::Moves
+            },
+        }
+    }
+}
+
+impl AArch64Arch {
+    /// Returns the lowering strategies this configuration selects.
+    pub fn strategies(&self) -> Strategies {
+        Strategies::for_arch(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lse_selects_native_exchange() {
+        let base = AArch64Arch::default().strategies();
+        assert_eq!(base.exchange, Exchange::Moves);
+
+        let lse = AArch64Arch::default().with_lse(true).strategies();
+        assert_eq!(lse.exchange, Exchange::Native);
+    }
+}
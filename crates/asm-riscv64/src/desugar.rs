@@ -61,6 +61,9 @@ use alloc::vec::Vec;
 //         ld   x10, 8(t6)     // x10 = mem[t6 + 8]
 // ```
 //
+// A zero literal base means no base at all and becomes `x0`, which reads
+// as zero: `mem[base=0, offset=x6, scale=0, disp=8]` is `ld x10, 8(x6)`.
+//
 // ## Computational Instructions
 //
 // ### Memory Operands in Arithmetic
@@ -94,6 +97,22 @@ use alloc::vec::Vec;
 // Output: li   x10, 123       // Load literal directly
 // ```
 //
+// ### Zero Operands
+//
+// A literal 0 operand is read from `zero` rather than loaded, and adding
+// zero is a move:
+//
+// ```text
+// Input:  sd 0, mem[base=x5, disp=8]
+// Output: sd   zero, 8(x5)
+//
+// Input:  add x10, x5, 0
+// Output: mv   x10, x5
+//
+// Input:  mv x10, zero
+// Output: li   x10, 0
+// ```
+//
 // ### Branch Instructions
 //
 // ```text
@@ -115,6 +134,21 @@ use alloc::vec::Vec;
 //         jalr ra, t4, 0      // Jump to computed address
 // ```
 //
+// ## Bit Manipulation Without Zbb
+//
+// When `RiscV64Arch::zbb` is false (see `RiscV64Arch::strategies`), `cpop`, `clz`,
+// `ctz`, `rev8`, `sext_b` and `sext_h` are lowered to base-ISA shift/mask sequences
+// in t6, clobbering t3 and t4:
+//
+// ```text
+// Input:  cpop a0, a1
+// Output: mv   t6, a1
+//         li   t4, 1
+//         srl  t3, t6, t4     // SWAR population count ...
+//         ...
+//         mv   a0, t6
+// ```
+//
 // # Usage
 //
 // Wrap any WriterCore implementation with DesugaringWriter to automatically handle
@@ -194,11 +228,13 @@ use alloc::vec::Vec;
 use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
 
 use crate::{
-    RiscV64Arch,
+    Csr, RiscV64Arch,
     out::{
         WriterCore,
         arg::{ArgKind, MemArg, MemArgKind},
+        error::EmitError,
     },
+    strategy::{BitCount, SignExtend},
 };
 
 /// Configuration for the desugaring wrapper.
@@ -406,7 +442,10 @@ where
     phantom: core::marker::PhantomData<Context>,
 }
 
-impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Context> {
+impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Context>
+where
+    W::Error: From<EmitError>,
+{
     /// Creates a new desugaring wrapper with default configuration.
     pub fn new(writer: &'a mut W) -> Self {
         let config = DesugarConfig::default();
@@ -520,6 +559,113 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
         value >= -2048 && value < 2048
     }
 
+    /// Returns `value` as a 12-bit I-type immediate if it fits.
+    fn imm12(value: i64) -> Option<i32> {
+        i32::try_from(value).ok().filter(|&v| Self::fits_in_12_bits(v))
+    }
+
+    /// Returns the value of `arg` if it is a literal operand.
+    fn literal(arg: &(dyn MemArg + '_)) -> Option<i64> {
+        match arg.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Lit(v)) => Some(v as i64),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if `arg` reads as zero: the literal 0 or `zero`.
+    fn is_zero(arg: &(dyn MemArg + '_)) -> bool {
+        matches!(
+            arg.concrete_mem_kind(),
+            MemArgKind::NoMem(ArgKind::Lit(0) | ArgKind::Reg { reg: Reg(0), .. })
+        )
+    }
+
+    /// The `zero` register, standing in for a literal 0 operand.
+    fn zero() -> MemArgKind<ArgKind> {
+        MemArgKind::NoMem(ArgKind::Reg {
+            reg: Reg(0),
+            size: MemorySize::_64,
+        })
+    }
+
+    /// Replaces a literal 0 store source with `zero`.
+    fn store_src(src: &(dyn MemArg + '_)) -> MemArgKind<ArgKind> {
+        match src.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Lit(0)) => Self::zero(),
+            src => src,
+        }
+    }
+
+    /// Loads a literal, or a symbol's address, into `dest`.
+    fn load_lit(
+        &mut self,
+        ctx: &mut Context,
+        arch: RiscV64Arch,
+        dest: Reg,
+        lit: &ArgKind,
+    ) -> Result<(), W::Error> {
+        match lit {
+            ArgKind::Lit(val) => self.writer.li(ctx, arch, &dest, *val),
+            _ => self.writer.la(ctx, arch, &dest, lit),
+        }
+    }
+
+    /// Emits a cache-block instruction with `emit`, on `mem` desugared to a
+    /// base register and an offset.
+    ///
+    /// Zicbop encodes only offsets that are multiples of 32 and Zicbom none
+    /// at all, so an address with an offset `encodable` rejects is formed in
+    /// a temporary first.
+    fn emit_cache_block(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+        encodable: fn(i32) -> bool,
+        emit: impl FnOnce(&mut W, &mut Context, &(dyn MemArg + '_)) -> Result<(), W::Error>,
+    ) -> Result<(), W::Error> {
+        let mut mem = self.desugar_mem_arg(ctx, cfg, mem)?;
+        let mut saved = None;
+        if let MemArgKind::Mem {
+            base: ArgKind::Reg { reg: base, .. },
+            offset: None,
+            disp,
+            size,
+            reg_class,
+        } = mem
+        {
+            if !encodable(disp) {
+                let (temp, needs_save, saved_reg) = self.select_temp_reg(&[base]);
+                if needs_save {
+                    saved = saved_reg;
+                }
+                if let Some(reg) = saved {
+                    self.save_reg_to_stack(ctx, cfg, reg)?;
+                }
+                self.writer.addi(ctx, cfg, &temp, &base, disp)?;
+                mem = Self::simple_mem(temp, 0, size, reg_class);
+            }
+        }
+        emit(&mut *self.writer, ctx, &mem)?;
+        if let Some(reg) = saved {
+            self.restore_reg_from_stack(ctx, cfg, reg)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if `mem` is `[sym + disp]` loadable with the
+    /// PC-relative load pseudo-instruction (`ld a0, sym`).
+    fn is_pc_rel_load(mem: &(dyn MemArg + '_)) -> bool {
+        matches!(
+            mem.concrete_mem_kind(),
+            MemArgKind::Mem {
+                base: ArgKind::Sym { .. },
+                offset: None,
+                ..
+            }
+        )
+    }
+
     /// Checks if an immediate fits in 5 bits (RISC-V shift immediate range for RV64I).
     fn fits_in_5_bits(imm: i32) -> bool {
         imm >= 0 && imm < 32
@@ -535,17 +681,22 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
     /// Returns (base_reg, displacement, size, reg_class) where base_reg might be a temp register
     /// if address calculation was needed. The size and reg_class are preserved from the original
     /// memory operand to maintain type safety throughout desugaring.
+    ///
+    /// Fails with [`EmitError::InvalidOperands`] if `mem` is not a memory operand.
     fn desugar_mem_operand(
         &mut self,
         ctx: &mut Context,
         arch: RiscV64Arch,
         mem: &MemArgKind<ArgKind>,
     ) -> Result<(Reg, i32, MemorySize, crate::RegisterClass), W::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(?mem, "desugaring memory operand");
         match mem {
-            MemArgKind::NoMem(_) => {
-                // This shouldn't be called for non-memory operands
-                panic!("desugar_mem_operand called with NoMem variant")
+            MemArgKind::NoMem(_) => Err(EmitError::InvalidOperands {
+                mnemonic: "address",
+                reason: "expected a memory operand",
             }
+            .into()),
             MemArgKind::Mem {
                 base,
                 offset,
@@ -564,15 +715,17 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                         avoid_regs_len += 1;
                         *reg
                     }
-                    ArgKind::Lit(val) => {
-                        // Load literal into temp register
+                    // No base (`[index << scale + disp]`): x0 reads as zero.
+                    ArgKind::Lit(0) => Reg(0),
+                    lit => {
+                        // Load literal (or symbol address) into temp register
                         let (temp, needs_save, saved_reg) = self.select_temp_reg(&avoid_regs);
                         if needs_save {
                             if let Some(reg_to_save) = saved_reg {
                                 self.save_reg_to_stack(ctx, arch, reg_to_save)?;
                             }
                         }
-                        self.writer.li(ctx, arch, &temp, *val)?;
+                        self.load_lit(ctx, arch, temp, lit)?;
                         if needs_save {
                             if let Some(reg_to_save) = saved_reg {
                                 self.restore_reg_from_stack(ctx, arch, reg_to_save)?;
@@ -595,7 +748,7 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                             avoid_regs_len += 1;
                             *reg
                         }
-                        ArgKind::Lit(val) => {
+                        lit => {
                             // Load literal offset into a temp register that doesn't conflict
                             let (temp, needs_save, saved_reg) = self.select_temp_reg(&avoid_regs);
                             if needs_save {
@@ -603,7 +756,7 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                                     self.save_reg_to_stack(ctx, arch, reg_to_save)?;
                                 }
                             }
-                            self.writer.li(ctx, arch, &temp, *val)?;
+                            self.load_lit(ctx, arch, temp, lit)?;
                             if needs_save {
                                 if let Some(reg_to_save) = saved_reg {
                                     self.restore_reg_from_stack(ctx, arch, reg_to_save)?;
@@ -655,22 +808,26 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                         offset_reg
                     };
 
-                    // Add base: result = base_reg + scaled_offset_reg
-                    let (result_reg, needs_save, saved_reg) = self.select_temp_reg(&avoid_regs);
-                    if needs_save {
-                        if let Some(reg_to_save) = saved_reg {
-                            self.save_reg_to_stack(ctx, arch, reg_to_save)?;
+                    if base_reg == Reg(0) {
+                        scaled_offset_reg
+                    } else {
+                        // Add base: result = base_reg + scaled_offset_reg
+                        let (result_reg, needs_save, saved_reg) = self.select_temp_reg(&avoid_regs);
+                        if needs_save {
+                            if let Some(reg_to_save) = saved_reg {
+                                self.save_reg_to_stack(ctx, arch, reg_to_save)?;
+                            }
                         }
-                    }
-                    self.writer
-                        .add(ctx, arch, &result_reg, &base_reg, &scaled_offset_reg)?;
-                    if needs_save {
-                        if let Some(reg_to_save) = saved_reg {
-                            self.restore_reg_from_stack(ctx, arch, reg_to_save)?;
+                        self.writer
+                            .add(ctx, arch, &result_reg, &base_reg, &scaled_offset_reg)?;
+                        if needs_save {
+                            if let Some(reg_to_save) = saved_reg {
+                                self.restore_reg_from_stack(ctx, arch, reg_to_save)?;
+                            }
                         }
-                    }
 
-                    result_reg
+                        result_reg
+                    }
                 } else {
                     base_reg
                 };
@@ -804,6 +961,19 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                     preserved_reg_class,
                 )))
             }
+            MemArgKind::Mem {
+                base: ArgKind::Sym { .. }, // Symbol base - form the address first
+                ..
+            } => {
+                let (base, new_disp, preserved_size, preserved_reg_class) =
+                    self.desugar_mem_operand(ctx, arch, &concrete)?;
+                Ok(self.adjust_sp_mem_kind(Self::simple_mem(
+                    base,
+                    new_disp,
+                    preserved_size,
+                    preserved_reg_class,
+                )))
+            }
             _ => Ok(self.adjust_sp_mem_kind(concrete)), // Simple case - no desugaring needed
         }
     }
@@ -824,15 +994,16 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
 
         match &concrete {
             MemArgKind::NoMem(ArgKind::Reg { .. }) => Ok(concrete), // Already a register
-            MemArgKind::NoMem(ArgKind::Lit(val)) => {
-                // This is a literal operand - need to load it into a temp register
+            MemArgKind::NoMem(ArgKind::Lit(0)) => Ok(Self::zero()), // x0 reads as zero
+            MemArgKind::NoMem(lit) => {
+                // This is a literal or symbol operand - need to load it into a temp register
                 let (temp_reg, needs_save, saved_reg) = self.select_temp_reg(avoid_regs);
                 if needs_save {
                     if let Some(reg_to_save) = saved_reg {
                         self.save_reg_to_stack(ctx, arch, reg_to_save)?;
                     }
                 }
-                self.writer.li(ctx, arch, &temp_reg, *val as u64)?;
+                self.load_lit(ctx, arch, temp_reg, lit)?;
                 if needs_save {
                     if let Some(reg_to_save) = saved_reg {
                         self.restore_reg_from_stack(ctx, arch, reg_to_save)?;
@@ -1232,12 +1403,87 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
             }
         }
     }
+
+    /// Copies `src` into the primary temporary for a Zbb soft-emulation sequence.
+    ///
+    /// Returns `(x, t, k)`: `x` holds the value being transformed, `t` and `k`
+    /// are scratch registers for partial results and shift amounts / masks.
+    fn load_soft_zbb_src(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(Reg, Reg, Reg), W::Error> {
+        let x = self.config.temp_reg;
+        let t = self.config.temp_reg2;
+        let k = self.config.temp_reg3;
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_src])?;
+        self.writer.mv(ctx, cfg, &x, &desugared_src)?;
+        Ok((x, t, k))
+    }
+
+    /// Emits a base-ISA population count of `x` in place (SWAR), clobbering `t` and `k`.
+    fn soft_cpop(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        x: Reg,
+        t: Reg,
+        k: Reg,
+    ) -> Result<(), W::Error> {
+        // x -= (x >> 1) & 0x5555...
+        self.writer.li(ctx, cfg, &k, 1)?;
+        self.writer.srl(ctx, cfg, &t, &x, &k)?;
+        self.writer.li(ctx, cfg, &k, 0x5555_5555_5555_5555)?;
+        self.writer.and(ctx, cfg, &t, &t, &k)?;
+        self.writer.sub(ctx, cfg, &x, &x, &t)?;
+        // x = (x & 0x3333...) + ((x >> 2) & 0x3333...)
+        self.writer.li(ctx, cfg, &k, 0x3333_3333_3333_3333)?;
+        self.writer.and(ctx, cfg, &t, &x, &k)?;
+        self.writer.li(ctx, cfg, &k, 2)?;
+        self.writer.srl(ctx, cfg, &x, &x, &k)?;
+        self.writer.li(ctx, cfg, &k, 0x3333_3333_3333_3333)?;
+        self.writer.and(ctx, cfg, &x, &x, &k)?;
+        self.writer.add(ctx, cfg, &x, &x, &t)?;
+        // x = (x + (x >> 4)) & 0x0f0f...
+        self.writer.li(ctx, cfg, &k, 4)?;
+        self.writer.srl(ctx, cfg, &t, &x, &k)?;
+        self.writer.add(ctx, cfg, &x, &x, &t)?;
+        self.writer.li(ctx, cfg, &k, 0x0f0f_0f0f_0f0f_0f0f)?;
+        self.writer.and(ctx, cfg, &x, &x, &k)?;
+        // Sum the per-byte counts into the low byte (avoids depending on M)
+        for shift in [8, 16, 32] {
+            self.writer.li(ctx, cfg, &k, shift)?;
+            self.writer.srl(ctx, cfg, &t, &x, &k)?;
+            self.writer.add(ctx, cfg, &x, &x, &t)?;
+        }
+        self.writer.li(ctx, cfg, &k, 0x7f)?;
+        self.writer.and(ctx, cfg, &x, &x, &k)
+    }
+
+    /// Sign-extends the low `64 - shift` bits of `x` in place, clobbering `k`.
+    fn soft_sext(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        x: Reg,
+        k: Reg,
+        shift: u64,
+    ) -> Result<(), W::Error> {
+        self.writer.li(ctx, cfg, &k, shift)?;
+        self.writer.sll(ctx, cfg, &x, &x, &k)?;
+        self.writer.sra(ctx, cfg, &x, &x, &k)
+    }
 }
 
 // Implement WriterCore for DesugaringWriter
 // We forward most methods and only intercept memory operations
 impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
     for DesugaringWriter<'a, W, Context>
+where
+    W::Error: From<EmitError>,
 {
     type Error = W::Error;
 
@@ -1250,6 +1496,9 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         dest: &(dyn MemArg + '_),
         mem: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if Self::is_pc_rel_load(mem) {
+            return self.writer.ld(ctx, cfg, dest, mem);
+        }
         let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
         self.writer.ld(ctx, cfg, dest, &desugared_mem)
     }
@@ -1262,7 +1511,8 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         mem: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
         let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
-        self.writer.sd(ctx, cfg, src, &desugared_mem)
+        let src = Self::store_src(src);
+        self.writer.sd(ctx, cfg, &src, &desugared_mem)
     }
 
     fn lw(
@@ -1272,10 +1522,27 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         dest: &(dyn MemArg + '_),
         mem: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if Self::is_pc_rel_load(mem) {
+            return self.writer.lw(ctx, cfg, dest, mem);
+        }
         let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
         self.writer.lw(ctx, cfg, dest, &desugared_mem)
     }
 
+    fn lwu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if Self::is_pc_rel_load(mem) {
+            return self.writer.lwu(ctx, cfg, dest, mem);
+        }
+        let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
+        self.writer.lwu(ctx, cfg, dest, &desugared_mem)
+    }
+
     fn sw(
         &mut self,
         ctx: &mut Context,
@@ -1284,7 +1551,8 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         mem: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
         let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
-        self.writer.sw(ctx, cfg, src, &desugared_mem)
+        let src = Self::store_src(src);
+        self.writer.sw(ctx, cfg, &src, &desugared_mem)
     }
 
     fn lb(
@@ -1294,10 +1562,27 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         dest: &(dyn MemArg + '_),
         mem: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if Self::is_pc_rel_load(mem) {
+            return self.writer.lb(ctx, cfg, dest, mem);
+        }
         let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
         self.writer.lb(ctx, cfg, dest, &desugared_mem)
     }
 
+    fn lbu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if Self::is_pc_rel_load(mem) {
+            return self.writer.lbu(ctx, cfg, dest, mem);
+        }
+        let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
+        self.writer.lbu(ctx, cfg, dest, &desugared_mem)
+    }
+
     fn sb(
         &mut self,
         ctx: &mut Context,
@@ -1306,7 +1591,8 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         mem: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
         let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
-        self.writer.sb(ctx, cfg, src, &desugared_mem)
+        let src = Self::store_src(src);
+        self.writer.sb(ctx, cfg, &src, &desugared_mem)
     }
 
     fn lh(
@@ -1316,10 +1602,27 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         dest: &(dyn MemArg + '_),
         mem: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if Self::is_pc_rel_load(mem) {
+            return self.writer.lh(ctx, cfg, dest, mem);
+        }
         let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
         self.writer.lh(ctx, cfg, dest, &desugared_mem)
     }
 
+    fn lhu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if Self::is_pc_rel_load(mem) {
+            return self.writer.lhu(ctx, cfg, dest, mem);
+        }
+        let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
+        self.writer.lhu(ctx, cfg, dest, &desugared_mem)
+    }
+
     fn sh(
         &mut self,
         ctx: &mut Context,
@@ -1328,7 +1631,8 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         mem: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
         let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
-        self.writer.sh(ctx, cfg, src, &desugared_mem)
+        let src = Self::store_src(src);
+        self.writer.sh(ctx, cfg, &src, &desugared_mem)
     }
 
     fn fld(
@@ -1360,6 +1664,125 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.writer.ebreak(ctx, cfg)
     }
 
+    fn unimp(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.writer.unimp(ctx, cfg)
+    }
+
+    fn ecall(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.writer.ecall(ctx, cfg)
+    }
+
+    fn lpad(&mut self, ctx: &mut Context, cfg: RiscV64Arch, label: u32) -> Result<(), Self::Error> {
+        self.writer.lpad(ctx, cfg, label)
+    }
+
+    fn sspush(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.sspush(ctx, cfg, src)
+    }
+
+    fn sspopchk(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.sspopchk(ctx, cfg, src)
+    }
+
+    fn fence(&mut self, ctx: &mut Context, cfg: RiscV64Arch, pred: crate::FenceSet, succ: crate::FenceSet) -> Result<(), Self::Error> {
+        self.writer.fence(ctx, cfg, pred, succ)
+    }
+
+    fn fence_i(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.writer.fence_i(ctx, cfg)
+    }
+
+    fn pause(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.writer.pause(ctx, cfg)
+    }
+
+    fn prefetch_r(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.emit_cache_block(ctx, cfg, mem, |disp| disp % 32 == 0, |w, ctx, mem| {
+            w.prefetch_r(ctx, cfg, mem)
+        })
+    }
+
+    fn prefetch_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.emit_cache_block(ctx, cfg, mem, |disp| disp % 32 == 0, |w, ctx, mem| {
+            w.prefetch_w(ctx, cfg, mem)
+        })
+    }
+
+    fn cbo_clean(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.emit_cache_block(ctx, cfg, mem, |disp| disp == 0, |w, ctx, mem| {
+            w.cbo_clean(ctx, cfg, mem)
+        })
+    }
+
+    fn cbo_flush(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.emit_cache_block(ctx, cfg, mem, |disp| disp == 0, |w, ctx, mem| {
+            w.cbo_flush(ctx, cfg, mem)
+        })
+    }
+
+    fn cbo_inval(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.emit_cache_block(ctx, cfg, mem, |disp| disp == 0, |w, ctx, mem| {
+            w.cbo_inval(ctx, cfg, mem)
+        })
+    }
+
+    fn amoswap_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.amoswap_w(ctx, cfg, dest, src, addr)
+    }
+
+    fn amoswap_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.amoswap_d(ctx, cfg, dest, src, addr)
+    }
+
     fn mv(
         &mut self,
         ctx: &mut Context,
@@ -1374,6 +1797,16 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
                 self.flush_sp_if_needed(ctx, cfg, &[dest, src])?;
                 self.writer.li(ctx, cfg, dest, *val as u64)
             }
+            MemArgKind::NoMem(ArgKind::Reg { reg: Reg(0), .. }) => {
+                // `mv rd, zero` is `li rd, 0`
+                self.flush_sp_if_needed(ctx, cfg, &[dest])?;
+                self.writer.li(ctx, cfg, dest, 0)
+            }
+            MemArgKind::NoMem(ArgKind::Sym { .. }) => {
+                // Source is a symbol address - use la
+                self.flush_sp_if_needed(ctx, cfg, &[dest])?;
+                self.writer.la(ctx, cfg, dest, src)
+            }
             _ => {
                 // Source is register or memory - desugar and use mv
                 let desugared_src = self.desugar_operand(ctx, cfg, src)?;
@@ -1391,6 +1824,16 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        // Adding zero is a move
+        if Self::is_zero(b) {
+            return self.mv(ctx, cfg, dest, a);
+        }
+        if Self::is_zero(a) {
+            return self.mv(ctx, cfg, dest, b);
+        }
+        if let Some(imm) = Self::literal(b).and_then(|v| Self::imm12(v)) {
+            return self.addi(ctx, cfg, dest, a, imm);
+        }
         self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
             writer.add(ctx, cfg, dest, a, b)
         })
@@ -1404,6 +1847,12 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if Self::is_zero(b) {
+            return self.mv(ctx, cfg, dest, a);
+        }
+        if let Some(imm) = Self::literal(b).and_then(|v| Self::imm12(v.wrapping_neg())) {
+            return self.addi(ctx, cfg, dest, a, imm);
+        }
         self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
             writer.sub(ctx, cfg, dest, a, b)
         })
@@ -1493,6 +1942,19 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         })
     }
 
+    fn mulhu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.mulhu(ctx, cfg, dest, a, b)
+        })
+    }
+
     fn div(
         &mut self,
         ctx: &mut Context,
@@ -1555,12 +2017,891 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        if let Some(imm) = Self::literal(b).and_then(|v| Self::imm12(v)) {
+            return self.andi(ctx, cfg, dest, a, imm);
+        }
         self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
             writer.and(ctx, cfg, dest, a, b)
         })
     }
 
-    fn or(
+    fn andi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        if Self::fits_in_12_bits(imm) {
+            let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+            self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_src])?;
+            self.writer.andi(ctx, cfg, dest, &desugared_src, imm)
+        } else {
+            // Large immediate - the register form loads it into a temp
+            self.and(ctx, cfg, dest, src, &(imm as i64 as u64))
+        }
+    }
+
+    fn or(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if let Some(imm) = Self::literal(b).and_then(|v| Self::imm12(v)) {
+            return self.ori(ctx, cfg, dest, a, imm);
+        }
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.or(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn ori(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        if Self::fits_in_12_bits(imm) {
+            let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+            self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_src])?;
+            self.writer.ori(ctx, cfg, dest, &desugared_src, imm)
+        } else {
+            // Large immediate - the register form loads it into a temp
+            self.or(ctx, cfg, dest, src, &(imm as i64 as u64))
+        }
+    }
+
+    fn xor(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if let Some(imm) = Self::literal(b).and_then(|v| Self::imm12(v)) {
+            return self.xori(ctx, cfg, dest, a, imm);
+        }
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.xor(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn xori(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        if Self::fits_in_12_bits(imm) {
+            let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+            self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_src])?;
+            self.writer.xori(ctx, cfg, dest, &desugared_src, imm)
+        } else {
+            // Large immediate - the register form loads it into a temp
+            self.xor(ctx, cfg, dest, src, &(imm as i64 as u64))
+        }
+    }
+
+    fn srl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.srl(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn sra(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.sra(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn rol(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.rol(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.ror(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn cpop(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if cfg.strategies().bit_count == BitCount::Native {
+            let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+            self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_src])?;
+            return self.writer.cpop(ctx, cfg, dest, &desugared_src);
+        }
+        let (x, t, k) = self.load_soft_zbb_src(ctx, cfg, dest, src)?;
+        self.soft_cpop(ctx, cfg, x, t, k)?;
+        self.writer.mv(ctx, cfg, dest, &x)
+    }
+
+    fn clz(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if cfg.strategies().bit_count == BitCount::Native {
+            let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+            self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_src])?;
+            return self.writer.clz(ctx, cfg, dest, &desugared_src);
+        }
+        let (x, t, k) = self.load_soft_zbb_src(ctx, cfg, dest, src)?;
+        // Smear the highest set bit downwards, then count the remaining zeros
+        for shift in [1, 2, 4, 8, 16, 32] {
+            self.writer.li(ctx, cfg, &k, shift)?;
+            self.writer.srl(ctx, cfg, &t, &x, &k)?;
+            self.writer.or(ctx, cfg, &x, &x, &t)?;
+        }
+        self.writer.li(ctx, cfg, &k, !0u64)?;
+        self.writer.xor(ctx, cfg, &x, &x, &k)?;
+        self.soft_cpop(ctx, cfg, x, t, k)?;
+        self.writer.mv(ctx, cfg, dest, &x)
+    }
+
+    fn ctz(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if cfg.strategies().bit_count == BitCount::Native {
+            let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+            self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_src])?;
+            return self.writer.ctz(ctx, cfg, dest, &desugared_src);
+        }
+        let (x, t, k) = self.load_soft_zbb_src(ctx, cfg, dest, src)?;
+        // ctz(x) = cpop(!x & (x - 1)); yields 64 for x == 0 like Zbb
+        self.writer.li(ctx, cfg, &k, 1)?;
+        self.writer.sub(ctx, cfg, &t, &x, &k)?;
+        self.writer.li(ctx, cfg, &k, !0u64)?;
+        self.writer.xor(ctx, cfg, &x, &x, &k)?;
+        self.writer.and(ctx, cfg, &x, &x, &t)?;
+        self.soft_cpop(ctx, cfg, x, t, k)?;
+        self.writer.mv(ctx, cfg, dest, &x)
+    }
+
+    fn rev8(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if cfg.strategies().bit_count == BitCount::Native {
+            let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+            self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_src])?;
+            return self.writer.rev8(ctx, cfg, dest, &desugared_src);
+        }
+        let (x, t, k) = self.load_soft_zbb_src(ctx, cfg, dest, src)?;
+        // Swap adjacent bytes, then halfwords, then words
+        for (shift, mask) in [(8u64, 0x00ff_00ff_00ff_00ffu64), (16, 0x0000_ffff_0000_ffff)] {
+            self.writer.li(ctx, cfg, &k, mask)?;
+            self.writer.and(ctx, cfg, &t, &x, &k)?;
+            self.writer.li(ctx, cfg, &k, shift)?;
+            self.writer.sll(ctx, cfg, &t, &t, &k)?;
+            self.writer.srl(ctx, cfg, &x, &x, &k)?;
+            self.writer.li(ctx, cfg, &k, mask)?;
+            self.writer.and(ctx, cfg, &x, &x, &k)?;
+            self.writer.or(ctx, cfg, &x, &x, &t)?;
+        }
+        self.writer.li(ctx, cfg, &k, 32)?;
+        self.writer.sll(ctx, cfg, &t, &x, &k)?;
+        self.writer.srl(ctx, cfg, &x, &x, &k)?;
+        self.writer.or(ctx, cfg, &x, &x, &t)?;
+        self.writer.mv(ctx, cfg, dest, &x)
+    }
+
+    fn sext_b(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if cfg.strategies().sign_extend == SignExtend::Native {
+            let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+            self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_src])?;
+            return self.writer.sext_b(ctx, cfg, dest, &desugared_src);
+        }
+        let (x, _, k) = self.load_soft_zbb_src(ctx, cfg, dest, src)?;
+        self.soft_sext(ctx, cfg, x, k, 56)?;
+        self.writer.mv(ctx, cfg, dest, &x)
+    }
+
+    fn sext_h(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if cfg.strategies().sign_extend == SignExtend::Native {
+            let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+            self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_src])?;
+            return self.writer.sext_h(ctx, cfg, dest, &desugared_src);
+        }
+        let (x, _, k) = self.load_soft_zbb_src(ctx, cfg, dest, src)?;
+        self.soft_sext(ctx, cfg, x, k, 48)?;
+        self.writer.mv(ctx, cfg, dest, &x)
+    }
+
+    fn sext_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_src])?;
+        self.writer.sext_w(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn slt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.slt(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn sltu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.sltu(ctx, cfg, dest, a, b)
+        })
+    }
+
+    // Control flow operations
+
+    fn jalr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        base: &(dyn MemArg + '_),
+        offset: i32,
+    ) -> Result<(), Self::Error> {
+        if Self::fits_in_12_bits(offset) {
+            let desugared_base = self.desugar_operand(ctx, cfg, base)?;
+            self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_base])?;
+            self.writer.jalr(ctx, cfg, dest, &desugared_base, offset)
+        } else {
+            // Large offset - compute address in temp register
+            let temp_reg = self.config.temp_reg3;
+            self.writer.li(ctx, cfg, &temp_reg, offset as u64)?;
+            let desugared_base = self.desugar_operand(ctx, cfg, base)?;
+            self.flush_sp_if_needed(
+                ctx,
+                cfg,
+                &[
+                    dest,
+                    &desugared_base,
+                    &MemArgKind::NoMem(ArgKind::Reg {
+                        reg: temp_reg,
+                        size: MemorySize::_64,
+                    }),
+                ],
+            )?;
+            self.writer
+                .add(ctx, cfg, &temp_reg, &desugared_base, &temp_reg)?;
+            self.writer.jalr(ctx, cfg, dest, &temp_reg, 0)
+        }
+    }
+
+    fn jal(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if let MemArgKind::NoMem(ArgKind::Sym { .. }) = target.concrete_mem_kind() {
+            self.flush_sp_if_needed(ctx, cfg, &[dest])?;
+            return self.writer.jal(ctx, cfg, dest, target);
+        }
+        let desugared_target = self.desugar_operand(ctx, cfg, target)?;
+        self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_target])?;
+        self.writer.jal(ctx, cfg, dest, &desugared_target)
+    }
+
+    fn beq(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
+            writer.beq(ctx, cfg, a, b, target)
+        })
+    }
+
+    fn bne(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
+            writer.bne(ctx, cfg, a, b, target)
+        })
+    }
+
+    fn blt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
+            writer.blt(ctx, cfg, a, b, target)
+        })
+    }
+
+    fn bge(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
+            writer.bge(ctx, cfg, a, b, target)
+        })
+    }
+
+    fn bltu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
+            writer.bltu(ctx, cfg, a, b, target)
+        })
+    }
+
+    fn bgeu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
+            writer.bgeu(ctx, cfg, a, b, target)
+        })
+    }
+
+    fn ret(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.writer.ret(ctx, cfg)
+    }
+
+    fn call(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Direct jumps to a symbol are relocated, not materialized
+        if let MemArgKind::NoMem(ArgKind::Sym { .. }) = target.concrete_mem_kind() {
+            return self.writer.call(ctx, cfg, target);
+        }
+        let desugared_target = self.desugar_operand(ctx, cfg, target)?;
+        self.writer.call(ctx, cfg, &desugared_target)
+    }
+
+    fn j(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Direct jumps to a symbol are relocated, not materialized
+        if let MemArgKind::NoMem(ArgKind::Sym { .. }) = target.concrete_mem_kind() {
+            return self.writer.j(ctx, cfg, target);
+        }
+        let desugared_target = self.desugar_operand(ctx, cfg, target)?;
+        self.writer.j(ctx, cfg, &desugared_target)
+    }
+
+    // Special operations
+
+    fn lui(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        imm: u32,
+    ) -> Result<(), Self::Error> {
+        self.writer.lui(ctx, cfg, dest, imm)
+    }
+
+    fn auipc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        imm: u32,
+    ) -> Result<(), Self::Error> {
+        self.writer.auipc(ctx, cfg, dest, imm)
+    }
+
+    fn la(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.flush_sp_if_needed(ctx, cfg, &[dest])?;
+        self.writer.la(ctx, cfg, dest, src)
+    }
+
+    // Floating-point operations
+
+    fn fadd_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.fadd_d(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn fsub_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.fsub_d(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn fmul_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.fmul_d(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn fdiv_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.fdiv_d(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn fmadd_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fmadd_d(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fmadd_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fmadd_s(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fmsub_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fmsub_d(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fmsub_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fmsub_s(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmsub_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fnmsub_d(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmsub_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fnmsub_s(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmadd_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fnmadd_d(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmadd_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // The multiply-add forms only take FP registers
+        self.writer.fnmadd_s(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fmov_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fmov_d(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn fcvt_d_l(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fcvt_d_l(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn fcvt_l_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fcvt_l_d(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn feq_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.feq_d(ctx, cfg, dest, a, b)
+    }
+
+    fn flt_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.flt_d(ctx, cfg, dest, a, b)
+    }
+
+    fn fle_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fle_d(ctx, cfg, dest, a, b)
+    }
+
+    fn fmin_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fmin_d(ctx, cfg, dest, a, b)
+    }
+
+    fn fmax_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fmax_d(ctx, cfg, dest, a, b)
+    }
+
+    fn fsqrt_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fsqrt_d(ctx, cfg, dest, src)
+    }
+
+    fn fabs_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fabs_d(ctx, cfg, dest, src)
+    }
+
+    fn fneg_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fneg_d(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_d_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fcvt_d_w(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn fcvt_d_wu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fcvt_d_wu(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn fcvt_d_lu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fcvt_d_lu(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn fcvt_w_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fcvt_w_d(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_wu_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fcvt_wu_d(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_lu_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fcvt_lu_d(ctx, cfg, dest, src)
+    }
+
+    fn flw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
+        self.writer.flw(ctx, cfg, dest, &desugared_mem)
+    }
+
+    fn fsw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_mem = self.desugar_mem_arg(ctx, cfg, mem)?;
+        self.writer.fsw(ctx, cfg, src, &desugared_mem)
+    }
+
+    fn fadd_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.fadd_s(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn fsub_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
@@ -1568,12 +2909,70 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
-            writer.or(ctx, cfg, dest, a, b)
-        })
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.fsub_s(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn fmul_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.fmul_s(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn fdiv_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
+            writer.fdiv_s(ctx, cfg, dest, a, b)
+        })
+    }
+
+    fn fmov_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fmov_s(ctx, cfg, dest, &desugared_src)
     }
 
-    fn xor(
+    fn fcvt_s_l(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fcvt_s_l(ctx, cfg, dest, &desugared_src)
+    }
+
+    fn fcvt_l_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fcvt_l_s(ctx, cfg, dest, src)
+    }
+
+    fn feq_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
@@ -1581,12 +2980,10 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
-            writer.xor(ctx, cfg, dest, a, b)
-        })
+        self.writer.feq_s(ctx, cfg, dest, a, b)
     }
 
-    fn srl(
+    fn flt_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
@@ -1594,12 +2991,10 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
-            writer.srl(ctx, cfg, dest, a, b)
-        })
+        self.writer.flt_s(ctx, cfg, dest, a, b)
     }
 
-    fn sra(
+    fn fle_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
@@ -1607,12 +3002,10 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
-            writer.sra(ctx, cfg, dest, a, b)
-        })
+        self.writer.fle_s(ctx, cfg, dest, a, b)
     }
 
-    fn slt(
+    fn fmin_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
@@ -1620,12 +3013,10 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
-            writer.slt(ctx, cfg, dest, a, b)
-        })
+        self.writer.fmin_s(ctx, cfg, dest, a, b)
     }
 
-    fn sltu(
+    fn fmax_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
@@ -1633,269 +3024,256 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         a: &(dyn MemArg + '_),
         b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
-            writer.sltu(ctx, cfg, dest, a, b)
-        })
+        self.writer.fmax_s(ctx, cfg, dest, a, b)
     }
 
-    // Control flow operations
-
-    fn jalr(
+    fn fsqrt_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
         dest: &(dyn MemArg + '_),
-        base: &(dyn MemArg + '_),
-        offset: i32,
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        if Self::fits_in_12_bits(offset) {
-            let desugared_base = self.desugar_operand(ctx, cfg, base)?;
-            self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_base])?;
-            self.writer.jalr(ctx, cfg, dest, &desugared_base, offset)
-        } else {
-            // Large offset - compute address in temp register
-            let temp_reg = self.config.temp_reg3;
-            self.writer.li(ctx, cfg, &temp_reg, offset as u64)?;
-            let desugared_base = self.desugar_operand(ctx, cfg, base)?;
-            self.flush_sp_if_needed(
-                ctx,
-                cfg,
-                &[
-                    dest,
-                    &desugared_base,
-                    &MemArgKind::NoMem(ArgKind::Reg {
-                        reg: temp_reg,
-                        size: MemorySize::_64,
-                    }),
-                ],
-            )?;
-            self.writer
-                .add(ctx, cfg, &temp_reg, &desugared_base, &temp_reg)?;
-            self.writer.jalr(ctx, cfg, dest, &temp_reg, 0)
-        }
+        self.writer.fsqrt_s(ctx, cfg, dest, src)
     }
 
-    fn jal(
+    fn fabs_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
         dest: &(dyn MemArg + '_),
-        target: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let desugared_target = self.desugar_operand(ctx, cfg, target)?;
-        self.flush_sp_if_needed(ctx, cfg, &[dest, &desugared_target])?;
-        self.writer.jal(ctx, cfg, dest, &desugared_target)
+        self.writer.fabs_s(ctx, cfg, dest, src)
     }
 
-    fn beq(
+    fn fneg_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
-        a: &(dyn MemArg + '_),
-        b: &(dyn MemArg + '_),
-        target: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
-            writer.beq(ctx, cfg, a, b, target)
-        })
+        self.writer.fneg_s(ctx, cfg, dest, src)
     }
 
-    fn bne(
+    fn fcvt_s_w(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
-        a: &(dyn MemArg + '_),
-        b: &(dyn MemArg + '_),
-        target: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
-            writer.bne(ctx, cfg, a, b, target)
-        })
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fcvt_s_w(ctx, cfg, dest, &desugared_src)
     }
 
-    fn blt(
+    fn fcvt_s_wu(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
-        a: &(dyn MemArg + '_),
-        b: &(dyn MemArg + '_),
-        target: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
-            writer.blt(ctx, cfg, a, b, target)
-        })
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fcvt_s_wu(ctx, cfg, dest, &desugared_src)
     }
 
-    fn bge(
+    fn fcvt_s_lu(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
-        a: &(dyn MemArg + '_),
-        b: &(dyn MemArg + '_),
-        target: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
-            writer.bge(ctx, cfg, a, b, target)
-        })
+        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fcvt_s_lu(ctx, cfg, dest, &desugared_src)
     }
 
-    fn bltu(
+    fn fcvt_w_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
-        a: &(dyn MemArg + '_),
-        b: &(dyn MemArg + '_),
-        target: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
-            writer.bltu(ctx, cfg, a, b, target)
-        })
+        self.writer.fcvt_w_s(ctx, cfg, dest, src)
     }
 
-    fn bgeu(
+    fn fcvt_wu_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
-        a: &(dyn MemArg + '_),
-        b: &(dyn MemArg + '_),
-        target: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op_no_dest(ctx, cfg, a, b, target, |writer, ctx, cfg, a, b, target| {
-            writer.bgeu(ctx, cfg, a, b, target)
-        })
+        self.writer.fcvt_wu_s(ctx, cfg, dest, src)
     }
 
-    fn ret(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
-        self.writer.ret(ctx, cfg)
+    fn fcvt_lu_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fcvt_lu_s(ctx, cfg, dest, src)
     }
 
-    fn call(
+    fn fcvt_s_d(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
-        target: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let desugared_target = self.desugar_operand(ctx, cfg, target)?;
-        self.writer.call(ctx, cfg, &desugared_target)
+        self.writer.fcvt_s_d(ctx, cfg, dest, src)
     }
 
-    fn j(
+    fn fcvt_d_s(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
-        target: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let desugared_target = self.desugar_operand(ctx, cfg, target)?;
-        self.writer.j(ctx, cfg, &desugared_target)
+        self.writer.fcvt_d_s(ctx, cfg, dest, src)
     }
 
-    // Special operations
+    fn fsrm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fsrm(ctx, cfg, src)
+    }
 
-    fn lui(
+    fn frrm(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
         dest: &(dyn MemArg + '_),
-        imm: u32,
     ) -> Result<(), Self::Error> {
-        self.writer.lui(ctx, cfg, dest, imm)
+        self.writer.frrm(ctx, cfg, dest)
     }
 
-    fn auipc(
+    fn csrrw(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
         dest: &(dyn MemArg + '_),
-        imm: u32,
+        csr: Csr,
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.writer.auipc(ctx, cfg, dest, imm)
+        self.writer.csrrw(ctx, cfg, dest, csr, src)
     }
 
-    // Floating-point operations
-
-    fn fadd_d(
+    fn csrrs(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
         dest: &(dyn MemArg + '_),
-        a: &(dyn MemArg + '_),
-        b: &(dyn MemArg + '_),
+        csr: Csr,
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
-            writer.fadd_d(ctx, cfg, dest, a, b)
-        })
+        self.writer.csrrs(ctx, cfg, dest, csr, src)
     }
 
-    fn fsub_d(
+    fn csrrc(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
         dest: &(dyn MemArg + '_),
-        a: &(dyn MemArg + '_),
-        b: &(dyn MemArg + '_),
+        csr: Csr,
+        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
-            writer.fsub_d(ctx, cfg, dest, a, b)
-        })
+        self.writer.csrrc(ctx, cfg, dest, csr, src)
     }
 
-    fn fmul_d(
+    fn csrrwi(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
         dest: &(dyn MemArg + '_),
-        a: &(dyn MemArg + '_),
-        b: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
     ) -> Result<(), Self::Error> {
-        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
-            writer.fmul_d(ctx, cfg, dest, a, b)
-        })
+        self.writer.csrrwi(ctx, cfg, dest, csr, imm)
     }
 
-    fn fdiv_d(
+    fn csrrsi(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
         dest: &(dyn MemArg + '_),
-        a: &(dyn MemArg + '_),
-        b: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
     ) -> Result<(), Self::Error> {
-        self.binary_op(ctx, cfg, dest, a, b, |writer, ctx, cfg, dest, a, b| {
-            writer.fdiv_d(ctx, cfg, dest, a, b)
-        })
+        self.writer.csrrsi(ctx, cfg, dest, csr, imm)
     }
 
-    fn fmov_d(
+    fn csrrci(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
         dest: &(dyn MemArg + '_),
-        src: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
     ) -> Result<(), Self::Error> {
-        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
-        self.writer.fmov_d(ctx, cfg, dest, &desugared_src)
+        self.writer.csrrci(ctx, cfg, dest, csr, imm)
     }
 
-    fn fcvt_d_l(
+    fn rdcycle(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
         dest: &(dyn MemArg + '_),
-        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
-        self.writer.fcvt_d_l(ctx, cfg, dest, &desugared_src)
+        self.writer.rdcycle(ctx, cfg, dest)
     }
 
-    fn fcvt_l_d(
+    fn rdtime(
         &mut self,
         ctx: &mut Context,
         cfg: RiscV64Arch,
         dest: &(dyn MemArg + '_),
-        src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let desugared_src = self.desugar_operand(ctx, cfg, src)?;
-        self.writer.fcvt_l_d(ctx, cfg, dest, &desugared_src)
+        self.writer.rdtime(ctx, cfg, dest)
+    }
+
+    fn db(&mut self, ctx: &mut Context, cfg: RiscV64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.writer.db(ctx, cfg, bytes)
+    }
+
+    fn current_offset(&self) -> Option<usize> {
+        self.writer.current_offset()
+    }
+
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        self.writer.align_to(ctx, cfg, alignment)
+    }
+
+    fn nop(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.writer.nop(ctx, cfg)
+    }
+
+    fn nops(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        self.writer.nops(ctx, cfg, n_bytes)
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: RiscV64Arch, pow2: u32) -> Result<(), Self::Error> {
+        self.writer.align(ctx, cfg, pow2)
     }
 }
 
@@ -1904,6 +3282,7 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
 impl<'a, W, L, Context> crate::out::Writer<L, Context> for DesugaringWriter<'a, W, Context>
 where
     W: crate::out::Writer<L, Context> + ?Sized,
+    W::Error: From<EmitError>,
 {
     fn set_label(
         &mut self,
@@ -1935,6 +3314,15 @@ where
     ) -> Result<(), Self::Error> {
         self.writer.bcond_label(ctx, cfg, cond, a, b, label)
     }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.dd_label(ctx, cfg, label)
+    }
 }
 
 // #[cfg(all(test, feature = "alloc"))]
@@ -2109,13 +3497,12 @@ mod tests {
             // Literal operand in add instruction
             let b_literal = MemArgKind::NoMem(ArgKind::Lit(42));
 
-            // This should desugar to li + actx, dd
+            // A 12-bit literal folds into addi
             let _ = desugar.add(ctx, &dest, &a, &b_literal);
         }
 
-        // Check that output contains li and add
-        assert!(output.contains("li"));
-        assert!(output.contains("add"));
+        assert!(output.contains("addi"));
+        assert!(!output.contains("li"));
     }
 
     #[test]
@@ -2456,4 +3843,34 @@ mod tests {
         assert!(output.contains("li")); // For loading literals
         assert!(output.contains("sll") || output.contains("add")); // For address calculation
     }
+
+    #[test]
+    fn test_zbb_enabled_emits_native_cpop() {
+        let mut output = String::new();
+        {
+            let mut desugar = DesugaringWriter::new(&mut output as &mut dyn Write);
+            let cfg = RiscV64Arch {
+                zbb: true,
+                ..RiscV64Arch::default()
+            };
+            let _ = desugar.cpop(&mut (), cfg, &Reg(10), &Reg(11));
+        }
+
+        assert!(output.contains("cpop"));
+    }
+
+    #[test]
+    fn test_zbb_disabled_emulates_clz() {
+        let mut output = String::new();
+        {
+            let mut desugar = DesugaringWriter::new(&mut output as &mut dyn Write);
+            let cfg = RiscV64Arch::default();
+            let _ = desugar.clz(&mut (), cfg, &Reg(10), &Reg(11));
+        }
+
+        // Lowered to shifts and masks, never the Zbb instruction
+        assert!(!output.contains("clz"));
+        assert!(output.contains("srl"));
+        assert!(output.contains("mv a0"));
+    }
 }
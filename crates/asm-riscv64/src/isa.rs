@@ -0,0 +1,250 @@
+//! ISA strings, as passed to `-march`.
+//!
+//! [`RiscV64Arch::from_isa_string`] reads the extension flags from a string
+//! such as `rv64gc_zbb`, and `Display` writes them back in canonical form:
+//!
+//! ```ignore
+//! let arch = RiscV64Arch::from_isa_string("rv64imafdc_zbb_zba")?;
+//! assert_eq!(arch, RiscV64Arch::rv64gc().with_zbb(true));
+//! assert_eq!(arch.to_string(), "rv64gc_zbb");
+//! ```
+//!
+//! Parsing is case-insensitive and skips version numbers (`rv64i2p1_m2p0`).
+//! Extensions the configuration has no field for are accepted and ignored:
+//! `zba` and `v`, since no writer emits their instructions, and `zicsr` and
+//! `zifencei`, whose instructions every configuration allows. They are
+//! dropped from the canonical string. Only the extension fields are read, so
+//! the text-output settings of the result are the defaults.
+
+use core::fmt::{self, Display, Formatter};
+use core::str::FromStr;
+
+use crate::RiscV64Arch;
+
+/// Why an ISA string could not be parsed.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum IsaStringError {
+    /// The string does not start with `rv64` and the `i` or `g` base.
+    Base,
+    /// An extension name is empty or contains characters other than ASCII
+    /// letters and digits.
+    Malformed,
+}
+
+impl Display for IsaStringError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IsaStringError::Base => write!(f, "ISA string must start with rv64i or rv64g"),
+            IsaStringError::Malformed => write!(f, "malformed extension in ISA string"),
+        }
+    }
+}
+
+impl core::error::Error for IsaStringError {}
+
+/// The multi-letter extensions with a field, in canonical order: `zi*`
+/// before `za*` before `zb*`, following the single-letter order I, A, B.
+const MULTI_LETTER: [(&str, fn(&mut RiscV64Arch) -> &mut bool); 7] = [
+    ("zicbom", |arch| &mut arch.zicbom),
+    ("zicbop", |arch| &mut arch.zicbop),
+    ("zicfilp", |arch| &mut arch.zicfilp),
+    ("zicfiss", |arch| &mut arch.zicfiss),
+    ("zabha", |arch| &mut arch.zabha),
+    ("zacas", |arch| &mut arch.zacas),
+    ("zbb", |arch| &mut arch.zbb),
+];
+
+/// Strips a leading version number such as `2p1` or `2`.
+fn skip_version(text: &str) -> &str {
+    let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let major = digits(text);
+    if major == 0 {
+        return text;
+    }
+    match text[major..].strip_prefix(['p', 'P']) {
+        Some(minor) if digits(minor) > 0 => &minor[digits(minor)..],
+        _ => &text[major..],
+    }
+}
+
+impl RiscV64Arch {
+    /// Parses the extension flags from an ISA string such as
+    /// `rv64gc_zbb`; see the [`isa`](crate::isa) module.
+    pub fn from_isa_string(isa: &str) -> Result<Self, IsaStringError> {
+        let rest = match isa.get(..4) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("rv64") => &isa[4..],
+            _ => return Err(IsaStringError::Base),
+        };
+        let mut arch = Self::default();
+        match rest.bytes().next().map(|b| b.to_ascii_lowercase()) {
+            Some(b'i') => {}
+            Some(b'g') => {
+                arch.m_extension = true;
+                arch.a_extension = true;
+                arch.f_extension = true;
+                arch.d_extension = true;
+            }
+            _ => return Err(IsaStringError::Base),
+        }
+        for (i, part) in rest.split('_').enumerate() {
+            // The base letter already set its extensions.
+            let part = if i == 0 {
+                skip_version(&part[1..])
+            } else {
+                part
+            };
+            if i > 0 && part.is_empty() {
+                return Err(IsaStringError::Malformed);
+            }
+            arch.parse_extensions(part)?;
+        }
+        Ok(arch)
+    }
+
+    /// Parses one `_`-separated part: single-letter extensions with optional
+    /// versions, ending in at most one multi-letter extension.
+    fn parse_extensions(&mut self, part: &str) -> Result<(), IsaStringError> {
+        if !part.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(IsaStringError::Malformed);
+        }
+        let mut rest = part;
+        while let Some(c) = rest.chars().next() {
+            let c = c.to_ascii_lowercase();
+            if matches!(c, 'z' | 's' | 'x') {
+                return self.parse_multi_letter(rest);
+            }
+            if !c.is_ascii_alphabetic() {
+                return Err(IsaStringError::Malformed);
+            }
+            match c {
+                'm' => self.m_extension = true,
+                'a' => self.a_extension = true,
+                'f' => self.f_extension = true,
+                'd' => {
+                    self.d_extension = true;
+                    self.f_extension = true;
+                }
+                'c' => self.c_extension = true,
+                _ => {}
+            }
+            rest = skip_version(&rest[1..]);
+        }
+        Ok(())
+    }
+
+    /// Parses a multi-letter extension such as `zbb` or `zacas1p0`.
+    fn parse_multi_letter(&mut self, name: &str) -> Result<(), IsaStringError> {
+        for (ext, field) in MULTI_LETTER {
+            match name.get(..ext.len()) {
+                Some(prefix)
+                    if prefix.eq_ignore_ascii_case(ext)
+                        && skip_version(&name[ext.len()..]).is_empty() =>
+                {
+                    *field(self) = true;
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        if name.len() < 2 {
+            return Err(IsaStringError::Malformed);
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for RiscV64Arch {
+    type Err = IsaStringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_isa_string(s)
+    }
+}
+
+/// Writes the canonical ISA string for the extension flags, such as
+/// `rv64gc_zbb`.
+impl Display for RiscV64Arch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("rv64")?;
+        if self.m_extension && self.a_extension && self.f_extension && self.d_extension {
+            f.write_str("g")?;
+        } else {
+            f.write_str("i")?;
+            for (enabled, c) in [
+                (self.m_extension, 'm'),
+                (self.a_extension, 'a'),
+                (self.f_extension, 'f'),
+                (self.d_extension, 'd'),
+            ] {
+                if enabled {
+                    write!(f, "{c}")?;
+                }
+            }
+        }
+        if self.c_extension {
+            f.write_str("c")?;
+        }
+        let mut arch = *self;
+        for (ext, field) in MULTI_LETTER {
+            if *field(&mut arch) {
+                write!(f, "_{ext}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    extern crate alloc;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parses_g_and_multi_letter_extensions() {
+        let arch = RiscV64Arch::from_isa_string("rv64gc_zba_zbb").unwrap();
+        assert_eq!(arch, RiscV64Arch::rv64gc().with_zbb(true));
+        assert_eq!(arch.to_string(), "rv64gc_zbb");
+    }
+
+    #[test]
+    fn expands_to_g_and_skips_versions() {
+        let arch: RiscV64Arch = "RV64I2p1M2p0A_F_D_C_Zicsr_Zifencei_Zacas1p0"
+            .parse()
+            .unwrap();
+        assert_eq!(arch, RiscV64Arch::rv64gc().with_zacas(true));
+        assert_eq!(arch.to_string(), "rv64gc_zacas");
+    }
+
+    #[test]
+    fn canonical_string_round_trips() {
+        let arch = RiscV64Arch::default()
+            .with_m(true)
+            .with_c(true)
+            .with_zicfiss(true)
+            .with_zbb(true);
+        assert_eq!(arch.to_string(), "rv64imc_zicfiss_zbb");
+        assert_eq!(RiscV64Arch::from_isa_string(&arch.to_string()), Ok(arch));
+        assert_eq!(RiscV64Arch::rv64imfd().to_string(), "rv64imfd");
+        assert_eq!(RiscV64Arch::default().to_string(), "rv64i");
+    }
+
+    #[test]
+    fn d_implies_f() {
+        let arch = RiscV64Arch::from_isa_string("rv64id").unwrap();
+        assert!(arch.f_extension && arch.d_extension);
+        assert_eq!(RiscV64Arch::default().with_d(true), arch);
+    }
+
+    #[test]
+    fn rejects_other_bases_and_malformed_parts() {
+        let parse = RiscV64Arch::from_isa_string;
+        assert_eq!(parse("rv32gc"), Err(IsaStringError::Base));
+        assert_eq!(parse("rv64e"), Err(IsaStringError::Base));
+        assert_eq!(parse("rv64"), Err(IsaStringError::Base));
+        assert_eq!(parse("rv64gc__zbb"), Err(IsaStringError::Malformed));
+        assert_eq!(parse("rv64gc_zb-b"), Err(IsaStringError::Malformed));
+        assert_eq!(parse("rv64g2c"), Ok(RiscV64Arch::rv64gc()));
+    }
+}
@@ -7,6 +7,8 @@
 //!
 //! - `alloc`: Enables heap allocation support for dynamic collections
 //! - `x64_shim`: Enables x86-64 to RISC-V64 translation shim
+//! - `serde`: Derives `Serialize`/`Deserialize` for the architecture
+//!   configuration and operand types
 //!
 //! # Example
 //!
@@ -35,6 +37,7 @@ extern crate alloc;
 #[doc(hidden)]
 pub mod __ {
     pub use core;
+    pub use portal_pc_asm_common::types::reg::Reg;
 }
 
 use core::fmt::Display;
@@ -69,6 +72,7 @@ static FREG_NAMES: &'static [&'static str; 32] = &[
 /// or floating-point registers.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RegisterClass {
     /// General-purpose register (x0-x31, or their ABI names).
     #[default]
@@ -122,6 +126,8 @@ impl From<RiscV64Arch> for DisplayOpts {
 /// such as available extensions (I, M, A, F, D, C, etc.).
 #[non_exhaustive]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct RiscV64Arch {
     /// Whether the M extension (integer multiplication/division) is enabled.
     pub m_extension: bool,
@@ -132,7 +138,159 @@ pub struct RiscV64Arch {
     /// Whether the D extension (double-precision floating-point) is enabled.
     pub d_extension: bool,
     /// Whether the C extension (compressed instructions) is enabled.
+    /// When enabled, writers emit 16-bit forms where the operands allow.
     pub c_extension: bool,
+    /// Whether the Zbb extension (basic bit manipulation) is enabled.
+    /// When disabled, `DesugaringWriter` lowers Zbb operations to base-ISA sequences.
+    pub zbb: bool,
+    /// Whether the Zacas extension (`amocas.w`/`amocas.d` compare-and-swap) is enabled.
+    pub zacas: bool,
+    /// Whether the Zabha extension (byte and halfword AMOs) is enabled.
+    pub zabha: bool,
+    /// Whether the Zicbom extension (`cbo.clean`/`cbo.flush`/`cbo.inval`
+    /// cache-block maintenance) is enabled.
+    pub zicbom: bool,
+    /// Whether the Zicbop extension (`prefetch.r`/`prefetch.w` cache-block
+    /// prefetches) is enabled.
+    pub zicbop: bool,
+    /// Whether Zicfilp landing pads are enforced.
+    ///
+    /// Function entries and other indirect-branch targets then start with
+    /// `lpad` (see [`out::cfi`]).
+    pub zicfilp: bool,
+    /// Whether the Zicfiss shadow stack is enabled.
+    ///
+    /// Function entries then push `ra` to the shadow stack and returns check
+    /// it against the copy there (see [`out::cfi`]).
+    pub zicfiss: bool,
+    /// How text output refers to labels.
+    pub label_refs: LabelRefs,
+    /// Whether to emit position-independent code.
+    ///
+    /// Moving a symbol's address into a register with `mv` then uses
+    /// `auipc` + `addi` instead of an absolute `lui` + `addi`. The explicit
+    /// [`la`](out::WriterCore::la) is always PC-relative.
+    pub pic: bool,
+    /// Which assembler text output is written for.
+    pub assembler: AssemblerDialect,
+    /// The rounding mode written into the `rm` field of floating-point
+    /// instructions that round.
+    ///
+    /// The default, [`RoundingMode::Dynamic`], rounds as the `frm` CSR
+    /// says; see [`fsrm`](out::WriterCore::fsrm) for changing it at run time.
+    pub rounding_mode: RoundingMode,
+    /// How text output names registers.
+    pub reg_names: RegNames,
+}
+
+/// How assembly text refers to labels in branches and address loads.
+///
+/// Binary writers resolve labels themselves and always encode PC-relative
+/// offsets, so the policy only changes text output. Conditional branches
+/// have no absolute form and stay label-relative under either policy.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LabelRefs {
+    /// PC-relative references (`jal ra, label`, `la` via `auipc`), valid at any load address.
+    #[default]
+    Relative,
+    /// Absolute addresses (`lui` + `%lo` relocations against the label), for code linked at a fixed base.
+    Absolute,
+}
+
+/// The static rounding mode of a floating-point instruction.
+///
+/// Only instructions whose result can be inexact take one: arithmetic,
+/// square root, fused multiply-add, conversions to integers and narrowing
+/// conversions. Exact conversions such as `fcvt.d.w` ignore it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RoundingMode {
+    /// Round to nearest, ties to even (`rne`).
+    NearestEven,
+    /// Round towards zero (`rtz`).
+    TowardZero,
+    /// Round down, towards negative infinity (`rdn`).
+    Down,
+    /// Round up, towards positive infinity (`rup`).
+    Up,
+    /// Round to nearest, ties away from zero (`rmm`).
+    NearestMaxMagnitude,
+    /// Use the mode in the `frm` CSR (`dyn`).
+    #[default]
+    Dynamic,
+}
+
+impl RoundingMode {
+    /// Returns the 3-bit `rm` field value, which is also the `frm` CSR
+    /// encoding for the static modes.
+    pub fn bits(self) -> u32 {
+        match self {
+            RoundingMode::NearestEven => 0,
+            RoundingMode::TowardZero => 1,
+            RoundingMode::Down => 2,
+            RoundingMode::Up => 3,
+            RoundingMode::NearestMaxMagnitude => 4,
+            RoundingMode::Dynamic => 7,
+        }
+    }
+
+    /// Returns the text appended to an instruction's operands: empty for
+    /// [`Dynamic`](Self::Dynamic), which assemblers assume when the
+    /// operand is omitted, and `, rtz` and so on otherwise.
+    pub fn operand(self) -> &'static str {
+        match self {
+            RoundingMode::NearestEven => ", rne",
+            RoundingMode::TowardZero => ", rtz",
+            RoundingMode::Down => ", rdn",
+            RoundingMode::Up => ", rup",
+            RoundingMode::NearestMaxMagnitude => ", rmm",
+            RoundingMode::Dynamic => "",
+        }
+    }
+}
+
+/// How registers are named in text output.
+///
+/// Assemblers accept both spellings; numeric names suit tools that compare
+/// output textually or do not know the ABI names. Binary writers ignore it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RegNames {
+    /// ABI names: `a0`, `sp`, `s0`, `fa0`.
+    #[default]
+    Abi,
+    /// ABI names, with x8 as `fp` instead of `s0`.
+    AbiFp,
+    /// Architectural names: `x10`, `x2`, `x8`, `f10`.
+    Numeric,
+}
+
+/// The assembler that consumes text output.
+///
+/// GNU `as` and LLVM's integrated assembler accept the same directives for
+/// everything the text writers emit (`.balign 16`, `.type f, @function`); the dialect
+/// names the target so directive and comment spellings can follow it.
+/// Binary writers ignore it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AssemblerDialect {
+    /// GNU `as`.
+    #[default]
+    Gnu,
+    /// LLVM's integrated assembler.
+    Llvm,
+}
+
+impl AssemblerDialect {
+    /// Returns the string that starts a comment running to the end of the line.
+    pub fn comment(self) -> &'static str {
+        "#"
+    }
 }
 
 impl RiscV64Arch {
@@ -144,6 +302,18 @@ impl RiscV64Arch {
             f_extension: true,
             d_extension: true,
             c_extension: false,
+            zbb: false,
+            zacas: false,
+            zabha: false,
+            zicbom: false,
+            zicbop: false,
+            zicfilp: false,
+            zicfiss: false,
+            label_refs: LabelRefs::Relative,
+            pic: false,
+            assembler: AssemblerDialect::Gnu,
+            rounding_mode: RoundingMode::Dynamic,
+            reg_names: RegNames::Abi,
         }
     }
 
@@ -155,6 +325,159 @@ impl RiscV64Arch {
             f_extension: true,
             d_extension: true,
             c_extension: true,
+            zbb: false,
+            zacas: false,
+            zabha: false,
+            zicbom: false,
+            zicbop: false,
+            zicfilp: false,
+            zicfiss: false,
+            label_refs: LabelRefs::Relative,
+            pic: false,
+            assembler: AssemblerDialect::Gnu,
+            rounding_mode: RoundingMode::Dynamic,
+            reg_names: RegNames::Abi,
+        }
+    }
+
+    /// Returns this configuration with compressed output turned off.
+    ///
+    /// Pass it for a single instruction that must keep its 32-bit encoding,
+    /// such as a patch site or a fixed-stride table entry.
+    pub fn uncompressed(self) -> Self {
+        Self {
+            c_extension: false,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with floating-point instructions rounding
+    /// in `mode`.
+    ///
+    /// Pass it for a single instruction whose rounding is fixed regardless
+    /// of `frm`, such as a truncating conversion.
+    pub fn with_rounding_mode(self, mode: RoundingMode) -> Self {
+        Self {
+            rounding_mode: mode,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with text output naming registers as
+    /// `names` says.
+    pub fn with_reg_names(self, names: RegNames) -> Self {
+        Self {
+            reg_names: names,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with the M extension set to `enabled`.
+    pub fn with_m(self, enabled: bool) -> Self {
+        Self {
+            m_extension: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with the A extension set to `enabled`.
+    pub fn with_a(self, enabled: bool) -> Self {
+        Self {
+            a_extension: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with the F extension set to `enabled`.
+    pub fn with_f(self, enabled: bool) -> Self {
+        Self {
+            f_extension: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with the D extension set to `enabled`.
+    ///
+    /// D builds on F, so enabling it also enables F.
+    pub fn with_d(self, enabled: bool) -> Self {
+        Self {
+            d_extension: enabled,
+            f_extension: self.f_extension || enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with the C extension set to `enabled`.
+    pub fn with_c(self, enabled: bool) -> Self {
+        Self {
+            c_extension: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with Zbb set to `enabled`.
+    pub fn with_zbb(self, enabled: bool) -> Self {
+        Self {
+            zbb: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with Zacas set to `enabled`.
+    pub fn with_zacas(self, enabled: bool) -> Self {
+        Self {
+            zacas: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with Zabha set to `enabled`.
+    pub fn with_zabha(self, enabled: bool) -> Self {
+        Self {
+            zabha: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with Zicbom set to `enabled`.
+    pub fn with_zicbom(self, enabled: bool) -> Self {
+        Self {
+            zicbom: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with Zicbop set to `enabled`.
+    pub fn with_zicbop(self, enabled: bool) -> Self {
+        Self {
+            zicbop: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with Zicfilp landing pads set to `enabled`.
+    pub fn with_zicfilp(self, enabled: bool) -> Self {
+        Self {
+            zicfilp: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration with the Zicfiss shadow stack set to
+    /// `enabled`.
+    pub fn with_zicfiss(self, enabled: bool) -> Self {
+        Self {
+            zicfiss: enabled,
+            ..self
+        }
+    }
+
+    /// Returns this configuration emitting position-independent code when
+    /// `enabled` is set.
+    pub fn with_pic(self, enabled: bool) -> Self {
+        Self {
+            pic: enabled,
+            ..self
         }
     }
 }
@@ -172,6 +495,9 @@ pub struct RegFormatOpts {
     pub size: MemorySize,
     /// The register class for display.
     pub reg_class: RegisterClass,
+    /// How registers are named; taken from [`RiscV64Arch::reg_names`] by
+    /// the constructors.
+    pub names: RegNames,
 }
 
 impl RegFormatOpts {
@@ -181,11 +507,7 @@ impl RegFormatOpts {
     }
     /// Creates formatting options with the given architecture and size.
     pub fn default_with_arch_and_size(arch: RiscV64Arch, size: MemorySize) -> Self {
-        Self {
-            arch,
-            size,
-            reg_class: Default::default(),
-        }
+        Self::with_reg_class(arch, size, Default::default())
     }
     /// Creates formatting options with the given architecture, size, and register class.
     pub fn with_reg_class(arch: RiscV64Arch, size: MemorySize, reg_class: RegisterClass) -> Self {
@@ -193,8 +515,13 @@ impl RegFormatOpts {
             arch,
             size,
             reg_class,
+            names: arch.reg_names,
         }
     }
+    /// Returns these options with registers named as `names` says.
+    pub fn with_names(self, names: RegNames) -> Self {
+        Self { names, ..self }
+    }
 }
 
 impl Default for RegFormatOpts {
@@ -205,10 +532,14 @@ impl Default for RegFormatOpts {
 
 /// Desugaring wrapper for complex memory operands.
 pub mod desugar;
+/// ISA string parsing and formatting.
+pub mod isa;
 /// Instruction output generation module.
 pub mod out;
 /// Register handling and formatting module.
 pub mod reg;
+/// Lowering strategy selection keyed by enabled extensions.
+pub mod strategy;
 
 #[cfg(feature = "x64_shim")]
 pub use portal_solutions_asm_x86_64_shim::riscv64 as shim;
@@ -216,6 +547,9 @@ pub use portal_solutions_asm_x86_64_shim::riscv64 as shim;
 /// Register allocation integration module (gated by `regalloc-integration` feature).
 #[cfg(feature = "regalloc-integration")]
 pub mod regalloc;
+/// Serde adapters for foreign types in operands (gated by `serde` feature).
+#[cfg(feature = "serde")]
+mod serde_remote;
 
 #[cfg(all(test, feature = "alloc"))]
 mod tests {
@@ -253,6 +587,90 @@ mod tests {
 
         assert_eq!(ft0, "ft0");
         assert_eq!(fa0, "fa0");
+
+        // Test vector-width display
+        let vec_opts = RegFormatOpts::with_reg_class(cfg, MemorySize::_128, RegisterClass::Fp);
+        assert_eq!(format!("{}", RiscV64Reg::display(&reg10, vec_opts)), "v10");
+    }
+
+    #[test]
+    fn test_register_name_modes() {
+        let numeric = RiscV64Arch::default().with_reg_names(RegNames::Numeric);
+        let gpr = RegFormatOpts::default_with_arch(numeric);
+        let fp = RegFormatOpts::with_reg_class(numeric, MemorySize::_64, RegisterClass::Fp);
+        let show = |opts| format!("{}", RiscV64Reg::display(&Reg(8), opts));
+        assert_eq!(show(gpr.clone()), "x8");
+        assert_eq!(show(fp), "f8");
+        assert_eq!(show(gpr.clone().with_names(RegNames::Abi)), "s0");
+        assert_eq!(show(gpr.with_names(RegNames::AbiFp)), "fp");
+
+        let mem = out::arg::MemArgKind::tp_relative(16, MemorySize::_64);
+        assert_eq!(format!("{}", mem.display(numeric.into())), "16(x4)");
+    }
+
+    #[test]
+    fn test_tp_relative_display() {
+        let mem = out::arg::MemArgKind::tp_relative(16, MemorySize::_64);
+        assert_eq!(format!("{}", mem.display(RiscV64Arch::default().into())), "16(tp)");
+    }
+
+    #[test]
+    fn test_absolute_label_refs() {
+        use crate::out::Writer;
+        use core::fmt::Write;
+
+        let cfg = RiscV64Arch {
+            label_refs: LabelRefs::Absolute,
+            ..RiscV64Arch::default()
+        };
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        Writer::<&str>::la_label(&mut writer, &mut (), cfg, &Reg(10), "table").unwrap();
+        Writer::<&str>::jal_label(&mut writer, &mut (), cfg, &Reg(1), "callee").unwrap();
+        assert_eq!(
+            output,
+            "lui a0, %hi(table)\naddi a0, a0, %lo(table)\nlui t6, %hi(callee)\njalr ra, t6, %lo(callee)\n"
+        );
+
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        Writer::<&str>::la_label(&mut writer, &mut (), RiscV64Arch::default(), &Reg(10), "table").unwrap();
+        assert_eq!(output, "la a0, table\n");
+    }
+
+    #[test]
+    fn test_pic_symbol_moves() {
+        use crate::out::WriterCore;
+        use core::fmt::Write;
+
+        let table = out::arg::ArgKind::sym("table");
+        let mut output = String::new();
+        let writer: &mut dyn Write = &mut output;
+        WriterCore::mv(writer, &mut (), RiscV64Arch::default(), &Reg(10), &table).unwrap();
+        let pic = RiscV64Arch {
+            pic: true,
+            ..RiscV64Arch::default()
+        };
+        let writer: &mut dyn Write = &mut output;
+        WriterCore::mv(writer, &mut (), pic, &Reg(10), &table).unwrap();
+        assert_eq!(
+            output,
+            "lui a0, %hi(table)\naddi a0, a0, %lo(table)\nlla a0, table\n"
+        );
+    }
+
+    #[test]
+    fn test_static_rounding_mode() {
+        use crate::out::WriterCore;
+        use core::fmt::Write;
+
+        let mut output = String::new();
+        let writer: &mut dyn Write = &mut output;
+        let rtz = RiscV64Arch::rv64imfd().with_rounding_mode(RoundingMode::TowardZero);
+        WriterCore::fcvt_l_d(writer, &mut (), rtz, &Reg(10), &Reg(10)).unwrap();
+        WriterCore::fcvt_d_w(writer, &mut (), rtz, &Reg(10), &Reg(10)).unwrap();
+        WriterCore::fsrm(writer, &mut (), rtz, &Reg(10)).unwrap();
+        assert_eq!(output, "fcvt.l.d a0, fa0, rtz\nfcvt.d.w fa0, a0\nfsrm a0\n");
     }
 }
 
@@ -287,6 +705,24 @@ pub enum ConditionCode {
     LEU,
 }
 
+impl ConditionCode {
+    /// Returns the condition that holds exactly when `self` does not.
+    pub fn invert(self) -> Self {
+        match self {
+            ConditionCode::EQ => ConditionCode::NE,
+            ConditionCode::NE => ConditionCode::EQ,
+            ConditionCode::LT => ConditionCode::GE,
+            ConditionCode::GE => ConditionCode::LT,
+            ConditionCode::LTU => ConditionCode::GEU,
+            ConditionCode::GEU => ConditionCode::LTU,
+            ConditionCode::GT => ConditionCode::LE,
+            ConditionCode::LE => ConditionCode::GT,
+            ConditionCode::GTU => ConditionCode::LEU,
+            ConditionCode::LEU => ConditionCode::GTU,
+        }
+    }
+}
+
 impl Display for ConditionCode {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -303,3 +739,127 @@ impl Display for ConditionCode {
         }
     }
 }
+
+/// Expected direction of a conditional branch.
+///
+/// Hints never change what a branch does. Layout helpers (see
+/// [`out::branch_hint`]) use them to keep the expected path in fall-through
+/// order. RISC-V has no
+/// hint encoding, so binary and text writers drop them.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum BranchHint {
+    /// No expectation.
+    #[default]
+    None,
+    /// The branch is expected to be taken.
+    Likely,
+    /// The branch is expected to fall through.
+    Unlikely,
+}
+
+/// Set of access kinds ordered by a FENCE instruction.
+///
+/// Combine the constants with `|`; `FenceSet::R | FenceSet::W` is the `rw`
+/// operand of `fence rw, rw`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct FenceSet(u8);
+
+impl FenceSet {
+    /// Device input.
+    pub const I: Self = Self(0b1000);
+    /// Device output.
+    pub const O: Self = Self(0b0100);
+    /// Memory reads.
+    pub const R: Self = Self(0b0010);
+    /// Memory writes.
+    pub const W: Self = Self(0b0001);
+    /// Memory reads and writes.
+    pub const RW: Self = Self(0b0011);
+
+    /// Returns the 4-bit `IORW` field of the encoding.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns whether every access kind in `other` is in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for FenceSet {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl Display for FenceSet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (set, c) in [(Self::I, 'i'), (Self::O, 'o'), (Self::R, 'r'), (Self::W, 'w')] {
+            if self.contains(set) {
+                write!(f, "{c}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A control and status register, the operand of the CSR instructions.
+///
+/// Displays as the assembler's name for it, or as its number for
+/// [`Custom`](Self::Custom).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[non_exhaustive]
+pub enum Csr {
+    /// The accrued floating-point exception flags (`fflags`, 0x001).
+    Fflags,
+    /// The dynamic floating-point rounding mode (`frm`, 0x002).
+    Frm,
+    /// `frm` and `fflags` together (`fcsr`, 0x003).
+    Fcsr,
+    /// The cycle counter (`cycle`, 0xC00), read-only.
+    Cycle,
+    /// The real-time clock (`time`, 0xC01), read-only.
+    Time,
+    /// The retired instruction counter (`instret`, 0xC02), read-only.
+    Instret,
+    /// Any other CSR, by its 12-bit number.
+    Custom(u16),
+}
+
+impl Csr {
+    /// Returns the 12-bit CSR number.
+    pub fn number(self) -> u16 {
+        match self {
+            Csr::Fflags => 0x001,
+            Csr::Frm => 0x002,
+            Csr::Fcsr => 0x003,
+            Csr::Cycle => 0xC00,
+            Csr::Time => 0xC01,
+            Csr::Instret => 0xC02,
+            Csr::Custom(number) => number & 0xFFF,
+        }
+    }
+
+    /// Returns whether the CSR is read-only, which the top two bits of its
+    /// number say.
+    pub fn is_read_only(self) -> bool {
+        self.number() >> 10 == 0b11
+    }
+}
+
+impl Display for Csr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Csr::Fflags => write!(f, "fflags"),
+            Csr::Frm => write!(f, "frm"),
+            Csr::Fcsr => write!(f, "fcsr"),
+            Csr::Cycle => write!(f, "cycle"),
+            Csr::Time => write!(f, "time"),
+            Csr::Instret => write!(f, "instret"),
+            Csr::Custom(_) => write!(f, "{:#x}", self.number()),
+        }
+    }
+}
@@ -10,6 +10,36 @@
 //!
 //! - [`arg`]: Argument and memory operand types
 //! - [`asm`]: Assembly text output implementations
+//! - `label_scope`: Scoped label allocation with definition checks (requires `alloc`)
+//! - `const_pool`: Pooled 64-bit constants with PC-relative loads (requires `alloc`)
+//! - `got`: Patchable address slots for load-time-resolved symbols (requires `alloc`)
+//! - `veneer`: Deduplicated long-range call stubs (requires `alloc`)
+//! - `record`: Recorded writer calls for replay into other writers (requires `alloc`)
+//! - `blocks`: Functions built from basic blocks, with layout and fall-through chosen on emission (requires `alloc`)
+//! - [`multiversion`]: One function emitted for several arch configurations
+//! - [`session`]: Two-pass emission with label offsets from a sizing pass
+//! - [`dyn_writer`]: Object-safe writer facade with erased labels and context
+//! - [`error`]: Structured errors for rejected instructions and operands
+//! - [`validate`]: Rejects instructions the arch configuration does not support
+//! - [`dialect`]: Mnemonic and operand rewriting for other assemblers
+//! - [`function`]: Function symbol directives and hot/cold section placement
+//! - [`branch_align`]: Alignment of loop headers and other hot branch targets
+//! - [`save_restore`]: Saving and restoring register sets in prologues and epilogues
+//! - [`patchpoint`]: Fixed-length call sites whose target can be rewritten atomically
+//! - [`data`]: Data directives (integers, strings, zero fill) interleaved with code
+//! - [`jump_table`]: Switch dispatch through a table of self-relative offsets
+//! - [`compress`]: Selection of compressed (C extension) instruction forms
+//! - [`frame`]: Stack frame allocation with guard-page probing
+//! - [`cfi`]: Zicfilp landing pads and Zicfiss shadow-stack checks
+//! - [`branch_hint`]: Two-way branch layout by expected direction
+//! - [`instrument`]: Profiling counters and hook calls at blocks, entries and call sites
+//! - [`select`]: Branchless conditional selects and booleans from comparisons
+//! - [`fma`]: Fused multiply-add with a single rounding
+//! - [`tee`]: Emission to two writers at once, e.g. a listing and machine code
+//! - [`sync`]: Spinlocks with Zihintpause spin hints and Linux futex wait/wake system calls
+//! - [`prefetch`]: Software prefetches by locality and access, lowered to Zicbop `prefetch.r`/`prefetch.w`
+//! - [`timestamp`]: Reads of the `time` counter with `rdtime`
+//! - [`bounds`]: Unsigned index bounds checks that branch or trap, and the checked element operand
 
 use core::error::Error;
 
@@ -23,6 +53,106 @@ pub mod asm;
 #[cfg(feature = "rv-asm-backend")]
 pub mod rv_asm_backend;
 
+/// Scoped label allocation with definition checks.
+#[cfg(feature = "alloc")]
+pub mod label_scope;
+
+/// Runtime-configured layering of writer passes.
+#[cfg(feature = "alloc")]
+pub mod writer_stack;
+
+/// Multi-versioned function emission with a dispatcher stub.
+pub mod multiversion;
+
+/// Two-pass emission with label offsets from a sizing pass.
+pub mod session;
+
+/// Object-safe writer facade for runtime backend selection.
+pub mod dyn_writer;
+
+/// Structured errors for instruction emission.
+pub mod error;
+
+/// Instruction validation against the arch configuration.
+pub mod validate;
+
+/// Assembler dialects for text output.
+pub mod dialect;
+
+/// Function symbols and hot/cold placement for text output.
+pub mod function;
+
+/// Alignment of loop headers and other hot branch targets.
+pub mod branch_align;
+
+/// Register save and restore sequences.
+pub mod save_restore;
+
+/// Patchable call sites for hot-patching and lazy binding.
+pub mod patchpoint;
+
+/// Data directives for jump tables and literals placed next to code.
+pub mod data;
+
+/// Jump tables for lowering dense `switch` statements.
+pub mod jump_table;
+
+/// Compressed (C extension) instruction selection.
+pub mod compress;
+
+/// Probed allocation of large stack frames.
+pub mod frame;
+
+/// Control-flow integrity landing pads and shadow-stack checks.
+pub mod cfi;
+
+/// Conditional branches laid out by expected direction.
+pub mod branch_hint;
+
+/// Profiling probes injected at function entries, blocks and call sites.
+pub mod instrument;
+
+/// Branchless selects and boolean materialization from `slt`/`sltu`.
+pub mod select;
+
+/// Fused multiply-add through `fmadd.d`.
+pub mod fma;
+
+/// Forwarding of every call to two writers.
+pub mod tee;
+
+/// Spinlocks and futex system calls.
+pub mod sync;
+
+/// Software prefetches.
+pub mod prefetch;
+
+/// Reads of the real-time counter.
+pub mod timestamp;
+
+/// Bounds checks on array indices.
+pub mod bounds;
+
+/// Recording of writer calls for later replay.
+#[cfg(feature = "alloc")]
+pub mod record;
+
+/// Basic blocks laid out into a function.
+#[cfg(feature = "alloc")]
+pub mod blocks;
+
+/// Constant pool for 64-bit immediates and floating-point literals.
+#[cfg(feature = "alloc")]
+pub mod const_pool;
+
+/// Patchable address slots for symbols resolved at load time.
+#[cfg(feature = "alloc")]
+pub mod got;
+
+/// Long-range call veneers keyed by target symbol.
+#[cfg(feature = "alloc")]
+pub mod veneer;
+
 /// Core trait for writing RISC-V 64-bit instructions.
 ///
 /// Implementors of this trait can emit individual RISC-V instructions.
@@ -37,6 +167,212 @@ pub trait WriterCore<Context> {
         todo!("ebreak instruction not implemented")
     }
 
+    /// Emits an UNIMP instruction, which always raises an illegal-instruction
+    /// exception.
+    #[track_caller]
+    fn unimp(&mut self, ctx: &mut Context, _cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        todo!("unimp instruction not implemented")
+    }
+
+    /// Marks a point control never reaches, trapping if it does.
+    ///
+    /// Emits [`unimp`](Self::unimp).
+    fn unreachable(&mut self, ctx: &mut Context, cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        self.unimp(ctx, cfg)
+    }
+
+    /// Stops in an attached debugger.
+    ///
+    /// Emits [`ebreak`](Self::ebreak).
+    fn debug_break(&mut self, ctx: &mut Context, cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        self.ebreak(ctx, cfg)
+    }
+
+    /// Emits an ECALL (environment call) instruction, entering the kernel.
+    ///
+    /// Linux takes the system call number from A7 and its arguments from
+    /// A0-A5; the result is returned in A0.
+    #[track_caller]
+    fn ecall(&mut self, ctx: &mut Context, _cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        todo!("ecall instruction not implemented")
+    }
+
+    /// Emits an LPAD (Zicfilp landing pad) instruction.
+    ///
+    /// With landing pads enforced, an indirect jump must land on one whose
+    /// `label` matches the upper 20 bits of T2, or on any landing pad when
+    /// `label` is 0. It is encoded as `auipc zero, label`, a NOP elsewhere.
+    #[track_caller]
+    fn lpad(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _label: u32,
+    ) -> Result<(), Self::Error> {
+        todo!("lpad instruction not implemented")
+    }
+
+    /// Emits an SSPUSH (Zicfiss) instruction, pushing `src` onto the shadow
+    /// stack.
+    ///
+    /// `src` must be RA or T0, the two link registers.
+    #[track_caller]
+    fn sspush(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sspush instruction not implemented")
+    }
+
+    /// Emits an SSPOPCHK (Zicfiss) instruction, popping the shadow stack and
+    /// faulting unless the popped value equals `src`.
+    ///
+    /// `src` must be RA or T0, the two link registers.
+    #[track_caller]
+    fn sspopchk(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sspopchk instruction not implemented")
+    }
+
+    /// Emits a FENCE instruction, ordering the `pred` accesses before it
+    /// against the `succ` accesses after it.
+    #[track_caller]
+    fn fence(&mut self, ctx: &mut Context, _cfg: crate::RiscV64Arch, pred: crate::FenceSet, succ: crate::FenceSet) -> Result<(), Self::Error> {
+        todo!("fence instruction not implemented")
+    }
+
+    /// Emits a FENCE.I instruction (Zifencei), making stores to instruction
+    /// memory visible to instruction fetches on this hart.
+    ///
+    /// Other harts that will run the written code need a FENCE.I of their
+    /// own, usually requested through the `riscv_flush_icache` system call.
+    #[track_caller]
+    fn fence_i(&mut self, ctx: &mut Context, _cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        todo!("fence.i instruction not implemented")
+    }
+
+    /// Emits a PAUSE instruction (Zihintpause), a hint that the code is a
+    /// spin-wait loop.
+    ///
+    /// PAUSE is a FENCE with no successor set, so cores without Zihintpause
+    /// execute it as an ordinary fence.
+    #[track_caller]
+    fn pause(&mut self, ctx: &mut Context, _cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        todo!("pause instruction not implemented")
+    }
+
+    /// Emits a PREFETCH.R instruction (Zicbop), a hint that the cache block
+    /// holding `mem` will soon be read.
+    ///
+    /// The offset of `mem` must be a multiple of 32. Prefetches never fault,
+    /// and are ORI encodings writing `zero`, so cores without Zicbop execute
+    /// them as no-ops.
+    #[track_caller]
+    fn prefetch_r(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("prefetch.r instruction not implemented")
+    }
+
+    /// Emits a PREFETCH.W instruction (Zicbop), a hint that the cache block
+    /// holding `mem` will soon be written.
+    ///
+    /// The offset of `mem` must be a multiple of 32.
+    #[track_caller]
+    fn prefetch_w(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("prefetch.w instruction not implemented")
+    }
+
+    /// Emits a CBO.CLEAN instruction (Zicbom), writing the cache block
+    /// holding `mem` back to memory if it is dirty, without invalidating it.
+    ///
+    /// The offset of `mem` must be 0.
+    #[track_caller]
+    fn cbo_clean(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("cbo.clean instruction not implemented")
+    }
+
+    /// Emits a CBO.FLUSH instruction (Zicbom), writing the cache block
+    /// holding `mem` back to memory if it is dirty and invalidating it.
+    ///
+    /// The offset of `mem` must be 0.
+    #[track_caller]
+    fn cbo_flush(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("cbo.flush instruction not implemented")
+    }
+
+    /// Emits a CBO.INVAL instruction (Zicbom), invalidating the cache block
+    /// holding `mem`.
+    ///
+    /// Depending on how the execution environment configures it, dirty data
+    /// in the block is either discarded or written back first, so only use
+    /// it on blocks whose contents may be lost. The offset of `mem` must be
+    /// 0.
+    #[track_caller]
+    fn cbo_inval(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("cbo.inval instruction not implemented")
+    }
+
+    /// Emits an AMOSWAP.W instruction with acquire-release ordering (A
+    /// extension): atomically stores the low 32 bits of `src` to the word at
+    /// the address in `addr` and loads its old value, sign-extended, into
+    /// `dest`.
+    #[track_caller]
+    fn amoswap_w(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+        _addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("amoswap.w instruction not implemented")
+    }
+
+    /// Emits an AMOSWAP.D instruction with acquire-release ordering (A
+    /// extension): atomically stores `src` to the doubleword at the address
+    /// in `addr` and loads its old value into `dest`.
+    #[track_caller]
+    fn amoswap_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+        _addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("amoswap.d instruction not implemented")
+    }
+
     /// Emits a MV (move/copy register) pseudo-instruction.
     /// Implemented as: ADDI dest, src, 0
     #[track_caller]
@@ -89,6 +425,42 @@ pub trait WriterCore<Context> {
         todo!("addi instruction not implemented")
     }
 
+    /// Computes `dest = a + imm` with an immediate operand.
+    ///
+    /// Uses `addi` when the immediate fits in 12 signed bits; otherwise
+    /// forwards to [`add`](Self::add) with `imm` as a literal.
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        match i32::try_from(imm) {
+            Ok(i) if (-2048..=2047).contains(&i) => self.addi(ctx, cfg, dest, a, i),
+            _ => self.add(ctx, cfg, dest, a, &(imm as u64)),
+        }
+    }
+
+    /// Computes `dest = a - imm` with an immediate operand.
+    ///
+    /// Uses `addi` with the negated immediate when it fits in 12 signed bits; otherwise
+    /// forwards to [`sub`](Self::sub) with `imm` as a literal.
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        match i32::try_from(imm.wrapping_neg()) {
+            Ok(i) if (-2048..=2047).contains(&i) => self.addi(ctx, cfg, dest, a, i),
+            _ => self.sub(ctx, cfg, dest, a, &(imm as u64)),
+        }
+    }
+
     /// Emits a SD (store doubleword) instruction.
     #[track_caller]
     fn sd(
@@ -125,6 +497,18 @@ pub trait WriterCore<Context> {
         todo!("lw instruction not implemented")
     }
 
+    /// Emits a LWU (load word, zero-extended) instruction.
+    #[track_caller]
+    fn lwu(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("lwu instruction not implemented")
+    }
+
     /// Emits a SW (store word) instruction.
     #[track_caller]
     fn sw(
@@ -149,6 +533,18 @@ pub trait WriterCore<Context> {
         todo!("lb instruction not implemented")
     }
 
+    /// Emits an LBU (load byte, zero-extended) instruction.
+    #[track_caller]
+    fn lbu(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("lbu instruction not implemented")
+    }
+
     /// Emits a SB (store byte) instruction.
     #[track_caller]
     fn sb(
@@ -173,6 +569,18 @@ pub trait WriterCore<Context> {
         todo!("lh instruction not implemented")
     }
 
+    /// Emits an LHU (load halfword, zero-extended) instruction.
+    #[track_caller]
+    fn lhu(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("lhu instruction not implemented")
+    }
+
     /// Emits a SH (store halfword) instruction.
     #[track_caller]
     fn sh(
@@ -288,6 +696,50 @@ pub trait WriterCore<Context> {
         todo!("bgeu instruction not implemented")
     }
 
+    /// Emits a branch to `target` taken if `cond` holds between `a` and `b`.
+    ///
+    /// The pseudo conditions GT, LE, GTU and LEU become BLT, BGE, BLTU and
+    /// BGEU with the operands swapped.
+    fn bcond(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        cond: ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        match cond {
+            ConditionCode::EQ => self.beq(ctx, cfg, a, b, target),
+            ConditionCode::NE => self.bne(ctx, cfg, a, b, target),
+            ConditionCode::LT => self.blt(ctx, cfg, a, b, target),
+            ConditionCode::GE => self.bge(ctx, cfg, a, b, target),
+            ConditionCode::LTU => self.bltu(ctx, cfg, a, b, target),
+            ConditionCode::GEU => self.bgeu(ctx, cfg, a, b, target),
+            ConditionCode::GT => self.blt(ctx, cfg, b, a, target),
+            ConditionCode::LE => self.bge(ctx, cfg, b, a, target),
+            ConditionCode::GTU => self.bltu(ctx, cfg, b, a, target),
+            ConditionCode::LEU => self.bgeu(ctx, cfg, b, a, target),
+        }
+    }
+
+    /// Emits a branch to `target` taken if `cond` holds between `a` and zero
+    /// (the BEQZ, BNEZ, BLTZ, BGEZ, BLEZ and BGTZ pseudo-instructions).
+    ///
+    /// RISC-V branches only compare registers, so zero, through the zero
+    /// register, is the one immediate they take; load any other constant
+    /// with [`li`](Self::li) and branch with [`bcond`](Self::bcond).
+    fn bcond_zero(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        cond: ConditionCode,
+        a: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.bcond(ctx, cfg, cond, a, &portal_pc_asm_common::types::reg::Reg(0), target)
+    }
+
     /// Emits an AND (bitwise AND) instruction.
     #[track_caller]
     fn and(
@@ -301,6 +753,37 @@ pub trait WriterCore<Context> {
         todo!("and instruction not implemented")
     }
 
+    /// Emits an ANDI (bitwise AND immediate) instruction.
+    #[track_caller]
+    fn andi(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+        _imm: i32,
+    ) -> Result<(), Self::Error> {
+        todo!("andi instruction not implemented")
+    }
+
+    /// Computes `dest = a & imm` with an immediate operand.
+    ///
+    /// Uses `andi` when the immediate fits in 12 signed bits; otherwise
+    /// forwards to [`and`](Self::and) with `imm` as a literal.
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        match i32::try_from(imm) {
+            Ok(i) if (-2048..=2047).contains(&i) => self.andi(ctx, cfg, dest, a, i),
+            _ => self.and(ctx, cfg, dest, a, &(imm as u64)),
+        }
+    }
+
     /// Emits an OR (bitwise OR) instruction.
     #[track_caller]
     fn or(
@@ -314,6 +797,37 @@ pub trait WriterCore<Context> {
         todo!("or instruction not implemented")
     }
 
+    /// Emits an ORI (bitwise OR immediate) instruction.
+    #[track_caller]
+    fn ori(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+        _imm: i32,
+    ) -> Result<(), Self::Error> {
+        todo!("ori instruction not implemented")
+    }
+
+    /// Computes `dest = a | imm` with an immediate operand.
+    ///
+    /// Uses `ori` when the immediate fits in 12 signed bits; otherwise
+    /// forwards to [`or`](Self::or) with `imm` as a literal.
+    fn or_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        match i32::try_from(imm) {
+            Ok(i) if (-2048..=2047).contains(&i) => self.ori(ctx, cfg, dest, a, i),
+            _ => self.or(ctx, cfg, dest, a, &(imm as u64)),
+        }
+    }
+
     /// Emits an XOR (bitwise exclusive OR) instruction.
     #[track_caller]
     fn xor(
@@ -327,6 +841,37 @@ pub trait WriterCore<Context> {
         todo!("xor instruction not implemented")
     }
 
+    /// Emits an XORI (bitwise exclusive OR immediate) instruction.
+    #[track_caller]
+    fn xori(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+        _imm: i32,
+    ) -> Result<(), Self::Error> {
+        todo!("xori instruction not implemented")
+    }
+
+    /// Computes `dest = a ^ imm` with an immediate operand.
+    ///
+    /// Uses `xori` when the immediate fits in 12 signed bits; otherwise
+    /// forwards to [`xor`](Self::xor) with `imm` as a literal.
+    fn xor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        match i32::try_from(imm) {
+            Ok(i) if (-2048..=2047).contains(&i) => self.xori(ctx, cfg, dest, a, i),
+            _ => self.xor(ctx, cfg, dest, a, &(imm as u64)),
+        }
+    }
+
     /// Emits a SLL (shift left logical) instruction.
     #[track_caller]
     fn sll(
@@ -366,9 +911,9 @@ pub trait WriterCore<Context> {
         todo!("sra instruction not implemented")
     }
 
-    /// Emits a SLT (set less than, signed) instruction.
+    /// Emits a ROL (rotate left, Zbb) instruction.
     #[track_caller]
-    fn slt(
+    fn rol(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::RiscV64Arch,
@@ -376,12 +921,12 @@ pub trait WriterCore<Context> {
         _a: &(dyn MemArg + '_),
         _b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("slt instruction not implemented")
+        todo!("rol instruction not implemented")
     }
 
-    /// Emits a SLTU (set less than, unsigned) instruction.
+    /// Emits a ROR (rotate right, Zbb) instruction.
     #[track_caller]
-    fn sltu(
+    fn ror(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::RiscV64Arch,
@@ -389,7 +934,117 @@ pub trait WriterCore<Context> {
         _a: &(dyn MemArg + '_),
         _b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("sltu instruction not implemented")
+        todo!("ror instruction not implemented")
+    }
+
+    /// Emits a CPOP (count set bits, Zbb) instruction.
+    #[track_caller]
+    fn cpop(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("cpop instruction not implemented")
+    }
+
+    /// Emits a CLZ (count leading zeros, Zbb) instruction.
+    #[track_caller]
+    fn clz(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("clz instruction not implemented")
+    }
+
+    /// Emits a CTZ (count trailing zeros, Zbb) instruction.
+    #[track_caller]
+    fn ctz(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("ctz instruction not implemented")
+    }
+
+    /// Emits a REV8 (reverse bytes, Zbb) instruction.
+    #[track_caller]
+    fn rev8(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("rev8 instruction not implemented")
+    }
+
+    /// Emits a SEXT.B (sign-extend byte, Zbb) instruction.
+    #[track_caller]
+    fn sext_b(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sext.b instruction not implemented")
+    }
+
+    /// Emits a SEXT.H (sign-extend halfword, Zbb) instruction.
+    #[track_caller]
+    fn sext_h(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sext.h instruction not implemented")
+    }
+
+    /// Emits a SEXT.W (sign-extend word; `addiw rd, rs, 0`) instruction.
+    #[track_caller]
+    fn sext_w(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sext.w instruction not implemented")
+    }
+
+    /// Emits a SLT (set less than, signed) instruction.
+    #[track_caller]
+    fn slt(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("slt instruction not implemented")
+    }
+
+    /// Emits a SLTU (set less than, unsigned) instruction.
+    #[track_caller]
+    fn sltu(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sltu instruction not implemented")
     }
 
     /// Emits a LUI (load upper immediate) instruction.
@@ -416,6 +1071,19 @@ pub trait WriterCore<Context> {
         todo!("auipc instruction not implemented")
     }
 
+    /// Emits an LA (load address) pseudo-instruction for a symbol operand.
+    /// Expands to AUIPC + ADDI.
+    #[track_caller]
+    fn la(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("la instruction not implemented")
+    }
+
     /// Emits a LI (load immediate) pseudo-instruction.
     /// Uses LUI + ADDI for large immediates.
     #[track_caller]
@@ -488,6 +1156,19 @@ pub trait WriterCore<Context> {
         todo!("mulh instruction not implemented")
     }
 
+    /// Emits a MULHU (multiply high, unsigned×unsigned) instruction.
+    #[track_caller]
+    fn mulhu(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("mulhu instruction not implemented")
+    }
+
     /// Emits a DIV (divide, signed) instruction.
     #[track_caller]
     fn div(
@@ -618,6 +1299,118 @@ pub trait WriterCore<Context> {
         todo!("fdiv.d instruction not implemented")
     }
 
+    /// Emits a FMADD.D (fused multiply-add double): `dest = a * b + c`, rounding once.
+    #[track_caller]
+    fn fmadd_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmadd.d instruction not implemented")
+    }
+
+    /// Emits a FMADD.S (fused multiply-add single): `dest = a * b + c`, rounding once.
+    #[track_caller]
+    fn fmadd_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmadd.s instruction not implemented")
+    }
+
+    /// Emits a FMSUB.D (fused multiply-subtract double): `dest = a * b - c`, rounding once.
+    #[track_caller]
+    fn fmsub_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmsub.d instruction not implemented")
+    }
+
+    /// Emits a FMSUB.S (fused multiply-subtract single): `dest = a * b - c`, rounding once.
+    #[track_caller]
+    fn fmsub_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmsub.s instruction not implemented")
+    }
+
+    /// Emits a FNMSUB.D (negated fused multiply-subtract double): `dest = -(a * b) + c`, rounding once.
+    #[track_caller]
+    fn fnmsub_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fnmsub.d instruction not implemented")
+    }
+
+    /// Emits a FNMSUB.S (negated fused multiply-subtract single): `dest = -(a * b) + c`, rounding once.
+    #[track_caller]
+    fn fnmsub_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fnmsub.s instruction not implemented")
+    }
+
+    /// Emits a FNMADD.D (negated fused multiply-add double): `dest = -(a * b) - c`, rounding once.
+    #[track_caller]
+    fn fnmadd_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fnmadd.d instruction not implemented")
+    }
+
+    /// Emits a FNMADD.S (negated fused multiply-add single): `dest = -(a * b) - c`, rounding once.
+    #[track_caller]
+    fn fnmadd_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fnmadd.s instruction not implemented")
+    }
+
     /// Emits a FMOV.D (floating-point move double) pseudo-instruction.
     /// Implemented as: FSGNJ.D dest, src, src
     #[track_caller]
@@ -654,121 +1447,1351 @@ pub trait WriterCore<Context> {
     ) -> Result<(), Self::Error> {
         todo!("fcvt.l.d instruction not implemented")
     }
-}
 
-/// Extended writer trait with label support.
-///
-/// This trait extends [`WriterCore`] with methods for working with labels,
-/// enabling structured control flow in generated code.
-pub trait Writer<L, Context>: WriterCore<Context> {
-    /// Sets a label at the current position.
+    /// Emits a FEQ.D instruction: `dest = (a == b)`, 0 if either operand is NaN.
     #[track_caller]
-    fn set_label(
+    fn feq_d(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::RiscV64Arch,
-        _s: L,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("set_label not implemented")
+        todo!("feq.d instruction not implemented")
     }
 
-    /// Emits a JAL instruction to a label.
+    /// Emits a FLT.D instruction: `dest = (a < b)`, 0 if either operand is NaN.
     #[track_caller]
-    fn jal_label(
+    fn flt_d(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::RiscV64Arch,
         _dest: &(dyn MemArg + '_),
-        _label: L,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("jal_label not implemented")
+        todo!("flt.d instruction not implemented")
     }
 
-    /// Emits a load-address pseudo-instruction to a label.
+    /// Emits a FLE.D instruction: `dest = (a <= b)`, 0 if either operand is NaN.
+    #[track_caller]
+    fn fle_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fle.d instruction not implemented")
+    }
+
+    /// Emits a FMIN.D instruction.
     ///
-    /// Loads the *address* of `label` into `dest` without transferring control.
-    /// Emits the assembler `la` pseudo-instruction (`auipc`+`addi` pair).
-    /// Unlike `jal_label`, this does NOT modify the program counter.
+    /// If one operand is NaN the other is returned; if both are, the
+    /// canonical NaN. -0.0 is less than +0.0.
     #[track_caller]
-    fn la_label(
+    fn fmin_d(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::RiscV64Arch,
         _dest: &(dyn MemArg + '_),
-        _label: L,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("la_label not implemented")
+        todo!("fmin.d instruction not implemented")
     }
 
-    /// Emits a branch instruction to a label based on condition code.
+    /// Emits a FMAX.D instruction.
+    ///
+    /// If one operand is NaN the other is returned; if both are, the
+    /// canonical NaN. +0.0 is greater than -0.0.
     #[track_caller]
-    fn bcond_label(
+    fn fmax_d(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::RiscV64Arch,
-        _cond: ConditionCode,
+        _dest: &(dyn MemArg + '_),
         _a: &(dyn MemArg + '_),
         _b: &(dyn MemArg + '_),
-        _label: L,
     ) -> Result<(), Self::Error> {
-        todo!("bcond_label not implemented")
+        todo!("fmax.d instruction not implemented")
     }
-}
 
-// Macro to forward WriterCore methods through Box/&mut T
-#[macro_export]
-macro_rules! writer_dispatch {
-    ($( [ $($t:tt)* ] [$($u:tt)*] $ty:ty => $e:ty [$l:ty] [ $ctx:ty] [$wrapped:ty]),*) => {
-        const _: () = {
-            $(
-                impl<$($t)*> $crate::out::WriterCore<$ctx> for $ty{
-                    type Error = $e;
-                    fn ebreak(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::ebreak(&mut **self,ctx, cfg)
-                    }
-                    fn mv(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::mv(&mut **self, ctx, cfg, dest, src)
-                    }
-                    fn sd(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::sd(&mut **self, ctx, cfg, src, mem)
-                    }
-                    fn ld(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::ld(&mut **self, ctx, cfg, dest, mem)
-                    }
-                    fn add(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::add(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn sub(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::sub(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn mul(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::mul(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn div(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::div(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn and(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::and(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn or(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::or(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn xor(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::xor(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn sll(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::sll(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn srl(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::srl(&mut **self, ctx, cfg, dest, a, b)
-                    }
-                    fn ret(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::ret(&mut **self, ctx, cfg)
-                    }
-                    fn li(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), val: u64) -> Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::li(&mut **self, ctx, cfg, dest, val)
-                    }
-                }
+    /// Emits a FSQRT.D (floating-point square root double) instruction.
+    #[track_caller]
+    fn fsqrt_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fsqrt.d instruction not implemented")
+    }
+
+    /// Emits a FABS.D (floating-point absolute value double) pseudo-instruction.
+    /// Implemented as: FSGNJX.D dest, src, src
+    #[track_caller]
+    fn fabs_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fabs.d pseudo-instruction not implemented")
+    }
+
+    /// Emits a FNEG.D (floating-point negate double) pseudo-instruction.
+    /// Implemented as: FSGNJN.D dest, src, src
+    #[track_caller]
+    fn fneg_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fneg.d pseudo-instruction not implemented")
+    }
+
+    /// Emits a FCVT.D.W (convert signed 32-bit integer to double) instruction.
+    #[track_caller]
+    fn fcvt_d_w(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.d.w instruction not implemented")
+    }
+
+    /// Emits a FCVT.D.WU (convert unsigned 32-bit integer to double) instruction.
+    #[track_caller]
+    fn fcvt_d_wu(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.d.wu instruction not implemented")
+    }
+
+    /// Emits a FCVT.D.LU (convert unsigned 64-bit integer to double) instruction.
+    #[track_caller]
+    fn fcvt_d_lu(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.d.lu instruction not implemented")
+    }
+
+    /// Emits a FCVT.W.D (convert double to signed 32-bit integer) instruction.
+    #[track_caller]
+    fn fcvt_w_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.w.d instruction not implemented")
+    }
+
+    /// Emits a FCVT.WU.D (convert double to unsigned 32-bit integer) instruction.
+    #[track_caller]
+    fn fcvt_wu_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.wu.d instruction not implemented")
+    }
+
+    /// Emits a FCVT.LU.D (convert double to unsigned 64-bit integer) instruction.
+    #[track_caller]
+    fn fcvt_lu_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.lu.d instruction not implemented")
+    }
+
+    /// Emits a FLW (floating-point load single) instruction.
+    #[track_caller]
+    fn flw(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("flw instruction not implemented")
+    }
+
+    /// Emits a FSW (floating-point store single) instruction.
+    #[track_caller]
+    fn fsw(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _src: &(dyn MemArg + '_),
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fsw instruction not implemented")
+    }
+
+    /// Emits a FADD.S (floating-point add single) instruction.
+    #[track_caller]
+    fn fadd_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fadd.s instruction not implemented")
+    }
+
+    /// Emits a FSUB.S (floating-point subtract single) instruction.
+    #[track_caller]
+    fn fsub_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fsub.s instruction not implemented")
+    }
+
+    /// Emits a FMUL.S (floating-point multiply single) instruction.
+    #[track_caller]
+    fn fmul_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmul.s instruction not implemented")
+    }
+
+    /// Emits a FDIV.S (floating-point divide single) instruction.
+    #[track_caller]
+    fn fdiv_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fdiv.s instruction not implemented")
+    }
+
+    /// Emits a FMOV.S (floating-point move single) pseudo-instruction.
+    /// Implemented as: FSGNJ.S dest, src, src
+    #[track_caller]
+    fn fmov_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmv.s pseudo-instruction not implemented")
+    }
+
+    /// Emits a FCVT.S.L (convert signed 64-bit integer to single) instruction.
+    #[track_caller]
+    fn fcvt_s_l(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.s.l instruction not implemented")
+    }
+
+    /// Emits a FCVT.L.S (convert single to signed 64-bit integer) instruction.
+    #[track_caller]
+    fn fcvt_l_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.l.s instruction not implemented")
+    }
+
+    /// Emits a FEQ.S instruction: `dest = (a == b)`, 0 if either operand is NaN.
+    #[track_caller]
+    fn feq_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("feq.s instruction not implemented")
+    }
+
+    /// Emits a FLT.S instruction: `dest = (a < b)`, 0 if either operand is NaN.
+    #[track_caller]
+    fn flt_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("flt.s instruction not implemented")
+    }
+
+    /// Emits a FLE.S instruction: `dest = (a <= b)`, 0 if either operand is NaN.
+    #[track_caller]
+    fn fle_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fle.s instruction not implemented")
+    }
+
+    /// Emits a FMIN.S instruction.
+    ///
+    /// NaN and signed-zero handling is as for [`Self::fmin_d`].
+    #[track_caller]
+    fn fmin_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmin.s instruction not implemented")
+    }
+
+    /// Emits a FMAX.S instruction.
+    ///
+    /// NaN and signed-zero handling is as for [`Self::fmax_d`].
+    #[track_caller]
+    fn fmax_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmax.s instruction not implemented")
+    }
+
+    /// Emits a FSQRT.S (floating-point square root single) instruction.
+    #[track_caller]
+    fn fsqrt_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fsqrt.s instruction not implemented")
+    }
+
+    /// Emits a FABS.S (floating-point absolute value single) pseudo-instruction.
+    /// Implemented as: FSGNJX.S dest, src, src
+    #[track_caller]
+    fn fabs_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fabs.s pseudo-instruction not implemented")
+    }
+
+    /// Emits a FNEG.S (floating-point negate single) pseudo-instruction.
+    /// Implemented as: FSGNJN.S dest, src, src
+    #[track_caller]
+    fn fneg_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fneg.s pseudo-instruction not implemented")
+    }
+
+    /// Emits a FCVT.S.W (convert signed 32-bit integer to single) instruction.
+    #[track_caller]
+    fn fcvt_s_w(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.s.w instruction not implemented")
+    }
+
+    /// Emits a FCVT.S.WU (convert unsigned 32-bit integer to single) instruction.
+    #[track_caller]
+    fn fcvt_s_wu(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.s.wu instruction not implemented")
+    }
+
+    /// Emits a FCVT.S.LU (convert unsigned 64-bit integer to single) instruction.
+    #[track_caller]
+    fn fcvt_s_lu(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.s.lu instruction not implemented")
+    }
+
+    /// Emits a FCVT.W.S (convert single to signed 32-bit integer) instruction.
+    #[track_caller]
+    fn fcvt_w_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.w.s instruction not implemented")
+    }
+
+    /// Emits a FCVT.WU.S (convert single to unsigned 32-bit integer) instruction.
+    #[track_caller]
+    fn fcvt_wu_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.wu.s instruction not implemented")
+    }
+
+    /// Emits a FCVT.LU.S (convert single to unsigned 64-bit integer) instruction.
+    #[track_caller]
+    fn fcvt_lu_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.lu.s instruction not implemented")
+    }
+
+    /// Emits a FCVT.S.D (convert double to single) instruction.
+    #[track_caller]
+    fn fcvt_s_d(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.s.d instruction not implemented")
+    }
+
+    /// Emits a FCVT.D.S (convert single to double) instruction.
+    #[track_caller]
+    fn fcvt_d_s(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcvt.d.s instruction not implemented")
+    }
+
+    /// Emits an FSRM instruction (`csrrw zero, frm, src`), setting the dynamic
+    /// rounding mode in the `frm` CSR from the low three bits of `src`.
+    ///
+    /// Instructions whose [`RoundingMode`](crate::RoundingMode) is
+    /// [`Dynamic`](crate::RoundingMode::Dynamic) round as `frm` says.
+    #[track_caller]
+    fn fsrm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fsrm instruction not implemented")
+    }
+
+    /// Emits an FRRM instruction (`csrrs dest, frm, zero`), reading the dynamic
+    /// rounding mode from the `frm` CSR into `dest`.
+    #[track_caller]
+    fn frrm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("frrm instruction not implemented")
+    }
+
+    /// Emits a CSRRW instruction, writing `src` to `csr` and the old value
+    /// to `dest`.
+    ///
+    /// With `x0` as `dest` the CSR is not read, so the instruction has no
+    /// read side effects.
+    #[track_caller]
+    fn csrrw(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _csr: crate::Csr,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("csrrw instruction not implemented")
+    }
+
+    /// Emits a CSRRS instruction, setting the bits of `csr` that are set in
+    /// `src` and writing the old value to `dest`.
+    ///
+    /// With `x0` as `src` the CSR is only read.
+    #[track_caller]
+    fn csrrs(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _csr: crate::Csr,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("csrrs instruction not implemented")
+    }
+
+    /// Emits a CSRRC instruction, clearing the bits of `csr` that are set in
+    /// `src` and writing the old value to `dest`.
+    ///
+    /// With `x0` as `src` the CSR is only read.
+    #[track_caller]
+    fn csrrc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _csr: crate::Csr,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("csrrc instruction not implemented")
+    }
+
+    /// Emits a CSRRWI instruction, writing the 5-bit unsigned `imm` to `csr`
+    /// and the old value to `dest`.
+    #[track_caller]
+    fn csrrwi(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _csr: crate::Csr,
+        _imm: u8,
+    ) -> Result<(), Self::Error> {
+        todo!("csrrwi instruction not implemented")
+    }
+
+    /// Emits a CSRRSI instruction, setting the bits of `csr` that are set in
+    /// the 5-bit unsigned `imm` and writing the old value to `dest`.
+    ///
+    /// With an `imm` of 0 the CSR is only read.
+    #[track_caller]
+    fn csrrsi(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _csr: crate::Csr,
+        _imm: u8,
+    ) -> Result<(), Self::Error> {
+        todo!("csrrsi instruction not implemented")
+    }
+
+    /// Emits a CSRRCI instruction, clearing the bits of `csr` that are set in
+    /// the 5-bit unsigned `imm` and writing the old value to `dest`.
+    ///
+    /// With an `imm` of 0 the CSR is only read.
+    #[track_caller]
+    fn csrrci(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _csr: crate::Csr,
+        _imm: u8,
+    ) -> Result<(), Self::Error> {
+        todo!("csrrci instruction not implemented")
+    }
+
+    /// Emits an RDCYCLE instruction (`csrrs dest, cycle, zero`), reading the
+    /// cycle counter into `dest`.
+    ///
+    /// Whether user code may read the counter is up to the execution
+    /// environment; where it may not, the read traps.
+    #[track_caller]
+    fn rdcycle(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.csrrs(
+            ctx,
+            cfg,
+            dest,
+            crate::Csr::Cycle,
+            &portal_pc_asm_common::types::reg::Reg(0),
+        )
+    }
+
+    /// Emits an RDTIME instruction (`csrrs dest, time, zero`), reading the
+    /// real-time counter into `dest`.
+    #[track_caller]
+    fn rdtime(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.csrrs(
+            ctx,
+            cfg,
+            dest,
+            crate::Csr::Time,
+            &portal_pc_asm_common::types::reg::Reg(0),
+        )
+    }
+
+    /// Emits raw bytes as data.
+    ///
+    /// Generates a `.byte` directive (or equivalent) for the given bytes.
+    #[track_caller]
+    fn db(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _bytes: &[u8],
+    ) -> Result<(), Self::Error> {
+        todo!("db directive not implemented")
+    }
+
+    /// Returns the current byte offset into the output stream, if tracked.
+    ///
+    /// Binary writers return `Some(n)`; text writers return `None`.
+    fn current_offset(&self) -> Option<usize> {
+        None
+    }
+
+    /// Emits alignment padding to the next `alignment`-byte boundary.
+    ///
+    /// Binary writers emit NOP words; text writers emit `.balign`.
+    #[track_caller]
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _alignment: usize,
+    ) -> Result<(), Self::Error> {
+        todo!("align_to not implemented")
+    }
+
+    /// Emits a `nop` instruction.
+    #[track_caller]
+    fn nop(&mut self, ctx: &mut Context, _cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        todo!("nop not implemented")
+    }
+
+    /// Emits exactly `n_bytes` bytes of `nop` instructions.
+    ///
+    /// `n_bytes` must be a multiple of 4, or of 2 with the C extension, where
+    /// an odd halfword is filled with `c.nop`. Otherwise an
+    /// [`EmitError::InvalidOperands`](error::EmitError::InvalidOperands) is
+    /// returned.
+    #[track_caller]
+    fn nops(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        todo!("nops not implemented")
+    }
+
+    /// Pads with `nop`s to the next `1 << pow2`-byte boundary.
+    ///
+    /// Text writers emit a `.p2align` directive; binary writers emit the
+    /// `nop`s directly. Use it before loop headers and other hot branch
+    /// targets.
+    #[track_caller]
+    fn align(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _pow2: u32,
+    ) -> Result<(), Self::Error> {
+        todo!("align not implemented")
+    }
+}
+
+/// Extended writer trait with label support.
+///
+/// This trait extends [`WriterCore`] with methods for working with labels,
+/// enabling structured control flow in generated code.
+pub trait Writer<L, Context>: WriterCore<Context> {
+    /// Sets a label at the current position.
+    #[track_caller]
+    fn set_label(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _s: L,
+    ) -> Result<(), Self::Error> {
+        todo!("set_label not implemented")
+    }
+
+    /// Emits a JAL instruction to a label.
+    #[track_caller]
+    fn jal_label(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _label: L,
+    ) -> Result<(), Self::Error> {
+        todo!("jal_label not implemented")
+    }
+
+    /// Emits a load-address pseudo-instruction to a label.
+    ///
+    /// Loads the *address* of `label` into `dest` without transferring control.
+    /// Emits the assembler `la` pseudo-instruction (`auipc`+`addi` pair).
+    /// Unlike `jal_label`, this does NOT modify the program counter.
+    #[track_caller]
+    fn la_label(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _dest: &(dyn MemArg + '_),
+        _label: L,
+    ) -> Result<(), Self::Error> {
+        todo!("la_label not implemented")
+    }
+
+    /// Emits a branch instruction to a label based on condition code.
+    #[track_caller]
+    fn bcond_label(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _cond: ConditionCode,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _label: L,
+    ) -> Result<(), Self::Error> {
+        todo!("bcond_label not implemented")
+    }
+
+    /// Emits a branch to a label, expected to go as `hint` says.
+    ///
+    /// RISC-V has no branch hint encoding; by default this is
+    /// [`bcond_label`](Self::bcond_label). Writers that lay out code
+    /// themselves may use the hint.
+    #[allow(clippy::too_many_arguments)]
+    fn bcond_label_hinted(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        cond: ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        label: L,
+        _hint: crate::BranchHint,
+    ) -> Result<(), Self::Error> {
+        self.bcond_label(ctx, cfg, cond, a, b, label)
+    }
+
+    /// Emits a 32-bit data word holding the signed offset from the word
+    /// itself to `label` (`.4byte label - .`).
+    ///
+    /// Entries of position-independent jump tables are written this way (see
+    /// [`jump_table`]).
+    #[track_caller]
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        _label: L,
+    ) -> Result<(), Self::Error> {
+        todo!("dd_label not implemented")
+    }
+
+    /// Runs `body` inside a [`LabelScope`](label_scope::LabelScope) that allocates
+    /// labels with `alloc`.
+    ///
+    /// Once `body` returns, every label it allocated must have been defined exactly
+    /// once; otherwise a [`LabelScopeError`](label_scope::LabelScopeError) is returned.
+    #[cfg(feature = "alloc")]
+    fn loop_scope<T, F: FnMut() -> L>(
+        &mut self,
+        ctx: &mut Context,
+        alloc: F,
+        body: impl FnOnce(
+            &mut label_scope::LabelScope<'_, Self, L, F>,
+            &mut Context,
+        ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>,
+    ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>
+    where
+        Self: Sized,
+        L: Clone + PartialEq,
+    {
+        let mut scope = label_scope::LabelScope::new(self, alloc);
+        let result = body(&mut scope, ctx)?;
+        scope.finish()?;
+        Ok(result)
+    }
+
+    /// Allocates a single label with `alloc` and passes it to `body`, like
+    /// [`Writer::loop_scope`].
+    #[cfg(feature = "alloc")]
+    fn with_label<T, F: FnMut() -> L>(
+        &mut self,
+        ctx: &mut Context,
+        alloc: F,
+        body: impl FnOnce(
+            &mut label_scope::LabelScope<'_, Self, L, F>,
+            &mut Context,
+            L,
+        ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>,
+    ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>
+    where
+        Self: Sized,
+        L: Clone + PartialEq,
+    {
+        self.loop_scope(ctx, alloc, |scope, ctx| {
+            let label = scope.label();
+            body(scope, ctx, label)
+        })
+    }
+}
+
+// Macro to forward WriterCore methods through Box/&mut T
+#[macro_export]
+macro_rules! writer_dispatch {
+    (@core [ $($t:tt)* ] $ty:ty => $e:ty [$ctx:ty] [$wrapped:ty]) => {
+        impl<$($t)*> $crate::out::WriterCore<$ctx> for $ty{
+            type Error = $e;
+            fn ebreak(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ebreak(&mut **self,ctx, cfg)
+            }
+            fn fabs_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fabs_s(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fadd_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fadd_s(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fcvt_d_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_d_s(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_l_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_l_s(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_lu_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_lu_s(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_s_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_s_d(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_s_l(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_s_l(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_s_lu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_s_lu(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_s_w(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_s_w(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_s_wu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_s_wu(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_w_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_w_s(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_wu_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_wu_s(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fdiv_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fdiv_s(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn feq_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::feq_s(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fle_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fle_s(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn flt_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::flt_s(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn flw(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::flw(&mut **self, ctx, cfg, dest, mem)
+            }
+            fn fmax_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmax_s(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fmin_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmin_s(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fmov_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmov_s(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fmul_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmul_s(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fneg_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fneg_s(&mut **self, ctx, cfg, dest, src)
+            }
+            fn frrm(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::frrm(&mut **self, ctx, cfg, dest)
+            }
+            fn csrrw(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::csrrw(&mut **self, ctx, cfg, dest, csr, src)
+            }
+            fn csrrs(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::csrrs(&mut **self, ctx, cfg, dest, csr, src)
+            }
+            fn csrrc(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::csrrc(&mut **self, ctx, cfg, dest, csr, src)
+            }
+            fn csrrwi(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, imm: u8) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::csrrwi(&mut **self, ctx, cfg, dest, csr, imm)
+            }
+            fn csrrsi(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, imm: u8) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::csrrsi(&mut **self, ctx, cfg, dest, csr, imm)
+            }
+            fn csrrci(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, imm: u8) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::csrrci(&mut **self, ctx, cfg, dest, csr, imm)
+            }
+            fn rdcycle(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::rdcycle(&mut **self, ctx, cfg, dest)
+            }
+            fn rdtime(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::rdtime(&mut **self, ctx, cfg, dest)
+            }
+            fn fsqrt_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsqrt_s(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fsrm(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsrm(&mut **self, ctx, cfg, src)
+            }
+            fn fsub_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsub_s(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fsw(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsw(&mut **self, ctx, cfg, src, mem)
+            }
+            fn unimp(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::unimp(&mut **self,ctx, cfg)
+            }
+            fn unreachable(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::unreachable(&mut **self,ctx, cfg)
+            }
+            fn debug_break(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::debug_break(&mut **self,ctx, cfg)
+            }
+            fn ecall(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ecall(&mut **self,ctx, cfg)
+            }
+            fn lpad(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, label: u32) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lpad(&mut **self,ctx, cfg, label)
+            }
+            fn sspush(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sspush(&mut **self,ctx, cfg, src)
+            }
+            fn sspopchk(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sspopchk(&mut **self,ctx, cfg, src)
+            }
+            fn fence(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, pred: $crate::FenceSet, succ: $crate::FenceSet) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fence(&mut **self,ctx, cfg, pred, succ)
+            }
+            fn fence_i(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fence_i(&mut **self, ctx, cfg)
+            }
+            fn pause(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::pause(&mut **self, ctx, cfg)
+            }
+            fn prefetch_r(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::prefetch_r(&mut **self, ctx, cfg, mem)
+            }
+            fn prefetch_w(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::prefetch_w(&mut **self, ctx, cfg, mem)
+            }
+            fn cbo_clean(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cbo_clean(&mut **self, ctx, cfg, mem)
+            }
+            fn cbo_flush(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cbo_flush(&mut **self, ctx, cfg, mem)
+            }
+            fn cbo_inval(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cbo_inval(&mut **self, ctx, cfg, mem)
+            }
+            fn amoswap_w(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), addr: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::amoswap_w(&mut **self, ctx, cfg, dest, src, addr)
+            }
+            fn amoswap_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), addr: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::amoswap_d(&mut **self, ctx, cfg, dest, src, addr)
+            }
+            fn mv(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mv(&mut **self, ctx, cfg, dest, src)
+            }
+            fn sd(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sd(&mut **self, ctx, cfg, src, mem)
+            }
+            fn ld(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ld(&mut **self, ctx, cfg, dest, mem)
+            }
+            fn lw(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lw(&mut **self, ctx, cfg, dest, mem)
+            }
+            fn lwu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lwu(&mut **self, ctx, cfg, dest, mem)
+            }
+            fn lh(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lh(&mut **self, ctx, cfg, dest, mem)
+            }
+            fn lhu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lhu(&mut **self, ctx, cfg, dest, mem)
+            }
+            fn lb(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lb(&mut **self, ctx, cfg, dest, mem)
+            }
+            fn lbu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lbu(&mut **self, ctx, cfg, dest, mem)
+            }
+            fn add(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::add(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sub(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sub(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn mul(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mul(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn mulh(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mulh(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn mulhu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mulhu(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn div(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::div(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn and(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::and(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn or(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::or(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn xor(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::xor(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn addi(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::addi(&mut **self, ctx, cfg, dest, src, imm)
+            }
+            fn andi(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::andi(&mut **self, ctx, cfg, dest, src, imm)
+            }
+            fn ori(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ori(&mut **self, ctx, cfg, dest, src, imm)
+            }
+            fn xori(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::xori(&mut **self, ctx, cfg, dest, src, imm)
+            }
+            fn add_imm(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: i64) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::add_imm(&mut **self, ctx, cfg, dest, a, imm)
+            }
+            fn sub_imm(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: i64) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sub_imm(&mut **self, ctx, cfg, dest, a, imm)
+            }
+            fn and_imm(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: i64) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::and_imm(&mut **self, ctx, cfg, dest, a, imm)
+            }
+            fn or_imm(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: i64) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::or_imm(&mut **self, ctx, cfg, dest, a, imm)
+            }
+            fn xor_imm(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), imm: i64) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::xor_imm(&mut **self, ctx, cfg, dest, a, imm)
+            }
+            fn sll(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sll(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn srl(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::srl(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sra(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sra(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn rol(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::rol(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn ror(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ror(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn cpop(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cpop(&mut **self, ctx, cfg, dest, src)
+            }
+            fn clz(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::clz(&mut **self, ctx, cfg, dest, src)
+            }
+            fn ctz(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ctz(&mut **self, ctx, cfg, dest, src)
+            }
+            fn rev8(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::rev8(&mut **self, ctx, cfg, dest, src)
+            }
+            fn sext_b(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sext_b(&mut **self, ctx, cfg, dest, src)
+            }
+            fn sext_h(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sext_h(&mut **self, ctx, cfg, dest, src)
+            }
+            fn sext_w(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sext_w(&mut **self, ctx, cfg, dest, src)
+            }
+            fn ret(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ret(&mut **self, ctx, cfg)
+            }
+            fn li(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), val: u64) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::li(&mut **self, ctx, cfg, dest, val)
+            }
+            fn auipc(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), imm: u32) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::auipc(&mut **self, ctx, cfg, dest, imm)
+            }
+            fn la(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::la(&mut **self, ctx, cfg, dest, src)
+            }
+            fn beq(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::beq(&mut **self, ctx, cfg, a, b, target)
+            }
+            fn bge(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::bge(&mut **self, ctx, cfg, a, b, target)
+            }
+            fn bgeu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::bgeu(&mut **self, ctx, cfg, a, b, target)
+            }
+            fn blt(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::blt(&mut **self, ctx, cfg, a, b, target)
+            }
+            fn bltu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::bltu(&mut **self, ctx, cfg, a, b, target)
+            }
+            fn bcond(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, cond: $crate::ConditionCode, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::bcond(&mut **self,ctx, cfg, cond, a, b, target)
+            }
+            fn bcond_zero(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, cond: $crate::ConditionCode, a: &(dyn $crate::out::arg::MemArg + '_), target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::bcond_zero(&mut **self,ctx, cfg, cond, a, target)
+            }
+            fn bne(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::bne(&mut **self, ctx, cfg, a, b, target)
+            }
+            fn call(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::call(&mut **self, ctx, cfg, target)
+            }
+            fn divu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::divu(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fabs_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fabs_d(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fadd_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fadd_d(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fcvt_d_l(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_d_l(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_l_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_l_d(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_d_lu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_d_lu(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_d_w(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_d_w(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_d_wu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_d_wu(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_lu_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_lu_d(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_w_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_w_d(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fcvt_wu_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcvt_wu_d(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fdiv_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fdiv_d(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn feq_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::feq_d(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fld(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fld(&mut **self, ctx, cfg, dest, mem)
+            }
+            fn fle_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fle_d(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn flt_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::flt_d(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fmadd_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmadd_d(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fmadd_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmadd_s(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fmax_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmax_d(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fmin_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmin_d(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fmov_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmov_d(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fmsub_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmsub_d(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fmsub_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmsub_s(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fmul_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmul_d(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn fneg_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fneg_d(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fnmadd_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fnmadd_d(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fnmadd_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fnmadd_s(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fnmsub_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fnmsub_d(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fnmsub_s(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fnmsub_s(&mut **self, ctx, cfg, dest, a, b, c)
+            }
+            fn fsd(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsd(&mut **self, ctx, cfg, src, mem)
+            }
+            fn fsqrt_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsqrt_d(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fsub_d(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsub_d(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn j(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::j(&mut **self, ctx, cfg, target)
+            }
+            fn jal(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::jal(&mut **self, ctx, cfg, dest, target)
+            }
+            fn jalr(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), base: &(dyn $crate::out::arg::MemArg + '_), offset: i32) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::jalr(&mut **self, ctx, cfg, dest, base, offset)
+            }
+            fn lui(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), imm: u32) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lui(&mut **self, ctx, cfg, dest, imm)
+            }
+            fn rem(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::rem(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn remu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::remu(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sb(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sb(&mut **self, ctx, cfg, src, mem)
+            }
+            fn sh(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sh(&mut **self, ctx, cfg, src, mem)
+            }
+            fn slt(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::slt(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sltu(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sltu(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn sw(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sw(&mut **self, ctx, cfg, src, mem)
+            }
+            fn db(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, bytes: &[u8]) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::db(&mut **self, ctx, cfg, bytes)
+            }
+            fn current_offset(&self) -> ::core::option::Option<usize> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::current_offset(&**self)
+            }
+            fn align_to(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, alignment: usize) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::align_to(&mut **self, ctx, cfg, alignment)
+            }
+            fn nop(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::nop(&mut **self, ctx, cfg)
+            }
+            fn nops(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, n_bytes: usize) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::nops(&mut **self, ctx, cfg, n_bytes)
+            }
+            fn align(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, pow2: u32) -> Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::align(&mut **self, ctx, cfg, pow2)
+            }
+        }
+    };
+    ($( [ $($t:tt)* ] [$($u:tt)*] $ty:ty => $e:ty [$l:ty] [ $ctx:ty] [$wrapped:ty]),*) => {
+        const _: () = {
+            $(
+                $crate::writer_dispatch!(@core [ $($t)* ] $ty => $e [$ctx] [$wrapped]);
                 impl<$($u)*>$crate::out::Writer<$l, $ctx> for $ty{
                     fn set_label(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, s: $l) -> Result<(), Self::Error> {
                         <$wrapped as $crate::out::Writer<$l, $ctx>>::set_label(&mut **self, ctx, cfg, s)
@@ -779,6 +2802,16 @@ macro_rules! writer_dispatch {
                     fn la_label(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), label: $l) -> Result<(), Self::Error> {
                        <$wrapped as $crate::out::Writer<$l, $ctx>>::la_label(&mut **self, ctx, cfg, dest, label)
                     }
+                    fn bcond_label(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, cond: $crate::ConditionCode, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), label: $l) -> Result<(), Self::Error> {
+                       <$wrapped as $crate::out::Writer<$l, $ctx>>::bcond_label(&mut **self, ctx, cfg, cond, a, b, label)
+                    }
+                    #[allow(clippy::too_many_arguments)]
+                    fn bcond_label_hinted(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, cond: $crate::ConditionCode, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), label: $l, hint: $crate::BranchHint) -> Result<(), Self::Error> {
+                       <$wrapped as $crate::out::Writer<$l, $ctx>>::bcond_label_hinted(&mut **self, ctx, cfg, cond, a, b, label, hint)
+                    }
+                    fn dd_label(&mut self, ctx: &mut $ctx, cfg: $crate::RiscV64Arch, label: $l) -> Result<(), Self::Error> {
+                        <$wrapped as $crate::out::Writer<$l, $ctx>>::dd_label(&mut **self, ctx, cfg, label)
+                    }
                 }
             )*
         };
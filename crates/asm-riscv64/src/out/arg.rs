@@ -14,22 +14,41 @@ use core::{
 };
 use typeid;
 
-/// Represents a concrete argument kind (register or literal).
+/// Represents a concrete argument kind (register, literal, or symbol).
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArgKind {
     /// A register with a specific size.
     Reg {
         /// The register.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::reg"))]
         reg: Reg,
         /// The operand size.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::memory_size"))]
         size: MemorySize,
     },
     /// A literal 64-bit value.
     Lit(u64),
+    /// The address of a named global or external function, plus `addend`.
+    ///
+    /// As a call target or `la` source this is the symbol itself; as the base
+    /// of a memory operand it is a PC-relative access (`ld a0, sym`).
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    Sym {
+        /// The symbol name.
+        name: &'static str,
+        /// Byte offset added to the symbol's address.
+        addend: i64,
+    },
 }
 
 impl ArgKind {
+    /// Creates a reference to the symbol `name`.
+    pub const fn sym(name: &'static str) -> Self {
+        ArgKind::Sym { name, addend: 0 }
+    }
+
     /// Creates a displayable representation of this argument kind.
     pub fn display(&self, opts: crate::DisplayOpts) -> ArgKindDisplay {
         match self {
@@ -38,6 +57,10 @@ impl ArgKind {
                 RegFormatOpts::with_reg_class(opts.arch, *size, opts.reg_class),
             )),
             ArgKind::Lit(i) => ArgKindDisplay::Lit(*i),
+            ArgKind::Sym { name, addend } => ArgKindDisplay::Sym {
+                name: *name,
+                addend: *addend,
+            },
         }
     }
 }
@@ -49,6 +72,13 @@ pub enum ArgKindDisplay {
     Reg(RegDisplay),
     /// A literal value.
     Lit(u64),
+    /// A symbol reference.
+    Sym {
+        /// The symbol name.
+        name: &'static str,
+        /// Byte offset added to the symbol's address.
+        addend: i64,
+    },
 }
 
 impl Display for ArgKindDisplay {
@@ -56,6 +86,11 @@ impl Display for ArgKindDisplay {
         match self {
             ArgKindDisplay::Reg(reg_display) => write!(f, "{reg_display}"),
             ArgKindDisplay::Lit(i) => write!(f, "{i}"), // RISC-V immediates don't use # prefix
+            ArgKindDisplay::Sym { name, addend } => match *addend {
+                0 => write!(f, "{name}"),
+                a if a > 0 => write!(f, "{name}+{a}"),
+                a => write!(f, "{name}{a}"),
+            },
         }
     }
 }
@@ -72,6 +107,7 @@ impl Display for ArgKind {
 /// RISC-V uses simple base+displacement addressing (no pre/post-indexing).
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MemArgKind<A = ArgKind> {
     /// A direct operand (not a memory reference).
     NoMem(A),
@@ -84,6 +120,7 @@ pub enum MemArgKind<A = ArgKind> {
         /// Displacement added to the address (12-bit signed immediate in RISC-V).
         disp: i32,
         /// Size of the memory access.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::memory_size"))]
         size: MemorySize,
         /// Register class for the memory access.
         reg_class: crate::RegisterClass,
@@ -107,8 +144,16 @@ impl<A: Arg> MemArgKind<A> {
             } => {
                 // For memory operands, force base and offset to be GPRs
                 let gpr_opts = crate::DisplayOpts::new(opts.arch);
+                let base = base.display(gpr_opts);
+                // `[sym + disp]` uses the assembler's PC-relative pseudo form.
+                if let ArgKindDisplay::Sym { name, addend } = base {
+                    return MemArgKind::NoMem(ArgKindDisplay::Sym {
+                        name,
+                        addend: addend + *disp as i64,
+                    });
+                }
                 MemArgKind::Mem {
-                    base: base.display(gpr_opts),
+                    base,
                     offset: offset
                         .as_ref()
                         .map(|(a, scale)| (a.display(gpr_opts), *scale)),
@@ -121,6 +166,19 @@ impl<A: Arg> MemArgKind<A> {
     }
 }
 
+impl MemArgKind<ArgKind> {
+    /// Creates a PC-relative operand addressing the global `name`.
+    pub fn sym(name: &'static str, size: MemorySize) -> Self {
+        MemArgKind::Mem {
+            base: ArgKind::sym(name),
+            offset: None,
+            disp: 0,
+            size,
+            reg_class: crate::RegisterClass::Gpr,
+        }
+    }
+}
+
 impl<T: Display> Display for MemArgKind<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -145,6 +203,23 @@ impl<T: Display> Display for MemArgKind<T> {
     }
 }
 
+impl MemArgKind<ArgKind> {
+    /// Creates a `disp(tp)` operand addressing thread-local storage at offset
+    /// `disp` from the thread pointer (`x4`).
+    pub fn tp_relative(disp: i32, size: MemorySize) -> Self {
+        MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(4),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp,
+            size,
+            reg_class: crate::RegisterClass::Gpr,
+        }
+    }
+}
+
 impl<A> MemArgKind<A> {
     /// Returns a reference view of this memory argument kind.
     pub fn as_ref<'a>(&'a self) -> MemArgKind<&'a A> {
@@ -263,7 +338,7 @@ pub trait Arg: MemArg {
 
         match self.kind() {
             ArgKind::Reg { reg, size: _ } => ::alloc::boxed::Box::new([reg].into_iter()),
-            ArgKind::Lit(_) => ::alloc::boxed::Box::new(empty()),
+            ArgKind::Lit(_) | ArgKind::Sym { .. } => ::alloc::boxed::Box::new(empty()),
         }
     }
 }
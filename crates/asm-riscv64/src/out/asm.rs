@@ -21,23 +21,103 @@ macro_rules! writers {
                 fn ebreak(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch) -> Result<(),Self::Error>{
                     $crate::__::core::write!(self,"ebreak\n")
                 }
+                fn unimp(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch) -> Result<(),Self::Error>{
+                    $crate::__::core::write!(self,"unimp\n")
+                }
+                fn ecall(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch) -> Result<(),Self::Error>{
+                    $crate::__::core::write!(self,"ecall\n")
+                }
+                fn lpad(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch, label: u32) -> Result<(),Self::Error>{
+                    $crate::__::core::write!(self,"lpad {label}\n")
+                }
+                fn sspush(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(),Self::Error>{
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"sspush {src}\n")
+                }
+                fn sspopchk(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(),Self::Error>{
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"sspopchk {src}\n")
+                }
+                fn fence(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch, pred: $crate::FenceSet, succ: $crate::FenceSet) -> Result<(),Self::Error>{
+                    $crate::__::core::write!(self,"fence {pred}, {succ}\n")
+                }
+
+                fn fence_i(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch) -> Result<(),Self::Error>{
+                    $crate::__::core::write!(self,"fence.i\n")
+                }
+
+                fn pause(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch) -> Result<(),Self::Error>{
+                    $crate::__::core::write!(self,"pause\n")
+                }
+
+                fn prefetch_r(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(),Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"prefetch.r {mem}\n")
+                }
+
+                fn prefetch_w(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(),Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"prefetch.w {mem}\n")
+                }
+
+                fn cbo_clean(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(),Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"cbo.clean {mem}\n")
+                }
+
+                fn cbo_flush(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(),Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"cbo.flush {mem}\n")
+                }
+
+                fn cbo_inval(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(),Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"cbo.inval {mem}\n")
+                }
+
+                fn amoswap_w(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), addr: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(),Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    let addr = addr.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"amoswap.w.aqrl {dest}, {src}, ({addr})\n")
+                }
+
+                fn amoswap_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), addr: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(),Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    let addr = addr.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"amoswap.d.aqrl {dest}, {src}, ({addr})\n")
+                }
 
                 fn mv(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let is_sym = $crate::__::core::matches!(src.concrete_mem_kind(), $crate::out::arg::MemArgKind::NoMem($crate::out::arg::ArgKind::Sym { .. }));
+                    let compressed = $crate::out::compress::mv(cfg, dest, src);
                     let dest = dest.mem_display(cfg.into());
                     let src = src.mem_display(cfg.into());
+                    if is_sym {
+                        return match cfg.pic {
+                            true => $crate::__::core::write!(self,"lla {dest}, {src}\n"),
+                            false => $crate::__::core::write!(self,"lui {dest}, %hi({src})\naddi {dest}, {dest}, %lo({src})\n"),
+                        };
+                    }
+                    if compressed {
+                        return $crate::__::core::write!(self,"c.mv {dest}, {src}\n");
+                    }
                     $crate::__::core::write!(self,"mv {dest}, {src}\n")
                 }
 
                 fn sd(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let mnemonic = $crate::out::compress::sd(cfg, src, mem).unwrap_or("sd");
                     let src = src.mem_display(cfg.into());
                     let mem = mem.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"sd {src}, {mem}\n")
+                    $crate::__::core::write!(self,"{mnemonic} {src}, {mem}\n")
                 }
 
                 fn ld(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let mnemonic = $crate::out::compress::ld(cfg, dest, mem).unwrap_or("ld");
                     let dest = dest.mem_display(cfg.into());
                     let mem = mem.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"ld {dest}, {mem}\n")
+                    $crate::__::core::write!(self,"{mnemonic} {dest}, {mem}\n")
                 }
 
                 fn lw(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
@@ -46,6 +126,12 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"lw {dest}, {mem}\n")
                 }
 
+                fn lwu(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"lwu {dest}, {mem}\n")
+                }
+
                 fn sw(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
                     let src = src.mem_display(cfg.into());
                     let mem = mem.mem_display(cfg.into());
@@ -58,6 +144,12 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"lb {dest}, {mem}\n")
                 }
 
+                fn lbu(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"lbu {dest}, {mem}\n")
+                }
+
                 fn sb(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
                     let src = src.mem_display(cfg.into());
                     let mem = mem.mem_display(cfg.into());
@@ -70,6 +162,12 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"lh {dest}, {mem}\n")
                 }
 
+                fn lhu(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"lhu {dest}, {mem}\n")
+                }
+
                 fn sh(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
                     let src = src.mem_display(cfg.into());
                     let mem = mem.mem_display(cfg.into());
@@ -130,6 +228,23 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"bgeu {a}, {b}, {target}\n")
                 }
 
+                fn bcond_zero(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, cond: $crate::ConditionCode, a: &(dyn $crate::out::arg::MemArg + '_), target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    let target = target.mem_display(cfg.into());
+                    let zero = $crate::out::arg::MemArg::mem_display(&$crate::__::Reg(0), cfg.into());
+                    match cond {
+                        $crate::ConditionCode::EQ | $crate::ConditionCode::LEU => $crate::__::core::write!(self,"beqz {a}, {target}\n"),
+                        $crate::ConditionCode::NE | $crate::ConditionCode::GTU => $crate::__::core::write!(self,"bnez {a}, {target}\n"),
+                        $crate::ConditionCode::LT => $crate::__::core::write!(self,"bltz {a}, {target}\n"),
+                        $crate::ConditionCode::GE => $crate::__::core::write!(self,"bgez {a}, {target}\n"),
+                        $crate::ConditionCode::GT => $crate::__::core::write!(self,"bgtz {a}, {target}\n"),
+                        $crate::ConditionCode::LE => $crate::__::core::write!(self,"blez {a}, {target}\n"),
+                        // Never and always taken; there is no pseudo-instruction for them.
+                        $crate::ConditionCode::LTU => $crate::__::core::write!(self,"bltu {a}, {zero}, {target}\n"),
+                        $crate::ConditionCode::GEU => $crate::__::core::write!(self,"bgeu {a}, {zero}, {target}\n"),
+                    }
+                }
+
                 fn and(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
                     let dest = dest.mem_display(cfg.into());
                     let a = a.mem_display(cfg.into());
@@ -172,6 +287,69 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"sra {dest}, {a}, {b}\n")
                 }
 
+                fn rol(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let a = a.mem_display(cfg.into());
+                    // Zbb has no ROLI: rotating left by n is RORI by 64 - n
+                    if let $crate::out::arg::MemArgKind::NoMem($crate::out::arg::ArgKind::Lit(v)) = b.concrete_mem_kind() {
+                        let shamt = (64 - (v & 0x3F)) & 0x3F;
+                        return $crate::__::core::write!(self,"rori {dest}, {a}, {shamt}\n");
+                    }
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"rol {dest}, {a}, {b}\n")
+                }
+
+                fn ror(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let a = a.mem_display(cfg.into());
+                    if let $crate::out::arg::MemArgKind::NoMem($crate::out::arg::ArgKind::Lit(v)) = b.concrete_mem_kind() {
+                        let shamt = v & 0x3F;
+                        return $crate::__::core::write!(self,"rori {dest}, {a}, {shamt}\n");
+                    }
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"ror {dest}, {a}, {b}\n")
+                }
+
+                fn cpop(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"cpop {dest}, {src}\n")
+                }
+
+                fn clz(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"clz {dest}, {src}\n")
+                }
+
+                fn ctz(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"ctz {dest}, {src}\n")
+                }
+
+                fn rev8(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"rev8 {dest}, {src}\n")
+                }
+
+                fn sext_b(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"sext.b {dest}, {src}\n")
+                }
+                fn sext_h(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"sext.h {dest}, {src}\n")
+                }
+                fn sext_w(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"sext.w {dest}, {src}\n")
+                }
+
                 fn slt(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
                     let dest = dest.mem_display(cfg.into());
                     let a = a.mem_display(cfg.into());
@@ -201,9 +379,31 @@ macro_rules! writers {
                 }
 
                 fn addi(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error>{
+                    let compressed = $crate::out::compress::addi(cfg, dest, src, imm);
                     let dest = dest.mem_display(cfg.into());
                     let src = src.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"addi {dest}, {src}, {imm}\n")
+                    match compressed {
+                        Some(mnemonic) => $crate::__::core::write!(self,"{mnemonic} {dest}, {imm}\n"),
+                        None => $crate::__::core::write!(self,"addi {dest}, {src}, {imm}\n"),
+                    }
+                }
+
+                fn andi(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"andi {dest}, {src}, {imm}\n")
+                }
+
+                fn ori(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"ori {dest}, {src}, {imm}\n")
+                }
+
+                fn xori(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"xori {dest}, {src}, {imm}\n")
                 }
 
                 fn lui(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), imm: u32) -> Result<(), Self::Error>{
@@ -216,6 +416,12 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"auipc {dest}, {imm}\n")
                 }
 
+                fn la(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"la {dest}, {src}\n")
+                }
+
                 fn ret(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
                     $crate::__::core::write!(self,"ret\n")
                 }
@@ -226,12 +432,17 @@ macro_rules! writers {
                 }
 
                 fn j(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, target: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let mnemonic = if $crate::out::compress::j(cfg, target) { "c.j" } else { "j" };
                     let target = target.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"j {target}\n")
+                    $crate::__::core::write!(self,"{mnemonic} {target}\n")
                 }
 
                 fn li(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), val: u64) -> Result<(),Self::Error>{
+                    let compressed = $crate::out::compress::li(cfg, dest, val);
                     let dest = dest.mem_display(cfg.into());
+                    if compressed {
+                        return $crate::__::core::write!(self,"c.li {dest}, {}\n", val as i64);
+                    }
                     $crate::__::core::write!(self,"li {dest}, {val}\n")
                 }
 
@@ -250,6 +461,13 @@ macro_rules! writers {
                     $crate::__::core::write!(self,"mulh {dest}, {a}, {b}\n")
                 }
 
+                fn mulhu(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"mulhu {dest}, {a}, {b}\n")
+                }
+
                 fn div(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
                     let dest = dest.mem_display(cfg.into());
                     let a = a.mem_display(cfg.into());
@@ -298,7 +516,7 @@ macro_rules! writers {
                     let dest = dest.mem_display(opts);
                     let a = a.mem_display(opts);
                     let b = b.mem_display(opts);
-                    $crate::__::core::write!(self,"fadd.d {dest}, {a}, {b}\n")
+                    $crate::__::core::write!(self,"fadd.d {dest}, {a}, {b}{rm}\n", rm = cfg.rounding_mode.operand())
                 }
 
                 fn fsub_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
@@ -306,7 +524,7 @@ macro_rules! writers {
                     let dest = dest.mem_display(opts);
                     let a = a.mem_display(opts);
                     let b = b.mem_display(opts);
-                    $crate::__::core::write!(self,"fsub.d {dest}, {a}, {b}\n")
+                    $crate::__::core::write!(self,"fsub.d {dest}, {a}, {b}{rm}\n", rm = cfg.rounding_mode.operand())
                 }
 
                 fn fmul_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
@@ -314,7 +532,7 @@ macro_rules! writers {
                     let dest = dest.mem_display(opts);
                     let a = a.mem_display(opts);
                     let b = b.mem_display(opts);
-                    $crate::__::core::write!(self,"fmul.d {dest}, {a}, {b}\n")
+                    $crate::__::core::write!(self,"fmul.d {dest}, {a}, {b}{rm}\n", rm = cfg.rounding_mode.operand())
                 }
 
                 fn fdiv_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
@@ -322,7 +540,79 @@ macro_rules! writers {
                     let dest = dest.mem_display(opts);
                     let a = a.mem_display(opts);
                     let b = b.mem_display(opts);
-                    $crate::__::core::write!(self,"fdiv.d {dest}, {a}, {b}\n")
+                    $crate::__::core::write!(self,"fdiv.d {dest}, {a}, {b}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fmadd_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fmadd.d {dest}, {a}, {b}, {c}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fmadd_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fmadd.s {dest}, {a}, {b}, {c}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fmsub_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fmsub.d {dest}, {a}, {b}, {c}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fmsub_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fmsub.s {dest}, {a}, {b}, {c}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fnmsub_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fnmsub.d {dest}, {a}, {b}, {c}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fnmsub_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fnmsub.s {dest}, {a}, {b}, {c}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fnmadd_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fnmadd.d {dest}, {a}, {b}, {c}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fnmadd_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), c: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    let c = c.mem_display(opts);
+                    $crate::__::core::write!(self,"fnmadd.s {dest}, {a}, {b}, {c}{rm}\n", rm = cfg.rounding_mode.operand())
                 }
 
                 fn fmov_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
@@ -337,7 +627,7 @@ macro_rules! writers {
                     let opts_gpr = $crate::DisplayOpts::new(cfg);
                     let dest = dest.mem_display(opts_fp);
                     let src = src.mem_display(opts_gpr);
-                    $crate::__::core::write!(self,"fcvt.d.l {dest}, {src}\n")
+                    $crate::__::core::write!(self,"fcvt.d.l {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
                 }
 
                 fn fcvt_l_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
@@ -345,7 +635,402 @@ macro_rules! writers {
                     let opts_gpr = $crate::DisplayOpts::new(cfg);
                     let dest = dest.mem_display(opts_gpr);
                     let src = src.mem_display(opts_fp);
-                    $crate::__::core::write!(self,"fcvt.l.d {dest}, {src}\n")
+                    $crate::__::core::write!(self,"fcvt.l.d {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn feq_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let a = a.mem_display(opts_fp);
+                    let b = b.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"feq.d {dest}, {a}, {b}\n")
+                }
+
+                fn flt_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let a = a.mem_display(opts_fp);
+                    let b = b.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"flt.d {dest}, {a}, {b}\n")
+                }
+
+                fn fle_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let a = a.mem_display(opts_fp);
+                    let b = b.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"fle.d {dest}, {a}, {b}\n")
+                }
+
+                fn fmin_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fmin.d {dest}, {a}, {b}\n")
+                }
+
+                fn fmax_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fmax.d {dest}, {a}, {b}\n")
+                }
+
+                fn fsqrt_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fsqrt.d {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fabs_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fabs.d {dest}, {src}\n")
+                }
+
+                fn fneg_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fneg.d {dest}, {src}\n")
+                }
+
+                fn fcvt_d_w(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_fp);
+                    let src = src.mem_display(opts_gpr);
+                    $crate::__::core::write!(self,"fcvt.d.w {dest}, {src}\n")
+                }
+
+                fn fcvt_d_wu(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_fp);
+                    let src = src.mem_display(opts_gpr);
+                    $crate::__::core::write!(self,"fcvt.d.wu {dest}, {src}\n")
+                }
+
+                fn fcvt_d_lu(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_fp);
+                    let src = src.mem_display(opts_gpr);
+                    $crate::__::core::write!(self,"fcvt.d.lu {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fcvt_w_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let src = src.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"fcvt.w.d {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fcvt_wu_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let src = src.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"fcvt.wu.d {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fcvt_lu_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let src = src.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"fcvt.lu.d {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn flw(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"flw {dest}, {mem}\n")
+                }
+
+                fn fsw(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_), mem: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let src = src.mem_display(opts);
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"fsw {src}, {mem}\n")
+                }
+
+                fn fadd_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fadd.s {dest}, {a}, {b}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fsub_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fsub.s {dest}, {a}, {b}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fmul_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fmul.s {dest}, {a}, {b}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fdiv_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fdiv.s {dest}, {a}, {b}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fmov_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fmv.s {dest}, {src}\n")
+                }
+
+                fn fcvt_s_l(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_fp);
+                    let src = src.mem_display(opts_gpr);
+                    $crate::__::core::write!(self,"fcvt.s.l {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fcvt_l_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let src = src.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"fcvt.l.s {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn feq_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let a = a.mem_display(opts_fp);
+                    let b = b.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"feq.s {dest}, {a}, {b}\n")
+                }
+
+                fn flt_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let a = a.mem_display(opts_fp);
+                    let b = b.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"flt.s {dest}, {a}, {b}\n")
+                }
+
+                fn fle_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let a = a.mem_display(opts_fp);
+                    let b = b.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"fle.s {dest}, {a}, {b}\n")
+                }
+
+                fn fmin_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fmin.s {dest}, {a}, {b}\n")
+                }
+
+                fn fmax_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"fmax.s {dest}, {a}, {b}\n")
+                }
+
+                fn fsqrt_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fsqrt.s {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fabs_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fabs.s {dest}, {src}\n")
+                }
+
+                fn fneg_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fneg.s {dest}, {src}\n")
+                }
+
+                fn fcvt_s_w(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_fp);
+                    let src = src.mem_display(opts_gpr);
+                    $crate::__::core::write!(self,"fcvt.s.w {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fcvt_s_wu(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_fp);
+                    let src = src.mem_display(opts_gpr);
+                    $crate::__::core::write!(self,"fcvt.s.wu {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fcvt_s_lu(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_fp);
+                    let src = src.mem_display(opts_gpr);
+                    $crate::__::core::write!(self,"fcvt.s.lu {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fcvt_w_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let src = src.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"fcvt.w.s {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fcvt_wu_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let src = src.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"fcvt.wu.s {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fcvt_lu_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts_fp = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let opts_gpr = $crate::DisplayOpts::new(cfg);
+                    let dest = dest.mem_display(opts_gpr);
+                    let src = src.mem_display(opts_fp);
+                    $crate::__::core::write!(self,"fcvt.lu.s {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fcvt_s_d(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fcvt.s.d {dest}, {src}{rm}\n", rm = cfg.rounding_mode.operand())
+                }
+
+                fn fcvt_d_s(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Fp);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"fcvt.d.s {dest}, {src}\n")
+                }
+
+                fn fsrm(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"fsrm {src}\n")
+                }
+
+                fn frrm(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"frrm {dest}\n")
+                }
+
+                fn csrrw(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"csrrw {dest}, {csr}, {src}\n")
+                }
+
+                fn csrrs(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"csrrs {dest}, {csr}, {src}\n")
+                }
+
+                fn csrrc(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, src: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"csrrc {dest}, {csr}, {src}\n")
+                }
+
+                fn csrrwi(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, imm: u8) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"csrrwi {dest}, {csr}, {imm}\n")
+                }
+
+                fn csrrsi(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, imm: u8) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"csrrsi {dest}, {csr}, {imm}\n")
+                }
+
+                fn csrrci(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), csr: $crate::Csr, imm: u8) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"csrrci {dest}, {csr}, {imm}\n")
+                }
+
+                fn rdcycle(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"rdcycle {dest}\n")
+                }
+
+                fn rdtime(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"rdtime {dest}\n")
+                }
+
+                fn db(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch, bytes: &[u8]) -> Result<(), Self::Error>{
+                    $crate::__::core::write!(self, ".byte ")?;
+                    for (i, b) in bytes.iter().enumerate() {
+                        if i > 0 {
+                            $crate::__::core::write!(self, ", ")?;
+                        }
+                        $crate::__::core::write!(self, "0x{:02x}", b)?;
+                    }
+                    $crate::__::core::write!(self, "\n")
+                }
+
+                fn align_to(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch, alignment: usize) -> Result<(), Self::Error>{
+                    $crate::__::core::write!(self, ".balign {alignment}\n")
+                }
+
+                fn nop(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch) -> Result<(), Self::Error>{
+                    $crate::__::core::write!(self, "nop\n")
+                }
+
+                fn nops(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, n_bytes: usize) -> Result<(), Self::Error>{
+                    // An assembler targeting C compresses every `nop`, so spell out the size.
+                    let (mnemonic, size) = if cfg.c_extension { ("c.nop", 2) } else { ("nop", 4) };
+                    if n_bytes % size != 0 {
+                        return Err($crate::out::error::EmitError::InvalidOperands { mnemonic: "nops", reason: "length is not a multiple of the nop size" }.into());
+                    }
+                    for _ in 0..n_bytes / size {
+                        $crate::__::core::write!(self, "{mnemonic}\n")?;
+                    }
+                    Ok(())
+                }
+
+                fn align(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch, pow2: u32) -> Result<(), Self::Error>{
+                    $crate::__::core::write!(self, ".p2align {pow2}\n")
                 }
             }
 
@@ -356,12 +1041,20 @@ macro_rules! writers {
 
                 fn jal_label(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), label: L) -> Result<(),Self::Error>{
                     let dest = dest.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"jal {dest}, {label}\n")
+                    // Absolute jumps materialize the upper bits in t6.
+                    let t6 = $crate::out::arg::MemArg::mem_display(&$crate::__::Reg(31), cfg.into());
+                    match cfg.label_refs {
+                        $crate::LabelRefs::Absolute => $crate::__::core::write!(self,"lui {t6}, %hi({label})\njalr {dest}, {t6}, %lo({label})\n"),
+                        _ => $crate::__::core::write!(self,"jal {dest}, {label}\n"),
+                    }
                 }
 
                 fn la_label(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), label: L) -> Result<(),Self::Error>{
                     let dest = dest.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"la {dest}, {label}\n")
+                    match cfg.label_refs {
+                        $crate::LabelRefs::Absolute => $crate::__::core::write!(self,"lui {dest}, %hi({label})\naddi {dest}, {dest}, %lo({label})\n"),
+                        _ => $crate::__::core::write!(self,"la {dest}, {label}\n"),
+                    }
                 }
 
                 fn bcond_label(&mut self, _ctx: &mut Context, cfg: $crate::RiscV64Arch, cond: $crate::ConditionCode, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), label: L) -> Result<(),Self::Error>{
@@ -381,6 +1074,9 @@ macro_rules! writers {
                         _ => $crate::__::core::write!(self,"b? {a}, {b}, {label}\n"),
                     }
                 }
+                fn dd_label(&mut self, _ctx: &mut Context, _cfg: $crate::RiscV64Arch, label: L) -> Result<(), Self::Error> {
+                    $crate::__::core::write!(self, ".4byte {label} - .\n")
+                }
             })*
         };
     };
@@ -0,0 +1,624 @@
+//! Functions assembled from basic blocks.
+//!
+//! Deciding where each label goes, and whether a block ends in a jump or
+//! falls through into the next one, is the error-prone part of driving a
+//! [`Writer`] by hand. [`FunctionBuilder`] instead collects a function as
+//! [`Block`]s, each a recorded body plus a [`Terminator`] naming its
+//! successors, filled in any order. [`FunctionBuilder::finish`] then lays
+//! the blocks out, drops jumps to the block placed next, and defines a label
+//! only for the blocks something still jumps to:
+//!
+//! ```ignore
+//! let mut f = FunctionBuilder::new();
+//! let (entry, less, done) = (f.entry(), f.block(), f.block());
+//! let (a, b) = (a0.concrete_mem_kind(), a1.concrete_mem_kind());
+//! f.terminate(entry, Terminator::Branch { cond: ConditionCode::LT, a, b, hint: BranchHint::Likely, taken: less, not_taken: done });
+//! f.body(done).add(&mut (), cfg, &a0, &a0, &a2)?;
+//! f.terminate(done, Terminator::Return);
+//! f.body(less).mv(&mut (), cfg, &a0, &a1)?;
+//! f.terminate(less, Terminator::Jump(done));
+//! f.finish(&mut writer, &mut ctx, cfg, |block| labels[block.index()])?;
+//! // bge a0, a1, .L2
+//! // mv a0, a1
+//! // .L2:
+//! // add a0, a0, a2
+//! // ret
+//! ```
+//!
+//! By default blocks follow their preferred successors
+//! ([`BlockOrder::Preferred`]). [`BlockOrder::Weighted`] instead orders
+//! them by edge weight, taken from a profile through
+//! [`FunctionBuilder::set_weight`] or inferred from the terminators, so the
+//! most frequent transfers become fall-throughs, and bypasses empty blocks
+//! that only jump elsewhere.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use portal_pc_asm_common::types::reg::Reg;
+
+use crate::out::Writer;
+use crate::out::arg::MemArgKind;
+use crate::out::branch_hint::Fallthrough;
+use crate::out::record::RecordingWriter;
+use crate::{BranchHint, ConditionCode, RiscV64Arch};
+
+/// A basic block of a [`FunctionBuilder`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Block(usize);
+
+impl Block {
+    /// Returns the position of the block in creation order, starting with 0
+    /// for the entry block.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// How control leaves a block.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Terminator {
+    /// The body transfers control itself, e.g. with a tail call or an
+    /// indirect jump; nothing is emitted after it.
+    #[default]
+    Body,
+    /// Returns from the function with `ret`.
+    Return,
+    /// Continues at another block.
+    Jump(Block),
+    /// Continues at `taken` if `cond` holds between `a` and `b`, and at
+    /// `not_taken` otherwise.
+    Branch {
+        /// The comparison.
+        cond: ConditionCode,
+        /// The first register compared.
+        a: MemArgKind,
+        /// The second register compared.
+        b: MemArgKind,
+        /// The expected direction, which also picks the successor laid out
+        /// next.
+        hint: BranchHint,
+        /// The block run when `cond` holds.
+        taken: Block,
+        /// The block run when `cond` does not hold.
+        not_taken: Block,
+    },
+}
+
+impl Terminator {
+    /// Returns the successor layout tries to place right after the block.
+    fn preferred(&self) -> Option<Block> {
+        match *self {
+            Terminator::Jump(target) => Some(target),
+            Terminator::Branch {
+                hint,
+                taken,
+                not_taken,
+                ..
+            } => Some(match Fallthrough::for_hint(hint) {
+                Fallthrough::Taken => taken,
+                Fallthrough::NotTaken => not_taken,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the blocks control can continue at.
+    fn successors(&self) -> [Option<Block>; 2] {
+        match *self {
+            Terminator::Jump(target) => [Some(target), None],
+            Terminator::Branch {
+                taken, not_taken, ..
+            } => [Some(taken), Some(not_taken)],
+            _ => [None, None],
+        }
+    }
+
+    /// Returns the weight of the edge to `to` when none was set, as
+    /// described on [`FunctionBuilder::weight`].
+    fn inferred_weight(&self, to: Block) -> u64 {
+        match *self {
+            Terminator::Jump(target) if target == to => 2,
+            Terminator::Branch {
+                hint,
+                taken,
+                not_taken,
+                ..
+            } if to == taken || to == not_taken => match (hint, to == taken) {
+                (BranchHint::Likely, true) | (BranchHint::Unlikely, false) => 2,
+                _ => 1,
+            },
+            _ => 0,
+        }
+    }
+
+    /// Returns the terminator with each successor replaced by `f(successor)`.
+    fn retarget(mut self, f: impl Fn(Block) -> Block) -> Self {
+        match &mut self {
+            Terminator::Jump(target) => *target = f(*target),
+            Terminator::Branch {
+                taken, not_taken, ..
+            } => {
+                *taken = f(*taken);
+                *not_taken = f(*not_taken);
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Returns the jumps that end the block when `next` is laid out after
+    /// it.
+    fn lower(&self, next: Option<Block>) -> [Option<Jump>; 2] {
+        match *self {
+            Terminator::Body => [None, None],
+            Terminator::Return => [Some(Jump::Ret), None],
+            Terminator::Jump(target) if next == Some(target) => [None, None],
+            Terminator::Jump(target) => [Some(Jump::Always(target)), None],
+            Terminator::Branch {
+                taken, not_taken, ..
+            } if taken == not_taken => Terminator::Jump(taken).lower(next),
+            Terminator::Branch {
+                cond,
+                a,
+                b,
+                hint,
+                taken,
+                not_taken,
+            } => {
+                if next == Some(not_taken) {
+                    [Some(Jump::Cond(cond, a, b, hint, taken)), None]
+                } else if next == Some(taken) {
+                    [
+                        Some(Jump::Cond(cond.invert(), a, b, flip(hint), not_taken)),
+                        None,
+                    ]
+                } else {
+                    [
+                        Some(Jump::Cond(cond, a, b, hint, taken)),
+                        Some(Jump::Always(not_taken)),
+                    ]
+                }
+            }
+        }
+    }
+}
+
+/// A jump emitted at the end of a block.
+#[derive(Clone, Copy)]
+enum Jump {
+    Ret,
+    Always(Block),
+    Cond(ConditionCode, MemArgKind, MemArgKind, BranchHint, Block),
+}
+
+impl Jump {
+    fn target(self) -> Option<Block> {
+        match self {
+            Jump::Ret => None,
+            Jump::Always(target) | Jump::Cond(.., target) => Some(target),
+        }
+    }
+}
+
+/// Returns the hint of a branch whose condition was inverted.
+fn flip(hint: BranchHint) -> BranchHint {
+    match hint {
+        BranchHint::Likely => BranchHint::Unlikely,
+        BranchHint::Unlikely => BranchHint::Likely,
+        BranchHint::None => BranchHint::None,
+    }
+}
+
+/// How [`FunctionBuilder::layout`] orders blocks. The entry block always
+/// comes first.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum BlockOrder {
+    /// Starts at the entry block and keeps following the preferred
+    /// successor of each block, the target of a [`Jump`](Terminator::Jump)
+    /// or the expected side of a [`Branch`](Terminator::Branch) (see
+    /// [`Fallthrough::for_hint`]), until it reaches a block already placed;
+    /// the remaining blocks start new chains in the order they were created.
+    #[default]
+    Preferred,
+    /// Joins blocks into chains along the heaviest edges first (see
+    /// [`FunctionBuilder::weight`]), each edge linking the end of one chain
+    /// to the start of another, and emits the chain holding the entry block
+    /// followed by the others in creation order.
+    ///
+    /// Jumps to an empty block whose terminator is a
+    /// [`Jump`](Terminator::Jump) go straight to its target instead, and
+    /// such blocks are left out unless they are the entry block or keep
+    /// their label.
+    Weighted,
+}
+
+struct BlockData<L> {
+    body: RecordingWriter<L>,
+    terminator: Terminator,
+    keep_label: bool,
+    weights: Vec<(Block, u64)>,
+}
+
+impl<L> BlockData<L> {
+    fn new() -> Self {
+        Self {
+            body: RecordingWriter::new(),
+            terminator: Terminator::Body,
+            keep_label: false,
+            weights: Vec::new(),
+        }
+    }
+}
+
+/// Collects a function as basic blocks and emits them in a chosen layout.
+///
+/// Blocks are addressed by [`Block`] handles; passing a handle from another
+/// builder panics or names the wrong block.
+pub struct FunctionBuilder<L> {
+    blocks: Vec<BlockData<L>>,
+    order: BlockOrder,
+}
+
+impl<L> FunctionBuilder<L> {
+    /// Creates a builder holding only the entry block.
+    pub fn new() -> Self {
+        Self {
+            blocks: vec![BlockData::new()],
+            order: BlockOrder::Preferred,
+        }
+    }
+
+    /// Sets how blocks are ordered on emission.
+    pub fn with_order(self, order: BlockOrder) -> Self {
+        Self { order, ..self }
+    }
+
+    /// Returns the entry block, which is always laid out first.
+    pub fn entry(&self) -> Block {
+        Block(0)
+    }
+
+    /// Creates an empty block ending in [`Terminator::Body`].
+    pub fn block(&mut self) -> Block {
+        self.blocks.push(BlockData::new());
+        Block(self.blocks.len() - 1)
+    }
+
+    /// Returns the writer recording the body of `block`.
+    ///
+    /// The body runs before the terminator and must not transfer control
+    /// unless the terminator is [`Terminator::Body`].
+    pub fn body(&mut self, block: Block) -> &mut RecordingWriter<L> {
+        &mut self.blocks[block.0].body
+    }
+
+    /// Sets how control leaves `block`, replacing any earlier terminator.
+    pub fn terminate(&mut self, block: Block, terminator: Terminator) {
+        self.blocks[block.0].terminator = terminator;
+    }
+
+    /// Defines the label of `block` even if no jump needs it, for bodies
+    /// or data that refer to the block by its label.
+    pub fn keep_label(&mut self, block: Block) {
+        self.blocks[block.0].keep_label = true;
+    }
+
+    /// Sets the weight of the edge from `from` to `to`, such as a profile
+    /// count, replacing the inferred one.
+    ///
+    /// Weights only matter relative to each other. The inferred ones are 1
+    /// and 2, so with a profile every edge should be given a weight.
+    pub fn set_weight(&mut self, from: Block, to: Block, weight: u64) {
+        let weights = &mut self.blocks[from.0].weights;
+        match weights.iter_mut().find(|(block, _)| *block == to) {
+            Some(edge) => edge.1 = weight,
+            None => weights.push((to, weight)),
+        }
+    }
+
+    /// Returns the weight of the edge from `from` to `to`.
+    ///
+    /// Unless set with [`set_weight`](Self::set_weight), it is inferred from
+    /// the terminator of `from`: 2 for a jump and for the expected side of a
+    /// hinted branch, 1 for the other side and for both sides of an
+    /// unhinted one, and 0 if `to` is not a successor.
+    pub fn weight(&self, from: Block, to: Block) -> u64 {
+        let data = &self.blocks[from.0];
+        data.weights
+            .iter()
+            .find(|(block, _)| *block == to)
+            .map_or_else(|| data.terminator.inferred_weight(to), |edge| edge.1)
+    }
+
+    /// Returns the blocks [`finish`](Self::finish) emits, in order, as the
+    /// builder's [`BlockOrder`] arranges them.
+    pub fn layout(&self) -> Vec<Block> {
+        match self.order {
+            BlockOrder::Preferred => self.preferred_layout(),
+            BlockOrder::Weighted => self.weighted_layout(),
+        }
+    }
+
+    fn preferred_layout(&self) -> Vec<Block> {
+        let mut placed = vec![false; self.blocks.len()];
+        let mut order = Vec::with_capacity(self.blocks.len());
+        for start in 0..self.blocks.len() {
+            let mut next = Some(Block(start));
+            while let Some(block) = next.filter(|b| !placed[b.0]) {
+                placed[block.0] = true;
+                order.push(block);
+                next = self.blocks[block.0].terminator.preferred();
+            }
+        }
+        order
+    }
+
+    fn weighted_layout(&self) -> Vec<Block> {
+        let n = self.blocks.len();
+        let mut live: Vec<bool> = (0..n).map(|i| !self.is_trampoline(Block(i))).collect();
+        let mut work: Vec<Block> = (0..n).map(Block).filter(|b| live[b.0]).collect();
+        let mut edges = Vec::new();
+        while let Some(from) = work.pop() {
+            let successors = self.blocks[from.0].terminator.successors();
+            for (slot, to) in successors.into_iter().enumerate() {
+                let Some(to) = to else { continue };
+                let target = self.resolve(to);
+                edges.push((self.weight(from, to), from, slot, target));
+                if !live[target.0] {
+                    live[target.0] = true;
+                    work.push(target);
+                }
+            }
+        }
+        edges.sort_by_key(|&(weight, from, slot, _)| (Reverse(weight), from, slot));
+
+        // Each chain keeps the index of its first block, so chains stay in
+        // creation order with the entry block's first.
+        let mut chain: Vec<usize> = (0..n).collect();
+        let mut chains: Vec<Vec<Block>> = (0..n).map(|i| vec![Block(i)]).collect();
+        for (_, from, _, to) in edges {
+            let (a, b) = (chain[from.0], chain[to.0]);
+            if a == b || to == self.entry() || chains[a].last() != Some(&from) || chains[b][0] != to
+            {
+                continue;
+            }
+            let moved = core::mem::take(&mut chains[b]);
+            for block in &moved {
+                chain[block.0] = a;
+            }
+            chains[a].extend(moved);
+        }
+        chains
+            .into_iter()
+            .filter(|c| c.first().is_some_and(|b| live[b.0]))
+            .flatten()
+            .collect()
+    }
+
+    /// Returns whether `block` is an empty block that only jumps elsewhere,
+    /// which weighted layout bypasses.
+    fn is_trampoline(&self, block: Block) -> bool {
+        let data = &self.blocks[block.0];
+        block != self.entry()
+            && !data.keep_label
+            && data.body.insns().is_empty()
+            && matches!(data.terminator, Terminator::Jump(target) if target != block)
+    }
+
+    /// Returns the block a jump to `block` goes to after bypassing empty
+    /// blocks that only jump elsewhere, under weighted layout.
+    fn resolve(&self, block: Block) -> Block {
+        if self.order != BlockOrder::Weighted {
+            return block;
+        }
+        let mut target = block;
+        for _ in 0..self.blocks.len() {
+            match self.blocks[target.0].terminator {
+                Terminator::Jump(next) if self.is_trampoline(target) => target = next,
+                _ => return target,
+            }
+        }
+        // The empty blocks form a cycle; keep the jump as written.
+        block
+    }
+
+    /// Emits the function into `w`: each block in [`layout`](Self::layout)
+    /// order, preceded by its label where one is needed and followed by the
+    /// jumps its terminator still requires.
+    ///
+    /// `label` names each block and is called once per block, in creation
+    /// order. The builder is left unchanged, so the same function can be
+    /// emitted again, e.g. after a sizing pass.
+    pub fn finish<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        label: impl FnMut(Block) -> L,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+        L: Clone,
+    {
+        let labels: Vec<L> = (0..self.blocks.len()).map(Block).map(label).collect();
+        let order = self.layout();
+        let jumps: Vec<[Option<Jump>; 2]> = order
+            .iter()
+            .enumerate()
+            .map(|(i, block)| {
+                let next = order.get(i + 1).copied();
+                let terminator = self.blocks[block.0].terminator;
+                terminator.retarget(|t| self.resolve(t)).lower(next)
+            })
+            .collect();
+        let mut needs_label: Vec<bool> = self.blocks.iter().map(|b| b.keep_label).collect();
+        for jump in jumps.iter().flatten().flatten() {
+            if let Some(target) = jump.target() {
+                needs_label[target.0] = true;
+            }
+        }
+        for (block, jumps) in order.iter().zip(&jumps) {
+            if needs_label[block.0] {
+                w.set_label(ctx, cfg, labels[block.0].clone())?;
+            }
+            self.blocks[block.0].body.replay(w, ctx, cfg)?;
+            for jump in jumps.iter().flatten() {
+                match *jump {
+                    Jump::Ret => w.ret(ctx, cfg)?,
+                    Jump::Always(target) => {
+                        w.jal_label(ctx, cfg, &Reg(0), labels[target.0].clone())?
+                    }
+                    Jump::Cond(cond, a, b, hint, target) => w.bcond_label_hinted(
+                        ctx,
+                        cfg,
+                        cond,
+                        &a,
+                        &b,
+                        labels[target.0].clone(),
+                        hint,
+                    )?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<L> Default for FunctionBuilder<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::out::WriterCore;
+    use crate::out::arg::MemArg;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    fn emit(f: &FunctionBuilder<&'static str>) -> String {
+        let names = [".Lentry", ".L1", ".L2", ".L3"];
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        f.finish(&mut writer, &mut (), RiscV64Arch::default(), |b| {
+            names[b.index()]
+        })
+        .unwrap();
+        output
+    }
+
+    #[test]
+    fn test_diamond_falls_through_and_labels_targets() {
+        let cfg = RiscV64Arch::default();
+        let mut f = FunctionBuilder::new();
+        let (entry, less, done) = (f.entry(), f.block(), f.block());
+        // Filled out of order; `done` is created before it is reached.
+        f.body(done).ebreak(&mut (), cfg).unwrap();
+        f.terminate(done, Terminator::Return);
+        f.body(entry).nop(&mut (), cfg).unwrap();
+        f.terminate(
+            entry,
+            Terminator::Branch {
+                cond: ConditionCode::LT,
+                a: Reg(10).concrete_mem_kind(),
+                b: Reg(11).concrete_mem_kind(),
+                hint: BranchHint::None,
+                taken: less,
+                not_taken: done,
+            },
+        );
+        f.body(less).unimp(&mut (), cfg).unwrap();
+        f.terminate(less, Terminator::Jump(done));
+        assert_eq!(f.layout(), [entry, done, less]);
+        assert_eq!(
+            emit(&f),
+            "nop\nblt a0, a1, .L1\n.L2:\nebreak\nret\n.L1:\nunimp\njal zero, .L2\n"
+        );
+    }
+
+    #[test]
+    fn test_likely_branch_inverts_and_keeps_loop_label() {
+        let cfg = RiscV64Arch::default();
+        let mut f = FunctionBuilder::new();
+        let (entry, body, exit) = (f.entry(), f.block(), f.block());
+        f.terminate(entry, Terminator::Jump(body));
+        f.body(body).nop(&mut (), cfg).unwrap();
+        f.terminate(
+            body,
+            Terminator::Branch {
+                cond: ConditionCode::NE,
+                a: Reg(10).concrete_mem_kind(),
+                b: Reg(11).concrete_mem_kind(),
+                hint: BranchHint::Likely,
+                taken: body,
+                not_taken: exit,
+            },
+        );
+        f.terminate(exit, Terminator::Return);
+        f.keep_label(entry);
+        assert_eq!(emit(&f), ".Lentry:\n.L1:\nnop\nbne a0, a1, .L1\nret\n");
+    }
+
+    #[test]
+    fn test_weighted_order_follows_heaviest_edges() {
+        let cfg = RiscV64Arch::default();
+        let mut f = FunctionBuilder::new().with_order(BlockOrder::Weighted);
+        let (entry, less, done) = (f.entry(), f.block(), f.block());
+        f.body(entry).nop(&mut (), cfg).unwrap();
+        f.terminate(
+            entry,
+            Terminator::Branch {
+                cond: ConditionCode::LT,
+                a: Reg(10).concrete_mem_kind(),
+                b: Reg(11).concrete_mem_kind(),
+                hint: BranchHint::None,
+                taken: less,
+                not_taken: done,
+            },
+        );
+        f.body(less).unimp(&mut (), cfg).unwrap();
+        f.terminate(less, Terminator::Jump(done));
+        f.body(done).ebreak(&mut (), cfg).unwrap();
+        f.terminate(done, Terminator::Return);
+        f.set_weight(entry, less, 90);
+        f.set_weight(entry, done, 10);
+        assert_eq!(f.weight(less, done), 2);
+        assert_eq!(f.layout(), [entry, less, done]);
+        assert_eq!(emit(&f), "nop\nbge a0, a1, .L2\nunimp\n.L2:\nebreak\nret\n");
+    }
+
+    #[test]
+    fn test_weighted_order_bypasses_empty_jump_blocks() {
+        let cfg = RiscV64Arch::default();
+        let mut f = FunctionBuilder::new().with_order(BlockOrder::Weighted);
+        let (entry, hop, done, far) = (f.entry(), f.block(), f.block(), f.block());
+        f.body(entry).nop(&mut (), cfg).unwrap();
+        f.terminate(
+            entry,
+            Terminator::Branch {
+                cond: ConditionCode::EQ,
+                a: Reg(10).concrete_mem_kind(),
+                b: Reg(11).concrete_mem_kind(),
+                hint: BranchHint::None,
+                taken: hop,
+                not_taken: done,
+            },
+        );
+        f.terminate(hop, Terminator::Jump(far));
+        f.body(done).ebreak(&mut (), cfg).unwrap();
+        f.terminate(done, Terminator::Return);
+        f.body(far).unimp(&mut (), cfg).unwrap();
+        f.terminate(far, Terminator::Return);
+        assert_eq!(f.layout(), [entry, far, done]);
+        assert_eq!(
+            emit(&f),
+            "nop\nbne a0, a1, .L2\nunimp\nret\n.L2:\nebreak\nret\n"
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Bounds checks on array indices.
+//!
+//! [`BoundsCheck::bounds_check`] compares an index against a limit as
+//! unsigned values, so a negative index is out of bounds too, and either
+//! branches to a handler or traps when `index >= limit`:
+//!
+//! ```ignore
+//! writer.bounds_check(ctx, cfg, &Reg(11), &Reg(12), BoundsPolicy::Branch(oob))?;
+//! // bgeu a1, a2, oob
+//! writer.bounds_check(ctx, cfg, &Reg(11), &Reg(12), BoundsPolicy::Trap(ok))?;
+//! // bltu a1, a2, ok
+//! // ebreak
+//! // ok:
+//! ```
+//!
+//! RISC-V compares in the branch, so the check clobbers nothing, but both
+//! operands must be registers; load a constant limit with
+//! [`li`](crate::out::WriterCore::li) first.
+//!
+//! [`checked_element`](BoundsCheck::checked_element) emits the check and
+//! returns the element operand `[base + index << log2(size)]` for the load
+//! or store that follows. RISC-V has no indexed addressing, so the access
+//! must go through a [`DesugaringWriter`](crate::desugar::DesugaringWriter),
+//! which computes the address into a temporary.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::Writer;
+use crate::out::arg::{ArgKind, MemArg, MemArgKind};
+use crate::{BranchHint, ConditionCode, RegisterClass, RiscV64Arch};
+
+/// What a failed bounds check does.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BoundsPolicy<L> {
+    /// Branches to the label.
+    Branch(L),
+    /// Traps with `ebreak`. The label is defined after the trap, where
+    /// execution continues when the check passes.
+    Trap(L),
+}
+
+/// Emits bounds checks.
+///
+/// Implemented for every [`Writer`].
+pub trait BoundsCheck<L: Clone, Context>: Writer<L, Context> {
+    /// Checks `index < limit`, unsigned, and applies `policy` when it does
+    /// not hold.
+    ///
+    /// `index` and `limit` are registers.
+    fn bounds_check(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        index: &(dyn MemArg + '_),
+        limit: &(dyn MemArg + '_),
+        policy: BoundsPolicy<L>,
+    ) -> Result<(), Self::Error> {
+        match policy {
+            BoundsPolicy::Branch(oob) => self.bcond_label_hinted(
+                ctx,
+                cfg,
+                ConditionCode::GEU,
+                index,
+                limit,
+                oob,
+                BranchHint::Unlikely,
+            ),
+            BoundsPolicy::Trap(ok) => {
+                self.bcond_label_hinted(
+                    ctx,
+                    cfg,
+                    ConditionCode::LTU,
+                    index,
+                    limit,
+                    ok.clone(),
+                    BranchHint::Likely,
+                )?;
+                self.ebreak(ctx, cfg)?;
+                self.set_label(ctx, cfg, ok)
+            }
+        }
+    }
+
+    /// Checks `index < limit` as [`bounds_check`](Self::bounds_check) does
+    /// and returns the `size` element at `index` of the array at `base`.
+    #[allow(clippy::too_many_arguments)]
+    fn checked_element(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        base: Reg,
+        index: Reg,
+        limit: &(dyn MemArg + '_),
+        size: MemorySize,
+        policy: BoundsPolicy<L>,
+    ) -> Result<MemArgKind<ArgKind>, Self::Error> {
+        self.bounds_check(ctx, cfg, &index, limit, policy)?;
+        Ok(element(base, index, size))
+    }
+}
+
+impl<L: Clone, Context, W: Writer<L, Context> + ?Sized> BoundsCheck<L, Context> for W {}
+
+/// The `size` element `[base + index << log2(size)]`.
+///
+/// # Panics
+///
+/// Panics if `size` is wider than 64 bits.
+pub fn element(base: Reg, index: Reg, size: MemorySize) -> MemArgKind<ArgKind> {
+    let shift = match size {
+        MemorySize::_8 => 0,
+        MemorySize::_16 => 1,
+        MemorySize::_32 => 2,
+        MemorySize::_64 => 3,
+        _ => panic!("element size has no RISC-V index shift"),
+    };
+    let reg = |reg| ArgKind::Reg {
+        reg,
+        size: MemorySize::_64,
+    };
+    MemArgKind::Mem {
+        base: reg(base),
+        offset: Some((reg(index), shift)),
+        disp: 0,
+        size,
+        reg_class: RegisterClass::Gpr,
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::desugar::DesugaringWriter;
+    use crate::out::WriterCore;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt::Write;
+
+    /// Runs the branches at the top of `listing` with `regs` holding the
+    /// named registers, and returns the first other instruction reached.
+    fn reached<'a>(listing: &'a str, regs: &[(&str, u64)]) -> &'a str {
+        let value = |operand: &str| regs.iter().find(|(name, _)| *name == operand).unwrap().1;
+        let lines: Vec<&str> = listing.lines().collect();
+        let mut pc = 0;
+        loop {
+            let line = lines[pc];
+            pc += 1;
+            let (mnemonic, operands) = line.split_once(' ').unwrap_or((line, ""));
+            let mut operands = operands.split(", ");
+            let below = match mnemonic {
+                "bltu" => true,
+                "bgeu" => false,
+                _ if line.ends_with(':') => continue,
+                _ => return line,
+            };
+            let (a, b) = (operands.next().unwrap(), operands.next().unwrap());
+            let target = operands.next().unwrap();
+            if (value(a) < value(b)) == below {
+                pc = lines
+                    .iter()
+                    .position(|l| l.strip_suffix(':') == Some(target))
+                    .unwrap()
+                    + 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_index_takes_the_trap() {
+        let mut output = String::new();
+        let writer: &mut dyn Write = &mut output;
+        let cfg = RiscV64Arch::default();
+        writer
+            .bounds_check(&mut (), cfg, &Reg(11), &Reg(12), BoundsPolicy::Trap(".Lok"))
+            .unwrap();
+        writer.ret(&mut (), cfg).unwrap();
+        assert_eq!(reached(&output, &[("a1", 15), ("a2", 16)]), "ret");
+        assert_eq!(reached(&output, &[("a1", 16), ("a2", 16)]), "ebreak");
+        // A negative index is a huge unsigned one.
+        assert_eq!(
+            reached(&output, &[("a1", -1i64 as u64), ("a2", 16)]),
+            "ebreak"
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_index_takes_the_branch() {
+        let mut output = String::new();
+        let writer: &mut dyn Write = &mut output;
+        let cfg = RiscV64Arch::default();
+        writer
+            .bounds_check(
+                &mut (),
+                cfg,
+                &Reg(11),
+                &Reg(12),
+                BoundsPolicy::Branch(".Loob"),
+            )
+            .unwrap();
+        writer.ret(&mut (), cfg).unwrap();
+        writer.set_label(&mut (), cfg, ".Loob").unwrap();
+        writer.nop(&mut (), cfg).unwrap();
+        assert_eq!(reached(&output, &[("a1", 3), ("a2", 4)]), "ret");
+        assert_eq!(reached(&output, &[("a1", 4), ("a2", 4)]), "nop");
+        assert_eq!(reached(&output, &[("a1", u64::MAX), ("a2", 4)]), "nop");
+    }
+
+    #[test]
+    fn test_checked_element_through_desugarer() {
+        let mut output = String::new();
+        {
+            let mut writer = DesugaringWriter::new(&mut output as &mut dyn Write);
+            let cfg = RiscV64Arch::default();
+            let elem = writer
+                .checked_element(
+                    &mut (),
+                    cfg,
+                    Reg(10),
+                    Reg(11),
+                    &Reg(12),
+                    MemorySize::_32,
+                    BoundsPolicy::Branch(".Loob"),
+                )
+                .unwrap();
+            writer.lw(&mut (), cfg, &Reg(13), &elem).unwrap();
+        }
+        assert!(output.starts_with("bgeu a1, a2, .Loob\n"));
+        assert!(output.contains("sll t6, a1, "));
+        assert!(output.ends_with("add t6, a0, t6\nlw a3, 0(t6)\n"));
+    }
+}
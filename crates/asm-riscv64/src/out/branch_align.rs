@@ -0,0 +1,157 @@
+//! Alignment of loop headers and other hot branch targets.
+//!
+//! The front end fetches code in aligned blocks, so a loop whose header sits
+//! just before a block boundary pays for an extra fetch on every iteration.
+//! [`BranchAlignWriter`] pads to an alignment boundary with
+//! [`align_to`](WriterCore::align_to) before each label its predicate
+//! selects. The layer that knows the control-flow graph marks loop headers
+//! through the predicate; the code emitting the loop is unchanged:
+//!
+//! ```ignore
+//! let mut writer = BranchAlignWriter::new(&mut output, |label: &Label| headers.contains(label));
+//! writer.set_label(ctx, cfg, header)?; // .balign 4, then header:
+//! ```
+//!
+//! Padding is emitted as NOPs, which code falling through into the label
+//! executes, so only labels that are mostly reached by branches are worth
+//! aligning.
+
+use core::ops::{Deref, DerefMut};
+
+use crate::out::{Writer, WriterCore};
+
+/// Default alignment of selected labels, in bytes: with compressed
+/// instructions a label can land on a 2-byte boundary, which makes its first
+/// 4-byte instruction straddle two fetch words.
+pub const DEFAULT_ALIGNMENT: usize = 4;
+
+/// Aligns the labels selected by `is_target` before defining them.
+///
+/// `is_target` is called with each label passed to
+/// [`set_label`](Writer::set_label); everything else is forwarded unchanged.
+pub struct BranchAlignWriter<W, F> {
+    writer: W,
+    is_target: F,
+    alignment: usize,
+}
+
+impl<W, F> BranchAlignWriter<W, F> {
+    /// Wraps `writer`, aligning labels selected by `is_target` to
+    /// [`DEFAULT_ALIGNMENT`].
+    pub fn new(writer: W, is_target: F) -> Self {
+        Self {
+            writer,
+            is_target,
+            alignment: DEFAULT_ALIGNMENT,
+        }
+    }
+
+    /// Sets the alignment of selected labels, in bytes.
+    pub fn with_alignment(self, alignment: usize) -> Self {
+        Self { alignment, ..self }
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W, F> Deref for BranchAlignWriter<W, F> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        &self.writer
+    }
+}
+
+impl<W, F> DerefMut for BranchAlignWriter<W, F> {
+    fn deref_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+crate::writer_dispatch!(@core [ W: WriterCore<Context>, F, Context ] BranchAlignWriter<W, F> => W::Error [Context] [W]);
+
+impl<W, F, L, Context> Writer<L, Context> for BranchAlignWriter<W, F>
+where
+    W: Writer<L, Context>,
+    F: FnMut(&L) -> bool,
+{
+    fn set_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        s: L,
+    ) -> Result<(), Self::Error> {
+        if (self.is_target)(&s) {
+            self.writer.align_to(ctx, cfg, self.alignment)?;
+        }
+        self.writer.set_label(ctx, cfg, s)
+    }
+
+    fn jal_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        dest: &(dyn crate::out::arg::MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.jal_label(ctx, cfg, dest, label)
+    }
+
+    fn la_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        dest: &(dyn crate::out::arg::MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.la_label(ctx, cfg, dest, label)
+    }
+
+    fn bcond_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        cond: crate::ConditionCode,
+        a: &(dyn crate::out::arg::MemArg + '_),
+        b: &(dyn crate::out::arg::MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.bcond_label(ctx, cfg, cond, a, b, label)
+    }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.dd_label(ctx, cfg, label)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::RiscV64Arch;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_aligns_selected_labels() {
+        let cfg = RiscV64Arch::default();
+        let mut output = String::new();
+        {
+            let mut writer = BranchAlignWriter::new(&mut output as &mut dyn Write, |l: &&str| {
+                l.starts_with("loop")
+            });
+            writer.set_label(&mut (), cfg, "entry").unwrap();
+            writer.set_label(&mut (), cfg, "loop_0").unwrap();
+            writer.jal_label(&mut (), cfg, &Reg(0), "loop_0").unwrap();
+        }
+        assert_eq!(output, "entry:\n.balign 4\nloop_0:\njal zero, loop_0\n");
+    }
+}
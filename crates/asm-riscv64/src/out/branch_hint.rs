@@ -0,0 +1,205 @@
+//! Layout of two-way branches by expected direction.
+//!
+//! Static predictors and the instruction fetch both favour the fall-through
+//! path. [`HintedBranch::branch_two_way`] emits the single conditional
+//! branch that leaves the expected successor in fall-through position,
+//! inverting the condition when the branch is [`Likely`](BranchHint::Likely):
+//!
+//! ```ignore
+//! match writer.branch_two_way(ctx, cfg, ConditionCode::EQ, &a0, &zero, BranchHint::Likely, hot, cold)? {
+//!     // bne a0, zero, cold
+//!     Fallthrough::Taken => { /* hot: ... */ }
+//!     Fallthrough::NotTaken => unreachable!(),
+//! }
+//! ```
+//!
+//! Keeping cold blocks out of line also keeps hot code compact for the C
+//! extension: `c.beqz`/`c.bnez` reach only 256 bytes and `c.j` 2 KiB. For
+//! the same reason an `EQ`/`NE` test against `zero` is written with `zero`
+//! second, the only order the compressed forms accept (see
+//! [`compress`](crate::out::compress)).
+
+use portal_pc_asm_common::types::reg::Reg;
+
+use crate::out::Writer;
+use crate::out::arg::{ArgKind, MemArg, MemArgKind};
+use crate::{BranchHint, ConditionCode, RiscV64Arch};
+
+/// Which successor of a two-way branch follows it in memory.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Fallthrough {
+    /// The block run when the condition holds.
+    Taken,
+    /// The block run when the condition does not hold.
+    NotTaken,
+}
+
+impl Fallthrough {
+    /// Returns the successor to place next for a branch hinted `hint`.
+    pub fn for_hint(hint: BranchHint) -> Self {
+        match hint {
+            BranchHint::Likely => Fallthrough::Taken,
+            _ => Fallthrough::NotTaken,
+        }
+    }
+}
+
+/// Returns whether `arg` is the `zero` register.
+fn is_zero(arg: &(dyn MemArg + '_)) -> bool {
+    matches!(
+        arg.concrete_mem_kind(),
+        MemArgKind::NoMem(ArgKind::Reg { reg: Reg(0), .. })
+    )
+}
+
+/// Emits conditional branches laid out by their expected direction.
+///
+/// Implemented for every [`Writer`].
+pub trait HintedBranch<L, Context>: Writer<L, Context> {
+    /// Branches to `taken` if `cc` holds between `a` and `b`, and to
+    /// `not_taken` otherwise.
+    ///
+    /// Only one branch is emitted; the returned successor must be placed
+    /// immediately after it.
+    #[allow(clippy::too_many_arguments)]
+    fn branch_two_way(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        cc: ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        hint: BranchHint,
+        taken: L,
+        not_taken: L,
+    ) -> Result<Fallthrough, Self::Error> {
+        let (a, b) = match cc {
+            ConditionCode::EQ | ConditionCode::NE if is_zero(a) && !is_zero(b) => (b, a),
+            _ => (a, b),
+        };
+        let next = Fallthrough::for_hint(hint);
+        match next {
+            Fallthrough::Taken => self.bcond_label_hinted(
+                ctx,
+                cfg,
+                cc.invert(),
+                a,
+                b,
+                not_taken,
+                BranchHint::Unlikely,
+            )?,
+            Fallthrough::NotTaken => self.bcond_label_hinted(ctx, cfg, cc, a, b, taken, hint)?,
+        }
+        Ok(next)
+    }
+}
+
+impl<L, Context, W: Writer<L, Context> + ?Sized> HintedBranch<L, Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt::Write;
+
+    const CONDITIONS: [ConditionCode; 10] = {
+        use ConditionCode::*;
+        [EQ, NE, LT, GE, LTU, GEU, GT, LE, GTU, LEU]
+    };
+
+    /// Whether `cc` holds between `a` and `b`.
+    fn holds(cc: ConditionCode, a: u64, b: u64) -> bool {
+        use ConditionCode::*;
+        let (sa, sb) = (a as i64, b as i64);
+        match cc {
+            EQ => a == b,
+            NE => a != b,
+            LT => sa < sb,
+            GE => sa >= sb,
+            LTU => a < b,
+            GEU => a >= b,
+            GT => sa > sb,
+            LE => sa <= sb,
+            GTU => a > b,
+            LEU => a <= b,
+        }
+    }
+
+    #[test]
+    fn test_each_successor_is_reached_exactly_when_it_should_be() {
+        let values = [0, 1, i64::MAX as u64, i64::MIN as u64, u64::MAX];
+        for cc in CONDITIONS {
+            for hint in [BranchHint::None, BranchHint::Likely, BranchHint::Unlikely] {
+                let mut output = String::new();
+                let mut writer: &mut dyn Write = &mut output;
+                let next = writer
+                    .branch_two_way(
+                        &mut (),
+                        RiscV64Arch::default(),
+                        cc,
+                        &Reg(10),
+                        &Reg(11),
+                        hint,
+                        "taken",
+                        "not_taken",
+                    )
+                    .unwrap();
+                assert_eq!(next, Fallthrough::for_hint(hint));
+                let (mnemonic, operands) = output.trim_end().split_once(' ').unwrap();
+                let bcond = CONDITIONS
+                    .into_iter()
+                    .find(|c| format!("b{c}") == mnemonic)
+                    .unwrap();
+                let [ra, rb, target] = operands.split(", ").collect::<Vec<_>>()[..] else {
+                    panic!("expected two registers and a label: {output}");
+                };
+                let fallthrough = match next {
+                    Fallthrough::Taken => "taken",
+                    Fallthrough::NotTaken => "not_taken",
+                };
+                for a in values {
+                    for b in values {
+                        let reg = |name: &str| match name {
+                            "a0" => a,
+                            "a1" => b,
+                            _ => panic!("unexpected operand {name}"),
+                        };
+                        let reached = if holds(bcond, reg(ra), reg(rb)) {
+                            target
+                        } else {
+                            fallthrough
+                        };
+                        let expected = if holds(cc, a, b) {
+                            "taken"
+                        } else {
+                            "not_taken"
+                        };
+                        assert_eq!(reached, expected, "{cc:?} {hint:?} {a:#x} {b:#x}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_is_moved_second() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let next = writer
+            .branch_two_way(
+                &mut (),
+                RiscV64Arch::default(),
+                ConditionCode::EQ,
+                &Reg(0),
+                &Reg(8),
+                BranchHint::Unlikely,
+                ".Lcold",
+                ".Lhot",
+            )
+            .unwrap();
+        assert_eq!(next, Fallthrough::NotTaken);
+        assert_eq!(output, "beq s0, zero, .Lcold\n");
+    }
+}
@@ -0,0 +1,132 @@
+//! Control-flow integrity landing pads and shadow-stack checks.
+//!
+//! With Zicfilp landing pads enforced, an indirect jump must land on an
+//! `lpad`. With the Zicfiss shadow stack, a function pushes `ra` onto a
+//! stack ordinary stores cannot reach on entry and checks it against that
+//! copy before returning, so a corrupted return address faults instead of
+//! being followed. [`Cfi`] emits both where [`RiscV64Arch::zicfilp`] and
+//! [`RiscV64Arch::zicfiss`] ask for them and nothing otherwise:
+//!
+//! ```ignore
+//! writer.function_entry(ctx, cfg)?;             // lpad 0; sspush ra
+//! // ...
+//! writer.set_branch_target(ctx, cfg, case0)?;   // case0: lpad 0
+//! // ...
+//! writer.function_return(ctx, cfg)?;            // sspopchk ra; ret
+//! ```
+//!
+//! Landing pads use label 0, which every indirect jump accepts. They must
+//! be 4-byte aligned, so with the C extension enabled
+//! [`set_branch_target`](Cfi::set_branch_target) aligns the label first.
+//! Function entries written with
+//! [`emit_function`](crate::out::function::emit_function) get their pad
+//! from [`Function::with_landing_pad`](crate::out::function::Function::with_landing_pad).
+
+use portal_pc_asm_common::types::reg::Reg;
+
+use crate::RiscV64Arch;
+use crate::out::{Writer, WriterCore};
+
+/// The return address register, `ra`.
+const RA: Reg = Reg(1);
+
+/// Emits control-flow integrity landing pads and shadow-stack checks.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Cfi<Context>: WriterCore<Context> {
+    /// Emits what a function entry needs: a landing pad under Zicfilp and a
+    /// shadow-stack push of `ra` under Zicfiss.
+    fn function_entry(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.branch_target(ctx, cfg)?;
+        if cfg.zicfiss {
+            self.sspush(ctx, cfg, &RA)?;
+        }
+        Ok(())
+    }
+
+    /// Returns from a function entered with
+    /// [`function_entry`](Self::function_entry), checking `ra` against the
+    /// shadow stack first under Zicfiss.
+    fn function_return(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        if cfg.zicfiss {
+            self.sspopchk(ctx, cfg, &RA)?;
+        }
+        self.ret(ctx, cfg)
+    }
+
+    /// Emits the landing pad an indirect jump target needs, if any.
+    fn branch_target(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        if cfg.zicfilp {
+            self.lpad(ctx, cfg, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Defines `label` as the target of an indirect jump.
+    fn set_branch_target<L>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        label: L,
+    ) -> Result<(), Self::Error>
+    where
+        Self: Writer<L, Context>,
+    {
+        if cfg.zicfilp && cfg.c_extension {
+            self.align(ctx, cfg, 2)?;
+        }
+        self.set_label(ctx, cfg, label)?;
+        self.branch_target(ctx, cfg)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Cfi<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[cfg(feature = "rv-asm-backend")]
+    #[test]
+    fn test_pads_and_shadow_stack_in_machine_code() {
+        use crate::out::rv_asm_backend::RvAsmWriter;
+
+        let cfg = RiscV64Arch {
+            zicfilp: true,
+            zicfiss: true,
+            ..RiscV64Arch::rv64gc()
+        };
+        let mut binary = RvAsmWriter::<u32>::new();
+        binary.function_entry(&mut (), cfg).unwrap();
+        // A compressed instruction leaves the next label 2-byte aligned.
+        binary.nops(&mut (), cfg, 2).unwrap();
+        binary.set_branch_target(&mut (), cfg, 1).unwrap();
+        binary.function_return(&mut (), cfg).unwrap();
+        let (code, labels) = binary.into_parts();
+        let word = |at: usize| u32::from_le_bytes(code[at..at + 4].try_into().unwrap());
+        // `lpad 0` then `sspush ra`.
+        assert_eq!(word(0), 0x0000_0017);
+        assert_eq!(word(4), 0xCE10_4073);
+        // The label is padded to a word, where an indirect jump finds
+        // `lpad 0`.
+        assert_eq!(labels[&1], 12);
+        assert_eq!(word(12), 0x0000_0017);
+        // `sspopchk ra` checks the return address before `ret`.
+        assert_eq!(word(16), 0xCDC0_C073);
+        assert_eq!(word(20), 0x0000_8067);
+    }
+
+    #[test]
+    fn test_compressed_targets_are_aligned() {
+        let cfg = RiscV64Arch {
+            zicfilp: true,
+            ..RiscV64Arch::rv64gc()
+        };
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        writer.set_branch_target(&mut (), cfg, ".Lcase").unwrap();
+        assert_eq!(output, ".p2align 2\n.Lcase:\nlpad 0\n");
+    }
+}
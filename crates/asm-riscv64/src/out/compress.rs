@@ -0,0 +1,186 @@
+//! Selection of compressed (C extension) instruction forms.
+//!
+//! When [`RiscV64Arch::c_extension`] is set, writers emit the 16-bit form of
+//! an instruction whenever its operands fit one: `addi sp, sp, -16` becomes
+//! `c.addi16sp sp, -16` and `ld a0, 8(sp)` becomes `c.ldsp a0, 8(sp)`. The
+//! functions here decide whether that is possible for one instruction and
+//! return `None` (or `false`) when the extension is off.
+//!
+//! An instruction that must keep its full 32-bit encoding, such as a patch
+//! site or an entry in a fixed-stride table, is emitted with
+//! [`RiscV64Arch::uncompressed`] as its configuration.
+
+use portal_pc_asm_common::types::reg::Reg;
+
+use crate::RiscV64Arch;
+use crate::out::arg::{ArgKind, MemArg, MemArgKind};
+
+const ZERO: Reg = Reg(0);
+const SP: Reg = Reg(2);
+
+/// Returns the register of a direct register operand.
+fn reg(arg: &(dyn MemArg + '_)) -> Option<Reg> {
+    match arg.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => Some(reg),
+        _ => None,
+    }
+}
+
+/// Returns the base register and displacement of a `disp(base)` operand.
+fn base_disp(arg: &(dyn MemArg + '_)) -> Option<(Reg, i32)> {
+    match arg.concrete_mem_kind() {
+        MemArgKind::Mem {
+            base: ArgKind::Reg { reg, .. },
+            offset: None,
+            disp,
+            ..
+        } => Some((reg, disp)),
+        _ => None,
+    }
+}
+
+/// Whether `reg` is one of `x8`–`x15`, the registers the three-bit fields of
+/// `c.ld`/`c.sd` can name.
+fn is_popular(reg: Reg) -> bool {
+    (8..16).contains(&reg.0)
+}
+
+/// Whether `imm` fits a six-bit signed immediate.
+fn is_imm6(imm: i64) -> bool {
+    (-32..32).contains(&imm)
+}
+
+/// Picks the compressed form of `addi dest, src, imm`.
+///
+/// Returns `c.addi`, `c.addi16sp` or `c.li`; all three are written with
+/// `dest` and `imm` as their operands.
+pub fn addi(
+    cfg: RiscV64Arch,
+    dest: &(dyn MemArg + '_),
+    src: &(dyn MemArg + '_),
+    imm: i32,
+) -> Option<&'static str> {
+    if !cfg.c_extension {
+        return None;
+    }
+    let (rd, rs1) = (reg(dest)?, reg(src)?);
+    if rd == ZERO {
+        return None;
+    }
+    if rs1 == ZERO && is_imm6(imm.into()) {
+        Some("c.li")
+    } else if rd != rs1 || imm == 0 {
+        None
+    } else if is_imm6(imm.into()) {
+        Some("c.addi")
+    } else if rd == SP && imm % 16 == 0 && (-512..512).contains(&imm) {
+        Some("c.addi16sp")
+    } else {
+        None
+    }
+}
+
+/// Whether `li dest, val` can be written as `c.li`.
+pub fn li(cfg: RiscV64Arch, dest: &(dyn MemArg + '_), val: u64) -> bool {
+    cfg.c_extension && reg(dest).is_some_and(|rd| rd != ZERO) && is_imm6(val as i64)
+}
+
+/// Whether `mv dest, src` can be written as `c.mv`.
+pub fn mv(cfg: RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> bool {
+    cfg.c_extension
+        && reg(dest).is_some_and(|rd| rd != ZERO)
+        && reg(src).is_some_and(|rs| rs != ZERO)
+}
+
+/// Checks whether a doubleword access to `mem`, loading into or storing from
+/// `data`, has a compressed form.
+///
+/// Returns `Some(true)` for the stack-pointer forms (`c.ldsp`/`c.sdsp`) and
+/// `Some(false)` for `c.ld`/`c.sd`.
+fn doubleword(data: Reg, mem: &(dyn MemArg + '_), load: bool) -> Option<bool> {
+    let (base, disp) = base_disp(mem)?;
+    if disp % 8 != 0 || disp < 0 {
+        None
+    } else if base == SP && disp < 512 && !(load && data == ZERO) {
+        Some(true)
+    } else if is_popular(base) && is_popular(data) && disp < 256 {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Picks the compressed form of `ld dest, mem`: `c.ld` or `c.ldsp`.
+pub fn ld(
+    cfg: RiscV64Arch,
+    dest: &(dyn MemArg + '_),
+    mem: &(dyn MemArg + '_),
+) -> Option<&'static str> {
+    if !cfg.c_extension {
+        return None;
+    }
+    let sp = doubleword(reg(dest)?, mem, true)?;
+    Some(if sp { "c.ldsp" } else { "c.ld" })
+}
+
+/// Picks the compressed form of `sd src, mem`: `c.sd` or `c.sdsp`.
+pub fn sd(
+    cfg: RiscV64Arch,
+    src: &(dyn MemArg + '_),
+    mem: &(dyn MemArg + '_),
+) -> Option<&'static str> {
+    if !cfg.c_extension {
+        return None;
+    }
+    let sp = doubleword(reg(src)?, mem, false)?;
+    Some(if sp { "c.sdsp" } else { "c.sd" })
+}
+
+/// Whether `j target` can be written as `c.j`.
+///
+/// Only literal offsets qualify; a label's distance is not known when the
+/// jump is written, and `c.j` reaches just ±2 KiB.
+pub fn j(cfg: RiscV64Arch, target: &(dyn MemArg + '_)) -> bool {
+    match target.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Lit(offset)) => {
+            let offset = offset as i64;
+            cfg.c_extension && offset % 2 == 0 && (-2048..2048).contains(&offset)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_forms() {
+        let c = RiscV64Arch::rv64gc();
+        let (sp, a0, a1, s2) = (Reg(2), Reg(10), Reg(11), Reg(18));
+        assert_eq!(addi(c, &sp, &sp, -16), Some("c.addi"));
+        assert_eq!(addi(c, &sp, &sp, -64), Some("c.addi16sp"));
+        assert_eq!(addi(c, &sp, &sp, -72), None);
+        assert_eq!(addi(c, &a0, &ZERO, -1), Some("c.li"));
+        assert_eq!(addi(c, &a0, &a1, 4), None);
+        assert_eq!(addi(c.uncompressed(), &sp, &sp, -64), None);
+        assert!(mv(c, &a0, &a1));
+        assert!(!mv(c, &a0, &ZERO));
+
+        let mem = |base, disp| MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: base,
+                size: portal_pc_asm_common::types::mem::MemorySize::_64,
+            },
+            offset: None,
+            disp,
+            size: portal_pc_asm_common::types::mem::MemorySize::_64,
+            reg_class: crate::RegisterClass::Gpr,
+        };
+        assert_eq!(ld(c, &a0, &mem(sp, 8)), Some("c.ldsp"));
+        assert_eq!(ld(c, &a0, &mem(a1, 8)), Some("c.ld"));
+        assert_eq!(ld(c, &s2, &mem(a1, 8)), None);
+        assert_eq!(sd(c, &a0, &mem(a1, 4)), None);
+        assert_eq!(sd(c, &s2, &mem(sp, 504)), Some("c.sdsp"));
+    }
+}
@@ -0,0 +1,125 @@
+//! Data directives interleaved with code.
+//!
+//! Jump tables, string literals and other constants often belong next to
+//! the function that uses them. [`DataWriter`] emits them through
+//! [`db`](WriterCore::db) on any [`WriterCore`], and labels them with
+//! [`set_label`](crate::out::Writer::set_label) like code:
+//!
+//! ```ignore
+//! writer.set_label(ctx, cfg, greeting)?;
+//! writer.asciz(ctx, cfg, "hello")?; // .byte 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x00
+//! writer.align_to(ctx, cfg, 8)?;
+//! writer.set_label(ctx, cfg, table)?;
+//! writer.dq(ctx, cfg, 0x1000)?;
+//! ```
+//!
+//! Multi-byte values are little-endian. Data is not executable: emit it
+//! where execution cannot fall into it, such as after a `ret` or `j`.
+
+use crate::RiscV64Arch;
+use crate::out::WriterCore;
+
+/// Zero bytes written per [`db`](WriterCore::db) call by
+/// [`DataWriter::zero`].
+const ZERO_CHUNK: [u8; 64] = [0; 64];
+
+/// Emits data directives.
+///
+/// Implemented for every [`WriterCore`].
+pub trait DataWriter<Context>: WriterCore<Context> {
+    /// Emits a 16-bit value.
+    fn dw(&mut self, ctx: &mut Context, cfg: RiscV64Arch, value: u16) -> Result<(), Self::Error> {
+        self.db(ctx, cfg, &value.to_le_bytes())
+    }
+
+    /// Emits a 32-bit value.
+    fn dd(&mut self, ctx: &mut Context, cfg: RiscV64Arch, value: u32) -> Result<(), Self::Error> {
+        self.db(ctx, cfg, &value.to_le_bytes())
+    }
+
+    /// Emits a 64-bit value.
+    fn dq(&mut self, ctx: &mut Context, cfg: RiscV64Arch, value: u64) -> Result<(), Self::Error> {
+        self.db(ctx, cfg, &value.to_le_bytes())
+    }
+
+    /// Emits the bytes of `s`, without a terminator.
+    fn ascii(&mut self, ctx: &mut Context, cfg: RiscV64Arch, s: &str) -> Result<(), Self::Error> {
+        if s.is_empty() {
+            return Ok(());
+        }
+        self.db(ctx, cfg, s.as_bytes())
+    }
+
+    /// Emits the bytes of `s` followed by a NUL terminator.
+    fn asciz(&mut self, ctx: &mut Context, cfg: RiscV64Arch, s: &str) -> Result<(), Self::Error> {
+        self.ascii(ctx, cfg, s)?;
+        self.db(ctx, cfg, &[0])
+    }
+
+    /// Emits `n_bytes` zero bytes.
+    fn zero(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        let mut left = n_bytes;
+        while left > 0 {
+            let n = left.min(ZERO_CHUNK.len());
+            self.db(ctx, cfg, &ZERO_CHUNK[..n])?;
+            left -= n;
+        }
+        Ok(())
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> DataWriter<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt::Write;
+
+    #[cfg(feature = "rv-asm-backend")]
+    #[test]
+    fn test_labelled_data_reads_back() {
+        use crate::out::Writer;
+        use crate::out::rv_asm_backend::RvAsmWriter;
+
+        let cfg = RiscV64Arch::default();
+        let mut binary = RvAsmWriter::<u32>::new();
+        binary.ret(&mut (), cfg).unwrap();
+        binary.set_label(&mut (), cfg, 1).unwrap();
+        binary.asciz(&mut (), cfg, "hi").unwrap();
+        binary.align_to(&mut (), cfg, 8).unwrap();
+        binary.set_label(&mut (), cfg, 2).unwrap();
+        binary.dq(&mut (), cfg, 0x0102_0304_0506_0708).unwrap();
+        binary.dw(&mut (), cfg, 0xBEEF).unwrap();
+        let (code, labels) = binary.into_parts();
+        let (string, table) = (labels[&1], labels[&2]);
+        assert_eq!(string, 4);
+        assert_eq!(code[string..string + 3], *b"hi\0");
+        assert_eq!(table, 8);
+        let dq = u64::from_le_bytes(code[table..table + 8].try_into().unwrap());
+        assert_eq!(dq, 0x0102_0304_0506_0708);
+        assert_eq!(
+            u16::from_le_bytes(code[table + 8..].try_into().unwrap()),
+            0xBEEF
+        );
+    }
+
+    #[test]
+    fn test_zero_fill_and_empty_strings() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let cfg = RiscV64Arch::default();
+        out.zero(&mut (), cfg, 150).unwrap();
+        // An empty string emits no empty `.byte` line.
+        out.ascii(&mut (), cfg, "").unwrap();
+        out.asciz(&mut (), cfg, "").unwrap();
+        let lines: Vec<usize> = output.lines().map(|l| l.matches("0x00").count()).collect();
+        assert_eq!(lines, [64, 64, 22, 1]);
+    }
+}
@@ -0,0 +1,66 @@
+//! Fused multiply-add.
+//!
+//! [`Fma`] computes `a * b + c` on double-precision values with a single
+//! rounding:
+//!
+//! ```ignore
+//! writer.fma(ctx, cfg, &fa0, &fa1, &fa2, &fa0)?;
+//! // fmadd.d fa0, fa1, fa2, fa0
+//! ```
+//!
+//! `fmadd.d` is part of the D extension, so there is no fallback; the
+//! [`validate`](crate::out::validate) layer rejects it like any other
+//! double-precision instruction when D is disabled. The x86-64 and AArch64
+//! crates provide the same helper, with x86-64 falling back to a separately
+//! rounded multiply and add when FMA3 is disabled.
+
+use crate::RiscV64Arch;
+use crate::out::WriterCore;
+use crate::out::arg::MemArg;
+
+/// Emits fused multiply-adds.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Fma<Context>: WriterCore<Context> {
+    /// Sets `dest` to `a * b + c`, rounded once.
+    ///
+    /// Any of the operands may be the same register.
+    fn fma(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.fmadd_d(ctx, cfg, dest, a, b, c)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Fma<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_fma_is_fmadd_d() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        writer
+            .fma(
+                &mut (),
+                RiscV64Arch::default(),
+                &Reg(10),
+                &Reg(11),
+                &Reg(12),
+                &Reg(10),
+            )
+            .unwrap();
+        assert_eq!(output, "fmadd.d fa0, fa1, fa2, fa0\n");
+    }
+}
@@ -0,0 +1,195 @@
+//! Stack probing for large frames.
+//!
+//! Below the stack sits a guard page that turns an overflow into a fault.
+//! Moving `sp` down by a page or more in one step can jump over it and land
+//! in other memory (a "stack clash"), so every page of a large frame must be
+//! touched in order as it is allocated. [`FrameBuilder`] allocates frames
+//! that way:
+//!
+//! ```ignore
+//! let frame = FrameBuilder::new(probe_label);
+//! frame.probe_large_frame(&mut writer, ctx, cfg, 0x6000)?;
+//! // li t6, 6
+//! // probe_label: addi sp, sp, -2048; addi sp, sp, -2048; sd zero, 0(sp)
+//! // addi t6, t6, -1; bne t6, zero, probe_label
+//! ```
+//!
+//! `addi` reaches at most 2048 bytes, so each page is two steps and no
+//! register is needed to hold the page size. A frame of up to four pages is
+//! probed with straight-line code and larger ones with a loop counting pages
+//! in a scratch register.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::arg::MemArgKind;
+use crate::out::{Writer, WriterCore};
+use crate::{ConditionCode, RegisterClass, RiscV64Arch};
+
+/// Bytes of stack a frame may allocate before it must be probed.
+pub const PROBE_INTERVAL: u32 = 4096;
+
+/// Default scratch register for the probe loop: the temporary `t6`.
+pub const DEFAULT_SCRATCH: Reg = Reg(31);
+
+/// Most pages probed with straight-line code before a loop is used.
+const UNROLL_LIMIT: u32 = 4;
+
+/// Largest step `addi` can move `sp` down by.
+const MAX_STEP: u32 = 2048;
+
+/// The stack pointer, `sp`.
+const SP: Reg = Reg(2);
+
+/// The zero register.
+const ZERO: Reg = Reg(0);
+
+/// The doubleword at `0(sp)`.
+fn stack_top() -> MemArgKind<Reg> {
+    MemArgKind::Mem {
+        base: SP,
+        offset: None,
+        disp: 0,
+        size: MemorySize::_64,
+        reg_class: RegisterClass::Gpr,
+    }
+}
+
+/// Allocates stack frames without skipping the guard page.
+pub struct FrameBuilder<L> {
+    probe_loop: L,
+    scratch: Reg,
+}
+
+impl<L: Clone> FrameBuilder<L> {
+    /// Creates a builder that labels its probe loop `probe_loop`.
+    ///
+    /// The label is only defined when a loop is emitted, and at most once
+    /// per call to [`probe_large_frame`](Self::probe_large_frame).
+    pub fn new(probe_loop: L) -> Self {
+        Self {
+            probe_loop,
+            scratch: DEFAULT_SCRATCH,
+        }
+    }
+
+    /// Sets the register the probe loop counts pages in.
+    pub fn with_scratch(self, scratch: Reg) -> Self {
+        Self { scratch, ..self }
+    }
+
+    /// Moves `sp` down by `size` bytes, touching each new page on the way.
+    ///
+    /// Frames smaller than [`PROBE_INTERVAL`] are only `addi`s. Frames of
+    /// more than four pages clobber the scratch register.
+    pub fn probe_large_frame<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        size: u32,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        if size < PROBE_INTERVAL {
+            return move_sp(w, ctx, cfg, size);
+        }
+        let pages = size / PROBE_INTERVAL;
+        if pages <= UNROLL_LIMIT {
+            for _ in 0..pages {
+                probe_page(w, ctx, cfg, PROBE_INTERVAL)?;
+            }
+        } else {
+            w.li(ctx, cfg, &self.scratch, u64::from(pages))?;
+            w.set_label(ctx, cfg, self.probe_loop.clone())?;
+            probe_page(w, ctx, cfg, PROBE_INTERVAL)?;
+            w.addi(ctx, cfg, &self.scratch, &self.scratch, -1)?;
+            w.bcond_label(
+                ctx,
+                cfg,
+                ConditionCode::NE,
+                &self.scratch,
+                &ZERO,
+                self.probe_loop.clone(),
+            )?;
+        }
+        match size % PROBE_INTERVAL {
+            0 => Ok(()),
+            rest => probe_page(w, ctx, cfg, rest),
+        }
+    }
+}
+
+/// Moves `sp` down by `bytes` in `addi` steps.
+fn move_sp<W, Context>(
+    w: &mut W,
+    ctx: &mut Context,
+    cfg: RiscV64Arch,
+    mut bytes: u32,
+) -> Result<(), W::Error>
+where
+    W: WriterCore<Context> + ?Sized,
+{
+    while bytes != 0 {
+        let step = bytes.min(MAX_STEP);
+        w.addi(ctx, cfg, &SP, &SP, -(step as i32))?;
+        bytes -= step;
+    }
+    Ok(())
+}
+
+/// Moves `sp` down by `bytes` and stores zero at the new top of stack.
+fn probe_page<W, Context>(
+    w: &mut W,
+    ctx: &mut Context,
+    cfg: RiscV64Arch,
+    bytes: u32,
+) -> Result<(), W::Error>
+where
+    W: WriterCore<Context> + ?Sized,
+{
+    move_sp(w, ctx, cfg, bytes)?;
+    w.sd(ctx, cfg, &ZERO, &stack_top())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_small_frame_is_not_probed() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        FrameBuilder::new(".Lprobe")
+            .probe_large_frame(&mut writer, &mut (), RiscV64Arch::default(), 3072)
+            .unwrap();
+        assert_eq!(output, "addi sp, sp, -2048\naddi sp, sp, -1024\n");
+    }
+
+    #[test]
+    fn test_few_pages_are_unrolled() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        FrameBuilder::new(".Lprobe")
+            .probe_large_frame(&mut writer, &mut (), RiscV64Arch::default(), 2 * 4096 + 16)
+            .unwrap();
+        assert_eq!(output.matches("sd zero, 0(sp)\n").count(), 3);
+        assert!(output.ends_with("addi sp, sp, -16\nsd zero, 0(sp)\n"));
+    }
+
+    #[test]
+    fn test_many_pages_use_a_loop() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        FrameBuilder::new(".Lprobe")
+            .probe_large_frame(&mut writer, &mut (), RiscV64Arch::default(), 10 * 4096)
+            .unwrap();
+        assert_eq!(
+            output,
+            "li t6, 10\n.Lprobe:\naddi sp, sp, -2048\naddi sp, sp, -2048\n\
+             sd zero, 0(sp)\naddi t6, t6, -1\nbne t6, zero, .Lprobe\n"
+        );
+    }
+}
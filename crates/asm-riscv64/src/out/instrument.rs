@@ -0,0 +1,348 @@
+//! Profiling probes at function entries, basic blocks and call sites.
+//!
+//! [`InstrumentingWriter`] wraps a writer and injects a [`Probe`] at three
+//! kinds of event, each configured separately:
+//!
+//! - function entries, defined with [`InstrumentingWriter::function_entry`];
+//! - every other label passed to [`set_label`](Writer::set_label), which
+//!   starts a basic block;
+//! - calls made with [`jal_label`](Writer::jal_label) linking through `ra`.
+//!
+//! Events are numbered in emission order, shared by all three kinds. A
+//! [`Probe::Count`] probe increments the event's own 64-bit slot in a
+//! counter table, so wrapping the target writer of a translation shim gives
+//! block-level execution counts without touching the emitter:
+//!
+//! ```ignore
+//! let writer = InstrumentingWriter::new(writer).with_blocks(Probe::Count { base: Reg(27), disp: 0 });
+//! let mut shim = X64ToRiscV64Shim::new(writer);
+//! // ... translate ...
+//! let slots = shim.inner.event_count(); // the table needs this many u64s
+//! ```
+//!
+//! Probes clobber t5, which the shim also uses for its instruction counters.
+//! Calls through [`call`](WriterCore::call) or [`jalr`](WriterCore::jalr)
+//! are not call-site events. A probe lands after the label, so a label that
+//! must start with an `lpad` landing pad should not be instrumented.
+
+use core::ops::{Deref, DerefMut};
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::arg::{ArgKind, MemArg, MemArgKind};
+use crate::out::{Writer, WriterCore};
+use crate::{RegisterClass, RiscV64Arch};
+
+/// Register probes load counters into and pass event numbers in: `t5`.
+const SCRATCH: Reg = Reg(30);
+
+/// The return address register, saved around [`Probe::Call`].
+const RA: Reg = Reg(1);
+
+/// The stack pointer.
+const SP: Reg = Reg(2);
+
+/// The doubleword at `disp(base)`.
+fn doubleword(base: Reg, disp: i32) -> MemArgKind<Reg> {
+    MemArgKind::Mem {
+        base,
+        offset: None,
+        disp,
+        size: MemorySize::_64,
+        reg_class: RegisterClass::Gpr,
+    }
+}
+
+/// Returns whether `arg` is the `ra` register.
+fn is_ra(arg: &(dyn MemArg + '_)) -> bool {
+    matches!(
+        arg.concrete_mem_kind(),
+        MemArgKind::NoMem(ArgKind::Reg { reg: RA, .. })
+    )
+}
+
+/// Code injected at an instrumented event.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Probe {
+    /// Nothing is injected.
+    #[default]
+    Off,
+    /// Increments the 64-bit counter of event `n` at `disp + 8 * n(base)`.
+    ///
+    /// `base` must hold the table address wherever a probe runs, and
+    /// `disp + 8 * n` must fit the 12-bit signed offset of `ld`/`sd`.
+    Count {
+        /// Register holding the counter table address.
+        base: Reg,
+        /// Byte offset of event 0's counter from `base`.
+        disp: i32,
+    },
+    /// Calls the routine `symbol` with the event number in t5.
+    ///
+    /// The routine must preserve every register except t5. The probe saves
+    /// `ra` itself.
+    Call(&'static str),
+}
+
+/// Injects [`Probe`]s at function entries, block labels and call sites.
+pub struct InstrumentingWriter<W> {
+    writer: W,
+    function_entries: Probe,
+    blocks: Probe,
+    call_sites: Probe,
+    events: usize,
+}
+
+impl<W> InstrumentingWriter<W> {
+    /// Wraps `writer` with every probe [`Off`](Probe::Off).
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            function_entries: Probe::Off,
+            blocks: Probe::Off,
+            call_sites: Probe::Off,
+            events: 0,
+        }
+    }
+
+    /// Sets the probe run at function entries.
+    pub fn with_function_entries(self, probe: Probe) -> Self {
+        Self {
+            function_entries: probe,
+            ..self
+        }
+    }
+
+    /// Sets the probe run at basic block labels.
+    pub fn with_blocks(self, probe: Probe) -> Self {
+        Self {
+            blocks: probe,
+            ..self
+        }
+    }
+
+    /// Sets the probe run before call sites.
+    pub fn with_call_sites(self, probe: Probe) -> Self {
+        Self {
+            call_sites: probe,
+            ..self
+        }
+    }
+
+    /// Returns how many events have been numbered, which is the number of
+    /// slots a counter table needs.
+    pub fn event_count(&self) -> usize {
+        self.events
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Emits `probe` for the next event, if it is not off.
+    fn probe<Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        probe: Probe,
+    ) -> Result<(), W::Error>
+    where
+        W: WriterCore<Context>,
+    {
+        let event = self.events;
+        match probe {
+            Probe::Off => return Ok(()),
+            Probe::Count { base, disp } => {
+                let offset = disp + 8 * event as i32;
+                assert!(
+                    (-2048..2048).contains(&offset),
+                    "counter offset {offset} is out of ld/sd range"
+                );
+                let slot = doubleword(base, offset);
+                self.writer.ld(ctx, cfg, &SCRATCH, &slot)?;
+                self.writer.addi(ctx, cfg, &SCRATCH, &SCRATCH, 1)?;
+                self.writer.sd(ctx, cfg, &SCRATCH, &slot)?;
+            }
+            Probe::Call(symbol) => {
+                self.writer.addi(ctx, cfg, &SP, &SP, -16)?;
+                self.writer.sd(ctx, cfg, &RA, &doubleword(SP, 0))?;
+                self.writer.li(ctx, cfg, &SCRATCH, event as u64)?;
+                self.writer.call(ctx, cfg, &ArgKind::sym(symbol))?;
+                self.writer.ld(ctx, cfg, &RA, &doubleword(SP, 0))?;
+                self.writer.addi(ctx, cfg, &SP, &SP, 16)?;
+            }
+        }
+        self.events += 1;
+        Ok(())
+    }
+
+    /// Defines `label` as a function entry and runs the function-entry
+    /// probe.
+    pub fn function_entry<L, Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        label: L,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context>,
+    {
+        self.writer.set_label(ctx, cfg, label)?;
+        self.probe(ctx, cfg, self.function_entries)
+    }
+}
+
+impl<W> Deref for InstrumentingWriter<W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        &self.writer
+    }
+}
+
+impl<W> DerefMut for InstrumentingWriter<W> {
+    fn deref_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+crate::writer_dispatch!(@core [ W: WriterCore<Context>, Context ] InstrumentingWriter<W> => W::Error [Context] [W]);
+
+impl<W, L, Context> Writer<L, Context> for InstrumentingWriter<W>
+where
+    W: Writer<L, Context>,
+{
+    fn set_label(&mut self, ctx: &mut Context, cfg: RiscV64Arch, s: L) -> Result<(), Self::Error> {
+        self.writer.set_label(ctx, cfg, s)?;
+        self.probe(ctx, cfg, self.blocks)
+    }
+
+    fn jal_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        if is_ra(dest) {
+            self.probe(ctx, cfg, self.call_sites)?;
+        }
+        self.writer.jal_label(ctx, cfg, dest, label)
+    }
+
+    fn la_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.la_label(ctx, cfg, dest, label)
+    }
+
+    fn bcond_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        cond: crate::ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.bcond_label(ctx, cfg, cond, a, b, label)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn bcond_label_hinted(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        cond: crate::ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        label: L,
+        hint: crate::BranchHint,
+    ) -> Result<(), Self::Error> {
+        self.writer
+            .bcond_label_hinted(ctx, cfg, cond, a, b, label, hint)
+    }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.dd_label(ctx, cfg, label)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_call_sites_link_through_ra() {
+        let cfg = RiscV64Arch::default();
+        let mut output = String::new();
+        let mut writer =
+            InstrumentingWriter::new(&mut output as &mut dyn Write).with_call_sites(Probe::Count {
+                base: Reg(27),
+                disp: 0,
+            });
+        writer.jal_label(&mut (), cfg, &Reg(0), "loop").unwrap();
+        writer.jal_label(&mut (), cfg, &RA, "f").unwrap();
+        writer.jal_label(&mut (), cfg, &RA, "g").unwrap();
+        assert_eq!(writer.event_count(), 2);
+        drop(writer);
+        // Only the jumps that link through ra are call sites.
+        assert_eq!(
+            output,
+            "jal zero, loop\nld t5, 0(s11)\naddi t5, t5, 1\nsd t5, 0(s11)\njal ra, f\n\
+             ld t5, 8(s11)\naddi t5, t5, 1\nsd t5, 8(s11)\njal ra, g\n"
+        );
+    }
+
+    #[test]
+    fn test_events_are_numbered_across_kinds() {
+        let cfg = RiscV64Arch::default();
+        let mut output = String::new();
+        let count = Probe::Count {
+            base: Reg(27),
+            disp: 0,
+        };
+        let mut writer = InstrumentingWriter::new(&mut output as &mut dyn Write)
+            .with_function_entries(count)
+            .with_call_sites(count);
+        writer.function_entry(&mut (), cfg, "main").unwrap();
+        // Blocks are not instrumented, so the label takes no event number.
+        writer.set_label(&mut (), cfg, "loop").unwrap();
+        writer.jal_label(&mut (), cfg, &RA, "f").unwrap();
+        assert_eq!(writer.event_count(), 2);
+        drop(writer);
+        assert_eq!(
+            output,
+            "main:\nld t5, 0(s11)\naddi t5, t5, 1\nsd t5, 0(s11)\nloop:\n\
+             ld t5, 8(s11)\naddi t5, t5, 1\nsd t5, 8(s11)\njal ra, f\n"
+        );
+    }
+
+    #[test]
+    fn test_call_probe_saves_ra() {
+        let cfg = RiscV64Arch::default();
+        let mut output = String::new();
+        let mut writer = InstrumentingWriter::new(&mut output as &mut dyn Write)
+            .with_function_entries(Probe::Call("__profile_hook"));
+        writer.function_entry(&mut (), cfg, "main").unwrap();
+        drop(writer);
+        assert_eq!(
+            output,
+            "main:\naddi sp, sp, -16\nsd ra, 0(sp)\nli t5, 0\ncall __profile_hook\n\
+             ld ra, 0(sp)\naddi sp, sp, 16\n"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Jump tables for dense `switch` statements.
+//!
+//! A `switch` over a dense range of values is cheapest as an indexed jump:
+//! bounds-check the index once, load the target from a table and jump
+//! there. [`JumpTable`] emits both halves. Entries are 32-bit offsets from
+//! the entry to its case label (see
+//! [`dd_label`](crate::out::Writer::dd_label)), so the table needs no
+//! relocations and works in position-independent code:
+//!
+//! ```ignore
+//! let cases = [case0, case1, case2];
+//! let table = JumpTable::new(&cases, default);
+//! table.dispatch(&mut writer, ctx, cfg, &Reg(10), table_label)?;
+//! // li t6, 3; bgeu a0, t6, default
+//! // la t5, table_label; add t6, a0, a0; add t6, t6, t6; add t5, t5, t6
+//! // lw t6, 0(t5); add t5, t5, t6; jalr zero, t5, 0
+//! // ... case bodies ...
+//! table.emit_table(&mut writer, ctx, cfg, table_label)?;
+//! ```
+//!
+//! The index is an unsigned 64-bit value in a register; values at or above
+//! the number of cases go to the default label. RISC-V has no scaled
+//! addressing, so the entry address is computed with two doublings, and
+//! `lw` sign-extends the loaded offset.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::Writer;
+use crate::out::arg::{ArgKind, MemArgKind};
+use crate::{ConditionCode, RegisterClass, RiscV64Arch};
+
+/// Default scratch registers: the temporaries `t5` and `t6`.
+pub const DEFAULT_SCRATCH: [Reg; 2] = [Reg(30), Reg(31)];
+
+/// The hard-wired zero register.
+const ZERO: Reg = Reg(0);
+
+/// A jump table over the labels of consecutive cases.
+pub struct JumpTable<'a, L> {
+    cases: &'a [L],
+    default: L,
+    scratch: [Reg; 2],
+}
+
+impl<'a, L: Clone> JumpTable<'a, L> {
+    /// Creates a table that jumps to `cases[index]`, or to `default` when
+    /// the index is out of range.
+    pub fn new(cases: &'a [L], default: L) -> Self {
+        Self {
+            cases,
+            default,
+            scratch: DEFAULT_SCRATCH,
+        }
+    }
+
+    /// Sets the two registers the dispatch sequence clobbers.
+    pub fn with_scratch(self, scratch: [Reg; 2]) -> Self {
+        Self { scratch, ..self }
+    }
+
+    /// Returns the number of cases.
+    pub fn len(&self) -> usize {
+        self.cases.len()
+    }
+
+    /// Returns `true` if the table has no cases.
+    pub fn is_empty(&self) -> bool {
+        self.cases.is_empty()
+    }
+
+    /// Emits the bounds check and the indexed jump through the table at
+    /// `table`.
+    ///
+    /// `index` must not be one of the scratch registers.
+    pub fn dispatch<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        index: &Reg,
+        table: L,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let [base, entry] = self.scratch;
+        w.li(ctx, cfg, &entry, self.cases.len() as u64)?;
+        w.bcond_label(
+            ctx,
+            cfg,
+            ConditionCode::GEU,
+            index,
+            &entry,
+            self.default.clone(),
+        )?;
+        w.la_label(ctx, cfg, &base, table)?;
+        w.add(ctx, cfg, &entry, index, index)?;
+        w.add(ctx, cfg, &entry, &entry, &entry)?;
+        w.add(ctx, cfg, &base, &base, &entry)?;
+        w.lw(ctx, cfg, &entry, &table_entry(base))?;
+        w.add(ctx, cfg, &base, &base, &entry)?;
+        w.jalr(ctx, cfg, &ZERO, &base, 0)
+    }
+
+    /// Emits the table, 4-byte aligned, labelled `table`.
+    ///
+    /// The table is data: place it where execution cannot fall into it.
+    pub fn emit_table<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        table: L,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        w.align_to(ctx, cfg, 4)?;
+        w.set_label(ctx, cfg, table)?;
+        for case in self.cases {
+            w.dd_label(ctx, cfg, case.clone())?;
+        }
+        Ok(())
+    }
+}
+
+/// The 32-bit entry at `0(addr)`.
+fn table_entry(addr: Reg) -> MemArgKind<ArgKind> {
+    MemArgKind::Mem {
+        base: ArgKind::Reg {
+            reg: addr,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp: 0,
+        size: MemorySize::_32,
+        reg_class: RegisterClass::Gpr,
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_dispatch_and_table() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = RiscV64Arch::default();
+        let cases = [".Lc0", ".Lc1"];
+        let table = JumpTable::new(&cases, ".Ldef");
+        table
+            .dispatch(&mut writer, &mut (), cfg, &Reg(10), ".Ltab")
+            .unwrap();
+        table
+            .emit_table(&mut writer, &mut (), cfg, ".Ltab")
+            .unwrap();
+
+        assert!(output.starts_with("li t6, 2\nbgeu a0, t6, .Ldef\nla t5, .Ltab\n"));
+        assert!(output.contains(
+            "add t6, a0, a0\nadd t6, t6, t6\nadd t5, t5, t6\n\
+             lw t6, 0(t5)\nadd t5, t5, t6\njalr zero, t5, 0\n"
+        ));
+        assert!(output.ends_with(".Ltab:\n.4byte .Lc0 - .\n.4byte .Lc1 - .\n"));
+    }
+}
@@ -0,0 +1,234 @@
+//! Scoped label allocation with definition checks.
+//!
+//! Loops and other forward/backward branch structures need their labels
+//! allocated before the code that defines them is emitted. [`LabelScope`]
+//! hands out fresh labels for such a region and verifies, when the scope
+//! ends, that every label it allocated was defined exactly once.
+//!
+//! Use it through [`Writer::loop_scope`] or [`Writer::with_label`]:
+//!
+//! ```ignore
+//! let mut next = 0usize;
+//! writer.loop_scope(ctx, || { next += 1; next }, |scope, ctx| {
+//!     let head = scope.label();
+//!     let exit = scope.label();
+//!     scope.define(ctx, cfg, head)?;
+//!     scope.writer().bcond_label(ctx, cfg, ConditionCode::EQ, &a, &b, exit)
+//!         .map_err(LabelScopeError::Writer)?;
+//!     scope.writer().jal_label(ctx, cfg, &Reg(0), head)
+//!         .map_err(LabelScopeError::Writer)?;
+//!     scope.define(ctx, cfg, exit)
+//! })?;
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display};
+
+use crate::out::Writer;
+
+/// A label misuse detected by a [`LabelScope`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum LabelScopeError<L, E> {
+    /// The underlying writer returned an error.
+    Writer(E),
+    /// A label allocated by the scope was never defined.
+    Undefined(L),
+    /// A label allocated by the scope was defined more than once.
+    Duplicate(L),
+}
+
+impl<L: Debug, E: Display> Display for LabelScopeError<L, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LabelScopeError::Writer(e) => write!(f, "{e}"),
+            LabelScopeError::Undefined(l) => write!(f, "label {l:?} was never defined"),
+            LabelScopeError::Duplicate(l) => write!(f, "label {l:?} was defined more than once"),
+        }
+    }
+}
+
+impl<L: Debug, E: core::error::Error> core::error::Error for LabelScopeError<L, E> {}
+
+/// Allocates labels for a region of code and checks that each is defined once.
+///
+/// Labels come from the `alloc` closure; [`LabelScope::define`] records their
+/// definition. Labels not allocated by this scope are forwarded to the writer
+/// without being tracked.
+pub struct LabelScope<'w, W: ?Sized, L, F> {
+    writer: &'w mut W,
+    alloc: F,
+    /// Allocated labels and whether each has been defined.
+    labels: Vec<(L, bool)>,
+}
+
+impl<'w, W: ?Sized, L: Clone + PartialEq, F: FnMut() -> L> LabelScope<'w, W, L, F> {
+    /// Creates a scope over `writer` that allocates labels with `alloc`.
+    pub fn new(writer: &'w mut W, alloc: F) -> Self {
+        Self {
+            writer,
+            alloc,
+            labels: Vec::new(),
+        }
+    }
+
+    /// Allocates a fresh label that must be defined before the scope ends.
+    pub fn label(&mut self) -> L {
+        let label = (self.alloc)();
+        self.labels.push((label.clone(), false));
+        label
+    }
+
+    /// Returns the underlying writer for emitting instructions and branches.
+    pub fn writer(&mut self) -> &mut W {
+        &mut *self.writer
+    }
+
+    /// Defines `label` at the current position.
+    ///
+    /// Returns [`LabelScopeError::Duplicate`] if the scope already defined it.
+    pub fn define<Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::RiscV64Arch,
+        label: L,
+    ) -> Result<(), LabelScopeError<L, W::Error>>
+    where
+        W: Writer<L, Context>,
+    {
+        if let Some((_, defined)) = self.labels.iter_mut().find(|(l, _)| *l == label) {
+            if *defined {
+                return Err(LabelScopeError::Duplicate(label));
+            }
+            *defined = true;
+        }
+        self.writer
+            .set_label(ctx, cfg, label)
+            .map_err(LabelScopeError::Writer)
+    }
+
+    /// Ends the scope, failing on the first allocated label that was never defined.
+    pub fn finish<E>(self) -> Result<(), LabelScopeError<L, E>> {
+        match self.labels.into_iter().find(|(_, defined)| !defined) {
+            Some((label, _)) => Err(LabelScopeError::Undefined(label)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[cfg(feature = "rv-asm-backend")]
+    #[test]
+    fn test_loop_branches_resolve_in_both_directions() {
+        use crate::ConditionCode;
+        use crate::out::rv_asm_backend::RvAsmWriter;
+
+        let cfg = crate::RiscV64Arch::default();
+        let mut binary = RvAsmWriter::<u32>::new();
+        let mut next = 0u32;
+        let exit = Writer::loop_scope(
+            &mut binary,
+            &mut (),
+            || {
+                next += 1;
+                next
+            },
+            |scope, ctx| {
+                let head = scope.label();
+                let exit = scope.label();
+                scope.define(ctx, cfg, head)?;
+                scope
+                    .writer()
+                    .bcond_label(ctx, cfg, ConditionCode::EQ, &Reg(10), &Reg(0), exit)
+                    .map_err(LabelScopeError::Writer)?;
+                scope
+                    .writer()
+                    .jal_label(ctx, cfg, &Reg(0), head)
+                    .map_err(LabelScopeError::Writer)?;
+                scope.define(ctx, cfg, exit)?;
+                Ok(exit)
+            },
+        )
+        .unwrap();
+        let (code, labels) = binary.into_parts();
+        assert_eq!((labels[&1], labels[&exit]), (0, 8));
+        let word = |at: usize| u32::from_le_bytes(code[at..at + 4].try_into().unwrap());
+        // `beq a0, zero, exit`, 8 bytes forward.
+        assert_eq!(word(0), 0x0005_0463);
+        // `jal zero, head`, 4 bytes back.
+        assert_eq!(word(4), 0xFFDF_F06F);
+    }
+
+    #[test]
+    fn test_only_allocated_labels_are_checked() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = crate::RiscV64Arch::default();
+        let mut next = 0usize;
+        let result = Writer::loop_scope(
+            &mut writer,
+            &mut (),
+            || {
+                next += 1;
+                next
+            },
+            |scope, ctx| {
+                let first = scope.label();
+                let second = scope.label();
+                // A label from outside the scope is forwarded untracked.
+                scope.define(ctx, cfg, 10)?;
+                scope.define(ctx, cfg, 10)?;
+                scope.define(ctx, cfg, second)?;
+                Ok(first)
+            },
+        );
+        assert_eq!(result, Err(LabelScopeError::Undefined(1)));
+        assert_eq!(output, "10:\n10:\n2:\n");
+    }
+
+    #[test]
+    fn test_writer_errors_are_forwarded() {
+        struct Full;
+        impl Write for Full {
+            fn write_str(&mut self, _: &str) -> fmt::Result {
+                Err(fmt::Error)
+            }
+        }
+        let mut writer: &mut dyn Write = &mut Full;
+        let cfg = crate::RiscV64Arch::default();
+        let result = Writer::with_label(
+            &mut writer,
+            &mut (),
+            || 1usize,
+            |scope, ctx, label| scope.define(ctx, cfg, label),
+        );
+        assert_eq!(result, Err(LabelScopeError::Writer(fmt::Error)));
+    }
+
+    #[test]
+    fn test_label_scope_reports_misuse() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = crate::RiscV64Arch::default();
+
+        let undefined = Writer::with_label(&mut writer, &mut (), || 7usize, |_, _, _| Ok(()));
+        assert_eq!(undefined, Err(LabelScopeError::Undefined(7)));
+
+        let duplicate = Writer::with_label(
+            &mut writer,
+            &mut (),
+            || 8usize,
+            |scope, ctx, label| {
+                scope.define(ctx, cfg, label)?;
+                scope.define(ctx, cfg, label)
+            },
+        );
+        assert_eq!(duplicate, Err(LabelScopeError::Duplicate(8)));
+    }
+}
@@ -0,0 +1,157 @@
+//! Multi-versioned function emission.
+//!
+//! Fat binaries often carry several builds of a hot function, each tuned for
+//! a different set of extensions, plus a small dispatcher that jumps to the
+//! one selected at load time. [`emit_multiversioned`] replays the same body
+//! once per [`Version`] under a suffixed label and emits that dispatcher:
+//!
+//! ```ignore
+//! let versions = [
+//!     Version { cfg: zbb_cfg, suffix: "zbb" },
+//!     Version { cfg: RiscV64Arch::rv64gc(), suffix: "base" },
+//! ];
+//! let dispatch = Dispatcher { cfg: RiscV64Arch::rv64gc(), scratch: &Reg(5), selector: &slot };
+//! emit_multiversioned(
+//!     &mut writer, ctx, dispatch, &versions,
+//!     |suffix| match suffix { Some(s) => format!("memcpy.{s}"), None => "memcpy".into() },
+//!     |w, ctx, cfg| emit_memcpy(w, ctx, cfg),
+//! )?;
+//! ```
+
+use crate::out::Writer;
+use crate::out::arg::MemArg;
+use crate::{ConditionCode, RiscV64Arch};
+use portal_pc_asm_common::types::reg::Reg;
+
+/// One build of a multi-versioned function.
+#[derive(Clone, Copy, Debug)]
+pub struct Version<'a> {
+    /// Configuration the body is emitted for.
+    pub cfg: RiscV64Arch,
+    /// Suffix distinguishing this build's symbol, e.g. `"zbb"`.
+    pub suffix: &'a str,
+}
+
+/// How the dispatcher selects a version at run time.
+///
+/// The dispatcher loads the index of the selected version from `selector` (a
+/// slot filled by a loader or CPU-feature probe) into `scratch` and branches
+/// to that version; an out-of-range index selects the last one.
+#[derive(Clone, Copy)]
+pub struct Dispatcher<'a> {
+    /// Configuration the dispatcher is emitted for; it must run on every target.
+    pub cfg: RiscV64Arch,
+    /// Register clobbered by the dispatcher.
+    pub scratch: &'a (dyn MemArg + 'a),
+    /// Memory operand holding the selected version's index.
+    pub selector: &'a (dyn MemArg + 'a),
+}
+
+/// Emits a dispatcher followed by `body` once per version.
+///
+/// `label(None)` names the dispatcher and `label(Some(suffix))` each version;
+/// the closure must return the same label for the same argument.
+///
+/// # Panics
+///
+/// Panics if `versions` is empty.
+pub fn emit_multiversioned<W, L, Context>(
+    w: &mut W,
+    ctx: &mut Context,
+    dispatch: Dispatcher<'_>,
+    versions: &[Version<'_>],
+    mut label: impl FnMut(Option<&str>) -> L,
+    mut body: impl FnMut(&mut W, &mut Context, RiscV64Arch) -> Result<(), W::Error>,
+) -> Result<(), W::Error>
+where
+    W: Writer<L, Context> + ?Sized,
+{
+    let (last, rest) = versions
+        .split_last()
+        .expect("multi-versioned function needs at least one version");
+
+    let Dispatcher {
+        cfg: base,
+        scratch,
+        selector,
+    } = dispatch;
+    w.set_label(ctx, base, label(None))?;
+    w.ld(ctx, base, scratch, selector)?;
+    // Count the index down to zero, branching when it reaches each version.
+    for version in rest {
+        w.bcond_label(
+            ctx,
+            base,
+            ConditionCode::EQ,
+            scratch,
+            &Reg(0),
+            label(Some(version.suffix)),
+        )?;
+        w.addi(ctx, base, scratch, scratch, -1)?;
+    }
+    w.jal_label(ctx, base, &Reg(0), label(Some(last.suffix)))?;
+
+    for version in versions {
+        w.set_label(ctx, version.cfg, label(Some(version.suffix)))?;
+        body(w, ctx, version.cfg)?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::out::WriterCore;
+    use alloc::format;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_versions_and_dispatcher() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let base = RiscV64Arch::rv64gc();
+        let mut zbb = base;
+        zbb.zbb = true;
+        let versions = [
+            Version {
+                cfg: zbb,
+                suffix: "zbb",
+            },
+            Version {
+                cfg: base,
+                suffix: "base",
+            },
+        ];
+        let selector = crate::out::arg::MemArgKind::tp_relative(
+            0,
+            portal_pc_asm_common::types::mem::MemorySize::_64,
+        );
+        let mut bodies = 0;
+        let dispatch = Dispatcher {
+            cfg: base,
+            scratch: &Reg(5),
+            selector: &selector,
+        };
+        emit_multiversioned(
+            &mut writer,
+            &mut (),
+            dispatch,
+            &versions,
+            |suffix| match suffix {
+                Some(s) => format!("f.{s}"),
+                None => String::from("f"),
+            },
+            |w, ctx, cfg| {
+                bodies += 1;
+                w.ret(ctx, cfg)
+            },
+        )
+        .unwrap();
+        assert_eq!(bodies, 2);
+        assert!(output.starts_with("f:\n"));
+        assert!(output.contains("f.zbb:\n"));
+        assert!(output.contains("f.base:\n"));
+        assert_eq!(output.matches("ret").count(), 2);
+    }
+}
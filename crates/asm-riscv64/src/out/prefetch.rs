@@ -0,0 +1,139 @@
+//! Software prefetches.
+//!
+//! [`Prefetch::prefetch`] asks for the cache block holding an address to be
+//! brought in before it is used, picking the Zicbop instruction from
+//! whether the data will be read or written:
+//!
+//! ```ignore
+//! writer.prefetch(ctx, cfg, &at_a0, Locality::High, PrefetchAccess::Write)?;
+//! // prefetch.w 0(a0)
+//! ```
+//!
+//! Zicbop has no locality hints, so the locality only matters to the x86-64
+//! and AArch64 crates, which provide the same helper with
+//! `prefetcht0`-`prefetchnta` and `prfm`. Without
+//! [`RiscV64Arch::zicbop`] nothing is emitted; a prefetch is only a hint, so
+//! leaving it out does not change what the code does.
+
+use crate::RiscV64Arch;
+use crate::out::WriterCore;
+use crate::out::arg::MemArg;
+
+/// How much prefetched data will be reused, from least to most.
+///
+/// The levels match the `locality` argument of GCC's `__builtin_prefetch`,
+/// 0 to 3.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Locality {
+    /// Used once, so it should not displace other data from the caches.
+    NonTemporal,
+    /// Reused a little; only the outermost cache needs to keep it.
+    Low,
+    /// Reused moderately; the second-level cache and above keep it.
+    Moderate,
+    /// Reused heavily; every cache level keeps it.
+    #[default]
+    High,
+}
+
+/// Whether prefetched data will be read or written.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub enum PrefetchAccess {
+    /// The data will be loaded.
+    #[default]
+    Read,
+    /// The data will be stored to, so the line is best fetched for ownership.
+    Write,
+}
+
+/// Emits software prefetches by locality.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Prefetch<Context>: WriterCore<Context> {
+    /// Hints that the cache block holding `mem` will soon be accessed as
+    /// `access`, with reuse `locality`.
+    ///
+    /// The offset of `mem` must be a multiple of 32. Emits nothing without
+    /// Zicbop.
+    fn prefetch(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+        _locality: Locality,
+        access: PrefetchAccess,
+    ) -> Result<(), Self::Error> {
+        if !cfg.zicbop {
+            return Ok(());
+        }
+        match access {
+            PrefetchAccess::Read => self.prefetch_r(ctx, cfg, mem),
+            PrefetchAccess::Write => self.prefetch_w(ctx, cfg, mem),
+        }
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Prefetch<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::RegisterClass;
+    use crate::out::arg::{ArgKind, MemArgKind};
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+    fn at_a0(disp: i32) -> MemArgKind {
+        MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(10),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp,
+            size: MemorySize::_64,
+            reg_class: RegisterClass::Gpr,
+        }
+    }
+
+    #[test]
+    fn test_access_picks_the_instruction() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        let cfg = RiscV64Arch::default().with_zicbop(true);
+        w.prefetch(
+            &mut (),
+            cfg,
+            &at_a0(0),
+            Locality::High,
+            PrefetchAccess::Read,
+        )
+        .unwrap();
+        w.prefetch(
+            &mut (),
+            cfg,
+            &at_a0(64),
+            Locality::NonTemporal,
+            PrefetchAccess::Write,
+        )
+        .unwrap();
+        assert_eq!(output, "prefetch.r 0(a0)\nprefetch.w 64(a0)\n");
+    }
+
+    #[test]
+    fn test_nothing_without_zicbop() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        w.prefetch(
+            &mut (),
+            RiscV64Arch::default(),
+            &at_a0(0),
+            Locality::High,
+            PrefetchAccess::Read,
+        )
+        .unwrap();
+        assert_eq!(output, "");
+    }
+}
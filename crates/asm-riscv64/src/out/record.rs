@@ -0,0 +1,3081 @@
+//! Recorded instruction streams for replay.
+//!
+//! [`RecordingWriter`] emits nothing: it captures every call made to it as
+//! an owned [`Insn`], with operands converted to [`MemArgKind`]s. The list
+//! can then be replayed into any other writer, as many times as needed and
+//! after editing or reordering it, which suits multi-pass layout (a sizing
+//! pass, then emission) and caching generated sequences:
+//!
+//! ```ignore
+//! let mut rec = RecordingWriter::new();
+//! generate(&mut rec, &mut (), cfg)?;
+//! rec.replay(&mut sizer, &mut ctx, cfg)?;
+//! rec.replay(&mut binary, &mut ctx, cfg)?;
+//! ```
+//!
+//! The configuration and context passed while recording are dropped, and
+//! each replay supplies its own. Labels are cloned on every replay.
+//! Recording never fails, and the writer reports no offset.
+
+use alloc::vec::Vec;
+
+use crate::out::arg::{MemArg, MemArgKind};
+use crate::out::error::EmitError;
+use crate::out::{Writer, WriterCore};
+use crate::{BranchHint, ConditionCode, Csr, FenceSet, RiscV64Arch};
+
+/// A recorded writer call.
+///
+/// Each variant names the [`WriterCore`] or [`Writer`] method it replays
+/// and holds that method's arguments after the configuration.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Insn<L> {
+    /// [`WriterCore::ebreak`].
+    Ebreak,
+    /// [`WriterCore::unimp`].
+    Unimp,
+    /// [`WriterCore::unreachable`].
+    Unreachable,
+    /// [`WriterCore::debug_break`].
+    DebugBreak,
+    /// [`WriterCore::ecall`].
+    Ecall,
+    /// [`WriterCore::lpad`].
+    Lpad { label: u32 },
+    /// [`WriterCore::sspush`].
+    Sspush { src: MemArgKind },
+    /// [`WriterCore::sspopchk`].
+    Sspopchk { src: MemArgKind },
+    /// [`WriterCore::fence`].
+    Fence { pred: FenceSet, succ: FenceSet },
+    /// [`WriterCore::fence_i`].
+    FenceI,
+    /// [`WriterCore::pause`].
+    Pause,
+    /// [`WriterCore::prefetch_r`].
+    PrefetchR { mem: MemArgKind },
+    /// [`WriterCore::prefetch_w`].
+    PrefetchW { mem: MemArgKind },
+    /// [`WriterCore::cbo_clean`].
+    CboClean { mem: MemArgKind },
+    /// [`WriterCore::cbo_flush`].
+    CboFlush { mem: MemArgKind },
+    /// [`WriterCore::cbo_inval`].
+    CboInval { mem: MemArgKind },
+    /// [`WriterCore::amoswap_w`].
+    AmoswapW {
+        dest: MemArgKind,
+        src: MemArgKind,
+        addr: MemArgKind,
+    },
+    /// [`WriterCore::amoswap_d`].
+    AmoswapD {
+        dest: MemArgKind,
+        src: MemArgKind,
+        addr: MemArgKind,
+    },
+    /// [`WriterCore::mv`].
+    Mv { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::sub`].
+    Sub {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::add`].
+    Add {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::addi`].
+    Addi {
+        dest: MemArgKind,
+        src: MemArgKind,
+        imm: i32,
+    },
+    /// [`WriterCore::add_imm`].
+    AddImm {
+        dest: MemArgKind,
+        a: MemArgKind,
+        imm: i64,
+    },
+    /// [`WriterCore::sub_imm`].
+    SubImm {
+        dest: MemArgKind,
+        a: MemArgKind,
+        imm: i64,
+    },
+    /// [`WriterCore::sd`].
+    Sd { src: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::ld`].
+    Ld { dest: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::lw`].
+    Lw { dest: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::lwu`].
+    Lwu { dest: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::sw`].
+    Sw { src: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::lb`].
+    Lb { dest: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::lbu`].
+    Lbu { dest: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::sb`].
+    Sb { src: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::lh`].
+    Lh { dest: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::lhu`].
+    Lhu { dest: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::sh`].
+    Sh { src: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::jalr`].
+    Jalr {
+        dest: MemArgKind,
+        base: MemArgKind,
+        offset: i32,
+    },
+    /// [`WriterCore::jal`].
+    Jal {
+        dest: MemArgKind,
+        target: MemArgKind,
+    },
+    /// [`WriterCore::beq`].
+    Beq {
+        a: MemArgKind,
+        b: MemArgKind,
+        target: MemArgKind,
+    },
+    /// [`WriterCore::bne`].
+    Bne {
+        a: MemArgKind,
+        b: MemArgKind,
+        target: MemArgKind,
+    },
+    /// [`WriterCore::blt`].
+    Blt {
+        a: MemArgKind,
+        b: MemArgKind,
+        target: MemArgKind,
+    },
+    /// [`WriterCore::bge`].
+    Bge {
+        a: MemArgKind,
+        b: MemArgKind,
+        target: MemArgKind,
+    },
+    /// [`WriterCore::bltu`].
+    Bltu {
+        a: MemArgKind,
+        b: MemArgKind,
+        target: MemArgKind,
+    },
+    /// [`WriterCore::bgeu`].
+    Bgeu {
+        a: MemArgKind,
+        b: MemArgKind,
+        target: MemArgKind,
+    },
+    /// [`WriterCore::bcond`].
+    Bcond {
+        cond: ConditionCode,
+        a: MemArgKind,
+        b: MemArgKind,
+        target: MemArgKind,
+    },
+    /// [`WriterCore::bcond_zero`].
+    BcondZero {
+        cond: ConditionCode,
+        a: MemArgKind,
+        target: MemArgKind,
+    },
+    /// [`WriterCore::and`].
+    And {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::andi`].
+    Andi {
+        dest: MemArgKind,
+        src: MemArgKind,
+        imm: i32,
+    },
+    /// [`WriterCore::and_imm`].
+    AndImm {
+        dest: MemArgKind,
+        a: MemArgKind,
+        imm: i64,
+    },
+    /// [`WriterCore::or`].
+    Or {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::ori`].
+    Ori {
+        dest: MemArgKind,
+        src: MemArgKind,
+        imm: i32,
+    },
+    /// [`WriterCore::or_imm`].
+    OrImm {
+        dest: MemArgKind,
+        a: MemArgKind,
+        imm: i64,
+    },
+    /// [`WriterCore::xor`].
+    Xor {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::xori`].
+    Xori {
+        dest: MemArgKind,
+        src: MemArgKind,
+        imm: i32,
+    },
+    /// [`WriterCore::xor_imm`].
+    XorImm {
+        dest: MemArgKind,
+        a: MemArgKind,
+        imm: i64,
+    },
+    /// [`WriterCore::sll`].
+    Sll {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::srl`].
+    Srl {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::sra`].
+    Sra {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::rol`].
+    Rol {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::ror`].
+    Ror {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::cpop`].
+    Cpop { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::clz`].
+    Clz { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::ctz`].
+    Ctz { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::rev8`].
+    Rev8 { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::sext_b`].
+    SextB { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::sext_h`].
+    SextH { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::sext_w`].
+    SextW { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::slt`].
+    Slt {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::sltu`].
+    Sltu {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::lui`].
+    Lui { dest: MemArgKind, imm: u32 },
+    /// [`WriterCore::auipc`].
+    Auipc { dest: MemArgKind, imm: u32 },
+    /// [`WriterCore::la`].
+    La { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::li`].
+    Li { dest: MemArgKind, val: u64 },
+    /// [`WriterCore::ret`].
+    Ret,
+    /// [`WriterCore::call`].
+    Call { target: MemArgKind },
+    /// [`WriterCore::j`].
+    J { target: MemArgKind },
+    /// [`WriterCore::mul`].
+    Mul {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::mulh`].
+    Mulh {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::mulhu`].
+    Mulhu {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::div`].
+    Div {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::divu`].
+    Divu {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::rem`].
+    Rem {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::remu`].
+    Remu {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fld`].
+    Fld { dest: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::fsd`].
+    Fsd { src: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::fadd_d`].
+    FaddD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fsub_d`].
+    FsubD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fmul_d`].
+    FmulD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fdiv_d`].
+    FdivD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fmadd_d`].
+    FmaddD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fmadd_s`].
+    FmaddS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fmsub_d`].
+    FmsubD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fmsub_s`].
+    FmsubS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fnmsub_d`].
+    FnmsubD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fnmsub_s`].
+    FnmsubS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fnmadd_d`].
+    FnmaddD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fnmadd_s`].
+    FnmaddS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+        c: MemArgKind,
+    },
+    /// [`WriterCore::fmov_d`].
+    FmovD { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_d_l`].
+    FcvtDL { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_l_d`].
+    FcvtLD { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::feq_d`].
+    FeqD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::flt_d`].
+    FltD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fle_d`].
+    FleD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fmin_d`].
+    FminD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fmax_d`].
+    FmaxD {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fsqrt_d`].
+    FsqrtD { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fabs_d`].
+    FabsD { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fneg_d`].
+    FnegD { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_d_w`].
+    FcvtDW { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_d_wu`].
+    FcvtDWu { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_d_lu`].
+    FcvtDLu { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_w_d`].
+    FcvtWD { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_wu_d`].
+    FcvtWuD { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_lu_d`].
+    FcvtLuD { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::flw`].
+    Flw { dest: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::fsw`].
+    Fsw { src: MemArgKind, mem: MemArgKind },
+    /// [`WriterCore::fadd_s`].
+    FaddS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fsub_s`].
+    FsubS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fmul_s`].
+    FmulS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fdiv_s`].
+    FdivS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fmov_s`].
+    FmovS { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_s_l`].
+    FcvtSL { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_l_s`].
+    FcvtLS { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::feq_s`].
+    FeqS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::flt_s`].
+    FltS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fle_s`].
+    FleS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fmin_s`].
+    FminS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fmax_s`].
+    FmaxS {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::fsqrt_s`].
+    FsqrtS { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fabs_s`].
+    FabsS { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fneg_s`].
+    FnegS { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_s_w`].
+    FcvtSW { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_s_wu`].
+    FcvtSWu { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_s_lu`].
+    FcvtSLu { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_w_s`].
+    FcvtWS { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_wu_s`].
+    FcvtWuS { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_lu_s`].
+    FcvtLuS { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_s_d`].
+    FcvtSD { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcvt_d_s`].
+    FcvtDS { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fsrm`].
+    Fsrm { src: MemArgKind },
+    /// [`WriterCore::frrm`].
+    Frrm { dest: MemArgKind },
+    /// [`WriterCore::csrrw`].
+    Csrrw {
+        dest: MemArgKind,
+        csr: Csr,
+        src: MemArgKind,
+    },
+    /// [`WriterCore::csrrs`].
+    Csrrs {
+        dest: MemArgKind,
+        csr: Csr,
+        src: MemArgKind,
+    },
+    /// [`WriterCore::csrrc`].
+    Csrrc {
+        dest: MemArgKind,
+        csr: Csr,
+        src: MemArgKind,
+    },
+    /// [`WriterCore::csrrwi`].
+    Csrrwi { dest: MemArgKind, csr: Csr, imm: u8 },
+    /// [`WriterCore::csrrsi`].
+    Csrrsi { dest: MemArgKind, csr: Csr, imm: u8 },
+    /// [`WriterCore::csrrci`].
+    Csrrci { dest: MemArgKind, csr: Csr, imm: u8 },
+    /// [`WriterCore::rdcycle`].
+    Rdcycle { dest: MemArgKind },
+    /// [`WriterCore::rdtime`].
+    Rdtime { dest: MemArgKind },
+    /// [`WriterCore::db`].
+    Db { bytes: Vec<u8> },
+    /// [`WriterCore::align_to`].
+    AlignTo { alignment: usize },
+    /// [`WriterCore::nop`].
+    Nop,
+    /// [`WriterCore::nops`].
+    Nops { n_bytes: usize },
+    /// [`WriterCore::align`].
+    Align { pow2: u32 },
+    /// [`Writer::set_label`].
+    SetLabel { label: L },
+    /// [`Writer::jal_label`].
+    JalLabel { dest: MemArgKind, label: L },
+    /// [`Writer::la_label`].
+    LaLabel { dest: MemArgKind, label: L },
+    /// [`Writer::bcond_label`].
+    BcondLabel {
+        cond: ConditionCode,
+        a: MemArgKind,
+        b: MemArgKind,
+        label: L,
+    },
+    /// [`Writer::bcond_label_hinted`].
+    BcondLabelHinted {
+        cond: ConditionCode,
+        a: MemArgKind,
+        b: MemArgKind,
+        label: L,
+        hint: BranchHint,
+    },
+    /// [`Writer::dd_label`].
+    DdLabel { label: L },
+}
+
+impl<L: Clone> Insn<L> {
+    /// Makes the recorded call on `w`.
+    pub fn replay<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        match self {
+            Insn::Ebreak => w.ebreak(ctx, cfg),
+            Insn::Unimp => w.unimp(ctx, cfg),
+            Insn::Unreachable => w.unreachable(ctx, cfg),
+            Insn::DebugBreak => w.debug_break(ctx, cfg),
+            Insn::Ecall => w.ecall(ctx, cfg),
+            Insn::Lpad { label } => w.lpad(ctx, cfg, *label),
+            Insn::Sspush { src } => w.sspush(ctx, cfg, src),
+            Insn::Sspopchk { src } => w.sspopchk(ctx, cfg, src),
+            Insn::Fence { pred, succ } => w.fence(ctx, cfg, *pred, *succ),
+            Insn::FenceI => w.fence_i(ctx, cfg),
+            Insn::Pause => w.pause(ctx, cfg),
+            Insn::PrefetchR { mem } => w.prefetch_r(ctx, cfg, mem),
+            Insn::PrefetchW { mem } => w.prefetch_w(ctx, cfg, mem),
+            Insn::CboClean { mem } => w.cbo_clean(ctx, cfg, mem),
+            Insn::CboFlush { mem } => w.cbo_flush(ctx, cfg, mem),
+            Insn::CboInval { mem } => w.cbo_inval(ctx, cfg, mem),
+            Insn::AmoswapW { dest, src, addr } => w.amoswap_w(ctx, cfg, dest, src, addr),
+            Insn::AmoswapD { dest, src, addr } => w.amoswap_d(ctx, cfg, dest, src, addr),
+            Insn::Mv { dest, src } => w.mv(ctx, cfg, dest, src),
+            Insn::Sub { dest, a, b } => w.sub(ctx, cfg, dest, a, b),
+            Insn::Add { dest, a, b } => w.add(ctx, cfg, dest, a, b),
+            Insn::Addi { dest, src, imm } => w.addi(ctx, cfg, dest, src, *imm),
+            Insn::AddImm { dest, a, imm } => w.add_imm(ctx, cfg, dest, a, *imm),
+            Insn::SubImm { dest, a, imm } => w.sub_imm(ctx, cfg, dest, a, *imm),
+            Insn::Sd { src, mem } => w.sd(ctx, cfg, src, mem),
+            Insn::Ld { dest, mem } => w.ld(ctx, cfg, dest, mem),
+            Insn::Lw { dest, mem } => w.lw(ctx, cfg, dest, mem),
+            Insn::Lwu { dest, mem } => w.lwu(ctx, cfg, dest, mem),
+            Insn::Sw { src, mem } => w.sw(ctx, cfg, src, mem),
+            Insn::Lb { dest, mem } => w.lb(ctx, cfg, dest, mem),
+            Insn::Lbu { dest, mem } => w.lbu(ctx, cfg, dest, mem),
+            Insn::Sb { src, mem } => w.sb(ctx, cfg, src, mem),
+            Insn::Lh { dest, mem } => w.lh(ctx, cfg, dest, mem),
+            Insn::Lhu { dest, mem } => w.lhu(ctx, cfg, dest, mem),
+            Insn::Sh { src, mem } => w.sh(ctx, cfg, src, mem),
+            Insn::Jalr { dest, base, offset } => w.jalr(ctx, cfg, dest, base, *offset),
+            Insn::Jal { dest, target } => w.jal(ctx, cfg, dest, target),
+            Insn::Beq { a, b, target } => w.beq(ctx, cfg, a, b, target),
+            Insn::Bne { a, b, target } => w.bne(ctx, cfg, a, b, target),
+            Insn::Blt { a, b, target } => w.blt(ctx, cfg, a, b, target),
+            Insn::Bge { a, b, target } => w.bge(ctx, cfg, a, b, target),
+            Insn::Bltu { a, b, target } => w.bltu(ctx, cfg, a, b, target),
+            Insn::Bgeu { a, b, target } => w.bgeu(ctx, cfg, a, b, target),
+            Insn::Bcond { cond, a, b, target } => w.bcond(ctx, cfg, *cond, a, b, target),
+            Insn::BcondZero { cond, a, target } => w.bcond_zero(ctx, cfg, *cond, a, target),
+            Insn::And { dest, a, b } => w.and(ctx, cfg, dest, a, b),
+            Insn::Andi { dest, src, imm } => w.andi(ctx, cfg, dest, src, *imm),
+            Insn::AndImm { dest, a, imm } => w.and_imm(ctx, cfg, dest, a, *imm),
+            Insn::Or { dest, a, b } => w.or(ctx, cfg, dest, a, b),
+            Insn::Ori { dest, src, imm } => w.ori(ctx, cfg, dest, src, *imm),
+            Insn::OrImm { dest, a, imm } => w.or_imm(ctx, cfg, dest, a, *imm),
+            Insn::Xor { dest, a, b } => w.xor(ctx, cfg, dest, a, b),
+            Insn::Xori { dest, src, imm } => w.xori(ctx, cfg, dest, src, *imm),
+            Insn::XorImm { dest, a, imm } => w.xor_imm(ctx, cfg, dest, a, *imm),
+            Insn::Sll { dest, a, b } => w.sll(ctx, cfg, dest, a, b),
+            Insn::Srl { dest, a, b } => w.srl(ctx, cfg, dest, a, b),
+            Insn::Sra { dest, a, b } => w.sra(ctx, cfg, dest, a, b),
+            Insn::Rol { dest, a, b } => w.rol(ctx, cfg, dest, a, b),
+            Insn::Ror { dest, a, b } => w.ror(ctx, cfg, dest, a, b),
+            Insn::Cpop { dest, src } => w.cpop(ctx, cfg, dest, src),
+            Insn::Clz { dest, src } => w.clz(ctx, cfg, dest, src),
+            Insn::Ctz { dest, src } => w.ctz(ctx, cfg, dest, src),
+            Insn::Rev8 { dest, src } => w.rev8(ctx, cfg, dest, src),
+            Insn::SextB { dest, src } => w.sext_b(ctx, cfg, dest, src),
+            Insn::SextH { dest, src } => w.sext_h(ctx, cfg, dest, src),
+            Insn::SextW { dest, src } => w.sext_w(ctx, cfg, dest, src),
+            Insn::Slt { dest, a, b } => w.slt(ctx, cfg, dest, a, b),
+            Insn::Sltu { dest, a, b } => w.sltu(ctx, cfg, dest, a, b),
+            Insn::Lui { dest, imm } => w.lui(ctx, cfg, dest, *imm),
+            Insn::Auipc { dest, imm } => w.auipc(ctx, cfg, dest, *imm),
+            Insn::La { dest, src } => w.la(ctx, cfg, dest, src),
+            Insn::Li { dest, val } => w.li(ctx, cfg, dest, *val),
+            Insn::Ret => w.ret(ctx, cfg),
+            Insn::Call { target } => w.call(ctx, cfg, target),
+            Insn::J { target } => w.j(ctx, cfg, target),
+            Insn::Mul { dest, a, b } => w.mul(ctx, cfg, dest, a, b),
+            Insn::Mulh { dest, a, b } => w.mulh(ctx, cfg, dest, a, b),
+            Insn::Mulhu { dest, a, b } => w.mulhu(ctx, cfg, dest, a, b),
+            Insn::Div { dest, a, b } => w.div(ctx, cfg, dest, a, b),
+            Insn::Divu { dest, a, b } => w.divu(ctx, cfg, dest, a, b),
+            Insn::Rem { dest, a, b } => w.rem(ctx, cfg, dest, a, b),
+            Insn::Remu { dest, a, b } => w.remu(ctx, cfg, dest, a, b),
+            Insn::Fld { dest, mem } => w.fld(ctx, cfg, dest, mem),
+            Insn::Fsd { src, mem } => w.fsd(ctx, cfg, src, mem),
+            Insn::FaddD { dest, a, b } => w.fadd_d(ctx, cfg, dest, a, b),
+            Insn::FsubD { dest, a, b } => w.fsub_d(ctx, cfg, dest, a, b),
+            Insn::FmulD { dest, a, b } => w.fmul_d(ctx, cfg, dest, a, b),
+            Insn::FdivD { dest, a, b } => w.fdiv_d(ctx, cfg, dest, a, b),
+            Insn::FmaddD { dest, a, b, c } => w.fmadd_d(ctx, cfg, dest, a, b, c),
+            Insn::FmaddS { dest, a, b, c } => w.fmadd_s(ctx, cfg, dest, a, b, c),
+            Insn::FmsubD { dest, a, b, c } => w.fmsub_d(ctx, cfg, dest, a, b, c),
+            Insn::FmsubS { dest, a, b, c } => w.fmsub_s(ctx, cfg, dest, a, b, c),
+            Insn::FnmsubD { dest, a, b, c } => w.fnmsub_d(ctx, cfg, dest, a, b, c),
+            Insn::FnmsubS { dest, a, b, c } => w.fnmsub_s(ctx, cfg, dest, a, b, c),
+            Insn::FnmaddD { dest, a, b, c } => w.fnmadd_d(ctx, cfg, dest, a, b, c),
+            Insn::FnmaddS { dest, a, b, c } => w.fnmadd_s(ctx, cfg, dest, a, b, c),
+            Insn::FmovD { dest, src } => w.fmov_d(ctx, cfg, dest, src),
+            Insn::FcvtDL { dest, src } => w.fcvt_d_l(ctx, cfg, dest, src),
+            Insn::FcvtLD { dest, src } => w.fcvt_l_d(ctx, cfg, dest, src),
+            Insn::FeqD { dest, a, b } => w.feq_d(ctx, cfg, dest, a, b),
+            Insn::FltD { dest, a, b } => w.flt_d(ctx, cfg, dest, a, b),
+            Insn::FleD { dest, a, b } => w.fle_d(ctx, cfg, dest, a, b),
+            Insn::FminD { dest, a, b } => w.fmin_d(ctx, cfg, dest, a, b),
+            Insn::FmaxD { dest, a, b } => w.fmax_d(ctx, cfg, dest, a, b),
+            Insn::FsqrtD { dest, src } => w.fsqrt_d(ctx, cfg, dest, src),
+            Insn::FabsD { dest, src } => w.fabs_d(ctx, cfg, dest, src),
+            Insn::FnegD { dest, src } => w.fneg_d(ctx, cfg, dest, src),
+            Insn::FcvtDW { dest, src } => w.fcvt_d_w(ctx, cfg, dest, src),
+            Insn::FcvtDWu { dest, src } => w.fcvt_d_wu(ctx, cfg, dest, src),
+            Insn::FcvtDLu { dest, src } => w.fcvt_d_lu(ctx, cfg, dest, src),
+            Insn::FcvtWD { dest, src } => w.fcvt_w_d(ctx, cfg, dest, src),
+            Insn::FcvtWuD { dest, src } => w.fcvt_wu_d(ctx, cfg, dest, src),
+            Insn::FcvtLuD { dest, src } => w.fcvt_lu_d(ctx, cfg, dest, src),
+            Insn::Flw { dest, mem } => w.flw(ctx, cfg, dest, mem),
+            Insn::Fsw { src, mem } => w.fsw(ctx, cfg, src, mem),
+            Insn::FaddS { dest, a, b } => w.fadd_s(ctx, cfg, dest, a, b),
+            Insn::FsubS { dest, a, b } => w.fsub_s(ctx, cfg, dest, a, b),
+            Insn::FmulS { dest, a, b } => w.fmul_s(ctx, cfg, dest, a, b),
+            Insn::FdivS { dest, a, b } => w.fdiv_s(ctx, cfg, dest, a, b),
+            Insn::FmovS { dest, src } => w.fmov_s(ctx, cfg, dest, src),
+            Insn::FcvtSL { dest, src } => w.fcvt_s_l(ctx, cfg, dest, src),
+            Insn::FcvtLS { dest, src } => w.fcvt_l_s(ctx, cfg, dest, src),
+            Insn::FeqS { dest, a, b } => w.feq_s(ctx, cfg, dest, a, b),
+            Insn::FltS { dest, a, b } => w.flt_s(ctx, cfg, dest, a, b),
+            Insn::FleS { dest, a, b } => w.fle_s(ctx, cfg, dest, a, b),
+            Insn::FminS { dest, a, b } => w.fmin_s(ctx, cfg, dest, a, b),
+            Insn::FmaxS { dest, a, b } => w.fmax_s(ctx, cfg, dest, a, b),
+            Insn::FsqrtS { dest, src } => w.fsqrt_s(ctx, cfg, dest, src),
+            Insn::FabsS { dest, src } => w.fabs_s(ctx, cfg, dest, src),
+            Insn::FnegS { dest, src } => w.fneg_s(ctx, cfg, dest, src),
+            Insn::FcvtSW { dest, src } => w.fcvt_s_w(ctx, cfg, dest, src),
+            Insn::FcvtSWu { dest, src } => w.fcvt_s_wu(ctx, cfg, dest, src),
+            Insn::FcvtSLu { dest, src } => w.fcvt_s_lu(ctx, cfg, dest, src),
+            Insn::FcvtWS { dest, src } => w.fcvt_w_s(ctx, cfg, dest, src),
+            Insn::FcvtWuS { dest, src } => w.fcvt_wu_s(ctx, cfg, dest, src),
+            Insn::FcvtLuS { dest, src } => w.fcvt_lu_s(ctx, cfg, dest, src),
+            Insn::FcvtSD { dest, src } => w.fcvt_s_d(ctx, cfg, dest, src),
+            Insn::FcvtDS { dest, src } => w.fcvt_d_s(ctx, cfg, dest, src),
+            Insn::Fsrm { src } => w.fsrm(ctx, cfg, src),
+            Insn::Frrm { dest } => w.frrm(ctx, cfg, dest),
+            Insn::Csrrw { dest, csr, src } => w.csrrw(ctx, cfg, dest, *csr, src),
+            Insn::Csrrs { dest, csr, src } => w.csrrs(ctx, cfg, dest, *csr, src),
+            Insn::Csrrc { dest, csr, src } => w.csrrc(ctx, cfg, dest, *csr, src),
+            Insn::Csrrwi { dest, csr, imm } => w.csrrwi(ctx, cfg, dest, *csr, *imm),
+            Insn::Csrrsi { dest, csr, imm } => w.csrrsi(ctx, cfg, dest, *csr, *imm),
+            Insn::Csrrci { dest, csr, imm } => w.csrrci(ctx, cfg, dest, *csr, *imm),
+            Insn::Rdcycle { dest } => w.rdcycle(ctx, cfg, dest),
+            Insn::Rdtime { dest } => w.rdtime(ctx, cfg, dest),
+            Insn::Db { bytes } => w.db(ctx, cfg, bytes),
+            Insn::AlignTo { alignment } => w.align_to(ctx, cfg, *alignment),
+            Insn::Nop => w.nop(ctx, cfg),
+            Insn::Nops { n_bytes } => w.nops(ctx, cfg, *n_bytes),
+            Insn::Align { pow2 } => w.align(ctx, cfg, *pow2),
+            Insn::SetLabel { label } => w.set_label(ctx, cfg, label.clone()),
+            Insn::JalLabel { dest, label } => w.jal_label(ctx, cfg, dest, label.clone()),
+            Insn::LaLabel { dest, label } => w.la_label(ctx, cfg, dest, label.clone()),
+            Insn::BcondLabel { cond, a, b, label } => {
+                w.bcond_label(ctx, cfg, *cond, a, b, label.clone())
+            }
+            Insn::BcondLabelHinted {
+                cond,
+                a,
+                b,
+                label,
+                hint,
+            } => w.bcond_label_hinted(ctx, cfg, *cond, a, b, label.clone(), *hint),
+            Insn::DdLabel { label } => w.dd_label(ctx, cfg, label.clone()),
+        }
+    }
+}
+
+/// Records calls as a list of [`Insn`]s.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RecordingWriter<L> {
+    insns: Vec<Insn<L>>,
+}
+
+impl<L> RecordingWriter<L> {
+    /// Creates an empty recording.
+    pub fn new() -> Self {
+        Self { insns: Vec::new() }
+    }
+
+    /// Returns the recorded calls in the order they were made.
+    pub fn insns(&self) -> &[Insn<L>] {
+        &self.insns
+    }
+
+    /// Returns the recorded calls for editing or reordering.
+    pub fn insns_mut(&mut self) -> &mut Vec<Insn<L>> {
+        &mut self.insns
+    }
+
+    /// Consumes the recording and returns its calls.
+    pub fn into_insns(self) -> Vec<Insn<L>> {
+        self.insns
+    }
+
+    /// Replays every recorded call into `w`, in order, stopping at the first
+    /// error.
+    pub fn replay<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+        L: Clone,
+    {
+        for insn in &self.insns {
+            insn.replay(w, ctx, cfg)?;
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, insn: Insn<L>) -> Result<(), EmitError> {
+        self.insns.push(insn);
+        Ok(())
+    }
+}
+
+impl<L> Default for RecordingWriter<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L> From<Vec<Insn<L>>> for RecordingWriter<L> {
+    fn from(insns: Vec<Insn<L>>) -> Self {
+        Self { insns }
+    }
+}
+
+impl<L, Context> WriterCore<Context> for RecordingWriter<L> {
+    type Error = EmitError;
+
+    fn ebreak(&mut self, _ctx: &mut Context, _cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Ebreak)
+    }
+
+    fn unimp(&mut self, _ctx: &mut Context, _cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Unimp)
+    }
+
+    fn unreachable(&mut self, _ctx: &mut Context, _cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Unreachable)
+    }
+
+    fn debug_break(&mut self, _ctx: &mut Context, _cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::DebugBreak)
+    }
+
+    fn ecall(&mut self, _ctx: &mut Context, _cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Ecall)
+    }
+
+    fn lpad(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        label: u32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lpad { label })
+    }
+
+    fn sspush(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sspush {
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn sspopchk(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sspopchk {
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fence(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        pred: FenceSet,
+        succ: FenceSet,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fence { pred, succ })
+    }
+
+    fn fence_i(&mut self, _ctx: &mut Context, _cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::FenceI)
+    }
+
+    fn pause(&mut self, _ctx: &mut Context, _cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Pause)
+    }
+
+    fn prefetch_r(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::PrefetchR {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn prefetch_w(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::PrefetchW {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn cbo_clean(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::CboClean {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn cbo_flush(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::CboFlush {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn cbo_inval(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::CboInval {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn amoswap_w(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AmoswapW {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+            addr: addr.concrete_mem_kind(),
+        })
+    }
+
+    fn amoswap_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AmoswapD {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+            addr: addr.concrete_mem_kind(),
+        })
+    }
+
+    fn mv(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mv {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn sub(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sub {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn add(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Add {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn addi(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Addi {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn add_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AddImm {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn sub_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SubImm {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn sd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sd {
+            src: src.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn ld(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ld {
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn lw(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lw {
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn lwu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lwu {
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn sw(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sw {
+            src: src.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn lb(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lb {
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn lbu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lbu {
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn sb(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sb {
+            src: src.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn lh(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lh {
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn lhu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lhu {
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn sh(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sh {
+            src: src.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn jalr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        base: &(dyn MemArg + '_),
+        offset: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Jalr {
+            dest: dest.concrete_mem_kind(),
+            base: base.concrete_mem_kind(),
+            offset,
+        })
+    }
+
+    fn jal(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Jal {
+            dest: dest.concrete_mem_kind(),
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn beq(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Beq {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn bne(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Bne {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn blt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Blt {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn bge(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Bge {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn bltu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Bltu {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn bgeu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Bgeu {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn bcond(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        cond: ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Bcond {
+            cond,
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn bcond_zero(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        cond: ConditionCode,
+        a: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::BcondZero {
+            cond,
+            a: a.concrete_mem_kind(),
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn and(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::And {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn andi(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Andi {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn and_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AndImm {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn or(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Or {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn ori(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ori {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn or_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::OrImm {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn xor(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Xor {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn xori(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Xori {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn xor_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::XorImm {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn sll(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sll {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn srl(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Srl {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sra(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sra {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn rol(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Rol {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn ror(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ror {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn cpop(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cpop {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn clz(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Clz {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn ctz(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ctz {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn rev8(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Rev8 {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn sext_b(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SextB {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn sext_h(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SextH {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn sext_w(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SextW {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn slt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Slt {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sltu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sltu {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn lui(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        imm: u32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lui {
+            dest: dest.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn auipc(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        imm: u32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Auipc {
+            dest: dest.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn la(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::La {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn li(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Li {
+            dest: dest.concrete_mem_kind(),
+            val,
+        })
+    }
+
+    fn ret(&mut self, _ctx: &mut Context, _cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Ret)
+    }
+
+    fn call(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Call {
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn j(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::J {
+            target: target.concrete_mem_kind(),
+        })
+    }
+
+    fn mul(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mul {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn mulh(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mulh {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn mulhu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mulhu {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn div(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Div {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn divu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Divu {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn rem(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Rem {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn remu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Remu {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fld(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fld {
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn fsd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fsd {
+            src: src.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn fadd_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FaddD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fsub_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FsubD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmul_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FmulD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fdiv_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FdivD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmadd_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FmaddD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fmadd_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FmaddS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fmsub_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FmsubD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fmsub_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FmsubS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fnmsub_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FnmsubD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fnmsub_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FnmsubS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fnmadd_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FnmaddD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fnmadd_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FnmaddS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            c: c.concrete_mem_kind(),
+        })
+    }
+
+    fn fmov_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FmovD {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_d_l(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtDL {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_l_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtLD {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn feq_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FeqD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn flt_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FltD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fle_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FleD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmin_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FminD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmax_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FmaxD {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fsqrt_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FsqrtD {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fabs_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FabsD {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fneg_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FnegD {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_d_w(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtDW {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_d_wu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtDWu {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_d_lu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtDLu {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_w_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtWD {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_wu_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtWuD {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_lu_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtLuD {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn flw(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Flw {
+            dest: dest.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn fsw(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fsw {
+            src: src.concrete_mem_kind(),
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn fadd_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FaddS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fsub_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FsubS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmul_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FmulS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fdiv_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FdivS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmov_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FmovS {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_s_l(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtSL {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_l_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtLS {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn feq_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FeqS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn flt_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FltS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fle_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FleS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmin_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FminS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmax_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FmaxS {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fsqrt_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FsqrtS {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fabs_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FabsS {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fneg_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FnegS {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_s_w(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtSW {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_s_wu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtSWu {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_s_lu(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtSLu {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_w_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtWS {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_wu_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtWuS {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_lu_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtLuS {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_s_d(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtSD {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcvt_d_s(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::FcvtDS {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fsrm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fsrm {
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn frrm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Frrm {
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn csrrw(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Csrrw {
+            dest: dest.concrete_mem_kind(),
+            csr,
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn csrrs(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Csrrs {
+            dest: dest.concrete_mem_kind(),
+            csr,
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn csrrc(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Csrrc {
+            dest: dest.concrete_mem_kind(),
+            csr,
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn csrrwi(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Csrrwi {
+            dest: dest.concrete_mem_kind(),
+            csr,
+            imm,
+        })
+    }
+
+    fn csrrsi(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Csrrsi {
+            dest: dest.concrete_mem_kind(),
+            csr,
+            imm,
+        })
+    }
+
+    fn csrrci(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Csrrci {
+            dest: dest.concrete_mem_kind(),
+            csr,
+            imm,
+        })
+    }
+
+    fn rdcycle(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Rdcycle {
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn rdtime(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Rdtime {
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn db(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        bytes: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Db {
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    fn align_to(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AlignTo { alignment })
+    }
+
+    fn nop(&mut self, _ctx: &mut Context, _cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Nop)
+    }
+
+    fn nops(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Nops { n_bytes })
+    }
+
+    fn align(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        pow2: u32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Align { pow2 })
+    }
+}
+
+impl<L, Context> Writer<L, Context> for RecordingWriter<L> {
+    fn set_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        s: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SetLabel { label: s })
+    }
+
+    fn jal_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::JalLabel {
+            dest: dest.concrete_mem_kind(),
+            label,
+        })
+    }
+
+    fn la_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::LaLabel {
+            dest: dest.concrete_mem_kind(),
+            label,
+        })
+    }
+
+    fn bcond_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        cond: ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::BcondLabel {
+            cond,
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            label,
+        })
+    }
+
+    fn bcond_label_hinted(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        cond: ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        label: L,
+        hint: BranchHint,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::BcondLabelHinted {
+            cond,
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            label,
+            hint,
+        })
+    }
+
+    fn dd_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: RiscV64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::DdLabel { label })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    fn generate<W: Writer<u32, ()> + ?Sized>(w: &mut W, cfg: RiscV64Arch) -> Result<(), W::Error> {
+        w.set_label(&mut (), cfg, 1)?;
+        w.mv(&mut (), cfg, &Reg(0), &Reg(1))?;
+        w.ebreak(&mut (), cfg)?;
+        w.jal_label(&mut (), cfg, &Reg(0), 1)
+    }
+
+    #[test]
+    fn test_replay_matches_direct_emission() {
+        let cfg = RiscV64Arch::default();
+        let mut rec = RecordingWriter::new();
+        generate(&mut rec, cfg).unwrap();
+        assert_eq!(rec.insns().len(), 4);
+        assert_eq!(rec.insns()[0], Insn::SetLabel { label: 1 });
+
+        let mut direct = String::new();
+        let mut w: &mut dyn Write = &mut direct;
+        generate(&mut w, cfg).unwrap();
+
+        let mut replayed = String::new();
+        let mut w: &mut dyn Write = &mut replayed;
+        rec.replay(&mut w, &mut (), cfg).unwrap();
+        rec.replay(&mut w, &mut (), cfg).unwrap();
+        assert_eq!(replayed, direct.repeat(2));
+    }
+
+    /// Records a trap, then a block at label 1 that returns, then a jump
+    /// back to the label.
+    fn record(cfg: RiscV64Arch) -> RecordingWriter<u32> {
+        let mut rec = RecordingWriter::new();
+        rec.ebreak(&mut (), cfg).unwrap();
+        rec.set_label(&mut (), cfg, 1).unwrap();
+        rec.ret(&mut (), cfg).unwrap();
+        rec.jal_label(&mut (), cfg, &Reg(0), 1).unwrap();
+        rec
+    }
+
+    #[test]
+    fn test_reordered_replay() {
+        let cfg = RiscV64Arch::default();
+        let mut rec = record(cfg);
+        // Move the label in front of the trap, so the jump now targets it.
+        let label = rec.insns_mut().remove(1);
+        rec.insns_mut().insert(0, label);
+
+        let mut output = String::new();
+        let mut w: &mut dyn Write = &mut output;
+        rec.replay(&mut w, &mut (), cfg).unwrap();
+        assert_eq!(output, "1:\nebreak\nret\njal zero, 1\n");
+    }
+
+    #[cfg(feature = "rv-asm-backend")]
+    #[test]
+    fn test_reordered_replay_resolves_labels_at_their_new_offset() {
+        use crate::out::rv_asm_backend::RvAsmWriter;
+
+        let cfg = RiscV64Arch::default();
+        let mut rec = record(cfg);
+        let label = rec.insns_mut().remove(1);
+        rec.insns_mut().insert(0, label);
+
+        let mut binary = RvAsmWriter::<u32>::new();
+        rec.replay(&mut binary, &mut (), cfg).unwrap();
+        let (code, labels) = binary.into_parts();
+        assert_eq!(labels[&1], 0);
+        assert_eq!(
+            code,
+            [
+                0x73, 0x00, 0x10, 0x00, 0x67, 0x80, 0x00, 0x00, 0x6F, 0xF0, 0x9F, 0xFF
+            ]
+        );
+    }
+}
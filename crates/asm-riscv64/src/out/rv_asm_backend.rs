@@ -1,6 +1,6 @@
 extern crate alloc;
 
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::vec::Vec;
 use rv_asm::{Imm, Inst, Reg as RvReg, Xlen};
 
@@ -14,27 +14,82 @@ use crate::out::MemArg;
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum NoLabel {}
 
+/// Panics when `$cond` is false and the `debug_checks` feature is enabled.
+///
+/// Without the feature the condition is behind a constant `false` and is
+/// compiled out. Used from `#[track_caller]` helpers, so the panic location
+/// is the `WriterCore` method that received the bad operand.
+macro_rules! debug_check {
+    ($cond:expr, $($msg:tt)*) => {
+        if cfg!(feature = "debug_checks") && !$cond {
+            panic!("debug_checks: {}", format_args!($($msg)*));
+        }
+    };
+}
+
+/// Checks that the extension an instruction belongs to is enabled in the config.
+#[track_caller]
+fn require_ext(enabled: bool, ext: &str) {
+    debug_check!(enabled, "instruction requires the {ext} extension, which RiscV64Arch disables");
+}
+
+/// Checks that an I-type immediate fits in 12 signed bits.
+#[track_caller]
+fn check_imm12(imm: i64) {
+    debug_check!((-2048..=2047).contains(&imm), "immediate {imm} does not fit in a signed 12-bit field");
+}
+
+#[track_caller]
 fn to_rv_reg(arg: &dyn MemArg) -> RvReg {
     match arg.concrete_mem_kind() {
-        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => RvReg(reg.0 as u8),
-        _ => RvReg::ZERO,
+        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => {
+            debug_check!(reg.0 < 32, "register index {} is out of range (0..=31)", reg.0);
+            RvReg(reg.0 as u8)
+        }
+        other => {
+            debug_check!(false, "expected a register operand, got {other:?}");
+            RvReg::ZERO
+        }
     }
 }
 
+#[track_caller]
 fn mem_base_offset(arg: &dyn MemArg) -> (RvReg, Imm) {
     match arg.concrete_mem_kind() {
-        MemArgKind::Mem { base: ArgKind::Reg { reg, .. }, disp, .. } => {
+        MemArgKind::Mem { base: ArgKind::Reg { reg, .. }, offset, disp, .. } => {
+            debug_check!(reg.0 < 32, "base register index {} is out of range (0..=31)", reg.0);
+            debug_check!(offset.is_none(), "index register {offset:?} must be desugared before encoding");
+            check_imm12(disp as i64);
             (RvReg(reg.0 as u8), Imm::new_i32(disp as i32))
         }
         MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => (RvReg(reg.0 as u8), Imm::ZERO),
-        _ => (RvReg::ZERO, Imm::ZERO),
+        other => {
+            debug_check!(false, "expected a register-based memory operand, got {other:?}");
+            (RvReg::ZERO, Imm::ZERO)
+        }
     }
 }
 
+#[track_caller]
 fn lit_as_imm(arg: &dyn MemArg) -> Imm {
     match arg.concrete_mem_kind() {
-        MemArgKind::NoMem(ArgKind::Lit(v)) => Imm::new_i32(v as i32),
-        _ => Imm::ZERO,
+        MemArgKind::NoMem(ArgKind::Lit(v)) => {
+            debug_check!(v as i64 == v as i32 as i64, "literal {v:#x} does not fit in 32 signed bits");
+            Imm::new_i32(v as i32)
+        }
+        other => {
+            debug_check!(false, "expected a literal operand, got {other:?}");
+            Imm::ZERO
+        }
+    }
+}
+
+/// The symbol and addend of a `sym` or `[sym + disp]` operand.
+fn sym_value(arg: &dyn MemArg) -> Option<(&'static str, i64)> {
+    match arg.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Sym { name, addend }) => Some((name, addend)),
+        MemArgKind::Mem { base: ArgKind::Sym { name, addend }, offset: None, disp, .. } => Some((name, addend + disp as i64)),
+        _ => None,
     }
 }
 
@@ -53,6 +108,8 @@ enum RvFixupKind {
     /// Conditional branch — 4 bytes, B-type immediate.
     /// `rs1`/`rs2` are already in emission order (may have been swapped for pseudo-conditions).
     BCond { rs1: u32, rs2: u32, funct3: u32 },
+    /// `.4byte label - .` — 4-byte data word holding the offset itself.
+    Data32,
 }
 
 struct RvFixup<L> {
@@ -81,6 +138,92 @@ fn encode_branch(rs1: u32, rs2: u32, funct3: u32, delta: i32) -> u32 {
     (imm12 << 31) | (imm10_5 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (imm4_1 << 8) | (imm11 << 7) | 0x63
 }
 
+/// Encode a Zbb R-type instruction (OP opcode), for ops `rv_asm` does not model.
+fn encode_zbb_r(funct7: u32, rs2: u32, rs1: u32, funct3: u32, rd: u32) -> u32 {
+    (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0x33
+}
+
+/// Encode RORI rd, rs1, shamt (Zbb, RV64 6-bit shift amount).
+fn encode_rori(rd: u32, rs1: u32, shamt: u32) -> u32 {
+    (0b011000 << 26) | (shamt << 20) | (rs1 << 15) | (0b101 << 12) | (rd << 7) | 0x13
+}
+
+/// Encode FENCE pred, succ (MISC-MEM opcode, FM=0, rs1=rd=x0).
+fn encode_fence(pred: u32, succ: u32) -> u32 {
+    (pred << 24) | (succ << 20) | 0x0F
+}
+
+/// Encode a Zicbop PREFETCH.{I,R,W} offset(rs1): an ORI writing `zero`, with
+/// `which` (0, 1 or 3) in the rs2 field and offset[11:5] above it.
+fn encode_prefetch(offset: i32, rs1: u32, which: u32) -> u32 {
+    (((offset as u32) & 0xFE0) << 20) | (which << 20) | (rs1 << 15) | (0b110 << 12) | 0x13
+}
+
+/// Encodes a Zicbom cache-block operation: `0` inval, `1` clean, `2` flush.
+fn encode_cbo(rs1: u32, which: u32) -> u32 {
+    (which << 20) | (rs1 << 15) | (0b010 << 12) | 0x0F
+}
+
+/// The base register and offset of a cache-block address, whose offset must
+/// be in the 12-bit range.
+#[track_caller]
+fn block_base_offset(mem: &dyn MemArg) -> (u32, i32) {
+    let (base, disp) = match mem.concrete_mem_kind() {
+        MemArgKind::Mem { base: ArgKind::Reg { reg, .. }, offset, disp, .. } => {
+            debug_check!(offset.is_none(), "index register {offset:?} must be desugared before encoding");
+            (reg.0, disp)
+        }
+        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => (reg.0, 0),
+        other => {
+            debug_check!(false, "expected a register-based memory operand, got {other:?}");
+            (0, 0)
+        }
+    };
+    debug_check!(base < 32, "base register index {base} is out of range (0..=31)");
+    check_imm12(disp as i64);
+    (base as u32, disp)
+}
+
+/// The base register and offset of a prefetch address, whose offset must be
+/// a multiple of 32.
+#[track_caller]
+fn prefetch_base_offset(mem: &dyn MemArg) -> (u32, i32) {
+    let (base, disp) = block_base_offset(mem);
+    debug_check!(disp % 32 == 0, "prefetch offset {disp} is not a multiple of 32");
+    (base, disp)
+}
+
+/// The base register of a Zicbom address, whose offset must be 0.
+#[track_caller]
+fn cbo_base(mem: &dyn MemArg) -> u32 {
+    let (base, disp) = block_base_offset(mem);
+    debug_check!(disp == 0, "cache-block operation offset {disp} is not 0");
+    base
+}
+
+/// Encode a Zicsr instruction (SYSTEM opcode): `rs1` holds the source
+/// register, or the 5-bit immediate for the `i` forms.
+fn encode_csr(csr: crate::Csr, rs1: u32, funct3: u32, rd: u32) -> u32 {
+    ((csr.number() as u32) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0x73
+}
+
+/// The 5-bit unsigned immediate of a CSR instruction.
+#[track_caller]
+fn csr_uimm(imm: u8) -> u32 {
+    debug_check!(imm < 32, "CSR immediate {imm} is out of range (0..=31)");
+    imm as u32 & 0x1F
+}
+
+/// Encode AMOSWAP.{W,D}.AQRL rd, rs2, (rs1) (AMO opcode, funct3 selecting the width).
+fn encode_amoswap(rd: u32, rs2: u32, rs1: u32, funct3: u32) -> u32 {
+    (0b00001 << 27) | (1 << 26) | (1 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0x2F
+}
+
+/// Encode a single-source Zbb instruction (OP-IMM opcode) selected by its 12-bit immediate field.
+fn encode_zbb_unary(imm12: u32, rs1: u32, funct3: u32, rd: u32) -> u32 {
+    (imm12 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0x13
+}
+
 /// Returns `(funct3, swap_operands)`.  RISC-V pseudo-conditions GT/LE/GTU/LEU
 /// are encoded as the reverse comparison with rs1 and rs2 swapped.
 fn funct3_for_cond(cond: crate::ConditionCode) -> (u32, bool) {
@@ -120,19 +263,72 @@ impl<L> RvFixup<L> {
                 let word = encode_branch(*rs1, *rs2, *funct3, delta);
                 buf[self.instr_offset..self.instr_offset + 4].copy_from_slice(&word.to_le_bytes());
             }
+            RvFixupKind::Data32 => {
+                buf[self.instr_offset..self.instr_offset + 4].copy_from_slice(&delta.to_le_bytes());
+            }
         }
     }
 }
 
+/// Relocation types recorded by [`RvAsmWriter`], named after their ELF counterparts.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[non_exhaustive]
+pub enum RelocKind {
+    /// `R_RISCV_CALL_PLT`: AUIPC + JALR pair of a `call`.
+    CallPlt,
+    /// `R_RISCV_JAL`: `jal`/`j` target.
+    Jal,
+    /// `R_RISCV_PCREL_HI20`: AUIPC of an `la` or PC-relative load.
+    PcrelHi20,
+    /// `R_RISCV_PCREL_LO12_I`: ADDI or load completing the preceding
+    /// `PcrelHi20`. In ELF this refers to the AUIPC rather than the symbol;
+    /// the entry here repeats the symbol and addend of its pair.
+    PcrelLo12I,
+    /// `R_RISCV_HI20`: LUI of an absolute address.
+    Hi20,
+    /// `R_RISCV_LO12_I`: ADDI completing the preceding `Hi20`.
+    Lo12I,
+}
+
+/// A symbol reference left for the linker, recorded by [`RvAsmWriter`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Relocation {
+    /// Byte offset of the instruction to patch within the assembled output.
+    pub offset: usize,
+    pub kind: RelocKind,
+    pub symbol: &'static str,
+    pub addend: i64,
+}
+
+/// A label problem found by [`RvAsmWriter::label_violations`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum LabelViolation<L> {
+    /// A branch or address reference targets a label that was never set.
+    Undefined(L),
+    /// The label was set more than once; the last definition wins.
+    Duplicate(L),
+}
+
 pub struct RvAsmWriter<L = NoLabel> {
     buf: Vec<u8>,
     labels: BTreeMap<L, usize>,
     pending_fixups: Vec<RvFixup<L>>,
+    /// Labels passed to `set_label` after they were already defined.
+    duplicate_labels: Vec<L>,
+    relocations: Vec<Relocation>,
 }
 
 impl<L> RvAsmWriter<L> {
     pub fn new() -> Self {
-        Self { buf: Vec::new(), labels: BTreeMap::new(), pending_fixups: Vec::new() }
+        Self { buf: Vec::new(), labels: BTreeMap::new(), pending_fixups: Vec::new(), duplicate_labels: Vec::new(), relocations: Vec::new() }
+    }
+
+    /// Symbol references in the assembled bytes, in emission order.
+    ///
+    /// Symbol operands are encoded with a zero offset; each needs its
+    /// relocation applied before the code can run.
+    pub fn relocations(&self) -> &[Relocation] {
+        &self.relocations
     }
 
     /// Return the assembled bytes, discarding any recorded label offsets.
@@ -152,8 +348,52 @@ impl<L> RvAsmWriter<L> {
 
     fn emit(&mut self, inst: Inst) {
         let word = inst.encode_normal(Xlen::Rv64);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(word, offset = self.buf.len(), "encoding instruction");
         self.buf.extend_from_slice(&word.to_le_bytes());
     }
+
+    /// Emit `inst` with a relocation against `sym`.
+    fn emit_reloc(&mut self, inst: Inst, kind: RelocKind, (symbol, addend): (&'static str, i64)) {
+        self.relocations.push(Relocation { offset: self.buf.len(), kind, symbol, addend });
+        self.emit(inst);
+    }
+
+    /// Base and offset for a load into `dest`. `[sym + disp]` is addressed
+    /// with an AUIPC into `dest`, paired with the load emitted next.
+    #[track_caller]
+    fn load_base(&mut self, dest: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> (RvReg, Imm) {
+        match sym_value(mem) {
+            Some(sym) => {
+                let rd = to_rv_reg(dest);
+                self.emit_reloc(Inst::Auipc { dest: rd, uimm: Imm::ZERO }, RelocKind::PcrelHi20, sym);
+                self.relocations.push(Relocation { offset: self.buf.len(), kind: RelocKind::PcrelLo12I, symbol: sym.0, addend: sym.1 });
+                (rd, Imm::ZERO)
+            }
+            None => mem_base_offset(mem),
+        }
+    }
+}
+
+impl<L: Ord + Clone> RvAsmWriter<L> {
+    /// List every label that is referenced but never defined, or defined more
+    /// than once. An empty list means the output is safe to finalize.
+    ///
+    /// Undefined labels are those with fixups still pending; each is reported
+    /// once regardless of how many instructions reference it.
+    pub fn label_violations(&self) -> Vec<LabelViolation<L>> {
+        let undefined: BTreeSet<&L> = self.pending_fixups.iter().map(|fix| &fix.label).collect();
+        undefined.into_iter().cloned().map(LabelViolation::Undefined)
+            .chain(self.duplicate_labels.iter().cloned().map(LabelViolation::Duplicate))
+            .collect()
+    }
+
+    /// Like [`into_parts`](Self::into_parts), but fails with the list of
+    /// [`label_violations`](Self::label_violations) if there are any.
+    pub fn try_into_parts(self) -> Result<(Vec<u8>, BTreeMap<L, usize>), Vec<LabelViolation<L>>> {
+        let violations = self.label_violations();
+        if violations.is_empty() { Ok(self.into_parts()) } else { Err(violations) }
+    }
 }
 
 impl<L> Default for RvAsmWriter<L> {
@@ -163,14 +403,170 @@ impl<L> Default for RvAsmWriter<L> {
 }
 
 impl<L, Context> crate::out::WriterCore<Context> for RvAsmWriter<L> {
-    type Error = core::convert::Infallible;
+    type Error = crate::out::error::EmitError;
 
     fn ebreak(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
         self.emit(Inst::Ebreak);
         Ok(())
     }
 
-    fn mv(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+    fn unimp(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        // UNIMP = CSRRW x0, cycle, x0: a write to the read-only cycle CSR
+        let word: u32 = 0xC000_1073;
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn ecall(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        self.emit(Inst::Ecall);
+        Ok(())
+    }
+
+    fn lpad(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, label: u32) -> Result<(), Self::Error> {
+        debug_check!(label < 1 << 20, "landing pad label {label} does not fit in 20 bits");
+        // LPAD label = AUIPC x0, label
+        let word = 0x0000_0017 | (label << 12);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn sspush(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zicfiss, "Zicfiss");
+        let rs2 = to_rv_reg(src).0 as u32;
+        debug_check!(rs2 == 1 || rs2 == 5, "sspush takes ra or t0, got x{rs2}");
+        // SSPUSH rs2 = 0xCE004073 | rs2 << 20
+        let word = 0xCE00_4073 | (rs2 << 20);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn sspopchk(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zicfiss, "Zicfiss");
+        let rs1 = to_rv_reg(src).0 as u32;
+        debug_check!(rs1 == 1 || rs1 == 5, "sspopchk takes ra or t0, got x{rs1}");
+        // SSPOPCHK rs1 = 0xCDC04073 | rs1 << 15
+        let word = 0xCDC0_4073 | (rs1 << 15);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn fence(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, pred: crate::FenceSet, succ: crate::FenceSet) -> Result<(), Self::Error> {
+        let word = encode_fence(pred.bits() as u32, succ.bits() as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn fence_i(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        // FENCE.I = 0x0000100F
+        self.buf.extend_from_slice(&0x0000_100Fu32.to_le_bytes());
+        Ok(())
+    }
+
+    fn pause(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        // PAUSE = FENCE w, 0
+        let word = encode_fence(crate::FenceSet::W.bits() as u32, 0);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn prefetch_r(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zicbop, "Zicbop");
+        let (rs1, offset) = prefetch_base_offset(mem);
+        let word = encode_prefetch(offset, rs1, 1);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn prefetch_w(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zicbop, "Zicbop");
+        let (rs1, offset) = prefetch_base_offset(mem);
+        let word = encode_prefetch(offset, rs1, 3);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn cbo_clean(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zicbom, "Zicbom");
+        let word = encode_cbo(cbo_base(mem), 1);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn cbo_flush(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zicbom, "Zicbom");
+        let word = encode_cbo(cbo_base(mem), 2);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn cbo_inval(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zicbom, "Zicbom");
+        let word = encode_cbo(cbo_base(mem), 0);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn csrrw(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), csr: crate::Csr, src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let word = encode_csr(csr, to_rv_reg(src).0 as u32, 0b001, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn csrrs(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), csr: crate::Csr, src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let word = encode_csr(csr, to_rv_reg(src).0 as u32, 0b010, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn csrrc(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), csr: crate::Csr, src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let word = encode_csr(csr, to_rv_reg(src).0 as u32, 0b011, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn csrrwi(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), csr: crate::Csr, imm: u8) -> Result<(), Self::Error> {
+        let word = encode_csr(csr, csr_uimm(imm), 0b101, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn csrrsi(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), csr: crate::Csr, imm: u8) -> Result<(), Self::Error> {
+        let word = encode_csr(csr, csr_uimm(imm), 0b110, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn csrrci(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), csr: crate::Csr, imm: u8) -> Result<(), Self::Error> {
+        let word = encode_csr(csr, csr_uimm(imm), 0b111, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn amoswap_w(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_), addr: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.a_extension, "A");
+        let word = encode_amoswap(to_rv_reg(dest).0 as u32, to_rv_reg(src).0 as u32, to_rv_reg(addr).0 as u32, 0b010);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn amoswap_d(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_), addr: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.a_extension, "A");
+        let word = encode_amoswap(to_rv_reg(dest).0 as u32, to_rv_reg(src).0 as u32, to_rv_reg(addr).0 as u32, 0b011);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn mv(&mut self, ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        if let MemArgKind::NoMem(ArgKind::Sym { name, addend }) = src.concrete_mem_kind() {
+            if cfg.pic {
+                return crate::out::WriterCore::la(self, ctx, cfg, dest, src);
+            }
+            // LUI rd, %hi(sym) ; ADDI rd, rd, %lo(sym)
+            let rd = to_rv_reg(dest);
+            self.emit_reloc(Inst::Lui { dest: rd, uimm: Imm::ZERO }, RelocKind::Hi20, (name, addend));
+            self.emit_reloc(Inst::Addi { dest: rd, src1: rd, imm: Imm::ZERO }, RelocKind::Lo12I, (name, addend));
+            return Ok(());
+        }
         self.emit(Inst::Addi {
             dest: to_rv_reg(dest),
             src1: to_rv_reg(src),
@@ -186,17 +582,23 @@ impl<L, Context> crate::out::WriterCore<Context> for RvAsmWriter<L> {
     }
 
     fn ld(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
-        let (base, offset) = mem_base_offset(mem);
+        let (base, offset) = self.load_base(dest, mem);
         self.emit(Inst::Ld { dest: to_rv_reg(dest), base, offset });
         Ok(())
     }
 
     fn lw(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
-        let (base, offset) = mem_base_offset(mem);
+        let (base, offset) = self.load_base(dest, mem);
         self.emit(Inst::Lw { dest: to_rv_reg(dest), base, offset });
         Ok(())
     }
 
+    fn lwu(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (base, offset) = self.load_base(dest, mem);
+        self.emit(Inst::Lwu { dest: to_rv_reg(dest), base, offset });
+        Ok(())
+    }
+
     fn sw(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, src: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let (base, offset) = mem_base_offset(mem);
         self.emit(Inst::Sw { src: to_rv_reg(src), base, offset });
@@ -204,11 +606,17 @@ impl<L, Context> crate::out::WriterCore<Context> for RvAsmWriter<L> {
     }
 
     fn lb(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
-        let (base, offset) = mem_base_offset(mem);
+        let (base, offset) = self.load_base(dest, mem);
         self.emit(Inst::Lb { dest: to_rv_reg(dest), base, offset });
         Ok(())
     }
 
+    fn lbu(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (base, offset) = self.load_base(dest, mem);
+        self.emit(Inst::Lbu { dest: to_rv_reg(dest), base, offset });
+        Ok(())
+    }
+
     fn sb(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, src: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let (base, offset) = mem_base_offset(mem);
         self.emit(Inst::Sb { src: to_rv_reg(src), base, offset });
@@ -216,11 +624,17 @@ impl<L, Context> crate::out::WriterCore<Context> for RvAsmWriter<L> {
     }
 
     fn lh(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
-        let (base, offset) = mem_base_offset(mem);
+        let (base, offset) = self.load_base(dest, mem);
         self.emit(Inst::Lh { dest: to_rv_reg(dest), base, offset });
         Ok(())
     }
 
+    fn lhu(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let (base, offset) = self.load_base(dest, mem);
+        self.emit(Inst::Lhu { dest: to_rv_reg(dest), base, offset });
+        Ok(())
+    }
+
     fn sh(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, src: &(dyn MemArg + '_), mem: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         let (base, offset) = mem_base_offset(mem);
         self.emit(Inst::Sh { src: to_rv_reg(src), base, offset });
@@ -237,6 +651,10 @@ impl<L, Context> crate::out::WriterCore<Context> for RvAsmWriter<L> {
     }
 
     fn jal(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), target: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        if let Some(sym) = sym_value(target) {
+            self.emit_reloc(Inst::Jal { dest: to_rv_reg(dest), offset: Imm::ZERO }, RelocKind::Jal, sym);
+            return Ok(());
+        }
         self.emit(Inst::Jal {
             dest: to_rv_reg(dest),
             offset: lit_as_imm(target),
@@ -285,10 +703,29 @@ impl<L, Context> crate::out::WriterCore<Context> for RvAsmWriter<L> {
     }
 
     fn addi(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_), imm: i32) -> Result<(), Self::Error> {
+        check_imm12(imm as i64);
         self.emit(Inst::Addi { dest: to_rv_reg(dest), src1: to_rv_reg(src), imm: Imm::new_i32(imm) });
         Ok(())
     }
 
+    fn andi(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_), imm: i32) -> Result<(), Self::Error> {
+        check_imm12(imm as i64);
+        self.emit(Inst::Andi { dest: to_rv_reg(dest), src1: to_rv_reg(src), imm: Imm::new_i32(imm) });
+        Ok(())
+    }
+
+    fn ori(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_), imm: i32) -> Result<(), Self::Error> {
+        check_imm12(imm as i64);
+        self.emit(Inst::Ori { dest: to_rv_reg(dest), src1: to_rv_reg(src), imm: Imm::new_i32(imm) });
+        Ok(())
+    }
+
+    fn xori(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_), imm: i32) -> Result<(), Self::Error> {
+        check_imm12(imm as i64);
+        self.emit(Inst::Xori { dest: to_rv_reg(dest), src1: to_rv_reg(src), imm: Imm::new_i32(imm) });
+        Ok(())
+    }
+
     fn and(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
         self.emit(Inst::And { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) });
         Ok(())
@@ -305,17 +742,99 @@ impl<L, Context> crate::out::WriterCore<Context> for RvAsmWriter<L> {
     }
 
     fn sll(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
-        self.emit(Inst::Sll { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) });
+        let inst = match b.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Lit(v)) => Inst::Slli { dest: to_rv_reg(dest), src1: to_rv_reg(a), imm: Imm::new_i32((v & 0x3F) as i32) },
+            _ => Inst::Sll { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) },
+        };
+        self.emit(inst);
         Ok(())
     }
 
     fn srl(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
-        self.emit(Inst::Srl { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) });
+        let inst = match b.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Lit(v)) => Inst::Srli { dest: to_rv_reg(dest), src1: to_rv_reg(a), imm: Imm::new_i32((v & 0x3F) as i32) },
+            _ => Inst::Srl { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) },
+        };
+        self.emit(inst);
         Ok(())
     }
 
     fn sra(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
-        self.emit(Inst::Sra { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) });
+        let inst = match b.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Lit(v)) => Inst::Srai { dest: to_rv_reg(dest), src1: to_rv_reg(a), imm: Imm::new_i32((v & 0x3F) as i32) },
+            _ => Inst::Sra { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) },
+        };
+        self.emit(inst);
+        Ok(())
+    }
+
+    fn rol(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zbb, "Zbb");
+        let (rd, rs1) = (to_rv_reg(dest).0 as u32, to_rv_reg(a).0 as u32);
+        let word = match b.concrete_mem_kind() {
+            // Zbb has no ROLI; rotate right by the complement instead.
+            MemArgKind::NoMem(ArgKind::Lit(v)) => encode_rori(rd, rs1, (64 - (v as u32 & 0x3F)) & 0x3F),
+            _ => encode_zbb_r(0b0110000, to_rv_reg(b).0 as u32, rs1, 0b001, rd),
+        };
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn ror(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zbb, "Zbb");
+        let (rd, rs1) = (to_rv_reg(dest).0 as u32, to_rv_reg(a).0 as u32);
+        let word = match b.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Lit(v)) => encode_rori(rd, rs1, v as u32 & 0x3F),
+            _ => encode_zbb_r(0b0110000, to_rv_reg(b).0 as u32, rs1, 0b101, rd),
+        };
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn cpop(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zbb, "Zbb");
+        let word = encode_zbb_unary(0x602, to_rv_reg(src).0 as u32, 0b001, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn clz(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zbb, "Zbb");
+        let word = encode_zbb_unary(0x600, to_rv_reg(src).0 as u32, 0b001, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn ctz(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zbb, "Zbb");
+        let word = encode_zbb_unary(0x601, to_rv_reg(src).0 as u32, 0b001, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn rev8(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zbb, "Zbb");
+        let word = encode_zbb_unary(0x6B8, to_rv_reg(src).0 as u32, 0b101, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn sext_b(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zbb, "Zbb");
+        let word = encode_zbb_unary(0x604, to_rv_reg(src).0 as u32, 0b001, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn sext_h(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.zbb, "Zbb");
+        let word = encode_zbb_unary(0x605, to_rv_reg(src).0 as u32, 0b001, to_rv_reg(dest).0 as u32);
+        self.buf.extend_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn sext_w(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        self.emit(Inst::Addiw { dest: to_rv_reg(dest), src1: to_rv_reg(src), imm: Imm::ZERO });
         Ok(())
     }
 
@@ -339,6 +858,17 @@ impl<L, Context> crate::out::WriterCore<Context> for RvAsmWriter<L> {
         Ok(())
     }
 
+    fn la(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), src: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        let rd = to_rv_reg(dest);
+        let sym = sym_value(src);
+        debug_check!(sym.is_some(), "expected a symbol operand, got {:?}", src.concrete_mem_kind());
+        let sym = sym.unwrap_or(("", 0));
+        // AUIPC rd, %pcrel_hi(sym) ; ADDI rd, rd, %pcrel_lo(sym)
+        self.emit_reloc(Inst::Auipc { dest: rd, uimm: Imm::ZERO }, RelocKind::PcrelHi20, sym);
+        self.emit_reloc(Inst::Addi { dest: rd, src1: rd, imm: Imm::ZERO }, RelocKind::PcrelLo12I, sym);
+        Ok(())
+    }
+
     fn li(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), val: u64) -> Result<(), Self::Error> {
         let rd = to_rv_reg(dest);
         let v64 = val as i64;
@@ -406,44 +936,114 @@ impl<L, Context> crate::out::WriterCore<Context> for RvAsmWriter<L> {
     }
 
     fn call(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, target: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        if let Some(sym) = sym_value(target) {
+            // AUIPC ra, 0 ; JALR ra, 0(ra)
+            self.emit_reloc(Inst::Auipc { dest: RvReg::RA, uimm: Imm::ZERO }, RelocKind::CallPlt, sym);
+            self.emit(Inst::Jalr { dest: RvReg::RA, base: RvReg::RA, offset: Imm::ZERO });
+            return Ok(());
+        }
         self.emit(Inst::Jalr { dest: RvReg::RA, base: to_rv_reg(target), offset: Imm::ZERO });
         Ok(())
     }
 
     fn j(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, target: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        if let Some(sym) = sym_value(target) {
+            self.emit_reloc(Inst::Jal { dest: RvReg::ZERO, offset: Imm::ZERO }, RelocKind::Jal, sym);
+            return Ok(());
+        }
         self.emit(Inst::Jal { dest: RvReg::ZERO, offset: lit_as_imm(target) });
         Ok(())
     }
 
-    fn mul(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+    fn mul(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.m_extension, "M");
         self.emit(Inst::Mul { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) });
         Ok(())
     }
 
-    fn mulh(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+    fn mulh(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.m_extension, "M");
         self.emit(Inst::Mulh { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) });
         Ok(())
     }
 
-    fn div(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+    fn mulhu(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.m_extension, "M");
+        self.emit(Inst::Mulhu { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) });
+        Ok(())
+    }
+
+    fn div(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.m_extension, "M");
         self.emit(Inst::Div { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) });
         Ok(())
     }
 
-    fn divu(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+    fn divu(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.m_extension, "M");
         self.emit(Inst::Divu { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) });
         Ok(())
     }
 
-    fn rem(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+    fn rem(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.m_extension, "M");
         self.emit(Inst::Rem { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) });
         Ok(())
     }
 
-    fn remu(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+    fn remu(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, dest: &(dyn MemArg + '_), a: &(dyn MemArg + '_), b: &(dyn MemArg + '_)) -> Result<(), Self::Error> {
+        require_ext(cfg.m_extension, "M");
         self.emit(Inst::Remu { dest: to_rv_reg(dest), src1: to_rv_reg(a), src2: to_rv_reg(b) });
         Ok(())
     }
+
+    fn db(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.buf.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn current_offset(&self) -> Option<usize> {
+        Some(self.buf.len())
+    }
+
+    fn align_to(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch, alignment: usize) -> Result<(), Self::Error> {
+        // Instructions are 4 bytes; pad any odd data tail with zero bytes first.
+        while self.buf.len() % 4 != 0 && self.buf.len() % alignment != 0 {
+            self.buf.push(0);
+        }
+        // NOP = ADDI x0, x0, 0
+        while self.buf.len() % alignment != 0 {
+            self.buf.extend_from_slice(&0x0000_0013u32.to_le_bytes());
+        }
+        Ok(())
+    }
+
+    fn nop(&mut self, _ctx: &mut Context, _cfg: crate::RiscV64Arch) -> Result<(), Self::Error> {
+        self.buf.extend_from_slice(&0x0000_0013u32.to_le_bytes());
+        Ok(())
+    }
+
+    fn nops(&mut self, _ctx: &mut Context, cfg: crate::RiscV64Arch, n_bytes: usize) -> Result<(), Self::Error> {
+        match n_bytes % 4 {
+            0 => {}
+            // C.NOP = C.ADDI x0, 0
+            2 if cfg.c_extension => self.buf.extend_from_slice(&0x0001u16.to_le_bytes()),
+            _ => return Err(crate::out::error::EmitError::InvalidOperands { mnemonic: "nops", reason: "length is not a multiple of the nop size" }),
+        }
+        for _ in 0..n_bytes / 4 {
+            self.buf.extend_from_slice(&0x0000_0013u32.to_le_bytes());
+        }
+        Ok(())
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: crate::RiscV64Arch, pow2: u32) -> Result<(), Self::Error> {
+        let pad = self.buf.len().next_multiple_of(1 << pow2) - self.buf.len();
+        // After compressed code, pad with C.NOP rather than a zero halfword.
+        if pad % 4 == 2 && cfg.c_extension {
+            return crate::out::WriterCore::<Context>::nops(self, ctx, cfg, pad);
+        }
+        crate::out::WriterCore::<Context>::align_to(self, ctx, cfg, 1 << pow2)
+    }
 }
 
 // ── Writer implementation ────────────────────────────────────────────────────
@@ -465,6 +1065,9 @@ impl<L: Ord, Context> crate::out::Writer<L, Context> for RvAsmWriter<L> {
                 i += 1;
             }
         }
+        if let Some((prev, _)) = self.labels.remove_entry(&s) {
+            self.duplicate_labels.push(prev);
+        }
         self.labels.insert(s, target);
         Ok(())
     }
@@ -539,6 +1142,23 @@ impl<L: Ord, Context> crate::out::Writer<L, Context> for RvAsmWriter<L> {
         }
         Ok(())
     }
+
+    fn dd_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: crate::RiscV64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        let instr_offset = self.buf.len();
+        if let Some(&target) = self.labels.get(&label) {
+            let delta = (target as i64 - instr_offset as i64) as i32;
+            self.buf.extend_from_slice(&delta.to_le_bytes());
+        } else {
+            self.buf.extend_from_slice(&[0; 4]);
+            self.pending_fixups.push(RvFixup { instr_offset, label, kind: RvFixupKind::Data32 });
+        }
+        Ok(())
+    }
 }
 
 // ── Tests ────────────────────────────────────────────────────────────────────
@@ -569,4 +1189,265 @@ mod tests {
         assert_eq!(bytes.len(), 12);
         assert_eq!(labels[&7u32], 8);
     }
+
+    #[test]
+    fn pseudo_conditions_swap_operands() {
+        use crate::ConditionCode;
+        use portal_pc_asm_common::types::reg::Reg;
+        let arch = crate::RiscV64Arch::default();
+        let mut ctx = ();
+        let mut w: RvAsmWriter<u32> = RvAsmWriter::new();
+
+        w.bcond(&mut ctx, arch, ConditionCode::GT, &Reg(10), &Reg(11), &ArgKind::Lit(8)).unwrap(); // blt a1, a0, 8
+        w.bcond_zero(&mut ctx, arch, ConditionCode::LE, &Reg(10), &ArgKind::Lit(8)).unwrap(); // bge zero, a0, 8
+        let (bytes, _) = w.into_parts();
+        assert_eq!(bytes, [0x63, 0xC4, 0xA5, 0x00, 0x63, 0x54, 0xA0, 0x00]);
+    }
+
+    #[test]
+    fn traps_encode_unimp_and_ebreak() {
+        let arch = crate::RiscV64Arch::default();
+        let mut ctx = ();
+        let mut w: RvAsmWriter<u32> = RvAsmWriter::new();
+
+        w.unreachable(&mut ctx, arch).unwrap(); // unimp
+        w.debug_break(&mut ctx, arch).unwrap(); // ebreak
+        let (bytes, _) = w.into_parts();
+        assert_eq!(bytes, [0x73, 0x10, 0x00, 0xC0, 0x73, 0x00, 0x10, 0x00]);
+    }
+
+    #[test]
+    fn label_violations_reports_undefined_and_duplicate() {
+        let arch = crate::RiscV64Arch::default();
+        let mut ctx = ();
+        let mut w: RvAsmWriter<u32> = RvAsmWriter::new();
+        let zero = portal_pc_asm_common::types::reg::Reg(0);
+
+        w.jal_label(&mut ctx, arch, &zero, 1u32).unwrap();
+        w.jal_label(&mut ctx, arch, &zero, 1u32).unwrap();
+        w.set_label(&mut ctx, arch, 2u32).unwrap();
+        w.set_label(&mut ctx, arch, 2u32).unwrap();
+
+        assert_eq!(w.label_violations(), [LabelViolation::Undefined(1), LabelViolation::Duplicate(2)]);
+
+        w.set_label(&mut ctx, arch, 1u32).unwrap();
+        assert_eq!(w.try_into_parts().unwrap_err(), [LabelViolation::Duplicate(2)]);
+    }
+
+    #[test]
+    fn sign_extension_encodings() {
+        use crate::out::WriterCore as _;
+        let arch = crate::RiscV64Arch { zbb: true, ..Default::default() };
+        let mut ctx = ();
+        let mut w: RvAsmWriter = RvAsmWriter::new();
+        let (a0, a1) = (portal_pc_asm_common::types::reg::Reg(10), portal_pc_asm_common::types::reg::Reg(11));
+
+        w.sext_w(&mut ctx, arch, &a0, &a1).unwrap();
+        w.sext_b(&mut ctx, arch, &a0, &a1).unwrap();
+        w.sext_h(&mut ctx, arch, &a0, &a1).unwrap();
+
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // addiw a0, a1, 0 / sext.b a0, a1 / sext.h a0, a1
+        assert_eq!(words, [0x0005_851B, 0x6045_9513, 0x6055_9513]);
+    }
+
+    #[test]
+    fn symbol_operands_record_relocations() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::mem::MemorySize;
+        let arch = crate::RiscV64Arch::default();
+        let mut ctx = ();
+        let mut w: RvAsmWriter = RvAsmWriter::new();
+        let a0 = portal_pc_asm_common::types::reg::Reg(10);
+
+        w.call(&mut ctx, arch, &ArgKind::sym("puts")).unwrap();
+        w.ld(&mut ctx, arch, &a0, &MemArgKind::sym("counter", MemorySize::_64)).unwrap();
+        w.j(&mut ctx, arch, &ArgKind::Sym { name: "exit", addend: 4 }).unwrap();
+
+        assert_eq!(w.relocations(), [
+            Relocation { offset: 0,  kind: RelocKind::CallPlt,    symbol: "puts",    addend: 0 },
+            Relocation { offset: 8,  kind: RelocKind::PcrelHi20,  symbol: "counter", addend: 0 },
+            Relocation { offset: 12, kind: RelocKind::PcrelLo12I, symbol: "counter", addend: 0 },
+            Relocation { offset: 16, kind: RelocKind::Jal,        symbol: "exit",    addend: 4 },
+        ]);
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // auipc ra, 0 / jalr ra, 0(ra) / auipc a0, 0 / ld a0, 0(a0) / j 0
+        assert_eq!(words, [0x0000_0097, 0x0000_80E7, 0x0000_0517, 0x0005_3503, 0x0000_006F]);
+    }
+
+    #[test]
+    fn mv_symbol_honors_pic() {
+        use crate::out::WriterCore as _;
+        let absolute = crate::RiscV64Arch::default();
+        let pic = crate::RiscV64Arch { pic: true, ..absolute };
+        let mut ctx = ();
+        let mut w: RvAsmWriter = RvAsmWriter::new();
+        let (a0, a1) = (portal_pc_asm_common::types::reg::Reg(10), portal_pc_asm_common::types::reg::Reg(11));
+
+        w.mv(&mut ctx, absolute, &a0, &ArgKind::sym("table")).unwrap();
+        w.mv(&mut ctx, pic, &a1, &ArgKind::sym("table")).unwrap();
+
+        assert_eq!(w.relocations(), [
+            Relocation { offset: 0,  kind: RelocKind::Hi20,       symbol: "table", addend: 0 },
+            Relocation { offset: 4,  kind: RelocKind::Lo12I,      symbol: "table", addend: 0 },
+            Relocation { offset: 8,  kind: RelocKind::PcrelHi20,  symbol: "table", addend: 0 },
+            Relocation { offset: 12, kind: RelocKind::PcrelLo12I, symbol: "table", addend: 0 },
+        ]);
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // lui a0, 0 / addi a0, a0, 0 / auipc a1, 0 / addi a1, a1, 0
+        assert_eq!(words, [0x0000_0537, 0x0005_0513, 0x0000_0597, 0x0005_8593]);
+    }
+
+    #[test]
+    fn fence_encodings() {
+        use crate::out::WriterCore as _;
+        use crate::FenceSet;
+        let arch = crate::RiscV64Arch::default();
+        let mut ctx = ();
+        let mut w: RvAsmWriter = RvAsmWriter::new();
+
+        w.fence(&mut ctx, arch, FenceSet::R, FenceSet::RW).unwrap();
+        w.fence(&mut ctx, arch, FenceSet::RW, FenceSet::W).unwrap();
+        w.pause(&mut ctx, arch).unwrap();
+
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // fence r, rw / fence rw, w / pause
+        assert_eq!(words, [0x0230_000F, 0x0310_000F, 0x0100_000F]);
+    }
+
+    #[test]
+    fn amoswap_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+        let arch = crate::RiscV64Arch::rv64gc();
+        let mut ctx = ();
+        let mut w: RvAsmWriter = RvAsmWriter::new();
+
+        w.amoswap_w(&mut ctx, arch, &Reg(5), &Reg(6), &Reg(10)).unwrap();
+        w.amoswap_d(&mut ctx, arch, &Reg(0), &Reg(0), &Reg(10)).unwrap();
+
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // amoswap.w.aqrl t0, t1, (a0) / amoswap.d.aqrl zero, zero, (a0)
+        assert_eq!(words, [0x0E65_22AF, 0x0E05_302F]);
+    }
+
+    #[test]
+    fn prefetch_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+        let arch = crate::RiscV64Arch::default().with_zicbop(true);
+        let mut ctx = ();
+        let mut w: RvAsmWriter = RvAsmWriter::new();
+        let mem = |base, disp| MemArgKind::Mem {
+            base: ArgKind::Reg { reg: Reg(base), size: MemorySize::_64 },
+            offset: None,
+            disp,
+            size: MemorySize::_64,
+            reg_class: crate::RegisterClass::Gpr,
+        };
+
+        w.prefetch_r(&mut ctx, arch, &mem(10, 0)).unwrap();
+        w.prefetch_w(&mut ctx, arch, &mem(10, 64)).unwrap();
+        w.prefetch_r(&mut ctx, arch, &mem(2, -32)).unwrap();
+
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // prefetch.r 0(a0) / prefetch.w 64(a0) / prefetch.r -32(sp)
+        assert_eq!(words, [0x0015_6013, 0x0435_6013, 0xFE11_6013]);
+    }
+
+    #[test]
+    fn cache_maintenance_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+        let arch = crate::RiscV64Arch::default().with_zicbom(true);
+        let mut ctx = ();
+        let mut w: RvAsmWriter = RvAsmWriter::new();
+        let mem = MemArgKind::Mem {
+            base: ArgKind::Reg { reg: Reg(10), size: MemorySize::_64 },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_64,
+            reg_class: crate::RegisterClass::Gpr,
+        };
+
+        w.cbo_clean(&mut ctx, arch, &mem).unwrap();
+        w.cbo_flush(&mut ctx, arch, &mem).unwrap();
+        w.cbo_inval(&mut ctx, arch, &mem).unwrap();
+        w.fence_i(&mut ctx, arch).unwrap();
+
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // cbo.clean 0(a0) / cbo.flush 0(a0) / cbo.inval 0(a0) / fence.i
+        assert_eq!(words, [0x0015_200F, 0x0025_200F, 0x0005_200F, 0x0000_100F]);
+    }
+
+    #[test]
+    fn csr_encodings() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+        let arch = crate::RiscV64Arch::default();
+        let mut ctx = ();
+        let mut w: RvAsmWriter = RvAsmWriter::new();
+
+        w.rdcycle(&mut ctx, arch, &Reg(10)).unwrap();
+        w.rdtime(&mut ctx, arch, &Reg(10)).unwrap();
+        w.csrrw(&mut ctx, arch, &Reg(0), crate::Csr::Frm, &Reg(11)).unwrap();
+        w.csrrci(&mut ctx, arch, &Reg(0), crate::Csr::Fflags, 31).unwrap();
+        w.csrrs(&mut ctx, arch, &Reg(10), crate::Csr::Custom(0x7C0), &Reg(0)).unwrap();
+
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // rdcycle a0 / rdtime a0 / csrrw zero, frm, a1 / csrrci zero, fflags, 31 / csrrs a0, 0x7c0, zero
+        assert_eq!(words, [0xC000_2573, 0xC010_2573, 0x0025_9073, 0x001F_F073, 0x7C00_2573]);
+    }
+
+    #[test]
+    fn nops_fill_halfwords_with_c_nop() {
+        use crate::out::WriterCore as _;
+        let gc = crate::RiscV64Arch::rv64gc();
+        let mut ctx = ();
+        let mut w: RvAsmWriter = RvAsmWriter::new();
+
+        w.nops(&mut ctx, gc, 6).unwrap();
+        assert!(w.nops(&mut ctx, crate::RiscV64Arch::rv64imfd(), 6).is_err());
+        w.align(&mut ctx, gc, 4).unwrap();
+
+        // C.NOP; NOP, then 10 bytes of padding: C.NOP; NOP; NOP
+        let (c_nop, nop) = ([0x01, 0x00], [0x13, 0x00, 0x00, 0x00]);
+        assert_eq!(w.into_bytes(), [&c_nop[..], &nop, &c_nop, &nop, &nop].concat());
+    }
+
+    #[test]
+    fn dd_label_is_relative_to_the_word() {
+        use crate::out::WriterCore as _;
+        let arch = crate::RiscV64Arch::default();
+        let mut ctx = ();
+        let mut w: RvAsmWriter<u32> = RvAsmWriter::new();
+
+        w.set_label(&mut ctx, arch, 1).unwrap();
+        w.nop(&mut ctx, arch).unwrap();
+        w.dd_label(&mut ctx, arch, 1).unwrap();
+        w.dd_label(&mut ctx, arch, 2).unwrap();
+        w.nop(&mut ctx, arch).unwrap();
+        w.set_label(&mut ctx, arch, 2).unwrap();
+
+        let words: Vec<i32> = w.into_bytes().chunks(4).map(|c| i32::from_le_bytes(c.try_into().unwrap())).collect();
+        // NOP; .4byte 1b - .; .4byte 2f - .; NOP
+        assert_eq!(words[1..3], [-4, 8]);
+    }
+
+    #[test]
+    fn literal_shift_amounts_use_immediate_forms() {
+        use crate::out::WriterCore as _;
+        use portal_pc_asm_common::types::reg::Reg;
+        let arch = crate::RiscV64Arch::default();
+        let mut ctx = ();
+        let mut w: RvAsmWriter = RvAsmWriter::new();
+        let (t5, t6) = (Reg(30), Reg(31));
+
+        w.sll(&mut ctx, arch, &t5, &t5, &ArgKind::Lit(7)).unwrap();
+        w.srl(&mut ctx, arch, &t5, &t6, &ArgKind::Lit(63)).unwrap();
+
+        let words: Vec<u32> = w.into_bytes().chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+        // slli t5, t5, 7; srli t5, t6, 63
+        assert_eq!(words, [0x007F_1F13, 0x03FF_DF13]);
+    }
 }
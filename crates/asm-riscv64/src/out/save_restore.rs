@@ -0,0 +1,132 @@
+//! Saving and restoring sets of registers on the stack.
+//!
+//! Prologues and epilogues that preserve callee-saved registers, and
+//! shims that spill around a call, need the same two sequences: store a set
+//! of registers keeping `sp` 16-byte aligned, and reload it later.
+//! [`SaveRestore`] emits them for any [`WriterCore`]:
+//!
+//! ```ignore
+//! let saved = [Reg(1), Reg(8), Reg(9)];   // ra, s0, s1
+//! writer.save_regs(ctx, cfg, &saved)?;    // addi sp, sp, -32; sd ra, 0(sp); sd s0, 8(sp); sd s1, 16(sp)
+//! // ...
+//! writer.restore_regs(ctx, cfg, &saved)?; // ld ra, 0(sp); ld s0, 8(sp); ld s1, 16(sp); addi sp, sp, 32
+//! ```
+//!
+//! RISC-V has no multi-register stores, so the area is allocated with a
+//! single `addi` and filled with one `sd` per register. With the C
+//! extension enabled these compress to `c.addi16sp` and `c.sdsp`/`c.ldsp`.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::WriterCore;
+use crate::out::arg::MemArgKind;
+use crate::{RegisterClass, RiscV64Arch};
+
+/// The stack pointer, `sp`.
+const SP: Reg = Reg(2);
+
+/// Returns the size, in bytes, of the area [`SaveRestore::save_regs`]
+/// allocates for `count` registers: 8 bytes each, rounded up to 16.
+pub fn save_area_size(count: usize) -> i32 {
+    (count * 8).next_multiple_of(16) as i32
+}
+
+/// `disp(sp)`.
+fn sp_slot(disp: i32) -> MemArgKind<Reg> {
+    MemArgKind::Mem {
+        base: SP,
+        offset: None,
+        disp,
+        size: MemorySize::_64,
+        reg_class: RegisterClass::Gpr,
+    }
+}
+
+/// Emits register save and restore sequences.
+///
+/// Implemented for every [`WriterCore`].
+pub trait SaveRestore<Context>: WriterCore<Context> {
+    /// Moves `sp` down by [`save_area_size`]`(regs.len())` and stores the
+    /// general-purpose registers `regs` there, first register lowest.
+    fn save_regs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        regs: &[Reg],
+    ) -> Result<(), Self::Error> {
+        if regs.is_empty() {
+            return Ok(());
+        }
+        self.addi(ctx, cfg, &SP, &SP, -save_area_size(regs.len()))?;
+        for (i, reg) in regs.iter().enumerate() {
+            self.sd(ctx, cfg, reg, &sp_slot(i as i32 * 8))?;
+        }
+        Ok(())
+    }
+
+    /// Restores registers saved by [`save_regs`](Self::save_regs) with the
+    /// same `regs`, moving `sp` back up.
+    fn restore_regs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        regs: &[Reg],
+    ) -> Result<(), Self::Error> {
+        if regs.is_empty() {
+            return Ok(());
+        }
+        for (i, reg) in regs.iter().enumerate() {
+            self.ld(ctx, cfg, reg, &sp_slot(i as i32 * 8))?;
+        }
+        self.addi(ctx, cfg, &SP, &SP, save_area_size(regs.len()))
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> SaveRestore<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_single_adjustment_around_stores() {
+        let cfg = RiscV64Arch::default().uncompressed();
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let regs = [Reg(1), Reg(8), Reg(9)];
+        out.save_regs(&mut (), cfg, &regs).unwrap();
+        out.restore_regs(&mut (), cfg, &regs).unwrap();
+        assert_eq!(
+            output,
+            "addi sp, sp, -32\nsd ra, 0(sp)\nsd s0, 8(sp)\nsd s1, 16(sp)\n\
+             ld ra, 0(sp)\nld s0, 8(sp)\nld s1, 16(sp)\naddi sp, sp, 32\n"
+        );
+    }
+
+    #[test]
+    fn test_empty_set_emits_nothing() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        out.save_regs(&mut (), RiscV64Arch::default(), &[]).unwrap();
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn test_compressed_stack_forms() {
+        let cfg = RiscV64Arch::rv64gc();
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let regs = [Reg(1), Reg(8), Reg(9), Reg(18), Reg(19)];
+        out.save_regs(&mut (), cfg, &regs).unwrap();
+        out.restore_regs(&mut (), cfg, &regs).unwrap();
+        assert_eq!(
+            output,
+            "c.addi16sp sp, -48\nc.sdsp ra, 0(sp)\nc.sdsp s0, 8(sp)\nc.sdsp s1, 16(sp)\n\
+             c.sdsp s2, 24(sp)\nc.sdsp s3, 32(sp)\n\
+             c.ldsp ra, 0(sp)\nc.ldsp s0, 8(sp)\nc.ldsp s1, 16(sp)\n\
+             c.ldsp s2, 24(sp)\nc.ldsp s3, 32(sp)\nc.addi16sp sp, 48\n"
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Branchless conditional selects and booleans from comparisons.
+//!
+//! RISC-V has no flags, so [`Select`] takes the two operands of the
+//! comparison along with the [`ConditionCode`], and builds the result from
+//! `slt`/`sltu` and bitwise operations instead of branching:
+//!
+//! ```ignore
+//! writer.set_bool(ctx, cfg, ConditionCode::GE, &t0, &a0, &a1)?;
+//! // slt t0, a0, a1; xori t0, t0, 1
+//! writer.select(ctx, cfg, ConditionCode::LT, &a0, &a1, &a2, &a0, &a1, &t6)?;
+//! // slt t6, a0, a1; sub t6, zero, t6
+//! // xor a2, a0, a1; and a2, a2, t6; xor a2, a2, a1
+//! ```
+//!
+//! The x86-64 and AArch64 crates provide the same helpers with
+//! `cmov`/`setcc` and `csel`/`cset`, testing flags instead.
+
+use portal_pc_asm_common::types::reg::Reg;
+
+use crate::out::WriterCore;
+use crate::out::arg::{ArgKind, MemArg, MemArgKind};
+use crate::{ConditionCode, RiscV64Arch};
+
+/// The zero register.
+const ZERO: Reg = Reg(0);
+
+/// Returns whether `arg` is the `zero` register.
+fn is_zero(arg: &(dyn MemArg + '_)) -> bool {
+    matches!(
+        arg.concrete_mem_kind(),
+        MemArgKind::NoMem(ArgKind::Reg { reg: ZERO, .. })
+    )
+}
+
+/// Emits branchless selects and booleans from register comparisons.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Select<Context>: WriterCore<Context> {
+    /// Sets `dest` to `if_true` if `cc` holds between `a` and `b`, and to
+    /// `if_false` otherwise.
+    ///
+    /// The values are blended through a mask built in `scratch`. `scratch`
+    /// must differ from `if_true` and `if_false`, and `dest` from
+    /// `if_false` and `scratch`.
+    #[allow(clippy::too_many_arguments)]
+    fn select(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        cc: ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        dest: &(dyn MemArg + '_),
+        if_true: &(dyn MemArg + '_),
+        if_false: &(dyn MemArg + '_),
+        scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.set_bool(ctx, cfg, cc, scratch, a, b)?;
+        // All ones when `cc` holds, zero otherwise.
+        self.sub(ctx, cfg, scratch, &ZERO, scratch)?;
+        self.xor(ctx, cfg, dest, if_true, if_false)?;
+        self.and(ctx, cfg, dest, dest, scratch)?;
+        self.xor(ctx, cfg, dest, dest, if_false)
+    }
+
+    /// Sets `dest` to 1 if `cc` holds between `a` and `b`, and to 0
+    /// otherwise.
+    ///
+    /// `dest` may be the same register as `a` or `b`.
+    fn set_bool(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        cc: ConditionCode,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let negate = match cc {
+            ConditionCode::EQ | ConditionCode::NE => {
+                // Nonzero exactly when the operands differ.
+                let diff: &(dyn MemArg + '_) = if is_zero(b) {
+                    a
+                } else if is_zero(a) {
+                    b
+                } else {
+                    self.xor(ctx, cfg, dest, a, b)?;
+                    dest
+                };
+                self.sltu(ctx, cfg, dest, &ZERO, diff)?;
+                cc == ConditionCode::EQ
+            }
+            ConditionCode::LT | ConditionCode::GE => {
+                self.slt(ctx, cfg, dest, a, b)?;
+                cc == ConditionCode::GE
+            }
+            ConditionCode::LTU | ConditionCode::GEU => {
+                self.sltu(ctx, cfg, dest, a, b)?;
+                cc == ConditionCode::GEU
+            }
+            ConditionCode::GT | ConditionCode::LE => {
+                self.slt(ctx, cfg, dest, b, a)?;
+                cc == ConditionCode::LE
+            }
+            ConditionCode::GTU | ConditionCode::LEU => {
+                self.sltu(ctx, cfg, dest, b, a)?;
+                cc == ConditionCode::LEU
+            }
+        };
+        if negate {
+            self.xori(ctx, cfg, dest, dest, 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Select<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_set_bool_swaps_and_negates() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = RiscV64Arch::default();
+        writer
+            .set_bool(
+                &mut (),
+                cfg,
+                ConditionCode::LEU,
+                &Reg(5),
+                &Reg(10),
+                &Reg(11),
+            )
+            .unwrap();
+        writer
+            .set_bool(&mut (), cfg, ConditionCode::EQ, &Reg(5), &Reg(10), &ZERO)
+            .unwrap();
+        assert_eq!(
+            output,
+            "sltu t0, a1, a0\nxori t0, t0, 1\nsltu t0, zero, a0\nxori t0, t0, 1\n"
+        );
+    }
+
+    #[test]
+    fn test_select_blends_through_a_mask() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        writer
+            .select(
+                &mut (),
+                RiscV64Arch::default(),
+                ConditionCode::NE,
+                &Reg(10),
+                &Reg(11),
+                &Reg(12),
+                &Reg(13),
+                &Reg(14),
+                &Reg(31),
+            )
+            .unwrap();
+        assert_eq!(
+            output,
+            "xor t6, a0, a1\nsltu t6, zero, t6\nsub t6, zero, t6\n\
+             xor a2, a3, a4\nand a2, a2, t6\nxor a2, a2, a4\n"
+        );
+    }
+}
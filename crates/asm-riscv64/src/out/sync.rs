@@ -0,0 +1,256 @@
+//! Spinlocks and Linux futex system calls.
+//!
+//! [`SpinLock`] emits the acquire and release sequences of a lock held in a
+//! 32-bit word, 0 when free and 1 when held. Acquiring swaps 1 in with
+//! `amoswap.w.aqrl` and, while the lock is held, spins on plain loads with
+//! the Zihintpause `pause` between them, only retrying the swap once the
+//! lock reads free:
+//!
+//! ```ignore
+//! // lock address in a0, t0 as scratch
+//! let lock = SpinLock::new(Reg(10), Reg(5), spin, attempt);
+//! lock.acquire(w, ctx, cfg)?;
+//! // j attempt
+//! // spin: pause; lw t0, 0(a0); bnez t0, spin
+//! // attempt: li t0, 1; amoswap.w.aqrl t0, t0, (a0); bnez t0, spin
+//! lock.release(w, ctx, cfg)?;
+//! // fence rw, w; sw zero, 0(a0)
+//! ```
+//!
+//! `amoswap.w` needs the A extension. `pause` is a `fence` that cores
+//! without Zihintpause execute as a no-op, so it needs no extension. A lock
+//! word is also a futex, so a runtime that would rather sleep than spin can
+//! block on it with [`SpinWait::futex_wait`] and wake waiters with
+//! [`SpinWait::futex_wake`]. The x86-64 and AArch64 crates provide the same
+//! helpers with `xchg` and `swpal`.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::arg::{ArgKind, MemArg, MemArgKind};
+use crate::out::{Writer, WriterCore};
+use crate::{ConditionCode, FenceSet, RegisterClass, RiscV64Arch};
+
+/// The Linux `futex` system call number.
+pub const SYS_FUTEX: u64 = 98;
+
+/// The `futex` operation that sleeps while the word holds an expected value,
+/// for a word not shared between processes.
+pub const FUTEX_WAIT_PRIVATE: u64 = 128;
+
+/// The `futex` operation that wakes sleepers on a word, for a word not
+/// shared between processes.
+pub const FUTEX_WAKE_PRIVATE: u64 = 129;
+
+/// The zero register.
+const ZERO: Reg = Reg(0);
+
+/// Returns the 32-bit word at the address in `base`.
+fn word_at(base: Reg) -> MemArgKind {
+    MemArgKind::Mem {
+        base: ArgKind::Reg {
+            reg: base,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp: 0,
+        size: MemorySize::_32,
+        reg_class: RegisterClass::Gpr,
+    }
+}
+
+/// Moves `src` into `dest`, unless it is already there.
+fn set<Context, W: WriterCore<Context> + ?Sized>(
+    w: &mut W,
+    ctx: &mut Context,
+    cfg: RiscV64Arch,
+    dest: Reg,
+    src: &(dyn MemArg + '_),
+) -> Result<(), W::Error> {
+    match src.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) if reg == dest => Ok(()),
+        MemArgKind::NoMem(ArgKind::Lit(val)) => w.li(ctx, cfg, &dest, val),
+        _ => w.mv(ctx, cfg, &dest, src),
+    }
+}
+
+/// Emits spin-wait hints and futex system calls.
+///
+/// Implemented for every [`WriterCore`].
+pub trait SpinWait<Context>: WriterCore<Context> {
+    /// Emits the Zihintpause `pause`, telling the core it is in a spin-wait
+    /// loop so it can slow down and save power.
+    fn spin_hint(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.pause(ctx, cfg)
+    }
+
+    /// Emits a `futex` system call that sleeps until woken, as long as the
+    /// 32-bit word at `addr` holds `val`, with no timeout.
+    ///
+    /// `addr` goes in `a0` and `val` in `a2`, in that order, so `val` must
+    /// not be in `a0` unless `addr` is too. The call returns 0 in `a0` once
+    /// woken and `-EAGAIN` if the word did not hold `val`, and clobbers
+    /// `a0` to `a3` and `a7`.
+    fn futex_wait(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        addr: &(dyn MemArg + '_),
+        val: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        set(self, ctx, cfg, Reg(10), addr)?;
+        set(self, ctx, cfg, Reg(12), val)?;
+        self.li(ctx, cfg, &Reg(11), FUTEX_WAIT_PRIVATE)?;
+        self.li(ctx, cfg, &Reg(13), 0)?;
+        self.li(ctx, cfg, &Reg(17), SYS_FUTEX)?;
+        self.ecall(ctx, cfg)
+    }
+
+    /// Emits a `futex` system call that wakes up to `count` threads sleeping
+    /// on the word at `addr`.
+    ///
+    /// `addr` goes in `a0`. The call returns the number of threads woken in
+    /// `a0`, and clobbers `a0` to `a2` and `a7`.
+    fn futex_wake(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        addr: &(dyn MemArg + '_),
+        count: u32,
+    ) -> Result<(), Self::Error> {
+        set(self, ctx, cfg, Reg(10), addr)?;
+        self.li(ctx, cfg, &Reg(12), count as u64)?;
+        self.li(ctx, cfg, &Reg(11), FUTEX_WAKE_PRIVATE)?;
+        self.li(ctx, cfg, &Reg(17), SYS_FUTEX)?;
+        self.ecall(ctx, cfg)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> SpinWait<Context> for W {}
+
+/// Emits test-and-test-and-set spinlock sequences.
+pub struct SpinLock<L> {
+    lock: Reg,
+    scratch: Reg,
+    spin: L,
+    attempt: L,
+}
+
+impl<L: Clone> SpinLock<L> {
+    /// Creates a lock on the word at the address in `lock`, using `scratch`
+    /// for the swapped value and labelling the spin loop `spin` and the swap
+    /// `attempt`.
+    pub fn new(lock: Reg, scratch: Reg, spin: L, attempt: L) -> Self {
+        Self {
+            lock,
+            scratch,
+            spin,
+            attempt,
+        }
+    }
+
+    /// Emits a loop that returns once it has set the lock word from 0 to 1.
+    ///
+    /// The swap has acquire-release ordering, so accesses after the sequence
+    /// cannot be performed before the lock is taken. The sequence needs the A
+    /// extension, clobbers `scratch`, and defines both labels, so it can be
+    /// emitted once per pair of labels.
+    pub fn acquire<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let scratch = self.scratch;
+        w.jal_label(ctx, cfg, &ZERO, self.attempt.clone())?;
+        w.set_label(ctx, cfg, self.spin.clone())?;
+        w.spin_hint(ctx, cfg)?;
+        w.lw(ctx, cfg, &scratch, &word_at(self.lock))?;
+        w.bcond_label(
+            ctx,
+            cfg,
+            ConditionCode::NE,
+            &scratch,
+            &ZERO,
+            self.spin.clone(),
+        )?;
+        w.set_label(ctx, cfg, self.attempt.clone())?;
+        w.li(ctx, cfg, &scratch, 1)?;
+        w.amoswap_w(ctx, cfg, &scratch, &scratch, &self.lock)?;
+        w.bcond_label(
+            ctx,
+            cfg,
+            ConditionCode::NE,
+            &scratch,
+            &ZERO,
+            self.spin.clone(),
+        )
+    }
+
+    /// Emits the release fence and store of 0 that free the lock.
+    pub fn release<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        w.fence(ctx, cfg, FenceSet::RW, FenceSet::W)?;
+        w.sw(ctx, cfg, &ZERO, &word_at(self.lock))
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_acquire_spins_on_loads_with_pause() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = RiscV64Arch::default().with_a(true);
+        let lock = SpinLock::new(Reg(10), Reg(5), ".Lspin", ".Lattempt");
+        lock.acquire(&mut writer, &mut (), cfg).unwrap();
+        lock.release(&mut writer, &mut (), cfg).unwrap();
+        assert_eq!(
+            output,
+            "jal zero, .Lattempt\n.Lspin:\npause\nlw t0, 0(a0)\n\
+             bne t0, zero, .Lspin\n.Lattempt:\nli t0, 1\namoswap.w.aqrl t0, t0, (a0)\n\
+             bne t0, zero, .Lspin\nfence rw, w\nsw zero, 0(a0)\n"
+        );
+    }
+
+    #[test]
+    fn test_futex_arguments_in_place_are_not_moved() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        let cfg = RiscV64Arch::default();
+        w.futex_wait(&mut (), cfg, &Reg(10), &Reg(12)).unwrap();
+        w.futex_wake(&mut (), cfg, &Reg(10), 1).unwrap();
+        assert_eq!(
+            output,
+            "li a1, 128\nli a3, 0\nli a7, 98\necall\n\
+             li a2, 1\nli a1, 129\nli a7, 98\necall\n"
+        );
+    }
+
+    #[test]
+    fn test_futex_wait_loads_an_immediate_value() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        let cfg = RiscV64Arch::default();
+        w.futex_wait(&mut (), cfg, &Reg(18), &ArgKind::Lit(1))
+            .unwrap();
+        assert_eq!(
+            output,
+            "mv a0, s2\nli a2, 1\n\
+             li a1, 128\nli a3, 0\nli a7, 98\necall\n"
+        );
+    }
+}
@@ -0,0 +1,2038 @@
+//! Emission to two writers at once.
+//!
+//! [`TeeWriter`] forwards every instruction, directive and label to two
+//! writers in turn, so one run of a code generator can produce an assembly
+//! listing together with the machine code, or feed a text writer and a
+//! counting pass, without generating the code twice:
+//!
+//! ```ignore
+//! let mut listing = String::new();
+//! let mut tee = TeeWriter::new(&mut listing as &mut dyn Write, RvAsmWriter::<u32>::new());
+//! generate(&mut tee, &mut ctx, cfg)?;
+//! let (_, binary) = tee.into_inner();
+//! let (code, labels) = binary.into_parts();
+//! ```
+//!
+//! The first writer gets each call before the second, and an error from
+//! either ends the call, so after a failure the second writer may be one
+//! call behind the first. Errors from the second writer are converted into
+//! the first writer's error type.
+
+use crate::out::arg::MemArg;
+use crate::out::{Writer, WriterCore};
+use crate::{BranchHint, ConditionCode, Csr, FenceSet, RiscV64Arch};
+
+/// Forwards every call to two writers.
+pub struct TeeWriter<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> TeeWriter<A, B> {
+    /// Creates a writer that forwards to `first`, then `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Returns the first writer.
+    pub fn first(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Returns the second writer.
+    pub fn second(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Returns both writers.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: WriterCore<Context>, B: WriterCore<Context>, Context> WriterCore<Context>
+    for TeeWriter<A, B>
+where
+    A::Error: From<B::Error>,
+{
+    type Error = A::Error;
+
+    fn ebreak(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.first.ebreak(ctx, cfg)?;
+        self.second.ebreak(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn unimp(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.first.unimp(ctx, cfg)?;
+        self.second.unimp(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn unreachable(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.first.unreachable(ctx, cfg)?;
+        self.second.unreachable(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn debug_break(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.first.debug_break(ctx, cfg)?;
+        self.second.debug_break(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn ecall(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.first.ecall(ctx, cfg)?;
+        self.second.ecall(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn lpad(&mut self, ctx: &mut Context, cfg: RiscV64Arch, label: u32) -> Result<(), Self::Error> {
+        self.first.lpad(ctx, cfg, label)?;
+        self.second.lpad(ctx, cfg, label)?;
+        Ok(())
+    }
+
+    fn sspush(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sspush(ctx, cfg, src)?;
+        self.second.sspush(ctx, cfg, src)?;
+        Ok(())
+    }
+
+    fn sspopchk(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sspopchk(ctx, cfg, src)?;
+        self.second.sspopchk(ctx, cfg, src)?;
+        Ok(())
+    }
+
+    fn fence(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        pred: FenceSet,
+        succ: FenceSet,
+    ) -> Result<(), Self::Error> {
+        self.first.fence(ctx, cfg, pred, succ)?;
+        self.second.fence(ctx, cfg, pred, succ)?;
+        Ok(())
+    }
+
+    fn fence_i(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.first.fence_i(ctx, cfg)?;
+        self.second.fence_i(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn pause(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.first.pause(ctx, cfg)?;
+        self.second.pause(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn prefetch_r(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.prefetch_r(ctx, cfg, mem)?;
+        self.second.prefetch_r(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn prefetch_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.prefetch_w(ctx, cfg, mem)?;
+        self.second.prefetch_w(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn cbo_clean(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cbo_clean(ctx, cfg, mem)?;
+        self.second.cbo_clean(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn cbo_flush(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cbo_flush(ctx, cfg, mem)?;
+        self.second.cbo_flush(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn cbo_inval(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cbo_inval(ctx, cfg, mem)?;
+        self.second.cbo_inval(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn amoswap_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.amoswap_w(ctx, cfg, dest, src, addr)?;
+        self.second.amoswap_w(ctx, cfg, dest, src, addr)?;
+        Ok(())
+    }
+
+    fn amoswap_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.amoswap_d(ctx, cfg, dest, src, addr)?;
+        self.second.amoswap_d(ctx, cfg, dest, src, addr)?;
+        Ok(())
+    }
+
+    fn mv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mv(ctx, cfg, dest, src)?;
+        self.second.mv(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn sub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sub(ctx, cfg, dest, a, b)?;
+        self.second.sub(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn add(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.add(ctx, cfg, dest, a, b)?;
+        self.second.add(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn addi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.addi(ctx, cfg, dest, src, imm)?;
+        self.second.addi(ctx, cfg, dest, src, imm)?;
+        Ok(())
+    }
+
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.first.add_imm(ctx, cfg, dest, a, imm)?;
+        self.second.add_imm(ctx, cfg, dest, a, imm)?;
+        Ok(())
+    }
+
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.first.sub_imm(ctx, cfg, dest, a, imm)?;
+        self.second.sub_imm(ctx, cfg, dest, a, imm)?;
+        Ok(())
+    }
+
+    fn sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sd(ctx, cfg, src, mem)?;
+        self.second.sd(ctx, cfg, src, mem)?;
+        Ok(())
+    }
+
+    fn ld(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ld(ctx, cfg, dest, mem)?;
+        self.second.ld(ctx, cfg, dest, mem)?;
+        Ok(())
+    }
+
+    fn lw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.lw(ctx, cfg, dest, mem)?;
+        self.second.lw(ctx, cfg, dest, mem)?;
+        Ok(())
+    }
+
+    fn lwu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.lwu(ctx, cfg, dest, mem)?;
+        self.second.lwu(ctx, cfg, dest, mem)?;
+        Ok(())
+    }
+
+    fn sw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sw(ctx, cfg, src, mem)?;
+        self.second.sw(ctx, cfg, src, mem)?;
+        Ok(())
+    }
+
+    fn lb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.lb(ctx, cfg, dest, mem)?;
+        self.second.lb(ctx, cfg, dest, mem)?;
+        Ok(())
+    }
+
+    fn lbu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.lbu(ctx, cfg, dest, mem)?;
+        self.second.lbu(ctx, cfg, dest, mem)?;
+        Ok(())
+    }
+
+    fn sb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sb(ctx, cfg, src, mem)?;
+        self.second.sb(ctx, cfg, src, mem)?;
+        Ok(())
+    }
+
+    fn lh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.lh(ctx, cfg, dest, mem)?;
+        self.second.lh(ctx, cfg, dest, mem)?;
+        Ok(())
+    }
+
+    fn lhu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.lhu(ctx, cfg, dest, mem)?;
+        self.second.lhu(ctx, cfg, dest, mem)?;
+        Ok(())
+    }
+
+    fn sh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sh(ctx, cfg, src, mem)?;
+        self.second.sh(ctx, cfg, src, mem)?;
+        Ok(())
+    }
+
+    fn jalr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        base: &(dyn MemArg + '_),
+        offset: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.jalr(ctx, cfg, dest, base, offset)?;
+        self.second.jalr(ctx, cfg, dest, base, offset)?;
+        Ok(())
+    }
+
+    fn jal(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.jal(ctx, cfg, dest, target)?;
+        self.second.jal(ctx, cfg, dest, target)?;
+        Ok(())
+    }
+
+    fn beq(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.beq(ctx, cfg, a, b, target)?;
+        self.second.beq(ctx, cfg, a, b, target)?;
+        Ok(())
+    }
+
+    fn bne(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.bne(ctx, cfg, a, b, target)?;
+        self.second.bne(ctx, cfg, a, b, target)?;
+        Ok(())
+    }
+
+    fn blt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.blt(ctx, cfg, a, b, target)?;
+        self.second.blt(ctx, cfg, a, b, target)?;
+        Ok(())
+    }
+
+    fn bge(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.bge(ctx, cfg, a, b, target)?;
+        self.second.bge(ctx, cfg, a, b, target)?;
+        Ok(())
+    }
+
+    fn bltu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.bltu(ctx, cfg, a, b, target)?;
+        self.second.bltu(ctx, cfg, a, b, target)?;
+        Ok(())
+    }
+
+    fn bgeu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.bgeu(ctx, cfg, a, b, target)?;
+        self.second.bgeu(ctx, cfg, a, b, target)?;
+        Ok(())
+    }
+
+    fn bcond(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        cond: ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.bcond(ctx, cfg, cond, a, b, target)?;
+        self.second.bcond(ctx, cfg, cond, a, b, target)?;
+        Ok(())
+    }
+
+    fn bcond_zero(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        cond: ConditionCode,
+        a: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.bcond_zero(ctx, cfg, cond, a, target)?;
+        self.second.bcond_zero(ctx, cfg, cond, a, target)?;
+        Ok(())
+    }
+
+    fn and(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.and(ctx, cfg, dest, a, b)?;
+        self.second.and(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn andi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.andi(ctx, cfg, dest, src, imm)?;
+        self.second.andi(ctx, cfg, dest, src, imm)?;
+        Ok(())
+    }
+
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.first.and_imm(ctx, cfg, dest, a, imm)?;
+        self.second.and_imm(ctx, cfg, dest, a, imm)?;
+        Ok(())
+    }
+
+    fn or(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.or(ctx, cfg, dest, a, b)?;
+        self.second.or(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn ori(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.ori(ctx, cfg, dest, src, imm)?;
+        self.second.ori(ctx, cfg, dest, src, imm)?;
+        Ok(())
+    }
+
+    fn or_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.first.or_imm(ctx, cfg, dest, a, imm)?;
+        self.second.or_imm(ctx, cfg, dest, a, imm)?;
+        Ok(())
+    }
+
+    fn xor(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.xor(ctx, cfg, dest, a, b)?;
+        self.second.xor(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn xori(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.xori(ctx, cfg, dest, src, imm)?;
+        self.second.xori(ctx, cfg, dest, src, imm)?;
+        Ok(())
+    }
+
+    fn xor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        self.first.xor_imm(ctx, cfg, dest, a, imm)?;
+        self.second.xor_imm(ctx, cfg, dest, a, imm)?;
+        Ok(())
+    }
+
+    fn sll(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sll(ctx, cfg, dest, a, b)?;
+        self.second.sll(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn srl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.srl(ctx, cfg, dest, a, b)?;
+        self.second.srl(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn sra(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sra(ctx, cfg, dest, a, b)?;
+        self.second.sra(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn rol(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.rol(ctx, cfg, dest, a, b)?;
+        self.second.rol(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ror(ctx, cfg, dest, a, b)?;
+        self.second.ror(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn cpop(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cpop(ctx, cfg, dest, src)?;
+        self.second.cpop(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn clz(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.clz(ctx, cfg, dest, src)?;
+        self.second.clz(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn ctz(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ctz(ctx, cfg, dest, src)?;
+        self.second.ctz(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn rev8(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.rev8(ctx, cfg, dest, src)?;
+        self.second.rev8(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn sext_b(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sext_b(ctx, cfg, dest, src)?;
+        self.second.sext_b(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn sext_h(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sext_h(ctx, cfg, dest, src)?;
+        self.second.sext_h(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn sext_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sext_w(ctx, cfg, dest, src)?;
+        self.second.sext_w(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn slt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.slt(ctx, cfg, dest, a, b)?;
+        self.second.slt(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn sltu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sltu(ctx, cfg, dest, a, b)?;
+        self.second.sltu(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn lui(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        imm: u32,
+    ) -> Result<(), Self::Error> {
+        self.first.lui(ctx, cfg, dest, imm)?;
+        self.second.lui(ctx, cfg, dest, imm)?;
+        Ok(())
+    }
+
+    fn auipc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        imm: u32,
+    ) -> Result<(), Self::Error> {
+        self.first.auipc(ctx, cfg, dest, imm)?;
+        self.second.auipc(ctx, cfg, dest, imm)?;
+        Ok(())
+    }
+
+    fn la(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.la(ctx, cfg, dest, src)?;
+        self.second.la(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn li(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        self.first.li(ctx, cfg, dest, val)?;
+        self.second.li(ctx, cfg, dest, val)?;
+        Ok(())
+    }
+
+    fn ret(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.first.ret(ctx, cfg)?;
+        self.second.ret(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn call(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.call(ctx, cfg, target)?;
+        self.second.call(ctx, cfg, target)?;
+        Ok(())
+    }
+
+    fn j(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.j(ctx, cfg, target)?;
+        self.second.j(ctx, cfg, target)?;
+        Ok(())
+    }
+
+    fn mul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mul(ctx, cfg, dest, a, b)?;
+        self.second.mul(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn mulh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mulh(ctx, cfg, dest, a, b)?;
+        self.second.mulh(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn mulhu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mulhu(ctx, cfg, dest, a, b)?;
+        self.second.mulhu(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn div(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.div(ctx, cfg, dest, a, b)?;
+        self.second.div(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn divu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.divu(ctx, cfg, dest, a, b)?;
+        self.second.divu(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn rem(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.rem(ctx, cfg, dest, a, b)?;
+        self.second.rem(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn remu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.remu(ctx, cfg, dest, a, b)?;
+        self.second.remu(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fld(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fld(ctx, cfg, dest, mem)?;
+        self.second.fld(ctx, cfg, dest, mem)?;
+        Ok(())
+    }
+
+    fn fsd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fsd(ctx, cfg, src, mem)?;
+        self.second.fsd(ctx, cfg, src, mem)?;
+        Ok(())
+    }
+
+    fn fadd_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fadd_d(ctx, cfg, dest, a, b)?;
+        self.second.fadd_d(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fsub_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fsub_d(ctx, cfg, dest, a, b)?;
+        self.second.fsub_d(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fmul_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmul_d(ctx, cfg, dest, a, b)?;
+        self.second.fmul_d(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fdiv_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fdiv_d(ctx, cfg, dest, a, b)?;
+        self.second.fdiv_d(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fmadd_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmadd_d(ctx, cfg, dest, a, b, c)?;
+        self.second.fmadd_d(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fmadd_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmadd_s(ctx, cfg, dest, a, b, c)?;
+        self.second.fmadd_s(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fmsub_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmsub_d(ctx, cfg, dest, a, b, c)?;
+        self.second.fmsub_d(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fmsub_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmsub_s(ctx, cfg, dest, a, b, c)?;
+        self.second.fmsub_s(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fnmsub_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fnmsub_d(ctx, cfg, dest, a, b, c)?;
+        self.second.fnmsub_d(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fnmsub_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fnmsub_s(ctx, cfg, dest, a, b, c)?;
+        self.second.fnmsub_s(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fnmadd_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fnmadd_d(ctx, cfg, dest, a, b, c)?;
+        self.second.fnmadd_d(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fnmadd_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fnmadd_s(ctx, cfg, dest, a, b, c)?;
+        self.second.fnmadd_s(ctx, cfg, dest, a, b, c)?;
+        Ok(())
+    }
+
+    fn fmov_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmov_d(ctx, cfg, dest, src)?;
+        self.second.fmov_d(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_d_l(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_d_l(ctx, cfg, dest, src)?;
+        self.second.fcvt_d_l(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_l_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_l_d(ctx, cfg, dest, src)?;
+        self.second.fcvt_l_d(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn feq_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.feq_d(ctx, cfg, dest, a, b)?;
+        self.second.feq_d(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn flt_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.flt_d(ctx, cfg, dest, a, b)?;
+        self.second.flt_d(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fle_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fle_d(ctx, cfg, dest, a, b)?;
+        self.second.fle_d(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fmin_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmin_d(ctx, cfg, dest, a, b)?;
+        self.second.fmin_d(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fmax_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmax_d(ctx, cfg, dest, a, b)?;
+        self.second.fmax_d(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fsqrt_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fsqrt_d(ctx, cfg, dest, src)?;
+        self.second.fsqrt_d(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fabs_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fabs_d(ctx, cfg, dest, src)?;
+        self.second.fabs_d(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fneg_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fneg_d(ctx, cfg, dest, src)?;
+        self.second.fneg_d(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_d_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_d_w(ctx, cfg, dest, src)?;
+        self.second.fcvt_d_w(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_d_wu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_d_wu(ctx, cfg, dest, src)?;
+        self.second.fcvt_d_wu(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_d_lu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_d_lu(ctx, cfg, dest, src)?;
+        self.second.fcvt_d_lu(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_w_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_w_d(ctx, cfg, dest, src)?;
+        self.second.fcvt_w_d(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_wu_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_wu_d(ctx, cfg, dest, src)?;
+        self.second.fcvt_wu_d(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_lu_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_lu_d(ctx, cfg, dest, src)?;
+        self.second.fcvt_lu_d(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn flw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.flw(ctx, cfg, dest, mem)?;
+        self.second.flw(ctx, cfg, dest, mem)?;
+        Ok(())
+    }
+
+    fn fsw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fsw(ctx, cfg, src, mem)?;
+        self.second.fsw(ctx, cfg, src, mem)?;
+        Ok(())
+    }
+
+    fn fadd_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fadd_s(ctx, cfg, dest, a, b)?;
+        self.second.fadd_s(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fsub_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fsub_s(ctx, cfg, dest, a, b)?;
+        self.second.fsub_s(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fmul_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmul_s(ctx, cfg, dest, a, b)?;
+        self.second.fmul_s(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fdiv_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fdiv_s(ctx, cfg, dest, a, b)?;
+        self.second.fdiv_s(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fmov_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmov_s(ctx, cfg, dest, src)?;
+        self.second.fmov_s(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_s_l(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_s_l(ctx, cfg, dest, src)?;
+        self.second.fcvt_s_l(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_l_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_l_s(ctx, cfg, dest, src)?;
+        self.second.fcvt_l_s(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn feq_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.feq_s(ctx, cfg, dest, a, b)?;
+        self.second.feq_s(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn flt_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.flt_s(ctx, cfg, dest, a, b)?;
+        self.second.flt_s(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fle_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fle_s(ctx, cfg, dest, a, b)?;
+        self.second.fle_s(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fmin_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmin_s(ctx, cfg, dest, a, b)?;
+        self.second.fmin_s(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fmax_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmax_s(ctx, cfg, dest, a, b)?;
+        self.second.fmax_s(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn fsqrt_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fsqrt_s(ctx, cfg, dest, src)?;
+        self.second.fsqrt_s(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fabs_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fabs_s(ctx, cfg, dest, src)?;
+        self.second.fabs_s(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fneg_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fneg_s(ctx, cfg, dest, src)?;
+        self.second.fneg_s(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_s_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_s_w(ctx, cfg, dest, src)?;
+        self.second.fcvt_s_w(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_s_wu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_s_wu(ctx, cfg, dest, src)?;
+        self.second.fcvt_s_wu(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_s_lu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_s_lu(ctx, cfg, dest, src)?;
+        self.second.fcvt_s_lu(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_w_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_w_s(ctx, cfg, dest, src)?;
+        self.second.fcvt_w_s(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_wu_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_wu_s(ctx, cfg, dest, src)?;
+        self.second.fcvt_wu_s(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_lu_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_lu_s(ctx, cfg, dest, src)?;
+        self.second.fcvt_lu_s(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_s_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_s_d(ctx, cfg, dest, src)?;
+        self.second.fcvt_s_d(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcvt_d_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcvt_d_s(ctx, cfg, dest, src)?;
+        self.second.fcvt_d_s(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fsrm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fsrm(ctx, cfg, src)?;
+        self.second.fsrm(ctx, cfg, src)?;
+        Ok(())
+    }
+
+    fn frrm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.frrm(ctx, cfg, dest)?;
+        self.second.frrm(ctx, cfg, dest)?;
+        Ok(())
+    }
+
+    fn csrrw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.csrrw(ctx, cfg, dest, csr, src)?;
+        self.second.csrrw(ctx, cfg, dest, csr, src)?;
+        Ok(())
+    }
+
+    fn csrrs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.csrrs(ctx, cfg, dest, csr, src)?;
+        self.second.csrrs(ctx, cfg, dest, csr, src)?;
+        Ok(())
+    }
+
+    fn csrrc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.csrrc(ctx, cfg, dest, csr, src)?;
+        self.second.csrrc(ctx, cfg, dest, csr, src)?;
+        Ok(())
+    }
+
+    fn csrrwi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
+    ) -> Result<(), Self::Error> {
+        self.first.csrrwi(ctx, cfg, dest, csr, imm)?;
+        self.second.csrrwi(ctx, cfg, dest, csr, imm)?;
+        Ok(())
+    }
+
+    fn csrrsi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
+    ) -> Result<(), Self::Error> {
+        self.first.csrrsi(ctx, cfg, dest, csr, imm)?;
+        self.second.csrrsi(ctx, cfg, dest, csr, imm)?;
+        Ok(())
+    }
+
+    fn csrrci(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
+    ) -> Result<(), Self::Error> {
+        self.first.csrrci(ctx, cfg, dest, csr, imm)?;
+        self.second.csrrci(ctx, cfg, dest, csr, imm)?;
+        Ok(())
+    }
+
+    fn rdcycle(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.rdcycle(ctx, cfg, dest)?;
+        self.second.rdcycle(ctx, cfg, dest)?;
+        Ok(())
+    }
+
+    fn rdtime(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.rdtime(ctx, cfg, dest)?;
+        self.second.rdtime(ctx, cfg, dest)?;
+        Ok(())
+    }
+
+    fn db(&mut self, ctx: &mut Context, cfg: RiscV64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.first.db(ctx, cfg, bytes)?;
+        self.second.db(ctx, cfg, bytes)?;
+        Ok(())
+    }
+
+    fn current_offset(&self) -> Option<usize> {
+        self.first
+            .current_offset()
+            .or_else(|| self.second.current_offset())
+    }
+
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        self.first.align_to(ctx, cfg, alignment)?;
+        self.second.align_to(ctx, cfg, alignment)?;
+        Ok(())
+    }
+
+    fn nop(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        self.first.nop(ctx, cfg)?;
+        self.second.nop(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn nops(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        self.first.nops(ctx, cfg, n_bytes)?;
+        self.second.nops(ctx, cfg, n_bytes)?;
+        Ok(())
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: RiscV64Arch, pow2: u32) -> Result<(), Self::Error> {
+        self.first.align(ctx, cfg, pow2)?;
+        self.second.align(ctx, cfg, pow2)?;
+        Ok(())
+    }
+}
+
+/// Labels are cloned for the first writer and moved into the second.
+impl<A: Writer<L, Context>, B: Writer<L, Context>, L: Clone, Context> Writer<L, Context>
+    for TeeWriter<A, B>
+where
+    A::Error: From<B::Error>,
+{
+    fn set_label(&mut self, ctx: &mut Context, cfg: RiscV64Arch, s: L) -> Result<(), Self::Error> {
+        self.first.set_label(ctx, cfg, s.clone())?;
+        self.second.set_label(ctx, cfg, s)?;
+        Ok(())
+    }
+
+    fn jal_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.first.jal_label(ctx, cfg, dest, label.clone())?;
+        self.second.jal_label(ctx, cfg, dest, label)?;
+        Ok(())
+    }
+
+    fn la_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.first.la_label(ctx, cfg, dest, label.clone())?;
+        self.second.la_label(ctx, cfg, dest, label)?;
+        Ok(())
+    }
+
+    fn bcond_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        cond: ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.first
+            .bcond_label(ctx, cfg, cond, a, b, label.clone())?;
+        self.second.bcond_label(ctx, cfg, cond, a, b, label)?;
+        Ok(())
+    }
+
+    fn bcond_label_hinted(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        cond: ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        label: L,
+        hint: BranchHint,
+    ) -> Result<(), Self::Error> {
+        self.first
+            .bcond_label_hinted(ctx, cfg, cond, a, b, label.clone(), hint)?;
+        self.second
+            .bcond_label_hinted(ctx, cfg, cond, a, b, label, hint)?;
+        Ok(())
+    }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.first.dd_label(ctx, cfg, label.clone())?;
+        self.second.dd_label(ctx, cfg, label)?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::{self, Write};
+    use portal_pc_asm_common::types::reg::Reg;
+
+    /// A text sink that rejects every write.
+    struct Full;
+
+    impl Write for Full {
+        fn write_str(&mut self, _: &str) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    #[test]
+    fn test_labelled_call_reaches_both_writers() {
+        let cfg = RiscV64Arch::default();
+        let (mut first, mut second) = (String::new(), String::new());
+        let mut tee = TeeWriter::new(&mut first as &mut dyn Write, &mut second as &mut dyn Write);
+        tee.jal_label(&mut (), cfg, &Reg(1), 1u32).unwrap();
+        tee.set_label(&mut (), cfg, 1u32).unwrap();
+        assert_eq!(first, "jal ra, 1\n1:\n");
+        assert_eq!(second, first);
+    }
+
+    #[test]
+    fn test_error_from_second_writer_is_returned() {
+        let cfg = RiscV64Arch::default();
+        let (mut first, mut full) = (String::new(), Full);
+        let mut tee = TeeWriter::new(&mut first as &mut dyn Write, &mut full as &mut dyn Write);
+        assert_eq!(tee.ebreak(&mut (), cfg), Err(fmt::Error));
+        assert_eq!(tee.set_label(&mut (), cfg, 1u32), Err(fmt::Error));
+        // The first writer took each call before the second failed it.
+        assert_eq!(first, "ebreak\n1:\n");
+    }
+
+    #[cfg(feature = "rv-asm-backend")]
+    #[test]
+    fn test_listing_and_machine_code_in_one_pass() {
+        use crate::out::rv_asm_backend::RvAsmWriter;
+
+        let cfg = RiscV64Arch::default();
+        let mut listing = String::new();
+        let mut tee = TeeWriter::new(&mut listing as &mut dyn Write, RvAsmWriter::<u32>::new());
+        tee.ebreak(&mut (), cfg).unwrap();
+        tee.set_label(&mut (), cfg, 1u32).unwrap();
+        tee.ebreak(&mut (), cfg).unwrap();
+        tee.jal_label(&mut (), cfg, &Reg(1), 1u32).unwrap();
+        assert_eq!(WriterCore::<()>::current_offset(&tee), Some(12));
+        let (_, binary) = tee.into_inner();
+        let (code, labels) = binary.into_parts();
+        assert_eq!(listing, "ebreak\n1:\nebreak\njal ra, 1\n");
+        assert_eq!(
+            code,
+            [
+                0x73, 0x00, 0x10, 0x00, 0x73, 0x00, 0x10, 0x00, 0xEF, 0xF0, 0xDF, 0xFF
+            ]
+        );
+        assert_eq!(labels[&1], 4);
+    }
+}
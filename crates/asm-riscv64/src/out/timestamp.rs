@@ -0,0 +1,53 @@
+//! Reads of the real-time counter.
+//!
+//! [`ReadTimestamp::read_timestamp`] reads a 64-bit counter that increases
+//! at a constant rate into a register, for timers and profiling:
+//!
+//! ```ignore
+//! writer.read_timestamp(ctx, cfg, &Reg(10))?;
+//! // rdtime a0
+//! ```
+//!
+//! The counter is the `time` CSR rather than `cycle`, which counts at a
+//! rate that changes with the clock and which Linux from 6.6 on does not
+//! let user code read by default. Its frequency is the platform's timebase, found in the device
+//! tree. The x86-64 crate reads the time-stamp counter with `rdtsc` and the
+//! AArch64 crate reads `cntvct_el0`.
+
+use crate::RiscV64Arch;
+use crate::out::WriterCore;
+use crate::out::arg::MemArg;
+
+/// Emits reads of the real-time counter.
+///
+/// Implemented for every [`WriterCore`].
+pub trait ReadTimestamp<Context>: WriterCore<Context> {
+    /// Reads the 64-bit `time` counter into the register `dest`.
+    fn read_timestamp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.rdtime(ctx, cfg, dest)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> ReadTimestamp<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_reads_the_time_counter() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        w.read_timestamp(&mut (), RiscV64Arch::default(), &Reg(10))
+            .unwrap();
+        assert_eq!(output, "rdtime a0\n");
+    }
+}
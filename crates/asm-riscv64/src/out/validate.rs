@@ -0,0 +1,2013 @@
+//! Instruction validation against the arch configuration.
+//!
+//! Writers emit whatever they are asked to: a text writer happily prints
+//! `mul` for a core without the M extension, and the mistake only surfaces
+//! when the assembler or the hardware rejects it. [`ValidatingWriter`] sits
+//! in front of another writer and checks each instruction against the
+//! [`RiscV64Arch`] it is emitted for, returning an [`EmitError`] instead of
+//! forwarding it:
+//!
+//! - `mul`, `div`, `rem` and their variants require the M extension;
+//! - double-precision loads, stores and arithmetic require the D extension;
+//! - rotates, `cpop`, `clz`, `ctz`, `rev8` and `sext.b`/`sext.h` require Zbb;
+//! - `sspush` and `sspopchk` require Zicfiss and take only `ra` or `t0`;
+//! - `amoswap.w` and `amoswap.d` require the A extension;
+//! - `prefetch.r` and `prefetch.w` require Zicbop and an offset that is a
+//!   multiple of 32;
+//! - `cbo.clean`, `cbo.flush` and `cbo.inval` require Zicbom and an offset
+//!   of 0;
+//! - CSR instructions that write may not target a read-only CSR such as
+//!   `cycle`, and CSR immediates must fit in five bits;
+//! - register operands must name one of the 32 registers.
+//!
+//! Place it below [`DesugaringWriter`](crate::desugar::DesugaringWriter) to
+//! check the lowered output, or above it to check what callers request.
+
+use core::ops::{Deref, DerefMut};
+
+use crate::out::arg::{ArgKind, MemArg, MemArgKind};
+use crate::out::error::EmitError;
+use crate::out::{Writer, WriterCore};
+use crate::{Csr, RiscV64Arch};
+
+/// Rejects instructions the arch configuration does not support.
+///
+/// Every instruction is checked against the `cfg` it is emitted with before
+/// being forwarded to the wrapped writer; rejected instructions are not
+/// forwarded.
+pub struct ValidatingWriter<W> {
+    writer: W,
+}
+
+impl<W> ValidatingWriter<W> {
+    /// Wraps `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W> Deref for ValidatingWriter<W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        &self.writer
+    }
+}
+
+impl<W> DerefMut for ValidatingWriter<W> {
+    fn deref_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+/// Returns the extension `mnemonic` needs, if `cfg` does not enable it.
+fn missing_extension(cfg: RiscV64Arch, mnemonic: &str) -> Option<&'static str> {
+    match mnemonic {
+        "mul" | "mulh" | "mulhu" | "div" | "divu" | "rem" | "remu" if !cfg.m_extension => Some("M"),
+        "fld" | "fsd" | "fadd_d" | "fsub_d" | "fmul_d" | "fdiv_d" | "fmov_d" | "fcvt_d_l"
+        | "fcvt_l_d" | "fmadd_d" | "fmsub_d" | "fnmsub_d" | "fnmadd_d" | "feq_d" | "flt_d"
+        | "fle_d" | "fmin_d" | "fmax_d" | "fsqrt_d" | "fabs_d" | "fneg_d" | "fcvt_d_w"
+        | "fcvt_d_wu" | "fcvt_d_lu" | "fcvt_w_d" | "fcvt_wu_d" | "fcvt_lu_d" | "fcvt_s_d"
+        | "fcvt_d_s"
+            if !cfg.d_extension =>
+        {
+            Some("D")
+        }
+        "flw" | "fsw" | "fadd_s" | "fsub_s" | "fmul_s" | "fdiv_s" | "fmov_s" | "fcvt_s_l"
+        | "fcvt_l_s" | "fmadd_s" | "fmsub_s" | "fnmsub_s" | "fnmadd_s" | "feq_s" | "flt_s"
+        | "fle_s" | "fmin_s" | "fmax_s" | "fsqrt_s" | "fabs_s" | "fneg_s" | "fcvt_s_w"
+        | "fcvt_s_wu" | "fcvt_s_lu" | "fcvt_w_s" | "fcvt_wu_s" | "fcvt_lu_s" | "fsrm" | "frrm"
+            if !cfg.f_extension =>
+        {
+            Some("F")
+        }
+        "rol" | "ror" | "cpop" | "clz" | "ctz" | "rev8" | "sext_b" | "sext_h" if !cfg.zbb => {
+            Some("Zbb")
+        }
+        "sspush" | "sspopchk" if !cfg.zicfiss => Some("Zicfiss"),
+        "amoswap_w" | "amoswap_d" if !cfg.a_extension => Some("A"),
+        "prefetch_r" | "prefetch_w" if !cfg.zicbop => Some("Zicbop"),
+        "cbo_clean" | "cbo_flush" | "cbo_inval" if !cfg.zicbom => Some("Zicbom"),
+        _ => None,
+    }
+}
+
+/// Checks that a register operand names one of the 32 registers.
+fn check_arg(mnemonic: &'static str, arg: ArgKind) -> Result<(), EmitError> {
+    match arg {
+        ArgKind::Reg { reg, .. } if reg.0 >= 32 => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "register number out of range",
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a shadow-stack operand is one of the link registers, RA or T0.
+fn check_link_reg(mnemonic: &'static str, op: &(dyn MemArg + '_)) -> Result<(), EmitError> {
+    match op.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) if reg.0 == 1 || reg.0 == 5 => Ok(()),
+        _ => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "operand must be ra or t0",
+        }),
+    }
+}
+
+/// Checks that a prefetch address has an offset Zicbop can encode: a
+/// multiple of 32, since the low five bits of the field select the prefetch.
+fn check_prefetch_offset(mnemonic: &'static str, mem: &(dyn MemArg + '_)) -> Result<(), EmitError> {
+    match mem.concrete_mem_kind() {
+        MemArgKind::Mem { disp, .. } if disp % 32 != 0 => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "prefetch offset must be a multiple of 32",
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a Zicbom address has no offset, since the instructions have
+/// no offset field.
+fn check_cbo_offset(mnemonic: &'static str, mem: &(dyn MemArg + '_)) -> Result<(), EmitError> {
+    match mem.concrete_mem_kind() {
+        MemArgKind::Mem { disp, .. } if disp != 0 => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "cache-block operation offset must be 0",
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that an instruction writing `csr` does not target a read-only CSR,
+/// which would trap.
+fn check_csr_write(mnemonic: &'static str, csr: Csr, writes: bool) -> Result<(), EmitError> {
+    if writes && csr.is_read_only() {
+        return Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "CSR is read-only",
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a CSR immediate fits the 5-bit unsigned field.
+fn check_csr_imm(mnemonic: &'static str, imm: u8) -> Result<(), EmitError> {
+    if imm >= 32 {
+        return Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "CSR immediate must be in 0..=31",
+        });
+    }
+    Ok(())
+}
+
+/// Returns whether `src` is a register other than `x0`, so that a set or
+/// clear through it writes the CSR.
+fn is_nonzero_reg(src: &(dyn MemArg + '_)) -> bool {
+    !matches!(src.concrete_mem_kind(), MemArgKind::NoMem(ArgKind::Reg { reg, .. }) if reg.0 == 0)
+}
+
+/// Checks one instruction against `cfg`.
+///
+/// `mnemonic` is the name of the [`WriterCore`] or [`Writer`] method.
+pub fn check(
+    cfg: RiscV64Arch,
+    mnemonic: &'static str,
+    operands: &[&(dyn MemArg + '_)],
+) -> Result<(), EmitError> {
+    if let Some(extension) = missing_extension(cfg, mnemonic) {
+        return Err(EmitError::ExtensionNotEnabled {
+            mnemonic,
+            extension,
+        });
+    }
+    for op in operands {
+        match op.concrete_mem_kind() {
+            MemArgKind::NoMem(arg) => check_arg(mnemonic, arg)?,
+            MemArgKind::Mem { base, offset, .. } => {
+                check_arg(mnemonic, base)?;
+                if let Some((index, _)) = offset {
+                    check_arg(mnemonic, index)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+impl<W: WriterCore<Context>, Context> WriterCore<Context> for ValidatingWriter<W>
+where
+    W::Error: From<EmitError>,
+{
+    type Error = W::Error;
+
+    fn ebreak(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        check(cfg, "ebreak", &[])?;
+        self.writer.ebreak(ctx, cfg)
+    }
+
+    fn unimp(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        check(cfg, "unimp", &[])?;
+        self.writer.unimp(ctx, cfg)
+    }
+
+    fn ecall(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        check(cfg, "ecall", &[])?;
+        self.writer.ecall(ctx, cfg)
+    }
+
+    fn lpad(&mut self, ctx: &mut Context, cfg: RiscV64Arch, label: u32) -> Result<(), Self::Error> {
+        check(cfg, "lpad", &[])?;
+        if label >= 1 << 20 {
+            return Err(EmitError::InvalidOperands {
+                mnemonic: "lpad",
+                reason: "label does not fit in 20 bits",
+            });
+        }
+        self.writer.lpad(ctx, cfg, label)
+    }
+
+    fn sspush(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sspush", &[src])?;
+        check_link_reg("sspush", src)?;
+        self.writer.sspush(ctx, cfg, src)
+    }
+
+    fn sspopchk(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sspopchk", &[src])?;
+        check_link_reg("sspopchk", src)?;
+        self.writer.sspopchk(ctx, cfg, src)
+    }
+
+    fn fence(&mut self, ctx: &mut Context, cfg: RiscV64Arch, pred: crate::FenceSet, succ: crate::FenceSet) -> Result<(), Self::Error> {
+        check(cfg, "fence", &[])?;
+        self.writer.fence(ctx, cfg, pred, succ)
+    }
+
+    fn fence_i(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        check(cfg, "fence_i", &[])?;
+        self.writer.fence_i(ctx, cfg)
+    }
+
+    fn pause(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        check(cfg, "pause", &[])?;
+        self.writer.pause(ctx, cfg)
+    }
+
+    fn prefetch_r(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "prefetch_r", &[mem])?;
+        check_prefetch_offset("prefetch_r", mem)?;
+        self.writer.prefetch_r(ctx, cfg, mem)
+    }
+
+    fn prefetch_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "prefetch_w", &[mem])?;
+        check_prefetch_offset("prefetch_w", mem)?;
+        self.writer.prefetch_w(ctx, cfg, mem)
+    }
+
+    fn cbo_clean(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cbo_clean", &[mem])?;
+        check_cbo_offset("cbo_clean", mem)?;
+        self.writer.cbo_clean(ctx, cfg, mem)
+    }
+
+    fn cbo_flush(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cbo_flush", &[mem])?;
+        check_cbo_offset("cbo_flush", mem)?;
+        self.writer.cbo_flush(ctx, cfg, mem)
+    }
+
+    fn cbo_inval(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cbo_inval", &[mem])?;
+        check_cbo_offset("cbo_inval", mem)?;
+        self.writer.cbo_inval(ctx, cfg, mem)
+    }
+
+    fn amoswap_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "amoswap_w", &[dest, src, addr])?;
+        self.writer.amoswap_w(ctx, cfg, dest, src, addr)
+    }
+
+    fn amoswap_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        addr: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "amoswap_d", &[dest, src, addr])?;
+        self.writer.amoswap_d(ctx, cfg, dest, src, addr)
+    }
+
+    fn mv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mv", &[dest, src])?;
+        self.writer.mv(ctx, cfg, dest, src)
+    }
+
+    fn sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sd", &[src, mem])?;
+        self.writer.sd(ctx, cfg, src, mem)
+    }
+
+    fn ld(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ld", &[dest, mem])?;
+        self.writer.ld(ctx, cfg, dest, mem)
+    }
+
+    fn lw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lw", &[dest, mem])?;
+        self.writer.lw(ctx, cfg, dest, mem)
+    }
+
+    fn lwu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lwu", &[dest, mem])?;
+        self.writer.lwu(ctx, cfg, dest, mem)
+    }
+
+    fn lh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lh", &[dest, mem])?;
+        self.writer.lh(ctx, cfg, dest, mem)
+    }
+
+    fn lhu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lhu", &[dest, mem])?;
+        self.writer.lhu(ctx, cfg, dest, mem)
+    }
+
+    fn lb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lb", &[dest, mem])?;
+        self.writer.lb(ctx, cfg, dest, mem)
+    }
+
+    fn lbu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lbu", &[dest, mem])?;
+        self.writer.lbu(ctx, cfg, dest, mem)
+    }
+
+    fn add(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "add", &[dest, a, b])?;
+        self.writer.add(ctx, cfg, dest, a, b)
+    }
+
+    fn sub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sub", &[dest, a, b])?;
+        self.writer.sub(ctx, cfg, dest, a, b)
+    }
+
+    fn mul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mul", &[dest, a, b])?;
+        self.writer.mul(ctx, cfg, dest, a, b)
+    }
+
+    fn mulh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mulh", &[dest, a, b])?;
+        self.writer.mulh(ctx, cfg, dest, a, b)
+    }
+
+    fn mulhu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mulhu", &[dest, a, b])?;
+        self.writer.mulhu(ctx, cfg, dest, a, b)
+    }
+
+    fn div(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "div", &[dest, a, b])?;
+        self.writer.div(ctx, cfg, dest, a, b)
+    }
+
+    fn and(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "and", &[dest, a, b])?;
+        self.writer.and(ctx, cfg, dest, a, b)
+    }
+
+    fn or(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "or", &[dest, a, b])?;
+        self.writer.or(ctx, cfg, dest, a, b)
+    }
+
+    fn xor(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "xor", &[dest, a, b])?;
+        self.writer.xor(ctx, cfg, dest, a, b)
+    }
+
+    fn addi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "addi", &[dest, src])?;
+        self.writer.addi(ctx, cfg, dest, src, imm)
+    }
+
+    fn andi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "andi", &[dest, src])?;
+        self.writer.andi(ctx, cfg, dest, src, imm)
+    }
+
+    fn ori(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ori", &[dest, src])?;
+        self.writer.ori(ctx, cfg, dest, src, imm)
+    }
+
+    fn xori(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "xori", &[dest, src])?;
+        self.writer.xori(ctx, cfg, dest, src, imm)
+    }
+
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "add_imm", &[dest, a])?;
+        self.writer.add_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sub_imm", &[dest, a])?;
+        self.writer.sub_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "and_imm", &[dest, a])?;
+        self.writer.and_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn or_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "or_imm", &[dest, a])?;
+        self.writer.or_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn xor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "xor_imm", &[dest, a])?;
+        self.writer.xor_imm(ctx, cfg, dest, a, imm)
+    }
+
+    fn sll(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sll", &[dest, a, b])?;
+        self.writer.sll(ctx, cfg, dest, a, b)
+    }
+
+    fn srl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "srl", &[dest, a, b])?;
+        self.writer.srl(ctx, cfg, dest, a, b)
+    }
+
+    fn sra(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sra", &[dest, a, b])?;
+        self.writer.sra(ctx, cfg, dest, a, b)
+    }
+
+    fn rol(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "rol", &[dest, a, b])?;
+        self.writer.rol(ctx, cfg, dest, a, b)
+    }
+
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ror", &[dest, a, b])?;
+        self.writer.ror(ctx, cfg, dest, a, b)
+    }
+
+    fn cpop(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cpop", &[dest, src])?;
+        self.writer.cpop(ctx, cfg, dest, src)
+    }
+
+    fn clz(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "clz", &[dest, src])?;
+        self.writer.clz(ctx, cfg, dest, src)
+    }
+
+    fn ctz(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ctz", &[dest, src])?;
+        self.writer.ctz(ctx, cfg, dest, src)
+    }
+
+    fn rev8(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "rev8", &[dest, src])?;
+        self.writer.rev8(ctx, cfg, dest, src)
+    }
+
+    fn sext_b(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sext_b", &[dest, src])?;
+        self.writer.sext_b(ctx, cfg, dest, src)
+    }
+
+    fn sext_h(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sext_h", &[dest, src])?;
+        self.writer.sext_h(ctx, cfg, dest, src)
+    }
+
+    fn sext_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sext_w", &[dest, src])?;
+        self.writer.sext_w(ctx, cfg, dest, src)
+    }
+
+    fn ret(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        check(cfg, "ret", &[])?;
+        self.writer.ret(ctx, cfg)
+    }
+
+    fn li(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "li", &[dest])?;
+        self.writer.li(ctx, cfg, dest, val)
+    }
+
+    fn auipc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        imm: u32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "auipc", &[dest])?;
+        self.writer.auipc(ctx, cfg, dest, imm)
+    }
+
+    fn la(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "la", &[dest, src])?;
+        self.writer.la(ctx, cfg, dest, src)
+    }
+
+    fn beq(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "beq", &[a, b, target])?;
+        self.writer.beq(ctx, cfg, a, b, target)
+    }
+
+    fn bge(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bge", &[a, b, target])?;
+        self.writer.bge(ctx, cfg, a, b, target)
+    }
+
+    fn bgeu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bgeu", &[a, b, target])?;
+        self.writer.bgeu(ctx, cfg, a, b, target)
+    }
+
+    fn blt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "blt", &[a, b, target])?;
+        self.writer.blt(ctx, cfg, a, b, target)
+    }
+
+    fn bltu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bltu", &[a, b, target])?;
+        self.writer.bltu(ctx, cfg, a, b, target)
+    }
+
+    fn bne(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bne", &[a, b, target])?;
+        self.writer.bne(ctx, cfg, a, b, target)
+    }
+
+    fn call(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "call", &[target])?;
+        self.writer.call(ctx, cfg, target)
+    }
+
+    fn divu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "divu", &[dest, a, b])?;
+        self.writer.divu(ctx, cfg, dest, a, b)
+    }
+
+    fn fadd_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fadd_d", &[dest, a, b])?;
+        self.writer.fadd_d(ctx, cfg, dest, a, b)
+    }
+
+    fn fcvt_d_l(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_d_l", &[dest, src])?;
+        self.writer.fcvt_d_l(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_l_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_l_d", &[dest, src])?;
+        self.writer.fcvt_l_d(ctx, cfg, dest, src)
+    }
+
+    fn feq_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "feq_d", &[dest, a, b])?;
+        self.writer.feq_d(ctx, cfg, dest, a, b)
+    }
+
+    fn flt_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "flt_d", &[dest, a, b])?;
+        self.writer.flt_d(ctx, cfg, dest, a, b)
+    }
+
+    fn fle_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fle_d", &[dest, a, b])?;
+        self.writer.fle_d(ctx, cfg, dest, a, b)
+    }
+
+    fn fmin_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmin_d", &[dest, a, b])?;
+        self.writer.fmin_d(ctx, cfg, dest, a, b)
+    }
+
+    fn fmax_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmax_d", &[dest, a, b])?;
+        self.writer.fmax_d(ctx, cfg, dest, a, b)
+    }
+
+    fn fsqrt_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fsqrt_d", &[dest, src])?;
+        self.writer.fsqrt_d(ctx, cfg, dest, src)
+    }
+
+    fn fabs_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fabs_d", &[dest, src])?;
+        self.writer.fabs_d(ctx, cfg, dest, src)
+    }
+
+    fn fneg_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fneg_d", &[dest, src])?;
+        self.writer.fneg_d(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_d_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_d_w", &[dest, src])?;
+        self.writer.fcvt_d_w(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_d_wu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_d_wu", &[dest, src])?;
+        self.writer.fcvt_d_wu(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_d_lu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_d_lu", &[dest, src])?;
+        self.writer.fcvt_d_lu(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_w_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_w_d", &[dest, src])?;
+        self.writer.fcvt_w_d(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_wu_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_wu_d", &[dest, src])?;
+        self.writer.fcvt_wu_d(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_lu_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_lu_d", &[dest, src])?;
+        self.writer.fcvt_lu_d(ctx, cfg, dest, src)
+    }
+
+    fn flw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "flw", &[dest, mem])?;
+        self.writer.flw(ctx, cfg, dest, mem)
+    }
+
+    fn fsw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fsw", &[src, mem])?;
+        self.writer.fsw(ctx, cfg, src, mem)
+    }
+
+    fn fadd_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fadd_s", &[dest, a, b])?;
+        self.writer.fadd_s(ctx, cfg, dest, a, b)
+    }
+
+    fn fsub_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fsub_s", &[dest, a, b])?;
+        self.writer.fsub_s(ctx, cfg, dest, a, b)
+    }
+
+    fn fmul_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmul_s", &[dest, a, b])?;
+        self.writer.fmul_s(ctx, cfg, dest, a, b)
+    }
+
+    fn fdiv_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fdiv_s", &[dest, a, b])?;
+        self.writer.fdiv_s(ctx, cfg, dest, a, b)
+    }
+
+    fn fmov_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmov_s", &[dest, src])?;
+        self.writer.fmov_s(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_s_l(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_s_l", &[dest, src])?;
+        self.writer.fcvt_s_l(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_l_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_l_s", &[dest, src])?;
+        self.writer.fcvt_l_s(ctx, cfg, dest, src)
+    }
+
+    fn feq_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "feq_s", &[dest, a, b])?;
+        self.writer.feq_s(ctx, cfg, dest, a, b)
+    }
+
+    fn flt_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "flt_s", &[dest, a, b])?;
+        self.writer.flt_s(ctx, cfg, dest, a, b)
+    }
+
+    fn fle_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fle_s", &[dest, a, b])?;
+        self.writer.fle_s(ctx, cfg, dest, a, b)
+    }
+
+    fn fmin_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmin_s", &[dest, a, b])?;
+        self.writer.fmin_s(ctx, cfg, dest, a, b)
+    }
+
+    fn fmax_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmax_s", &[dest, a, b])?;
+        self.writer.fmax_s(ctx, cfg, dest, a, b)
+    }
+
+    fn fsqrt_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fsqrt_s", &[dest, src])?;
+        self.writer.fsqrt_s(ctx, cfg, dest, src)
+    }
+
+    fn fabs_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fabs_s", &[dest, src])?;
+        self.writer.fabs_s(ctx, cfg, dest, src)
+    }
+
+    fn fneg_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fneg_s", &[dest, src])?;
+        self.writer.fneg_s(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_s_w(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_s_w", &[dest, src])?;
+        self.writer.fcvt_s_w(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_s_wu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_s_wu", &[dest, src])?;
+        self.writer.fcvt_s_wu(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_s_lu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_s_lu", &[dest, src])?;
+        self.writer.fcvt_s_lu(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_w_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_w_s", &[dest, src])?;
+        self.writer.fcvt_w_s(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_wu_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_wu_s", &[dest, src])?;
+        self.writer.fcvt_wu_s(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_lu_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_lu_s", &[dest, src])?;
+        self.writer.fcvt_lu_s(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_s_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_s_d", &[dest, src])?;
+        self.writer.fcvt_s_d(ctx, cfg, dest, src)
+    }
+
+    fn fcvt_d_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcvt_d_s", &[dest, src])?;
+        self.writer.fcvt_d_s(ctx, cfg, dest, src)
+    }
+
+    fn fsrm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fsrm", &[src])?;
+        self.writer.fsrm(ctx, cfg, src)
+    }
+
+    fn frrm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "frrm", &[dest])?;
+        self.writer.frrm(ctx, cfg, dest)
+    }
+
+    fn csrrw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "csrrw", &[dest, src])?;
+        check_csr_write("csrrw", csr, true)?;
+        self.writer.csrrw(ctx, cfg, dest, csr, src)
+    }
+
+    fn csrrs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "csrrs", &[dest, src])?;
+        check_csr_write("csrrs", csr, is_nonzero_reg(src))?;
+        self.writer.csrrs(ctx, cfg, dest, csr, src)
+    }
+
+    fn csrrc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "csrrc", &[dest, src])?;
+        check_csr_write("csrrc", csr, is_nonzero_reg(src))?;
+        self.writer.csrrc(ctx, cfg, dest, csr, src)
+    }
+
+    fn csrrwi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "csrrwi", &[dest])?;
+        check_csr_imm("csrrwi", imm)?;
+        check_csr_write("csrrwi", csr, true)?;
+        self.writer.csrrwi(ctx, cfg, dest, csr, imm)
+    }
+
+    fn csrrsi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "csrrsi", &[dest])?;
+        check_csr_imm("csrrsi", imm)?;
+        check_csr_write("csrrsi", csr, imm != 0)?;
+        self.writer.csrrsi(ctx, cfg, dest, csr, imm)
+    }
+
+    fn csrrci(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        csr: Csr,
+        imm: u8,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "csrrci", &[dest])?;
+        check_csr_imm("csrrci", imm)?;
+        check_csr_write("csrrci", csr, imm != 0)?;
+        self.writer.csrrci(ctx, cfg, dest, csr, imm)
+    }
+
+    fn rdcycle(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "rdcycle", &[dest])?;
+        self.writer.rdcycle(ctx, cfg, dest)
+    }
+
+    fn rdtime(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "rdtime", &[dest])?;
+        self.writer.rdtime(ctx, cfg, dest)
+    }
+
+    fn fdiv_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fdiv_d", &[dest, a, b])?;
+        self.writer.fdiv_d(ctx, cfg, dest, a, b)
+    }
+
+    fn fmadd_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmadd_d", &[dest, a, b, c])?;
+        self.writer.fmadd_d(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fmadd_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmadd_s", &[dest, a, b, c])?;
+        self.writer.fmadd_s(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fmsub_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmsub_d", &[dest, a, b, c])?;
+        self.writer.fmsub_d(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fmsub_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmsub_s", &[dest, a, b, c])?;
+        self.writer.fmsub_s(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmsub_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fnmsub_d", &[dest, a, b, c])?;
+        self.writer.fnmsub_d(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmsub_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fnmsub_s", &[dest, a, b, c])?;
+        self.writer.fnmsub_s(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmadd_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fnmadd_d", &[dest, a, b, c])?;
+        self.writer.fnmadd_d(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fnmadd_s(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        c: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fnmadd_s", &[dest, a, b, c])?;
+        self.writer.fnmadd_s(ctx, cfg, dest, a, b, c)
+    }
+
+    fn fld(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fld", &[dest, mem])?;
+        self.writer.fld(ctx, cfg, dest, mem)
+    }
+
+    fn fmov_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmov_d", &[dest, src])?;
+        self.writer.fmov_d(ctx, cfg, dest, src)
+    }
+
+    fn fmul_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmul_d", &[dest, a, b])?;
+        self.writer.fmul_d(ctx, cfg, dest, a, b)
+    }
+
+    fn fsd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fsd", &[src, mem])?;
+        self.writer.fsd(ctx, cfg, src, mem)
+    }
+
+    fn fsub_d(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fsub_d", &[dest, a, b])?;
+        self.writer.fsub_d(ctx, cfg, dest, a, b)
+    }
+
+    fn j(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "j", &[target])?;
+        self.writer.j(ctx, cfg, target)
+    }
+
+    fn jal(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        target: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "jal", &[dest, target])?;
+        self.writer.jal(ctx, cfg, dest, target)
+    }
+
+    fn jalr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        base: &(dyn MemArg + '_),
+        offset: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "jalr", &[dest, base])?;
+        self.writer.jalr(ctx, cfg, dest, base, offset)
+    }
+
+    fn lui(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        imm: u32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lui", &[dest])?;
+        self.writer.lui(ctx, cfg, dest, imm)
+    }
+
+    fn rem(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "rem", &[dest, a, b])?;
+        self.writer.rem(ctx, cfg, dest, a, b)
+    }
+
+    fn remu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "remu", &[dest, a, b])?;
+        self.writer.remu(ctx, cfg, dest, a, b)
+    }
+
+    fn sb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sb", &[src, mem])?;
+        self.writer.sb(ctx, cfg, src, mem)
+    }
+
+    fn sh(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sh", &[src, mem])?;
+        self.writer.sh(ctx, cfg, src, mem)
+    }
+
+    fn slt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "slt", &[dest, a, b])?;
+        self.writer.slt(ctx, cfg, dest, a, b)
+    }
+
+    fn sltu(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sltu", &[dest, a, b])?;
+        self.writer.sltu(ctx, cfg, dest, a, b)
+    }
+
+    fn sw(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sw", &[src, mem])?;
+        self.writer.sw(ctx, cfg, src, mem)
+    }
+
+    fn db(&mut self, ctx: &mut Context, cfg: RiscV64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        check(cfg, "db", &[])?;
+        self.writer.db(ctx, cfg, bytes)
+    }
+
+    fn current_offset(&self) -> Option<usize> {
+        self.writer.current_offset()
+    }
+
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "align_to", &[])?;
+        self.writer.align_to(ctx, cfg, alignment)
+    }
+
+    fn nop(&mut self, ctx: &mut Context, cfg: RiscV64Arch) -> Result<(), Self::Error> {
+        check(cfg, "nop", &[])?;
+        self.writer.nop(ctx, cfg)
+    }
+
+    fn nops(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "nops", &[])?;
+        self.writer.nops(ctx, cfg, n_bytes)
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: RiscV64Arch, pow2: u32) -> Result<(), Self::Error> {
+        check(cfg, "align", &[])?;
+        self.writer.align(ctx, cfg, pow2)
+    }
+}
+
+impl<W: Writer<L, Context>, L, Context> Writer<L, Context> for ValidatingWriter<W>
+where
+    W::Error: From<EmitError>,
+{
+    fn set_label(&mut self, ctx: &mut Context, cfg: RiscV64Arch, s: L) -> Result<(), Self::Error> {
+        check(cfg, "set_label", &[])?;
+        self.writer.set_label(ctx, cfg, s)
+    }
+
+    fn jal_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "jal_label", &[dest])?;
+        self.writer.jal_label(ctx, cfg, dest, label)
+    }
+
+    fn la_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "la_label", &[dest])?;
+        self.writer.la_label(ctx, cfg, dest, label)
+    }
+
+    fn bcond_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        cond: crate::ConditionCode,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bcond_label", &[a, b])?;
+        self.writer.bcond_label(ctx, cfg, cond, a, b, label)
+    }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "dd_label", &[])?;
+        self.writer.dd_label(ctx, cfg, label)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_rejects_disabled_extensions() {
+        let base = RiscV64Arch::default();
+        let (a0, a1) = (Reg(10), Reg(11));
+        assert_eq!(
+            check(base, "mul", &[&a0, &a0, &a1]),
+            Err(EmitError::ExtensionNotEnabled {
+                mnemonic: "mul",
+                extension: "M",
+            })
+        );
+        assert!(check(base, "fadd_d", &[&a0, &a0, &a1]).is_err());
+        assert!(check(base, "fmadd_s", &[&a0, &a0, &a1, &a1]).is_err());
+        assert!(check(base, "fcvt_w_d", &[&a0, &a1]).is_err());
+        let rv64if = RiscV64Arch {
+            f_extension: true,
+            ..base
+        };
+        assert!(check(rv64if, "fadd_s", &[&a0, &a0, &a1]).is_ok());
+        assert!(check(rv64if, "fcvt_d_s", &[&a0, &a1]).is_err());
+        assert!(check(base, "add", &[&a0, &a0, &a1]).is_ok());
+        assert!(check(base, "add", &[&a0, &a0, &Reg(32)]).is_err());
+
+        let mut output = String::new();
+        let mut writer = ValidatingWriter::new(&mut output as &mut dyn Write);
+        assert!(writer.mul(&mut (), base, &a0, &a0, &a1).is_err());
+        let rv64gc = RiscV64Arch {
+            m_extension: true,
+            ..base
+        };
+        writer.mul(&mut (), rv64gc, &a0, &a0, &a1).unwrap();
+        assert_eq!(output, "mul a0, a0, a1\n");
+    }
+
+    #[test]
+    fn test_cache_block_operations_need_zicbom_and_no_offset() {
+        use crate::RegisterClass;
+        use portal_pc_asm_common::types::mem::MemorySize;
+
+        let at_a0 = |disp| MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(10),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp,
+            size: MemorySize::_64,
+            reg_class: RegisterClass::Gpr,
+        };
+        let mut output = String::new();
+        let mut writer = ValidatingWriter::new(&mut output as &mut dyn Write);
+        let base = RiscV64Arch::default();
+        assert!(writer.cbo_flush(&mut (), base, &at_a0(0)).is_err());
+        let zicbom = base.with_zicbom(true);
+        assert!(writer.cbo_flush(&mut (), zicbom, &at_a0(64)).is_err());
+        writer.cbo_flush(&mut (), zicbom, &at_a0(0)).unwrap();
+        assert_eq!(output, "cbo.flush 0(a0)\n");
+    }
+
+    #[test]
+    fn test_csr_writes_must_target_writable_csrs() {
+        let (zero, a0, a1) = (Reg(0), Reg(10), Reg(11));
+        let mut output = String::new();
+        let mut writer = ValidatingWriter::new(&mut output as &mut dyn Write);
+        let base = RiscV64Arch::default();
+        assert!(writer.csrrw(&mut (), base, &a0, Csr::Cycle, &a1).is_err());
+        assert!(writer.csrrs(&mut (), base, &a0, Csr::Time, &a1).is_err());
+        assert!(writer.csrrsi(&mut (), base, &a0, Csr::Fflags, 32).is_err());
+        writer
+            .csrrs(&mut (), base, &a0, Csr::Instret, &zero)
+            .unwrap();
+        writer
+            .csrrci(&mut (), base, &zero, Csr::Fflags, 31)
+            .unwrap();
+        writer.rdcycle(&mut (), base, &a0).unwrap();
+        assert_eq!(
+            output,
+            "csrrs a0, instret, zero\ncsrrci zero, fflags, 31\nrdcycle a0\n"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Long-range call veneers.
+//!
+//! A direct `jal` reaches ±1 MiB, which is not enough when code is
+//! placed far from its callees, as in a JIT that maps code and runtime
+//! helpers independently. A veneer is a small stub near the caller that
+//! forms the target address with `auipc` + `addi` and jumps there. [`VeneerPool`] creates one
+//! veneer per target symbol, branches call sites to it by label, and emits
+//! the stubs after the function:
+//!
+//! ```ignore
+//! let mut next = 0usize;
+//! let mut pool = VeneerPool::new(|| { next += 1; format!(".LVN{next}") });
+//! pool.call(&mut writer, ctx, cfg, "runtime_alloc", 0)?; // jal ra, .LVN1
+//! pool.call(&mut writer, ctx, cfg, "runtime_alloc", 0)?; // jal ra, .LVN1
+//! writer.ret(ctx, cfg)?;
+//! pool.emit(&mut writer, ctx, cfg)?; // .LVN1: la t1, runtime_alloc; jalr zero, t1, 0
+//! ```
+//!
+//! Call sites reach their veneer through ordinary label resolution, so they
+//! are patched wherever the writer resolves labels.
+
+use alloc::vec::Vec;
+
+use portal_pc_asm_common::types::reg::Reg;
+
+use crate::RiscV64Arch;
+use crate::out::Writer;
+use crate::out::arg::ArgKind;
+
+/// Default scratch register: `t1`, which the psABI designates for
+/// linker-generated call stubs.
+pub const DEFAULT_SCRATCH: Reg = Reg(6);
+
+/// The return address register, `ra`.
+const RA: Reg = Reg(1);
+
+/// The zero register, used as the link register of a plain jump.
+const ZERO: Reg = Reg(0);
+
+/// Collects long-range call targets and emits one veneer for each.
+///
+/// Calls to the same symbol and addend share one veneer. Veneers are
+/// labelled with labels from the `alloc` closure and must be written out
+/// with [`VeneerPool::emit`] within direct branch range of the call sites.
+pub struct VeneerPool<L, F> {
+    alloc: F,
+    scratch: Reg,
+    /// Target symbols, their addends and the labels of their veneers.
+    entries: Vec<(&'static str, i64, L)>,
+}
+
+impl<L: Clone, F: FnMut() -> L> VeneerPool<L, F> {
+    /// Creates an empty pool that labels its veneers with `alloc`.
+    pub fn new(alloc: F) -> Self {
+        Self {
+            alloc,
+            scratch: DEFAULT_SCRATCH,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Sets the register the veneers clobber to hold the target address.
+    pub fn with_scratch(self, scratch: Reg) -> Self {
+        Self { scratch, ..self }
+    }
+
+    /// Returns the number of distinct veneers in the pool.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no veneers have been requested.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the label of the veneer for `symbol + addend`, adding one if
+    /// needed.
+    pub fn intern(&mut self, symbol: &'static str, addend: i64) -> L {
+        if let Some((_, _, label)) = self
+            .entries
+            .iter()
+            .find(|(s, a, _)| *s == symbol && *a == addend)
+        {
+            return label.clone();
+        }
+        let label = (self.alloc)();
+        self.entries.push((symbol, addend, label.clone()));
+        label
+    }
+
+    /// Calls `symbol + addend` through its veneer.
+    pub fn call<W, Context>(
+        &mut self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        symbol: &'static str,
+        addend: i64,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let label = self.intern(symbol, addend);
+        w.jal_label(ctx, cfg, &RA, label)
+    }
+
+    /// Tail-jumps to `symbol + addend` through its veneer.
+    pub fn jump<W, Context>(
+        &mut self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+        symbol: &'static str,
+        addend: i64,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let label = self.intern(symbol, addend);
+        w.jal_label(ctx, cfg, &ZERO, label)
+    }
+
+    /// Emits the veneers and empties the pool.
+    ///
+    /// Each veneer is `la scratch, symbol` (`auipc` + `addi`), reaching
+    /// ±2 GiB, followed by `jalr zero, scratch, 0`. The return address set
+    /// by the call site is left in `ra`. Emit them where execution cannot
+    /// fall in, such as after the function's final `ret` or `j`.
+    pub fn emit<W, Context>(
+        &mut self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: RiscV64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        for (name, addend, label) in self.entries.drain(..) {
+            w.set_label(ctx, cfg, label)?;
+            w.la(ctx, cfg, &self.scratch, &ArgKind::Sym { name, addend })?;
+            w.jalr(ctx, cfg, &ZERO, &self.scratch, 0)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_veneers_are_shared_and_emitted() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = RiscV64Arch::default().uncompressed();
+        let mut next = 0;
+        let mut pool = VeneerPool::new(|| {
+            next += 1;
+            format!(".LVN{next}")
+        });
+        pool.call(&mut writer, &mut (), cfg, "far", 0).unwrap();
+        pool.jump(&mut writer, &mut (), cfg, "far", 0).unwrap();
+        pool.call(&mut writer, &mut (), cfg, "far", 8).unwrap();
+        assert_eq!(pool.len(), 2);
+        pool.emit(&mut writer, &mut (), cfg).unwrap();
+        assert!(pool.is_empty());
+
+        assert!(output.starts_with("jal ra, .LVN1\njal zero, .LVN1\njal ra, .LVN2\n"));
+        assert!(output.contains(".LVN1:\nla t1, far\njalr zero, t1, 0\n"));
+        assert!(output.contains(".LVN2:\nla t1, far+8\njalr zero, t1, 0\n"));
+    }
+}
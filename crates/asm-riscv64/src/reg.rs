@@ -91,14 +91,19 @@ impl RiscV64Reg for Reg {
         let idx = (self.0 as usize) % 32;
 
         match opts.reg_class {
-            crate::RegisterClass::Fp => {
+            crate::RegisterClass::Fp => match &opts.size {
+                // Vector-sized operands live in the V extension's v registers
+                MemorySize::_128 | MemorySize::_256 | MemorySize::_512 => write!(f, "v{idx}"),
+                _ if opts.names == RegNames::Numeric => write!(f, "f{idx}"),
                 // For floating-point registers, use f registers
-                write!(f, "{}", FREG_NAMES[idx])
-            }
-            crate::RegisterClass::Gpr => {
+                _ => write!(f, "{}", FREG_NAMES[idx]),
+            },
+            crate::RegisterClass::Gpr => match opts.names {
+                RegNames::Numeric => write!(f, "x{idx}"),
+                RegNames::AbiFp if idx == 8 => write!(f, "fp"),
                 // For general-purpose registers, RISC-V doesn't have separate 32/64 names
-                write!(f, "{}", REG_NAMES_64[idx])
-            }
+                _ => write!(f, "{}", REG_NAMES_64[idx]),
+            },
         }
     }
 
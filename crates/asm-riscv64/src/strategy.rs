@@ -0,0 +1,164 @@
+//! Lowering strategy selection.
+//!
+//! Several operations have more than one correct lowering depending on which
+//! extensions [`RiscV64Arch`] enables. [`Strategies::for_arch`] makes each of
+//! those choices in one place; the desugaring layer and the x86-64 shim consult
+//! it rather than testing extension flags directly, and callers can inspect it
+//! to see which sequences a configuration will produce.
+
+use crate::RiscV64Arch;
+
+/// How rotates (`rol`/`ror`) are lowered.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum Rotate {
+    /// Zbb `rol`/`ror`.
+    Native,
+    /// `(a << b) | (a >> (64 - b))` with base-ISA shifts.
+    ShiftOr,
+}
+
+/// How bit counting and byte reversal (`cpop`, `clz`, `ctz`, `rev8`) are lowered.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum BitCount {
+    /// Zbb instructions.
+    Native,
+    /// Base-ISA shift/mask (SWAR) sequences.
+    Swar,
+}
+
+/// How byte and halfword sign extension (`sext_b`, `sext_h`) is lowered.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum SignExtend {
+    /// Zbb `sext.b`/`sext.h`.
+    Native,
+    /// Shift left then arithmetic shift right.
+    ShiftPair,
+}
+
+/// How compare-and-swap is lowered.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum CompareAndSwap {
+    /// Zacas `amocas.w`/`amocas.d`.
+    Native,
+    /// An `lr`/`sc` retry loop.
+    LrSc,
+    /// No atomic instructions are available (the A extension is off).
+    Unavailable,
+}
+
+/// How byte and halfword atomic read-modify-write operations are lowered.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum SubwordAmo {
+    /// Zabha `amo*.b`/`amo*.h`.
+    Native,
+    /// An `lr.w`/`sc.w` loop on the containing aligned word, masking the
+    /// other bytes.
+    MaskedLrSc,
+    /// No atomic instructions are available (the A extension is off).
+    Unavailable,
+}
+
+/// The lowering chosen for each operation with more than one.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub struct Strategies {
+    /// Rotate lowering.
+    pub rotate: Rotate,
+    /// Bit-count and byte-reverse lowering.
+    pub bit_count: BitCount,
+    /// Sub-word sign-extension lowering.
+    pub sign_extend: SignExtend,
+    /// Compare-and-swap lowering.
+    pub cas: CompareAndSwap,
+    /// Byte and halfword atomic read-modify-write lowering.
+    pub subword_amo: SubwordAmo,
+}
+
+impl Strategies {
+    /// Selects the lowering for each operation from the enabled extensions.
+    pub fn for_arch(cfg: RiscV64Arch) -> Self {
+        let zbb = cfg.zbb;
+        Self {
+            rotate: if zbb { Rotate::Native } else { Rotate::ShiftOr },
+            bit_count: if zbb {
+                BitCount::Native
+            } else {
+                BitCount::Swar
+            },
+            sign_extend: if zbb {
+                SignExtend::Native
+            } else {
+                SignExtend::ShiftPair
+            },
+            // Zacas and Zabha both build on the A extension.
+            cas: match (cfg.a_extension, cfg.zacas) {
+                (false, _) => CompareAndSwap::Unavailable,
+                (true, true) => CompareAndSwap::Native,
+                (true, false) => CompareAndSwap::LrSc,
+            },
+            subword_amo: match (cfg.a_extension, cfg.zabha) {
+                (false, _) => SubwordAmo::Unavailable,
+                (true, true) => SubwordAmo::Native,
+                (true, false) => SubwordAmo::MaskedLrSc,
+            },
+        }
+    }
+}
+
+impl RiscV64Arch {
+    /// Returns the lowering strategies this configuration selects.
+    pub fn strategies(&self) -> Strategies {
+        Strategies::for_arch(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zbb_selects_native_lowerings() {
+        let base = RiscV64Arch::rv64gc().strategies();
+        assert_eq!(base.rotate, Rotate::ShiftOr);
+        assert_eq!(base.bit_count, BitCount::Swar);
+        assert_eq!(base.sign_extend, SignExtend::ShiftPair);
+
+        let zbb = RiscV64Arch {
+            zbb: true,
+            ..RiscV64Arch::rv64gc()
+        }
+        .strategies();
+        assert_eq!(zbb.rotate, Rotate::Native);
+        assert_eq!(zbb.bit_count, BitCount::Native);
+        assert_eq!(zbb.sign_extend, SignExtend::Native);
+    }
+
+    #[test]
+    fn atomic_extensions_select_single_instructions() {
+        let base = RiscV64Arch::rv64gc().strategies();
+        assert_eq!(base.cas, CompareAndSwap::LrSc);
+        assert_eq!(base.subword_amo, SubwordAmo::MaskedLrSc);
+
+        let native = RiscV64Arch {
+            zacas: true,
+            zabha: true,
+            ..RiscV64Arch::rv64gc()
+        }
+        .strategies();
+        assert_eq!(native.cas, CompareAndSwap::Native);
+        assert_eq!(native.subword_amo, SubwordAmo::Native);
+
+        let no_a = RiscV64Arch {
+            zacas: true,
+            ..RiscV64Arch::rv64imfd()
+        }
+        .strategies();
+        assert_eq!(no_a.cas, CompareAndSwap::Unavailable);
+        assert_eq!(no_a.subword_amo, SubwordAmo::Unavailable);
+    }
+}
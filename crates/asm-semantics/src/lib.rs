@@ -6,6 +6,10 @@
 //! # Features
 //!
 //! - `alloc`: Enables heap allocation support for dynamic collections
+//! - `serde`: Derives `Serialize`/`Deserialize` for [`Arg`], [`ArgKind`],
+//!   [`MemArgKind`] and [`BitLength`]. [`Val`] and [`PredicateTree`] are not
+//!   covered yet, since `Arith` and `Cmp` from `portal-pc-asm-common` do not
+//!   implement serde.
 
 #![no_std]
 extern crate alloc;
@@ -15,12 +19,16 @@ pub use core;
 use core::ops::Deref;
 use portal_pc_asm_common::types::{Arith, Cmp, reg::Reg};
 
+#[cfg(feature = "serde")]
+mod serde_remote;
+
 /// Represents the kind of an argument.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArgKind {
     /// A fixed physical register.
-    FixedReg(Reg),
+    FixedReg(#[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::reg"))] Reg),
     /// A stack slot.
     Slot(u8),
     /// Reference to a previous value.
@@ -32,6 +40,7 @@ pub enum ArgKind {
 /// Represents the kind of a memory argument.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MemArgKind {
     /// A direct argument.
     Arg(ArgKind),
@@ -44,6 +53,7 @@ pub enum MemArgKind {
 
 /// An argument with bit range information.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Arg<K = ArgKind> {
     /// The kind of argument.
     pub kind: K,
@@ -202,4 +212,5 @@ pub enum Val {
 /// Bit length wrapper.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BitLength(pub u8);
@@ -0,0 +1,18 @@
+//! Serde adapters for the `portal-pc-asm-common` types that appear in
+//! arguments, which do not implement serde themselves.
+//!
+//! A register is written as its number.
+
+/// `serde(with)` adapter for [`Reg`](portal_pc_asm_common::types::reg::Reg).
+pub(crate) mod reg {
+    use portal_pc_asm_common::types::reg::Reg;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(reg: &Reg, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(reg.0)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Reg, D::Error> {
+        u8::deserialize(deserializer).map(Reg)
+    }
+}
@@ -2,23 +2,82 @@
 //!
 //! Adapted to live in a separate crate; references types from `portal-solutions-asm-aarch64`.
 
-use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+use crate::faults::DivFaultMode;
+use crate::float::NanPolicy;
+use crate::guest_stack::{GuestStack, ReturnStack};
+use crate::hooks::{
+    CountGranularity, CounterLocation, FnHooks, GuestInstruction, ends_basic_block,
+};
+use crate::memory_model::MemoryModelPolicy;
+use crate::reg_class::RegClassError;
+use crate::string_ops::{StringLoop, compare_shift, element_bytes};
+use crate::syscall::{ENOSYS_RESULT, LINUX_TABLE_LEN, NO_SYSCALL, SyscallMode, linux_table_bytes};
+use crate::x87::{PrecisionLoss, X87Stack};
+use core::cell::Cell;
+use portal_pc_asm_common::types::{
+    mem::{MemorySize, MemorySized},
+    reg::Reg,
+};
 use portal_solutions_asm_aarch64::out::arg::MemArg;
+use portal_solutions_asm_aarch64::{Barrier, DcOp, PrfKind, PrfOp, PrfPolicy, TargetOs};
 use portal_solutions_asm_x86_64::{
-    ConditionCode as X64ConditionCode, X64Arch,
-    out::{Writer as X64Writer, WriterCore as X64WriterCore, arg::MemArg as X64MemArg},
+    ConditionCode as X64ConditionCode, RepPrefix, X64Arch,
+    out::{
+        Writer as X64Writer, WriterCore as X64WriterCore,
+        arg::{ArgKind as X64ArgKind, MemArg as X64MemArg, MemArgKind as X64MemArgKind, Segment},
+    },
 };
 
 /// Label type for shim system.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// The labels the shim generates count up from 0 and print as `.Lshim_N`.
+/// Labels the embedder defines for the shim to branch to, such as a
+/// [`DivFaultMode::Branch`] handler, come from [`ShimLabel::user`] and print
+/// as `.Luser_N`, so the two never collide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ShimLabel(pub usize);
 
+impl ShimLabel {
+    /// Bit set in embedder labels and never reached by generated ones.
+    const USER: usize = 1 << (usize::BITS - 1);
+
+    /// Returns the embedder label `n`.
+    pub const fn user(n: usize) -> Self {
+        ShimLabel(n | Self::USER)
+    }
+
+    /// Returns `true` if this label came from [`ShimLabel::user`].
+    pub const fn is_user(self) -> bool {
+        self.0 & Self::USER != 0
+    }
+}
+
 impl core::fmt::Display for ShimLabel {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, ".Lshim_{}", self.0)
+        if self.is_user() {
+            write!(f, ".Luser_{}", self.0 & !Self::USER)
+        } else {
+            write!(f, ".Lshim_{}", self.0)
+        }
     }
 }
 
+/// Register holding a copy of `TPIDR_EL0` for `fs:`-relative guest accesses (x15).
+///
+/// Translated `fs:` operands address memory relative to this register; emit
+/// [`X64ToAArch64Shim::load_tls_base`] before the first such access. Guest
+/// `rdfsbase` and `wrfsbase` read and replace it; `gs:` has no counterpart.
+pub const TLS_BASE: Reg = Reg(15);
+
+/// Returns the register the shim forms guest addresses in when no load or
+/// store can encode them: x18, or x14 where
+/// [`TargetOs::reserves_x18`] forbids writing x18.
+///
+/// No guest register maps to either.
+pub fn address_temp(os: TargetOs) -> Reg {
+    if os.reserves_x18() { Reg(14) } else { Reg(18) }
+}
+
 /// Adapter that converts x86-64 MemArg to AArch64 MemArg.
 ///
 /// This type wraps a reference to an x86-64 MemArg and implements the AArch64 MemArg trait,
@@ -43,6 +102,118 @@ pub enum APXAccess {
     MemIndex { base: Reg, index: u32, scale: u32 },
 }
 
+/// Stack frame holding the APX backing store that r24+ live in.
+///
+/// Size it with [`X64ToAArch64Shim::apx_slots_used`] once a function is
+/// translated, then wrap the translated code in its prologue and epilogue:
+///
+/// ```ignore
+/// let frame = ApxFrame::new(shim.apx_slots_used());
+/// frame.emit_prologue(&mut prologue_writer, ctx, cfg)?; // str x28, [sp, #-N]!; add x28, sp, #8
+/// frame.emit_epilogue(&mut epilogue_writer, ctx, cfg)?; // ldr x28, [sp], #N
+/// ```
+///
+/// The caller's X28 (callee-saved under AAPCS64) is kept below the slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ApxFrame {
+    /// Number of 8-byte backing-store slots.
+    pub slots: usize,
+}
+
+impl ApxFrame {
+    /// Creates a frame with `slots` backing-store slots.
+    pub fn new(slots: usize) -> Self {
+        Self { slots }
+    }
+
+    /// Returns the bytes reserved below the stack pointer: the slots and the
+    /// saved X28, rounded up to keep SP 16-byte aligned.
+    pub fn size(&self) -> i32 {
+        ((self.slots + 1) * 8).next_multiple_of(16) as i32
+    }
+
+    /// Reserves the frame and points X28 at its first slot. Emits nothing
+    /// for an empty frame.
+    pub fn emit_prologue<W, Context>(
+        &self,
+        writer: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_aarch64::AArch64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::WriterCore<Context> + ?Sized,
+    {
+        use portal_solutions_asm_aarch64::out::arg::AddressingMode;
+
+        if self.slots == 0 {
+            return Ok(());
+        }
+        let saved = frame_slot(-self.size(), AddressingMode::PreIndex);
+        writer.str(ctx, cfg, &Reg(28), &saved)?;
+        writer.add_imm(ctx, cfg, &Reg(28), &Reg(31), 8)
+    }
+
+    /// Restores the caller's X28 and releases the frame.
+    pub fn emit_epilogue<W, Context>(
+        &self,
+        writer: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_aarch64::AArch64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::WriterCore<Context> + ?Sized,
+    {
+        use portal_solutions_asm_aarch64::out::arg::AddressingMode;
+
+        if self.slots == 0 {
+            return Ok(());
+        }
+        let saved = frame_slot(self.size(), AddressingMode::PostIndex);
+        writer.ldr(ctx, cfg, &Reg(28), &saved)
+    }
+}
+
+/// `[sp, #disp]` with `mode` writeback, for [`ApxFrame`].
+fn frame_slot(
+    disp: i32,
+    mode: portal_solutions_asm_aarch64::out::arg::AddressingMode,
+) -> portal_solutions_asm_aarch64::out::arg::MemArgKind<
+    portal_solutions_asm_aarch64::out::arg::ArgKind,
+> {
+    portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
+        base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
+            reg: Reg(31),
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp,
+        size: MemorySize::_64,
+        reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
+        mode,
+    }
+}
+
+/// `[ss, #disp]` with `mode` writeback, for [`ReturnStack::Shadow`].
+fn shadow_stack_slot(
+    ss: Reg,
+    disp: i32,
+    mode: portal_solutions_asm_aarch64::out::arg::AddressingMode,
+) -> portal_solutions_asm_aarch64::out::arg::MemArgKind<
+    portal_solutions_asm_aarch64::out::arg::ArgKind,
+> {
+    portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
+        base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
+            reg: ss,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp,
+        size: MemorySize::_64,
+        reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
+        mode,
+    }
+}
+
 impl<'a> MemArgAdapter<'a> {
     /// Creates a new adapter wrapping an x86-64 MemArg and records the x86_64 arch.
     pub fn new(inner: &'a (dyn X64MemArg + 'a), arch: X64Arch) -> Self {
@@ -131,6 +302,77 @@ impl<'a> MemArgAdapter<'a> {
             _ => APXAccess::None,
         }
     }
+
+    /// Returns how many 8-byte slots of the X28-addressed APX backing store
+    /// this operand needs: one past the highest slot any of its registers
+    /// (r24+) uses, or 0.
+    pub fn apx_slots(&self) -> usize {
+        use portal_solutions_asm_x86_64::out::arg::ArgKind as X64ArgKind;
+        use portal_solutions_asm_x86_64::out::arg::MemArgKind as X64MemArgKind;
+
+        if !self.arch.apx {
+            return 0;
+        }
+        let slots = |arg: X64ArgKind| match arg {
+            X64ArgKind::Reg { reg, .. } if reg.0 >= 24 => reg.0 as usize - 23,
+            _ => 0,
+        };
+        match self.inner.concrete_mem_kind() {
+            X64MemArgKind::NoMem(arg) => slots(arg),
+            X64MemArgKind::Mem { base, offset, .. } => {
+                slots(base).max(offset.map_or(0, |(index, _)| slots(index)))
+            }
+            _ => 0,
+        }
+    }
+
+    /// Returns why this operand's register class has no AArch64 equivalent,
+    /// if it has none.
+    pub fn reg_class_error(&self) -> Option<RegClassError> {
+        match self.inner.concrete_mem_kind() {
+            portal_solutions_asm_x86_64::out::arg::MemArgKind::Mem { reg_class, .. } => {
+                match crate::reg_class::x64_to_aarch64(reg_class) {
+                    // Guest vectors are lowered to NEON; SVE's width need
+                    // not match YMM or ZMM.
+                    Ok(portal_solutions_asm_aarch64::RegisterClass::Sve) => Some(
+                        RegClassError::Unsupported(crate::reg_class::RegClass::WideVector),
+                    ),
+                    result => result.err(),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns why this operand cannot be translated, if it cannot: its
+    /// register class has no AArch64 equivalent, it names a register with no
+    /// AArch64 counterpart, or it is a `gs:` operand, or an `fs:` operand
+    /// with both a base and an index register, which leave no room for the
+    /// thread pointer.
+    pub fn operand_error(&self) -> Option<&'static str> {
+        if let Some(e) = self.reg_class_error() {
+            return Some(e.reason());
+        }
+        match self.inner.concrete_mem_kind() {
+            X64MemArgKind::NoMem(arg) => arg_kind_error(arg),
+            X64MemArgKind::Mem {
+                segment: Segment::Gs,
+                ..
+            } => Some("gs: operands have no base register on the target"),
+            X64MemArgKind::Mem {
+                base,
+                offset: Some(_),
+                segment: Segment::Fs,
+                ..
+            } if !matches!(base, X64ArgKind::Lit(_) | X64ArgKind::NoBase) => {
+                Some("fs: operands cannot have both a base and an index register")
+            }
+            X64MemArgKind::Mem { base, offset, .. } => {
+                arg_kind_error(base).or_else(|| offset.and_then(|(index, _)| arg_kind_error(index)))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl<'a> portal_solutions_asm_aarch64::out::arg::MemArg for MemArgAdapter<'a> {
@@ -187,14 +429,15 @@ impl<'a> portal_solutions_asm_aarch64::out::arg::MemArg for MemArgAdapter<'a> {
                 disp,
                 size,
                 reg_class,
-                segment: _,
+                segment,
             } => {
                 // Memory reference - convert components, with APX handling
                 // Default conversions
                 let mut aarch64_base = convert_arg_kind(base, self.arch);
-                let mut aarch64_offset =
-                    offset.map(|(off, scale)| (convert_arg_kind(off, self.arch), scale));
-                let mut aarch64_disp = disp as i32; // Convert u32 to i32
+                // x86 scales by a factor; AArch64 shifts the index.
+                let mut aarch64_offset = offset
+                    .map(|(off, scale)| (convert_arg_kind(off, self.arch), scale.trailing_zeros()));
+                let mut aarch64_disp = disp;
                 let aarch64_reg_class = convert_register_class(reg_class);
 
                 // If base is an APX late register, replace base with APX base pointer and adjust displacement
@@ -229,6 +472,27 @@ impl<'a> portal_solutions_asm_aarch64::out::arg::MemArg for MemArgAdapter<'a> {
                     }
                 }
 
+                // fs:-relative accesses are thread-local; rebase them onto the
+                // thread pointer copy in TLS_BASE. Operands that cannot be
+                // rebased are left as they are and reported by operand_error.
+                if segment == Segment::Fs {
+                    let tp = portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
+                        reg: TLS_BASE,
+                        size: MemorySize::_64,
+                    };
+                    match (base, &aarch64_offset) {
+                        (portal_solutions_asm_x86_64::out::arg::ArgKind::NoBase, _) => {
+                            aarch64_base = tp
+                        }
+                        (portal_solutions_asm_x86_64::out::arg::ArgKind::Lit(v), _) => {
+                            aarch64_base = tp;
+                            aarch64_disp = aarch64_disp.wrapping_add(v as i32);
+                        }
+                        (_, None) => aarch64_offset = Some((tp, 0)),
+                        (_, Some(_)) => {}
+                    }
+                }
+
                 // Create the memory argument and pass references to its components
                 // x86-64 doesn't have pre/post-index, so always use Offset mode
                 match &aarch64_offset {
@@ -263,6 +527,20 @@ impl<'a> portal_solutions_asm_aarch64::out::arg::MemArg for MemArgAdapter<'a> {
     }
 }
 
+/// Returns why a guest operand component has no counterpart on the target,
+/// if it has none.
+fn arg_kind_error(arg: X64ArgKind) -> Option<&'static str> {
+    match arg {
+        X64ArgKind::HighByte(_) => {
+            Some("high-byte registers are only translated by mov, movzx and movsx")
+        }
+        X64ArgKind::Rip => {
+            Some("RIP-relative operands address guest code and cannot be translated")
+        }
+        _ => None,
+    }
+}
+
 /// Converts x86-64 ArgKind to AArch64 ArgKind with register mapping.
 fn convert_arg_kind(
     arg: portal_solutions_asm_x86_64::out::arg::ArgKind,
@@ -281,21 +559,63 @@ fn convert_arg_kind(
             }
         }
         X64ArgKind::Lit(val) => AArch64ArgKind::Lit(val),
+        X64ArgKind::Sym { name, addend } => AArch64ArgKind::Sym { name, addend },
+        // Reported by `MemArgAdapter::operand_error`
+        X64ArgKind::HighByte(_) | X64ArgKind::Rip => AArch64ArgKind::Lit(0),
+        // The target has no base-less form; address from zero instead.
+        X64ArgKind::NoBase => AArch64ArgKind::Lit(0),
         _ => AArch64ArgKind::Lit(0), // Handle any future variants
     }
 }
 
 /// Converts x86-64 RegisterClass to AArch64 RegisterClass.
+///
+/// A class with no equivalent on the target (e.g. YMM/ZMM) becomes a GPR
+/// here, since an operand cannot fail to convert; the shim records the
+/// error when it adapts the operand and returns it from the instruction.
 fn convert_register_class(
     reg_class: portal_solutions_asm_x86_64::RegisterClass,
 ) -> portal_solutions_asm_aarch64::RegisterClass {
-    use portal_solutions_asm_aarch64::RegisterClass as AArch64RegClass;
-    use portal_solutions_asm_x86_64::RegisterClass as X64RegClass;
+    crate::reg_class::x64_to_aarch64(reg_class)
+        .unwrap_or(portal_solutions_asm_aarch64::RegisterClass::Gpr)
+}
+
+/// Restates a guest-side x86-64 [`EmitError`](portal_solutions_asm_x86_64::out::error::EmitError)
+/// as the AArch64 writer's error.
+fn convert_emit_error(
+    e: portal_solutions_asm_x86_64::out::error::EmitError,
+) -> portal_solutions_asm_aarch64::out::error::EmitError {
+    use portal_solutions_asm_aarch64::out::error::EmitError;
+    use portal_solutions_asm_x86_64::out::error::EmitError as X64EmitError;
+    match e {
+        X64EmitError::UnsupportedInstruction { mnemonic } => {
+            EmitError::UnsupportedInstruction { mnemonic }
+        }
+        X64EmitError::InvalidOperands { mnemonic, reason } => {
+            EmitError::InvalidOperands { mnemonic, reason }
+        }
+        X64EmitError::ImmediateOutOfRange { mnemonic, value } => {
+            EmitError::ImmediateOutOfRange { mnemonic, value }
+        }
+        X64EmitError::ExtensionNotEnabled {
+            mnemonic,
+            extension,
+        } => EmitError::ExtensionNotEnabled {
+            mnemonic,
+            extension,
+        },
+        e => EmitError::UnsupportedInstruction {
+            mnemonic: e.mnemonic(),
+        },
+    }
+}
 
-    match reg_class {
-        X64RegClass::Gpr => AArch64RegClass::Gpr,
-        X64RegClass::Xmm => AArch64RegClass::Simd,
-        _ => AArch64RegClass::Gpr, // Handle any future variants
+/// The error for an operand kind the shim has no lowering for, such as a
+/// `MemArgKind` variant added after it was written.
+fn unknown_operand(mnemonic: &'static str) -> portal_solutions_asm_aarch64::out::error::EmitError {
+    portal_solutions_asm_aarch64::out::error::EmitError::InvalidOperands {
+        mnemonic,
+        reason: "unsupported operand kind",
     }
 }
 
@@ -369,7 +689,11 @@ pub trait WriterShimExt<Context>:
         cfg: portal_solutions_asm_aarch64::AArch64Arch,
         dest: &Reg,
         src: &(dyn portal_solutions_asm_aarch64::out::arg::MemArg + '_),
-    ) -> Result<(), <Self as portal_solutions_asm_aarch64::out::WriterCore<Context>>::Error> {
+    ) -> Result<(), <Self as portal_solutions_asm_aarch64::out::WriterCore<Context>>::Error>
+    where
+        <Self as portal_solutions_asm_aarch64::out::WriterCore<Context>>::Error:
+            From<portal_solutions_asm_aarch64::out::error::EmitError>,
+    {
         use portal_solutions_asm_aarch64::out::arg::MemArgKind;
 
         let src_kind = src.concrete_mem_kind();
@@ -382,7 +706,7 @@ pub trait WriterShimExt<Context>:
                 // Source is memory, use LDR
                 self.ldr(ctx, cfg, dest, src)
             }
-            _ => todo!(),
+            _ => Err(unknown_operand("ldr").into()),
         }
     }
 
@@ -396,7 +720,11 @@ pub trait WriterShimExt<Context>:
         cfg: portal_solutions_asm_aarch64::AArch64Arch,
         dest: &(dyn portal_solutions_asm_aarch64::out::arg::MemArg + '_),
         src: &Reg,
-    ) -> Result<(), <Self as portal_solutions_asm_aarch64::out::WriterCore<Context>>::Error> {
+    ) -> Result<(), <Self as portal_solutions_asm_aarch64::out::WriterCore<Context>>::Error>
+    where
+        <Self as portal_solutions_asm_aarch64::out::WriterCore<Context>>::Error:
+            From<portal_solutions_asm_aarch64::out::error::EmitError>,
+    {
         use portal_solutions_asm_aarch64::out::arg::MemArgKind;
 
         let dest_kind = dest.concrete_mem_kind();
@@ -409,7 +737,7 @@ pub trait WriterShimExt<Context>:
                 // Destination is memory, use STR
                 self.str(ctx, cfg, src, dest)
             }
-            _ => todo!(),
+            _ => Err(unknown_operand("str").into()),
         }
     }
 }
@@ -428,8 +756,8 @@ macro_rules! handle_two_operand_instr {
     ($self:expr, $ctx:expr, $a:expr, $b:expr, $instr:ident, $cfg:expr) => {{
         use portal_solutions_asm_aarch64::out::arg::MemArgKind;
 
-        let a_adapter = MemArgAdapter::new($a, $cfg);
-        let b_adapter = MemArgAdapter::new($b, $cfg);
+        let a_adapter = $self.adapt($a, $cfg);
+        let b_adapter = $self.adapt($b, $cfg);
 
         let a_kind = a_adapter.concrete_mem_kind();
         let b_kind = b_adapter.concrete_mem_kind();
@@ -448,7 +776,7 @@ macro_rules! handle_two_operand_instr {
                 $self
                     .inner
                     .$instr($ctx, $self.aarch64_cfg, &temp, &temp, &b_adapter)?;
-                $self.inner.str($ctx, $self.aarch64_cfg, &temp, &a_adapter)
+                $self.store_guest($ctx, &temp, &a_adapter)
             }
             (MemArgKind::NoMem(_), MemArgKind::Mem { .. }) => {
                 // a is register, b is memory - LDR b into temp, then INSTR
@@ -467,11 +795,9 @@ macro_rules! handle_two_operand_instr {
                 $self
                     .inner
                     .$instr($ctx, $self.aarch64_cfg, &temp_a, &temp_a, &temp_b)?;
-                $self
-                    .inner
-                    .str($ctx, $self.aarch64_cfg, &temp_a, &a_adapter)
+                $self.store_guest($ctx, &temp_a, &a_adapter)
             }
-            _ => todo!(),
+            _ => Err(unknown_operand(stringify!($instr)).into()),
         }
     }};
 }
@@ -482,8 +808,8 @@ macro_rules! handle_two_operand_instr_2arg {
     ($self:expr, $ctx:expr, $a:expr, $b:expr, $instr:ident, $cfg:expr) => {{
         use portal_solutions_asm_aarch64::out::arg::MemArgKind;
 
-        let a_adapter = MemArgAdapter::new($a, $cfg);
-        let b_adapter = MemArgAdapter::new($b, $cfg);
+        let a_adapter = $self.adapt($a, $cfg);
+        let b_adapter = $self.adapt($b, $cfg);
 
         let a_kind = a_adapter.concrete_mem_kind();
         let b_kind = b_adapter.concrete_mem_kind();
@@ -502,7 +828,7 @@ macro_rules! handle_two_operand_instr_2arg {
                 $self
                     .inner
                     .$instr($ctx, $self.aarch64_cfg, &temp, &temp, &b_adapter)?;
-                $self.inner.str($ctx, $self.aarch64_cfg, &temp, &a_adapter)
+                $self.store_guest($ctx, &temp, &a_adapter)
             }
             (MemArgKind::NoMem(_), MemArgKind::Mem { .. }) => {
                 // a is register, b is memory - LDR b into temp, then INSTR with dest=a
@@ -521,15 +847,220 @@ macro_rules! handle_two_operand_instr_2arg {
                 $self
                     .inner
                     .$instr($ctx, $self.aarch64_cfg, &temp_a, &temp_a, &temp_b)?;
-                $self
-                    .inner
-                    .str($ctx, $self.aarch64_cfg, &temp_a, &a_adapter)
+                $self.store_guest($ctx, &temp_a, &a_adapter)
             }
-            _ => todo!(),
+            _ => Err(unknown_operand(stringify!($instr)).into()),
         }
     }};
 }
 
+/// Runs the shim's pre/post hooks around the translation of one guest instruction.
+///
+/// The body is evaluated as a block; the post hook only runs if it succeeds.
+macro_rules! hooked {
+    ($self:expr, $ctx:expr, $mnemonic:expr, $body:block) => {{
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "guest_instruction",
+            mnemonic = $mnemonic,
+            index = $self.guest_count
+        )
+        .entered();
+        $self.pre_hook($ctx, $mnemonic)?;
+        let result: Result<(), Self::Error> = $body;
+        result?;
+        $self.post_hook($ctx, $mnemonic)
+    }};
+}
+
+/// Per-instruction hooks for [`X64ToAArch64Shim`].
+///
+/// Both methods receive the underlying AArch64 writer and may emit instructions
+/// through it. The default implementations do nothing.
+pub trait ShimHooks<W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>, Context> {
+    /// Called before the guest instruction is translated.
+    fn pre_instruction(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_aarch64::AArch64Arch,
+        insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        let _ = (inner, ctx, cfg, insn);
+        Ok(())
+    }
+
+    /// Called after the guest instruction has been translated.
+    fn post_instruction(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_aarch64::AArch64Arch,
+        insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        let _ = (inner, ctx, cfg, insn);
+        Ok(())
+    }
+
+    /// Called after a guest label has been defined.
+    fn label(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_aarch64::AArch64Arch,
+    ) -> Result<(), W::Error> {
+        let _ = (inner, ctx, cfg);
+        Ok(())
+    }
+
+    /// Called when a guest instruction is translated approximately.
+    ///
+    /// Runs during translation and cannot emit code; use it to log or reject
+    /// inputs whose results may differ from the guest (see [`crate::x87`]).
+    fn precision_loss(&mut self, insn: GuestInstruction, loss: PrecisionLoss) {
+        let _ = (insn, loss);
+    }
+}
+
+impl<W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>, Context>
+    ShimHooks<W, Context> for ()
+{
+}
+
+impl<W, Context, Pre, Post> ShimHooks<W, Context> for FnHooks<Pre, Post>
+where
+    W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    Pre: FnMut(
+        &mut W,
+        &mut Context,
+        portal_solutions_asm_aarch64::AArch64Arch,
+        GuestInstruction,
+    ) -> Result<(), W::Error>,
+    Post: FnMut(
+        &mut W,
+        &mut Context,
+        portal_solutions_asm_aarch64::AArch64Arch,
+        GuestInstruction,
+    ) -> Result<(), W::Error>,
+{
+    fn pre_instruction(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_aarch64::AArch64Arch,
+        insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        (self.pre)(inner, ctx, cfg, insn)
+    }
+
+    fn post_instruction(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_aarch64::AArch64Arch,
+        insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        (self.post)(inner, ctx, cfg, insn)
+    }
+}
+
+/// Hook that increments a counter per translated guest instruction or basic block.
+///
+/// The increment uses `ADD` rather than `ADDS`, so guest flags are preserved.
+/// Memory counters are loaded into x16, which is free between guest instructions.
+pub struct InstructionCounter {
+    /// Where the counter is kept.
+    pub location: CounterLocation,
+    /// How often the counter is incremented.
+    pub granularity: CountGranularity,
+    /// Whether the next guest instruction starts a basic block.
+    block_start: bool,
+}
+
+impl InstructionCounter {
+    /// Creates a counter hook.
+    pub fn new(location: CounterLocation, granularity: CountGranularity) -> Self {
+        Self {
+            location,
+            granularity,
+            block_start: true,
+        }
+    }
+
+    fn emit_increment<W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>, Context>(
+        &self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_aarch64::AArch64Arch,
+    ) -> Result<(), W::Error> {
+        match self.location {
+            CounterLocation::Register(reg) => inner.add_imm(ctx, cfg, &reg, &reg, 1),
+            CounterLocation::Memory { base, disp } => {
+                let temp = Reg(16); // x16
+                let slot = portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
+                    base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
+                        reg: base,
+                        size: MemorySize::_64,
+                    },
+                    offset: None,
+                    disp,
+                    size: MemorySize::_64,
+                    reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
+                    mode: portal_solutions_asm_aarch64::out::arg::AddressingMode::Offset,
+                };
+                inner.ldr(ctx, cfg, &temp, &slot)?;
+                inner.add_imm(ctx, cfg, &temp, &temp, 1)?;
+                inner.str(ctx, cfg, &temp, &slot)
+            }
+        }
+    }
+}
+
+impl<W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>, Context>
+    ShimHooks<W, Context> for InstructionCounter
+{
+    fn pre_instruction(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_aarch64::AArch64Arch,
+        _insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        let count = match self.granularity {
+            CountGranularity::Instruction => true,
+            CountGranularity::BasicBlock => core::mem::replace(&mut self.block_start, false),
+        };
+        if count {
+            self.emit_increment(inner, ctx, cfg)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn post_instruction(
+        &mut self,
+        _inner: &mut W,
+        _ctx: &mut Context,
+        _cfg: portal_solutions_asm_aarch64::AArch64Arch,
+        insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        if ends_basic_block(insn.mnemonic) {
+            self.block_start = true;
+        }
+        Ok(())
+    }
+
+    fn label(
+        &mut self,
+        _inner: &mut W,
+        _ctx: &mut Context,
+        _cfg: portal_solutions_asm_aarch64::AArch64Arch,
+    ) -> Result<(), W::Error> {
+        self.block_start = true;
+        Ok(())
+    }
+}
+
 /// Wrapper that translates x86-64 instructions to AArch64.
 ///
 /// This type wraps an AArch64 writer and implements the x86-64 WriterCore trait,
@@ -542,13 +1073,59 @@ macro_rules! handle_two_operand_instr_2arg {
 /// - RET instructions directly emit code that pops the return address from the stack, then returns
 ///
 /// This ensures x86-64 semantics where the return address is stored on the stack rather than in LR.
-pub struct X64ToAArch64Shim<W> {
+/// With [`return_stack`](Self::return_stack) set to [`ReturnStack::Shadow`], calls
+/// instead branch with link and save the caller's LR on a separate shadow stack
+/// around the call, and RET is a plain `ret`, so guest frames hold no return address.
+///
+/// With [`pac_ret`](Self::pac_ret) set, the return address is signed with
+/// `paciasp` before it is pushed and authenticated with `autiasp` after it
+/// is popped, so translated code runs where pointer authentication is
+/// enforced (arm64e). Guest code that reads or rewrites its return address
+/// on the stack then sees the signed value.
+///
+/// # Hooks
+///
+/// `H` receives a callback before and after every translated guest instruction
+/// (see [`ShimHooks`]); the default `()` does nothing.
+pub struct X64ToAArch64Shim<W, H = ()> {
     /// The underlying AArch64 writer.
     pub inner: W,
     /// AArch64 architecture configuration.
     pub aarch64_cfg: portal_solutions_asm_aarch64::AArch64Arch,
+    /// Per-instruction hooks.
+    pub hooks: H,
+    /// How DIV/IDIV inputs that fault on x86-64 are handled. A `Branch`
+    /// label must come from [`ShimLabel::user`].
+    pub div_fault: DivFaultMode<ShimLabel>,
+    /// Where `push`/`pop`/`call`/`ret` keep the guest stack.
+    pub guest_stack: GuestStack,
+    /// Where `call`/`ret` keep return addresses.
+    pub return_stack: ReturnStack,
+    /// How `syscall` is translated.
+    pub syscall: SyscallMode,
+    /// How guest loads and stores are ordered.
+    pub memory_model: MemoryModelPolicy,
+    /// Whether return addresses are signed while on the guest stack.
+    pub pac_ret: bool,
+    /// How NaN-sensitive floating-point instructions are translated. See
+    /// [`crate::float`].
+    pub nan_policy: NanPolicy,
+    /// Label of the system call number table, once a `syscall` needs it.
+    syscall_table: Option<ShimLabel>,
     /// Counter for generating unique shim labels.
     shim_counter: usize,
+    /// Number of guest instructions translated so far.
+    guest_count: usize,
+    /// Translation-time x87 stack depth.
+    x87: X87Stack,
+    /// APX backing-store slots referenced so far (see [`ApxFrame`]).
+    apx_slots: Cell<usize>,
+    /// Why the first operand of the current guest instruction that has no
+    /// AArch64 translation cannot be translated.
+    operand_error: Cell<Option<&'static str>>,
+    /// Target offset after the pre-instruction hook of the current guest
+    /// instruction, if the writer reports offsets.
+    insn_start: Option<usize>,
 }
 
 impl<W> X64ToAArch64Shim<W> {
@@ -557,7 +1134,21 @@ impl<W> X64ToAArch64Shim<W> {
         Self {
             inner,
             aarch64_cfg: Default::default(),
+            hooks: (),
+            div_fault: DivFaultMode::Native,
+            guest_stack: GuestStack::default(),
+            return_stack: ReturnStack::Standard,
+            syscall: SyscallMode::Trap,
+            memory_model: MemoryModelPolicy::Native,
+            pac_ret: false,
+            nan_policy: NanPolicy::Host,
+            syscall_table: None,
             shim_counter: 0,
+            guest_count: 0,
+            x87: X87Stack::default(),
+            apx_slots: Cell::new(0),
+            operand_error: Cell::new(None),
+            insn_start: None,
         }
     }
 
@@ -566,929 +1157,3785 @@ impl<W> X64ToAArch64Shim<W> {
         Self {
             inner,
             aarch64_cfg,
+            hooks: (),
+            div_fault: DivFaultMode::Native,
+            guest_stack: GuestStack::default(),
+            return_stack: ReturnStack::Standard,
+            syscall: SyscallMode::Trap,
+            memory_model: MemoryModelPolicy::Native,
+            pac_ret: aarch64_cfg.pac_ret,
+            nan_policy: NanPolicy::Host,
+            syscall_table: None,
             shim_counter: 0,
+            guest_count: 0,
+            x87: X87Stack::default(),
+            apx_slots: Cell::new(0),
+            operand_error: Cell::new(None),
+            insn_start: None,
         }
     }
+}
 
-    /// Generates a unique shim label.
-    fn next_shim_label(&mut self) -> ShimLabel {
-        let label = ShimLabel(self.shim_counter);
-        self.shim_counter += 1;
-        label
+impl<W, H> X64ToAArch64Shim<W, H> {
+    /// Replaces the per-instruction hooks, keeping the writer and all counters.
+    pub fn with_hooks<H2>(self, hooks: H2) -> X64ToAArch64Shim<W, H2> {
+        X64ToAArch64Shim {
+            inner: self.inner,
+            aarch64_cfg: self.aarch64_cfg,
+            hooks,
+            div_fault: self.div_fault,
+            guest_stack: self.guest_stack,
+            return_stack: self.return_stack,
+            syscall: self.syscall,
+            memory_model: self.memory_model,
+            pac_ret: self.pac_ret,
+            nan_policy: self.nan_policy,
+            syscall_table: self.syscall_table,
+            shim_counter: self.shim_counter,
+            guest_count: self.guest_count,
+            x87: self.x87,
+            apx_slots: self.apx_slots,
+            operand_error: self.operand_error,
+            insn_start: self.insn_start,
+        }
     }
 
-    /// Load a value from a possibly-APX memory argument into `dest`.
+    /// Sets how DIV/IDIV inputs that fault on x86-64 are handled.
     ///
-    /// If the given adapter references a memory operand whose index is an APX register,
-    /// this will first load the APX index value from the APX backing store ([X28 + slot])
-    /// into a temporary register and then perform the actual load using that temp as the
-    /// scaled index. Otherwise, delegates to the underlying writer's LDR.
-    fn load_memarg_into_temp<Context>(
+    /// # Errors
+    ///
+    /// Fails with
+    /// [`EmitError::InvalidOperands`](portal_solutions_asm_aarch64::out::error::EmitError::InvalidOperands)
+    /// if a [`DivFaultMode::Branch`] label is not a [`ShimLabel::user`]
+    /// label, which could name a label the shim generates.
+    pub fn with_div_fault(
+        mut self,
+        div_fault: DivFaultMode<ShimLabel>,
+    ) -> Result<Self, portal_solutions_asm_aarch64::out::error::EmitError> {
+        use portal_solutions_asm_aarch64::out::error::EmitError;
+
+        if let DivFaultMode::Branch(label) = div_fault {
+            if !label.is_user() {
+                return Err(EmitError::InvalidOperands {
+                    mnemonic: "div",
+                    reason: "fault handlers need a ShimLabel::user label",
+                });
+            }
+        }
+        self.div_fault = div_fault;
+        Ok(self)
+    }
+
+    /// Sets where `push`/`pop`/`call`/`ret` keep the guest stack.
+    pub fn with_guest_stack(mut self, guest_stack: GuestStack) -> Self {
+        self.guest_stack = guest_stack;
+        self
+    }
+
+    /// Sets where `call`/`ret` keep return addresses.
+    ///
+    /// With a shadow stack the guest stack holds no return addresses.
+    ///
+    /// # Errors
+    ///
+    /// Fails with
+    /// [`EmitError::InvalidOperands`](portal_solutions_asm_aarch64::out::error::EmitError::InvalidOperands)
+    /// if the shadow stack register is one the shim already uses: a guest
+    /// or APX register, a scratch register or the link register.
+    pub fn with_return_stack(
+        mut self,
+        return_stack: ReturnStack,
+    ) -> Result<Self, portal_solutions_asm_aarch64::out::error::EmitError> {
+        use portal_solutions_asm_aarch64::out::error::EmitError;
+
+        if let Some(ss) = return_stack.shadow() {
+            if self.uses_register(ss) {
+                return Err(EmitError::InvalidOperands {
+                    mnemonic: "call",
+                    reason: "the shim already uses the shadow stack register",
+                });
+            }
+        }
+        self.return_stack = return_stack;
+        Ok(self)
+    }
+
+    /// Returns `true` if a guest register, with APX, maps to `reg` or the
+    /// shim keeps `reg` for itself.
+    fn uses_register(&self, reg: Reg) -> bool {
+        let mut apx = X64Arch::default();
+        apx.apx = true;
+        let own = [
+            TLS_BASE,
+            Reg(16),
+            Reg(17),
+            address_temp(self.aarch64_cfg.os),
+            Reg(30),
+            Reg(31),
+        ];
+        own.contains(&reg) || (0..32).any(|g| map_x64_register_to_aarch64(Reg(g), apx) == reg)
+    }
+
+    /// Sets how `syscall` is translated.
+    pub fn with_syscall(mut self, syscall: SyscallMode) -> Self {
+        self.syscall = syscall;
+        self
+    }
+
+    /// Sets how guest loads and stores are ordered.
+    pub fn with_memory_model(mut self, memory_model: MemoryModelPolicy) -> Self {
+        self.memory_model = memory_model;
+        self
+    }
+
+    /// Sets whether return addresses are signed while on the guest stack.
+    pub fn with_pac_ret(mut self, pac_ret: bool) -> Self {
+        self.pac_ret = pac_ret;
+        self
+    }
+
+    /// Sets how NaN-sensitive floating-point instructions are translated.
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    /// Returns the number of guest instructions translated so far.
+    pub fn guest_instruction_count(&self) -> usize {
+        self.guest_count
+    }
+
+    /// Returns how many 8-byte slots of the APX backing store the code
+    /// translated so far uses, for sizing an [`ApxFrame`].
+    pub fn apx_slots_used(&self) -> usize {
+        self.apx_slots.get()
+    }
+
+    /// Wraps a guest operand for the AArch64 writer, recording the APX
+    /// backing-store slots it uses and why it cannot be translated, if it
+    /// cannot, for the instruction to report.
+    fn adapt<'b>(&self, op: &'b (dyn X64MemArg + 'b), cfg: X64Arch) -> MemArgAdapter<'b> {
+        let adapter = MemArgAdapter::new(op, cfg);
+        self.apx_slots
+            .set(self.apx_slots.get().max(adapter.apx_slots()));
+        if self.operand_error.get().is_none() {
+            self.operand_error.set(adapter.operand_error());
+        }
+        adapter
+    }
+
+    /// Adapts a scalar single-precision operand: registers become S
+    /// registers and memory operands 32 bits wide, which selects the
+    /// single-precision forms of the floating-point instructions.
+    fn adapt_single<'b>(
+        &self,
+        op: &'b (dyn X64MemArg + 'b),
+        cfg: X64Arch,
+    ) -> MemorySized<MemArgAdapter<'b>> {
+        MemorySized {
+            value: self.adapt(op, cfg),
+            size: MemorySize::_32,
+        }
+    }
+
+    /// Captures the label counter and guest instruction count so a later shim
+    /// can resume translation without reusing label numbers.
+    pub fn save_state(&self) -> crate::state::ShimState {
+        crate::state::ShimState::new(self.shim_counter, self.guest_count)
+    }
+
+    /// Resumes from a state captured by [`save_state`](Self::save_state).
+    pub fn restore_state(&mut self, state: crate::state::ShimState) {
+        self.shim_counter = state.shim_counter;
+        self.guest_count = state.guest_count;
+    }
+
+    /// Moves the guest's integer call arguments, as laid out by
+    /// `cfg.call_conv`, into X0-X7 for a call to a native function.
+    ///
+    /// Clobbers X16. See [`host_abi`](crate::host_abi).
+    pub fn host_call_args<Context>(
         &mut self,
         ctx: &mut Context,
-        adapter: &MemArgAdapter<'_>,
-        dest: &Reg,
+        cfg: X64Arch,
     ) -> Result<(), W::Error>
     where
         W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
     {
-        use portal_solutions_asm_x86_64::out::arg::MemArgKind as X64MemArgKind;
+        let mut moves = [(Reg(0), Reg(0)); 6];
+        let args = cfg.call_conv.int_args();
+        for (i, (pair, arg)) in moves.iter_mut().zip(args).enumerate() {
+            *pair = (Reg(i as u8), map_x64_register_to_aarch64(*arg, cfg));
+        }
+        let aarch64_cfg = self.aarch64_cfg;
+        crate::host_abi::parallel_move(&mut moves[..args.len()], Reg(16), |dest, src| {
+            self.inner.mov(ctx, aarch64_cfg, &dest, &src)
+        })
+    }
 
-        match adapter.apx_access() {
-            APXAccess::MemIndex {
-                base: _base,
-                index,
-                scale,
-            } => {
-                // Load the APX index value from APX backing store into a temp register
-                let slot_offset = (index as i32) * 8; // each slot is 8 bytes
-                let mem_slot = portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
-                    base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
-                        reg: Reg(28),
-                        size: MemorySize::_64,
-                    },
+    /// Generates a unique shim label.
+    fn next_shim_label(&mut self) -> ShimLabel {
+        let label = ShimLabel(self.shim_counter);
+        self.shim_counter += 1;
+        label
+    }
+
+    /// Loads the thread pointer into [`TLS_BASE`].
+    ///
+    /// Emit this at the start of translated code, and again after anything that
+    /// may change the thread pointer, so `fs:` operands resolve correctly.
+    pub fn load_tls_base<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        self.inner.mrs_tpidr_el0(ctx, self.aarch64_cfg, &TLS_BASE)
+    }
+
+    /// Translates a guest `jmp` through a jump table (see
+    /// [`jump_table`](crate::jump_table)) into a bounds check and a table of
+    /// `b` instructions to `table.targets`.
+    ///
+    /// Out-of-range indices branch to `table.default`. Clobbers x16, x17 and
+    /// NZCV.
+    pub fn jump_table<Context, L: Clone>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        table: &crate::jump_table::JumpTable<'_, L>,
+    ) -> Result<(), <W as portal_solutions_asm_aarch64::out::WriterCore<Context>>::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>
+            + portal_solutions_asm_aarch64::out::Writer<L, Context>,
+        <W as portal_solutions_asm_aarch64::out::WriterCore<Context>>::Error:
+            From<portal_solutions_asm_aarch64::out::error::EmitError>,
+        H: ShimHooks<W, Context>,
+    {
+        use portal_solutions_asm_aarch64::ConditionCode;
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "guest_instruction",
+            mnemonic = "jmp",
+            index = self.guest_count,
+            targets = table.targets.len()
+        )
+        .entered();
+        self.pre_hook(ctx, "jmp")?;
+        let a64 = self.aarch64_cfg;
+        let (addr, scaled) = (Reg(16), Reg(17)); // x16, x17
+        let index = self.adapt(&table.index, cfg);
+        let entries = self.next_shim_label();
+
+        self.inner
+            .cmp_imm(ctx, a64, &index, table.targets.len() as i64)?;
+        self.inner
+            .bcond_label(ctx, a64, ConditionCode::HS, table.default.clone())?;
+        // Entries are 4-byte branches: offset = index * 4.
+        self.inner.add(ctx, a64, &scaled, &index, &index)?;
+        self.inner.add(ctx, a64, &scaled, &scaled, &scaled)?;
+        self.inner.adr_label(ctx, a64, &addr, entries)?;
+        self.inner.add(ctx, a64, &addr, &addr, &scaled)?;
+        self.inner.br(ctx, a64, &addr)?;
+        self.inner.set_label(ctx, a64, entries)?;
+        for target in table.targets {
+            self.inner.b_label(ctx, a64, target.clone())?;
+        }
+        self.post_hook(ctx, "jmp")
+    }
+
+    /// Emits the system call number table used by [`SyscallMode::Linux`], if
+    /// any translated `syscall` refers to it.
+    ///
+    /// Call once after translation, at a point execution never falls into
+    /// (e.g. after the last function).
+    pub fn emit_syscall_table<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        let Some(table) = self.syscall_table else {
+            return Ok(());
+        };
+        let cfg = self.aarch64_cfg;
+        self.inner.align_to(ctx, cfg, 2)?;
+        self.inner.set_label(ctx, cfg, table)?;
+        self.inner.db(ctx, cfg, &linux_table_bytes(cfg.big_endian))
+    }
+
+    /// Reports that the current guest instruction is translated approximately.
+    fn report_precision_loss<Context>(&mut self, mnemonic: &'static str, loss: PrecisionLoss)
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+        H: ShimHooks<W, Context>,
+    {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(mnemonic, index = self.guest_count, ?loss, "precision loss");
+        let insn = GuestInstruction {
+            mnemonic,
+            index: self.guest_count,
+            emitted_bytes: None,
+        };
+        self.hooks.precision_loss(insn, loss);
+    }
+
+    fn pre_hook<Context>(
+        &mut self,
+        ctx: &mut Context,
+        mnemonic: &'static str,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+        H: ShimHooks<W, Context>,
+    {
+        self.operand_error.set(None);
+        let insn = GuestInstruction {
+            mnemonic,
+            index: self.guest_count,
+            emitted_bytes: None,
+        };
+        self.hooks
+            .pre_instruction(&mut self.inner, ctx, self.aarch64_cfg, insn)?;
+        self.insn_start = self.inner.current_offset();
+        Ok(())
+    }
+
+    fn post_hook<Context>(
+        &mut self,
+        ctx: &mut Context,
+        mnemonic: &'static str,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+        W::Error: From<portal_solutions_asm_aarch64::out::error::EmitError>,
+        H: ShimHooks<W, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::error::EmitError;
+
+        if let Some(reason) = self.operand_error.take() {
+            return Err(EmitError::InvalidOperands { mnemonic, reason }.into());
+        }
+        let emitted_bytes = self
+            .inner
+            .current_offset()
+            .zip(self.insn_start.take())
+            .map(|(end, start)| end - start);
+        let insn = GuestInstruction {
+            mnemonic,
+            index: self.guest_count,
+            emitted_bytes,
+        };
+        self.guest_count += 1;
+        self.hooks
+            .post_instruction(&mut self.inner, ctx, self.aarch64_cfg, insn)
+    }
+
+    /// The guest stack slot at `disp` from the guest stack register.
+    fn guest_stack_slot(
+        &self,
+        disp: i32,
+        mode: portal_solutions_asm_aarch64::out::arg::AddressingMode,
+    ) -> portal_solutions_asm_aarch64::out::arg::MemArgKind<
+        portal_solutions_asm_aarch64::out::arg::ArgKind,
+    > {
+        portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
+            base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
+                reg: self.guest_stack.register(Reg(31)),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp,
+            size: MemorySize::_64,
+            reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
+            mode,
+        }
+    }
+
+    /// Returns whether `mem` is accessed with LDAR/STLR: under
+    /// [`MemoryModelPolicy::TsoViaAcqRel`], for general-purpose accesses
+    /// through a bare base register, the only form those instructions take.
+    fn acq_rel_address(&self, mem: &(dyn MemArg + '_)) -> bool {
+        use portal_solutions_asm_aarch64::out::arg::{AddressingMode, ArgKind, MemArgKind};
+
+        self.memory_model == MemoryModelPolicy::TsoViaAcqRel
+            && matches!(
+                mem.concrete_mem_kind(),
+                MemArgKind::Mem {
+                    base: ArgKind::Reg { .. },
                     offset: None,
-                    disp: slot_offset,
-                    size: MemorySize::_64,
+                    disp: 0,
                     reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
-                    mode: portal_solutions_asm_aarch64::out::arg::AddressingMode::Offset,
+                    mode: AddressingMode::Offset,
+                    ..
+                }
+            )
+    }
+
+    /// Folds a scaled index that no AArch64 load or store can encode.
+    ///
+    /// `[base, index, LSL #s]` is kept when there is no displacement and `s`
+    /// is 0 or matches the access size, the register-offset form of
+    /// LDR/STR. Otherwise `add tmp, base, index, lsl #s` forms the address
+    /// in the [`address_temp`] and the access becomes `[tmp, #disp]`.
+    fn fold_index<Context>(
+        &mut self,
+        ctx: &mut Context,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<
+        portal_solutions_asm_aarch64::out::arg::MemArgKind<
+            portal_solutions_asm_aarch64::out::arg::ArgKind,
+        >,
+        W::Error,
+    >
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::{AddressingMode, ArgKind, MemArgKind};
+
+        let mem = mem.concrete_mem_kind();
+        let MemArgKind::Mem {
+            base,
+            offset: Some((index, shift)),
+            disp,
+            size,
+            reg_class,
+            mode: AddressingMode::Offset,
+        } = mem
+        else {
+            return Ok(mem);
+        };
+        let access_shift = match size {
+            MemorySize::_8 => 0,
+            MemorySize::_16 => 1,
+            MemorySize::_32 => 2,
+            _ => 3,
+        };
+        if disp == 0 && (shift == 0 || shift == access_shift) {
+            return Ok(mem);
+        }
+        let temp = address_temp(self.aarch64_cfg.os);
+        self.inner
+            .add_lsl(ctx, self.aarch64_cfg, &temp, &base, &index, shift as u8)?;
+        Ok(MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: temp,
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp,
+            size,
+            reg_class,
+            mode: AddressingMode::Offset,
+        })
+    }
+
+    /// Reduces a memory operand to a bare base register, the only form the
+    /// LSE atomics take.
+    ///
+    /// An index and a displacement are added into the [`address_temp`] with
+    /// `add`; an operand that already is `[xn]` is returned unchanged.
+    fn bare_address<Context>(
+        &mut self,
+        ctx: &mut Context,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<
+        portal_solutions_asm_aarch64::out::arg::MemArgKind<
+            portal_solutions_asm_aarch64::out::arg::ArgKind,
+        >,
+        W::Error,
+    >
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::{AddressingMode, ArgKind, MemArgKind};
+
+        let mem = mem.concrete_mem_kind();
+        let MemArgKind::Mem {
+            mut base,
+            offset,
+            disp,
+            size,
+            reg_class,
+            mode: AddressingMode::Offset,
+        } = mem
+        else {
+            return Ok(mem);
+        };
+        let cfg = self.aarch64_cfg;
+        let temp = ArgKind::Reg {
+            reg: address_temp(cfg.os),
+            size: MemorySize::_64,
+        };
+        if let Some((index, shift)) = offset {
+            self.inner
+                .add_lsl(ctx, cfg, &temp, &base, &index, shift as u8)?;
+            base = temp;
+        }
+        if disp != 0 {
+            self.inner.add_imm(ctx, cfg, &temp, &base, disp.into())?;
+            base = temp;
+        }
+        Ok(MemArgKind::Mem {
+            base,
+            offset: None,
+            disp: 0,
+            size,
+            reg_class,
+            mode: AddressingMode::Offset,
+        })
+    }
+
+    /// Loads guest memory `mem` into `dest`, ordered after older guest
+    /// accesses as [`Self::memory_model`] requires.
+    fn guest_ldr<Context>(
+        &mut self,
+        ctx: &mut Context,
+        dest: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        let cfg = self.aarch64_cfg;
+        let mem = &self.fold_index(ctx, mem)?;
+        if self.acq_rel_address(mem) {
+            return self.inner.ldar(ctx, cfg, dest, mem);
+        }
+        self.inner.ldr(ctx, cfg, dest, mem)?;
+        if self.memory_model.is_tso() {
+            self.inner.dmb(ctx, cfg, Barrier::ISHLD)?;
+        }
+        Ok(())
+    }
+
+    /// Stores `src` to guest memory `mem`, ordered after older guest
+    /// accesses as [`Self::memory_model`] requires.
+    fn guest_str<Context>(
+        &mut self,
+        ctx: &mut Context,
+        src: &(dyn MemArg + '_),
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        let cfg = self.aarch64_cfg;
+        let mem = &self.fold_index(ctx, mem)?;
+        if self.acq_rel_address(mem) {
+            return self.inner.stlr(ctx, cfg, src, mem);
+        }
+        if self.memory_model.is_tso() {
+            self.inner.dmb(ctx, cfg, Barrier::ISHST)?;
+        }
+        self.inner.str(ctx, cfg, src, mem)
+    }
+
+    /// Prefetches the guest memory at `mem` with `op`.
+    ///
+    /// A displacement PRFM cannot encode, neither a multiple of 8 nor in
+    /// -256..=255, is added into the [`address_temp`] first.
+    fn guest_prfm<Context>(
+        &mut self,
+        ctx: &mut Context,
+        op: PrfOp,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+        let mut mem = self.fold_index(ctx, mem)?;
+        if let MemArgKind::Mem { disp, .. } = mem {
+            let scaled = disp >= 0 && disp % 8 == 0 && disp / 8 < 0x1000;
+            if !scaled && !(-256..=255).contains(&disp) {
+                mem = self.bare_address(ctx, &mem)?;
+            }
+        }
+        self.inner.prfm(ctx, self.aarch64_cfg, op, &mem)
+    }
+
+    /// Performs the data cache maintenance `op` on the guest line at `mem`.
+    ///
+    /// DC takes only a register, so an index and a displacement are added
+    /// into the [`address_temp`] first.
+    fn guest_dc<Context>(
+        &mut self,
+        ctx: &mut Context,
+        op: DcOp,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+        let addr = match self.bare_address(ctx, mem)? {
+            MemArgKind::Mem { base, .. } => base,
+            MemArgKind::NoMem(arg) => arg,
+        };
+        self.inner.dc(ctx, self.aarch64_cfg, op, &addr)
+    }
+
+    /// Reads the virtual counter into guest EDX:EAX (x2 and x0), with the
+    /// upper halves of RDX and RAX cleared as `rdtsc` leaves them.
+    fn guest_rdtsc<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::timestamp::ReadTimestamp;
+
+        let cfg = self.aarch64_cfg;
+        let (rax, rdx) = (Reg(0), Reg(2));
+        self.inner.read_timestamp(ctx, cfg, &rdx)?;
+        self.inner.ubfx(ctx, cfg, &rax, &rdx, 0, 32)?;
+        self.inner.lsr(ctx, cfg, &rdx, &rdx, &32u64)
+    }
+
+    /// Pushes a 64-bit `value` onto the guest stack.
+    ///
+    /// Without a bias this is `str value, [sp, #-8]!`; with one, the
+    /// adjustment and the store are separate.
+    fn push_guest<Context>(
+        &mut self,
+        ctx: &mut Context,
+        value: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::AddressingMode;
+
+        let bias = self.guest_stack.bias;
+        if bias == 0 {
+            let slot = self.guest_stack_slot(-8, AddressingMode::PreIndex);
+            return self.guest_str(ctx, value, &slot);
+        }
+        let sp = self.guest_stack.register(Reg(31));
+        self.inner.sub(ctx, self.aarch64_cfg, &sp, &sp, &8u64)?;
+        let slot = self.guest_stack_slot(bias, AddressingMode::Offset);
+        self.guest_str(ctx, value, &slot)
+    }
+
+    /// Pops the 64-bit top of the guest stack into `dest`.
+    ///
+    /// Without a bias this is `ldr dest, [sp], #8`; with one, the load and
+    /// the adjustment are separate.
+    fn pop_guest<Context>(
+        &mut self,
+        ctx: &mut Context,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::AddressingMode;
+
+        let bias = self.guest_stack.bias;
+        if bias == 0 {
+            let slot = self.guest_stack_slot(8, AddressingMode::PostIndex);
+            return self.guest_ldr(ctx, dest, &slot);
+        }
+        let slot = self.guest_stack_slot(bias, AddressingMode::Offset);
+        self.guest_ldr(ctx, dest, &slot)?;
+        let sp = self.guest_stack.register(Reg(31));
+        self.inner.add(ctx, self.aarch64_cfg, &sp, &sp, &8u64)
+    }
+
+    /// Pushes the return address in `lr` onto the guest stack, or saves it
+    /// on the shadow stack under [`ReturnStack::Shadow`], signing it first
+    /// under [`pac_ret`](Self::pac_ret).
+    ///
+    /// `paciasp` takes the host `sp` as its modifier; the guest stack is
+    /// balanced again by the matching `ret`, so `autiasp` sees the same one.
+    fn push_return_address<Context>(
+        &mut self,
+        ctx: &mut Context,
+        lr: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::AddressingMode;
+
+        if self.pac_ret {
+            self.inner.paciasp(ctx, self.aarch64_cfg)?;
+        }
+        match self.return_stack {
+            ReturnStack::Standard => self.push_guest(ctx, lr),
+            ReturnStack::Shadow(ss) => {
+                let slot = shadow_stack_slot(ss, -8, AddressingMode::PreIndex);
+                self.inner.str(ctx, self.aarch64_cfg, lr, &slot)
+            }
+        }
+    }
+
+    /// Pops the return address into `lr` from wherever
+    /// [`push_return_address`](Self::push_return_address) put it,
+    /// authenticating it under [`pac_ret`](Self::pac_ret).
+    fn pop_return_address<Context>(
+        &mut self,
+        ctx: &mut Context,
+        lr: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::AddressingMode;
+
+        match self.return_stack {
+            ReturnStack::Standard => self.pop_guest(ctx, lr)?,
+            ReturnStack::Shadow(ss) => {
+                let slot = shadow_stack_slot(ss, 8, AddressingMode::PostIndex);
+                self.inner.ldr(ctx, self.aarch64_cfg, lr, &slot)?
+            }
+        }
+        if self.pac_ret {
+            self.inner.autiasp(ctx, self.aarch64_cfg)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the width to byte-swap a general-purpose guest memory access
+    /// with, or `None` if no swap is needed.
+    ///
+    /// Guest memory is little-endian; on a big-endian target every load from
+    /// it and every store to it must reverse the bytes of the value.
+    fn guest_swap_width(&self, mem: &MemArgAdapter<'_>) -> Option<MemorySize> {
+        use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+        if !self.aarch64_cfg.big_endian {
+            return None;
+        }
+        match mem.concrete_mem_kind() {
+            MemArgKind::Mem {
+                size: MemorySize::_8,
+                ..
+            } => None,
+            MemArgKind::Mem {
+                size,
+                reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
+                ..
+            } => Some(size),
+            _ => None,
+        }
+    }
+
+    /// Reverses the low `size` bytes of `reg`, clearing the rest.
+    fn swap_bytes<Context>(
+        &mut self,
+        ctx: &mut Context,
+        reg: Reg,
+        size: MemorySize,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::ArgKind;
+
+        let cfg = self.aarch64_cfg;
+        match size {
+            MemorySize::_16 => {
+                // REV on the W view moves the halfword to the top.
+                let w = ArgKind::Reg {
+                    reg,
+                    size: MemorySize::_32,
                 };
-                let temp_idx = Reg(18);
-                // Load index value from APX backing store
-                self.inner
-                    .ldr(ctx, self.aarch64_cfg, &temp_idx, &mem_slot)?;
+                self.inner.rev(ctx, cfg, &w, &w)?;
+                self.inner.lsr(ctx, cfg, &w, &w, &16u64)
+            }
+            size => {
+                let r = ArgKind::Reg { reg, size };
+                self.inner.rev(ctx, cfg, &r, &r)
+            }
+        }
+    }
 
-                // Rebuild the memory argument using temp_idx as the index register
-                // Extract original components to get base, disp, size and reg_class
-                if let X64MemArgKind::Mem {
-                    base: orig_base,
-                    offset: _orig_offset,
-                    disp,
-                    size,
-                    reg_class,
-                    segment: _,
-                } = adapter.inner.concrete_mem_kind()
-                {
-                    let aarch64_base = convert_arg_kind(orig_base, adapter.arch);
-                    let aarch64_disp = disp as i32;
-                    let off_arg = portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
-                        reg: temp_idx,
-                        size: MemorySize::_64,
-                    };
-                    let mem_arg = portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
-                        base: aarch64_base,
-                        offset: Some((off_arg, scale)),
-                        disp: aarch64_disp,
-                        size,
-                        reg_class: convert_register_class(reg_class),
-                        mode: portal_solutions_asm_aarch64::out::arg::AddressingMode::Offset,
-                    };
-                    // Perform final load into dest using reconstructed memory operand
-                    self.inner.ldr(ctx, self.aarch64_cfg, dest, &mem_arg)
-                } else {
-                    // should not happen: adapter indicated MemIndex but not a Mem
-                    self.inner.ldr(ctx, self.aarch64_cfg, dest, adapter)
+    /// Stores `src` to guest memory, byte-swapping through x16 on big-endian
+    /// targets.
+    fn store_guest<Context>(
+        &mut self,
+        ctx: &mut Context,
+        src: &(dyn MemArg + '_),
+        dest: &MemArgAdapter<'_>,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        let Some(size) = self.guest_swap_width(dest) else {
+            return self.guest_str(ctx, src, dest);
+        };
+        let temp = Reg(16);
+        self.inner.mov(ctx, self.aarch64_cfg, &temp, src)?;
+        self.swap_bytes(ctx, temp, size)?;
+        self.guest_str(ctx, &temp, dest)
+    }
+
+    /// Loads `ah`, `ch`, `dh` or `bh` of the guest register `reg` into
+    /// `dest`, zero-extended, or sign-extended when `signed` is set.
+    fn read_high_byte<Context>(
+        &mut self,
+        ctx: &mut Context,
+        dest: &(dyn MemArg + '_),
+        reg: Reg,
+        arch: X64Arch,
+        signed: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        let cfg = self.aarch64_cfg;
+        let src = map_x64_register_to_aarch64(reg, arch);
+        if signed {
+            self.inner.lsl(ctx, cfg, dest, &src, &48u64)?;
+            self.inner.asr(ctx, cfg, dest, dest, &56u64)
+        } else {
+            self.inner.ubfx(ctx, cfg, dest, &src, 8, 8)
+        }
+    }
+
+    /// Translates a `mov` whose destination is a high-byte register: the
+    /// byte is inserted into bits 8–15 with `bfi`.
+    fn mov_to_high_byte<Context>(
+        &mut self,
+        ctx: &mut Context,
+        arch: X64Arch,
+        reg: Reg,
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        let (cfg, temp) = (self.aarch64_cfg, Reg(16)); // x16
+        match src.concrete_mem_kind() {
+            X64MemArgKind::NoMem(X64ArgKind::HighByte(s)) => {
+                self.read_high_byte(ctx, &temp, s, arch, false)?
+            }
+            X64MemArgKind::NoMem(X64ArgKind::Lit(v)) => self.inner.mov_imm(ctx, cfg, &temp, v)?,
+            X64MemArgKind::NoMem(_) => {
+                let src_adapter = self.adapt(src, arch);
+                self.inner.mov(ctx, cfg, &temp, &src_adapter)?
+            }
+            _ => {
+                let src_adapter = self.adapt(src, arch);
+                self.load_memarg_into_temp(ctx, &src_adapter, &temp)?
+            }
+        }
+        let dest = map_x64_register_to_aarch64(reg, arch);
+        self.inner.bfi(ctx, cfg, &dest, &temp, 8, 8)
+    }
+
+    /// Translates a `mov` whose source is a high-byte register. Only the low
+    /// byte of a register destination changes, as on x86-64.
+    fn mov_from_high_byte<Context>(
+        &mut self,
+        ctx: &mut Context,
+        arch: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        reg: Reg,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        let temp = Reg(16); // x16
+        let dest_adapter = self.adapt(dest, arch);
+        self.read_high_byte(ctx, &temp, reg, arch, false)?;
+        match dest.concrete_mem_kind() {
+            X64MemArgKind::Mem { .. } => self.store_guest(ctx, &temp, &dest_adapter),
+            _ => self
+                .inner
+                .bfi(ctx, self.aarch64_cfg, &dest_adapter, &temp, 0, 8),
+        }
+    }
+
+    /// Translates a `movzx` or `movsx` from a high-byte register.
+    fn extend_high_byte<Context>(
+        &mut self,
+        ctx: &mut Context,
+        arch: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        reg: Reg,
+        signed: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        let dest_adapter = self.adapt(dest, arch);
+        match dest.concrete_mem_kind() {
+            X64MemArgKind::Mem { .. } => {
+                let temp = Reg(16); // x16
+                self.read_high_byte(ctx, &temp, reg, arch, signed)?;
+                self.store_guest(ctx, &temp, &dest_adapter)
+            }
+            _ => self.read_high_byte(ctx, &dest_adapter, reg, arch, signed),
+        }
+    }
+
+    /// Load a value from a possibly-APX memory argument into `dest`.
+    ///
+    /// If the given adapter references a memory operand whose index is an APX register,
+    /// this will first load the APX index value from the APX backing store ([X28 + slot])
+    /// into a temporary register and then perform the actual load using that temp as the
+    /// scaled index. Otherwise, delegates to the underlying writer's LDR. On big-endian
+    /// targets the loaded value is then byte-swapped from the guest's little-endian layout.
+    fn load_memarg_into_temp<Context>(
+        &mut self,
+        ctx: &mut Context,
+        adapter: &MemArgAdapter<'_>,
+        dest: &Reg,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        self.load_memarg_raw(ctx, adapter, dest)?;
+        match self.guest_swap_width(adapter) {
+            Some(size) => self.swap_bytes(ctx, *dest, size),
+            None => Ok(()),
+        }
+    }
+
+    /// [`Self::load_memarg_into_temp`] without the byte swap.
+    fn load_memarg_raw<Context>(
+        &mut self,
+        ctx: &mut Context,
+        adapter: &MemArgAdapter<'_>,
+        dest: &Reg,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_x86_64::out::arg::MemArgKind as X64MemArgKind;
+
+        match adapter.apx_access() {
+            APXAccess::MemIndex {
+                base: _base,
+                index,
+                scale,
+            } => {
+                // Load the APX index value from APX backing store into a temp register
+                let slot_offset = (index as i32) * 8; // each slot is 8 bytes
+                let mem_slot = portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
+                    base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
+                        reg: Reg(28),
+                        size: MemorySize::_64,
+                    },
+                    offset: None,
+                    disp: slot_offset,
+                    size: MemorySize::_64,
+                    reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
+                    mode: portal_solutions_asm_aarch64::out::arg::AddressingMode::Offset,
+                };
+                let temp_idx = address_temp(self.aarch64_cfg.os);
+                // Load index value from APX backing store
+                self.inner
+                    .ldr(ctx, self.aarch64_cfg, &temp_idx, &mem_slot)?;
+
+                // Rebuild the memory argument using temp_idx as the index register
+                // Extract original components to get base, disp, size and reg_class
+                if let X64MemArgKind::Mem {
+                    base: orig_base,
+                    offset: _orig_offset,
+                    disp,
+                    size,
+                    reg_class,
+                    segment: _,
+                } = adapter.inner.concrete_mem_kind()
+                {
+                    let aarch64_base = convert_arg_kind(orig_base, adapter.arch);
+                    let aarch64_disp = disp;
+                    let off_arg = portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
+                        reg: temp_idx,
+                        size: MemorySize::_64,
+                    };
+                    let mem_arg = portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
+                        base: aarch64_base,
+                        offset: Some((off_arg, scale.trailing_zeros())),
+                        disp: aarch64_disp,
+                        size,
+                        reg_class: convert_register_class(reg_class),
+                        mode: portal_solutions_asm_aarch64::out::arg::AddressingMode::Offset,
+                    };
+                    // Perform final load into dest using reconstructed memory operand
+                    self.guest_ldr(ctx, dest, &mem_arg)
+                } else {
+                    // should not happen: adapter indicated MemIndex but not a Mem
+                    self.guest_ldr(ctx, dest, adapter)
+                }
+            }
+            _ => {
+                // Default: delegate to underlying writer
+                self.guest_ldr(ctx, dest, adapter)
+            }
+        }
+    }
+
+    /// Lowers a widening multiply (`hi:lo = a * b`) to UMULH/SMULH plus MUL.
+    ///
+    /// Memory and immediate sources are loaded into x16/x17 first. Whichever
+    /// half does not overwrite a source is computed first; if both would, the
+    /// high half is staged in x17. `hi` and `lo` must be distinct registers.
+    fn emulate_wide_mul<Context>(
+        &mut self,
+        ctx: &mut Context,
+        hi: &MemArgAdapter<'_>,
+        lo: &MemArgAdapter<'_>,
+        a: &MemArgAdapter<'_>,
+        b: &MemArgAdapter<'_>,
+        signed: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::{ArgKind, MemArgKind};
+
+        let cfg = self.aarch64_cfg;
+        let reg_of = |m: &dyn MemArg| match m.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => Some(reg),
+            _ => None,
+        };
+        let (temp_a, temp_b) = (Reg(16), Reg(17)); // x16, x17
+        let a: &dyn MemArg = match a.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { .. }) => a,
+            MemArgKind::NoMem(ArgKind::Lit(v)) => {
+                self.inner.mov_imm(ctx, cfg, &temp_a, v)?;
+                &temp_a
+            }
+            _ => {
+                self.load_memarg_into_temp(ctx, a, &temp_a)?;
+                &temp_a
+            }
+        };
+        let b: &dyn MemArg = match b.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { .. }) => b,
+            MemArgKind::NoMem(ArgKind::Lit(v)) => {
+                self.inner.mov_imm(ctx, cfg, &temp_b, v)?;
+                &temp_b
+            }
+            _ => {
+                self.load_memarg_into_temp(ctx, b, &temp_b)?;
+                &temp_b
+            }
+        };
+        let high = |w: &mut W, ctx: &mut Context, dest: &dyn MemArg| {
+            if signed {
+                w.smulh(ctx, cfg, dest, a, b)
+            } else {
+                w.umulh(ctx, cfg, dest, a, b)
+            }
+        };
+
+        let srcs = [reg_of(a), reg_of(b)];
+        if !srcs.contains(&reg_of(hi)) {
+            high(&mut self.inner, ctx, hi)?;
+            self.inner.mul(ctx, cfg, lo, a, b)
+        } else if !srcs.contains(&reg_of(lo)) {
+            self.inner.mul(ctx, cfg, lo, a, b)?;
+            high(&mut self.inner, ctx, hi)
+        } else {
+            // hi and lo are exactly the two source registers, so x17 is free.
+            high(&mut self.inner, ctx, &temp_b)?;
+            self.inner.mul(ctx, cfg, lo, a, b)?;
+            self.inner.mov(ctx, cfg, hi, &temp_b)
+        }
+    }
+
+    /// Copies a register, immediate or memory operand into `temp`.
+    fn load_operand_into_temp<Context>(
+        &mut self,
+        ctx: &mut Context,
+        adapter: &MemArgAdapter<'_>,
+        temp: &Reg,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::{ArgKind, MemArgKind};
+
+        match adapter.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { .. }) => {
+                self.inner.mov(ctx, self.aarch64_cfg, temp, adapter)
+            }
+            MemArgKind::NoMem(ArgKind::Lit(v)) => {
+                self.inner.mov_imm(ctx, self.aarch64_cfg, temp, v)
+            }
+            _ => self.load_memarg_into_temp(ctx, adapter, temp),
+        }
+    }
+
+    /// Emits the guard selected by [`div_fault`](Self::div_fault) ahead of a
+    /// translated DIV (`signed == false`) or IDIV (`signed == true`).
+    ///
+    /// The divisor is copied into x17 and checked for zero; IDIV also checks
+    /// for `INT_MIN / -1`, using x16 for the dividend. NZCV is clobbered,
+    /// which x86-64 allows since DIV/IDIV leave the flags undefined.
+    fn emit_div_guard<Context>(
+        &mut self,
+        ctx: &mut Context,
+        a: &MemArgAdapter<'_>,
+        b: &MemArgAdapter<'_>,
+        signed: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::ConditionCode;
+
+        if self.div_fault == DivFaultMode::Native {
+            return Ok(());
+        }
+        let cfg = self.aarch64_cfg;
+        let (temp_a, temp_b) = (Reg(16), Reg(17)); // x16, x17
+        let fault = self.next_shim_label();
+        let ok = self.next_shim_label();
+
+        self.load_operand_into_temp(ctx, b, &temp_b)?;
+        self.inner.cmp_imm(ctx, cfg, &temp_b, 0)?;
+        if signed {
+            self.inner.bcond_label(ctx, cfg, ConditionCode::EQ, fault)?;
+            // b == -1 exactly when !b == 0
+            self.inner.mvn(ctx, cfg, &temp_a, &temp_b)?;
+            self.inner.cmp_imm(ctx, cfg, &temp_a, 0)?;
+            self.inner.bcond_label(ctx, cfg, ConditionCode::NE, ok)?;
+            // a - 1 overflows exactly when a == INT_MIN
+            self.load_operand_into_temp(ctx, a, &temp_a)?;
+            self.inner.cmp_imm(ctx, cfg, &temp_a, 1)?;
+            self.inner.bcond_label(ctx, cfg, ConditionCode::VC, ok)?;
+        } else {
+            self.inner.bcond_label(ctx, cfg, ConditionCode::NE, ok)?;
+        }
+
+        self.inner.set_label(ctx, cfg, fault)?;
+        match self.div_fault {
+            DivFaultMode::Branch(target) => self.inner.b_label(ctx, cfg, target)?,
+            _ => self.inner.brk(ctx, cfg, 0)?,
+        }
+        self.inner.set_label(ctx, cfg, ok)
+    }
+
+    /// Lowers x86-64 ADC (`subtract == false`) or SBB to ADCS or SBCS.
+    ///
+    /// C holds the inverted x86 borrow, as SUBS leaves it, which is exactly
+    /// the carry-in SBCS expects. ADCS wants CF itself, so C is flipped
+    /// before it and flipped back afterwards.
+    ///
+    /// AArch64 has no immediate or memory forms, so a literal or memory `b`
+    /// is first placed in x17 (MOV and LDR leave the flags alone), and a
+    /// memory `a` goes through x16.
+    fn carry_op<Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+        subtract: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::{ArgKind, MemArgKind};
+
+        let a_adapter = self.adapt(a, cfg);
+        let b_adapter = self.adapt(b, cfg);
+        let cfg = self.aarch64_cfg;
+        let (temp_a, temp_b) = (Reg(16), Reg(17)); // x16, x17
+        if !subtract {
+            self.invert_carry(ctx, &temp_a)?;
+        }
+        let b_reg: &(dyn MemArg + '_) = match b_adapter.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Lit(v)) => {
+                self.inner.mov_imm(ctx, cfg, &temp_b, v)?;
+                &temp_b
+            }
+            MemArgKind::Mem { .. } => {
+                self.load_memarg_into_temp(ctx, &b_adapter, &temp_b)?;
+                &temp_b
+            }
+            _ => &b_adapter,
+        };
+        let a_mem = matches!(a_adapter.concrete_mem_kind(), MemArgKind::Mem { .. });
+        let a_reg: &(dyn MemArg + '_) = if a_mem {
+            self.load_memarg_into_temp(ctx, &a_adapter, &temp_a)?;
+            &temp_a
+        } else {
+            &a_adapter
+        };
+        if subtract {
+            self.inner.sbcs(ctx, cfg, a_reg, a_reg, b_reg)?;
+        } else {
+            self.inner.adcs(ctx, cfg, a_reg, a_reg, b_reg)?;
+        }
+        if a_mem {
+            self.store_guest(ctx, &temp_a, &a_adapter)?;
+        }
+        if !subtract {
+            self.invert_carry(ctx, &temp_b)?;
+        }
+        Ok(())
+    }
+
+    /// Flips C, turning the CF that ADDS and ADCS leave into the inverted
+    /// borrow SUBS leaves, which the shim's conditions, SBB and LAHF all
+    /// expect. Clobbers `scratch`.
+    fn invert_carry<Context>(&mut self, ctx: &mut Context, scratch: &Reg) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        let cfg = self.aarch64_cfg;
+        self.inner.mrs_nzcv(ctx, cfg, scratch)?;
+        self.inner.eor_imm(ctx, cfg, scratch, scratch, 1 << 29)?;
+        self.inner.msr_nzcv(ctx, cfg, scratch)
+    }
+
+    /// Lowers x86-64 NEG to NEGS, and INC/DEC to ADDS/SUBS by one.
+    ///
+    /// A memory `a` goes through x16. INC and DEC leave CF alone on x86, so
+    /// NZCV is saved in x17 first and its C bit put back afterwards. SP is
+    /// adjusted without touching the flags, as for ADD/SUB, except that NEG
+    /// negates a copy in x16 and sets them.
+    fn unary_op<Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        op: UnaryOp,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+        let a_adapter = self.adapt(a, cfg);
+        let cfg = self.aarch64_cfg;
+        let (value, saved) = (Reg(16), Reg(17)); // x16, x17
+        if is_sp(&a_adapter) {
+            return match op {
+                UnaryOp::Inc => self.inner.add_imm(ctx, cfg, &a_adapter, &a_adapter, 1),
+                UnaryOp::Dec => self.inner.sub_imm(ctx, cfg, &a_adapter, &a_adapter, 1),
+                UnaryOp::Neg => {
+                    // NEGS cannot name SP: negate a copy in x16 and move it back
+                    self.inner.add_imm(ctx, cfg, &value, &a_adapter, 0)?;
+                    self.inner.negs(ctx, cfg, &value, &value)?;
+                    self.inner.add_imm(ctx, cfg, &a_adapter, &value, 0)
+                }
+            };
+        }
+        if op != UnaryOp::Neg {
+            self.inner.mrs_nzcv(ctx, cfg, &saved)?;
+        }
+        let a_mem = matches!(a_adapter.concrete_mem_kind(), MemArgKind::Mem { .. });
+        let a_reg: &(dyn MemArg + '_) = if a_mem {
+            self.load_memarg_into_temp(ctx, &a_adapter, &value)?;
+            &value
+        } else {
+            &a_adapter
+        };
+        match op {
+            UnaryOp::Neg => self.inner.negs(ctx, cfg, a_reg, a_reg)?,
+            UnaryOp::Inc => self.inner.adds(ctx, cfg, a_reg, a_reg, &1u64)?,
+            UnaryOp::Dec => self.inner.subs(ctx, cfg, a_reg, a_reg, &1u64)?,
+        }
+        if a_mem {
+            self.store_guest(ctx, &value, &a_adapter)?;
+        }
+        if op != UnaryOp::Neg {
+            self.inner.mrs_nzcv(ctx, cfg, &value)?;
+            self.inner.lsr(ctx, cfg, &saved, &saved, &29u64)?;
+            self.inner.bfi(ctx, cfg, &value, &saved, 29, 1)?;
+            self.inner.msr_nzcv(ctx, cfg, &value)?;
+        }
+        Ok(())
+    }
+
+    /// Lowers x86-64 MINSD/MINSS (`keep` = MI) or MAXSD/MAXSS (`keep` = GT)
+    /// on adapted operands, whose size selects the precision.
+    ///
+    /// Under [`NanPolicy::Host`] this is FMIN/FMAX. Under
+    /// [`NanPolicy::X86`], `dest` is kept only when `keep` holds after
+    /// `fcmp dest, src`, which is false for NaNs and equal operands, as on
+    /// x86; NZCV is saved in x16 around the compare.
+    fn float_min_max<Context>(
+        &mut self,
+        ctx: &mut Context,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        keep: portal_solutions_asm_aarch64::ConditionCode,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::ConditionCode;
+
+        let cfg = self.aarch64_cfg;
+        match (self.nan_policy, keep) {
+            (NanPolicy::Host, ConditionCode::MI) => self.inner.fmin(ctx, cfg, dest, dest, src),
+            (NanPolicy::Host, _) => self.inner.fmax(ctx, cfg, dest, dest, src),
+            (NanPolicy::X86, _) => {
+                let saved = Reg(16); // x16
+                self.inner.mrs_nzcv(ctx, cfg, &saved)?;
+                self.inner.fcmp(ctx, cfg, dest, src)?;
+                self.inner.fcsel(ctx, cfg, keep, dest, dest, src)?;
+                self.inner.msr_nzcv(ctx, cfg, &saved)
+            }
+        }
+    }
+
+    /// Lowers x86-64 UCOMISD/UCOMISS on adapted operands to FCMP. An
+    /// unordered FCMP sets C and V, which the shim's conditions read as
+    /// "above"; under [`NanPolicy::X86`] it is rewritten to Z alone, i.e. ZF
+    /// and CF.
+    fn float_compare<Context>(
+        &mut self,
+        ctx: &mut Context,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::ConditionCode;
+
+        let cfg = self.aarch64_cfg;
+        self.inner.fcmp(ctx, cfg, a, b)?;
+        if self.nan_policy == NanPolicy::X86 {
+            let (nzcv, unordered) = (Reg(16), Reg(17)); // x16, x17
+            self.inner.mrs_nzcv(ctx, cfg, &nzcv)?;
+            self.inner.mov_imm(ctx, cfg, &unordered, 1 << 30)?;
+            self.inner
+                .csel(ctx, cfg, ConditionCode::VS, &nzcv, &unordered, &nzcv)?;
+            self.inner.msr_nzcv(ctx, cfg, &nzcv)?;
+        }
+        Ok(())
+    }
+
+    /// Lowers x86-64 `op a, imm` through the AArch64 immediate form of `op`.
+    ///
+    /// A memory `a` is loaded into x16 and written back afterwards. Returns
+    /// `Ok(false)` without emitting anything when `imm` has no immediate
+    /// encoding for `op`, so the caller can fall back to the register form.
+    fn try_imm_op<Context>(
+        &mut self,
+        ctx: &mut Context,
+        op: ImmOp,
+        a: &MemArgAdapter<'_>,
+        imm: i64,
+    ) -> Result<bool, W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::imm::{arith_imm, logical_imm};
+        use portal_solutions_asm_aarch64::out::arg::{ArgKind, MemArgKind};
+
+        let encodable = match op {
+            ImmOp::Add | ImmOp::Sub | ImmOp::Cmp => arith_imm(imm.unsigned_abs()).is_some(),
+            ImmOp::And | ImmOp::Or | ImmOp::Xor => logical_imm(imm as u64).is_some(),
+        };
+        let is_mem = match a.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { .. }) => false,
+            MemArgKind::Mem { .. } => true,
+            _ => return Ok(false),
+        };
+        if !encodable {
+            return Ok(false);
+        }
+        let cfg = self.aarch64_cfg;
+        let temp = Reg(16); // x16
+        if is_mem {
+            self.load_memarg_into_temp(ctx, a, &temp)?;
+        }
+        let reg: &(dyn MemArg + '_) = if is_mem { &temp } else { a };
+        // ADDS/SUBS cannot write SP. Adding -n carries exactly when
+        // subtracting n does not borrow, so negative values flip the op;
+        // either way an ADD leaves C = CF, which is flipped afterwards.
+        let sp = is_sp(reg);
+        match op {
+            ImmOp::Add if sp => self.inner.add_imm(ctx, cfg, reg, reg, imm)?,
+            ImmOp::Sub if sp => self.inner.sub_imm(ctx, cfg, reg, reg, imm)?,
+            ImmOp::Add if imm < 0 => self.inner.subs(ctx, cfg, reg, reg, &imm.unsigned_abs())?,
+            ImmOp::Add => self.inner.adds(ctx, cfg, reg, reg, &(imm as u64))?,
+            ImmOp::Sub if imm < 0 => self.inner.adds(ctx, cfg, reg, reg, &imm.unsigned_abs())?,
+            ImmOp::Sub => self.inner.subs(ctx, cfg, reg, reg, &(imm as u64))?,
+            ImmOp::And => self.inner.and_imm(ctx, cfg, reg, reg, imm as u64)?,
+            ImmOp::Or => self.inner.orr_imm(ctx, cfg, reg, reg, imm as u64)?,
+            ImmOp::Xor => self.inner.eor_imm(ctx, cfg, reg, reg, imm as u64)?,
+            ImmOp::Cmp => {
+                self.inner.cmp_imm(ctx, cfg, reg, imm)?;
+                return Ok(true);
+            }
+        }
+        if is_mem {
+            self.guest_str(ctx, &temp, a)?;
+        }
+        if op == ImmOp::Add && !sp {
+            self.invert_carry(ctx, &temp)?;
+        }
+        Ok(true)
+    }
+
+    /// Lowers `syscall` to `svc #0` under the Linux AArch64 convention.
+    ///
+    /// The x86-64 number in x0 (RAX) is looked up in the syscall table and
+    /// RDI, RSI, RDX, R10, R8, R9 (x4, x3, x2, x7, x5, x6) are moved into
+    /// x0-x5. The kernel preserves everything but x0, so afterwards RSI and R8
+    /// are moved back from x1 and x4 and RDI from x16. x1 (RCX) and x8 (R11)
+    /// end up clobbered, as on x86-64.
+    fn linux_syscall<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::ConditionCode;
+        use portal_solutions_asm_aarch64::out::arg::{AddressingMode, ArgKind, MemArgKind};
+
+        let cfg = self.aarch64_cfg;
+        let (rax, rcx, rsi, rdi, r8, r9, r10, r11) = (
+            Reg(0),
+            Reg(1),
+            Reg(3),
+            Reg(4),
+            Reg(5),
+            Reg(6),
+            Reg(7),
+            Reg(8),
+        );
+        let (saved_rdi, scratch) = (Reg(16), Reg(17)); // x16, x17
+        let table = match self.syscall_table {
+            Some(table) => table,
+            None => {
+                let table = self.next_shim_label();
+                self.syscall_table = Some(table);
+                table
+            }
+        };
+        let nosys = self.next_shim_label();
+        let done = self.next_shim_label();
+
+        // RCX holds the guest's flags during the lookup and R11 the number.
+        self.inner.mrs_nzcv(ctx, cfg, &rcx)?;
+        self.inner.cmp_imm(ctx, cfg, &rax, LINUX_TABLE_LEN as i64)?;
+        self.inner.bcond_label(ctx, cfg, ConditionCode::HS, nosys)?;
+        self.inner.adr_label(ctx, cfg, &r11, table)?;
+        self.inner.add(ctx, cfg, &scratch, &rax, &rax)?;
+        self.inner.add(ctx, cfg, &r11, &r11, &scratch)?;
+        let number = ArgKind::Reg {
+            reg: r11,
+            size: MemorySize::_16,
+        };
+        let entry = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: r11,
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_16,
+            reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
+            mode: AddressingMode::Offset,
+        };
+        self.inner.ldr(ctx, cfg, &number, &entry)?;
+        self.inner.mov_imm(ctx, cfg, &scratch, NO_SYSCALL as u64)?;
+        self.inner.cmp(ctx, cfg, &r11, &scratch)?;
+        self.inner.bcond_label(ctx, cfg, ConditionCode::EQ, nosys)?;
+        self.inner.msr_nzcv(ctx, cfg, &rcx)?;
+
+        // x0-x5 = RDI, RSI, RDX, R10, R8, R9; RDX (x2) is already in place.
+        self.inner.mov(ctx, cfg, &saved_rdi, &rdi)?;
+        self.inner.mov(ctx, cfg, &Reg(0), &rdi)?;
+        self.inner.mov(ctx, cfg, &Reg(1), &rsi)?;
+        self.inner.mov(ctx, cfg, &Reg(3), &r10)?;
+        self.inner.mov(ctx, cfg, &Reg(4), &r8)?;
+        self.inner.mov(ctx, cfg, &Reg(5), &r9)?;
+        self.inner.svc(ctx, cfg, 0)?;
+        self.inner.mov(ctx, cfg, &rsi, &Reg(1))?;
+        self.inner.mov(ctx, cfg, &r8, &Reg(4))?;
+        self.inner.mov(ctx, cfg, &rdi, &saved_rdi)?;
+        self.inner.b_label(ctx, cfg, done)?;
+
+        self.inner.set_label(ctx, cfg, nosys)?;
+        self.inner.msr_nzcv(ctx, cfg, &rcx)?;
+        self.inner.mov_imm(ctx, cfg, &rax, ENOSYS_RESULT as u64)?;
+        self.inner.set_label(ctx, cfg, done)
+    }
+
+    /// Lowers a string instruction to a loop counting RCX (x1) down (see
+    /// [`crate::string_ops`]).
+    ///
+    /// `step` processes one element; in the comparing loops it must leave the
+    /// comparison in NZCV. The guest's flags are held in x16 while RCX is
+    /// tested, so a zero count leaves them unchanged and `step` may only
+    /// clobber x16 if it sets the flags itself.
+    fn string_loop<Context>(
+        &mut self,
+        ctx: &mut Context,
+        kind: StringLoop,
+        mut step: impl FnMut(&mut Self, &mut Context) -> Result<(), W::Error>,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::ConditionCode;
+
+        if kind == StringLoop::Once {
+            return step(self, ctx);
+        }
+        let cfg = self.aarch64_cfg;
+        let (rcx, flags) = (Reg(1), Reg(16)); // RCX → x1, x16
+        let check = self.next_shim_label();
+        let exit = self.next_shim_label();
+        let done = self.next_shim_label();
+
+        self.inner.mrs_nzcv(ctx, cfg, &flags)?;
+        self.inner.set_label(ctx, cfg, check)?;
+        self.inner.cmp_imm(ctx, cfg, &rcx, 0)?;
+        self.inner.bcond_label(ctx, cfg, ConditionCode::EQ, exit)?;
+        step(self, ctx)?;
+        // SUB leaves NZCV alone, so the element comparison survives it.
+        self.inner.sub_imm(ctx, cfg, &rcx, &rcx, 1)?;
+        let stop = match kind {
+            StringLoop::WhileEqual => Some(ConditionCode::NE),
+            StringLoop::WhileNotEqual => Some(ConditionCode::EQ),
+            _ => None,
+        };
+        if let Some(stop) = stop {
+            self.inner.bcond_label(ctx, cfg, stop, done)?;
+            self.inner.mrs_nzcv(ctx, cfg, &flags)?;
+        }
+        self.inner.b_label(ctx, cfg, check)?;
+        self.inner.set_label(ctx, cfg, exit)?;
+        self.inner.msr_nzcv(ctx, cfg, &flags)?;
+        self.inner.set_label(ctx, cfg, done)
+    }
+
+    /// Loads the string element at `[base]` into `dest`, advancing `base`.
+    ///
+    /// With `shift`, the element is moved to the top of `dest` so a 64-bit
+    /// CMP compares it at its own width.
+    fn load_string_element<Context>(
+        &mut self,
+        ctx: &mut Context,
+        dest: Reg,
+        base: Reg,
+        size: MemorySize,
+        shift: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_aarch64::out::arg::ArgKind;
+
+        let cfg = self.aarch64_cfg;
+        let data = ArgKind::Reg { reg: dest, size };
+        self.guest_ldr(ctx, &data, &string_element(base, size))?;
+        if cfg.big_endian && size != MemorySize::_8 {
+            self.swap_bytes(ctx, dest, size)?;
+        }
+        if shift && size != MemorySize::_64 {
+            self.inner
+                .lsl(ctx, cfg, &dest, &dest, &compare_shift(size))?;
+        }
+        Ok(())
+    }
+}
+
+/// `[base], #n`: a post-indexed access to the string element at `base`.
+fn string_element(
+    base: Reg,
+    size: MemorySize,
+) -> portal_solutions_asm_aarch64::out::arg::MemArgKind {
+    use portal_solutions_asm_aarch64::out::arg::{AddressingMode, ArgKind, MemArgKind};
+
+    MemArgKind::Mem {
+        base: ArgKind::Reg {
+            reg: base,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp: element_bytes(size),
+        size,
+        reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
+        mode: AddressingMode::PostIndex,
+    }
+}
+
+/// An x86-64 ALU operation that has an AArch64 immediate form.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ImmOp {
+    Add,
+    Sub,
+    And,
+    Or,
+    Xor,
+    Cmp,
+}
+
+/// An x86-64 single-operand arithmetic instruction.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum UnaryOp {
+    Neg,
+    Inc,
+    Dec,
+}
+
+/// Returns whether `arg` is SP, which ADDS/SUBS would read as XZR.
+fn is_sp(arg: &(dyn MemArg + '_)) -> bool {
+    use portal_solutions_asm_aarch64::out::arg::{ArgKind, MemArgKind};
+
+    matches!(
+        arg.concrete_mem_kind(),
+        MemArgKind::NoMem(ArgKind::Reg { reg: Reg(31), .. })
+    )
+}
+
+/// Splits the operands of a register/memory exchange into the register
+/// and the memory operand, if `swpal` can perform it: one operand is a 32-
+/// or 64-bit register and the other a register-based memory operand.
+fn swap_operands(
+    a: &(dyn MemArg + '_),
+    b: &(dyn MemArg + '_),
+) -> Option<(
+    portal_solutions_asm_aarch64::out::arg::ArgKind,
+    portal_solutions_asm_aarch64::out::arg::MemArgKind<
+        portal_solutions_asm_aarch64::out::arg::ArgKind,
+    >,
+)> {
+    use portal_solutions_asm_aarch64::out::arg::{ArgKind, MemArgKind};
+
+    let is_word = |arg: &ArgKind| {
+        matches!(
+            arg,
+            ArgKind::Reg {
+                size: MemorySize::_32 | MemorySize::_64,
+                ..
+            }
+        )
+    };
+    let is_mem = |arg: &MemArgKind<ArgKind>| {
+        matches!(
+            arg,
+            MemArgKind::Mem {
+                base: ArgKind::Reg { .. },
+                ..
+            }
+        )
+    };
+    match (a.concrete_mem_kind(), b.concrete_mem_kind()) {
+        (MemArgKind::NoMem(reg), mem) | (mem, MemArgKind::NoMem(reg))
+            if is_word(&reg) && is_mem(&mem) =>
+        {
+            Some((reg, mem))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the value of `arg` if it is an immediate operand.
+fn literal_operand(arg: &(dyn X64MemArg + '_)) -> Option<i64> {
+    use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind};
+
+    match arg.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Lit(v)) => Some(v as i64),
+        _ => None,
+    }
+}
+
+/// Returns the register whose high byte `arg` names, if it is `ah`..`bh`.
+fn high_byte_operand(arg: &(dyn X64MemArg + '_)) -> Option<Reg> {
+    match arg.concrete_mem_kind() {
+        X64MemArgKind::NoMem(X64ArgKind::HighByte(reg)) => Some(reg),
+        _ => None,
+    }
+}
+
+/// Translates x86-64 condition codes to AArch64 condition codes.
+///
+/// See [`crate::cond`] for the full table.
+pub fn translate_condition(cc: X64ConditionCode) -> portal_solutions_asm_aarch64::ConditionCode {
+    crate::cond::x64_to_aarch64(cc)
+}
+
+impl<Context, W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>, H>
+    X64WriterCore<Context> for X64ToAArch64Shim<W, H>
+where
+    H: ShimHooks<W, Context>,
+    W::Error: From<portal_solutions_asm_aarch64::out::error::EmitError>,
+{
+    type Error = W::Error;
+
+    fn hlt(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "hlt", {
+            // x86-64 HLT -> AArch64 BRK
+            self.inner.brk(ctx, self.aarch64_cfg, 0)
+        })
+    }
+
+    fn ud2(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "ud2", {
+            // x86-64 UD2 -> AArch64 BRK #1
+            self.inner.unreachable(ctx, self.aarch64_cfg)
+        })
+    }
+
+    fn int3(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "int3", {
+            // x86-64 INT3 -> AArch64 BRK #0xF000
+            self.inner.debug_break(ctx, self.aarch64_cfg)
+        })
+    }
+
+    fn pause(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "pause", {
+            // x86-64 PAUSE -> AArch64 YIELD (both spin-wait hints)
+            self.inner.yield_(ctx, self.aarch64_cfg)
+        })
+    }
+
+    fn prefetcht0(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "prefetcht0", {
+            // x86-64 PREFETCHT0 -> AArch64 PRFM PLDL1KEEP
+            let mem = self.adapt(mem, _cfg);
+            self.guest_prfm(ctx, PrfOp::new(PrfKind::PLD, 1, PrfPolicy::KEEP), &mem)
+        })
+    }
+
+    fn prefetcht1(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "prefetcht1", {
+            // x86-64 PREFETCHT1 -> AArch64 PRFM PLDL2KEEP
+            let mem = self.adapt(mem, _cfg);
+            self.guest_prfm(ctx, PrfOp::new(PrfKind::PLD, 2, PrfPolicy::KEEP), &mem)
+        })
+    }
+
+    fn prefetcht2(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "prefetcht2", {
+            // x86-64 PREFETCHT2 -> AArch64 PRFM PLDL3KEEP
+            let mem = self.adapt(mem, _cfg);
+            self.guest_prfm(ctx, PrfOp::new(PrfKind::PLD, 3, PrfPolicy::KEEP), &mem)
+        })
+    }
+
+    fn prefetchnta(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "prefetchnta", {
+            // x86-64 PREFETCHNTA -> AArch64 PRFM PLDL1STRM
+            let mem = self.adapt(mem, _cfg);
+            self.guest_prfm(ctx, PrfOp::new(PrfKind::PLD, 1, PrfPolicy::STRM), &mem)
+        })
+    }
+
+    fn clflush(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "clflush", {
+            // x86-64 CLFLUSH -> AArch64 DC CIVAC (clean and invalidate to the point of coherency)
+            let mem = self.adapt(mem, _cfg);
+            self.guest_dc(ctx, DcOp::CIVAC, &mem)
+        })
+    }
+
+    fn clflushopt(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "clflushopt", {
+            // x86-64 CLFLUSHOPT -> AArch64 DC CIVAC
+            let mem = self.adapt(mem, _cfg);
+            self.guest_dc(ctx, DcOp::CIVAC, &mem)
+        })
+    }
+
+    fn clwb(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "clwb", {
+            // x86-64 CLWB -> AArch64 DC CVAC (clean to the point of coherency, the line stays cached)
+            let mem = self.adapt(mem, _cfg);
+            self.guest_dc(ctx, DcOp::CVAC, &mem)
+        })
+    }
+
+    fn sfence(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "sfence", {
+            // x86-64 SFENCE -> AArch64 DMB ISHST (stores, and DC on them,
+            // before later stores)
+            self.inner.dmb(ctx, self.aarch64_cfg, Barrier::ISHST)
+        })
+    }
+
+    fn rdtsc(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "rdtsc", {
+            // x86-64 RDTSC -> AArch64 MRS x2, CNTVCT_EL0; UBFX x0, x2, #0, #32;
+            // LSR x2, x2, #32 (the virtual counter split over EDX:EAX)
+            self.guest_rdtsc(ctx)
+        })
+    }
+
+    fn rdtscp(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "rdtscp", {
+            // x86-64 RDTSCP -> AArch64 ISB, then as RDTSC; ECX (x1) reads 0,
+            // since IA32_TSC_AUX has no counterpart
+            self.inner.isb(ctx, self.aarch64_cfg)?;
+            self.guest_rdtsc(ctx)?;
+            self.inner.mov_imm(ctx, self.aarch64_cfg, &Reg(1), 0)
+        })
+    }
+
+    fn xchg(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "xchg", {
+            use portal_solutions_asm_aarch64::strategy::Exchange;
+
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            // XCHG with memory is atomic on x86-64; with LSE so is SWPAL.
+            let swap = match self.aarch64_cfg.strategies().exchange {
+                Exchange::Native
+                    if high_byte_operand(dest).is_none() && high_byte_operand(src).is_none() =>
+                {
+                    swap_operands(&dest_adapter, &src_adapter)
+                }
+                _ => None,
+            };
+            if let Some((reg, mem)) = swap {
+                let mem = self.bare_address(ctx, &mem)?;
+                self.inner.swpal(ctx, self.aarch64_cfg, &reg, &reg, &mem)
+            } else {
+                // Without LSE, exchange through x16 (IP0), which is
+                // caller-saved.
+                // PERFORMANCE: Uses 3 MOV instructions instead of 1 XCHG
+                let temp = Reg(16);
+                self.inner
+                    .mov(ctx, self.aarch64_cfg, &temp, &dest_adapter)?;
+                self.inner
+                    .mov(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)?;
+                self.inner.mov(ctx, self.aarch64_cfg, &src_adapter, &temp)?;
+                Ok(())
+            }
+        })
+    }
+
+    fn mov(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "mov", {
+            // x86-64 MOV -> AArch64 MOV/LDR/STR depending on operands
+            use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+            if let Some(reg) = high_byte_operand(dest) {
+                self.mov_to_high_byte(ctx, _cfg, reg, src)
+            } else if let Some(reg) = high_byte_operand(src) {
+                self.mov_from_high_byte(ctx, _cfg, dest, reg)
+            } else {
+                let dest_adapter = self.adapt(dest, _cfg);
+                let src_adapter = self.adapt(src, _cfg);
+
+                let dest_kind = dest_adapter.concrete_mem_kind();
+                let src_kind = src_adapter.concrete_mem_kind();
+
+                match (dest_kind, src_kind) {
+                    (MemArgKind::NoMem(_), MemArgKind::NoMem(_)) => {
+                        // Register to register or immediate to register - use MOV
+                        self.inner
+                            .mov(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+                    }
+                    (MemArgKind::NoMem(dest_arg), MemArgKind::Mem { .. }) => {
+                        // Memory to register - use LDR
+                        self.guest_ldr(ctx, &dest_adapter, &src_adapter)?;
+                        match (dest_arg, self.guest_swap_width(&src_adapter)) {
+                            (
+                                portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
+                                    reg, ..
+                                },
+                                Some(size),
+                            ) => self.swap_bytes(ctx, reg, size),
+                            _ => Ok(()),
+                        }
+                    }
+                    (MemArgKind::Mem { .. }, MemArgKind::NoMem(_)) => {
+                        // Register to memory - use STR
+                        self.store_guest(ctx, &src_adapter, &dest_adapter)
+                    }
+                    (MemArgKind::Mem { .. }, MemArgKind::Mem { .. }) => {
+                        // Memory to memory - need temporary register
+                        // Use x16 (IP0) as temporary
+                        let temp = Reg(16);
+                        self.load_memarg_into_temp(ctx, &src_adapter, &temp)?;
+                        self.store_guest(ctx, &temp, &dest_adapter)
+                    }
+                    _ => Err(unknown_operand("mov").into()),
+                }
+            }
+        })
+    }
+
+    fn sub(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "sub", {
+            // x86-64 SUB a, b (a = a - b) -> AArch64 SUBS a, a, b, so SBB sees the borrow
+            // Handle memory operands with LDR/STR
+            match literal_operand(b) {
+                Some(imm) if self.try_imm_op(ctx, ImmOp::Sub, &self.adapt(a, _cfg), imm)? => Ok(()),
+                _ if is_sp(&self.adapt(a, _cfg)) => {
+                    handle_two_operand_instr_2arg!(self, ctx, a, b, sub, _cfg)
+                }
+                _ => handle_two_operand_instr_2arg!(self, ctx, a, b, subs, _cfg),
+            }
+        })
+    }
+
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "sub", {
+            let lit = imm as i64 as u64;
+            if self.try_imm_op(ctx, ImmOp::Sub, &self.adapt(a, _cfg), imm as i64)? {
+                Ok(())
+            } else if is_sp(&self.adapt(a, _cfg)) {
+                handle_two_operand_instr_2arg!(self, ctx, a, &lit, sub, _cfg)
+            } else {
+                handle_two_operand_instr_2arg!(self, ctx, a, &lit, subs, _cfg)
+            }
+        })
+    }
+
+    fn add(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "add", {
+            // x86-64 ADD a, b (a = a + b) -> AArch64 ADDS a, a, b, with C
+            // flipped to the inverted borrow SUBS leaves
+            // Handle memory operands with LDR/STR
+            match literal_operand(b) {
+                Some(imm) if self.try_imm_op(ctx, ImmOp::Add, &self.adapt(a, _cfg), imm)? => Ok(()),
+                _ if is_sp(&self.adapt(a, _cfg)) => {
+                    handle_two_operand_instr_2arg!(self, ctx, a, b, add, _cfg)
+                }
+                _ => {
+                    handle_two_operand_instr_2arg!(self, ctx, a, b, adds, _cfg)?;
+                    self.invert_carry(ctx, &Reg(17))
+                }
+            }
+        })
+    }
+
+    fn adc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "adc", {
+            // x86-64 ADC a, b -> AArch64 ADCS a, a, b, with C flipped to CF around it
+            self.carry_op(ctx, _cfg, a, b, false)
+        })
+    }
+
+    fn sbb(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "sbb", {
+            // x86-64 SBB a, b -> AArch64 SBCS a, a, b; the x86 borrow is the inverse of C,
+            // which is how SUBS/CMP leave it
+            self.carry_op(ctx, _cfg, a, b, true)
+        })
+    }
+
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "add", {
+            let lit = imm as i64 as u64;
+            if self.try_imm_op(ctx, ImmOp::Add, &self.adapt(a, _cfg), imm as i64)? {
+                Ok(())
+            } else if is_sp(&self.adapt(a, _cfg)) {
+                handle_two_operand_instr_2arg!(self, ctx, a, &lit, add, _cfg)
+            } else {
+                handle_two_operand_instr_2arg!(self, ctx, a, &lit, adds, _cfg)?;
+                self.invert_carry(ctx, &Reg(17))
+            }
+        })
+    }
+
+    fn movsx(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "movsx", {
+            // x86-64 MOVSX -> AArch64 SXTB/SXTH/SXTW (handle memory operands)
+            use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+            if let Some(reg) = high_byte_operand(src) {
+                self.extend_high_byte(ctx, _cfg, dest, reg, true)
+            } else {
+                let dest_adapter = self.adapt(dest, _cfg);
+                let src_adapter = self.adapt(src, _cfg);
+                let src_kind = src_adapter.concrete_mem_kind();
+
+                match src_kind {
+                    MemArgKind::NoMem(_) => {
+                        // Source is register - direct SXT, then store if needed
+                        let dest_kind = dest_adapter.concrete_mem_kind();
+                        match dest_kind {
+                            MemArgKind::NoMem(_) => {
+                                self.inner
+                                    .sxt(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+                            }
+                            MemArgKind::Mem { .. } => {
+                                let temp = Reg(16); // x16
+                                self.inner.sxt(ctx, self.aarch64_cfg, &temp, &src_adapter)?;
+                                self.guest_str(ctx, &temp, &dest_adapter)
+                            }
+                            _ => Err(unknown_operand("movsx").into()),
+                        }
+                    }
+                    MemArgKind::Mem { .. } => {
+                        // Source is memory - LDR, SXT, store if needed
+                        let temp = Reg(16); // x16
+                        self.load_memarg_into_temp(ctx, &src_adapter, &temp)?;
+                        let temp2 = Reg(17); // x17 for result
+                        self.inner.sxt(ctx, self.aarch64_cfg, &temp2, &temp)?;
+
+                        let dest_kind = dest_adapter.concrete_mem_kind();
+                        match dest_kind {
+                            MemArgKind::NoMem(_) => {
+                                self.inner.mov(ctx, self.aarch64_cfg, &dest_adapter, &temp2)
+                            }
+                            MemArgKind::Mem { .. } => self.guest_str(ctx, &temp2, &dest_adapter),
+                            _ => Err(unknown_operand("movsx").into()),
+                        }
+                    }
+                    _ => Err(unknown_operand("movsx").into()),
+                }
+            }
+        })
+    }
+
+    fn movzx(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "movzx", {
+            // x86-64 MOVZX -> AArch64 UXTB/UXTH (handle memory operands)
+            use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+            if let Some(reg) = high_byte_operand(src) {
+                self.extend_high_byte(ctx, _cfg, dest, reg, false)
+            } else {
+                let dest_adapter = self.adapt(dest, _cfg);
+                let src_adapter = self.adapt(src, _cfg);
+                let src_kind = src_adapter.concrete_mem_kind();
+
+                match src_kind {
+                    MemArgKind::NoMem(_) => {
+                        // Source is register - direct UXT, then store if needed
+                        let dest_kind = dest_adapter.concrete_mem_kind();
+                        match dest_kind {
+                            MemArgKind::NoMem(_) => {
+                                self.inner
+                                    .uxt(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+                            }
+                            MemArgKind::Mem { .. } => {
+                                let temp = Reg(16); // x16
+                                self.inner.uxt(ctx, self.aarch64_cfg, &temp, &src_adapter)?;
+                                self.guest_str(ctx, &temp, &dest_adapter)
+                            }
+                            _ => Err(unknown_operand("movzx").into()),
+                        }
+                    }
+                    MemArgKind::Mem { .. } => {
+                        // Source is memory - LDR, UXT, store if needed
+                        let temp = Reg(16); // x16
+                        self.load_memarg_into_temp(ctx, &src_adapter, &temp)?;
+                        let temp2 = Reg(17); // x17 for result
+                        self.inner.uxt(ctx, self.aarch64_cfg, &temp2, &temp)?;
+
+                        let dest_kind = dest_adapter.concrete_mem_kind();
+                        match dest_kind {
+                            MemArgKind::NoMem(_) => {
+                                self.inner.mov(ctx, self.aarch64_cfg, &dest_adapter, &temp2)
+                            }
+                            MemArgKind::Mem { .. } => self.guest_str(ctx, &temp2, &dest_adapter),
+                            _ => Err(unknown_operand("movzx").into()),
+                        }
+                    }
+                    _ => Err(unknown_operand("movzx").into()),
+                }
+            }
+        })
+    }
+
+    fn push(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "push", {
+            // x86-64 PUSH -> AArch64 STR to the guest stack
+            let op_adapter = self.adapt(op, _cfg);
+            self.push_guest(ctx, &op_adapter)
+        })
+    }
+
+    fn pop(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "pop", {
+            // x86-64 POP -> AArch64 LDR from the guest stack
+            let op_adapter = self.adapt(op, _cfg);
+            self.pop_guest(ctx, &op_adapter)
+        })
+    }
+
+    fn pushf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "pushf", {
+            // x86-64 PUSHF -> AArch64 MRS NZCV + STR to the guest stack
+            // Store NZCV flags using MRS
+            let temp = Reg(16); // x16
+            // Read NZCV flags into temp register
+            self.inner.mrs_nzcv(ctx, self.aarch64_cfg, &temp)?;
+            // Store flags to the guest stack
+            self.push_guest(ctx, &temp)
+        })
+    }
+
+    fn popf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "popf", {
+            // x86-64 POPF -> AArch64 LDR from the guest stack + MSR NZCV
+            let temp = Reg(16); // x16
+            // Load flags from the guest stack
+            self.pop_guest(ctx, &temp)?;
+            // Write flags back to NZCV
+            self.inner.msr_nzcv(ctx, self.aarch64_cfg, &temp)
+        })
+    }
+
+    fn lahf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "lahf", {
+            // x86-64 LAHF -> rebuild SF:ZF:0:0:0:0:1:CF from NZCV into bits 8-15 of X0
+            let cfg = self.aarch64_cfg;
+            let (nzcv, bits) = (Reg(16), Reg(17)); // x16, x17
+            self.inner.mrs_nzcv(ctx, cfg, &nzcv)?;
+            // AArch64 C is the inverse of the x86 borrow
+            self.inner.eor_imm(ctx, cfg, &nzcv, &nzcv, 1 << 29)?;
+            // bits[0] = CF, bits[1] = ZF, bits[2] = SF
+            self.inner.lsr(ctx, cfg, &bits, &nzcv, &29u64)?;
+            self.inner
+                .and_imm(ctx, cfg, &nzcv, &bits, crate::flags::CF)?;
+            self.inner
+                .orr_imm(ctx, cfg, &nzcv, &nzcv, crate::flags::RESERVED)?;
+            self.inner.lsr(ctx, cfg, &bits, &bits, &1u64)?;
+            self.inner.bfi(ctx, cfg, &nzcv, &bits, 6, 2)?;
+            self.inner.bfi(ctx, cfg, &Reg(0), &nzcv, 8, 8)
+        })
+    }
+
+    fn sahf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "sahf", {
+            // x86-64 SAHF -> NZCV from SF, ZF and CF in bits 8-15 of X0, keeping V
+            let cfg = self.aarch64_cfg;
+            let (ah, nzcv) = (Reg(16), Reg(17)); // x16, x17
+            self.inner.ubfx(ctx, cfg, &ah, &Reg(0), 8, 8)?;
+            self.inner.mrs_nzcv(ctx, cfg, &nzcv)?;
+            self.inner.and_imm(ctx, cfg, &nzcv, &nzcv, 1 << 28)?;
+            self.inner.bfi(ctx, cfg, &nzcv, &ah, 29, 1)?;
+            self.inner.lsr(ctx, cfg, &ah, &ah, &6u64)?;
+            self.inner.bfi(ctx, cfg, &nzcv, &ah, 30, 2)?;
+            self.inner.eor_imm(ctx, cfg, &nzcv, &nzcv, 1 << 29)?;
+            self.inner.msr_nzcv(ctx, cfg, &nzcv)
+        })
+    }
+
+    fn call(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "call", {
+            let lr = Reg(30); // LR (x30)
+            let op_adapter = self.adapt(op, _cfg);
+            match self.return_stack {
+                ReturnStack::Standard => {
+                    // x86-64 CALL -> AArch64 call shim using labels
+                    // Strategy: Branch to a shim that pushes LR and branches to the target
+                    // The shim is emitted inline with a jump over it to ensure correctness
+
+                    // Generate unique labels
+                    let shim_label = self.next_shim_label();
+                    let skip_label = self.next_shim_label();
+
+                    // Jump over the shim to skip_label
+                    self.inner.b_label(ctx, self.aarch64_cfg, skip_label)?;
+
+                    // Emit the call shim inline
+                    self.inner.set_label(ctx, self.aarch64_cfg, shim_label)?;
+
+                    // Push LR onto the guest stack
+                    self.push_return_address(ctx, &lr)?;
+
+                    // Branch to the target
+                    self.inner.b(ctx, self.aarch64_cfg, &op_adapter)?;
+
+                    // Set skip label (execution continues here)
+                    self.inner.set_label(ctx, self.aarch64_cfg, skip_label)?;
+
+                    // Branch and link to the shim
+                    self.inner.bl_label(ctx, self.aarch64_cfg, shim_label)
+                }
+                ReturnStack::Shadow(_) => {
+                    // Save the caller's LR around a native call:
+                    // str lr, [ss, #-8]!; blr target; ldr lr, [ss], #8
+                    self.push_return_address(ctx, &lr)?;
+                    self.inner.bl(ctx, self.aarch64_cfg, &op_adapter)?;
+                    self.pop_return_address(ctx, &lr)
+                }
+            }
+        })
+    }
+
+    fn jmp(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "jmp", {
+            // x86-64 JMP -> AArch64 B or BR
+            let op_adapter = self.adapt(op, _cfg);
+            self.inner.b(ctx, self.aarch64_cfg, &op_adapter)
+        })
+    }
+
+    fn cmp(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cmp", {
+            match literal_operand(b) {
+                Some(imm) if self.try_imm_op(ctx, ImmOp::Cmp, &self.adapt(a, _cfg), imm)? => Ok(()),
+                _ => {
+                    // x86-64 CMP -> AArch64 CMP (handle memory operands)
+                    use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+                    let a_adapter = self.adapt(a, _cfg);
+                    let b_adapter = self.adapt(b, _cfg);
+
+                    let a_kind = a_adapter.concrete_mem_kind();
+                    let b_kind = b_adapter.concrete_mem_kind();
+
+                    match (a_kind, b_kind) {
+                        (MemArgKind::NoMem(_), MemArgKind::NoMem(_)) => {
+                            // Both are registers/immediates - direct CMP
+                            self.inner
+                                .cmp(ctx, self.aarch64_cfg, &a_adapter, &b_adapter)
+                        }
+                        (MemArgKind::Mem { .. }, _) => {
+                            // a is memory - LDR into temp, then CMP
+                            let temp = Reg(16); // x16
+                            self.load_memarg_into_temp(ctx, &a_adapter, &temp)?;
+                            if matches!(b_kind, MemArgKind::Mem { .. }) {
+                                let temp_b = Reg(17); // x17
+                                self.guest_ldr(ctx, &temp_b, &b_adapter)?;
+                                self.inner.cmp(ctx, self.aarch64_cfg, &temp, &temp_b)
+                            } else {
+                                self.inner.cmp(ctx, self.aarch64_cfg, &temp, &b_adapter)
+                            }
+                        }
+                        (MemArgKind::NoMem(_), MemArgKind::Mem { .. }) => {
+                            // b is memory - LDR into temp, then CMP
+                            let temp = Reg(17); // x17
+                            self.load_memarg_into_temp(ctx, &b_adapter, &temp)?;
+                            self.inner.cmp(ctx, self.aarch64_cfg, &a_adapter, &temp)
+                        }
+                        _ => Err(unknown_operand("cmp").into()),
+                    }
+                }
+            }
+        })
+    }
+
+    fn cmp_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cmp", {
+            let a_adapter = self.adapt(a, _cfg);
+            if self.try_imm_op(ctx, ImmOp::Cmp, &a_adapter, imm as i64)? {
+                Ok(())
+            } else {
+                // No immediate form: compare against the value in a register
+                let (temp_a, temp_b) = (Reg(16), Reg(17)); // x16, x17
+                self.load_operand_into_temp(ctx, &a_adapter, &temp_a)?;
+                self.inner
+                    .mov_imm(ctx, self.aarch64_cfg, &temp_b, imm as i64 as u64)?;
+                self.inner.cmp(ctx, self.aarch64_cfg, &temp_a, &temp_b)
+            }
+        })
+    }
+
+    fn cmp0(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cmp0", {
+            // x86-64 CMP op, 0 -> AArch64 CMP op, #0 (handle memory operands)
+            use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+            let op_adapter = self.adapt(op, _cfg);
+            let op_kind = op_adapter.concrete_mem_kind();
+
+            match op_kind {
+                MemArgKind::NoMem(_) => {
+                    // Register/immediate - direct CMP
+                    self.inner.cmp_imm(ctx, self.aarch64_cfg, &op_adapter, 0)
+                }
+                MemArgKind::Mem { .. } => {
+                    // Memory - LDR into temp, then CMP
+                    let temp = Reg(16); // x16
+                    self.load_memarg_into_temp(ctx, &op_adapter, &temp)?;
+                    self.inner.cmp_imm(ctx, self.aarch64_cfg, &temp, 0)
+                }
+                _ => Err(unknown_operand("cmp").into()),
+            }
+        })
+    }
+
+    fn test(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "test", {
+            // x86-64 TEST -> AArch64 TST (ANDS XZR) (handle memory operands)
+            use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+            let a_adapter = self.adapt(a, _cfg);
+            let b_adapter = self.adapt(b, _cfg);
+
+            let a_kind = a_adapter.concrete_mem_kind();
+            let b_kind = b_adapter.concrete_mem_kind();
+
+            match (a_kind, b_kind) {
+                (MemArgKind::NoMem(_), MemArgKind::NoMem(_)) => {
+                    // Both are registers/immediates - direct TST
+                    self.inner
+                        .tst(ctx, self.aarch64_cfg, &a_adapter, &b_adapter)
+                }
+                (MemArgKind::Mem { .. }, _) => {
+                    // a is memory - LDR into temp, then TST
+                    let temp = Reg(16); // x16
+                    self.load_memarg_into_temp(ctx, &a_adapter, &temp)?;
+                    if matches!(b_kind, MemArgKind::Mem { .. }) {
+                        let temp_b = Reg(17); // x17
+                        self.load_memarg_into_temp(ctx, &b_adapter, &temp_b)?;
+                        self.inner.tst(ctx, self.aarch64_cfg, &temp, &temp_b)
+                    } else {
+                        self.inner.tst(ctx, self.aarch64_cfg, &temp, &b_adapter)
+                    }
+                }
+                (MemArgKind::NoMem(_), MemArgKind::Mem { .. }) => {
+                    // b is memory - LDR into temp, then TST
+                    let temp = Reg(17); // x17
+                    self.load_memarg_into_temp(ctx, &b_adapter, &temp)?;
+                    self.inner.tst(ctx, self.aarch64_cfg, &a_adapter, &temp)
+                }
+                _ => Err(unknown_operand("test").into()),
+            }
+        })
+    }
+
+    fn test0(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "test0", {
+            // x86-64 TEST op, op -> AArch64 TST op, op (handle memory operands)
+            use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+            let op_adapter = self.adapt(op, _cfg);
+            let op_kind = op_adapter.concrete_mem_kind();
+
+            match op_kind {
+                MemArgKind::NoMem(_) => {
+                    // Register - direct TST
+                    self.inner
+                        .tst(ctx, self.aarch64_cfg, &op_adapter, &op_adapter)
+                }
+                MemArgKind::Mem { .. } => {
+                    // Memory - LDR into temp, then TST
+                    let temp = Reg(16); // x16
+                    self.load_memarg_into_temp(ctx, &op_adapter, &temp)?;
+                    self.inner.tst(ctx, self.aarch64_cfg, &temp, &temp)
+                }
+                _ => Err(unknown_operand("test").into()),
+            }
+        })
+    }
+
+    fn cmovcc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        cond: X64ConditionCode,
+        op: &(dyn X64MemArg + '_),
+        val: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cmovcc", {
+            // x86-64 CMOVcc -> AArch64 CSEL (handle memory operands)
+            use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+            let aarch64_cond = translate_condition(cond);
+            let op_adapter = self.adapt(op, _cfg);
+            let val_adapter = self.adapt(val, _cfg);
+
+            let op_kind = op_adapter.concrete_mem_kind();
+            let val_kind = val_adapter.concrete_mem_kind();
+
+            match (op_kind, val_kind) {
+                (MemArgKind::NoMem(_), MemArgKind::NoMem(_)) => {
+                    // Both registers - direct CSEL
+                    self.inner.csel(
+                        ctx,
+                        self.aarch64_cfg,
+                        aarch64_cond,
+                        &op_adapter,
+                        &val_adapter,
+                        &op_adapter,
+                    )
+                }
+                (MemArgKind::Mem { .. }, MemArgKind::NoMem(_)) => {
+                    // op is memory - LDR, CSEL, STR
+                    let temp = Reg(16); // x16
+                    self.load_memarg_into_temp(ctx, &op_adapter, &temp)?;
+                    self.inner.csel(
+                        ctx,
+                        self.aarch64_cfg,
+                        aarch64_cond,
+                        &temp,
+                        &val_adapter,
+                        &temp,
+                    )?;
+                    self.guest_str(ctx, &temp, &op_adapter)
+                }
+                (MemArgKind::NoMem(_), MemArgKind::Mem { .. }) => {
+                    // val is memory - LDR val, then CSEL
+                    let temp = Reg(17); // x17
+                    self.load_memarg_into_temp(ctx, &val_adapter, &temp)?;
+                    self.inner.csel(
+                        ctx,
+                        self.aarch64_cfg,
+                        aarch64_cond,
+                        &op_adapter,
+                        &temp,
+                        &op_adapter,
+                    )
+                }
+                (MemArgKind::Mem { .. }, MemArgKind::Mem { .. }) => {
+                    // Both memory - LDR both, CSEL, STR
+                    let temp_op = Reg(16); // x16
+                    let temp_val = Reg(17); // x17
+                    self.load_memarg_into_temp(ctx, &op_adapter, &temp_op)?;
+                    self.load_memarg_into_temp(ctx, &val_adapter, &temp_val)?;
+                    self.inner.csel(
+                        ctx,
+                        self.aarch64_cfg,
+                        aarch64_cond,
+                        &temp_op,
+                        &temp_val,
+                        &temp_op,
+                    )?;
+                    self.guest_str(ctx, &temp_op, &op_adapter)
+                }
+                _ => Err(unknown_operand("cmovcc").into()),
+            }
+        })
+    }
+
+    fn not(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "not", {
+            // x86-64 NOT -> AArch64 MVN (handle memory operands)
+            use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+            let op_adapter = self.adapt(op, _cfg);
+            let op_kind = op_adapter.concrete_mem_kind();
+
+            match op_kind {
+                MemArgKind::NoMem(_) => {
+                    // Register - direct MVN
+                    self.inner
+                        .mvn(ctx, self.aarch64_cfg, &op_adapter, &op_adapter)
+                }
+                MemArgKind::Mem { .. } => {
+                    // Memory - LDR, MVN, STR
+                    let temp = Reg(16); // x16
+                    self.load_memarg_into_temp(ctx, &op_adapter, &temp)?;
+                    self.inner.mvn(ctx, self.aarch64_cfg, &temp, &temp)?;
+                    self.guest_str(ctx, &temp, &op_adapter)
+                }
+                _ => Err(unknown_operand("not").into()),
+            }
+        })
+    }
+
+    fn neg(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "neg", {
+            // x86-64 NEG -> AArch64 NEGS; CF is set unless op was zero, which
+            // matches the shim's inverted C
+            self.unary_op(ctx, _cfg, op, UnaryOp::Neg)
+        })
+    }
+
+    fn inc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "inc", {
+            self.unary_op(ctx, _cfg, op, UnaryOp::Inc)
+        })
+    }
+
+    fn dec(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "dec", {
+            self.unary_op(ctx, _cfg, op, UnaryOp::Dec)
+        })
+    }
+
+    fn lea(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "lea", {
+            // x86-64 LEA -> AArch64 ADD/ADR (depending on context)
+            use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind};
+            let dest_adapter = self.adapt(dest, _cfg);
+            if let MemArgKind::Mem {
+                base: ArgKind::Sym { name, addend },
+                offset: None,
+                disp,
+                ..
+            } = src.concrete_mem_kind()
+            {
+                // `lea r, [rip + sym]` is the symbol's address; MOV of a
+                // symbol forms it as `aarch64_cfg.pic` requires
+                let sym = portal_solutions_asm_aarch64::out::arg::ArgKind::Sym {
+                    name,
+                    addend: addend + disp as i64,
+                };
+                self.inner.mov(ctx, self.aarch64_cfg, &dest_adapter, &sym)
+            } else {
+                // For simplicity, use ADR for now
+                let src_adapter = self.adapt(src, _cfg);
+                self.inner
+                    .adr(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+            }
+        })
+    }
+
+    fn get_ip(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "get_ip", {
+            // x86-64 get IP (typically via CALL trick) -> AArch64 ADR
+            // PERFORMANCE: Different approach than x86-64
+            let pc_reg = Reg(30); // LR (link register)
+            self.inner.adr(ctx, self.aarch64_cfg, &pc_reg, &0u64)
+        })
+    }
+
+    fn ret(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "ret", {
+            // x86-64 RET -> AArch64 ret shim (inline, no jump)
+            // Directly emit: pop return address from stack, then return
+            // (with a shadow stack the return address is already in LR)
+
+            let lr = Reg(30); // LR (x30)
+
+            // Pop return address from the guest stack
+            if self.return_stack == ReturnStack::Standard {
+                self.pop_return_address(ctx, &lr)?;
+            }
+
+            // Return
+            self.inner.ret(ctx, self.aarch64_cfg)
+        })
+    }
+
+    fn mov64(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        r: &(dyn X64MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "mov64", {
+            // x86-64 MOV r, imm64 -> AArch64 MOVZ/MOVK sequence
+            let r_adapter = self.adapt(r, _cfg);
+            self.inner.mov_imm(ctx, self.aarch64_cfg, &r_adapter, val)
+        })
+    }
+
+    fn mul(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "mul", {
+            // x86-64 MUL a, b -> AArch64 MUL a, a, b
+            handle_two_operand_instr!(self, ctx, a, b, mul, _cfg)
+        })
+    }
+
+    fn imul2(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "imul", {
+            // The truncated product is the same signed or unsigned: MUL dest, dest, src
+            handle_two_operand_instr!(self, ctx, dest, src, mul, _cfg)
+        })
+    }
+
+    fn imul3(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "imul", {
+            // x86-64 IMUL dest, src, imm -> AArch64 MOV x17, #imm; MUL dest, src, x17
+            use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            let cfg = self.aarch64_cfg;
+            let (temp, factor) = (Reg(16), Reg(17)); // x16, x17
+            self.inner.mov_imm(ctx, cfg, &factor, imm as i64 as u64)?;
+            let src_reg: &(dyn MemArg + '_) = match src_adapter.concrete_mem_kind() {
+                MemArgKind::Mem { .. } => {
+                    self.load_memarg_into_temp(ctx, &src_adapter, &temp)?;
+                    &temp
                 }
+                _ => &src_adapter,
+            };
+            match dest_adapter.concrete_mem_kind() {
+                MemArgKind::Mem { .. } => {
+                    self.inner.mul(ctx, cfg, &temp, src_reg, &factor)?;
+                    self.store_guest(ctx, &temp, &dest_adapter)
+                }
+                _ => self.inner.mul(ctx, cfg, &dest_adapter, src_reg, &factor),
             }
-            _ => {
-                // Default: delegate to underlying writer
-                self.inner.ldr(ctx, self.aarch64_cfg, dest, adapter)
-            }
-        }
+        })
     }
-}
 
-/// Translates x86-64 condition codes to AArch64 condition codes.
-///
-/// # Translation Table
-///
-/// | x86-64 | AArch64 | Notes |
-/// |--------|---------|-------|
-/// | E/Z    | EQ      | Equal / Zero |
-/// | NE/NZ  | NE      | Not equal / Not zero |
-/// | B/C    | LO      | Unsigned less / Carry |
-/// | NB/NC  | HS      | Unsigned >= / No carry |
-/// | A      | HI      | Unsigned greater |
-/// | NA     | LS      | Unsigned <= |
-/// | L      | LT      | Signed less |
-/// | NL     | GE      | Signed >= |
-/// | G      | GT      | Signed greater |
-/// | NG     | LE      | Signed <= |
-/// | O      | VS      | Overflow |
-/// | NO     | VC      | No overflow |
-/// | S      | MI      | Sign / Negative |
-/// | NS     | PL      | No sign / Positive |
-/// | P/PE   | AL      | Parity even (no equivalent) |
-/// | NP/PO  | AL      | Parity odd (no equivalent) |
-pub fn translate_condition(cc: X64ConditionCode) -> portal_solutions_asm_aarch64::ConditionCode {
-    match cc {
-        X64ConditionCode::E => portal_solutions_asm_aarch64::ConditionCode::EQ, // Equal
-        X64ConditionCode::NE => portal_solutions_asm_aarch64::ConditionCode::NE, // Not equal
-        X64ConditionCode::B => portal_solutions_asm_aarch64::ConditionCode::LO, // Unsigned less (below)
-        X64ConditionCode::NB => portal_solutions_asm_aarch64::ConditionCode::HS, // Unsigned greater or equal (not below)
-        X64ConditionCode::A => portal_solutions_asm_aarch64::ConditionCode::HI, // Unsigned greater (above)
-        X64ConditionCode::NA => portal_solutions_asm_aarch64::ConditionCode::LS, // Unsigned less or equal (not above)
-        X64ConditionCode::L => portal_solutions_asm_aarch64::ConditionCode::LT,  // Signed less
-        X64ConditionCode::NL => portal_solutions_asm_aarch64::ConditionCode::GE, // Signed greater or equal
-        X64ConditionCode::G => portal_solutions_asm_aarch64::ConditionCode::GT,  // Signed greater
-        X64ConditionCode::NG => portal_solutions_asm_aarch64::ConditionCode::LE, // Signed less or equal
-        X64ConditionCode::O => portal_solutions_asm_aarch64::ConditionCode::VS,  // Overflow
-        X64ConditionCode::NO => portal_solutions_asm_aarch64::ConditionCode::VC, // No overflow
-        X64ConditionCode::S => portal_solutions_asm_aarch64::ConditionCode::MI,  // Sign (negative)
-        X64ConditionCode::NS => portal_solutions_asm_aarch64::ConditionCode::PL, // No sign (positive)
-        X64ConditionCode::P => portal_solutions_asm_aarch64::ConditionCode::AL, // Parity - no direct equivalent, use always
-        X64ConditionCode::NP => portal_solutions_asm_aarch64::ConditionCode::AL, // No parity - no direct equivalent
-        _ => portal_solutions_asm_aarch64::ConditionCode::AL, // Catch-all for any future variants
+    fn mul_wide(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        hi: &(dyn X64MemArg + '_),
+        lo: &(dyn X64MemArg + '_),
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "mul_wide", {
+            // x86-64 MUL (hi:lo = a * b) -> AArch64 UMULH hi, a, b; MUL lo, a, b
+            let hi = self.adapt(hi, _cfg);
+            let lo = self.adapt(lo, _cfg);
+            let a = self.adapt(a, _cfg);
+            let b = self.adapt(b, _cfg);
+            self.emulate_wide_mul(ctx, &hi, &lo, &a, &b, false)
+        })
     }
-}
-
-impl<Context, W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>>
-    X64WriterCore<Context> for X64ToAArch64Shim<W>
-{
-    type Error = W::Error;
 
-    fn hlt(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
-        // x86-64 HLT -> AArch64 BRK
-        self.inner.brk(ctx, self.aarch64_cfg, 0)
+    fn imul_wide(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        hi: &(dyn X64MemArg + '_),
+        lo: &(dyn X64MemArg + '_),
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "imul_wide", {
+            // x86-64 IMUL (hi:lo = a * b) -> AArch64 SMULH hi, a, b; MUL lo, a, b
+            let hi = self.adapt(hi, _cfg);
+            let lo = self.adapt(lo, _cfg);
+            let a = self.adapt(a, _cfg);
+            let b = self.adapt(b, _cfg);
+            self.emulate_wide_mul(ctx, &hi, &lo, &a, &b, true)
+        })
     }
 
-    fn xchg(
+    fn div(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 XCHG doesn't have a direct AArch64 equivalent
-        // We need a temporary register. Use x16 (IP0) which is caller-saved
-        // PERFORMANCE: Uses 3 MOV instructions instead of 1 XCHG
-        let temp = Reg(16);
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner
-            .mov(ctx, self.aarch64_cfg, &temp, &dest_adapter)?;
-        self.inner
-            .mov(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)?;
-        self.inner.mov(ctx, self.aarch64_cfg, &src_adapter, &temp)?;
-        Ok(())
+        hooked!(self, ctx, "div", {
+            // x86-64 DIV a, b -> AArch64 UDIV a, a, b
+            // (preceded by a fault guard unless `div_fault` is `Native`)
+            self.emit_div_guard(ctx, &self.adapt(a, _cfg), &self.adapt(b, _cfg), false)?;
+            handle_two_operand_instr!(self, ctx, a, b, udiv, _cfg)
+        })
     }
 
-    fn mov(
+    fn idiv(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-
-        dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 MOV -> AArch64 MOV/LDR/STR depending on operands
-        use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+        hooked!(self, ctx, "idiv", {
+            // x86-64 IDIV a, b -> AArch64 SDIV a, a, b
+            // (preceded by a fault guard unless `div_fault` is `Native`)
+            self.emit_div_guard(ctx, &self.adapt(a, _cfg), &self.adapt(b, _cfg), true)?;
+            handle_two_operand_instr!(self, ctx, a, b, sdiv, _cfg)
+        })
+    }
 
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
+    fn and(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "and", {
+            // x86-64 AND a, b -> AArch64 AND a, a, b
+            match literal_operand(b) {
+                Some(imm) if self.try_imm_op(ctx, ImmOp::And, &self.adapt(a, _cfg), imm)? => Ok(()),
+                _ => handle_two_operand_instr!(self, ctx, a, b, and, _cfg),
+            }
+        })
+    }
 
-        let dest_kind = dest_adapter.concrete_mem_kind();
-        let src_kind = src_adapter.concrete_mem_kind();
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "and", {
+            let lit = imm as i64 as u64;
+            if self.try_imm_op(ctx, ImmOp::And, &self.adapt(a, _cfg), imm as i64)? {
+                Ok(())
+            } else {
+                handle_two_operand_instr!(self, ctx, a, &lit, and, _cfg)
+            }
+        })
+    }
 
-        match (dest_kind, src_kind) {
-            (MemArgKind::NoMem(_), MemArgKind::NoMem(_)) => {
-                // Register to register or immediate to register - use MOV
-                self.inner
-                    .mov(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+    fn or(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "or", {
+            // x86-64 OR a, b -> AArch64 ORR a, a, b
+            match literal_operand(b) {
+                Some(imm) if self.try_imm_op(ctx, ImmOp::Or, &self.adapt(a, _cfg), imm)? => Ok(()),
+                _ => handle_two_operand_instr!(self, ctx, a, b, orr, _cfg),
             }
-            (MemArgKind::NoMem(_), MemArgKind::Mem { .. }) => {
-                // Memory to register - use LDR
-                self.inner
-                    .ldr(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        })
+    }
+
+    fn or_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "or", {
+            let lit = imm as i64 as u64;
+            if self.try_imm_op(ctx, ImmOp::Or, &self.adapt(a, _cfg), imm as i64)? {
+                Ok(())
+            } else {
+                handle_two_operand_instr!(self, ctx, a, &lit, orr, _cfg)
             }
-            (MemArgKind::Mem { .. }, MemArgKind::NoMem(_)) => {
-                // Register to memory - use STR
-                self.inner
-                    .str(ctx, self.aarch64_cfg, &src_adapter, &dest_adapter)
+        })
+    }
+
+    fn eor(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "eor", {
+            // x86-64 XOR a, b -> AArch64 EOR a, a, b
+            match literal_operand(b) {
+                Some(imm) if self.try_imm_op(ctx, ImmOp::Xor, &self.adapt(a, _cfg), imm)? => Ok(()),
+                _ => handle_two_operand_instr!(self, ctx, a, b, eor, _cfg),
             }
-            (MemArgKind::Mem { .. }, MemArgKind::Mem { .. }) => {
-                // Memory to memory - need temporary register
-                // Use x16 (IP0) as temporary
-                let temp = Reg(16);
-                self.load_memarg_into_temp(ctx, &src_adapter, &temp)?;
-                self.inner.str(ctx, self.aarch64_cfg, &temp, &dest_adapter)
+        })
+    }
+
+    fn eor_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "eor", {
+            let lit = imm as i64 as u64;
+            if self.try_imm_op(ctx, ImmOp::Xor, &self.adapt(a, _cfg), imm as i64)? {
+                Ok(())
+            } else {
+                handle_two_operand_instr!(self, ctx, a, &lit, eor, _cfg)
             }
-            _ => todo!(),
-        }
+        })
     }
 
-    fn sub(
+    fn shl(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         a: &(dyn X64MemArg + '_),
         b: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 SUB a, b (a = a - b) -> AArch64 SUB a, a, b
-        // Handle memory operands with LDR/STR
-        handle_two_operand_instr_2arg!(self, ctx, a, b, sub, _cfg)
+        hooked!(self, ctx, "shl", {
+            // x86-64 SHL a, b -> AArch64 LSL a, a, b
+            handle_two_operand_instr!(self, ctx, a, b, lsl, _cfg)
+        })
     }
 
-    fn add(
+    fn shr(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         a: &(dyn X64MemArg + '_),
         b: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 ADD a, b (a = a + b) -> AArch64 ADD a, a, b
-        // Handle memory operands with LDR/STR
-        handle_two_operand_instr_2arg!(self, ctx, a, b, add, _cfg)
+        hooked!(self, ctx, "shr", {
+            // x86-64 SHR a, b -> AArch64 LSR a, a, b
+            handle_two_operand_instr!(self, ctx, a, b, lsr, _cfg)
+        })
     }
 
-    fn movsx(
+    fn sar(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 MOVSX -> AArch64 SXTB/SXTH/SXTW (handle memory operands)
-        use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+        hooked!(self, ctx, "sar", {
+            // x86-64 SAR a, b -> AArch64 ASR a, a, b
+            handle_two_operand_instr!(self, ctx, a, b, asr, _cfg)
+        })
+    }
 
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        let src_kind = src_adapter.concrete_mem_kind();
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "ror", {
+            // x86-64 ROR a, b -> AArch64 ROR a, a, b
+            handle_two_operand_instr!(self, ctx, a, b, ror, _cfg)
+        })
+    }
 
-        match src_kind {
-            MemArgKind::NoMem(_) => {
-                // Source is register - direct SXT, then store if needed
-                let dest_kind = dest_adapter.concrete_mem_kind();
-                match dest_kind {
+    fn rol(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "rol", {
+            // x86-64 ROL a, b -> AArch64 ROR a, a, (64 - b)
+            // AArch64 has no rotate-left; RORV takes the count modulo 64.
+            use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+            use portal_solutions_asm_x86_64::out::arg::{
+                ArgKind as X64ArgKind, MemArgKind as X64MemArgKind,
+            };
+
+            if let X64MemArgKind::NoMem(X64ArgKind::Lit(v)) = b.concrete_mem_kind() {
+                let count = X64MemArgKind::NoMem(X64ArgKind::Lit((64 - (v & 0x3F)) & 0x3F));
+                handle_two_operand_instr!(self, ctx, a, &count, ror, _cfg)
+            } else {
+                // Negate the count into x17 (x86 counts are CL or immediate, never memory)
+                let count = Reg(17); // x17
+                let a_adapter = self.adapt(a, _cfg);
+                let b_adapter = self.adapt(b, _cfg);
+                self.inner.mov_imm(ctx, self.aarch64_cfg, &count, 64)?;
+                self.inner
+                    .sub(ctx, self.aarch64_cfg, &count, &count, &b_adapter)?;
+                match a_adapter.concrete_mem_kind() {
                     MemArgKind::NoMem(_) => {
                         self.inner
-                            .sxt(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+                            .ror(ctx, self.aarch64_cfg, &a_adapter, &a_adapter, &count)
                     }
                     MemArgKind::Mem { .. } => {
                         let temp = Reg(16); // x16
-                        self.inner.sxt(ctx, self.aarch64_cfg, &temp, &src_adapter)?;
-                        self.inner.str(ctx, self.aarch64_cfg, &temp, &dest_adapter)
+                        self.load_memarg_into_temp(ctx, &a_adapter, &temp)?;
+                        self.inner
+                            .ror(ctx, self.aarch64_cfg, &temp, &temp, &count)?;
+                        self.guest_str(ctx, &temp, &a_adapter)
                     }
-                    _ => todo!(),
+                    _ => Err(unknown_operand("rol").into()),
                 }
             }
-            MemArgKind::Mem { .. } => {
-                // Source is memory - LDR, SXT, store if needed
-                let temp = Reg(16); // x16
-                self.load_memarg_into_temp(ctx, &src_adapter, &temp)?;
-                let temp2 = Reg(17); // x17 for result
-                self.inner.sxt(ctx, self.aarch64_cfg, &temp2, &temp)?;
+        })
+    }
 
-                let dest_kind = dest_adapter.concrete_mem_kind();
-                match dest_kind {
-                    MemArgKind::NoMem(_) => {
-                        self.inner.mov(ctx, self.aarch64_cfg, &dest_adapter, &temp2)
-                    }
-                    MemArgKind::Mem { .. } => {
-                        self.inner.str(ctx, self.aarch64_cfg, &temp2, &dest_adapter)
-                    }
-                    _ => todo!(),
-                }
-            }
-            _ => todo!(),
-        }
+    fn fadd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fadd", {
+            // x86-64 ADDSD -> AArch64 FADD
+            handle_two_operand_instr!(self, ctx, dest, src, fadd, _cfg)
+        })
     }
 
-    fn movzx(
+    fn fsub(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         dest: &(dyn X64MemArg + '_),
         src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 MOVZX -> AArch64 UXTB/UXTH (handle memory operands)
-        use portal_solutions_asm_aarch64::out::arg::MemArgKind;
+        hooked!(self, ctx, "fsub", {
+            // x86-64 SUBSD -> AArch64 FSUB
+            handle_two_operand_instr!(self, ctx, dest, src, fsub, _cfg)
+        })
+    }
 
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        let src_kind = src_adapter.concrete_mem_kind();
+    fn fmul(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fmul", {
+            // x86-64 MULSD -> AArch64 FMUL
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.fmul(
+                ctx,
+                self.aarch64_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
+    }
 
-        match src_kind {
-            MemArgKind::NoMem(_) => {
-                // Source is register - direct UXT, then store if needed
-                let dest_kind = dest_adapter.concrete_mem_kind();
-                match dest_kind {
-                    MemArgKind::NoMem(_) => {
-                        self.inner
-                            .uxt(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
-                    }
-                    MemArgKind::Mem { .. } => {
-                        let temp = Reg(16); // x16
-                        self.inner.uxt(ctx, self.aarch64_cfg, &temp, &src_adapter)?;
-                        self.inner.str(ctx, self.aarch64_cfg, &temp, &dest_adapter)
-                    }
-                    _ => todo!(),
-                }
-            }
-            MemArgKind::Mem { .. } => {
-                // Source is memory - LDR, UXT, store if needed
-                let temp = Reg(16); // x16
-                self.load_memarg_into_temp(ctx, &src_adapter, &temp)?;
-                let temp2 = Reg(17); // x17 for result
-                self.inner.uxt(ctx, self.aarch64_cfg, &temp2, &temp)?;
+    fn fdiv(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fdiv", {
+            // x86-64 DIVSD -> AArch64 FDIV
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.fdiv(
+                ctx,
+                self.aarch64_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
+    }
 
-                let dest_kind = dest_adapter.concrete_mem_kind();
-                match dest_kind {
-                    MemArgKind::NoMem(_) => {
-                        self.inner.mov(ctx, self.aarch64_cfg, &dest_adapter, &temp2)
-                    }
-                    MemArgKind::Mem { .. } => {
-                        self.inner.str(ctx, self.aarch64_cfg, &temp2, &dest_adapter)
-                    }
-                    _ => todo!(),
-                }
-            }
-            _ => todo!(),
-        }
+    fn fcmp(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fcmp", {
+            // x86-64 UCOMISD -> AArch64 FCMP
+            let a_adapter = self.adapt(a, _cfg);
+            let b_adapter = self.adapt(b, _cfg);
+            self.float_compare(ctx, &a_adapter, &b_adapter)
+        })
     }
 
-    fn push(
+    fn fmin(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fmin", {
+            // x86-64 MINSD -> AArch64 FMIN, or FCMP + FCSEL under
+            // NanPolicy::X86 so NaNs and equal operands select `src`.
+            use portal_solutions_asm_aarch64::ConditionCode;
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.float_min_max(ctx, &dest_adapter, &src_adapter, ConditionCode::MI)
+        })
+    }
+
+    fn fmax(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fmax", {
+            // x86-64 MAXSD -> AArch64 FMAX, or FCMP + FCSEL under
+            // NanPolicy::X86.
+            use portal_solutions_asm_aarch64::ConditionCode;
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.float_min_max(ctx, &dest_adapter, &src_adapter, ConditionCode::GT)
+        })
+    }
+
+    fn fsqrt(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fsqrt", {
+            // x86-64 SQRTSD -> AArch64 FSQRT
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .fsqrt(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        })
+    }
+
+    fn fabs(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        _scratch: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fabs", {
+            // The x86-64 mask sequence collapses to FABS; no scratch needed.
+            let dest_adapter = self.adapt(dest, _cfg);
+            self.inner
+                .fabs(ctx, self.aarch64_cfg, &dest_adapter, &dest_adapter)
+        })
+    }
+
+    fn fneg(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        _scratch: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fneg", {
+            // The x86-64 mask sequence collapses to FNEG; no scratch needed.
+            let dest_adapter = self.adapt(dest, _cfg);
+            self.inner
+                .fneg(ctx, self.aarch64_cfg, &dest_adapter, &dest_adapter)
+        })
+    }
+
+    fn cvtsi2sd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cvtsi2sd", {
+            // x86-64 CVTSI2SD -> AArch64 SCVTF; the adapter keeps the source
+            // width, which selects the W or X form.
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .scvtf(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        })
+    }
+
+    fn cvttsd2si(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cvttsd2si", {
+            // x86-64 CVTTSD2SI -> AArch64 FCVTZS. FCVTZS saturates and maps
+            // NaN to 0 where x86-64 returns the integer indefinite value; see
+            // `crate::float`.
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .fcvtzs(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        })
+    }
+
+    fn fmov(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 PUSH -> AArch64 STR with pre-indexed addressing
-        // [sp, #-8]! means: sp = sp - 8, then str to [sp]
-        let sp = Reg(31); // SP
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        self.inner.str(
-            ctx,
-            self.aarch64_cfg,
-            &op_adapter,
-            &portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
-                base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
-                    reg: sp,
-                    size: MemorySize::_64,
-                },
-                offset: None,
-                disp: -8,
-                size: MemorySize::_64,
-                reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
-                mode: portal_solutions_asm_aarch64::out::arg::AddressingMode::PreIndex,
-            },
-        )
+        hooked!(self, ctx, "fmov", {
+            // x86-64 MOVSD -> AArch64 FMOV
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .fmov(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        })
     }
 
-    fn pop(
+    fn addss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 POP -> AArch64 LDR with post-indexed addressing
-        // [sp], #8 means: ldr from [sp], then sp = sp + 8
-        let sp = Reg(31); // SP
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        self.inner.ldr(
-            ctx,
-            self.aarch64_cfg,
-            &op_adapter,
-            &portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
-                base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
-                    reg: sp,
-                    size: MemorySize::_64,
-                },
-                offset: None,
-                disp: 8,
-                size: MemorySize::_64,
-                reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
-                mode: portal_solutions_asm_aarch64::out::arg::AddressingMode::PostIndex,
-            },
-        )
-    }
-
-    fn pushf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
-        // x86-64 PUSHF -> AArch64 MRS NZCV + STR with pre-indexed addressing
-        // Store NZCV flags using MRS
-        let temp = Reg(16); // x16
-        let sp = Reg(31);
-        // Read NZCV flags into temp register
-        self.inner.mrs_nzcv(ctx, self.aarch64_cfg, &temp)?;
-        // Store flags to stack with pre-decrement: [sp, #-8]!
-        self.inner.str(
-            ctx,
-            self.aarch64_cfg,
-            &temp,
-            &portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
-                base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
-                    reg: sp,
-                    size: MemorySize::_64,
-                },
-                offset: None,
-                disp: -8,
-                size: MemorySize::_64,
-                reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
-                mode: portal_solutions_asm_aarch64::out::arg::AddressingMode::PreIndex,
-            },
-        )
+        hooked!(self, ctx, "addss", {
+            // x86-64 ADDSS -> AArch64 FADD on S registers
+            let dest_adapter = self.adapt_single(dest, _cfg);
+            let src_adapter = self.adapt_single(src, _cfg);
+            self.inner.fadd(
+                ctx,
+                self.aarch64_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
     }
 
-    fn popf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
-        // x86-64 POPF -> AArch64 LDR with post-indexed addressing + MSR NZCV
-        let temp = Reg(16); // x16
-        let sp = Reg(31);
-        // Load flags from stack with post-increment: [sp], #8
-        self.inner.ldr(
-            ctx,
-            self.aarch64_cfg,
-            &temp,
-            &portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
-                base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
-                    reg: sp,
-                    size: MemorySize::_64,
-                },
-                offset: None,
-                disp: 8,
-                size: MemorySize::_64,
-                reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
-                mode: portal_solutions_asm_aarch64::out::arg::AddressingMode::PostIndex,
-            },
-        )?;
-        // Write flags back to NZCV
-        self.inner.msr_nzcv(ctx, self.aarch64_cfg, &temp)
+    fn subss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "subss", {
+            // x86-64 SUBSS -> AArch64 FSUB on S registers
+            let dest_adapter = self.adapt_single(dest, _cfg);
+            let src_adapter = self.adapt_single(src, _cfg);
+            self.inner.fsub(
+                ctx,
+                self.aarch64_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
     }
 
-    fn call(
+    fn mulss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 CALL -> AArch64 call shim using labels
-        // Strategy: Branch to a shim that pushes LR and branches to the target
-        // The shim is emitted inline with a jump over it to ensure correctness
-
-        let sp = Reg(31); // SP
-        let lr = Reg(30); // LR (x30)
-
-        // Generate unique labels
-        let shim_label = self.next_shim_label();
-        let skip_label = self.next_shim_label();
-
-        // Jump over the shim to skip_label
-        self.inner.b_label(ctx, self.aarch64_cfg, skip_label)?;
-
-        // Emit the call shim inline
-        self.inner.set_label(ctx, self.aarch64_cfg, shim_label)?;
-
-        // Push LR onto stack with pre-indexed addressing: [sp, #-8]!
-        self.inner.str(
-            ctx,
-            self.aarch64_cfg,
-            &lr,
-            &portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
-                base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
-                    reg: sp,
-                    size: MemorySize::_64,
-                },
-                offset: None,
-                disp: -8,
-                size: MemorySize::_64,
-                reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
-                mode: portal_solutions_asm_aarch64::out::arg::AddressingMode::PreIndex,
-            },
-        )?;
-
-        // Branch to the target
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        self.inner.b(ctx, self.aarch64_cfg, &op_adapter)?;
-
-        // Set skip label (execution continues here)
-        self.inner.set_label(ctx, self.aarch64_cfg, skip_label)?;
-
-        // Branch and link to the shim
-        self.inner.bl_label(ctx, self.aarch64_cfg, shim_label)?;
-
-        Ok(())
+        hooked!(self, ctx, "mulss", {
+            // x86-64 MULSS -> AArch64 FMUL on S registers
+            let dest_adapter = self.adapt_single(dest, _cfg);
+            let src_adapter = self.adapt_single(src, _cfg);
+            self.inner.fmul(
+                ctx,
+                self.aarch64_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
     }
 
-    fn jmp(
+    fn divss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 JMP -> AArch64 B or BR
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        self.inner.b(ctx, self.aarch64_cfg, &op_adapter)
+        hooked!(self, ctx, "divss", {
+            // x86-64 DIVSS -> AArch64 FDIV on S registers
+            let dest_adapter = self.adapt_single(dest, _cfg);
+            let src_adapter = self.adapt_single(src, _cfg);
+            self.inner.fdiv(
+                ctx,
+                self.aarch64_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
     }
 
-    fn cmp(
+    fn ucomiss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         a: &(dyn X64MemArg + '_),
         b: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 CMP -> AArch64 CMP (handle memory operands)
-        use portal_solutions_asm_aarch64::out::arg::MemArgKind;
-
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-
-        let a_kind = a_adapter.concrete_mem_kind();
-        let b_kind = b_adapter.concrete_mem_kind();
-
-        match (a_kind, b_kind) {
-            (MemArgKind::NoMem(_), MemArgKind::NoMem(_)) => {
-                // Both are registers/immediates - direct CMP
-                self.inner
-                    .cmp(ctx, self.aarch64_cfg, &a_adapter, &b_adapter)
-            }
-            (MemArgKind::Mem { .. }, _) => {
-                // a is memory - LDR into temp, then CMP
-                let temp = Reg(16); // x16
-                self.load_memarg_into_temp(ctx, &a_adapter, &temp)?;
-                if matches!(b_kind, MemArgKind::Mem { .. }) {
-                    let temp_b = Reg(17); // x17
-                    self.inner.ldr(ctx, self.aarch64_cfg, &temp_b, &b_adapter)?;
-                    self.inner.cmp(ctx, self.aarch64_cfg, &temp, &temp_b)
-                } else {
-                    self.inner.cmp(ctx, self.aarch64_cfg, &temp, &b_adapter)
-                }
-            }
-            (MemArgKind::NoMem(_), MemArgKind::Mem { .. }) => {
-                // b is memory - LDR into temp, then CMP
-                let temp = Reg(17); // x17
-                self.load_memarg_into_temp(ctx, &b_adapter, &temp)?;
-                self.inner.cmp(ctx, self.aarch64_cfg, &a_adapter, &temp)
-            }
-            _ => todo!(),
-        }
+        hooked!(self, ctx, "ucomiss", {
+            // x86-64 UCOMISS -> AArch64 FCMP on S registers
+            let a_adapter = self.adapt_single(a, _cfg);
+            let b_adapter = self.adapt_single(b, _cfg);
+            self.float_compare(ctx, &a_adapter, &b_adapter)
+        })
     }
 
-    fn cmp0(
+    fn minss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 CMP op, 0 -> AArch64 CMP op, #0 (handle memory operands)
-        use portal_solutions_asm_aarch64::out::arg::MemArgKind;
-
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        let op_kind = op_adapter.concrete_mem_kind();
-
-        match op_kind {
-            MemArgKind::NoMem(_) => {
-                // Register/immediate - direct CMP
-                self.inner.cmp(ctx, self.aarch64_cfg, &op_adapter, &0u64)
-            }
-            MemArgKind::Mem { .. } => {
-                // Memory - LDR into temp, then CMP
-                let temp = Reg(16); // x16
-                self.load_memarg_into_temp(ctx, &op_adapter, &temp)?;
-                self.inner.cmp(ctx, self.aarch64_cfg, &temp, &0u64)
-            }
-            _ => todo!(),
-        }
+        hooked!(self, ctx, "minss", {
+            // x86-64 MINSS -> AArch64 FMIN, as for `fmin`.
+            use portal_solutions_asm_aarch64::ConditionCode;
+            let dest_adapter = self.adapt_single(dest, _cfg);
+            let src_adapter = self.adapt_single(src, _cfg);
+            self.float_min_max(ctx, &dest_adapter, &src_adapter, ConditionCode::MI)
+        })
     }
 
-    fn cmovcc(
+    fn maxss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        cond: X64ConditionCode,
-        op: &(dyn X64MemArg + '_),
-        val: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 CMOVcc -> AArch64 CSEL (handle memory operands)
-        use portal_solutions_asm_aarch64::out::arg::MemArgKind;
-
-        let aarch64_cond = translate_condition(cond);
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        let val_adapter = MemArgAdapter::new(val, _cfg);
-
-        let op_kind = op_adapter.concrete_mem_kind();
-        let val_kind = val_adapter.concrete_mem_kind();
-
-        match (op_kind, val_kind) {
-            (MemArgKind::NoMem(_), MemArgKind::NoMem(_)) => {
-                // Both registers - direct CSEL
-                self.inner.csel(
-                    ctx,
-                    self.aarch64_cfg,
-                    aarch64_cond,
-                    &op_adapter,
-                    &val_adapter,
-                    &op_adapter,
-                )
-            }
-            (MemArgKind::Mem { .. }, MemArgKind::NoMem(_)) => {
-                // op is memory - LDR, CSEL, STR
-                let temp = Reg(16); // x16
-                self.load_memarg_into_temp(ctx, &op_adapter, &temp)?;
-                self.inner.csel(
-                    ctx,
-                    self.aarch64_cfg,
-                    aarch64_cond,
-                    &temp,
-                    &val_adapter,
-                    &temp,
-                )?;
-                self.inner.str(ctx, self.aarch64_cfg, &temp, &op_adapter)
-            }
-            (MemArgKind::NoMem(_), MemArgKind::Mem { .. }) => {
-                // val is memory - LDR val, then CSEL
-                let temp = Reg(17); // x17
-                self.load_memarg_into_temp(ctx, &val_adapter, &temp)?;
-                self.inner.csel(
-                    ctx,
-                    self.aarch64_cfg,
-                    aarch64_cond,
-                    &op_adapter,
-                    &temp,
-                    &op_adapter,
-                )
-            }
-            (MemArgKind::Mem { .. }, MemArgKind::Mem { .. }) => {
-                // Both memory - LDR both, CSEL, STR
-                let temp_op = Reg(16); // x16
-                let temp_val = Reg(17); // x17
-                self.load_memarg_into_temp(ctx, &op_adapter, &temp_op)?;
-                self.load_memarg_into_temp(ctx, &val_adapter, &temp_val)?;
-                self.inner.csel(
-                    ctx,
-                    self.aarch64_cfg,
-                    aarch64_cond,
-                    &temp_op,
-                    &temp_val,
-                    &temp_op,
-                )?;
-                self.inner.str(ctx, self.aarch64_cfg, &temp_op, &op_adapter)
-            }
-            _ => todo!(),
-        }
+        hooked!(self, ctx, "maxss", {
+            // x86-64 MAXSS -> AArch64 FMAX, as for `fmax`.
+            use portal_solutions_asm_aarch64::ConditionCode;
+            let dest_adapter = self.adapt_single(dest, _cfg);
+            let src_adapter = self.adapt_single(src, _cfg);
+            self.float_min_max(ctx, &dest_adapter, &src_adapter, ConditionCode::GT)
+        })
     }
 
-    fn not(
+    fn sqrtss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 NOT -> AArch64 MVN (handle memory operands)
-        use portal_solutions_asm_aarch64::out::arg::MemArgKind;
-
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        let op_kind = op_adapter.concrete_mem_kind();
-
-        match op_kind {
-            MemArgKind::NoMem(_) => {
-                // Register - direct MVN
-                self.inner
-                    .mvn(ctx, self.aarch64_cfg, &op_adapter, &op_adapter)
-            }
-            MemArgKind::Mem { .. } => {
-                // Memory - LDR, MVN, STR
-                let temp = Reg(16); // x16
-                self.load_memarg_into_temp(ctx, &op_adapter, &temp)?;
-                self.inner.mvn(ctx, self.aarch64_cfg, &temp, &temp)?;
-                self.inner.str(ctx, self.aarch64_cfg, &temp, &op_adapter)
-            }
-            _ => todo!(),
-        }
+        hooked!(self, ctx, "sqrtss", {
+            // x86-64 SQRTSS -> AArch64 FSQRT
+            let dest_adapter = self.adapt_single(dest, _cfg);
+            let src_adapter = self.adapt_single(src, _cfg);
+            self.inner
+                .fsqrt(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        })
     }
 
-    fn lea(
+    fn cvtsi2ss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         dest: &(dyn X64MemArg + '_),
         src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 LEA -> AArch64 ADD/ADR (depending on context)
-        // For simplicity, use ADR for now
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner
-            .adr(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
-    }
-
-    fn get_ip(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
-        // x86-64 get IP (typically via CALL trick) -> AArch64 ADR
-        // PERFORMANCE: Different approach than x86-64
-        let pc_reg = Reg(30); // LR (link register)
-        self.inner.adr(ctx, self.aarch64_cfg, &pc_reg, &0u64)
+        hooked!(self, ctx, "cvtsi2ss", {
+            // x86-64 CVTSI2SS -> AArch64 SCVTF; the source width selects the
+            // W or X form.
+            let dest_adapter = self.adapt_single(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .scvtf(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        })
     }
 
-    fn ret(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
-        // x86-64 RET -> AArch64 ret shim (inline, no jump)
-        // Directly emit: pop return address from stack, then return
-
-        let sp = Reg(31); // SP
-        let lr = Reg(30); // LR (x30)
-
-        // Pop return address from stack with post-indexed addressing: [sp], #8
-        self.inner.ldr(
-            ctx,
-            self.aarch64_cfg,
-            &lr,
-            &portal_solutions_asm_aarch64::out::arg::MemArgKind::Mem {
-                base: portal_solutions_asm_aarch64::out::arg::ArgKind::Reg {
-                    reg: sp,
-                    size: MemorySize::_64,
-                },
-                offset: None,
-                disp: 8,
-                size: MemorySize::_64,
-                reg_class: portal_solutions_asm_aarch64::RegisterClass::Gpr,
-                mode: portal_solutions_asm_aarch64::out::arg::AddressingMode::PostIndex,
-            },
-        )?;
-
-        // Return
-        self.inner.ret(ctx, self.aarch64_cfg)
+    fn cvttss2si(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cvttss2si", {
+            // x86-64 CVTTSS2SI -> AArch64 FCVTZS, saturating as for
+            // `cvttsd2si`.
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt_single(src, _cfg);
+            self.inner
+                .fcvtzs(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        })
     }
 
-    fn mov64(
+    fn cvtss2sd(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        r: &(dyn X64MemArg + '_),
-        val: u64,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 MOV r, imm64 -> AArch64 MOVZ/MOVK sequence
-        let r_adapter = MemArgAdapter::new(r, _cfg);
-        self.inner.mov_imm(ctx, self.aarch64_cfg, &r_adapter, val)
+        hooked!(self, ctx, "cvtss2sd", {
+            // x86-64 CVTSS2SD -> AArch64 FCVT
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt_single(src, _cfg);
+            self.inner
+                .fcvt(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        })
     }
 
-    fn mul(
+    fn cvtsd2ss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 MUL a, b -> AArch64 MUL a, a, b
-        handle_two_operand_instr!(self, ctx, a, b, mul, _cfg)
+        hooked!(self, ctx, "cvtsd2ss", {
+            // x86-64 CVTSD2SS -> AArch64 FCVT
+            let dest_adapter = self.adapt_single(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .fcvt(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        })
     }
 
-    fn div(
+    fn movss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 DIV a, b -> AArch64 UDIV a, a, b
-        handle_two_operand_instr!(self, ctx, a, b, udiv, _cfg)
+        hooked!(self, ctx, "movss", {
+            // x86-64 MOVSS -> AArch64 FMOV
+            let dest_adapter = self.adapt_single(dest, _cfg);
+            let src_adapter = self.adapt_single(src, _cfg);
+            self.inner
+                .fmov(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        })
     }
 
-    fn idiv(
+    fn ldmxcsr(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 IDIV a, b -> AArch64 SDIV a, a, b
-        handle_two_operand_instr!(self, ctx, a, b, sdiv, _cfg)
+        hooked!(self, ctx, "ldmxcsr", {
+            // Only RC reaches FPCR.RMode; see `crate::float`. The two fields
+            // order their modes as bit reversals of each other.
+            let cfg = self.aarch64_cfg;
+            let (rc, fpcr) = (Reg(16), Reg(17));
+            let adapter = self.adapt(src, _cfg);
+            self.load_memarg_into_temp(ctx, &adapter, &rc)?;
+            self.inner.ubfx(ctx, cfg, &rc, &rc, 13, 2)?;
+            self.inner.rbit(ctx, cfg, &rc, &rc)?;
+            self.inner.lsr(ctx, cfg, &rc, &rc, &62u64)?;
+            self.inner.mrs_fpcr(ctx, cfg, &fpcr)?;
+            self.inner.bfi(ctx, cfg, &fpcr, &rc, 22, 2)?;
+            self.inner.msr_fpcr(ctx, cfg, &fpcr)
+        })
     }
 
-    fn and(
+    fn stmxcsr(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 AND a, b -> AArch64 AND a, a, b
-        handle_two_operand_instr!(self, ctx, a, b, and, _cfg)
+        hooked!(self, ctx, "stmxcsr", {
+            use portal_solutions_asm_aarch64::out::arg::ArgKind;
+
+            let cfg = self.aarch64_cfg;
+            let value = Reg(17);
+            self.inner.mrs_fpcr(ctx, cfg, &value)?;
+            self.inner.ubfx(ctx, cfg, &value, &value, 22, 2)?;
+            self.inner.rbit(ctx, cfg, &value, &value)?;
+            self.inner.lsr(ctx, cfg, &value, &value, &62u64)?;
+            // RC << 13 | 0x1F80, every exception masked.
+            self.inner.lsl(ctx, cfg, &value, &value, &13u64)?;
+            self.inner.orr_imm(ctx, cfg, &value, &value, 0x1F80)?;
+            let word = ArgKind::Reg {
+                reg: value,
+                size: MemorySize::_32,
+            };
+            let adapter = self.adapt(dest, _cfg);
+            self.store_guest(ctx, &word, &adapter)
+        })
     }
 
-    fn or(
+    fn rdsegbase(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        seg: Segment,
+        dest: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 OR a, b -> AArch64 ORR a, a, b
-        handle_two_operand_instr!(self, ctx, a, b, orr, _cfg)
+        hooked!(self, ctx, "rdsegbase", {
+            // The fs base is TLS_BASE, as for fs: operands; gs has no counterpart.
+            use portal_solutions_asm_aarch64::out::error::EmitError;
+            if seg == Segment::Fs {
+                let dest_adapter = self.adapt(dest, _cfg);
+                self.inner
+                    .mov(ctx, self.aarch64_cfg, &dest_adapter, &TLS_BASE)
+            } else {
+                Err(EmitError::UnsupportedInstruction {
+                    mnemonic: "rdgsbase",
+                }
+                .into())
+            }
+        })
     }
 
-    fn eor(
+    fn wrsegbase(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        seg: Segment,
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 XOR a, b -> AArch64 EOR a, a, b
-        handle_two_operand_instr!(self, ctx, a, b, eor, _cfg)
+        hooked!(self, ctx, "wrsegbase", {
+            // Only the shim's copy changes; TPIDR_EL0 keeps the host's thread
+            // pointer, so a later load_tls_base undoes this.
+            use portal_solutions_asm_aarch64::out::error::EmitError;
+            if seg == Segment::Fs {
+                let src_adapter = self.adapt(src, _cfg);
+                self.inner
+                    .mov(ctx, self.aarch64_cfg, &TLS_BASE, &src_adapter)
+            } else {
+                Err(EmitError::UnsupportedInstruction {
+                    mnemonic: "wrgsbase",
+                }
+                .into())
+            }
+        })
     }
 
-    fn shl(
+    // x87 stack slots live in v24-v31; see `crate::x87` for the model.
+    fn fld(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 SHL a, b -> AArch64 LSL a, a, b
-        handle_two_operand_instr!(self, ctx, a, b, lsl, _cfg)
+        hooked!(self, ctx, "fld", {
+            crate::x87::check_operand(_cfg, "fld", Some(src)).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fld", PrecisionLoss::X87AsDouble);
+            let slot = self.x87.push();
+            let adapter = self.adapt(src, _cfg);
+            self.load_memarg_into_temp(ctx, &adapter, &Reg(24 + slot))
+        })
     }
 
-    fn shr(
+    fn fst(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 SHR a, b -> AArch64 LSR a, a, b
-        handle_two_operand_instr!(self, ctx, a, b, lsr, _cfg)
+        hooked!(self, ctx, "fst", {
+            crate::x87::check_operand(_cfg, "fst", Some(dest)).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fst", PrecisionLoss::X87AsDouble);
+            let slot = self.x87.st(0);
+            let adapter = self.adapt(dest, _cfg);
+            self.guest_str(ctx, &Reg(24 + slot), &adapter)
+        })
     }
 
-    fn fadd(
+    fn fstp(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // x86-64 ADDSD -> AArch64 FADD
-        handle_two_operand_instr!(self, ctx, dest, src, fadd, _cfg)
+        hooked!(self, ctx, "fstp", {
+            crate::x87::check_operand(_cfg, "fstp", Some(dest)).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fstp", PrecisionLoss::X87AsDouble);
+            let slot = self.x87.st(0);
+            self.x87.pop();
+            let adapter = self.adapt(dest, _cfg);
+            self.guest_str(ctx, &Reg(24 + slot), &adapter)
+        })
     }
 
-    fn fsub(
+    fn faddp(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "faddp", {
+            // ST(1) = ST(1) op ST(0), then pop
+            crate::x87::check_operand(_cfg, "faddp", None).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("faddp", PrecisionLoss::X87AsDouble);
+            let a = Reg(24 + self.x87.st(1));
+            let b = Reg(24 + self.x87.st(0));
+            self.x87.pop();
+            self.inner.fadd(ctx, self.aarch64_cfg, &a, &a, &b)
+        })
+    }
+
+    fn fsubp(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fsubp", {
+            // ST(1) = ST(1) op ST(0), then pop
+            crate::x87::check_operand(_cfg, "fsubp", None).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fsubp", PrecisionLoss::X87AsDouble);
+            let a = Reg(24 + self.x87.st(1));
+            let b = Reg(24 + self.x87.st(0));
+            self.x87.pop();
+            self.inner.fsub(ctx, self.aarch64_cfg, &a, &a, &b)
+        })
+    }
+
+    fn fmulp(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fmulp", {
+            // ST(1) = ST(1) op ST(0), then pop
+            crate::x87::check_operand(_cfg, "fmulp", None).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fmulp", PrecisionLoss::X87AsDouble);
+            let a = Reg(24 + self.x87.st(1));
+            let b = Reg(24 + self.x87.st(0));
+            self.x87.pop();
+            self.inner.fmul(ctx, self.aarch64_cfg, &a, &a, &b)
+        })
+    }
+
+    fn fdivp(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fdivp", {
+            // ST(1) = ST(1) op ST(0), then pop
+            crate::x87::check_operand(_cfg, "fdivp", None).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fdivp", PrecisionLoss::X87AsDouble);
+            let a = Reg(24 + self.x87.st(1));
+            let b = Reg(24 + self.x87.st(0));
+            self.x87.pop();
+            self.inner.fdiv(ctx, self.aarch64_cfg, &a, &a, &b)
+        })
+    }
+
+    // String instructions walk RSI/RDI (x3/x4); see `crate::string_ops`.
+    fn movs(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
+        size: MemorySize,
+        rep: RepPrefix,
     ) -> Result<(), Self::Error> {
-        // x86-64 SUBSD -> AArch64 FSUB
-        handle_two_operand_instr!(self, ctx, dest, src, fsub, _cfg)
+        hooked!(self, ctx, "movs", {
+            // Bytes are copied as is, so no swap on big-endian targets.
+            let data = portal_solutions_asm_aarch64::out::arg::ArgKind::Reg { reg: Reg(17), size };
+            self.string_loop(ctx, StringLoop::new(rep, false), |this, ctx| {
+                this.guest_ldr(ctx, &data, &string_element(Reg(3), size))?;
+                this.guest_str(ctx, &data, &string_element(Reg(4), size))
+            })
+        })
     }
 
-    fn fmul(
+    fn stos(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
+        size: MemorySize,
+        rep: RepPrefix,
     ) -> Result<(), Self::Error> {
-        // x86-64 MULSD -> AArch64 FMUL
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner.fmul(
-            ctx,
-            self.aarch64_cfg,
-            &dest_adapter,
-            &dest_adapter,
-            &src_adapter,
-        )
+        hooked!(self, ctx, "stos", {
+            let temp = Reg(17); // x17
+            let data = portal_solutions_asm_aarch64::out::arg::ArgKind::Reg { reg: temp, size };
+            self.inner.mov(ctx, self.aarch64_cfg, &temp, &Reg(0))?;
+            if self.aarch64_cfg.big_endian && size != MemorySize::_8 {
+                self.swap_bytes(ctx, temp, size)?;
+            }
+            self.string_loop(ctx, StringLoop::new(rep, false), |this, ctx| {
+                this.guest_str(ctx, &data, &string_element(Reg(4), size))
+            })
+        })
     }
 
-    fn fdiv(
+    fn cmps(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
+        size: MemorySize,
+        rep: RepPrefix,
     ) -> Result<(), Self::Error> {
-        // x86-64 DIVSD -> AArch64 FDIV
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner.fdiv(
-            ctx,
-            self.aarch64_cfg,
-            &dest_adapter,
-            &dest_adapter,
-            &src_adapter,
-        )
+        hooked!(self, ctx, "cmps", {
+            let (a, b) = (Reg(16), Reg(17)); // x16, x17
+            self.string_loop(ctx, StringLoop::new(rep, true), |this, ctx| {
+                this.load_string_element(ctx, a, Reg(3), size, true)?;
+                this.load_string_element(ctx, b, Reg(4), size, true)?;
+                this.inner.cmp(ctx, this.aarch64_cfg, &a, &b)
+            })
+        })
     }
 
-    fn fmov(
+    fn scas(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
+        size: MemorySize,
+        rep: RepPrefix,
     ) -> Result<(), Self::Error> {
-        // x86-64 MOVSD -> AArch64 FMOV
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner
-            .fmov(ctx, self.aarch64_cfg, &dest_adapter, &src_adapter)
+        hooked!(self, ctx, "scas", {
+            let (a, b) = (Reg(16), Reg(17)); // x16, x17
+            self.string_loop(ctx, StringLoop::new(rep, true), |this, ctx| {
+                let cfg = this.aarch64_cfg;
+                // x16 held the saved flags; the CMP below replaces them.
+                if size == MemorySize::_64 {
+                    this.inner.mov(ctx, cfg, &a, &Reg(0))?;
+                } else {
+                    this.inner
+                        .lsl(ctx, cfg, &a, &Reg(0), &compare_shift(size))?;
+                }
+                this.load_string_element(ctx, b, Reg(4), size, true)?;
+                this.inner.cmp(ctx, cfg, &a, &b)
+            })
+        })
+    }
+
+    fn syscall(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "syscall", {
+            match self.syscall {
+                SyscallMode::Trap => self.inner.svc(ctx, self.aarch64_cfg, 0),
+                SyscallMode::Linux => self.linux_syscall(ctx),
+            }
+        })
+    }
+
+    fn endbr64(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "endbr64", {
+            // x86-64 ENDBR64 -> AArch64 BTI JC (the guest may reach it by call or jump)
+            if self.aarch64_cfg.bti {
+                self.inner
+                    .bti(ctx, self.aarch64_cfg, portal_solutions_asm_aarch64::BtiTarget::JC)?;
+            }
+            Ok(())
+        })
     }
 }
 
-impl<W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>, L, Context>
-    X64Writer<L, Context> for X64ToAArch64Shim<W>
+impl<W: portal_solutions_asm_aarch64::out::Writer<ShimLabel, Context>, H, L, Context>
+    X64Writer<L, Context> for X64ToAArch64Shim<W, H>
 where
     W: portal_solutions_asm_aarch64::out::Writer<L, Context>,
+    H: ShimHooks<W, Context>,
+    <W as portal_solutions_asm_aarch64::out::WriterCore<Context>>::Error:
+        From<portal_solutions_asm_aarch64::out::error::EmitError>,
 {
     fn set_label(&mut self, ctx: &mut Context, _cfg: X64Arch, s: L) -> Result<(), Self::Error> {
-        self.inner.set_label(ctx, self.aarch64_cfg, s)
+        self.inner.set_label(ctx, self.aarch64_cfg, s)?;
+        self.hooks.label(&mut self.inner, ctx, self.aarch64_cfg)
     }
 
     fn lea_label(
@@ -1498,9 +4945,73 @@ where
         dest: &(dyn X64MemArg + '_),
         label: L,
     ) -> Result<(), Self::Error> {
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        self.inner
-            .adr_label(ctx, self.aarch64_cfg, &dest_adapter, label)
+        hooked!(self, ctx, "lea_label", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            self.inner
+                .adr_label(ctx, self.aarch64_cfg, &dest_adapter, label)
+        })
+    }
+
+    fn call_label(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "call_label", {
+            // Same shape as `call`, with a direct branch to the label:
+            // b skip; shim: push lr; b label; skip: bl shim
+            // or, with a shadow stack: save lr; bl label; restore lr
+            let lr = Reg(30); // LR (x30)
+            match self.return_stack {
+                ReturnStack::Standard => {
+                    let shim_label = self.next_shim_label();
+                    let skip_label = self.next_shim_label();
+
+                    self.inner.b_label(ctx, self.aarch64_cfg, skip_label)?;
+                    self.inner.set_label(ctx, self.aarch64_cfg, shim_label)?;
+                    self.push_return_address(ctx, &lr)?;
+                    self.inner.b_label(ctx, self.aarch64_cfg, label)?;
+                    self.inner.set_label(ctx, self.aarch64_cfg, skip_label)?;
+                    self.inner.bl_label(ctx, self.aarch64_cfg, shim_label)
+                }
+                ReturnStack::Shadow(_) => {
+                    self.push_return_address(ctx, &lr)?;
+                    self.inner.bl_label(ctx, self.aarch64_cfg, label)?;
+                    self.pop_return_address(ctx, &lr)
+                }
+            }
+        })
+    }
+
+    fn jcc_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: X64ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.jcc_label_hinted(ctx, cfg, cc, label, portal_solutions_asm_x86_64::BranchHint::None)
+    }
+
+    fn jcc_label_hinted(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        cc: X64ConditionCode,
+        label: L,
+        hint: portal_solutions_asm_x86_64::BranchHint,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "jcc_label", {
+            // x86-64 Jcc -> AArch64 B.cond on the flags the last CMP/TEST left in NZCV
+            self.inner.bcond_label_hinted(
+                ctx,
+                self.aarch64_cfg,
+                translate_condition(cc),
+                label,
+                crate::cond::x64_hint_to_aarch64(hint),
+            )
+        })
     }
 }
 
@@ -1516,6 +5027,7 @@ pub mod translation_guide {
     //! - `ADD` → `ADD`
     //! - `SUB` → `SUB`
     //! - `CMP` → `CMP`
+    //! - `TEST` → `TST` (`ANDS XZR`)
     //! - `RET` → `RET`
     //! - `CALL` → `BL` (direct) / `BLR` (indirect)
     //! - `JMP` → `B` (direct) / `BR` (indirect)
@@ -1528,6 +5040,8 @@ pub mod translation_guide {
     //! - `XOR` → `EOR`
     //! - `SHL` → `LSL`
     //! - `SHR` → `LSR`
+    //! - `SAR` → `ASR`
+    //! - `ROR` → `ROR`
     //! - `NOT` → `MVN`
     //! - `MOVSX` → `SXTB/SXTH/SXTW`
     //! - `MOVZX` → `UXTB/UXTH`
@@ -1546,10 +5060,21 @@ pub mod translation_guide {
     //! - `POPF` → `LDR temp, [sp]; ADD sp, sp, #8; MSR NZCV, temp` (3 instructions)
     //! - `LEA` → `ADR` or `ADD` (depending on addressing mode)
     //! - `MOV r, imm64` → `MOVZ/MOVK` sequence (1-4 instructions)
+    //! - `ROL a, imm` → `ROR a, a, #(64 - imm)`
+    //! - `ROL a, cl` → `MOV x17, #64; SUB x17, x17, cl; ROR a, a, x17` (3 instructions)
+    //! - `MUL`/`IMUL` (RDX:RAX form) → `UMULH`/`SMULH` + `MUL`
+    //! - `RDTSC` → `MRS x2, CNTVCT_EL0; UBFX x0, x2, #0, #32; LSR x2, x2, #32`
+    //!   (3 instructions)
+    //! - `DIV`/`IDIV` with a guarding `div_fault` mode → `CMP`/`B.cond` checks,
+    //!   `BRK #0` or `B handler` on fault, then `UDIV`/`SDIV`
     //!
     //! ## Approximations (behavior differs)
     //! - Parity flag conditions (`P`/`NP`) → Always true (AArch64 has no parity flag)
+    //! - `DIV`/`IDIV` by zero or `INT_MIN / -1` → No fault under the default
+    //!   `DivFaultMode::Native` (AArch64 returns 0 / `INT_MIN`)
     //! - `XCHG` → Not atomic without explicit barriers
+    //! - `RDTSC`/`RDTSCP` → Count at the system counter's frequency, not the
+    //!   TSC's; `RDTSCP` leaves 0 in ECX
     //!
     //! ## Register Mapping
     //! - x86-64: RAX-RDI (0-7), R8-R15 (8-15)
@@ -0,0 +1,238 @@
+//! Condition-code translation between x86-64 and the target architectures.
+//!
+//! The shims lower `cmp`/`test` plus a condition into target-native flag
+//! checks (AArch64) or compare-and-branch forms (RISC-V). Every translation
+//! goes through this module so the mappings stay consistent between `jcc`,
+//! `cmovcc` and `setcc`, and so the inverse direction is available to shims
+//! translating *into* x86-64.
+//!
+//! # Translation table
+//!
+//! | x86-64 | AArch64 | RISC-V | Notes |
+//! |--------|---------|--------|-------|
+//! | E/Z    | EQ      | EQ     | |
+//! | NE/NZ  | NE      | NE     | |
+//! | B/C    | LO      | LTU    | AArch64 carry is inverted for subtraction |
+//! | NB/NC  | HS      | GEU    | |
+//! | A      | HI      | GTU    | |
+//! | NA     | LS      | LEU    | |
+//! | L      | LT      | LT     | |
+//! | NL     | GE      | GE     | |
+//! | G      | GT      | GT     | |
+//! | NG     | LE      | LE     | |
+//! | O      | VS      | NE*    | RISC-V has no overflow flag |
+//! | NO     | VC      | EQ*    | |
+//! | S      | MI      | LT*    | Sign of the comparison result |
+//! | NS     | PL      | GE*    | |
+//! | P/PE   | AL*     | EQ*    | Neither target has a parity flag |
+//! | NP/PO  | AL*     | NE*    | |
+//!
+//! Entries marked `*` are approximations: the inverse mapping does not lead
+//! back to the same x86-64 condition.
+
+use portal_solutions_asm_aarch64::BranchHint as AArch64Hint;
+use portal_solutions_asm_aarch64::ConditionCode as AArch64CC;
+use portal_solutions_asm_riscv64::BranchHint as RiscVHint;
+use portal_solutions_asm_riscv64::ConditionCode as RiscVCC;
+use portal_solutions_asm_x86_64::BranchHint as X64Hint;
+use portal_solutions_asm_x86_64::ConditionCode as X64CC;
+
+/// Translates an x86-64 condition to the AArch64 condition tested after the
+/// shim's flag-setting lowering.
+pub fn x64_to_aarch64(cc: X64CC) -> AArch64CC {
+    match cc {
+        X64CC::E => AArch64CC::EQ,
+        X64CC::NE => AArch64CC::NE,
+        X64CC::B => AArch64CC::LO,
+        X64CC::NB => AArch64CC::HS,
+        X64CC::A => AArch64CC::HI,
+        X64CC::NA => AArch64CC::LS,
+        X64CC::L => AArch64CC::LT,
+        X64CC::NL => AArch64CC::GE,
+        X64CC::G => AArch64CC::GT,
+        X64CC::NG => AArch64CC::LE,
+        X64CC::O => AArch64CC::VS,
+        X64CC::NO => AArch64CC::VC,
+        X64CC::S => AArch64CC::MI,
+        X64CC::NS => AArch64CC::PL,
+        // No parity flag; treat as always taken.
+        X64CC::P | X64CC::NP => AArch64CC::AL,
+        _ => AArch64CC::AL,
+    }
+}
+
+/// Translates an x86-64 condition to the RISC-V branch comparison applied to
+/// the shim's comparison result.
+pub fn x64_to_riscv64(cc: X64CC) -> RiscVCC {
+    match cc {
+        X64CC::E => RiscVCC::EQ,
+        X64CC::NE => RiscVCC::NE,
+        X64CC::B => RiscVCC::LTU,
+        X64CC::NB => RiscVCC::GEU,
+        X64CC::A => RiscVCC::GTU,
+        X64CC::NA => RiscVCC::LEU,
+        X64CC::L => RiscVCC::LT,
+        X64CC::NL => RiscVCC::GE,
+        X64CC::G => RiscVCC::GT,
+        X64CC::NG => RiscVCC::LE,
+        // Approximations: RISC-V has no overflow or parity flags.
+        X64CC::O => RiscVCC::NE,
+        X64CC::NO => RiscVCC::EQ,
+        X64CC::S => RiscVCC::LT,
+        X64CC::NS => RiscVCC::GE,
+        X64CC::P => RiscVCC::EQ,
+        X64CC::NP => RiscVCC::NE,
+        _ => RiscVCC::EQ,
+    }
+}
+
+/// Carries an x86-64 branch hint over to AArch64.
+pub fn x64_hint_to_aarch64(hint: X64Hint) -> AArch64Hint {
+    match hint {
+        X64Hint::Likely => AArch64Hint::Likely,
+        X64Hint::Unlikely => AArch64Hint::Unlikely,
+        _ => AArch64Hint::None,
+    }
+}
+
+/// Carries an x86-64 branch hint over to RISC-V.
+pub fn x64_hint_to_riscv64(hint: X64Hint) -> RiscVHint {
+    match hint {
+        X64Hint::Likely => RiscVHint::Likely,
+        X64Hint::Unlikely => RiscVHint::Unlikely,
+        _ => RiscVHint::None,
+    }
+}
+
+/// Translates an AArch64 condition back to x86-64.
+///
+/// Returns `None` for `AL`/`NV`, which have no conditional x86-64 form.
+pub fn aarch64_to_x64(cc: AArch64CC) -> Option<X64CC> {
+    match cc {
+        AArch64CC::EQ => Some(X64CC::E),
+        AArch64CC::NE => Some(X64CC::NE),
+        AArch64CC::HS => Some(X64CC::NB),
+        AArch64CC::LO => Some(X64CC::B),
+        AArch64CC::MI => Some(X64CC::S),
+        AArch64CC::PL => Some(X64CC::NS),
+        AArch64CC::VS => Some(X64CC::O),
+        AArch64CC::VC => Some(X64CC::NO),
+        AArch64CC::HI => Some(X64CC::A),
+        AArch64CC::LS => Some(X64CC::NA),
+        AArch64CC::GE => Some(X64CC::NL),
+        AArch64CC::LT => Some(X64CC::L),
+        AArch64CC::GT => Some(X64CC::G),
+        AArch64CC::LE => Some(X64CC::NG),
+        _ => None,
+    }
+}
+
+/// Translates a RISC-V branch comparison back to the x86-64 condition tested
+/// after `cmp a, b`.
+pub fn riscv64_to_x64(cc: RiscVCC) -> Option<X64CC> {
+    match cc {
+        RiscVCC::EQ => Some(X64CC::E),
+        RiscVCC::NE => Some(X64CC::NE),
+        RiscVCC::LT => Some(X64CC::L),
+        RiscVCC::GE => Some(X64CC::NL),
+        RiscVCC::LTU => Some(X64CC::B),
+        RiscVCC::GEU => Some(X64CC::NB),
+        RiscVCC::GT => Some(X64CC::G),
+        RiscVCC::LE => Some(X64CC::NG),
+        RiscVCC::GTU => Some(X64CC::A),
+        RiscVCC::LEU => Some(X64CC::NA),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(x86-64, AArch64, RISC-V, exact on AArch64, exact on RISC-V)`.
+    const TABLE: [(X64CC, AArch64CC, RiscVCC, bool, bool); 16] = [
+        (X64CC::E, AArch64CC::EQ, RiscVCC::EQ, true, true),
+        (X64CC::NE, AArch64CC::NE, RiscVCC::NE, true, true),
+        (X64CC::B, AArch64CC::LO, RiscVCC::LTU, true, true),
+        (X64CC::NB, AArch64CC::HS, RiscVCC::GEU, true, true),
+        (X64CC::A, AArch64CC::HI, RiscVCC::GTU, true, true),
+        (X64CC::NA, AArch64CC::LS, RiscVCC::LEU, true, true),
+        (X64CC::L, AArch64CC::LT, RiscVCC::LT, true, true),
+        (X64CC::NL, AArch64CC::GE, RiscVCC::GE, true, true),
+        (X64CC::G, AArch64CC::GT, RiscVCC::GT, true, true),
+        (X64CC::NG, AArch64CC::LE, RiscVCC::LE, true, true),
+        (X64CC::O, AArch64CC::VS, RiscVCC::NE, true, false),
+        (X64CC::NO, AArch64CC::VC, RiscVCC::EQ, true, false),
+        (X64CC::S, AArch64CC::MI, RiscVCC::LT, true, false),
+        (X64CC::NS, AArch64CC::PL, RiscVCC::GE, true, false),
+        (X64CC::P, AArch64CC::AL, RiscVCC::EQ, false, false),
+        (X64CC::NP, AArch64CC::AL, RiscVCC::NE, false, false),
+    ];
+
+    #[test]
+    fn forward_matches_table() {
+        for (x64, a64, rv, _, _) in TABLE {
+            assert_eq!(x64_to_aarch64(x64), a64, "{x64:?} -> AArch64");
+            assert_eq!(x64_to_riscv64(x64), rv, "{x64:?} -> RISC-V");
+        }
+    }
+
+    #[test]
+    fn exact_entries_round_trip() {
+        for (x64, a64, rv, a64_exact, rv_exact) in TABLE {
+            assert_eq!(
+                aarch64_to_x64(a64) == Some(x64),
+                a64_exact,
+                "{x64:?} via {a64:?}"
+            );
+            assert_eq!(
+                riscv64_to_x64(rv) == Some(x64),
+                rv_exact,
+                "{x64:?} via {rv:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn inverse_round_trips() {
+        let a64 = [
+            AArch64CC::EQ,
+            AArch64CC::NE,
+            AArch64CC::HS,
+            AArch64CC::LO,
+            AArch64CC::MI,
+            AArch64CC::PL,
+            AArch64CC::VS,
+            AArch64CC::VC,
+            AArch64CC::HI,
+            AArch64CC::LS,
+            AArch64CC::GE,
+            AArch64CC::LT,
+            AArch64CC::GT,
+            AArch64CC::LE,
+        ];
+        for cc in a64 {
+            let x64 = aarch64_to_x64(cc).unwrap();
+            assert_eq!(x64_to_aarch64(x64), cc);
+        }
+        assert_eq!(aarch64_to_x64(AArch64CC::AL), None);
+        assert_eq!(aarch64_to_x64(AArch64CC::NV), None);
+
+        let rv = [
+            RiscVCC::EQ,
+            RiscVCC::NE,
+            RiscVCC::LT,
+            RiscVCC::GE,
+            RiscVCC::LTU,
+            RiscVCC::GEU,
+            RiscVCC::GT,
+            RiscVCC::LE,
+            RiscVCC::GTU,
+            RiscVCC::LEU,
+        ];
+        for cc in rv {
+            let x64 = riscv64_to_x64(cc).unwrap();
+            assert_eq!(x64_to_riscv64(x64), cc);
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Cost estimates for translated guest instructions.
+//!
+//! [`estimate_cost`] runs the shim for the chosen target over a sink that only
+//! counts emitted instructions, so estimates always agree with what the shims
+//! actually produce, including strategy-dependent lowerings such as rotates
+//! without Zbb:
+//!
+//! ```ignore
+//! let rv = RiscV64Arch::rv64gc();
+//! let cost = estimate_cost(|w, ctx| w.rol(ctx, x64, &Reg(0), &Reg(1)), rv, &mut ());
+//! assert!(cost.insts > 1);
+//! ```
+//!
+//! Instructions are counted from the target's assembly text, one per line.
+//! Pseudo-instructions (`li`, `la`, `call`, ...) count once even when they
+//! expand to several machine instructions, so `insts` and `bytes` are lower
+//! bounds for sequences that materialise large constants or far addresses.
+//! Compressed RISC-V instructions (`c.addi`, `c.ld`, ...) count as 2 bytes.
+
+use core::fmt::{self, Display, Write};
+
+use portal_solutions_asm_aarch64::AArch64Arch;
+use portal_solutions_asm_riscv64::RiscV64Arch;
+use portal_solutions_asm_x86_64::out::Writer as X64Writer;
+
+use crate::hooks::GuestInstruction;
+use crate::x87::PrecisionLoss;
+
+/// Estimated cost of translating one guest instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Cost {
+    /// Number of target instructions emitted.
+    pub insts: usize,
+    /// Encoded size in bytes.
+    pub bytes: usize,
+    /// Whether the translation is approximate (reported through the hooks'
+    /// `precision_loss` callback).
+    pub approx: bool,
+}
+
+/// A target architecture the shims can translate into.
+pub trait CostModel: Copy {
+    /// Translates `guest` with this target's shim and measures the output.
+    fn measure<L: Display, Context>(
+        self,
+        ctx: &mut Context,
+        guest: &mut dyn FnMut(
+            &mut (dyn X64Writer<L, Context, Error = fmt::Error> + '_),
+            &mut Context,
+        ) -> fmt::Result,
+    ) -> Result<Cost, fmt::Error>;
+}
+
+/// Estimates the cost of the guest instructions `guest` emits when translated
+/// for `arch_cfg`.
+///
+/// `guest` receives an x86-64 writer and usually emits a single instruction.
+/// Guest instructions the shim does not support panic just as they would
+/// during translation.
+pub fn estimate_cost<A: CostModel, L: Display, Context>(
+    mut guest: impl FnMut(
+        &mut (dyn X64Writer<L, Context, Error = fmt::Error> + '_),
+        &mut Context,
+    ) -> fmt::Result,
+    arch_cfg: A,
+    ctx: &mut Context,
+) -> Result<Cost, fmt::Error> {
+    arch_cfg.measure(ctx, &mut guest)
+}
+
+/// Text sink that counts instruction lines, skipping label definitions.
+#[derive(Default)]
+struct LineCounter {
+    lines: usize,
+    /// Lines holding a 16-bit RISC-V compressed (`c.`) instruction.
+    compressed: usize,
+    /// First two non-blank characters of the current line.
+    head: [Option<char>; 2],
+    last: Option<char>,
+}
+
+impl LineCounter {
+    /// Encoded size, with 4 bytes per instruction and 2 per compressed one.
+    fn bytes(&self) -> usize {
+        self.lines * 4 - self.compressed * 2
+    }
+}
+
+impl Write for LineCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if c == '\n' {
+                if !matches!(self.last, None | Some(':')) {
+                    self.lines += 1;
+                    if self.head == [Some('c'), Some('.')] {
+                        self.compressed += 1;
+                    }
+                }
+                self.head = [None; 2];
+                self.last = None;
+            } else if !c.is_whitespace() {
+                if self.last.is_none() {
+                    self.head[0] = Some(c);
+                } else if self.head[1].is_none() {
+                    self.head[1] = Some(c);
+                }
+                self.last = Some(c);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Hook that records whether any instruction was translated approximately.
+#[derive(Default)]
+struct LossFlag(bool);
+
+impl<W, Context> crate::aarch64::ShimHooks<W, Context> for LossFlag
+where
+    W: portal_solutions_asm_aarch64::out::Writer<crate::aarch64::ShimLabel, Context>,
+{
+    fn precision_loss(&mut self, _insn: GuestInstruction, _loss: PrecisionLoss) {
+        self.0 = true;
+    }
+}
+
+impl<W, Context> crate::riscv64::ShimHooks<W, Context> for LossFlag
+where
+    W: portal_solutions_asm_riscv64::out::Writer<crate::riscv64::ShimLabel, Context>,
+{
+    fn precision_loss(&mut self, _insn: GuestInstruction, _loss: PrecisionLoss) {
+        self.0 = true;
+    }
+}
+
+impl CostModel for AArch64Arch {
+    fn measure<L: Display, Context>(
+        self,
+        ctx: &mut Context,
+        guest: &mut dyn FnMut(
+            &mut (dyn X64Writer<L, Context, Error = fmt::Error> + '_),
+            &mut Context,
+        ) -> fmt::Result,
+    ) -> Result<Cost, fmt::Error> {
+        let mut counter = LineCounter::default();
+        let sink: &mut dyn Write = &mut counter;
+        let mut shim = crate::aarch64::X64ToAArch64Shim::with_config(sink, self)
+            .with_hooks(LossFlag::default());
+        guest(&mut shim, ctx)?;
+        let approx = shim.hooks.0;
+        Ok(Cost {
+            insts: counter.lines,
+            bytes: counter.lines * 4,
+            approx,
+        })
+    }
+}
+
+impl CostModel for RiscV64Arch {
+    fn measure<L: Display, Context>(
+        self,
+        ctx: &mut Context,
+        guest: &mut dyn FnMut(
+            &mut (dyn X64Writer<L, Context, Error = fmt::Error> + '_),
+            &mut Context,
+        ) -> fmt::Result,
+    ) -> Result<Cost, fmt::Error> {
+        let mut counter = LineCounter::default();
+        let sink: &mut dyn Write = &mut counter;
+        let mut shim = crate::riscv64::X64ToRiscV64Shim::with_config(sink, self)
+            .with_hooks(LossFlag::default());
+        guest(&mut shim, ctx)?;
+        let approx = shim.hooks.0;
+        Ok(Cost {
+            insts: counter.lines,
+            bytes: counter.bytes(),
+            approx,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+    use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind, Segment};
+    use portal_solutions_asm_x86_64::{RegisterClass, X64Arch};
+
+    type Guest<'a> = dyn X64Writer<usize, (), Error = fmt::Error> + 'a;
+
+    #[test]
+    fn rotate_cost_follows_strategy() {
+        let x64 = X64Arch::default();
+        let rol = |w: &mut Guest<'_>, ctx: &mut ()| w.rol(ctx, x64, &Reg(0), &Reg(1));
+        let mut zbb = RiscV64Arch::rv64gc();
+        zbb.zbb = true;
+
+        let native = estimate_cost(rol, zbb, &mut ()).unwrap();
+        let emulated = estimate_cost(rol, RiscV64Arch::rv64gc(), &mut ()).unwrap();
+        assert_eq!(native.insts, 1);
+        assert!(emulated.insts > native.insts);
+        assert_eq!(emulated.bytes, emulated.insts * 4);
+        assert!(!emulated.approx);
+    }
+
+    #[test]
+    fn x87_is_approximate() {
+        let mut x64 = X64Arch::default();
+        x64.x87 = true;
+        let mem = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(0),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_64,
+            reg_class: RegisterClass::Gpr,
+            segment: Segment::None,
+        };
+        let sum = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.fld(ctx, x64, &mem)?;
+            w.fld(ctx, x64, &mem)?;
+            w.faddp(ctx, x64)?;
+            w.fstp(ctx, x64, &mem)
+        };
+        let cost = estimate_cost(sum, AArch64Arch::default(), &mut ()).unwrap();
+        assert!(cost.approx);
+        assert!(cost.insts >= 4);
+    }
+}
@@ -0,0 +1,23 @@
+//! Guest fault emulation options shared by the translation shims.
+//!
+//! x86-64 raises a divide error (#DE) when `DIV`/`IDIV` get a zero divisor or
+//! when `IDIV` computes `INT_MIN / -1`. AArch64 and RISC-V instead return a
+//! defined result without trapping, so translated code silently diverges
+//! unless the shim inserts guard checks.
+
+/// How a shim translates integer division inputs that fault on x86-64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DivFaultMode<L> {
+    /// Emit the native divide and accept its result; no guard code.
+    #[default]
+    Native,
+    /// Guard each divide and execute a breakpoint (`brk #0` / `ebreak`) on a
+    /// faulting input.
+    Trap,
+    /// Guard each divide and branch to this label on a faulting input, e.g. a
+    /// handler that raises the guest's divide error.
+    ///
+    /// The shims take a `ShimLabel::user` label here, kept apart from the
+    /// labels they generate for the guards themselves.
+    Branch(L),
+}
@@ -0,0 +1,143 @@
+//! x86-64 FLAGS materialization shared by the translation shims.
+//!
+//! Translated code never keeps an x86 FLAGS register: AArch64 shims test
+//! NZCV directly, and RISC-V shims keep the result of the last `cmp`/`test`
+//! in t6 and compare it against zero at each branch. Instructions that read
+//! or write FLAGS as a value therefore rebuild the x86 layout from that
+//! state, and turn a restored word back into it. The RISC-V shim does so
+//! for `pushf`, `popf`, `lahf` and `sahf`; the AArch64 shim converts for
+//! `lahf`/`sahf` and pushes NZCV unchanged, which [`nzcv_to_rflags`]
+//! decodes.
+//!
+//! Only the arithmetic flags the shims model are reconstructed: CF, ZF, SF
+//! and OF. Bit 1, which always reads as set on x86-64, is included; AF and
+//! PF are never computed. Other bits of a word written by `popf` (DF, IF,
+//! ...) have no effect.
+//!
+//! On RISC-V, CF and OF cannot be recovered from the comparison result
+//! alone. [`FlagTracking::Full`] records them in s1 at every `cmp`, at the
+//! cost of seven or eight extra instructions, and the carry of every `add`,
+//! `sub` and `neg` so that `adc` and `sbb` can be translated; OF reads as
+//! clear after those and after `inc`/`dec`, which keep CF. The default reports CF and OF as clear and rejects `adc`
+//! and `sbb`.
+
+/// Carry flag.
+pub const CF: u64 = 1 << 0;
+/// Reserved bit 1, which always reads as set.
+pub const RESERVED: u64 = 1 << 1;
+/// Parity flag.
+pub const PF: u64 = 1 << 2;
+/// Auxiliary carry flag.
+pub const AF: u64 = 1 << 4;
+/// Zero flag.
+pub const ZF: u64 = 1 << 6;
+/// Sign flag.
+pub const SF: u64 = 1 << 7;
+/// Overflow flag.
+pub const OF: u64 = 1 << 11;
+
+/// The flags `sahf` writes from AH.
+pub const SAHF_MASK: u64 = SF | ZF | AF | PF | CF;
+
+/// AArch64 NZCV bit positions.
+const NZCV_N: u64 = 1 << 31;
+const NZCV_Z: u64 = 1 << 30;
+const NZCV_C: u64 = 1 << 29;
+const NZCV_V: u64 = 1 << 28;
+
+/// How much comparison state a RISC-V shim keeps for FLAGS materialization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FlagTracking {
+    /// Derive ZF and SF from the comparison result; CF and OF read as clear.
+    /// `cmp` and `test` cost nothing extra.
+    #[default]
+    ZeroSign,
+    /// Also record CF and OF in s1 at every `cmp`, and CF at every `add`,
+    /// `sub`, `adc` and `sbb`, so `pushf` and `lahf` see all four flags and
+    /// carry chains can be translated.
+    Full,
+}
+
+/// Converts an AArch64 NZCV value, as left by the shims' `cmp`, to x86-64
+/// FLAGS.
+///
+/// AArch64 sets C when a subtraction does *not* borrow, so CF is its
+/// inverse.
+pub fn nzcv_to_rflags(nzcv: u64) -> u64 {
+    let mut flags = RESERVED;
+    if nzcv & NZCV_N != 0 {
+        flags |= SF;
+    }
+    if nzcv & NZCV_Z != 0 {
+        flags |= ZF;
+    }
+    if nzcv & NZCV_C == 0 {
+        flags |= CF;
+    }
+    if nzcv & NZCV_V != 0 {
+        flags |= OF;
+    }
+    flags
+}
+
+/// Converts x86-64 FLAGS to the AArch64 NZCV value the shims' conditions
+/// expect. The inverse of [`nzcv_to_rflags`] on SF, ZF, CF and OF.
+pub fn rflags_to_nzcv(flags: u64) -> u64 {
+    let mut nzcv = 0;
+    if flags & SF != 0 {
+        nzcv |= NZCV_N;
+    }
+    if flags & ZF != 0 {
+        nzcv |= NZCV_Z;
+    }
+    if flags & CF == 0 {
+        nzcv |= NZCV_C;
+    }
+    if flags & OF != 0 {
+        nzcv |= NZCV_V;
+    }
+    nzcv
+}
+
+/// The comparison result a RISC-V shim leaves in t6 so that later branches
+/// see the ZF and SF of `flags`: zero when ZF is set, otherwise -1 when SF
+/// is set and 1 when it is clear.
+pub fn comparison_record(flags: u64) -> i64 {
+    if flags & ZF != 0 {
+        0
+    } else if flags & SF != 0 {
+        -1
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nzcv_round_trips_the_tracked_flags() {
+        for bits in 0..16u64 {
+            let flags = [CF, ZF, SF, OF]
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| bits & (1 << i) != 0)
+                .fold(RESERVED, |acc, (_, f)| acc | f);
+            assert_eq!(nzcv_to_rflags(rflags_to_nzcv(flags)), flags);
+        }
+    }
+
+    #[test]
+    fn cmp_equal_operands_sets_zf_only() {
+        // cmp x, x on AArch64 leaves Z and C set.
+        assert_eq!(nzcv_to_rflags(NZCV_Z | NZCV_C), RESERVED | ZF);
+    }
+
+    #[test]
+    fn comparison_record_preserves_zf_and_sf() {
+        assert_eq!(comparison_record(ZF | SF), 0);
+        assert_eq!(comparison_record(SF | CF), -1);
+        assert_eq!(comparison_record(OF), 1);
+    }
+}
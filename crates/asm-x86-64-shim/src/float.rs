@@ -0,0 +1,47 @@
+//! Floating-point semantics options shared by the translation shims.
+//!
+//! Scalar SSE instructions and their AArch64 and RISC-V counterparts agree
+//! on ordinary values but not on NaN, signed zero and out-of-range inputs:
+//!
+//! | x86-64 | AArch64 | RISC-V |
+//! |--------|---------|--------|
+//! | `ucomisd` unordered sets ZF, PF and CF | `fcmp` sets C and V, which reads as "above" | `flt`/`fle` give 0, so the shim's record reads as "equal" |
+//! | `minsd`/`maxsd` return the second operand if either is NaN, or if both are zero | `fmin`/`fmax` return NaN | `fmin.d`/`fmax.d` return the non-NaN operand, and order -0.0 below +0.0 |
+//! | `cvttsd2si` returns the minimum integer for NaN and out-of-range values | `fcvtzs` saturates, NaN gives 0 | `fcvt.l.d` saturates, NaN gives the maximum integer |
+//!
+//! [`NanPolicy`] selects whether a shim pays for the x86-64 results in the
+//! first two rows. Conversions are always translated to the host's
+//! saturating forms. The single-precision `ucomiss`, `minss`, `maxss` and
+//! `cvttss2si` follow their double-precision rows.
+//!
+//! The RISC-V shim's comparison record in t6 is -1, 0 or 1 and already
+//! matches `ucomisd` on ZF, so the policy only changes its `minsd`/`maxsd`.
+//! Branches read the record as they do after an integer `cmp`; the exact CF
+//! is only available to `lahf`/`pushf`, under
+//! [`FlagTracking::Full`](crate::flags::FlagTracking::Full). Neither target
+//! has a parity flag, so `jp` after an unordered compare is not translated
+//! under either policy.
+//!
+//! `ldmxcsr` carries the MXCSR rounding control into the target's dynamic
+//! rounding mode, `frm` on RISC-V and FPCR.RMode on AArch64, so later
+//! arithmetic and conversions round as the program asked. Exception flags
+//! and masks are not modelled: `stmxcsr` stores the current rounding
+//! control with every exception masked (`0x1F80`) and no flags raised.
+//! `cvttsd2si` and `cvttss2si` truncate regardless of the mode, as
+//! `fcvtzs` does on AArch64 and a static `rtz` does on RISC-V.
+
+/// How a shim translates floating-point instructions whose NaN handling
+/// differs between x86-64 and the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NanPolicy {
+    /// Use the target's instruction directly. Ordered inputs get the x86-64
+    /// result; NaNs and, for `minsd`/`maxsd`, zeros of opposite sign follow
+    /// the target.
+    #[default]
+    Host,
+    /// Reproduce the x86-64 result for unordered `ucomisd` and for
+    /// `minsd`/`maxsd`: AArch64 rewrites NZCV after an unordered `fcmp` and
+    /// selects with `fcmp` + `fcsel`, and RISC-V branches on `flt.d` around
+    /// an `fmv.d`. Costs three to five extra instructions each.
+    X86,
+}
@@ -0,0 +1,528 @@
+//! Randomized checking of the shims.
+//!
+//! [`fuzz`] generates random sequences of guest `mov`/`add`/`sub`/`and`/`or`
+//! instructions over registers, small immediates and memory operands with
+//! random bases, scaled indices and displacements. It translates each sequence
+//! with the AArch64 shim and with the RISC-V shim over a
+//! [`DesugaringWriter`], and checks every run for:
+//!
+//! - panics, including `todo!` paths reached by the generated operands;
+//! - guest registers that map onto a register the shim reserves for itself,
+//!   which the shim's own scratch sequences would silently clobber;
+//! - memory operands the target cannot encode: AArch64 offsets outside the
+//!   `LDR`/`LDUR`/`LDP` immediate ranges or with a shift that does not match
+//!   the access size, and RISC-V offsets outside 12 bits or scaled indices
+//!   left for the assembler.
+//!
+//! ```ignore
+//! if let Err((seed, failure)) = fuzz(0..1000, 16, X64Arch::default()) {
+//!     panic!("seed {seed}: {failure}\n{:?}", program(seed, 16));
+//! }
+//! ```
+//!
+//! Generation is deterministic per seed, so [`program`] rebuilds a failing
+//! sequence for a regression test. The operand space stays within what x86-64
+//! can encode and skips `rsp`, immediates into memory and APX registers. With
+//! APX enabled the register check does flag some mappings: `r24` and up share
+//! `x28` on AArch64, and the RISC-V mapping wraps onto `t5` and `t6`.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter, Write};
+use std::panic::{self, AssertUnwindSafe};
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+use portal_solutions_asm_aarch64::{AArch64Arch, TargetOs};
+use portal_solutions_asm_riscv64::RiscV64Arch;
+use portal_solutions_asm_riscv64::desugar::DesugaringWriter;
+use portal_solutions_asm_x86_64::out::Writer as X64Writer;
+use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind, Segment};
+use portal_solutions_asm_x86_64::{RegisterClass, X64Arch};
+
+use crate::testing::{Entry, Trace};
+
+/// Returns the AArch64 registers the shim keeps for itself on `os`: `x15`
+/// (thread base), `x16` and `x17` (scratch) and the
+/// [`address_temp`](crate::aarch64::address_temp).
+pub fn aarch64_reserved(os: TargetOs) -> [Reg; 4] {
+    [Reg(15), Reg(16), Reg(17), crate::aarch64::address_temp(os)]
+}
+
+/// RISC-V registers the shim keeps for itself: `t5` and `t6` (scratch) and
+/// `s1` (flags).
+pub const RISCV64_RESERVED: [Reg; 3] = [Reg(30), Reg(31), Reg(9)];
+
+/// A small deterministic generator (xorshift64*).
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a generator; every seed, including 0, gives a distinct stream.
+    pub fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a value in `0..n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// Returns a guest GPR other than `rsp`.
+    fn gpr(&mut self) -> Reg {
+        match self.below(15) as u8 {
+            r if r >= 4 => Reg(r + 1),
+            r => Reg(r),
+        }
+    }
+}
+
+/// A generated guest operand. All operands are 64 bits wide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    /// A general-purpose register.
+    Reg(Reg),
+    /// An immediate.
+    Imm(u64),
+    /// `[base + index*scale + disp]`.
+    Mem {
+        /// The base register.
+        base: Reg,
+        /// The index register and its scale factor (1, 2, 4 or 8).
+        index: Option<(Reg, u32)>,
+        /// The displacement.
+        disp: i32,
+    },
+}
+
+impl Operand {
+    /// Generates a memory operand.
+    fn random_mem(rng: &mut Rng) -> Self {
+        let index = match rng.below(2) {
+            0 => None,
+            _ => Some((rng.gpr(), 1 << rng.below(4))),
+        };
+        // Offsets both targets reach directly, plus larger aligned ones that
+        // only AArch64's scaled form covers.
+        let disp = match rng.below(3) {
+            0 => 0,
+            1 => rng.below(512) as i32 - 256,
+            _ => rng.below(1024) as i32 * 8,
+        };
+        Operand::Mem {
+            base: rng.gpr(),
+            index,
+            disp,
+        }
+    }
+
+    /// The registers this operand names.
+    pub fn regs(&self) -> impl Iterator<Item = Reg> {
+        let (a, b) = match *self {
+            Operand::Reg(reg) => (Some(reg), None),
+            Operand::Imm(_) => (None, None),
+            Operand::Mem { base, index, .. } => (Some(base), index.map(|(reg, _)| reg)),
+        };
+        a.into_iter().chain(b)
+    }
+
+    /// The x86-64 operand.
+    pub fn kind(&self) -> MemArgKind<ArgKind> {
+        let reg = |reg| ArgKind::Reg {
+            reg,
+            size: MemorySize::_64,
+        };
+        match *self {
+            Operand::Reg(r) => MemArgKind::NoMem(reg(r)),
+            Operand::Imm(value) => MemArgKind::NoMem(ArgKind::Lit(value)),
+            Operand::Mem { base, index, disp } => MemArgKind::Mem {
+                base: reg(base),
+                offset: index.map(|(index, scale)| (reg(index), scale)),
+                disp,
+                size: MemorySize::_64,
+                reg_class: RegisterClass::Gpr,
+                segment: Segment::None,
+            },
+        }
+    }
+}
+
+/// A generated guest operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// `mov dest, src`.
+    Mov,
+    /// `add dest, src`.
+    Add,
+    /// `sub dest, src`.
+    Sub,
+    /// `and dest, src`.
+    And,
+    /// `or dest, src`.
+    Or,
+}
+
+/// A generated guest instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuestInsn {
+    /// The operation.
+    pub op: Op,
+    /// The destination, which is also the first source for ALU operations.
+    pub dest: Operand,
+    /// The source.
+    pub src: Operand,
+}
+
+impl GuestInsn {
+    /// Generates an instruction. At most one operand is in memory, and
+    /// immediates only go to registers.
+    pub fn random(rng: &mut Rng) -> Self {
+        let op = [Op::Mov, Op::Add, Op::Sub, Op::And, Op::Or][rng.below(5) as usize];
+        let (dest, src) = match rng.below(4) {
+            0 => (Operand::Reg(rng.gpr()), Operand::Reg(rng.gpr())),
+            1 => (Operand::Reg(rng.gpr()), Operand::Imm(rng.below(2048))),
+            2 => (Operand::Reg(rng.gpr()), Operand::random_mem(rng)),
+            _ => (Operand::random_mem(rng), Operand::Reg(rng.gpr())),
+        };
+        GuestInsn { op, dest, src }
+    }
+
+    /// Emits the instruction to `w`.
+    pub fn emit<L, Context>(
+        &self,
+        w: &mut (dyn X64Writer<L, Context, Error = fmt::Error> + '_),
+        ctx: &mut Context,
+        x64: X64Arch,
+    ) -> fmt::Result {
+        let (dest, src) = (&self.dest.kind(), &self.src.kind());
+        match self.op {
+            Op::Mov => w.mov(ctx, x64, dest, src),
+            Op::Add => w.add(ctx, x64, dest, src),
+            Op::Sub => w.sub(ctx, x64, dest, src),
+            Op::And => w.and(ctx, x64, dest, src),
+            Op::Or => w.or(ctx, x64, dest, src),
+        }
+    }
+}
+
+/// Generates the `len`-instruction program for `seed`.
+pub fn program(seed: u64, len: usize) -> Vec<GuestInsn> {
+    let mut rng = Rng::new(seed);
+    (0..len).map(|_| GuestInsn::random(&mut rng)).collect()
+}
+
+/// A broken invariant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// Translation panicked.
+    Panic {
+        /// The target that was being translated for.
+        target: &'static str,
+        /// The panic message, if it was a string.
+        message: String,
+    },
+    /// A guest register maps onto a register the shim reserves.
+    ReservedReg {
+        /// The target whose mapping collides.
+        target: &'static str,
+        /// The guest register.
+        guest: Reg,
+        /// The host register it maps to.
+        host: Reg,
+    },
+    /// An emitted memory operand is not encodable.
+    Address {
+        /// The target the operand was emitted for.
+        target: &'static str,
+        /// The offending instruction.
+        entry: Entry,
+    },
+    /// The writer returned an error.
+    Error {
+        /// The target that was being translated for.
+        target: &'static str,
+    },
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Panic { target, message } => write!(f, "{target}: panicked: {message}"),
+            Failure::ReservedReg {
+                target,
+                guest,
+                host,
+            } => write!(
+                f,
+                "{target}: guest register {} maps to reserved register {}",
+                guest.0, host.0
+            ),
+            Failure::Address { target, entry } => {
+                write!(f, "{target}: unencodable address in `{entry}`")
+            }
+            Failure::Error { target } => write!(f, "{target}: the writer returned an error"),
+        }
+    }
+}
+
+/// Translates `program` for both targets and checks every invariant.
+pub fn check(program: &[GuestInsn], x64: X64Arch) -> Result<(), Failure> {
+    let aarch64 = AArch64Arch::default();
+    let reserved = aarch64_reserved(aarch64.os);
+    for insn in program {
+        for guest in insn.dest.regs().chain(insn.src.regs()) {
+            let host = crate::aarch64::map_x64_register_to_aarch64(guest, x64);
+            if reserved.contains(&host) {
+                return Err(Failure::ReservedReg {
+                    target: "aarch64",
+                    guest,
+                    host,
+                });
+            }
+            let host = crate::riscv64::map_x64_register_to_riscv(guest, x64);
+            if RISCV64_RESERVED.contains(&host) {
+                return Err(Failure::ReservedReg {
+                    target: "riscv64",
+                    guest,
+                    host,
+                });
+            }
+        }
+    }
+
+    let emit = |w: &mut (dyn X64Writer<usize, (), Error = fmt::Error> + '_)| {
+        program
+            .iter()
+            .try_for_each(|insn| insn.emit(w, &mut (), x64))
+    };
+
+    let trace = run("aarch64", || {
+        let mut trace = Trace::new();
+        let sink: &mut dyn Write = &mut trace;
+        let mut shim = crate::aarch64::X64ToAArch64Shim::with_config(sink, aarch64);
+        emit(&mut shim)?;
+        Ok(trace)
+    })?;
+    if let Some(entry) = trace.entries().iter().find(|e| !aarch64_encodable(e)) {
+        return Err(Failure::Address {
+            target: "aarch64",
+            entry: entry.clone(),
+        });
+    }
+
+    let trace = run("riscv64", || {
+        let mut trace = Trace::new();
+        let sink: &mut dyn Write = &mut trace;
+        let desugar = DesugaringWriter::new(sink);
+        let mut shim =
+            crate::riscv64::X64ToRiscV64Shim::with_config(desugar, RiscV64Arch::default());
+        emit(&mut shim)?;
+        Ok(trace)
+    })?;
+    if let Some(entry) = trace.entries().iter().find(|e| !riscv64_encodable(e)) {
+        return Err(Failure::Address {
+            target: "riscv64",
+            entry: entry.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Runs `f`, turning a panic or an error into a [`Failure`].
+fn run(
+    target: &'static str,
+    f: impl FnOnce() -> Result<Trace, fmt::Error>,
+) -> Result<Trace, Failure> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(trace)) => Ok(trace),
+        Ok(Err(fmt::Error)) => Err(Failure::Error { target }),
+        Err(payload) => {
+            let message = match payload.downcast_ref::<&str>() {
+                Some(s) => String::from(*s),
+                None => payload
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .unwrap_or_default(),
+            };
+            Err(Failure::Panic { target, message })
+        }
+    }
+}
+
+/// Checks `len`-instruction programs for each of `seeds`, stopping at the
+/// first failure.
+pub fn fuzz(
+    seeds: impl IntoIterator<Item = u64>,
+    len: usize,
+    x64: X64Arch,
+) -> Result<(), (u64, Failure)> {
+    for seed in seeds {
+        check(&program(seed, len), x64).map_err(|failure| (seed, failure))?;
+    }
+    Ok(())
+}
+
+/// Parses an assembler immediate (`#-8`, `0x10`, `16`).
+fn parse_imm(text: &str) -> Option<i64> {
+    let text = text.trim().trim_start_matches('#');
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse().ok()?,
+    };
+    Some(if negative { -value } else { value })
+}
+
+/// The access size in bytes of an AArch64 load or store, from its mnemonic
+/// and transfer register.
+fn aarch64_access_size(mnemonic: &str, reg: &str) -> i64 {
+    match mnemonic {
+        m if m.ends_with("sw") => 4,
+        m if m.ends_with('b') => 1,
+        m if m.ends_with('h') => 2,
+        _ => match reg.as_bytes().first() {
+            Some(b'w' | b's') => 4,
+            Some(b'q') => 16,
+            Some(b'h') => 2,
+            Some(b'b') => 1,
+            _ => 8,
+        },
+    }
+}
+
+/// Returns `false` if `entry` has an AArch64 memory operand no load or store
+/// encoding can hold.
+fn aarch64_encodable(entry: &Entry) -> bool {
+    let Entry::Insn { mnemonic, operands } = entry else {
+        return true;
+    };
+    let Some(at) = operands.iter().position(|op| op.starts_with('[')) else {
+        return true;
+    };
+    let size = aarch64_access_size(mnemonic, operands.first().map_or("", |op| op.as_str()));
+    let pair = mnemonic.starts_with("ldp") || mnemonic.starts_with("stp");
+    let mem = &operands[at];
+    let pre = mem.ends_with('!');
+    let inner = mem
+        .trim_end_matches('!')
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    let parts: Vec<&str> = inner.split(',').map(str::trim).collect();
+    // A post-index offset follows the bracket as its own operand.
+    let post = operands.get(at + 1).map(String::as_str);
+    let offset = match (parts.get(1).copied(), post) {
+        (None, None) => return true,
+        (Some(index), None) if !index.starts_with('#') => {
+            // Register offset: the shift must be 0 or match the access size.
+            return !pair
+                && parts
+                    .get(2)
+                    .and_then(|s| s.strip_prefix("lsl"))
+                    .is_none_or(|shift| {
+                        parse_imm(shift).is_some_and(|s| s == 0 || 1 << s == size)
+                    });
+        }
+        (Some(imm), None) | (None, Some(imm)) => parse_imm(imm),
+        (Some(_), Some(_)) => return false,
+    };
+    let Some(offset) = offset else {
+        return false;
+    };
+    if pair {
+        offset % size == 0 && (-64..64).contains(&(offset / size))
+    } else if pre || post.is_some() {
+        (-256..256).contains(&offset)
+    } else {
+        (-256..256).contains(&offset) || (offset >= 0 && offset % size == 0 && offset / size < 4096)
+    }
+}
+
+/// Returns `false` if `entry` has a RISC-V memory operand outside the 12-bit
+/// offset range, or one still carrying a scaled index.
+fn riscv64_encodable(entry: &Entry) -> bool {
+    let Entry::Insn { operands, .. } = entry else {
+        return true;
+    };
+    operands.iter().all(|op| match op.split_once('(') {
+        // Relocation operators such as `%lo(sym)(a0)` are resolved by the assembler.
+        _ if op.starts_with('%') => true,
+        Some((disp, base)) if base.ends_with(')') => {
+            !base.contains('+')
+                && (disp.is_empty() || parse_imm(disp).is_some_and(|d| (-2048..2048).contains(&d)))
+        }
+        _ => true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insn(text: &str) -> Entry {
+        Entry::parse(text).unwrap()
+    }
+
+    #[test]
+    fn generation_is_deterministic() {
+        assert_eq!(program(7, 32), program(7, 32));
+        assert_ne!(program(7, 32), program(8, 32));
+        let rsp = Reg(4);
+        assert!(
+            program(3, 256)
+                .iter()
+                .all(|i| i.dest.regs().chain(i.src.regs()).all(|r| r != rsp))
+        );
+    }
+
+    #[test]
+    fn address_checks() {
+        assert!(aarch64_encodable(&insn("ldr x0, [x1, #-256]")));
+        assert!(aarch64_encodable(&insn("ldr x0, [x1, #32760]")));
+        assert!(!aarch64_encodable(&insn("ldr x0, [x1, #32768]")));
+        assert!(!aarch64_encodable(&insn("ldr x0, [x1, #-264]")));
+        assert!(!aarch64_encodable(&insn("str w0, [x1, #258]")));
+        assert!(aarch64_encodable(&insn("ldr x0, [x1, x2, lsl #3]")));
+        assert!(!aarch64_encodable(&insn("ldr x0, [x1, x2, lsl #2]")));
+        assert!(!aarch64_encodable(&insn("stp x0, x1, [sp, #512]")));
+        assert!(riscv64_encodable(&insn("ld a0, -2048(a1)")));
+        assert!(!riscv64_encodable(&insn("ld a0, 2048(a1)")));
+        assert!(!riscv64_encodable(&insn("ld a0, 0(a1+a2<<3)")));
+    }
+
+    #[test]
+    fn apx_mappings_are_reported() {
+        let mut x64 = X64Arch::default();
+        x64.apx = true;
+        let program = [GuestInsn {
+            op: Op::Mov,
+            dest: Operand::Reg(Reg(26)),
+            src: Operand::Reg(Reg(0)),
+        }];
+        assert!(matches!(
+            check(&program, x64),
+            Err(Failure::ReservedReg {
+                target: "riscv64",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn random_programs_translate() {
+        if let Err((seed, failure)) = fuzz(0..64, 16, X64Arch::default()) {
+            panic!("seed {seed}: {failure}\n{:#?}", program(seed, 16));
+        }
+    }
+}
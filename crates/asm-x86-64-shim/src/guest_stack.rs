@@ -0,0 +1,102 @@
+//! Guest stack placement options shared by the translation shims.
+//!
+//! By default the shims lower `push`, `pop`, `call` and `ret` against the
+//! target's own stack pointer. Some environments keep the guest stack apart
+//! from the host's, in a dedicated register, or bias the register away from
+//! the real top of stack (SPARC V9's 2047-byte bias, or a fixed offset that
+//! reserves a scratch area below the guest's data). [`GuestStack`] describes
+//! both, and every stack-adjusting lowering in a shim goes through it.
+//!
+//! [`ReturnStack`] moves the return addresses of `call` and `ret` off the
+//! guest stack onto a shadow stack of their own, and returns through the
+//! link register as native code does. Guest code can then call native-ABI
+//! functions and be called by them; code that reads its return address from
+//! `[rsp]` no longer finds it there.
+
+use portal_pc_asm_common::types::reg::Reg;
+
+/// Where a shim keeps the guest stack pointer.
+///
+/// The top of the guest stack is at `sp + bias`; pushes decrement `sp` by 8
+/// and store to `[sp + bias]`, pops load from there and increment `sp`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GuestStack {
+    /// Target register holding the guest stack pointer, or `None` for the
+    /// target's own stack pointer.
+    pub sp: Option<Reg>,
+    /// Byte offset from `sp` to the top of the guest stack.
+    pub bias: i32,
+}
+
+impl GuestStack {
+    /// Keeps the guest stack pointer in `sp` instead of the target's.
+    pub fn in_register(sp: Reg) -> Self {
+        Self {
+            sp: Some(sp),
+            bias: 0,
+        }
+    }
+
+    /// Sets the byte offset from the register to the top of the stack.
+    pub fn with_bias(self, bias: i32) -> Self {
+        Self { bias, ..self }
+    }
+
+    /// Returns the guest stack register, given the target's stack pointer.
+    pub fn register(&self, native_sp: Reg) -> Reg {
+        self.sp.unwrap_or(native_sp)
+    }
+}
+
+/// Where a shim keeps the return addresses of guest calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ReturnStack {
+    /// Where the shim keeps them without a shadow stack: pushed onto the
+    /// guest stack by the AArch64 shim and left in `ra` by the RISC-V shim.
+    #[default]
+    Standard,
+    /// On a shadow stack whose pointer is held in the register.
+    ///
+    /// The running function's return address stays in the link register,
+    /// where `ret` returns through it. Each `call` pushes the caller's own
+    /// return address with a pre-decrement of 8 bytes before branching with
+    /// link, and pops it back on return. The register must point at the top
+    /// of a stack the embedder has allocated, and translated code must not
+    /// otherwise use it; the shims reject registers they map guest registers
+    /// to.
+    ///
+    /// With no return address pushed above them, stack-passed arguments
+    /// move from `[rsp+8]` to `[rsp]` on entry to the callee, so guest code
+    /// that reads them must be adjusted.
+    Shadow(Reg),
+}
+
+impl ReturnStack {
+    /// Returns the shadow stack register, if there is one.
+    pub fn shadow(&self) -> Option<Reg> {
+        match self {
+            ReturnStack::Standard => None,
+            ReturnStack::Shadow(reg) => Some(*reg),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_to_native_stack_pointer() {
+        let native = Reg(31);
+        assert_eq!(GuestStack::default().register(native), native);
+        let guest = GuestStack::in_register(Reg(19)).with_bias(128);
+        assert_eq!(guest.register(native), Reg(19));
+        assert_eq!(guest.bias, 128);
+    }
+
+    #[test]
+    fn return_stack_defaults_to_standard() {
+        assert_eq!(ReturnStack::default().shadow(), None);
+        assert_eq!(ReturnStack::Shadow(Reg(27)).shadow(), Some(Reg(27)));
+    }
+}
@@ -0,0 +1,87 @@
+//! Per-instruction hooks shared by the translation shims.
+//!
+//! Each shim calls its `ShimHooks` implementation immediately before and after
+//! translating a guest (x86-64) instruction, passing the target writer so the
+//! hook can emit extra code (counters, tracing calls, ...) around it.
+//!
+//! Each shim module also provides an `InstructionCounter` hook that increments
+//! a counter per guest instruction or per basic block.
+
+use portal_pc_asm_common::types::reg::Reg;
+
+/// Describes the guest instruction currently being translated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GuestInstruction {
+    /// Lower-case x86-64 mnemonic, matching the `WriterCore` method name (e.g. `"mov"`).
+    pub mnemonic: &'static str,
+    /// Number of guest instructions translated before this one.
+    pub index: usize,
+    /// Bytes of target code the translation emitted, not counting code
+    /// emitted by the hooks themselves.
+    ///
+    /// Only `post_instruction` sees a value, and only when the target
+    /// writer reports its [`current_offset`]; it is `None` otherwise.
+    ///
+    /// [`current_offset`]: portal_solutions_asm_aarch64::out::WriterCore::current_offset
+    pub emitted_bytes: Option<usize>,
+}
+
+/// Pair of closures usable as shim hooks.
+///
+/// `pre` runs before the translated instruction is emitted, `post` after it.
+pub struct FnHooks<Pre, Post> {
+    /// Called before each guest instruction.
+    pub pre: Pre,
+    /// Called after each guest instruction.
+    pub post: Post,
+}
+
+impl<Pre, Post> FnHooks<Pre, Post> {
+    /// Creates hooks from a pre- and post-instruction closure.
+    pub fn new(pre: Pre, post: Post) -> Self {
+        Self { pre, post }
+    }
+}
+
+/// Where an instruction counter lives on the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CounterLocation {
+    /// A target register reserved for the counter. It must not be one of the
+    /// registers guest registers are mapped to.
+    Register(Reg),
+    /// A 64-bit memory slot at `[base + disp]`, with `base` a target register.
+    Memory { base: Reg, disp: i32 },
+}
+
+/// How often an instruction counter is incremented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CountGranularity {
+    /// Once per translated guest instruction.
+    #[default]
+    Instruction,
+    /// Once per guest basic block: at the first instruction, after every
+    /// control transfer (see [`ends_basic_block`]) and after every label.
+    BasicBlock,
+}
+
+/// Returns whether the guest instruction with this mnemonic ends a basic block.
+///
+/// Every jump does, conditional or not and whatever its target operand.
+pub fn ends_basic_block(mnemonic: &str) -> bool {
+    mnemonic.starts_with('j') || matches!(mnemonic, "call" | "call_label" | "ret" | "hlt" | "ud2")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jumps_of_every_form_end_blocks() {
+        for mnemonic in ["jmp", "jmp_label", "jcc", "jcc_label", "call", "ret", "ud2"] {
+            assert!(ends_basic_block(mnemonic), "{mnemonic}");
+        }
+        for mnemonic in ["mov", "cmp", "setcc", "cmovcc", "int3"] {
+            assert!(!ends_basic_block(mnemonic), "{mnemonic}");
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Passing guest call arguments to host functions.
+//!
+//! The shims map guest registers to fixed target registers, chosen for
+//! translation rather than to line up with the target's calling
+//! convention. Guest code that calls a native function therefore has its
+//! arguments in the wrong places: RCX, RDX, R8 and R9 for
+//! [`CallConv::Win64`] source, RDI, RSI, RDX, RCX, R8 and R9 for
+//! [`CallConv::SysV`]. The shims' `host_call_args` moves them into the
+//! target's argument registers, following the convention in
+//! [`X64Arch::call_conv`](portal_solutions_asm_x86_64::X64Arch::call_conv).
+//! RAX maps to the target's return register on both targets, so results
+//! need no move back.
+//!
+//! The moves form a permutation that may contain cycles; [`parallel_move`]
+//! orders them and breaks cycles through a scratch register.
+
+use portal_pc_asm_common::types::reg::Reg;
+pub use portal_solutions_asm_x86_64::CallConv;
+
+/// Performs the register moves `(dest, src)` as if simultaneously, calling
+/// `mov(dest, src)` for each step.
+///
+/// Moves whose source and destination are equal are skipped. Cycles are
+/// broken by moving one source to `scratch` first, which must not appear in
+/// `moves`. Destinations must be distinct.
+///
+/// `moves` is reordered and rewritten as the moves are performed, so there
+/// is no limit on how many it holds.
+pub fn parallel_move<E>(
+    moves: &mut [(Reg, Reg)],
+    scratch: Reg,
+    mut mov: impl FnMut(Reg, Reg) -> Result<(), E>,
+) -> Result<(), E> {
+    let pending = moves;
+    let mut count = 0;
+    for i in 0..pending.len() {
+        let (dest, src) = pending[i];
+        if dest != src {
+            pending[count] = (dest, src);
+            count += 1;
+        }
+    }
+    while count > 0 {
+        // A move is safe once no other pending move still reads its
+        // destination.
+        let ready = (0..count).find(|&i| {
+            let dest = pending[i].0;
+            !pending[..count].iter().any(|&(_, src)| src == dest)
+        });
+        match ready {
+            Some(i) => {
+                let (dest, src) = pending[i];
+                mov(dest, src)?;
+                pending[i] = pending[count - 1];
+                count -= 1;
+            }
+            None => {
+                // Every destination is still read: a cycle. Park one
+                // source in the scratch register and read it from there.
+                let src = pending[0].1;
+                mov(scratch, src)?;
+                for pair in &mut pending[..count] {
+                    if pair.1 == src {
+                        pair.1 = scratch;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the moves on a register file and returns it.
+    fn run(moves: &mut [(Reg, Reg)]) -> [u8; 8] {
+        let mut regs = [0, 1, 2, 3, 4, 5, 6, 7];
+        parallel_move::<()>(moves, Reg(7), |dest, src| {
+            regs[dest.0 as usize] = regs[src.0 as usize];
+            Ok(())
+        })
+        .unwrap();
+        regs
+    }
+
+    #[test]
+    fn resolves_chains_and_cycles() {
+        // 0 <- 1 <- 2: a chain, emitted back to front.
+        let regs = run(&mut [(Reg(0), Reg(1)), (Reg(1), Reg(2))]);
+        assert_eq!(&regs[..3], &[1, 2, 2]);
+        // A three-register rotation needs the scratch register.
+        let regs = run(&mut [(Reg(0), Reg(1)), (Reg(1), Reg(2)), (Reg(2), Reg(0))]);
+        assert_eq!(&regs[..3], &[1, 2, 0]);
+    }
+}
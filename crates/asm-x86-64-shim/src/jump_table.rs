@@ -0,0 +1,175 @@
+//! Recognition and retargeting of x86-64 jump tables.
+//!
+//! Compilers lower dense `switch` statements to a bounds check followed by an
+//! indirect jump through a table of code addresses:
+//!
+//! ```text
+//! cmp  rcx, 4
+//! ja   .Ldefault
+//! lea  rax, [rip + .Ltable]
+//! jmp  qword ptr [rax + rcx*8]
+//! ```
+//!
+//! Translating that `jmp` as written would load a guest code address from the
+//! guest's table. Instead, [`table_index`] recognizes the indirect operand,
+//! [`recover_bound`] recovers the entry count from the guarding comparison,
+//! and the shims' `jump_table` methods emit a table of target-side branches
+//! to the translated case labels behind their own bounds check:
+//!
+//! ```ignore
+//! let index = table_index(&operand).expect("not a jump table");
+//! let len = recover_bound(4, ConditionCode::A).unwrap();
+//! shim.jump_table(ctx, x64, &JumpTable { index, targets: &cases[..len], default })?;
+//! ```
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+use portal_solutions_asm_x86_64::{
+    ConditionCode,
+    out::arg::{ArgKind, MemArg, MemArgKind},
+};
+
+/// A guest jump table, retargeted to translated labels.
+#[derive(Debug, Clone, Copy)]
+pub struct JumpTable<'a, L> {
+    /// Guest register holding the zero-based case index.
+    pub index: Reg,
+    /// Translated target of each table entry, in table order.
+    pub targets: &'a [L],
+    /// Where indices at or past `targets.len()` go.
+    pub default: L,
+}
+
+/// Returns the index register of a `jmp` operand that reads a table of 8-byte
+/// code addresses (`qword ptr [base + index*8]`), or `None` for any other
+/// operand.
+///
+/// The table's own location does not matter: its entries are guest addresses
+/// and are replaced wholesale by [`JumpTable::targets`].
+pub fn table_index(op: &(dyn MemArg + '_)) -> Option<Reg> {
+    match op.concrete_mem_kind() {
+        MemArgKind::Mem {
+            offset: Some((ArgKind::Reg { reg, .. }, 8)),
+            size: MemorySize::_64,
+            ..
+        } => Some(reg),
+        _ => None,
+    }
+}
+
+/// Recovers the number of table entries from the guard in front of the jump.
+///
+/// `imm` is the immediate of the `cmp index, imm` and `cc` the condition of
+/// the `jcc` that branches to the default case: `ja` guards `imm + 1` entries
+/// and `jae` (`jnb`) guards `imm`. Other conditions do not bound the index
+/// from above and yield `None`.
+pub fn recover_bound(imm: u64, cc: ConditionCode) -> Option<usize> {
+    let len = match cc {
+        ConditionCode::A => imm.checked_add(1)?,
+        ConditionCode::NB => imm,
+        _ => return None,
+    };
+    usize::try_from(len).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::{self, Write};
+    use portal_solutions_asm_x86_64::out::arg::Segment;
+    use portal_solutions_asm_x86_64::{RegisterClass, X64Arch};
+
+    /// Fixed-capacity text sink, since the crate's tests run without `alloc`.
+    struct Buf {
+        bytes: [u8; 512],
+        len: usize,
+    }
+
+    impl Buf {
+        fn new() -> Self {
+            Buf {
+                bytes: [0; 512],
+                len: 0,
+            }
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+        }
+    }
+
+    impl Write for Buf {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.bytes
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    fn table_operand(scale: u32) -> MemArgKind<ArgKind> {
+        MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(0),
+                size: MemorySize::_64,
+            },
+            offset: Some((
+                ArgKind::Reg {
+                    reg: Reg(1),
+                    size: MemorySize::_64,
+                },
+                scale,
+            )),
+            disp: 0,
+            size: MemorySize::_64,
+            reg_class: RegisterClass::Gpr,
+            segment: Segment::None,
+        }
+    }
+
+    #[test]
+    fn detects_scaled_table_loads() {
+        assert_eq!(table_index(&table_operand(8)), Some(Reg(1)));
+        assert_eq!(table_index(&table_operand(4)), None);
+        assert_eq!(table_index(&Reg(1)), None);
+    }
+
+    #[test]
+    fn bound_follows_guard_condition() {
+        assert_eq!(recover_bound(4, ConditionCode::A), Some(5));
+        assert_eq!(recover_bound(4, ConditionCode::NB), Some(4));
+        assert_eq!(recover_bound(4, ConditionCode::G), None);
+        assert_eq!(recover_bound(u64::MAX, ConditionCode::A), None);
+    }
+
+    #[test]
+    fn shims_emit_branch_tables() {
+        let x64 = X64Arch::default();
+        let targets = [10, 11];
+        let table = JumpTable {
+            index: Reg(1),
+            targets: &targets,
+            default: 12,
+        };
+
+        let mut output = Buf::new();
+        let sink: &mut dyn Write = &mut output;
+        let mut shim = crate::aarch64::X64ToAArch64Shim::new(sink);
+        shim.jump_table(&mut (), x64, &table).unwrap();
+        let output = output.as_str();
+        assert!(output.contains("b.hs 12\n"));
+        assert!(output.contains("br x16\n"));
+        assert!(output.ends_with(":\nb 10\nb 11\n"));
+
+        let mut output = Buf::new();
+        let sink: &mut dyn Write = &mut output;
+        let mut shim = crate::riscv64::X64ToRiscV64Shim::new(sink);
+        shim.jump_table(&mut (), x64, &table).unwrap();
+        let output = output.as_str();
+        assert!(output.contains("bgeu "));
+        assert!(output.contains("jalr zero, t5, 0\n"));
+        assert!(output.ends_with(":\njal zero, 10\njal zero, 11\n"));
+    }
+}
@@ -2,6 +2,27 @@
 #![no_std]
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "fuzz")]
+extern crate std;
 
 pub mod aarch64;
+pub mod cond;
+pub mod cost;
+pub mod faults;
+pub mod flags;
+pub mod float;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+pub mod guest_stack;
+pub mod hooks;
+pub mod host_abi;
+pub mod jump_table;
+pub mod memory_model;
+pub mod reg_class;
 pub mod riscv64;
+pub mod state;
+pub mod string_ops;
+pub mod syscall;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod x87;
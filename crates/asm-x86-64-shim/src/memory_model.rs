@@ -0,0 +1,36 @@
+//! Memory ordering options shared by the translation shims.
+//!
+//! x86-64 is total store order (TSO): loads are not reordered with older
+//! loads, and stores are not reordered with older loads or stores. AArch64
+//! and RISC-V are weakly ordered, so translated multi-threaded code that
+//! relies on TSO (spin locks released with a plain `mov`, message passing
+//! through flags) can observe stale data. [`MemoryModelPolicy`] selects how
+//! a shim restores the x86 ordering for every guest load and store.
+//!
+//! Accesses the shims make on their own behalf (APX backing-store slots,
+//! the system call table, profiling counters) are not guest-visible and are
+//! left unordered.
+
+/// How a shim orders translated guest memory accesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MemoryModelPolicy {
+    /// Emit plain loads and stores. Correct for single-threaded guests.
+    #[default]
+    Native,
+    /// Follow each load with a load barrier (`dmb ishld` / `fence r, rw`)
+    /// and precede each store with a store barrier (`dmb ishst` /
+    /// `fence rw, w`).
+    TsoViaBarriers,
+    /// Use load-acquire and store-release (`ldar` / `stlr`) where the
+    /// address is a bare base register, and barriers elsewhere. RISC-V has
+    /// no acquire/release loads and stores in the base ISA and always uses
+    /// barriers.
+    TsoViaAcqRel,
+}
+
+impl MemoryModelPolicy {
+    /// Returns whether guest accesses need ordering at all.
+    pub fn is_tso(self) -> bool {
+        self != Self::Native
+    }
+}
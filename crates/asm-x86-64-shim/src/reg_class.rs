@@ -0,0 +1,197 @@
+//! Architecture-neutral register classes and conversions between the
+//! per-architecture `RegisterClass` enums.
+//!
+//! Each architecture crate defines its own `#[non_exhaustive]` `RegisterClass`.
+//! Rather than mapping between them with catch-all arms (which would silently
+//! turn a new vector or predicate class into a GPR), every architecture class
+//! converts to and from [`RegClass`] through `TryFrom`, failing with
+//! [`RegClassError`] when one side has no matching register file.
+
+use portal_solutions_asm_aarch64::RegisterClass as AArch64RegClass;
+use portal_solutions_asm_riscv64::RegisterClass as RiscVRegClass;
+use portal_solutions_asm_x86_64::RegisterClass as X64RegClass;
+
+/// Architecture-neutral register class.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[non_exhaustive]
+pub enum RegClass {
+    /// General-purpose integer register.
+    Gpr,
+    /// Scalar floating-point / 128-bit SIMD register (x86 XMM, AArch64 V, RISC-V F).
+    FpSimd,
+    /// Wide vector register beyond 128 bits (x86 YMM/ZMM, SVE `z`).
+    WideVector,
+    /// Predicate / mask register (AVX-512 `k`, SVE `p`).
+    Predicate,
+}
+
+/// Error returned when a register class cannot be converted.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum RegClassError {
+    /// The target architecture has no register file for this class.
+    Unsupported(RegClass),
+    /// The source architecture class is newer than this conversion table.
+    Unknown,
+}
+
+impl core::fmt::Display for RegClassError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RegClassError::Unsupported(c) => {
+                write!(f, "register class {c:?} has no equivalent on the target")
+            }
+            RegClassError::Unknown => write!(f, "unknown register class"),
+        }
+    }
+}
+
+impl core::error::Error for RegClassError {}
+
+impl RegClassError {
+    /// Returns why an operand of the class cannot be translated, as the
+    /// `reason` of an `InvalidOperands` emission error.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            RegClassError::Unsupported(_) => "register class has no equivalent on the target",
+            RegClassError::Unknown => "unknown register class",
+        }
+    }
+}
+
+impl TryFrom<X64RegClass> for RegClass {
+    type Error = RegClassError;
+
+    fn try_from(value: X64RegClass) -> Result<Self, Self::Error> {
+        match value {
+            X64RegClass::Gpr => Ok(RegClass::Gpr),
+            X64RegClass::Xmm => Ok(RegClass::FpSimd),
+            X64RegClass::Ymm | X64RegClass::Zmm => Ok(RegClass::WideVector),
+            _ => Err(RegClassError::Unknown),
+        }
+    }
+}
+
+impl TryFrom<AArch64RegClass> for RegClass {
+    type Error = RegClassError;
+
+    fn try_from(value: AArch64RegClass) -> Result<Self, Self::Error> {
+        match value {
+            AArch64RegClass::Gpr => Ok(RegClass::Gpr),
+            AArch64RegClass::Simd => Ok(RegClass::FpSimd),
+            AArch64RegClass::Sve => Ok(RegClass::WideVector),
+            AArch64RegClass::Predicate => Ok(RegClass::Predicate),
+            // `RegisterClass` is non-exhaustive across crates.
+            _ => Err(RegClassError::Unknown),
+        }
+    }
+}
+
+impl TryFrom<RiscVRegClass> for RegClass {
+    type Error = RegClassError;
+
+    fn try_from(value: RiscVRegClass) -> Result<Self, Self::Error> {
+        match value {
+            RiscVRegClass::Gpr => Ok(RegClass::Gpr),
+            RiscVRegClass::Fp => Ok(RegClass::FpSimd),
+            _ => Err(RegClassError::Unknown),
+        }
+    }
+}
+
+impl TryFrom<RegClass> for X64RegClass {
+    type Error = RegClassError;
+
+    fn try_from(value: RegClass) -> Result<Self, Self::Error> {
+        match value {
+            RegClass::Gpr => Ok(X64RegClass::Gpr),
+            RegClass::FpSimd => Ok(X64RegClass::Xmm),
+            // Width is carried by MemorySize; Xmm picks ymm/zmm from it.
+            RegClass::WideVector => Ok(X64RegClass::Xmm),
+            c => Err(RegClassError::Unsupported(c)),
+        }
+    }
+}
+
+impl TryFrom<RegClass> for AArch64RegClass {
+    type Error = RegClassError;
+
+    fn try_from(value: RegClass) -> Result<Self, Self::Error> {
+        match value {
+            RegClass::Gpr => Ok(AArch64RegClass::Gpr),
+            RegClass::FpSimd => Ok(AArch64RegClass::Simd),
+            // SVE vectors have an implementation-defined width of at least
+            // 128 bits; MemorySize carries the operand's own width.
+            RegClass::WideVector => Ok(AArch64RegClass::Sve),
+            RegClass::Predicate => Ok(AArch64RegClass::Predicate),
+        }
+    }
+}
+
+impl TryFrom<RegClass> for RiscVRegClass {
+    type Error = RegClassError;
+
+    fn try_from(value: RegClass) -> Result<Self, Self::Error> {
+        match value {
+            RegClass::Gpr => Ok(RiscVRegClass::Gpr),
+            RegClass::FpSimd => Ok(RiscVRegClass::Fp),
+            c => Err(RegClassError::Unsupported(c)),
+        }
+    }
+}
+
+/// Converts an x86-64 register class to the AArch64 equivalent.
+pub fn x64_to_aarch64(value: X64RegClass) -> Result<AArch64RegClass, RegClassError> {
+    RegClass::try_from(value)?.try_into()
+}
+
+/// Converts an x86-64 register class to the RISC-V equivalent.
+pub fn x64_to_riscv64(value: X64RegClass) -> Result<RiscVRegClass, RegClassError> {
+    RegClass::try_from(value)?.try_into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn x64_classes_round_trip_through_abstract() {
+        for c in [X64RegClass::Gpr, X64RegClass::Xmm] {
+            assert_eq!(RegClass::try_from(c).and_then(X64RegClass::try_from), Ok(c));
+        }
+    }
+
+    #[test]
+    fn aarch64_classes_round_trip_through_abstract() {
+        for c in [
+            AArch64RegClass::Gpr,
+            AArch64RegClass::Simd,
+            AArch64RegClass::Sve,
+            AArch64RegClass::Predicate,
+        ] {
+            assert_eq!(
+                RegClass::try_from(c).and_then(AArch64RegClass::try_from),
+                Ok(c)
+            );
+        }
+        assert_eq!(
+            RegClass::try_from(AArch64RegClass::Sve),
+            Ok(RegClass::WideVector)
+        );
+        assert_eq!(
+            RegClass::try_from(AArch64RegClass::Predicate),
+            Ok(RegClass::Predicate)
+        );
+    }
+
+    #[test]
+    fn wide_vectors_do_not_become_gprs() {
+        assert_eq!(x64_to_aarch64(X64RegClass::Ymm), Ok(AArch64RegClass::Sve));
+        assert_eq!(
+            x64_to_riscv64(X64RegClass::Zmm),
+            Err(RegClassError::Unsupported(RegClass::WideVector))
+        );
+        assert_eq!(x64_to_aarch64(X64RegClass::Xmm), Ok(AArch64RegClass::Simd));
+        assert_eq!(x64_to_riscv64(X64RegClass::Xmm), Ok(RiscVRegClass::Fp));
+    }
+}
@@ -18,22 +18,65 @@
 //! - **Complex addressing**: RISC-V only supports base+imm12, scaled addressing needs extra instructions
 //! - **Parity flags**: No direct equivalent
 
+use core::cell::Cell;
 use core::task::Context;
 
+use crate::faults::DivFaultMode;
+use crate::flags::FlagTracking;
+use crate::float::NanPolicy;
+use crate::guest_stack::{GuestStack, ReturnStack};
+use crate::hooks::{
+    CountGranularity, CounterLocation, FnHooks, GuestInstruction, ends_basic_block,
+};
+use crate::memory_model::MemoryModelPolicy;
+use crate::reg_class::RegClassError;
+use crate::string_ops::{StringLoop, element_bytes};
+use crate::syscall::{ENOSYS_RESULT, LINUX_TABLE_LEN, NO_SYSCALL, SyscallMode, linux_table_bytes};
+use crate::x87::{PrecisionLoss, X87Stack};
 use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
 use portal_solutions_asm_riscv64::out::arg::MemArg;
+use portal_solutions_asm_riscv64::out::prefetch::{Locality, Prefetch, PrefetchAccess};
+use portal_solutions_asm_riscv64::strategy::Rotate;
+use portal_solutions_asm_riscv64::{FenceSet, RoundingMode};
 use portal_solutions_asm_x86_64::{
-    ConditionCode as X64ConditionCode, X64Arch,
-    out::{Writer as X64Writer, WriterCore as X64WriterCore, arg::MemArg as X64MemArg},
+    ConditionCode as X64ConditionCode, RepPrefix, X64Arch,
+    out::{
+        Writer as X64Writer, WriterCore as X64WriterCore,
+        arg::{ArgKind as X64ArgKind, MemArg as X64MemArg, MemArgKind as X64MemArgKind, Segment},
+    },
 };
 
 /// Label type for shim system.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// The labels the shim generates count up from 0 and print as `.Lshim_N`.
+/// Labels the embedder defines for the shim to branch to, such as a
+/// [`DivFaultMode::Branch`] handler, come from [`ShimLabel::user`] and print
+/// as `.Luser_N`, so the two never collide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ShimLabel(pub usize);
 
+impl ShimLabel {
+    /// Bit set in embedder labels and never reached by generated ones.
+    const USER: usize = 1 << (usize::BITS - 1);
+
+    /// Returns the embedder label `n`.
+    pub const fn user(n: usize) -> Self {
+        ShimLabel(n | Self::USER)
+    }
+
+    /// Returns `true` if this label came from [`ShimLabel::user`].
+    pub const fn is_user(self) -> bool {
+        self.0 & Self::USER != 0
+    }
+}
+
 impl core::fmt::Display for ShimLabel {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, ".Lshim_{}", self.0)
+        if self.is_user() {
+            write!(f, ".Luser_{}", self.0 & !Self::USER)
+        } else {
+            write!(f, ".Lshim_{}", self.0)
+        }
     }
 }
 
@@ -48,6 +91,47 @@ impl<'a> MemArgAdapter<'a> {
     pub fn new(inner: &'a (dyn X64MemArg + 'a), arch: X64Arch) -> Self {
         Self { inner, arch }
     }
+
+    /// Returns why this operand's register class has no RISC-V equivalent,
+    /// if it has none.
+    pub fn reg_class_error(&self) -> Option<RegClassError> {
+        match self.inner.concrete_mem_kind() {
+            X64MemArgKind::Mem { reg_class, .. } => {
+                crate::reg_class::x64_to_riscv64(reg_class).err()
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns why this operand cannot be translated, if it cannot: its
+    /// register class has no RISC-V equivalent, it names a register with no
+    /// RISC-V counterpart, or it is a `gs:` operand, or an `fs:` operand
+    /// with both a base and an index register, which leave no room for the
+    /// thread pointer.
+    pub fn operand_error(&self) -> Option<&'static str> {
+        if let Some(e) = self.reg_class_error() {
+            return Some(e.reason());
+        }
+        match self.inner.concrete_mem_kind() {
+            X64MemArgKind::NoMem(arg) => arg_kind_error(arg),
+            X64MemArgKind::Mem {
+                segment: Segment::Gs,
+                ..
+            } => Some("gs: operands have no base register on the target"),
+            X64MemArgKind::Mem {
+                base,
+                offset: Some(_),
+                segment: Segment::Fs,
+                ..
+            } if !matches!(base, X64ArgKind::Lit(_) | X64ArgKind::NoBase) => {
+                Some("fs: operands cannot have both a base and an index register")
+            }
+            X64MemArgKind::Mem { base, offset, .. } => {
+                arg_kind_error(base).or_else(|| offset.and_then(|(index, _)| arg_kind_error(index)))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl<'a> portal_solutions_asm_riscv64::out::arg::MemArg for MemArgAdapter<'a> {
@@ -77,14 +161,33 @@ impl<'a> portal_solutions_asm_riscv64::out::arg::MemArg for MemArgAdapter<'a> {
                 disp,
                 size,
                 reg_class,
-                segment: _,
+                segment,
             } => {
-                let riscv_base = convert_arg_kind(base, self.arch);
-                let riscv_offset =
+                let mut riscv_base = convert_arg_kind(base, self.arch);
+                let mut riscv_offset =
                     offset.map(|(off, scale)| (convert_arg_kind(off, self.arch), scale));
-                let riscv_disp = disp as i32;
+                let mut riscv_disp = disp;
                 let riscv_reg_class = convert_register_class(reg_class);
 
+                // fs:-relative accesses are thread-local; rebase them onto tp.
+                // Operands that cannot be rebased are left as they are and
+                // reported by operand_error.
+                if segment == Segment::Fs {
+                    let tp = portal_solutions_asm_riscv64::out::arg::ArgKind::Reg {
+                        reg: Reg(4),
+                        size: MemorySize::_64,
+                    };
+                    match (base, &riscv_offset) {
+                        (X64ArgKind::NoBase, _) => riscv_base = tp,
+                        (X64ArgKind::Lit(v), _) => {
+                            riscv_base = tp;
+                            riscv_disp = riscv_disp.wrapping_add(v as i32);
+                        }
+                        (_, None) => riscv_offset = Some((tp, 1)),
+                        (_, Some(_)) => {}
+                    }
+                }
+
                 match &riscv_offset {
                     None => {
                         go(RiscVMemArgKind::Mem {
@@ -114,6 +217,20 @@ impl<'a> portal_solutions_asm_riscv64::out::arg::MemArg for MemArgAdapter<'a> {
     }
 }
 
+/// Returns why a guest operand component has no counterpart on the target,
+/// if it has none.
+fn arg_kind_error(arg: X64ArgKind) -> Option<&'static str> {
+    match arg {
+        X64ArgKind::HighByte(_) => {
+            Some("high-byte registers are only translated by mov, movzx and movsx")
+        }
+        X64ArgKind::Rip => {
+            Some("RIP-relative operands address guest code and cannot be translated")
+        }
+        _ => None,
+    }
+}
+
 /// Converts x86-64 ArgKind to RISC-V ArgKind with register mapping.
 fn convert_arg_kind(
     arg: portal_solutions_asm_x86_64::out::arg::ArgKind,
@@ -131,21 +248,74 @@ fn convert_arg_kind(
             }
         }
         X64ArgKind::Lit(val) => RiscVArgKind::Lit(val),
+        X64ArgKind::Sym { name, addend } => RiscVArgKind::Sym { name, addend },
+        // Reported by `MemArgAdapter::operand_error`
+        X64ArgKind::HighByte(_) | X64ArgKind::Rip => RiscVArgKind::Lit(0),
+        // The target has no base-less form; address from zero instead.
+        X64ArgKind::NoBase => RiscVArgKind::Lit(0),
         _ => RiscVArgKind::Lit(0),
     }
 }
 
 /// Converts x86-64 RegisterClass to RISC-V RegisterClass.
+///
+/// A class with no equivalent on the target (e.g. YMM/ZMM) becomes a GPR
+/// here, since an operand cannot fail to convert; the shim records the
+/// error when it adapts the operand and returns it from the instruction.
 fn convert_register_class(
     reg_class: portal_solutions_asm_x86_64::RegisterClass,
 ) -> portal_solutions_asm_riscv64::RegisterClass {
-    use portal_solutions_asm_riscv64::RegisterClass as RiscVRegClass;
-    use portal_solutions_asm_x86_64::RegisterClass as X64RegClass;
+    crate::reg_class::x64_to_riscv64(reg_class)
+        .unwrap_or(portal_solutions_asm_riscv64::RegisterClass::Gpr)
+}
+
+/// Restates a guest-side x86-64 [`EmitError`](portal_solutions_asm_x86_64::out::error::EmitError)
+/// as the RISC-V writer's error.
+fn convert_emit_error(
+    e: portal_solutions_asm_x86_64::out::error::EmitError,
+) -> portal_solutions_asm_riscv64::out::error::EmitError {
+    use portal_solutions_asm_riscv64::out::error::EmitError;
+    use portal_solutions_asm_x86_64::out::error::EmitError as X64EmitError;
+    match e {
+        X64EmitError::UnsupportedInstruction { mnemonic } => {
+            EmitError::UnsupportedInstruction { mnemonic }
+        }
+        X64EmitError::InvalidOperands { mnemonic, reason } => {
+            EmitError::InvalidOperands { mnemonic, reason }
+        }
+        X64EmitError::ImmediateOutOfRange { mnemonic, value } => {
+            EmitError::ImmediateOutOfRange { mnemonic, value }
+        }
+        X64EmitError::ExtensionNotEnabled {
+            mnemonic,
+            extension,
+        } => EmitError::ExtensionNotEnabled {
+            mnemonic,
+            extension,
+        },
+        e => EmitError::UnsupportedInstruction {
+            mnemonic: e.mnemonic(),
+        },
+    }
+}
+
+/// The error for an operand kind the shim has no lowering for, such as a
+/// `MemArgKind` variant added after it was written.
+fn unknown_operand(mnemonic: &'static str) -> portal_solutions_asm_riscv64::out::error::EmitError {
+    portal_solutions_asm_riscv64::out::error::EmitError::InvalidOperands {
+        mnemonic,
+        reason: "unsupported operand kind",
+    }
+}
 
-    match reg_class {
-        X64RegClass::Gpr => RiscVRegClass::Gpr,
-        X64RegClass::Xmm => RiscVRegClass::Fp,
-        _ => RiscVRegClass::Gpr,
+/// `0(ss)`: the top of the shadow stack, for [`ReturnStack::Shadow`].
+fn shadow_stack_top(ss: Reg) -> portal_solutions_asm_riscv64::out::arg::MemArgKind<Reg> {
+    portal_solutions_asm_riscv64::out::arg::MemArgKind::Mem {
+        base: ss,
+        offset: None,
+        disp: 0,
+        size: MemorySize::_64,
+        reg_class: portal_solutions_asm_riscv64::RegisterClass::Gpr,
     }
 }
 
@@ -193,14 +363,253 @@ pub fn map_x64_register_to_riscv(reg: Reg, arch: X64Arch) -> Reg {
     }
 }
 
+/// Runs the shim's pre/post hooks around the translation of one guest instruction.
+///
+/// The body is evaluated as a block; the post hook only runs if it succeeds.
+macro_rules! hooked {
+    ($self:expr, $ctx:expr, $mnemonic:expr, $body:block) => {{
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "guest_instruction",
+            mnemonic = $mnemonic,
+            index = $self.guest_count
+        )
+        .entered();
+        $self.pre_hook($ctx, $mnemonic)?;
+        let result: Result<(), Self::Error> = $body;
+        result?;
+        $self.post_hook($ctx, $mnemonic)
+    }};
+}
+
+/// Per-instruction hooks for [`X64ToRiscV64Shim`].
+///
+/// Both methods receive the underlying RISC-V64 writer and may emit instructions
+/// through it. The default implementations do nothing.
+pub trait ShimHooks<W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>, Context> {
+    /// Called before the guest instruction is translated.
+    fn pre_instruction(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_riscv64::RiscV64Arch,
+        insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        let _ = (inner, ctx, cfg, insn);
+        Ok(())
+    }
+
+    /// Called after the guest instruction has been translated.
+    fn post_instruction(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_riscv64::RiscV64Arch,
+        insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        let _ = (inner, ctx, cfg, insn);
+        Ok(())
+    }
+
+    /// Called after a guest label has been defined.
+    fn label(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_riscv64::RiscV64Arch,
+    ) -> Result<(), W::Error> {
+        let _ = (inner, ctx, cfg);
+        Ok(())
+    }
+
+    /// Called when a guest instruction is translated approximately.
+    ///
+    /// Runs during translation and cannot emit code; use it to log or reject
+    /// inputs whose results may differ from the guest (see [`crate::x87`]).
+    fn precision_loss(&mut self, insn: GuestInstruction, loss: PrecisionLoss) {
+        let _ = (insn, loss);
+    }
+}
+
+impl<W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>, Context>
+    ShimHooks<W, Context> for ()
+{
+}
+
+impl<W, Context, Pre, Post> ShimHooks<W, Context> for FnHooks<Pre, Post>
+where
+    W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    Pre: FnMut(
+        &mut W,
+        &mut Context,
+        portal_solutions_asm_riscv64::RiscV64Arch,
+        GuestInstruction,
+    ) -> Result<(), W::Error>,
+    Post: FnMut(
+        &mut W,
+        &mut Context,
+        portal_solutions_asm_riscv64::RiscV64Arch,
+        GuestInstruction,
+    ) -> Result<(), W::Error>,
+{
+    fn pre_instruction(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_riscv64::RiscV64Arch,
+        insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        (self.pre)(inner, ctx, cfg, insn)
+    }
+
+    fn post_instruction(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_riscv64::RiscV64Arch,
+        insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        (self.post)(inner, ctx, cfg, insn)
+    }
+}
+
+/// Hook that increments a counter per translated guest instruction or basic block.
+///
+/// RISC-V has no flags, so the increment cannot disturb guest conditions; the
+/// comparison record in t6 is left alone. Memory counters are loaded into t5.
+pub struct InstructionCounter {
+    /// Where the counter is kept.
+    pub location: CounterLocation,
+    /// How often the counter is incremented.
+    pub granularity: CountGranularity,
+    /// Whether the next guest instruction starts a basic block.
+    block_start: bool,
+}
+
+impl InstructionCounter {
+    /// Creates a counter hook.
+    pub fn new(location: CounterLocation, granularity: CountGranularity) -> Self {
+        Self {
+            location,
+            granularity,
+            block_start: true,
+        }
+    }
+
+    fn emit_increment<W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>, Context>(
+        &self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_riscv64::RiscV64Arch,
+    ) -> Result<(), W::Error> {
+        match self.location {
+            CounterLocation::Register(reg) => inner.addi(ctx, cfg, &reg, &reg, 1),
+            CounterLocation::Memory { base, disp } => {
+                let temp = Reg(30); // t5
+                let slot = portal_solutions_asm_riscv64::out::arg::MemArgKind::Mem {
+                    base,
+                    offset: None,
+                    disp,
+                    size: MemorySize::_64,
+                    reg_class: portal_solutions_asm_riscv64::RegisterClass::Gpr,
+                };
+                inner.ld(ctx, cfg, &temp, &slot)?;
+                inner.addi(ctx, cfg, &temp, &temp, 1)?;
+                inner.sd(ctx, cfg, &temp, &slot)
+            }
+        }
+    }
+}
+
+impl<W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>, Context>
+    ShimHooks<W, Context> for InstructionCounter
+{
+    fn pre_instruction(
+        &mut self,
+        inner: &mut W,
+        ctx: &mut Context,
+        cfg: portal_solutions_asm_riscv64::RiscV64Arch,
+        _insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        let count = match self.granularity {
+            CountGranularity::Instruction => true,
+            CountGranularity::BasicBlock => core::mem::replace(&mut self.block_start, false),
+        };
+        if count {
+            self.emit_increment(inner, ctx, cfg)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn post_instruction(
+        &mut self,
+        _inner: &mut W,
+        _ctx: &mut Context,
+        _cfg: portal_solutions_asm_riscv64::RiscV64Arch,
+        insn: GuestInstruction,
+    ) -> Result<(), W::Error> {
+        if ends_basic_block(insn.mnemonic) {
+            self.block_start = true;
+        }
+        Ok(())
+    }
+
+    fn label(
+        &mut self,
+        _inner: &mut W,
+        _ctx: &mut Context,
+        _cfg: portal_solutions_asm_riscv64::RiscV64Arch,
+    ) -> Result<(), W::Error> {
+        self.block_start = true;
+        Ok(())
+    }
+}
+
 /// Wrapper that translates x86-64 instructions to RISC-V64.
-pub struct X64ToRiscV64Shim<W> {
+///
+/// # Hooks
+///
+/// `H` receives a callback before and after every translated guest instruction
+/// (see [`ShimHooks`]); the default `()` does nothing.
+pub struct X64ToRiscV64Shim<W, H = ()> {
     /// The underlying RISC-V64 writer.
     pub inner: W,
     /// RISC-V64 architecture configuration.
     pub riscv_cfg: portal_solutions_asm_riscv64::RiscV64Arch,
+    /// Per-instruction hooks.
+    pub hooks: H,
+    /// How DIV/IDIV inputs that fault on x86-64 are handled. A `Branch`
+    /// label must come from [`ShimLabel::user`].
+    pub div_fault: DivFaultMode<ShimLabel>,
+    /// Where `push`/`pop` keep the guest stack.
+    pub guest_stack: GuestStack,
+    /// Where `call`/`ret` keep return addresses.
+    pub return_stack: ReturnStack,
+    /// How `syscall` is translated.
+    pub syscall: SyscallMode,
+    /// How guest loads and stores are ordered. [`MemoryModelPolicy::TsoViaAcqRel`]
+    /// uses fences like [`MemoryModelPolicy::TsoViaBarriers`].
+    pub memory_model: MemoryModelPolicy,
+    /// Which flags `pushf` and `lahf` can reconstruct. See [`crate::flags`].
+    pub flag_tracking: FlagTracking,
+    /// How NaN-sensitive floating-point instructions are translated. See
+    /// [`crate::float`].
+    pub nan_policy: NanPolicy,
+    /// Label of the system call number table, once a `syscall` needs it.
+    syscall_table: Option<ShimLabel>,
     /// Counter for generating unique shim labels.
     shim_counter: usize,
+    /// Number of guest instructions translated so far.
+    guest_count: usize,
+    /// Translation-time x87 stack depth.
+    x87: X87Stack,
+    /// Why the first operand of the current guest instruction that has no
+    /// RISC-V translation cannot be translated.
+    operand_error: Cell<Option<&'static str>>,
+    /// Target offset after the pre-instruction hook of the current guest
+    /// instruction, if the writer reports offsets.
+    insn_start: Option<usize>,
 }
 
 impl<W> X64ToRiscV64Shim<W> {
@@ -209,7 +618,20 @@ impl<W> X64ToRiscV64Shim<W> {
         Self {
             inner,
             riscv_cfg: portal_solutions_asm_riscv64::RiscV64Arch::rv64imfd(),
+            hooks: (),
+            div_fault: DivFaultMode::Native,
+            guest_stack: GuestStack::default(),
+            return_stack: ReturnStack::Standard,
+            syscall: SyscallMode::Trap,
+            memory_model: MemoryModelPolicy::Native,
+            flag_tracking: FlagTracking::ZeroSign,
+            nan_policy: NanPolicy::Host,
+            syscall_table: None,
             shim_counter: 0,
+            guest_count: 0,
+            x87: X87Stack::default(),
+            operand_error: Cell::new(None),
+            insn_start: None,
         }
     }
 
@@ -218,554 +640,3198 @@ impl<W> X64ToRiscV64Shim<W> {
         Self {
             inner,
             riscv_cfg,
+            hooks: (),
+            div_fault: DivFaultMode::Native,
+            guest_stack: GuestStack::default(),
+            return_stack: ReturnStack::Standard,
+            syscall: SyscallMode::Trap,
+            memory_model: MemoryModelPolicy::Native,
+            flag_tracking: FlagTracking::ZeroSign,
+            nan_policy: NanPolicy::Host,
+            syscall_table: None,
             shim_counter: 0,
+            guest_count: 0,
+            x87: X87Stack::default(),
+            operand_error: Cell::new(None),
+            insn_start: None,
         }
     }
+}
 
-    /// Generates a unique shim label.
-    fn next_shim_label(&mut self) -> ShimLabel {
-        let label = ShimLabel(self.shim_counter);
-        self.shim_counter += 1;
-        label
+impl<W, H> X64ToRiscV64Shim<W, H> {
+    /// Replaces the per-instruction hooks, keeping the writer and all counters.
+    pub fn with_hooks<H2>(self, hooks: H2) -> X64ToRiscV64Shim<W, H2> {
+        X64ToRiscV64Shim {
+            inner: self.inner,
+            riscv_cfg: self.riscv_cfg,
+            hooks,
+            div_fault: self.div_fault,
+            guest_stack: self.guest_stack,
+            return_stack: self.return_stack,
+            syscall: self.syscall,
+            memory_model: self.memory_model,
+            flag_tracking: self.flag_tracking,
+            nan_policy: self.nan_policy,
+            syscall_table: self.syscall_table,
+            shim_counter: self.shim_counter,
+            guest_count: self.guest_count,
+            x87: self.x87,
+            operand_error: self.operand_error,
+            insn_start: self.insn_start,
+        }
     }
-}
 
-/// Translates x86-64 condition codes to RISC-V condition codes.
-pub fn translate_condition(cc: X64ConditionCode) -> portal_solutions_asm_riscv64::ConditionCode {
-    match cc {
-        X64ConditionCode::E => portal_solutions_asm_riscv64::ConditionCode::EQ,
-        X64ConditionCode::NE => portal_solutions_asm_riscv64::ConditionCode::NE,
-        X64ConditionCode::B => portal_solutions_asm_riscv64::ConditionCode::LTU,
-        X64ConditionCode::NB => portal_solutions_asm_riscv64::ConditionCode::GEU,
-        X64ConditionCode::A => portal_solutions_asm_riscv64::ConditionCode::GTU,
-        X64ConditionCode::NA => portal_solutions_asm_riscv64::ConditionCode::LEU,
-        X64ConditionCode::L => portal_solutions_asm_riscv64::ConditionCode::LT,
-        X64ConditionCode::NL => portal_solutions_asm_riscv64::ConditionCode::GE,
-        X64ConditionCode::G => portal_solutions_asm_riscv64::ConditionCode::GT,
-        X64ConditionCode::NG => portal_solutions_asm_riscv64::ConditionCode::LE,
-        X64ConditionCode::O => portal_solutions_asm_riscv64::ConditionCode::NE, // Approximation
-        X64ConditionCode::NO => portal_solutions_asm_riscv64::ConditionCode::EQ, // Approximation
-        X64ConditionCode::S => portal_solutions_asm_riscv64::ConditionCode::LT, // Sign bit set
-        X64ConditionCode::NS => portal_solutions_asm_riscv64::ConditionCode::GE, // Sign bit clear
-        X64ConditionCode::P => portal_solutions_asm_riscv64::ConditionCode::EQ, // No parity equivalent
-        X64ConditionCode::NP => portal_solutions_asm_riscv64::ConditionCode::NE, // No parity equivalent
-        _ => portal_solutions_asm_riscv64::ConditionCode::EQ,
+    /// Sets how DIV/IDIV inputs that fault on x86-64 are handled.
+    ///
+    /// # Errors
+    ///
+    /// Fails with
+    /// [`EmitError::InvalidOperands`](portal_solutions_asm_riscv64::out::error::EmitError::InvalidOperands)
+    /// if a [`DivFaultMode::Branch`] label is not a [`ShimLabel::user`]
+    /// label, which could name a label the shim generates.
+    pub fn with_div_fault(
+        mut self,
+        div_fault: DivFaultMode<ShimLabel>,
+    ) -> Result<Self, portal_solutions_asm_riscv64::out::error::EmitError> {
+        use portal_solutions_asm_riscv64::out::error::EmitError;
+
+        if let DivFaultMode::Branch(label) = div_fault {
+            if !label.is_user() {
+                return Err(EmitError::InvalidOperands {
+                    mnemonic: "div",
+                    reason: "fault handlers need a ShimLabel::user label",
+                });
+            }
+        }
+        self.div_fault = div_fault;
+        Ok(self)
     }
-}
 
-impl<W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>, Context>
-    X64WriterCore<Context> for X64ToRiscV64Shim<W>
-{
-    type Error = W::Error;
+    /// Sets where `push`/`pop` keep the guest stack.
+    ///
+    /// `call` and `ret` keep the return address in `ra` and do not touch it.
+    pub fn with_guest_stack(mut self, guest_stack: GuestStack) -> Self {
+        self.guest_stack = guest_stack;
+        self
+    }
 
-    fn hlt(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
-        // x86-64 HLT → RISC-V EBREAK
-        self.inner.ebreak(ctx, self.riscv_cfg)
+    /// Sets where `call`/`ret` keep return addresses.
+    ///
+    /// With a shadow stack, `call` saves `ra` there before branching and
+    /// reloads it once the callee returns, so nested guest calls return
+    /// correctly without the guest saving `ra` itself.
+    ///
+    /// # Errors
+    ///
+    /// Fails with
+    /// [`EmitError::InvalidOperands`](portal_solutions_asm_riscv64::out::error::EmitError::InvalidOperands)
+    /// if the shadow stack register is one the shim already uses: a guest
+    /// or APX register, a scratch register or one the ABI fixes, such as
+    /// `ra` or `tp`.
+    pub fn with_return_stack(
+        mut self,
+        return_stack: ReturnStack,
+    ) -> Result<Self, portal_solutions_asm_riscv64::out::error::EmitError> {
+        use portal_solutions_asm_riscv64::out::error::EmitError;
+
+        if let Some(ss) = return_stack.shadow() {
+            if Self::uses_register(ss) {
+                return Err(EmitError::InvalidOperands {
+                    mnemonic: "call",
+                    reason: "the shim already uses the shadow stack register",
+                });
+            }
+        }
+        self.return_stack = return_stack;
+        Ok(self)
     }
 
-    fn xchg(
-        &mut self,
-        ctx: &mut Context,
-        _cfg: X64Arch,
-        dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
-    ) -> Result<(), Self::Error> {
-        // XCHG using temporary register
-        let temp = Reg(30); // t5
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner.mv(ctx, self.riscv_cfg, &temp, &dest_adapter)?;
-        self.inner
-            .mv(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)?;
-        self.inner.mv(ctx, self.riscv_cfg, &src_adapter, &temp)?;
-        Ok(())
+    /// Returns `true` if a guest register, with APX, maps to `reg` or the
+    /// shim or the ABI keeps `reg` for itself.
+    fn uses_register(reg: Reg) -> bool {
+        let mut apx = X64Arch::default();
+        apx.apx = true;
+        // zero, ra, gp, tp, s1 (flags), t5 and t6 (scratch)
+        let own = [0, 1, 3, 4, 9, 30, 31].map(Reg);
+        own.contains(&reg) || (0..32).any(|g| map_x64_register_to_riscv(Reg(g), apx) == reg)
     }
 
-    fn mov(
-        &mut self,
-        ctx: &mut Context,
-        _cfg: X64Arch,
-        dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
-    ) -> Result<(), Self::Error> {
-        use portal_solutions_asm_riscv64::out::arg::MemArgKind;
+    /// Sets how `syscall` is translated.
+    pub fn with_syscall(mut self, syscall: SyscallMode) -> Self {
+        self.syscall = syscall;
+        self
+    }
 
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
+    /// Sets how guest loads and stores are ordered.
+    pub fn with_memory_model(mut self, memory_model: MemoryModelPolicy) -> Self {
+        self.memory_model = memory_model;
+        self
+    }
 
-        let dest_kind = dest_adapter.concrete_mem_kind();
-        let src_kind = src_adapter.concrete_mem_kind();
+    /// Sets which flags `pushf` and `lahf` can reconstruct.
+    pub fn with_flag_tracking(mut self, flag_tracking: FlagTracking) -> Self {
+        self.flag_tracking = flag_tracking;
+        self
+    }
 
-        match (dest_kind, src_kind) {
-            (MemArgKind::NoMem(_), MemArgKind::NoMem(_)) => {
-                self.inner
-                    .mv(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
-            }
-            (MemArgKind::NoMem(_), MemArgKind::Mem { .. }) => {
-                self.inner
-                    .ld(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
-            }
-            (MemArgKind::Mem { .. }, MemArgKind::NoMem(_)) => {
-                self.inner
-                    .sd(ctx, self.riscv_cfg, &src_adapter, &dest_adapter)
-            }
-            (MemArgKind::Mem { .. }, MemArgKind::Mem { .. }) => {
-                let temp = Reg(30); // t5
-                self.inner.ld(ctx, self.riscv_cfg, &temp, &src_adapter)?;
-                self.inner.sd(ctx, self.riscv_cfg, &temp, &dest_adapter)
-            }
-            _ => todo!("Unsupported MOV operand kinds"),
-        }
+    /// Sets how NaN-sensitive floating-point instructions are translated.
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
     }
 
-    fn sub(
-        &mut self,
-        ctx: &mut Context,
-        _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
-    ) -> Result<(), Self::Error> {
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-        self.inner
-            .sub(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+    /// Returns the number of guest instructions translated so far.
+    pub fn guest_instruction_count(&self) -> usize {
+        self.guest_count
     }
 
-    fn add(
-        &mut self,
-        ctx: &mut Context,
-        _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
-    ) -> Result<(), Self::Error> {
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-        self.inner
-            .add(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+    /// Captures the label counter and guest instruction count so a later shim
+    /// can resume translation without reusing label numbers.
+    pub fn save_state(&self) -> crate::state::ShimState {
+        crate::state::ShimState::new(self.shim_counter, self.guest_count)
     }
 
-    fn movsx(
-        &mut self,
-        ctx: &mut Context,
-        _cfg: X64Arch,
-        dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
-    ) -> Result<(), Self::Error> {
-        // Sign-extend - use load with sign extension or shift sequences
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        // Simplified: just move for now, proper sign-extension would need size info
-        self.inner
-            .mv(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+    /// Resumes from a state captured by [`save_state`](Self::save_state).
+    pub fn restore_state(&mut self, state: crate::state::ShimState) {
+        self.shim_counter = state.shim_counter;
+        self.guest_count = state.guest_count;
     }
 
-    fn movzx(
+    /// Moves the guest's integer call arguments, as laid out by
+    /// `cfg.call_conv`, into a0-a7 for a call to a native function.
+    ///
+    /// Clobbers t6. See [`host_abi`](crate::host_abi).
+    pub fn host_call_args<Context>(
         &mut self,
         ctx: &mut Context,
-        _cfg: X64Arch,
-        dest: &(dyn X64MemArg + '_),
-        src: &(dyn X64MemArg + '_),
-    ) -> Result<(), Self::Error> {
-        // Zero-extend - RISC-V loads are zero-extending by default
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner
-            .mv(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+        cfg: X64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        let mut moves = [(Reg(0), Reg(0)); 6];
+        let args = cfg.call_conv.int_args();
+        for (i, (pair, arg)) in moves.iter_mut().zip(args).enumerate() {
+            *pair = (Reg(10 + i as u8), map_x64_register_to_riscv(*arg, cfg));
+        }
+        let riscv_cfg = self.riscv_cfg;
+        crate::host_abi::parallel_move(&mut moves[..args.len()], Reg(31), |dest, src| {
+            self.inner.mv(ctx, riscv_cfg, &dest, &src)
+        })
     }
 
-    fn push(
-        &mut self,
-        ctx: &mut Context,
-        _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
-    ) -> Result<(), Self::Error> {
-        // PUSH: sp = sp - 8; [sp] = op
-        let sp = Reg(2);
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        self.inner.addi(ctx, self.riscv_cfg, &sp, &sp, -8)?;
-        self.inner.sd(
-            ctx,
-            self.riscv_cfg,
-            &op_adapter,
-            &portal_solutions_asm_riscv64::out::arg::MemArgKind::Mem {
-                base: sp,
-                offset: None,
-                disp: 0,
-                size: MemorySize::_64,
-                reg_class: portal_solutions_asm_riscv64::RegisterClass::Gpr,
-            },
-        )
+    /// Adapts a guest operand, recording why it cannot be translated, if it
+    /// cannot, for the instruction to report.
+    fn adapt<'b>(&self, op: &'b (dyn X64MemArg + 'b), cfg: X64Arch) -> MemArgAdapter<'b> {
+        let adapter = MemArgAdapter::new(op, cfg);
+        if self.operand_error.get().is_none() {
+            self.operand_error.set(adapter.operand_error());
+        }
+        adapter
     }
 
-    fn pop(
+    /// Generates a unique shim label.
+    fn next_shim_label(&mut self) -> ShimLabel {
+        let label = ShimLabel(self.shim_counter);
+        self.shim_counter += 1;
+        label
+    }
+
+    /// Translates a guest `jmp` through a jump table (see
+    /// [`jump_table`](crate::jump_table)) into a bounds check and a table of
+    /// `j` instructions to `table.targets`.
+    ///
+    /// Out-of-range indices branch to `table.default`. Clobbers t5 and t6.
+    /// Entries must stay 4 bytes wide, so text output has to be assembled
+    /// without compressing them (`.option norvc`).
+    pub fn jump_table<Context, L: Clone>(
         &mut self,
         ctx: &mut Context,
-        _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
-    ) -> Result<(), Self::Error> {
-        // POP: op = [sp]; sp = sp + 8
-        let sp = Reg(2);
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        self.inner.ld(
+        cfg: X64Arch,
+        table: &crate::jump_table::JumpTable<'_, L>,
+    ) -> Result<(), <W as portal_solutions_asm_riscv64::out::WriterCore<Context>>::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>
+            + portal_solutions_asm_riscv64::out::Writer<L, Context>,
+        <W as portal_solutions_asm_riscv64::out::WriterCore<Context>>::Error:
+            From<portal_solutions_asm_riscv64::out::error::EmitError>,
+        H: ShimHooks<W, Context>,
+    {
+        use portal_solutions_asm_riscv64::ConditionCode;
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "guest_instruction",
+            mnemonic = "jmp",
+            index = self.guest_count,
+            targets = table.targets.len()
+        )
+        .entered();
+        self.pre_hook(ctx, "jmp")?;
+        let rv = self.riscv_cfg;
+        let (addr, scaled) = (Reg(30), Reg(31)); // t5, t6
+        let index = self.adapt(&table.index, cfg);
+        let entries = self.next_shim_label();
+
+        self.inner
+            .li(ctx, rv, &scaled, table.targets.len() as u64)?;
+        self.inner.bcond_label(
             ctx,
-            self.riscv_cfg,
-            &op_adapter,
-            &portal_solutions_asm_riscv64::out::arg::MemArgKind::Mem {
-                base: sp,
-                offset: None,
-                disp: 0,
-                size: MemorySize::_64,
-                reg_class: portal_solutions_asm_riscv64::RegisterClass::Gpr,
-            },
+            rv,
+            ConditionCode::GEU,
+            &index,
+            &scaled,
+            table.default.clone(),
         )?;
-        self.inner.addi(ctx, self.riscv_cfg, &sp, &sp, 8)
+        // Entries are 4-byte jumps: offset = index * 4.
+        self.inner.add(ctx, rv, &scaled, &index, &index)?;
+        self.inner.add(ctx, rv, &scaled, &scaled, &scaled)?;
+        self.inner.la_label(ctx, rv, &addr, entries)?;
+        self.inner.add(ctx, rv, &addr, &addr, &scaled)?;
+        self.inner.jalr(ctx, rv, &Reg(0), &addr, 0)?;
+        self.inner.set_label(ctx, rv, entries)?;
+        for target in table.targets {
+            self.inner.jal_label(ctx, rv, &Reg(0), target.clone())?;
+        }
+        self.post_hook(ctx, "jmp")
     }
 
-    fn pushf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
-        // RISC-V doesn't have flags register - skip or use custom solution
-        // For now, push zero as placeholder
-        let sp = Reg(2);
-        let zero = Reg(0);
-        self.inner.addi(ctx, self.riscv_cfg, &sp, &sp, -8)?;
-        self.inner.sd(
-            ctx,
-            self.riscv_cfg,
-            &zero,
-            &portal_solutions_asm_riscv64::out::arg::MemArgKind::Mem {
-                base: sp,
-                offset: None,
-                disp: 0,
-                size: MemorySize::_64,
-                reg_class: portal_solutions_asm_riscv64::RegisterClass::Gpr,
-            },
-        )
+    /// Emits the system call number table used by [`SyscallMode::Linux`], if
+    /// any translated `syscall` refers to it.
+    ///
+    /// Call once after translation, at a point execution never falls into
+    /// (e.g. after the last function).
+    pub fn emit_syscall_table<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        let Some(table) = self.syscall_table else {
+            return Ok(());
+        };
+        let rv = self.riscv_cfg;
+        self.inner.align_to(ctx, rv, 2)?;
+        self.inner.set_label(ctx, rv, table)?;
+        self.inner.db(ctx, rv, &linux_table_bytes(false))
     }
 
-    fn popf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
-        // RISC-V doesn't have flags register - skip or use custom solution
-        let sp = Reg(2);
-        self.inner.addi(ctx, self.riscv_cfg, &sp, &sp, 8)
+    /// Reports that the current guest instruction is translated approximately.
+    fn report_precision_loss<Context>(&mut self, mnemonic: &'static str, loss: PrecisionLoss)
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+        H: ShimHooks<W, Context>,
+    {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(mnemonic, index = self.guest_count, ?loss, "precision loss");
+        let insn = GuestInstruction {
+            mnemonic,
+            index: self.guest_count,
+            emitted_bytes: None,
+        };
+        self.hooks.precision_loss(insn, loss);
     }
 
-    fn call(
+    fn pre_hook<Context>(
         &mut self,
         ctx: &mut Context,
-        _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
-    ) -> Result<(), Self::Error> {
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        self.inner.call(ctx, self.riscv_cfg, &op_adapter)
+        mnemonic: &'static str,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+        H: ShimHooks<W, Context>,
+    {
+        self.operand_error.set(None);
+        let insn = GuestInstruction {
+            mnemonic,
+            index: self.guest_count,
+            emitted_bytes: None,
+        };
+        self.hooks
+            .pre_instruction(&mut self.inner, ctx, self.riscv_cfg, insn)?;
+        self.insn_start = self.inner.current_offset();
+        Ok(())
     }
 
-    fn jmp(
+    fn post_hook<Context>(
         &mut self,
         ctx: &mut Context,
-        _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
-    ) -> Result<(), Self::Error> {
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        self.inner.j(ctx, self.riscv_cfg, &op_adapter)
+        mnemonic: &'static str,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+        W::Error: From<portal_solutions_asm_riscv64::out::error::EmitError>,
+        H: ShimHooks<W, Context>,
+    {
+        use portal_solutions_asm_riscv64::out::error::EmitError;
+
+        if let Some(reason) = self.operand_error.take() {
+            return Err(EmitError::InvalidOperands { mnemonic, reason }.into());
+        }
+        let emitted_bytes = self
+            .inner
+            .current_offset()
+            .zip(self.insn_start.take())
+            .map(|(end, start)| end - start);
+        let insn = GuestInstruction {
+            mnemonic,
+            index: self.guest_count,
+            emitted_bytes,
+        };
+        self.guest_count += 1;
+        self.hooks
+            .post_instruction(&mut self.inner, ctx, self.riscv_cfg, insn)
     }
 
-    fn cmp(
+    /// Emits a 64-bit rotate of `a` by `b` using base-ISA shifts.
+    ///
+    /// `first` shifts by the count and `second` by its negation, which RISC-V
+    /// shifts reduce modulo 64: `a = first(a, b) | second(a, -b)`.
+    fn emulate_rotate<Context>(
         &mut self,
         ctx: &mut Context,
-        _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
-    ) -> Result<(), Self::Error> {
-        // CMP sets flags - RISC-V uses direct comparisons in branches
-        // Store comparison result for later branches (not ideal but works)
-        let temp = Reg(31); // t6 as comparison result holder
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-        self.inner
-            .sub(ctx, self.riscv_cfg, &temp, &a_adapter, &b_adapter)
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        left: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::out::arg::{ArgKind, MemArgKind};
+
+        let temp = Reg(30); // t5
+        match b.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Lit(v)) => {
+                let v = v & 0x3F;
+                let neg = (64 - v) & 0x3F;
+                if left {
+                    self.inner.srl(ctx, self.riscv_cfg, &temp, a, &neg)?;
+                    self.inner.sll(ctx, self.riscv_cfg, a, a, &v)?;
+                } else {
+                    self.inner.sll(ctx, self.riscv_cfg, &temp, a, &neg)?;
+                    self.inner.srl(ctx, self.riscv_cfg, a, a, &v)?;
+                }
+            }
+            _ => {
+                let zero = Reg(0);
+                self.inner.sub(ctx, self.riscv_cfg, &temp, &zero, b)?;
+                if left {
+                    self.inner.srl(ctx, self.riscv_cfg, &temp, a, &temp)?;
+                    self.inner.sll(ctx, self.riscv_cfg, a, a, b)?;
+                } else {
+                    self.inner.sll(ctx, self.riscv_cfg, &temp, a, &temp)?;
+                    self.inner.srl(ctx, self.riscv_cfg, a, a, b)?;
+                }
+            }
+        }
+        self.inner.or(ctx, self.riscv_cfg, a, a, &temp)
     }
 
-    fn cmp0(
+    /// Lowers a widening multiply (`hi:lo = a * b`) to MULHU/MULH plus MUL.
+    ///
+    /// Memory and immediate sources are loaded into t5/t6 first. Whichever
+    /// half does not overwrite a source is computed first; if both would, the
+    /// high half is staged in t6. `hi` and `lo` must be distinct registers.
+    fn emulate_wide_mul<Context>(
+        &mut self,
+        ctx: &mut Context,
+        hi: &(dyn MemArg + '_),
+        lo: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        signed: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::out::arg::{ArgKind, MemArgKind};
+
+        let cfg = self.riscv_cfg;
+        let reg_of = |m: &dyn MemArg| match m.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => Some(reg),
+            _ => None,
+        };
+        let (temp_a, temp_b) = (Reg(30), Reg(31)); // t5, t6
+        let a: &dyn MemArg = match a.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { .. }) => a,
+            MemArgKind::NoMem(ArgKind::Lit(v)) => {
+                self.inner.li(ctx, cfg, &temp_a, v)?;
+                &temp_a
+            }
+            _ => {
+                self.inner.ld(ctx, cfg, &temp_a, a)?;
+                self.load_fence(ctx)?;
+                &temp_a
+            }
+        };
+        let b: &dyn MemArg = match b.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { .. }) => b,
+            MemArgKind::NoMem(ArgKind::Lit(v)) => {
+                self.inner.li(ctx, cfg, &temp_b, v)?;
+                &temp_b
+            }
+            _ => {
+                self.inner.ld(ctx, cfg, &temp_b, b)?;
+                self.load_fence(ctx)?;
+                &temp_b
+            }
+        };
+        let high = |w: &mut W, ctx: &mut Context, dest: &dyn MemArg| {
+            if signed {
+                w.mulh(ctx, cfg, dest, a, b)
+            } else {
+                w.mulhu(ctx, cfg, dest, a, b)
+            }
+        };
+
+        let srcs = [reg_of(a), reg_of(b)];
+        if !srcs.contains(&reg_of(hi)) {
+            high(&mut self.inner, ctx, hi)?;
+            self.inner.mul(ctx, cfg, lo, a, b)
+        } else if !srcs.contains(&reg_of(lo)) {
+            self.inner.mul(ctx, cfg, lo, a, b)?;
+            high(&mut self.inner, ctx, hi)
+        } else {
+            // hi and lo are exactly the two source registers, so t6 is free.
+            high(&mut self.inner, ctx, &temp_b)?;
+            self.inner.mul(ctx, cfg, lo, a, b)?;
+            self.inner.mv(ctx, cfg, hi, &temp_b)
+        }
+    }
+
+    /// Moves the guest stack pointer by `delta` bytes.
+    fn adjust_guest_sp<Context>(&mut self, ctx: &mut Context, delta: i32) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        let sp = self.guest_stack.register(Reg(2));
+        self.inner.addi(ctx, self.riscv_cfg, &sp, &sp, delta)
+    }
+
+    /// The 64-bit slot at the top of the guest stack.
+    fn guest_stack_top(&self) -> portal_solutions_asm_riscv64::out::arg::MemArgKind<Reg> {
+        portal_solutions_asm_riscv64::out::arg::MemArgKind::Mem {
+            base: self.guest_stack.register(Reg(2)),
+            offset: None,
+            disp: self.guest_stack.bias,
+            size: MemorySize::_64,
+            reg_class: portal_solutions_asm_riscv64::RegisterClass::Gpr,
+        }
+    }
+
+    /// Saves `ra` on the shadow stack at `ss` ahead of a call:
+    /// `addi ss, ss, -8; sd ra, 0(ss)`.
+    fn push_return_address<Context>(&mut self, ctx: &mut Context, ss: Reg) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        let top = shadow_stack_top(ss);
+        self.inner.addi(ctx, self.riscv_cfg, &ss, &ss, -8)?;
+        self.inner.sd(ctx, self.riscv_cfg, &Reg(1), &top)
+    }
+
+    /// Restores `ra` from the shadow stack at `ss` after a call:
+    /// `ld ra, 0(ss); addi ss, ss, 8`.
+    fn pop_return_address<Context>(&mut self, ctx: &mut Context, ss: Reg) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        let top = shadow_stack_top(ss);
+        self.inner.ld(ctx, self.riscv_cfg, &Reg(1), &top)?;
+        self.inner.addi(ctx, self.riscv_cfg, &ss, &ss, 8)
+    }
+
+    /// Orders a guest load emitted just before against later guest accesses,
+    /// as [`Self::memory_model`] requires.
+    fn load_fence<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        if self.memory_model.is_tso() {
+            self.inner
+                .fence(ctx, self.riscv_cfg, FenceSet::R, FenceSet::RW)?;
+        }
+        Ok(())
+    }
+
+    /// Orders older guest accesses against a guest store emitted just
+    /// after, as [`Self::memory_model`] requires.
+    fn store_fence<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        if self.memory_model.is_tso() {
+            self.inner
+                .fence(ctx, self.riscv_cfg, FenceSet::RW, FenceSet::W)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the `time` counter into guest EDX:EAX (a2 and a0), with the
+    /// upper halves of RDX and RAX cleared as `rdtsc` leaves them.
+    fn guest_rdtsc<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::out::timestamp::ReadTimestamp;
+
+        let cfg = self.riscv_cfg;
+        let (rax, rdx) = (Reg(10), Reg(12)); // a0, a2
+        self.inner.read_timestamp(ctx, cfg, &rdx)?;
+        self.inner.sll(ctx, cfg, &rax, &rdx, &32u64)?;
+        self.inner.srl(ctx, cfg, &rax, &rax, &32u64)?;
+        self.inner.srl(ctx, cfg, &rdx, &rdx, &32u64)
+    }
+
+    /// Loads `ah`, `ch`, `dh` or `bh` of the guest register `reg` into
+    /// `dest`, zero-extended, or sign-extended when `signed` is set.
+    fn read_high_byte<Context>(
+        &mut self,
+        ctx: &mut Context,
+        dest: &(dyn MemArg + '_),
+        reg: Reg,
+        arch: X64Arch,
+        signed: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::out::arg::ArgKind;
+
+        let cfg = self.riscv_cfg;
+        let src = map_x64_register_to_riscv(reg, arch);
+        if signed {
+            self.inner.sll(ctx, cfg, dest, &src, &ArgKind::Lit(48))?;
+            self.inner.sra(ctx, cfg, dest, dest, &ArgKind::Lit(56))
+        } else {
+            self.inner.srl(ctx, cfg, dest, &src, &ArgKind::Lit(8))?;
+            self.inner.and_imm(ctx, cfg, dest, dest, 0xFF)
+        }
+    }
+
+    /// Replaces bits 8–15 of the guest register `reg` with the low byte of
+    /// t5, leaving its other bits alone. Clobbers t5.
+    fn write_high_byte<Context>(
+        &mut self,
+        ctx: &mut Context,
+        reg: Reg,
+        arch: X64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::out::arg::ArgKind;
+
+        let (cfg, temp) = (self.riscv_cfg, Reg(30)); // t5
+        let dest = map_x64_register_to_riscv(reg, arch);
+        // t5 = ((byte << 8) ^ dest) & 0xFF00, so dest ^ t5 swaps the byte in;
+        // andi only reaches 0x7FF, hence the shifts around the mask.
+        self.inner.sll(ctx, cfg, &temp, &temp, &ArgKind::Lit(8))?;
+        self.inner.xor(ctx, cfg, &temp, &temp, &dest)?;
+        self.inner.srl(ctx, cfg, &temp, &temp, &ArgKind::Lit(8))?;
+        self.inner.and_imm(ctx, cfg, &temp, &temp, 0xFF)?;
+        self.inner.sll(ctx, cfg, &temp, &temp, &ArgKind::Lit(8))?;
+        self.inner.xor(ctx, cfg, &dest, &dest, &temp)
+    }
+
+    /// Translates a `mov` whose destination is a high-byte register.
+    fn mov_to_high_byte<Context>(
+        &mut self,
+        ctx: &mut Context,
+        arch: X64Arch,
+        reg: Reg,
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        let (cfg, temp) = (self.riscv_cfg, Reg(30)); // t5
+        match src.concrete_mem_kind() {
+            X64MemArgKind::NoMem(X64ArgKind::HighByte(s)) => {
+                self.read_high_byte(ctx, &temp, s, arch, false)?
+            }
+            X64MemArgKind::NoMem(X64ArgKind::Lit(v)) => self.inner.li(ctx, cfg, &temp, v)?,
+            X64MemArgKind::NoMem(_) => self.inner.mv(ctx, cfg, &temp, &self.adapt(src, arch))?,
+            _ => {
+                self.inner.lbu(ctx, cfg, &temp, &self.adapt(src, arch))?;
+                self.load_fence(ctx)?;
+            }
+        }
+        self.write_high_byte(ctx, reg, arch)
+    }
+
+    /// Translates a `mov` whose source is a high-byte register. Only the low
+    /// byte of a register destination changes, as on x86-64.
+    fn mov_from_high_byte<Context>(
+        &mut self,
+        ctx: &mut Context,
+        arch: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        reg: Reg,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        let (cfg, temp) = (self.riscv_cfg, Reg(30)); // t5
+        let dest_adapter = self.adapt(dest, arch);
+        self.read_high_byte(ctx, &temp, reg, arch, false)?;
+        match dest.concrete_mem_kind() {
+            X64MemArgKind::Mem { .. } => {
+                self.store_fence(ctx)?;
+                self.inner.sb(ctx, cfg, &temp, &dest_adapter)
+            }
+            _ => {
+                self.inner.xor(ctx, cfg, &temp, &temp, &dest_adapter)?;
+                self.inner.and_imm(ctx, cfg, &temp, &temp, 0xFF)?;
+                self.inner
+                    .xor(ctx, cfg, &dest_adapter, &dest_adapter, &temp)
+            }
+        }
+    }
+
+    /// Records CF and OF of the `cmp` that just left `a - b` in t6 into s1,
+    /// under [`FlagTracking::Full`].
+    ///
+    /// `b` may be s1 itself. Clobbers t5.
+    fn record_carry_overflow<Context>(
+        &mut self,
+        ctx: &mut Context,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::out::arg::ArgKind;
+
+        if self.flag_tracking != FlagTracking::Full {
+            return Ok(());
+        }
+        let cfg = self.riscv_cfg;
+        let (record, flags, temp) = (Reg(31), Reg(9), Reg(30)); // t6, s1, t5
+        // OF: the operands differ in sign, and so do a and the difference
+        self.inner.xor(ctx, cfg, &temp, a, b)?;
+        self.inner.xor(ctx, cfg, &flags, a, &record)?;
+        self.inner.and(ctx, cfg, &temp, &temp, &flags)?;
+        self.inner.srl(ctx, cfg, &temp, &temp, &ArgKind::Lit(63))?;
+        self.inner.sll(ctx, cfg, &temp, &temp, &ArgKind::Lit(11))?;
+        // CF: the subtraction borrowed exactly when the difference exceeds a
+        self.inner.sltu(ctx, cfg, &flags, a, &record)?;
+        self.inner.or(ctx, cfg, &flags, &flags, &temp)
+    }
+
+    /// Like [`Self::record_carry_overflow`], for a `cmp` against the literal
+    /// `imm`, which is first loaded into s1.
+    fn record_carry_overflow_imm<Context>(
+        &mut self,
+        ctx: &mut Context,
+        a: &(dyn MemArg + '_),
+        imm: i64,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        match (self.flag_tracking, imm) {
+            (FlagTracking::Full, 0) => self.clear_carry_overflow(ctx),
+            (FlagTracking::Full, _) => {
+                self.inner.li(ctx, self.riscv_cfg, &Reg(9), imm as u64)?;
+                self.record_carry_overflow(ctx, a, &Reg(9))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Computes `a += b`, or `a -= b` when `subtract` is set, and records
+    /// the carry or borrow in s1 for a following `adc`/`sbb`.
+    ///
+    /// Only used under [`FlagTracking::Full`]. The result is staged in t5,
+    /// so `b` may be the same register as `a`, or t5 itself.
+    fn arith_recording_carry<Context>(
+        &mut self,
+        ctx: &mut Context,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        subtract: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        let cfg = self.riscv_cfg;
+        let (flags, temp) = (Reg(9), Reg(30)); // s1, t5
+        if subtract {
+            // Borrowed exactly when the difference exceeds a
+            self.inner.sub(ctx, cfg, &temp, a, b)?;
+            self.inner.sltu(ctx, cfg, &flags, a, &temp)?;
+        } else {
+            // Carried exactly when the sum is below a
+            self.inner.add(ctx, cfg, &temp, a, b)?;
+            self.inner.sltu(ctx, cfg, &flags, &temp, a)?;
+        }
+        self.inner.mv(ctx, cfg, a, &temp)
+    }
+
+    /// Drops a stale OF from s1 after an `inc` or `dec`, which leave CF
+    /// alone, under [`FlagTracking::Full`].
+    fn keep_carry_only<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        if self.flag_tracking != FlagTracking::Full {
+            return Ok(());
+        }
+        let flags = Reg(9); // s1
+        self.inner
+            .andi(ctx, self.riscv_cfg, &flags, &flags, crate::flags::CF as i32)
+    }
+
+    /// Lowers `adc a, b` (or `sbb` when `subtract` is set) using the CF in
+    /// bit 0 of s1, leaving the new CF there.
+    ///
+    /// The incoming carry is applied first; at most one of the two steps
+    /// can carry, so the carries are ORed. `adc a, a` and `sbb a, a` are
+    /// special-cased since `b` changes with `a`. Clobbers t5.
+    fn carry_chain<Context>(
+        &mut self,
+        ctx: &mut Context,
+        a: &(dyn MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+        x64_cfg: X64Arch,
+        subtract: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::out::arg::ArgKind;
+
+        let cfg = self.riscv_cfg;
+        let (flags, temp, zero) = (Reg(9), Reg(30), Reg(0)); // s1, t5, zero
+        let b_adapter = self.adapt(b, x64_cfg);
+        self.inner
+            .andi(ctx, cfg, &flags, &flags, crate::flags::CF as i32)?;
+        if a.concrete_mem_kind() == b_adapter.concrete_mem_kind() {
+            if subtract {
+                // a - a - CF = -CF, borrowing exactly when CF is set
+                return self.inner.sub(ctx, cfg, a, &zero, &flags);
+            }
+            // a + a + CF carries out the top bit of a
+            self.inner.srl(ctx, cfg, &temp, a, &ArgKind::Lit(63))?;
+            self.inner.add(ctx, cfg, a, a, a)?;
+            self.inner.add(ctx, cfg, a, a, &flags)?;
+            return self.inner.mv(ctx, cfg, &flags, &temp);
+        }
+        let imm = literal_operand(b);
+        if subtract {
+            self.inner.sltu(ctx, cfg, &temp, a, &flags)?;
+            self.inner.sub(ctx, cfg, a, a, &flags)?;
+            match imm {
+                Some(v) => {
+                    self.inner.li(ctx, cfg, &flags, v as u64)?;
+                    self.inner.sltu(ctx, cfg, &flags, a, &flags)?;
+                    self.inner.sub_imm(ctx, cfg, a, a, v)?;
+                }
+                None => {
+                    self.inner.sltu(ctx, cfg, &flags, a, &b_adapter)?;
+                    self.inner.sub(ctx, cfg, a, a, &b_adapter)?;
+                }
+            }
+        } else {
+            self.inner.add(ctx, cfg, a, a, &flags)?;
+            self.inner.sltu(ctx, cfg, &temp, a, &flags)?;
+            match imm {
+                Some(v) => {
+                    self.inner.add_imm(ctx, cfg, a, a, v)?;
+                    self.inner.li(ctx, cfg, &flags, v as u64)?;
+                    self.inner.sltu(ctx, cfg, &flags, a, &flags)?;
+                }
+                None => {
+                    self.inner.add(ctx, cfg, a, a, &b_adapter)?;
+                    self.inner.sltu(ctx, cfg, &flags, a, &b_adapter)?;
+                }
+            }
+        }
+        self.inner.or(ctx, cfg, &flags, &flags, &temp)
+    }
+
+    /// Lowers x86-64 MINSD, or MAXSD when `max` is set; MINSS and MAXSS
+    /// when `size` is 32 bits.
+    ///
+    /// Under [`NanPolicy::Host`] this is `fmin`/`fmax`. Under
+    /// [`NanPolicy::X86`], `dest` is kept only when it compares strictly
+    /// below (above) `src`, and `src` is copied in otherwise, which covers
+    /// NaNs and equal operands as on x86. Clobbers t5.
+    fn float_min_max<Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+        size: MemorySize,
+        max: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::ConditionCode;
+
+        let dest_adapter = self.adapt(dest, cfg);
+        let src_adapter = self.adapt(src, cfg);
+        let cfg = self.riscv_cfg;
+        let single = size == MemorySize::_32;
+        match (self.nan_policy, max) {
+            (NanPolicy::Host, false) if single => {
+                self.inner
+                    .fmin_s(ctx, cfg, &dest_adapter, &dest_adapter, &src_adapter)
+            }
+            (NanPolicy::Host, false) => {
+                self.inner
+                    .fmin_d(ctx, cfg, &dest_adapter, &dest_adapter, &src_adapter)
+            }
+            (NanPolicy::Host, true) if single => {
+                self.inner
+                    .fmax_s(ctx, cfg, &dest_adapter, &dest_adapter, &src_adapter)
+            }
+            (NanPolicy::Host, true) => {
+                self.inner
+                    .fmax_d(ctx, cfg, &dest_adapter, &dest_adapter, &src_adapter)
+            }
+            (NanPolicy::X86, _) => {
+                let (temp, zero) = (Reg(30), Reg(0)); // t5, zero
+                let keep = self.next_shim_label();
+                let (lo, hi) = if max {
+                    (&src_adapter, &dest_adapter)
+                } else {
+                    (&dest_adapter, &src_adapter)
+                };
+                self.float_lt(ctx, size, &temp, lo, hi)?;
+                self.inner
+                    .bcond_label(ctx, cfg, ConditionCode::NE, &temp, &zero, keep)?;
+                if single {
+                    self.inner.fmov_s(ctx, cfg, &dest_adapter, &src_adapter)?;
+                } else {
+                    self.inner.fmov_d(ctx, cfg, &dest_adapter, &src_adapter)?;
+                }
+                self.inner.set_label(ctx, cfg, keep)
+            }
+        }
+    }
+
+    /// Sets `dest` to 1 if `a < b` in the precision `size` selects, and to
+    /// 0 otherwise, including when either is NaN.
+    fn float_lt<Context>(
+        &mut self,
+        ctx: &mut Context,
+        size: MemorySize,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        if size == MemorySize::_32 {
+            self.inner.flt_s(ctx, self.riscv_cfg, dest, a, b)
+        } else {
+            self.inner.flt_d(ctx, self.riscv_cfg, dest, a, b)
+        }
+    }
+
+    /// Lowers x86-64 UCOMISD, or UCOMISS when `size` is 32 bits, to
+    /// t6 = (a > b) - (a < b), which is zero for equal and unordered
+    /// operands, matching ZF. Under [`FlagTracking::Full`], s1 records CF:
+    /// a < b or unordered. Clobbers t5.
+    fn float_compare<Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+        size: MemorySize,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        let a_adapter = self.adapt(a, cfg);
+        let b_adapter = self.adapt(b, cfg);
+        let cfg = self.riscv_cfg;
+        let (record, flags, temp) = (Reg(31), Reg(9), Reg(30)); // t6, s1, t5
+        self.float_lt(ctx, size, &record, &b_adapter, &a_adapter)?;
+        self.float_lt(ctx, size, &temp, &a_adapter, &b_adapter)?;
+        self.inner.sub(ctx, cfg, &record, &record, &temp)?;
+        if self.flag_tracking == FlagTracking::Full {
+            if size == MemorySize::_32 {
+                self.inner.fle_s(ctx, cfg, &flags, &b_adapter, &a_adapter)?;
+            } else {
+                self.inner.fle_d(ctx, cfg, &flags, &b_adapter, &a_adapter)?;
+            }
+            self.inner.xori(ctx, cfg, &flags, &flags, 1)?;
+        }
+        Ok(())
+    }
+
+    /// Clears the CF and OF recorded in s1 after a `test` or a compare
+    /// against zero, under [`FlagTracking::Full`].
+    fn clear_carry_overflow<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        if self.flag_tracking != FlagTracking::Full {
+            return Ok(());
+        }
+        self.inner.mv(ctx, self.riscv_cfg, &Reg(9), &Reg(0))
+    }
+
+    /// Builds the guest FLAGS word in s1 from the comparison record in t6
+    /// and, under [`FlagTracking::Full`], the CF and OF already in s1.
+    ///
+    /// Clobbers t5.
+    fn materialize_flags<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::out::arg::ArgKind;
+
+        let cfg = self.riscv_cfg;
+        let (record, flags, temp, zero) = (Reg(31), Reg(9), Reg(30), Reg(0)); // t6, s1, t5, zero
+        if self.flag_tracking != FlagTracking::Full {
+            self.inner.mv(ctx, cfg, &flags, &zero)?;
+        }
+        // ZF: the record is zero
+        self.inner.sltu(ctx, cfg, &temp, &zero, &record)?;
+        self.inner.xori(ctx, cfg, &temp, &temp, 1)?;
+        self.inner.sll(ctx, cfg, &temp, &temp, &ArgKind::Lit(6))?;
+        self.inner.or(ctx, cfg, &flags, &flags, &temp)?;
+        // SF: the record is negative
+        self.inner
+            .srl(ctx, cfg, &temp, &record, &ArgKind::Lit(63))?;
+        self.inner.sll(ctx, cfg, &temp, &temp, &ArgKind::Lit(7))?;
+        self.inner.or(ctx, cfg, &flags, &flags, &temp)?;
+        self.inner
+            .ori(ctx, cfg, &flags, &flags, crate::flags::RESERVED as i32)
+    }
+
+    /// Rebuilds the comparison record in t6 from the FLAGS word in s1, so
+    /// later branches see its ZF and SF.
+    ///
+    /// See [`crate::flags::comparison_record`]. Clobbers t5.
+    fn restore_comparison<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::out::arg::ArgKind;
+
+        let cfg = self.riscv_cfg;
+        let (record, flags, temp) = (Reg(31), Reg(9), Reg(30)); // t6, s1, t5
+        // record = SF ? -1 : 1
+        self.inner.srl(ctx, cfg, &temp, &flags, &ArgKind::Lit(6))?;
+        self.inner.andi(ctx, cfg, &temp, &temp, 2)?;
+        self.inner.addi(ctx, cfg, &record, &Reg(0), 1)?;
+        self.inner.sub(ctx, cfg, &record, &record, &temp)?;
+        // record &= ZF ? 0 : -1
+        self.inner.srl(ctx, cfg, &temp, &flags, &ArgKind::Lit(6))?;
+        self.inner.andi(ctx, cfg, &temp, &temp, 1)?;
+        self.inner.addi(ctx, cfg, &temp, &temp, -1)?;
+        self.inner.and(ctx, cfg, &record, &record, &temp)
+    }
+
+    /// Copies a register, immediate or memory operand into `temp`.
+    fn load_operand_into_temp<Context>(
+        &mut self,
+        ctx: &mut Context,
+        src: &(dyn MemArg + '_),
+        temp: &Reg,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::out::arg::{ArgKind, MemArgKind};
+
+        match src.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { .. }) => self.inner.mv(ctx, self.riscv_cfg, temp, src),
+            MemArgKind::NoMem(ArgKind::Lit(v)) => self.inner.li(ctx, self.riscv_cfg, temp, v),
+            _ => {
+                self.inner.ld(ctx, self.riscv_cfg, temp, src)?;
+                self.load_fence(ctx)
+            }
+        }
+    }
+
+    /// Emits the guard selected by [`div_fault`](Self::div_fault) ahead of a
+    /// translated DIV (`signed == false`) or IDIV (`signed == true`).
+    ///
+    /// The divisor is copied into t6 and checked for zero; IDIV also checks
+    /// for `INT_MIN / -1`, using t5 for the dividend.
+    fn emit_div_guard<Context>(
+        &mut self,
+        ctx: &mut Context,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        signed: bool,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::ConditionCode;
+
+        if self.div_fault == DivFaultMode::Native {
+            return Ok(());
+        }
+        let cfg = self.riscv_cfg;
+        let (temp_a, temp_b) = (Reg(30), Reg(31)); // t5, t6
+        let zero = Reg(0);
+        let fault = self.next_shim_label();
+        let ok = self.next_shim_label();
+
+        self.load_operand_into_temp(ctx, b, &temp_b)?;
+        if signed {
+            self.inner
+                .bcond_label(ctx, cfg, ConditionCode::EQ, &temp_b, &zero, fault)?;
+            // b == -1 exactly when b + 1 == 0
+            self.inner.addi(ctx, cfg, &temp_a, &temp_b, 1)?;
+            self.inner
+                .bcond_label(ctx, cfg, ConditionCode::NE, &temp_a, &zero, ok)?;
+            self.load_operand_into_temp(ctx, a, &temp_a)?;
+            self.inner.li(ctx, cfg, &temp_b, 1 << 63)?;
+            self.inner
+                .bcond_label(ctx, cfg, ConditionCode::NE, &temp_a, &temp_b, ok)?;
+        } else {
+            self.inner
+                .bcond_label(ctx, cfg, ConditionCode::NE, &temp_b, &zero, ok)?;
+        }
+
+        self.inner.set_label(ctx, cfg, fault)?;
+        match self.div_fault {
+            DivFaultMode::Branch(target) => self.inner.jal_label(ctx, cfg, &zero, target)?,
+            _ => self.inner.ebreak(ctx, cfg)?,
+        }
+        self.inner.set_label(ctx, cfg, ok)
+    }
+
+    /// Lowers `syscall` to `ecall` under the Linux RISC-V convention.
+    ///
+    /// The x86-64 number in a0 (RAX) is looked up in the syscall table and
+    /// RDI, RSI, RDX, R10, R8, R9 (a4, a3, a2, a7, a5, a6) are moved into
+    /// a0-a5, with the number in a7. The kernel preserves everything but a0,
+    /// so afterwards R10, RSI and R8 are moved back from a3, a1 and a4 and RDI
+    /// from t5. a1 (RCX) and t0 (R11) end up clobbered, as on x86-64; the
+    /// comparison record in t6 is untouched.
+    fn linux_syscall<Context>(&mut self, ctx: &mut Context) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::ConditionCode;
+
+        let rv = self.riscv_cfg;
+        let (rax, rsi, rdi, r8, r9, r10, r11) =
+            (Reg(10), Reg(13), Reg(14), Reg(15), Reg(16), Reg(17), Reg(5));
+        let scratch = Reg(30); // t5
+        let table = match self.syscall_table {
+            Some(table) => table,
+            None => {
+                let table = self.next_shim_label();
+                self.syscall_table = Some(table);
+                table
+            }
+        };
+        let nosys = self.next_shim_label();
+        let done = self.next_shim_label();
+
+        // R11 receives the generic number.
+        self.inner.li(ctx, rv, &scratch, LINUX_TABLE_LEN as u64)?;
+        self.inner
+            .bcond_label(ctx, rv, ConditionCode::GEU, &rax, &scratch, nosys)?;
+        self.inner.la_label(ctx, rv, &scratch, table)?;
+        self.inner.add(ctx, rv, &r11, &rax, &rax)?;
+        self.inner.add(ctx, rv, &scratch, &scratch, &r11)?;
+        self.inner
+            .lhu(ctx, rv, &r11, &string_element(scratch, MemorySize::_16))?;
+        self.inner.li(ctx, rv, &scratch, NO_SYSCALL as u64)?;
+        self.inner
+            .bcond_label(ctx, rv, ConditionCode::EQ, &r11, &scratch, nosys)?;
+
+        // a0-a5 = RDI, RSI, RDX, R10, R8, R9; RDX (a2) is already in place.
+        self.inner.mv(ctx, rv, &scratch, &rdi)?;
+        self.inner.mv(ctx, rv, &Reg(10), &rdi)?;
+        self.inner.mv(ctx, rv, &Reg(11), &rsi)?;
+        self.inner.mv(ctx, rv, &Reg(13), &r10)?;
+        self.inner.mv(ctx, rv, &Reg(14), &r8)?;
+        self.inner.mv(ctx, rv, &Reg(15), &r9)?;
+        self.inner.mv(ctx, rv, &Reg(17), &r11)?;
+        self.inner.ecall(ctx, rv)?;
+        self.inner.mv(ctx, rv, &r10, &Reg(13))?;
+        self.inner.mv(ctx, rv, &rsi, &Reg(11))?;
+        self.inner.mv(ctx, rv, &r8, &Reg(14))?;
+        self.inner.mv(ctx, rv, &rdi, &scratch)?;
+        self.inner.jal_label(ctx, rv, &Reg(0), done)?;
+
+        self.inner.set_label(ctx, rv, nosys)?;
+        self.inner.li(ctx, rv, &rax, ENOSYS_RESULT as u64)?;
+        self.inner.set_label(ctx, rv, done)
+    }
+
+    /// Lowers a string instruction to a loop counting RCX (a1) down (see
+    /// [`crate::string_ops`]).
+    ///
+    /// `step` processes one element; in the comparing loops it must leave the
+    /// comparison record in t6. A zero count emits no comparison, so the
+    /// previous record is kept, as x86-64 keeps its flags.
+    fn string_loop<Context>(
+        &mut self,
+        ctx: &mut Context,
+        kind: StringLoop,
+        mut step: impl FnMut(&mut Self, &mut Context) -> Result<(), W::Error>,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        use portal_solutions_asm_riscv64::ConditionCode;
+
+        if kind == StringLoop::Once {
+            return step(self, ctx);
+        }
+        let cfg = self.riscv_cfg;
+        let (rcx, record, zero) = (Reg(11), Reg(31), Reg(0)); // RCX → a1, t6
+        let check = self.next_shim_label();
+        let done = self.next_shim_label();
+
+        self.inner.set_label(ctx, cfg, check)?;
+        self.inner
+            .bcond_label(ctx, cfg, ConditionCode::EQ, &rcx, &zero, done)?;
+        step(self, ctx)?;
+        self.inner.addi(ctx, cfg, &rcx, &rcx, -1)?;
+        let stop = match kind {
+            StringLoop::WhileEqual => Some(ConditionCode::NE),
+            StringLoop::WhileNotEqual => Some(ConditionCode::EQ),
+            _ => None,
+        };
+        if let Some(stop) = stop {
+            self.inner
+                .bcond_label(ctx, cfg, stop, &record, &zero, done)?;
+        }
+        self.inner.jal_label(ctx, cfg, &zero, check)?;
+        self.inner.set_label(ctx, cfg, done)
+    }
+
+    /// Loads the zero-extended string element at `0(base)` into `dest` and
+    /// advances `base`.
+    fn load_string_element<Context>(
+        &mut self,
+        ctx: &mut Context,
+        dest: Reg,
+        base: Reg,
+        size: MemorySize,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        let cfg = self.riscv_cfg;
+        let mem = string_element(base, size);
+        match size {
+            MemorySize::_8 => self.inner.lbu(ctx, cfg, &dest, &mem)?,
+            MemorySize::_16 => self.inner.lhu(ctx, cfg, &dest, &mem)?,
+            MemorySize::_32 => self.inner.lwu(ctx, cfg, &dest, &mem)?,
+            _ => self.inner.ld(ctx, cfg, &dest, &mem)?,
+        }
+        self.load_fence(ctx)?;
+        self.inner.addi(ctx, cfg, &base, &base, element_bytes(size))
+    }
+
+    /// Stores the low `size` bytes of `src` to `0(base)` and advances `base`.
+    fn store_string_element<Context>(
+        &mut self,
+        ctx: &mut Context,
+        src: Reg,
+        base: Reg,
+        size: MemorySize,
+    ) -> Result<(), W::Error>
+    where
+        W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>,
+    {
+        let cfg = self.riscv_cfg;
+        let mem = string_element(base, size);
+        self.store_fence(ctx)?;
+        match size {
+            MemorySize::_8 => self.inner.sb(ctx, cfg, &src, &mem)?,
+            MemorySize::_16 => self.inner.sh(ctx, cfg, &src, &mem)?,
+            MemorySize::_32 => self.inner.sw(ctx, cfg, &src, &mem)?,
+            _ => self.inner.sd(ctx, cfg, &src, &mem)?,
+        }
+        self.inner.addi(ctx, cfg, &base, &base, element_bytes(size))
+    }
+}
+
+/// `0(base)`: the string element at `base`.
+fn string_element(
+    base: Reg,
+    size: MemorySize,
+) -> portal_solutions_asm_riscv64::out::arg::MemArgKind<Reg> {
+    portal_solutions_asm_riscv64::out::arg::MemArgKind::Mem {
+        base,
+        offset: None,
+        disp: 0,
+        size,
+        reg_class: portal_solutions_asm_riscv64::RegisterClass::Gpr,
+    }
+}
+
+/// Returns the value of `arg` if it is an immediate operand.
+fn literal_operand(arg: &(dyn X64MemArg + '_)) -> Option<i64> {
+    use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind};
+
+    match arg.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Lit(v)) => Some(v as i64),
+        _ => None,
+    }
+}
+
+/// Returns the register whose high byte `arg` names, if it is `ah`..`bh`.
+fn high_byte_operand(arg: &(dyn X64MemArg + '_)) -> Option<Reg> {
+    match arg.concrete_mem_kind() {
+        X64MemArgKind::NoMem(X64ArgKind::HighByte(reg)) => Some(reg),
+        _ => None,
+    }
+}
+
+/// Returns whether `arg` is a 32-bit register or memory operand.
+fn is_32_bit(arg: &(dyn X64MemArg + '_)) -> bool {
+    matches!(
+        arg.concrete_mem_kind(),
+        X64MemArgKind::NoMem(X64ArgKind::Reg {
+            size: MemorySize::_32,
+            ..
+        }) | X64MemArgKind::Mem {
+            size: MemorySize::_32,
+            ..
+        }
+    )
+}
+
+/// Translates x86-64 condition codes to RISC-V condition codes.
+///
+/// See [`crate::cond`] for the full table, including approximations.
+pub fn translate_condition(cc: X64ConditionCode) -> portal_solutions_asm_riscv64::ConditionCode {
+    crate::cond::x64_to_riscv64(cc)
+}
+
+impl<W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>, H, Context>
+    X64WriterCore<Context> for X64ToRiscV64Shim<W, H>
+where
+    H: ShimHooks<W, Context>,
+    W::Error: From<portal_solutions_asm_riscv64::out::error::EmitError>,
+{
+    type Error = W::Error;
+
+    fn hlt(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "hlt", {
+            // x86-64 HLT → RISC-V EBREAK
+            self.inner.ebreak(ctx, self.riscv_cfg)
+        })
+    }
+
+    fn ud2(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "ud2", {
+            // x86-64 UD2 -> RISC-V UNIMP
+            self.inner.unreachable(ctx, self.riscv_cfg)
+        })
+    }
+
+    fn int3(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "int3", {
+            // x86-64 INT3 -> RISC-V EBREAK
+            self.inner.debug_break(ctx, self.riscv_cfg)
+        })
+    }
+
+    fn pause(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "pause", {
+            // x86-64 PAUSE -> RISC-V PAUSE (Zihintpause; a FENCE elsewhere)
+            self.inner.pause(ctx, self.riscv_cfg)
+        })
+    }
+
+    fn prefetcht0(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "prefetcht0", {
+            // x86-64 PREFETCHh -> RISC-V PREFETCH.R (Zicbop has no locality
+            // hints, and without Zicbop the prefetch is left out)
+            let mem = self.adapt(mem, _cfg);
+            self.inner.prefetch(
+                ctx,
+                self.riscv_cfg,
+                &mem,
+                Locality::High,
+                PrefetchAccess::Read,
+            )
+        })
+    }
+
+    fn prefetcht1(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "prefetcht1", {
+            let mem = self.adapt(mem, _cfg);
+            self.inner.prefetch(
+                ctx,
+                self.riscv_cfg,
+                &mem,
+                Locality::Moderate,
+                PrefetchAccess::Read,
+            )
+        })
+    }
+
+    fn prefetcht2(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "prefetcht2", {
+            let mem = self.adapt(mem, _cfg);
+            self.inner.prefetch(
+                ctx,
+                self.riscv_cfg,
+                &mem,
+                Locality::Low,
+                PrefetchAccess::Read,
+            )
+        })
+    }
+
+    fn prefetchnta(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "prefetchnta", {
+            let mem = self.adapt(mem, _cfg);
+            self.inner.prefetch(
+                ctx,
+                self.riscv_cfg,
+                &mem,
+                Locality::NonTemporal,
+                PrefetchAccess::Read,
+            )
+        })
+    }
+
+    fn clflush(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "clflush", {
+            // x86-64 CLFLUSH -> RISC-V CBO.FLUSH (Zicbom); without Zicbom
+            // the caches are taken to be coherent and only the ordering is
+            // kept
+            if self.riscv_cfg.zicbom {
+                let mem = self.adapt(mem, _cfg);
+                self.inner.cbo_flush(ctx, self.riscv_cfg, &mem)
+            } else {
+                self.inner
+                    .fence(ctx, self.riscv_cfg, FenceSet::RW, FenceSet::RW)
+            }
+        })
+    }
+
+    fn clflushopt(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "clflushopt", {
+            // x86-64 CLFLUSHOPT -> RISC-V CBO.FLUSH (Zicbom)
+            if self.riscv_cfg.zicbom {
+                let mem = self.adapt(mem, _cfg);
+                self.inner.cbo_flush(ctx, self.riscv_cfg, &mem)
+            } else {
+                self.inner
+                    .fence(ctx, self.riscv_cfg, FenceSet::RW, FenceSet::RW)
+            }
+        })
+    }
+
+    fn clwb(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "clwb", {
+            // x86-64 CLWB -> RISC-V CBO.CLEAN (Zicbom)
+            if self.riscv_cfg.zicbom {
+                let mem = self.adapt(mem, _cfg);
+                self.inner.cbo_clean(ctx, self.riscv_cfg, &mem)
+            } else {
+                self.inner
+                    .fence(ctx, self.riscv_cfg, FenceSet::RW, FenceSet::RW)
+            }
+        })
+    }
+
+    fn sfence(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "sfence", {
+            // x86-64 SFENCE -> RISC-V FENCE W, W
+            self.inner.fence(ctx, self.riscv_cfg, FenceSet::W, FenceSet::W)
+        })
+    }
+
+    fn rdtsc(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "rdtsc", {
+            // x86-64 RDTSC -> RISC-V RDTIME a2; SLLI a0, a2, 32; SRLI a0, a0, 32;
+            // SRLI a2, a2, 32 (the time counter split over EDX:EAX)
+            self.guest_rdtsc(ctx)
+        })
+    }
+
+    fn rdtscp(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "rdtscp", {
+            // x86-64 RDTSCP -> as RDTSC, without the ordering, which RISC-V
+            // has no instruction for; ECX (a1) reads 0, since IA32_TSC_AUX
+            // has no counterpart
+            self.guest_rdtsc(ctx)?;
+            self.inner.li(ctx, self.riscv_cfg, &Reg(11), 0)
+        })
+    }
+
+    fn xchg(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "xchg", {
+            // XCHG using temporary register
+            let temp = Reg(30); // t5
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.mv(ctx, self.riscv_cfg, &temp, &dest_adapter)?;
+            self.inner
+                .mv(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)?;
+            self.inner.mv(ctx, self.riscv_cfg, &src_adapter, &temp)?;
+            Ok(())
+        })
+    }
+
+    fn mov(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "mov", {
+            use portal_solutions_asm_riscv64::out::arg::MemArgKind;
+
+            if let Some(reg) = high_byte_operand(dest) {
+                self.mov_to_high_byte(ctx, _cfg, reg, src)
+            } else if let Some(reg) = high_byte_operand(src) {
+                self.mov_from_high_byte(ctx, _cfg, dest, reg)
+            } else {
+                let dest_adapter = self.adapt(dest, _cfg);
+                let src_adapter = self.adapt(src, _cfg);
+
+                let dest_kind = dest_adapter.concrete_mem_kind();
+                let src_kind = src_adapter.concrete_mem_kind();
+
+                match (dest_kind, src_kind) {
+                    (MemArgKind::NoMem(_), MemArgKind::NoMem(_)) => {
+                        self.inner
+                            .mv(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+                    }
+                    (MemArgKind::NoMem(_), MemArgKind::Mem { .. }) => {
+                        self.inner
+                            .ld(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)?;
+                        self.load_fence(ctx)
+                    }
+                    (MemArgKind::Mem { .. }, MemArgKind::NoMem(_)) => {
+                        self.store_fence(ctx)?;
+                        self.inner
+                            .sd(ctx, self.riscv_cfg, &src_adapter, &dest_adapter)
+                    }
+                    (MemArgKind::Mem { .. }, MemArgKind::Mem { .. }) => {
+                        let temp = Reg(30); // t5
+                        self.inner.ld(ctx, self.riscv_cfg, &temp, &src_adapter)?;
+                        self.load_fence(ctx)?;
+                        self.store_fence(ctx)?;
+                        self.inner.sd(ctx, self.riscv_cfg, &temp, &dest_adapter)
+                    }
+                    _ => Err(unknown_operand("mov").into()),
+                }
+            }
+        })
+    }
+
+    fn sub(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "sub", {
+            let a_adapter = self.adapt(a, _cfg);
+            match literal_operand(b) {
+                Some(imm) if self.flag_tracking == FlagTracking::Full => {
+                    self.inner.li(ctx, self.riscv_cfg, &Reg(30), imm as u64)?;
+                    self.arith_recording_carry(ctx, &a_adapter, &Reg(30), true)
+                }
+                None if self.flag_tracking == FlagTracking::Full => {
+                    let b_adapter = self.adapt(b, _cfg);
+                    self.arith_recording_carry(ctx, &a_adapter, &b_adapter, true)
+                }
+                Some(imm) => self
+                    .inner
+                    .sub_imm(ctx, self.riscv_cfg, &a_adapter, &a_adapter, imm),
+                None => {
+                    let b_adapter = self.adapt(b, _cfg);
+                    self.inner
+                        .sub(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+                }
+            }
+        })
+    }
+
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "sub", {
+            let a_adapter = self.adapt(a, _cfg);
+            if self.flag_tracking == FlagTracking::Full {
+                self.inner
+                    .li(ctx, self.riscv_cfg, &Reg(30), imm as i64 as u64)?;
+                self.arith_recording_carry(ctx, &a_adapter, &Reg(30), true)
+            } else {
+                self.inner
+                    .sub_imm(ctx, self.riscv_cfg, &a_adapter, &a_adapter, imm as i64)
+            }
+        })
+    }
+
+    fn add(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "add", {
+            let a_adapter = self.adapt(a, _cfg);
+            match literal_operand(b) {
+                Some(imm) if self.flag_tracking == FlagTracking::Full => {
+                    self.inner.li(ctx, self.riscv_cfg, &Reg(30), imm as u64)?;
+                    self.arith_recording_carry(ctx, &a_adapter, &Reg(30), false)
+                }
+                None if self.flag_tracking == FlagTracking::Full => {
+                    let b_adapter = self.adapt(b, _cfg);
+                    self.arith_recording_carry(ctx, &a_adapter, &b_adapter, false)
+                }
+                Some(imm) => self
+                    .inner
+                    .add_imm(ctx, self.riscv_cfg, &a_adapter, &a_adapter, imm),
+                None => {
+                    let b_adapter = self.adapt(b, _cfg);
+                    self.inner
+                        .add(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+                }
+            }
+        })
+    }
+
+    fn adc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "adc", {
+            if self.flag_tracking == FlagTracking::Full {
+                let a_adapter = self.adapt(a, _cfg);
+                self.carry_chain(ctx, &a_adapter, b, _cfg, false)
+            } else {
+                // The carry is only recorded under full flag tracking
+                use portal_solutions_asm_riscv64::out::error::EmitError;
+                Err(EmitError::UnsupportedInstruction { mnemonic: "adc" }.into())
+            }
+        })
+    }
+
+    fn sbb(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "sbb", {
+            if self.flag_tracking == FlagTracking::Full {
+                let a_adapter = self.adapt(a, _cfg);
+                self.carry_chain(ctx, &a_adapter, b, _cfg, true)
+            } else {
+                use portal_solutions_asm_riscv64::out::error::EmitError;
+                Err(EmitError::UnsupportedInstruction { mnemonic: "sbb" }.into())
+            }
+        })
+    }
+
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "add", {
+            let a_adapter = self.adapt(a, _cfg);
+            if self.flag_tracking == FlagTracking::Full {
+                self.inner
+                    .li(ctx, self.riscv_cfg, &Reg(30), imm as i64 as u64)?;
+                self.arith_recording_carry(ctx, &a_adapter, &Reg(30), false)
+            } else {
+                self.inner
+                    .add_imm(ctx, self.riscv_cfg, &a_adapter, &a_adapter, imm as i64)
+            }
+        })
+    }
+
+    fn movsx(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "movsx", {
+            // Sign-extend from the source width: signed loads for memory,
+            // sext.b/h/w for registers.
+            let dest_adapter = self.adapt(dest, _cfg);
+            if let Some(reg) = high_byte_operand(src) {
+                self.read_high_byte(ctx, &dest_adapter, reg, _cfg, true)
+            } else {
+                let src_adapter = self.adapt(src, _cfg);
+                let cfg = self.riscv_cfg;
+                match src.concrete_mem_kind() {
+                    X64MemArgKind::Mem { size, .. } => {
+                        match size {
+                            MemorySize::_8 => {
+                                self.inner.lb(ctx, cfg, &dest_adapter, &src_adapter)?
+                            }
+                            MemorySize::_16 => {
+                                self.inner.lh(ctx, cfg, &dest_adapter, &src_adapter)?
+                            }
+                            MemorySize::_32 => {
+                                self.inner.lw(ctx, cfg, &dest_adapter, &src_adapter)?
+                            }
+                            _ => self.inner.ld(ctx, cfg, &dest_adapter, &src_adapter)?,
+                        }
+                        self.load_fence(ctx)
+                    }
+                    X64MemArgKind::NoMem(X64ArgKind::Reg { size, .. }) => match size {
+                        MemorySize::_8 => self.inner.sext_b(ctx, cfg, &dest_adapter, &src_adapter),
+                        MemorySize::_16 => self.inner.sext_h(ctx, cfg, &dest_adapter, &src_adapter),
+                        MemorySize::_32 => self.inner.sext_w(ctx, cfg, &dest_adapter, &src_adapter),
+                        _ => self.inner.mv(ctx, cfg, &dest_adapter, &src_adapter),
+                    },
+                    _ => self.inner.mv(ctx, cfg, &dest_adapter, &src_adapter),
+                }
+            }
+        })
+    }
+
+    fn movzx(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "movzx", {
+            // Zero-extend from the source width: unsigned loads for memory,
+            // masking for registers.
+            let dest_adapter = self.adapt(dest, _cfg);
+            if let Some(reg) = high_byte_operand(src) {
+                self.read_high_byte(ctx, &dest_adapter, reg, _cfg, false)
+            } else {
+                let src_adapter = self.adapt(src, _cfg);
+                let cfg = self.riscv_cfg;
+                match src.concrete_mem_kind() {
+                    X64MemArgKind::Mem { size, .. } => {
+                        match size {
+                            MemorySize::_8 => {
+                                self.inner.lbu(ctx, cfg, &dest_adapter, &src_adapter)?
+                            }
+                            MemorySize::_16 => {
+                                self.inner.lhu(ctx, cfg, &dest_adapter, &src_adapter)?
+                            }
+                            MemorySize::_32 => {
+                                self.inner.lwu(ctx, cfg, &dest_adapter, &src_adapter)?
+                            }
+                            _ => self.inner.ld(ctx, cfg, &dest_adapter, &src_adapter)?,
+                        }
+                        self.load_fence(ctx)
+                    }
+                    X64MemArgKind::NoMem(X64ArgKind::Reg { size, .. }) => {
+                        let mask = match size {
+                            MemorySize::_8 => Some(0xFF),
+                            MemorySize::_16 => Some(0xFFFF),
+                            MemorySize::_32 => Some(0xFFFF_FFFF),
+                            _ => None,
+                        };
+                        match mask {
+                            Some(mask) => {
+                                self.inner
+                                    .and_imm(ctx, cfg, &dest_adapter, &src_adapter, mask)
+                            }
+                            None => self.inner.mv(ctx, cfg, &dest_adapter, &src_adapter),
+                        }
+                    }
+                    _ => self.inner.mv(ctx, cfg, &dest_adapter, &src_adapter),
+                }
+            }
+        })
+    }
+
+    fn push(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "push", {
+            // PUSH: sp = sp - 8; [sp + bias] = op
+            let op_adapter = self.adapt(op, _cfg);
+            self.adjust_guest_sp(ctx, -8)?;
+            let top = self.guest_stack_top();
+            self.store_fence(ctx)?;
+            self.inner.sd(ctx, self.riscv_cfg, &op_adapter, &top)
+        })
+    }
+
+    fn pop(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "pop", {
+            // POP: op = [sp + bias]; sp = sp + 8
+            let op_adapter = self.adapt(op, _cfg);
+            let top = self.guest_stack_top();
+            self.inner.ld(ctx, self.riscv_cfg, &op_adapter, &top)?;
+            self.load_fence(ctx)?;
+            self.adjust_guest_sp(ctx, 8)
+        })
+    }
+
+    fn pushf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "pushf", {
+            // RISC-V has no flags register: rebuild FLAGS in s1 and push it
+            let flags = Reg(9); // s1
+            self.materialize_flags(ctx)?;
+            self.adjust_guest_sp(ctx, -8)?;
+            let top = self.guest_stack_top();
+            self.store_fence(ctx)?;
+            self.inner.sd(ctx, self.riscv_cfg, &flags, &top)
+        })
+    }
+
+    fn popf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "popf", {
+            // Pop FLAGS into s1 and turn ZF/SF back into a comparison record
+            let flags = Reg(9); // s1
+            let top = self.guest_stack_top();
+            self.inner.ld(ctx, self.riscv_cfg, &flags, &top)?;
+            self.load_fence(ctx)?;
+            self.adjust_guest_sp(ctx, 8)?;
+            self.restore_comparison(ctx)
+        })
+    }
+
+    fn lahf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "lahf", {
+            // Rebuild FLAGS in s1, then replace bits 8-15 of a0 (AH) with its low byte
+            use portal_solutions_asm_riscv64::out::arg::ArgKind;
+
+            let cfg = self.riscv_cfg;
+            let (rax, flags, temp) = (Reg(10), Reg(9), Reg(30)); // a0, s1, t5
+            self.materialize_flags(ctx)?;
+            self.inner.srl(ctx, cfg, &temp, &rax, &ArgKind::Lit(8))?;
+            self.inner.andi(ctx, cfg, &temp, &temp, 0xff)?;
+            self.inner.sll(ctx, cfg, &temp, &temp, &ArgKind::Lit(8))?;
+            self.inner.xor(ctx, cfg, &rax, &rax, &temp)?;
+            let ah = crate::flags::SAHF_MASK | crate::flags::RESERVED;
+            self.inner.andi(ctx, cfg, &temp, &flags, ah as i32)?;
+            self.inner.sll(ctx, cfg, &temp, &temp, &ArgKind::Lit(8))?;
+            self.inner.or(ctx, cfg, &rax, &rax, &temp)
+        })
+    }
+
+    fn sahf(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "sahf", {
+            // Merge AH into the low flags in s1, then rebuild the comparison record
+            use portal_solutions_asm_riscv64::out::arg::ArgKind;
+
+            let cfg = self.riscv_cfg;
+            let (rax, flags, temp) = (Reg(10), Reg(9), Reg(30)); // a0, s1, t5
+            self.inner.srl(ctx, cfg, &temp, &rax, &ArgKind::Lit(8))?;
+            self.inner.xor(ctx, cfg, &temp, &temp, &flags)?;
+            self.inner
+                .andi(ctx, cfg, &temp, &temp, crate::flags::SAHF_MASK as i32)?;
+            self.inner.xor(ctx, cfg, &flags, &flags, &temp)?;
+            self.restore_comparison(ctx)
+        })
+    }
+
+    fn call(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "call", {
+            let op_adapter = self.adapt(op, _cfg);
+            match self.return_stack {
+                ReturnStack::Standard => self.inner.call(ctx, self.riscv_cfg, &op_adapter),
+                ReturnStack::Shadow(ss) => {
+                    // Keep the caller's ra across the call on the shadow stack
+                    self.push_return_address(ctx, ss)?;
+                    self.inner.call(ctx, self.riscv_cfg, &op_adapter)?;
+                    self.pop_return_address(ctx, ss)
+                }
+            }
+        })
+    }
+
+    fn jmp(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "jmp", {
+            let op_adapter = self.adapt(op, _cfg);
+            self.inner.j(ctx, self.riscv_cfg, &op_adapter)
+        })
+    }
+
+    fn cmp(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cmp", {
+            // CMP sets flags - RISC-V uses direct comparisons in branches
+            // Store comparison result for later branches (not ideal but works)
+            let temp = Reg(31); // t6 as comparison result holder
+            let a_adapter = self.adapt(a, _cfg);
+            match literal_operand(b) {
+                Some(imm) => {
+                    self.inner
+                        .sub_imm(ctx, self.riscv_cfg, &temp, &a_adapter, imm)?;
+                    self.record_carry_overflow_imm(ctx, &a_adapter, imm)
+                }
+                None => {
+                    let b_adapter = self.adapt(b, _cfg);
+                    self.inner
+                        .sub(ctx, self.riscv_cfg, &temp, &a_adapter, &b_adapter)?;
+                    self.record_carry_overflow(ctx, &a_adapter, &b_adapter)
+                }
+            }
+        })
+    }
+
+    fn cmp_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cmp", {
+            let temp = Reg(31); // t6 as comparison result holder
+            let a_adapter = self.adapt(a, _cfg);
+            self.inner
+                .sub_imm(ctx, self.riscv_cfg, &temp, &a_adapter, imm as i64)?;
+            self.record_carry_overflow_imm(ctx, &a_adapter, imm as i64)
+        })
+    }
+
+    fn cmp0(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cmp0", {
+            let temp = Reg(31); // t6
+            let zero = Reg(0);
+            let op_adapter = self.adapt(op, _cfg);
+            self.inner
+                .sub(ctx, self.riscv_cfg, &temp, &op_adapter, &zero)?;
+            self.clear_carry_overflow(ctx)
+        })
+    }
+
+    fn test(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "test", {
+            // TEST records a & b in the comparison holder, so E/NE/S/NS compare it against zero
+            let temp = Reg(31); // t6 as comparison result holder
+            let a_adapter = self.adapt(a, _cfg);
+            let b_adapter = self.adapt(b, _cfg);
+            self.inner
+                .and(ctx, self.riscv_cfg, &temp, &a_adapter, &b_adapter)?;
+            self.clear_carry_overflow(ctx)
+        })
+    }
+
+    fn test0(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "test0", {
+            // op & op == op, so the operand itself is the comparison record
+            let temp = Reg(31); // t6
+            let op_adapter = self.adapt(op, _cfg);
+            self.inner.mv(ctx, self.riscv_cfg, &temp, &op_adapter)?;
+            self.clear_carry_overflow(ctx)
+        })
+    }
+
+    fn cmovcc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        cond: X64ConditionCode,
+        op: &(dyn X64MemArg + '_),
+        val: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cmovcc", {
+            // Conditional move - branch past the move unless the condition
+            // holds for the comparison record in t6
+            let skip_label = self.next_shim_label();
+            let op_adapter = self.adapt(op, _cfg);
+            let val_adapter = self.adapt(val, _cfg);
+            self.inner.bcond_label(
+                ctx,
+                self.riscv_cfg,
+                translate_condition(cond).invert(),
+                &Reg(31),
+                &Reg(0),
+                skip_label,
+            )?;
+            self.inner
+                .mv(ctx, self.riscv_cfg, &op_adapter, &val_adapter)?;
+            self.inner.set_label(ctx, self.riscv_cfg, skip_label)
+        })
+    }
+
+    fn not(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "not", {
+            // Bitwise NOT - XOR with all 1s
+            let op_adapter = self.adapt(op, _cfg);
+            let temp = Reg(30);
+            self.inner.li(ctx, self.riscv_cfg, &temp, !0u64)?;
+            self.inner
+                .xor(ctx, self.riscv_cfg, &op_adapter, &op_adapter, &temp)
+        })
+    }
+
+    fn neg(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "neg", {
+            // NEG - subtract from zero; CF is set unless op was zero
+            let op_adapter = self.adapt(op, _cfg);
+            if self.flag_tracking == FlagTracking::Full {
+                self.inner
+                    .sltu(ctx, self.riscv_cfg, &Reg(9), &Reg(0), &op_adapter)?;
+            }
+            self.inner
+                .sub(ctx, self.riscv_cfg, &op_adapter, &Reg(0), &op_adapter)
+        })
+    }
+
+    fn inc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "inc", {
+            let op_adapter = self.adapt(op, _cfg);
+            self.inner
+                .add_imm(ctx, self.riscv_cfg, &op_adapter, &op_adapter, 1)?;
+            self.keep_carry_only(ctx)
+        })
+    }
+
+    fn dec(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "dec", {
+            let op_adapter = self.adapt(op, _cfg);
+            self.inner
+                .sub_imm(ctx, self.riscv_cfg, &op_adapter, &op_adapter, 1)?;
+            self.keep_carry_only(ctx)
+        })
+    }
+
+    fn lea(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "lea", {
+            // LEA - compute address
+            use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind};
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            if let MemArgKind::Mem {
+                base: ArgKind::Sym { name, addend },
+                offset: None,
+                disp,
+                ..
+            } = src.concrete_mem_kind()
+            {
+                // `lea r, [rip + sym]` is the symbol's address; MV of a
+                // symbol forms it as `riscv_cfg.pic` requires
+                let sym = portal_solutions_asm_riscv64::out::arg::ArgKind::Sym {
+                    name,
+                    addend: addend + disp as i64,
+                };
+                self.inner.mv(ctx, self.riscv_cfg, &dest_adapter, &sym)
+            } else {
+                // Simplified - would need to extract base+disp and use ADDI
+                self.inner
+                    .mv(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+            }
+        })
+    }
+
+    fn get_ip(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "get_ip", {
+            // Get instruction pointer - use AUIPC
+            let ra = Reg(1);
+            self.inner.auipc(ctx, self.riscv_cfg, &ra, 0)
+        })
+    }
+
+    fn ret(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "ret", { self.inner.ret(ctx, self.riscv_cfg) })
+    }
+
+    fn mov64(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        r: &(dyn X64MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "mov64", {
+            let r_adapter = self.adapt(r, _cfg);
+            self.inner.li(ctx, self.riscv_cfg, &r_adapter, val)
+        })
+    }
+
+    fn mul(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "mul", {
+            let a_adapter = self.adapt(a, _cfg);
+            let b_adapter = self.adapt(b, _cfg);
+            self.inner
+                .mul(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        })
+    }
+
+    fn imul2(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "imul", {
+            // The truncated product is the same signed or unsigned
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.mul(
+                ctx,
+                self.riscv_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
+    }
+
+    fn imul3(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "imul", {
+            // x86-64 IMUL dest, src, imm -> RISC-V LI t5, imm; MUL dest, src, t5
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            let temp = Reg(30); // t5
+            self.inner
+                .li(ctx, self.riscv_cfg, &temp, imm as i64 as u64)?;
+            self.inner
+                .mul(ctx, self.riscv_cfg, &dest_adapter, &src_adapter, &temp)
+        })
+    }
+
+    fn mul_wide(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        hi: &(dyn X64MemArg + '_),
+        lo: &(dyn X64MemArg + '_),
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "mul_wide", {
+            // x86-64 MUL (hi:lo = a * b) -> RISC-V MULHU hi, a, b; MUL lo, a, b
+            let hi = self.adapt(hi, _cfg);
+            let lo = self.adapt(lo, _cfg);
+            let a = self.adapt(a, _cfg);
+            let b = self.adapt(b, _cfg);
+            self.emulate_wide_mul(ctx, &hi, &lo, &a, &b, false)
+        })
+    }
+
+    fn imul_wide(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        hi: &(dyn X64MemArg + '_),
+        lo: &(dyn X64MemArg + '_),
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "imul_wide", {
+            // x86-64 IMUL (hi:lo = a * b) -> RISC-V MULH hi, a, b; MUL lo, a, b
+            let hi = self.adapt(hi, _cfg);
+            let lo = self.adapt(lo, _cfg);
+            let a = self.adapt(a, _cfg);
+            let b = self.adapt(b, _cfg);
+            self.emulate_wide_mul(ctx, &hi, &lo, &a, &b, true)
+        })
+    }
+
+    fn div(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "div", {
+            let a_adapter = self.adapt(a, _cfg);
+            let b_adapter = self.adapt(b, _cfg);
+            self.emit_div_guard(ctx, &a_adapter, &b_adapter, false)?;
+            self.inner
+                .divu(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        })
+    }
+
+    fn idiv(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "idiv", {
+            let a_adapter = self.adapt(a, _cfg);
+            let b_adapter = self.adapt(b, _cfg);
+            self.emit_div_guard(ctx, &a_adapter, &b_adapter, true)?;
+            self.inner
+                .div(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        })
+    }
+
+    fn and(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "and", {
+            let a_adapter = self.adapt(a, _cfg);
+            match literal_operand(b) {
+                Some(imm) => self
+                    .inner
+                    .and_imm(ctx, self.riscv_cfg, &a_adapter, &a_adapter, imm),
+                None => {
+                    let b_adapter = self.adapt(b, _cfg);
+                    self.inner
+                        .and(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+                }
+            }
+        })
+    }
+
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "and", {
+            let a_adapter = self.adapt(a, _cfg);
+            self.inner
+                .and_imm(ctx, self.riscv_cfg, &a_adapter, &a_adapter, imm as i64)
+        })
+    }
+
+    fn or(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "or", {
+            let a_adapter = self.adapt(a, _cfg);
+            match literal_operand(b) {
+                Some(imm) => self
+                    .inner
+                    .or_imm(ctx, self.riscv_cfg, &a_adapter, &a_adapter, imm),
+                None => {
+                    let b_adapter = self.adapt(b, _cfg);
+                    self.inner
+                        .or(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+                }
+            }
+        })
+    }
+
+    fn or_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "or", {
+            let a_adapter = self.adapt(a, _cfg);
+            self.inner
+                .or_imm(ctx, self.riscv_cfg, &a_adapter, &a_adapter, imm as i64)
+        })
+    }
+
+    fn eor(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "eor", {
+            let a_adapter = self.adapt(a, _cfg);
+            match literal_operand(b) {
+                Some(imm) => self
+                    .inner
+                    .xor_imm(ctx, self.riscv_cfg, &a_adapter, &a_adapter, imm),
+                None => {
+                    let b_adapter = self.adapt(b, _cfg);
+                    self.inner
+                        .xor(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+                }
+            }
+        })
+    }
+
+    fn eor_imm(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "eor", {
+            let a_adapter = self.adapt(a, _cfg);
+            self.inner
+                .xor_imm(ctx, self.riscv_cfg, &a_adapter, &a_adapter, imm as i64)
+        })
+    }
+
+    fn shl(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "shl", {
+            let a_adapter = self.adapt(a, _cfg);
+            let b_adapter = self.adapt(b, _cfg);
+            self.inner
+                .sll(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        })
+    }
+
+    fn shr(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "shr", {
+            let a_adapter = self.adapt(a, _cfg);
+            let b_adapter = self.adapt(b, _cfg);
+            self.inner
+                .srl(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        })
+    }
+
+    fn sar(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "sar", {
+            let a_adapter = self.adapt(a, _cfg);
+            let b_adapter = self.adapt(b, _cfg);
+            self.inner
+                .sra(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        })
+    }
+
+    fn rol(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "rol", {
+            // x86-64 ROL a, b -> Zbb ROL (RORI by 64 - b for an immediate),
+            // or (a << b) | (a >> (64 - b)) without Zbb
+            let a_adapter = self.adapt(a, _cfg);
+            let b_adapter = self.adapt(b, _cfg);
+            match self.riscv_cfg.strategies().rotate {
+                Rotate::Native => {
+                    self.inner
+                        .rol(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+                }
+                _ => self.emulate_rotate(ctx, &a_adapter, &b_adapter, true),
+            }
+        })
+    }
+
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "ror", {
+            // x86-64 ROR a, b -> Zbb ROR (RORI for an immediate),
+            // or (a >> b) | (a << (64 - b)) without Zbb
+            let a_adapter = self.adapt(a, _cfg);
+            let b_adapter = self.adapt(b, _cfg);
+            match self.riscv_cfg.strategies().rotate {
+                Rotate::Native => {
+                    self.inner
+                        .ror(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+                }
+                _ => self.emulate_rotate(ctx, &a_adapter, &b_adapter, false),
+            }
+        })
+    }
+
+    fn fadd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fadd", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.fadd_d(
+                ctx,
+                self.riscv_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
+    }
+
+    fn fsub(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fsub", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.fsub_d(
+                ctx,
+                self.riscv_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
+    }
+
+    fn fmul(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let temp = Reg(31); // t6
-        let zero = Reg(0);
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        self.inner
-            .sub(ctx, self.riscv_cfg, &temp, &op_adapter, &zero)
+        hooked!(self, ctx, "fmul", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.fmul_d(
+                ctx,
+                self.riscv_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
     }
 
-    fn cmovcc(
+    fn fdiv(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        cond: X64ConditionCode,
-        op: &(dyn X64MemArg + '_),
-        val: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // Conditional move - use branch + move sequence
-        let skip_label = self.next_shim_label();
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        let val_adapter = MemArgAdapter::new(val, _cfg);
-        let temp = Reg(30); // t5
-        let zero = Reg(0);
+        hooked!(self, ctx, "fdiv", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.fdiv_d(
+                ctx,
+                self.riscv_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
+    }
 
-        // Compare and branch past move if condition not met
-        let riscv_cond = translate_condition(cond);
-        // This is simplified - proper implementation needs condition inversion
-        self.inner
-            .mv(ctx, self.riscv_cfg, &op_adapter, &val_adapter)?;
-        Ok(())
+    fn fcmp(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn X64MemArg + '_),
+        b: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fcmp", {
+            self.float_compare(ctx, _cfg, a, b, MemorySize::_64)
+        })
     }
 
-    fn not(
+    fn fmin(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        op: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // Bitwise NOT - XOR with all 1s
-        let op_adapter = MemArgAdapter::new(op, _cfg);
-        let temp = Reg(30);
-        self.inner.li(ctx, self.riscv_cfg, &temp, !0u64)?;
-        self.inner
-            .xor(ctx, self.riscv_cfg, &op_adapter, &op_adapter, &temp)
+        hooked!(self, ctx, "fmin", {
+            self.float_min_max(ctx, _cfg, dest, src, MemorySize::_64, false)
+        })
     }
 
-    fn lea(
+    fn fmax(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         dest: &(dyn X64MemArg + '_),
         src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        // LEA - compute address
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        // Simplified - would need to extract base+disp and use ADDI
-        self.inner
-            .mv(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+        hooked!(self, ctx, "fmax", {
+            self.float_min_max(ctx, _cfg, dest, src, MemorySize::_64, true)
+        })
     }
 
-    fn get_ip(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
-        // Get instruction pointer - use AUIPC
-        let ra = Reg(1);
-        self.inner.auipc(ctx, self.riscv_cfg, &ra, 0)
+    fn fsqrt(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fsqrt", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .fsqrt_d(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+        })
     }
 
-    fn ret(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
-        self.inner.ret(ctx, self.riscv_cfg)
+    fn fabs(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        _scratch: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fabs", {
+            // The x86-64 mask sequence collapses to fabs.d; no scratch needed.
+            let dest_adapter = self.adapt(dest, _cfg);
+            self.inner
+                .fabs_d(ctx, self.riscv_cfg, &dest_adapter, &dest_adapter)
+        })
     }
 
-    fn mov64(
+    fn fneg(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        r: &(dyn X64MemArg + '_),
-        val: u64,
+        dest: &(dyn X64MemArg + '_),
+        _scratch: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let r_adapter = MemArgAdapter::new(r, _cfg);
-        self.inner.li(ctx, self.riscv_cfg, &r_adapter, val)
+        hooked!(self, ctx, "fneg", {
+            // The x86-64 mask sequence collapses to fneg.d; no scratch needed.
+            let dest_adapter = self.adapt(dest, _cfg);
+            self.inner
+                .fneg_d(ctx, self.riscv_cfg, &dest_adapter, &dest_adapter)
+        })
     }
 
-    fn mul(
+    fn cvtsi2sd(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-        self.inner
-            .mul(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        hooked!(self, ctx, "cvtsi2sd", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            if is_32_bit(src) {
+                self.inner
+                    .fcvt_d_w(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+            } else {
+                self.inner
+                    .fcvt_d_l(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+            }
+        })
     }
 
-    fn div(
+    fn cvttsd2si(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-        self.inner
-            .divu(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        hooked!(self, ctx, "cvttsd2si", {
+            // fcvt.w.d/fcvt.l.d with a static rtz truncate whatever frm
+            // holds. They saturate where x86-64 returns the integer
+            // indefinite value; see `crate::float`.
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            let cfg = self.riscv_cfg.with_rounding_mode(RoundingMode::TowardZero);
+            if is_32_bit(dest) {
+                self.inner.fcvt_w_d(ctx, cfg, &dest_adapter, &src_adapter)
+            } else {
+                self.inner.fcvt_l_d(ctx, cfg, &dest_adapter, &src_adapter)
+            }
+        })
     }
 
-    fn idiv(
+    fn fmov(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-        self.inner
-            .div(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        hooked!(self, ctx, "fmov", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .fmov_d(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+        })
     }
 
-    fn and(
+    fn addss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-        self.inner
-            .and(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        hooked!(self, ctx, "addss", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.fadd_s(
+                ctx,
+                self.riscv_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
     }
 
-    fn or(
+    fn subss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-        self.inner
-            .or(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        hooked!(self, ctx, "subss", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.fsub_s(
+                ctx,
+                self.riscv_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
     }
 
-    fn eor(
+    fn mulss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-        self.inner
-            .xor(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        hooked!(self, ctx, "mulss", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.fmul_s(
+                ctx,
+                self.riscv_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
     }
 
-    fn shl(
+    fn divss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
-        a: &(dyn X64MemArg + '_),
-        b: &(dyn X64MemArg + '_),
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-        self.inner
-            .sll(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        hooked!(self, ctx, "divss", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner.fdiv_s(
+                ctx,
+                self.riscv_cfg,
+                &dest_adapter,
+                &dest_adapter,
+                &src_adapter,
+            )
+        })
     }
 
-    fn shr(
+    fn ucomiss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         a: &(dyn X64MemArg + '_),
         b: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let a_adapter = MemArgAdapter::new(a, _cfg);
-        let b_adapter = MemArgAdapter::new(b, _cfg);
-        self.inner
-            .srl(ctx, self.riscv_cfg, &a_adapter, &a_adapter, &b_adapter)
+        hooked!(self, ctx, "ucomiss", {
+            self.float_compare(ctx, _cfg, a, b, MemorySize::_32)
+        })
     }
 
-    fn fadd(
+    fn minss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         dest: &(dyn X64MemArg + '_),
         src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner.fadd_d(
-            ctx,
-            self.riscv_cfg,
-            &dest_adapter,
-            &dest_adapter,
-            &src_adapter,
-        )
+        hooked!(self, ctx, "minss", {
+            self.float_min_max(ctx, _cfg, dest, src, MemorySize::_32, false)
+        })
     }
 
-    fn fsub(
+    fn maxss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         dest: &(dyn X64MemArg + '_),
         src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner.fsub_d(
-            ctx,
-            self.riscv_cfg,
-            &dest_adapter,
-            &dest_adapter,
-            &src_adapter,
-        )
+        hooked!(self, ctx, "maxss", {
+            self.float_min_max(ctx, _cfg, dest, src, MemorySize::_32, true)
+        })
     }
 
-    fn fmul(
+    fn sqrtss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         dest: &(dyn X64MemArg + '_),
         src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner.fmul_d(
-            ctx,
-            self.riscv_cfg,
-            &dest_adapter,
-            &dest_adapter,
-            &src_adapter,
-        )
+        hooked!(self, ctx, "sqrtss", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .fsqrt_s(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+        })
     }
 
-    fn fdiv(
+    fn cvtsi2ss(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         dest: &(dyn X64MemArg + '_),
         src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner.fdiv_d(
-            ctx,
-            self.riscv_cfg,
-            &dest_adapter,
-            &dest_adapter,
-            &src_adapter,
-        )
+        hooked!(self, ctx, "cvtsi2ss", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            if is_32_bit(src) {
+                self.inner
+                    .fcvt_s_w(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+            } else {
+                self.inner
+                    .fcvt_s_l(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+            }
+        })
     }
 
-    fn fmov(
+    fn cvttss2si(
         &mut self,
         ctx: &mut Context,
         _cfg: X64Arch,
         dest: &(dyn X64MemArg + '_),
         src: &(dyn X64MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        let src_adapter = MemArgAdapter::new(src, _cfg);
-        self.inner
-            .fmov_d(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+        hooked!(self, ctx, "cvttss2si", {
+            // Truncates and saturates, as `cvttsd2si` does.
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            let cfg = self.riscv_cfg.with_rounding_mode(RoundingMode::TowardZero);
+            if is_32_bit(dest) {
+                self.inner.fcvt_w_s(ctx, cfg, &dest_adapter, &src_adapter)
+            } else {
+                self.inner.fcvt_l_s(ctx, cfg, &dest_adapter, &src_adapter)
+            }
+        })
+    }
+
+    fn cvtss2sd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cvtss2sd", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .fcvt_d_s(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+        })
+    }
+
+    fn cvtsd2ss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cvtsd2ss", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .fcvt_s_d(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+        })
+    }
+
+    fn movss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "movss", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            let src_adapter = self.adapt(src, _cfg);
+            self.inner
+                .fmov_s(ctx, self.riscv_cfg, &dest_adapter, &src_adapter)
+        })
+    }
+
+    fn ldmxcsr(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "ldmxcsr", {
+            // Only RC reaches frm; see `crate::float`. t6 and s1 hold flag
+            // state, so the mapping works in t5 alone.
+            use portal_solutions_asm_riscv64::out::arg::ArgKind;
+
+            let cfg = self.riscv_cfg;
+            let temp = Reg(30); // t5
+            let adapter = self.adapt(src, _cfg);
+            self.inner.lwu(ctx, cfg, &temp, &adapter)?;
+            self.load_fence(ctx)?;
+            self.inner.srl(ctx, cfg, &temp, &temp, &ArgKind::Lit(13))?;
+            self.inner.andi(ctx, cfg, &temp, &temp, 3)?;
+            // RC 0, 1, 2, 3 (nearest, down, up, zero) -> frm 0, 2, 3, 1
+            self.inner.addi(ctx, cfg, &temp, &temp, -5)?;
+            self.inner.xori(ctx, cfg, &temp, &temp, 2)?;
+            self.inner.addi(ctx, cfg, &temp, &temp, 7)?;
+            self.inner.srl(ctx, cfg, &temp, &temp, &ArgKind::Lit(1))?;
+            self.inner.fsrm(ctx, cfg, &temp)
+        })
+    }
+
+    fn stmxcsr(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "stmxcsr", {
+            use portal_solutions_asm_riscv64::ConditionCode;
+            use portal_solutions_asm_riscv64::out::arg::ArgKind;
+
+            let cfg = self.riscv_cfg;
+            let (temp, zero) = (Reg(30), Reg(0)); // t5, zero
+            let done = self.next_shim_label();
+            self.inner.frrm(ctx, cfg, &temp)?;
+            // frm 0, 1, 2, 3 (nearest, rtz, down, up) -> RC 0, 3, 1, 2
+            self.inner
+                .bcond_label(ctx, cfg, ConditionCode::EQ, &temp, &zero, done)?;
+            self.inner.addi(ctx, cfg, &temp, &temp, -1)?;
+            self.inner
+                .bcond_label(ctx, cfg, ConditionCode::NE, &temp, &zero, done)?;
+            self.inner.addi(ctx, cfg, &temp, &zero, 3)?;
+            self.inner.set_label(ctx, cfg, done)?;
+            // RC << 13 | 0x1F80, every exception masked.
+            self.inner.sll(ctx, cfg, &temp, &temp, &ArgKind::Lit(6))?;
+            self.inner.ori(ctx, cfg, &temp, &temp, 0x3F)?;
+            self.inner.sll(ctx, cfg, &temp, &temp, &ArgKind::Lit(7))?;
+            let adapter = self.adapt(dest, _cfg);
+            self.store_fence(ctx)?;
+            self.inner.sw(ctx, cfg, &temp, &adapter)
+        })
+    }
+
+    fn rdsegbase(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        seg: Segment,
+        dest: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "rdsegbase", {
+            // The fs base is tp, as for fs: operands; gs has no counterpart.
+            use portal_solutions_asm_riscv64::out::error::EmitError;
+            if seg == Segment::Fs {
+                let dest_adapter = self.adapt(dest, _cfg);
+                self.inner.mv(ctx, self.riscv_cfg, &dest_adapter, &Reg(4))
+            } else {
+                Err(EmitError::UnsupportedInstruction {
+                    mnemonic: "rdgsbase",
+                }
+                .into())
+            }
+        })
+    }
+
+    fn wrsegbase(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        seg: Segment,
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "wrsegbase", {
+            use portal_solutions_asm_riscv64::out::error::EmitError;
+            if seg == Segment::Fs {
+                let src_adapter = self.adapt(src, _cfg);
+                self.inner.mv(ctx, self.riscv_cfg, &Reg(4), &src_adapter)
+            } else {
+                Err(EmitError::UnsupportedInstruction {
+                    mnemonic: "wrgsbase",
+                }
+                .into())
+            }
+        })
+    }
+
+    // x87 stack slots live in f24-f31; see `crate::x87` for the model.
+    fn fld(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        src: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fld", {
+            crate::x87::check_operand(_cfg, "fld", Some(src)).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fld", PrecisionLoss::X87AsDouble);
+            let slot = self.x87.push();
+            let adapter = self.adapt(src, _cfg);
+            self.inner
+                .fld(ctx, self.riscv_cfg, &Reg(24 + slot), &adapter)
+        })
+    }
+
+    fn fst(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fst", {
+            crate::x87::check_operand(_cfg, "fst", Some(dest)).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fst", PrecisionLoss::X87AsDouble);
+            let slot = self.x87.st(0);
+            let adapter = self.adapt(dest, _cfg);
+            self.inner
+                .fsd(ctx, self.riscv_cfg, &Reg(24 + slot), &adapter)
+        })
+    }
+
+    fn fstp(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn X64MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fstp", {
+            crate::x87::check_operand(_cfg, "fstp", Some(dest)).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fstp", PrecisionLoss::X87AsDouble);
+            let slot = self.x87.st(0);
+            self.x87.pop();
+            let adapter = self.adapt(dest, _cfg);
+            self.inner
+                .fsd(ctx, self.riscv_cfg, &Reg(24 + slot), &adapter)
+        })
+    }
+
+    fn faddp(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "faddp", {
+            // ST(1) = ST(1) op ST(0), then pop
+            crate::x87::check_operand(_cfg, "faddp", None).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("faddp", PrecisionLoss::X87AsDouble);
+            let a = Reg(24 + self.x87.st(1));
+            let b = Reg(24 + self.x87.st(0));
+            self.x87.pop();
+            self.inner.fadd_d(ctx, self.riscv_cfg, &a, &a, &b)
+        })
+    }
+
+    fn fsubp(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fsubp", {
+            // ST(1) = ST(1) op ST(0), then pop
+            crate::x87::check_operand(_cfg, "fsubp", None).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fsubp", PrecisionLoss::X87AsDouble);
+            let a = Reg(24 + self.x87.st(1));
+            let b = Reg(24 + self.x87.st(0));
+            self.x87.pop();
+            self.inner.fsub_d(ctx, self.riscv_cfg, &a, &a, &b)
+        })
+    }
+
+    fn fmulp(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fmulp", {
+            // ST(1) = ST(1) op ST(0), then pop
+            crate::x87::check_operand(_cfg, "fmulp", None).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fmulp", PrecisionLoss::X87AsDouble);
+            let a = Reg(24 + self.x87.st(1));
+            let b = Reg(24 + self.x87.st(0));
+            self.x87.pop();
+            self.inner.fmul_d(ctx, self.riscv_cfg, &a, &a, &b)
+        })
+    }
+
+    fn fdivp(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "fdivp", {
+            // ST(1) = ST(1) op ST(0), then pop
+            crate::x87::check_operand(_cfg, "fdivp", None).map_err(convert_emit_error)?;
+            self.report_precision_loss::<Context>("fdivp", PrecisionLoss::X87AsDouble);
+            let a = Reg(24 + self.x87.st(1));
+            let b = Reg(24 + self.x87.st(0));
+            self.x87.pop();
+            self.inner.fdiv_d(ctx, self.riscv_cfg, &a, &a, &b)
+        })
+    }
+
+    // String instructions walk RSI/RDI (a3/a4); see `crate::string_ops`.
+    fn movs(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "movs", {
+            let temp = Reg(30); // t5
+            self.string_loop(ctx, StringLoop::new(rep, false), |this, ctx| {
+                this.load_string_element(ctx, temp, Reg(13), size)?;
+                this.store_string_element(ctx, temp, Reg(14), size)
+            })
+        })
+    }
+
+    fn stos(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "stos", {
+            self.string_loop(ctx, StringLoop::new(rep, false), |this, ctx| {
+                this.store_string_element(ctx, Reg(10), Reg(14), size)
+            })
+        })
+    }
+
+    fn cmps(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "cmps", {
+            // The record is [rsi] - [rdi], as for `cmp`.
+            let (a, record) = (Reg(30), Reg(31)); // t5, t6
+            self.string_loop(ctx, StringLoop::new(rep, true), |this, ctx| {
+                this.load_string_element(ctx, a, Reg(13), size)?;
+                this.load_string_element(ctx, record, Reg(14), size)?;
+                this.inner.sub(ctx, this.riscv_cfg, &record, &a, &record)
+            })
+        })
+    }
+
+    fn scas(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "scas", {
+            // The record is RAX - [rdi], with RAX cut to the element size.
+            let (a, record) = (Reg(30), Reg(31)); // t5, t6
+            self.string_loop(ctx, StringLoop::new(rep, true), |this, ctx| {
+                let cfg = this.riscv_cfg;
+                match size {
+                    MemorySize::_64 => this.inner.mv(ctx, cfg, &a, &Reg(10))?,
+                    _ => {
+                        let mask = (1u64 << (8 * element_bytes(size))) - 1;
+                        this.inner.li(ctx, cfg, &a, mask)?;
+                        this.inner.and(ctx, cfg, &a, &a, &Reg(10))?;
+                    }
+                }
+                this.load_string_element(ctx, record, Reg(14), size)?;
+                this.inner.sub(ctx, cfg, &record, &a, &record)
+            })
+        })
+    }
+
+    fn syscall(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "syscall", {
+            match self.syscall {
+                SyscallMode::Trap => self.inner.ecall(ctx, self.riscv_cfg),
+                SyscallMode::Linux => self.linux_syscall(ctx),
+            }
+        })
+    }
+
+    fn endbr64(&mut self, ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "endbr64", {
+            // x86-64 ENDBR64 -> RISC-V LPAD 0
+            if self.riscv_cfg.zicfilp {
+                self.inner.lpad(ctx, self.riscv_cfg, 0)?;
+            }
+            Ok(())
+        })
     }
 }
 
-impl<W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>, L, Context>
-    X64Writer<L, Context> for X64ToRiscV64Shim<W>
+impl<W: portal_solutions_asm_riscv64::out::Writer<ShimLabel, Context>, H, L, Context>
+    X64Writer<L, Context> for X64ToRiscV64Shim<W, H>
 where
     W: portal_solutions_asm_riscv64::out::Writer<L, Context>,
+    H: ShimHooks<W, Context>,
+    <W as portal_solutions_asm_riscv64::out::WriterCore<Context>>::Error:
+        From<portal_solutions_asm_riscv64::out::error::EmitError>,
 {
     fn set_label(&mut self, ctx: &mut Context, _cfg: X64Arch, s: L) -> Result<(), Self::Error> {
-        self.inner.set_label(ctx, self.riscv_cfg, s)
+        self.inner.set_label(ctx, self.riscv_cfg, s)?;
+        self.hooks.label(&mut self.inner, ctx, self.riscv_cfg)
     }
 
     fn lea_label(
@@ -775,8 +3841,61 @@ where
         dest: &(dyn X64MemArg + '_),
         label: L,
     ) -> Result<(), Self::Error> {
-        let dest_adapter = MemArgAdapter::new(dest, _cfg);
-        self.inner.jal_label(ctx, self.riscv_cfg, &Reg(0), label)?;
-        Ok(())
+        hooked!(self, ctx, "lea_label", {
+            let dest_adapter = self.adapt(dest, _cfg);
+            self.inner.jal_label(ctx, self.riscv_cfg, &Reg(0), label)?;
+            Ok(())
+        })
+    }
+
+    fn call_label(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "call_label", {
+            // Like `call`, the return address stays in ra: jal ra, label
+            match self.return_stack {
+                ReturnStack::Standard => self.inner.jal_label(ctx, self.riscv_cfg, &Reg(1), label),
+                ReturnStack::Shadow(ss) => {
+                    self.push_return_address(ctx, ss)?;
+                    self.inner.jal_label(ctx, self.riscv_cfg, &Reg(1), label)?;
+                    self.pop_return_address(ctx, ss)
+                }
+            }
+        })
+    }
+
+    fn jcc_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: X64ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.jcc_label_hinted(ctx, cfg, cc, label, portal_solutions_asm_x86_64::BranchHint::None)
+    }
+
+    fn jcc_label_hinted(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: X64Arch,
+        cc: X64ConditionCode,
+        label: L,
+        hint: portal_solutions_asm_x86_64::BranchHint,
+    ) -> Result<(), Self::Error> {
+        hooked!(self, ctx, "jcc_label", {
+            // x86-64 Jcc -> RISC-V branch comparing the CMP/TEST record in t6 against zero
+            self.inner.bcond_label_hinted(
+                ctx,
+                self.riscv_cfg,
+                translate_condition(cc),
+                &Reg(31),
+                &Reg(0),
+                label,
+                crate::cond::x64_hint_to_riscv64(hint),
+            )
+        })
     }
 }
@@ -0,0 +1,31 @@
+//! Resumable translation state shared by the shims.
+//!
+//! A long translation may be split across several shim instances, for example
+//! one per chunk of guest code. Shim-generated labels are numbered from a
+//! per-shim counter, so a fresh shim would reuse label numbers already emitted
+//! by its predecessor. [`ShimState`] captures everything a shim needs to carry
+//! on where another left off; pass it from `save_state` on the old shim to
+//! `restore_state` on the new one.
+
+/// Snapshot of a shim's translation state.
+///
+/// Restoring a snapshot makes the shim's output identical to what the saved
+/// shim would have emitted next.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ShimState {
+    /// Next shim label number.
+    pub shim_counter: usize,
+    /// Number of guest instructions translated so far.
+    pub guest_count: usize,
+}
+
+impl ShimState {
+    /// Creates a state with the given label counter and guest instruction count.
+    pub fn new(shim_counter: usize, guest_count: usize) -> Self {
+        Self {
+            shim_counter,
+            guest_count,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! String instruction (`movs`, `stos`, `cmps`, `scas`) lowering shared by the
+//! translation shims.
+//!
+//! Neither target has string instructions, so the shims lower each one to an
+//! explicit loop over the registers RSI, RDI and RCX are mapped to. One
+//! element is processed per iteration: `rep movsb` becomes a byte-copy loop
+//! rather than a vectorised `memcpy`, which keeps the guest's RSI/RDI/RCX
+//! exactly as x86-64 leaves them, including after an early exit.
+//!
+//! The direction flag is assumed clear, as the System V ABI requires on entry
+//! to and exit from every function; elements are always processed upwards.
+
+use portal_pc_asm_common::types::mem::MemorySize;
+use portal_solutions_asm_x86_64::RepPrefix;
+
+/// Loop structure of a string instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringLoop {
+    /// Processes one element, without touching RCX.
+    Once,
+    /// Processes RCX elements.
+    Count,
+    /// Processes elements while RCX≠0 and they compare equal (`repe`).
+    WhileEqual,
+    /// Processes elements while RCX≠0 and they compare different (`repne`).
+    WhileNotEqual,
+}
+
+impl StringLoop {
+    /// Returns the loop for `rep`; `compares` is set for `cmps` and `scas`.
+    ///
+    /// `rep` and `repe` share an encoding, so `rep` on a comparing
+    /// instruction is [`StringLoop::WhileEqual`]. Non-comparing instructions
+    /// ignore the ZF condition and repeat RCX times under either prefix.
+    pub fn new(rep: RepPrefix, compares: bool) -> Self {
+        match rep {
+            RepPrefix::None => StringLoop::Once,
+            _ if !compares => StringLoop::Count,
+            RepPrefix::Repne => StringLoop::WhileNotEqual,
+            _ => StringLoop::WhileEqual,
+        }
+    }
+}
+
+/// Returns how far RSI/RDI advance per element of `size`.
+pub fn element_bytes(size: MemorySize) -> i32 {
+    match size {
+        MemorySize::_8 => 1,
+        MemorySize::_16 => 2,
+        MemorySize::_32 => 4,
+        _ => 8,
+    }
+}
+
+/// Returns the left shift that moves a zero-extended element of `size` to
+/// the top of a 64-bit register.
+///
+/// Comparing shifted elements gives the same sign, zero, carry and overflow
+/// results as an x86-64 comparison at the element's own width.
+pub fn compare_shift(size: MemorySize) -> u64 {
+    64 - 8 * element_bytes(size) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loops_follow_prefix_and_operation() {
+        assert_eq!(StringLoop::new(RepPrefix::None, true), StringLoop::Once);
+        assert_eq!(StringLoop::new(RepPrefix::Repne, false), StringLoop::Count);
+        assert_eq!(
+            StringLoop::new(RepPrefix::Rep, true),
+            StringLoop::WhileEqual
+        );
+        assert_eq!(
+            StringLoop::new(RepPrefix::Repne, true),
+            StringLoop::WhileNotEqual
+        );
+        assert_eq!(element_bytes(MemorySize::_16), 2);
+        assert_eq!(compare_shift(MemorySize::_8), 56);
+        assert_eq!(compare_shift(MemorySize::_64), 0);
+    }
+}
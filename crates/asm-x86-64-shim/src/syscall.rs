@@ -0,0 +1,221 @@
+//! System call translation options shared by the translation shims.
+//!
+//! x86-64 Linux takes the system call number in RAX and its arguments in RDI,
+//! RSI, RDX, R10, R8 and R9. AArch64 (`svc #0`) and RISC-V (`ecall`) Linux
+//! take the number in X8/A7 and the arguments in X0-X5/A0-A5, and both use
+//! the generic (`asm-generic/unistd.h`) numbering, which differs from the
+//! x86-64 one. [`SyscallMode::Linux`] makes the shims remap both at run time
+//! through a table of [`LINUX_NUMBERS`] that the shim emits once.
+//!
+//! The generic table omits calls that have an `*at` replacement (`open`,
+//! `stat`, `mkdir`, ...) and legacy calls (`fork`, `pause`, `alarm`, ...).
+//! `clone` is omitted too: its TLS and child TID arguments are swapped on
+//! the generic ABI. Unmapped calls return `-ENOSYS` without entering the
+//! kernel, as an x86-64 kernel does for an unknown number.
+
+/// How a shim translates the guest `syscall` instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SyscallMode {
+    /// Emit the target's trap (`svc #0` / `ecall`) with the guest registers
+    /// as they are mapped, for a runtime whose trap handler implements the
+    /// x86-64 convention.
+    #[default]
+    Trap,
+    /// Remap the number and arguments to the target's Linux convention and
+    /// move the result back into RAX.
+    Linux,
+}
+
+/// Marks an x86-64 number in [`LINUX_NUMBERS`] with no generic equivalent.
+pub const NO_SYSCALL: u16 = u16::MAX;
+
+/// Value returned in RAX for unmapped calls (`-ENOSYS`).
+pub const ENOSYS_RESULT: i64 = -38;
+
+/// `(x86-64, generic)` number pairs.
+const LINUX_PAIRS: &[(u16, u16)] = &[
+    (0, 63),    // read
+    (1, 64),    // write
+    (3, 57),    // close
+    (5, 80),    // fstat
+    (8, 62),    // lseek
+    (9, 222),   // mmap
+    (10, 226),  // mprotect
+    (11, 215),  // munmap
+    (12, 214),  // brk
+    (13, 134),  // rt_sigaction
+    (14, 135),  // rt_sigprocmask
+    (15, 139),  // rt_sigreturn
+    (16, 29),   // ioctl
+    (17, 67),   // pread64
+    (18, 68),   // pwrite64
+    (19, 65),   // readv
+    (20, 66),   // writev
+    (24, 124),  // sched_yield
+    (25, 216),  // mremap
+    (26, 227),  // msync
+    (27, 232),  // mincore
+    (28, 233),  // madvise
+    (29, 194),  // shmget
+    (30, 196),  // shmat
+    (31, 195),  // shmctl
+    (32, 23),   // dup
+    (35, 101),  // nanosleep
+    (36, 102),  // getitimer
+    (38, 103),  // setitimer
+    (39, 172),  // getpid
+    (40, 71),   // sendfile
+    (41, 198),  // socket
+    (42, 203),  // connect
+    (43, 202),  // accept
+    (44, 206),  // sendto
+    (45, 207),  // recvfrom
+    (46, 211),  // sendmsg
+    (47, 212),  // recvmsg
+    (48, 210),  // shutdown
+    (49, 200),  // bind
+    (50, 201),  // listen
+    (51, 204),  // getsockname
+    (52, 205),  // getpeername
+    (53, 199),  // socketpair
+    (54, 208),  // setsockopt
+    (55, 209),  // getsockopt
+    (59, 221),  // execve
+    (60, 93),   // exit
+    (61, 260),  // wait4
+    (62, 129),  // kill
+    (63, 160),  // uname
+    (64, 190),  // semget
+    (65, 193),  // semop
+    (66, 191),  // semctl
+    (67, 197),  // shmdt
+    (68, 186),  // msgget
+    (69, 189),  // msgsnd
+    (70, 188),  // msgrcv
+    (71, 187),  // msgctl
+    (72, 25),   // fcntl
+    (73, 32),   // flock
+    (74, 82),   // fsync
+    (75, 83),   // fdatasync
+    (76, 45),   // truncate
+    (77, 46),   // ftruncate
+    (79, 17),   // getcwd
+    (80, 49),   // chdir
+    (81, 50),   // fchdir
+    (91, 52),   // fchmod
+    (93, 55),   // fchown
+    (95, 166),  // umask
+    (96, 169),  // gettimeofday
+    (97, 163),  // getrlimit
+    (98, 165),  // getrusage
+    (99, 179),  // sysinfo
+    (100, 153), // times
+    (101, 117), // ptrace
+    (102, 174), // getuid
+    (103, 116), // syslog
+    (104, 176), // getgid
+    (105, 146), // setuid
+    (106, 144), // setgid
+    (107, 175), // geteuid
+    (108, 177), // getegid
+    (109, 154), // setpgid
+    (110, 173), // getppid
+    (112, 157), // setsid
+    (113, 145), // setreuid
+    (114, 143), // setregid
+    (115, 158), // getgroups
+    (116, 159), // setgroups
+    (117, 147), // setresuid
+    (118, 148), // getresuid
+    (119, 149), // setresgid
+    (120, 150), // getresgid
+    (121, 155), // getpgid
+    (122, 151), // setfsuid
+    (123, 152), // setfsgid
+    (124, 156), // getsid
+    (125, 90),  // capget
+    (126, 91),  // capset
+    (127, 136), // rt_sigpending
+    (128, 137), // rt_sigtimedwait
+    (129, 138), // rt_sigqueueinfo
+    (130, 133), // rt_sigsuspend
+    (131, 132), // sigaltstack
+    (186, 178), // gettid
+    (200, 130), // tkill
+    (202, 98),  // futex
+    (217, 61),  // getdents64
+    (218, 96),  // set_tid_address
+    (228, 113), // clock_gettime
+    (229, 114), // clock_getres
+    (230, 115), // clock_nanosleep
+    (231, 94),  // exit_group
+    (234, 131), // tgkill
+    (257, 56),  // openat
+    (258, 34),  // mkdirat
+    (262, 79),  // newfstatat
+    (263, 35),  // unlinkat
+    (264, 38),  // renameat
+    (267, 78),  // readlinkat
+    (268, 53),  // fchmodat
+    (269, 48),  // faccessat
+    (270, 72),  // pselect6
+    (271, 73),  // ppoll
+    (273, 99),  // set_robust_list
+    (292, 24),  // dup3
+    (293, 59),  // pipe2
+    (302, 261), // prlimit64
+    (318, 278), // getrandom
+];
+
+/// Number of entries in [`LINUX_NUMBERS`]: one past the highest mapped
+/// x86-64 number.
+pub const LINUX_TABLE_LEN: usize = 319;
+
+/// Generic Linux number of each x86-64 number, or [`NO_SYSCALL`].
+pub const LINUX_NUMBERS: [u16; LINUX_TABLE_LEN] = {
+    let mut table = [NO_SYSCALL; LINUX_TABLE_LEN];
+    let mut i = 0;
+    while i < LINUX_PAIRS.len() {
+        let (x86, generic) = LINUX_PAIRS[i];
+        table[x86 as usize] = generic;
+        i += 1;
+    }
+    table
+};
+
+/// Returns the generic Linux number of x86-64 system call `x86`, if any.
+pub fn linux_number(x86: u64) -> Option<u16> {
+    let generic = *LINUX_NUMBERS.get(usize::try_from(x86).ok()?)?;
+    (generic != NO_SYSCALL).then_some(generic)
+}
+
+/// Returns [`LINUX_NUMBERS`] as bytes in the target's byte order.
+pub fn linux_table_bytes(big_endian: bool) -> [u8; 2 * LINUX_TABLE_LEN] {
+    let mut bytes = [0; 2 * LINUX_TABLE_LEN];
+    for (chunk, number) in bytes.chunks_exact_mut(2).zip(LINUX_NUMBERS) {
+        let encoded = if big_endian {
+            number.to_be_bytes()
+        } else {
+            number.to_le_bytes()
+        };
+        chunk.copy_from_slice(&encoded);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_common_calls_to_generic_numbers() {
+        assert_eq!(linux_number(0), Some(63)); // read
+        assert_eq!(linux_number(231), Some(94)); // exit_group
+        assert_eq!(linux_number(318), Some(278)); // getrandom
+        assert_eq!(linux_number(2), None); // open
+        assert_eq!(linux_number(56), None); // clone
+        assert_eq!(linux_number(u64::MAX), None);
+        assert_eq!(linux_table_bytes(true)[..2], [0, 63]);
+        assert_eq!(linux_table_bytes(false)[4..6], [0xFF, 0xFF]);
+    }
+}
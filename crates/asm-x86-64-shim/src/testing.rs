@@ -0,0 +1,1089 @@
+//! Golden-output harness for translated instruction streams.
+//!
+//! [`Trace`] is a text sink that parses what a writer emits into a
+//! structured log: one [`Entry`] per label or instruction, each instruction
+//! split into its mnemonic and operands. Every backend has a text writer, so
+//! the same harness covers the shims, the desugarers and the writers under
+//! them:
+//!
+//! ```ignore
+//! let x64 = X64Arch::default();
+//! let trace = trace(|w, ctx| w.mov(ctx, x64, &Reg(0), &Reg(1)), AArch64Arch::default(), &mut ())?;
+//! trace.assert_golden("mov x0, x1\n");
+//! ```
+//!
+//! Golden text goes through the same parser, so snapshots compare operand by
+//! operand and ignore spacing and blank lines. A swapped source and
+//! destination is a mismatch even where a `contains` check on the output
+//! would still pass. [`Trace::diff`] aligns two traces like a line diff, so
+//! an inserted or dropped instruction shows up as one edit rather than
+//! shifting everything after it. `Display` writes a trace back out in the
+//! golden format, ready to be stored as a new snapshot.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter, Write};
+
+use portal_solutions_asm_aarch64::AArch64Arch;
+use portal_solutions_asm_riscv64::RiscV64Arch;
+use portal_solutions_asm_x86_64::out::Writer as X64Writer;
+
+/// One line of an instruction stream.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Entry {
+    /// A label definition (`name:`).
+    Label(String),
+    /// An instruction or assembler directive.
+    Insn {
+        /// The mnemonic, such as `ldr` or `.4byte`.
+        mnemonic: String,
+        /// The operands in order, as written.
+        operands: Vec<String>,
+    },
+}
+
+impl Entry {
+    /// Parses one line of assembly text, or returns `None` for a blank line.
+    pub fn parse(line: &str) -> Option<Entry> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        if let Some(name) = line.strip_suffix(':') {
+            if !name.contains(char::is_whitespace) {
+                return Some(Entry::Label(name.into()));
+            }
+        }
+        let (mnemonic, operands) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        Some(Entry::Insn {
+            mnemonic: mnemonic.into(),
+            operands: split_operands(operands.trim()),
+        })
+    }
+}
+
+/// Splits `text` at the commas outside brackets, braces and parentheses, so
+/// `x0, [x1, #8]` is two operands.
+fn split_operands(text: &str) -> Vec<String> {
+    let mut operands = Vec::new();
+    if text.is_empty() {
+        return operands;
+    }
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in text.char_indices() {
+        match c {
+            '[' | '(' | '{' => depth += 1,
+            ']' | ')' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                operands.push(text[start..i].trim().into());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    operands.push(text[start..].trim().into());
+    operands
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Label(name) => write!(f, "{name}:"),
+            Entry::Insn { mnemonic, operands } => {
+                f.write_str(mnemonic)?;
+                for (i, operand) in operands.iter().enumerate() {
+                    f.write_str(if i == 0 { " " } else { ", " })?;
+                    f.write_str(operand)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A parsed instruction stream, and a text sink that writers emit into.
+///
+/// Only complete lines are parsed; text after the last newline is held back
+/// until the line ends.
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    entries: Vec<Entry>,
+    /// The unterminated last line.
+    line: String,
+}
+
+impl Trace {
+    /// Creates an empty trace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses assembly text, such as a golden snapshot.
+    pub fn parse(text: &str) -> Self {
+        Trace {
+            entries: text.lines().filter_map(Entry::parse).collect(),
+            line: String::new(),
+        }
+    }
+
+    /// The labels and instructions recorded so far.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Aligns this trace against `golden`.
+    ///
+    /// The edits cover both traces in order, keeping as many entries as
+    /// possible [`Same`](Edit::Same).
+    pub fn diff(&self, golden: &Trace) -> Vec<Edit> {
+        let (old, new) = (&golden.entries, &self.entries);
+        // lcs[i][j] is the longest common subsequence of old[i..] and new[j..].
+        let width = new.len() + 1;
+        let mut lcs = alloc::vec![0usize; (old.len() + 1) * width];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i * width + j] = if old[i] == new[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let mut edits = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() && j < new.len() {
+            if old[i] == new[j] {
+                edits.push(Edit::Same(old[i].clone()));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                edits.push(Edit::Removed(old[i].clone()));
+                i += 1;
+            } else {
+                edits.push(Edit::Added(new[j].clone()));
+                j += 1;
+            }
+        }
+        edits.extend(old[i..].iter().cloned().map(Edit::Removed));
+        edits.extend(new[j..].iter().cloned().map(Edit::Added));
+        edits
+    }
+
+    /// Returns `true` if this trace has the same entries as `golden`.
+    pub fn matches(&self, golden: &Trace) -> bool {
+        self.entries == golden.entries
+    }
+
+    /// Panics with a line diff unless this trace matches the `golden` text.
+    #[track_caller]
+    pub fn assert_golden(&self, golden: &str) {
+        let golden = Trace::parse(golden);
+        if !self.matches(&golden) {
+            let mut report = String::new();
+            for edit in self.diff(&golden) {
+                let _ = writeln!(report, "{edit}");
+            }
+            panic!("trace does not match the golden output (-golden +actual):\n{report}");
+        }
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for Trace {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.line.push_str(s);
+        while let Some(end) = self.line.find('\n') {
+            let rest = self.line.split_off(end + 1);
+            self.entries.extend(Entry::parse(&self.line));
+            self.line = rest;
+        }
+        Ok(())
+    }
+}
+
+/// One line of a [`Trace::diff`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Edit {
+    /// In both traces.
+    Same(Entry),
+    /// Only in the golden trace.
+    Removed(Entry),
+    /// Only in the traced output.
+    Added(Entry),
+}
+
+impl Display for Edit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Edit::Same(entry) => write!(f, "  {entry}"),
+            Edit::Removed(entry) => write!(f, "- {entry}"),
+            Edit::Added(entry) => write!(f, "+ {entry}"),
+        }
+    }
+}
+
+/// A target architecture whose shim output can be traced.
+pub trait TraceTarget: Copy {
+    /// Translates `guest` with this target's shim into a [`Trace`].
+    fn trace<L: Display, Context>(
+        self,
+        ctx: &mut Context,
+        guest: &mut dyn FnMut(
+            &mut (dyn X64Writer<L, Context, Error = fmt::Error> + '_),
+            &mut Context,
+        ) -> fmt::Result,
+    ) -> Result<Trace, fmt::Error>;
+}
+
+/// Traces the target instructions `guest` translates to for `arch_cfg`.
+///
+/// `guest` receives an x86-64 writer and may emit any number of guest
+/// instructions.
+pub fn trace<A: TraceTarget, L: Display, Context>(
+    mut guest: impl FnMut(
+        &mut (dyn X64Writer<L, Context, Error = fmt::Error> + '_),
+        &mut Context,
+    ) -> fmt::Result,
+    arch_cfg: A,
+    ctx: &mut Context,
+) -> Result<Trace, fmt::Error> {
+    arch_cfg.trace(ctx, &mut guest)
+}
+
+impl TraceTarget for AArch64Arch {
+    fn trace<L: Display, Context>(
+        self,
+        ctx: &mut Context,
+        guest: &mut dyn FnMut(
+            &mut (dyn X64Writer<L, Context, Error = fmt::Error> + '_),
+            &mut Context,
+        ) -> fmt::Result,
+    ) -> Result<Trace, fmt::Error> {
+        let mut trace = Trace::new();
+        let sink: &mut dyn Write = &mut trace;
+        let mut shim = crate::aarch64::X64ToAArch64Shim::with_config(sink, self);
+        guest(&mut shim, ctx)?;
+        Ok(trace)
+    }
+}
+
+impl TraceTarget for RiscV64Arch {
+    fn trace<L: Display, Context>(
+        self,
+        ctx: &mut Context,
+        guest: &mut dyn FnMut(
+            &mut (dyn X64Writer<L, Context, Error = fmt::Error> + '_),
+            &mut Context,
+        ) -> fmt::Result,
+    ) -> Result<Trace, fmt::Error> {
+        let mut trace = Trace::new();
+        let sink: &mut dyn Write = &mut trace;
+        let mut shim = crate::riscv64::X64ToRiscV64Shim::with_config(sink, self);
+        guest(&mut shim, ctx)?;
+        Ok(trace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use portal_pc_asm_common::types::reg::Reg;
+    use portal_solutions_asm_x86_64::X64Arch;
+
+    type Guest<'a> = dyn X64Writer<usize, (), Error = fmt::Error> + 'a;
+
+    fn insn(mnemonic: &str, operands: &[&str]) -> Entry {
+        Entry::Insn {
+            mnemonic: mnemonic.into(),
+            operands: operands.iter().map(|&op| op.into()).collect(),
+        }
+    }
+
+    #[test]
+    fn parses_operands_and_labels() {
+        let trace = Trace::parse("  ldr x0, [x1, #8]\n\n7:\nret\n");
+        assert_eq!(
+            trace.entries(),
+            [
+                insn("ldr", &["x0", "[x1, #8]"]),
+                Entry::Label("7".into()),
+                insn("ret", &[]),
+            ]
+        );
+        assert_eq!(alloc::format!("{trace}"), "ldr x0, [x1, #8]\n7:\nret\n");
+    }
+
+    #[test]
+    fn diff_reports_swapped_operands() {
+        let golden = Trace::parse("mov x0, x1\nadd x0, x0, x2\n");
+        let actual = Trace::parse("mov x1, x0\nadd x0, x0, x2\n");
+        assert!(!actual.matches(&golden));
+        assert_eq!(
+            actual.diff(&golden),
+            [
+                Edit::Removed(insn("mov", &["x0", "x1"])),
+                Edit::Added(insn("mov", &["x1", "x0"])),
+                Edit::Same(insn("add", &["x0", "x0", "x2"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn traces_shim_output() {
+        let x64 = X64Arch::default();
+        let mov = |w: &mut Guest<'_>, ctx: &mut ()| w.mov(ctx, x64, &Reg(0), &Reg(1));
+        let trace = trace(mov, AArch64Arch::default(), &mut ()).unwrap();
+        trace.assert_golden("mov x0, x1");
+    }
+
+    #[test]
+    fn lse_exchange_is_a_single_swap() {
+        use portal_pc_asm_common::types::mem::MemorySize;
+        use portal_solutions_asm_x86_64::RegisterClass;
+        use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind, Segment};
+
+        let x64 = X64Arch::default();
+        let mem = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(3),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 8,
+            size: MemorySize::_64,
+            reg_class: RegisterClass::Gpr,
+            segment: Segment::None,
+        };
+        let xchg = |w: &mut Guest<'_>, ctx: &mut ()| w.xchg(ctx, x64, &Reg(0), &mem);
+        let lse = AArch64Arch::default().with_lse(true);
+        let trace = trace(xchg, lse, &mut ()).unwrap();
+        trace.assert_golden("add x18, x19, #8\nswpal x0, x0, [x18]\n");
+    }
+
+    #[test]
+    fn pause_becomes_yield() {
+        let x64 = X64Arch::default();
+        let pause = |w: &mut Guest<'_>, ctx: &mut ()| w.pause(ctx, x64);
+        let trace = trace(pause, AArch64Arch::default(), &mut ()).unwrap();
+        trace.assert_golden("yield\n");
+    }
+
+    #[test]
+    fn prefetch_hints_become_prfm_operations() {
+        use portal_pc_asm_common::types::mem::MemorySize;
+        use portal_solutions_asm_x86_64::RegisterClass;
+        use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind, Segment};
+
+        let x64 = X64Arch::default();
+        let mem = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(3),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 64,
+            size: MemorySize::_8,
+            reg_class: RegisterClass::Gpr,
+            segment: Segment::None,
+        };
+        let prefetch = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.prefetcht0(ctx, x64, &mem)?;
+            w.prefetchnta(ctx, x64, &mem)
+        };
+        let trace = trace(prefetch, AArch64Arch::default(), &mut ()).unwrap();
+        trace.assert_golden("prfm pldl1keep, [x19, #64]\nprfm pldl1strm, [x19, #64]\n");
+    }
+
+    #[test]
+    fn cache_line_flush_becomes_dc_civac() {
+        use portal_pc_asm_common::types::mem::MemorySize;
+        use portal_solutions_asm_x86_64::RegisterClass;
+        use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind, Segment};
+
+        let x64 = X64Arch::default();
+        let mem = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(3),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 64,
+            size: MemorySize::_8,
+            reg_class: RegisterClass::Gpr,
+            segment: Segment::None,
+        };
+        let flush = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.clflush(ctx, x64, &mem)?;
+            w.sfence(ctx, x64)
+        };
+        let trace = trace(flush, AArch64Arch::default(), &mut ()).unwrap();
+        trace.assert_golden("add x18, x19, #64\ndc civac, x18\ndmb ishst\n");
+
+        // Apple and Windows reserve x18, so the address goes through x14
+        let mut apple = AArch64Arch::default();
+        apple.os = portal_solutions_asm_aarch64::TargetOs::Apple;
+        let apple_trace = super::trace(flush, apple, &mut ()).unwrap();
+        apple_trace.assert_golden("add x14, x19, #64\ndc civac, x14\ndmb ishst\n");
+    }
+
+    #[test]
+    fn rdtsc_splits_the_counter_into_edx_eax() {
+        let x64 = X64Arch::default();
+        let rdtsc = |w: &mut Guest<'_>, ctx: &mut ()| w.rdtsc(ctx, x64);
+        let trace = trace(rdtsc, AArch64Arch::default(), &mut ()).unwrap();
+        trace.assert_golden("mrs x2, cntvct_el0\nubfx x0, x2, #0, #32\nlsr x2, x2, #32\n");
+    }
+
+    #[test]
+    fn neg_of_the_stack_pointer_goes_through_x16() {
+        let x64 = X64Arch::default();
+        let neg = |w: &mut Guest<'_>, ctx: &mut ()| w.neg(ctx, x64, &Reg(4));
+        let trace = trace(neg, AArch64Arch::default(), &mut ()).unwrap();
+        trace.assert_golden("add x16, sp, #0\nnegs x16, x16\nadd sp, x16, #0\n");
+    }
+
+    #[test]
+    fn imul2_becomes_mul() {
+        let x64 = X64Arch::default();
+        let imul = |w: &mut Guest<'_>, ctx: &mut ()| w.imul2(ctx, x64, &Reg(0), &Reg(1));
+        trace(imul, AArch64Arch::default(), &mut ())
+            .unwrap()
+            .assert_golden("mul x0, x0, x1\n");
+        trace(imul, RiscV64Arch::default(), &mut ())
+            .unwrap()
+            .assert_golden("mul a0, a0, a1\n");
+    }
+
+    #[test]
+    fn zbb_rotates_are_native() {
+        use portal_solutions_asm_x86_64::out::arg::ArgKind;
+
+        let x64 = X64Arch::default();
+        let rotates = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.rol(ctx, x64, &Reg(0), &Reg(1))?;
+            w.rol(ctx, x64, &Reg(0), &ArgKind::Lit(8))?;
+            w.ror(ctx, x64, &Reg(0), &ArgKind::Lit(8))
+        };
+        trace(rotates, RiscV64Arch::default().with_zbb(true), &mut ())
+            .unwrap()
+            .assert_golden("rol a0, a0, a1\nrori a0, a0, 56\nrori a0, a0, 8\n");
+    }
+
+    #[test]
+    fn untranslatable_register_class_is_an_error() {
+        use portal_pc_asm_common::types::mem::MemorySize;
+        use portal_solutions_asm_x86_64::RegisterClass;
+        use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind, Segment};
+
+        let x64 = X64Arch::default();
+        let ymm = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(3),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_64,
+            reg_class: RegisterClass::Ymm,
+            segment: Segment::None,
+        };
+        let load = |w: &mut Guest<'_>, ctx: &mut ()| w.mov(ctx, x64, &Reg(0), &ymm);
+        assert!(trace(load, AArch64Arch::default(), &mut ()).is_err());
+        assert!(trace(load, RiscV64Arch::default(), &mut ()).is_err());
+    }
+
+    #[test]
+    fn high_byte_moves_insert_and_extract_bits_8_to_15() {
+        use portal_solutions_asm_x86_64::out::arg::ArgKind;
+
+        let x64 = X64Arch::default();
+        let ah = ArgKind::HighByte(Reg(0));
+        let insert = |w: &mut Guest<'_>, ctx: &mut ()| w.mov(ctx, x64, &ah, &Reg(1));
+        let extract = |w: &mut Guest<'_>, ctx: &mut ()| w.mov(ctx, x64, &Reg(1), &ah);
+        let zero_extend = |w: &mut Guest<'_>, ctx: &mut ()| w.movzx(ctx, x64, &Reg(1), &ah);
+        let sign_extend = |w: &mut Guest<'_>, ctx: &mut ()| w.movsx(ctx, x64, &Reg(1), &ah);
+
+        let aarch64 = AArch64Arch::default();
+        trace(insert, aarch64, &mut ())
+            .unwrap()
+            .assert_golden("mov x16, x1\nbfi x0, x16, #8, #8\n");
+        trace(extract, aarch64, &mut ())
+            .unwrap()
+            .assert_golden("ubfx x16, x0, #8, #8\nbfi x1, x16, #0, #8\n");
+        trace(zero_extend, aarch64, &mut ())
+            .unwrap()
+            .assert_golden("ubfx x1, x0, #8, #8\n");
+        trace(sign_extend, aarch64, &mut ())
+            .unwrap()
+            .assert_golden("lsl x1, x0, #48\nasr x1, x1, #56\n");
+
+        let riscv = RiscV64Arch::default();
+        trace(insert, riscv, &mut ()).unwrap().assert_golden(
+            "mv t5, a1\nsll t5, t5, 8\nxor t5, t5, a0\nsrl t5, t5, 8\n\
+             andi t5, t5, 255\nsll t5, t5, 8\nxor a0, a0, t5\n",
+        );
+        trace(extract, riscv, &mut ()).unwrap().assert_golden(
+            "srl t5, a0, 8\nandi t5, t5, 255\nxor t5, t5, a1\nandi t5, t5, 255\nxor a1, a1, t5\n",
+        );
+        trace(zero_extend, riscv, &mut ())
+            .unwrap()
+            .assert_golden("srl a1, a0, 8\nandi a1, a1, 255\n");
+        trace(sign_extend, riscv, &mut ())
+            .unwrap()
+            .assert_golden("sll a1, a0, 48\nsra a1, a1, 56\n");
+
+        // Other instructions have no high-byte lowering
+        let add = |w: &mut Guest<'_>, ctx: &mut ()| w.add(ctx, x64, &ah, &Reg(1));
+        assert!(trace(add, aarch64, &mut ()).is_err());
+        assert!(trace(add, riscv, &mut ()).is_err());
+    }
+
+    #[test]
+    fn segment_operands_without_a_thread_pointer_slot_are_errors() {
+        use portal_pc_asm_common::types::mem::MemorySize;
+        use portal_solutions_asm_x86_64::RegisterClass;
+        use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind, Segment};
+
+        let x64 = X64Arch::default();
+        let rbx = ArgKind::Reg {
+            reg: Reg(3),
+            size: MemorySize::_64,
+        };
+        let mem = |segment, offset| MemArgKind::Mem {
+            base: rbx,
+            offset,
+            disp: 0,
+            size: MemorySize::_64,
+            reg_class: RegisterClass::Gpr,
+            segment,
+        };
+        let gs = mem(Segment::Gs, None);
+        let fs_indexed = mem(Segment::Fs, Some((rbx, 8)));
+        let fs = mem(Segment::Fs, None);
+        for op in [&gs, &fs_indexed] {
+            let load = |w: &mut Guest<'_>, ctx: &mut ()| w.mov(ctx, x64, &Reg(0), op);
+            assert!(trace(load, AArch64Arch::default(), &mut ()).is_err());
+            assert!(trace(load, RiscV64Arch::default(), &mut ()).is_err());
+        }
+        let load = |w: &mut Guest<'_>, ctx: &mut ()| w.mov(ctx, x64, &Reg(0), &fs);
+        assert!(trace(load, AArch64Arch::default(), &mut ()).is_ok());
+        assert!(trace(load, RiscV64Arch::default(), &mut ()).is_ok());
+    }
+
+    #[test]
+    fn rip_operands_are_errors_and_base_less_tls_rebases_onto_tp() {
+        use portal_pc_asm_common::types::mem::MemorySize;
+        use portal_solutions_asm_x86_64::out::arg::MemArgKind;
+
+        let x64 = X64Arch::default();
+        let rip = MemArgKind::rip(16, MemorySize::_64);
+        let load = |w: &mut Guest<'_>, ctx: &mut ()| w.mov(ctx, x64, &Reg(0), &rip);
+        assert!(trace(load, AArch64Arch::default(), &mut ()).is_err());
+        assert!(trace(load, RiscV64Arch::default(), &mut ()).is_err());
+
+        let tls = MemArgKind::tls(16, MemorySize::_64);
+        let load = |w: &mut Guest<'_>, ctx: &mut ()| w.mov(ctx, x64, &Reg(0), &tls);
+        let riscv = trace(load, RiscV64Arch::default(), &mut ()).unwrap();
+        assert!(riscv.entries().contains(&insn("ld", &["a0", "16(tp)"])));
+    }
+
+    #[test]
+    fn div_fault_handlers_do_not_collide_with_shim_labels() {
+        use crate::faults::DivFaultMode;
+
+        let x64 = X64Arch::default();
+        let handler = crate::aarch64::ShimLabel::user(0);
+        assert_ne!(handler, crate::aarch64::ShimLabel(0));
+        assert_eq!(alloc::format!("{handler}"), ".Luser_0");
+        let branches_to_handler = |trace: &Trace| {
+            trace.entries().iter().any(|entry| match entry {
+                Entry::Insn { operands, .. } => operands.iter().any(|op| op == ".Luser_0"),
+                Entry::Label(_) => false,
+            })
+        };
+
+        let mut aarch64 = Trace::new();
+        {
+            let sink: &mut dyn Write = &mut aarch64;
+            let mut shim = crate::aarch64::X64ToAArch64Shim::new(sink)
+                .with_div_fault(DivFaultMode::Branch(handler))
+                .unwrap();
+            let guest: &mut Guest<'_> = &mut shim;
+            guest.div(&mut (), x64, &Reg(0), &Reg(1)).unwrap();
+        }
+        assert!(branches_to_handler(&aarch64));
+        assert!(aarch64.entries().contains(&Entry::Label(".Lshim_0".into())));
+
+        let mut riscv = Trace::new();
+        {
+            let sink: &mut dyn Write = &mut riscv;
+            let mut shim = crate::riscv64::X64ToRiscV64Shim::new(sink)
+                .with_div_fault(DivFaultMode::Branch(crate::riscv64::ShimLabel::user(0)))
+                .unwrap();
+            let guest: &mut Guest<'_> = &mut shim;
+            guest.div(&mut (), x64, &Reg(0), &Reg(1)).unwrap();
+        }
+        assert!(branches_to_handler(&riscv));
+        assert!(riscv.entries().contains(&Entry::Label(".Lshim_0".into())));
+    }
+
+    #[test]
+    fn div_fault_rejects_generated_labels() {
+        let shim = crate::aarch64::X64ToAArch64Shim::new(String::new()).with_div_fault(
+            crate::faults::DivFaultMode::Branch(crate::aarch64::ShimLabel(0)),
+        );
+        assert!(shim.is_err());
+        let shim = crate::riscv64::X64ToRiscV64Shim::new(String::new()).with_div_fault(
+            crate::faults::DivFaultMode::Branch(crate::riscv64::ShimLabel(0)),
+        );
+        assert!(shim.is_err());
+    }
+
+    #[test]
+    fn shadow_stack_saves_the_link_register_around_calls() {
+        use crate::guest_stack::ReturnStack;
+
+        let x64 = X64Arch::default();
+        let mut aarch64 = Trace::new();
+        {
+            let sink: &mut dyn Write = &mut aarch64;
+            let mut shim = crate::aarch64::X64ToAArch64Shim::new(sink)
+                .with_return_stack(ReturnStack::Shadow(Reg(13)))
+                .unwrap();
+            let guest: &mut Guest<'_> = &mut shim;
+            guest.call(&mut (), x64, &Reg(0)).unwrap();
+            guest.ret(&mut (), x64).unwrap();
+        }
+        aarch64.assert_golden("str x30, [x13, #-8]!\nblr x0\nldr x30, [x13], #8\nret\n");
+
+        let mut riscv = Trace::new();
+        {
+            let sink: &mut dyn Write = &mut riscv;
+            let mut shim = crate::riscv64::X64ToRiscV64Shim::new(sink)
+                .with_return_stack(ReturnStack::Shadow(Reg(19)))
+                .unwrap();
+            let guest: &mut Guest<'_> = &mut shim;
+            guest.call(&mut (), x64, &Reg(0)).unwrap();
+            guest.ret(&mut (), x64).unwrap();
+        }
+        riscv.assert_golden(
+            "addi s3, s3, -8\nsd ra, 0(s3)\ncall a0\nld ra, 0(s3)\naddi s3, s3, 8\nret\n",
+        );
+    }
+
+    #[test]
+    fn post_hook_sees_the_bytes_each_instruction_emitted() {
+        use crate::aarch64::{ShimLabel, X64ToAArch64Shim};
+        use crate::hooks::{FnHooks, GuestInstruction};
+        use portal_solutions_asm_aarch64::out::WriterCore;
+        use portal_solutions_asm_aarch64::out::bin::AArch64Writer;
+        use portal_solutions_asm_aarch64::out::error::EmitError;
+        use portal_solutions_asm_x86_64::out::WriterCore as X64WriterCore;
+
+        type Host = AArch64Writer<ShimLabel>;
+        let x64 = X64Arch::default();
+        let mut emitted = Vec::new();
+        {
+            // The pre-instruction counter bump is hook code and is not counted.
+            let hooks = FnHooks::new(
+                |w: &mut Host, ctx: &mut (), cfg: AArch64Arch, _: GuestInstruction| {
+                    w.add_imm(ctx, cfg, &Reg(13), &Reg(13), 1)
+                },
+                |_: &mut Host, _: &mut (), _: AArch64Arch, insn: GuestInstruction| {
+                    emitted.push(insn.emitted_bytes);
+                    Ok::<(), EmitError>(())
+                },
+            );
+            let mut shim = X64ToAArch64Shim::new(Host::new()).with_hooks(hooks);
+            shim.mov(&mut (), x64, &Reg(0), &Reg(1)).unwrap();
+            shim.ud2(&mut (), x64).unwrap();
+            assert_eq!(shim.inner.offset(), 16);
+        }
+        assert_eq!(emitted, [Some(4), Some(4)]);
+    }
+
+    #[test]
+    fn aarch64_rejects_a_guest_register_as_shadow_stack() {
+        let shim = crate::aarch64::X64ToAArch64Shim::new(String::new())
+            .with_return_stack(crate::guest_stack::ReturnStack::Shadow(Reg(27)));
+        assert!(shim.is_err());
+    }
+
+    #[test]
+    fn riscv_rejects_a_guest_register_as_shadow_stack() {
+        let shim = crate::riscv64::X64ToRiscV64Shim::new(String::new())
+            .with_return_stack(crate::guest_stack::ReturnStack::Shadow(Reg(27)));
+        assert!(shim.is_err());
+    }
+
+    #[test]
+    fn aarch64_carry_ops_see_cf_from_add_and_sub() {
+        use portal_solutions_asm_x86_64::out::arg::ArgKind;
+
+        let x64 = X64Arch::default();
+        let aarch64 = AArch64Arch::default();
+        // C holds the inverted borrow, so ADDS and ADCS flip it afterwards
+        // and ADCS flips it to CF for its carry-in
+        let add_adc = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.add(ctx, x64, &Reg(0), &Reg(1))?;
+            w.adc(ctx, x64, &Reg(2), &Reg(6))
+        };
+        trace(add_adc, aarch64, &mut ()).unwrap().assert_golden(
+            "adds x0, x0, x1\nmrs x17, nzcv\neor x17, x17, #0x20000000\nmsr nzcv, x17\n\
+             mrs x16, nzcv\neor x16, x16, #0x20000000\nmsr nzcv, x16\nadcs x2, x2, x3\n\
+             mrs x17, nzcv\neor x17, x17, #0x20000000\nmsr nzcv, x17\n",
+        );
+        let add_imm_sbb = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.add(ctx, x64, &Reg(0), &ArgKind::Lit(1))?;
+            w.sbb(ctx, x64, &Reg(2), &Reg(6))
+        };
+        trace(add_imm_sbb, aarch64, &mut ()).unwrap().assert_golden(
+            "adds x0, x0, #1\nmrs x16, nzcv\neor x16, x16, #0x20000000\nmsr nzcv, x16\n\
+             sbcs x2, x2, x3\n",
+        );
+        // SUBS and CMP already leave the inverted borrow SBCS takes
+        let cmp_sbb = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.cmp(ctx, x64, &Reg(0), &Reg(1))?;
+            w.sbb(ctx, x64, &Reg(2), &Reg(6))
+        };
+        trace(cmp_sbb, aarch64, &mut ())
+            .unwrap()
+            .assert_golden("cmp x0, x1\nsbcs x2, x2, x3\n");
+        let sub_adc = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.sub(ctx, x64, &Reg(0), &Reg(1))?;
+            w.adc(ctx, x64, &Reg(2), &Reg(6))
+        };
+        trace(sub_adc, aarch64, &mut ()).unwrap().assert_golden(
+            "subs x0, x0, x1\nmrs x16, nzcv\neor x16, x16, #0x20000000\nmsr nzcv, x16\n\
+             adcs x2, x2, x3\nmrs x17, nzcv\neor x17, x17, #0x20000000\nmsr nzcv, x17\n",
+        );
+    }
+
+    #[test]
+    fn riscv_carry_chains_read_cf_from_s1() {
+        use crate::flags::FlagTracking;
+
+        let x64 = X64Arch::default();
+        let mut riscv = Trace::new();
+        {
+            let sink: &mut dyn Write = &mut riscv;
+            let mut shim =
+                crate::riscv64::X64ToRiscV64Shim::new(sink).with_flag_tracking(FlagTracking::Full);
+            let guest: &mut Guest<'_> = &mut shim;
+            guest.add(&mut (), x64, &Reg(0), &Reg(1)).unwrap();
+            guest.adc(&mut (), x64, &Reg(2), &Reg(6)).unwrap();
+            guest.cmp(&mut (), x64, &Reg(0), &Reg(1)).unwrap();
+            guest.sbb(&mut (), x64, &Reg(2), &Reg(6)).unwrap();
+        }
+        riscv.assert_golden(
+            "add t5, a0, a1\nsltu s1, t5, a0\nmv a0, t5\n\
+             andi s1, s1, 1\nadd a2, a2, s1\nsltu t5, a2, s1\n\
+             add a2, a2, a3\nsltu s1, a2, a3\nor s1, s1, t5\n\
+             sub t6, a0, a1\nxor t5, a0, a1\nxor s1, a0, t6\nand t5, t5, s1\n\
+             srl t5, t5, 63\nsll t5, t5, 11\nsltu s1, a0, t6\nor s1, s1, t5\n\
+             andi s1, s1, 1\nsltu t5, a2, s1\nsub a2, a2, s1\n\
+             sltu s1, a2, a3\nsub a2, a2, a3\nor s1, s1, t5\n",
+        );
+
+        // Without the recorded carry there is nothing to chain
+        let adc = |w: &mut Guest<'_>, ctx: &mut ()| w.adc(ctx, x64, &Reg(2), &Reg(6));
+        assert!(trace(adc, RiscV64Arch::default(), &mut ()).is_err());
+    }
+
+    #[test]
+    fn aarch64_flag_transfers_see_cf_from_add_and_sub() {
+        let x64 = X64Arch::default();
+        let aarch64 = AArch64Arch::default();
+        // LAHF flips C back to CF before packing SF:ZF:0:0:0:0:1:CF into AH
+        let lahf = "mrs x16, nzcv\neor x16, x16, #0x20000000\nlsr x17, x16, #29\n\
+                    and x16, x17, #0x1\norr x16, x16, #0x2\nlsr x17, x17, #1\n\
+                    bfi x16, x17, #6, #2\nbfi x0, x16, #8, #8\n";
+        let add = "adds x0, x0, x1\nmrs x17, nzcv\neor x17, x17, #0x20000000\nmsr nzcv, x17\n";
+        let add_lahf = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.add(ctx, x64, &Reg(0), &Reg(1))?;
+            w.lahf(ctx, x64)
+        };
+        trace(add_lahf, aarch64, &mut ())
+            .unwrap()
+            .assert_golden(&format!("{add}{lahf}"));
+        let sub_lahf = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.sub(ctx, x64, &Reg(0), &Reg(1))?;
+            w.lahf(ctx, x64)
+        };
+        trace(sub_lahf, aarch64, &mut ())
+            .unwrap()
+            .assert_golden(&format!("subs x0, x0, x1\n{lahf}"));
+        // SAHF stores CF inverted into C, as SUBS would
+        let sahf_sbb = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.sahf(ctx, x64)?;
+            w.sbb(ctx, x64, &Reg(2), &Reg(6))
+        };
+        trace(sahf_sbb, aarch64, &mut ()).unwrap().assert_golden(
+            "ubfx x16, x0, #8, #8\nmrs x17, nzcv\nand x17, x17, #0x10000000\n\
+             bfi x17, x16, #29, #1\nlsr x16, x16, #6\nbfi x17, x16, #30, #2\n\
+             eor x17, x17, #0x20000000\nmsr nzcv, x17\nsbcs x2, x2, x3\n",
+        );
+        // PUSHF and POPF round-trip NZCV untouched, whichever polarity C has
+        let push_pop = "mrs x16, nzcv\nstr x16, [sp, #-8]!\nldr x16, [sp], #8\nmsr nzcv, x16\n";
+        let add_pushf_popf = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.add(ctx, x64, &Reg(0), &Reg(1))?;
+            w.pushf(ctx, x64)?;
+            w.popf(ctx, x64)
+        };
+        trace(add_pushf_popf, aarch64, &mut ())
+            .unwrap()
+            .assert_golden(&format!("{add}{push_pop}"));
+        let sub_pushf_popf = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.sub(ctx, x64, &Reg(0), &Reg(1))?;
+            w.pushf(ctx, x64)?;
+            w.popf(ctx, x64)
+        };
+        trace(sub_pushf_popf, aarch64, &mut ())
+            .unwrap()
+            .assert_golden(&format!("subs x0, x0, x1\n{push_pop}"));
+    }
+
+    #[test]
+    fn riscv_flag_transfers_see_cf_from_add_and_sub() {
+        use crate::flags::FlagTracking;
+
+        let x64 = X64Arch::default();
+        let full = |ops: &dyn Fn(&mut Guest<'_>) -> fmt::Result| {
+            let mut riscv = Trace::new();
+            {
+                let sink: &mut dyn Write = &mut riscv;
+                let mut shim = crate::riscv64::X64ToRiscV64Shim::new(sink)
+                    .with_flag_tracking(FlagTracking::Full);
+                let guest: &mut Guest<'_> = &mut shim;
+                ops(guest).unwrap();
+            }
+            riscv
+        };
+        // FLAGS is built in s1 around the CF that ADD and SUB leave there
+        let flags = "sltu t5, zero, t6\nxori t5, t5, 1\nsll t5, t5, 6\nor s1, s1, t5\n\
+                     srl t5, t6, 63\nsll t5, t5, 7\nor s1, s1, t5\nori s1, s1, 2\n";
+        let lahf = "srl t5, a0, 8\nandi t5, t5, 255\nsll t5, t5, 8\nxor a0, a0, t5\n\
+                    andi t5, s1, 215\nsll t5, t5, 8\nor a0, a0, t5\n";
+        let record = "srl t5, s1, 6\nandi t5, t5, 2\naddi t6, zero, 1\nsub t6, t6, t5\n\
+                      srl t5, s1, 6\nandi t5, t5, 1\naddi t5, t5, -1\nand t6, t6, t5\n";
+        let add = "add t5, a0, a1\nsltu s1, t5, a0\nmv a0, t5\n";
+        let sub = "sub t5, a0, a1\nsltu s1, a0, t5\nmv a0, t5\n";
+        full(&|w| {
+            w.add(&mut (), x64, &Reg(0), &Reg(1))?;
+            w.lahf(&mut (), x64)
+        })
+        .assert_golden(&format!("{add}{flags}{lahf}"));
+        full(&|w| {
+            w.sub(&mut (), x64, &Reg(0), &Reg(1))?;
+            w.lahf(&mut (), x64)
+        })
+        .assert_golden(&format!("{sub}{flags}{lahf}"));
+        // SAHF keeps CF at bit 0 of s1, where SBB reads it
+        full(&|w| {
+            w.sahf(&mut (), x64)?;
+            w.sbb(&mut (), x64, &Reg(2), &Reg(6))
+        })
+        .assert_golden(&format!(
+            "srl t5, a0, 8\nxor t5, t5, s1\nandi t5, t5, 213\nxor s1, s1, t5\n{record}\
+             andi s1, s1, 1\nsltu t5, a2, s1\nsub a2, a2, s1\n\
+             sltu s1, a2, a3\nsub a2, a2, a3\nor s1, s1, t5\n"
+        ));
+        let push_pop =
+            format!("{flags}addi sp, sp, -8\nsd s1, 0(sp)\nld s1, 0(sp)\naddi sp, sp, 8\n{record}");
+        full(&|w| {
+            w.add(&mut (), x64, &Reg(0), &Reg(1))?;
+            w.pushf(&mut (), x64)?;
+            w.popf(&mut (), x64)
+        })
+        .assert_golden(&format!("{add}{push_pop}"));
+        full(&|w| {
+            w.sub(&mut (), x64, &Reg(0), &Reg(1))?;
+            w.pushf(&mut (), x64)?;
+            w.popf(&mut (), x64)
+        })
+        .assert_golden(&format!("{sub}{push_pop}"));
+    }
+
+    #[test]
+    fn aarch64_inc_and_dec_keep_cf() {
+        let x64 = X64Arch::default();
+        let aarch64 = AArch64Arch::default();
+        // C is saved in x17 and put back into the NZCV ADDS/SUBS leave
+        let keep = "mrs x16, nzcv\nlsr x17, x17, #29\nbfi x16, x17, #29, #1\nmsr nzcv, x16\n";
+        let cmp_inc_adc = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.cmp(ctx, x64, &Reg(0), &Reg(1))?;
+            w.inc(ctx, x64, &Reg(0))?;
+            w.adc(ctx, x64, &Reg(2), &Reg(6))
+        };
+        let golden = format!(
+            "cmp x0, x1\nmrs x17, nzcv\nadds x0, x0, #1\n{keep}\
+             mrs x16, nzcv\neor x16, x16, #0x20000000\nmsr nzcv, x16\nadcs x2, x2, x3\n\
+             mrs x17, nzcv\neor x17, x17, #0x20000000\nmsr nzcv, x17\n"
+        );
+        trace(cmp_inc_adc, aarch64, &mut ())
+            .unwrap()
+            .assert_golden(&golden);
+        let add_dec_sbb = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.add(ctx, x64, &Reg(0), &Reg(1))?;
+            w.dec(ctx, x64, &Reg(0))?;
+            w.sbb(ctx, x64, &Reg(2), &Reg(6))
+        };
+        let golden = format!(
+            "adds x0, x0, x1\nmrs x17, nzcv\neor x17, x17, #0x20000000\nmsr nzcv, x17\n\
+             mrs x17, nzcv\nsubs x0, x0, #1\n{keep}sbcs x2, x2, x3\n"
+        );
+        trace(add_dec_sbb, aarch64, &mut ())
+            .unwrap()
+            .assert_golden(&golden);
+    }
+
+    #[test]
+    fn riscv_inc_and_dec_keep_cf() {
+        use crate::flags::FlagTracking;
+
+        let x64 = X64Arch::default();
+        let mut riscv = Trace::new();
+        {
+            let sink: &mut dyn Write = &mut riscv;
+            let mut shim =
+                crate::riscv64::X64ToRiscV64Shim::new(sink).with_flag_tracking(FlagTracking::Full);
+            let guest: &mut Guest<'_> = &mut shim;
+            guest.cmp(&mut (), x64, &Reg(0), &Reg(1)).unwrap();
+            guest.inc(&mut (), x64, &Reg(0)).unwrap();
+            guest.adc(&mut (), x64, &Reg(2), &Reg(6)).unwrap();
+            guest.add(&mut (), x64, &Reg(0), &Reg(1)).unwrap();
+            guest.dec(&mut (), x64, &Reg(0)).unwrap();
+            guest.sbb(&mut (), x64, &Reg(2), &Reg(6)).unwrap();
+        }
+        // CF stays in bit 0 of s1; only the OF from the CMP is dropped
+        riscv.assert_golden(
+            "sub t6, a0, a1\nxor t5, a0, a1\nxor s1, a0, t6\nand t5, t5, s1\n\
+             srl t5, t5, 63\nsll t5, t5, 11\nsltu s1, a0, t6\nor s1, s1, t5\n\
+             addi a0, a0, 1\nandi s1, s1, 1\n\
+             andi s1, s1, 1\nadd a2, a2, s1\nsltu t5, a2, s1\n\
+             add a2, a2, a3\nsltu s1, a2, a3\nor s1, s1, t5\n\
+             add t5, a0, a1\nsltu s1, t5, a0\nmv a0, t5\n\
+             addi a0, a0, -1\nandi s1, s1, 1\n\
+             andi s1, s1, 1\nsltu t5, a2, s1\nsub a2, a2, s1\n\
+             sltu s1, a2, a3\nsub a2, a2, a3\nor s1, s1, t5\n",
+        );
+    }
+
+    #[test]
+    fn pac_ret_signs_and_authenticates_the_return_address() {
+        use crate::guest_stack::ReturnStack;
+        use portal_solutions_asm_x86_64::out::arg::ArgKind;
+
+        let x64 = X64Arch::default();
+        // Signed in the call shim before the push, authenticated after the pop
+        let call_ret = |w: &mut Guest<'_>, ctx: &mut ()| {
+            w.call(ctx, x64, &ArgKind::sym("callee"))?;
+            w.ret(ctx, x64)
+        };
+        let aarch64 = AArch64Arch::default().with_pac_ret(true);
+        trace(call_ret, aarch64, &mut ()).unwrap().assert_golden(
+            "b .Lshim_1\n.Lshim_0:\npaciasp\nstr x30, [sp, #-8]!\nb callee\n.Lshim_1:\n\
+             bl .Lshim_0\nldr x30, [sp], #8\nautiasp\nret\n",
+        );
+
+        let mut shadow = Trace::new();
+        {
+            let sink: &mut dyn Write = &mut shadow;
+            let mut shim = crate::aarch64::X64ToAArch64Shim::new(sink)
+                .with_return_stack(ReturnStack::Shadow(Reg(13)))
+                .unwrap()
+                .with_pac_ret(true);
+            let guest: &mut Guest<'_> = &mut shim;
+            guest.call(&mut (), x64, &Reg(0)).unwrap();
+            guest.ret(&mut (), x64).unwrap();
+        }
+        shadow.assert_golden(
+            "paciasp\nstr x30, [x13, #-8]!\nblr x0\nldr x30, [x13], #8\nautiasp\nret\n",
+        );
+    }
+
+    #[test]
+    fn tso_fences_order_guest_loads_and_stores() {
+        use crate::memory_model::MemoryModelPolicy;
+        use portal_pc_asm_common::types::mem::MemorySize;
+        use portal_solutions_asm_x86_64::RegisterClass;
+        use portal_solutions_asm_x86_64::out::arg::{ArgKind, MemArgKind, Segment};
+
+        let x64 = X64Arch::default();
+        let mem = |disp| MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(3),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp,
+            size: MemorySize::_64,
+            reg_class: RegisterClass::Gpr,
+            segment: Segment::None,
+        };
+        let (field, slot) = (mem(8), mem(0));
+        let load_store = |guest: &mut Guest<'_>| {
+            guest.mov(&mut (), x64, &Reg(0), &field).unwrap();
+            guest.mov(&mut (), x64, &field, &Reg(0)).unwrap();
+            guest.mov(&mut (), x64, &Reg(0), &slot).unwrap();
+            guest.mov(&mut (), x64, &slot, &Reg(0)).unwrap();
+        };
+        let aarch64_with = |memory_model| {
+            let mut aarch64 = Trace::new();
+            {
+                let sink: &mut dyn Write = &mut aarch64;
+                let mut shim =
+                    crate::aarch64::X64ToAArch64Shim::new(sink).with_memory_model(memory_model);
+                let guest: &mut Guest<'_> = &mut shim;
+                load_store(guest);
+            }
+            aarch64
+        };
+        let riscv_with = |memory_model| {
+            let mut riscv = Trace::new();
+            {
+                let sink: &mut dyn Write = &mut riscv;
+                let mut shim =
+                    crate::riscv64::X64ToRiscV64Shim::new(sink).with_memory_model(memory_model);
+                let guest: &mut Guest<'_> = &mut shim;
+                load_store(guest);
+            }
+            riscv
+        };
+
+        aarch64_with(MemoryModelPolicy::Native)
+            .assert_golden("ldr x0, [x19, #8]\nstr x0, [x19, #8]\nldr x0, [x19]\nstr x0, [x19]\n");
+        // Load barriers follow loads and store barriers precede stores
+        aarch64_with(MemoryModelPolicy::TsoViaBarriers).assert_golden(
+            "ldr x0, [x19, #8]\ndmb ishld\ndmb ishst\nstr x0, [x19, #8]\n\
+             ldr x0, [x19]\ndmb ishld\ndmb ishst\nstr x0, [x19]\n",
+        );
+        // LDAR and STLR only take a bare base register
+        aarch64_with(MemoryModelPolicy::TsoViaAcqRel).assert_golden(
+            "ldr x0, [x19, #8]\ndmb ishld\ndmb ishst\nstr x0, [x19, #8]\n\
+             ldar x0, [x19]\nstlr x0, [x19]\n",
+        );
+
+        riscv_with(MemoryModelPolicy::Native)
+            .assert_golden("ld a0, 8(s2)\nsd a0, 8(s2)\nld a0, 0(s2)\nsd a0, 0(s2)\n");
+        let fenced = "ld a0, 8(s2)\nfence r, rw\nfence rw, w\nsd a0, 8(s2)\n\
+                      ld a0, 0(s2)\nfence r, rw\nfence rw, w\nsd a0, 0(s2)\n";
+        riscv_with(MemoryModelPolicy::TsoViaBarriers).assert_golden(fenced);
+        // RISC-V has no acquire/release forms and fences either way
+        riscv_with(MemoryModelPolicy::TsoViaAcqRel).assert_golden(fenced);
+    }
+
+    #[test]
+    #[should_panic(expected = "+ mov x0, x1")]
+    fn golden_mismatch_panics_with_diff() {
+        Trace::parse("mov x0, x1\n").assert_golden("mov x1, x0\n");
+    }
+}
@@ -0,0 +1,154 @@
+//! x87 floating-point stack emulation shared by the translation shims.
+//!
+//! Neither target has an 80-bit extended type, so the shims keep each x87
+//! stack slot in a 64-bit double register and perform arithmetic in double
+//! precision. Results can therefore differ from the guest in the low bits and
+//! in range; every translated x87 instruction reports this through the shim
+//! hooks' `precision_loss` callback so callers can log or reject it.
+//!
+//! The stack is tracked statically: `fld` pushes and the popping forms pop at
+//! translation time, and ST(i) is pinned to a fixed bank register. This is
+//! exact for straight-line code and for control flow that leaves the stack at
+//! the same depth on every path into a label, which is how compilers emit
+//! x87 code; anything else is outside what the shims model.
+
+use portal_pc_asm_common::types::mem::MemorySize;
+use portal_solutions_asm_x86_64::{
+    X64Arch,
+    out::{
+        arg::{MemArg, MemArgKind},
+        error::EmitError,
+    },
+};
+
+/// Number of x87 stack slots.
+pub const DEPTH: u8 = 8;
+
+/// Why a translated guest instruction may not produce bit-identical results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PrecisionLoss {
+    /// x87 extended (80-bit) arithmetic performed in 64-bit double precision.
+    X87AsDouble,
+}
+
+/// Translation-time model of the x87 register stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct X87Stack {
+    depth: u8,
+}
+
+impl X87Stack {
+    /// Returns the number of values currently on the stack.
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Returns the bank index holding ST(`i`).
+    ///
+    /// Panics if fewer than `i + 1` values are on the stack.
+    #[track_caller]
+    pub fn st(&self, i: u8) -> u8 {
+        assert!(
+            i < self.depth,
+            "x87 stack underflow: ST({i}) with depth {}",
+            self.depth
+        );
+        self.depth - 1 - i
+    }
+
+    /// Pushes a value and returns the bank index of the new ST(0).
+    #[track_caller]
+    pub fn push(&mut self) -> u8 {
+        assert!(self.depth < DEPTH, "x87 stack overflow");
+        self.depth += 1;
+        self.depth - 1
+    }
+
+    /// Pops ST(0).
+    #[track_caller]
+    pub fn pop(&mut self) {
+        assert!(self.depth > 0, "x87 stack underflow on pop");
+        self.depth -= 1;
+    }
+}
+
+/// Checks that x87 is enabled and, if given, that `mem` is an `m64fp` operand.
+///
+/// Neither target can load or store 32- or 80-bit floats into the double
+/// bank, so other operand sizes are rejected.
+pub(crate) fn check_operand(
+    cfg: X64Arch,
+    mnemonic: &'static str,
+    mem: Option<&(dyn MemArg + '_)>,
+) -> Result<(), EmitError> {
+    if !cfg.x87 {
+        return Err(EmitError::ExtensionNotEnabled {
+            mnemonic,
+            extension: "X64Arch::x87",
+        });
+    }
+    match mem.map(|m| m.concrete_mem_kind()) {
+        None
+        | Some(MemArgKind::Mem {
+            size: MemorySize::_64,
+            ..
+        }) => Ok(()),
+        Some(MemArgKind::Mem { .. }) => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "only m64fp operands are supported",
+        }),
+        Some(MemArgKind::NoMem(_)) => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "requires a memory operand",
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_follow_push_and_pop() {
+        let mut stack = X87Stack::default();
+        assert_eq!(stack.push(), 0);
+        assert_eq!(stack.push(), 1);
+        assert_eq!(stack.st(0), 1);
+        assert_eq!(stack.st(1), 0);
+        stack.pop();
+        assert_eq!(stack.st(0), 0);
+        assert_eq!(stack.depth(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "underflow")]
+    fn underflow_panics() {
+        X87Stack::default().st(0);
+    }
+
+    #[test]
+    fn operand_checks_return_typed_errors() {
+        use portal_pc_asm_common::types::reg::Reg;
+
+        let cfg = X64Arch {
+            x87: true,
+            ..X64Arch::default()
+        };
+        assert_eq!(check_operand(cfg, "faddp", None), Ok(()));
+        assert_eq!(
+            check_operand(X64Arch::default(), "faddp", None),
+            Err(EmitError::ExtensionNotEnabled {
+                mnemonic: "faddp",
+                extension: "X64Arch::x87",
+            })
+        );
+        assert!(matches!(
+            check_operand(cfg, "fld", Some(&Reg(0))),
+            Err(EmitError::InvalidOperands {
+                mnemonic: "fld",
+                ..
+            })
+        ));
+    }
+}
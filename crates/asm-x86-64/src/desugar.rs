@@ -21,10 +21,12 @@
 use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
 
 use crate::{
-    RegisterClass, X64Arch,
+    MaskReg, RegisterClass, RepPrefix, X64Arch,
     out::{
         WriterCore,
         arg::{ArgKind, MemArg, MemArgKind},
+        error::EmitError,
+        evex::OpMask,
     },
     stack::StackManager,
 };
@@ -177,7 +179,8 @@ impl TempRegManager {
 /// - Register class-aware temporary selection
 /// - Conflict-free temporary register allocation
 /// - Canonical scale materialization (SHL for powers of two, MUL otherwise)
-/// - Large displacement folding with proper size preservation
+/// - Base-less (`[index*scale + disp]`) and RIP-relative operands passed
+///   through when directly encodable
 /// - SIMD register class handling for XMM operations
 /// - Advanced stack management with inter-instruction optimization
 /// - Offset-based stack data access
@@ -195,7 +198,10 @@ where
     phantom: core::marker::PhantomData<Context>,
 }
 
-impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Context> {
+impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Context>
+where
+    W::Error: From<EmitError>,
+{
     pub fn new(writer: &'a mut W) -> Self {
         let config = DesugarConfig::default();
         let temp_manager = TempRegManager::new();
@@ -335,11 +341,6 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
         Ok(())
     }
 
-    /// Checks if a displacement fits in a signed 32-bit immediate.
-    fn fits_in_i32(d: u64) -> bool {
-        d <= i32::MAX as u64
-    }
-
     /// Checks whether a scale is valid for x86 addressing (1,2,4,8).
     fn valid_scale(scale: u32) -> bool {
         matches!(scale, 1 | 2 | 4 | 8)
@@ -375,7 +376,7 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                 buffer[count] = *reg;
                 count += 1;
             }
-            MemArgKind::NoMem(ArgKind::Lit(_)) => {}
+            MemArgKind::NoMem(_) => {}
             MemArgKind::Mem { base, offset, .. } => {
                 if let ArgKind::Reg { reg, .. } = base {
                     buffer[count] = *reg;
@@ -446,17 +447,26 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
     }
 
     /// Desugars a memory operand into a simple base+disp form.
-    /// Returns (base_reg, disp) where the returned `disp` is a u32 displacement
-    /// suitable for `MemArgKind::Mem`. If the displacement is too large it will
-    /// be folded into the returned `base_reg` (and disp==0).
+    /// Returns (base_reg, disp) where the returned `disp` is the operand's
+    /// own displacement, suitable for `MemArgKind::Mem`.
+    ///
+    /// Fails with [`EmitError::InvalidOperands`] if `mem` is not a memory
+    /// operand, or is RIP-relative: the address is relative to the original
+    /// instruction and cannot be computed ahead of it.
     fn desugar_mem_operand(
         &mut self,
         ctx: &mut Context,
         arch: X64Arch,
         mem: &MemArgKind<ArgKind>,
-    ) -> Result<(Reg, u32), W::Error> {
+    ) -> Result<(Reg, i32), W::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(?mem, "desugaring memory operand");
         match mem {
-            MemArgKind::NoMem(_) => panic!("desugar_mem_operand called with NoMem"),
+            MemArgKind::NoMem(_) => Err(EmitError::InvalidOperands {
+                mnemonic: "address",
+                reason: "expected a memory operand",
+            }
+            .into()),
             MemArgKind::Mem {
                 base,
                 offset,
@@ -474,6 +484,30 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                         self.writer.mov64(ctx, arch, &temp, *val)?;
                         temp
                     }
+                    ArgKind::Sym { name, addend } => {
+                        let temp = self.config.temp_gpr;
+                        self.lea_sym(ctx, arch, temp, *name, *addend)?;
+                        temp
+                    }
+                    ArgKind::HighByte(_) => {
+                        return Err(EmitError::InvalidOperands {
+                            mnemonic: "address",
+                            reason: "a high-byte register cannot address memory",
+                        }
+                        .into());
+                    }
+                    ArgKind::Rip => {
+                        return Err(EmitError::InvalidOperands {
+                            mnemonic: "address",
+                            reason: "a RIP-relative address cannot take an index",
+                        }
+                        .into());
+                    }
+                    ArgKind::NoBase => {
+                        let temp = self.config.temp_gpr;
+                        self.writer.mov64(ctx, arch, &temp, 0)?;
+                        temp
+                    }
                 };
 
                 // Handle offset (index*scale)
@@ -491,6 +525,36 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                             self.writer.mov64(ctx, arch, &tmp, *val)?;
                             tmp
                         }
+                        ArgKind::Sym { name, addend } => {
+                            let tmp = if base_reg == self.config.temp_gpr {
+                                self.config.temp_gpr2
+                            } else {
+                                self.config.temp_gpr
+                            };
+                            self.lea_sym(ctx, arch, tmp, *name, *addend)?;
+                            tmp
+                        }
+                        ArgKind::HighByte(_) => {
+                            return Err(EmitError::InvalidOperands {
+                                mnemonic: "address",
+                                reason: "a high-byte register cannot address memory",
+                            }
+                            .into());
+                        }
+                        ArgKind::Rip => {
+                            return Err(EmitError::InvalidOperands {
+                                mnemonic: "address",
+                                reason: "rip can only be the base of a memory operand",
+                            }
+                            .into());
+                        }
+                        ArgKind::NoBase => {
+                            return Err(EmitError::InvalidOperands {
+                                mnemonic: "address",
+                                reason: "only the base of a memory operand can be left out",
+                            }
+                            .into());
+                        }
                     };
 
                     // Materialize scaled index into a register if needed; otherwise
@@ -561,34 +625,34 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                     base_reg
                 };
 
-                // Handle large displacement: x86 uses signed 32-bit displacement
-                if Self::fits_in_i32(*disp as u64) {
-                    Ok((effective_base, *disp))
-                } else {
-                    // Fold displacement into base
-                    let mut used_regs = [Reg(0); 1];
-                    used_regs[0] = effective_base;
-                    let temp = self.select_safe_temp_reg(crate::RegisterClass::Gpr, &used_regs, 1);
-                    self.writer.mov64(ctx, arch, &temp, *disp as u64)?;
-                    self.writer.mov(ctx, arch, &temp, &effective_base)?;
-                    self.writer.add(
-                        ctx,
-                        arch,
-                        &temp,
-                        &MemArgKind::NoMem(ArgKind::Reg {
-                            reg: effective_base,
-                            size: MemorySize::_64,
-                        }),
-                    )?;
-                    Ok((temp, 0))
-                }
+                Ok((effective_base, *disp))
             }
         }
     }
 
+    /// Loads the address of `name + addend` into `dest` with a RIP-relative `lea`.
+    fn lea_sym(
+        &mut self,
+        ctx: &mut Context,
+        arch: X64Arch,
+        dest: Reg,
+        name: &'static str,
+        addend: i64,
+    ) -> Result<(), W::Error> {
+        let addr = MemArgKind::Mem {
+            base: ArgKind::Sym { name, addend },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_64,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: crate::out::arg::Segment::None,
+        };
+        self.writer.lea(ctx, arch, &dest, &addr)
+    }
+
     fn simple_mem(
         base: Reg,
-        disp: u32,
+        disp: i32,
         size: MemorySize,
         reg_class: crate::RegisterClass,
     ) -> MemArgKind<ArgKind> {
@@ -609,8 +673,31 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
         mem_arg: &(dyn MemArg + '_),
     ) -> Result<MemArgKind<ArgKind>, W::Error> {
         let concrete = mem_arg.concrete_mem_kind();
-        match concrete {
+        let segment = match concrete {
+            MemArgKind::Mem { segment, .. } => segment,
+            MemArgKind::NoMem(_) => crate::out::arg::Segment::None,
+        };
+        let mut desugared = match concrete {
             MemArgKind::NoMem(_) => Ok(concrete),
+            // Absolute (`fs:[disp]`) and base-less indexed addresses are
+            // directly encodable.
+            MemArgKind::Mem {
+                base: ArgKind::NoBase,
+                offset: None | Some((ArgKind::Reg { .. }, 1 | 2 | 4 | 8)),
+                ..
+            } => Ok(concrete),
+            // RIP-relative addressing has no index; compute the address first.
+            // That is impossible for a bare `rip` base, which is rejected.
+            MemArgKind::Mem {
+                base: ArgKind::Sym { .. } | ArgKind::Rip,
+                offset: Some(_),
+                size,
+                reg_class,
+                ..
+            } => {
+                let (base_reg, new_disp) = self.desugar_mem_operand(ctx, arch, &concrete)?;
+                Ok(Self::simple_mem(base_reg, new_disp, size, reg_class))
+            }
             MemArgKind::Mem {
                 base: ArgKind::Lit(_),
                 offset,
@@ -634,19 +721,13 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                 let (base, new_disp) = self.desugar_mem_operand(ctx, arch, &concrete)?;
                 Ok(Self::simple_mem(base, new_disp, size, reg_class))
             }
-            MemArgKind::Mem {
-                offset: None,
-                disp,
-                size,
-                reg_class,
-                ..
-            } if !Self::fits_in_i32(disp as u64) => {
-                // large displacement - fold into base
-                let (base, new_disp) = self.desugar_mem_operand(ctx, arch, &concrete)?;
-                Ok(Self::simple_mem(base, new_disp, size, reg_class))
-            }
             m => Ok(m),
+        }?;
+        // Keep the segment override when the address was rebuilt around a temp.
+        if let MemArgKind::Mem { segment: s, .. } = &mut desugared {
+            *s = segment;
         }
+        Ok(desugared)
     }
 
     fn desugar_operand(
@@ -657,7 +738,7 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
     ) -> Result<MemArgKind<ArgKind>, W::Error> {
         let concrete = operand.concrete_mem_kind();
         match concrete {
-            MemArgKind::NoMem(ArgKind::Reg { .. }) => Ok(concrete),
+            MemArgKind::NoMem(ArgKind::Reg { .. } | ArgKind::HighByte(_)) => Ok(concrete),
             MemArgKind::NoMem(ArgKind::Lit(val)) => {
                 // Load literal into temp - use appropriate size based on value
                 let temp = self.config.temp_gpr;
@@ -667,6 +748,25 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
                     size: MemorySize::_64,
                 }))
             }
+            MemArgKind::NoMem(ArgKind::Sym { name, addend }) => {
+                // Symbol addresses are materialized RIP-relatively.
+                let temp = self.config.temp_gpr;
+                self.lea_sym(ctx, arch, temp, name, addend)?;
+                Ok(MemArgKind::NoMem(ArgKind::Reg {
+                    reg: temp,
+                    size: MemorySize::_64,
+                }))
+            }
+            MemArgKind::NoMem(ArgKind::Rip) => Err(EmitError::InvalidOperands {
+                mnemonic: "operand",
+                reason: "rip can only be the base of a memory operand",
+            }
+            .into()),
+            MemArgKind::NoMem(ArgKind::NoBase) => Err(EmitError::InvalidOperands {
+                mnemonic: "operand",
+                reason: "only the base of a memory operand can be left out",
+            }
+            .into()),
             MemArgKind::Mem {
                 size, reg_class, ..
             } => {
@@ -821,6 +921,8 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> DesugaringWriter<'a, W, Conte
 
 impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
     for DesugaringWriter<'a, W, Context>
+where
+    W::Error: From<EmitError>,
 {
     type Error = W::Error;
 
@@ -1014,6 +1116,10 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         cfg: X64Arch,
         op: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        // Direct branches to a symbol are relocated, not materialized.
+        if let MemArgKind::NoMem(ArgKind::Sym { .. }) = op.concrete_mem_kind() {
+            return self.writer.call(ctx, cfg, op);
+        }
         let desugared = self.desugar_operand(ctx, cfg, op)?;
         self.writer.call(ctx, cfg, &desugared)
     }
@@ -1024,6 +1130,10 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         cfg: X64Arch,
         op: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
+        // Direct branches to a symbol are relocated, not materialized.
+        if let MemArgKind::NoMem(ArgKind::Sym { .. }) = op.concrete_mem_kind() {
+            return self.writer.jmp(ctx, cfg, op);
+        }
         let desugared = self.desugar_operand(ctx, cfg, op)?;
         self.writer.jmp(ctx, cfg, &desugared)
     }
@@ -1060,6 +1170,42 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.binary_op(ctx, cfg, a, b, |w, ctx, c, x, y| w.add(ctx, c, x, y))
     }
 
+    fn adc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[a, b])?;
+        self.binary_op(ctx, cfg, a, b, |w, ctx, c, x, y| w.adc(ctx, c, x, y))
+    }
+
+    fn sbb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[a, b])?;
+        self.binary_op(ctx, cfg, a, b, |w, ctx, c, x, y| w.sbb(ctx, c, x, y))
+    }
+
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[a])?;
+        // The literal only steers operand desugaring; the immediate form encodes `imm` itself.
+        self.binary_op(ctx, cfg, a, &(imm as i64 as u64), |w, ctx, c, x, _| {
+            w.add_imm(ctx, c, x, imm)
+        })
+    }
+
     fn sub(
         &mut self,
         ctx: &mut Context,
@@ -1072,6 +1218,19 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.binary_op(ctx, cfg, a, b, |w, ctx, c, x, y| w.sub(ctx, c, x, y))
     }
 
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[a])?;
+        self.binary_op(ctx, cfg, a, &(imm as i64 as u64), |w, ctx, c, x, _| {
+            w.sub_imm(ctx, c, x, imm)
+        })
+    }
+
     fn cmp(
         &mut self,
         ctx: &mut Context,
@@ -1084,6 +1243,19 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.binary_op_no_dest(ctx, cfg, a, b, |w, ctx, c, x, y| w.cmp(ctx, c, x, y))
     }
 
+    fn cmp_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[a])?;
+        self.binary_op_no_dest(ctx, cfg, a, &(imm as i64 as u64), |w, ctx, c, x, _| {
+            w.cmp_imm(ctx, c, x, imm)
+        })
+    }
+
     fn cmp0(
         &mut self,
         ctx: &mut Context,
@@ -1095,6 +1267,29 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.writer.cmp0(ctx, cfg, op)
     }
 
+    fn test(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[a, b])?;
+        self.binary_op_no_dest(ctx, cfg, a, b, |w, ctx, c, x, y| w.test(ctx, c, x, y))
+    }
+
+    fn test0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[op])?;
+        self.writer.test0(ctx, cfg, op)
+    }
+
     fn cmovcc(
         &mut self,
         ctx: &mut Context,
@@ -1108,6 +1303,17 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.writer.cmovcc(ctx, cfg, cond, op, val)
     }
 
+    fn setcc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cond: crate::ConditionCode,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[op])?;
+        self.writer.setcc(ctx, cfg, cond, op)
+    }
+
     fn not(
         &mut self,
         ctx: &mut Context,
@@ -1119,6 +1325,39 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.writer.not(ctx, cfg, op)
     }
 
+    fn neg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[op])?;
+        self.writer.neg(ctx, cfg, op)
+    }
+
+    fn inc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[op])?;
+        self.writer.inc(ctx, cfg, op)
+    }
+
+    fn dec(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[op])?;
+        self.writer.dec(ctx, cfg, op)
+    }
+
     fn mul(
         &mut self,
         ctx: &mut Context,
@@ -1131,6 +1370,59 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.binary_op(ctx, cfg, a, b, |w, ctx, c, x, y| w.mul(ctx, c, x, y))
     }
 
+    fn imul2(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[dest, src])?;
+        self.binary_op(ctx, cfg, dest, src, |w, ctx, c, x, y| w.imul2(ctx, c, x, y))
+    }
+
+    fn imul3(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[dest, src])?;
+        self.writer.imul3(ctx, cfg, dest, src, imm)
+    }
+
+    fn mul_wide(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        hi: &(dyn MemArg + '_),
+        lo: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[hi, lo, a, b])?;
+        self.writer.mul_wide(ctx, cfg, hi, lo, a, b)
+    }
+
+    fn imul_wide(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        hi: &(dyn MemArg + '_),
+        lo: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[hi, lo, a, b])?;
+        self.writer.imul_wide(ctx, cfg, hi, lo, a, b)
+    }
+
     fn div(
         &mut self,
         ctx: &mut Context,
@@ -1167,6 +1459,19 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.binary_op(ctx, cfg, a, b, |w, ctx, c, x, y| w.and(ctx, c, x, y))
     }
 
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[a])?;
+        self.binary_op(ctx, cfg, a, &(imm as i64 as u64), |w, ctx, c, x, _| {
+            w.and_imm(ctx, c, x, imm)
+        })
+    }
+
     fn or(
         &mut self,
         ctx: &mut Context,
@@ -1179,6 +1484,19 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.binary_op(ctx, cfg, a, b, |w, ctx, c, x, y| w.or(ctx, c, x, y))
     }
 
+    fn or_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[a])?;
+        self.binary_op(ctx, cfg, a, &(imm as i64 as u64), |w, ctx, c, x, _| {
+            w.or_imm(ctx, c, x, imm)
+        })
+    }
+
     fn eor(
         &mut self,
         ctx: &mut Context,
@@ -1191,6 +1509,19 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.binary_op(ctx, cfg, a, b, |w, ctx, c, x, y| w.eor(ctx, c, x, y))
     }
 
+    fn eor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[a])?;
+        self.binary_op(ctx, cfg, a, &(imm as i64 as u64), |w, ctx, c, x, _| {
+            w.eor_imm(ctx, c, x, imm)
+        })
+    }
+
     fn shl(
         &mut self,
         ctx: &mut Context,
@@ -1227,6 +1558,30 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.binary_op(ctx, cfg, a, b, |w, ctx, c, x, y| w.sar(ctx, c, x, y))
     }
 
+    fn rol(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[a, b])?;
+        self.binary_op(ctx, cfg, a, b, |w, ctx, c, x, y| w.rol(ctx, c, x, y))
+    }
+
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[a, b])?;
+        self.binary_op(ctx, cfg, a, b, |w, ctx, c, x, y| w.ror(ctx, c, x, y))
+    }
+
     fn movsx(
         &mut self,
         ctx: &mut Context,
@@ -1251,26 +1606,490 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         self.writer.movzx(ctx, cfg, dest, src)
     }
 
-    fn get_ip(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
-        self.writer.get_ip(ctx, cfg)
-    }
-    fn ret(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
-        self.writer.ret(ctx, cfg)
-    }
-    fn mov64(
+    fn popcnt(
         &mut self,
         ctx: &mut Context,
         cfg: X64Arch,
-        r: &(dyn MemArg + '_),
-        val: u64,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[dest, src])?;
+        self.writer.popcnt(ctx, cfg, dest, src)
+    }
+
+    fn lzcnt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[dest, src])?;
+        self.writer.lzcnt(ctx, cfg, dest, src)
+    }
+
+    fn tzcnt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[dest, src])?;
+        self.writer.tzcnt(ctx, cfg, dest, src)
+    }
+
+    fn bswap(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Flush pending operations if RSP is involved
+        self.ensure_stack_flushed_for_rsp(ctx, cfg, &[op])?;
+        self.writer.bswap(ctx, cfg, op)
+    }
+
+    fn get_ip(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.get_ip(ctx, cfg)
+    }
+    fn ret(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.ret(ctx, cfg)
+    }
+    fn mov64(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        r: &(dyn MemArg + '_),
+        val: u64,
     ) -> Result<(), Self::Error> {
         // Flush pending operations if RSP is involved
         self.ensure_stack_flushed_for_rsp(ctx, cfg, &[r])?;
         self.writer.mov64(ctx, cfg, r, val)
     }
 
-    // Floating and other ops: ensure operands are valid via desugar_operand where appropriate
-    fn fadd(
+    // Floating and other ops: ensure operands are valid via desugar_operand where appropriate
+    fn fadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let d = if matches!(dest.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, dest)?
+        } else {
+            dest.concrete_mem_kind()
+        };
+        let s = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fadd(ctx, cfg, &d, &s)
+    }
+
+    fn fsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let d = if matches!(dest.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, dest)?
+        } else {
+            dest.concrete_mem_kind()
+        };
+        let s = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fsub(ctx, cfg, &d, &s)
+    }
+
+    fn fmul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let d = if matches!(dest.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, dest)?
+        } else {
+            dest.concrete_mem_kind()
+        };
+        let s = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fmul(ctx, cfg, &d, &s)
+    }
+
+    fn fdiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let d = if matches!(dest.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, dest)?
+        } else {
+            dest.concrete_mem_kind()
+        };
+        let s = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.fdiv(ctx, cfg, &d, &s)
+    }
+
+    fn fcmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer.fcmp(ctx, cfg, a, &b)
+    }
+
+    fn fmin(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.fmin(ctx, cfg, dest, &s)
+    }
+
+    fn fmax(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.fmax(ctx, cfg, dest, &s)
+    }
+
+    fn fsqrt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.fsqrt(ctx, cfg, dest, &s)
+    }
+
+    fn fabs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fabs(ctx, cfg, dest, scratch)
+    }
+
+    fn fneg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.fneg(ctx, cfg, dest, scratch)
+    }
+
+    fn cvtsi2sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.cvtsi2sd(ctx, cfg, dest, &s)
+    }
+
+    fn cvttsd2si(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.cvttsd2si(ctx, cfg, dest, &s)
+    }
+
+    fn vfmadd231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer.vfmadd231sd(ctx, cfg, dest, a, &b)
+    }
+
+    fn vfmsub231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer.vfmsub231sd(ctx, cfg, dest, a, &b)
+    }
+
+    fn vfnmadd231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer.vfnmadd231sd(ctx, cfg, dest, a, &b)
+    }
+
+    fn vfnmsub231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer.vfnmsub231sd(ctx, cfg, dest, a, &b)
+    }
+
+    fn kmov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: MaskReg,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            self.desugar_operand(ctx, cfg, src)?
+        };
+        self.writer.kmov(ctx, cfg, dest, &s)
+    }
+
+    fn vmovupd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let d = if matches!(dest.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, dest)?
+        } else {
+            dest.concrete_mem_kind()
+        };
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.vmovupd(ctx, cfg, &d, mask, &s)
+    }
+
+    fn vaddpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer.vaddpd(ctx, cfg, dest, mask, a, &b, broadcast)
+    }
+
+    fn vsubpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer.vsubpd(ctx, cfg, dest, mask, a, &b, broadcast)
+    }
+
+    fn vmulpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer.vmulpd(ctx, cfg, dest, mask, a, &b, broadcast)
+    }
+
+    fn vdivpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer.vdivpd(ctx, cfg, dest, mask, a, &b, broadcast)
+    }
+
+    fn vfmadd231pd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer
+            .vfmadd231pd(ctx, cfg, dest, mask, a, &b, broadcast)
+    }
+
+    fn vcmppd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: MaskReg,
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        predicate: u8,
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer
+            .vcmppd(ctx, cfg, dest, mask, a, &b, predicate, broadcast)
+    }
+
+    fn fmov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = self.desugar_operand(ctx, cfg, src)?;
+        let d = if matches!(dest.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, dest)?
+        } else {
+            dest.concrete_mem_kind()
+        };
+        self.writer.fmov(ctx, cfg, &d, &s)
+    }
+
+    fn addss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let d = if matches!(dest.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, dest)?
+        } else {
+            dest.concrete_mem_kind()
+        };
+        let s = self.desugar_operand(ctx, cfg, src)?;
+        self.writer.addss(ctx, cfg, &d, &s)
+    }
+
+    fn subss(
         &mut self,
         ctx: &mut Context,
         cfg: X64Arch,
@@ -1283,10 +2102,10 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
             dest.concrete_mem_kind()
         };
         let s = self.desugar_operand(ctx, cfg, src)?;
-        self.writer.fadd(ctx, cfg, &d, &s)
+        self.writer.subss(ctx, cfg, &d, &s)
     }
 
-    fn fsub(
+    fn mulss(
         &mut self,
         ctx: &mut Context,
         cfg: X64Arch,
@@ -1299,10 +2118,10 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
             dest.concrete_mem_kind()
         };
         let s = self.desugar_operand(ctx, cfg, src)?;
-        self.writer.fsub(ctx, cfg, &d, &s)
+        self.writer.mulss(ctx, cfg, &d, &s)
     }
 
-    fn fmul(
+    fn divss(
         &mut self,
         ctx: &mut Context,
         cfg: X64Arch,
@@ -1315,26 +2134,130 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
             dest.concrete_mem_kind()
         };
         let s = self.desugar_operand(ctx, cfg, src)?;
-        self.writer.fmul(ctx, cfg, &d, &s)
+        self.writer.divss(ctx, cfg, &d, &s)
     }
 
-    fn fdiv(
+    fn ucomiss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let b = if matches!(b.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, b)?
+        } else {
+            b.concrete_mem_kind()
+        };
+        self.writer.ucomiss(ctx, cfg, a, &b)
+    }
+
+    fn minss(
         &mut self,
         ctx: &mut Context,
         cfg: X64Arch,
         dest: &(dyn MemArg + '_),
         src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        let d = if matches!(dest.concrete_mem_kind(), MemArgKind::Mem { .. }) {
-            self.desugar_mem_arg(ctx, cfg, dest)?
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
         } else {
-            dest.concrete_mem_kind()
+            src.concrete_mem_kind()
         };
-        let s = self.desugar_operand(ctx, cfg, src)?;
-        self.writer.fdiv(ctx, cfg, &d, &s)
+        self.writer.minss(ctx, cfg, dest, &s)
     }
 
-    fn fmov(
+    fn maxss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.maxss(ctx, cfg, dest, &s)
+    }
+
+    fn sqrtss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.sqrtss(ctx, cfg, dest, &s)
+    }
+
+    fn cvtsi2ss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.cvtsi2ss(ctx, cfg, dest, &s)
+    }
+
+    fn cvttss2si(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.cvttss2si(ctx, cfg, dest, &s)
+    }
+
+    fn cvtss2sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.cvtss2sd(ctx, cfg, dest, &s)
+    }
+
+    fn cvtsd2ss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let s = if matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+            self.desugar_mem_arg(ctx, cfg, src)?
+        } else {
+            src.concrete_mem_kind()
+        };
+        self.writer.cvtsd2ss(ctx, cfg, dest, &s)
+    }
+
+    fn movss(
         &mut self,
         ctx: &mut Context,
         cfg: X64Arch,
@@ -1347,17 +2270,272 @@ impl<'a, W: WriterCore<Context> + ?Sized, Context> WriterCore<Context>
         } else {
             dest.concrete_mem_kind()
         };
-        self.writer.fmov(ctx, cfg, &d, &s)
+        self.writer.movss(ctx, cfg, &d, &s)
+    }
+
+    fn ldmxcsr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let m = self.desugar_mem_arg(ctx, cfg, src)?;
+        self.writer.ldmxcsr(ctx, cfg, &m)
+    }
+
+    fn stmxcsr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let m = self.desugar_mem_arg(ctx, cfg, dest)?;
+        self.writer.stmxcsr(ctx, cfg, &m)
+    }
+
+    fn rdsegbase(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        seg: crate::out::arg::Segment,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.rdsegbase(ctx, cfg, seg, dest)
+    }
+
+    fn wrsegbase(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        seg: crate::out::arg::Segment,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.wrsegbase(ctx, cfg, seg, src)
+    }
+
+    fn fld(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let m = self.desugar_mem_arg(ctx, cfg, src)?;
+        self.writer.fld(ctx, cfg, &m)
+    }
+
+    fn fst(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let m = self.desugar_mem_arg(ctx, cfg, dest)?;
+        self.writer.fst(ctx, cfg, &m)
+    }
+
+    fn fstp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let m = self.desugar_mem_arg(ctx, cfg, dest)?;
+        self.writer.fstp(ctx, cfg, &m)
+    }
+
+    fn faddp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.faddp(ctx, cfg)
+    }
+
+    fn fsubp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.fsubp(ctx, cfg)
+    }
+
+    fn fmulp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.fmulp(ctx, cfg)
+    }
+
+    fn fdivp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.fdivp(ctx, cfg)
+    }
+
+    fn movs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.writer.movs(ctx, cfg, size, rep)
+    }
+
+    fn stos(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.writer.stos(ctx, cfg, size, rep)
+    }
+
+    fn cmps(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.writer.cmps(ctx, cfg, size, rep)
+    }
+
+    fn scas(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.writer.scas(ctx, cfg, size, rep)
+    }
+
+    fn syscall(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.syscall(ctx, cfg)
+    }
+
+    fn endbr64(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.endbr64(ctx, cfg)
+    }
+
+    fn lahf(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.lahf(ctx, cfg)
+    }
+
+    fn sahf(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.sahf(ctx, cfg)
+    }
+
+    fn ud2(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.ud2(ctx, cfg)
+    }
+
+    fn int3(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.int3(ctx, cfg)
+    }
+
+    fn pause(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.pause(ctx, cfg)
+    }
+
+    fn prefetcht0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.prefetcht0(ctx, cfg, mem)
+    }
+
+    fn prefetcht1(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.prefetcht1(ctx, cfg, mem)
+    }
+
+    fn prefetcht2(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.prefetcht2(ctx, cfg, mem)
+    }
+
+    fn prefetchnta(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.prefetchnta(ctx, cfg, mem)
+    }
+
+    fn clflush(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.clflush(ctx, cfg, mem)
+    }
+
+    fn clflushopt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.clflushopt(ctx, cfg, mem)
+    }
+
+    fn clwb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.writer.clwb(ctx, cfg, mem)
+    }
+
+    fn sfence(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.sfence(ctx, cfg)
+    }
+
+    fn rdtsc(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.rdtsc(ctx, cfg)
+    }
+
+    fn rdtscp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.rdtscp(ctx, cfg)
     }
 
     fn db(&mut self, ctx: &mut Context, cfg: X64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
         self.writer.db(ctx, cfg, bytes)
     }
+
+    fn current_offset(&self) -> Option<usize> {
+        self.writer.current_offset()
+    }
+
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        self.writer.align_to(ctx, cfg, alignment)
+    }
+
+    fn nop(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.writer.nop(ctx, cfg)
+    }
+
+    fn nops(&mut self, ctx: &mut Context, cfg: X64Arch, n_bytes: usize) -> Result<(), Self::Error> {
+        self.writer.nops(ctx, cfg, n_bytes)
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: X64Arch, pow2: u32) -> Result<(), Self::Error> {
+        self.writer.align(ctx, cfg, pow2)
+    }
 }
 
 impl<'a, W, L, Context> crate::out::Writer<L, Context> for DesugaringWriter<'a, W, Context>
 where
     W: crate::out::Writer<L, Context> + ?Sized,
+    W::Error: From<EmitError>,
 {
     fn set_label(&mut self, ctx: &mut Context, cfg: X64Arch, label: L) -> Result<(), Self::Error> {
         self.writer.set_label(ctx, cfg, label)
@@ -1386,4 +2564,8 @@ where
     ) -> Result<(), Self::Error> {
         self.writer.jcc_label(ctx, cfg, cc, label)
     }
+
+    fn dd_label(&mut self, ctx: &mut Context, cfg: X64Arch, label: L) -> Result<(), Self::Error> {
+        self.writer.dd_label(ctx, cfg, label)
+    }
 }
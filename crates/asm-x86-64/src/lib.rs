@@ -6,6 +6,8 @@
 //! # Features
 //!
 //! - `alloc`: Enables heap allocation support for dynamic collections
+//! - `serde`: Derives `Serialize`/`Deserialize` for the architecture
+//!   configuration and operand types
 //!
 //! # Example
 //!
@@ -21,10 +23,11 @@ extern crate alloc;
 #[doc(hidden)]
 pub mod __ {
     pub use core;
+    pub use portal_pc_asm_common::types::mem::MemorySize;
 }
 use core::fmt::Display;
 
-use portal_pc_asm_common::types::mem::MemorySize;
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
 
 /// 64-bit register names (rax, rcx, rdx, rbx, rsp, rbp, rsi, rdi).
 static REG_NAMES: &'static [&'static str; 8] =
@@ -37,6 +40,8 @@ static REG_NAMES_16: &'static [&'static str; 8] = &["ax", "cx", "dx", "bx", "sp"
 /// 8-bit register names (al, cl, dl, bl, spl, bpl, sil, dil).
 static REG_NAMES_8: &'static [&'static str; 8] =
     &["al", "cl", "dl", "bl", "spl", "bpl", "sil", "dil"];
+/// High-byte register names (ah, ch, dh, bh), bits 8–15 of registers 0–3.
+static HIGH_BYTE_NAMES: &'static [&'static str; 4] = &["ah", "ch", "dh", "bh"];
 /// XMM register names (xmm0 through xmm15).
 static XMM_REG_NAMES: &'static [&'static str; 16] = &[
     "xmm0", "xmm1", "xmm2", "xmm3", "xmm4", "xmm5", "xmm6", "xmm7", "xmm8", "xmm9", "xmm10",
@@ -65,6 +70,7 @@ static ZMM_REG_NAMES: &'static [&'static str; 32] = &[
 /// Use `Ymm` or `Zmm` directly when the width is known at the call site.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RegisterClass {
     /// General-purpose register (rax, rbx, etc.).
     #[default]
@@ -77,6 +83,20 @@ pub enum RegisterClass {
     Zmm,
 }
 
+/// An AVX-512 opmask register, `k0`–`k7`.
+///
+/// Masked instructions name one in an [`OpMask`](out::evex::OpMask); `k0`
+/// there means no mask.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaskReg(pub u8);
+
+impl Display for MaskReg {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "k{}", self.0)
+    }
+}
+
 /// Display options for formatting assembly operands.
 ///
 /// This struct combines architecture configuration with register class selection
@@ -119,10 +139,161 @@ impl From<X64Arch> for DisplayOpts {
 /// such as whether APX (Advanced Performance Extensions) is enabled.
 #[non_exhaustive]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct X64Arch {
     /// Whether APX (Advanced Performance Extensions) is enabled.
     /// When enabled, 32 general-purpose registers are available instead of 16.
     pub apx: bool,
+    /// Whether the x87 floating-point stack (`fld`, `fstp`, `faddp`, ...) may be used.
+    /// Backends reject x87 emitters when this is unset.
+    pub x87: bool,
+    /// Whether FMA3 fused multiply-adds (`vfmadd231sd`, ...) may be used.
+    /// Backends reject them when this is unset.
+    pub fma: bool,
+    /// Whether AVX-512 (F, VL and DQ) may be used: the EVEX-encoded
+    /// instructions in [`out::evex`], the opmask registers `k0`–`k7`, and
+    /// `xmm16`–`xmm31` without APX. Backends reject the EVEX emitters when
+    /// this is unset.
+    pub avx512: bool,
+    /// Whether `rdfsbase`/`wrfsbase` and their `gs` forms may be used.
+    /// Backends reject [`rdsegbase`](out::WriterCore::rdsegbase) and
+    /// [`wrsegbase`](out::WriterCore::wrsegbase) when this is unset.
+    pub fsgsbase: bool,
+    /// Whether [`clflushopt`](out::WriterCore::clflushopt) may be used.
+    /// Backends reject it when this is unset.
+    pub clflushopt: bool,
+    /// Whether [`clwb`](out::WriterCore::clwb) may be used. Backends reject
+    /// it when this is unset.
+    pub clwb: bool,
+    /// Whether [`rdtscp`](out::WriterCore::rdtscp) may be used. Backends
+    /// reject it when this is unset.
+    pub rdtscp: bool,
+    /// How text output refers to labels.
+    pub label_refs: LabelRefs,
+    /// Whether to emit position-independent code.
+    ///
+    /// Moving a symbol's address into a register then uses a RIP-relative
+    /// `lea reg, [rip + sym]` instead of an absolute `mov reg, offset sym`.
+    pub pic: bool,
+    /// Which assembler text output is written for.
+    pub assembler: AssemblerDialect,
+    /// Calling convention of the code, used by helpers that emit calls.
+    pub call_conv: CallConv,
+    /// Whether CET indirect branch tracking is enforced.
+    ///
+    /// Function entries and other indirect-branch targets then start with
+    /// `endbr64` (see [`out::cfi`]).
+    pub ibt: bool,
+    /// Whether conditional branches hinted [`BranchHint::Likely`] get the
+    /// `ds` (0x3E) prefix. Intel cores from Redwood Cove on use it as a
+    /// static "taken" prediction; older cores and AMD ignore it.
+    pub branch_hints: bool,
+    /// Which immediate encoding instructions with several forms use.
+    pub imm_encoding: ImmEncoding,
+}
+
+/// How assembly text refers to labels in branches and address loads.
+///
+/// Binary writers resolve labels themselves and always encode PC-relative
+/// offsets, so the policy only changes text output. Conditional branches
+/// have no absolute form and stay label-relative under either policy.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LabelRefs {
+    /// PC-relative references (`jmp label`, `[rip + label]`), valid at any load address.
+    #[default]
+    Relative,
+    /// Absolute addresses (`movabs r11, offset label` then `jmp r11`), for code linked at a fixed base.
+    Absolute,
+}
+
+/// Immediate encoding for instructions that have several.
+///
+/// Operand size always follows the operands' [`MemorySize`]; this picks
+/// between the immediate forms of one size, e.g. `83 /0 ib` against
+/// `81 /0 id` for `add rax, 1`, or `mov rax, imm32` against `movabs`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImmEncoding {
+    /// Writers choose: assemblers pick the shortest form, and binary writers
+    /// use a sign-extended imm8 for ALU operations when the value fits and
+    /// `movabs` for every 64-bit register move.
+    #[default]
+    Auto,
+    /// ALU operations always take a 32-bit immediate, and 64-bit moves the
+    /// sign-extended `mov r/m64, imm32` form. Values outside the `i32`
+    /// range are rejected by [`ValidatingWriter`](out::validate::ValidatingWriter).
+    /// Assembly text has no spelling for this, so text output is unchanged.
+    Imm32,
+    /// 64-bit register moves always use `movabs` with a full 8-byte
+    /// immediate, in text output too. ALU operations have no 64-bit
+    /// immediate form and are encoded as under [`ImmEncoding::Auto`].
+    Imm64,
+}
+
+/// The assembler that consumes text output.
+///
+/// Instructions are written in Intel syntax for every dialect; the dialect
+/// picks the spelling of directives, data and comments. Binary writers
+/// ignore it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AssemblerDialect {
+    /// GNU `as` (`.balign 16`, `.byte 0x90`, `#` comments).
+    #[default]
+    Gnu,
+    /// LLVM's integrated assembler, which takes the same directives as GNU `as`.
+    Llvm,
+    /// MASM-style assemblers (`ALIGN 16`, `DB 090h`, `;` comments, `PROC`/`ENDP`).
+    Masm,
+}
+
+/// An x86-64 calling convention.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CallConv {
+    /// The System V AMD64 ABI used on Linux and macOS.
+    #[default]
+    SysV,
+    /// The Windows x64 convention: four register arguments and 32 bytes of
+    /// caller-allocated shadow space.
+    Win64,
+}
+
+impl CallConv {
+    /// Returns the integer argument registers, in argument order.
+    pub fn int_args(self) -> &'static [Reg] {
+        match self {
+            // rdi, rsi, rdx, rcx, r8, r9
+            CallConv::SysV => &[Reg(7), Reg(6), Reg(2), Reg(1), Reg(8), Reg(9)],
+            // rcx, rdx, r8, r9
+            CallConv::Win64 => &[Reg(1), Reg(2), Reg(8), Reg(9)],
+        }
+    }
+
+    /// Returns the bytes the caller must reserve above the return address
+    /// for the callee to spill its register arguments.
+    pub fn shadow_space(self) -> i32 {
+        match self {
+            CallConv::SysV => 0,
+            CallConv::Win64 => 32,
+        }
+    }
+}
+
+impl AssemblerDialect {
+    /// Returns the string that starts a comment running to the end of the line.
+    pub fn comment(self) -> &'static str {
+        match self {
+            AssemblerDialect::Masm => ";",
+            _ => "#",
+        }
+    }
 }
 
 /// Options for formatting register names.
@@ -178,6 +349,9 @@ pub mod reg;
 /// Register allocation integration module (gated by `regalloc-integration` feature).
 #[cfg(feature = "regalloc-integration")]
 pub mod regalloc;
+/// Serde adapters for foreign types in operands (gated by `serde` feature).
+#[cfg(feature = "serde")]
+mod serde_remote;
 /// Advanced stack management and optimization.
 pub mod stack;
 
@@ -261,33 +435,24 @@ mod tests {
     }
 
     #[test]
-    fn test_ymm_zmm_register_hack() {
-        // Test the temporary hack for YMM/ZMM register naming
-        // _8 → xmm (128-bit), _16 → ymm (256-bit), _32 → zmm (512-bit)
+    fn test_xmm_register_width_from_size() {
+        // Xmm registers are named by operand width: _128 → xmm, _256 → ymm, _512 → zmm
         let cfg = X64Arch::default();
         let reg0 = Reg(0);
         let reg1 = Reg(1);
 
-        // Test XMM with _8 (hack: 128-bit xmm)
-        let xmm_opts = RegFormatOpts::with_reg_class(cfg, MemorySize::_8, RegisterClass::Xmm);
-        let xmm0 = format!("{}", X64Reg::display(&reg0, xmm_opts.clone()));
-        let xmm1 = format!("{}", X64Reg::display(&reg1, xmm_opts));
-        assert_eq!(xmm0, "xmm0", "MemorySize::_8 should map to xmm with hack");
-        assert_eq!(xmm1, "xmm1", "MemorySize::_8 should map to xmm with hack");
-
-        // Test YMM with _16 (hack: 256-bit ymm)
-        let ymm_opts = RegFormatOpts::with_reg_class(cfg, MemorySize::_16, RegisterClass::Xmm);
-        let ymm0 = format!("{}", X64Reg::display(&reg0, ymm_opts.clone()));
-        let ymm1 = format!("{}", X64Reg::display(&reg1, ymm_opts));
-        assert_eq!(ymm0, "ymm0", "MemorySize::_16 should map to ymm with hack");
-        assert_eq!(ymm1, "ymm1", "MemorySize::_16 should map to ymm with hack");
-
-        // Test ZMM with _32 (hack: 512-bit zmm)
-        let zmm_opts = RegFormatOpts::with_reg_class(cfg, MemorySize::_32, RegisterClass::Xmm);
-        let zmm0 = format!("{}", X64Reg::display(&reg0, zmm_opts.clone()));
-        let zmm1 = format!("{}", X64Reg::display(&reg1, zmm_opts));
-        assert_eq!(zmm0, "zmm0", "MemorySize::_32 should map to zmm with hack");
-        assert_eq!(zmm1, "zmm1", "MemorySize::_32 should map to zmm with hack");
+        for (size, expected) in [
+            (MemorySize::_64, "xmm"),
+            (MemorySize::_128, "xmm"),
+            (MemorySize::_256, "ymm"),
+            (MemorySize::_512, "zmm"),
+        ] {
+            let opts = RegFormatOpts::with_reg_class(cfg, size, RegisterClass::Xmm);
+            let r0 = format!("{}", X64Reg::display(&reg0, opts.clone()));
+            let r1 = format!("{}", X64Reg::display(&reg1, opts));
+            assert_eq!(r0, format!("{expected}0"), "{size:?} should map to {expected}");
+            assert_eq!(r1, format!("{expected}1"), "{size:?} should map to {expected}");
+        }
     }
 
     #[test]
@@ -450,6 +615,65 @@ mod tests {
             "cmp should emit 'cmp rax, rcx\\n'"
         );
     }
+    #[test]
+    fn test_symbol_operands() {
+        use crate::out::WriterCore;
+        use crate::out::arg::{ArgKind, MemArgKind};
+        use alloc::string::String;
+        use core::fmt::Write;
+        use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+        let cfg = X64Arch::default();
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let rax = Reg(0);
+        WriterCore::call(&mut writer, &mut (), cfg, &ArgKind::sym("puts")).unwrap();
+        WriterCore::mov(&mut writer, &mut (), cfg, &rax, &ArgKind::sym("table")).unwrap();
+        let counter = MemArgKind::sym("counter", MemorySize::_64);
+        WriterCore::mov(&mut writer, &mut (), cfg, &rax, &counter).unwrap();
+        assert_eq!(
+            output,
+            "call puts\nmov rax, offset table\nmov rax, qword ptr [rip + counter+0]\n"
+        );
+    }
+    #[test]
+    fn test_high_byte_registers() {
+        use crate::out::WriterCore;
+        use crate::out::arg::ArgKind;
+        use core::fmt::Write;
+
+        let cfg = X64Arch::default();
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let al = ArgKind::Reg {
+            reg: Reg(0),
+            size: MemorySize::_8,
+        };
+        WriterCore::mov(&mut writer, &mut (), cfg, &ArgKind::AH, &al).unwrap();
+        WriterCore::mov(&mut writer, &mut (), cfg, &ArgKind::DH, &ArgKind::BH).unwrap();
+        let ecx = ArgKind::Reg {
+            reg: Reg(1),
+            size: MemorySize::_32,
+        };
+        WriterCore::movzx(&mut writer, &mut (), cfg, &ecx, &ArgKind::CH).unwrap();
+        assert_eq!(output, "mov ah, al\nmov dh, bh\nmovzx ecx,ch\n");
+    }
+    #[test]
+    fn test_imm64_encoding_uses_movabs() {
+        use crate::out::WriterCore;
+        use core::fmt::Write;
+
+        let cfg = X64Arch {
+            imm_encoding: ImmEncoding::Imm64,
+            ..X64Arch::default()
+        };
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        WriterCore::mov(&mut writer, &mut (), cfg, &Reg(0), &1u64).unwrap();
+        WriterCore::mov64(&mut writer, &mut (), cfg, &Reg(1), 2).unwrap();
+        WriterCore::mov(&mut writer, &mut (), cfg, &Reg(2), &Reg(0)).unwrap();
+        assert_eq!(output, "movabs rax, 1\nmovabs rcx, 2\nmov rdx, rax\n");
+    }
 }
 
 /// x86-64 condition codes for conditional instructions.
@@ -493,6 +717,31 @@ pub enum ConditionCode {
     /// Greater (ZF=0 and SF=OF). Signed greater than.
     G,
 }
+impl ConditionCode {
+    /// Returns the condition that holds exactly when `self` does not.
+    pub fn invert(self) -> Self {
+        use ConditionCode::*;
+        match self {
+            O => NO,
+            NO => O,
+            B => NB,
+            NB => B,
+            E => NE,
+            NE => E,
+            NA => A,
+            A => NA,
+            S => NS,
+            NS => S,
+            P => NP,
+            NP => P,
+            L => NL,
+            NL => L,
+            NG => G,
+            G => NG,
+        }
+    }
+}
+
 impl Display for ConditionCode {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -515,3 +764,52 @@ impl Display for ConditionCode {
         }
     }
 }
+
+/// Expected direction of a conditional branch.
+///
+/// Hints never change what a branch does. Layout helpers (see
+/// [`out::branch_hint`]) use them to keep the expected path in fall-through
+/// order, and
+/// [`X64Arch::branch_hints`] turns [`Likely`](Self::Likely) into the `ds`
+/// (0x3E) prefix that recent Intel cores read as "predict taken".
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum BranchHint {
+    /// No expectation.
+    #[default]
+    None,
+    /// The branch is expected to be taken.
+    Likely,
+    /// The branch is expected to fall through.
+    Unlikely,
+}
+
+/// Repeat prefix of a string instruction (`movs`, `stos`, `cmps`, `scas`).
+///
+/// Repeated forms run once per element while RCX is non-zero, decrementing
+/// RCX each time. `rep` and `repe` share an encoding; on `cmps`/`scas` both
+/// also stop once ZF=0.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum RepPrefix {
+    /// Processes a single element.
+    #[default]
+    None,
+    /// Repeats RCX times (`rep`).
+    Rep,
+    /// Repeats while RCX≠0 and ZF=1 (`repe`).
+    Repe,
+    /// Repeats while RCX≠0 and ZF=0 (`repne`).
+    Repne,
+}
+impl Display for RepPrefix {
+    /// Writes the prefix followed by a space, or nothing for [`RepPrefix::None`].
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RepPrefix::None => Ok(()),
+            RepPrefix::Rep => write!(f, "rep "),
+            RepPrefix::Repe => write!(f, "repe "),
+            RepPrefix::Repne => write!(f, "repne "),
+        }
+    }
+}
@@ -10,6 +10,37 @@
 //!
 //! - [`arg`]: Argument and memory operand types
 //! - [`asm`]: Assembly text output implementations
+//! - `label_scope`: Scoped label allocation with definition checks (requires `alloc`)
+//! - `const_pool`: Pooled 64-bit constants with PC-relative loads (requires `alloc`)
+//! - `got`: Patchable address slots for load-time-resolved symbols (requires `alloc`)
+//! - `veneer`: Deduplicated long-range call stubs (requires `alloc`)
+//! - `record`: Recorded writer calls for replay into other writers (requires `alloc`)
+//! - `blocks`: Functions built from basic blocks, with layout and fall-through chosen on emission (requires `alloc`)
+//! - [`multiversion`]: One function emitted for several arch configurations
+//! - [`session`]: Two-pass emission with label offsets from a sizing pass
+//! - [`dyn_writer`]: Object-safe writer facade with erased labels and context
+//! - [`error`]: Structured errors for rejected instructions and operands
+//! - [`validate`]: Rejects instructions the arch configuration does not support
+//! - [`dialect`]: Mnemonic and operand rewriting for other assemblers
+//! - [`function`]: Function symbol directives and hot/cold section placement
+//! - [`branch_align`]: Alignment of loop headers and other hot branch targets
+//! - [`save_restore`]: Saving and restoring register sets in prologues and epilogues
+//! - [`patchpoint`]: Fixed-length call sites whose target can be rewritten atomically
+//! - [`data`]: Data directives (integers, strings, zero fill) interleaved with code
+//! - [`jump_table`]: Switch dispatch through a table of self-relative offsets
+//! - [`call`]: Calls framed for the configured calling convention
+//! - [`frame`]: Stack frame allocation with guard-page probing
+//! - [`cfi`]: Indirect-branch landing pads for control-flow integrity
+//! - [`branch_hint`]: Two-way branch layout by expected direction and `ds` hint prefixes
+//! - [`instrument`]: Profiling counters and hook calls at blocks, entries and call sites
+//! - [`select`]: Branchless conditional selects and booleans from flags
+//! - [`fma`]: Multiply-add, fused when FMA3 is enabled
+//! - [`evex`]: AVX-512 write masks and broadcast memory operands
+//! - [`tee`]: Emission to two writers at once, e.g. a listing and machine code
+//! - [`sync`]: Spinlocks with `pause` spin hints and Linux futex wait/wake system calls
+//! - [`prefetch`]: Software prefetches by locality and access, lowered to `prefetcht0`/`t1`/`t2`/`nta`
+//! - [`timestamp`]: Reads of the time-stamp counter into one register
+//! - [`bounds`]: Unsigned index bounds checks that branch or trap, and the checked element operand
 
 use core::error::Error;
 
@@ -17,6 +48,7 @@ use crate::{
     out::arg::{Arg, MemArg},
     *,
 };
+use portal_pc_asm_common::types::mem::MemorySize;
 // use alloc::boxed::Box;
 
 /// Argument types for instruction operands.
@@ -27,6 +59,138 @@ pub mod asm;
 #[cfg(feature = "iced")]
 pub mod iced;
 
+/// Scoped label allocation with definition checks.
+#[cfg(feature = "alloc")]
+pub mod label_scope;
+
+/// Runtime-configured layering of writer passes.
+#[cfg(feature = "alloc")]
+pub mod writer_stack;
+
+/// Multi-versioned function emission with a dispatcher stub.
+pub mod multiversion;
+
+/// Two-pass emission with label offsets from a sizing pass.
+pub mod session;
+
+/// Object-safe writer facade for runtime backend selection.
+pub mod dyn_writer;
+
+/// Structured errors for instruction emission.
+pub mod error;
+
+/// Instruction validation against the arch configuration.
+pub mod validate;
+
+/// Assembler dialects for text output.
+pub mod dialect;
+
+/// Function symbols and hot/cold placement for text output.
+pub mod function;
+
+/// Alignment of loop headers and other hot branch targets.
+pub mod branch_align;
+
+/// Register save and restore sequences.
+pub mod save_restore;
+
+/// Patchable call sites for hot-patching and lazy binding.
+pub mod patchpoint;
+
+/// Data directives for jump tables and literals placed next to code.
+pub mod data;
+
+/// Jump tables for lowering dense `switch` statements.
+pub mod jump_table;
+
+/// Calls with the shadow space and stack arguments of a calling convention.
+pub mod call;
+
+/// Probed allocation of large stack frames.
+pub mod frame;
+
+/// Control-flow integrity landing pads.
+pub mod cfi;
+
+/// Conditional branches laid out by expected direction.
+pub mod branch_hint;
+
+/// Profiling probes injected at function entries, blocks and call sites.
+pub mod instrument;
+
+/// Branchless selects and boolean materialization with CMOVcc and SETcc.
+pub mod select;
+
+/// Multiply-add through FMA3, or MULSD and ADDSD without it.
+pub mod fma;
+
+/// AVX-512 write masks and broadcast memory operands.
+pub mod evex;
+
+/// Forwarding of every call to two writers.
+pub mod tee;
+
+/// Spinlocks and futex system calls.
+pub mod sync;
+
+/// Software prefetches.
+pub mod prefetch;
+
+/// Reads of the time-stamp counter.
+pub mod timestamp;
+
+/// Bounds checks on array indices.
+pub mod bounds;
+
+/// Recording of writer calls for later replay.
+#[cfg(feature = "alloc")]
+pub mod record;
+
+/// Basic blocks laid out into a function.
+#[cfg(feature = "alloc")]
+pub mod blocks;
+
+/// Constant pool for 64-bit immediates and floating-point literals.
+#[cfg(feature = "alloc")]
+pub mod const_pool;
+
+/// Patchable address slots for symbols resolved at load time.
+#[cfg(feature = "alloc")]
+pub mod got;
+
+/// Long-range call veneers keyed by target symbol.
+#[cfg(feature = "alloc")]
+pub mod veneer;
+
+/// Recommended multi-byte NOP encodings, indexed by length minus one.
+///
+/// These are the `0F 1F /0` forms from the Intel SDM; each decodes as a
+/// single instruction.
+pub const NOPS: [&[u8]; 9] = [
+    &[0x90],
+    &[0x66, 0x90],
+    &[0x0f, 0x1f, 0x00],
+    &[0x0f, 0x1f, 0x40, 0x00],
+    &[0x0f, 0x1f, 0x44, 0x00, 0x00],
+    &[0x66, 0x0f, 0x1f, 0x44, 0x00, 0x00],
+    &[0x0f, 0x1f, 0x80, 0x00, 0x00, 0x00, 0x00],
+    &[0x0f, 0x1f, 0x84, 0x00, 0x00, 0x00, 0x00, 0x00],
+    &[0x66, 0x0f, 0x1f, 0x84, 0x00, 0x00, 0x00, 0x00, 0x00],
+];
+
+/// Splits `n_bytes` of padding into NOP instructions from [`NOPS`], longest
+/// first.
+pub fn nop_fill(mut n_bytes: usize) -> impl Iterator<Item = &'static [u8]> {
+    core::iter::from_fn(move || {
+        let len = n_bytes.min(NOPS.len());
+        if len == 0 {
+            return None;
+        }
+        n_bytes -= len;
+        Some(NOPS[len - 1])
+    })
+}
+
 /// Core trait for writing x86-64 instructions.
 ///
 /// Implementors of this trait can emit individual x86-64 instructions.
@@ -97,6 +261,62 @@ pub trait WriterCore<Context> {
         todo!("add instruction not implemented")
     }
 
+    /// Emits an ADC (add with carry) instruction.
+    ///
+    /// Adds `b` and the carry flag to `a` and stores the result in `a`.
+    #[track_caller]
+    fn adc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("adc instruction not implemented")
+    }
+
+    /// Emits an SBB (subtract with borrow) instruction.
+    ///
+    /// Subtracts `b` and the carry flag from `a` and stores the result in `a`.
+    #[track_caller]
+    fn sbb(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sbb instruction not implemented")
+    }
+
+    /// Emits an ADD with an immediate operand: `a = a + imm`.
+    ///
+    /// `imm` is sign-extended to the operand size, as in the x86 encoding.
+    /// Backends pick the 8-bit form when it fits and imm32 otherwise.
+    /// The default forwards to [`add`](Self::add) with `imm` as a literal.
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.add(ctx, cfg, a, &(imm as i64 as u64))
+    }
+
+    /// Emits a SUB with an immediate operand: `a = a - imm`.
+    ///
+    /// The default forwards to [`sub`](Self::sub) with `imm` as a literal.
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.sub(ctx, cfg, a, &(imm as i64 as u64))
+    }
+
     /// Emits a MOVSX (move with sign-extend) instruction.
     ///
     /// Copies the value from `src` to `dest` with sign extension.
@@ -159,6 +379,18 @@ pub trait WriterCore<Context> {
         todo!("popf instruction not implemented")
     }
 
+    /// Emits a LAHF instruction, loading SF, ZF, AF, PF and CF into AH.
+    #[track_caller]
+    fn lahf(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("lahf instruction not implemented")
+    }
+
+    /// Emits a SAHF instruction, storing AH into SF, ZF, AF, PF and CF.
+    #[track_caller]
+    fn sahf(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("sahf instruction not implemented")
+    }
+
     /// Emits a CALL instruction.
     #[track_caller]
     fn call(
@@ -195,6 +427,19 @@ pub trait WriterCore<Context> {
         todo!("cmp instruction not implemented")
     }
 
+    /// Emits a CMP with an immediate operand: sets flags from `a - imm`.
+    ///
+    /// The default forwards to [`cmp`](Self::cmp) with `imm` as a literal.
+    fn cmp_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.cmp(ctx, cfg, a, &(imm as i64 as u64))
+    }
+
     /// Emits a CMP (compare with zero) instruction.
     #[track_caller]
     fn cmp0(
@@ -206,6 +451,31 @@ pub trait WriterCore<Context> {
         todo!("cmp0 instruction not implemented")
     }
 
+    /// Emits a TEST instruction.
+    ///
+    /// Computes `a & b`, setting flags and discarding the result.
+    #[track_caller]
+    fn test(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("test instruction not implemented")
+    }
+
+    /// Emits a TEST of an operand against itself (`test op, op`).
+    #[track_caller]
+    fn test0(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("test0 instruction not implemented")
+    }
+
     /// Emits a CMOVcc (conditional move) instruction for 64-bit operands.
     #[track_caller]
     fn cmovcc(
@@ -219,6 +489,19 @@ pub trait WriterCore<Context> {
         todo!("cmovcc instruction not implemented")
     }
 
+    /// Emits a SETcc instruction, writing 1 to the byte `op` if `cond`
+    /// holds and 0 otherwise.
+    #[track_caller]
+    fn setcc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _cond: ConditionCode,
+        _op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("setcc instruction not implemented")
+    }
+
     /// Emits a NOT (bitwise complement) instruction.
     #[track_caller]
     fn not(
@@ -230,6 +513,39 @@ pub trait WriterCore<Context> {
         todo!("not instruction not implemented")
     }
 
+    /// Emits a NEG (two's complement negation) instruction.
+    #[track_caller]
+    fn neg(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("neg instruction not implemented")
+    }
+
+    /// Emits a INC (increment by one) instruction.
+    #[track_caller]
+    fn inc(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("inc instruction not implemented")
+    }
+
+    /// Emits a DEC (decrement by one) instruction.
+    #[track_caller]
+    fn dec(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("dec instruction not implemented")
+    }
+
     /// Emits a LEA (load effective address) instruction.
     #[track_caller]
     fn lea(
@@ -278,6 +594,66 @@ pub trait WriterCore<Context> {
         todo!("mul instruction not implemented")
     }
 
+    /// Emits a two-operand IMUL: `dest *= src`, truncated to the operand
+    /// size (IMUL r64, r/m64).
+    #[track_caller]
+    fn imul2(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("imul2 instruction not implemented")
+    }
+
+    /// Emits a three-operand IMUL: `dest = src * imm`, truncated to the
+    /// operand size (IMUL r64, r/m64, imm32). `dest` must be a register.
+    #[track_caller]
+    fn imul3(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+        _imm: i32,
+    ) -> Result<(), Self::Error> {
+        todo!("imul3 instruction not implemented")
+    }
+
+    /// Emits a widening unsigned multiply: `hi:lo = a * b` (MUL r/m64).
+    ///
+    /// Lowered through the one-operand MUL form, so RAX and RDX are
+    /// clobbered; `b` must not be RAX and `lo` must not be RDX.
+    #[track_caller]
+    fn mul_wide(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _hi: &(dyn MemArg + '_),
+        _lo: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("mul_wide instruction not implemented")
+    }
+
+    /// Emits a widening signed multiply: `hi:lo = a * b` (IMUL r/m64).
+    ///
+    /// Same register constraints as [`mul_wide`](Self::mul_wide).
+    #[track_caller]
+    fn imul_wide(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _hi: &(dyn MemArg + '_),
+        _lo: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("imul_wide instruction not implemented")
+    }
+
     /// Emits a DIV (unsigned divide) instruction.
     #[track_caller]
     fn div(
@@ -338,6 +714,45 @@ pub trait WriterCore<Context> {
         todo!("eor instruction not implemented")
     }
 
+    /// Emits an AND with an immediate operand: `a = a & imm`.
+    ///
+    /// The default forwards to [`and`](Self::and) with `imm` as a literal.
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.and(ctx, cfg, a, &(imm as i64 as u64))
+    }
+
+    /// Emits an OR with an immediate operand: `a = a | imm`.
+    ///
+    /// The default forwards to [`or`](Self::or) with `imm` as a literal.
+    fn or_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.or(ctx, cfg, a, &(imm as i64 as u64))
+    }
+
+    /// Emits an XOR with an immediate operand: `a = a ^ imm`.
+    ///
+    /// The default forwards to [`eor`](Self::eor) with `imm` as a literal.
+    fn eor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.eor(ctx, cfg, a, &(imm as i64 as u64))
+    }
+
     /// Emits a SHL (shift left) instruction.
     #[track_caller]
     fn shl(
@@ -376,109 +791,1071 @@ pub trait WriterCore<Context> {
         todo!("sar instruction not implemented")
     }
 
-    /// Emits an ADD instruction for floating point values.
+    /// Emits a ROL (rotate left) instruction.
+    ///
+    /// Rotates `a` left by `b` bits.
     #[track_caller]
-    fn fadd(
+    fn rol(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::X64Arch,
-        _dest: &(dyn MemArg + '_),
-        _src: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("fadd instruction not implemented")
+        todo!("rol instruction not implemented")
     }
 
-    /// Emits a SUB instruction for floating point values.
+    /// Emits a ROR (rotate right) instruction.
+    ///
+    /// Rotates `a` right by `b` bits.
     #[track_caller]
-    fn fsub(
+    fn ror(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::X64Arch,
-        _dest: &(dyn MemArg + '_),
-        _src: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("fsub instruction not implemented")
+        todo!("ror instruction not implemented")
     }
 
-    /// Emits a MUL instruction for floating point values.
+    /// Emits a POPCNT (population count) instruction.
+    ///
+    /// Stores the number of set bits in `src` into `dest`.
     #[track_caller]
-    fn fmul(
+    fn popcnt(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::X64Arch,
         _dest: &(dyn MemArg + '_),
         _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("fmul instruction not implemented")
+        todo!("popcnt instruction not implemented")
     }
 
-    /// Emits a DIV instruction for floating point values.
+    /// Emits an LZCNT (count leading zeros) instruction.
+    ///
+    /// Stores the number of leading zero bits in `src` into `dest`; an all-zero
+    /// source yields the operand width.
     #[track_caller]
-    fn fdiv(
+    fn lzcnt(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::X64Arch,
         _dest: &(dyn MemArg + '_),
         _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("fdiv instruction not implemented")
+        todo!("lzcnt instruction not implemented")
     }
 
-    /// Emits a MOV instruction for floating point values.
+    /// Emits a TZCNT (count trailing zeros) instruction.
+    ///
+    /// Stores the number of trailing zero bits in `src` into `dest`; an all-zero
+    /// source yields the operand width.
     #[track_caller]
-    fn fmov(
+    fn tzcnt(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::X64Arch,
         _dest: &(dyn MemArg + '_),
         _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("fmov instruction not implemented")
+        todo!("tzcnt instruction not implemented")
     }
 
-    /// Emits raw bytes as data.
+    /// Emits a BSWAP (byte swap) instruction.
     ///
-    /// Generates a `.byte` directive (or equivalent) for the given bytes.
+    /// Reverses the byte order of the register `op`.
     #[track_caller]
-    fn db(
+    fn bswap(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::X64Arch,
-        _bytes: &[u8],
+        _op: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("db directive not implemented")
-    }
-
-    /// Returns the current byte offset into the output stream, if tracked.
-    ///
-    /// Text-mode writers return `None`. Binary writers (e.g. iced) return
-    /// `Some(n)` where `n` is the number of bytes emitted so far.
-    fn current_offset(&self) -> Option<usize> {
-        None
+        todo!("bswap instruction not implemented")
     }
 
-    /// Emits alignment padding to the next `alignment`-byte boundary.
-    ///
-    /// For text writers this emits a `.balign` assembler directive and the
-    /// assembler fills the gap with NOPs. For binary writers, NOP instructions
-    /// are emitted directly until the offset is aligned.
+    /// Emits an ADD instruction for floating point values.
     #[track_caller]
-    fn align_to(
+    fn fadd(
         &mut self,
         ctx: &mut Context,
         _cfg: crate::X64Arch,
-        _alignment: usize,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
     ) -> Result<(), Self::Error> {
-        todo!("align_to not implemented")
+        todo!("fadd instruction not implemented")
     }
-}
 
-/// Extended writer trait with label support.
-///
-/// This trait extends [`WriterCore`] with methods for working with labels,
-/// enabling structured control flow in generated code.
-pub trait Writer<L, Context>: WriterCore<Context> {
-    /// Sets a label at the current position.
+    /// Emits a SUB instruction for floating point values.
+    #[track_caller]
+    fn fsub(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fsub instruction not implemented")
+    }
+
+    /// Emits a MUL instruction for floating point values.
+    #[track_caller]
+    fn fmul(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmul instruction not implemented")
+    }
+
+    /// Emits a DIV instruction for floating point values.
+    #[track_caller]
+    fn fdiv(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fdiv instruction not implemented")
+    }
+
+    /// Emits a UCOMISD instruction, comparing the low doubles of `a` and `b`.
+    ///
+    /// Sets ZF, PF and CF as an unsigned compare would (unordered sets all
+    /// three) and clears OF, SF and AF. `b` may be a memory operand.
+    #[track_caller]
+    fn fcmp(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fcmp instruction not implemented")
+    }
+
+    /// Emits a MINSD instruction: `dest = dest < src ? dest : src`.
+    ///
+    /// Returns `src` when either operand is NaN or both are zero.
+    #[track_caller]
+    fn fmin(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmin instruction not implemented")
+    }
+
+    /// Emits a MAXSD instruction: `dest = dest > src ? dest : src`.
+    ///
+    /// Returns `src` when either operand is NaN or both are zero.
+    #[track_caller]
+    fn fmax(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmax instruction not implemented")
+    }
+
+    /// Emits a SQRTSD instruction: `dest = sqrt(src)` on the low doubles.
+    #[track_caller]
+    fn fsqrt(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fsqrt instruction not implemented")
+    }
+
+    /// Clears the sign bit of the low double in `dest`.
+    ///
+    /// SSE2 has no absolute-value instruction, so this builds the mask in
+    /// `scratch`: `pcmpeqd scratch,scratch; psrlq scratch,1; andpd dest,scratch`.
+    #[track_caller]
+    fn fabs(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fabs instruction not implemented")
+    }
+
+    /// Flips the sign bit of the low double in `dest`.
+    ///
+    /// Builds the mask in `scratch` like [`fabs`](Self::fabs):
+    /// `pcmpeqd scratch,scratch; psllq scratch,63; xorpd dest,scratch`.
+    #[track_caller]
+    fn fneg(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fneg instruction not implemented")
+    }
+
+    /// Emits a CVTSI2SD instruction, converting the signed integer `src` to a
+    /// double in `dest`.
+    ///
+    /// The size of `src` selects the 32- or 64-bit form.
+    #[track_caller]
+    fn cvtsi2sd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("cvtsi2sd instruction not implemented")
+    }
+
+    /// Emits a CVTTSD2SI instruction, converting the double `src` to a signed
+    /// integer in `dest`, rounding toward zero.
+    ///
+    /// The size of `dest` selects the 32- or 64-bit form. NaN and
+    /// out-of-range inputs produce the "integer indefinite" value, the
+    /// minimum signed integer.
+    #[track_caller]
+    fn cvttsd2si(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("cvttsd2si instruction not implemented")
+    }
+
+    /// Emits a VFMADD231SD (FMA3) instruction: `dest = a * b + dest` on the low doubles,
+    /// rounded once. `b` may be a memory operand.
+    #[track_caller]
+    fn vfmadd231sd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("vfmadd231sd instruction not implemented")
+    }
+
+    /// Emits a VFMSUB231SD (FMA3) instruction: `dest = a * b - dest` on the low doubles,
+    /// rounded once. `b` may be a memory operand.
+    #[track_caller]
+    fn vfmsub231sd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("vfmsub231sd instruction not implemented")
+    }
+
+    /// Emits a VFNMADD231SD (FMA3) instruction: `dest = -(a * b) + dest` on the low doubles,
+    /// rounded once. `b` may be a memory operand.
+    #[track_caller]
+    fn vfnmadd231sd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("vfnmadd231sd instruction not implemented")
+    }
+
+    /// Emits a VFNMSUB231SD (FMA3) instruction: `dest = -(a * b) - dest` on the low doubles,
+    /// rounded once. `b` may be a memory operand.
+    #[track_caller]
+    fn vfnmsub231sd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("vfnmsub231sd instruction not implemented")
+    }
+
+    /// Emits a KMOV instruction, loading the opmask register `dest` from a
+    /// general-purpose register or memory.
+    ///
+    /// The size of `src` selects `kmovb`, `kmovw`, `kmovd` or `kmovq`; the
+    /// byte and word forms still read a 32-bit register.
+    #[track_caller]
+    fn kmov(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: crate::MaskReg,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("kmov instruction not implemented")
+    }
+
+    /// Emits an EVEX VMOVUPD instruction, moving packed doubles between
+    /// vector registers or to and from unaligned memory, in the elements
+    /// `mask` selects.
+    ///
+    /// A store cannot zero the elements it skips.
+    #[track_caller]
+    fn vmovupd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _mask: evex::OpMask,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("vmovupd instruction not implemented")
+    }
+
+    /// Emits an EVEX VADDPD instruction: `dest = a + b` on packed doubles,
+    /// in the elements `mask` selects.
+    ///
+    /// `b` may be a memory operand; with `broadcast` it is a single double
+    /// repeated across the vector.
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    fn vaddpd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _mask: evex::OpMask,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        todo!("vaddpd instruction not implemented")
+    }
+
+    /// Emits an EVEX VSUBPD instruction: `dest = a - b` on packed doubles,
+    /// in the elements `mask` selects.
+    ///
+    /// `b` may be a memory operand; with `broadcast` it is a single double
+    /// repeated across the vector.
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    fn vsubpd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _mask: evex::OpMask,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        todo!("vsubpd instruction not implemented")
+    }
+
+    /// Emits an EVEX VMULPD instruction: `dest = a * b` on packed doubles,
+    /// in the elements `mask` selects.
+    ///
+    /// `b` may be a memory operand; with `broadcast` it is a single double
+    /// repeated across the vector.
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    fn vmulpd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _mask: evex::OpMask,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        todo!("vmulpd instruction not implemented")
+    }
+
+    /// Emits an EVEX VDIVPD instruction: `dest = a / b` on packed doubles,
+    /// in the elements `mask` selects.
+    ///
+    /// `b` may be a memory operand; with `broadcast` it is a single double
+    /// repeated across the vector.
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    fn vdivpd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _mask: evex::OpMask,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        todo!("vdivpd instruction not implemented")
+    }
+
+    /// Emits an EVEX VFMADD231PD instruction: `dest = a * b + dest`, rounded once, on packed doubles,
+    /// in the elements `mask` selects.
+    ///
+    /// `b` may be a memory operand; with `broadcast` it is a single double
+    /// repeated across the vector.
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    fn vfmadd231pd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _mask: evex::OpMask,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        todo!("vfmadd231pd instruction not implemented")
+    }
+
+    /// Emits an EVEX VCMPPD instruction, setting bit `i` of the opmask `dest`
+    /// to the result of comparing element `i` of `a` and `b` with
+    /// `predicate` (0 is `eq_oq`, 1 `lt_os`, 2 `le_os`, 4 `neq_uq` and so on).
+    ///
+    /// Bits for elements `mask` disables are cleared; the mask cannot be a
+    /// zeroing one. `b` may be a memory operand, broadcast as for
+    /// [`vaddpd`](Self::vaddpd).
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    fn vcmppd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: crate::MaskReg,
+        _mask: evex::OpMask,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+        _predicate: u8,
+        _broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        todo!("vcmppd instruction not implemented")
+    }
+
+    /// Emits a MOV instruction for floating point values.
+    #[track_caller]
+    fn fmov(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fmov instruction not implemented")
+    }
+
+    /// Emits an ADDSS instruction: single-precision [`fadd`](Self::fadd).
+    #[track_caller]
+    fn addss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("addss instruction not implemented")
+    }
+
+    /// Emits a SUBSS instruction: single-precision [`fsub`](Self::fsub).
+    #[track_caller]
+    fn subss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("subss instruction not implemented")
+    }
+
+    /// Emits a MULSS instruction: single-precision [`fmul`](Self::fmul).
+    #[track_caller]
+    fn mulss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("mulss instruction not implemented")
+    }
+
+    /// Emits a DIVSS instruction: single-precision [`fdiv`](Self::fdiv).
+    #[track_caller]
+    fn divss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("divss instruction not implemented")
+    }
+
+    /// Emits a UCOMISS instruction: single-precision [`fcmp`](Self::fcmp).
+    #[track_caller]
+    fn ucomiss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _a: &(dyn MemArg + '_),
+        _b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("ucomiss instruction not implemented")
+    }
+
+    /// Emits a MINSS instruction: single-precision [`fmin`](Self::fmin).
+    #[track_caller]
+    fn minss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("minss instruction not implemented")
+    }
+
+    /// Emits a MAXSS instruction: single-precision [`fmax`](Self::fmax).
+    #[track_caller]
+    fn maxss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("maxss instruction not implemented")
+    }
+
+    /// Emits a SQRTSS instruction: single-precision [`fsqrt`](Self::fsqrt).
+    #[track_caller]
+    fn sqrtss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("sqrtss instruction not implemented")
+    }
+
+    /// Emits a CVTSI2SS instruction, converting the signed integer `src` to a
+    /// single in `dest`.
+    ///
+    /// The size of `src` selects the 32- or 64-bit form.
+    #[track_caller]
+    fn cvtsi2ss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("cvtsi2ss instruction not implemented")
+    }
+
+    /// Emits a CVTTSS2SI instruction: single-precision
+    /// [`cvttsd2si`](Self::cvttsd2si).
+    #[track_caller]
+    fn cvttss2si(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("cvttss2si instruction not implemented")
+    }
+
+    /// Emits a CVTSS2SD instruction, widening the low single of `src` to a
+    /// double in `dest`. Exact.
+    #[track_caller]
+    fn cvtss2sd(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("cvtss2sd instruction not implemented")
+    }
+
+    /// Emits a CVTSD2SS instruction, narrowing the low double of `src` to a
+    /// single in `dest`, rounded by MXCSR.
+    #[track_caller]
+    fn cvtsd2ss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("cvtsd2ss instruction not implemented")
+    }
+
+    /// Emits a MOVSS instruction: single-precision [`fmov`](Self::fmov).
+    ///
+    /// A register-to-register move keeps the upper lanes of `dest`; a load
+    /// zeroes them.
+    #[track_caller]
+    fn movss(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("movss instruction not implemented")
+    }
+
+    /// Loads MXCSR from the 32-bit value at `src` (`ldmxcsr`).
+    ///
+    /// Bits 13-14 select the rounding mode of SSE arithmetic and
+    /// conversions.
+    #[track_caller]
+    fn ldmxcsr(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("ldmxcsr instruction not implemented")
+    }
+
+    /// Stores MXCSR to the 32-bit location `dest` (`stmxcsr`).
+    #[track_caller]
+    fn stmxcsr(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("stmxcsr instruction not implemented")
+    }
+
+    /// Reads the base address of segment `seg` into the register `dest`
+    /// (`rdfsbase`/`rdgsbase`).
+    ///
+    /// `seg` must be [`Segment::Fs`](crate::out::arg::Segment::Fs) or
+    /// [`Segment::Gs`](crate::out::arg::Segment::Gs). Requires
+    /// [`X64Arch::fsgsbase`](crate::X64Arch::fsgsbase).
+    #[track_caller]
+    fn rdsegbase(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _seg: crate::out::arg::Segment,
+        _dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("rdsegbase instruction not implemented")
+    }
+
+    /// Sets the base address of segment `seg` from the register `src`
+    /// (`wrfsbase`/`wrgsbase`).
+    ///
+    /// Later `fs:`/`gs:` memory operands address relative to the new base.
+    #[track_caller]
+    fn wrsegbase(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _seg: crate::out::arg::Segment,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("wrsegbase instruction not implemented")
+    }
+
+    /// Loads the 32- or 64-bit float at `src` onto the x87 stack (`fld`).
+    ///
+    /// The operand size selects `m32fp` or `m64fp`.
+    #[track_caller]
+    fn fld(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fld instruction not implemented")
+    }
+
+    /// Stores ST(0) to `dest` without popping (`fst`).
+    #[track_caller]
+    fn fst(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fst instruction not implemented")
+    }
+
+    /// Stores ST(0) to `dest` and pops the x87 stack (`fstp`).
+    #[track_caller]
+    fn fstp(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("fstp instruction not implemented")
+    }
+
+    /// Sets ST(1) to `ST(1) + ST(0)` and pops the x87 stack (`faddp`).
+    #[track_caller]
+    fn faddp(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("faddp instruction not implemented")
+    }
+
+    /// Sets ST(1) to `ST(1) - ST(0)` and pops the x87 stack (`fsubp`).
+    #[track_caller]
+    fn fsubp(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("fsubp instruction not implemented")
+    }
+
+    /// Sets ST(1) to `ST(1) * ST(0)` and pops the x87 stack (`fmulp`).
+    #[track_caller]
+    fn fmulp(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("fmulp instruction not implemented")
+    }
+
+    /// Sets ST(1) to `ST(1) / ST(0)` and pops the x87 stack (`fdivp`).
+    #[track_caller]
+    fn fdivp(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("fdivp instruction not implemented")
+    }
+
+    /// Copies `size` bytes from `[rsi]` to `[rdi]` and advances both (`movs`).
+    ///
+    /// Under a repeat prefix this copies RCX elements, as `rep movsb` does for
+    /// `memcpy`. The direction flag is assumed clear.
+    #[track_caller]
+    fn movs(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _size: MemorySize,
+        _rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        todo!("movs instruction not implemented")
+    }
+
+    /// Stores the low `size` bytes of RAX to `[rdi]` and advances RDI (`stos`).
+    #[track_caller]
+    fn stos(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _size: MemorySize,
+        _rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        todo!("stos instruction not implemented")
+    }
+
+    /// Compares `[rsi]` with `[rdi]` and advances both (`cmps`).
+    ///
+    /// Sets the flags as `cmp [rsi], [rdi]` would.
+    #[track_caller]
+    fn cmps(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _size: MemorySize,
+        _rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        todo!("cmps instruction not implemented")
+    }
+
+    /// Compares the low `size` bytes of RAX with `[rdi]` and advances RDI (`scas`).
+    #[track_caller]
+    fn scas(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _size: MemorySize,
+        _rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        todo!("scas instruction not implemented")
+    }
+
+    /// Emits a SYSCALL instruction, entering the kernel.
+    ///
+    /// The system call number is in RAX and its arguments in RDI, RSI, RDX,
+    /// R10, R8 and R9; the result is returned in RAX, and RCX and R11 are
+    /// clobbered.
+    #[track_caller]
+    fn syscall(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("syscall instruction not implemented")
+    }
+
+    /// Emits an ENDBR64 instruction, marking a valid indirect-branch target.
+    ///
+    /// Under CET indirect branch tracking, an indirect `call` or `jmp` must
+    /// land on one; elsewhere it executes as a NOP.
+    #[track_caller]
+    fn endbr64(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("endbr64 instruction not implemented")
+    }
+
+    /// Emits a UD2 instruction, which always raises an invalid-opcode
+    /// exception.
+    #[track_caller]
+    fn ud2(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("ud2 instruction not implemented")
+    }
+
+    /// Emits an INT3 instruction, the one-byte breakpoint trap.
+    #[track_caller]
+    fn int3(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("int3 instruction not implemented")
+    }
+
+    /// Emits a PAUSE instruction, a hint that the code is a spin-wait loop.
+    ///
+    /// The core slows the loop down, saving power and leaving resources to
+    /// the other hyperthread, and avoids the memory-order mis-speculation
+    /// that would otherwise follow the loop's exit.
+    #[track_caller]
+    fn pause(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("pause instruction not implemented")
+    }
+
+    /// Emits a PREFETCHT0 instruction, a hint to fetch the cache line holding
+    /// `mem` into every level of the cache hierarchy.
+    ///
+    /// Prefetches never fault, so `mem` may be any address.
+    #[track_caller]
+    fn prefetcht0(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("prefetcht0 instruction not implemented")
+    }
+
+    /// Emits a PREFETCHT1 instruction, a hint to fetch the cache line holding
+    /// `mem` into the second-level cache and above.
+    #[track_caller]
+    fn prefetcht1(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("prefetcht1 instruction not implemented")
+    }
+
+    /// Emits a PREFETCHT2 instruction, a hint to fetch the cache line holding
+    /// `mem` into the third-level cache and above.
+    #[track_caller]
+    fn prefetcht2(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("prefetcht2 instruction not implemented")
+    }
+
+    /// Emits a PREFETCHNTA instruction, a hint to fetch the cache line holding
+    /// `mem` close to the core while keeping it out of the other caches, for
+    /// data used once.
+    #[track_caller]
+    fn prefetchnta(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("prefetchnta instruction not implemented")
+    }
+
+    /// Emits a CLFLUSH instruction, writing the cache line holding `mem`
+    /// back to memory if it is dirty and invalidating it in every cache.
+    ///
+    /// CLFLUSH is ordered against stores and other CLFLUSHes.
+    #[track_caller]
+    fn clflush(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("clflush instruction not implemented")
+    }
+
+    /// Emits a CLFLUSHOPT instruction, which flushes like
+    /// [`clflush`](Self::clflush) but is only ordered against stores to the
+    /// same line, so an [`sfence`](Self::sfence) has to follow a batch of
+    /// them.
+    ///
+    /// Requires [`X64Arch::clflushopt`](crate::X64Arch::clflushopt).
+    #[track_caller]
+    fn clflushopt(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("clflushopt instruction not implemented")
+    }
+
+    /// Emits a CLWB instruction, writing the cache line holding `mem` back
+    /// to memory if it is dirty; the line may stay cached. Ordered like
+    /// [`clflushopt`](Self::clflushopt).
+    ///
+    /// Requires [`X64Arch::clwb`](crate::X64Arch::clwb).
+    #[track_caller]
+    fn clwb(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        todo!("clwb instruction not implemented")
+    }
+
+    /// Emits an SFENCE instruction, ordering earlier stores, CLFLUSHOPTs and
+    /// CLWBs before later ones.
+    #[track_caller]
+    fn sfence(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("sfence instruction not implemented")
+    }
+
+    /// Emits an RDTSC instruction, reading the time-stamp counter into
+    /// EDX:EAX and clearing the upper halves of RDX and RAX.
+    ///
+    /// The read is not ordered against surrounding instructions; see
+    /// [`rdtscp`](Self::rdtscp).
+    #[track_caller]
+    fn rdtsc(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("rdtsc instruction not implemented")
+    }
+
+    /// Emits an RDTSCP instruction, which reads the time-stamp counter like
+    /// [`rdtsc`](Self::rdtsc) once every earlier instruction has executed,
+    /// and the `IA32_TSC_AUX` value, normally the core number, into ECX.
+    ///
+    /// Requires [`X64Arch::rdtscp`](crate::X64Arch::rdtscp).
+    #[track_caller]
+    fn rdtscp(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("rdtscp instruction not implemented")
+    }
+
+    /// Marks a point control never reaches, trapping if it does.
+    ///
+    /// Emits [`ud2`](Self::ud2).
+    fn unreachable(&mut self, ctx: &mut Context, cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        self.ud2(ctx, cfg)
+    }
+
+    /// Stops in an attached debugger.
+    ///
+    /// Emits [`int3`](Self::int3).
+    fn debug_break(&mut self, ctx: &mut Context, cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        self.int3(ctx, cfg)
+    }
+
+    /// Emits raw bytes as data.
+    ///
+    /// Generates a `.byte` directive (or equivalent) for the given bytes.
+    #[track_caller]
+    fn db(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _bytes: &[u8],
+    ) -> Result<(), Self::Error> {
+        todo!("db directive not implemented")
+    }
+
+    /// Returns the current byte offset into the output stream, if tracked.
+    ///
+    /// Text-mode writers return `None`. Binary writers (e.g. iced) return
+    /// `Some(n)` where `n` is the number of bytes emitted so far.
+    fn current_offset(&self) -> Option<usize> {
+        None
+    }
+
+    /// Emits alignment padding to the next `alignment`-byte boundary.
+    ///
+    /// For text writers this emits a `.balign` assembler directive and the
+    /// assembler fills the gap with NOPs. For binary writers, NOP instructions
+    /// are emitted directly until the offset is aligned.
+    #[track_caller]
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _alignment: usize,
+    ) -> Result<(), Self::Error> {
+        todo!("align_to not implemented")
+    }
+
+    /// Emits a one-byte `nop`.
+    #[track_caller]
+    fn nop(&mut self, ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        todo!("nop not implemented")
+    }
+
+    /// Emits exactly `n_bytes` bytes of no-op padding.
+    ///
+    /// The padding is made of the multi-byte NOPs in [`NOPS`] (see
+    /// [`nop_fill`]), so long runs decode as few instructions.
+    #[track_caller]
+    fn nops(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        todo!("nops not implemented")
+    }
+
+    /// Pads with no-ops to the next `1 << pow2`-byte boundary.
+    ///
+    /// Text writers emit a `.p2align` directive; binary writers emit
+    /// [`nops`](Self::nops) directly. Use it before loop headers and other
+    /// hot branch targets.
+    #[track_caller]
+    fn align(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _pow2: u32,
+    ) -> Result<(), Self::Error> {
+        todo!("align not implemented")
+    }
+}
+
+/// Extended writer trait with label support.
+///
+/// This trait extends [`WriterCore`] with methods for working with labels,
+/// enabling structured control flow in generated code.
+pub trait Writer<L, Context>: WriterCore<Context> {
+    /// Sets a label at the current position.
     #[track_caller]
     fn set_label(
         &mut self,
@@ -534,134 +1911,507 @@ pub trait Writer<L, Context>: WriterCore<Context> {
     ) -> Result<(), Self::Error> {
         todo!("jcc_label not implemented")
     }
+
+    /// Emits a conditional jump to a label, expected to go as `hint` says.
+    ///
+    /// With [`X64Arch::branch_hints`](crate::X64Arch::branch_hints) set, a
+    /// [`Likely`](crate::BranchHint::Likely) jump is preceded by the `ds`
+    /// prefix byte. Otherwise this is [`jcc_label`](Self::jcc_label).
+    fn jcc_label_hinted(
+        &mut self,
+        ctx: &mut Context,
+        cfg: crate::X64Arch,
+        cc: crate::ConditionCode,
+        label: L,
+        hint: crate::BranchHint,
+    ) -> Result<(), Self::Error> {
+        if cfg.branch_hints && hint == crate::BranchHint::Likely {
+            self.db(ctx, cfg, &[0x3E])?;
+        }
+        self.jcc_label(ctx, cfg, cc, label)
+    }
+
+    /// Emits a 32-bit data word holding the signed offset from the word
+    /// itself to `label` (`.4byte label - .`).
+    ///
+    /// Entries of position-independent jump tables are written this way (see
+    /// [`jump_table`]).
+    #[track_caller]
+    fn dd_label(
+        &mut self,
+        ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        _label: L,
+    ) -> Result<(), Self::Error> {
+        todo!("dd_label not implemented")
+    }
+
+    /// Runs `body` inside a [`LabelScope`](label_scope::LabelScope) that allocates
+    /// labels with `alloc`.
+    ///
+    /// Once `body` returns, every label it allocated must have been defined exactly
+    /// once; otherwise a [`LabelScopeError`](label_scope::LabelScopeError) is returned.
+    #[cfg(feature = "alloc")]
+    fn loop_scope<T, F: FnMut() -> L>(
+        &mut self,
+        ctx: &mut Context,
+        alloc: F,
+        body: impl FnOnce(
+            &mut label_scope::LabelScope<'_, Self, L, F>,
+            &mut Context,
+        ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>,
+    ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>
+    where
+        Self: Sized,
+        L: Clone + PartialEq,
+    {
+        let mut scope = label_scope::LabelScope::new(self, alloc);
+        let result = body(&mut scope, ctx)?;
+        scope.finish()?;
+        Ok(result)
+    }
+
+    /// Allocates a single label with `alloc` and passes it to `body`, like
+    /// [`Writer::loop_scope`].
+    #[cfg(feature = "alloc")]
+    fn with_label<T, F: FnMut() -> L>(
+        &mut self,
+        ctx: &mut Context,
+        alloc: F,
+        body: impl FnOnce(
+            &mut label_scope::LabelScope<'_, Self, L, F>,
+            &mut Context,
+            L,
+        ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>,
+    ) -> Result<T, label_scope::LabelScopeError<L, Self::Error>>
+    where
+        Self: Sized,
+        L: Clone + PartialEq,
+    {
+        self.loop_scope(ctx, alloc, |scope, ctx| {
+            let label = scope.label();
+            body(scope, ctx, label)
+        })
+    }
 }
 #[macro_export]
 macro_rules! writer_dispatch {
+    (@core [ $($t:tt)* ] $ty:ty => $e:ty [$ctx:ty] [$wrapped:ty]) => {
+        impl<$($t)*> $crate::out::WriterCore<$ctx> for $ty{
+            type Error = $e;
+            fn hlt(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::hlt(&mut **self, ctx, cfg)
+            }
+            fn xchg(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::xchg(&mut **self, ctx, cfg, dest, src)
+            }
+            fn push(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::push(&mut **self, ctx, cfg, op)
+            }
+            fn pop(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::pop(&mut **self, ctx, cfg, op)
+            }
+            fn pushf(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::pushf(&mut **self, ctx, cfg)
+            }
+            fn popf(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::popf(&mut **self, ctx, cfg)
+            }
+            fn lahf(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lahf(&mut **self, ctx, cfg)
+            }
+            fn sahf(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sahf(&mut **self, ctx, cfg)
+            }
+            fn call(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::call(&mut **self, ctx, cfg,op)
+            }
+            fn jmp(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::jmp(&mut **self, ctx, cfg,op)
+            }
+            fn cmp(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cmp(&mut **self, ctx, cfg,a,b)
+            }
+            fn cmp0(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cmp0(&mut **self, ctx, cfg,op)
+            }
+            fn test(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::test(&mut **self, ctx, cfg,a,b)
+            }
+            fn test0(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::test0(&mut **self, ctx, cfg,op)
+            }
+            fn cmovcc(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch,cc: $crate::ConditionCode, op: &(dyn $crate::out::arg::MemArg + '_),val: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cmovcc(&mut **self, ctx, cfg,cc,op,val)
+            }
+            fn setcc(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch,cc: $crate::ConditionCode, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::setcc(&mut **self, ctx, cfg,cc,op)
+            }
+            fn lea(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch,
+                dest: &(dyn $crate::out::arg::MemArg + '_),
+                src: &(dyn $crate::out::arg::MemArg + '_),
+
+            ) -> $crate::__::core::result::Result<(), Self::Error> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lea(&mut **self, ctx, cfg, dest, src)
+            }
+
+            fn get_ip(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::get_ip(&mut **self, ctx, cfg)
+            }
+            fn ret(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ret(&mut **self, ctx, cfg)
+            }
+            fn mov64(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, r: &(dyn $crate::out::arg::MemArg + '_), val: u64) -> $crate::__::core::result::Result<(),Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mov64(&mut **self, ctx, cfg,r,val)
+            }
+            fn mov(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mov(&mut **self, ctx, cfg,dest,src)
+            }
+            fn not(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::not(&mut **self, ctx, cfg,op)
+            }
+            fn neg(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::neg(&mut **self, ctx, cfg,op)
+            }
+            fn inc(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::inc(&mut **self, ctx, cfg,op)
+            }
+            fn dec(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::dec(&mut **self, ctx, cfg,op)
+            }
+            fn mul(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mul(&mut **self, ctx, cfg,a,b)
+            }
+            fn imul2(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::imul2(&mut **self, ctx, cfg, dest, src)
+            }
+            fn imul3(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::imul3(&mut **self, ctx, cfg, dest, src, imm)
+            }
+            fn mul_wide(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, hi: &(dyn $crate::out::arg::MemArg + '_), lo: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mul_wide(&mut **self, ctx, cfg, hi, lo, a, b)
+            }
+            fn imul_wide(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, hi: &(dyn $crate::out::arg::MemArg + '_), lo: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::imul_wide(&mut **self, ctx, cfg, hi, lo, a, b)
+            }
+            fn div(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::div(&mut **self, ctx, cfg,a,b)
+            }
+            fn idiv(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::idiv(&mut **self, ctx, cfg,a,b)
+            }
+            fn and(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::and(&mut **self, ctx, cfg,a,b)
+            }
+            fn or(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::or(&mut **self, ctx, cfg,a,b)
+            }
+            fn eor(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::eor(&mut **self, ctx, cfg,a,b)
+            }
+            fn shl(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::shl(&mut **self, ctx, cfg,a,b)
+            }
+            fn shr(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::shr(&mut **self, ctx, cfg,a,b)
+            }
+            fn sar(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sar(&mut **self, ctx, cfg,a,b)
+            }
+            fn rol(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::rol(&mut **self, ctx, cfg,a,b)
+            }
+            fn ror(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ror(&mut **self, ctx, cfg,a,b)
+            }
+            fn sub(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sub(&mut **self, ctx, cfg,a,b)
+            }
+            fn add(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::add(&mut **self, ctx, cfg,a,b)
+            }
+            fn adc(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::adc(&mut **self, ctx, cfg,a,b)
+            }
+            fn sbb(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sbb(&mut **self, ctx, cfg,a,b)
+            }
+            fn add_imm(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::add_imm(&mut **self, ctx, cfg, a, imm)
+            }
+            fn sub_imm(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sub_imm(&mut **self, ctx, cfg, a, imm)
+            }
+            fn and_imm(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::and_imm(&mut **self, ctx, cfg, a, imm)
+            }
+            fn or_imm(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::or_imm(&mut **self, ctx, cfg, a, imm)
+            }
+            fn eor_imm(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::eor_imm(&mut **self, ctx, cfg, a, imm)
+            }
+            fn cmp_imm(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cmp_imm(&mut **self, ctx, cfg, a, imm)
+            }
+            fn movsx(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::movsx(&mut **self, ctx, cfg,dest,src)
+            }
+            fn movzx(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::movzx(&mut **self, ctx, cfg,dest,src)
+            }
+            fn popcnt(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::popcnt(&mut **self, ctx, cfg,dest,src)
+            }
+            fn lzcnt(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::lzcnt(&mut **self, ctx, cfg,dest,src)
+            }
+            fn tzcnt(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::tzcnt(&mut **self, ctx, cfg,dest,src)
+            }
+            fn bswap(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::bswap(&mut **self, ctx, cfg, op)
+            }
+            fn fadd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fadd(&mut **self, ctx, cfg,dest,src)
+            }
+            fn fsub(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsub(&mut **self, ctx, cfg,dest,src)
+            }
+            fn fmul(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmul(&mut **self, ctx, cfg,dest,src)
+            }
+            fn fdiv(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fdiv(&mut **self, ctx, cfg,dest,src)
+            }
+            fn fcmp(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fcmp(&mut **self, ctx, cfg, a, b)
+            }
+            fn fmin(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmin(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fmax(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmax(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fsqrt(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsqrt(&mut **self, ctx, cfg, dest, src)
+            }
+            fn fabs(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), scratch: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fabs(&mut **self, ctx, cfg, dest, scratch)
+            }
+            fn fneg(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), scratch: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fneg(&mut **self, ctx, cfg, dest, scratch)
+            }
+            fn cvtsi2sd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cvtsi2sd(&mut **self, ctx, cfg, dest, src)
+            }
+            fn cvttsd2si(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cvttsd2si(&mut **self, ctx, cfg, dest, src)
+            }
+            fn vfmadd231sd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::vfmadd231sd(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn vfmsub231sd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::vfmsub231sd(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn vfnmadd231sd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::vfnmadd231sd(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn vfnmsub231sd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::vfnmsub231sd(&mut **self, ctx, cfg, dest, a, b)
+            }
+            fn kmov(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: $crate::MaskReg, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::kmov(&mut **self, ctx, cfg, dest, src)
+            }
+            fn vmovupd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::vmovupd(&mut **self, ctx, cfg, dest, mask, src)
+            }
+            #[allow(clippy::too_many_arguments)]
+            fn vaddpd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::vaddpd(&mut **self, ctx, cfg, dest, mask, a, b, broadcast)
+            }
+            #[allow(clippy::too_many_arguments)]
+            fn vsubpd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::vsubpd(&mut **self, ctx, cfg, dest, mask, a, b, broadcast)
+            }
+            #[allow(clippy::too_many_arguments)]
+            fn vmulpd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::vmulpd(&mut **self, ctx, cfg, dest, mask, a, b, broadcast)
+            }
+            #[allow(clippy::too_many_arguments)]
+            fn vdivpd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::vdivpd(&mut **self, ctx, cfg, dest, mask, a, b, broadcast)
+            }
+            #[allow(clippy::too_many_arguments)]
+            fn vfmadd231pd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::vfmadd231pd(&mut **self, ctx, cfg, dest, mask, a, b, broadcast)
+            }
+            #[allow(clippy::too_many_arguments)]
+            fn vcmppd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: $crate::MaskReg, mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), predicate: u8, broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::vcmppd(&mut **self, ctx, cfg, dest, mask, a, b, predicate, broadcast)
+            }
+            fn fmov(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmov(&mut **self, ctx, cfg,dest,src)
+            }
+            fn addss(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::addss(&mut **self, ctx, cfg, dest, src)
+            }
+            fn subss(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::subss(&mut **self, ctx, cfg, dest, src)
+            }
+            fn mulss(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::mulss(&mut **self, ctx, cfg, dest, src)
+            }
+            fn divss(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::divss(&mut **self, ctx, cfg, dest, src)
+            }
+            fn ucomiss(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ucomiss(&mut **self, ctx, cfg, a, b)
+            }
+            fn minss(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::minss(&mut **self, ctx, cfg, dest, src)
+            }
+            fn maxss(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::maxss(&mut **self, ctx, cfg, dest, src)
+            }
+            fn sqrtss(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sqrtss(&mut **self, ctx, cfg, dest, src)
+            }
+            fn cvtsi2ss(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cvtsi2ss(&mut **self, ctx, cfg, dest, src)
+            }
+            fn cvttss2si(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cvttss2si(&mut **self, ctx, cfg, dest, src)
+            }
+            fn cvtss2sd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cvtss2sd(&mut **self, ctx, cfg, dest, src)
+            }
+            fn cvtsd2ss(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cvtsd2ss(&mut **self, ctx, cfg, dest, src)
+            }
+            fn movss(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::movss(&mut **self, ctx, cfg, dest, src)
+            }
+            fn ldmxcsr(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ldmxcsr(&mut **self, ctx, cfg, src)
+            }
+            fn stmxcsr(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::stmxcsr(&mut **self, ctx, cfg, dest)
+            }
+            fn rdsegbase(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, seg: $crate::out::arg::Segment, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::rdsegbase(&mut **self, ctx, cfg, seg, dest)
+            }
+            fn wrsegbase(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, seg: $crate::out::arg::Segment, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::wrsegbase(&mut **self, ctx, cfg, seg, src)
+            }
+            fn fld(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fld(&mut **self, ctx, cfg, src)
+            }
+            fn fst(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fst(&mut **self, ctx, cfg, dest)
+            }
+            fn fstp(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fstp(&mut **self, ctx, cfg, dest)
+            }
+            fn faddp(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::faddp(&mut **self, ctx, cfg)
+            }
+            fn fsubp(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fsubp(&mut **self, ctx, cfg)
+            }
+            fn fmulp(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fmulp(&mut **self, ctx, cfg)
+            }
+            fn fdivp(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::fdivp(&mut **self, ctx, cfg)
+            }
+            fn movs(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, size: $crate::__::MemorySize, rep: $crate::RepPrefix) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::movs(&mut **self, ctx, cfg, size, rep)
+            }
+            fn stos(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, size: $crate::__::MemorySize, rep: $crate::RepPrefix) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::stos(&mut **self, ctx, cfg, size, rep)
+            }
+            fn cmps(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, size: $crate::__::MemorySize, rep: $crate::RepPrefix) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::cmps(&mut **self, ctx, cfg, size, rep)
+            }
+            fn scas(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, size: $crate::__::MemorySize, rep: $crate::RepPrefix) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::scas(&mut **self, ctx, cfg, size, rep)
+            }
+            fn syscall(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::syscall(&mut **self, ctx, cfg)
+            }
+            fn endbr64(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::endbr64(&mut **self, ctx, cfg)
+            }
+            fn ud2(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::ud2(&mut **self, ctx, cfg)
+            }
+            fn int3(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::int3(&mut **self, ctx, cfg)
+            }
+            fn pause(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::pause(&mut **self, ctx, cfg)
+            }
+            fn prefetcht0(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::prefetcht0(&mut **self, ctx, cfg,mem)
+            }
+            fn prefetcht1(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::prefetcht1(&mut **self, ctx, cfg,mem)
+            }
+            fn prefetcht2(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::prefetcht2(&mut **self, ctx, cfg,mem)
+            }
+            fn prefetchnta(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::prefetchnta(&mut **self, ctx, cfg,mem)
+            }
+            fn clflush(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::clflush(&mut **self, ctx, cfg,mem)
+            }
+            fn clflushopt(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::clflushopt(&mut **self, ctx, cfg,mem)
+            }
+            fn clwb(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::clwb(&mut **self, ctx, cfg,mem)
+            }
+            fn sfence(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::sfence(&mut **self, ctx, cfg)
+            }
+            fn rdtsc(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::rdtsc(&mut **self, ctx, cfg)
+            }
+            fn rdtscp(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::rdtscp(&mut **self, ctx, cfg)
+            }
+            fn unreachable(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::unreachable(&mut **self, ctx, cfg)
+            }
+            fn debug_break(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::debug_break(&mut **self, ctx, cfg)
+            }
+            fn db(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, bytes: &[u8]) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::db(&mut **self, ctx, cfg,bytes)
+            }
+            fn current_offset(&self) -> ::core::option::Option<usize> {
+                <$wrapped as $crate::out::WriterCore<$ctx>>::current_offset(&**self)
+            }
+            fn align_to(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, alignment: usize) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::align_to(&mut **self, ctx, cfg, alignment)
+            }
+            fn nop(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::nop(&mut **self, ctx, cfg)
+            }
+            fn nops(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, n_bytes: usize) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::nops(&mut **self, ctx, cfg, n_bytes)
+            }
+            fn align(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, pow2: u32) -> $crate::__::core::result::Result<(), Self::Error>{
+                <$wrapped as $crate::out::WriterCore<$ctx>>::align(&mut **self, ctx, cfg, pow2)
+            }
+        }
+    };
     ($( [ $($t:tt)* ] [$($u:tt)*] $ty:ty => $e:ty [$l:ty] [$ctx:ty] [$wrapped:ty]),*) => {
         const _: () = {
             $(
-                impl<$($t)*> $crate::out::WriterCore<$ctx> for $ty{
-                    type Error = $e;
-                    fn hlt(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(),Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::hlt(&mut **self, ctx, cfg)
-                    }
-                    fn xchg(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::xchg(&mut **self, ctx, cfg, dest, src)
-                    }
-                    fn push(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::push(&mut **self, ctx, cfg, op)
-                    }
-                    fn pop(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::pop(&mut **self, ctx, cfg, op)
-                    }
-                    fn pushf(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::pushf(&mut **self, ctx, cfg)
-                    }
-                    fn popf(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::popf(&mut **self, ctx, cfg)
-                    }
-                    fn call(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::call(&mut **self, ctx, cfg,op)
-                    }
-                    fn jmp(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::jmp(&mut **self, ctx, cfg,op)
-                    }
-                    fn cmp(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::cmp(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn cmp0(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::cmp0(&mut **self, ctx, cfg,op)
-                    }
-                    fn cmovcc(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch,cc: $crate::ConditionCode, op: &(dyn $crate::out::arg::MemArg + '_),val: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::cmovcc(&mut **self, ctx, cfg,cc,op,val)
-                    }
-                    fn lea(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch,
-                        dest: &(dyn $crate::out::arg::MemArg + '_),
-                        src: &(dyn $crate::out::arg::MemArg + '_),
-
-                    ) -> $crate::__::core::result::Result<(), Self::Error> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::lea(&mut **self, ctx, cfg, dest, src)
-                    }
-
-                    fn get_ip(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::get_ip(&mut **self, ctx, cfg)
-                    }
-                    fn ret(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::ret(&mut **self, ctx, cfg)
-                    }
-                    fn mov64(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, r: &(dyn $crate::out::arg::MemArg + '_), val: u64) -> $crate::__::core::result::Result<(),Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::mov64(&mut **self, ctx, cfg,r,val)
-                    }
-                    fn mov(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::mov(&mut **self, ctx, cfg,dest,src)
-                    }
-                    fn not(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::not(&mut **self, ctx, cfg,op)
-                    }
-                    fn mul(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::mul(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn div(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::div(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn idiv(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::idiv(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn and(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::and(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn or(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::or(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn eor(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::eor(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn shl(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::shl(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn shr(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::shr(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn sar(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::sar(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn sub(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::sub(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn add(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::add(&mut **self, ctx, cfg,a,b)
-                    }
-                    fn movsx(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::movsx(&mut **self, ctx, cfg,dest,src)
-                    }
-                    fn movzx(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::movzx(&mut **self, ctx, cfg,dest,src)
-                    }
-                    fn fadd(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::fadd(&mut **self, ctx, cfg,dest,src)
-                    }
-                    fn fsub(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::fsub(&mut **self, ctx, cfg,dest,src)
-                    }
-                    fn fmul(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::fmul(&mut **self, ctx, cfg,dest,src)
-                    }
-                    fn fdiv(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::fdiv(&mut **self, ctx, cfg,dest,src)
-                    }
-                    fn fmov(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::fmov(&mut **self, ctx, cfg,dest,src)
-                    }
-                    fn db(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, bytes: &[u8]) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::db(&mut **self, ctx, cfg,bytes)
-                    }
-                    fn current_offset(&self) -> ::core::option::Option<usize> {
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::current_offset(&**self)
-                    }
-                    fn align_to(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, alignment: usize) -> $crate::__::core::result::Result<(), Self::Error>{
-                        <$wrapped as $crate::out::WriterCore<$ctx>>::align_to(&mut **self, ctx, cfg, alignment)
-                    }
-                }
+                $crate::writer_dispatch!(@core [ $($t)* ] $ty => $e [$ctx] [$wrapped]);
                 impl<$($u)*>$crate::out::Writer<$l, $ctx> for $ty{
 
                     fn set_label(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, s: $l) -> $crate::__::core::result::Result<(), Self::Error> {
@@ -679,6 +2429,12 @@ macro_rules! writer_dispatch {
                     fn jcc_label(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, cc: $crate::ConditionCode, label: $l) -> $crate::__::core::result::Result<(), Self::Error> {
                         <$wrapped as $crate::out::Writer<$l, $ctx>>::jcc_label(&mut **self, ctx, cfg, cc, label)
                     }
+                    fn jcc_label_hinted(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, cc: $crate::ConditionCode, label: $l, hint: $crate::BranchHint) -> $crate::__::core::result::Result<(), Self::Error> {
+                        <$wrapped as $crate::out::Writer<$l, $ctx>>::jcc_label_hinted(&mut **self, ctx, cfg, cc, label, hint)
+                    }
+                    fn dd_label(&mut self, ctx: &mut $ctx, cfg: $crate::X64Arch, label: $l) -> $crate::__::core::result::Result<(), Self::Error> {
+                        <$wrapped as $crate::out::Writer<$l, $ctx>>::dd_label(&mut **self, ctx, cfg, label)
+                    }
 
                 }
             )*
@@ -9,25 +9,72 @@ use super::*;
 use crate::reg::{RegDisplay, X64Reg};
 use core::{
     convert::Infallible,
-    fmt::{Display, Formatter},
+    fmt::{Display, Formatter, Write},
     mem::transmute,
 };
 
-/// Represents a concrete argument kind (register or literal).
+/// Represents a concrete argument kind (register, literal, or symbol).
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArgKind {
     /// A register with a specific size.
     Reg {
         /// The register.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::reg"))]
         reg: Reg,
         /// The operand size.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::memory_size"))]
         size: MemorySize,
     },
     /// A literal 64-bit value.
     Lit(u64),
+    /// The address of a named global or external function, plus `addend`.
+    ///
+    /// As a direct operand this is the symbol's address (`offset sym` in
+    /// text, an absolute relocation in binary output); as the base of a
+    /// memory operand it is addressed RIP-relatively (`[rip + sym]`).
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    Sym {
+        /// The symbol name.
+        name: &'static str,
+        /// Byte offset added to the symbol's address.
+        addend: i64,
+    },
+    /// A legacy high-byte register: bits 8–15 of `rax`, `rcx`, `rdx` or
+    /// `rbx` (`ah`, `ch`, `dh`, `bh`).
+    ///
+    /// Only registers 0–3 have a high byte. An instruction that uses one
+    /// cannot carry a REX prefix, so it cannot also name `spl`–`dil`,
+    /// `r8`–`r15` or a 64-bit operand; see [`crate::validate`].
+    HighByte(#[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::reg"))] Reg),
+    /// The instruction pointer.
+    ///
+    /// Only valid as the base of a memory operand without an index, where
+    /// `[rip + disp]` addresses `disp` bytes from the end of the instruction.
+    Rip,
+    /// The missing base of a memory operand, leaving `[index*scale + disp]`
+    /// or the absolute `[disp]`.
+    ///
+    /// Only valid as a memory base. Unlike `Lit(0)`, it never stands for a
+    /// value.
+    NoBase,
 }
 impl ArgKind {
+    /// `ah`, bits 8–15 of `rax`.
+    pub const AH: ArgKind = ArgKind::HighByte(Reg(0));
+    /// `ch`, bits 8–15 of `rcx`.
+    pub const CH: ArgKind = ArgKind::HighByte(Reg(1));
+    /// `dh`, bits 8–15 of `rdx`.
+    pub const DH: ArgKind = ArgKind::HighByte(Reg(2));
+    /// `bh`, bits 8–15 of `rbx`.
+    pub const BH: ArgKind = ArgKind::HighByte(Reg(3));
+
+    /// Creates a reference to the symbol `name`.
+    pub const fn sym(name: &'static str) -> Self {
+        ArgKind::Sym { name, addend: 0 }
+    }
+
     /// Creates a displayable representation of this argument kind.
     pub fn display(&self, opts: crate::DisplayOpts) -> ArgKindDisplay {
         match self {
@@ -36,6 +83,13 @@ impl ArgKind {
                 RegFormatOpts::with_reg_class(opts.arch, *size, opts.reg_class),
             )),
             ArgKind::Lit(i) => ArgKindDisplay::Lit(*i),
+            ArgKind::Sym { name, addend } => ArgKindDisplay::Sym {
+                name: *name,
+                addend: *addend,
+            },
+            ArgKind::HighByte(reg) => ArgKindDisplay::HighByte(*reg),
+            ArgKind::Rip => ArgKindDisplay::Rip,
+            ArgKind::NoBase => ArgKindDisplay::NoBase,
         }
     }
 }
@@ -47,12 +101,56 @@ pub enum ArgKindDisplay {
     Reg(RegDisplay),
     /// A literal value.
     Lit(u64),
+    /// A symbol's address used as an immediate.
+    Sym {
+        /// The symbol name.
+        name: &'static str,
+        /// Byte offset added to the symbol's address.
+        addend: i64,
+    },
+    /// A symbol used as a memory base, addressed RIP-relatively.
+    RipSym {
+        /// The symbol name.
+        name: &'static str,
+        /// Byte offset added to the symbol's address.
+        addend: i64,
+    },
+    /// A high-byte register.
+    HighByte(Reg),
+    /// The instruction pointer.
+    Rip,
+    /// A memory operand without a base register.
+    NoBase,
 }
 impl Display for ArgKindDisplay {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             ArgKindDisplay::Reg(reg_display) => write!(f, "{reg_display}"),
             ArgKindDisplay::Lit(i) => write!(f, "{i}"),
+            ArgKindDisplay::Sym { name, addend } => {
+                write!(f, "offset {}", SymDisplay(name, *addend))
+            }
+            ArgKindDisplay::RipSym { name, addend } => {
+                write!(f, "rip + {}", SymDisplay(name, *addend))
+            }
+            ArgKindDisplay::HighByte(reg) => match crate::HIGH_BYTE_NAMES.get(reg.0 as usize) {
+                Some(name) => write!(f, "{name}"),
+                None => write!(f, "<invalid high byte {}>", reg.0),
+            },
+            ArgKindDisplay::Rip => write!(f, "rip"),
+            ArgKindDisplay::NoBase => Ok(()),
+        }
+    }
+}
+
+/// Formats `name` followed by a signed addend, omitting a zero addend.
+pub struct SymDisplay<'a>(pub &'a str, pub i64);
+impl Display for SymDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.1 {
+            0 => write!(f, "{}", self.0),
+            a if a > 0 => write!(f, "{}+{a}", self.0),
+            a => write!(f, "{}{a}", self.0),
         }
     }
 }
@@ -64,16 +162,29 @@ impl Display for ArgKind {
 
 /// Segment register override for memory operands.
 ///
-/// Used by LFI sandboxed code generation to emit `gs:`-prefixed memory
-/// accesses, which the LFI verifier requires for all non-rsp/non-rip
-/// memory operands in sandboxed code.
+/// `fs:` addresses thread-local storage under the System V ABI. `gs:` is used
+/// by LFI sandboxed code generation, whose verifier requires it for all
+/// non-rsp/non-rip memory operands in sandboxed code.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Segment {
     /// No segment override (default).
     #[default]
     None,
     /// GS segment register override — emits `gs:` prefix before the memory operand.
     Gs,
+    /// FS segment register override — emits `fs:` prefix; addresses thread-local storage.
+    Fs,
+}
+impl Segment {
+    /// The segment register's name (`fs`, `gs`), or `""` for [`Segment::None`].
+    pub const fn name(self) -> &'static str {
+        match self {
+            Segment::None => "",
+            Segment::Gs => "gs",
+            Segment::Fs => "fs",
+        }
+    }
 }
 
 /// Represents a memory argument kind.
@@ -82,18 +193,24 @@ pub enum Segment {
 /// displacement, and size.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MemArgKind<A = ArgKind> {
     /// A direct operand (not a memory reference).
     NoMem(A),
     /// A memory reference.
     Mem {
         /// The base operand.
+        ///
+        /// [`ArgKind::NoBase`] leaves `[index*scale + disp]` or the absolute
+        /// `[disp]`; [`ArgKind::Rip`] addresses relative to the next
+        /// instruction.
         base: A,
         /// Optional scaled index (operand, scale factor).
         offset: Option<(A, u32)>,
-        /// Displacement added to the address.
-        disp: u32,
+        /// Signed displacement added to the address.
+        disp: i32,
         /// Size of the memory access.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::memory_size"))]
         size: MemorySize,
         /// Register class for pointer naming (xmmword vs qword, etc.).
         reg_class: crate::RegisterClass,
@@ -119,8 +236,12 @@ impl<A: Arg> MemArgKind<A> {
             } => {
                 // For memory operands, force base and offset to be GPRs
                 let gpr_opts = crate::DisplayOpts::new(opts.arch);
+                let base = match base.display(gpr_opts) {
+                    ArgKindDisplay::Sym { name, addend } => ArgKindDisplay::RipSym { name, addend },
+                    base => base,
+                };
                 MemArgKind::Mem {
-                    base: base.display(gpr_opts),
+                    base,
                     offset: offset
                         .as_ref()
                         .map(|(a, scale)| (a.display(gpr_opts), *scale)),
@@ -166,26 +287,96 @@ impl<T: Display> Display for MemArgKind<T> {
                 let seg = match segment {
                     Segment::None => "",
                     Segment::Gs => "gs:",
+                    Segment::Fs => "fs:",
                 };
-                let c;
-                let d;
-
-                match offset.as_ref() {
-                    None => match format_args!("") {
-                        x => write!(f, "{ptr} ptr {seg}[{base}{x}+{disp}]"),
-                    },
-                    Some((a, b)) => {
-                        c = a;
-                        d = b;
-                        match format_args!("+{c}*{d}") {
-                            x => write!(f, "{ptr} ptr {seg}[{base}{x}+{disp}]"),
-                        }
+                write!(f, "{ptr} ptr {seg}[")?;
+                // Separators depend on whether the base printed anything.
+                let mut out = Tracked { f, wrote: false };
+                write!(out, "{base}")?;
+                if let Some((index, scale)) = offset {
+                    if out.wrote {
+                        out.write_char('+')?;
                     }
+                    write!(out, "{index}*{scale}")?;
+                }
+                if out.wrote && *disp >= 0 {
+                    out.write_char('+')?;
                 }
+                write!(out, "{disp}]")
             }
         }
     }
 }
+
+/// Forwards to a formatter, noting whether anything was written.
+struct Tracked<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    wrote: bool,
+}
+impl Write for Tracked<'_, '_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.wrote |= !s.is_empty();
+        self.f.write_str(s)
+    }
+}
+impl MemArgKind<ArgKind> {
+    /// Creates an `fs:[disp]` operand addressing thread-local storage at
+    /// offset `disp` from the thread pointer.
+    pub fn tls(disp: i32, size: MemorySize) -> Self {
+        MemArgKind::Mem {
+            base: ArgKind::NoBase,
+            offset: None,
+            disp,
+            size,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: Segment::Fs,
+        }
+    }
+
+    /// Creates a RIP-relative `[rip + name]` operand addressing the global
+    /// `name`.
+    pub fn sym(name: &'static str, size: MemorySize) -> Self {
+        MemArgKind::Mem {
+            base: ArgKind::sym(name),
+            offset: None,
+            disp: 0,
+            size,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: Segment::None,
+        }
+    }
+
+    /// Creates a `[rip + disp]` operand, `disp` bytes from the end of the
+    /// instruction.
+    pub fn rip(disp: i32, size: MemorySize) -> Self {
+        MemArgKind::Mem {
+            base: ArgKind::Rip,
+            offset: None,
+            disp,
+            size,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: Segment::None,
+        }
+    }
+
+    /// Creates a base-less `[index*scale + disp]` operand.
+    pub fn indexed(index: Reg, scale: u32, disp: i32, size: MemorySize) -> Self {
+        MemArgKind::Mem {
+            base: ArgKind::NoBase,
+            offset: Some((
+                ArgKind::Reg {
+                    reg: index,
+                    size: MemorySize::_64,
+                },
+                scale,
+            )),
+            disp,
+            size,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: Segment::None,
+        }
+    }
+}
 impl<A> MemArgKind<A> {
     /// Returns a reference view of this memory argument kind.
     pub fn as_ref<'a>(&'a self) -> MemArgKind<&'a A> {
@@ -356,8 +547,12 @@ pub trait Arg: MemArg {
         use core::iter::empty;
 
         match self.kind() {
-            ArgKind::Reg { reg, size } => ::alloc::boxed::Box::new([reg].into_iter()),
-            ArgKind::Lit(_) => ::alloc::boxed::Box::new(empty()),
+            ArgKind::Reg { reg, size: _ } | ArgKind::HighByte(reg) => {
+                ::alloc::boxed::Box::new([reg].into_iter())
+            }
+            ArgKind::Lit(_) | ArgKind::Sym { .. } | ArgKind::Rip | ArgKind::NoBase => {
+                ::alloc::boxed::Box::new(empty())
+            }
         }
     }
 }
@@ -468,7 +663,7 @@ impl<T: MemArg> MemArg for MemorySized<T> {
                     reg,
                     size: self.size,
                 },
-                ArgKind::Lit(l) => ArgKind::Lit(l),
+                other => other,
             }),
             MemArgKind::Mem {
                 base,
@@ -39,11 +39,25 @@ macro_rules! writers {
                 fn popf(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
                     $crate::__::core::write!(self,"popfq\n")
                 }
+                fn lahf(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"lahf\n")
+                }
+                fn sahf(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"sahf\n")
+                }
                 fn call(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    // Direct branches name the symbol without `offset`.
+                    if let $crate::out::arg::MemArgKind::NoMem($crate::out::arg::ArgKind::Sym { name, addend }) = op.concrete_mem_kind() {
+                        return $crate::__::core::write!(self,"call {}\n", $crate::out::arg::SymDisplay(name, addend));
+                    }
                     let op = op.mem_display(cfg.into());
                     $crate::__::core::write!(self,"call {op}\n")
                 }
                  fn jmp(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    // Direct branches name the symbol without `offset`.
+                    if let $crate::out::arg::MemArgKind::NoMem($crate::out::arg::ArgKind::Sym { name, addend }) = op.concrete_mem_kind() {
+                        return $crate::__::core::write!(self,"jmp {}\n", $crate::out::arg::SymDisplay(name, addend));
+                    }
                     let op = op.mem_display(cfg.into());
                     $crate::__::core::write!(self,"jmp {op}\n")
                 }
@@ -52,24 +66,48 @@ macro_rules! writers {
                     let b = b.mem_display(cfg.into());
                     $crate::__::core::write!(self,"cmp {a}, {b}\n")
                 }
+                fn cmp_imm(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"cmp {a}, {imm}\n")
+                }
                 fn cmp0(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
                     let op = op.mem_display(cfg.into());
                     $crate::__::core::write!(self,"cmp {op}, 0\n")
                 }
+                fn test(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"test {a}, {b}\n")
+                }
+                fn test0(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
+                    let op = op.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"test {op}, {op}\n")
+                }
                 fn cmovcc(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch,cc: $crate::ConditionCode, op: &(dyn $crate::out::arg::MemArg + '_),val:&(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                      let op = op.mem_display(cfg.into());
                      let val = val.mem_display(cfg.into());
                     $crate::__::core::write!(self,"cmov{cc} {op}, {val}\n")
                 }
+                fn setcc(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch,cc: $crate::ConditionCode, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                     let op = op.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"set{cc} {op}\n")
+                }
                 fn lea(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(),Self::Error>{
                     let dest = dest.mem_display(cfg.into());
                     let src = src.mem_display(cfg.into());
                     $crate::__::core::write!(self,"lea {dest}, {src}\n")
                 }
                 fn mov(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let movabs = cfg.imm_encoding == $crate::ImmEncoding::Imm64
+                        && $crate::__::core::matches!(dest.concrete_mem_kind(), $crate::out::arg::MemArgKind::NoMem($crate::out::arg::ArgKind::Reg { size: $crate::__::MemorySize::_64, .. }))
+                        && $crate::__::core::matches!(src.concrete_mem_kind(), $crate::out::arg::MemArgKind::NoMem($crate::out::arg::ArgKind::Lit(_) | $crate::out::arg::ArgKind::Sym { .. }));
                      let dest = dest.mem_display(cfg.into());
+                    if let (true, $crate::out::arg::MemArgKind::NoMem($crate::out::arg::ArgKind::Sym { name, addend })) = (cfg.pic, src.concrete_mem_kind()) {
+                        return $crate::__::core::write!(self,"lea {dest}, [rip + {}]\n", $crate::out::arg::SymDisplay(name, addend));
+                    }
+                    let mnemonic = if movabs { "movabs" } else { "mov" };
                     let src = src.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"mov {dest}, {src}\n")
+                    $crate::__::core::write!(self,"{mnemonic} {dest}, {src}\n")
                 }
 
                 fn get_ip(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(),Self::Error>{
@@ -81,17 +119,54 @@ macro_rules! writers {
                 }
                 fn mov64(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, r: &(dyn $crate::out::arg::MemArg + '_), val: u64) -> $crate::__::core::result::Result<(),Self::Error>{
                     let r = r.mem_display(cfg.into());
-                    $crate::__::core::write!(self,"mov {r}, {val}\n")
+                    let mnemonic = if cfg.imm_encoding == $crate::ImmEncoding::Imm64 { "movabs" } else { "mov" };
+                    $crate::__::core::write!(self,"{mnemonic} {r}, {val}\n")
                 }
                 fn not(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let op = op.mem_display(cfg.into());
                     $crate::__::core::write!(self,"not {op}\n")
                 }
+                fn neg(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let op = op.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"neg {op}\n")
+                }
+                fn inc(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let op = op.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"inc {op}\n")
+                }
+                fn dec(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let op = op.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"dec {op}\n")
+                }
                 fn mul(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let a = a.mem_display(cfg.into());
                     let b = b.mem_display(cfg.into());
                     $crate::__::core::write!(self,"mul {a},{b}\n")
                 }
+                fn imul2(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"imul {dest},{src}\n")
+                }
+                fn imul3(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"imul {dest},{src},{imm}\n")
+                }
+                fn mul_wide(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, hi: &(dyn $crate::out::arg::MemArg + '_), lo: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let hi = hi.mem_display(cfg.into());
+                    let lo = lo.mem_display(cfg.into());
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"mov rax,{a}\nmul {b}\nmov {lo},rax\nmov {hi},rdx\n")
+                }
+                fn imul_wide(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, hi: &(dyn $crate::out::arg::MemArg + '_), lo: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let hi = hi.mem_display(cfg.into());
+                    let lo = lo.mem_display(cfg.into());
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"mov rax,{a}\nimul {b}\nmov {lo},rax\nmov {hi},rdx\n")
+                }
                 fn div(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let a = a.mem_display(cfg.into());
                     let b = b.mem_display(cfg.into());
@@ -107,16 +182,28 @@ macro_rules! writers {
                     let b = b.mem_display(cfg.into());
                     $crate::__::core::write!(self,"and {a},{b}\n")
                 }
+                fn and_imm(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"and {a},{imm}\n")
+                }
                 fn or(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let a = a.mem_display(cfg.into());
                     let b = b.mem_display(cfg.into());
                     $crate::__::core::write!(self,"or {a},{b}\n")
                 }
+                fn or_imm(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"or {a},{imm}\n")
+                }
                 fn eor(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let a = a.mem_display(cfg.into());
                     let b = b.mem_display(cfg.into());
                     $crate::__::core::write!(self,"eor {a},{b}\n")
                 }
+                fn eor_imm(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"xor {a},{imm}\n")
+                }
                 fn shl(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let a = a.mem_display(cfg.into());
                     let b = b.mem_display(cfg.into());
@@ -134,16 +221,44 @@ macro_rules! writers {
                     let b = b.mem_display(cfg.into());
                     $crate::__::core::write!(self,"sar {a},{b}\n")
                 }
+                fn rol(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"rol {a},{b}\n")
+                }
+                fn ror(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"ror {a},{b}\n")
+                }
                 fn sub(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let a = a.mem_display(cfg.into());
                     let b = b.mem_display(cfg.into());
                     $crate::__::core::write!(self,"sub {a},{b}\n")
                 }
+                fn sub_imm(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"sub {a},{imm}\n")
+                }
                 fn add(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let a = a.mem_display(cfg.into());
                     let b = b.mem_display(cfg.into());
                     $crate::__::core::write!(self,"add {a},{b}\n")
                 }
+                fn adc(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"adc {a},{b}\n")
+                }
+                fn sbb(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    let b = b.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"sbb {a},{b}\n")
+                }
+                fn add_imm(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), imm: i32) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let a = a.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"add {a},{imm}\n")
+                }
                 fn movsx(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let dest = dest.mem_display(cfg.into());
                     let src = src.mem_display(cfg.into());
@@ -154,6 +269,25 @@ macro_rules! writers {
                     let src = src.mem_display(cfg.into());
                     $crate::__::core::write!(self,"movzx {dest},{src}\n")
                 }
+                fn popcnt(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"popcnt {dest},{src}\n")
+                }
+                fn lzcnt(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"lzcnt {dest},{src}\n")
+                }
+                fn tzcnt(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"tzcnt {dest},{src}\n")
+                }
+                fn bswap(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, op: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let op = op.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"bswap {op}\n")
+                }
                 fn fadd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
                     let dest = dest.mem_display(opts);
@@ -178,24 +312,374 @@ macro_rules! writers {
                     let src = src.mem_display(opts);
                     $crate::__::core::write!(self,"divsd {dest},{src}\n")
                 }
+                fn fcmp(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"ucomisd {a},{b}\n")
+                }
+                fn fmin(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"minsd {dest},{src}\n")
+                }
+                fn fmax(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"maxsd {dest},{src}\n")
+                }
+                fn fsqrt(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"sqrtsd {dest},{src}\n")
+                }
+                fn fabs(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), scratch: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let scratch = scratch.mem_display(opts);
+                    $crate::__::core::write!(self,"pcmpeqd {scratch},{scratch}\npsrlq {scratch},1\nandpd {dest},{scratch}\n")
+                }
+                fn fneg(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), scratch: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let scratch = scratch.mem_display(opts);
+                    $crate::__::core::write!(self,"pcmpeqd {scratch},{scratch}\npsllq {scratch},63\nxorpd {dest},{scratch}\n")
+                }
+                fn cvtsi2sd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display($crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm));
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"cvtsi2sd {dest},{src}\n")
+                }
+                fn cvttsd2si(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display($crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm));
+                    $crate::__::core::write!(self,"cvttsd2si {dest},{src}\n")
+                }
+                fn vfmadd231sd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"vfmadd231sd {dest},{a},{b}\n")
+                }
+                fn vfmsub231sd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"vfmsub231sd {dest},{a},{b}\n")
+                }
+                fn vfnmadd231sd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"vfnmadd231sd {dest},{a},{b}\n")
+                }
+                fn vfnmsub231sd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"vfnmsub231sd {dest},{a},{b}\n")
+                }
+                fn kmov(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: $crate::MaskReg, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let suffix = match src.concrete_mem_kind() {
+                        $crate::out::arg::MemArgKind::NoMem($crate::out::arg::ArgKind::Reg { size, .. }) | $crate::out::arg::MemArgKind::Mem { size, .. } => size,
+                        _ => $crate::__::MemorySize::_64,
+                    };
+                    let suffix = match suffix {
+                        $crate::__::MemorySize::_8 => "b",
+                        $crate::__::MemorySize::_16 => "w",
+                        $crate::__::MemorySize::_32 => "d",
+                        _ => "q",
+                    };
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"kmov{suffix} {dest}, {src}\n")
+                }
+                fn vmovupd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"vmovupd {dest}{mask},{src}\n")
+                }
+                fn vaddpd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let bcst = $crate::out::evex::Broadcast::new(dest, $crate::__::MemorySize::_64, broadcast);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"vaddpd {dest}{mask},{a},{b}{bcst}\n")
+                }
+                fn vsubpd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let bcst = $crate::out::evex::Broadcast::new(dest, $crate::__::MemorySize::_64, broadcast);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"vsubpd {dest}{mask},{a},{b}{bcst}\n")
+                }
+                fn vmulpd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let bcst = $crate::out::evex::Broadcast::new(dest, $crate::__::MemorySize::_64, broadcast);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"vmulpd {dest}{mask},{a},{b}{bcst}\n")
+                }
+                fn vdivpd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let bcst = $crate::out::evex::Broadcast::new(dest, $crate::__::MemorySize::_64, broadcast);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"vdivpd {dest}{mask},{a},{b}{bcst}\n")
+                }
+                fn vfmadd231pd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let bcst = $crate::out::evex::Broadcast::new(dest, $crate::__::MemorySize::_64, broadcast);
+                    let dest = dest.mem_display(opts);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"vfmadd231pd {dest}{mask},{a},{b}{bcst}\n")
+                }
+                fn vcmppd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: $crate::MaskReg, mask: $crate::out::evex::OpMask, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_), predicate: u8, broadcast: bool) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let bcst = $crate::out::evex::Broadcast::new(a, $crate::__::MemorySize::_64, broadcast);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"vcmppd {dest}{mask},{a},{b}{bcst},{predicate}\n")
+                }
                 fn fmov(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
                     let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
                     let dest = dest.mem_display(opts);
                     let src = src.mem_display(opts);
                     $crate::__::core::write!(self,"movsd {dest},{src}\n")
                 }
-                fn db(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch, bytes: &[u8]) -> $crate::__::core::result::Result<(), Self::Error>{
-                    $crate::__::core::write!(self, ".byte ")?;
+                fn addss(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"addss {dest},{src}\n")
+                }
+                fn subss(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"subss {dest},{src}\n")
+                }
+                fn mulss(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"mulss {dest},{src}\n")
+                }
+                fn divss(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"divss {dest},{src}\n")
+                }
+                fn ucomiss(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, a: &(dyn $crate::out::arg::MemArg + '_), b: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let a = a.mem_display(opts);
+                    let b = b.mem_display(opts);
+                    $crate::__::core::write!(self,"ucomiss {a},{b}\n")
+                }
+                fn minss(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"minss {dest},{src}\n")
+                }
+                fn maxss(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"maxss {dest},{src}\n")
+                }
+                fn sqrtss(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"sqrtss {dest},{src}\n")
+                }
+                fn cvtsi2ss(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display($crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm));
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"cvtsi2ss {dest},{src}\n")
+                }
+                fn cvttss2si(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    let src = src.mem_display($crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm));
+                    $crate::__::core::write!(self,"cvttss2si {dest},{src}\n")
+                }
+                fn cvtss2sd(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"cvtss2sd {dest},{src}\n")
+                }
+                fn cvtsd2ss(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"cvtsd2ss {dest},{src}\n")
+                }
+                fn movss(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_), src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let opts = $crate::DisplayOpts::with_reg_class(cfg, $crate::RegisterClass::Xmm);
+                    let dest = dest.mem_display(opts);
+                    let src = src.mem_display(opts);
+                    $crate::__::core::write!(self,"movss {dest},{src}\n")
+                }
+                fn ldmxcsr(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"ldmxcsr {src}\n")
+                }
+                fn stmxcsr(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"stmxcsr {dest}\n")
+                }
+                fn rdsegbase(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, seg: $crate::out::arg::Segment, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"rd{}base {dest}\n", seg.name())
+                }
+                fn wrsegbase(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, seg: $crate::out::arg::Segment, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"wr{}base {src}\n", seg.name())
+                }
+                fn fld(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, src: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let src = src.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"fld {src}\n")
+                }
+                fn fst(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"fst {dest}\n")
+                }
+                fn fstp(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, dest: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let dest = dest.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"fstp {dest}\n")
+                }
+                fn faddp(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"faddp st(1),st\n")
+                }
+                fn fsubp(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"fsubp st(1),st\n")
+                }
+                fn fmulp(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"fmulp st(1),st\n")
+                }
+                fn fdivp(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"fdivp st(1),st\n")
+                }
+                fn movs(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch, size: $crate::__::MemorySize, rep: $crate::RepPrefix) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let suffix = match size { $crate::__::MemorySize::_8 => 'b', $crate::__::MemorySize::_16 => 'w', $crate::__::MemorySize::_32 => 'd', _ => 'q' };
+                    $crate::__::core::write!(self,"{rep}movs{suffix}\n")
+                }
+                fn stos(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch, size: $crate::__::MemorySize, rep: $crate::RepPrefix) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let suffix = match size { $crate::__::MemorySize::_8 => 'b', $crate::__::MemorySize::_16 => 'w', $crate::__::MemorySize::_32 => 'd', _ => 'q' };
+                    $crate::__::core::write!(self,"{rep}stos{suffix}\n")
+                }
+                fn cmps(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch, size: $crate::__::MemorySize, rep: $crate::RepPrefix) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let suffix = match size { $crate::__::MemorySize::_8 => 'b', $crate::__::MemorySize::_16 => 'w', $crate::__::MemorySize::_32 => 'd', _ => 'q' };
+                    $crate::__::core::write!(self,"{rep}cmps{suffix}\n")
+                }
+                fn scas(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch, size: $crate::__::MemorySize, rep: $crate::RepPrefix) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let suffix = match size { $crate::__::MemorySize::_8 => 'b', $crate::__::MemorySize::_16 => 'w', $crate::__::MemorySize::_32 => 'd', _ => 'q' };
+                    $crate::__::core::write!(self,"{rep}scas{suffix}\n")
+                }
+                fn syscall(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"syscall\n")
+                }
+                fn endbr64(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"endbr64\n")
+                }
+                fn ud2(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"ud2\n")
+                }
+                fn int3(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"int3\n")
+                }
+                fn pause(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"pause\n")
+                }
+                fn prefetcht0(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"prefetcht0 {mem}\n")
+                }
+                fn prefetcht1(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"prefetcht1 {mem}\n")
+                }
+                fn prefetcht2(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"prefetcht2 {mem}\n")
+                }
+                fn prefetchnta(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"prefetchnta {mem}\n")
+                }
+                fn clflush(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"clflush {mem}\n")
+                }
+                fn clflushopt(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"clflushopt {mem}\n")
+                }
+                fn clwb(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, mem: &(dyn $crate::out::arg::MemArg + '_)) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let mem = mem.mem_display(cfg.into());
+                    $crate::__::core::write!(self,"clwb {mem}\n")
+                }
+                fn sfence(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"sfence\n")
+                }
+                fn rdtsc(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"rdtsc\n")
+                }
+                fn rdtscp(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self,"rdtscp\n")
+                }
+                fn db(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, bytes: &[u8]) -> $crate::__::core::result::Result<(), Self::Error>{
+                    let masm = cfg.assembler == $crate::AssemblerDialect::Masm;
+                    $crate::__::core::write!(self, "{}", if masm { "DB " } else { ".byte " })?;
                     for (i, b) in bytes.iter().enumerate() {
                         if i > 0 {
                             $crate::__::core::write!(self, ", ")?;
                         }
-                        $crate::__::core::write!(self, "0x{:02x}", b)?;
+                        // MASM hex literals start with a digit and end in `h`.
+                        if masm {
+                            $crate::__::core::write!(self, "0{:02x}h", b)?;
+                        } else {
+                            $crate::__::core::write!(self, "0x{:02x}", b)?;
+                        }
                     }
                     $crate::__::core::write!(self, "\n")
                 }
-                fn align_to(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch, alignment: usize) -> $crate::__::core::result::Result<(), Self::Error>{
-                    $crate::__::core::write!(self, ".balign {alignment}\n")
+                fn align_to(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, alignment: usize) -> $crate::__::core::result::Result<(), Self::Error>{
+                    match cfg.assembler {
+                        $crate::AssemblerDialect::Masm => $crate::__::core::write!(self, "ALIGN {alignment}\n"),
+                        _ => $crate::__::core::write!(self, ".balign {alignment}\n"),
+                    }
+                }
+                fn nop(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch) -> $crate::__::core::result::Result<(), Self::Error>{
+                    $crate::__::core::write!(self, "nop\n")
+                }
+                fn nops(&mut self, ctx: &mut Context, cfg: $crate::X64Arch, n_bytes: usize) -> $crate::__::core::result::Result<(), Self::Error>{
+                    // Spelled out as bytes so the assembler cannot pick other lengths.
+                    for nop in $crate::out::nop_fill(n_bytes) {
+                        $crate::out::WriterCore::<Context>::db(self, ctx, cfg, nop)?;
+                    }
+                    Ok(())
+                }
+                fn align(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, pow2: u32) -> $crate::__::core::result::Result<(), Self::Error>{
+                    match cfg.assembler {
+                        $crate::AssemblerDialect::Masm => $crate::__::core::write!(self, "ALIGN {}\n", 1u64 << pow2),
+                        _ => $crate::__::core::write!(self, ".p2align {pow2}\n"),
+                    }
                 }
             }
             impl<L: Display, Context> $crate::out::Writer<L, Context> for $ty {
@@ -210,18 +694,33 @@ macro_rules! writers {
                     // and the LEA loads address 0.  `[rip + label]` produces
                     // a PC-relative LEA whose offset the assembler resolves
                     // locally when the label lives in the same section.
-                    $crate::__::core::write!(self,"lea {dest}, [rip + {label}]\n")
+                    // Absolute references use a full 64-bit immediate instead.
+                    match cfg.label_refs {
+                        $crate::LabelRefs::Absolute => $crate::__::core::write!(self,"movabs {dest}, offset {label}\n"),
+                        _ => $crate::__::core::write!(self,"lea {dest}, [rip + {label}]\n"),
+                    }
                 }
-                fn call_label(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch, label: L) -> $crate::__::core::result::Result<(), Self::Error> {
-                    $crate::__::core::write!(self, "call {label}\n")
+                fn call_label(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, label: L) -> $crate::__::core::result::Result<(), Self::Error> {
+                    match cfg.label_refs {
+                        $crate::LabelRefs::Absolute => $crate::__::core::write!(self, "movabs r11, offset {label}\ncall r11\n"),
+                        _ => $crate::__::core::write!(self, "call {label}\n"),
+                    }
                 }
-                fn jmp_label(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch, label: L) -> $crate::__::core::result::Result<(), Self::Error> {
-                    $crate::__::core::write!(self, "jmp {label}\n")
+                fn jmp_label(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, label: L) -> $crate::__::core::result::Result<(), Self::Error> {
+                    match cfg.label_refs {
+                        $crate::LabelRefs::Absolute => $crate::__::core::write!(self, "movabs r11, offset {label}\njmp r11\n"),
+                        _ => $crate::__::core::write!(self, "jmp {label}\n"),
+                    }
                 }
                 fn jcc_label(&mut self, _ctx: &mut Context, _cfg: $crate::X64Arch, cc: $crate::ConditionCode, label: L) -> $crate::__::core::result::Result<(), Self::Error> {
                     $crate::__::core::write!(self, "j{cc} {label}\n")
                 }
-
+                fn dd_label(&mut self, _ctx: &mut Context, cfg: $crate::X64Arch, label: L) -> $crate::__::core::result::Result<(), Self::Error> {
+                    match cfg.assembler {
+                        $crate::AssemblerDialect::Masm => $crate::__::core::write!(self, "DD {label} - $\n"),
+                        _ => $crate::__::core::write!(self, ".4byte {label} - .\n"),
+                    }
+                }
             })*
         };
     };
@@ -0,0 +1,227 @@
+//! Bounds checks on array indices.
+//!
+//! [`BoundsCheck::bounds_check`] compares an index against a limit as
+//! unsigned values, so a negative index is out of bounds too, and either
+//! branches to a handler or traps when `index >= limit`:
+//!
+//! ```ignore
+//! writer.bounds_check(ctx, cfg, &Reg(1), &Reg(2), BoundsPolicy::Branch(oob))?;
+//! // cmp rcx, rdx
+//! // jnb oob
+//! writer.bounds_check(ctx, cfg, &Reg(1), &Reg(2), BoundsPolicy::Trap(ok))?;
+//! // cmp rcx, rdx
+//! // jb ok
+//! // ud2
+//! // ok:
+//! ```
+//!
+//! [`checked_element`](BoundsCheck::checked_element) emits the check and
+//! returns the element operand `[base + index*size]` for the load or store
+//! that follows, which x86 addresses directly.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::Writer;
+use crate::out::arg::{ArgKind, MemArg, MemArgKind, Segment};
+use crate::{BranchHint, ConditionCode, RegisterClass, X64Arch};
+
+/// What a failed bounds check does.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BoundsPolicy<L> {
+    /// Branches to the label.
+    Branch(L),
+    /// Traps with `ud2`. The label is defined after the trap, where
+    /// execution continues when the check passes.
+    Trap(L),
+}
+
+/// Emits bounds checks.
+///
+/// Implemented for every [`Writer`].
+pub trait BoundsCheck<L: Clone, Context>: Writer<L, Context> {
+    /// Checks `index < limit`, unsigned, and applies `policy` when it does
+    /// not hold.
+    ///
+    /// `limit` is a register, memory or a 32-bit immediate. Clobbers the
+    /// flags.
+    fn bounds_check(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        index: &(dyn MemArg + '_),
+        limit: &(dyn MemArg + '_),
+        policy: BoundsPolicy<L>,
+    ) -> Result<(), Self::Error> {
+        self.cmp(ctx, cfg, index, limit)?;
+        match policy {
+            BoundsPolicy::Branch(oob) => {
+                self.jcc_label_hinted(ctx, cfg, ConditionCode::NB, oob, BranchHint::Unlikely)
+            }
+            BoundsPolicy::Trap(ok) => {
+                self.jcc_label_hinted(ctx, cfg, ConditionCode::B, ok.clone(), BranchHint::Likely)?;
+                self.unreachable(ctx, cfg)?;
+                self.set_label(ctx, cfg, ok)
+            }
+        }
+    }
+
+    /// Checks `index < limit` as [`bounds_check`](Self::bounds_check) does
+    /// and returns the `size` element at `index` of the array at `base`.
+    #[allow(clippy::too_many_arguments)]
+    fn checked_element(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        base: Reg,
+        index: Reg,
+        limit: &(dyn MemArg + '_),
+        size: MemorySize,
+        policy: BoundsPolicy<L>,
+    ) -> Result<MemArgKind<ArgKind>, Self::Error> {
+        self.bounds_check(ctx, cfg, &reg64(index), limit, policy)?;
+        Ok(element(base, index, size))
+    }
+}
+
+impl<L: Clone, Context, W: Writer<L, Context> + ?Sized> BoundsCheck<L, Context> for W {}
+
+/// The `size` element `[base + index*scale]`, scaled by the element size.
+///
+/// # Panics
+///
+/// Panics if `size` is wider than 64 bits, which x86 cannot scale by.
+pub fn element(base: Reg, index: Reg, size: MemorySize) -> MemArgKind<ArgKind> {
+    let scale = match size {
+        MemorySize::_8 => 1,
+        MemorySize::_16 => 2,
+        MemorySize::_32 => 4,
+        MemorySize::_64 => 8,
+        _ => panic!("element size has no x86 index scale"),
+    };
+    MemArgKind::Mem {
+        base: reg64(base),
+        offset: Some((reg64(index), scale)),
+        disp: 0,
+        size,
+        reg_class: RegisterClass::Gpr,
+        segment: Segment::None,
+    }
+}
+
+fn reg64(reg: Reg) -> ArgKind {
+    ArgKind::Reg {
+        reg,
+        size: MemorySize::_64,
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::out::WriterCore;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt::Write;
+
+    /// Runs the compares and branches at the top of `listing` with `regs`
+    /// holding the named registers, and returns the first other
+    /// instruction reached.
+    fn reached<'a>(listing: &'a str, regs: &[(&str, u64)]) -> &'a str {
+        let value = |operand: &str| match regs.iter().find(|(name, _)| *name == operand) {
+            Some(&(_, value)) => value,
+            None => operand.parse().unwrap(),
+        };
+        let lines: Vec<&str> = listing.lines().collect();
+        let (mut pc, mut below) = (0, false);
+        loop {
+            let line = lines[pc];
+            pc += 1;
+            let (mnemonic, operands) = line.split_once(' ').unwrap_or((line, ""));
+            let taken = match mnemonic {
+                "cmp" => {
+                    let (a, b) = operands.split_once(", ").unwrap();
+                    below = value(a) < value(b);
+                    continue;
+                }
+                "jb" => below,
+                "jnb" => !below,
+                _ if line.ends_with(':') => continue,
+                _ => return line,
+            };
+            if taken {
+                pc = lines
+                    .iter()
+                    .position(|l| l.strip_suffix(':') == Some(operands))
+                    .unwrap()
+                    + 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_index_takes_the_trap() {
+        let mut output = String::new();
+        let writer: &mut dyn Write = &mut output;
+        let cfg = X64Arch::default();
+        writer
+            .bounds_check(
+                &mut (),
+                cfg,
+                &Reg(1),
+                &ArgKind::Lit(16),
+                BoundsPolicy::Trap(".Lok"),
+            )
+            .unwrap();
+        writer.ret(&mut (), cfg).unwrap();
+        assert_eq!(reached(&output, &[("rcx", 15)]), "ret");
+        assert_eq!(reached(&output, &[("rcx", 16)]), "ud2");
+        // A negative index is a huge unsigned one.
+        assert_eq!(reached(&output, &[("rcx", -1i64 as u64)]), "ud2");
+    }
+
+    #[test]
+    fn test_out_of_range_index_takes_the_branch() {
+        let mut output = String::new();
+        let writer: &mut dyn Write = &mut output;
+        let cfg = X64Arch::default();
+        writer
+            .bounds_check(
+                &mut (),
+                cfg,
+                &Reg(1),
+                &Reg(2),
+                BoundsPolicy::Branch(".Loob"),
+            )
+            .unwrap();
+        writer.ret(&mut (), cfg).unwrap();
+        writer.set_label(&mut (), cfg, ".Loob").unwrap();
+        writer.hlt(&mut (), cfg).unwrap();
+        assert_eq!(reached(&output, &[("rcx", 3), ("rdx", 4)]), "ret");
+        assert_eq!(reached(&output, &[("rcx", 4), ("rdx", 4)]), "hlt");
+        assert_eq!(reached(&output, &[("rcx", u64::MAX), ("rdx", 4)]), "hlt");
+    }
+
+    #[test]
+    fn test_checked_element() {
+        let mut output = String::new();
+        let writer: &mut dyn Write = &mut output;
+        let cfg = X64Arch::default();
+        let elem = writer
+            .checked_element(
+                &mut (),
+                cfg,
+                Reg(7),
+                Reg(1),
+                &Reg(2),
+                MemorySize::_32,
+                BoundsPolicy::Branch(".Loob"),
+            )
+            .unwrap();
+        assert_eq!(output, "cmp rcx, rdx\njnb .Loob\n");
+        assert_eq!(elem, element(Reg(7), Reg(1), MemorySize::_32));
+        let MemArgKind::Mem { offset, .. } = elem else {
+            panic!("expected a memory operand");
+        };
+        assert_eq!(offset, Some((reg64(Reg(1)), 4)));
+    }
+}
@@ -0,0 +1,172 @@
+//! Layout of two-way branches by expected direction.
+//!
+//! Static predictors and the instruction fetch both favour the fall-through
+//! path. [`HintedBranch::branch_two_way`] emits the single conditional jump
+//! that leaves the expected successor in fall-through position, inverting
+//! the condition when the branch is [`Likely`](BranchHint::Likely):
+//!
+//! ```ignore
+//! match writer.branch_two_way(ctx, cfg, ConditionCode::E, BranchHint::Likely, hot, cold)? {
+//!     // jne cold
+//!     Fallthrough::Taken => { /* hot: ... */ }
+//!     Fallthrough::NotTaken => unreachable!(),
+//! }
+//! ```
+//!
+//! Where the layout is fixed, such as a loop back-edge,
+//! [`jcc_label_hinted`](Writer::jcc_label_hinted) keeps the hint on the jump
+//! itself instead.
+
+use crate::out::Writer;
+use crate::{BranchHint, ConditionCode, X64Arch};
+
+/// Which successor of a two-way branch follows it in memory.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Fallthrough {
+    /// The block run when the condition holds.
+    Taken,
+    /// The block run when the condition does not hold.
+    NotTaken,
+}
+
+impl Fallthrough {
+    /// Returns the successor to place next for a branch hinted `hint`.
+    pub fn for_hint(hint: BranchHint) -> Self {
+        match hint {
+            BranchHint::Likely => Fallthrough::Taken,
+            _ => Fallthrough::NotTaken,
+        }
+    }
+}
+
+/// Emits conditional branches laid out by their expected direction.
+///
+/// Implemented for every [`Writer`].
+pub trait HintedBranch<L, Context>: Writer<L, Context> {
+    /// Jumps to `taken` if `cc` holds and to `not_taken` otherwise.
+    ///
+    /// Only one jump is emitted; the returned successor must be placed
+    /// immediately after it.
+    fn branch_two_way(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: ConditionCode,
+        hint: BranchHint,
+        taken: L,
+        not_taken: L,
+    ) -> Result<Fallthrough, Self::Error> {
+        let next = Fallthrough::for_hint(hint);
+        match next {
+            Fallthrough::Taken => {
+                self.jcc_label_hinted(ctx, cfg, cc.invert(), not_taken, BranchHint::Unlikely)?
+            }
+            Fallthrough::NotTaken => self.jcc_label_hinted(ctx, cfg, cc, taken, hint)?,
+        }
+        Ok(next)
+    }
+}
+
+impl<L, Context, W: Writer<L, Context> + ?Sized> HintedBranch<L, Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    const CONDITIONS: [ConditionCode; 16] = {
+        use ConditionCode::*;
+        [O, NO, B, NB, E, NE, NA, A, S, NS, P, NP, L, NL, NG, G]
+    };
+
+    /// Whether `cc` holds for the flags `[cf, zf, sf, of, pf]`.
+    fn holds(cc: ConditionCode, [cf, zf, sf, of, pf]: [bool; 5]) -> bool {
+        use ConditionCode::*;
+        match cc {
+            O => of,
+            NO => !of,
+            B => cf,
+            NB => !cf,
+            E => zf,
+            NE => !zf,
+            NA => cf || zf,
+            A => !cf && !zf,
+            S => sf,
+            NS => !sf,
+            P => pf,
+            NP => !pf,
+            L => sf != of,
+            NL => sf == of,
+            NG => zf || sf != of,
+            G => !zf && sf == of,
+        }
+    }
+
+    #[test]
+    fn test_each_successor_is_reached_exactly_when_it_should_be() {
+        for cc in CONDITIONS {
+            for hint in [BranchHint::None, BranchHint::Likely, BranchHint::Unlikely] {
+                let mut output = String::new();
+                let mut writer: &mut dyn Write = &mut output;
+                let next = writer
+                    .branch_two_way(&mut (), X64Arch::default(), cc, hint, "taken", "not_taken")
+                    .unwrap();
+                assert_eq!(next, Fallthrough::for_hint(hint));
+                let (mnemonic, target) = output.trim_end().split_once(' ').unwrap();
+                let jcc = CONDITIONS
+                    .into_iter()
+                    .find(|c| format!("j{c}") == mnemonic)
+                    .unwrap();
+                let fallthrough = match next {
+                    Fallthrough::Taken => "taken",
+                    Fallthrough::NotTaken => "not_taken",
+                };
+                for bits in 0..32 {
+                    let flags = core::array::from_fn(|i| (bits >> i) & 1 == 1);
+                    let reached = if holds(jcc, flags) {
+                        target
+                    } else {
+                        fallthrough
+                    };
+                    let expected = if holds(cc, flags) {
+                        "taken"
+                    } else {
+                        "not_taken"
+                    };
+                    assert_eq!(reached, expected, "{cc:?} {hint:?} {flags:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_likely_prefix_needs_branch_hints() {
+        let cfg = X64Arch {
+            branch_hints: true,
+            ..X64Arch::default()
+        };
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        writer
+            .jcc_label_hinted(
+                &mut (),
+                cfg,
+                ConditionCode::NE,
+                ".Lloop",
+                BranchHint::Likely,
+            )
+            .unwrap();
+        writer
+            .jcc_label_hinted(
+                &mut (),
+                X64Arch::default(),
+                ConditionCode::NE,
+                ".Lloop",
+                BranchHint::Likely,
+            )
+            .unwrap();
+        assert_eq!(output, ".byte 0x3e\njne .Lloop\njne .Lloop\n");
+    }
+}
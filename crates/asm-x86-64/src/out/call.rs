@@ -0,0 +1,113 @@
+//! Calls that follow the configured calling convention.
+//!
+//! A plain [`call`](WriterCore::call) leaves the stack as it is. Calling a
+//! function compiled for [`CallConv::Win64`] also needs 32 bytes of shadow
+//! space reserved above the return address, and any arguments past the
+//! fourth stored after it, with `rsp` 16-byte aligned at the call.
+//! [`AbiCall::call_abi`] wraps a call in that frame for the convention in
+//! [`X64Arch::call_conv`]:
+//!
+//! ```ignore
+//! let cfg = X64Arch { call_conv: CallConv::Win64, ..X64Arch::default() };
+//! writer.call_abi(ctx, cfg, &ArgKind::sym("callee"), &[&Reg(10)])?;
+//! // sub rsp,48; mov qword ptr [rsp+32], r10; call callee; add rsp,48
+//! ```
+//!
+//! Register arguments are the caller's job; [`CallConv::int_args`] lists
+//! them in order. `rsp` must be 16-byte aligned before the sequence.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::WriterCore;
+use crate::out::arg::{ArgKind, MemArg, MemArgKind, Segment};
+use crate::{CallConv, RegisterClass, X64Arch};
+
+/// The stack pointer, `rsp`.
+const RSP: Reg = Reg(4);
+
+/// Returns the bytes [`AbiCall::call_abi`] reserves below `rsp` for a call
+/// with `stack_args` stack arguments: the shadow space plus 8 bytes per
+/// argument, rounded up to 16.
+pub fn call_frame_size(conv: CallConv, stack_args: usize) -> i32 {
+    (conv.shadow_space() + 8 * stack_args as i32).next_multiple_of(16)
+}
+
+/// The stack argument slot `[rsp + disp]`.
+fn stack_slot(disp: i32) -> MemArgKind<ArgKind> {
+    MemArgKind::Mem {
+        base: ArgKind::Reg {
+            reg: RSP,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp,
+        size: MemorySize::_64,
+        reg_class: RegisterClass::Gpr,
+        segment: Segment::None,
+    }
+}
+
+/// Emits calls framed for a calling convention.
+///
+/// Implemented for every [`WriterCore`].
+pub trait AbiCall<Context>: WriterCore<Context> {
+    /// Calls `target` with `stack_args` as the arguments after the register
+    /// ones, reserving the convention's shadow space.
+    ///
+    /// Stack arguments must be registers other than `rsp`, or immediates.
+    fn call_abi(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        target: &(dyn MemArg + '_),
+        stack_args: &[&(dyn MemArg + '_)],
+    ) -> Result<(), Self::Error> {
+        let size = call_frame_size(cfg.call_conv, stack_args.len());
+        if size != 0 {
+            self.sub_imm(ctx, cfg, &RSP, size)?;
+        }
+        let shadow = cfg.call_conv.shadow_space();
+        for (i, arg) in stack_args.iter().enumerate() {
+            self.mov(ctx, cfg, &stack_slot(shadow + 8 * i as i32), *arg)?;
+        }
+        self.call(ctx, cfg, target)?;
+        if size != 0 {
+            self.add_imm(ctx, cfg, &RSP, size)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> AbiCall<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_win64_reserves_shadow_space() {
+        let cfg = X64Arch {
+            call_conv: CallConv::Win64,
+            ..X64Arch::default()
+        };
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        out.call_abi(&mut (), cfg, &ArgKind::sym("callee"), &[&Reg(10)])
+            .unwrap();
+        assert_eq!(
+            output,
+            "sub rsp,48\nmov qword ptr [rsp+32], r10\ncall callee\nadd rsp,48\n"
+        );
+    }
+
+    #[test]
+    fn test_sysv_register_only_call_is_plain() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        out.call_abi(&mut (), X64Arch::default(), &ArgKind::sym("callee"), &[])
+            .unwrap();
+        assert_eq!(output, "call callee\n");
+    }
+}
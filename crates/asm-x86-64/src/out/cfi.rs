@@ -0,0 +1,100 @@
+//! Control-flow integrity landing pads.
+//!
+//! With CET indirect branch tracking enforced, every indirect `call` or
+//! `jmp` must land on an `endbr64`, or the CPU raises a control-protection
+//! fault. [`Cfi`] emits the pads where [`X64Arch::ibt`] asks for them and
+//! nothing otherwise, so the same emission code serves both configurations:
+//!
+//! ```ignore
+//! writer.function_entry(ctx, cfg)?;             // endbr64
+//! // ...
+//! writer.set_branch_target(ctx, cfg, case0)?;   // case0: endbr64
+//! ```
+//!
+//! Function entries written with
+//! [`emit_function`](crate::out::function::emit_function) get their pad
+//! from [`Function::with_landing_pad`](crate::out::function::Function::with_landing_pad).
+//! The case labels of a [`JumpTable`](crate::out::jump_table::JumpTable)
+//! are indirect-branch targets too. Return addresses are protected by the
+//! CPU's shadow stack, so `call` and `ret` need no extra instructions.
+
+use crate::X64Arch;
+use crate::out::{Writer, WriterCore};
+
+/// Emits control-flow integrity landing pads.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Cfi<Context>: WriterCore<Context> {
+    /// Emits the landing pad a function entry needs, if any.
+    fn function_entry(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.branch_target(ctx, cfg)
+    }
+
+    /// Emits the landing pad an indirect jump or call target needs, if any.
+    fn branch_target(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        if cfg.ibt {
+            self.endbr64(ctx, cfg)?;
+        }
+        Ok(())
+    }
+
+    /// Defines `label` as the target of an indirect jump or call.
+    fn set_branch_target<L>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        label: L,
+    ) -> Result<(), Self::Error>
+    where
+        Self: Writer<L, Context>,
+    {
+        self.set_label(ctx, cfg, label)?;
+        self.branch_target(ctx, cfg)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Cfi<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[cfg(feature = "iced")]
+    #[test]
+    fn test_branch_target_lands_on_the_pad() {
+        use crate::out::iced::IcedWriter;
+
+        let cfg = X64Arch {
+            ibt: true,
+            ..X64Arch::default()
+        };
+        let mut binary = IcedWriter::<u32>::new(0);
+        binary.hlt(&mut (), cfg).unwrap();
+        binary.set_branch_target(&mut (), cfg, 1).unwrap();
+        binary.hlt(&mut (), cfg).unwrap();
+        binary
+            .set_branch_target(&mut (), X64Arch::default(), 2)
+            .unwrap();
+        binary.hlt(&mut (), cfg).unwrap();
+        let (code, labels) = binary.into_parts();
+        // An indirect jump to the label reaches `endbr64` first.
+        assert_eq!(labels[&1], 1);
+        assert_eq!(code[1..5], [0xF3, 0x0F, 0x1E, 0xFA]);
+        // Without IBT it reaches the code that follows.
+        assert_eq!(labels[&2], 6);
+        assert_eq!(code[6], 0xF4);
+    }
+
+    #[test]
+    fn test_no_pads_without_ibt() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        writer.function_entry(&mut (), X64Arch::default()).unwrap();
+        writer
+            .set_branch_target(&mut (), X64Arch::default(), ".Lcase")
+            .unwrap();
+        assert_eq!(output, ".Lcase:\n");
+    }
+}
@@ -0,0 +1,618 @@
+//! Assembler dialects for text output.
+//!
+//! The text writers produce one instruction per line in the Intel syntax GNU
+//! `as` accepts after `.intel_syntax noprefix`. Toolchains that want some
+//! instructions spelled differently can put a [`DialectWriter`] between a
+//! text writer and its output; it passes every instruction line through a
+//! [`Dialect`], which may rename the mnemonic or reorder the operands:
+//!
+//! ```ignore
+//! let mut out = DialectWriter::new(&mut output, |out: &mut dyn Write, m: &str, ops: &[&str]| {
+//!     write_insn(out, if m == "movabs" { "mov" } else { m }, ops)
+//! });
+//! let writer: &mut dyn Write = &mut out;
+//! ```
+//!
+//! [`Syntax::Att`] is a dialect that rewrites the whole line in AT&T syntax,
+//! for assemblers that do not accept Intel syntax.
+//!
+//! Labels, directives and lines too long to buffer are passed through
+//! unchanged.
+
+use core::fmt::{self, Write};
+
+/// Longest line [`DialectWriter`] rewrites; longer lines pass through as is.
+const LINE: usize = 256;
+
+/// Most operands [`DialectWriter`] splits a line into.
+const OPERANDS: usize = 8;
+
+/// Rewrites instructions for a particular assembler.
+///
+/// Closures with the same signature as [`Dialect::write_insn`] are dialects,
+/// so one-off renames need no type of their own.
+pub trait Dialect {
+    /// Writes one instruction, without the trailing newline.
+    ///
+    /// `operands` are split at top-level commas and trimmed. The default
+    /// writes the instruction unchanged.
+    fn write_insn(
+        &mut self,
+        out: &mut dyn Write,
+        mnemonic: &str,
+        operands: &[&str],
+    ) -> fmt::Result {
+        write_insn(out, mnemonic, operands)
+    }
+}
+
+impl<F> Dialect for F
+where
+    F: FnMut(&mut dyn Write, &str, &[&str]) -> fmt::Result,
+{
+    fn write_insn(
+        &mut self,
+        out: &mut dyn Write,
+        mnemonic: &str,
+        operands: &[&str],
+    ) -> fmt::Result {
+        self(out, mnemonic, operands)
+    }
+}
+
+/// Writes `mnemonic op1, op2, ...`.
+pub fn write_insn(out: &mut dyn Write, mnemonic: &str, operands: &[&str]) -> fmt::Result {
+    out.write_str(mnemonic)?;
+    for (i, op) in operands.iter().enumerate() {
+        out.write_str(if i == 0 { " " } else { ", " })?;
+        out.write_str(op)?;
+    }
+    Ok(())
+}
+
+/// GNU `as`, which accepts the text writers' output unchanged.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct GnuAs;
+
+impl Dialect for GnuAs {}
+
+/// LLVM's integrated assembler.
+///
+/// Accepts the text writers' output unchanged, like [`GnuAs`]; it is a
+/// separate dialect so callers can name the assembler they target.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct LlvmIas;
+
+impl Dialect for LlvmIas {}
+
+/// x86-64 assembly syntax of the text output.
+///
+/// The text writers produce Intel syntax; [`Syntax::Att`] rewrites it for
+/// assemblers that expect AT&T syntax, such as GNU `as` without
+/// `.intel_syntax`. Select it when constructing the sink:
+///
+/// ```ignore
+/// let mut out = DialectWriter::new(&mut output, Syntax::Att);
+/// let writer: &mut dyn Write = &mut out;
+/// writer.mov(ctx, cfg, &Reg(0), &MemArgKind::sym("counter", MemorySize::_64))?;
+/// // movq counter(%rip), %rax
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Syntax {
+    /// Intel syntax (`mov rax, qword ptr [rbx+8]`), written unchanged.
+    #[default]
+    Intel,
+    /// AT&T syntax (`movq 8(%rbx), %rax`): operands reversed, `%`
+    /// registers, `$` immediates, and a size suffix where a memory operand
+    /// is the only indication of the operand size.
+    Att,
+}
+
+impl Dialect for Syntax {
+    fn write_insn(
+        &mut self,
+        out: &mut dyn Write,
+        mnemonic: &str,
+        operands: &[&str],
+    ) -> fmt::Result {
+        match self {
+            Syntax::Intel => write_insn(out, mnemonic, operands),
+            Syntax::Att => write_att(out, mnemonic, operands),
+        }
+    }
+}
+
+/// Instruction prefixes written on the same line as their instruction.
+const PREFIXES: [&str; 6] = ["lock", "rep", "repe", "repz", "repne", "repnz"];
+
+/// Writes an Intel syntax instruction in AT&T syntax.
+fn write_att(out: &mut dyn Write, mnemonic: &str, operands: &[&str]) -> fmt::Result {
+    if PREFIXES.contains(&mnemonic) {
+        // `rep movsb` splits into the prefix and a `movsb` operand.
+        out.write_str(mnemonic)?;
+        let Some((first, rest)) = operands.split_first() else {
+            return Ok(());
+        };
+        out.write_char(' ')?;
+        let (inner, op) = first.split_once(' ').unwrap_or((first, ""));
+        let mut inner_ops = [""; OPERANDS];
+        let mut count = 0;
+        for op in core::iter::once(op.trim()).chain(rest.iter().copied()) {
+            if !op.is_empty() && count < OPERANDS {
+                inner_ops[count] = op;
+                count += 1;
+            }
+        }
+        return write_att(out, inner, &inner_ops[..count]);
+    }
+
+    let mut ops = [Operand::Other(""); OPERANDS];
+    for (op, text) in ops.iter_mut().zip(operands) {
+        *op = Operand::parse(text);
+    }
+    let ops = &ops[..operands.len().min(OPERANDS)];
+    if let ("movsx" | "movsxd" | "movzx", [dest, src]) = (mnemonic, ops) {
+        if let (Some(d), Some(s)) = (dest.int_suffix(), src.int_suffix()) {
+            let kind = if mnemonic == "movzx" { "movz" } else { "movs" };
+            write!(out, "{kind}{s}{d}")?;
+            return write_att_operands(out, ops, false);
+        }
+    }
+    out.write_str(mnemonic)?;
+    if let Some(suffix) = att_suffix(mnemonic, ops) {
+        out.write_str(suffix)?;
+    }
+    write_att_operands(out, ops, matches!(mnemonic, "jmp" | "call"))
+}
+
+/// The size suffix `mnemonic` needs, if a memory operand is the only
+/// indication of the operand size.
+fn att_suffix(mnemonic: &str, ops: &[Operand]) -> Option<&'static str> {
+    let size = ops.iter().find_map(|op| match op {
+        Operand::Mem(mem) => mem.size,
+        _ => None,
+    })?;
+    if mnemonic.starts_with('f') {
+        return x87_suffix(size, mnemonic.starts_with("fi"));
+    }
+    let sized_by_reg = ops
+        .iter()
+        .any(|op| matches!(op, Operand::Reg(r) if gpr_suffix(r).is_none()));
+    if mnemonic == "lea" || sized_by_reg {
+        return None;
+    }
+    int_suffix(size)
+}
+
+/// Writes `ops` in reverse order, as AT&T syntax orders them.
+fn write_att_operands(out: &mut dyn Write, ops: &[Operand], indirect: bool) -> fmt::Result {
+    for (i, op) in ops.iter().rev().enumerate() {
+        out.write_str(if i == 0 { " " } else { ", " })?;
+        match op {
+            Operand::Reg(r) => {
+                if indirect {
+                    out.write_char('*')?;
+                }
+                write!(out, "%{r}")?;
+            }
+            Operand::Imm(v) => write!(out, "${v}")?,
+            Operand::Other(t) => out.write_str(t)?,
+            Operand::Mem(mem) => {
+                if indirect {
+                    out.write_char('*')?;
+                }
+                mem.write_att(out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// An Intel syntax operand.
+#[derive(Clone, Copy)]
+enum Operand<'a> {
+    Reg(&'a str),
+    /// An immediate; `offset sym` is the immediate `sym`.
+    Imm(&'a str),
+    Mem(Mem<'a>),
+    /// A label or symbol, such as a branch target.
+    Other(&'a str),
+}
+
+impl<'a> Operand<'a> {
+    fn parse(text: &'a str) -> Self {
+        if let Some(sym) = text.strip_prefix("offset ") {
+            return Operand::Imm(sym.trim());
+        }
+        if let Some(mem) = Mem::parse(text) {
+            return Operand::Mem(mem);
+        }
+        if is_reg(text) {
+            return Operand::Reg(text);
+        }
+        if parse_num(text).is_some() {
+            return Operand::Imm(text);
+        }
+        Operand::Other(text)
+    }
+
+    /// The integer size suffix of a general-purpose register or sized
+    /// memory operand.
+    fn int_suffix(&self) -> Option<&'static str> {
+        match self {
+            Operand::Reg(r) => gpr_suffix(r),
+            Operand::Mem(mem) => mem.size.and_then(int_suffix),
+            _ => None,
+        }
+    }
+}
+
+/// An Intel syntax memory operand, `size ptr seg:[base+index*scale+disp]`.
+#[derive(Clone, Copy)]
+struct Mem<'a> {
+    size: Option<&'a str>,
+    segment: Option<&'a str>,
+    base: Option<&'a str>,
+    index: Option<(&'a str, &'a str)>,
+    symbol: Option<&'a str>,
+    disp: i64,
+}
+
+impl<'a> Mem<'a> {
+    fn parse(text: &'a str) -> Option<Self> {
+        let (head, inner) = text.split_once('[')?;
+        let inner = inner.strip_suffix(']')?;
+        let (size, head) = match head.split_once(" ptr") {
+            Some((size, rest)) => (Some(size.trim()), rest.trim()),
+            None => (None, head.trim()),
+        };
+        let mut mem = Mem {
+            size,
+            segment: head.strip_suffix(':'),
+            base: None,
+            index: None,
+            symbol: None,
+            disp: 0,
+        };
+        // Terms are joined by `+`, and a negative displacement by `-`.
+        let terms = inner.split('+').flat_map(|chunk| {
+            let chunk = chunk.trim();
+            let split = chunk
+                .get(1..)
+                .and_then(|rest| rest.find('-'))
+                .map_or(chunk.len(), |i| i + 1);
+            let (term, disp) = chunk.split_at(split);
+            [term.trim(), disp.trim()]
+        });
+        for term in terms.filter(|term| !term.is_empty()) {
+            if let Some((reg, scale)) = term.split_once('*') {
+                mem.index = Some((reg, scale));
+            } else if is_reg(term) {
+                match mem.base {
+                    None => mem.base = Some(term),
+                    Some(_) => mem.index = Some((term, "1")),
+                }
+            } else if let Some(n) = parse_num(term) {
+                mem.disp += n;
+            } else {
+                mem.symbol = Some(term);
+            }
+        }
+        // Hand-written text may give a negative displacement as its `u32`.
+        if mem.disp > i64::from(i32::MAX) && mem.disp <= i64::from(u32::MAX) {
+            mem.disp -= 1 << 32;
+        }
+        Some(mem)
+    }
+
+    /// Writes `seg:sym+disp(base,index,scale)`.
+    fn write_att(&self, out: &mut dyn Write) -> fmt::Result {
+        if let Some(seg) = self.segment {
+            write!(out, "%{seg}:")?;
+        }
+        let has_regs = self.base.is_some() || self.index.is_some();
+        match self.symbol {
+            Some(sym) if self.disp != 0 => write!(out, "{sym}{:+}", self.disp)?,
+            Some(sym) => out.write_str(sym)?,
+            None if self.disp != 0 || !has_regs => write!(out, "{}", self.disp)?,
+            None => {}
+        }
+        if has_regs {
+            out.write_char('(')?;
+            if let Some(base) = self.base {
+                write!(out, "%{base}")?;
+            }
+            if let Some((index, scale)) = self.index {
+                write!(out, ",%{index},{scale}")?;
+            }
+            out.write_char(')')?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a decimal or `0x` hexadecimal integer.
+fn parse_num(text: &str) -> Option<i64> {
+    let (neg, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None if digits.starts_with(|c: char| c.is_ascii_digit()) => digits.parse().ok()?,
+        None => return None,
+    };
+    Some(if neg { -value } else { value })
+}
+
+/// The AT&T size suffix of a general-purpose register.
+fn gpr_suffix(reg: &str) -> Option<&'static str> {
+    match reg {
+        "rax" | "rbx" | "rcx" | "rdx" | "rsi" | "rdi" | "rbp" | "rsp" => Some("q"),
+        "eax" | "ebx" | "ecx" | "edx" | "esi" | "edi" | "ebp" | "esp" => Some("l"),
+        "ax" | "bx" | "cx" | "dx" | "si" | "di" | "bp" | "sp" => Some("w"),
+        "al" | "bl" | "cl" | "dl" | "ah" | "bh" | "ch" | "dh" | "sil" | "dil" | "bpl" | "spl" => {
+            Some("b")
+        }
+        _ => {
+            // r8 ..= r31, with a `d`, `w` or `b` suffix for the narrower forms
+            let rest = reg.strip_prefix('r')?;
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if digits == 0 {
+                return None;
+            }
+            match &rest[digits..] {
+                "" => Some("q"),
+                "d" => Some("l"),
+                "w" => Some("w"),
+                "b" => Some("b"),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Returns `true` for register names, including vector, mask, x87 and
+/// segment registers.
+fn is_reg(name: &str) -> bool {
+    if gpr_suffix(name).is_some() || name.starts_with("st(") {
+        return true;
+    }
+    if matches!(name, "rip" | "st" | "cs" | "ds" | "es" | "fs" | "gs" | "ss") {
+        return true;
+    }
+    ["xmm", "ymm", "zmm", "mm", "k"].iter().any(|prefix| {
+        name.strip_prefix(prefix)
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+    })
+}
+
+/// The integer size suffix for a memory operand of `size`.
+fn int_suffix(size: &str) -> Option<&'static str> {
+    match size {
+        "byte" => Some("b"),
+        "word" => Some("w"),
+        "dword" => Some("l"),
+        "qword" => Some("q"),
+        _ => None,
+    }
+}
+
+/// The x87 size suffix for a memory operand of `size`, which is an integer
+/// for the `fi*` instructions and a float otherwise.
+fn x87_suffix(size: &str, int: bool) -> Option<&'static str> {
+    match (size, int) {
+        ("word", true) => Some("s"),
+        ("dword", true) => Some("l"),
+        ("qword", true) => Some("ll"),
+        ("dword", false) => Some("s"),
+        ("qword", false) => Some("l"),
+        ("tbyte", false) => Some("t"),
+        _ => None,
+    }
+}
+
+/// A text sink that rewrites each instruction line with a [`Dialect`].
+///
+/// Implements [`Write`], so it is used as `&mut dyn Write` with the text
+/// writers.
+pub struct DialectWriter<W, D> {
+    out: W,
+    dialect: D,
+    line: [u8; LINE],
+    len: usize,
+    /// The current line did not fit and is being passed through.
+    overflow: bool,
+}
+
+impl<W: Write, D: Dialect> DialectWriter<W, D> {
+    /// Writes to `out` in `dialect`.
+    pub fn new(out: W, dialect: D) -> Self {
+        Self {
+            out,
+            dialect,
+            line: [0; LINE],
+            len: 0,
+            overflow: false,
+        }
+    }
+
+    /// Returns the output sink.
+    ///
+    /// A final line without a newline is written unchanged.
+    pub fn into_inner(mut self) -> Result<W, fmt::Error> {
+        if !self.overflow {
+            let line = core::str::from_utf8(&self.line[..self.len]).map_err(|_| fmt::Error)?;
+            self.out.write_str(line)?;
+        }
+        Ok(self.out)
+    }
+
+    /// Appends `s`, which contains no newline, to the current line.
+    fn push(&mut self, s: &str) -> fmt::Result {
+        if self.overflow {
+            return self.out.write_str(s);
+        }
+        let end = self.len + s.len();
+        if end > LINE {
+            let held = core::str::from_utf8(&self.line[..self.len]).map_err(|_| fmt::Error)?;
+            self.out.write_str(held)?;
+            self.out.write_str(s)?;
+            self.overflow = true;
+            self.len = 0;
+            return Ok(());
+        }
+        self.line[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+
+    /// Writes the current line through the dialect and starts a new one.
+    fn end_line(&mut self) -> fmt::Result {
+        if !self.overflow {
+            let line = core::str::from_utf8(&self.line[..self.len]).map_err(|_| fmt::Error)?;
+            rewrite(&mut self.out, &mut self.dialect, line)?;
+        }
+        self.len = 0;
+        self.overflow = false;
+        self.out.write_char('\n')
+    }
+}
+
+impl<W: Write, D: Dialect> Write for DialectWriter<W, D> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut lines = s.split('\n');
+        if let Some(first) = lines.next() {
+            self.push(first)?;
+        }
+        for line in lines {
+            self.end_line()?;
+            self.push(line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Passes one line through `dialect` if it holds an instruction.
+fn rewrite(out: &mut dyn Write, dialect: &mut dyn Dialect, line: &str) -> fmt::Result {
+    let text = line.trim();
+    if text.is_empty() || text.ends_with(':') || text.starts_with('.') {
+        return out.write_str(line);
+    }
+    let (mnemonic, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let mut operands = [""; OPERANDS];
+    let mut count = 0;
+    let mut depth = 0i32;
+    let mut start = 0;
+    let rest = rest.trim();
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                if count == OPERANDS {
+                    return out.write_str(line);
+                }
+                operands[count] = rest[start..i].trim();
+                count += 1;
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !rest.is_empty() {
+        if count == OPERANDS {
+            return out.write_str(line);
+        }
+        operands[count] = rest[start..].trim();
+        count += 1;
+    }
+    let indent = &line[..line.len() - line.trim_start().len()];
+    out.write_str(indent)?;
+    dialect.write_insn(out, mnemonic, &operands[..count])
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::X64Arch;
+    use crate::out::WriterCore;
+    use alloc::string::String;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_custom_dialect_reorders_operands() {
+        let cfg = X64Arch::default();
+        let mut output = String::new();
+        {
+            let reverse = |out: &mut dyn Write, mnemonic: &str, operands: &[&str]| match operands {
+                [a, b] => write_insn(out, mnemonic, &[*b, *a]),
+                _ => write_insn(out, mnemonic, operands),
+            };
+            let mut att = DialectWriter::new(&mut output, reverse);
+            let writer: &mut dyn Write = &mut att;
+            writer.mov(&mut (), cfg, &Reg(0), &Reg(1)).unwrap();
+            writer.ret(&mut (), cfg).unwrap();
+        }
+        assert_eq!(output, "mov rcx, rax\nret\n");
+
+        let mut output = String::new();
+        {
+            let mut llvm = DialectWriter::new(&mut output, LlvmIas);
+            let writer: &mut dyn Write = &mut llvm;
+            writer.mov(&mut (), cfg, &Reg(0), &Reg(1)).unwrap();
+        }
+        assert_eq!(output, "mov rax, rcx\n");
+    }
+
+    fn att(line: &str) -> String {
+        let mut output = String::new();
+        rewrite(&mut output, &mut Syntax::Att, line).unwrap();
+        output
+    }
+
+    #[test]
+    fn test_att_syntax() {
+        let cfg = X64Arch::default();
+        let mut output = String::new();
+        {
+            let mut out = DialectWriter::new(&mut output, Syntax::Att);
+            let writer: &mut dyn Write = &mut out;
+            writer.mov(&mut (), cfg, &Reg(0), &Reg(1)).unwrap();
+            writer.ret(&mut (), cfg).unwrap();
+        }
+        assert_eq!(output, "mov %rcx, %rax\nret\n");
+
+        assert_eq!(
+            att("mov rax, qword ptr [rip + counter+0]"),
+            "movq counter(%rip), %rax"
+        );
+        assert_eq!(att("mov qword ptr [rbx+8], 1"), "movq $1, 8(%rbx)");
+        assert_eq!(
+            att("movsx r10,dword ptr [r11+rcx*4+0]"),
+            "movslq (%r11,%rcx,4), %r10"
+        );
+        assert_eq!(
+            att("movzx eax, byte ptr [rdi+4294967295]"),
+            "movzbl -1(%rdi), %eax"
+        );
+        assert_eq!(att("movabs r11, offset label"), "movabs $label, %r11");
+        assert_eq!(att("mov rax, qword ptr fs:[0+16]"), "movq %fs:16, %rax");
+        assert_eq!(att("mov rax, qword ptr [rbp-8]"), "movq -8(%rbp), %rax");
+        assert_eq!(att("mov rax, qword ptr [rip+16]"), "movq 16(%rip), %rax");
+        assert_eq!(
+            att("mov eax, dword ptr [rcx*4-8]"),
+            "movl -8(,%rcx,4), %eax"
+        );
+        assert_eq!(att("fld tbyte ptr [rsp+0]"), "fldt (%rsp)");
+        assert_eq!(att("faddp st(1),st"), "faddp %st, %st(1)");
+        assert_eq!(att("jmp r11"), "jmp *%r11");
+        assert_eq!(att("call 1f"), "call 1f");
+        assert_eq!(att("rep movsb"), "rep movsb");
+        assert_eq!(att("lea r11, [rip + .Ltab]"), "lea .Ltab(%rip), %r11");
+        assert_eq!(att(".Ltab:"), ".Ltab:");
+    }
+}
@@ -0,0 +1,172 @@
+//! Object-safe writer facade for runtime backend selection.
+//!
+//! [`Writer`] is generic over its label and context types, so choosing a
+//! backend at run time means threading both through every caller.
+//! [`DynWriter`] fixes them: labels are borrowed as [`DynLabel`]s and the
+//! context is `()`, leaving only the error type, so backends can sit behind a
+//! single `&mut dyn DynWriter`:
+//!
+//! ```ignore
+//! let mut binary = LabelMap::new(IcedWriter::<String>::new(), |l| l.to_string());
+//! let backend: &mut dyn DynWriter = match opts.backend {
+//!     Backend::Text => &mut text,
+//!     Backend::Binary => &mut binary,
+//! };
+//! backend.jmp_label(&mut (), cfg, &"exit")?;
+//! ```
+//!
+//! Text writers already accept any [`Display`] label. Writers with a label
+//! type of their own, such as the binary backends, are adapted with
+//! [`LabelMap`].
+
+use core::fmt::Display;
+use core::ops::{Deref, DerefMut};
+
+use crate::out::{Writer, WriterCore};
+
+/// A label as passed through a [`DynWriter`].
+pub type DynLabel<'a> = &'a (dyn Display + 'a);
+
+/// A [`Writer`] taking [`DynLabel`]s and no context, usable as a trait object.
+///
+/// Implemented for every writer that accepts [`DynLabel`]s of any lifetime
+/// with `()` as its context.
+pub trait DynWriter<E = core::fmt::Error>: for<'l> Writer<DynLabel<'l>, (), Error = E> {}
+
+impl<W, E> DynWriter<E> for W where W: for<'l> Writer<DynLabel<'l>, (), Error = E> + ?Sized {}
+
+/// Adapts a writer with its own label type to [`DynLabel`]s.
+///
+/// `map` converts each label as it is used and must map labels that display
+/// the same to equal values.
+pub struct LabelMap<W, F> {
+    map: F,
+    writer: W,
+}
+
+impl<W, F> LabelMap<W, F> {
+    /// Wraps `writer`, converting labels with `map`.
+    pub fn new(writer: W, map: F) -> Self {
+        Self { map, writer }
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W, F> Deref for LabelMap<W, F> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        &self.writer
+    }
+}
+
+impl<W, F> DerefMut for LabelMap<W, F> {
+    fn deref_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+crate::writer_dispatch!(@core [ W: WriterCore<()>, F ] LabelMap<W, F> => W::Error [()] [W]);
+
+impl<'l, W, F, L> Writer<DynLabel<'l>, ()> for LabelMap<W, F>
+where
+    W: Writer<L, ()>,
+    F: FnMut(DynLabel<'_>) -> L,
+{
+    fn set_label(
+        &mut self,
+        ctx: &mut (),
+        cfg: crate::X64Arch,
+        s: DynLabel<'l>,
+    ) -> Result<(), Self::Error> {
+        let s = (self.map)(s);
+        self.writer.set_label(ctx, cfg, s)
+    }
+
+    fn lea_label(
+        &mut self,
+        ctx: &mut (),
+        cfg: crate::X64Arch,
+        dest: &(dyn crate::out::arg::MemArg + '_),
+        label: DynLabel<'l>,
+    ) -> Result<(), Self::Error> {
+        let label = (self.map)(label);
+        self.writer.lea_label(ctx, cfg, dest, label)
+    }
+
+    fn call_label(
+        &mut self,
+        ctx: &mut (),
+        cfg: crate::X64Arch,
+        label: DynLabel<'l>,
+    ) -> Result<(), Self::Error> {
+        let label = (self.map)(label);
+        self.writer.call_label(ctx, cfg, label)
+    }
+
+    fn jmp_label(
+        &mut self,
+        ctx: &mut (),
+        cfg: crate::X64Arch,
+        label: DynLabel<'l>,
+    ) -> Result<(), Self::Error> {
+        let label = (self.map)(label);
+        self.writer.jmp_label(ctx, cfg, label)
+    }
+
+    fn jcc_label(
+        &mut self,
+        ctx: &mut (),
+        cfg: crate::X64Arch,
+        cc: crate::ConditionCode,
+        label: DynLabel<'l>,
+    ) -> Result<(), Self::Error> {
+        let label = (self.map)(label);
+        self.writer.jcc_label(ctx, cfg, cc, label)
+    }
+
+    fn dd_label(
+        &mut self,
+        ctx: &mut (),
+        cfg: crate::X64Arch,
+        label: DynLabel<'l>,
+    ) -> Result<(), Self::Error> {
+        let label = (self.map)(label);
+        self.writer.dd_label(ctx, cfg, label)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::X64Arch;
+    use alloc::format;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_backends_share_one_object_type() {
+        let cfg = X64Arch::default();
+        let exit: DynLabel<'_> = &"exit";
+
+        let mut text = String::new();
+        let mut renamed = String::new();
+        {
+            let mut plain: &mut dyn Write = &mut text;
+            let mut mapped = LabelMap::new(&mut renamed as &mut dyn Write, |l: DynLabel<'_>| {
+                format!(".L{l}")
+            });
+            let backends: [&mut dyn DynWriter; 2] = [&mut plain, &mut mapped];
+            for backend in backends {
+                backend.set_label(&mut (), cfg, exit).unwrap();
+                backend.jmp_label(&mut (), cfg, exit).unwrap();
+            }
+        }
+        assert_eq!(text, "exit:\njmp exit\n");
+        assert_eq!(renamed, ".Lexit:\njmp .Lexit\n");
+    }
+}
@@ -0,0 +1,83 @@
+//! Structured errors for instruction emission.
+//!
+//! Writers keep their own `Error` type, but lowering layers such as
+//! [`DesugaringWriter`](crate::desugar::DesugaringWriter) report problems
+//! with the input as an [`EmitError`] and convert it with `From`. The
+//! translation shims use it to reject guest instructions they cannot
+//! express. Text writers and the iced backend fold it into
+//! [`core::fmt::Error`], which carries no details.
+
+use core::fmt;
+
+/// Why an instruction could not be emitted.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum EmitError {
+    /// The instruction has no encoding or lowering on this target.
+    UnsupportedInstruction {
+        /// Mnemonic of the rejected instruction.
+        mnemonic: &'static str,
+    },
+    /// The operands are not valid for the instruction in this combination.
+    InvalidOperands {
+        /// Mnemonic of the rejected instruction.
+        mnemonic: &'static str,
+        /// What was wrong with the operands.
+        reason: &'static str,
+    },
+    /// An immediate does not fit the instruction's encoding.
+    ImmediateOutOfRange {
+        /// Mnemonic of the rejected instruction.
+        mnemonic: &'static str,
+        /// The immediate that was out of range.
+        value: i64,
+    },
+    /// The instruction needs an extension the arch configuration disables.
+    ExtensionNotEnabled {
+        /// Mnemonic of the rejected instruction.
+        mnemonic: &'static str,
+        /// Name of the missing extension.
+        extension: &'static str,
+    },
+}
+
+impl EmitError {
+    /// Returns the mnemonic of the rejected instruction.
+    pub fn mnemonic(&self) -> &'static str {
+        match *self {
+            EmitError::UnsupportedInstruction { mnemonic }
+            | EmitError::InvalidOperands { mnemonic, .. }
+            | EmitError::ImmediateOutOfRange { mnemonic, .. }
+            | EmitError::ExtensionNotEnabled { mnemonic, .. } => mnemonic,
+        }
+    }
+}
+
+impl fmt::Display for EmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmitError::UnsupportedInstruction { mnemonic } => {
+                write!(f, "{mnemonic}: not supported on this target")
+            }
+            EmitError::InvalidOperands { mnemonic, reason } => {
+                write!(f, "{mnemonic}: invalid operands: {reason}")
+            }
+            EmitError::ImmediateOutOfRange { mnemonic, value } => {
+                write!(f, "{mnemonic}: immediate {value} out of range")
+            }
+            EmitError::ExtensionNotEnabled {
+                mnemonic,
+                extension,
+            } => write!(f, "{mnemonic}: requires {extension}"),
+        }
+    }
+}
+
+impl core::error::Error for EmitError {}
+
+/// Text writers report emission errors as a bare formatting failure.
+impl From<EmitError> for fmt::Error {
+    fn from(_: EmitError) -> Self {
+        fmt::Error
+    }
+}
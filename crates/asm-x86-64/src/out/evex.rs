@@ -0,0 +1,228 @@
+//! AVX-512 write masks and broadcast operands.
+//!
+//! EVEX-encoded instructions take two decorations that VEX ones do not. A
+//! write mask limits the elements an instruction writes to those whose bit
+//! is set in an opmask register, either leaving the others unchanged
+//! (merging) or clearing them (zeroing). A broadcast memory source reads a
+//! single element and repeats it across the vector:
+//!
+//! ```ignore
+//! // zmm0 = zmm1 + [rax] in the elements k1 selects, zero elsewhere
+//! let zmm = |r| ArgKind::Reg { reg: Reg(r), size: MemorySize::_512 };
+//! writer.kmov(ctx, cfg, MaskReg(1), &Reg(2))?;
+//! writer.vaddpd(ctx, cfg, &zmm(0), OpMask::zeroing(MaskReg(1)), &zmm(1), &qword_at_rax, true)?;
+//! // kmovq k1, rdx
+//! // vaddpd zmm0{k1}{z},zmm1,qword ptr [rax]{1to8}
+//! ```
+//!
+//! The instructions themselves are [`WriterCore`](crate::out::WriterCore)
+//! methods (`kmov`, `vmovupd`, `vaddpd`, `vcmppd` and so on), which need
+//! [`X64Arch::avx512`](crate::X64Arch::avx512). Vector operands are
+//! registers whose operand size picks the vector length: 128, 256 or 512
+//! bits, with anything smaller taken as 128. A broadcast source is a memory
+//! operand one element wide.
+
+use core::fmt::{self, Display, Formatter};
+
+use portal_pc_asm_common::types::mem::MemorySize;
+
+use crate::MaskReg;
+use crate::out::arg::{ArgKind, MemArg, MemArgKind};
+
+/// The write mask of an EVEX instruction.
+///
+/// The default, [`OpMask::NONE`], writes every element.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpMask {
+    /// The mask register; `k0` writes every element.
+    pub reg: MaskReg,
+    /// Whether elements the mask disables are zeroed rather than left unchanged.
+    pub zeroing: bool,
+}
+
+impl OpMask {
+    /// No mask: every element is written.
+    pub const NONE: Self = Self {
+        reg: MaskReg(0),
+        zeroing: false,
+    };
+
+    /// Writes the elements `reg` selects and leaves the others unchanged.
+    pub fn merging(reg: MaskReg) -> Self {
+        Self {
+            reg,
+            zeroing: false,
+        }
+    }
+
+    /// Writes the elements `reg` selects and zeroes the others.
+    pub fn zeroing(reg: MaskReg) -> Self {
+        Self { reg, zeroing: true }
+    }
+
+    /// Returns whether the mask disables any element, i.e. names a register
+    /// other than `k0`.
+    pub fn is_masked(&self) -> bool {
+        self.reg.0 != 0
+    }
+}
+
+impl Display for OpMask {
+    /// Writes the `{k1}` or `{k1}{z}` decoration, or nothing without a mask.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.is_masked() {
+            return Ok(());
+        }
+        write!(f, "{{{}}}", self.reg)?;
+        if self.zeroing {
+            f.write_str("{z}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `{1toN}` decoration of a broadcast memory operand.
+///
+/// `Broadcast(0)` is no broadcast and displays as nothing.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Broadcast(pub u32);
+
+impl Broadcast {
+    /// Returns the decoration for broadcasting `elem`-sized elements across
+    /// a vector as long as `vector`, or none unless `enabled`.
+    pub fn new(vector: &(dyn MemArg + '_), elem: MemorySize, enabled: bool) -> Self {
+        if !enabled {
+            return Self(0);
+        }
+        Self(bits(vector_size(vector)) / bits(elem))
+    }
+}
+
+impl Display for Broadcast {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => Ok(()),
+            n => write!(f, "{{1to{n}}}"),
+        }
+    }
+}
+
+/// Returns the vector length of a register or memory operand: its size if
+/// that is 256 or 512 bits, and 128 bits otherwise.
+pub fn vector_size(arg: &(dyn MemArg + '_)) -> MemorySize {
+    let size = match arg.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Reg { size, .. }) | MemArgKind::Mem { size, .. } => size,
+        _ => MemorySize::_128,
+    };
+    match size {
+        MemorySize::_256 | MemorySize::_512 => size,
+        _ => MemorySize::_128,
+    }
+}
+
+fn bits(size: MemorySize) -> u32 {
+    match size {
+        MemorySize::_8 => 8,
+        MemorySize::_16 => 16,
+        MemorySize::_32 => 32,
+        MemorySize::_64 => 64,
+        MemorySize::_128 => 128,
+        MemorySize::_256 => 256,
+        _ => 512,
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::out::WriterCore;
+    use crate::out::arg::Segment;
+    use crate::{RegisterClass, X64Arch};
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    fn zmm(reg: u8) -> ArgKind {
+        ArgKind::Reg {
+            reg: Reg(reg),
+            size: MemorySize::_512,
+        }
+    }
+
+    fn at_rax(size: MemorySize, reg_class: RegisterClass) -> MemArgKind {
+        MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(0),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 0,
+            size,
+            reg_class,
+            segment: Segment::None,
+        }
+    }
+
+    #[test]
+    fn test_masked_and_broadcast_text() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        let cfg = X64Arch {
+            avx512: true,
+            ..X64Arch::default()
+        };
+        let (k1, k2) = (MaskReg(1), MaskReg(2));
+        let qword = at_rax(MemorySize::_64, RegisterClass::Gpr);
+        w.kmov(&mut (), cfg, k1, &Reg(2)).unwrap();
+        w.vaddpd(
+            &mut (),
+            cfg,
+            &zmm(0),
+            OpMask::zeroing(k1),
+            &zmm(1),
+            &zmm(17),
+            false,
+        )
+        .unwrap();
+        w.vfmadd231pd(
+            &mut (),
+            cfg,
+            &zmm(0),
+            OpMask::merging(k1),
+            &zmm(1),
+            &qword,
+            true,
+        )
+        .unwrap();
+        w.vcmppd(&mut (), cfg, k2, OpMask::NONE, &zmm(0), &zmm(1), 1, false)
+            .unwrap();
+        let zmmword = at_rax(MemorySize::_512, RegisterClass::Xmm);
+        w.vmovupd(&mut (), cfg, &zmmword, OpMask::merging(k2), &zmm(0))
+            .unwrap();
+        assert_eq!(
+            output,
+            "kmovq k1, rdx\nvaddpd zmm0{k1}{z},zmm1,zmm17\n\
+             vfmadd231pd zmm0{k1},zmm1,qword ptr [rax+0]{1to8}\nvcmppd k2,zmm0,zmm1,1\n\
+             vmovupd zmmword ptr [rax+0]{k2},zmm0\n"
+        );
+    }
+
+    #[test]
+    fn test_broadcast_counts_elements() {
+        let ymm = ArgKind::Reg {
+            reg: Reg(0),
+            size: MemorySize::_256,
+        };
+        assert_eq!(Broadcast::new(&ymm, MemorySize::_64, true), Broadcast(4));
+        assert_eq!(
+            Broadcast::new(&zmm(0), MemorySize::_32, true),
+            Broadcast(16)
+        );
+        assert_eq!(Broadcast::new(&Reg(0), MemorySize::_64, true), Broadcast(2));
+        assert_eq!(
+            Broadcast::new(&zmm(0), MemorySize::_64, false),
+            Broadcast(0)
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Multiply-add, fused when the target has FMA3.
+//!
+//! [`Fma`] accumulates `a * b` into a register on scalar doubles:
+//!
+//! ```ignore
+//! writer.fma(ctx, cfg, &acc, &a, &b, &scratch)?;
+//! // with cfg.fma:    vfmadd231sd acc,a,b
+//! // without cfg.fma: movsd scratch,a; mulsd scratch,b; addsd acc,scratch
+//! ```
+//!
+//! The fallback rounds the product before adding it, so its result can
+//! differ from the fused one in the last bit, and it does not keep the
+//! extra precision algorithms like compensated summation rely on. Code that
+//! needs the single rounding should require [`X64Arch::fma`] instead.
+//!
+//! The AArch64 and RISC-V crates provide the same helper with `fmadd` and
+//! `fmadd.d`, which are always fused.
+
+use crate::X64Arch;
+use crate::out::WriterCore;
+use crate::out::arg::MemArg;
+
+/// Emits multiply-adds, fused when [`X64Arch::fma`] is set.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Fma<Context>: WriterCore<Context> {
+    /// Sets the XMM register `acc` to `a * b + acc`.
+    ///
+    /// `scratch` is only written when FMA3 is disabled; it must be an XMM
+    /// register other than `acc` and `b`.
+    fn fma(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        acc: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        if cfg.fma {
+            return self.vfmadd231sd(ctx, cfg, acc, a, b);
+        }
+        self.fmov(ctx, cfg, scratch, a)?;
+        self.fmul(ctx, cfg, scratch, b)?;
+        self.fadd(ctx, cfg, acc, scratch)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Fma<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_fma_falls_back_without_fma3() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let base = X64Arch::default();
+        let (acc, a, b, scratch) = (Reg(0), Reg(1), Reg(2), Reg(3));
+        writer.fma(&mut (), base, &acc, &a, &b, &scratch).unwrap();
+        let fma = X64Arch { fma: true, ..base };
+        writer.fma(&mut (), fma, &acc, &a, &b, &scratch).unwrap();
+        assert_eq!(
+            output,
+            "movsd xmm3,xmm1\nmulsd xmm3,xmm2\naddsd xmm0,xmm3\nvfmadd231sd xmm0,xmm1,xmm2\n"
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Stack probing for large frames.
+//!
+//! Below the stack sits a guard page that turns an overflow into a fault.
+//! Moving `rsp` down by a page or more in one step can jump over it and
+//! land in other memory (a "stack clash"), so every page of a large frame
+//! must be touched in order as it is allocated. [`FrameBuilder`] allocates
+//! frames that way:
+//!
+//! ```ignore
+//! let frame = FrameBuilder::new(probe_label);
+//! frame.probe_large_frame(&mut writer, ctx, cfg, 0x6000)?;
+//! // mov r11, 6
+//! // probe_label: sub rsp,4096; or qword ptr [rsp], 0; sub r11,1; jne probe_label
+//! ```
+//!
+//! Under [`CallConv::Win64`](crate::CallConv::Win64) the platform routine
+//! `__chkstk` does the probing instead, as MSVC-compiled code expects: it
+//! takes the size in `rax` and clobbers `r10` and `r11`. Elsewhere a
+//! frame of up to four pages is probed with straight-line code and larger
+//! ones with a loop counting pages in a scratch register.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::Writer;
+use crate::out::arg::{ArgKind, MemArgKind, Segment};
+use crate::{CallConv, ConditionCode, RegisterClass, X64Arch};
+
+/// Bytes of stack a frame may allocate before it must be probed.
+pub const PROBE_INTERVAL: u32 = 4096;
+
+/// Default scratch register for the probe loop: `r11`, which the SysV ABI
+/// leaves free across calls and does not use for arguments.
+pub const DEFAULT_SCRATCH: Reg = Reg(11);
+
+/// Most pages probed with straight-line code before a loop is used.
+const UNROLL_LIMIT: u32 = 4;
+
+/// The Windows stack probe routine.
+const CHKSTK: &str = "__chkstk";
+
+/// The stack pointer, `rsp`.
+const RSP: Reg = Reg(4);
+
+/// `rax`, which carries the frame size to `__chkstk`.
+const RAX: Reg = Reg(0);
+
+/// The word at `[rsp]`.
+fn stack_top() -> MemArgKind<ArgKind> {
+    MemArgKind::Mem {
+        base: ArgKind::Reg {
+            reg: RSP,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp: 0,
+        size: MemorySize::_64,
+        reg_class: RegisterClass::Gpr,
+        segment: Segment::None,
+    }
+}
+
+/// Allocates stack frames without skipping the guard page.
+pub struct FrameBuilder<L> {
+    probe_loop: L,
+    scratch: Reg,
+}
+
+impl<L: Clone> FrameBuilder<L> {
+    /// Creates a builder that labels its probe loop `probe_loop`.
+    ///
+    /// The label is only defined when a loop is emitted, and at most once
+    /// per call to [`probe_large_frame`](Self::probe_large_frame).
+    pub fn new(probe_loop: L) -> Self {
+        Self {
+            probe_loop,
+            scratch: DEFAULT_SCRATCH,
+        }
+    }
+
+    /// Sets the register the probe loop counts pages in.
+    pub fn with_scratch(self, scratch: Reg) -> Self {
+        Self { scratch, ..self }
+    }
+
+    /// Moves `rsp` down by `size` bytes, touching each new page on the way.
+    ///
+    /// Frames smaller than [`PROBE_INTERVAL`] are a plain `sub`. The
+    /// sequence clobbers the flags and, for large frames, the scratch
+    /// register (or `rax`, `r10` and `r11` under Win64).
+    pub fn probe_large_frame<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: u32,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        if size < PROBE_INTERVAL {
+            return match size {
+                0 => Ok(()),
+                size => w.sub_imm(ctx, cfg, &RSP, size as i32),
+            };
+        }
+        if cfg.call_conv == CallConv::Win64 {
+            w.mov(ctx, cfg, &RAX, &u64::from(size))?;
+            w.call(ctx, cfg, &ArgKind::sym(CHKSTK))?;
+            return w.sub(ctx, cfg, &RSP, &RAX);
+        }
+        let pages = size / PROBE_INTERVAL;
+        if pages <= UNROLL_LIMIT {
+            for _ in 0..pages {
+                self.probe_page(w, ctx, cfg, PROBE_INTERVAL)?;
+            }
+        } else {
+            w.mov(ctx, cfg, &self.scratch, &u64::from(pages))?;
+            w.set_label(ctx, cfg, self.probe_loop.clone())?;
+            self.probe_page(w, ctx, cfg, PROBE_INTERVAL)?;
+            w.sub_imm(ctx, cfg, &self.scratch, 1)?;
+            w.jcc_label(ctx, cfg, ConditionCode::NE, self.probe_loop.clone())?;
+        }
+        match size % PROBE_INTERVAL {
+            0 => Ok(()),
+            rest => self.probe_page(w, ctx, cfg, rest),
+        }
+    }
+
+    /// Moves `rsp` down by `bytes` and touches the word it now points at.
+    fn probe_page<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        bytes: u32,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        w.sub_imm(ctx, cfg, &RSP, bytes as i32)?;
+        w.or_imm(ctx, cfg, &stack_top(), 0)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_small_frame_is_not_probed() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        FrameBuilder::new(".Lprobe")
+            .probe_large_frame(&mut writer, &mut (), X64Arch::default(), 48)
+            .unwrap();
+        assert_eq!(output, "sub rsp,48\n");
+    }
+
+    #[test]
+    fn test_few_pages_are_unrolled() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        FrameBuilder::new(".Lprobe")
+            .probe_large_frame(&mut writer, &mut (), X64Arch::default(), 8192 + 16)
+            .unwrap();
+        assert_eq!(output.matches("sub rsp,4096\nor ").count(), 2);
+        assert!(output.contains("sub rsp,16\nor "));
+        assert!(!output.contains(".Lprobe"));
+    }
+
+    #[test]
+    fn test_many_pages_use_a_loop() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        FrameBuilder::new(".Lprobe")
+            .probe_large_frame(&mut writer, &mut (), X64Arch::default(), 10 * 4096)
+            .unwrap();
+        assert!(output.starts_with("mov r11, 10\n.Lprobe:\nsub rsp,4096\n"));
+        assert!(output.ends_with("sub r11,1\njne .Lprobe\n"));
+    }
+
+    #[test]
+    fn test_win64_calls_chkstk() {
+        let cfg = X64Arch {
+            call_conv: CallConv::Win64,
+            ..X64Arch::default()
+        };
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        FrameBuilder::new(".Lprobe")
+            .probe_large_frame(&mut writer, &mut (), cfg, 0x10000)
+            .unwrap();
+        assert_eq!(output, "mov rax, 65536\ncall __chkstk\nsub rsp,rax\n");
+    }
+}
@@ -0,0 +1,235 @@
+//! Function symbols and hot/cold placement for text output.
+//!
+//! Writers emit instructions and labels but no symbol metadata, so a
+//! translated function is just a label to the linker. [`emit_function`]
+//! brackets a body with the directives an ELF toolchain expects: the
+//! section, the symbol's binding and visibility, `.type` and `.size`.
+//! Marking a function [`Temperature::Hot`] or [`Temperature::Cold`] places it
+//! in `.text.hot` or `.text.unlikely`, which linkers group together the same
+//! way they do for PGO-built code. For [`AssemblerDialect::Masm`] the
+//! function is a `PROC`/`ENDP` block in `.code` instead; MASM has no ELF
+//! visibility or hot/cold sections, so non-local symbols are just `PUBLIC`:
+//!
+//! ```ignore
+//! let out: &mut dyn Write = &mut output;
+//! let func = Function::new("handler").with_visibility(Visibility::Global).with_temperature(Temperature::Cold);
+//! emit_function(out, &func, |w| w.ret(ctx, cfg))?;
+//! ```
+
+use core::fmt::{self, Write};
+
+use crate::AssemblerDialect;
+
+/// Operand of `.type` that marks a function symbol.
+const FUNCTION_TYPE: &str = "@function";
+
+/// Where the linker should place a function relative to other code.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Temperature {
+    /// Ordinary code, in `.text`.
+    #[default]
+    Normal,
+    /// Frequently executed code, in `.text.hot`.
+    Hot,
+    /// Rarely executed code, in `.text.unlikely`.
+    Cold,
+}
+
+impl Temperature {
+    /// Returns the name of the section functions of this temperature go in.
+    pub fn section(self) -> &'static str {
+        match self {
+            Temperature::Normal => ".text",
+            Temperature::Hot => ".text.hot",
+            Temperature::Cold => ".text.unlikely",
+        }
+    }
+}
+
+/// Binding and visibility of a function symbol.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Visibility {
+    /// Local to the object file.
+    #[default]
+    Local,
+    /// Global and visible outside the shared object.
+    Global,
+    /// Global, but not exported from the shared object (`.hidden`).
+    Hidden,
+    /// Global and exported, but not preemptible (`.protected`).
+    Protected,
+}
+
+/// Symbol metadata for one function.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Function<'a> {
+    /// Symbol name, also used as the entry label.
+    pub name: &'a str,
+    /// Binding and visibility of the symbol.
+    pub visibility: Visibility,
+    /// Section placement.
+    pub temperature: Temperature,
+    /// Assembler the directives are written for.
+    pub assembler: AssemblerDialect,
+    /// Whether the entry starts with an `endbr64` landing pad, as CET
+    /// indirect branch tracking ([`X64Arch::ibt`](crate::X64Arch::ibt))
+    /// requires.
+    pub landing_pad: bool,
+}
+
+impl<'a> Function<'a> {
+    /// Describes a local function in `.text`.
+    pub fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            visibility: Visibility::Local,
+            temperature: Temperature::Normal,
+            assembler: AssemblerDialect::Gnu,
+            landing_pad: false,
+        }
+    }
+
+    /// Sets the symbol's binding and visibility.
+    pub fn with_visibility(self, visibility: Visibility) -> Self {
+        Self { visibility, ..self }
+    }
+
+    /// Sets the section placement.
+    pub fn with_temperature(self, temperature: Temperature) -> Self {
+        Self {
+            temperature,
+            ..self
+        }
+    }
+
+    /// Sets the assembler the directives are written for.
+    pub fn with_assembler(self, assembler: AssemblerDialect) -> Self {
+        Self { assembler, ..self }
+    }
+
+    /// Sets whether the entry starts with an `endbr64` landing pad.
+    pub fn with_landing_pad(self, landing_pad: bool) -> Self {
+        Self {
+            landing_pad,
+            ..self
+        }
+    }
+
+    /// Writes the section, symbol directives and entry label, followed by
+    /// the landing pad if there is one.
+    pub fn begin(&self, out: &mut (impl Write + ?Sized)) -> fmt::Result {
+        self.begin_symbol(out)?;
+        if self.landing_pad {
+            out.write_str("endbr64\n")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the section, symbol directives and entry label.
+    fn begin_symbol(&self, out: &mut (impl Write + ?Sized)) -> fmt::Result {
+        let name = self.name;
+        if self.assembler == AssemblerDialect::Masm {
+            out.write_str(".code\n")?;
+            if self.visibility != Visibility::Local {
+                writeln!(out, "PUBLIC {name}")?;
+            }
+            return writeln!(out, "{name} PROC");
+        }
+        match self.temperature {
+            Temperature::Normal => out.write_str(".text\n")?,
+            t => writeln!(out, ".section {},\"ax\",@progbits", t.section())?,
+        }
+        match self.visibility {
+            Visibility::Local => {}
+            Visibility::Global => writeln!(out, ".globl {name}")?,
+            Visibility::Hidden => writeln!(out, ".globl {name}\n.hidden {name}")?,
+            Visibility::Protected => writeln!(out, ".globl {name}\n.protected {name}")?,
+        }
+        writeln!(out, ".type {name}, {FUNCTION_TYPE}\n{name}:")
+    }
+
+    /// Writes the `.size` directive closing the function.
+    pub fn end(&self, out: &mut (impl Write + ?Sized)) -> fmt::Result {
+        if self.assembler == AssemblerDialect::Masm {
+            return writeln!(out, "{} ENDP", self.name);
+        }
+        writeln!(out, ".size {name}, .-{name}", name = self.name)
+    }
+}
+
+/// Emits `body` as the function `func`.
+///
+/// `body` receives the same sink, so with `W = dyn Write` it can use the
+/// text writers directly.
+pub fn emit_function<W, E>(
+    out: &mut W,
+    func: &Function<'_>,
+    body: impl FnOnce(&mut W) -> Result<(), E>,
+) -> Result<(), E>
+where
+    W: Write + ?Sized,
+    E: From<fmt::Error>,
+{
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("function", name = func.name).entered();
+    func.begin(out)?;
+    body(out)?;
+    func.end(out)?;
+    Ok(())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::X64Arch;
+    use crate::out::WriterCore;
+    use alloc::string::String;
+
+    #[test]
+    fn test_cold_global_function() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let func = Function::new("handler")
+            .with_visibility(Visibility::Hidden)
+            .with_temperature(Temperature::Cold);
+        emit_function(out, &func, |w| w.ret(&mut (), X64Arch::default())).unwrap();
+        assert_eq!(
+            output,
+            ".section .text.unlikely,\"ax\",@progbits\n.globl handler\n.hidden handler\n\
+             .type handler, @function\nhandler:\nret\n.size handler, .-handler\n"
+        );
+    }
+
+    #[test]
+    fn test_landing_pad_follows_entry_label() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let func = Function::new("handler").with_landing_pad(true);
+        emit_function(out, &func, |w| w.ret(&mut (), X64Arch::default())).unwrap();
+        assert!(output.contains("handler:\nendbr64\nret\n"));
+    }
+
+    #[test]
+    fn test_masm_function() {
+        let cfg = X64Arch {
+            assembler: AssemblerDialect::Masm,
+            ..X64Arch::default()
+        };
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let func = Function::new("handler")
+            .with_visibility(Visibility::Global)
+            .with_assembler(AssemblerDialect::Masm);
+        emit_function(out, &func, |w| {
+            w.align(&mut (), cfg, 4)?;
+            w.db(&mut (), cfg, &[0x90, 0xc3])
+        })
+        .unwrap();
+        assert_eq!(
+            output,
+            ".code\nPUBLIC handler\nhandler PROC\nALIGN 16\nDB 090h, 0c3h\nhandler ENDP\n"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Patchable address slots for symbols resolved at load time.
+//!
+//! Translated code that calls into imports cannot embed their addresses: they
+//! are only known once a loader has mapped the image. [`GlobalOffsetTable`]
+//! gives each such symbol a pointer-sized slot in a data island, and
+//! x86-64 code reaches such a symbol with a RIP-relative `mov` from its slot. The loader binds each slot by writing the resolved address
+//! at its label:
+//!
+//! ```ignore
+//! let mut next = 0usize;
+//! let mut got = GlobalOffsetTable::new(|| { next += 1; format!(".LGOT{next}") });
+//! got.load_address(&mut writer, ctx, cfg, Reg(0), "puts")?;
+//! writer.ret(ctx, cfg)?;
+//! for (name, label) in got.emit(&mut writer, ctx, cfg)? {
+//!     // record `name` -> offset of `label` for the loader
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::X64Arch;
+use crate::out::Writer;
+use crate::out::arg::{ArgKind, MemArgKind, Segment};
+
+/// Allocates one patchable 64-bit slot per imported symbol.
+///
+/// Each name gets a single slot, labelled with a label from the `alloc`
+/// closure. Slots are written out zeroed by [`GlobalOffsetTable::emit`] and
+/// must end up somewhere within RIP-relative range of the loads.
+pub struct GlobalOffsetTable<L, F> {
+    alloc: F,
+    /// Symbol names and the labels of their slots.
+    entries: Vec<(&'static str, L)>,
+}
+
+impl<L: Clone, F: FnMut() -> L> GlobalOffsetTable<L, F> {
+    /// Creates an empty table that labels its slots with `alloc`.
+    pub fn new(alloc: F) -> Self {
+        Self {
+            alloc,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the number of symbols with a slot.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no slots have been allocated.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the label of the slot for `name`, adding one if needed.
+    ///
+    /// This is the label the loader patches with the symbol's address.
+    pub fn slot(&mut self, name: &'static str) -> L {
+        if let Some((_, label)) = self.entries.iter().find(|(n, _)| *n == name) {
+            return label.clone();
+        }
+        let label = (self.alloc)();
+        self.entries.push((name, label.clone()));
+        label
+    }
+
+    /// Loads the runtime address of `name` into `dest` through its slot.
+    ///
+    /// Returns the slot's label.
+    pub fn load_address<W, Context>(
+        &mut self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: Reg,
+        name: &'static str,
+    ) -> Result<L, W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let label = self.slot(name);
+        w.lea_label(ctx, cfg, &dest, label.clone())?;
+        w.mov(ctx, cfg, &dest, &slot(dest))?;
+        Ok(label)
+    }
+
+    /// Emits the zeroed slots, 8-byte aligned, and empties the table.
+    ///
+    /// Returns each symbol name with its slot label so the caller can hand
+    /// the bindings to a loader. Like a constant pool, the table is data and
+    /// must be placed where execution cannot fall into it.
+    pub fn emit<W, Context>(
+        &mut self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: X64Arch,
+    ) -> Result<Vec<(&'static str, L)>, W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        if self.entries.is_empty() {
+            return Ok(Vec::new());
+        }
+        w.align_to(ctx, cfg, 8)?;
+        for (_, label) in &self.entries {
+            w.set_label(ctx, cfg, label.clone())?;
+            w.db(ctx, cfg, &[0; 8])?;
+        }
+        Ok(core::mem::take(&mut self.entries))
+    }
+}
+
+/// The 64-bit slot addressed by `addr`.
+fn slot(addr: Reg) -> MemArgKind<ArgKind> {
+    MemArgKind::Mem {
+        base: ArgKind::Reg {
+            reg: addr,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp: 0,
+        size: MemorySize::_64,
+        reg_class: crate::RegisterClass::Gpr,
+        segment: Segment::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_slots_are_shared_and_returned() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = X64Arch::default();
+        let mut next = 0;
+        let mut got = GlobalOffsetTable::new(|| {
+            next += 1;
+            format!(".LGOT{next}")
+        });
+        let puts = got
+            .load_address(&mut writer, &mut (), cfg, Reg(0), "puts")
+            .unwrap();
+        got.load_address(&mut writer, &mut (), cfg, Reg(1), "puts")
+            .unwrap();
+        let exit = got.slot("exit");
+        assert_eq!(got.len(), 2);
+        let bindings = got.emit(&mut writer, &mut (), cfg).unwrap();
+        assert!(got.is_empty());
+
+        assert_eq!(bindings, [("puts", puts), ("exit", exit)]);
+        assert!(output.starts_with("lea rax, [rip + .LGOT1]\nmov rax, qword ptr [rax+0]\n"));
+        assert!(output.contains("lea rcx, [rip + .LGOT1]\n"));
+        assert!(output.contains(".balign 8\n.LGOT1:\n.byte 0x00"));
+        assert!(output.contains(".LGOT2:\n"));
+    }
+}
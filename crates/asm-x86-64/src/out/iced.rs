@@ -38,6 +38,11 @@ mod helpers {
         match instr.op_kind(op_index) {
             iced_x86::OpKind::Register => {
                 let reg = instr.op_register(op_index);
+                use iced_x86::Register::{AH, BH, CH, DH};
+                if matches!(reg, AH | CH | DH | BH) {
+                    let idx = reg as u8 - iced_x86::Register::AH as u8;
+                    return Some(MemArgKind::NoMem(ArgKind::HighByte(Reg(idx))));
+                }
                 iced_register_to_reg(reg).map(|r| {
                     let size = MemorySize::_64; // default for registers
                     MemArgKind::NoMem(ArgKind::Reg { reg: r, size })
@@ -61,10 +66,21 @@ mod helpers {
                 instr.immediate8to64() as u64,
             ))),
             iced_x86::OpKind::Memory => {
-                let base = iced_register_to_reg(instr.memory_base());
-                let index = iced_register_to_reg(instr.memory_index());
+                let base = match instr.memory_base() {
+                    iced_x86::Register::None => None,
+                    reg => iced_register_to_reg(reg),
+                };
+                let index = match instr.memory_index() {
+                    iced_x86::Register::None => None,
+                    reg => iced_register_to_reg(reg),
+                };
                 let scale = instr.memory_index_scale();
-                let disp = instr.memory_displacement64() as u32;
+                // iced gives RIP-relative operands as the absolute target.
+                let rip = instr.is_ip_rel_memory_operand();
+                let disp = match rip {
+                    true => instr.memory_displacement64().wrapping_sub(instr.next_ip()) as i32,
+                    false => instr.memory_displacement64() as i32,
+                };
                 let size = match instr.memory_size().size() {
                     1 => MemorySize::_8,
                     2 => MemorySize::_16,
@@ -83,23 +99,45 @@ mod helpers {
                     )
                 });
                 Some(MemArgKind::Mem {
-                    base: base
-                        .map(|r| ArgKind::Reg {
+                    base: match (rip, base) {
+                        (true, _) => ArgKind::Rip,
+                        (false, Some(r)) => ArgKind::Reg {
                             reg: r,
                             size: MemorySize::_64,
-                        })
-                        .unwrap_or(ArgKind::Lit(0)), // dummy if no base
+                        },
+                        (false, None) => ArgKind::NoBase,
+                    },
                     offset,
                     disp,
                     size,
                     reg_class,
-                    segment: crate::out::arg::Segment::None,
+                    segment: match instr.segment_prefix() {
+                        iced_x86::Register::FS => crate::out::arg::Segment::Fs,
+                        iced_x86::Register::GS => crate::out::arg::Segment::Gs,
+                        _ => crate::out::arg::Segment::None,
+                    },
                 })
             }
             _ => None,
         }
     }
 
+    /// Get the repeat prefix of a string instruction.
+    ///
+    /// `rep` and `repe` share the F3 prefix; it reads as `repe` only on the
+    /// comparing instructions (`cmps`, `scas`).
+    pub fn rep_prefix(instr: &iced_x86::Instruction, compares: bool) -> crate::RepPrefix {
+        if instr.has_repne_prefix() {
+            crate::RepPrefix::Repne
+        } else if !instr.has_rep_prefix() {
+            crate::RepPrefix::None
+        } else if compares {
+            crate::RepPrefix::Repe
+        } else {
+            crate::RepPrefix::Rep
+        }
+    }
+
     /// Get the first operand as MemArgKind.
     pub fn op0(instr: &iced_x86::Instruction) -> Option<MemArgKind<ArgKind>> {
         iced_operand_to_mem_arg_kind(instr, 0)
@@ -156,6 +194,16 @@ where
                 writer.sub(ctx, *arch, a, b)?;
             }
         }
+        Mnemonic::Adc => {
+            if let (Some(a), Some(b)) = (dest, src) {
+                writer.adc(ctx, *arch, a, b)?;
+            }
+        }
+        Mnemonic::Sbb => {
+            if let (Some(a), Some(b)) = (dest, src) {
+                writer.sbb(ctx, *arch, a, b)?;
+            }
+        }
         Mnemonic::Cmp => {
             if let (Some(a), Some(b)) = (dest, src) {
                 writer.cmp(ctx, *arch, a, b)?;
@@ -196,6 +244,21 @@ where
                 writer.not(ctx, *arch, op)?;
             }
         }
+        Mnemonic::Neg => {
+            if let Some(op) = dest {
+                writer.neg(ctx, *arch, op)?;
+            }
+        }
+        Mnemonic::Inc => {
+            if let Some(op) = dest {
+                writer.inc(ctx, *arch, op)?;
+            }
+        }
+        Mnemonic::Dec => {
+            if let Some(op) = dest {
+                writer.dec(ctx, *arch, op)?;
+            }
+        }
         Mnemonic::Lea => {
             if let (Some(d), Some(s)) = (dest, src) {
                 writer.lea(ctx, *arch, d, s)?;
@@ -227,6 +290,12 @@ where
         Mnemonic::Popf => {
             writer.popf(ctx, *arch)?;
         }
+        Mnemonic::Lahf => {
+            writer.lahf(ctx, *arch)?;
+        }
+        Mnemonic::Sahf => {
+            writer.sahf(ctx, *arch)?;
+        }
         Mnemonic::Call => {
             if instr.op0_kind() == iced_x86::OpKind::NearBranch16
                 || instr.op0_kind() == iced_x86::OpKind::NearBranch32
@@ -263,14 +332,28 @@ where
         Mnemonic::Hlt => {
             writer.hlt(ctx, *arch)?;
         }
+        Mnemonic::Syscall => {
+            writer.syscall(ctx, *arch)?;
+        }
+        Mnemonic::Endbr64 => {
+            writer.endbr64(ctx, *arch)?;
+        }
+        Mnemonic::Ud2 => {
+            writer.ud2(ctx, *arch)?;
+        }
+        Mnemonic::Int3 => {
+            writer.int3(ctx, *arch)?;
+        }
         Mnemonic::Xchg => {
             if let (Some(d), Some(s)) = (dest, src) {
                 writer.xchg(ctx, *arch, d, s)?;
             }
         }
         Mnemonic::Imul => {
-            if let (Some(a), Some(b)) = (dest, src) {
-                writer.mul(ctx, *arch, a, b)?;
+            if let (Some(a), Some(b), 3) = (dest, src, instr.op_count()) {
+                writer.imul3(ctx, *arch, a, b, instr.immediate(2) as i32)?;
+            } else if let (Some(a), Some(b)) = (dest, src) {
+                writer.imul2(ctx, *arch, a, b)?;
             }
         }
         Mnemonic::Idiv => {
@@ -303,11 +386,170 @@ where
                 writer.fdiv(ctx, *arch, d, s)?;
             }
         }
+        Mnemonic::Ucomisd => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.fcmp(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Minsd => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.fmin(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Maxsd => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.fmax(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Sqrtsd => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.fsqrt(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Cvtsi2sd => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.cvtsi2sd(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Cvttsd2si => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.cvttsd2si(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Vfmadd231sd => {
+            if let (Some(d), Some(a), Some(b)) = (dest, src, val) {
+                writer.vfmadd231sd(ctx, *arch, d, a, b)?;
+            }
+        }
+        Mnemonic::Vfmsub231sd => {
+            if let (Some(d), Some(a), Some(b)) = (dest, src, val) {
+                writer.vfmsub231sd(ctx, *arch, d, a, b)?;
+            }
+        }
+        Mnemonic::Vfnmadd231sd => {
+            if let (Some(d), Some(a), Some(b)) = (dest, src, val) {
+                writer.vfnmadd231sd(ctx, *arch, d, a, b)?;
+            }
+        }
+        Mnemonic::Vfnmsub231sd => {
+            if let (Some(d), Some(a), Some(b)) = (dest, src, val) {
+                writer.vfnmsub231sd(ctx, *arch, d, a, b)?;
+            }
+        }
+        Mnemonic::Movsd if instr.code() == iced_x86::Code::Movsd_m32_m32 => {
+            writer.movs(ctx, *arch, MemorySize::_32, rep_prefix(instr, false))?;
+        }
         Mnemonic::Movsd => {
             if let (Some(d), Some(s)) = (dest, src) {
                 writer.fmov(ctx, *arch, d, s)?;
             }
         }
+        Mnemonic::Addss => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.addss(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Subss => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.subss(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Mulss => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.mulss(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Divss => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.divss(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Ucomiss => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.ucomiss(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Minss => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.minss(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Maxss => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.maxss(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Sqrtss => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.sqrtss(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Cvtsi2ss => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.cvtsi2ss(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Cvttss2si => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.cvttss2si(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Cvtss2sd => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.cvtss2sd(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Cvtsd2ss => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.cvtsd2ss(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Movss => {
+            if let (Some(d), Some(s)) = (dest, src) {
+                writer.movss(ctx, *arch, d, s)?;
+            }
+        }
+        Mnemonic::Ldmxcsr => {
+            if let Some(m) = dest {
+                writer.ldmxcsr(ctx, *arch, m)?;
+            }
+        }
+        Mnemonic::Stmxcsr => {
+            if let Some(m) = dest {
+                writer.stmxcsr(ctx, *arch, m)?;
+            }
+        }
+        Mnemonic::Rdfsbase | Mnemonic::Rdgsbase | Mnemonic::Wrfsbase | Mnemonic::Wrgsbase => {
+            use crate::out::arg::Segment;
+            let seg = match instr.mnemonic() {
+                Mnemonic::Rdfsbase | Mnemonic::Wrfsbase => Segment::Fs,
+                _ => Segment::Gs,
+            };
+            if let Some(r) = dest {
+                match instr.mnemonic() {
+                    Mnemonic::Rdfsbase | Mnemonic::Rdgsbase => writer.rdsegbase(ctx, *arch, seg, r)?,
+                    _ => writer.wrsegbase(ctx, *arch, seg, r)?,
+                }
+            }
+        }
+        // String instructions; operands are implicit in RSI/RDI/RAX
+        Mnemonic::Movsb => writer.movs(ctx, *arch, MemorySize::_8, rep_prefix(instr, false))?,
+        Mnemonic::Movsw => writer.movs(ctx, *arch, MemorySize::_16, rep_prefix(instr, false))?,
+        Mnemonic::Movsq => writer.movs(ctx, *arch, MemorySize::_64, rep_prefix(instr, false))?,
+        Mnemonic::Stosb => writer.stos(ctx, *arch, MemorySize::_8, rep_prefix(instr, false))?,
+        Mnemonic::Stosw => writer.stos(ctx, *arch, MemorySize::_16, rep_prefix(instr, false))?,
+        Mnemonic::Stosd => writer.stos(ctx, *arch, MemorySize::_32, rep_prefix(instr, false))?,
+        Mnemonic::Stosq => writer.stos(ctx, *arch, MemorySize::_64, rep_prefix(instr, false))?,
+        Mnemonic::Cmpsb => writer.cmps(ctx, *arch, MemorySize::_8, rep_prefix(instr, true))?,
+        Mnemonic::Cmpsw => writer.cmps(ctx, *arch, MemorySize::_16, rep_prefix(instr, true))?,
+        Mnemonic::Cmpsd if instr.code() == iced_x86::Code::Cmpsd_m32_m32 => {
+            writer.cmps(ctx, *arch, MemorySize::_32, rep_prefix(instr, true))?;
+        }
+        Mnemonic::Cmpsq => writer.cmps(ctx, *arch, MemorySize::_64, rep_prefix(instr, true))?,
+        Mnemonic::Scasb => writer.scas(ctx, *arch, MemorySize::_8, rep_prefix(instr, true))?,
+        Mnemonic::Scasw => writer.scas(ctx, *arch, MemorySize::_16, rep_prefix(instr, true))?,
+        Mnemonic::Scasd => writer.scas(ctx, *arch, MemorySize::_32, rep_prefix(instr, true))?,
+        Mnemonic::Scasq => writer.scas(ctx, *arch, MemorySize::_64, rep_prefix(instr, true))?,
         // Conditional moves
         Mnemonic::Cmovo => {
             if let (Some(op), Some(v)) = (dest, src) {
@@ -609,7 +851,30 @@ where
 use crate::out::arg::{ArgKind, MemArgKind};
 use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
 
+/// Panics when `$cond` is false and the `debug_checks` feature is enabled.
+///
+/// Without the feature the condition is behind a constant `false` and is
+/// compiled out. Used from `#[track_caller]` helpers, so the panic location
+/// is the `WriterCore` method that received the bad operand.
+macro_rules! debug_check {
+    ($cond:expr, $($msg:tt)*) => {
+        if cfg!(feature = "debug_checks") && !$cond {
+            panic!("debug_checks: {}", format_args!($($msg)*));
+        }
+    };
+}
+
+fn rep_to_iced(rep: crate::RepPrefix) -> iced_x86::RepPrefixKind {
+    match rep {
+        crate::RepPrefix::None => iced_x86::RepPrefixKind::None,
+        crate::RepPrefix::Rep | crate::RepPrefix::Repe => iced_x86::RepPrefixKind::Repe,
+        crate::RepPrefix::Repne => iced_x86::RepPrefixKind::Repne,
+    }
+}
+
+#[track_caller]
 fn reg_to_iced(r: Reg) -> iced_x86::Register {
+    debug_check!(r.0 < 16 || r.0 == 255, "register index {} is out of range (0..=15 or CTX)", r.0);
     // Blitz ModRM order: 0=RAX, 1=RCX, 2=RDX, 3=RBX, 4=RSP, 5=RBP, 6=RSI, 7=RDI, 8=R8..15=R15.
     // iced-x86 64-bit GPR discriminants: RAX=53, RCX=54, RDX=55, RBX=56, RSP=57, RBP=58, …
     // Reg::CTX = Reg(255) — the context register, which maps to R15 in x86-64.
@@ -617,39 +882,92 @@ fn reg_to_iced(r: Reg) -> iced_x86::Register {
     iced_x86::Register::try_from(idx + 53).unwrap_or(iced_x86::Register::RAX)
 }
 
+/// Maps an XMM register, numbered from 0 like the GPRs, to its iced register.
+#[track_caller]
+fn xmm_to_iced(r: Reg) -> iced_x86::Register {
+    debug_check!(r.0 < 32, "XMM register index {} is out of range (0..=31)", r.0);
+    iced_x86::Register::try_from(iced_x86::Register::XMM0 as usize + r.0 as usize)
+        .unwrap_or(iced_x86::Register::XMM0)
+}
+
+/// Maps a vector register to its iced XMM, YMM or ZMM register, chosen by
+/// the operand size as in [`evex::vector_size`](crate::out::evex::vector_size).
+#[track_caller]
+fn vec_to_iced(r: Reg, size: MemorySize) -> iced_x86::Register {
+    debug_check!(r.0 < 32, "vector register index {} is out of range (0..=31)", r.0);
+    let base = match size {
+        MemorySize::_512 => iced_x86::Register::ZMM0,
+        MemorySize::_256 => iced_x86::Register::YMM0,
+        _ => iced_x86::Register::XMM0,
+    };
+    iced_x86::Register::try_from(base as usize + r.0 as usize).unwrap_or(base)
+}
+
+/// Maps an opmask register to its iced register.
+#[track_caller]
+fn mask_to_iced(k: crate::MaskReg) -> iced_x86::Register {
+    debug_check!(k.0 < 8, "opmask register index {} is out of range (0..=7)", k.0);
+    iced_x86::Register::try_from(iced_x86::Register::K0 as usize + (k.0 & 7) as usize).unwrap_or(iced_x86::Register::K0)
+}
+
+#[track_caller]
 fn reg_to_iced_sized(r: Reg, size: MemorySize) -> iced_x86::Register {
     // For 8-bit sub-registers the iced discriminants differ from 64-bit:
     //   AL=1, CL=2, DL=3, BL=4 (Reg 0-3: offset +1)
     //   SPL=9, BPL=10, SIL=11, DIL=12 (Reg 4-7: offset +5)
+    //   R8L=13 .. R15L=20 (Reg 8-15: offset +5)
     // For all other sizes (16/32/64) reg_to_iced gives the 64-bit form, which
     // is correct for the arithmetic ops we use them in.
     if size == MemorySize::_8 {
         let idx = r.0 as usize;
-        let disc = if idx < 4 { idx + 1 } else if idx < 8 { idx + 5 } else { return reg_to_iced(r) };
+        let disc = if idx < 4 { idx + 1 } else if idx < 16 { idx + 5 } else { return reg_to_iced(r) };
         iced_x86::Register::try_from(disc).unwrap_or(reg_to_iced(r))
     } else {
         reg_to_iced(r)
     }
 }
 
+#[track_caller]
 fn mem_kind_to_iced(mk: &MemArgKind<ArgKind>) -> IcedOp {
     match mk {
         MemArgKind::NoMem(ArgKind::Reg { reg, size }) => {
             IcedOp::Reg(reg_to_iced_sized(*reg, *size), *size)
         }
         MemArgKind::NoMem(ArgKind::Lit(v)) => IcedOp::Imm(*v),
-        MemArgKind::Mem { base, offset, disp, size, .. } => {
+        MemArgKind::NoMem(ArgKind::HighByte(reg)) => {
+            debug_check!(reg.0 < 4, "high-byte register index {} is out of range (0..=3)", reg.0);
+            let disc = iced_x86::Register::AH as usize + (reg.0 & 3) as usize;
+            IcedOp::Reg(iced_x86::Register::try_from(disc).unwrap_or(iced_x86::Register::AH), MemorySize::_8)
+        }
+        MemArgKind::NoMem(ArgKind::Rip) => panic!("iced: rip is only addressable as a memory base"),
+        MemArgKind::NoMem(ArgKind::NoBase) => panic!("iced: a missing base is not an operand"),
+        MemArgKind::NoMem(ArgKind::Sym { name, .. }) | MemArgKind::Mem { base: ArgKind::Sym { name, .. }, .. } => {
+            panic!("iced: symbol `{name}` is only supported as a call/jmp target or a lea/mov source")
+        }
+        MemArgKind::Mem { base, offset, disp, size, segment, .. } => {
+            debug_check!(!(matches!(base, ArgKind::Rip) && offset.is_some()), "a RIP-relative address cannot take an index");
             let base_reg = match base {
                 ArgKind::Reg { reg, .. } => reg_to_iced(*reg),
-                ArgKind::Lit(_) => iced_x86::Register::None,
+                ArgKind::Rip => iced_x86::Register::RIP,
+                _ => iced_x86::Register::None,
             };
             let (idx_reg, scale) = match offset {
                 Some((ArgKind::Reg { reg, .. }, s)) => (reg_to_iced(*reg), *s),
-                _ => (iced_x86::Register::None, 1),
+                other => {
+                    debug_check!(other.is_none(), "memory index must be a register, got {other:?}");
+                    (iced_x86::Register::None, 1)
+                }
+            };
+            debug_check!(matches!(scale, 1 | 2 | 4 | 8), "index scale {scale} is not 1, 2, 4 or 8");
+            let mut mem = iced_x86::MemoryOperand::with_base_index_scale_displ_size(
+                base_reg, idx_reg, scale, *disp as i64, 1,
+            );
+            mem.segment_prefix = match segment {
+                crate::out::arg::Segment::None => iced_x86::Register::None,
+                crate::out::arg::Segment::Gs => iced_x86::Register::GS,
+                crate::out::arg::Segment::Fs => iced_x86::Register::FS,
             };
-            IcedOp::Mem(iced_x86::MemoryOperand::with_base_index_scale_displ_size(
-                base_reg, idx_reg, scale, *disp as i32 as i64, 1,
-            ), *size)
+            IcedOp::Mem(mem, *size)
         }
     }
 }
@@ -681,12 +999,53 @@ struct IcedFixup<L> {
     label: L,
 }
 
+/// Relocation types recorded by [`IcedWriter`], named after their ELF counterparts.
+#[cfg(feature = "iced")]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[non_exhaustive]
+pub enum RelocKind {
+    /// `R_X86_64_64`: 64-bit absolute address (`movabs`).
+    Abs64,
+    /// `R_X86_64_32`: 32-bit absolute address in an immediate.
+    Abs32,
+    /// `R_X86_64_PC32`: RIP-relative displacement of a memory operand.
+    Pc32,
+    /// `R_X86_64_PLT32`: rel32 target of a direct `call` or `jmp`.
+    Plt32,
+}
+
+/// A symbol reference left for the linker, recorded by [`IcedWriter`].
+#[cfg(feature = "iced")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Relocation {
+    /// Byte offset of the field to patch within the assembled output.
+    pub offset: usize,
+    pub kind: RelocKind,
+    pub symbol: &'static str,
+    /// ELF-style addend; PC-relative kinds already account for the field's
+    /// distance to the end of the instruction.
+    pub addend: i64,
+}
+
+/// A label problem found by [`IcedWriter::label_violations`].
+#[cfg(feature = "iced")]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum LabelViolation<L> {
+    /// A branch or address reference targets a label that was never set.
+    Undefined(L),
+    /// The label was set more than once; the last definition wins.
+    Duplicate(L),
+}
+
 #[cfg(feature = "iced")]
 pub struct IcedWriter<L = NoLabel> {
     buf: alloc::vec::Vec<u8>,
     ip: u64,
     labels: alloc::collections::BTreeMap<L, usize>,
     pending_fixups: alloc::vec::Vec<IcedFixup<L>>,
+    /// Labels passed to `set_label` after they were already defined.
+    duplicate_labels: alloc::vec::Vec<L>,
+    relocations: alloc::vec::Vec<Relocation>,
 }
 
 #[cfg(feature = "iced")]
@@ -699,9 +1058,19 @@ impl<L> IcedWriter<L> {
             ip: base_ip,
             labels: alloc::collections::BTreeMap::new(),
             pending_fixups: alloc::vec::Vec::new(),
+            duplicate_labels: alloc::vec::Vec::new(),
+            relocations: alloc::vec::Vec::new(),
         }
     }
 
+    /// Symbol references in the assembled bytes, in emission order.
+    ///
+    /// Symbol operands are encoded with zeroed fields; each needs its
+    /// relocation applied before the code can run.
+    pub fn relocations(&self) -> &[Relocation] {
+        &self.relocations
+    }
+
     /// Return the assembled bytes, discarding any recorded label offsets.
     pub fn into_bytes(self) -> alloc::vec::Vec<u8> {
         self.buf
@@ -717,20 +1086,107 @@ impl<L> IcedWriter<L> {
         self.buf.len()
     }
 
-    fn encode_instr(&mut self, instr: iced_x86::Instruction) -> Result<(), core::fmt::Error> {
+    /// Encodes `instr`.
+    ///
+    /// A RIP-relative memory operand carries its displacement from the end
+    /// of the instruction, as in `[rip + disp]`, rather than iced's absolute
+    /// target; it is written into the encoding as is.
+    fn encode_instr(&mut self, mut instr: iced_x86::Instruction) -> Result<(), core::fmt::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(mnemonic = ?instr.mnemonic(), offset = self.buf.len(), "encoding instruction");
+        // Encode against an in-range target, then write the real displacement.
+        let rip_disp = instr.is_ip_rel_memory_operand().then(|| {
+            let disp = instr.memory_displacement64() as i32;
+            instr.set_memory_displacement64(self.ip);
+            disp
+        });
+        let mut enc = iced_x86::Encoder::new(64);
+        let n = enc.encode(&instr, self.ip)
+            .unwrap_or_else(|e| panic!("iced encoding error (backend bug): {e}"));
+        let field = enc.get_constant_offsets().displacement_offset();
+        let mut bytes = enc.take_buffer();
+        if let Some(disp) = rip_disp {
+            bytes[field..field + 4].copy_from_slice(&disp.to_le_bytes());
+        }
+        self.ip += n as u64;
+        self.buf.extend_from_slice(&bytes);
+        Ok(())
+    }
+
+    /// Encodes `instr`, zeroing its symbol field and recording a relocation.
+    ///
+    /// The field is the RIP-relative displacement if there is one, else the
+    /// immediate, else the branch target.
+    fn encode_reloc(&mut self, instr: iced_x86::Instruction, symbol: &'static str, addend: i64) -> Result<(), core::fmt::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(mnemonic = ?instr.mnemonic(), offset = self.buf.len(), symbol, "encoding instruction");
         let mut enc = iced_x86::Encoder::new(64);
         let n = enc.encode(&instr, self.ip)
             .unwrap_or_else(|e| panic!("iced encoding error (backend bug): {e}"));
-        let bytes = enc.take_buffer();
+        let co = enc.get_constant_offsets();
+        let (field, width, kind) = if co.has_displacement() {
+            (co.displacement_offset(), co.displacement_size(), RelocKind::Pc32)
+        } else if co.has_immediate() {
+            let kind = if co.immediate_size() == 8 { RelocKind::Abs64 } else { RelocKind::Abs32 };
+            (co.immediate_offset(), co.immediate_size(), kind)
+        } else {
+            (co.branch_offset(), co.branch_size(), RelocKind::Plt32)
+        };
+        let mut bytes = enc.take_buffer();
+        bytes[field..field + width].fill(0);
+        let addend = match kind {
+            RelocKind::Pc32 | RelocKind::Plt32 => addend - (n - field) as i64,
+            _                                  => addend,
+        };
+        self.relocations.push(Relocation { offset: self.buf.len() + field, kind, symbol, addend });
         self.ip += n as u64;
         self.buf.extend_from_slice(&bytes);
         Ok(())
     }
 
+    /// Like [`mem_kind_to_iced`], but lowers symbol operands to placeholders.
+    ///
+    /// A bare symbol becomes a zero immediate and `[sym + disp]` a RIP-relative
+    /// memory operand; the symbol and its addend are returned for
+    /// [`encode_reloc`](Self::encode_reloc).
+    #[track_caller]
+    fn sym_op(&self, mk: &MemArgKind<ArgKind>) -> (IcedOp, Option<(&'static str, i64)>) {
+        match mk {
+            MemArgKind::NoMem(ArgKind::Sym { name, addend }) => (IcedOp::Imm(0), Some((*name, *addend))),
+            MemArgKind::Mem { base: ArgKind::Sym { name, addend }, offset: None, disp, size, .. } => {
+                let mem = iced_x86::MemoryOperand::with_base_displ(iced_x86::Register::RIP, self.ip as i64);
+                (IcedOp::Mem(mem, *size), Some((*name, *addend + *disp as i64)))
+            }
+            _ => (mem_kind_to_iced(mk), None),
+        }
+    }
+
+    /// Encodes `instr`, with a relocation if one of its operands was a symbol.
+    fn encode_sym(&mut self, instr: iced_x86::Instruction, sym: Option<(&'static str, i64)>) -> Result<(), core::fmt::Error> {
+        match sym {
+            Some((symbol, addend)) => self.encode_reloc(instr, symbol, addend),
+            None                   => self.encode_instr(instr),
+        }
+    }
+
+    /// Encodes the prefetch or cache-line `code` of the byte at `mem`, which
+    /// may be `[sym + disp]`.
+    #[track_caller]
+    fn encode_line_op(&mut self, code: iced_x86::Code, mem: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), core::fmt::Error> {
+        let (m, sym) = self.sym_op(&mem.concrete_mem_kind());
+        debug_check!(matches!(m, IcedOp::Mem(..)), "{code:?} takes a memory operand");
+        let instr = iced_x86::Instruction::with1(code, Self::op_to_mem(&m)).unwrap_or_else(|e| panic!("iced: {e}"));
+        self.encode_sym(instr, sym)
+    }
+
+    #[track_caller]
     fn op_to_reg(op: &IcedOp) -> iced_x86::Register {
         match op {
             IcedOp::Reg(r, _) => *r,
-            _ => iced_x86::Register::RAX,
+            _ => {
+                debug_check!(false, "expected a register operand, got an immediate or memory operand");
+                iced_x86::Register::RAX
+            }
         }
     }
 
@@ -773,6 +1229,178 @@ impl<L> IcedWriter<L> {
             _ => r, // _64 or unknown: unchanged
         }
     }
+
+    /// Encode a `reg, r/m` instruction whose form is picked by the destination size.
+    ///
+    /// `codes` holds the 16-, 32- and 64-bit forms, in that order.
+    fn encode_r_rm(&mut self, codes: [iced_x86::Code; 3], dest: &IcedOp, src: &IcedOp) -> Result<(), core::fmt::Error> {
+        let sz = Self::size_of(dest);
+        let dr = Self::gpr_for_size(Self::op_to_reg(dest), sz);
+        let code = match sz {
+            MemorySize::_16 => codes[0],
+            MemorySize::_32 => codes[1],
+            _               => codes[2],
+        };
+        let instr = match src {
+            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(code, dr, Self::gpr_for_size(*sr, sz)).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(code, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    /// Encode an `r/m64, imm` ALU instruction, using the sign-extended imm8
+    /// form when `imm` fits and imm32 otherwise.
+    ///
+    /// `codes` holds the imm8 and imm32 forms, in that order.
+    fn encode_alu_imm(&mut self, cfg: crate::X64Arch, codes: [iced_x86::Code; 2], a: &IcedOp, imm: i32) -> Result<(), core::fmt::Error> {
+        let code = if cfg.imm_encoding != crate::ImmEncoding::Imm32 && i8::try_from(imm).is_ok() { codes[0] } else { codes[1] };
+        let instr = match a {
+            IcedOp::Reg(r, _) => iced_x86::Instruction::with2(code, *r, imm).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(m, _) => iced_x86::Instruction::with2(code, m.clone(), imm).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Imm(_) => {
+                debug_check!(false, "ALU immediate destination must be a register or memory operand");
+                return Ok(());
+            }
+        };
+        self.encode_instr(instr)
+    }
+
+    /// Encode `hi:lo = a * b` through the one-operand MUL/IMUL form, which
+    /// multiplies RAX by its operand and leaves the product in RDX:RAX.
+    /// Moves into and out of RAX/RDX are skipped when already in place.
+    /// Encodes a scalar-double FMA3 `code` with XMM `dest` and `a`, and `b`
+    /// in an XMM register or memory.
+    fn encode_fma(&mut self, code: iced_x86::Code, dest: &(dyn crate::out::arg::MemArg + '_), a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), core::fmt::Error> {
+        let xmm = |op: &(dyn crate::out::arg::MemArg + '_)| match op.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => xmm_to_iced(reg),
+            _ => panic!("iced: FMA3 destination and first source must be XMM registers"),
+        };
+        let (dr, ar) = (xmm(dest), xmm(a));
+        let instr = match b.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => iced_x86::Instruction::with3(code, dr, ar, xmm_to_iced(reg)),
+            mk => match mem_kind_to_iced(&mk) {
+                IcedOp::Mem(m, _) => iced_x86::Instruction::with3(code, dr, ar, m),
+                _ => return Ok(()),
+            },
+        };
+        self.encode_instr(instr.unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    /// Encodes a scalar SSE instruction whose destination is an XMM register
+    /// and whose source is an XMM register or memory.
+    fn encode_sse(&mut self, code: iced_x86::Code, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), core::fmt::Error> {
+        let dr = match dest.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => xmm_to_iced(reg),
+            _ => panic!("iced: SSE destination must be an XMM register"),
+        };
+        let instr = match src.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => iced_x86::Instruction::with2(code, dr, xmm_to_iced(reg)),
+            mk => match mem_kind_to_iced(&mk) {
+                IcedOp::Mem(m, _) => iced_x86::Instruction::with2(code, dr, m),
+                _ => return Ok(()),
+            },
+        };
+        self.encode_instr(instr.unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    /// Returns the vector register an EVEX operand names, sized by `size`.
+    fn evex_reg(op: &(dyn crate::out::arg::MemArg + '_), size: MemorySize) -> iced_x86::Register {
+        match op.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => vec_to_iced(reg, size),
+            _ => panic!("iced: EVEX destination and first source must be vector registers"),
+        }
+    }
+
+    /// Applies the write mask and broadcast decorations to an EVEX instruction.
+    fn evex_decorate(instr: &mut iced_x86::Instruction, mask: crate::out::evex::OpMask, broadcast: bool) {
+        if mask.is_masked() {
+            instr.set_op_mask(mask_to_iced(mask.reg));
+        }
+        instr.set_zeroing_masking(mask.zeroing);
+        instr.set_is_broadcast(broadcast);
+    }
+
+    /// Encodes a packed EVEX instruction `dest{mask}, a, b`. `codes` are its
+    /// 128-, 256- and 512-bit forms; the length of `dest` picks one.
+    #[allow(clippy::too_many_arguments)]
+    fn encode_evex(&mut self, codes: [iced_x86::Code; 3], dest: &(dyn crate::out::arg::MemArg + '_), mask: crate::out::evex::OpMask, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_), broadcast: bool) -> Result<(), core::fmt::Error> {
+        let size = crate::out::evex::vector_size(dest);
+        let code = codes[match size { MemorySize::_512 => 2, MemorySize::_256 => 1, _ => 0 }];
+        let (dr, ar) = (Self::evex_reg(dest, size), Self::evex_reg(a, size));
+        let instr = match b.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => iced_x86::Instruction::with3(code, dr, ar, vec_to_iced(reg, size)),
+            mk => match mem_kind_to_iced(&mk) {
+                IcedOp::Mem(m, _) => iced_x86::Instruction::with3(code, dr, ar, m),
+                _ => return Ok(()),
+            },
+        };
+        let mut instr = instr.unwrap_or_else(|e| panic!("iced: {e}"));
+        Self::evex_decorate(&mut instr, mask, broadcast);
+        self.encode_instr(instr)
+    }
+
+    /// Builds the sign-bit mask in `scratch` and applies it to `dest`: `shift`
+    /// of all-ones by 1 or 63, then `op` (ANDPD or XORPD).
+    fn encode_sign_mask(&mut self, shift: iced_x86::Code, count: i32, op: iced_x86::Code, dest: &(dyn crate::out::arg::MemArg + '_), scratch: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), core::fmt::Error> {
+        use iced_x86::{Code, Instruction};
+        let xmm = |op: &(dyn crate::out::arg::MemArg + '_)| match op.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => xmm_to_iced(reg),
+            _ => panic!("iced: sign-mask operands must be XMM registers"),
+        };
+        let (dr, sr) = (xmm(dest), xmm(scratch));
+        let ins = |r: Result<Instruction, iced_x86::IcedError>| r.unwrap_or_else(|e| panic!("iced: {e}"));
+        self.encode_instr(ins(Instruction::with2(Code::Pcmpeqd_xmm_xmmm128, sr, sr)))?;
+        self.encode_instr(ins(Instruction::with2(shift, sr, count)))?;
+        self.encode_instr(ins(Instruction::with2(op, dr, sr)))
+    }
+
+    fn encode_wide_mul(&mut self, code: iced_x86::Code, hi: &IcedOp, lo: &IcedOp, a: &IcedOp, b: &IcedOp) -> Result<(), core::fmt::Error> {
+        use iced_x86::{Code, Instruction, Register};
+        let ins = |r: Result<Instruction, iced_x86::IcedError>| r.unwrap_or_else(|e| panic!("iced: {e}"));
+        match a {
+            IcedOp::Reg(Register::RAX, _) => {}
+            IcedOp::Reg(r, _) => self.encode_instr(ins(Instruction::with2(Code::Mov_r64_rm64, Register::RAX, *r)))?,
+            IcedOp::Mem(m, _) => self.encode_instr(ins(Instruction::with2(Code::Mov_r64_rm64, Register::RAX, m.clone())))?,
+            IcedOp::Imm(v) => self.encode_instr(ins(Instruction::with2(Code::Mov_r64_imm64, Register::RAX, *v)))?,
+        }
+        match b {
+            IcedOp::Reg(r, _) => self.encode_instr(ins(Instruction::with1(code, *r)))?,
+            IcedOp::Mem(m, _) => self.encode_instr(ins(Instruction::with1(code, m.clone())))?,
+            _ => return Ok(()),
+        }
+        for (dest, src) in [(lo, Register::RAX), (hi, Register::RDX)] {
+            match dest {
+                IcedOp::Reg(r, _) if *r == src => {}
+                IcedOp::Reg(r, _) => self.encode_instr(ins(Instruction::with2(Code::Mov_r64_rm64, *r, src)))?,
+                IcedOp::Mem(m, _) => self.encode_instr(ins(Instruction::with2(Code::Mov_rm64_r64, m.clone(), src)))?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "iced")]
+impl<L: Ord + Clone> IcedWriter<L> {
+    /// List every label that is referenced but never defined, or defined more
+    /// than once. An empty list means the output is safe to finalize.
+    ///
+    /// Undefined labels are those with fixups still pending; each is reported
+    /// once regardless of how many instructions reference it.
+    pub fn label_violations(&self) -> alloc::vec::Vec<LabelViolation<L>> {
+        let undefined: alloc::collections::BTreeSet<&L> = self.pending_fixups.iter().map(|fix| &fix.label).collect();
+        undefined.into_iter().cloned().map(LabelViolation::Undefined)
+            .chain(self.duplicate_labels.iter().cloned().map(LabelViolation::Duplicate))
+            .collect()
+    }
+
+    /// Like [`into_parts`](Self::into_parts), but fails with the list of
+    /// [`label_violations`](Self::label_violations) if there are any.
+    pub fn try_into_parts(self) -> Result<(alloc::vec::Vec<u8>, alloc::collections::BTreeMap<L, usize>), alloc::vec::Vec<LabelViolation<L>>> {
+        let violations = self.label_violations();
+        if violations.is_empty() { Ok(self.into_parts()) } else { Err(violations) }
+    }
 }
 
 #[cfg(feature = "iced")]
@@ -795,9 +1423,29 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         self.encode_instr(iced_x86::Instruction::with(iced_x86::Code::Popfq))
     }
 
-    fn mov(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
-        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
-        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+    fn lahf(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        self.encode_instr(iced_x86::Instruction::with(iced_x86::Code::Lahf))
+    }
+
+    fn sahf(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        self.encode_instr(iced_x86::Instruction::with(iced_x86::Code::Sahf))
+    }
+
+    fn mov(&mut self, ctx: &mut Context, cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        if let (true, MemArgKind::NoMem(ArgKind::Sym { name, addend })) = (cfg.pic, src.concrete_mem_kind()) {
+            // Position-independent: LEA reg, [RIP + sym]
+            let mem = MemArgKind::Mem {
+                base: ArgKind::Sym { name, addend },
+                offset: None,
+                disp: 0,
+                size: MemorySize::_64,
+                reg_class: crate::RegisterClass::Gpr,
+                segment: crate::out::arg::Segment::None,
+            };
+            return self.lea(ctx, cfg, dest, &mem);
+        }
+        let (d, dsym) = self.sym_op(&dest.concrete_mem_kind());
+        let (s, ssym) = self.sym_op(&src.concrete_mem_kind());
         let sz = Self::size_of(&d);
         let instr = match (&d, &s) {
             (IcedOp::Reg(dr, _), IcedOp::Reg(sr, _)) => {
@@ -811,6 +1459,10 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
                 };
                 iced_x86::Instruction::with2(code, dr, sr).unwrap_or_else(|e| panic!("iced: {e}"))
             }
+            // Symbols keep the 8-byte immediate so the relocation fits.
+            (IcedOp::Reg(dr, _), IcedOp::Imm(v)) if matches!(sz, MemorySize::_64) && cfg.imm_encoding == crate::ImmEncoding::Imm32 && ssym.is_none() => {
+                iced_x86::Instruction::with2(iced_x86::Code::Mov_rm64_imm32, *dr, *v as i32).unwrap_or_else(|e| panic!("iced: {e}"))
+            }
             (IcedOp::Reg(dr, _), IcedOp::Imm(v)) => {
                 let dr = Self::gpr_for_size(*dr, sz);
                 let code = match sz {
@@ -852,13 +1504,18 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
             }
             _ => return Ok(()),
         };
-        self.encode_instr(instr)
+        self.encode_sym(instr, dsym.or(ssym))
     }
 
-    fn mov64(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, r: &(dyn crate::out::arg::MemArg + '_), val: u64) -> Result<(), Self::Error> {
+    fn mov64(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, r: &(dyn crate::out::arg::MemArg + '_), val: u64) -> Result<(), Self::Error> {
         let d = mem_kind_to_iced(&r.concrete_mem_kind());
         let reg = Self::op_to_reg(&d);
-        self.encode_instr(iced_x86::Instruction::with2(iced_x86::Code::Mov_r64_imm64, reg, val).unwrap_or_else(|e| panic!("iced: {e}")))
+        let instr = if cfg.imm_encoding == crate::ImmEncoding::Imm32 {
+            iced_x86::Instruction::with2(iced_x86::Code::Mov_rm64_imm32, reg, val as i32)
+        } else {
+            iced_x86::Instruction::with2(iced_x86::Code::Mov_r64_imm64, reg, val)
+        };
+        self.encode_instr(instr.unwrap_or_else(|e| panic!("iced: {e}")))
     }
 
     fn xchg(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
@@ -897,23 +1554,25 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
     }
 
     fn call(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, op: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
-        let o = mem_kind_to_iced(&op.concrete_mem_kind());
+        let (o, sym) = self.sym_op(&op.concrete_mem_kind());
         let instr = match &o {
             IcedOp::Reg(r, _) => iced_x86::Instruction::with1(iced_x86::Code::Call_rm64, *r).unwrap_or_else(|e| panic!("iced: {e}")),
             IcedOp::Mem(m, _) => iced_x86::Instruction::with1(iced_x86::Code::Call_rm64, m.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Imm(_) if sym.is_some() => iced_x86::Instruction::with_branch(iced_x86::Code::Call_rel32_64, self.ip).unwrap_or_else(|e| panic!("iced: {e}")),
             _ => return Ok(()),
         };
-        self.encode_instr(instr)
+        self.encode_sym(instr, sym)
     }
 
     fn jmp(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, op: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
-        let o = mem_kind_to_iced(&op.concrete_mem_kind());
+        let (o, sym) = self.sym_op(&op.concrete_mem_kind());
         let instr = match &o {
             IcedOp::Reg(r, _) => iced_x86::Instruction::with1(iced_x86::Code::Jmp_rm64, *r).unwrap_or_else(|e| panic!("iced: {e}")),
             IcedOp::Mem(m, _) => iced_x86::Instruction::with1(iced_x86::Code::Jmp_rm64, m.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Imm(_) if sym.is_some() => iced_x86::Instruction::with_branch(iced_x86::Code::Jmp_rel32_64, self.ip).unwrap_or_else(|e| panic!("iced: {e}")),
             _ => return Ok(()),
         };
-        self.encode_instr(instr)
+        self.encode_sym(instr, sym)
     }
 
     fn add(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
@@ -930,6 +1589,39 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         self.encode_instr(instr)
     }
 
+    fn adc(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&a.concrete_mem_kind());
+        let s = mem_kind_to_iced(&b.concrete_mem_kind());
+        let instr = match (&d, &s) {
+            (IcedOp::Reg(dr, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Adc_r64_rm64, *dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Reg(dr, _), IcedOp::Imm(v)) => iced_x86::Instruction::with2(iced_x86::Code::Adc_rm64_imm32, *dr, *v as i32).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Reg(dr, _), IcedOp::Mem(sm, _)) => iced_x86::Instruction::with2(iced_x86::Code::Adc_r64_rm64, *dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Mem(dm, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Adc_rm64_r64, dm.clone(), *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Mem(dm, _), IcedOp::Imm(v)) => iced_x86::Instruction::with2(iced_x86::Code::Adc_rm64_imm32, dm.clone(), *v as i32).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn sbb(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&a.concrete_mem_kind());
+        let s = mem_kind_to_iced(&b.concrete_mem_kind());
+        let instr = match (&d, &s) {
+            (IcedOp::Reg(dr, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Sbb_r64_rm64, *dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Reg(dr, _), IcedOp::Imm(v)) => iced_x86::Instruction::with2(iced_x86::Code::Sbb_rm64_imm32, *dr, *v as i32).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Reg(dr, _), IcedOp::Mem(sm, _)) => iced_x86::Instruction::with2(iced_x86::Code::Sbb_r64_rm64, *dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Mem(dm, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Sbb_rm64_r64, dm.clone(), *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Mem(dm, _), IcedOp::Imm(v)) => iced_x86::Instruction::with2(iced_x86::Code::Sbb_rm64_imm32, dm.clone(), *v as i32).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn add_imm(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error> {
+        let a = mem_kind_to_iced(&a.concrete_mem_kind());
+        self.encode_alu_imm(cfg, [iced_x86::Code::Add_rm64_imm8, iced_x86::Code::Add_rm64_imm32], &a, imm)
+    }
+
     fn sub(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         let d = mem_kind_to_iced(&a.concrete_mem_kind());
         let s = mem_kind_to_iced(&b.concrete_mem_kind());
@@ -944,6 +1636,11 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         self.encode_instr(instr)
     }
 
+    fn sub_imm(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error> {
+        let a = mem_kind_to_iced(&a.concrete_mem_kind());
+        self.encode_alu_imm(cfg, [iced_x86::Code::Sub_rm64_imm8, iced_x86::Code::Sub_rm64_imm32], &a, imm)
+    }
+
     fn and(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         let d = mem_kind_to_iced(&a.concrete_mem_kind());
         let s = mem_kind_to_iced(&b.concrete_mem_kind());
@@ -958,6 +1655,11 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         self.encode_instr(instr)
     }
 
+    fn and_imm(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error> {
+        let a = mem_kind_to_iced(&a.concrete_mem_kind());
+        self.encode_alu_imm(cfg, [iced_x86::Code::And_rm64_imm8, iced_x86::Code::And_rm64_imm32], &a, imm)
+    }
+
     fn or(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         let d = mem_kind_to_iced(&a.concrete_mem_kind());
         let s = mem_kind_to_iced(&b.concrete_mem_kind());
@@ -972,6 +1674,11 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         self.encode_instr(instr)
     }
 
+    fn or_imm(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error> {
+        let a = mem_kind_to_iced(&a.concrete_mem_kind());
+        self.encode_alu_imm(cfg, [iced_x86::Code::Or_rm64_imm8, iced_x86::Code::Or_rm64_imm32], &a, imm)
+    }
+
     fn eor(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         let d = mem_kind_to_iced(&a.concrete_mem_kind());
         let s = mem_kind_to_iced(&b.concrete_mem_kind());
@@ -986,6 +1693,11 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         self.encode_instr(instr)
     }
 
+    fn eor_imm(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error> {
+        let a = mem_kind_to_iced(&a.concrete_mem_kind());
+        self.encode_alu_imm(cfg, [iced_x86::Code::Xor_rm64_imm8, iced_x86::Code::Xor_rm64_imm32], &a, imm)
+    }
+
     fn shl(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         let d = mem_kind_to_iced(&a.concrete_mem_kind());
         let s = mem_kind_to_iced(&b.concrete_mem_kind());
@@ -1074,26 +1786,116 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         self.encode_instr(instr)
     }
 
-    fn cmp(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+    fn rol(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         let d = mem_kind_to_iced(&a.concrete_mem_kind());
         let s = mem_kind_to_iced(&b.concrete_mem_kind());
-        let instr = match (&d, &s) {
-            (IcedOp::Reg(dr, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Cmp_r64_rm64, *dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
-            (IcedOp::Reg(dr, _), IcedOp::Imm(v)) => iced_x86::Instruction::with2(iced_x86::Code::Cmp_rm64_imm32, *dr, *v as i32).unwrap_or_else(|e| panic!("iced: {e}")),
-            (IcedOp::Reg(dr, _), IcedOp::Mem(sm, _)) => iced_x86::Instruction::with2(iced_x86::Code::Cmp_r64_rm64, *dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
-            (IcedOp::Mem(dm, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Cmp_rm64_r64, dm.clone(), *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+        let sz = Self::size_of(&d);
+        let dr = Self::gpr_for_size(Self::op_to_reg(&d), sz);
+        let instr = match &s {
+            IcedOp::Imm(v) => {
+                let code = match sz {
+                    MemorySize::_8  => iced_x86::Code::Rol_rm8_imm8,
+                    MemorySize::_16 => iced_x86::Code::Rol_rm16_imm8,
+                    MemorySize::_32 => iced_x86::Code::Rol_rm32_imm8,
+                    _               => iced_x86::Code::Rol_rm64_imm8,
+                };
+                iced_x86::Instruction::with2(code, dr, *v as u32).unwrap_or_else(|e| panic!("iced: {e}"))
+            }
+            IcedOp::Reg(sr, _) if *sr == iced_x86::Register::CL => {
+                let code = match sz {
+                    MemorySize::_8  => iced_x86::Code::Rol_rm8_CL,
+                    MemorySize::_16 => iced_x86::Code::Rol_rm16_CL,
+                    MemorySize::_32 => iced_x86::Code::Rol_rm32_CL,
+                    _               => iced_x86::Code::Rol_rm64_CL,
+                };
+                iced_x86::Instruction::with2(code, dr, iced_x86::Register::CL).unwrap_or_else(|e| panic!("iced: {e}"))
+            }
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn ror(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&a.concrete_mem_kind());
+        let s = mem_kind_to_iced(&b.concrete_mem_kind());
+        let sz = Self::size_of(&d);
+        let dr = Self::gpr_for_size(Self::op_to_reg(&d), sz);
+        let instr = match &s {
+            IcedOp::Imm(v) => {
+                let code = match sz {
+                    MemorySize::_8  => iced_x86::Code::Ror_rm8_imm8,
+                    MemorySize::_16 => iced_x86::Code::Ror_rm16_imm8,
+                    MemorySize::_32 => iced_x86::Code::Ror_rm32_imm8,
+                    _               => iced_x86::Code::Ror_rm64_imm8,
+                };
+                iced_x86::Instruction::with2(code, dr, *v as u32).unwrap_or_else(|e| panic!("iced: {e}"))
+            }
+            IcedOp::Reg(sr, _) if *sr == iced_x86::Register::CL => {
+                let code = match sz {
+                    MemorySize::_8  => iced_x86::Code::Ror_rm8_CL,
+                    MemorySize::_16 => iced_x86::Code::Ror_rm16_CL,
+                    MemorySize::_32 => iced_x86::Code::Ror_rm32_CL,
+                    _               => iced_x86::Code::Ror_rm64_CL,
+                };
+                iced_x86::Instruction::with2(code, dr, iced_x86::Register::CL).unwrap_or_else(|e| panic!("iced: {e}"))
+            }
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn cmp(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&a.concrete_mem_kind());
+        let s = mem_kind_to_iced(&b.concrete_mem_kind());
+        let instr = match (&d, &s) {
+            (IcedOp::Reg(dr, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Cmp_r64_rm64, *dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Reg(dr, _), IcedOp::Imm(v)) => iced_x86::Instruction::with2(iced_x86::Code::Cmp_rm64_imm32, *dr, *v as i32).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Reg(dr, _), IcedOp::Mem(sm, _)) => iced_x86::Instruction::with2(iced_x86::Code::Cmp_r64_rm64, *dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Mem(dm, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Cmp_rm64_r64, dm.clone(), *sr).unwrap_or_else(|e| panic!("iced: {e}")),
             (IcedOp::Mem(dm, _), IcedOp::Imm(v)) => iced_x86::Instruction::with2(iced_x86::Code::Cmp_rm64_imm32, dm.clone(), *v as i32).unwrap_or_else(|e| panic!("iced: {e}")),
             _ => return Ok(()),
         };
         self.encode_instr(instr)
     }
 
+    fn cmp_imm(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error> {
+        let a = mem_kind_to_iced(&a.concrete_mem_kind());
+        self.encode_alu_imm(cfg, [iced_x86::Code::Cmp_rm64_imm8, iced_x86::Code::Cmp_rm64_imm32], &a, imm)
+    }
+
     fn cmp0(&mut self, ctx: &mut Context, cfg: crate::X64Arch, op: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         let zero = MemArgKind::NoMem(ArgKind::Lit(0u64));
         let zero_ref: &dyn crate::out::arg::MemArg = &zero;
         self.cmp(ctx, cfg, op, zero_ref)
     }
 
+    fn test(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&a.concrete_mem_kind());
+        let s = mem_kind_to_iced(&b.concrete_mem_kind());
+        let instr = match (&d, &s) {
+            (IcedOp::Reg(dr, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Test_rm64_r64, *dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Reg(dr, _), IcedOp::Imm(v)) => iced_x86::Instruction::with2(iced_x86::Code::Test_rm64_imm32, *dr, *v as i32).unwrap_or_else(|e| panic!("iced: {e}")),
+            // TEST is commutative; the memory operand always goes in r/m.
+            (IcedOp::Reg(dr, _), IcedOp::Mem(sm, _)) => iced_x86::Instruction::with2(iced_x86::Code::Test_rm64_r64, sm.clone(), *dr).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Mem(dm, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Test_rm64_r64, dm.clone(), *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Mem(dm, _), IcedOp::Imm(v)) => iced_x86::Instruction::with2(iced_x86::Code::Test_rm64_imm32, dm.clone(), *v as i32).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn test0(&mut self, ctx: &mut Context, cfg: crate::X64Arch, op: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let o = op.concrete_mem_kind();
+        if matches!(o, MemArgKind::Mem { .. }) {
+            // `test m, m` is not encodable; an all-ones mask sets the same flags.
+            let ones = MemArgKind::NoMem(ArgKind::Lit(u64::MAX));
+            let ones_ref: &dyn crate::out::arg::MemArg = &ones;
+            self.test(ctx, cfg, op, ones_ref)
+        } else {
+            self.test(ctx, cfg, op, op)
+        }
+    }
+
     fn not(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, op: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         let o = mem_kind_to_iced(&op.concrete_mem_kind());
         let instr = match &o {
@@ -1104,9 +1906,39 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         self.encode_instr(instr)
     }
 
+    fn neg(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, op: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let o = mem_kind_to_iced(&op.concrete_mem_kind());
+        let instr = match &o {
+            IcedOp::Reg(r, _) => iced_x86::Instruction::with1(iced_x86::Code::Neg_rm64, *r).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(m, _) => iced_x86::Instruction::with1(iced_x86::Code::Neg_rm64, m.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn inc(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, op: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let o = mem_kind_to_iced(&op.concrete_mem_kind());
+        let instr = match &o {
+            IcedOp::Reg(r, _) => iced_x86::Instruction::with1(iced_x86::Code::Inc_rm64, *r).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(m, _) => iced_x86::Instruction::with1(iced_x86::Code::Inc_rm64, m.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn dec(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, op: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let o = mem_kind_to_iced(&op.concrete_mem_kind());
+        let instr = match &o {
+            IcedOp::Reg(r, _) => iced_x86::Instruction::with1(iced_x86::Code::Dec_rm64, *r).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(m, _) => iced_x86::Instruction::with1(iced_x86::Code::Dec_rm64, m.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
     fn lea(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         let d = mem_kind_to_iced(&dest.concrete_mem_kind());
-        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let (s, sym) = self.sym_op(&src.concrete_mem_kind());
         let sz = Self::size_of(&d);
         let dr = Self::gpr_for_size(Self::op_to_reg(&d), sz);
         let sm = Self::op_to_mem(&s);
@@ -1115,7 +1947,7 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
             MemorySize::_32 => iced_x86::Code::Lea_r32_m,
             _               => iced_x86::Code::Lea_r64_m,
         };
-        self.encode_instr(iced_x86::Instruction::with2(code, dr, sm).unwrap_or_else(|e| panic!("iced: {e}")))
+        self.encode_sym(iced_x86::Instruction::with2(code, dr, sm).unwrap_or_else(|e| panic!("iced: {e}")), sym)
     }
 
     fn mul(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
@@ -1135,6 +1967,62 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         self.encode_instr(instr)
     }
 
+    fn imul2(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let sz = Self::size_of(&d);
+        let dr = Self::gpr_for_size(Self::op_to_reg(&d), sz);
+        let code = match sz {
+            MemorySize::_16 => iced_x86::Code::Imul_r16_rm16,
+            MemorySize::_32 => iced_x86::Code::Imul_r32_rm32,
+            _ => iced_x86::Code::Imul_r64_rm64,
+        };
+        let instr = match &s {
+            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(code, dr, Self::gpr_for_size(*sr, sz)).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(code, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn imul3(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_), imm: i32) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let sz = Self::size_of(&d);
+        let dr = Self::gpr_for_size(Self::op_to_reg(&d), sz);
+        let short = i8::try_from(imm).is_ok();
+        let code = match (sz, short) {
+            (MemorySize::_16, true) => iced_x86::Code::Imul_r16_rm16_imm8,
+            (MemorySize::_16, false) => iced_x86::Code::Imul_r16_rm16_imm16,
+            (MemorySize::_32, true) => iced_x86::Code::Imul_r32_rm32_imm8,
+            (MemorySize::_32, false) => iced_x86::Code::Imul_r32_rm32_imm32,
+            (_, true) => iced_x86::Code::Imul_r64_rm64_imm8,
+            (_, false) => iced_x86::Code::Imul_r64_rm64_imm32,
+        };
+        let instr = match &s {
+            IcedOp::Reg(sr, _) => iced_x86::Instruction::with3(code, dr, Self::gpr_for_size(*sr, sz), imm).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(sm, _) => iced_x86::Instruction::with3(code, dr, sm.clone(), imm).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn mul_wide(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, hi: &(dyn crate::out::arg::MemArg + '_), lo: &(dyn crate::out::arg::MemArg + '_), a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let hi = mem_kind_to_iced(&hi.concrete_mem_kind());
+        let lo = mem_kind_to_iced(&lo.concrete_mem_kind());
+        let a = mem_kind_to_iced(&a.concrete_mem_kind());
+        let b = mem_kind_to_iced(&b.concrete_mem_kind());
+        self.encode_wide_mul(iced_x86::Code::Mul_rm64, &hi, &lo, &a, &b)
+    }
+
+    fn imul_wide(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, hi: &(dyn crate::out::arg::MemArg + '_), lo: &(dyn crate::out::arg::MemArg + '_), a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let hi = mem_kind_to_iced(&hi.concrete_mem_kind());
+        let lo = mem_kind_to_iced(&lo.concrete_mem_kind());
+        let a = mem_kind_to_iced(&a.concrete_mem_kind());
+        let b = mem_kind_to_iced(&b.concrete_mem_kind());
+        self.encode_wide_mul(iced_x86::Code::Imul_rm64, &hi, &lo, &a, &b)
+    }
+
     fn div(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, _a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         let s = mem_kind_to_iced(&b.concrete_mem_kind());
         let sz = Self::size_of(&s);
@@ -1176,6 +2064,7 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         let code = match Self::size_of(&s) {
             MemorySize::_8 => iced_x86::Code::Movsx_r64_rm8,
             MemorySize::_16 => iced_x86::Code::Movsx_r64_rm16,
+            MemorySize::_32 => iced_x86::Code::Movsxd_r64_rm32,
             _ => iced_x86::Code::Movsx_r64_rm16,
         };
         let instr = match &s {
@@ -1189,11 +2078,14 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
     fn movzx(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         let d = mem_kind_to_iced(&dest.concrete_mem_kind());
         let s = mem_kind_to_iced(&src.concrete_mem_kind());
-        let dr = Self::op_to_reg(&d);
-        let code = match Self::size_of(&s) {
-            MemorySize::_8 => iced_x86::Code::Movzx_r64_rm8,
-            MemorySize::_16 => iced_x86::Code::Movzx_r64_rm16,
-            _ => iced_x86::Code::Movzx_r64_rm16,
+        // A 32-bit destination needs no REX.W, so it can take `ah`..`bh`.
+        let wide = Self::size_of(&d) != MemorySize::_32;
+        let dr = Self::gpr_for_size(Self::op_to_reg(&d), if wide { MemorySize::_64 } else { MemorySize::_32 });
+        let code = match (Self::size_of(&s), wide) {
+            (MemorySize::_8, true) => iced_x86::Code::Movzx_r64_rm8,
+            (MemorySize::_8, false) => iced_x86::Code::Movzx_r32_rm8,
+            (_, true) => iced_x86::Code::Movzx_r64_rm16,
+            (_, false) => iced_x86::Code::Movzx_r32_rm16,
         };
         let instr = match &s {
             IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(code, dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
@@ -1203,6 +2095,35 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         self.encode_instr(instr)
     }
 
+    fn popcnt(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        self.encode_r_rm([iced_x86::Code::Popcnt_r16_rm16, iced_x86::Code::Popcnt_r32_rm32, iced_x86::Code::Popcnt_r64_rm64], &d, &s)
+    }
+
+    fn lzcnt(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        self.encode_r_rm([iced_x86::Code::Lzcnt_r16_rm16, iced_x86::Code::Lzcnt_r32_rm32, iced_x86::Code::Lzcnt_r64_rm64], &d, &s)
+    }
+
+    fn tzcnt(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        self.encode_r_rm([iced_x86::Code::Tzcnt_r16_rm16, iced_x86::Code::Tzcnt_r32_rm32, iced_x86::Code::Tzcnt_r64_rm64], &d, &s)
+    }
+
+    fn bswap(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, op: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let o = mem_kind_to_iced(&op.concrete_mem_kind());
+        let instr = match &o {
+            // BSWAP has no memory form; a 16-bit operand is undefined, so it uses the 32-bit form.
+            IcedOp::Reg(r, MemorySize::_64) => iced_x86::Instruction::with1(iced_x86::Code::Bswap_r64, *r).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Reg(r, _) => iced_x86::Instruction::with1(iced_x86::Code::Bswap_r32, Self::gpr_for_size(*r, MemorySize::_32)).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
     fn cmovcc(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, cond: crate::ConditionCode, op: &(dyn crate::out::arg::MemArg + '_), val: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
         use crate::ConditionCode::*;
         let d = mem_kind_to_iced(&op.concrete_mem_kind());
@@ -1268,64 +2189,526 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         self.encode_instr(instr)
     }
 
-    fn fadd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
-        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
-        let s = mem_kind_to_iced(&src.concrete_mem_kind());
-        let dr = Self::op_to_reg(&d);
-        let instr = match &s {
-            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(iced_x86::Code::Addsd_xmm_xmmm64, dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
-            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(iced_x86::Code::Addsd_xmm_xmmm64, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
-            _ => return Ok(()),
-        };
-        self.encode_instr(instr)
+    fn setcc(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, cond: crate::ConditionCode, op: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        use crate::ConditionCode::*;
+        let code = match cond {
+            O   => iced_x86::Code::Seto_rm8,
+            NO  => iced_x86::Code::Setno_rm8,
+            B   => iced_x86::Code::Setb_rm8,
+            NB  => iced_x86::Code::Setae_rm8,
+            E   => iced_x86::Code::Sete_rm8,
+            NE  => iced_x86::Code::Setne_rm8,
+            NA  => iced_x86::Code::Setbe_rm8,
+            A   => iced_x86::Code::Seta_rm8,
+            S   => iced_x86::Code::Sets_rm8,
+            NS  => iced_x86::Code::Setns_rm8,
+            P   => iced_x86::Code::Setp_rm8,
+            NP  => iced_x86::Code::Setnp_rm8,
+            L   => iced_x86::Code::Setl_rm8,
+            NL  => iced_x86::Code::Setge_rm8,
+            NG  => iced_x86::Code::Setle_rm8,
+            G   => iced_x86::Code::Setg_rm8,
+        };
+        // setcc always writes a byte, whatever width `op` was given at.
+        let instr = match op.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => iced_x86::Instruction::with1(code, reg_to_iced_sized(reg, MemorySize::_8)).unwrap_or_else(|e| panic!("iced: {e}")),
+            mk => match mem_kind_to_iced(&mk) {
+                IcedOp::Mem(m, _) => iced_x86::Instruction::with1(code, m).unwrap_or_else(|e| panic!("iced: {e}")),
+                _ => return Ok(()),
+            },
+        };
+        self.encode_instr(instr)
+    }
+
+    fn fadd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let dr = Self::op_to_reg(&d);
+        let instr = match &s {
+            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(iced_x86::Code::Addsd_xmm_xmmm64, dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(iced_x86::Code::Addsd_xmm_xmmm64, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn fsub(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let dr = Self::op_to_reg(&d);
+        let instr = match &s {
+            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(iced_x86::Code::Subsd_xmm_xmmm64, dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(iced_x86::Code::Subsd_xmm_xmmm64, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn fmul(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let dr = Self::op_to_reg(&d);
+        let instr = match &s {
+            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(iced_x86::Code::Mulsd_xmm_xmmm64, dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(iced_x86::Code::Mulsd_xmm_xmmm64, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn fdiv(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let dr = Self::op_to_reg(&d);
+        let instr = match &s {
+            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(iced_x86::Code::Divsd_xmm_xmmm64, dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(iced_x86::Code::Divsd_xmm_xmmm64, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn fcmp(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Ucomisd_xmm_xmmm64, a, b)
+    }
+
+    fn fmin(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Minsd_xmm_xmmm64, dest, src)
+    }
+
+    fn fmax(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Maxsd_xmm_xmmm64, dest, src)
+    }
+
+    fn fsqrt(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Sqrtsd_xmm_xmmm64, dest, src)
+    }
+
+    fn fabs(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), scratch: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sign_mask(iced_x86::Code::Psrlq_xmm_imm8, 1, iced_x86::Code::Andpd_xmm_xmmm128, dest, scratch)
+    }
+
+    fn fneg(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), scratch: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sign_mask(iced_x86::Code::Psllq_xmm_imm8, 63, iced_x86::Code::Xorpd_xmm_xmmm128, dest, scratch)
+    }
+
+    fn cvtsi2sd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let dr = match dest.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => xmm_to_iced(reg),
+            _ => panic!("iced: cvtsi2sd destination must be an XMM register"),
+        };
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let sz = Self::size_of(&s);
+        let code = if sz == MemorySize::_32 { iced_x86::Code::Cvtsi2sd_xmm_rm32 } else { iced_x86::Code::Cvtsi2sd_xmm_rm64 };
+        let instr = match &s {
+            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(code, dr, Self::gpr_for_size(*sr, sz)).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(code, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn cvttsd2si(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let sz = Self::size_of(&d);
+        let dr = Self::gpr_for_size(Self::op_to_reg(&d), sz);
+        let code = if sz == MemorySize::_32 { iced_x86::Code::Cvttsd2si_r32_xmmm64 } else { iced_x86::Code::Cvttsd2si_r64_xmmm64 };
+        let instr = match src.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => iced_x86::Instruction::with2(code, dr, xmm_to_iced(reg)),
+            mk => match mem_kind_to_iced(&mk) {
+                IcedOp::Mem(m, _) => iced_x86::Instruction::with2(code, dr, m),
+                _ => return Ok(()),
+            },
+        };
+        self.encode_instr(instr.unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn vfmadd231sd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_fma(iced_x86::Code::Vfmadd231sd_xmm_xmm_xmmm64, dest, a, b)
+    }
+
+    fn vfmsub231sd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_fma(iced_x86::Code::Vfmsub231sd_xmm_xmm_xmmm64, dest, a, b)
+    }
+
+    fn vfnmadd231sd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_fma(iced_x86::Code::Vfnmadd231sd_xmm_xmm_xmmm64, dest, a, b)
+    }
+
+    fn vfnmsub231sd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_fma(iced_x86::Code::Vfnmsub231sd_xmm_xmm_xmmm64, dest, a, b)
+    }
+
+    fn kmov(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: crate::MaskReg, src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        use iced_x86::Code;
+        let kr = mask_to_iced(dest);
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let (reg_code, mem_code) = match Self::size_of(&s) {
+            MemorySize::_8 => (Code::VEX_Kmovb_kr_r32, Code::VEX_Kmovb_kr_km8),
+            MemorySize::_16 => (Code::VEX_Kmovw_kr_r32, Code::VEX_Kmovw_kr_km16),
+            MemorySize::_32 => (Code::VEX_Kmovd_kr_r32, Code::VEX_Kmovd_kr_km32),
+            _ => (Code::VEX_Kmovq_kr_r64, Code::VEX_Kmovq_kr_km64),
+        };
+        let instr = match (src.concrete_mem_kind(), &s) {
+            (MemArgKind::NoMem(ArgKind::Reg { reg, size }), _) => {
+                // The byte, word and dword forms all read a 32-bit register.
+                let sr = if size == MemorySize::_64 { reg_to_iced(reg) } else { Self::gpr_for_size(reg_to_iced(reg), MemorySize::_32) };
+                iced_x86::Instruction::with2(reg_code, kr, sr)
+            }
+            (_, IcedOp::Mem(sm, _)) => iced_x86::Instruction::with2(mem_code, kr, sm.clone()),
+            _ => panic!("iced: kmov source must be a general-purpose register or memory"),
+        };
+        self.encode_instr(instr.unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn vmovupd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), mask: crate::out::evex::OpMask, src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        use iced_x86::Code;
+        let instr = match (dest.concrete_mem_kind(), src.concrete_mem_kind()) {
+            (MemArgKind::NoMem(ArgKind::Reg { reg, .. }), sk) => {
+                let size = crate::out::evex::vector_size(dest);
+                let code = match size { MemorySize::_512 => Code::EVEX_Vmovupd_zmm_k1z_zmmm512, MemorySize::_256 => Code::EVEX_Vmovupd_ymm_k1z_ymmm256, _ => Code::EVEX_Vmovupd_xmm_k1z_xmmm128 };
+                match sk {
+                    MemArgKind::NoMem(ArgKind::Reg { reg: sr, .. }) => iced_x86::Instruction::with2(code, vec_to_iced(reg, size), vec_to_iced(sr, size)),
+                    mk => match mem_kind_to_iced(&mk) {
+                        IcedOp::Mem(m, _) => iced_x86::Instruction::with2(code, vec_to_iced(reg, size), m),
+                        _ => return Ok(()),
+                    },
+                }
+            }
+            (dk, MemArgKind::NoMem(ArgKind::Reg { reg, .. })) => {
+                let size = crate::out::evex::vector_size(src);
+                let code = match size { MemorySize::_512 => Code::EVEX_Vmovupd_zmmm512_k1z_zmm, MemorySize::_256 => Code::EVEX_Vmovupd_ymmm256_k1z_ymm, _ => Code::EVEX_Vmovupd_xmmm128_k1z_xmm };
+                match mem_kind_to_iced(&dk) {
+                    IcedOp::Mem(m, _) => iced_x86::Instruction::with2(code, m, vec_to_iced(reg, size)),
+                    _ => return Ok(()),
+                }
+            }
+            _ => panic!("iced: vmovupd needs a vector register operand"),
+        };
+        let mut instr = instr.unwrap_or_else(|e| panic!("iced: {e}"));
+        Self::evex_decorate(&mut instr, mask, false);
+        self.encode_instr(instr)
+    }
+
+    fn vaddpd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), mask: crate::out::evex::OpMask, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_), broadcast: bool) -> Result<(), Self::Error> {
+        use iced_x86::Code;
+        self.encode_evex([Code::EVEX_Vaddpd_xmm_k1z_xmm_xmmm128b64, Code::EVEX_Vaddpd_ymm_k1z_ymm_ymmm256b64, Code::EVEX_Vaddpd_zmm_k1z_zmm_zmmm512b64_er], dest, mask, a, b, broadcast)
+    }
+
+    fn vsubpd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), mask: crate::out::evex::OpMask, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_), broadcast: bool) -> Result<(), Self::Error> {
+        use iced_x86::Code;
+        self.encode_evex([Code::EVEX_Vsubpd_xmm_k1z_xmm_xmmm128b64, Code::EVEX_Vsubpd_ymm_k1z_ymm_ymmm256b64, Code::EVEX_Vsubpd_zmm_k1z_zmm_zmmm512b64_er], dest, mask, a, b, broadcast)
+    }
+
+    fn vmulpd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), mask: crate::out::evex::OpMask, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_), broadcast: bool) -> Result<(), Self::Error> {
+        use iced_x86::Code;
+        self.encode_evex([Code::EVEX_Vmulpd_xmm_k1z_xmm_xmmm128b64, Code::EVEX_Vmulpd_ymm_k1z_ymm_ymmm256b64, Code::EVEX_Vmulpd_zmm_k1z_zmm_zmmm512b64_er], dest, mask, a, b, broadcast)
+    }
+
+    fn vdivpd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), mask: crate::out::evex::OpMask, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_), broadcast: bool) -> Result<(), Self::Error> {
+        use iced_x86::Code;
+        self.encode_evex([Code::EVEX_Vdivpd_xmm_k1z_xmm_xmmm128b64, Code::EVEX_Vdivpd_ymm_k1z_ymm_ymmm256b64, Code::EVEX_Vdivpd_zmm_k1z_zmm_zmmm512b64_er], dest, mask, a, b, broadcast)
+    }
+
+    fn vfmadd231pd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), mask: crate::out::evex::OpMask, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_), broadcast: bool) -> Result<(), Self::Error> {
+        use iced_x86::Code;
+        self.encode_evex([Code::EVEX_Vfmadd231pd_xmm_k1z_xmm_xmmm128b64, Code::EVEX_Vfmadd231pd_ymm_k1z_ymm_ymmm256b64, Code::EVEX_Vfmadd231pd_zmm_k1z_zmm_zmmm512b64_er], dest, mask, a, b, broadcast)
+    }
+
+    fn vcmppd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: crate::MaskReg, mask: crate::out::evex::OpMask, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_), predicate: u8, broadcast: bool) -> Result<(), Self::Error> {
+        use iced_x86::Code;
+        let size = crate::out::evex::vector_size(a);
+        let code = match size { MemorySize::_512 => Code::EVEX_Vcmppd_kr_k1_zmm_zmmm512b64_imm8_sae, MemorySize::_256 => Code::EVEX_Vcmppd_kr_k1_ymm_ymmm256b64_imm8, _ => Code::EVEX_Vcmppd_kr_k1_xmm_xmmm128b64_imm8 };
+        let (kr, ar) = (mask_to_iced(dest), Self::evex_reg(a, size));
+        let instr = match b.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => iced_x86::Instruction::with4(code, kr, ar, vec_to_iced(reg, size), predicate as i32),
+            mk => match mem_kind_to_iced(&mk) {
+                IcedOp::Mem(m, _) => iced_x86::Instruction::with4(code, kr, ar, m, predicate as i32),
+                _ => return Ok(()),
+            },
+        };
+        let mut instr = instr.unwrap_or_else(|e| panic!("iced: {e}"));
+        Self::evex_decorate(&mut instr, mask, broadcast);
+        self.encode_instr(instr)
+    }
+
+    fn fmov(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let instr = match (&d, &s) {
+            (IcedOp::Reg(dr, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Movsd_xmm_xmmm64, *dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Reg(dr, _), IcedOp::Mem(sm, _)) => iced_x86::Instruction::with2(iced_x86::Code::Movsd_xmm_xmmm64, *dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Mem(dm, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Movsd_xmmm64_xmm, dm.clone(), *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn addss(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Addss_xmm_xmmm32, dest, src)
+    }
+
+    fn subss(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Subss_xmm_xmmm32, dest, src)
+    }
+
+    fn mulss(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Mulss_xmm_xmmm32, dest, src)
+    }
+
+    fn divss(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Divss_xmm_xmmm32, dest, src)
+    }
+
+    fn ucomiss(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, a: &(dyn crate::out::arg::MemArg + '_), b: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Ucomiss_xmm_xmmm32, a, b)
+    }
+
+    fn minss(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Minss_xmm_xmmm32, dest, src)
+    }
+
+    fn maxss(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Maxss_xmm_xmmm32, dest, src)
+    }
+
+    fn sqrtss(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Sqrtss_xmm_xmmm32, dest, src)
+    }
+
+    fn cvtsi2ss(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let dr = match dest.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => xmm_to_iced(reg),
+            _ => panic!("iced: cvtsi2ss destination must be an XMM register"),
+        };
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let sz = Self::size_of(&s);
+        let code = if sz == MemorySize::_32 { iced_x86::Code::Cvtsi2ss_xmm_rm32 } else { iced_x86::Code::Cvtsi2ss_xmm_rm64 };
+        let instr = match &s {
+            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(code, dr, Self::gpr_for_size(*sr, sz)).unwrap_or_else(|e| panic!("iced: {e}")),
+            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(code, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn cvttss2si(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let sz = Self::size_of(&d);
+        let dr = Self::gpr_for_size(Self::op_to_reg(&d), sz);
+        let code = if sz == MemorySize::_32 { iced_x86::Code::Cvttss2si_r32_xmmm32 } else { iced_x86::Code::Cvttss2si_r64_xmmm32 };
+        let instr = match src.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => iced_x86::Instruction::with2(code, dr, xmm_to_iced(reg)),
+            mk => match mem_kind_to_iced(&mk) {
+                IcedOp::Mem(m, _) => iced_x86::Instruction::with2(code, dr, m),
+                _ => return Ok(()),
+            },
+        };
+        self.encode_instr(instr.unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn cvtss2sd(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Cvtss2sd_xmm_xmmm32, dest, src)
+    }
+
+    fn cvtsd2ss(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_sse(iced_x86::Code::Cvtsd2ss_xmm_xmmm64, dest, src)
+    }
+
+    fn movss(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
+        let s = mem_kind_to_iced(&src.concrete_mem_kind());
+        let instr = match (&d, &s) {
+            (IcedOp::Reg(dr, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Movss_xmm_xmmm32, *dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Reg(dr, _), IcedOp::Mem(sm, _)) => iced_x86::Instruction::with2(iced_x86::Code::Movss_xmm_xmmm32, *dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
+            (IcedOp::Mem(dm, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Movss_xmmm32_xmm, dm.clone(), *sr).unwrap_or_else(|e| panic!("iced: {e}")),
+            _ => return Ok(()),
+        };
+        self.encode_instr(instr)
+    }
+
+    fn ldmxcsr(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let IcedOp::Mem(m, _) = mem_kind_to_iced(&src.concrete_mem_kind()) else { panic!("ldmxcsr requires a memory operand") };
+        self.encode_instr(iced_x86::Instruction::with1(iced_x86::Code::Ldmxcsr_m32, m).unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn stmxcsr(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        let IcedOp::Mem(m, _) = mem_kind_to_iced(&dest.concrete_mem_kind()) else { panic!("stmxcsr requires a memory operand") };
+        self.encode_instr(iced_x86::Instruction::with1(iced_x86::Code::Stmxcsr_m32, m).unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn rdsegbase(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, seg: crate::out::arg::Segment, dest: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        debug_check!(cfg.fsgsbase, "rdsegbase requires X64Arch::fsgsbase");
+        let IcedOp::Reg(r, size) = mem_kind_to_iced(&dest.concrete_mem_kind()) else { panic!("rdsegbase requires a register operand") };
+        let code = match (seg, size) {
+            (crate::out::arg::Segment::Fs, MemorySize::_32) => iced_x86::Code::Rdfsbase_r32,
+            (crate::out::arg::Segment::Fs, _) => iced_x86::Code::Rdfsbase_r64,
+            (crate::out::arg::Segment::Gs, MemorySize::_32) => iced_x86::Code::Rdgsbase_r32,
+            (crate::out::arg::Segment::Gs, _) => iced_x86::Code::Rdgsbase_r64,
+            (crate::out::arg::Segment::None, _) => panic!("rdsegbase requires fs or gs"),
+        };
+        self.encode_instr(iced_x86::Instruction::with1(code, Self::gpr_for_size(r, size)).unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn wrsegbase(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, seg: crate::out::arg::Segment, src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        debug_check!(cfg.fsgsbase, "wrsegbase requires X64Arch::fsgsbase");
+        let IcedOp::Reg(r, size) = mem_kind_to_iced(&src.concrete_mem_kind()) else { panic!("wrsegbase requires a register operand") };
+        let code = match (seg, size) {
+            (crate::out::arg::Segment::Fs, MemorySize::_32) => iced_x86::Code::Wrfsbase_r32,
+            (crate::out::arg::Segment::Fs, _) => iced_x86::Code::Wrfsbase_r64,
+            (crate::out::arg::Segment::Gs, MemorySize::_32) => iced_x86::Code::Wrgsbase_r32,
+            (crate::out::arg::Segment::Gs, _) => iced_x86::Code::Wrgsbase_r64,
+            (crate::out::arg::Segment::None, _) => panic!("wrsegbase requires fs or gs"),
+        };
+        self.encode_instr(iced_x86::Instruction::with1(code, Self::gpr_for_size(r, size)).unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn fld(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        debug_check!(cfg.x87, "fld requires X64Arch::x87");
+        let IcedOp::Mem(m, size) = mem_kind_to_iced(&src.concrete_mem_kind()) else { panic!("fld requires a memory operand") };
+        let code = match size { MemorySize::_32 => iced_x86::Code::Fld_m32fp, _ => iced_x86::Code::Fld_m64fp };
+        self.encode_instr(iced_x86::Instruction::with1(code, m).unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn fst(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        debug_check!(cfg.x87, "fst requires X64Arch::x87");
+        let IcedOp::Mem(m, size) = mem_kind_to_iced(&dest.concrete_mem_kind()) else { panic!("fst requires a memory operand") };
+        let code = match size { MemorySize::_32 => iced_x86::Code::Fst_m32fp, _ => iced_x86::Code::Fst_m64fp };
+        self.encode_instr(iced_x86::Instruction::with1(code, m).unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn fstp(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        debug_check!(cfg.x87, "fstp requires X64Arch::x87");
+        let IcedOp::Mem(m, size) = mem_kind_to_iced(&dest.concrete_mem_kind()) else { panic!("fstp requires a memory operand") };
+        let code = match size { MemorySize::_32 => iced_x86::Code::Fstp_m32fp, _ => iced_x86::Code::Fstp_m64fp };
+        self.encode_instr(iced_x86::Instruction::with1(code, m).unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn faddp(&mut self, _ctx: &mut Context, cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        debug_check!(cfg.x87, "faddp requires X64Arch::x87");
+        self.encode_instr(iced_x86::Instruction::with2(iced_x86::Code::Faddp_sti_st0, iced_x86::Register::ST1, iced_x86::Register::ST0).unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn fsubp(&mut self, _ctx: &mut Context, cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        debug_check!(cfg.x87, "fsubp requires X64Arch::x87");
+        self.encode_instr(iced_x86::Instruction::with2(iced_x86::Code::Fsubp_sti_st0, iced_x86::Register::ST1, iced_x86::Register::ST0).unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn fmulp(&mut self, _ctx: &mut Context, cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        debug_check!(cfg.x87, "fmulp requires X64Arch::x87");
+        self.encode_instr(iced_x86::Instruction::with2(iced_x86::Code::Fmulp_sti_st0, iced_x86::Register::ST1, iced_x86::Register::ST0).unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn fdivp(&mut self, _ctx: &mut Context, cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        debug_check!(cfg.x87, "fdivp requires X64Arch::x87");
+        self.encode_instr(iced_x86::Instruction::with2(iced_x86::Code::Fdivp_sti_st0, iced_x86::Register::ST1, iced_x86::Register::ST0).unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn movs(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, size: MemorySize, rep: crate::RepPrefix) -> Result<(), Self::Error> {
+        let (seg, rep) = (iced_x86::Register::None, rep_to_iced(rep));
+        let instr = match size {
+            MemorySize::_8 => iced_x86::Instruction::with_movsb(64, seg, rep),
+            MemorySize::_16 => iced_x86::Instruction::with_movsw(64, seg, rep),
+            MemorySize::_32 => iced_x86::Instruction::with_movsd(64, seg, rep),
+            _ => iced_x86::Instruction::with_movsq(64, seg, rep),
+        };
+        self.encode_instr(instr.unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn stos(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, size: MemorySize, rep: crate::RepPrefix) -> Result<(), Self::Error> {
+        let rep = rep_to_iced(rep);
+        let instr = match size {
+            MemorySize::_8 => iced_x86::Instruction::with_stosb(64, rep),
+            MemorySize::_16 => iced_x86::Instruction::with_stosw(64, rep),
+            MemorySize::_32 => iced_x86::Instruction::with_stosd(64, rep),
+            _ => iced_x86::Instruction::with_stosq(64, rep),
+        };
+        self.encode_instr(instr.unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn cmps(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, size: MemorySize, rep: crate::RepPrefix) -> Result<(), Self::Error> {
+        let (seg, rep) = (iced_x86::Register::None, rep_to_iced(rep));
+        let instr = match size {
+            MemorySize::_8 => iced_x86::Instruction::with_cmpsb(64, seg, rep),
+            MemorySize::_16 => iced_x86::Instruction::with_cmpsw(64, seg, rep),
+            MemorySize::_32 => iced_x86::Instruction::with_cmpsd(64, seg, rep),
+            _ => iced_x86::Instruction::with_cmpsq(64, seg, rep),
+        };
+        self.encode_instr(instr.unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn scas(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, size: MemorySize, rep: crate::RepPrefix) -> Result<(), Self::Error> {
+        let rep = rep_to_iced(rep);
+        let instr = match size {
+            MemorySize::_8 => iced_x86::Instruction::with_scasb(64, rep),
+            MemorySize::_16 => iced_x86::Instruction::with_scasw(64, rep),
+            MemorySize::_32 => iced_x86::Instruction::with_scasd(64, rep),
+            _ => iced_x86::Instruction::with_scasq(64, rep),
+        };
+        self.encode_instr(instr.unwrap_or_else(|e| panic!("iced: {e}")))
+    }
+
+    fn syscall(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        self.encode_instr(iced_x86::Instruction::with(iced_x86::Code::Syscall))
+    }
+
+    fn endbr64(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        self.encode_instr(iced_x86::Instruction::with(iced_x86::Code::Endbr64))
+    }
+
+    fn ud2(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        self.encode_instr(iced_x86::Instruction::with(iced_x86::Code::Ud2))
+    }
+
+    fn int3(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        self.encode_instr(iced_x86::Instruction::with(iced_x86::Code::Int3))
+    }
+
+    fn pause(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        self.encode_instr(iced_x86::Instruction::with(iced_x86::Code::Pause))
+    }
+
+    fn prefetcht0(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, mem: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_line_op(iced_x86::Code::Prefetcht0_m8, mem)
+    }
+
+    fn prefetcht1(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, mem: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_line_op(iced_x86::Code::Prefetcht1_m8, mem)
+    }
+
+    fn prefetcht2(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, mem: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_line_op(iced_x86::Code::Prefetcht2_m8, mem)
+    }
+
+    fn prefetchnta(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, mem: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_line_op(iced_x86::Code::Prefetchnta_m8, mem)
+    }
+
+    fn clflush(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, mem: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        self.encode_line_op(iced_x86::Code::Clflush_m8, mem)
+    }
+
+    fn clflushopt(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, mem: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        debug_check!(cfg.clflushopt, "clflushopt requires X64Arch::clflushopt");
+        self.encode_line_op(iced_x86::Code::Clflushopt_m8, mem)
     }
 
-    fn fsub(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
-        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
-        let s = mem_kind_to_iced(&src.concrete_mem_kind());
-        let dr = Self::op_to_reg(&d);
-        let instr = match &s {
-            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(iced_x86::Code::Subsd_xmm_xmmm64, dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
-            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(iced_x86::Code::Subsd_xmm_xmmm64, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
-            _ => return Ok(()),
-        };
-        self.encode_instr(instr)
+    fn clwb(&mut self, _ctx: &mut Context, cfg: crate::X64Arch, mem: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
+        debug_check!(cfg.clwb, "clwb requires X64Arch::clwb");
+        self.encode_line_op(iced_x86::Code::Clwb_m8, mem)
     }
 
-    fn fmul(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
-        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
-        let s = mem_kind_to_iced(&src.concrete_mem_kind());
-        let dr = Self::op_to_reg(&d);
-        let instr = match &s {
-            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(iced_x86::Code::Mulsd_xmm_xmmm64, dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
-            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(iced_x86::Code::Mulsd_xmm_xmmm64, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
-            _ => return Ok(()),
-        };
-        self.encode_instr(instr)
+    fn sfence(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        self.encode_instr(iced_x86::Instruction::with(iced_x86::Code::Sfence))
     }
 
-    fn fdiv(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
-        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
-        let s = mem_kind_to_iced(&src.concrete_mem_kind());
-        let dr = Self::op_to_reg(&d);
-        let instr = match &s {
-            IcedOp::Reg(sr, _) => iced_x86::Instruction::with2(iced_x86::Code::Divsd_xmm_xmmm64, dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
-            IcedOp::Mem(sm, _) => iced_x86::Instruction::with2(iced_x86::Code::Divsd_xmm_xmmm64, dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
-            _ => return Ok(()),
-        };
-        self.encode_instr(instr)
+    fn rdtsc(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        self.encode_instr(iced_x86::Instruction::with(iced_x86::Code::Rdtsc))
     }
 
-    fn fmov(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, dest: &(dyn crate::out::arg::MemArg + '_), src: &(dyn crate::out::arg::MemArg + '_)) -> Result<(), Self::Error> {
-        let d = mem_kind_to_iced(&dest.concrete_mem_kind());
-        let s = mem_kind_to_iced(&src.concrete_mem_kind());
-        let instr = match (&d, &s) {
-            (IcedOp::Reg(dr, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Movsd_xmm_xmmm64, *dr, *sr).unwrap_or_else(|e| panic!("iced: {e}")),
-            (IcedOp::Reg(dr, _), IcedOp::Mem(sm, _)) => iced_x86::Instruction::with2(iced_x86::Code::Movsd_xmm_xmmm64, *dr, sm.clone()).unwrap_or_else(|e| panic!("iced: {e}")),
-            (IcedOp::Mem(dm, _), IcedOp::Reg(sr, _)) => iced_x86::Instruction::with2(iced_x86::Code::Movsd_xmmm64_xmm, dm.clone(), *sr).unwrap_or_else(|e| panic!("iced: {e}")),
-            _ => return Ok(()),
-        };
-        self.encode_instr(instr)
+    fn rdtscp(&mut self, _ctx: &mut Context, cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        debug_check!(cfg.rdtscp, "rdtscp requires X64Arch::rdtscp");
+        self.encode_instr(iced_x86::Instruction::with(iced_x86::Code::Rdtscp))
     }
 
     fn db(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
@@ -1334,6 +2717,31 @@ impl<L, Context> crate::out::WriterCore<Context> for IcedWriter<L> {
         Ok(())
     }
 
+    fn current_offset(&self) -> Option<usize> {
+        Some(self.buf.len())
+    }
+
+    fn align_to(&mut self, ctx: &mut Context, cfg: crate::X64Arch, alignment: usize) -> Result<(), Self::Error> {
+        let pad = self.buf.len().next_multiple_of(alignment) - self.buf.len();
+        crate::out::WriterCore::<Context>::nops(self, ctx, cfg, pad)
+    }
+
+    fn nop(&mut self, ctx: &mut Context, cfg: crate::X64Arch) -> Result<(), Self::Error> {
+        crate::out::WriterCore::<Context>::nops(self, ctx, cfg, 1)
+    }
+
+    fn nops(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch, n_bytes: usize) -> Result<(), Self::Error> {
+        for nop in crate::out::nop_fill(n_bytes) {
+            self.buf.extend_from_slice(nop);
+        }
+        self.ip += n_bytes as u64;
+        Ok(())
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: crate::X64Arch, pow2: u32) -> Result<(), Self::Error> {
+        crate::out::WriterCore::<Context>::align_to(self, ctx, cfg, 1 << pow2)
+    }
+
     fn get_ip(&mut self, _ctx: &mut Context, _cfg: crate::X64Arch) -> Result<(), Self::Error> {
         // CALL 0; POP rax pattern — caller must handle
         Ok(())
@@ -1363,6 +2771,9 @@ impl<L: Ord, Context> crate::out::Writer<L, Context> for IcedWriter<L> {
             let rel = (target as i64 - instr_end as i64) as i32;
             self.buf[patch_offset..patch_offset + 4].copy_from_slice(&rel.to_le_bytes());
         }
+        if let Some((prev, _)) = self.labels.remove_entry(&s) {
+            self.duplicate_labels.push(prev);
+        }
         self.labels.insert(s, target);
         Ok(())
     }
@@ -1465,6 +2876,26 @@ impl<L: Ord, Context> crate::out::Writer<L, Context> for IcedWriter<L> {
         }
         Ok(())
     }
+
+    fn dd_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: crate::X64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        // The offset is relative to the word itself, so the fixup's end is
+        // its start.
+        let start = self.buf.len();
+        self.buf.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        self.ip += 4;
+        if let Some(&target) = self.labels.get(&label) {
+            let rel = (target as i64 - start as i64) as i32;
+            self.buf[start..start + 4].copy_from_slice(&rel.to_le_bytes());
+        } else {
+            self.pending_fixups.push(IcedFixup { patch_offset: start, instr_end: start, label });
+        }
+        Ok(())
+    }
 }
 
 #[cfg(feature = "iced")]
@@ -1671,4 +3102,448 @@ mod tests {
         assert_eq!(bytes.len(), 3);
         assert_eq!(labels[&99u32], 2);
     }
+
+    #[test]
+    fn label_violations_reports_undefined_and_duplicate() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.jmp_label(&mut ctx, arch, 1u32).unwrap();
+        w.jmp_label(&mut ctx, arch, 1u32).unwrap();
+        w.set_label(&mut ctx, arch, 2u32).unwrap();
+        w.set_label(&mut ctx, arch, 2u32).unwrap();
+
+        assert_eq!(w.label_violations(), [LabelViolation::Undefined(1), LabelViolation::Duplicate(2)]);
+
+        w.set_label(&mut ctx, arch, 1u32).unwrap();
+        assert_eq!(w.try_into_parts().unwrap_err(), [LabelViolation::Duplicate(2)]);
+    }
+
+    #[test]
+    fn x87_encodings() {
+        let arch = crate::X64Arch { x87: true, ..Default::default() };
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+        let mem = |size| MemArgKind::Mem {
+            base: ArgKind::Reg { reg: Reg(0), size: MemorySize::_64 },
+            offset: None,
+            disp: 0,
+            size,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: crate::out::arg::Segment::None,
+        };
+
+        w.fld(&mut ctx, arch, &mem(MemorySize::_64)).unwrap(); // fld qword ptr [rax]
+        w.fld(&mut ctx, arch, &mem(MemorySize::_32)).unwrap(); // fld dword ptr [rax]
+        w.faddp(&mut ctx, arch).unwrap(); // faddp st(1),st
+        w.fstp(&mut ctx, arch, &mem(MemorySize::_64)).unwrap(); // fstp qword ptr [rax]
+        assert_eq!(w.into_parts().0, [0xDD, 0x00, 0xD9, 0x00, 0xDE, 0xC1, 0xDD, 0x18]);
+    }
+
+    #[test]
+    fn string_encodings() {
+        use crate::RepPrefix;
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.movs(&mut ctx, arch, MemorySize::_8, RepPrefix::Rep).unwrap(); // rep movsb
+        w.stos(&mut ctx, arch, MemorySize::_64, RepPrefix::None).unwrap(); // stosq
+        w.cmps(&mut ctx, arch, MemorySize::_32, RepPrefix::Repe).unwrap(); // repe cmpsd
+        w.scas(&mut ctx, arch, MemorySize::_8, RepPrefix::Repne).unwrap(); // repne scasb
+        assert_eq!(w.into_parts().0, [0xF3, 0xA4, 0x48, 0xAB, 0xF3, 0xA7, 0xF2, 0xAE]);
+    }
+
+    #[test]
+    fn traps_encode_ud2_and_int3() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.unreachable(&mut ctx, arch).unwrap(); // ud2
+        w.debug_break(&mut ctx, arch).unwrap(); // int3
+        assert_eq!(w.into_parts().0, [0x0F, 0x0B, 0xCC]);
+    }
+
+    #[test]
+    fn pause_encodes() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.pause(&mut ctx, arch).unwrap();
+        assert_eq!(w.into_parts().0, [0xF3, 0x90]);
+    }
+
+    #[test]
+    fn prefetch_encodings() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+        let mem = MemArgKind::Mem {
+            base: ArgKind::Reg { reg: Reg(0), size: MemorySize::_64 },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_8,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: crate::out::arg::Segment::None,
+        };
+
+        w.prefetcht0(&mut ctx, arch, &mem).unwrap(); // prefetcht0 byte ptr [rax]
+        w.prefetcht1(&mut ctx, arch, &mem).unwrap(); // prefetcht1 byte ptr [rax]
+        w.prefetcht2(&mut ctx, arch, &mem).unwrap(); // prefetcht2 byte ptr [rax]
+        w.prefetchnta(&mut ctx, arch, &mem).unwrap(); // prefetchnta byte ptr [rax]
+        assert_eq!(w.into_parts().0, [0x0F, 0x18, 0x08, 0x0F, 0x18, 0x10, 0x0F, 0x18, 0x18, 0x0F, 0x18, 0x00]);
+    }
+
+    #[test]
+    fn cache_line_flush_encodings() {
+        let arch = crate::X64Arch { clflushopt: true, clwb: true, ..Default::default() };
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+        let mem = MemArgKind::Mem {
+            base: ArgKind::Reg { reg: Reg(0), size: MemorySize::_64 },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_8,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: crate::out::arg::Segment::None,
+        };
+
+        w.clflush(&mut ctx, arch, &mem).unwrap(); // clflush byte ptr [rax]
+        w.clflushopt(&mut ctx, arch, &mem).unwrap(); // clflushopt byte ptr [rax]
+        w.clwb(&mut ctx, arch, &mem).unwrap(); // clwb byte ptr [rax]
+        w.sfence(&mut ctx, arch).unwrap(); // sfence
+        assert_eq!(w.into_parts().0, [0x0F, 0xAE, 0x38, 0x66, 0x0F, 0xAE, 0x38, 0x66, 0x0F, 0xAE, 0x30, 0x0F, 0xAE, 0xF8]);
+    }
+
+    #[test]
+    fn timestamp_counter_encodings() {
+        let arch = crate::X64Arch { rdtscp: true, ..Default::default() };
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.rdtsc(&mut ctx, arch).unwrap(); // rdtsc
+        w.rdtscp(&mut ctx, arch).unwrap(); // rdtscp
+        assert_eq!(w.into_parts().0, [0x0F, 0x31, 0x0F, 0x01, 0xF9]);
+    }
+
+    #[test]
+    fn lahf_and_sahf_encode() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.lahf(&mut ctx, arch).unwrap();
+        w.sahf(&mut ctx, arch).unwrap();
+        assert_eq!(w.into_parts().0, [0x9F, 0x9E]);
+    }
+
+    #[test]
+    fn adc_and_sbb_encode() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.adc(&mut ctx, arch, &Reg(0), &Reg(1)).unwrap(); // adc rax, rcx
+        w.sbb(&mut ctx, arch, &Reg(2), &Reg(3)).unwrap(); // sbb rdx, rbx
+        assert_eq!(w.into_parts().0, [0x48, 0x13, 0xC1, 0x48, 0x1B, 0xD3]);
+    }
+
+    #[test]
+    fn neg_inc_and_dec_encode() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.neg(&mut ctx, arch, &Reg(0)).unwrap(); // neg rax
+        w.inc(&mut ctx, arch, &Reg(1)).unwrap(); // inc rcx
+        w.dec(&mut ctx, arch, &Reg(2)).unwrap(); // dec rdx
+        assert_eq!(
+            w.into_parts().0,
+            [0x48, 0xF7, 0xD8, 0x48, 0xFF, 0xC1, 0x48, 0xFF, 0xCA]
+        );
+    }
+
+    #[test]
+    fn fma3_encodes_vex_forms() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.vfmadd231sd(&mut ctx, arch, &Reg(0), &Reg(1), &Reg(2)).unwrap(); // vfmadd231sd xmm0, xmm1, xmm2
+        w.vfnmsub231sd(&mut ctx, arch, &Reg(0), &Reg(1), &Reg(2)).unwrap(); // vfnmsub231sd xmm0, xmm1, xmm2
+        assert_eq!(
+            w.into_parts().0,
+            [0xC4, 0xE2, 0xF1, 0xB9, 0xC2, 0xC4, 0xE2, 0xF1, 0xBF, 0xC2]
+        );
+    }
+
+    #[test]
+    fn avx512_encodes_masks_and_broadcasts() {
+        use crate::out::evex::OpMask;
+        use crate::MaskReg;
+        let arch = crate::X64Arch { avx512: true, ..Default::default() };
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+        let zmm = |r| ArgKind::Reg { reg: Reg(r), size: MemorySize::_512 };
+        let qword = MemArgKind::Mem {
+            base: ArgKind::Reg { reg: Reg(0), size: MemorySize::_64 },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_64,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: crate::out::arg::Segment::None,
+        };
+
+        w.kmov(&mut ctx, arch, MaskReg(1), &Reg(0)).unwrap(); // kmovq k1, rax
+        w.vaddpd(&mut ctx, arch, &zmm(0), OpMask::zeroing(MaskReg(1)), &zmm(1), &zmm(2), false).unwrap(); // vaddpd zmm0{k1}{z}, zmm1, zmm2
+        w.vaddpd(&mut ctx, arch, &zmm(0), OpMask::merging(MaskReg(1)), &zmm(1), &qword, true).unwrap(); // vaddpd zmm0{k1}, zmm1, qword ptr [rax]{1to8}
+        assert_eq!(
+            w.into_parts().0,
+            [
+                0xC4, 0xE1, 0xFB, 0x92, 0xC8,
+                0x62, 0xF1, 0xF5, 0xC9, 0x58, 0xC2,
+                0x62, 0xF1, 0xF5, 0x59, 0x58, 0x00,
+            ]
+        );
+    }
+
+    #[test]
+    fn sse_compare_sqrt_and_conversions_encode() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+        let eax = ArgKind::Reg { reg: Reg(0), size: MemorySize::_32 };
+
+        w.fcmp(&mut ctx, arch, &Reg(0), &Reg(1)).unwrap(); // ucomisd xmm0, xmm1
+        w.fsqrt(&mut ctx, arch, &Reg(0), &Reg(1)).unwrap(); // sqrtsd xmm0, xmm1
+        w.cvtsi2sd(&mut ctx, arch, &Reg(0), &Reg(0)).unwrap(); // cvtsi2sd xmm0, rax
+        w.cvttsd2si(&mut ctx, arch, &eax, &Reg(0)).unwrap(); // cvttsd2si eax, xmm0
+        assert_eq!(
+            w.into_parts().0,
+            [
+                0x66, 0x0F, 0x2E, 0xC1,
+                0xF2, 0x0F, 0x51, 0xC1,
+                0xF2, 0x48, 0x0F, 0x2A, 0xC0,
+                0xF2, 0x0F, 0x2C, 0xC0,
+            ]
+        );
+    }
+
+    #[test]
+    fn single_precision_sse_encodes() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+        let eax = ArgKind::Reg { reg: Reg(0), size: MemorySize::_32 };
+
+        w.addss(&mut ctx, arch, &Reg(0), &Reg(1)).unwrap(); // addss xmm0, xmm1
+        w.ucomiss(&mut ctx, arch, &Reg(0), &Reg(1)).unwrap(); // ucomiss xmm0, xmm1
+        w.movss(&mut ctx, arch, &Reg(0), &Reg(1)).unwrap(); // movss xmm0, xmm1
+        w.cvtss2sd(&mut ctx, arch, &Reg(0), &Reg(1)).unwrap(); // cvtss2sd xmm0, xmm1
+        w.cvtsd2ss(&mut ctx, arch, &Reg(0), &Reg(1)).unwrap(); // cvtsd2ss xmm0, xmm1
+        w.cvttss2si(&mut ctx, arch, &eax, &Reg(0)).unwrap(); // cvttss2si eax, xmm0
+        assert_eq!(
+            w.into_parts().0,
+            [
+                0xF3, 0x0F, 0x58, 0xC1,
+                0x0F, 0x2E, 0xC1,
+                0xF3, 0x0F, 0x10, 0xC1,
+                0xF3, 0x0F, 0x5A, 0xC1,
+                0xF2, 0x0F, 0x5A, 0xC1,
+                0xF3, 0x0F, 0x2C, 0xC0,
+            ]
+        );
+    }
+
+    #[test]
+    fn mxcsr_load_store_encodes() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+        let mem = MemArgKind::Mem {
+            base: ArgKind::Reg { reg: Reg(0), size: MemorySize::_64 },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_32,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: crate::out::arg::Segment::None,
+        };
+
+        w.ldmxcsr(&mut ctx, arch, &mem).unwrap(); // ldmxcsr [rax]
+        w.stmxcsr(&mut ctx, arch, &mem).unwrap(); // stmxcsr [rax]
+        assert_eq!(w.into_parts().0, [0x0F, 0xAE, 0x10, 0x0F, 0xAE, 0x18]);
+    }
+
+    #[test]
+    fn imul3_picks_the_immediate_width() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.imul3(&mut ctx, arch, &Reg(0), &Reg(1), 10).unwrap(); // imul rax, rcx, 10
+        w.imul3(&mut ctx, arch, &Reg(0), &Reg(1), 1000).unwrap(); // imul rax, rcx, 1000
+        assert_eq!(
+            w.into_parts().0,
+            [0x48, 0x6B, 0xC1, 0x0A, 0x48, 0x69, 0xC1, 0xE8, 0x03, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn imul2_encodes_the_two_operand_form() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.imul2(&mut ctx, arch, &Reg(0), &Reg(1)).unwrap(); // imul rax, rcx
+        assert_eq!(w.into_parts().0, [0x48, 0x0F, 0xAF, 0xC1]);
+    }
+
+    #[test]
+    fn segment_base_access_encodes() {
+        let arch = crate::X64Arch { fsgsbase: true, ..Default::default() };
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+        let seg = crate::out::arg::Segment::Fs;
+
+        w.rdsegbase(&mut ctx, arch, seg, &Reg(0)).unwrap(); // rdfsbase rax
+        w.wrsegbase(&mut ctx, arch, crate::out::arg::Segment::Gs, &Reg(1)).unwrap(); // wrgsbase rcx
+        assert_eq!(w.into_parts().0, [0xF3, 0x48, 0x0F, 0xAE, 0xC0, 0xF3, 0x48, 0x0F, 0xAE, 0xD9]);
+    }
+
+    #[test]
+    fn imm_encoding_forces_immediate_width() {
+        let imm32 = crate::X64Arch { imm_encoding: crate::ImmEncoding::Imm32, ..Default::default() };
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.add_imm(&mut ctx, Default::default(), &Reg(0), 1).unwrap(); // add rax, 1 (imm8)
+        w.add_imm(&mut ctx, imm32, &Reg(0), 1).unwrap(); // add rax, 1 (imm32)
+        w.mov(&mut ctx, imm32, &Reg(0), &1u64).unwrap(); // mov rax, 1 (imm32)
+        w.mov64(&mut ctx, imm32, &Reg(1), u64::MAX).unwrap(); // mov rcx, -1 (imm32)
+        assert_eq!(
+            w.into_parts().0,
+            [
+                0x48, 0x83, 0xC0, 0x01,
+                0x48, 0x81, 0xC0, 0x01, 0x00, 0x00, 0x00,
+                0x48, 0xC7, 0xC0, 0x01, 0x00, 0x00, 0x00,
+                0x48, 0xC7, 0xC1, 0xFF, 0xFF, 0xFF, 0xFF,
+            ]
+        );
+    }
+
+    #[test]
+    fn high_byte_registers_encode_without_rex() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+        let bl = ArgKind::Reg { reg: Reg(3), size: MemorySize::_8 };
+        let eax = ArgKind::Reg { reg: Reg(0), size: MemorySize::_32 };
+
+        w.mov(&mut ctx, arch, &ArgKind::AH, &bl).unwrap(); // mov ah, bl
+        w.mov(&mut ctx, arch, &ArgKind::CH, &0x12u64).unwrap(); // mov ch, 0x12
+        w.movzx(&mut ctx, arch, &eax, &ArgKind::AH).unwrap(); // movzx eax, ah
+        assert_eq!(w.into_parts().0, [0x8A, 0xE3, 0xB5, 0x12, 0x0F, 0xB6, 0xC4]);
+    }
+
+    #[test]
+    fn symbol_operands_record_relocations() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0x1000);
+        let rax = Reg(0);
+
+        w.call(&mut ctx, arch, &ArgKind::sym("puts")).unwrap(); // call puts
+        w.mov(&mut ctx, arch, &rax, &ArgKind::Sym { name: "table", addend: 16 }).unwrap(); // movabs rax, table+16
+        w.lea(&mut ctx, arch, &rax, &MemArgKind::sym("counter", MemorySize::_64)).unwrap(); // lea rax, [rip + counter]
+
+        assert_eq!(w.relocations(), [
+            Relocation { offset: 1,  kind: RelocKind::Plt32, symbol: "puts",    addend: -4 },
+            Relocation { offset: 7,  kind: RelocKind::Abs64, symbol: "table",   addend: 16 },
+            Relocation { offset: 18, kind: RelocKind::Pc32,  symbol: "counter", addend: -4 },
+        ]);
+        assert_eq!(w.into_bytes(), [
+            0xE8, 0, 0, 0, 0,
+            0x48, 0xB8, 0, 0, 0, 0, 0, 0, 0, 0,
+            0x48, 0x8D, 0x05, 0, 0, 0, 0,
+        ]);
+    }
+
+    #[test]
+    fn pic_moves_symbol_addresses_rip_relative() {
+        let arch = crate::X64Arch { pic: true, ..Default::default() };
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.mov(&mut ctx, arch, &Reg(0), &ArgKind::Sym { name: "table", addend: 16 }).unwrap(); // lea rax, [rip + table+16]
+
+        assert_eq!(w.relocations(), [Relocation { offset: 3, kind: RelocKind::Pc32, symbol: "table", addend: 12 }]);
+        assert_eq!(w.into_bytes(), [0x48, 0x8D, 0x05, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn nops_use_multi_byte_forms_and_align() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.hlt(&mut ctx, arch).unwrap();
+        w.align(&mut ctx, arch, 4).unwrap();
+        assert_eq!(w.offset(), 16);
+        w.nops(&mut ctx, arch, 11).unwrap();
+        w.nop(&mut ctx, arch).unwrap();
+
+        let bytes = w.into_bytes();
+        // 15 bytes of padding: a 9-byte and a 6-byte NOP
+        assert_eq!(bytes[1..10], *crate::out::NOPS[8]);
+        assert_eq!(bytes[10..16], *crate::out::NOPS[5]);
+        // 11 bytes: 9 + 2
+        assert_eq!(bytes[16..25], *crate::out::NOPS[8]);
+        assert_eq!(bytes[25..27], [0x66, 0x90]);
+        assert_eq!(bytes[27..], [0x90]);
+    }
+
+    #[test]
+    fn dd_label_is_relative_to_the_word() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0);
+
+        w.set_label(&mut ctx, arch, 1u32).unwrap();
+        w.hlt(&mut ctx, arch).unwrap();
+        w.dd_label(&mut ctx, arch, 1u32).unwrap(); // backward: 0 - 1
+        w.dd_label(&mut ctx, arch, 2u32).unwrap(); // forward: 10 - 5
+        w.hlt(&mut ctx, arch).unwrap();
+        w.set_label(&mut ctx, arch, 2u32).unwrap();
+
+        let bytes = w.into_bytes();
+        assert_eq!(bytes[1..5], (-1i32).to_le_bytes());
+        assert_eq!(bytes[5..9], 5i32.to_le_bytes());
+    }
+
+    #[test]
+    fn rip_relative_base_less_and_negative_memory() {
+        let arch = crate::X64Arch::default();
+        let mut ctx = ();
+        let mut w: IcedWriter<u32> = IcedWriter::new(0x1000);
+
+        w.mov(&mut ctx, arch, &Reg(0), &MemArgKind::rip(16, MemorySize::_64)).unwrap(); // mov rax, [rip+16]
+        w.mov(&mut ctx, arch, &Reg(0), &MemArgKind::indexed(Reg(1), 4, 8, MemorySize::_64)).unwrap(); // mov rax, [rcx*4+8]
+        let rbp = ArgKind::Reg { reg: Reg(5), size: MemorySize::_64 };
+        w.mov(&mut ctx, arch, &Reg(0), &MemArgKind::Mem {
+            base: rbp,
+            offset: None,
+            disp: -8,
+            size: MemorySize::_64,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: crate::out::arg::Segment::None,
+        }).unwrap(); // mov rax, [rbp-8]
+        assert_eq!(w.into_bytes(), [
+            0x48, 0x8B, 0x05, 0x10, 0, 0, 0,
+            0x48, 0x8B, 0x04, 0x8D, 0x08, 0, 0, 0,
+            0x48, 0x8B, 0x45, 0xF8,
+        ]);
+    }
 }
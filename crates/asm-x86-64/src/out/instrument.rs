@@ -0,0 +1,287 @@
+//! Profiling probes at function entries, basic blocks and call sites.
+//!
+//! [`InstrumentingWriter`] wraps a writer and injects a [`Probe`] at three
+//! kinds of event, each configured separately:
+//!
+//! - function entries, defined with [`InstrumentingWriter::function_entry`];
+//! - every other label passed to [`set_label`](Writer::set_label), which
+//!   starts a basic block;
+//! - calls made with [`call_label`](Writer::call_label).
+//!
+//! Events are numbered in emission order, shared by all three kinds. A
+//! [`Probe::Count`] probe increments the event's own 64-bit slot in a
+//! counter table, so wrapping a writer gives block-level execution counts
+//! without touching the emitter:
+//!
+//! ```ignore
+//! let mut writer = InstrumentingWriter::new(writer).with_blocks(Probe::Count { base: Reg(15), disp: 0 });
+//! // ... emit ...
+//! let slots = writer.event_count(); // the table needs this many u64s
+//! ```
+//!
+//! Probes clobber r11 and preserve the flags, moving values with `mov` and
+//! `lea` only. Calls through [`call`](WriterCore::call) are not call-site
+//! events. A probe lands after the label, so a label that must start with
+//! an `endbr64` landing pad should not be instrumented.
+
+use core::ops::{Deref, DerefMut};
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::arg::{ArgKind, MemArgKind, Segment};
+use crate::out::{Writer, WriterCore};
+use crate::{RegisterClass, X64Arch};
+
+/// Register probes load counters into and pass event numbers in: `r11`.
+const SCRATCH: Reg = Reg(11);
+
+/// `[base + disp]`, a 64-bit operand.
+fn qword(base: Reg, disp: i32) -> MemArgKind<ArgKind> {
+    MemArgKind::Mem {
+        base: ArgKind::Reg {
+            reg: base,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp,
+        size: MemorySize::_64,
+        reg_class: RegisterClass::Gpr,
+        segment: Segment::None,
+    }
+}
+
+/// Code injected at an instrumented event.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Probe {
+    /// Nothing is injected.
+    #[default]
+    Off,
+    /// Increments the 64-bit counter of event `n` at `[base + disp + 8 * n]`.
+    ///
+    /// `base` must hold the table address wherever a probe runs.
+    Count {
+        /// Register holding the counter table address.
+        base: Reg,
+        /// Byte offset of event 0's counter from `base`.
+        disp: u32,
+    },
+    /// Calls the routine `symbol` with the event number in r11.
+    ///
+    /// The routine must preserve every register except r11, and the flags.
+    /// Its return address is pushed below `rsp`, so code that keeps data in
+    /// the SysV red zone must not be instrumented with calls.
+    Call(&'static str),
+}
+
+/// Injects [`Probe`]s at function entries, block labels and call sites.
+pub struct InstrumentingWriter<W> {
+    writer: W,
+    function_entries: Probe,
+    blocks: Probe,
+    call_sites: Probe,
+    events: usize,
+}
+
+impl<W> InstrumentingWriter<W> {
+    /// Wraps `writer` with every probe [`Off`](Probe::Off).
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            function_entries: Probe::Off,
+            blocks: Probe::Off,
+            call_sites: Probe::Off,
+            events: 0,
+        }
+    }
+
+    /// Sets the probe run at function entries.
+    pub fn with_function_entries(self, probe: Probe) -> Self {
+        Self {
+            function_entries: probe,
+            ..self
+        }
+    }
+
+    /// Sets the probe run at basic block labels.
+    pub fn with_blocks(self, probe: Probe) -> Self {
+        Self {
+            blocks: probe,
+            ..self
+        }
+    }
+
+    /// Sets the probe run before call sites.
+    pub fn with_call_sites(self, probe: Probe) -> Self {
+        Self {
+            call_sites: probe,
+            ..self
+        }
+    }
+
+    /// Returns how many events have been numbered, which is the number of
+    /// slots a counter table needs.
+    pub fn event_count(&self) -> usize {
+        self.events
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Emits `probe` for the next event, if it is not off.
+    fn probe<Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        probe: Probe,
+    ) -> Result<(), W::Error>
+    where
+        W: WriterCore<Context>,
+    {
+        let event = self.events;
+        match probe {
+            Probe::Off => return Ok(()),
+            Probe::Count { base, disp } => {
+                let slot = qword(base, (disp + 8 * event as u32) as i32);
+                self.writer.mov(ctx, cfg, &SCRATCH, &slot)?;
+                self.writer.lea(ctx, cfg, &SCRATCH, &qword(SCRATCH, 1))?;
+                self.writer.mov(ctx, cfg, &slot, &SCRATCH)?;
+            }
+            Probe::Call(symbol) => {
+                self.writer.mov(ctx, cfg, &SCRATCH, &(event as u64))?;
+                self.writer.call(ctx, cfg, &ArgKind::sym(symbol))?;
+            }
+        }
+        self.events += 1;
+        Ok(())
+    }
+
+    /// Defines `label` as a function entry and runs the function-entry
+    /// probe.
+    pub fn function_entry<L, Context>(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        label: L,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context>,
+    {
+        self.writer.set_label(ctx, cfg, label)?;
+        self.probe(ctx, cfg, self.function_entries)
+    }
+}
+
+impl<W> Deref for InstrumentingWriter<W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        &self.writer
+    }
+}
+
+impl<W> DerefMut for InstrumentingWriter<W> {
+    fn deref_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+crate::writer_dispatch!(@core [ W: WriterCore<Context>, Context ] InstrumentingWriter<W> => W::Error [Context] [W]);
+
+impl<W, L, Context> Writer<L, Context> for InstrumentingWriter<W>
+where
+    W: Writer<L, Context>,
+{
+    fn set_label(&mut self, ctx: &mut Context, cfg: X64Arch, s: L) -> Result<(), Self::Error> {
+        self.writer.set_label(ctx, cfg, s)?;
+        self.probe(ctx, cfg, self.blocks)
+    }
+
+    fn lea_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn crate::out::arg::MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.lea_label(ctx, cfg, dest, label)
+    }
+
+    fn call_label(&mut self, ctx: &mut Context, cfg: X64Arch, label: L) -> Result<(), Self::Error> {
+        self.probe(ctx, cfg, self.call_sites)?;
+        self.writer.call_label(ctx, cfg, label)
+    }
+
+    fn jmp_label(&mut self, ctx: &mut Context, cfg: X64Arch, label: L) -> Result<(), Self::Error> {
+        self.writer.jmp_label(ctx, cfg, label)
+    }
+
+    fn jcc_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: crate::ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.writer.jcc_label(ctx, cfg, cc, label)
+    }
+
+    fn jcc_label_hinted(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: crate::ConditionCode,
+        label: L,
+        hint: crate::BranchHint,
+    ) -> Result<(), Self::Error> {
+        self.writer.jcc_label_hinted(ctx, cfg, cc, label, hint)
+    }
+
+    fn dd_label(&mut self, ctx: &mut Context, cfg: X64Arch, label: L) -> Result<(), Self::Error> {
+        self.writer.dd_label(ctx, cfg, label)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_events_are_numbered_across_kinds() {
+        let cfg = X64Arch::default();
+        let mut output = String::new();
+        let count = Probe::Count {
+            base: Reg(15),
+            disp: 0,
+        };
+        let mut writer = InstrumentingWriter::new(&mut output as &mut dyn Write)
+            .with_function_entries(count)
+            .with_call_sites(count);
+        writer.function_entry(&mut (), cfg, "main").unwrap();
+        // Blocks are not instrumented, so the label takes no event number.
+        writer.set_label(&mut (), cfg, "loop").unwrap();
+        writer.call_label(&mut (), cfg, "f").unwrap();
+        assert_eq!(writer.event_count(), 2);
+        drop(writer);
+        assert_eq!(
+            output,
+            "main:\nmov r11, qword ptr [r15+0]\nlea r11, qword ptr [r11+1]\nmov qword ptr [r15+0], r11\nloop:\n\
+             mov r11, qword ptr [r15+8]\nlea r11, qword ptr [r11+1]\nmov qword ptr [r15+8], r11\ncall f\n"
+        );
+    }
+
+    #[test]
+    fn test_call_probe_passes_event_number() {
+        let cfg = X64Arch::default();
+        let mut output = String::new();
+        let mut writer = InstrumentingWriter::new(&mut output as &mut dyn Write)
+            .with_call_sites(Probe::Call("__profile_hook"));
+        writer.call_label(&mut (), cfg, "f").unwrap();
+        drop(writer);
+        assert_eq!(output, "mov r11, 0\ncall __profile_hook\ncall f\n");
+    }
+}
@@ -0,0 +1,262 @@
+//! Jump tables for dense `switch` statements.
+//!
+//! A `switch` over a dense range of values is cheapest as an indexed jump:
+//! bounds-check the index once, load the target from a table and jump
+//! there. [`JumpTable`] emits both halves. Entries are 32-bit offsets from
+//! the entry to its case label (see
+//! [`dd_label`](crate::out::Writer::dd_label)), so the table needs no
+//! relocations and works in position-independent code:
+//!
+//! ```ignore
+//! let cases = [case0, case1, case2];
+//! let table = JumpTable::new(&cases, default);
+//! table.dispatch(&mut writer, ctx, cfg, &Reg(1), table_label)?;
+//! // cmp rcx, 3; jnb default
+//! // lea r11, [rip + table_label]; lea r11, qword ptr [r11+rcx*4+0]
+//! // movsx r10,dword ptr [r11+0]; add r11,r10; jmp r11
+//! // ... case bodies ...
+//! table.emit_table(&mut writer, ctx, cfg, table_label)?;
+//! ```
+//!
+//! The index is an unsigned 64-bit value in a register; values at or above
+//! the number of cases go to the default label. Each entry is relative to
+//! itself, so the dispatch first moves the base to the entry and then adds
+//! the entry's value to that address.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::Writer;
+use crate::out::arg::{ArgKind, MemArgKind, Segment};
+use crate::{ConditionCode, RegisterClass, X64Arch};
+
+/// Default scratch registers: `r11` and `r10`, which the SysV ABI leaves
+/// free across calls and does not use for arguments.
+pub const DEFAULT_SCRATCH: [Reg; 2] = [Reg(11), Reg(10)];
+
+/// A jump table over the labels of consecutive cases.
+pub struct JumpTable<'a, L> {
+    cases: &'a [L],
+    default: L,
+    scratch: [Reg; 2],
+}
+
+impl<'a, L: Clone> JumpTable<'a, L> {
+    /// Creates a table that jumps to `cases[index]`, or to `default` when
+    /// the index is out of range.
+    pub fn new(cases: &'a [L], default: L) -> Self {
+        Self {
+            cases,
+            default,
+            scratch: DEFAULT_SCRATCH,
+        }
+    }
+
+    /// Sets the two registers the dispatch sequence clobbers.
+    pub fn with_scratch(self, scratch: [Reg; 2]) -> Self {
+        Self { scratch, ..self }
+    }
+
+    /// Returns the number of cases.
+    pub fn len(&self) -> usize {
+        self.cases.len()
+    }
+
+    /// Returns `true` if the table has no cases.
+    pub fn is_empty(&self) -> bool {
+        self.cases.is_empty()
+    }
+
+    /// Emits the bounds check and the indexed jump through the table at
+    /// `table`.
+    ///
+    /// `index` must not be one of the scratch registers.
+    pub fn dispatch<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        index: &Reg,
+        table: L,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let [base, entry] = self.scratch;
+        match i32::try_from(self.cases.len()) {
+            Ok(len) => w.cmp_imm(ctx, cfg, index, len)?,
+            Err(_) => {
+                w.mov(ctx, cfg, &entry, &ArgKind::Lit(self.cases.len() as u64))?;
+                w.cmp(ctx, cfg, index, &entry)?;
+            }
+        }
+        w.jcc_label(ctx, cfg, ConditionCode::NB, self.default.clone())?;
+        w.lea_label(ctx, cfg, &base, table)?;
+        w.lea(ctx, cfg, &base, &entry_address(base, *index))?;
+        w.movsx(ctx, cfg, &entry, &table_entry(base))?;
+        w.add(ctx, cfg, &base, &entry)?;
+        w.jmp(ctx, cfg, &base)
+    }
+
+    /// Emits the table, 4-byte aligned, labelled `table`.
+    ///
+    /// The table is data: place it where execution cannot fall into it.
+    pub fn emit_table<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        table: L,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        w.align_to(ctx, cfg, 4)?;
+        w.set_label(ctx, cfg, table)?;
+        for case in self.cases {
+            w.dd_label(ctx, cfg, case.clone())?;
+        }
+        Ok(())
+    }
+}
+
+fn reg64(reg: Reg) -> ArgKind {
+    ArgKind::Reg {
+        reg,
+        size: MemorySize::_64,
+    }
+}
+
+/// The address `[base + index*4]` of entry `index`.
+fn entry_address(base: Reg, index: Reg) -> MemArgKind<ArgKind> {
+    MemArgKind::Mem {
+        base: reg64(base),
+        offset: Some((reg64(index), 4)),
+        disp: 0,
+        size: MemorySize::_64,
+        reg_class: RegisterClass::Gpr,
+        segment: Segment::None,
+    }
+}
+
+/// The 32-bit entry at `[addr]`.
+fn table_entry(addr: Reg) -> MemArgKind<ArgKind> {
+    MemArgKind::Mem {
+        base: reg64(addr),
+        offset: None,
+        disp: 0,
+        size: MemorySize::_32,
+        reg_class: RegisterClass::Gpr,
+        segment: Segment::None,
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_dispatch_and_table() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = X64Arch::default();
+        let cases = [".Lc0", ".Lc1"];
+        let table = JumpTable::new(&cases, ".Ldef");
+        table
+            .dispatch(&mut writer, &mut (), cfg, &Reg(1), ".Ltab")
+            .unwrap();
+        table
+            .emit_table(&mut writer, &mut (), cfg, ".Ltab")
+            .unwrap();
+        assert_eq!(
+            output,
+            "cmp rcx, 2\njnb .Ldef\nlea r11, [rip + .Ltab]\n\
+             lea r11, qword ptr [r11+rcx*4+0]\nmovsx r10,dword ptr [r11+0]\n\
+             add r11,r10\njmp r11\n\
+             .balign 4\n.Ltab:\n.4byte .Lc0 - .\n.4byte .Lc1 - .\n"
+        );
+    }
+
+    #[cfg(feature = "iced")]
+    #[test]
+    fn test_every_case_is_reached() {
+        use crate::out::WriterCore;
+        use crate::out::iced::IcedWriter;
+        use iced_x86::{Decoder, DecoderOptions, Mnemonic, OpKind, Register};
+
+        let cfg = X64Arch::default();
+        let cases = [1u32, 2, 3];
+        let table = JumpTable::new(&cases, 4);
+        let mut binary = IcedWriter::<u32>::new(0);
+        table
+            .dispatch(&mut binary, &mut (), cfg, &Reg(1), 5)
+            .unwrap();
+        for label in 1..=4 {
+            binary.set_label(&mut (), cfg, label).unwrap();
+            binary.hlt(&mut (), cfg).unwrap();
+        }
+        table.emit_table(&mut binary, &mut (), cfg, 5).unwrap();
+        let (code, labels) = binary.into_parts();
+
+        // Runs the dispatch with `rcx = index` up to its indirect jump and
+        // returns the jump's target.
+        let target = |index: u64| {
+            let mut regs = [0u64; 256];
+            regs[Register::RCX as usize] = index;
+            let mut decoder = Decoder::with_ip(64, &code, 0, DecoderOptions::NONE);
+            loop {
+                let insn = decoder.decode();
+                let address = match insn.is_ip_rel_memory_operand() {
+                    true => insn.ip_rel_memory_address(),
+                    false => regs[insn.memory_base() as usize]
+                        .wrapping_add(
+                            regs[insn.memory_index() as usize] * insn.memory_index_scale() as u64,
+                        )
+                        .wrapping_add(insn.memory_displacement64()),
+                };
+                let dest = insn.op_register(0) as usize;
+                match insn.mnemonic() {
+                    // The index is in range.
+                    Mnemonic::Cmp | Mnemonic::Jae => {}
+                    Mnemonic::Lea => regs[dest] = address,
+                    Mnemonic::Movsxd => {
+                        let at = address as usize;
+                        let entry = i32::from_le_bytes(code[at..at + 4].try_into().unwrap());
+                        regs[dest] = entry as i64 as u64;
+                    }
+                    Mnemonic::Add => {
+                        regs[dest] = regs[dest].wrapping_add(regs[insn.op_register(1) as usize])
+                    }
+                    Mnemonic::Jmp if insn.op_kind(0) == OpKind::Register => {
+                        return regs[dest] as usize;
+                    }
+                    other => panic!("unexpected {other:?} in the dispatch"),
+                }
+            }
+        };
+        for (index, case) in cases.iter().enumerate() {
+            assert_eq!(target(index as u64), labels[case], "case {index}");
+        }
+    }
+
+    #[test]
+    fn test_large_tables_compare_against_a_register() {
+        /// A zero-sized label, so a huge table takes no memory.
+        #[derive(Clone, Copy)]
+        struct Case;
+        impl core::fmt::Display for Case {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str(".Lc")
+            }
+        }
+
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cases = [Case; 1 << 31];
+        JumpTable::new(&cases, Case)
+            .dispatch(&mut writer, &mut (), X64Arch::default(), &Reg(1), Case)
+            .unwrap();
+        assert!(output.starts_with("mov r10, 2147483648\ncmp rcx, r10\njnb .Lc\n"));
+    }
+}
@@ -0,0 +1,165 @@
+//! Patchable call sites.
+//!
+//! JITs that tier up, hot-patch, or lazily bind functions need call sites
+//! whose target can be changed while other threads may be executing them.
+//! [`emit_patchpoint`] emits a call of fixed length that goes through an
+//! 8-byte target literal embedded in the sequence:
+//!
+//! ```text
+//! +0   ff 15 02 00 00 00   call qword ptr [rip + 2]
+//! +6   eb 08               jmp +8
+//! +8   <8-byte target>
+//! +16
+//! ```
+//!
+//! The sequence is 8-byte aligned, so the literal is a naturally aligned
+//! 64-bit word and can be rewritten with one atomic store. Only data
+//! changes, so no instruction bytes are modified and no cross-modifying
+//! code synchronization is needed. The encoding does not depend on the
+//! site's address, and the target may be anywhere in the address space.
+//!
+//! ```ignore
+//! let site = emit_patchpoint(&mut writer, ctx, cfg, slow_path as u64)?;
+//! // ... after the code is mapped at `base`:
+//! unsafe { site.retarget_live(base, fast_path as u64) };
+//! ```
+
+use crate::X64Arch;
+use crate::out::WriterCore;
+
+/// A patchable call site emitted by [`emit_patchpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Patchpoint {
+    /// Byte offset of the sequence in the writer's output, if the writer
+    /// tracks offsets (see [`WriterCore::current_offset`]).
+    pub offset: Option<usize>,
+}
+
+impl Patchpoint {
+    /// Length of the sequence in bytes.
+    pub const LEN: usize = 16;
+    /// Alignment of the sequence in bytes.
+    pub const ALIGN: usize = 8;
+    /// Offset of the 8-byte little-endian target literal within the
+    /// sequence.
+    pub const TARGET_OFFSET: usize = 8;
+
+    /// Returns the sequence's bytes for `target`.
+    pub fn encode(target: u64) -> [u8; Self::LEN] {
+        let mut bytes = [0; Self::LEN];
+        bytes[..8].copy_from_slice(&[0xff, 0x15, 0x02, 0x00, 0x00, 0x00, 0xeb, 0x08]);
+        bytes[Self::TARGET_OFFSET..].copy_from_slice(&target.to_le_bytes());
+        bytes
+    }
+
+    /// Byte offset of the target literal in the writer's output, if known.
+    pub fn target_offset(&self) -> Option<usize> {
+        self.offset.map(|o| o + Self::TARGET_OFFSET)
+    }
+
+    /// Sets the target in an output buffer that is not executing yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the site's offset is unknown or out of bounds of `code`.
+    pub fn retarget(&self, code: &mut [u8], target: u64) {
+        let at = self.target_offset().expect("patchpoint offset not tracked");
+        code[at..at + 8].copy_from_slice(&target.to_le_bytes());
+    }
+
+    /// Atomically sets the target of the site in code mapped at `base`.
+    ///
+    /// Threads executing the call concurrently branch either to the old or
+    /// to the new target.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the site's offset is unknown.
+    ///
+    /// # Safety
+    ///
+    /// `base` must point to the start of this writer's output, mapped
+    /// writable, and the site must not have been moved since emission.
+    #[cfg(target_has_atomic = "64")]
+    pub unsafe fn retarget_live(&self, base: *mut u8, target: u64) {
+        use core::sync::atomic::{AtomicU64, Ordering};
+        let at = self.target_offset().expect("patchpoint offset not tracked");
+        // SAFETY: the literal is 8-byte aligned inside the caller's mapping.
+        let slot = unsafe { AtomicU64::from_ptr(base.add(at).cast()) };
+        slot.store(target.to_le(), Ordering::Release);
+    }
+}
+
+/// Emits a patchable call to the absolute address `target`.
+///
+/// The writer is aligned to [`Patchpoint::ALIGN`] first, so padding may
+/// precede the sequence. `target` may be a placeholder, such as 0, if the
+/// site is retargeted before it can execute.
+pub fn emit_patchpoint<W, Context>(
+    w: &mut W,
+    ctx: &mut Context,
+    cfg: X64Arch,
+    target: u64,
+) -> Result<Patchpoint, W::Error>
+where
+    W: WriterCore<Context> + ?Sized,
+{
+    w.align_to(ctx, cfg, Patchpoint::ALIGN)?;
+    let offset = w.current_offset();
+    w.db(ctx, cfg, &Patchpoint::encode(target))?;
+    Ok(Patchpoint { offset })
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "iced")]
+    #[test]
+    fn test_call_reads_the_literal_and_jumps_over_it() {
+        use crate::out::iced::IcedWriter;
+        use iced_x86::{Decoder, DecoderOptions, Mnemonic};
+
+        let cfg = X64Arch::default();
+        let mut binary = IcedWriter::<u32>::new(0);
+        binary.hlt(&mut (), cfg).unwrap();
+        let site = emit_patchpoint(&mut binary, &mut (), cfg, 0).unwrap();
+        let (mut code, _) = binary.into_parts();
+        assert_eq!(site.offset, Some(8));
+        site.retarget(&mut code, 0x1122_3344_5566_7788);
+
+        let mut decoder = Decoder::with_ip(64, &code[8..], 8, DecoderOptions::NONE);
+        let call = decoder.decode();
+        assert_eq!(call.mnemonic(), Mnemonic::Call);
+        let literal = call.memory_displacement64() as usize;
+        assert_eq!(Some(literal), site.target_offset());
+        assert_eq!(
+            code[literal..literal + 8],
+            0x1122_3344_5566_7788u64.to_le_bytes()
+        );
+        let jmp = decoder.decode();
+        assert_eq!(jmp.mnemonic(), Mnemonic::Jmp);
+        assert_eq!(jmp.near_branch64() as usize, 8 + Patchpoint::LEN);
+    }
+
+    #[test]
+    fn test_retarget_rewrites_only_the_literal() {
+        let site = Patchpoint { offset: Some(8) };
+        let mut code = [0u8; 24];
+        code[8..].copy_from_slice(&Patchpoint::encode(0));
+        site.retarget(&mut code, 0xdead_beef);
+        assert_eq!(code[8..16], Patchpoint::encode(0)[..8]);
+        assert_eq!(code[16..], 0xdead_beefu64.to_le_bytes());
+    }
+
+    #[cfg(target_has_atomic = "64")]
+    #[test]
+    fn test_retarget_live_stores_the_literal() {
+        let site = Patchpoint { offset: Some(8) };
+        let mut words = [0u64; 3];
+        // SAFETY: `words` is 8-byte aligned and covers the whole site.
+        unsafe { site.retarget_live(words.as_mut_ptr().cast(), 0xdead_beef) };
+        assert_eq!(u64::from_le(words[2]), 0xdead_beef);
+        assert!(words[..2].iter().all(|&w| w == 0));
+    }
+}
@@ -0,0 +1,119 @@
+//! Software prefetches.
+//!
+//! [`Prefetch::prefetch`] asks for the cache line holding an address to be
+//! brought in before it is used, picking the instruction from how much the
+//! data will be reused and whether it will be written:
+//!
+//! ```ignore
+//! writer.prefetch(ctx, cfg, &byte_at_rdi, Locality::High, PrefetchAccess::Read)?;
+//! // prefetcht0 byte ptr [rdi+0]
+//! writer.prefetch(ctx, cfg, &byte_at_rdi, Locality::NonTemporal, PrefetchAccess::Read)?;
+//! // prefetchnta byte ptr [rdi+0]
+//! ```
+//!
+//! The locality levels are those of GCC's `__builtin_prefetch`. Write
+//! prefetches are emitted as read prefetches, since the baseline has no
+//! write prefetch (`prefetchw` needs PRFCHW). The AArch64 and RISC-V crates
+//! provide the same helper with `prfm` and the Zicbop `prefetch.r` and
+//! `prefetch.w`.
+
+use crate::X64Arch;
+use crate::out::WriterCore;
+use crate::out::arg::MemArg;
+
+/// How much prefetched data will be reused, from least to most.
+///
+/// The levels match the `locality` argument of GCC's `__builtin_prefetch`,
+/// 0 to 3.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum Locality {
+    /// Used once, so it should not displace other data from the caches.
+    NonTemporal,
+    /// Reused a little; only the outermost cache needs to keep it.
+    Low,
+    /// Reused moderately; the second-level cache and above keep it.
+    Moderate,
+    /// Reused heavily; every cache level keeps it.
+    #[default]
+    High,
+}
+
+/// Whether prefetched data will be read or written.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub enum PrefetchAccess {
+    /// The data will be loaded.
+    #[default]
+    Read,
+    /// The data will be stored to, so the line is best fetched for ownership.
+    Write,
+}
+
+/// Emits software prefetches by locality.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Prefetch<Context>: WriterCore<Context> {
+    /// Hints that the cache line holding `mem` will soon be accessed as
+    /// `access`, with reuse `locality`.
+    ///
+    /// Prefetches never fault, so `mem` may be any address.
+    fn prefetch(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+        locality: Locality,
+        _access: PrefetchAccess,
+    ) -> Result<(), Self::Error> {
+        match locality {
+            Locality::NonTemporal => self.prefetchnta(ctx, cfg, mem),
+            Locality::Low => self.prefetcht2(ctx, cfg, mem),
+            Locality::Moderate => self.prefetcht1(ctx, cfg, mem),
+            Locality::High => self.prefetcht0(ctx, cfg, mem),
+        }
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Prefetch<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::RegisterClass;
+    use crate::out::arg::{ArgKind, MemArgKind, Segment};
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+    #[test]
+    fn test_locality_picks_the_hint() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        let cfg = X64Arch::default();
+        let mem = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(7),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 64,
+            size: MemorySize::_8,
+            reg_class: RegisterClass::Gpr,
+            segment: Segment::None,
+        };
+        for locality in [
+            Locality::High,
+            Locality::Moderate,
+            Locality::Low,
+            Locality::NonTemporal,
+        ] {
+            w.prefetch(&mut (), cfg, &mem, locality, PrefetchAccess::Write)
+                .unwrap();
+        }
+        assert_eq!(
+            output,
+            "prefetcht0 byte ptr [rdi+64]\nprefetcht1 byte ptr [rdi+64]\n\
+             prefetcht2 byte ptr [rdi+64]\nprefetchnta byte ptr [rdi+64]\n"
+        );
+    }
+}
@@ -0,0 +1,2279 @@
+//! Recorded instruction streams for replay.
+//!
+//! [`RecordingWriter`] emits nothing: it captures every call made to it as
+//! an owned [`Insn`], with operands converted to [`MemArgKind`]s. The list
+//! can then be replayed into any other writer, as many times as needed and
+//! after editing or reordering it, which suits multi-pass layout (a sizing
+//! pass, then emission) and caching generated sequences:
+//!
+//! ```ignore
+//! let mut rec = RecordingWriter::new();
+//! generate(&mut rec, &mut (), cfg)?;
+//! rec.replay(&mut sizer, &mut ctx, cfg)?;
+//! rec.replay(&mut binary, &mut ctx, cfg)?;
+//! ```
+//!
+//! The configuration and context passed while recording are dropped, and
+//! each replay supplies its own. Labels are cloned on every replay.
+//! Recording never fails, and the writer reports no offset.
+
+use alloc::vec::Vec;
+
+use portal_pc_asm_common::types::mem::MemorySize;
+
+use crate::out::arg::{MemArg, MemArgKind, Segment};
+use crate::out::error::EmitError;
+use crate::out::evex::OpMask;
+use crate::out::{Writer, WriterCore};
+use crate::{BranchHint, ConditionCode, MaskReg, RepPrefix, X64Arch};
+
+/// A recorded writer call.
+///
+/// Each variant names the [`WriterCore`] or [`Writer`] method it replays
+/// and holds that method's arguments after the configuration.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Insn<L> {
+    /// [`WriterCore::hlt`].
+    Hlt,
+    /// [`WriterCore::xchg`].
+    Xchg { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::mov`].
+    Mov { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::sub`].
+    Sub { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::add`].
+    Add { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::adc`].
+    Adc { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::sbb`].
+    Sbb { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::add_imm`].
+    AddImm { a: MemArgKind, imm: i32 },
+    /// [`WriterCore::sub_imm`].
+    SubImm { a: MemArgKind, imm: i32 },
+    /// [`WriterCore::movsx`].
+    Movsx { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::movzx`].
+    Movzx { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::push`].
+    Push { op: MemArgKind },
+    /// [`WriterCore::pop`].
+    Pop { op: MemArgKind },
+    /// [`WriterCore::pushf`].
+    Pushf,
+    /// [`WriterCore::popf`].
+    Popf,
+    /// [`WriterCore::lahf`].
+    Lahf,
+    /// [`WriterCore::sahf`].
+    Sahf,
+    /// [`WriterCore::call`].
+    Call { op: MemArgKind },
+    /// [`WriterCore::jmp`].
+    Jmp { op: MemArgKind },
+    /// [`WriterCore::cmp`].
+    Cmp { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::cmp_imm`].
+    CmpImm { a: MemArgKind, imm: i32 },
+    /// [`WriterCore::cmp0`].
+    Cmp0 { op: MemArgKind },
+    /// [`WriterCore::test`].
+    Test { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::test0`].
+    Test0 { op: MemArgKind },
+    /// [`WriterCore::cmovcc`].
+    Cmovcc {
+        cond: ConditionCode,
+        op: MemArgKind,
+        val: MemArgKind,
+    },
+    /// [`WriterCore::setcc`].
+    Setcc { cond: ConditionCode, op: MemArgKind },
+    /// [`WriterCore::not`].
+    Not { op: MemArgKind },
+    /// [`WriterCore::neg`].
+    Neg { op: MemArgKind },
+    /// [`WriterCore::inc`].
+    Inc { op: MemArgKind },
+    /// [`WriterCore::dec`].
+    Dec { op: MemArgKind },
+    /// [`WriterCore::lea`].
+    Lea { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::get_ip`].
+    GetIp,
+    /// [`WriterCore::ret`].
+    Ret,
+    /// [`WriterCore::mov64`].
+    Mov64 { r: MemArgKind, val: u64 },
+    /// [`WriterCore::mul`].
+    Mul { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::imul2`].
+    Imul2 { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::imul3`].
+    Imul3 {
+        dest: MemArgKind,
+        src: MemArgKind,
+        imm: i32,
+    },
+    /// [`WriterCore::mul_wide`].
+    MulWide {
+        hi: MemArgKind,
+        lo: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::imul_wide`].
+    ImulWide {
+        hi: MemArgKind,
+        lo: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::div`].
+    Div { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::idiv`].
+    Idiv { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::and`].
+    And { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::or`].
+    Or { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::eor`].
+    Eor { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::and_imm`].
+    AndImm { a: MemArgKind, imm: i32 },
+    /// [`WriterCore::or_imm`].
+    OrImm { a: MemArgKind, imm: i32 },
+    /// [`WriterCore::eor_imm`].
+    EorImm { a: MemArgKind, imm: i32 },
+    /// [`WriterCore::shl`].
+    Shl { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::shr`].
+    Shr { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::sar`].
+    Sar { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::rol`].
+    Rol { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::ror`].
+    Ror { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::popcnt`].
+    Popcnt { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::lzcnt`].
+    Lzcnt { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::tzcnt`].
+    Tzcnt { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::bswap`].
+    Bswap { op: MemArgKind },
+    /// [`WriterCore::fadd`].
+    Fadd { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fsub`].
+    Fsub { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fmul`].
+    Fmul { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fdiv`].
+    Fdiv { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fcmp`].
+    Fcmp { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::fmin`].
+    Fmin { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fmax`].
+    Fmax { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fsqrt`].
+    Fsqrt { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::fabs`].
+    Fabs {
+        dest: MemArgKind,
+        scratch: MemArgKind,
+    },
+    /// [`WriterCore::fneg`].
+    Fneg {
+        dest: MemArgKind,
+        scratch: MemArgKind,
+    },
+    /// [`WriterCore::cvtsi2sd`].
+    Cvtsi2sd { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::cvttsd2si`].
+    Cvttsd2si { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::vfmadd231sd`].
+    Vfmadd231sd {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::vfmsub231sd`].
+    Vfmsub231sd {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::vfnmadd231sd`].
+    Vfnmadd231sd {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::vfnmsub231sd`].
+    Vfnmsub231sd {
+        dest: MemArgKind,
+        a: MemArgKind,
+        b: MemArgKind,
+    },
+    /// [`WriterCore::kmov`].
+    Kmov { dest: MaskReg, src: MemArgKind },
+    /// [`WriterCore::vmovupd`].
+    Vmovupd {
+        dest: MemArgKind,
+        mask: OpMask,
+        src: MemArgKind,
+    },
+    /// [`WriterCore::vaddpd`].
+    Vaddpd {
+        dest: MemArgKind,
+        mask: OpMask,
+        a: MemArgKind,
+        b: MemArgKind,
+        broadcast: bool,
+    },
+    /// [`WriterCore::vsubpd`].
+    Vsubpd {
+        dest: MemArgKind,
+        mask: OpMask,
+        a: MemArgKind,
+        b: MemArgKind,
+        broadcast: bool,
+    },
+    /// [`WriterCore::vmulpd`].
+    Vmulpd {
+        dest: MemArgKind,
+        mask: OpMask,
+        a: MemArgKind,
+        b: MemArgKind,
+        broadcast: bool,
+    },
+    /// [`WriterCore::vdivpd`].
+    Vdivpd {
+        dest: MemArgKind,
+        mask: OpMask,
+        a: MemArgKind,
+        b: MemArgKind,
+        broadcast: bool,
+    },
+    /// [`WriterCore::vfmadd231pd`].
+    Vfmadd231pd {
+        dest: MemArgKind,
+        mask: OpMask,
+        a: MemArgKind,
+        b: MemArgKind,
+        broadcast: bool,
+    },
+    /// [`WriterCore::vcmppd`].
+    Vcmppd {
+        dest: MaskReg,
+        mask: OpMask,
+        a: MemArgKind,
+        b: MemArgKind,
+        predicate: u8,
+        broadcast: bool,
+    },
+    /// [`WriterCore::fmov`].
+    Fmov { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::addss`].
+    Addss { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::subss`].
+    Subss { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::mulss`].
+    Mulss { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::divss`].
+    Divss { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::ucomiss`].
+    Ucomiss { a: MemArgKind, b: MemArgKind },
+    /// [`WriterCore::minss`].
+    Minss { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::maxss`].
+    Maxss { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::sqrtss`].
+    Sqrtss { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::cvtsi2ss`].
+    Cvtsi2ss { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::cvttss2si`].
+    Cvttss2si { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::cvtss2sd`].
+    Cvtss2sd { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::cvtsd2ss`].
+    Cvtsd2ss { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::movss`].
+    Movss { dest: MemArgKind, src: MemArgKind },
+    /// [`WriterCore::ldmxcsr`].
+    Ldmxcsr { src: MemArgKind },
+    /// [`WriterCore::stmxcsr`].
+    Stmxcsr { dest: MemArgKind },
+    /// [`WriterCore::rdsegbase`].
+    Rdsegbase { seg: Segment, dest: MemArgKind },
+    /// [`WriterCore::wrsegbase`].
+    Wrsegbase { seg: Segment, src: MemArgKind },
+    /// [`WriterCore::fld`].
+    Fld { src: MemArgKind },
+    /// [`WriterCore::fst`].
+    Fst { dest: MemArgKind },
+    /// [`WriterCore::fstp`].
+    Fstp { dest: MemArgKind },
+    /// [`WriterCore::faddp`].
+    Faddp,
+    /// [`WriterCore::fsubp`].
+    Fsubp,
+    /// [`WriterCore::fmulp`].
+    Fmulp,
+    /// [`WriterCore::fdivp`].
+    Fdivp,
+    /// [`WriterCore::movs`].
+    Movs { size: MemorySize, rep: RepPrefix },
+    /// [`WriterCore::stos`].
+    Stos { size: MemorySize, rep: RepPrefix },
+    /// [`WriterCore::cmps`].
+    Cmps { size: MemorySize, rep: RepPrefix },
+    /// [`WriterCore::scas`].
+    Scas { size: MemorySize, rep: RepPrefix },
+    /// [`WriterCore::syscall`].
+    Syscall,
+    /// [`WriterCore::endbr64`].
+    Endbr64,
+    /// [`WriterCore::ud2`].
+    Ud2,
+    /// [`WriterCore::int3`].
+    Int3,
+    /// [`WriterCore::pause`].
+    Pause,
+    /// [`WriterCore::prefetcht0`].
+    Prefetcht0 { mem: MemArgKind },
+    /// [`WriterCore::prefetcht1`].
+    Prefetcht1 { mem: MemArgKind },
+    /// [`WriterCore::prefetcht2`].
+    Prefetcht2 { mem: MemArgKind },
+    /// [`WriterCore::prefetchnta`].
+    Prefetchnta { mem: MemArgKind },
+    /// [`WriterCore::clflush`].
+    Clflush { mem: MemArgKind },
+    /// [`WriterCore::clflushopt`].
+    Clflushopt { mem: MemArgKind },
+    /// [`WriterCore::clwb`].
+    Clwb { mem: MemArgKind },
+    /// [`WriterCore::sfence`].
+    Sfence,
+    /// [`WriterCore::rdtsc`].
+    Rdtsc,
+    /// [`WriterCore::rdtscp`].
+    Rdtscp,
+    /// [`WriterCore::unreachable`].
+    Unreachable,
+    /// [`WriterCore::debug_break`].
+    DebugBreak,
+    /// [`WriterCore::db`].
+    Db { bytes: Vec<u8> },
+    /// [`WriterCore::align_to`].
+    AlignTo { alignment: usize },
+    /// [`WriterCore::nop`].
+    Nop,
+    /// [`WriterCore::nops`].
+    Nops { n_bytes: usize },
+    /// [`WriterCore::align`].
+    Align { pow2: u32 },
+    /// [`Writer::set_label`].
+    SetLabel { label: L },
+    /// [`Writer::lea_label`].
+    LeaLabel { dest: MemArgKind, label: L },
+    /// [`Writer::call_label`].
+    CallLabel { label: L },
+    /// [`Writer::jmp_label`].
+    JmpLabel { label: L },
+    /// [`Writer::jcc_label`].
+    JccLabel { cc: ConditionCode, label: L },
+    /// [`Writer::jcc_label_hinted`].
+    JccLabelHinted {
+        cc: ConditionCode,
+        label: L,
+        hint: BranchHint,
+    },
+    /// [`Writer::dd_label`].
+    DdLabel { label: L },
+}
+
+impl<L: Clone> Insn<L> {
+    /// Makes the recorded call on `w`.
+    pub fn replay<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: X64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        match self {
+            Insn::Hlt => w.hlt(ctx, cfg),
+            Insn::Xchg { dest, src } => w.xchg(ctx, cfg, dest, src),
+            Insn::Mov { dest, src } => w.mov(ctx, cfg, dest, src),
+            Insn::Sub { a, b } => w.sub(ctx, cfg, a, b),
+            Insn::Add { a, b } => w.add(ctx, cfg, a, b),
+            Insn::Adc { a, b } => w.adc(ctx, cfg, a, b),
+            Insn::Sbb { a, b } => w.sbb(ctx, cfg, a, b),
+            Insn::AddImm { a, imm } => w.add_imm(ctx, cfg, a, *imm),
+            Insn::SubImm { a, imm } => w.sub_imm(ctx, cfg, a, *imm),
+            Insn::Movsx { dest, src } => w.movsx(ctx, cfg, dest, src),
+            Insn::Movzx { dest, src } => w.movzx(ctx, cfg, dest, src),
+            Insn::Push { op } => w.push(ctx, cfg, op),
+            Insn::Pop { op } => w.pop(ctx, cfg, op),
+            Insn::Pushf => w.pushf(ctx, cfg),
+            Insn::Popf => w.popf(ctx, cfg),
+            Insn::Lahf => w.lahf(ctx, cfg),
+            Insn::Sahf => w.sahf(ctx, cfg),
+            Insn::Call { op } => w.call(ctx, cfg, op),
+            Insn::Jmp { op } => w.jmp(ctx, cfg, op),
+            Insn::Cmp { a, b } => w.cmp(ctx, cfg, a, b),
+            Insn::CmpImm { a, imm } => w.cmp_imm(ctx, cfg, a, *imm),
+            Insn::Cmp0 { op } => w.cmp0(ctx, cfg, op),
+            Insn::Test { a, b } => w.test(ctx, cfg, a, b),
+            Insn::Test0 { op } => w.test0(ctx, cfg, op),
+            Insn::Cmovcc { cond, op, val } => w.cmovcc(ctx, cfg, *cond, op, val),
+            Insn::Setcc { cond, op } => w.setcc(ctx, cfg, *cond, op),
+            Insn::Not { op } => w.not(ctx, cfg, op),
+            Insn::Neg { op } => w.neg(ctx, cfg, op),
+            Insn::Inc { op } => w.inc(ctx, cfg, op),
+            Insn::Dec { op } => w.dec(ctx, cfg, op),
+            Insn::Lea { dest, src } => w.lea(ctx, cfg, dest, src),
+            Insn::GetIp => w.get_ip(ctx, cfg),
+            Insn::Ret => w.ret(ctx, cfg),
+            Insn::Mov64 { r, val } => w.mov64(ctx, cfg, r, *val),
+            Insn::Mul { a, b } => w.mul(ctx, cfg, a, b),
+            Insn::Imul2 { dest, src } => w.imul2(ctx, cfg, dest, src),
+            Insn::Imul3 { dest, src, imm } => w.imul3(ctx, cfg, dest, src, *imm),
+            Insn::MulWide { hi, lo, a, b } => w.mul_wide(ctx, cfg, hi, lo, a, b),
+            Insn::ImulWide { hi, lo, a, b } => w.imul_wide(ctx, cfg, hi, lo, a, b),
+            Insn::Div { a, b } => w.div(ctx, cfg, a, b),
+            Insn::Idiv { a, b } => w.idiv(ctx, cfg, a, b),
+            Insn::And { a, b } => w.and(ctx, cfg, a, b),
+            Insn::Or { a, b } => w.or(ctx, cfg, a, b),
+            Insn::Eor { a, b } => w.eor(ctx, cfg, a, b),
+            Insn::AndImm { a, imm } => w.and_imm(ctx, cfg, a, *imm),
+            Insn::OrImm { a, imm } => w.or_imm(ctx, cfg, a, *imm),
+            Insn::EorImm { a, imm } => w.eor_imm(ctx, cfg, a, *imm),
+            Insn::Shl { a, b } => w.shl(ctx, cfg, a, b),
+            Insn::Shr { a, b } => w.shr(ctx, cfg, a, b),
+            Insn::Sar { a, b } => w.sar(ctx, cfg, a, b),
+            Insn::Rol { a, b } => w.rol(ctx, cfg, a, b),
+            Insn::Ror { a, b } => w.ror(ctx, cfg, a, b),
+            Insn::Popcnt { dest, src } => w.popcnt(ctx, cfg, dest, src),
+            Insn::Lzcnt { dest, src } => w.lzcnt(ctx, cfg, dest, src),
+            Insn::Tzcnt { dest, src } => w.tzcnt(ctx, cfg, dest, src),
+            Insn::Bswap { op } => w.bswap(ctx, cfg, op),
+            Insn::Fadd { dest, src } => w.fadd(ctx, cfg, dest, src),
+            Insn::Fsub { dest, src } => w.fsub(ctx, cfg, dest, src),
+            Insn::Fmul { dest, src } => w.fmul(ctx, cfg, dest, src),
+            Insn::Fdiv { dest, src } => w.fdiv(ctx, cfg, dest, src),
+            Insn::Fcmp { a, b } => w.fcmp(ctx, cfg, a, b),
+            Insn::Fmin { dest, src } => w.fmin(ctx, cfg, dest, src),
+            Insn::Fmax { dest, src } => w.fmax(ctx, cfg, dest, src),
+            Insn::Fsqrt { dest, src } => w.fsqrt(ctx, cfg, dest, src),
+            Insn::Fabs { dest, scratch } => w.fabs(ctx, cfg, dest, scratch),
+            Insn::Fneg { dest, scratch } => w.fneg(ctx, cfg, dest, scratch),
+            Insn::Cvtsi2sd { dest, src } => w.cvtsi2sd(ctx, cfg, dest, src),
+            Insn::Cvttsd2si { dest, src } => w.cvttsd2si(ctx, cfg, dest, src),
+            Insn::Vfmadd231sd { dest, a, b } => w.vfmadd231sd(ctx, cfg, dest, a, b),
+            Insn::Vfmsub231sd { dest, a, b } => w.vfmsub231sd(ctx, cfg, dest, a, b),
+            Insn::Vfnmadd231sd { dest, a, b } => w.vfnmadd231sd(ctx, cfg, dest, a, b),
+            Insn::Vfnmsub231sd { dest, a, b } => w.vfnmsub231sd(ctx, cfg, dest, a, b),
+            Insn::Kmov { dest, src } => w.kmov(ctx, cfg, *dest, src),
+            Insn::Vmovupd { dest, mask, src } => w.vmovupd(ctx, cfg, dest, *mask, src),
+            Insn::Vaddpd {
+                dest,
+                mask,
+                a,
+                b,
+                broadcast,
+            } => w.vaddpd(ctx, cfg, dest, *mask, a, b, *broadcast),
+            Insn::Vsubpd {
+                dest,
+                mask,
+                a,
+                b,
+                broadcast,
+            } => w.vsubpd(ctx, cfg, dest, *mask, a, b, *broadcast),
+            Insn::Vmulpd {
+                dest,
+                mask,
+                a,
+                b,
+                broadcast,
+            } => w.vmulpd(ctx, cfg, dest, *mask, a, b, *broadcast),
+            Insn::Vdivpd {
+                dest,
+                mask,
+                a,
+                b,
+                broadcast,
+            } => w.vdivpd(ctx, cfg, dest, *mask, a, b, *broadcast),
+            Insn::Vfmadd231pd {
+                dest,
+                mask,
+                a,
+                b,
+                broadcast,
+            } => w.vfmadd231pd(ctx, cfg, dest, *mask, a, b, *broadcast),
+            Insn::Vcmppd {
+                dest,
+                mask,
+                a,
+                b,
+                predicate,
+                broadcast,
+            } => w.vcmppd(ctx, cfg, *dest, *mask, a, b, *predicate, *broadcast),
+            Insn::Fmov { dest, src } => w.fmov(ctx, cfg, dest, src),
+            Insn::Addss { dest, src } => w.addss(ctx, cfg, dest, src),
+            Insn::Subss { dest, src } => w.subss(ctx, cfg, dest, src),
+            Insn::Mulss { dest, src } => w.mulss(ctx, cfg, dest, src),
+            Insn::Divss { dest, src } => w.divss(ctx, cfg, dest, src),
+            Insn::Ucomiss { a, b } => w.ucomiss(ctx, cfg, a, b),
+            Insn::Minss { dest, src } => w.minss(ctx, cfg, dest, src),
+            Insn::Maxss { dest, src } => w.maxss(ctx, cfg, dest, src),
+            Insn::Sqrtss { dest, src } => w.sqrtss(ctx, cfg, dest, src),
+            Insn::Cvtsi2ss { dest, src } => w.cvtsi2ss(ctx, cfg, dest, src),
+            Insn::Cvttss2si { dest, src } => w.cvttss2si(ctx, cfg, dest, src),
+            Insn::Cvtss2sd { dest, src } => w.cvtss2sd(ctx, cfg, dest, src),
+            Insn::Cvtsd2ss { dest, src } => w.cvtsd2ss(ctx, cfg, dest, src),
+            Insn::Movss { dest, src } => w.movss(ctx, cfg, dest, src),
+            Insn::Ldmxcsr { src } => w.ldmxcsr(ctx, cfg, src),
+            Insn::Stmxcsr { dest } => w.stmxcsr(ctx, cfg, dest),
+            Insn::Rdsegbase { seg, dest } => w.rdsegbase(ctx, cfg, *seg, dest),
+            Insn::Wrsegbase { seg, src } => w.wrsegbase(ctx, cfg, *seg, src),
+            Insn::Fld { src } => w.fld(ctx, cfg, src),
+            Insn::Fst { dest } => w.fst(ctx, cfg, dest),
+            Insn::Fstp { dest } => w.fstp(ctx, cfg, dest),
+            Insn::Faddp => w.faddp(ctx, cfg),
+            Insn::Fsubp => w.fsubp(ctx, cfg),
+            Insn::Fmulp => w.fmulp(ctx, cfg),
+            Insn::Fdivp => w.fdivp(ctx, cfg),
+            Insn::Movs { size, rep } => w.movs(ctx, cfg, *size, *rep),
+            Insn::Stos { size, rep } => w.stos(ctx, cfg, *size, *rep),
+            Insn::Cmps { size, rep } => w.cmps(ctx, cfg, *size, *rep),
+            Insn::Scas { size, rep } => w.scas(ctx, cfg, *size, *rep),
+            Insn::Syscall => w.syscall(ctx, cfg),
+            Insn::Endbr64 => w.endbr64(ctx, cfg),
+            Insn::Ud2 => w.ud2(ctx, cfg),
+            Insn::Int3 => w.int3(ctx, cfg),
+            Insn::Pause => w.pause(ctx, cfg),
+            Insn::Prefetcht0 { mem } => w.prefetcht0(ctx, cfg, mem),
+            Insn::Prefetcht1 { mem } => w.prefetcht1(ctx, cfg, mem),
+            Insn::Prefetcht2 { mem } => w.prefetcht2(ctx, cfg, mem),
+            Insn::Prefetchnta { mem } => w.prefetchnta(ctx, cfg, mem),
+            Insn::Clflush { mem } => w.clflush(ctx, cfg, mem),
+            Insn::Clflushopt { mem } => w.clflushopt(ctx, cfg, mem),
+            Insn::Clwb { mem } => w.clwb(ctx, cfg, mem),
+            Insn::Sfence => w.sfence(ctx, cfg),
+            Insn::Rdtsc => w.rdtsc(ctx, cfg),
+            Insn::Rdtscp => w.rdtscp(ctx, cfg),
+            Insn::Unreachable => w.unreachable(ctx, cfg),
+            Insn::DebugBreak => w.debug_break(ctx, cfg),
+            Insn::Db { bytes } => w.db(ctx, cfg, bytes),
+            Insn::AlignTo { alignment } => w.align_to(ctx, cfg, *alignment),
+            Insn::Nop => w.nop(ctx, cfg),
+            Insn::Nops { n_bytes } => w.nops(ctx, cfg, *n_bytes),
+            Insn::Align { pow2 } => w.align(ctx, cfg, *pow2),
+            Insn::SetLabel { label } => w.set_label(ctx, cfg, label.clone()),
+            Insn::LeaLabel { dest, label } => w.lea_label(ctx, cfg, dest, label.clone()),
+            Insn::CallLabel { label } => w.call_label(ctx, cfg, label.clone()),
+            Insn::JmpLabel { label } => w.jmp_label(ctx, cfg, label.clone()),
+            Insn::JccLabel { cc, label } => w.jcc_label(ctx, cfg, *cc, label.clone()),
+            Insn::JccLabelHinted { cc, label, hint } => {
+                w.jcc_label_hinted(ctx, cfg, *cc, label.clone(), *hint)
+            }
+            Insn::DdLabel { label } => w.dd_label(ctx, cfg, label.clone()),
+        }
+    }
+}
+
+/// Records calls as a list of [`Insn`]s.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RecordingWriter<L> {
+    insns: Vec<Insn<L>>,
+}
+
+impl<L> RecordingWriter<L> {
+    /// Creates an empty recording.
+    pub fn new() -> Self {
+        Self { insns: Vec::new() }
+    }
+
+    /// Returns the recorded calls in the order they were made.
+    pub fn insns(&self) -> &[Insn<L>] {
+        &self.insns
+    }
+
+    /// Returns the recorded calls for editing or reordering.
+    pub fn insns_mut(&mut self) -> &mut Vec<Insn<L>> {
+        &mut self.insns
+    }
+
+    /// Consumes the recording and returns its calls.
+    pub fn into_insns(self) -> Vec<Insn<L>> {
+        self.insns
+    }
+
+    /// Replays every recorded call into `w`, in order, stopping at the first
+    /// error.
+    pub fn replay<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: X64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+        L: Clone,
+    {
+        for insn in &self.insns {
+            insn.replay(w, ctx, cfg)?;
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, insn: Insn<L>) -> Result<(), EmitError> {
+        self.insns.push(insn);
+        Ok(())
+    }
+}
+
+impl<L> Default for RecordingWriter<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L> From<Vec<Insn<L>>> for RecordingWriter<L> {
+    fn from(insns: Vec<Insn<L>>) -> Self {
+        Self { insns }
+    }
+}
+
+impl<L, Context> WriterCore<Context> for RecordingWriter<L> {
+    type Error = EmitError;
+
+    fn hlt(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Hlt)
+    }
+
+    fn xchg(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Xchg {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn mov(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mov {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn sub(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sub {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn add(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Add {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn adc(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Adc {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sbb(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sbb {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn add_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AddImm {
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn sub_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::SubImm {
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn movsx(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Movsx {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn movzx(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Movzx {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn push(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Push {
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn pop(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Pop {
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn pushf(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Pushf)
+    }
+
+    fn popf(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Popf)
+    }
+
+    fn lahf(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Lahf)
+    }
+
+    fn sahf(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Sahf)
+    }
+
+    fn call(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Call {
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn jmp(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Jmp {
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn cmp(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cmp {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn cmp_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::CmpImm {
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn cmp0(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cmp0 {
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn test(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Test {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn test0(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Test0 {
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn cmovcc(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        cond: ConditionCode,
+        op: &(dyn MemArg + '_),
+        val: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cmovcc {
+            cond,
+            op: op.concrete_mem_kind(),
+            val: val.concrete_mem_kind(),
+        })
+    }
+
+    fn setcc(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        cond: ConditionCode,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Setcc {
+            cond,
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn not(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Not {
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn neg(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Neg {
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn inc(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Inc {
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn dec(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Dec {
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn lea(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lea {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn get_ip(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::GetIp)
+    }
+
+    fn ret(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Ret)
+    }
+
+    fn mov64(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        r: &(dyn MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mov64 {
+            r: r.concrete_mem_kind(),
+            val,
+        })
+    }
+
+    fn mul(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mul {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn imul2(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Imul2 {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn imul3(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Imul3 {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn mul_wide(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        hi: &(dyn MemArg + '_),
+        lo: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::MulWide {
+            hi: hi.concrete_mem_kind(),
+            lo: lo.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn imul_wide(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        hi: &(dyn MemArg + '_),
+        lo: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::ImulWide {
+            hi: hi.concrete_mem_kind(),
+            lo: lo.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn div(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Div {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn idiv(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Idiv {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn and(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::And {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn or(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Or {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn eor(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Eor {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn and_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AndImm {
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn or_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::OrImm {
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn eor_imm(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::EorImm {
+            a: a.concrete_mem_kind(),
+            imm,
+        })
+    }
+
+    fn shl(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Shl {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn shr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Shr {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn sar(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sar {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn rol(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Rol {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn ror(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ror {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn popcnt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Popcnt {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn lzcnt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Lzcnt {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn tzcnt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Tzcnt {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn bswap(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Bswap {
+            op: op.concrete_mem_kind(),
+        })
+    }
+
+    fn fadd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fadd {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fsub(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fsub {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fmul(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fmul {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fdiv(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fdiv {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fcmp(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fcmp {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn fmin(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fmin {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fmax(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fmax {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fsqrt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fsqrt {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fabs(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fabs {
+            dest: dest.concrete_mem_kind(),
+            scratch: scratch.concrete_mem_kind(),
+        })
+    }
+
+    fn fneg(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fneg {
+            dest: dest.concrete_mem_kind(),
+            scratch: scratch.concrete_mem_kind(),
+        })
+    }
+
+    fn cvtsi2sd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cvtsi2sd {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn cvttsd2si(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cvttsd2si {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn vfmadd231sd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Vfmadd231sd {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn vfmsub231sd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Vfmsub231sd {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn vfnmadd231sd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Vfnmadd231sd {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn vfnmsub231sd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Vfnmsub231sd {
+            dest: dest.concrete_mem_kind(),
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn kmov(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: MaskReg,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Kmov {
+            dest,
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn vmovupd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Vmovupd {
+            dest: dest.concrete_mem_kind(),
+            mask,
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn vaddpd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Vaddpd {
+            dest: dest.concrete_mem_kind(),
+            mask,
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            broadcast,
+        })
+    }
+
+    fn vsubpd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Vsubpd {
+            dest: dest.concrete_mem_kind(),
+            mask,
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            broadcast,
+        })
+    }
+
+    fn vmulpd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Vmulpd {
+            dest: dest.concrete_mem_kind(),
+            mask,
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            broadcast,
+        })
+    }
+
+    fn vdivpd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Vdivpd {
+            dest: dest.concrete_mem_kind(),
+            mask,
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            broadcast,
+        })
+    }
+
+    fn vfmadd231pd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Vfmadd231pd {
+            dest: dest.concrete_mem_kind(),
+            mask,
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            broadcast,
+        })
+    }
+
+    fn vcmppd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: MaskReg,
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        predicate: u8,
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Vcmppd {
+            dest,
+            mask,
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+            predicate,
+            broadcast,
+        })
+    }
+
+    fn fmov(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fmov {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn addss(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Addss {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn subss(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Subss {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn mulss(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Mulss {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn divss(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Divss {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn ucomiss(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ucomiss {
+            a: a.concrete_mem_kind(),
+            b: b.concrete_mem_kind(),
+        })
+    }
+
+    fn minss(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Minss {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn maxss(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Maxss {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn sqrtss(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Sqrtss {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn cvtsi2ss(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cvtsi2ss {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn cvttss2si(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cvttss2si {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn cvtss2sd(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cvtss2sd {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn cvtsd2ss(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cvtsd2ss {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn movss(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Movss {
+            dest: dest.concrete_mem_kind(),
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn ldmxcsr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Ldmxcsr {
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn stmxcsr(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Stmxcsr {
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn rdsegbase(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        seg: Segment,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Rdsegbase {
+            seg,
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn wrsegbase(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        seg: Segment,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Wrsegbase {
+            seg,
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fld(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fld {
+            src: src.concrete_mem_kind(),
+        })
+    }
+
+    fn fst(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fst {
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn fstp(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Fstp {
+            dest: dest.concrete_mem_kind(),
+        })
+    }
+
+    fn faddp(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Faddp)
+    }
+
+    fn fsubp(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Fsubp)
+    }
+
+    fn fmulp(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Fmulp)
+    }
+
+    fn fdivp(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Fdivp)
+    }
+
+    fn movs(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Movs { size, rep })
+    }
+
+    fn stos(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Stos { size, rep })
+    }
+
+    fn cmps(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Cmps { size, rep })
+    }
+
+    fn scas(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Scas { size, rep })
+    }
+
+    fn syscall(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Syscall)
+    }
+
+    fn endbr64(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Endbr64)
+    }
+
+    fn ud2(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Ud2)
+    }
+
+    fn int3(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Int3)
+    }
+
+    fn pause(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Pause)
+    }
+
+    fn prefetcht0(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Prefetcht0 {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn prefetcht1(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Prefetcht1 {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn prefetcht2(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Prefetcht2 {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn prefetchnta(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Prefetchnta {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn clflush(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Clflush {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn clflushopt(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Clflushopt {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn clwb(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Clwb {
+            mem: mem.concrete_mem_kind(),
+        })
+    }
+
+    fn sfence(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Sfence)
+    }
+
+    fn rdtsc(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Rdtsc)
+    }
+
+    fn rdtscp(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Rdtscp)
+    }
+
+    fn unreachable(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Unreachable)
+    }
+
+    fn debug_break(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::DebugBreak)
+    }
+
+    fn db(&mut self, _ctx: &mut Context, _cfg: X64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.record(Insn::Db {
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    fn align_to(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::AlignTo { alignment })
+    }
+
+    fn nop(&mut self, _ctx: &mut Context, _cfg: X64Arch) -> Result<(), Self::Error> {
+        self.record(Insn::Nop)
+    }
+
+    fn nops(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        n_bytes: usize,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::Nops { n_bytes })
+    }
+
+    fn align(&mut self, _ctx: &mut Context, _cfg: X64Arch, pow2: u32) -> Result<(), Self::Error> {
+        self.record(Insn::Align { pow2 })
+    }
+}
+
+impl<L, Context> Writer<L, Context> for RecordingWriter<L> {
+    fn set_label(&mut self, _ctx: &mut Context, _cfg: X64Arch, s: L) -> Result<(), Self::Error> {
+        self.record(Insn::SetLabel { label: s })
+    }
+
+    fn lea_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::LeaLabel {
+            dest: dest.concrete_mem_kind(),
+            label,
+        })
+    }
+
+    fn call_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::CallLabel { label })
+    }
+
+    fn jmp_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::JmpLabel { label })
+    }
+
+    fn jcc_label(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        cc: ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::JccLabel { cc, label })
+    }
+
+    fn jcc_label_hinted(
+        &mut self,
+        _ctx: &mut Context,
+        _cfg: X64Arch,
+        cc: ConditionCode,
+        label: L,
+        hint: BranchHint,
+    ) -> Result<(), Self::Error> {
+        self.record(Insn::JccLabelHinted { cc, label, hint })
+    }
+
+    fn dd_label(&mut self, _ctx: &mut Context, _cfg: X64Arch, label: L) -> Result<(), Self::Error> {
+        self.record(Insn::DdLabel { label })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    fn generate<W: Writer<u32, ()> + ?Sized>(w: &mut W, cfg: X64Arch) -> Result<(), W::Error> {
+        w.set_label(&mut (), cfg, 1)?;
+        w.mov(&mut (), cfg, &Reg(0), &Reg(1))?;
+        w.hlt(&mut (), cfg)?;
+        w.jmp_label(&mut (), cfg, 1)
+    }
+
+    #[test]
+    fn test_replay_matches_direct_emission() {
+        let cfg = X64Arch::default();
+        let mut rec = RecordingWriter::new();
+        generate(&mut rec, cfg).unwrap();
+        assert_eq!(rec.insns().len(), 4);
+        assert_eq!(rec.insns()[0], Insn::SetLabel { label: 1 });
+
+        let mut direct = String::new();
+        let mut w: &mut dyn Write = &mut direct;
+        generate(&mut w, cfg).unwrap();
+
+        let mut replayed = String::new();
+        let mut w: &mut dyn Write = &mut replayed;
+        rec.replay(&mut w, &mut (), cfg).unwrap();
+        rec.replay(&mut w, &mut (), cfg).unwrap();
+        assert_eq!(replayed, direct.repeat(2));
+    }
+
+    /// Records a trap, then a block at label 1 that returns, then a jump
+    /// back to the label.
+    fn record(cfg: X64Arch) -> RecordingWriter<u32> {
+        let mut rec = RecordingWriter::new();
+        rec.hlt(&mut (), cfg).unwrap();
+        rec.set_label(&mut (), cfg, 1).unwrap();
+        rec.ret(&mut (), cfg).unwrap();
+        rec.jmp_label(&mut (), cfg, 1).unwrap();
+        rec
+    }
+
+    #[test]
+    fn test_reordered_replay() {
+        let cfg = X64Arch::default();
+        let mut rec = record(cfg);
+        // Move the label in front of the trap, so the jump now targets it.
+        let label = rec.insns_mut().remove(1);
+        rec.insns_mut().insert(0, label);
+
+        let mut output = String::new();
+        let mut w: &mut dyn Write = &mut output;
+        rec.replay(&mut w, &mut (), cfg).unwrap();
+        assert_eq!(output, "1:\nhlt\nret\njmp 1\n");
+    }
+
+    #[cfg(feature = "iced")]
+    #[test]
+    fn test_reordered_replay_resolves_labels_at_their_new_offset() {
+        use crate::out::iced::IcedWriter;
+
+        let cfg = X64Arch::default();
+        let mut rec = record(cfg);
+        let label = rec.insns_mut().remove(1);
+        rec.insns_mut().insert(0, label);
+
+        let mut binary = IcedWriter::<u32>::new(0);
+        rec.replay(&mut binary, &mut (), cfg).unwrap();
+        let (code, labels) = binary.into_parts();
+        assert_eq!(labels[&1], 0);
+        assert_eq!(code, [0xF4, 0xC3, 0xE9, 0xF9, 0xFF, 0xFF, 0xFF]);
+    }
+}
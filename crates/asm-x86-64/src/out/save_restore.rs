@@ -0,0 +1,124 @@
+//! Saving and restoring sets of registers on the stack.
+//!
+//! Prologues and epilogues that preserve callee-saved registers, and
+//! shims that spill around a call, need the same two sequences: push a set
+//! of registers keeping the stack 16-byte aligned, and restore it later.
+//! [`SaveRestore`] emits them for any [`WriterCore`]:
+//!
+//! ```ignore
+//! let saved = [Reg(3), Reg(12), Reg(13)]; // rbx, r12, r13
+//! writer.save_regs(ctx, cfg, &saved)?;    // push rbx; push r12; push r13; sub rsp,8
+//! // ...
+//! writer.restore_regs(ctx, cfg, &saved)?; // add rsp,8; pop r13; pop r12; pop rbx
+//! ```
+//!
+//! On x86-64 `push`/`pop` is already the most compact form. An odd number
+//! of registers is padded with one 8-byte slot, assuming the stack was
+//! aligned before the save.
+
+use portal_pc_asm_common::types::reg::Reg;
+
+use crate::X64Arch;
+use crate::out::WriterCore;
+
+/// The stack pointer, `rsp`.
+const RSP: Reg = Reg(4);
+
+/// Returns the padding, in bytes, that keeps `count` saved registers a
+/// multiple of 16 bytes.
+pub fn save_padding(count: usize) -> i32 {
+    if count % 2 == 1 { 8 } else { 0 }
+}
+
+/// Emits register save and restore sequences.
+///
+/// Implemented for every [`WriterCore`].
+pub trait SaveRestore<Context>: WriterCore<Context> {
+    /// Pushes the general-purpose registers `regs` in order, then pads the
+    /// stack to a 16-byte multiple.
+    fn save_regs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        regs: &[Reg],
+    ) -> Result<(), Self::Error> {
+        for reg in regs {
+            self.push(ctx, cfg, reg)?;
+        }
+        match save_padding(regs.len()) {
+            0 => Ok(()),
+            pad => self.sub_imm(ctx, cfg, &RSP, pad),
+        }
+    }
+
+    /// Restores registers saved by [`save_regs`](Self::save_regs) with the
+    /// same `regs`.
+    fn restore_regs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        regs: &[Reg],
+    ) -> Result<(), Self::Error> {
+        match save_padding(regs.len()) {
+            0 => {}
+            pad => self.add_imm(ctx, cfg, &RSP, pad)?,
+        }
+        for reg in regs.iter().rev() {
+            self.pop(ctx, cfg, reg)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> SaveRestore<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_odd_set_is_padded() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        let regs = [Reg(3), Reg(12), Reg(13)];
+        out.save_regs(&mut (), X64Arch::default(), &regs).unwrap();
+        out.restore_regs(&mut (), X64Arch::default(), &regs)
+            .unwrap();
+        assert_eq!(
+            output,
+            "push rbx\npush r12\npush r13\nsub rsp,8\nadd rsp,8\npop r13\npop r12\npop rbx\n"
+        );
+    }
+
+    #[test]
+    fn test_even_set_is_not_padded() {
+        let mut output = String::new();
+        let out: &mut dyn Write = &mut output;
+        out.save_regs(&mut (), X64Arch::default(), &[Reg(5), Reg(3)])
+            .unwrap();
+        assert_eq!(output, "push rbp\npush rbx\n");
+    }
+
+    #[test]
+    fn test_restore_undoes_save() {
+        use alloc::vec::Vec;
+
+        let cfg = X64Arch::default();
+        let all = [Reg(3), Reg(5), Reg(12), Reg(13), Reg(14)];
+        for n in 0..=all.len() {
+            let (mut save, mut restore) = (String::new(), String::new());
+            let out: &mut dyn Write = &mut save;
+            out.save_regs(&mut (), cfg, &all[..n]).unwrap();
+            let out: &mut dyn Write = &mut restore;
+            out.restore_regs(&mut (), cfg, &all[..n]).unwrap();
+            let undo: Vec<String> = save
+                .lines()
+                .rev()
+                .map(|line| line.replace("push", "pop").replace("sub", "add"))
+                .collect();
+            assert_eq!(restore.lines().collect::<Vec<_>>(), undo, "{n} registers");
+        }
+    }
+}
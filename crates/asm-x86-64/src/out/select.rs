@@ -0,0 +1,147 @@
+//! Branchless conditional selects and booleans from flags.
+//!
+//! [`Select`] turns the flags left by a compare into a value without a
+//! branch, using the x86-64 idioms for it:
+//!
+//! ```ignore
+//! writer.cmp(ctx, cfg, &a, &b)?;
+//! writer.select(ctx, cfg, ConditionCode::L, &min, &a, &b)?;
+//! // mov min, b; cmovl min, a
+//! writer.set_bool(ctx, cfg, ConditionCode::E, &eq)?;
+//! // mov eq, 0; sete eq_b
+//! ```
+//!
+//! Neither helper changes the flags, so several can share one compare. The
+//! AArch64 and RISC-V crates provide the same helpers with `csel`/`cset` and
+//! `slt`-based sequences.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::WriterCore;
+use crate::out::arg::{ArgKind, MemArg, MemArgKind};
+use crate::{ConditionCode, X64Arch};
+
+/// Returns the register `arg` names, if it is one.
+fn reg_of(arg: &(dyn MemArg + '_)) -> Option<Reg> {
+    match arg.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => Some(reg),
+        _ => None,
+    }
+}
+
+/// Emits branchless selects and booleans from the current flags.
+///
+/// Implemented for every [`WriterCore`].
+pub trait Select<Context>: WriterCore<Context> {
+    /// Sets `dest` to `if_true` if `cc` holds and to `if_false` otherwise.
+    ///
+    /// `if_true` must be a register or memory operand, as must `if_false`
+    /// when it is the same register as `dest`.
+    fn select(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: ConditionCode,
+        dest: &(dyn MemArg + '_),
+        if_true: &(dyn MemArg + '_),
+        if_false: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let dest_reg = reg_of(dest);
+        if dest_reg.is_some() && dest_reg == reg_of(if_true) {
+            return self.cmovcc(ctx, cfg, cc.invert(), dest, if_false);
+        }
+        if dest_reg.is_none() || dest_reg != reg_of(if_false) {
+            self.mov(ctx, cfg, dest, if_false)?;
+        }
+        self.cmovcc(ctx, cfg, cc, dest, if_true)
+    }
+
+    /// Sets the register `dest` to 1 if `cc` holds and to 0 otherwise.
+    ///
+    /// `dest` is cleared with `mov` rather than `xor`, which would destroy
+    /// the flags being tested.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dest` is not a register.
+    fn set_bool(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: ConditionCode,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        let reg = reg_of(dest).expect("set_bool needs a register destination");
+        self.mov(ctx, cfg, dest, &0u64)?;
+        self.setcc(
+            ctx,
+            cfg,
+            cc,
+            &ArgKind::Reg {
+                reg,
+                size: MemorySize::_8,
+            },
+        )
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> Select<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_select_reuses_an_aliased_operand() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = X64Arch::default();
+        writer
+            .select(&mut (), cfg, ConditionCode::L, &Reg(0), &Reg(1), &Reg(2))
+            .unwrap();
+        writer
+            .select(&mut (), cfg, ConditionCode::L, &Reg(0), &Reg(0), &Reg(2))
+            .unwrap();
+        writer
+            .select(&mut (), cfg, ConditionCode::L, &Reg(0), &Reg(1), &Reg(0))
+            .unwrap();
+        assert_eq!(
+            output,
+            "mov rax, rdx\ncmovl rax, rcx\ncmovnl rax, rdx\ncmovl rax, rcx\n"
+        );
+    }
+
+    #[test]
+    fn test_set_bool_keeps_the_flags() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        writer
+            .set_bool(&mut (), X64Arch::default(), ConditionCode::E, &Reg(11))
+            .unwrap();
+        assert_eq!(output, "mov r11, 0\nsete r11b\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "set_bool needs a register destination")]
+    fn test_set_bool_rejects_memory() {
+        use crate::RegisterClass;
+        use crate::out::arg::Segment;
+
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let slot = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(7),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_8,
+            reg_class: RegisterClass::Gpr,
+            segment: Segment::None,
+        };
+        let _ = writer.set_bool(&mut (), X64Arch::default(), ConditionCode::E, &slot);
+    }
+}
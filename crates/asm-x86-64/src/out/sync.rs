@@ -0,0 +1,256 @@
+//! Spinlocks and Linux futex system calls.
+//!
+//! [`SpinLock`] emits the acquire and release sequences of a lock held in a
+//! 32-bit word, 0 when free and 1 when held. Acquiring swaps 1 in with
+//! `xchg` and, while the lock is held, spins on plain loads with `pause`
+//! between them, only retrying the swap once the lock reads free:
+//!
+//! ```ignore
+//! // lock address in rdi, ecx as scratch
+//! let lock = SpinLock::new(Reg(7), Reg(1), spin, attempt);
+//! lock.acquire(w, ctx, cfg)?;
+//! // jmp attempt
+//! // spin: pause; cmp dword ptr [rdi], 0; jne spin
+//! // attempt: mov ecx, 1; xchg ecx, dword ptr [rdi]; test ecx, ecx; jne spin
+//! lock.release(w, ctx, cfg)?;
+//! // mov dword ptr [rdi], 0
+//! ```
+//!
+//! A lock word is also a futex, so a runtime that would rather sleep than
+//! spin can block on it with [`SpinWait::futex_wait`] and wake waiters with
+//! [`SpinWait::futex_wake`]. The AArch64 and RISC-V crates provide the same
+//! helpers with `swpal` and `amoswap.w`.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::out::arg::{ArgKind, MemArg, MemArgKind, Segment};
+use crate::out::{Writer, WriterCore};
+use crate::{ConditionCode, RegisterClass, X64Arch};
+
+/// The Linux `futex` system call number.
+pub const SYS_FUTEX: u64 = 202;
+
+/// The `futex` operation that sleeps while the word holds an expected value,
+/// for a word not shared between processes.
+pub const FUTEX_WAIT_PRIVATE: u64 = 128;
+
+/// The `futex` operation that wakes sleepers on a word, for a word not
+/// shared between processes.
+pub const FUTEX_WAKE_PRIVATE: u64 = 129;
+
+fn reg32(reg: Reg) -> ArgKind {
+    ArgKind::Reg {
+        reg,
+        size: MemorySize::_32,
+    }
+}
+
+/// Returns the 32-bit word at the address in `base`.
+fn word_at(base: Reg) -> MemArgKind {
+    MemArgKind::Mem {
+        base: ArgKind::Reg {
+            reg: base,
+            size: MemorySize::_64,
+        },
+        offset: None,
+        disp: 0,
+        size: MemorySize::_32,
+        reg_class: RegisterClass::Gpr,
+        segment: Segment::None,
+    }
+}
+
+/// Moves `src` into `dest`, resized to `dest`'s width if it is a register,
+/// unless it is already there.
+fn set<Context, W: WriterCore<Context> + ?Sized>(
+    w: &mut W,
+    ctx: &mut Context,
+    cfg: X64Arch,
+    dest: Reg,
+    size: MemorySize,
+    src: &(dyn MemArg + '_),
+) -> Result<(), W::Error> {
+    match src.concrete_mem_kind() {
+        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) if reg == dest => Ok(()),
+        MemArgKind::NoMem(ArgKind::Reg { reg, .. }) => w.mov(
+            ctx,
+            cfg,
+            &ArgKind::Reg { reg: dest, size },
+            &ArgKind::Reg { reg, size },
+        ),
+        _ => w.mov(ctx, cfg, &ArgKind::Reg { reg: dest, size }, src),
+    }
+}
+
+/// Emits spin-wait hints and futex system calls.
+///
+/// Implemented for every [`WriterCore`].
+pub trait SpinWait<Context>: WriterCore<Context> {
+    /// Emits `pause`, telling the core it is in a spin-wait loop so it can
+    /// save power and avoid the memory-order mis-speculation that would
+    /// otherwise follow the loop's exit.
+    fn spin_hint(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.pause(ctx, cfg)
+    }
+
+    /// Emits a `futex` system call that sleeps until woken, as long as the
+    /// 32-bit word at `addr` holds `val`, with no timeout.
+    ///
+    /// `addr` goes in `rdi` and `val` in `edx`, in that order, so `val` must
+    /// not be in `rdi` unless `addr` is too. The call returns 0 in `rax` once
+    /// woken and `-EAGAIN` if the word did not hold `val`, and clobbers
+    /// `rax`, `rcx`, `rdx`, `rsi`, `rdi`, `r10` and `r11`.
+    fn futex_wait(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        addr: &(dyn MemArg + '_),
+        val: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        set(self, ctx, cfg, Reg(7), MemorySize::_64, addr)?;
+        set(self, ctx, cfg, Reg(2), MemorySize::_32, val)?;
+        self.mov(ctx, cfg, &reg32(Reg(6)), &FUTEX_WAIT_PRIVATE)?;
+        self.mov(ctx, cfg, &Reg(10), &0u64)?;
+        self.mov(ctx, cfg, &reg32(Reg(0)), &SYS_FUTEX)?;
+        self.syscall(ctx, cfg)
+    }
+
+    /// Emits a `futex` system call that wakes up to `count` threads sleeping
+    /// on the word at `addr`.
+    ///
+    /// `addr` goes in `rdi`. The call returns the number of threads woken in
+    /// `rax`, and clobbers `rax`, `rcx`, `rdx`, `rsi`, `rdi` and `r11`.
+    fn futex_wake(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        addr: &(dyn MemArg + '_),
+        count: u32,
+    ) -> Result<(), Self::Error> {
+        set(self, ctx, cfg, Reg(7), MemorySize::_64, addr)?;
+        self.mov(ctx, cfg, &reg32(Reg(2)), &(count as u64))?;
+        self.mov(ctx, cfg, &reg32(Reg(6)), &FUTEX_WAKE_PRIVATE)?;
+        self.mov(ctx, cfg, &reg32(Reg(0)), &SYS_FUTEX)?;
+        self.syscall(ctx, cfg)
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> SpinWait<Context> for W {}
+
+/// Emits test-and-test-and-set spinlock sequences.
+pub struct SpinLock<L> {
+    lock: Reg,
+    scratch: Reg,
+    spin: L,
+    attempt: L,
+}
+
+impl<L: Clone> SpinLock<L> {
+    /// Creates a lock on the word at the address in `lock`, using `scratch`
+    /// for the swapped value and labelling the spin loop `spin` and the swap
+    /// `attempt`.
+    pub fn new(lock: Reg, scratch: Reg, spin: L, attempt: L) -> Self {
+        Self {
+            lock,
+            scratch,
+            spin,
+            attempt,
+        }
+    }
+
+    /// Emits a loop that returns once it has set the lock word from 0 to 1.
+    ///
+    /// The swap is a full barrier, so accesses after the sequence cannot be
+    /// performed before the lock is taken. The sequence clobbers `scratch`
+    /// and the flags, and defines both labels, so it can be emitted once per
+    /// pair of labels.
+    pub fn acquire<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: X64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        let (word, scratch) = (word_at(self.lock), reg32(self.scratch));
+        w.jmp_label(ctx, cfg, self.attempt.clone())?;
+        w.set_label(ctx, cfg, self.spin.clone())?;
+        w.spin_hint(ctx, cfg)?;
+        w.cmp_imm(ctx, cfg, &word, 0)?;
+        w.jcc_label(ctx, cfg, ConditionCode::NE, self.spin.clone())?;
+        w.set_label(ctx, cfg, self.attempt.clone())?;
+        w.mov(ctx, cfg, &scratch, &1u64)?;
+        // XCHG with memory is locked without a prefix.
+        w.xchg(ctx, cfg, &scratch, &word)?;
+        w.test0(ctx, cfg, &scratch)?;
+        w.jcc_label(ctx, cfg, ConditionCode::NE, self.spin.clone())
+    }
+
+    /// Emits the store that frees the lock.
+    ///
+    /// x86-64 stores are not reordered with earlier loads or stores, so a
+    /// plain store releases the accesses before it.
+    pub fn release<W, Context>(
+        &self,
+        w: &mut W,
+        ctx: &mut Context,
+        cfg: X64Arch,
+    ) -> Result<(), W::Error>
+    where
+        W: Writer<L, Context> + ?Sized,
+    {
+        w.mov(ctx, cfg, &word_at(self.lock), &0u64)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_acquire_spins_on_loads_with_pause() {
+        let mut output = String::new();
+        let mut writer: &mut dyn Write = &mut output;
+        let cfg = X64Arch::default();
+        let lock = SpinLock::new(Reg(7), Reg(1), ".Lspin", ".Lattempt");
+        lock.acquire(&mut writer, &mut (), cfg).unwrap();
+        lock.release(&mut writer, &mut (), cfg).unwrap();
+        assert_eq!(
+            output,
+            "jmp .Lattempt\n.Lspin:\npause\ncmp dword ptr [rdi+0], 0\njne .Lspin\n\
+             .Lattempt:\nmov ecx, 1\nxchg ecx, dword ptr [rdi+0]\ntest ecx, ecx\njne .Lspin\n\
+             mov dword ptr [rdi+0], 0\n"
+        );
+    }
+
+    #[test]
+    fn test_futex_arguments_in_place_are_not_moved() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        let cfg = X64Arch::default();
+        w.futex_wait(&mut (), cfg, &Reg(7), &Reg(2)).unwrap();
+        w.futex_wake(&mut (), cfg, &Reg(7), 1).unwrap();
+        assert_eq!(
+            output,
+            "mov esi, 128\nmov r10, 0\nmov eax, 202\nsyscall\n\
+             mov edx, 1\nmov esi, 129\nmov eax, 202\nsyscall\n"
+        );
+    }
+
+    #[test]
+    fn test_futex_wait_loads_an_immediate_value() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        let cfg = X64Arch::default();
+        w.futex_wait(&mut (), cfg, &Reg(12), &ArgKind::Lit(1))
+            .unwrap();
+        assert_eq!(
+            output,
+            "mov rdi, r12\nmov edx, 1\n\
+             mov esi, 128\nmov r10, 0\nmov eax, 202\nsyscall\n"
+        );
+    }
+}
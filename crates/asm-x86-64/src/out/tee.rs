@@ -0,0 +1,1627 @@
+//! Emission to two writers at once.
+//!
+//! [`TeeWriter`] forwards every instruction, directive and label to two
+//! writers in turn, so one run of a code generator can produce an assembly
+//! listing together with the machine code, or feed a text writer and a
+//! counting pass, without generating the code twice:
+//!
+//! ```ignore
+//! let mut listing = String::new();
+//! let mut tee = TeeWriter::new(&mut listing as &mut dyn Write, IcedWriter::<u32>::new(0));
+//! generate(&mut tee, &mut ctx, cfg)?;
+//! let (_, binary) = tee.into_inner();
+//! let (code, labels) = binary.into_parts();
+//! ```
+//!
+//! The first writer gets each call before the second, and an error from
+//! either ends the call, so after a failure the second writer may be one
+//! call behind the first. Errors from the second writer are converted into
+//! the first writer's error type.
+
+use portal_pc_asm_common::types::mem::MemorySize;
+
+use crate::out::arg::{MemArg, Segment};
+use crate::out::evex::OpMask;
+use crate::out::{Writer, WriterCore};
+use crate::{BranchHint, ConditionCode, MaskReg, RepPrefix, X64Arch};
+
+/// Forwards every call to two writers.
+pub struct TeeWriter<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> TeeWriter<A, B> {
+    /// Creates a writer that forwards to `first`, then `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Returns the first writer.
+    pub fn first(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Returns the second writer.
+    pub fn second(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Returns both writers.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: WriterCore<Context>, B: WriterCore<Context>, Context> WriterCore<Context>
+    for TeeWriter<A, B>
+where
+    A::Error: From<B::Error>,
+{
+    type Error = A::Error;
+
+    fn hlt(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.hlt(ctx, cfg)?;
+        self.second.hlt(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn xchg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.xchg(ctx, cfg, dest, src)?;
+        self.second.xchg(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn mov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mov(ctx, cfg, dest, src)?;
+        self.second.mov(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn sub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sub(ctx, cfg, a, b)?;
+        self.second.sub(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn add(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.add(ctx, cfg, a, b)?;
+        self.second.add(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn adc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.adc(ctx, cfg, a, b)?;
+        self.second.adc(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn sbb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sbb(ctx, cfg, a, b)?;
+        self.second.sbb(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.add_imm(ctx, cfg, a, imm)?;
+        self.second.add_imm(ctx, cfg, a, imm)?;
+        Ok(())
+    }
+
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.sub_imm(ctx, cfg, a, imm)?;
+        self.second.sub_imm(ctx, cfg, a, imm)?;
+        Ok(())
+    }
+
+    fn movsx(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.movsx(ctx, cfg, dest, src)?;
+        self.second.movsx(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn movzx(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.movzx(ctx, cfg, dest, src)?;
+        self.second.movzx(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn push(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.push(ctx, cfg, op)?;
+        self.second.push(ctx, cfg, op)?;
+        Ok(())
+    }
+
+    fn pop(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.pop(ctx, cfg, op)?;
+        self.second.pop(ctx, cfg, op)?;
+        Ok(())
+    }
+
+    fn pushf(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.pushf(ctx, cfg)?;
+        self.second.pushf(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn popf(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.popf(ctx, cfg)?;
+        self.second.popf(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn lahf(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.lahf(ctx, cfg)?;
+        self.second.lahf(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn sahf(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.sahf(ctx, cfg)?;
+        self.second.sahf(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn call(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.call(ctx, cfg, op)?;
+        self.second.call(ctx, cfg, op)?;
+        Ok(())
+    }
+
+    fn jmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.jmp(ctx, cfg, op)?;
+        self.second.jmp(ctx, cfg, op)?;
+        Ok(())
+    }
+
+    fn cmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cmp(ctx, cfg, a, b)?;
+        self.second.cmp(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn cmp_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.cmp_imm(ctx, cfg, a, imm)?;
+        self.second.cmp_imm(ctx, cfg, a, imm)?;
+        Ok(())
+    }
+
+    fn cmp0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cmp0(ctx, cfg, op)?;
+        self.second.cmp0(ctx, cfg, op)?;
+        Ok(())
+    }
+
+    fn test(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.test(ctx, cfg, a, b)?;
+        self.second.test(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn test0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.test0(ctx, cfg, op)?;
+        self.second.test0(ctx, cfg, op)?;
+        Ok(())
+    }
+
+    fn cmovcc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cond: ConditionCode,
+        op: &(dyn MemArg + '_),
+        val: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cmovcc(ctx, cfg, cond, op, val)?;
+        self.second.cmovcc(ctx, cfg, cond, op, val)?;
+        Ok(())
+    }
+
+    fn setcc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cond: ConditionCode,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.setcc(ctx, cfg, cond, op)?;
+        self.second.setcc(ctx, cfg, cond, op)?;
+        Ok(())
+    }
+
+    fn not(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.not(ctx, cfg, op)?;
+        self.second.not(ctx, cfg, op)?;
+        Ok(())
+    }
+
+    fn neg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.neg(ctx, cfg, op)?;
+        self.second.neg(ctx, cfg, op)?;
+        Ok(())
+    }
+
+    fn inc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.inc(ctx, cfg, op)?;
+        self.second.inc(ctx, cfg, op)?;
+        Ok(())
+    }
+
+    fn dec(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.dec(ctx, cfg, op)?;
+        self.second.dec(ctx, cfg, op)?;
+        Ok(())
+    }
+
+    fn lea(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.lea(ctx, cfg, dest, src)?;
+        self.second.lea(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn get_ip(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.get_ip(ctx, cfg)?;
+        self.second.get_ip(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn ret(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.ret(ctx, cfg)?;
+        self.second.ret(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn mov64(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        r: &(dyn MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        self.first.mov64(ctx, cfg, r, val)?;
+        self.second.mov64(ctx, cfg, r, val)?;
+        Ok(())
+    }
+
+    fn mul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mul(ctx, cfg, a, b)?;
+        self.second.mul(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn imul2(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.imul2(ctx, cfg, dest, src)?;
+        self.second.imul2(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn imul3(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.imul3(ctx, cfg, dest, src, imm)?;
+        self.second.imul3(ctx, cfg, dest, src, imm)?;
+        Ok(())
+    }
+
+    fn mul_wide(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        hi: &(dyn MemArg + '_),
+        lo: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mul_wide(ctx, cfg, hi, lo, a, b)?;
+        self.second.mul_wide(ctx, cfg, hi, lo, a, b)?;
+        Ok(())
+    }
+
+    fn imul_wide(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        hi: &(dyn MemArg + '_),
+        lo: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.imul_wide(ctx, cfg, hi, lo, a, b)?;
+        self.second.imul_wide(ctx, cfg, hi, lo, a, b)?;
+        Ok(())
+    }
+
+    fn div(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.div(ctx, cfg, a, b)?;
+        self.second.div(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn idiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.idiv(ctx, cfg, a, b)?;
+        self.second.idiv(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn and(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.and(ctx, cfg, a, b)?;
+        self.second.and(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn or(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.or(ctx, cfg, a, b)?;
+        self.second.or(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn eor(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.eor(ctx, cfg, a, b)?;
+        self.second.eor(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.and_imm(ctx, cfg, a, imm)?;
+        self.second.and_imm(ctx, cfg, a, imm)?;
+        Ok(())
+    }
+
+    fn or_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.or_imm(ctx, cfg, a, imm)?;
+        self.second.or_imm(ctx, cfg, a, imm)?;
+        Ok(())
+    }
+
+    fn eor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        self.first.eor_imm(ctx, cfg, a, imm)?;
+        self.second.eor_imm(ctx, cfg, a, imm)?;
+        Ok(())
+    }
+
+    fn shl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.shl(ctx, cfg, a, b)?;
+        self.second.shl(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn shr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.shr(ctx, cfg, a, b)?;
+        self.second.shr(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn sar(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sar(ctx, cfg, a, b)?;
+        self.second.sar(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn rol(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.rol(ctx, cfg, a, b)?;
+        self.second.rol(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ror(ctx, cfg, a, b)?;
+        self.second.ror(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn popcnt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.popcnt(ctx, cfg, dest, src)?;
+        self.second.popcnt(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn lzcnt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.lzcnt(ctx, cfg, dest, src)?;
+        self.second.lzcnt(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn tzcnt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.tzcnt(ctx, cfg, dest, src)?;
+        self.second.tzcnt(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn bswap(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.bswap(ctx, cfg, op)?;
+        self.second.bswap(ctx, cfg, op)?;
+        Ok(())
+    }
+
+    fn fadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fadd(ctx, cfg, dest, src)?;
+        self.second.fadd(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fsub(ctx, cfg, dest, src)?;
+        self.second.fsub(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fmul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmul(ctx, cfg, dest, src)?;
+        self.second.fmul(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fdiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fdiv(ctx, cfg, dest, src)?;
+        self.second.fdiv(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fcmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fcmp(ctx, cfg, a, b)?;
+        self.second.fcmp(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn fmin(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmin(ctx, cfg, dest, src)?;
+        self.second.fmin(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fmax(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmax(ctx, cfg, dest, src)?;
+        self.second.fmax(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fsqrt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fsqrt(ctx, cfg, dest, src)?;
+        self.second.fsqrt(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn fabs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fabs(ctx, cfg, dest, scratch)?;
+        self.second.fabs(ctx, cfg, dest, scratch)?;
+        Ok(())
+    }
+
+    fn fneg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fneg(ctx, cfg, dest, scratch)?;
+        self.second.fneg(ctx, cfg, dest, scratch)?;
+        Ok(())
+    }
+
+    fn cvtsi2sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cvtsi2sd(ctx, cfg, dest, src)?;
+        self.second.cvtsi2sd(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn cvttsd2si(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cvttsd2si(ctx, cfg, dest, src)?;
+        self.second.cvttsd2si(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn vfmadd231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.vfmadd231sd(ctx, cfg, dest, a, b)?;
+        self.second.vfmadd231sd(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn vfmsub231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.vfmsub231sd(ctx, cfg, dest, a, b)?;
+        self.second.vfmsub231sd(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn vfnmadd231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.vfnmadd231sd(ctx, cfg, dest, a, b)?;
+        self.second.vfnmadd231sd(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn vfnmsub231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.vfnmsub231sd(ctx, cfg, dest, a, b)?;
+        self.second.vfnmsub231sd(ctx, cfg, dest, a, b)?;
+        Ok(())
+    }
+
+    fn kmov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: MaskReg,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.kmov(ctx, cfg, dest, src)?;
+        self.second.kmov(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn vmovupd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.vmovupd(ctx, cfg, dest, mask, src)?;
+        self.second.vmovupd(ctx, cfg, dest, mask, src)?;
+        Ok(())
+    }
+
+    fn vaddpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.first.vaddpd(ctx, cfg, dest, mask, a, b, broadcast)?;
+        self.second.vaddpd(ctx, cfg, dest, mask, a, b, broadcast)?;
+        Ok(())
+    }
+
+    fn vsubpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.first.vsubpd(ctx, cfg, dest, mask, a, b, broadcast)?;
+        self.second.vsubpd(ctx, cfg, dest, mask, a, b, broadcast)?;
+        Ok(())
+    }
+
+    fn vmulpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.first.vmulpd(ctx, cfg, dest, mask, a, b, broadcast)?;
+        self.second.vmulpd(ctx, cfg, dest, mask, a, b, broadcast)?;
+        Ok(())
+    }
+
+    fn vdivpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.first.vdivpd(ctx, cfg, dest, mask, a, b, broadcast)?;
+        self.second.vdivpd(ctx, cfg, dest, mask, a, b, broadcast)?;
+        Ok(())
+    }
+
+    fn vfmadd231pd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.first
+            .vfmadd231pd(ctx, cfg, dest, mask, a, b, broadcast)?;
+        self.second
+            .vfmadd231pd(ctx, cfg, dest, mask, a, b, broadcast)?;
+        Ok(())
+    }
+
+    fn vcmppd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: MaskReg,
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        predicate: u8,
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        self.first
+            .vcmppd(ctx, cfg, dest, mask, a, b, predicate, broadcast)?;
+        self.second
+            .vcmppd(ctx, cfg, dest, mask, a, b, predicate, broadcast)?;
+        Ok(())
+    }
+
+    fn fmov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fmov(ctx, cfg, dest, src)?;
+        self.second.fmov(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn addss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.addss(ctx, cfg, dest, src)?;
+        self.second.addss(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn subss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.subss(ctx, cfg, dest, src)?;
+        self.second.subss(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn mulss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.mulss(ctx, cfg, dest, src)?;
+        self.second.mulss(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn divss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.divss(ctx, cfg, dest, src)?;
+        self.second.divss(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn ucomiss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ucomiss(ctx, cfg, a, b)?;
+        self.second.ucomiss(ctx, cfg, a, b)?;
+        Ok(())
+    }
+
+    fn minss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.minss(ctx, cfg, dest, src)?;
+        self.second.minss(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn maxss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.maxss(ctx, cfg, dest, src)?;
+        self.second.maxss(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn sqrtss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.sqrtss(ctx, cfg, dest, src)?;
+        self.second.sqrtss(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn cvtsi2ss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cvtsi2ss(ctx, cfg, dest, src)?;
+        self.second.cvtsi2ss(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn cvttss2si(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cvttss2si(ctx, cfg, dest, src)?;
+        self.second.cvttss2si(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn cvtss2sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cvtss2sd(ctx, cfg, dest, src)?;
+        self.second.cvtss2sd(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn cvtsd2ss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.cvtsd2ss(ctx, cfg, dest, src)?;
+        self.second.cvtsd2ss(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn movss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.movss(ctx, cfg, dest, src)?;
+        self.second.movss(ctx, cfg, dest, src)?;
+        Ok(())
+    }
+
+    fn ldmxcsr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.ldmxcsr(ctx, cfg, src)?;
+        self.second.ldmxcsr(ctx, cfg, src)?;
+        Ok(())
+    }
+
+    fn stmxcsr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.stmxcsr(ctx, cfg, dest)?;
+        self.second.stmxcsr(ctx, cfg, dest)?;
+        Ok(())
+    }
+
+    fn rdsegbase(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        seg: Segment,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.rdsegbase(ctx, cfg, seg, dest)?;
+        self.second.rdsegbase(ctx, cfg, seg, dest)?;
+        Ok(())
+    }
+
+    fn wrsegbase(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        seg: Segment,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.wrsegbase(ctx, cfg, seg, src)?;
+        self.second.wrsegbase(ctx, cfg, seg, src)?;
+        Ok(())
+    }
+
+    fn fld(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fld(ctx, cfg, src)?;
+        self.second.fld(ctx, cfg, src)?;
+        Ok(())
+    }
+
+    fn fst(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fst(ctx, cfg, dest)?;
+        self.second.fst(ctx, cfg, dest)?;
+        Ok(())
+    }
+
+    fn fstp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.fstp(ctx, cfg, dest)?;
+        self.second.fstp(ctx, cfg, dest)?;
+        Ok(())
+    }
+
+    fn faddp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.faddp(ctx, cfg)?;
+        self.second.faddp(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn fsubp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.fsubp(ctx, cfg)?;
+        self.second.fsubp(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn fmulp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.fmulp(ctx, cfg)?;
+        self.second.fmulp(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn fdivp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.fdivp(ctx, cfg)?;
+        self.second.fdivp(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn movs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.first.movs(ctx, cfg, size, rep)?;
+        self.second.movs(ctx, cfg, size, rep)?;
+        Ok(())
+    }
+
+    fn stos(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.first.stos(ctx, cfg, size, rep)?;
+        self.second.stos(ctx, cfg, size, rep)?;
+        Ok(())
+    }
+
+    fn cmps(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.first.cmps(ctx, cfg, size, rep)?;
+        self.second.cmps(ctx, cfg, size, rep)?;
+        Ok(())
+    }
+
+    fn scas(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        self.first.scas(ctx, cfg, size, rep)?;
+        self.second.scas(ctx, cfg, size, rep)?;
+        Ok(())
+    }
+
+    fn syscall(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.syscall(ctx, cfg)?;
+        self.second.syscall(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn endbr64(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.endbr64(ctx, cfg)?;
+        self.second.endbr64(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn ud2(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.ud2(ctx, cfg)?;
+        self.second.ud2(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn int3(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.int3(ctx, cfg)?;
+        self.second.int3(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn pause(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.pause(ctx, cfg)?;
+        self.second.pause(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn prefetcht0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.prefetcht0(ctx, cfg, mem)?;
+        self.second.prefetcht0(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn prefetcht1(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.prefetcht1(ctx, cfg, mem)?;
+        self.second.prefetcht1(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn prefetcht2(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.prefetcht2(ctx, cfg, mem)?;
+        self.second.prefetcht2(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn prefetchnta(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.prefetchnta(ctx, cfg, mem)?;
+        self.second.prefetchnta(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn clflush(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.clflush(ctx, cfg, mem)?;
+        self.second.clflush(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn clflushopt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.clflushopt(ctx, cfg, mem)?;
+        self.second.clflushopt(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn clwb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.first.clwb(ctx, cfg, mem)?;
+        self.second.clwb(ctx, cfg, mem)?;
+        Ok(())
+    }
+
+    fn sfence(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.sfence(ctx, cfg)?;
+        self.second.sfence(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn rdtsc(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.rdtsc(ctx, cfg)?;
+        self.second.rdtsc(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn rdtscp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.rdtscp(ctx, cfg)?;
+        self.second.rdtscp(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn unreachable(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.unreachable(ctx, cfg)?;
+        self.second.unreachable(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn debug_break(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.debug_break(ctx, cfg)?;
+        self.second.debug_break(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn db(&mut self, ctx: &mut Context, cfg: X64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.first.db(ctx, cfg, bytes)?;
+        self.second.db(ctx, cfg, bytes)?;
+        Ok(())
+    }
+
+    fn current_offset(&self) -> Option<usize> {
+        self.first
+            .current_offset()
+            .or_else(|| self.second.current_offset())
+    }
+
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        self.first.align_to(ctx, cfg, alignment)?;
+        self.second.align_to(ctx, cfg, alignment)?;
+        Ok(())
+    }
+
+    fn nop(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        self.first.nop(ctx, cfg)?;
+        self.second.nop(ctx, cfg)?;
+        Ok(())
+    }
+
+    fn nops(&mut self, ctx: &mut Context, cfg: X64Arch, n_bytes: usize) -> Result<(), Self::Error> {
+        self.first.nops(ctx, cfg, n_bytes)?;
+        self.second.nops(ctx, cfg, n_bytes)?;
+        Ok(())
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: X64Arch, pow2: u32) -> Result<(), Self::Error> {
+        self.first.align(ctx, cfg, pow2)?;
+        self.second.align(ctx, cfg, pow2)?;
+        Ok(())
+    }
+}
+
+/// Labels are cloned for the first writer and moved into the second.
+impl<A: Writer<L, Context>, B: Writer<L, Context>, L: Clone, Context> Writer<L, Context>
+    for TeeWriter<A, B>
+where
+    A::Error: From<B::Error>,
+{
+    fn set_label(&mut self, ctx: &mut Context, cfg: X64Arch, s: L) -> Result<(), Self::Error> {
+        self.first.set_label(ctx, cfg, s.clone())?;
+        self.second.set_label(ctx, cfg, s)?;
+        Ok(())
+    }
+
+    fn lea_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.first.lea_label(ctx, cfg, dest, label.clone())?;
+        self.second.lea_label(ctx, cfg, dest, label)?;
+        Ok(())
+    }
+
+    fn call_label(&mut self, ctx: &mut Context, cfg: X64Arch, label: L) -> Result<(), Self::Error> {
+        self.first.call_label(ctx, cfg, label.clone())?;
+        self.second.call_label(ctx, cfg, label)?;
+        Ok(())
+    }
+
+    fn jmp_label(&mut self, ctx: &mut Context, cfg: X64Arch, label: L) -> Result<(), Self::Error> {
+        self.first.jmp_label(ctx, cfg, label.clone())?;
+        self.second.jmp_label(ctx, cfg, label)?;
+        Ok(())
+    }
+
+    fn jcc_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        self.first.jcc_label(ctx, cfg, cc, label.clone())?;
+        self.second.jcc_label(ctx, cfg, cc, label)?;
+        Ok(())
+    }
+
+    fn jcc_label_hinted(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: ConditionCode,
+        label: L,
+        hint: BranchHint,
+    ) -> Result<(), Self::Error> {
+        self.first
+            .jcc_label_hinted(ctx, cfg, cc, label.clone(), hint)?;
+        self.second.jcc_label_hinted(ctx, cfg, cc, label, hint)?;
+        Ok(())
+    }
+
+    fn dd_label(&mut self, ctx: &mut Context, cfg: X64Arch, label: L) -> Result<(), Self::Error> {
+        self.first.dd_label(ctx, cfg, label.clone())?;
+        self.second.dd_label(ctx, cfg, label)?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::{self, Write};
+
+    /// A text sink that rejects every write.
+    struct Full;
+
+    impl Write for Full {
+        fn write_str(&mut self, _: &str) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    #[test]
+    fn test_labelled_call_reaches_both_writers() {
+        let cfg = X64Arch::default();
+        let (mut first, mut second) = (String::new(), String::new());
+        let mut tee = TeeWriter::new(&mut first as &mut dyn Write, &mut second as &mut dyn Write);
+        tee.call_label(&mut (), cfg, 1u32).unwrap();
+        tee.set_label(&mut (), cfg, 1u32).unwrap();
+        assert_eq!(first, "call 1\n1:\n");
+        assert_eq!(second, first);
+    }
+
+    #[test]
+    fn test_error_from_second_writer_is_returned() {
+        let cfg = X64Arch::default();
+        let (mut first, mut full) = (String::new(), Full);
+        let mut tee = TeeWriter::new(&mut first as &mut dyn Write, &mut full as &mut dyn Write);
+        assert_eq!(tee.hlt(&mut (), cfg), Err(fmt::Error));
+        assert_eq!(tee.set_label(&mut (), cfg, 1u32), Err(fmt::Error));
+        // The first writer took each call before the second failed it.
+        assert_eq!(first, "hlt\n1:\n");
+    }
+
+    #[cfg(feature = "iced")]
+    #[test]
+    fn test_listing_and_machine_code_in_one_pass() {
+        use crate::out::iced::IcedWriter;
+
+        let cfg = X64Arch::default();
+        let mut listing = String::new();
+        let mut tee = TeeWriter::new(&mut listing as &mut dyn Write, IcedWriter::<u32>::new(0));
+        tee.hlt(&mut (), cfg).unwrap();
+        tee.set_label(&mut (), cfg, 1u32).unwrap();
+        tee.hlt(&mut (), cfg).unwrap();
+        tee.call_label(&mut (), cfg, 1u32).unwrap();
+        assert_eq!(WriterCore::<()>::current_offset(&tee), Some(7));
+        let (_, binary) = tee.into_inner();
+        let (code, labels) = binary.into_parts();
+        assert_eq!(listing, "hlt\n1:\nhlt\ncall 1\n");
+        assert_eq!(code, [0xF4, 0xF4, 0xE8, 0xFA, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(labels[&1], 1);
+    }
+}
@@ -0,0 +1,82 @@
+//! Reads of the time-stamp counter.
+//!
+//! [`ReadTimestamp::read_timestamp`] reads a 64-bit counter that increases
+//! at a constant rate into a register, for timers and profiling:
+//!
+//! ```ignore
+//! writer.read_timestamp(ctx, cfg, &Reg(7))?;
+//! // rdtsc
+//! // shl rdx, 32
+//! // or rax, rdx
+//! // mov rdi, rax
+//! ```
+//!
+//! `rdtsc` returns the counter split over EDX:EAX, so the halves are joined
+//! in RAX, clobbering RAX, RDX and the flags. The read is not ordered
+//! against the surrounding code; emit [`rdtscp`](WriterCore::rdtscp) or an
+//! `lfence` first to time a region precisely. The AArch64 crate reads
+//! `cntvct_el0` and the RISC-V crate `time`, neither of which clobbers
+//! anything.
+
+use portal_pc_asm_common::types::{mem::MemorySize, reg::Reg};
+
+use crate::X64Arch;
+use crate::out::WriterCore;
+use crate::out::arg::{ArgKind, MemArg, MemArgKind};
+
+const RAX: Reg = Reg(0);
+const RDX: Reg = Reg(2);
+
+fn reg64(reg: Reg) -> ArgKind {
+    ArgKind::Reg {
+        reg,
+        size: MemorySize::_64,
+    }
+}
+
+/// Emits reads of the time-stamp counter.
+///
+/// Implemented for every [`WriterCore`].
+pub trait ReadTimestamp<Context>: WriterCore<Context> {
+    /// Reads the 64-bit time-stamp counter into `dest`, a register or
+    /// memory.
+    ///
+    /// Clobbers RAX, RDX and the flags.
+    fn read_timestamp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        self.rdtsc(ctx, cfg)?;
+        self.shl(ctx, cfg, &reg64(RDX), &ArgKind::Lit(32))?;
+        self.or(ctx, cfg, &reg64(RAX), &reg64(RDX))?;
+        match dest.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Reg { reg, .. }) if reg == RAX => Ok(()),
+            _ => self.mov(ctx, cfg, dest, &reg64(RAX)),
+        }
+    }
+}
+
+impl<Context, W: WriterCore<Context> + ?Sized> ReadTimestamp<Context> for W {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_joins_the_halves() {
+        let mut output = String::new();
+        let w: &mut dyn Write = &mut output;
+        let cfg = X64Arch::default();
+        w.read_timestamp(&mut (), cfg, &reg64(Reg(7))).unwrap();
+        w.read_timestamp(&mut (), cfg, &reg64(RAX)).unwrap();
+        assert_eq!(
+            output,
+            "rdtsc\nshl rdx,32\nor rax,rdx\nmov rdi, rax\n\
+             rdtsc\nshl rdx,32\nor rax,rdx\n"
+        );
+    }
+}
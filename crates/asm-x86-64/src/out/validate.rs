@@ -0,0 +1,1938 @@
+//! Instruction validation against the arch configuration.
+//!
+//! Writers emit whatever they are asked to: a text writer happily prints
+//! `r17` for a core without APX, and the mistake only surfaces when the
+//! assembler or the hardware rejects it. [`ValidatingWriter`] sits
+//! in front of another writer and checks each instruction against the
+//! [`X64Arch`] it is emitted for, returning an [`EmitError`] instead of
+//! forwarding it:
+//!
+//! - general-purpose registers `r16`–`r31` require APX, including as the
+//!   base or index of a memory operand;
+//! - the x87 stack instructions require [`X64Arch::x87`], and the segment
+//!   base instructions [`X64Arch::fsgsbase`] and an `fs` or `gs` segment;
+//! - `clflushopt`, `clwb` and `rdtscp` require [`X64Arch::clflushopt`],
+//!   [`X64Arch::clwb`] and [`X64Arch::rdtscp`];
+//! - prefetches and cache-line flushes take a memory operand;
+//! - the EVEX instructions require [`X64Arch::avx512`] and opmask registers
+//!   `k0`–`k7`; zeroing needs a mask other than `k0` and cannot apply to
+//!   stores or compares, and only memory operands can be broadcast;
+//! - register operands must name one of the 32 registers;
+//! - `ah`, `ch`, `dh` and `bh` cannot share an instruction with an operand
+//!   that needs a REX prefix: `spl`–`dil`, `r8`–`r31`, `xmm8` and up, or a
+//!   64-bit register;
+//! - `rip` may only be the base of a memory operand without an index;
+//! - under [`ImmEncoding::Imm32`](crate::ImmEncoding::Imm32), immediates
+//!   moved into 64-bit registers must fit in an `i32`.
+//!
+//! Place it below [`DesugaringWriter`](crate::desugar::DesugaringWriter) to
+//! check the lowered output, or above it to check what callers request.
+
+use core::ops::{Deref, DerefMut};
+
+use crate::out::arg::{ArgKind, MemArg, MemArgKind, Segment};
+use crate::out::error::EmitError;
+use crate::out::evex::OpMask;
+use crate::out::{Writer, WriterCore};
+use crate::{ImmEncoding, MaskReg, RepPrefix, X64Arch};
+use portal_pc_asm_common::types::mem::MemorySize;
+
+/// Rejects instructions the arch configuration does not support.
+///
+/// Every instruction is checked against the `cfg` it is emitted with before
+/// being forwarded to the wrapped writer; rejected instructions are not
+/// forwarded.
+pub struct ValidatingWriter<W> {
+    writer: W,
+}
+
+impl<W> ValidatingWriter<W> {
+    /// Wraps `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W> Deref for ValidatingWriter<W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        &self.writer
+    }
+}
+
+impl<W> DerefMut for ValidatingWriter<W> {
+    fn deref_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+/// SSE instructions, whose register operands are XMM rather than general-purpose registers.
+const XMM_OPS: &[&str] = &[
+    "fadd",
+    "fsub",
+    "fmul",
+    "fdiv",
+    "fcmp",
+    "fmin",
+    "fmax",
+    "fsqrt",
+    "fabs",
+    "fneg",
+    "fmov",
+    "vfmadd231sd",
+    "vfmsub231sd",
+    "vfnmadd231sd",
+    "vfnmsub231sd",
+    "addss",
+    "subss",
+    "mulss",
+    "divss",
+    "ucomiss",
+    "minss",
+    "maxss",
+    "sqrtss",
+    "cvtss2sd",
+    "cvtsd2ss",
+    "movss",
+    "vmovupd",
+    "vaddpd",
+    "vsubpd",
+    "vmulpd",
+    "vdivpd",
+    "vfmadd231pd",
+    "vcmppd",
+];
+
+/// Returns the extension `mnemonic` needs, if `cfg` does not enable it.
+fn missing_extension(cfg: X64Arch, mnemonic: &str) -> Option<&'static str> {
+    match mnemonic {
+        "fld" | "fst" | "fstp" | "faddp" | "fsubp" | "fmulp" | "fdivp" if !cfg.x87 => Some("x87"),
+        "vfmadd231sd" | "vfmsub231sd" | "vfnmadd231sd" | "vfnmsub231sd" if !cfg.fma => Some("FMA"),
+        "rdsegbase" | "wrsegbase" if !cfg.fsgsbase => Some("FSGSBASE"),
+        "clflushopt" if !cfg.clflushopt => Some("CLFLUSHOPT"),
+        "clwb" if !cfg.clwb => Some("CLWB"),
+        "rdtscp" if !cfg.rdtscp => Some("RDTSCP"),
+        "kmov" | "vmovupd" | "vaddpd" | "vsubpd" | "vmulpd" | "vdivpd" | "vfmadd231pd"
+        | "vcmppd"
+            if !cfg.avx512 =>
+        {
+            Some("AVX-512")
+        }
+        _ => None,
+    }
+}
+
+/// Checks that a register operand exists under `cfg`.
+///
+/// `gpr` is false for XMM operands, which go up to `xmm31` without APX.
+fn check_arg(
+    cfg: X64Arch,
+    mnemonic: &'static str,
+    arg: ArgKind,
+    gpr: bool,
+) -> Result<(), EmitError> {
+    match arg {
+        // Reg(255) is the context register, which backends map to r15.
+        ArgKind::Reg { reg, .. } if reg.0 == 255 => Ok(()),
+        ArgKind::Reg { reg, .. } if reg.0 >= 32 => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "register number out of range",
+        }),
+        ArgKind::Reg { reg, .. } if gpr && reg.0 >= 16 && !cfg.apx => {
+            Err(EmitError::ExtensionNotEnabled {
+                mnemonic,
+                extension: "APX",
+            })
+        }
+        ArgKind::HighByte(reg) if reg.0 >= 4 => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "only registers 0-3 have a high byte (ah, ch, dh, bh)",
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Returns whether encoding `arg` needs a REX prefix.
+///
+/// `gpr` is as for [`check_arg`]; only general-purpose operands widen to
+/// REX.W.
+fn needs_rex(arg: ArgKind, gpr: bool) -> bool {
+    match arg {
+        ArgKind::Reg { reg, size } => {
+            reg.0 >= 8
+                || (gpr && size == MemorySize::_8 && reg.0 >= 4)
+                || (gpr && size == MemorySize::_64)
+        }
+        _ => false,
+    }
+}
+
+/// Checks that `k` is one of the eight opmask registers.
+fn check_mask_reg(mnemonic: &'static str, k: MaskReg) -> Result<(), EmitError> {
+    if k.0 >= 8 {
+        return Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "opmask registers are k0-k7",
+        });
+    }
+    Ok(())
+}
+
+/// Checks the write mask of an EVEX instruction and its broadcast source.
+///
+/// `can_zero` is false for stores and compares, which only merge.
+fn check_evex(
+    mnemonic: &'static str,
+    mask: OpMask,
+    can_zero: bool,
+    src: &(dyn MemArg + '_),
+    broadcast: bool,
+) -> Result<(), EmitError> {
+    check_mask_reg(mnemonic, mask.reg)?;
+    let reason = if mask.zeroing && !mask.is_masked() {
+        "zeroing needs a mask register other than k0"
+    } else if mask.zeroing && !can_zero {
+        "stores and compares cannot zero masked-off elements"
+    } else if broadcast && !matches!(src.concrete_mem_kind(), MemArgKind::Mem { .. }) {
+        "only a memory operand can be broadcast"
+    } else {
+        return Ok(());
+    };
+    Err(EmitError::InvalidOperands { mnemonic, reason })
+}
+
+/// Checks that `seg` is a segment with a base register.
+fn check_segment_base(mnemonic: &'static str, seg: Segment) -> Result<(), EmitError> {
+    match seg {
+        Segment::Fs | Segment::Gs => Ok(()),
+        _ => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "only fs and gs have a base register",
+        }),
+    }
+}
+
+/// Checks that `op` is a memory operand.
+fn check_memory(mnemonic: &'static str, op: &(dyn MemArg + '_)) -> Result<(), EmitError> {
+    match op.concrete_mem_kind() {
+        MemArgKind::Mem { .. } => Ok(()),
+        MemArgKind::NoMem(_) => Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "operand must be a memory address",
+        }),
+    }
+}
+
+/// Checks that a 64-bit move of `val` can take the sign-extended 32-bit
+/// immediate that [`ImmEncoding::Imm32`] forces.
+fn check_imm32(cfg: X64Arch, mnemonic: &'static str, val: u64) -> Result<(), EmitError> {
+    if cfg.imm_encoding == ImmEncoding::Imm32 && i32::try_from(val as i64).is_err() {
+        return Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "immediate does not fit the forced 32-bit encoding",
+        });
+    }
+    Ok(())
+}
+
+/// Checks one instruction against `cfg`.
+///
+/// `mnemonic` is the name of the [`WriterCore`] or [`Writer`] method.
+pub fn check(
+    cfg: X64Arch,
+    mnemonic: &'static str,
+    operands: &[&(dyn MemArg + '_)],
+) -> Result<(), EmitError> {
+    if let Some(extension) = missing_extension(cfg, mnemonic) {
+        return Err(EmitError::ExtensionNotEnabled {
+            mnemonic,
+            extension,
+        });
+    }
+    let xmm = XMM_OPS.contains(&mnemonic);
+    let (mut high_byte, mut rex) = (false, false);
+    for op in operands {
+        match op.concrete_mem_kind() {
+            MemArgKind::NoMem(ArgKind::Rip)
+            | MemArgKind::Mem {
+                offset: Some((ArgKind::Rip, _)),
+                ..
+            } => {
+                return Err(EmitError::InvalidOperands {
+                    mnemonic,
+                    reason: "rip can only be the base of a memory operand",
+                });
+            }
+            MemArgKind::NoMem(ArgKind::NoBase)
+            | MemArgKind::Mem {
+                offset: Some((ArgKind::NoBase, _)),
+                ..
+            } => {
+                return Err(EmitError::InvalidOperands {
+                    mnemonic,
+                    reason: "only the base of a memory operand can be left out",
+                });
+            }
+            MemArgKind::Mem {
+                base: ArgKind::Rip,
+                offset: Some(_),
+                ..
+            } => {
+                return Err(EmitError::InvalidOperands {
+                    mnemonic,
+                    reason: "a RIP-relative address cannot take an index",
+                });
+            }
+            MemArgKind::NoMem(arg) => {
+                check_arg(cfg, mnemonic, arg, !xmm)?;
+                high_byte |= matches!(arg, ArgKind::HighByte(_));
+                rex |= needs_rex(arg, !xmm);
+            }
+            MemArgKind::Mem { base, offset, .. } => {
+                check_arg(cfg, mnemonic, base, true)?;
+                rex |= matches!(base, ArgKind::Reg { reg, .. } if reg.0 >= 8);
+                if let Some((index, _)) = offset {
+                    check_arg(cfg, mnemonic, index, true)?;
+                    rex |= matches!(index, ArgKind::Reg { reg, .. } if reg.0 >= 8);
+                }
+            }
+        }
+    }
+    if high_byte && rex {
+        return Err(EmitError::InvalidOperands {
+            mnemonic,
+            reason: "ah, ch, dh and bh cannot be combined with an operand that needs REX",
+        });
+    }
+    Ok(())
+}
+
+impl<W: WriterCore<Context>, Context> WriterCore<Context> for ValidatingWriter<W>
+where
+    W::Error: From<EmitError>,
+{
+    type Error = W::Error;
+
+    fn hlt(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "hlt", &[])?;
+        self.writer.hlt(ctx, cfg)
+    }
+
+    fn xchg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "xchg", &[dest, src])?;
+        self.writer.xchg(ctx, cfg, dest, src)
+    }
+
+    fn push(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "push", &[op])?;
+        self.writer.push(ctx, cfg, op)
+    }
+
+    fn pop(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "pop", &[op])?;
+        self.writer.pop(ctx, cfg, op)
+    }
+
+    fn pushf(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "pushf", &[])?;
+        self.writer.pushf(ctx, cfg)
+    }
+
+    fn popf(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "popf", &[])?;
+        self.writer.popf(ctx, cfg)
+    }
+
+    fn lahf(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "lahf", &[])?;
+        self.writer.lahf(ctx, cfg)
+    }
+
+    fn sahf(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "sahf", &[])?;
+        self.writer.sahf(ctx, cfg)
+    }
+
+    fn call(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "call", &[op])?;
+        self.writer.call(ctx, cfg, op)
+    }
+
+    fn jmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "jmp", &[op])?;
+        self.writer.jmp(ctx, cfg, op)
+    }
+
+    fn cmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cmp", &[a, b])?;
+        self.writer.cmp(ctx, cfg, a, b)
+    }
+
+    fn cmp0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cmp0", &[op])?;
+        self.writer.cmp0(ctx, cfg, op)
+    }
+
+    fn test(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "test", &[a, b])?;
+        self.writer.test(ctx, cfg, a, b)
+    }
+
+    fn test0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "test0", &[op])?;
+        self.writer.test0(ctx, cfg, op)
+    }
+
+    fn cmovcc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: crate::ConditionCode,
+        op: &(dyn MemArg + '_),
+        val: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cmovcc", &[op, val])?;
+        self.writer.cmovcc(ctx, cfg, cc, op, val)
+    }
+
+    fn setcc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: crate::ConditionCode,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "setcc", &[op])?;
+        self.writer.setcc(ctx, cfg, cc, op)
+    }
+
+    fn lea(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lea", &[dest, src])?;
+        self.writer.lea(ctx, cfg, dest, src)
+    }
+
+    fn get_ip(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "get_ip", &[])?;
+        self.writer.get_ip(ctx, cfg)
+    }
+
+    fn ret(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "ret", &[])?;
+        self.writer.ret(ctx, cfg)
+    }
+
+    fn mov64(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        r: &(dyn MemArg + '_),
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mov64", &[r])?;
+        check_imm32(cfg, "mov64", val)?;
+        self.writer.mov64(ctx, cfg, r, val)
+    }
+
+    fn mov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mov", &[dest, src])?;
+        if let (
+            MemArgKind::NoMem(ArgKind::Reg {
+                size: MemorySize::_64,
+                ..
+            }),
+            MemArgKind::NoMem(ArgKind::Lit(val)),
+        ) = (dest.concrete_mem_kind(), src.concrete_mem_kind())
+        {
+            check_imm32(cfg, "mov", val)?;
+        }
+        self.writer.mov(ctx, cfg, dest, src)
+    }
+
+    fn not(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "not", &[op])?;
+        self.writer.not(ctx, cfg, op)
+    }
+
+    fn neg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "neg", &[op])?;
+        self.writer.neg(ctx, cfg, op)
+    }
+
+    fn inc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "inc", &[op])?;
+        self.writer.inc(ctx, cfg, op)
+    }
+
+    fn dec(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "dec", &[op])?;
+        self.writer.dec(ctx, cfg, op)
+    }
+
+    fn mul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mul", &[a, b])?;
+        self.writer.mul(ctx, cfg, a, b)
+    }
+
+    fn imul2(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "imul2", &[dest, src])?;
+        self.writer.imul2(ctx, cfg, dest, src)
+    }
+
+    fn imul3(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "imul3", &[dest, src])?;
+        self.writer.imul3(ctx, cfg, dest, src, imm)
+    }
+
+    fn mul_wide(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        hi: &(dyn MemArg + '_),
+        lo: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mul_wide", &[hi, lo, a, b])?;
+        self.writer.mul_wide(ctx, cfg, hi, lo, a, b)
+    }
+
+    fn imul_wide(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        hi: &(dyn MemArg + '_),
+        lo: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "imul_wide", &[hi, lo, a, b])?;
+        self.writer.imul_wide(ctx, cfg, hi, lo, a, b)
+    }
+
+    fn div(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "div", &[a, b])?;
+        self.writer.div(ctx, cfg, a, b)
+    }
+
+    fn idiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "idiv", &[a, b])?;
+        self.writer.idiv(ctx, cfg, a, b)
+    }
+
+    fn and(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "and", &[a, b])?;
+        self.writer.and(ctx, cfg, a, b)
+    }
+
+    fn or(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "or", &[a, b])?;
+        self.writer.or(ctx, cfg, a, b)
+    }
+
+    fn eor(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "eor", &[a, b])?;
+        self.writer.eor(ctx, cfg, a, b)
+    }
+
+    fn shl(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "shl", &[a, b])?;
+        self.writer.shl(ctx, cfg, a, b)
+    }
+
+    fn shr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "shr", &[a, b])?;
+        self.writer.shr(ctx, cfg, a, b)
+    }
+
+    fn sar(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sar", &[a, b])?;
+        self.writer.sar(ctx, cfg, a, b)
+    }
+
+    fn rol(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "rol", &[a, b])?;
+        self.writer.rol(ctx, cfg, a, b)
+    }
+
+    fn ror(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ror", &[a, b])?;
+        self.writer.ror(ctx, cfg, a, b)
+    }
+
+    fn sub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sub", &[a, b])?;
+        self.writer.sub(ctx, cfg, a, b)
+    }
+
+    fn add(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "add", &[a, b])?;
+        self.writer.add(ctx, cfg, a, b)
+    }
+
+    fn adc(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "adc", &[a, b])?;
+        self.writer.adc(ctx, cfg, a, b)
+    }
+
+    fn sbb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sbb", &[a, b])?;
+        self.writer.sbb(ctx, cfg, a, b)
+    }
+
+    fn add_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "add_imm", &[a])?;
+        self.writer.add_imm(ctx, cfg, a, imm)
+    }
+
+    fn sub_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sub_imm", &[a])?;
+        self.writer.sub_imm(ctx, cfg, a, imm)
+    }
+
+    fn and_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "and_imm", &[a])?;
+        self.writer.and_imm(ctx, cfg, a, imm)
+    }
+
+    fn or_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "or_imm", &[a])?;
+        self.writer.or_imm(ctx, cfg, a, imm)
+    }
+
+    fn eor_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "eor_imm", &[a])?;
+        self.writer.eor_imm(ctx, cfg, a, imm)
+    }
+
+    fn cmp_imm(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        imm: i32,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cmp_imm", &[a])?;
+        self.writer.cmp_imm(ctx, cfg, a, imm)
+    }
+
+    fn movsx(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "movsx", &[dest, src])?;
+        self.writer.movsx(ctx, cfg, dest, src)
+    }
+
+    fn movzx(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "movzx", &[dest, src])?;
+        self.writer.movzx(ctx, cfg, dest, src)
+    }
+
+    fn popcnt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "popcnt", &[dest, src])?;
+        self.writer.popcnt(ctx, cfg, dest, src)
+    }
+
+    fn lzcnt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lzcnt", &[dest, src])?;
+        self.writer.lzcnt(ctx, cfg, dest, src)
+    }
+
+    fn tzcnt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "tzcnt", &[dest, src])?;
+        self.writer.tzcnt(ctx, cfg, dest, src)
+    }
+
+    fn bswap(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        op: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "bswap", &[op])?;
+        self.writer.bswap(ctx, cfg, op)
+    }
+
+    fn fadd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fadd", &[dest, src])?;
+        self.writer.fadd(ctx, cfg, dest, src)
+    }
+
+    fn fsub(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fsub", &[dest, src])?;
+        self.writer.fsub(ctx, cfg, dest, src)
+    }
+
+    fn fmul(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmul", &[dest, src])?;
+        self.writer.fmul(ctx, cfg, dest, src)
+    }
+
+    fn fdiv(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fdiv", &[dest, src])?;
+        self.writer.fdiv(ctx, cfg, dest, src)
+    }
+
+    fn fcmp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fcmp", &[a, b])?;
+        self.writer.fcmp(ctx, cfg, a, b)
+    }
+
+    fn fmin(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmin", &[dest, src])?;
+        self.writer.fmin(ctx, cfg, dest, src)
+    }
+
+    fn fmax(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmax", &[dest, src])?;
+        self.writer.fmax(ctx, cfg, dest, src)
+    }
+
+    fn fsqrt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fsqrt", &[dest, src])?;
+        self.writer.fsqrt(ctx, cfg, dest, src)
+    }
+
+    fn fabs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fabs", &[dest, scratch])?;
+        self.writer.fabs(ctx, cfg, dest, scratch)
+    }
+
+    fn fneg(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        scratch: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fneg", &[dest, scratch])?;
+        self.writer.fneg(ctx, cfg, dest, scratch)
+    }
+
+    fn cvtsi2sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Only the integer operand is a general-purpose register.
+        check(cfg, "cvtsi2sd", &[src])?;
+        self.writer.cvtsi2sd(ctx, cfg, dest, src)
+    }
+
+    fn cvttsd2si(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Only the integer operand is a general-purpose register.
+        check(cfg, "cvttsd2si", &[dest])?;
+        self.writer.cvttsd2si(ctx, cfg, dest, src)
+    }
+
+    fn vfmadd231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "vfmadd231sd", &[dest, a, b])?;
+        self.writer.vfmadd231sd(ctx, cfg, dest, a, b)
+    }
+
+    fn vfmsub231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "vfmsub231sd", &[dest, a, b])?;
+        self.writer.vfmsub231sd(ctx, cfg, dest, a, b)
+    }
+
+    fn vfnmadd231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "vfnmadd231sd", &[dest, a, b])?;
+        self.writer.vfnmadd231sd(ctx, cfg, dest, a, b)
+    }
+
+    fn vfnmsub231sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "vfnmsub231sd", &[dest, a, b])?;
+        self.writer.vfnmsub231sd(ctx, cfg, dest, a, b)
+    }
+
+    fn kmov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: MaskReg,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "kmov", &[src])?;
+        check_mask_reg("kmov", dest)?;
+        self.writer.kmov(ctx, cfg, dest, src)
+    }
+
+    fn vmovupd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "vmovupd", &[dest, src])?;
+        let store = matches!(dest.concrete_mem_kind(), MemArgKind::Mem { .. });
+        check_evex("vmovupd", mask, !store, src, false)?;
+        self.writer.vmovupd(ctx, cfg, dest, mask, src)
+    }
+
+    fn vaddpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "vaddpd", &[dest, a, b])?;
+        check_evex("vaddpd", mask, true, b, broadcast)?;
+        self.writer.vaddpd(ctx, cfg, dest, mask, a, b, broadcast)
+    }
+
+    fn vsubpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "vsubpd", &[dest, a, b])?;
+        check_evex("vsubpd", mask, true, b, broadcast)?;
+        self.writer.vsubpd(ctx, cfg, dest, mask, a, b, broadcast)
+    }
+
+    fn vmulpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "vmulpd", &[dest, a, b])?;
+        check_evex("vmulpd", mask, true, b, broadcast)?;
+        self.writer.vmulpd(ctx, cfg, dest, mask, a, b, broadcast)
+    }
+
+    fn vdivpd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "vdivpd", &[dest, a, b])?;
+        check_evex("vdivpd", mask, true, b, broadcast)?;
+        self.writer.vdivpd(ctx, cfg, dest, mask, a, b, broadcast)
+    }
+
+    fn vfmadd231pd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "vfmadd231pd", &[dest, a, b])?;
+        check_evex("vfmadd231pd", mask, true, b, broadcast)?;
+        self.writer
+            .vfmadd231pd(ctx, cfg, dest, mask, a, b, broadcast)
+    }
+
+    fn vcmppd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: MaskReg,
+        mask: OpMask,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+        predicate: u8,
+        broadcast: bool,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "vcmppd", &[a, b])?;
+        check_mask_reg("vcmppd", dest)?;
+        check_evex("vcmppd", mask, false, b, broadcast)?;
+        self.writer
+            .vcmppd(ctx, cfg, dest, mask, a, b, predicate, broadcast)
+    }
+
+    fn fmov(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fmov", &[dest, src])?;
+        self.writer.fmov(ctx, cfg, dest, src)
+    }
+
+    fn addss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "addss", &[dest, src])?;
+        self.writer.addss(ctx, cfg, dest, src)
+    }
+
+    fn subss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "subss", &[dest, src])?;
+        self.writer.subss(ctx, cfg, dest, src)
+    }
+
+    fn mulss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "mulss", &[dest, src])?;
+        self.writer.mulss(ctx, cfg, dest, src)
+    }
+
+    fn divss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "divss", &[dest, src])?;
+        self.writer.divss(ctx, cfg, dest, src)
+    }
+
+    fn ucomiss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        a: &(dyn MemArg + '_),
+        b: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ucomiss", &[a, b])?;
+        self.writer.ucomiss(ctx, cfg, a, b)
+    }
+
+    fn minss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "minss", &[dest, src])?;
+        self.writer.minss(ctx, cfg, dest, src)
+    }
+
+    fn maxss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "maxss", &[dest, src])?;
+        self.writer.maxss(ctx, cfg, dest, src)
+    }
+
+    fn sqrtss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "sqrtss", &[dest, src])?;
+        self.writer.sqrtss(ctx, cfg, dest, src)
+    }
+
+    fn cvtsi2ss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Only the integer operand is a general-purpose register.
+        check(cfg, "cvtsi2ss", &[src])?;
+        self.writer.cvtsi2ss(ctx, cfg, dest, src)
+    }
+
+    fn cvttss2si(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        // Only the integer operand is a general-purpose register.
+        check(cfg, "cvttss2si", &[dest])?;
+        self.writer.cvttss2si(ctx, cfg, dest, src)
+    }
+
+    fn cvtss2sd(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cvtss2sd", &[dest, src])?;
+        self.writer.cvtss2sd(ctx, cfg, dest, src)
+    }
+
+    fn cvtsd2ss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cvtsd2ss", &[dest, src])?;
+        self.writer.cvtsd2ss(ctx, cfg, dest, src)
+    }
+
+    fn movss(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "movss", &[dest, src])?;
+        self.writer.movss(ctx, cfg, dest, src)
+    }
+
+    fn ldmxcsr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "ldmxcsr", &[src])?;
+        self.writer.ldmxcsr(ctx, cfg, src)
+    }
+
+    fn stmxcsr(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "stmxcsr", &[dest])?;
+        self.writer.stmxcsr(ctx, cfg, dest)
+    }
+
+    fn rdsegbase(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        seg: Segment,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "rdsegbase", &[dest])?;
+        check_segment_base("rdsegbase", seg)?;
+        self.writer.rdsegbase(ctx, cfg, seg, dest)
+    }
+
+    fn wrsegbase(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        seg: Segment,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "wrsegbase", &[src])?;
+        check_segment_base("wrsegbase", seg)?;
+        self.writer.wrsegbase(ctx, cfg, seg, src)
+    }
+
+    fn fld(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        src: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fld", &[src])?;
+        self.writer.fld(ctx, cfg, src)
+    }
+
+    fn fst(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fst", &[dest])?;
+        self.writer.fst(ctx, cfg, dest)
+    }
+
+    fn fstp(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "fstp", &[dest])?;
+        self.writer.fstp(ctx, cfg, dest)
+    }
+
+    fn faddp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "faddp", &[])?;
+        self.writer.faddp(ctx, cfg)
+    }
+
+    fn fsubp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "fsubp", &[])?;
+        self.writer.fsubp(ctx, cfg)
+    }
+
+    fn fmulp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "fmulp", &[])?;
+        self.writer.fmulp(ctx, cfg)
+    }
+
+    fn fdivp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "fdivp", &[])?;
+        self.writer.fdivp(ctx, cfg)
+    }
+
+    fn movs(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "movs", &[])?;
+        self.writer.movs(ctx, cfg, size, rep)
+    }
+
+    fn stos(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "stos", &[])?;
+        self.writer.stos(ctx, cfg, size, rep)
+    }
+
+    fn cmps(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "cmps", &[])?;
+        self.writer.cmps(ctx, cfg, size, rep)
+    }
+
+    fn scas(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        size: MemorySize,
+        rep: RepPrefix,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "scas", &[])?;
+        self.writer.scas(ctx, cfg, size, rep)
+    }
+
+    fn syscall(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "syscall", &[])?;
+        self.writer.syscall(ctx, cfg)
+    }
+
+    fn endbr64(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "endbr64", &[])?;
+        self.writer.endbr64(ctx, cfg)
+    }
+
+    fn ud2(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "ud2", &[])?;
+        self.writer.ud2(ctx, cfg)
+    }
+
+    fn int3(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "int3", &[])?;
+        self.writer.int3(ctx, cfg)
+    }
+
+    fn pause(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "pause", &[])?;
+        self.writer.pause(ctx, cfg)
+    }
+
+    fn prefetcht0(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "prefetcht0", &[mem])?;
+        check_memory("prefetcht0", mem)?;
+        self.writer.prefetcht0(ctx, cfg, mem)
+    }
+
+    fn prefetcht1(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "prefetcht1", &[mem])?;
+        check_memory("prefetcht1", mem)?;
+        self.writer.prefetcht1(ctx, cfg, mem)
+    }
+
+    fn prefetcht2(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "prefetcht2", &[mem])?;
+        check_memory("prefetcht2", mem)?;
+        self.writer.prefetcht2(ctx, cfg, mem)
+    }
+
+    fn prefetchnta(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "prefetchnta", &[mem])?;
+        check_memory("prefetchnta", mem)?;
+        self.writer.prefetchnta(ctx, cfg, mem)
+    }
+
+    fn clflush(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "clflush", &[mem])?;
+        check_memory("clflush", mem)?;
+        self.writer.clflush(ctx, cfg, mem)
+    }
+
+    fn clflushopt(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "clflushopt", &[mem])?;
+        check_memory("clflushopt", mem)?;
+        self.writer.clflushopt(ctx, cfg, mem)
+    }
+
+    fn clwb(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        mem: &(dyn MemArg + '_),
+    ) -> Result<(), Self::Error> {
+        check(cfg, "clwb", &[mem])?;
+        check_memory("clwb", mem)?;
+        self.writer.clwb(ctx, cfg, mem)
+    }
+
+    fn sfence(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "sfence", &[])?;
+        self.writer.sfence(ctx, cfg)
+    }
+
+    fn rdtsc(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "rdtsc", &[])?;
+        self.writer.rdtsc(ctx, cfg)
+    }
+
+    fn rdtscp(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "rdtscp", &[])?;
+        self.writer.rdtscp(ctx, cfg)
+    }
+
+    fn db(&mut self, ctx: &mut Context, cfg: X64Arch, bytes: &[u8]) -> Result<(), Self::Error> {
+        check(cfg, "db", &[])?;
+        self.writer.db(ctx, cfg, bytes)
+    }
+
+    fn current_offset(&self) -> Option<usize> {
+        self.writer.current_offset()
+    }
+
+    fn align_to(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        alignment: usize,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "align_to", &[])?;
+        self.writer.align_to(ctx, cfg, alignment)
+    }
+
+    fn nop(&mut self, ctx: &mut Context, cfg: X64Arch) -> Result<(), Self::Error> {
+        check(cfg, "nop", &[])?;
+        self.writer.nop(ctx, cfg)
+    }
+
+    fn nops(&mut self, ctx: &mut Context, cfg: X64Arch, n_bytes: usize) -> Result<(), Self::Error> {
+        check(cfg, "nops", &[])?;
+        self.writer.nops(ctx, cfg, n_bytes)
+    }
+
+    fn align(&mut self, ctx: &mut Context, cfg: X64Arch, pow2: u32) -> Result<(), Self::Error> {
+        check(cfg, "align", &[])?;
+        self.writer.align(ctx, cfg, pow2)
+    }
+}
+
+impl<W: Writer<L, Context>, L, Context> Writer<L, Context> for ValidatingWriter<W>
+where
+    W::Error: From<EmitError>,
+{
+    fn set_label(&mut self, ctx: &mut Context, cfg: X64Arch, s: L) -> Result<(), Self::Error> {
+        check(cfg, "set_label", &[])?;
+        self.writer.set_label(ctx, cfg, s)
+    }
+
+    fn lea_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        dest: &(dyn MemArg + '_),
+        label: L,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "lea_label", &[dest])?;
+        self.writer.lea_label(ctx, cfg, dest, label)
+    }
+
+    fn call_label(&mut self, ctx: &mut Context, cfg: X64Arch, label: L) -> Result<(), Self::Error> {
+        check(cfg, "call_label", &[])?;
+        self.writer.call_label(ctx, cfg, label)
+    }
+
+    fn jmp_label(&mut self, ctx: &mut Context, cfg: X64Arch, label: L) -> Result<(), Self::Error> {
+        check(cfg, "jmp_label", &[])?;
+        self.writer.jmp_label(ctx, cfg, label)
+    }
+
+    fn jcc_label(
+        &mut self,
+        ctx: &mut Context,
+        cfg: X64Arch,
+        cc: crate::ConditionCode,
+        label: L,
+    ) -> Result<(), Self::Error> {
+        check(cfg, "jcc_label", &[])?;
+        self.writer.jcc_label(ctx, cfg, cc, label)
+    }
+
+    fn dd_label(&mut self, ctx: &mut Context, cfg: X64Arch, label: L) -> Result<(), Self::Error> {
+        check(cfg, "dd_label", &[])?;
+        self.writer.dd_label(ctx, cfg, label)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+    use portal_pc_asm_common::types::reg::Reg;
+
+    #[test]
+    fn test_rejects_disabled_extensions() {
+        let base = X64Arch::default();
+        let (rax, r17) = (Reg(0), Reg(17));
+        assert_eq!(
+            check(base, "mov", &[&rax, &r17]),
+            Err(EmitError::ExtensionNotEnabled {
+                mnemonic: "mov",
+                extension: "APX",
+            })
+        );
+        assert!(check(base, "fadd", &[&r17, &rax]).is_ok());
+        assert!(check(base, "fmin", &[&r17, &rax]).is_ok());
+        assert!(check(base, "cvtss2sd", &[&r17, &rax]).is_ok());
+        assert!(check(base, "faddp", &[]).is_err());
+        assert!(check(base, "vfmadd231sd", &[&rax, &rax, &r17]).is_err());
+        let fma = X64Arch { fma: true, ..base };
+        assert!(check(fma, "vfmadd231sd", &[&rax, &rax, &r17]).is_ok());
+        assert!(check(base, "mov", &[&rax, &Reg(32)]).is_err());
+
+        let mut output = String::new();
+        let mut writer = ValidatingWriter::new(&mut output as &mut dyn Write);
+        assert!(writer.mov(&mut (), base, &rax, &r17).is_err());
+        // Only the integer side of a conversion is a general-purpose register.
+        assert!(writer.cvttsd2si(&mut (), base, &r17, &r17).is_err());
+        assert!(writer.cvttss2si(&mut (), base, &r17, &r17).is_err());
+        let apx = X64Arch { apx: true, ..base };
+        writer.mov(&mut (), apx, &rax, &r17).unwrap();
+        assert!(output.starts_with("mov "));
+    }
+
+    #[test]
+    fn test_evex_masks_and_broadcasts() {
+        let base = X64Arch::default();
+        let avx512 = X64Arch {
+            avx512: true,
+            ..base
+        };
+        let zmm = |r| ArgKind::Reg {
+            reg: Reg(r),
+            size: MemorySize::_512,
+        };
+        let mem = MemArgKind::indexed(Reg(1), 8, 0, MemorySize::_64);
+        let (k0, k1) = (MaskReg(0), MaskReg(1));
+        let mut output = String::new();
+        let mut writer = ValidatingWriter::new(&mut output as &mut dyn Write);
+        assert!(writer.kmov(&mut (), base, k1, &Reg(0)).is_err());
+        assert!(writer.kmov(&mut (), avx512, MaskReg(8), &Reg(0)).is_err());
+        let zeroing = OpMask::zeroing(k1);
+        assert!(
+            writer
+                .vaddpd(&mut (), base, &zmm(0), zeroing, &zmm(1), &zmm(2), false)
+                .is_err()
+        );
+        // Zeroing needs a real mask, and only memory can be broadcast.
+        let bad = OpMask::zeroing(k0);
+        assert!(
+            writer
+                .vaddpd(&mut (), avx512, &zmm(0), bad, &zmm(1), &zmm(2), false)
+                .is_err()
+        );
+        assert!(
+            writer
+                .vmulpd(&mut (), avx512, &zmm(0), zeroing, &zmm(1), &zmm(2), true)
+                .is_err()
+        );
+        assert!(
+            writer
+                .vmovupd(&mut (), avx512, &mem, zeroing, &zmm(0))
+                .is_err()
+        );
+        assert!(
+            writer
+                .vcmppd(&mut (), avx512, k1, zeroing, &zmm(0), &zmm(1), 0, false)
+                .is_err()
+        );
+        writer
+            .vaddpd(&mut (), avx512, &zmm(0), zeroing, &zmm(1), &mem, true)
+            .unwrap();
+        assert_eq!(
+            output,
+            "vaddpd zmm0{k1}{z},zmm1,qword ptr [rcx*8+0]{1to8}\n"
+        );
+    }
+
+    #[test]
+    fn test_segment_base_needs_fsgsbase() {
+        let base = X64Arch::default();
+        let fsgsbase = X64Arch {
+            fsgsbase: true,
+            ..base
+        };
+        let mut output = String::new();
+        let mut writer = ValidatingWriter::new(&mut output as &mut dyn Write);
+        assert!(
+            writer
+                .rdsegbase(&mut (), base, Segment::Fs, &Reg(0))
+                .is_err()
+        );
+        assert!(
+            writer
+                .wrsegbase(&mut (), fsgsbase, Segment::None, &Reg(0))
+                .is_err()
+        );
+        writer
+            .rdsegbase(&mut (), fsgsbase, Segment::Fs, &Reg(0))
+            .unwrap();
+        writer
+            .wrsegbase(&mut (), fsgsbase, Segment::Gs, &Reg(1))
+            .unwrap();
+        assert_eq!(output, "rdfsbase rax\nwrgsbase rcx\n");
+    }
+
+    #[test]
+    fn test_cache_line_flushes() {
+        let base = X64Arch::default();
+        let clwb = X64Arch { clwb: true, ..base };
+        let line = MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(7),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_8,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: Segment::None,
+        };
+        let mut output = String::new();
+        let mut writer = ValidatingWriter::new(&mut output as &mut dyn Write);
+        assert!(writer.clwb(&mut (), base, &line).is_err());
+        assert!(writer.clflushopt(&mut (), clwb, &line).is_err());
+        assert!(writer.clflush(&mut (), base, &Reg(7)).is_err());
+        writer.clwb(&mut (), clwb, &line).unwrap();
+        writer.clflush(&mut (), base, &line).unwrap();
+        assert_eq!(output, "clwb byte ptr [rdi+0]\nclflush byte ptr [rdi+0]\n");
+    }
+
+    #[test]
+    fn test_imm32_encoding_range() {
+        let imm32 = X64Arch {
+            imm_encoding: ImmEncoding::Imm32,
+            ..X64Arch::default()
+        };
+        let mut output = String::new();
+        let mut writer = ValidatingWriter::new(&mut output as &mut dyn Write);
+        assert!(writer.mov64(&mut (), imm32, &Reg(0), 1 << 32).is_err());
+        assert!(writer.mov(&mut (), imm32, &Reg(0), &(1u64 << 32)).is_err());
+        writer.mov64(&mut (), imm32, &Reg(0), u64::MAX).unwrap();
+        writer
+            .mov64(&mut (), X64Arch::default(), &Reg(0), 1 << 32)
+            .unwrap();
+        assert_eq!(
+            output,
+            "mov rax, 18446744073709551615\nmov rax, 4294967296\n"
+        );
+    }
+
+    #[test]
+    fn test_high_byte_excludes_rex() {
+        let base = X64Arch::default();
+        let byte = |r| ArgKind::Reg {
+            reg: Reg(r),
+            size: MemorySize::_8,
+        };
+        let eax = ArgKind::Reg {
+            reg: Reg(0),
+            size: MemorySize::_32,
+        };
+        assert!(check(base, "mov", &[&ArgKind::AH, &byte(3)]).is_ok());
+        assert!(check(base, "movzx", &[&eax, &ArgKind::BH]).is_ok());
+        assert!(check(base, "mov", &[&ArgKind::AH, &byte(6)]).is_err());
+        assert!(check(base, "mov", &[&ArgKind::AH, &byte(9)]).is_err());
+        assert!(check(base, "movzx", &[&Reg(0), &ArgKind::AH]).is_err());
+        assert!(check(base, "mov", &[&ArgKind::HighByte(Reg(4)), &byte(0)]).is_err());
+
+        let mem = |base| MemArgKind::Mem {
+            base: ArgKind::Reg {
+                reg: Reg(base),
+                size: MemorySize::_64,
+            },
+            offset: None,
+            disp: 0,
+            size: MemorySize::_8,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: crate::out::arg::Segment::None,
+        };
+        assert!(check(base, "mov", &[&mem(0), &ArgKind::CH]).is_ok());
+        assert!(check(base, "mov", &[&mem(12), &ArgKind::CH]).is_err());
+    }
+
+    #[test]
+    fn test_rip_is_only_a_plain_base() {
+        let base = X64Arch::default();
+        let rip = MemArgKind::rip(16, MemorySize::_64);
+        let rip_indexed = MemArgKind::Mem {
+            base: ArgKind::Rip,
+            offset: Some((
+                ArgKind::Reg {
+                    reg: Reg(1),
+                    size: MemorySize::_64,
+                },
+                4,
+            )),
+            disp: 0,
+            size: MemorySize::_64,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: crate::out::arg::Segment::None,
+        };
+        assert!(check(base, "mov", &[&Reg(0), &rip]).is_ok());
+        assert!(check(base, "mov", &[&Reg(0), &ArgKind::Rip]).is_err());
+        assert!(check(base, "mov", &[&Reg(0), &rip_indexed]).is_err());
+    }
+
+    #[test]
+    fn test_no_base_is_not_address_zero() {
+        let base = X64Arch::default();
+        let tls = MemArgKind::tls(16, MemorySize::_64);
+        let absolute = MemArgKind::Mem {
+            base: ArgKind::Lit(0),
+            offset: None,
+            disp: 16,
+            size: MemorySize::_64,
+            reg_class: crate::RegisterClass::Gpr,
+            segment: crate::out::arg::Segment::Fs,
+        };
+        let (mut a, mut b) = (String::new(), String::new());
+        write!(a, "{tls}").unwrap();
+        write!(b, "{absolute}").unwrap();
+        assert_eq!(a, "qword ptr fs:[16]");
+        assert_eq!(b, "qword ptr fs:[0+16]");
+        assert!(check(base, "mov", &[&Reg(0), &tls]).is_ok());
+        assert!(check(base, "mov", &[&Reg(0), &ArgKind::NoBase]).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+//! Runtime-configured writer stacks.
+//!
+//! Composing wrappers by hand (`DesugaringWriter<'_, DesugaringWriter<'_, W, _>, _>`)
+//! bakes the layer combination into the type. [`WriterStack`] instead keeps the
+//! base writer behind a single boxed trait object and a list of [`Layer`]s
+//! chosen at runtime, so passes can be toggled from configuration:
+//!
+//! ```ignore
+//! let mut stack = WriterStack::new(base)
+//!     .layer_if(opts.desugar, Layer::Desugar(DesugarConfig::default()));
+//! stack.with(ctx, |w, ctx| w.mov(ctx, cfg, &dest, &src))?;
+//! ```
+//!
+//! Layers are assembled for the duration of [`WriterStack::with`] and torn
+//! down afterwards, so per-pass state such as pushed temporaries is released
+//! before control returns.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::desugar::{DesugarConfig, DesugaringWriter};
+use crate::out::Writer;
+use crate::out::error::EmitError;
+
+/// A pass that can be layered over a writer.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum Layer {
+    /// Wrap in a [`DesugaringWriter`] with the given configuration.
+    Desugar(DesugarConfig),
+}
+
+/// A base writer plus a runtime-selected list of layers.
+///
+/// Layers are applied in the order they are added: the first wraps the base
+/// writer, and the last added sees each instruction first.
+pub struct WriterStack<'a, L, Context, E> {
+    base: Box<dyn Writer<L, Context, Error = E> + 'a>,
+    layers: Vec<Layer>,
+}
+
+impl<'a, L, Context, E> WriterStack<'a, L, Context, E> {
+    /// Creates a stack with no layers over `base`.
+    pub fn new(base: impl Writer<L, Context, Error = E> + 'a) -> Self {
+        Self::from_boxed(Box::new(base))
+    }
+
+    /// Creates a stack with no layers over an already boxed writer.
+    pub fn from_boxed(base: Box<dyn Writer<L, Context, Error = E> + 'a>) -> Self {
+        Self {
+            base,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Adds `layer` on top of the current stack.
+    pub fn layer(mut self, layer: Layer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    /// Adds `layer` on top of the current stack if `enabled` is set.
+    pub fn layer_if(self, enabled: bool, layer: Layer) -> Self {
+        if enabled { self.layer(layer) } else { self }
+    }
+
+    /// Returns the configured layers, innermost first.
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Returns the base writer, bypassing all layers.
+    pub fn base(&mut self) -> &mut (dyn Writer<L, Context, Error = E> + 'a) {
+        &mut *self.base
+    }
+
+    /// Consumes the stack and returns the base writer.
+    pub fn into_base(self) -> Box<dyn Writer<L, Context, Error = E> + 'a> {
+        self.base
+    }
+
+    /// Assembles the layers and runs `f` against the outermost writer.
+    ///
+    /// Layer state is released once `f` returns, even if it failed.
+    pub fn with<R>(
+        &mut self,
+        ctx: &mut Context,
+        f: impl FnOnce(&mut (dyn Writer<L, Context, Error = E> + '_), &mut Context) -> Result<R, E>,
+    ) -> Result<R, E>
+    where
+        E: From<EmitError>,
+    {
+        let mut f = Some(f);
+        run(&self.layers, &mut *self.base, ctx, &mut |w, ctx| {
+            (f.take().expect("writer stack body runs once"))(w, ctx)
+        })
+    }
+}
+
+/// Wraps `writer` in `layers[0]`, recurses on the rest, and runs `f` innermost.
+fn run<L, Context, E, R>(
+    layers: &[Layer],
+    writer: &mut (dyn Writer<L, Context, Error = E> + '_),
+    ctx: &mut Context,
+    f: &mut dyn FnMut(&mut (dyn Writer<L, Context, Error = E> + '_), &mut Context) -> Result<R, E>,
+) -> Result<R, E>
+where
+    E: From<EmitError>,
+{
+    let Some((layer, rest)) = layers.split_first() else {
+        return f(writer, ctx);
+    };
+    match layer {
+        Layer::Desugar(config) => {
+            let mut desugar = DesugaringWriter::with_config(writer, *config);
+            let result = run(rest, &mut desugar, ctx, f);
+            desugar.release_all_temps(ctx)?;
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_layers_toggle_and_forward() {
+        let mut output = String::new();
+        let cfg = crate::X64Arch::default();
+        {
+            let writer: &mut dyn Write = &mut output;
+            let mut stack: WriterStack<'_, usize, (), core::fmt::Error> = WriterStack::new(writer)
+                .layer_if(false, Layer::Desugar(DesugarConfig::default()))
+                .layer(Layer::Desugar(DesugarConfig::default()));
+            assert_eq!(stack.layers().len(), 1);
+            stack.with(&mut (), |w, ctx| w.hlt(ctx, cfg)).unwrap();
+        }
+        assert!(output.contains("hlt"));
+    }
+}
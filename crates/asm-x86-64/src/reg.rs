@@ -49,7 +49,7 @@ pub trait X64Reg: crate::out::arg::Arg + Sized {
             &MemArgKind::Mem {
                 base: a,
                 offset: None,
-                disp: b,
+                disp: b as i32,
                 size: MemorySize::_64,
                 reg_class: crate::RegisterClass::Gpr,
                 segment: crate::out::arg::Segment::None,
@@ -63,7 +63,7 @@ pub trait X64Reg: crate::out::arg::Arg + Sized {
                 &MemArgKind::Mem {
                     base: self,
                     offset: None,
-                    disp: c,
+                    disp: c as i32,
                     size: MemorySize::_64,
                     reg_class: crate::RegisterClass::Gpr,
                     segment: crate::out::arg::Segment::None,
@@ -77,7 +77,7 @@ pub trait X64Reg: crate::out::arg::Arg + Sized {
                 &MemArgKind::Mem {
                     base: self,
                     offset: None,
-                    disp: c,
+                    disp: c as i32,
                     size: MemorySize::_64,
                     reg_class: crate::RegisterClass::Gpr,
                     segment: crate::out::arg::Segment::None,
@@ -89,8 +89,14 @@ pub trait X64Reg: crate::out::arg::Arg + Sized {
 }
 impl X64Reg for Reg {
     fn format(&self, f: &mut Formatter<'_>, opts: &RegFormatOpts) -> core::fmt::Result {
-        // Check APX support at the top of the method
-        let max_regs = if opts.arch.apx { 32 } else { 16 };
+        // APX doubles the general-purpose registers; AVX-512 and APX both
+        // double the vector registers.
+        let max_regs = match opts.reg_class {
+            crate::RegisterClass::Gpr if !opts.arch.apx => 16,
+            crate::RegisterClass::Gpr => 32,
+            _ if opts.arch.apx || opts.arch.avx512 => 32,
+            _ => 16,
+        };
         let idx = (self.0 as usize) % max_regs;
 
         match opts.reg_class {
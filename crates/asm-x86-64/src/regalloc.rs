@@ -136,7 +136,7 @@ pub fn process_cmd<Context, E: core::error::Error>(
                 let mem = crate::out::arg::MemArgKind::Mem {
                     base: Reg(5), // rbp for locals
                     offset: None,
-                    disp: offset as u32,
+                    disp: offset,
                     size,
                     reg_class,
                     segment: crate::out::arg::Segment::None,
@@ -162,7 +162,7 @@ pub fn process_cmd<Context, E: core::error::Error>(
             let mem = crate::out::arg::MemArgKind::Mem {
                 base: Reg(5), // rbp for locals
                 offset: None,
-                disp: offset as u32,
+                disp: offset,
                 size,
                 reg_class,
                 segment: crate::out::arg::Segment::None,
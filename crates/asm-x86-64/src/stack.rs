@@ -122,7 +122,7 @@ impl StackManager {
                 size: MemorySize::_64,
             }, // rsp
             offset: None,
-            disp: offset as u32,
+            disp: offset,
             size,
             reg_class,
             segment: crate::out::arg::Segment::None,
@@ -146,7 +146,7 @@ impl StackManager {
                 size: MemorySize::_64,
             }, // rbp (frame pointer)
             offset: None,
-            disp: offset as u32,
+            disp: offset,
             size,
             reg_class,
             segment: crate::out::arg::Segment::None,